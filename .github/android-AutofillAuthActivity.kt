@@ -0,0 +1,212 @@
+package space.haex.vault
+
+import android.app.Activity
+import android.app.AlertDialog
+import android.app.KeyguardManager
+import android.content.Intent
+import android.hardware.biometrics.BiometricManager
+import android.hardware.biometrics.BiometricPrompt
+import android.os.Build
+import android.os.Bundle
+import android.os.CancellationSignal
+import android.service.autofill.Dataset
+import android.view.autofill.AutofillId
+import android.view.autofill.AutofillManager
+import android.view.autofill.AutofillValue
+import android.widget.RemoteViews
+import android.widget.Toast
+import androidx.annotation.RequiresApi
+import org.json.JSONArray
+import org.json.JSONObject
+
+/** JNI entry into `passwords::autofill::android` in the Rust crate. */
+object AutofillBridge {
+    @JvmStatic
+    external fun nativeQuery(request: String): String?
+
+    /** `ok` value of the response; `null` with [lastError] set otherwise. */
+    fun query(request: JSONObject): Any? {
+        val response = try {
+            nativeQuery(request.toString())
+        } catch (e: UnsatisfiedLinkError) {
+            // The app isn't running, so no vault is open either
+            null
+        }
+        if (response == null) {
+            lastError = "VaultLocked"
+            return null
+        }
+        val json = JSONObject(response)
+        if (json.has("error")) {
+            lastError = json.getJSONObject("error").optString("type")
+            return null
+        }
+        lastError = null
+        return json.get("ok")
+    }
+
+    var lastError: String? = null
+        private set
+}
+
+/**
+ * Authentication step of [HaexAutofillService]: picks the login for the
+ * requesting app or website, confirms the user with the system biometric
+ * prompt (or the device credential) and hands the dataset back to the
+ * autofill framework. If the vault is locked the main app is opened instead.
+ */
+@RequiresApi(Build.VERSION_CODES.O)
+class AutofillAuthActivity : Activity() {
+    companion object {
+        const val EXTRA_APP_ID = "space.haex.vault.autofill.APP_ID"
+        const val EXTRA_WEB_DOMAIN = "space.haex.vault.autofill.WEB_DOMAIN"
+        const val EXTRA_USERNAME_ID = "space.haex.vault.autofill.USERNAME_ID"
+        const val EXTRA_PASSWORD_ID = "space.haex.vault.autofill.PASSWORD_ID"
+        private const val REQUEST_DEVICE_CREDENTIAL = 1
+    }
+
+    private lateinit var target: JSONObject
+    private var pendingItemId: String? = null
+
+    override fun onCreate(savedInstanceState: Bundle?) {
+        super.onCreate(savedInstanceState)
+        target = JSONObject().apply {
+            put("appId", intent.getStringExtra(EXTRA_APP_ID))
+            intent.getStringExtra(EXTRA_WEB_DOMAIN)?.let { put("webDomain", it) }
+        }
+
+        val candidates = AutofillBridge.query(JSONObject().apply {
+            put("op", "candidates")
+            put("target", target)
+        }) as? JSONArray
+        when {
+            candidates == null && AutofillBridge.lastError == "VaultLocked" -> openVault()
+            candidates == null || candidates.length() == 0 -> {
+                Toast.makeText(this, "No saved login for this app or website", Toast.LENGTH_SHORT).show()
+                cancel()
+            }
+            candidates.length() == 1 -> confirm(candidates.getJSONObject(0).getString("id"))
+            else -> pick(candidates)
+        }
+    }
+
+    private fun label(candidate: JSONObject): String {
+        val title = candidate.optString("title").ifBlank { candidate.optString("url") }
+        val username = candidate.optString("username")
+        return if (username.isBlank()) title else "$title ($username)"
+    }
+
+    private fun pick(candidates: JSONArray) {
+        val items = (0 until candidates.length()).map { candidates.getJSONObject(it) }
+        AlertDialog.Builder(this)
+            .setTitle("haex-vault")
+            .setItems(items.map(::label).toTypedArray()) { _, which ->
+                confirm(items[which].getString("id"))
+            }
+            .setOnCancelListener { cancel() }
+            .show()
+    }
+
+    private fun confirm(itemId: String) {
+        pendingItemId = itemId
+        if (Build.VERSION.SDK_INT < Build.VERSION_CODES.P) {
+            confirmWithDeviceCredential()
+            return
+        }
+        val builder = BiometricPrompt.Builder(this)
+            .setTitle("Fill in login")
+            .setSubtitle(target.optString("webDomain").ifBlank { target.optString("appId") })
+        when {
+            Build.VERSION.SDK_INT >= Build.VERSION_CODES.R -> builder.setAllowedAuthenticators(
+                BiometricManager.Authenticators.BIOMETRIC_STRONG or
+                    BiometricManager.Authenticators.DEVICE_CREDENTIAL,
+            )
+            Build.VERSION.SDK_INT >= Build.VERSION_CODES.Q -> @Suppress("DEPRECATION")
+                builder.setDeviceCredentialAllowed(true)
+            else -> builder.setNegativeButton("Cancel", mainExecutor) { _, _ -> cancel() }
+        }
+        builder.build().authenticate(
+            CancellationSignal(),
+            mainExecutor,
+            object : BiometricPrompt.AuthenticationCallback() {
+                override fun onAuthenticationSucceeded(result: BiometricPrompt.AuthenticationResult) {
+                    fill(itemId)
+                }
+
+                override fun onAuthenticationError(errorCode: Int, errString: CharSequence) {
+                    cancel()
+                }
+            },
+        )
+    }
+
+    @Suppress("DEPRECATION")
+    private fun confirmWithDeviceCredential() {
+        val keyguard = getSystemService(KeyguardManager::class.java)
+        val intent = keyguard?.createConfirmDeviceCredentialIntent("Fill in login", null)
+        if (intent == null) {
+            // No screen lock set up, nothing to confirm against
+            cancel()
+            return
+        }
+        startActivityForResult(intent, REQUEST_DEVICE_CREDENTIAL)
+    }
+
+    @Deprecated("Only used below API 28")
+    override fun onActivityResult(requestCode: Int, resultCode: Int, data: Intent?) {
+        super.onActivityResult(requestCode, resultCode, data)
+        val itemId = pendingItemId
+        if (requestCode == REQUEST_DEVICE_CREDENTIAL && resultCode == RESULT_OK && itemId != null) {
+            fill(itemId)
+        } else {
+            cancel()
+        }
+    }
+
+    @Suppress("DEPRECATION")
+    private fun fill(itemId: String) {
+        val credential = AutofillBridge.query(JSONObject().apply {
+            put("op", "credential")
+            put("target", target)
+            put("itemId", itemId)
+        }) as? JSONObject
+        if (credential == null) {
+            cancel()
+            return
+        }
+
+        val presentation = RemoteViews(packageName, android.R.layout.simple_list_item_1)
+        presentation.setTextViewText(android.R.id.text1, credential.optString("username").ifBlank { "haex-vault" })
+        val dataset = Dataset.Builder(presentation)
+        var filled = false
+        intent.getParcelableExtra<AutofillId>(EXTRA_USERNAME_ID)?.let { id ->
+            credential.optString("username").takeIf { it.isNotEmpty() }?.let {
+                dataset.setValue(id, AutofillValue.forText(it))
+                filled = true
+            }
+        }
+        intent.getParcelableExtra<AutofillId>(EXTRA_PASSWORD_ID)?.let { id ->
+            credential.optString("password").takeIf { it.isNotEmpty() }?.let {
+                dataset.setValue(id, AutofillValue.forText(it))
+                filled = true
+            }
+        }
+        if (!filled) {
+            cancel()
+            return
+        }
+        setResult(RESULT_OK, Intent().putExtra(AutofillManager.EXTRA_AUTHENTICATION_RESULT, dataset.build()))
+        finish()
+    }
+
+    private fun openVault() {
+        Toast.makeText(this, "Unlock haex-vault to fill in logins", Toast.LENGTH_SHORT).show()
+        startActivity(Intent(this, MainActivity::class.java).addFlags(Intent.FLAG_ACTIVITY_NEW_TASK))
+        cancel()
+    }
+
+    private fun cancel() {
+        setResult(RESULT_CANCELED)
+        finish()
+    }
+}
@@ -0,0 +1,75 @@
+package space.haex.vault
+
+import android.app.Activity
+import android.webkit.WebView
+import androidx.work.Constraints
+import androidx.work.ExistingPeriodicWorkPolicy
+import androidx.work.NetworkType
+import androidx.work.PeriodicWorkRequestBuilder
+import androidx.work.WorkManager
+import app.tauri.annotation.Command
+import app.tauri.annotation.InvokeArg
+import app.tauri.annotation.TauriPlugin
+import app.tauri.plugin.Invoke
+import app.tauri.plugin.JSObject
+import app.tauri.plugin.Plugin
+import java.util.concurrent.TimeUnit
+
+@InvokeArg
+class ScheduleArgs {
+    var intervalMinutes: Long = 60
+    var unmeteredOnly: Boolean = true
+    var requiresCharging: Boolean = false
+    var requiresBatteryNotLow: Boolean = true
+}
+
+/**
+ * Schedules the periodic [BackgroundSyncWorker] and forwards its wake-ups to
+ * the frontend as `wake` events (see `sync::background` in the Rust crate).
+ */
+@TauriPlugin
+class BackgroundSyncPlugin(private val activity: Activity) : Plugin(activity) {
+    companion object {
+        const val WORK_NAME = "haex-background-sync"
+
+        @Volatile
+        var instance: BackgroundSyncPlugin? = null
+            private set
+    }
+
+    override fun load(webView: WebView) {
+        instance = this
+    }
+
+    fun notifyWake() {
+        trigger("wake", JSObject())
+    }
+
+    @Command
+    fun schedule(invoke: Invoke) {
+        val args = invoke.parseArgs(ScheduleArgs::class.java)
+        val constraints = Constraints.Builder()
+            .setRequiredNetworkType(
+                if (args.unmeteredOnly) NetworkType.UNMETERED else NetworkType.CONNECTED
+            )
+            .setRequiresCharging(args.requiresCharging)
+            .setRequiresBatteryNotLow(args.requiresBatteryNotLow)
+            .build()
+        val request = PeriodicWorkRequestBuilder<BackgroundSyncWorker>(
+            args.intervalMinutes, TimeUnit.MINUTES
+        )
+            .setConstraints(constraints)
+            .build()
+
+        WorkManager.getInstance(activity.applicationContext).enqueueUniquePeriodicWork(
+            WORK_NAME, ExistingPeriodicWorkPolicy.UPDATE, request
+        )
+        invoke.resolve()
+    }
+
+    @Command
+    fun cancel(invoke: Invoke) {
+        WorkManager.getInstance(activity.applicationContext).cancelUniqueWork(WORK_NAME)
+        invoke.resolve()
+    }
+}
@@ -0,0 +1,29 @@
+package space.haex.vault
+
+import android.content.Context
+import androidx.work.Worker
+import androidx.work.WorkerParameters
+
+/**
+ * Periodic wake-up for background sync. Asks the running app to sync and
+ * keeps the process alive while it does. Without a loaded plugin there is
+ * no open vault to sync, so the run ends immediately.
+ */
+class BackgroundSyncWorker(context: Context, params: WorkerParameters) :
+    Worker(context, params) {
+    companion object {
+        /** Time the sync pass gets before the worker lets the process sleep again. */
+        const val SYNC_BUDGET_MS = 90_000L
+    }
+
+    override fun doWork(): Result {
+        val plugin = BackgroundSyncPlugin.instance ?: return Result.success()
+        plugin.notifyWake()
+        try {
+            Thread.sleep(SYNC_BUDGET_MS)
+        } catch (e: InterruptedException) {
+            // Stopped by WorkManager (constraints no longer met)
+        }
+        return Result.success()
+    }
+}
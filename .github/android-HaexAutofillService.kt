@@ -0,0 +1,127 @@
+package space.haex.vault
+
+import android.app.PendingIntent
+import android.app.assist.AssistStructure
+import android.content.Intent
+import android.os.Build
+import android.os.CancellationSignal
+import android.service.autofill.AutofillService
+import android.service.autofill.FillCallback
+import android.service.autofill.FillRequest
+import android.service.autofill.FillResponse
+import android.service.autofill.SaveCallback
+import android.service.autofill.SaveRequest
+import android.text.InputType
+import android.view.View
+import android.view.autofill.AutofillId
+import android.widget.RemoteViews
+import androidx.annotation.RequiresApi
+
+/**
+ * System autofill service backed by the password vault (see
+ * `passwords::autofill` in the Rust crate).
+ *
+ * Fill requests never carry credentials directly: the response only offers
+ * an "unlock" entry that starts [AutofillAuthActivity], which confirms the
+ * user and returns the dataset. Login forms are detected by autofill hints,
+ * input types and HTML attributes.
+ */
+@RequiresApi(Build.VERSION_CODES.O)
+class HaexAutofillService : AutofillService() {
+    class LoginFields {
+        var username: AutofillId? = null
+        var password: AutofillId? = null
+        var webDomain: String? = null
+    }
+
+    override fun onFillRequest(
+        request: FillRequest,
+        cancellationSignal: CancellationSignal,
+        callback: FillCallback,
+    ) {
+        val structure = request.fillContexts.lastOrNull()?.structure
+        val appId = structure?.activityComponent?.packageName
+        if (structure == null || appId == null || appId == packageName) {
+            callback.onSuccess(null)
+            return
+        }
+
+        val fields = LoginFields()
+        for (i in 0 until structure.windowNodeCount) {
+            collect(structure.getWindowNodeAt(i).rootViewNode, fields)
+        }
+        val ids = listOfNotNull(fields.username, fields.password)
+        if (fields.password == null && fields.username == null) {
+            callback.onSuccess(null)
+            return
+        }
+
+        val intent = Intent(this, AutofillAuthActivity::class.java).apply {
+            putExtra(AutofillAuthActivity.EXTRA_APP_ID, appId)
+            putExtra(AutofillAuthActivity.EXTRA_WEB_DOMAIN, fields.webDomain)
+            putExtra(AutofillAuthActivity.EXTRA_USERNAME_ID, fields.username)
+            putExtra(AutofillAuthActivity.EXTRA_PASSWORD_ID, fields.password)
+        }
+        val flags = PendingIntent.FLAG_CANCEL_CURRENT or
+            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.S) PendingIntent.FLAG_MUTABLE else 0
+        val sender = PendingIntent.getActivity(this, appId.hashCode(), intent, flags).intentSender
+
+        val presentation = RemoteViews(packageName, android.R.layout.simple_list_item_1)
+        presentation.setTextViewText(android.R.id.text1, "haex-vault")
+
+        val response = FillResponse.Builder()
+            .setAuthentication(ids.toTypedArray(), sender, presentation)
+            .build()
+        callback.onSuccess(response)
+    }
+
+    override fun onSaveRequest(request: SaveRequest, callback: SaveCallback) {
+        // Saving new logins happens in the vault UI
+        callback.onSuccess()
+    }
+
+    private fun collect(node: AssistStructure.ViewNode, fields: LoginFields) {
+        if (fields.webDomain == null) {
+            node.webDomain?.takeIf { it.isNotBlank() }?.let { fields.webDomain = it }
+        }
+        val id = node.autofillId
+        if (id != null && node.autofillType == View.AUTOFILL_TYPE_TEXT) {
+            when {
+                fields.password == null && isPassword(node) -> fields.password = id
+                fields.username == null && isUsername(node) -> fields.username = id
+            }
+        }
+        for (i in 0 until node.childCount) {
+            collect(node.getChildAt(i), fields)
+        }
+    }
+
+    private fun hints(node: AssistStructure.ViewNode): List<String> {
+        val hints = node.autofillHints.orEmpty().map { it.lowercase() }.toMutableList()
+        node.htmlInfo?.attributes?.forEach { attribute ->
+            if (attribute.first in listOf("type", "name", "id", "autocomplete")) {
+                hints.add(attribute.second.lowercase())
+            }
+        }
+        node.idEntry?.let { hints.add(it.lowercase()) }
+        return hints
+    }
+
+    private fun isPassword(node: AssistStructure.ViewNode): Boolean {
+        val variation = node.inputType and InputType.TYPE_MASK_VARIATION
+        val passwordInput = node.inputType and InputType.TYPE_MASK_CLASS == InputType.TYPE_CLASS_TEXT &&
+            (variation == InputType.TYPE_TEXT_VARIATION_PASSWORD ||
+                variation == InputType.TYPE_TEXT_VARIATION_WEB_PASSWORD ||
+                variation == InputType.TYPE_TEXT_VARIATION_VISIBLE_PASSWORD)
+        return passwordInput || hints(node).any { it.contains("password") }
+    }
+
+    private fun isUsername(node: AssistStructure.ViewNode): Boolean {
+        val variation = node.inputType and InputType.TYPE_MASK_VARIATION
+        val emailInput = variation == InputType.TYPE_TEXT_VARIATION_EMAIL_ADDRESS ||
+            variation == InputType.TYPE_TEXT_VARIATION_WEB_EMAIL_ADDRESS
+        return emailInput || hints(node).any { hint ->
+            listOf("username", "email", "login", "user").any { hint.contains(it) }
+        }
+    }
+}
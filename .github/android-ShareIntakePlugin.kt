@@ -0,0 +1,132 @@
+package space.haex.vault
+
+import android.app.Activity
+import android.content.Intent
+import android.net.Uri
+import android.os.Build
+import android.provider.OpenableColumns
+import android.webkit.WebView
+import app.tauri.annotation.Command
+import app.tauri.annotation.TauriPlugin
+import app.tauri.plugin.Invoke
+import app.tauri.plugin.JSArray
+import app.tauri.plugin.JSObject
+import app.tauri.plugin.Plugin
+import java.io.File
+import java.util.UUID
+
+/**
+ * Receives ACTION_SEND / ACTION_SEND_MULTIPLE, copies the shared content
+ * into `<cache>/share-intake/<uuid>/` and emits `share`. The main window then
+ * fetches the share through `share_intake_take` (see `extension::share` in
+ * the Rust crate), which calls [takePending].
+ */
+@TauriPlugin
+class ShareIntakePlugin(private val activity: Activity) : Plugin(activity) {
+    companion object {
+        const val INTAKE_DIR = "share-intake"
+    }
+
+    private val intakeDir: File
+        get() = File(activity.cacheDir, INTAKE_DIR)
+
+    @Volatile
+    private var pending: JSObject? = null
+
+    override fun load(webView: WebView) {
+        // Staged shares only live in memory, leftovers of a previous process
+        // can't be forwarded anymore
+        intakeDir.deleteRecursively()
+        handleIntent(activity.intent)
+    }
+
+    override fun onNewIntent(intent: Intent) {
+        handleIntent(intent)
+    }
+
+    private fun handleIntent(intent: Intent?) {
+        if (intent == null) return
+        if (intent.action != Intent.ACTION_SEND && intent.action != Intent.ACTION_SEND_MULTIPLE) {
+            return
+        }
+
+        val uris = sharedUris(intent)
+        val text = intent.getStringExtra(Intent.EXTRA_TEXT)
+        val subject = intent.getStringExtra(Intent.EXTRA_SUBJECT)
+        // Don't handle the same intent again when the activity is recreated
+        intent.action = null
+
+        Thread {
+            val shareDir = File(intakeDir, UUID.randomUUID().toString())
+            shareDir.mkdirs()
+            val paths = JSArray()
+            for (uri in uris) {
+                copyToIntake(uri, shareDir)?.let { paths.put(it.absolutePath) }
+            }
+
+            val share = JSObject()
+            share.put("paths", paths)
+            share.put("text", text)
+            share.put("subject", subject)
+            pending = share
+            trigger("share", JSObject())
+        }.start()
+    }
+
+    @Suppress("DEPRECATION")
+    private fun sharedUris(intent: Intent): List<Uri> {
+        return if (intent.action == Intent.ACTION_SEND_MULTIPLE) {
+            if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.TIRAMISU) {
+                intent.getParcelableArrayListExtra(Intent.EXTRA_STREAM, Uri::class.java)
+            } else {
+                intent.getParcelableArrayListExtra(Intent.EXTRA_STREAM)
+            } ?: emptyList()
+        } else {
+            val uri = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.TIRAMISU) {
+                intent.getParcelableExtra(Intent.EXTRA_STREAM, Uri::class.java)
+            } else {
+                intent.getParcelableExtra(Intent.EXTRA_STREAM)
+            }
+            listOfNotNull(uri)
+        }
+    }
+
+    private fun copyToIntake(uri: Uri, shareDir: File): File? {
+        return try {
+            val target = uniqueFile(shareDir, displayName(uri))
+            activity.contentResolver.openInputStream(uri)?.use { input ->
+                target.outputStream().use { output -> input.copyTo(output) }
+            } ?: return null
+            target
+        } catch (e: Exception) {
+            null
+        }
+    }
+
+    private fun displayName(uri: Uri): String {
+        val name = activity.contentResolver
+            .query(uri, arrayOf(OpenableColumns.DISPLAY_NAME), null, null, null)
+            ?.use { cursor -> if (cursor.moveToFirst()) cursor.getString(0) else null }
+            ?: uri.lastPathSegment
+            ?: "shared"
+        // The name comes from another app; never let it leave the share dir
+        return name.replace('/', '_').replace('\\', '_').trimStart('.').ifEmpty { "shared" }
+    }
+
+    private fun uniqueFile(dir: File, name: String): File {
+        var file = File(dir, name)
+        var counter = 1
+        while (file.exists()) {
+            file = File(dir, "${counter}_$name")
+            counter++
+        }
+        return file
+    }
+
+    @Command
+    fun takePending(invoke: Invoke) {
+        val share = pending ?: JSObject()
+        pending = null
+        invoke.resolve(share)
+    }
+}
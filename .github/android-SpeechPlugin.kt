@@ -0,0 +1,121 @@
+package space.haex.vault
+
+import android.app.Activity
+import android.speech.tts.TextToSpeech
+import android.webkit.WebView
+import app.tauri.annotation.Command
+import app.tauri.annotation.InvokeArg
+import app.tauri.annotation.TauriPlugin
+import app.tauri.plugin.Invoke
+import app.tauri.plugin.JSArray
+import app.tauri.plugin.JSObject
+import app.tauri.plugin.Plugin
+import java.util.Locale
+import java.util.UUID
+
+@InvokeArg
+class SpeakArgs {
+    var text: String = ""
+    var voice: String? = null
+    var language: String? = null
+    var rate: Float = 1.0f
+}
+
+/**
+ * Speech output through the system `TextToSpeech` engine for
+ * `accessibility::speech` in the Rust crate. The engine binds
+ * asynchronously; calls arriving before it is ready wait for it. A new text
+ * flushes the queue, so it interrupts whatever is being spoken.
+ */
+@TauriPlugin
+class SpeechPlugin(private val activity: Activity) : Plugin(activity) {
+    private var tts: TextToSpeech? = null
+    private var ready: Boolean? = null
+    private val pending = mutableListOf<() -> Unit>()
+
+    override fun load(webView: WebView) {
+        super.load(webView)
+        tts = TextToSpeech(activity) { status ->
+            activity.runOnUiThread {
+                ready = status == TextToSpeech.SUCCESS
+                pending.forEach { it() }
+                pending.clear()
+            }
+        }
+    }
+
+    private fun whenReady(invoke: Invoke, block: (TextToSpeech) -> Unit) {
+        activity.runOnUiThread {
+            val run = {
+                val engine = tts
+                if (ready == true && engine != null) {
+                    try {
+                        block(engine)
+                    } catch (e: Exception) {
+                        invoke.reject("Speech failed: ${e.message}")
+                    }
+                } else {
+                    invoke.reject("No text-to-speech engine available")
+                }
+            }
+            if (ready == null) pending.add(run) else run()
+        }
+    }
+
+    @Command
+    fun speak(invoke: Invoke) {
+        val args = invoke.parseArgs(SpeakArgs::class.java)
+        whenReady(invoke) { engine ->
+            val voice = args.voice?.let { id -> engine.voices?.firstOrNull { it.name == id } }
+            when {
+                voice != null -> engine.voice = voice
+                args.voice != null -> {
+                    invoke.reject("Unknown voice")
+                    return@whenReady
+                }
+                args.language != null -> engine.language = Locale.forLanguageTag(args.language!!)
+                else -> engine.language = Locale.getDefault()
+            }
+            engine.setSpeechRate(args.rate)
+            val result = engine.speak(args.text, TextToSpeech.QUEUE_FLUSH, null, UUID.randomUUID().toString())
+            if (result == TextToSpeech.SUCCESS) {
+                invoke.resolve(JSObject())
+            } else {
+                invoke.reject("Text-to-speech engine rejected the text")
+            }
+        }
+    }
+
+    @Command
+    fun stop(invoke: Invoke) {
+        whenReady(invoke) { engine ->
+            engine.stop()
+            invoke.resolve(JSObject())
+        }
+    }
+
+    @Command
+    fun listVoices(invoke: Invoke) {
+        whenReady(invoke) { engine ->
+            val items = JSArray()
+            engine.voices.orEmpty()
+                .filter { !it.isNetworkConnectionRequired }
+                .forEach { voice ->
+                    val item = JSObject()
+                    item.put("id", voice.name)
+                    item.put("name", voice.name)
+                    item.put("language", voice.locale.toLanguageTag())
+                    items.put(item)
+                }
+            val result = JSObject()
+            result.put("items", items)
+            invoke.resolve(result)
+        }
+    }
+
+    override fun onDestroy() {
+        tts?.shutdown()
+        tts = null
+        super.onDestroy()
+    }
+}
@@ -0,0 +1,287 @@
+package space.haex.vault
+
+import android.Manifest
+import android.app.Activity
+import android.content.ContentUris
+import android.database.Cursor
+import android.provider.CalendarContract
+import android.provider.ContactsContract
+import app.tauri.PermissionState
+import app.tauri.annotation.Command
+import app.tauri.annotation.InvokeArg
+import app.tauri.annotation.Permission
+import app.tauri.annotation.PermissionCallback
+import app.tauri.annotation.TauriPlugin
+import app.tauri.plugin.Invoke
+import app.tauri.plugin.JSArray
+import app.tauri.plugin.JSObject
+import app.tauri.plugin.Plugin
+
+@InvokeArg
+class EventsArgs {
+    var from: Long = 0
+    var to: Long = 0
+    var calendarIds: Array<String>? = null
+}
+
+/**
+ * Read-only access to the system address book and calendars for
+ * `system_pim` in the Rust crate. Contacts are returned in the normalized
+ * `SystemContact` shape; events as raw instances (epoch millis), which the
+ * Rust side formats. Missing runtime permissions are requested on first use
+ * and rejected with `PERMISSION_DENIED` if the user declines.
+ */
+@TauriPlugin(
+    permissions = [
+        Permission(strings = [Manifest.permission.READ_CONTACTS], alias = "contacts"),
+        Permission(strings = [Manifest.permission.READ_CALENDAR], alias = "calendar"),
+    ]
+)
+class SystemPimPlugin(private val activity: Activity) : Plugin(activity) {
+    companion object {
+        const val PERMISSION_DENIED = "PERMISSION_DENIED"
+    }
+
+    @Command
+    fun listContacts(invoke: Invoke) {
+        if (getPermissionState("contacts") != PermissionState.GRANTED) {
+            requestPermissionForAlias("contacts", invoke, "contactsPermissionCallback")
+            return
+        }
+        resolveContacts(invoke)
+    }
+
+    @Command
+    fun listCalendars(invoke: Invoke) {
+        if (getPermissionState("calendar") != PermissionState.GRANTED) {
+            requestPermissionForAlias("calendar", invoke, "calendarsPermissionCallback")
+            return
+        }
+        resolveCalendars(invoke)
+    }
+
+    @Command
+    fun listEvents(invoke: Invoke) {
+        if (getPermissionState("calendar") != PermissionState.GRANTED) {
+            requestPermissionForAlias("calendar", invoke, "eventsPermissionCallback")
+            return
+        }
+        resolveEvents(invoke)
+    }
+
+    @PermissionCallback
+    private fun contactsPermissionCallback(invoke: Invoke) {
+        if (getPermissionState("contacts") == PermissionState.GRANTED) {
+            resolveContacts(invoke)
+        } else {
+            invoke.reject("Contacts permission denied", PERMISSION_DENIED)
+        }
+    }
+
+    @PermissionCallback
+    private fun calendarsPermissionCallback(invoke: Invoke) {
+        if (getPermissionState("calendar") == PermissionState.GRANTED) {
+            resolveCalendars(invoke)
+        } else {
+            invoke.reject("Calendar permission denied", PERMISSION_DENIED)
+        }
+    }
+
+    @PermissionCallback
+    private fun eventsPermissionCallback(invoke: Invoke) {
+        if (getPermissionState("calendar") == PermissionState.GRANTED) {
+            resolveEvents(invoke)
+        } else {
+            invoke.reject("Calendar permission denied", PERMISSION_DENIED)
+        }
+    }
+
+    private fun resolveItems(invoke: Invoke, items: JSArray) {
+        val result = JSObject()
+        result.put("items", items)
+        invoke.resolve(result)
+    }
+
+    private fun resolveContacts(invoke: Invoke) {
+        try {
+            resolveItems(invoke, queryContacts())
+        } catch (e: Exception) {
+            invoke.reject("Failed to read contacts: ${e.message}")
+        }
+    }
+
+    private fun resolveCalendars(invoke: Invoke) {
+        try {
+            resolveItems(invoke, queryCalendars())
+        } catch (e: Exception) {
+            invoke.reject("Failed to read calendars: ${e.message}")
+        }
+    }
+
+    private fun resolveEvents(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(EventsArgs::class.java)
+            resolveItems(invoke, queryEvents(args))
+        } catch (e: Exception) {
+            invoke.reject("Failed to read events: ${e.message}")
+        }
+    }
+
+    private fun Cursor.string(column: String): String? {
+        val index = getColumnIndex(column)
+        return if (index < 0 || isNull(index)) null else getString(index)?.takeIf { it.isNotBlank() }
+    }
+
+    private fun Cursor.long(column: String): Long? {
+        val index = getColumnIndex(column)
+        return if (index < 0 || isNull(index)) null else getLong(index)
+    }
+
+    /** `--MM-DD` birthdays stay as they are, full dates are cut to `YYYY-MM-DD`. */
+    private fun normalizeBirthday(value: String): String? {
+        val date = value.substringBefore('T')
+        return when {
+            Regex("""--\d{2}-?\d{2}""").matches(date) ->
+                date.removePrefix("--").replace("-", "").let { "--${it.take(2)}-${it.drop(2)}" }
+            Regex("""\d{4}-?\d{2}-?\d{2}""").matches(date) ->
+                date.replace("-", "").let { "${it.take(4)}-${it.substring(4, 6)}-${it.drop(6)}" }
+            else -> null
+        }
+    }
+
+    private fun queryContacts(): JSArray {
+        val contacts = linkedMapOf<Long, JSObject>()
+        val emails = mutableMapOf<Long, JSArray>()
+        val phones = mutableMapOf<Long, JSArray>()
+        val data = ContactsContract.Data.CONTENT_URI
+        val projection = arrayOf(
+            ContactsContract.Data.CONTACT_ID,
+            ContactsContract.Data.DISPLAY_NAME,
+            ContactsContract.Data.MIMETYPE,
+            ContactsContract.Data.DATA1,
+            ContactsContract.Data.DATA2,
+            ContactsContract.Data.DATA3,
+        )
+
+        activity.contentResolver.query(data, projection, null, null, null)?.use { cursor ->
+            while (cursor.moveToNext()) {
+                val id = cursor.long(ContactsContract.Data.CONTACT_ID) ?: continue
+                val contact = contacts.getOrPut(id) {
+                    JSObject().apply {
+                        put("id", id.toString())
+                        put("displayName", cursor.string(ContactsContract.Data.DISPLAY_NAME) ?: "")
+                    }
+                }
+                when (cursor.string(ContactsContract.Data.MIMETYPE)) {
+                    ContactsContract.CommonDataKinds.StructuredName.CONTENT_ITEM_TYPE -> {
+                        cursor.string(ContactsContract.CommonDataKinds.StructuredName.GIVEN_NAME)
+                            ?.let { contact.put("givenName", it) }
+                        cursor.string(ContactsContract.CommonDataKinds.StructuredName.FAMILY_NAME)
+                            ?.let { contact.put("familyName", it) }
+                    }
+                    ContactsContract.CommonDataKinds.Organization.CONTENT_ITEM_TYPE ->
+                        cursor.string(ContactsContract.CommonDataKinds.Organization.COMPANY)
+                            ?.let { contact.put("organization", it) }
+                    ContactsContract.CommonDataKinds.Email.CONTENT_ITEM_TYPE ->
+                        cursor.string(ContactsContract.CommonDataKinds.Email.ADDRESS)
+                            ?.let { emails.getOrPut(id) { JSArray() }.put(it) }
+                    ContactsContract.CommonDataKinds.Phone.CONTENT_ITEM_TYPE ->
+                        cursor.string(ContactsContract.CommonDataKinds.Phone.NUMBER)
+                            ?.let { phones.getOrPut(id) { JSArray() }.put(it) }
+                    ContactsContract.CommonDataKinds.Note.CONTENT_ITEM_TYPE ->
+                        cursor.string(ContactsContract.CommonDataKinds.Note.NOTE)
+                            ?.let { contact.put("note", it) }
+                    ContactsContract.CommonDataKinds.Event.CONTENT_ITEM_TYPE -> {
+                        val type = cursor.long(ContactsContract.CommonDataKinds.Event.TYPE)
+                        if (type == ContactsContract.CommonDataKinds.Event.TYPE_BIRTHDAY.toLong()) {
+                            cursor.string(ContactsContract.CommonDataKinds.Event.START_DATE)
+                                ?.let(::normalizeBirthday)
+                                ?.let { contact.put("birthday", it) }
+                        }
+                    }
+                }
+            }
+        }
+
+        val items = JSArray()
+        for ((id, contact) in contacts) {
+            contact.put("emails", emails[id] ?: JSArray())
+            contact.put("phones", phones[id] ?: JSArray())
+            items.put(contact)
+        }
+        return items
+    }
+
+    private fun queryCalendars(): JSArray {
+        val items = JSArray()
+        val projection = arrayOf(
+            CalendarContract.Calendars._ID,
+            CalendarContract.Calendars.CALENDAR_DISPLAY_NAME,
+            CalendarContract.Calendars.CALENDAR_COLOR,
+        )
+        activity.contentResolver.query(
+            CalendarContract.Calendars.CONTENT_URI,
+            projection,
+            "${CalendarContract.Calendars.VISIBLE} = 1",
+            null,
+            null,
+        )?.use { cursor ->
+            while (cursor.moveToNext()) {
+                val id = cursor.long(CalendarContract.Calendars._ID) ?: continue
+                val calendar = JSObject()
+                calendar.put("id", id.toString())
+                calendar.put(
+                    "name",
+                    cursor.string(CalendarContract.Calendars.CALENDAR_DISPLAY_NAME) ?: id.toString(),
+                )
+                cursor.long(CalendarContract.Calendars.CALENDAR_COLOR)?.let {
+                    calendar.put("color", String.format("#%06x", it and 0xFFFFFF))
+                }
+                items.put(calendar)
+            }
+        }
+        return items
+    }
+
+    private fun queryEvents(args: EventsArgs): JSArray {
+        val uri = CalendarContract.Instances.CONTENT_URI.buildUpon().let {
+            ContentUris.appendId(it, args.from)
+            ContentUris.appendId(it, args.to)
+            it.build()
+        }
+        val projection = arrayOf(
+            CalendarContract.Instances.EVENT_ID,
+            CalendarContract.Instances.CALENDAR_ID,
+            CalendarContract.Instances.TITLE,
+            CalendarContract.Instances.DESCRIPTION,
+            CalendarContract.Instances.EVENT_LOCATION,
+            CalendarContract.Instances.BEGIN,
+            CalendarContract.Instances.END,
+            CalendarContract.Instances.ALL_DAY,
+        )
+        val calendarIds = args.calendarIds?.mapNotNull { it.toLongOrNull() }
+        val selection = calendarIds?.let { ids ->
+            "${CalendarContract.Instances.CALENDAR_ID} IN (${ids.joinToString(",") { "?" }})"
+        }
+        val selectionArgs = calendarIds?.map { it.toString() }?.toTypedArray()
+
+        val items = JSArray()
+        activity.contentResolver.query(uri, projection, selection, selectionArgs, null)?.use { cursor ->
+            while (cursor.moveToNext()) {
+                val id = cursor.long(CalendarContract.Instances.EVENT_ID) ?: continue
+                val begin = cursor.long(CalendarContract.Instances.BEGIN) ?: continue
+                val event = JSObject()
+                event.put("id", id.toString())
+                event.put("calendarId", cursor.long(CalendarContract.Instances.CALENDAR_ID)?.toString() ?: "")
+                event.put("title", cursor.string(CalendarContract.Instances.TITLE))
+                event.put("description", cursor.string(CalendarContract.Instances.DESCRIPTION))
+                event.put("location", cursor.string(CalendarContract.Instances.EVENT_LOCATION))
+                event.put("begin", begin)
+                cursor.long(CalendarContract.Instances.END)?.let { event.put("end", it) }
+                event.put("allDay", cursor.long(CalendarContract.Instances.ALL_DAY) == 1L)
+                items.put(event)
+            }
+        }
+        return items
+    }
+}
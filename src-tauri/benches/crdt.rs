@@ -0,0 +1,286 @@
+//! Benchmarks for the CRDT write, read and sync-apply paths:
+//! `execute_with_crdt`, `select_with_crdt` and `apply_remote_changes_to_db`.
+//!
+//! Runs on encrypted in-memory vaults from `test_support::crdt`, with the
+//! test clock and deterministic UUIDs, so every run writes the same rows
+//! and timestamps.
+//!
+//! Run: cargo bench --features test-support --bench crdt
+//!
+//! Comparing releases: save the numbers of a release as a named baseline,
+//! then run the current tree against it. Criterion reports the change per
+//! benchmark and flags regressions outside the noise threshold.
+//!
+//!   git checkout v1.4.0
+//!   cargo bench --features test-support --bench crdt -- --save-baseline v1.4.0
+//!   git checkout main
+//!   cargo bench --features test-support --bench crdt -- --baseline v1.4.0
+//!
+//! Baselines are matched by benchmark name, so keep the group and
+//! benchmark names and the data sizes below stable; a renamed benchmark
+//! starts without history.
+
+// Benchmark file — opt out of unwrap/expect lints like the `tests/*.rs`
+// files do; a failing setup should abort the run.
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use haex_vault_lib::database::core::{execute_with_crdt, select_with_crdt};
+use haex_vault_lib::test_support::clock::TestClock;
+use haex_vault_lib::test_support::crdt::{CrdtVault, RemoteColumnChange};
+use haex_vault_lib::test_support::ids::DeterministicIds;
+use serde_json::{json, Value as JsonValue};
+
+/// Rows per multi-row INSERT
+const BATCH_SIZES: [usize; 3] = [10, 100, 1_000];
+/// Columns written by one UPDATE of the wide table
+const WIDE_COLUMNS: usize = 20;
+/// Live rows of the tables read by `select_with_crdt`
+const SELECT_ROWS: usize = 10_000;
+/// Rows (three columns each) of one remote batch
+const APPLY_ROWS: usize = 10_000;
+
+const ITEMS_TABLE: &str = "CREATE TABLE items (
+    id TEXT PRIMARY KEY NOT NULL,
+    title TEXT,
+    body TEXT,
+    position INTEGER
+)";
+
+// ============================================================================
+// Fixtures
+// ============================================================================
+
+fn items_vault(device_id: &str) -> CrdtVault {
+    let vault = CrdtVault::open(device_id).unwrap();
+    vault.create_crdt_table("items", ITEMS_TABLE).unwrap();
+    vault
+}
+
+fn execute(vault: &CrdtVault, sql: &str, params: Vec<JsonValue>) {
+    execute_with_crdt(
+        sql.to_string(),
+        params,
+        &vault.db,
+        &vault.hlc.lock().unwrap(),
+    )
+    .unwrap();
+}
+
+fn item_id(n: usize) -> String {
+    format!("item-{n:08}")
+}
+
+/// `INSERT` of `rows` items in one statement
+fn insert_items_sql(rows: usize) -> String {
+    let values = vec!["(?, ?, ?, ?)"; rows].join(", ");
+    format!("INSERT INTO items (id, title, body, position) VALUES {values}")
+}
+
+fn item_params(first: usize, rows: usize) -> Vec<JsonValue> {
+    (first..first + rows)
+        .flat_map(|n| {
+            [
+                json!(item_id(n)),
+                json!(format!("Item {n}")),
+                json!("Lorem ipsum dolor sit amet, consectetur adipiscing elit."),
+                json!(n),
+            ]
+        })
+        .collect()
+}
+
+fn insert_items(vault: &CrdtVault, first: usize, rows: usize) {
+    for chunk_start in (first..first + rows).step_by(1_000) {
+        let chunk = 1_000.min(first + rows - chunk_start);
+        execute(
+            vault,
+            &insert_items_sql(chunk),
+            item_params(chunk_start, chunk),
+        );
+    }
+}
+
+// ============================================================================
+// execute_with_crdt
+// ============================================================================
+
+fn bench_execute(c: &mut Criterion) {
+    let _clock = TestClock::install();
+    let _ids = DeterministicIds::install(1);
+    let mut group = c.benchmark_group("execute_with_crdt");
+
+    let vault = items_vault("bench-local");
+    let mut next_id = 0;
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("single_insert", |b| {
+        let sql = insert_items_sql(1);
+        b.iter(|| {
+            execute(&vault, &sql, item_params(next_id, 1));
+            next_id += 1;
+        })
+    });
+
+    for rows in BATCH_SIZES {
+        group.throughput(Throughput::Elements(rows as u64));
+        group.bench_with_input(BenchmarkId::new("batch_insert", rows), &rows, |b, &rows| {
+            let sql = insert_items_sql(rows);
+            b.iter(|| {
+                execute(&vault, &sql, item_params(next_id, rows));
+                next_id += rows;
+            })
+        });
+    }
+
+    let columns: Vec<String> = (0..WIDE_COLUMNS).map(|n| format!("c{n:02}")).collect();
+    let wide = CrdtVault::open("bench-local").unwrap();
+    wide.create_crdt_table(
+        "wide",
+        &format!(
+            "CREATE TABLE wide (id TEXT PRIMARY KEY NOT NULL, {})",
+            columns
+                .iter()
+                .map(|column| format!("{column} TEXT"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    )
+    .unwrap();
+    execute(&wide, "INSERT INTO wide (id) VALUES ('row')", vec![]);
+    let update_sql = format!(
+        "UPDATE wide SET {} WHERE id = 'row'",
+        columns
+            .iter()
+            .map(|column| format!("{column} = ?"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let mut revision = 0;
+    group.throughput(Throughput::Elements(WIDE_COLUMNS as u64));
+    group.bench_function(BenchmarkId::new("update_columns", WIDE_COLUMNS), |b| {
+        b.iter(|| {
+            revision += 1;
+            let params = columns
+                .iter()
+                .map(|column| json!(format!("{column} revision {revision}")))
+                .collect();
+            execute(&wide, &update_sql, params);
+        })
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// select_with_crdt
+// ============================================================================
+
+fn bench_select(c: &mut Criterion) {
+    let _clock = TestClock::install();
+    let _ids = DeterministicIds::install(2);
+    let mut group = c.benchmark_group("select_with_crdt");
+    group.sample_size(20);
+
+    // Same live rows in both vaults; the second one also carries a delete
+    // log and trash of the same size from every other row being deleted.
+    let live = items_vault("bench-local");
+    insert_items(&live, 0, SELECT_ROWS);
+    let tombstoned = items_vault("bench-local");
+    insert_items(&tombstoned, 0, SELECT_ROWS * 2);
+    execute(
+        &tombstoned,
+        "DELETE FROM items WHERE position % 2 = 1",
+        vec![],
+    );
+
+    let full_scan = "SELECT id, title, body, position FROM items ORDER BY position";
+    let point_lookup = "SELECT id, title, body, position FROM items WHERE id = ?";
+    // Live in both vaults (even position)
+    let lookup_id = json!(item_id(SELECT_ROWS / 2));
+
+    for (variant, vault) in [
+        ("without_tombstones", &live),
+        ("with_tombstones", &tombstoned),
+    ] {
+        group.throughput(Throughput::Elements(SELECT_ROWS as u64));
+        group.bench_function(BenchmarkId::new("full_scan", variant), |b| {
+            b.iter(|| {
+                let rows = select_with_crdt(full_scan.to_string(), vec![], &vault.db).unwrap();
+                assert_eq!(rows.len(), SELECT_ROWS);
+            })
+        });
+
+        group.throughput(Throughput::Elements(1));
+        group.bench_function(BenchmarkId::new("point_lookup", variant), |b| {
+            b.iter(|| {
+                let rows =
+                    select_with_crdt(point_lookup.to_string(), vec![lookup_id.clone()], &vault.db)
+                        .unwrap();
+                assert_eq!(rows.len(), 1);
+            })
+        });
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// apply_remote_changes_to_db
+// ============================================================================
+
+/// Column changes of `rows` remote inserts, one transaction per row
+fn remote_item_changes(remote: &CrdtVault, rows: usize) -> Vec<RemoteColumnChange> {
+    (0..rows)
+        .flat_map(|n| {
+            let hlc_timestamp = remote.new_hlc_timestamp().unwrap();
+            let row_pks = json!({ "id": item_id(n) }).to_string();
+            [
+                ("title", json!(format!("Item {n}"))),
+                (
+                    "body",
+                    json!("Lorem ipsum dolor sit amet, consectetur adipiscing elit."),
+                ),
+                ("position", json!(n)),
+            ]
+            .into_iter()
+            .map(move |(column_name, decrypted_value)| RemoteColumnChange {
+                table_name: "items".to_string(),
+                row_pks: row_pks.clone(),
+                column_name: column_name.to_string(),
+                hlc_timestamp: hlc_timestamp.clone(),
+                decrypted_value,
+            })
+        })
+        .collect()
+}
+
+fn bench_apply_remote(c: &mut Criterion) {
+    let _clock = TestClock::install();
+    let _ids = DeterministicIds::install(3);
+    let mut group = c.benchmark_group("apply_remote_changes");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(30));
+
+    let remote = CrdtVault::open("bench-remote").unwrap();
+    let changes = remote_item_changes(&remote, APPLY_ROWS);
+
+    group.throughput(Throughput::Elements(APPLY_ROWS as u64));
+    group.bench_function(BenchmarkId::new("insert_rows", APPLY_ROWS), |b| {
+        b.iter_batched(
+            || (items_vault("bench-local"), changes.clone()),
+            |(vault, changes)| {
+                vault.apply_remote_changes(changes).unwrap();
+                // Dropped by criterion outside the measurement
+                vault
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_execute, bench_select, bench_apply_remote);
+criterion_main!(benches);
@@ -1,15 +1,20 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CalendarAction } from "./CalendarAction";
+import type { CameraAction } from "./CameraAction";
+import type { ContactsAction } from "./ContactsAction";
 import type { DbAction } from "./DbAction";
 import type { FileSyncAction } from "./FileSyncAction";
 import type { FsAction } from "./FsAction";
 import type { IdentityAction } from "./IdentityAction";
 import type { MailAction } from "./MailAction";
+import type { MicrophoneAction } from "./MicrophoneAction";
 import type { PasswordsAction } from "./PasswordsAction";
 import type { ShellAction } from "./ShellAction";
 import type { SpaceAction } from "./SpaceAction";
+import type { SpeechAction } from "./SpeechAction";
 import type { WebAction } from "./WebAction";
 
 /**
  * Ein typsicherer Container, der die spezifische Aktion für einen Ressourcentyp enthält.
  */
-export type Action = { "Database": DbAction } | { "Filesystem": FsAction } | { "Web": WebAction } | { "Shell": ShellAction } | { "FileSync": FileSyncAction } | { "Spaces": SpaceAction } | { "Identities": IdentityAction } | { "Passwords": PasswordsAction } | { "Mail": MailAction };
+export type Action = { "Database": DbAction } | { "Filesystem": FsAction } | { "Web": WebAction } | { "Shell": ShellAction } | { "FileSync": FileSyncAction } | { "Spaces": SpaceAction } | { "Identities": IdentityAction } | { "Passwords": PasswordsAction } | { "Mail": MailAction } | { "Camera": CameraAction } | { "Contacts": ContactsAction } | { "Calendar": CalendarAction } | { "Microphone": MicrophoneAction } | { "Speech": SpeechAction };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A profile as listed in the registry
+ */
+export type AppProfile = { id: string, name: string, 
+/**
+ * Unix timestamp (s), 0 for the default profile
+ */
+createdAt: number, };
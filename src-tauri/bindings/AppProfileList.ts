@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AppProfile } from "./AppProfile";
+
+/**
+ * Profiles for the startup picker and the settings
+ */
+export type AppProfileList = { 
+/**
+ * Profile this instance runs with
+ */
+activeId: string, 
+/**
+ * Profile the next start uses
+ */
+startupId: string, profiles: Array<AppProfile>, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Application context shared with extensions.
+ * Contains theme, locale, platform, and device information.
+ */
+export type ApplicationContext = { theme: string, locale: string, platform: string, deviceId: string, 
+/**
+ * Custom CSS variables (e.g. `--haex-primary` → `#3b82f6`) applied to
+ * the extension document root so extensions can follow the host theme.
+ */
+cssVariables: { [key in string]: string }, };
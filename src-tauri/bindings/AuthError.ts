@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * TypeScript type of a serialized [`AuthError`]: the shared envelope and
+ * the `type`/`details` pair of the variant
+ */
+export type AuthError = { code: number, 
+/**
+ * Technical description (English)
+ */
+message: string, 
+/**
+ * Localized message for the user, in the ApplicationContext locale
+ */
+userMessage: string, 
+/**
+ * Structured context of the error, `null` if there is none
+ */
+details: unknown, } & ({ "type": "PasswordRequired", "details": { reason: string, } } | { "type": "InvalidPassword" } | { "type": "Cancelled" } | { "type": "VaultNotOpen" } | { "type": "Database", "details": { reason: string, } });
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AutofillMatch } from "./AutofillMatch";
+
+/**
+ * An item offered for a target, without secrets.
+ */
+export type AutofillCandidate = { id: string, title: string | null, username: string | null, 
+/**
+ * The associated URL that matched
+ */
+url: string, match: AutofillMatch, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What gets filled into the requesting app.
+ */
+export type AutofillCredential = { id: string, username: string | null, password: string | null, };
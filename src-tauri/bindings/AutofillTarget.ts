@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Who asks for credentials.
+ */
+export type AutofillTarget = { 
+/**
+ * Android package name or iOS bundle id of the requesting app
+ */
+appId?: string, 
+/**
+ * Host of the page when the request comes from a browser
+ */
+webDomain?: string, };
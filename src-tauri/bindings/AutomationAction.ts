@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NotificationLevel } from "./NotificationLevel";
+
+export type AutomationAction = { "type": "runExtensionCommand", "config": { extensionId: string, command: string, args: unknown, } } | { "type": "sendNotification", "config": { title: string, text: string | null, level: NotificationLevel, } } | { "type": "callWebhook", "config": { webhookId: string, } };
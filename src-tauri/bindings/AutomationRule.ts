@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AutomationAction } from "./AutomationAction";
+import type { AutomationTrigger } from "./AutomationTrigger";
+
+export type AutomationRule = { id: string, name: string, enabled: boolean, trigger: AutomationTrigger, action: AutomationAction, lastRunAt: string | null, 
+/**
+ * Error of the last failed run, cleared by the next success
+ */
+lastError: string | null, createdAt: string, updatedAt: string, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FileEventKind } from "./FileEventKind";
+
+export type AutomationTrigger = { "type": "tableChange", "config": { tables: Array<string>, } } | { "type": "schedule", "config": { intervalSeconds: number, } } | { "type": "fileEvent", "config": { events: Array<FileEventKind>, syncRuleId: string | null, } };
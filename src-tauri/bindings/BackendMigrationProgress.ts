@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Progress of a running backend migration
+ */
+export type BackendMigrationProgress = { fromBackendId: string, toBackendId: string, 
+/**
+ * Object being copied, `None` once all objects are done
+ */
+currentKey: string | null, objectsDone: number, objectsTotal: number, bytesDone: number, bytesTotal: number, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of `remote_storage_migrate_backend`
+ */
+export type BackendMigrationResult = { fromBackendId: string, toBackendId: string, objectsCopied: number, 
+/**
+ * Objects that were already on the target with the same content
+ */
+objectsSkipped: number, bytesCopied: number, 
+/**
+ * Sync rules now pointing at the target. Running sync loops of these
+ * rules were stopped and have to be restarted with the new config.
+ */
+updatedRuleIds: Array<string>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Usage of one storage backend
+ */
+export type BackendUsage = { backendId: string, 
+/**
+ * `None` for ledger entries of a backend that no longer exists
+ */
+name: string | null, files: number, bytes: number, quotaBytes: number | null, };
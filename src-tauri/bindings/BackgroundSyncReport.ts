@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a background pass woke up
+ */
+export type BackgroundSyncReport = { 
+/**
+ * Backend ids of the orchestrators that run a cycle now
+ */
+orchestrators: Array<string>, 
+/**
+ * FileSync rules that sync now
+ */
+fileSyncRules: Array<string>, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * When and under which conditions the app is woken up to sync.
+ */
+export type BackgroundSyncSettings = { enabled: boolean, 
+/**
+ * Minutes between wake-ups; the OS may stretch it
+ */
+intervalMinutes: number, 
+/**
+ * Only sync on unmetered networks (Wi-Fi)
+ */
+unmeteredOnly: boolean, requiresCharging: boolean, 
+/**
+ * Skip wake-ups while the battery is low
+ */
+requiresBatteryNotLow: boolean, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BackgroundSyncSettings } from "./BackgroundSyncSettings";
+
+/**
+ * Result of [`background_sync_set_settings`]
+ */
+export type BackgroundSyncStatus = { settings: BackgroundSyncSettings, 
+/**
+ * A periodic wake-up is scheduled with the OS
+ */
+scheduled: boolean, };
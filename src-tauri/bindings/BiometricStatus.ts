@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BiometricKind } from "./BiometricKind";
+
+export type BiometricStatus = { available: boolean, kind: BiometricKind | null, 
+/**
+ * Why biometrics can't be used (no reader, nothing enrolled, …)
+ */
+reason: string | null, };
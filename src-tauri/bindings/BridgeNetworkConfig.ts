@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Network settings of the bridge
+ */
+export type BridgeNetworkConfig = { 
+/**
+ * IP address to listen on; `127.0.0.1` keeps the bridge on this device
+ */
+bindAddress: string, 
+/**
+ * Serve `wss://` with the self-signed identity; required off loopback
+ */
+tls: boolean, 
+/**
+ * Client IP ranges (CIDR or single address) accepted besides loopback
+ */
+allowedClientRanges: Array<string>, };
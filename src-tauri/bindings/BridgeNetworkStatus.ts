@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What the running bridge listens on, for the settings UI and pairing
+ */
+export type BridgeNetworkStatus = { running: boolean, bindAddress: string, port: number, tls: boolean, 
+/**
+ * SHA-256 of the TLS certificate (hex) clients pin; `None` without TLS
+ */
+tlsFingerprint: string | null, allowedClientRanges: Array<string>, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Lesezugriff auf die Kalender des Betriebssystems.
+ * 
+ * Die Extension bekommt normalisierte Termine (`system_pim`), nie Zugriff auf
+ * die OS-APIs selbst. `target` ist immer "*".
+ */
+export type CalendarAction = "read";
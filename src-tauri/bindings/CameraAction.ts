@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Aktionen auf der Kamera des Geräts.
+ * 
+ * Die Aufnahme läuft immer im Hauptfenster (Extension-Webviews haben unter
+ * dem Custom-Protocol keinen verlässlichen `getUserMedia`-Zugriff); die
+ * Extension bekommt nur das Ergebnis. `target` ist immer "*".
+ */
+export type CameraAction = "scan" | "capture";
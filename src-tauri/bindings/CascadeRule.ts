@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Deleting a row of `referencedTable` deletes the rows of `table` whose
+ * `columns` match its `referencedColumns`.
+ */
+export type CascadeRule = { 
+/**
+ * Child table
+ */
+table: string, 
+/**
+ * Foreign key columns in the child table
+ */
+columns: Array<string>, 
+/**
+ * Parent table
+ */
+referencedTable: string, 
+/**
+ * Referenced columns in the parent table, pairwise with `columns`
+ */
+referencedColumns: Array<string>, };
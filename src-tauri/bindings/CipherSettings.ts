@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HmacAlgorithm } from "./HmacAlgorithm";
+
+/**
+ * SQLCipher parameters of a vault
+ */
+export type CipherSettings = { 
+/**
+ * PBKDF2 iterations used to derive the key from the password
+ */
+kdfIter: number, 
+/**
+ * Page size in bytes (power of two, 512 – 65536)
+ */
+cipherPageSize: number, hmacAlgorithm: HmacAlgorithm, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * TypeScript type of a serialized [`CommandError`]
+ */
+export type CommandError = { code: number, 
+/**
+ * Technical description (English)
+ */
+message: string, 
+/**
+ * Localized message for the user, in the ApplicationContext locale
+ */
+userMessage: string, 
+/**
+ * Structured context of the error, `null` if there is none
+ */
+details: unknown, } & ({ "type": "VaultNotOpen", "details": { command: string, } } | { "type": "InternalError", "details": { command: string, correlationId: string, } });
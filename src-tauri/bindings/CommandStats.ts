@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Counters of one command.
+ */
+export type CommandStats = { 
+/**
+ * Dispatched invocations (including panicked ones)
+ */
+calls: number, 
+/**
+ * Invocations rejected by a precondition before dispatch
+ */
+rejected: number, 
+/**
+ * Invocations that panicked during dispatch
+ */
+panicked: number, 
+/**
+ * Sum of all dispatch durations in microseconds
+ */
+totalMicros: number, 
+/**
+ * Longest dispatch in microseconds
+ */
+maxMicros: number, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PathType } from "./PathType";
+
+export type ConnectionDiagnostics = { pathType: PathType, remoteAddr: string | null, rttMs: number | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ApplicationContext } from "./ApplicationContext";
+
+/**
+ * Payload of `CONTEXT_CHANGED_EVENT`. Same shape the frontend posts to
+ * iframe extensions (`{ context }`).
+ */
+export type ContextChangedPayload = { context: ApplicationContext, };
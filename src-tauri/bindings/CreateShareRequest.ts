@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Parameters of `share_create`.
+ */
+export type CreateShareRequest = { 
+/**
+ * Identity the share is published as; must have a private key.
+ */
+ownerDid: string, 
+name: string, 
+tables: Array<string>, 
+spaceId: string | null, 
+/**
+ * Storage backend the batches are published to. Recipients need
+ * read access to it.
+ */
+backendId: string, 
+recipientDids: Array<string>, };
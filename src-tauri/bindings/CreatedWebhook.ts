@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WebhookInfo } from "./WebhookInfo";
+
+/**
+ * Result of `webhooks_create`; the secret is only returned here
+ */
+export type CreatedWebhook = { webhook: WebhookInfo, secret: string, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CredentialPromptKind } from "./CredentialPromptKind";
+import type { PhishingWarning } from "./PhishingWarning";
+
+/**
+ * Payload of `external-bridge:credential-prompt`. The main window asks
+ * the user and answers with
+ * `external_bridge_credential_respond(promptId, allow, remember)`.
+ */
+export type CredentialPromptRequested = { promptId: string, clientId: string, clientName: string, origin: string, kind: CredentialPromptKind, 
+/**
+ * Usernames of the logins to fill, or the one to save
+ */
+usernames: Array<string>, 
+/**
+ * Saving replaces the password of an existing login
+ */
+replacesExisting: boolean, warnings: Array<PhishingWarning>, };
@@ -1,3 +1,19 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type DatabaseError = { "type": "ParseError", "details": { reason: string, sql: string, } } | { "type": "ParameterMismatchError", "details": { expected: number, provided: number, sql: string, } } | { "type": "NoTableError", "details": { sql: string, } } | { "type": "StatementError", "details": { reason: string, } } | { "type": "PrepareError", "details": { reason: string, } } | { "type": "DatabaseError", "details": { reason: string, } } | { "type": "ExecutionError", "details": { sql: string, reason: string, table: string | null, } } | { "type": "TransactionError", "details": { reason: string, } } | { "type": "UnsupportedStatement", "details": { reason: string, sql: string, } } | { "type": "HlcError", "details": { reason: string, } } | { "type": "LockError", "details": { reason: string, } } | { "type": "ConnectionError", "details": { reason: string, } } | { "type": "SerializationError", "details": { reason: string, } } | { "type": "PermissionError", "details": { extensionId: string, operation: string | null, resource: string | null, reason: string, } } | { "type": "QueryError", "details": { reason: string, } } | { "type": "RowProcessingError", "details": { reason: string, } } | { "type": "MutexPoisoned", "details": { reason: string, } } | { "type": "ConnectionFailed", "details": { path: string, reason: string, } } | { "type": "PragmaError", "details": { pragma: string, reason: string, } } | { "type": "PathResolutionError", "details": { reason: string, } } | { "type": "IoError", "details": { path: string, reason: string, } } | { "type": "CrdtSetup", "details": string } | { "type": "MigrationError", "details": { reason: string, } } | { "type": "VaultAlreadyExists", "details": { vaultName: string, } } | { "type": "VaultAlreadyOpenElsewhere", "details": { path: string, reason: string, } } | { "type": "VaultAlreadyMountedInProcess", "details": { existingPath: string, requestedPath: string, } } | { "type": "ValidationError", "details": { reason: string, } } | { "type": "LimitExceeded", "details": { reason: string, } };
+/**
+ * TypeScript type of a serialized [`DatabaseError`]: the shared envelope
+ * and the `type`/`details` pair of the variant
+ */
+export type DatabaseError = { code: number, 
+/**
+ * Technical description (English)
+ */
+message: string, 
+/**
+ * Localized message for the user, in the ApplicationContext locale
+ */
+userMessage: string, 
+/**
+ * Structured context of the error, `null` if there is none
+ */
+details: unknown, } & ({ "type": "ParseError", "details": { reason: string, sql: string, } } | { "type": "ParameterMismatchError", "details": { expected: number, provided: number, sql: string, } } | { "type": "ParameterTypeError", "details": { position: number, table: string, column: string, expected: string, provided: string, } } | { "type": "NoTableError", "details": { sql: string, } } | { "type": "StatementError", "details": { reason: string, } } | { "type": "PrepareError", "details": { reason: string, } } | { "type": "DatabaseError", "details": { reason: string, } } | { "type": "ExecutionError", "details": { sql: string, reason: string, table: string | null, } } | { "type": "TransactionError", "details": { reason: string, } } | { "type": "UnsupportedStatement", "details": { reason: string, sql: string, } } | { "type": "HlcError", "details": { reason: string, } } | { "type": "LockError", "details": { reason: string, } } | { "type": "ConnectionError", "details": { reason: string, } } | { "type": "SerializationError", "details": { reason: string, } } | { "type": "PermissionError", "details": { extensionId: string, operation: string | null, resource: string | null, reason: string, } } | { "type": "QueryError", "details": { reason: string, } } | { "type": "RowProcessingError", "details": { reason: string, } } | { "type": "MutexPoisoned", "details": { reason: string, } } | { "type": "ConnectionFailed", "details": { path: string, reason: string, } } | { "type": "PragmaError", "details": { pragma: string, reason: string, } } | { "type": "PathResolutionError", "details": { reason: string, } } | { "type": "IoError", "details": { path: string, reason: string, } } | { "type": "CrdtSetup", "details": string } | { "type": "MigrationError", "details": { reason: string, } } | { "type": "VaultAlreadyExists", "details": { vaultName: string, } } | { "type": "VaultAlreadyOpenElsewhere", "details": { path: string, reason: string, holderPid: number | null, holderHostname: string | null, } } | { "type": "VaultBusy", "details": { path: string, reason: string, } } | { "type": "VaultAlreadyMountedInProcess", "details": { existingPath: string, requestedPath: string, } } | { "type": "VaultLockedOut", "details": { failedAttempts: number, retryAfterSecs: number, } } | { "type": "ValidationError", "details": { reason: string, } } | { "type": "LimitExceeded", "details": { reason: string, } });
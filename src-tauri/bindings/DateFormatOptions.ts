@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DateStyle } from "./DateStyle";
+import type { TimeStyle } from "./TimeStyle";
+
+/**
+ * Options of `extension_intl_format_date`
+ */
+export type DateFormatOptions = { 
+/**
+ * Defaults to `medium` when neither style is given
+ */
+dateStyle: DateStyle | null, timeStyle: TimeStyle | null, 
+/**
+ * Offset to format in (`-new Date().getTimezoneOffset()`); defaults to
+ * the offset of the timestamp
+ */
+utcOffsetMinutes: number | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Length of a formatted date, like `Intl.DateTimeFormat`'s `dateStyle`
+ */
+export type DateStyle = "short" | "medium" | "long" | "full";
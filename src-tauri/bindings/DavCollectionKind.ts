@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a collection holds; selects the CalDAV or CardDAV vocabulary.
+ */
+export type DavCollectionKind = "calendar" | "addressBook";
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DavAuthMethod } from "./DavAuthMethod";
+import type { DavCollectionKind } from "./DavCollectionKind";
+
+/**
+ * A CalDAV/CardDAV collection + credentials.
+ */
+export type DavConfig = { 
+/**
+ * URL of the calendar or address book collection itself (not the
+ * principal or home set), e.g.
+ * `https://cloud.example/remote.php/dav/calendars/alice/personal/`
+ */
+url: string, kind: DavCollectionKind, auth: DavAuthMethod, username: string, 
+/**
+ * Password, app password or OAuth2 access token (see `auth`). May be
+ * left empty when `credential_item_id` is set.
+ */
+password: string, 
+/**
+ * Password-vault item to take username and secret from, resolved by
+ * the extension wrapper within the extension's tag scope.
+ */
+credentialItemId?: string, };
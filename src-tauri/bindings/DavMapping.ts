@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where the resources of a collection are stored.
+ */
+export type DavMapping = { 
+/**
+ * Table name without the extension prefix
+ */
+table: string, 
+/**
+ * Column identifying the row; needs a PRIMARY KEY or UNIQUE constraint
+ */
+keyColumn: string, 
+/**
+ * Source of the key, `$href` if not set. `$uid` keeps rows stable
+ * when the server renames resources.
+ */
+keySource?: string, 
+/**
+ * Column name to source; properties missing in a resource become NULL
+ */
+columns: { [key in string]: string }, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Counts of one `extension_dav_sync` call.
+ */
+export type DavSyncResult = { upserted: number, deleted: number, 
+/**
+ * Resources the server reported again with an unchanged etag
+ */
+unchanged: number, 
+/**
+ * Resources without a value for the key source (e.g. no `UID`)
+ */
+skipped: number, 
+/**
+ * The server had no usable sync token, so the whole collection was
+ * listed and rows of vanished resources were removed
+ */
+fullResync: boolean, };
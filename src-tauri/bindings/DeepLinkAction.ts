@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What an action link asks for, shown to the user before it runs
+ */
+export type DeepLinkAction = { extensionId: string, action: string, payload: unknown, callbackUrl: string | null, 
+/**
+ * Whether the callback is a local program rather than an app redirect
+ */
+callbackIsLoopback: boolean, };
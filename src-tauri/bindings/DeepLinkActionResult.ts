@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Outcome of an executed action link
+ */
+export type DeepLinkActionResult = { requestId: string, success: boolean, data: unknown, error: string | null, 
+/**
+ * Whether the result was delivered to the callback
+ */
+callbackInvoked: boolean, callbackError: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Deleted rows still present in one CRDT table
+ */
+export type DeletedRowsInTable = { tableName: string, 
+/**
+ * Number of rows in the table (not only those the query matches)
+ */
+rows: number, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One captured console call or error of a dev extension.
+ */
+export type DevLogEntry = { extensionId: string, 
+/**
+ * Label of the extension window, `None` for iframe extensions
+ */
+windowId: string | null, 
+/**
+ * `log`, `info`, `warn`, `error` or `debug`
+ */
+level: string, message: string, 
+/**
+ * `url:line:column` of uncaught errors
+ */
+source: string | null, stack: string | null, timestamp: number, };
@@ -1,3 +1,21 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type DirtyTable = { tableName: string, lastModified: string, };
+export type DirtyTable = { 
+tableName: string, 
+lastModified: string, 
+/**
+ * Column changes the next push of this table would send.
+ */
+pendingChanges: bigint, 
+/**
+ * Oldest HLC among the pending changes.
+ */
+oldestHlc: string | null, 
+/**
+ * Newest HLC among the pending changes.
+ */
+newestHlc: string | null, 
+/**
+ * Estimated plaintext payload of the pending changes, before encryption.
+ */
+estimatedBytes: bigint, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of `crdt:dirty-tables-changed`. Listeners re-read the dirty
+ * tables themselves.
+ */
+export type DirtyTablesChanged = Record<symbol, never>;
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Stable error codes for frontend and SDK handling. Serialized as a number.
+ */
+export enum ErrorCode { "Internal" = 100, "Serialization" = 101, "Timeout" = 102, "ResourceNotFound" = 103, "Unsupported" = 104, "Cancelled" = 105, "AlreadyInProgress" = 106, "ProcessingFailed" = 107, "SecurityViolation" = 1000, "NotFound" = 1001, "PermissionDenied" = 1002, "MutexPoisoned" = 1003, "PermissionPromptRequired" = 1004, "PermissionPromptTimeout" = 1005, "PasswordRequired" = 1006, "InvalidPassword" = 1007, "Database" = 2000, "Filesystem" = 2001, "Http" = 2002, "Shell" = 2003, "FilesystemWithPath" = 2004, "Web" = 2005, "RemoteAuthentication" = 2006, "PortUnavailable" = 2007, "Manifest" = 3000, "Validation" = 3001, "IncompatibleHostVersion" = 3002, "InvalidPublicKey" = 4000, "InvalidSignature" = 4001, "SignatureVerificationFailed" = 4002, "CalculateHash" = 4003, "InvalidActionString" = 4004, "Crypto" = 4005, "Installation" = 5000, "Storage" = 6000, "StorageConnection" = 6001, "StorageQuotaExceeded" = 6002, "LimitExceeded" = 7000, "InvalidQuery" = 8000, "Transaction" = 8001, "Migration" = 8002, "Connection" = 8003, "Crdt" = 8004, "VaultAlreadyExists" = 8010, "VaultAlreadyOpenElsewhere" = 8011, "VaultBusy" = 8012, "VaultAlreadyMountedInProcess" = 8013, "VaultLockedOut" = 8014, "VaultNotOpen" = 8015, "SyncInvalidConfig" = 9000, "SyncProvider" = 9001, "SyncEngine" = 9002, "SyncNotRunning" = 9003, "BridgeNotRunning" = 10000, "BridgeAlreadyRunning" = 10001, "BridgeUnauthorized" = 10002, "BridgeAuthorizationDenied" = 10003, "BridgeConnection" = 10004, "LocalApiNotRunning" = 10005, "LocalApiAlreadyRunning" = 10006, "VaultSyncTransport" = 11000, "VaultSyncEnvelope" = 11001, "VaultSyncShare" = 11002 }
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Fields every serialized command error carries. Error types may add
+ * their own fields next to these (e.g. `type`) for older callers.
+ */
+export type ErrorEnvelope = { code: number, 
+/**
+ * Technical description (English)
+ */
+message: string, 
+/**
+ * Localized message for the user, in the ApplicationContext locale
+ */
+userMessage: string, 
+/**
+ * Structured context of the error, `null` if there is none
+ */
+details: unknown, };
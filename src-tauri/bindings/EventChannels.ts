@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Event bus channels of an extension. Publishing needs the exact channel
+ * name; subscriptions may end in `.*` (or be `*`), but such wildcards are
+ * only honoured when the vault's event policy allows them.
+ */
+export type EventChannels = { 
+/**
+ * Channels the extension may publish to
+ */
+publish: Array<string>, 
+/**
+ * Channels (or wildcard patterns) the extension receives events from
+ */
+subscribe: Array<string>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Wire format of every registered event.
+ */
+export type EventEnvelope<T> = { 
+/**
+ * Payload schema version
+ */
+v: number, payload: T, };
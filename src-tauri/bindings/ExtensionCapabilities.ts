@@ -0,0 +1,39 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Optional subsystems available on this host
+ */
+export type ExtensionCapabilities = { 
+/**
+ * `linux`, `macos`, `windows`, `android` or `ios`
+ */
+platform: string, 
+/**
+ * Host API version the extension is checked against
+ */
+hostApiVersion: string, 
+/**
+ * Extensions can open in native windows (otherwise iframe only)
+ */
+webviewWindows: boolean, 
+/**
+ * PTY shell sessions
+ */
+shell: boolean, 
+/**
+ * LocalSend-compatible file transfer; not part of this build
+ */
+localsend: boolean, 
+/**
+ * External bridge for browser extensions and local clients
+ */
+externalBridge: boolean, 
+/**
+ * Biometric authentication: on desktop a platform authenticator is set
+ * up, on mobile the biometry plugin is present
+ */
+biometrics: boolean, 
+/**
+ * Photo capture and QR scanning through the main window
+ */
+camera: boolean, };
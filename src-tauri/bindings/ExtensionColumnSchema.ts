@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A column of an extension table.
+ */
+export type ExtensionColumnSchema = { 
+name: string, 
+/**
+ * Declared type as written in `CREATE TABLE` (may be empty)
+ */
+dataType: string, 
+notNull: boolean, 
+/**
+ * Default value expression as SQL text
+ */
+defaultValue: string | null, 
+/**
+ * 1-based position in the primary key, 0 if not part of it
+ */
+primaryKeyPosition: number, 
+/**
+ * Generated column (`GENERATED ALWAYS AS`)
+ */
+generated: boolean, 
+/**
+ * CRDT bookkeeping column added by the vault (`haex_hlc`, `haex_column_hlcs`)
+ */
+crdt: boolean, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExtensionDataImportTable } from "./ExtensionDataImportTable";
+
+/**
+ * Result of `extension_import_data`
+ */
+export type ExtensionDataImportResult = { 
+/**
+ * Version of the extension that exported the data
+ */
+sourceVersion: string, exportedAt: string, tables: Array<ExtensionDataImportTable>, totalRows: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of restoring one table
+ */
+export type ExtensionDataImportTable = { tableName: string, rows: number, 
+/**
+ * Archived columns the table doesn't have (anymore)
+ */
+skippedColumns: Array<string>, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExtensionEventMessage } from "./ExtensionEventMessage";
+
+/**
+ * Payload of `EXTENSION_EVENT` sent to the main window for iframe
+ * subscribers. The frontend delivers it to these extensions only and strips
+ * `subscriberExtensionIds` before forwarding.
+ */
+export type ExtensionEventBroadcast = { subscriberExtensionIds: Array<string>, } & ExtensionEventMessage;
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Event as delivered to a subscriber.
+ */
+export type ExtensionEventMessage = { channel: string, 
+/**
+ * Public key of the publishing extension
+ */
+sourcePublicKey: string, 
+/**
+ * Name of the publishing extension
+ */
+sourceName: string, payload: unknown, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Vault-wide rules of the event bus.
+ */
+export type ExtensionEventPolicy = { 
+/**
+ * Honour wildcard subscriptions (`*`, `prefix.*`) from manifests
+ */
+allowWildcardSubscriptions: boolean, };
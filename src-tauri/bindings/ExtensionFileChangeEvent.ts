@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FileChange } from "./FileChange";
+
+/**
+ * Event delivered to an extension for one of its watches (desktop only)
+ */
+export type ExtensionFileChangeEvent = { 
+/**
+ * Extension that owns the watch; the main window routes iframe
+ * extensions by it
+ */
+extensionId: string, 
+/**
+ * ID the extension passed to `extension_filesystem_watch`
+ */
+watchId: string, 
+/**
+ * Changes collected during one debounce interval
+ */
+changes: Array<FileChange>, };
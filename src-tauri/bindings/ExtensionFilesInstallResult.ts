@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PermissionDiff } from "./PermissionDiff";
+
+/**
+ * Result of installing extension files for an already registered extension.
+ */
+export type ExtensionFilesInstallResult = { 
+extensionId: string, 
+/**
+ * Permission changes of the bundle compared to the installed version
+ */
+permissionDiff: PermissionDiff, 
+/**
+ * True if added or changed permissions were granted without a prompt
+ */
+autoGranted: boolean, };
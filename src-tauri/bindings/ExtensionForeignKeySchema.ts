@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A foreign key of an extension table. Composite keys list their columns
+ * pairwise in `columns` / `referencedColumns`.
+ */
+export type ExtensionForeignKeySchema = { 
+columns: Array<string>, 
+referencedTable: string, 
+/**
+ * Empty entries reference the primary key of `referencedTable`
+ */
+referencedColumns: Array<string | null>, 
+onUpdate: string, 
+onDelete: string, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An index of an extension table.
+ */
+export type ExtensionIndexSchema = { 
+name: string, 
+unique: boolean, 
+/**
+ * `c` for `CREATE INDEX`, `u` for a UNIQUE constraint, `pk` for the primary key
+ */
+origin: string, 
+/**
+ * Partial index (`CREATE INDEX ... WHERE`)
+ */
+partial: boolean, 
+/**
+ * Indexed columns in index order; expression entries are `null`
+ */
+columns: Array<string | null>, };
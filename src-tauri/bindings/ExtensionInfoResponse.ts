@@ -1,5 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { DisplayMode } from "./DisplayMode";
 import type { ManifestI18nEntry } from "./ManifestI18nEntry";
+import type { ManifestLocales } from "./ManifestLocales";
 
-export type ExtensionInfoResponse = { id: string, publicKey: string, name: string, version: string, author: string | null, enabled: boolean, description: string | null, homepage: string | null, icon: string | null, entry: string | null, singleInstance: boolean | null, displayMode: DisplayMode | null, devServerUrl: string | null, i18n?: { [key in string]: ManifestI18nEntry } | null, };
+export type ExtensionInfoResponse = { id: string, publicKey: string, name: string, version: string, author: string | null, enabled: boolean, description: string | null, homepage: string | null, icon: string | null, entry: string | null, singleInstance: boolean | null, displayMode: DisplayMode | null, devServerUrl: string | null, i18n?: { [key in string]: ManifestI18nEntry } | null, locales?: ManifestLocales | null, };
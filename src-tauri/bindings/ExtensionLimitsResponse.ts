@@ -3,7 +3,7 @@
 /**
  * Response with extension limits
  */
-export type ExtensionLimitsResponse = { extensionId: string, queryTimeoutMs: bigint, maxResultRows: bigint, maxConcurrentQueries: bigint, maxQuerySizeBytes: bigint, 
+export type ExtensionLimitsResponse = { extensionId: string, queryTimeoutMs: bigint, maxResultRows: bigint, maxConcurrentQueries: bigint, maxQuerySizeBytes: bigint, maxMemoryBytes: bigint, maxCpuPercent: bigint, 
 /**
  * Whether custom limits are configured (false = using defaults)
  */
@@ -1,7 +1,11 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { DisplayMode } from "./DisplayMode";
+import type { EventChannels } from "./EventChannels";
 import type { ExtensionPermissions } from "./ExtensionPermissions";
+import type { KeyRotationProof } from "./KeyRotationProof";
 import type { ManifestI18nEntry } from "./ManifestI18nEntry";
+import type { ManifestLocales } from "./ManifestLocales";
+import type { ShareTarget } from "./ShareTarget";
 
 export type ExtensionManifest = { name: string, version: string, author: string | null, entry: string | null, icon: string | null, publicKey: string, signature: string, permissions: ExtensionPermissions, homepage: string | null, description: string | null, singleInstance: boolean | null, displayMode: DisplayMode | null, 
 /**
@@ -14,4 +18,32 @@ migrationsDir: string | null,
  * Locale-specific overrides for name, description, etc.
  * Key is locale code (e.g. "de", "en"), value contains localized fields.
  */
-i18n: { [key in string]: ManifestI18nEntry } | null, };
+i18n: { [key in string]: ManifestI18nEntry } | null, 
+/**
+ * Key rotation chain from a previously used signing key to `public_key`.
+ * Lets installed extensions accept bundles signed by a rotated key.
+ */
+keyRotations: Array<KeyRotationProof> | null, 
+/**
+ * Supported locales and default locale for the extension's
+ * `locales/<locale>.json` assets.
+ */
+locales: ManifestLocales | null, 
+/**
+ * Content the extension accepts from the OS share sheet (mobile).
+ * Also accepted as `share-target`.
+ */
+shareTarget: ShareTarget | null, 
+/**
+ * Event bus channels the extension publishes to and listens on
+ */
+events: EventChannels | null, 
+/**
+ * Lowest host API version the extension works with (e.g. "3.1")
+ */
+minHostVersion: string | null, 
+/**
+ * Highest host API version the extension works with; "3" accepts
+ * every 3.x host
+ */
+maxHostVersion: string | null, };
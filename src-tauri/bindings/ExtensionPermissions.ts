@@ -4,4 +4,4 @@ import type { PermissionEntry } from "./PermissionEntry";
 /**
  * Definiert die einheitliche Struktur für alle Berechtigungsarten im Manifest und UI.
  */
-export type ExtensionPermissions = { database: Array<PermissionEntry> | null, filesystem: Array<PermissionEntry> | null, http: Array<PermissionEntry> | null, shell: Array<PermissionEntry> | null, filesync: Array<PermissionEntry> | null, spaces: Array<PermissionEntry> | null, identities: Array<PermissionEntry> | null, passwords: Array<PermissionEntry> | null, mail: Array<PermissionEntry> | null, };
+export type ExtensionPermissions = { database: Array<PermissionEntry> | null, filesystem: Array<PermissionEntry> | null, http: Array<PermissionEntry> | null, shell: Array<PermissionEntry> | null, filesync: Array<PermissionEntry> | null, spaces: Array<PermissionEntry> | null, identities: Array<PermissionEntry> | null, passwords: Array<PermissionEntry> | null, mail: Array<PermissionEntry> | null, camera: Array<PermissionEntry> | null, contacts: Array<PermissionEntry> | null, calendar: Array<PermissionEntry> | null, microphone: Array<PermissionEntry> | null, speech: Array<PermissionEntry> | null, };
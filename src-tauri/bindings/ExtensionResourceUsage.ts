@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ResourceKind } from "./ResourceKind";
+
+/**
+ * Latest sample for one extension window
+ */
+export type ExtensionResourceUsage = { windowId: string, extensionId: string, 
+/**
+ * Processes attributed to the window (0 = not attributed yet)
+ */
+processCount: number, 
+/**
+ * Resident memory in bytes
+ */
+memoryBytes: number, 
+/**
+ * CPU usage in percent of one core (can exceed 100)
+ */
+cpuPercent: number, 
+/**
+ * Resources currently above their limit
+ */
+exceeded: Array<ResourceKind>, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A row filter registered by an extension on one of its tables.
+ */
+export type ExtensionRowFilter = { tableName: string, 
+/**
+ * Boolean SQL expression over the table's columns, `?` for parameters
+ */
+predicate: string, 
+/**
+ * Values bound to the predicate's placeholders
+ */
+params: Array<unknown>, };
@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExtensionColumnSchema } from "./ExtensionColumnSchema";
+import type { ExtensionForeignKeySchema } from "./ExtensionForeignKeySchema";
+import type { ExtensionIndexSchema } from "./ExtensionIndexSchema";
+
+/**
+ * Schema of one extension table or view.
+ */
+export type ExtensionTableSchema = { 
+name: string, 
+/**
+ * Read-only view (`CREATE VIEW`); views have no indexes or foreign keys
+ */
+view: boolean, 
+/**
+ * `CREATE TABLE` / `CREATE VIEW` statement from `sqlite_master`
+ */
+sql: string, 
+columns: Array<ExtensionColumnSchema>, 
+indexes: Array<ExtensionIndexSchema>, 
+foreignKeys: Array<ExtensionForeignKeySchema>, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FileChangeType } from "./FileChangeType";
+
+/**
+ * A single change reported to an extension watch
+ */
+export type FileChange = { 
+/**
+ * Path relative to the watched path (empty for the watched path itself)
+ */
+path: string, changeType: FileChangeType, };
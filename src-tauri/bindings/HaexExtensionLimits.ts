@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type HaexExtensionLimits = { id: string, extensionId: string, queryTimeoutMs: bigint, maxResultRows: bigint, maxConcurrentQueries: bigint, maxQuerySizeBytes: bigint, maxMemoryBytes: bigint | null, maxCpuPercent: bigint | null, createdAt: string | null, updatedAt: string | null, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type HaexExtensionPermissions = { id: string, extensionId: string, resourceType: string | null, action: string | null, target: string | null, constraints: string | null, status: string, createdAt: string | null, updatedAt: string | null, };
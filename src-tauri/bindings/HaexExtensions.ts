@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type HaexExtensions = { id: string, publicKey: string, name: string, version: string, author: string | null, description: string | null, entry: string | null, homepage: string | null, enabled: boolean | null, icon: string | null, signature: string, singleInstance: boolean | null, displayMode: string | null, i18n: string | null, devPath: string | null, createdAt: string | null, updatedAt: string | null, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type HaexPasswordsGeneratorPresets = { id: string, name: string, length: bigint, uppercase: boolean, lowercase: boolean, numbers: boolean, symbols: boolean, excludeChars: string | null, usePattern: boolean, pattern: string | null, isDefault: boolean, createdAt: string | null, updatedAt: string | null, };
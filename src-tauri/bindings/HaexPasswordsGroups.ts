@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type HaexPasswordsGroups = { id: string, name: string | null, description: string | null, icon: string | null, sortOrder: bigint | null, color: string | null, parentId: string | null, createdAt: string | null, updatedAt: string | null, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type HaexPasswordsItemDetails = { id: string, title: string | null, username: string | null, password: string | null, note: string | null, icon: string | null, color: string | null, url: string | null, otpSecret: string | null, otpDigits: bigint | null, otpPeriod: bigint | null, otpAlgorithm: string | null, expiresAt: string | null, autofillAliases: string | null, createdAt: string | null, updatedAt: string | null, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type HaexPasswordsItemKeyValues = { id: string, itemId: string, key: string | null, value: string | null, updatedAt: string | null, };
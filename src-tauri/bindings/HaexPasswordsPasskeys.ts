@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type HaexPasswordsPasskeys = { id: string, itemId: string | null, credentialId: string, relyingPartyId: string, relyingPartyName: string | null, userHandle: string, userName: string | null, userDisplayName: string | null, privateKey: string, publicKey: string, algorithm: bigint, signCount: bigint, isDiscoverable: boolean, icon: string | null, color: string | null, nickname: string | null, createdAt: string | null, lastUsedAt: string | null, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type HaexWorkspacesNoSync = { id: string, deviceId: string, name: string, position: bigint, background: string | null, };
@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HealthStatus } from "./HealthStatus";
+import type { MissingExtension } from "./MissingExtension";
+import type { SubsystemHealth } from "./SubsystemHealth";
+
+/**
+ * Result of `app_health_check`
+ */
+export type HealthReport = { 
+/**
+ * Worst status of all subsystems
+ */
+status: HealthStatus, subsystems: Array<SubsystemHealth>, 
+/**
+ * Installed extensions whose files are missing on this device
+ */
+missingExtensions: Array<MissingExtension>, 
+/**
+ * RFC 3339 time of the check
+ */
+checkedAt: string, };
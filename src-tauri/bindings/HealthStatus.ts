@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Status of one subsystem, ordered from best to worst
+ */
+export type HealthStatus = "ok" | "degraded" | "error";
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * HMAC used for page authentication (and the matching PBKDF2 variant)
+ */
+export type HmacAlgorithm = "hmacSha1" | "hmacSha256" | "hmacSha512";
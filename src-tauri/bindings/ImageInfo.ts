@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImageMetadata } from "./ImageMetadata";
+
+export type ImageInfo = { mimeType: string, 
+/**
+ * As displayed, i.e. after applying the EXIF orientation
+ */
+width: number, height: number, size: number, 
+/**
+ * `None` for formats whose metadata isn't inspected (only JPEG and
+ * PNG are)
+ */
+metadata?: ImageMetadata, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Metadata found in (or removed from) an image.
+ */
+export type ImageMetadata = { exif: boolean, 
+/**
+ * The EXIF block contains a GPS position
+ */
+gps: boolean, xmp: boolean, 
+/**
+ * IPTC, comments, text chunks and other application data
+ */
+other: boolean, };
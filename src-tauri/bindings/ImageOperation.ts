@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One step of `media_image_process`, applied in order.
+ */
+export type ImageOperation = { "op": "resize", maxWidth?: number, maxHeight?: number, } | { "op": "crop", x: number, y: number, width: number, height: number, } | { "op": "rotate", degrees: number, };
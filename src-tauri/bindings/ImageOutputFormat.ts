@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Encoding of processed images. WebP is written lossless.
+ */
+export type ImageOutputFormat = "jpeg" | "png" | "webp";
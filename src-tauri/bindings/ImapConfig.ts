@@ -1,7 +1,19 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { ConnectionSecurity } from "./ConnectionSecurity";
+import type { MailAuthMethod } from "./MailAuthMethod";
 
 /**
  * IMAP server configuration + credentials.
  */
-export type ImapConfig = { host: string, port: number, security: ConnectionSecurity, username: string, password: string, };
+export type ImapConfig = { host: string, port: number, security: ConnectionSecurity, auth: MailAuthMethod, username: string, 
+/**
+ * Password, app password or OAuth2 access token (see `auth`). May be
+ * left empty when `credential_item_id` is set.
+ */
+password: string, 
+/**
+ * Password-vault item to take username and secret from. Resolved by
+ * the extension wrapper within the extension's tag scope, so the
+ * secret never passes through extension code.
+ */
+credentialItemId?: string, };
@@ -0,0 +1,34 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A suggested index for an extension table.
+ */
+export type IndexSuggestion = { 
+/**
+ * Extension that owns the table and issued the slow queries
+ */
+extensionId: string, 
+/**
+ * Table that is scanned without an index
+ */
+tableName: string, 
+/**
+ * Indexed columns, in index order
+ */
+columns: Array<string>, 
+/**
+ * `CREATE INDEX IF NOT EXISTS` statement for the extension's migrations
+ */
+sql: string, 
+/**
+ * Number of recorded slow queries this index would serve
+ */
+queryCount: number, 
+/**
+ * Slowest recorded query this index would serve, in milliseconds
+ */
+maxDurationMs: bigint, 
+/**
+ * Example query (the slowest one)
+ */
+exampleQuery: string, };
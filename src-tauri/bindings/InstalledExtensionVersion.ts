@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One version of an extension installed on this device
+ */
+export type InstalledExtensionVersion = { version: string, 
+/**
+ * Version registered in the vault, the one that gets loaded
+ */
+active: boolean, };
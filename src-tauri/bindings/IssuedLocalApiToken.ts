@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LocalApiTokenInfo } from "./LocalApiTokenInfo";
+
+/**
+ * Result of issuing a token; `token` is not retrievable later
+ */
+export type IssuedLocalApiToken = { info: LocalApiTokenInfo, token: string, };
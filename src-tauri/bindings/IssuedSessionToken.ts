@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A token as delivered to the client
+ */
+export type IssuedSessionToken = { 
+/**
+ * Opaque token for the next handshake
+ */
+token: string, tokenId: string, scopes: Array<string>, 
+/**
+ * Unix seconds
+ */
+expiresAt: number, 
+/**
+ * Unix seconds; refresh before `expiresAt`, approve again after this
+ */
+sessionExpiresAt: number, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One `json_set` operation of `sql_execute_json_patch`.
+ */
+export type JsonPatchOperation = { 
+/**
+ * JSON path to set, e.g. `$.theme` or `$.layout.columns[0]`
+ */
+path: string, 
+/**
+ * New value at `path`
+ */
+value: unknown, };
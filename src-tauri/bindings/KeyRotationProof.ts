@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One step of a signing key rotation, signed by the previous key.
+ */
+export type KeyRotationProof = { 
+/**
+ * Hex-encoded Ed25519 key being rotated away from
+ */
+previousPublicKey: string, 
+/**
+ * Hex-encoded Ed25519 key taking over
+ */
+publicKey: string, 
+/**
+ * Hex-encoded signature of the previous key over the rotation payload
+ */
+signature: string, };
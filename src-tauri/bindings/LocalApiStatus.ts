@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Running state of the local API server
+ */
+export type LocalApiStatus = { running: boolean, port: number, };
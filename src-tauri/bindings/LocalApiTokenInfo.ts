@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Token metadata shown in the settings UI
+ */
+export type LocalApiTokenInfo = { id: string, clientId: string, label: string | null, createdAt: number, };
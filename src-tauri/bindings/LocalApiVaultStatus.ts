@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Response of `GET /v1/status`
+ */
+export type LocalApiVaultStatus = { vaultOpen: boolean, 
+/**
+ * Extension the token acts as (`None` while the vault is locked)
+ */
+extensionId: string | null, extensionName: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of `local-sync-error`
+ */
+export type LocalSyncError = { spaceId: string, error: string, reconnecting: boolean, endpointClosed: boolean, attempt: number, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Unlock throttling state of a vault
+ */
+export type LockoutStatus = { 
+/**
+ * Failed unlock attempts since the last successful one
+ */
+failedAttempts: number, 
+/**
+ * Seconds until the next attempt is accepted (0 = now)
+ */
+retryAfterSecs: number, 
+/**
+ * The quick-unlock (biometric) entry must be removed; only the typed
+ * password is accepted until the next successful unlock
+ */
+quickUnlockRevoked: boolean, 
+/**
+ * Failures after which quick unlock is revoked (`null` = never)
+ */
+wipeQuickUnlockAfter: number | null, };
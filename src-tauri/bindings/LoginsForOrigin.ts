@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OriginLogin } from "./OriginLogin";
+import type { PhishingWarning } from "./PhishingWarning";
+
+/**
+ * Response of `credentials.getLoginsForOrigin`.
+ */
+export type LoginsForOrigin = { 
+/**
+ * The origin the logins are bound to
+ */
+origin: string, logins: Array<OriginLogin>, warnings: Array<PhishingWarning>, };
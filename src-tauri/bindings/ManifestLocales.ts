@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Locale metadata declared in the extension manifest.
+ */
+export type ManifestLocales = { 
+/**
+ * Locale used when none of the user's locales is available (e.g. "en")
+ */
+defaultLocale: string | null, 
+/**
+ * Locales the extension ships translations for (e.g. ["en", "de"])
+ */
+supported: Array<string>, 
+/**
+ * Directory of the `<locale>.json` files relative to the extension root
+ */
+dir: string | null, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CaptureKind } from "./CaptureKind";
+
+/**
+ * Payload of `media:capture-requested`. The main window opens the camera
+ * or microphone and answers with
+ * `media_resolve_capture(requestId, data, mimeType)`.
+ */
+export type MediaCaptureRequested = { requestId: string, extensionId: string, extensionName: string, kind: CaptureKind, 
+/**
+ * Recording limit, audio only
+ */
+maxDurationSecs?: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A capture stored in the extension's private directory.
+ */
+export type MediaCaptureResult = { 
+/**
+ * `private://captures/...`, usable with the filesystem API
+ */
+path: string, mimeType: string, size: number, };
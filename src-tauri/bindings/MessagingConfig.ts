@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a messaging transport publishes batches.
+ */
+export type MessagingConfig = { "kind": "matrix", 
+/**
+ * e.g. `https://matrix.example.org`
+ */
+homeserverUrl: string, 
+/**
+ * Room id (`!abc:example.org`), not an alias
+ */
+roomId: string, accessToken: string, } | { "kind": "nostr", 
+/**
+ * `wss://` relay URLs; a batch counts as uploaded once one accepts it
+ */
+relays: Array<string>, 
+/**
+ * Hex secret key shared by all devices of the vault. Generated
+ * when saved without one.
+ */
+secretKey: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MessagingConfig } from "./MessagingConfig";
+
+/**
+ * A configured messaging transport.
+ */
+export type MessagingTransport = { id: string, name: string, config: MessagingConfig, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A messaging transport as listed, without its credentials.
+ */
+export type MessagingTransportInfo = { id: string, name: string, 
+/**
+ * `matrix` or `nostr`
+ */
+kind: string, 
+/**
+ * Room id or relay URLs
+ */
+targets: Array<string>, 
+/**
+ * Hex public key, to check that all devices use the same Nostr key
+ */
+nostrPublicKey: string | null, };
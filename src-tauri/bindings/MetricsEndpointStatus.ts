@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * State of the Prometheus endpoint
+ */
+export type MetricsEndpointStatus = { enabled: boolean, localApiRunning: boolean, 
+/**
+ * URL to scrape (served only while enabled and the local API runs)
+ */
+endpoint: string, };
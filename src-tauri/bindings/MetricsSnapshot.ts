@@ -0,0 +1,25 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CacheStats } from "./CacheStats";
+import type { CommandStats } from "./CommandStats";
+import type { SyncStats } from "./SyncStats";
+
+/**
+ * All metrics at one point in time.
+ */
+export type MetricsSnapshot = { 
+/**
+ * Keyed by command name
+ */
+commands: { [key in string]: CommandStats }, 
+/**
+ * Keyed by sync kind (`remote`, `local_delivery`, `file_sync`)
+ */
+syncs: { [key in string]: SyncStats }, 
+/**
+ * Keyed by cache name (`file_hash`, `thumbnail`)
+ */
+caches: { [key in string]: CacheStats }, 
+/**
+ * Connected external bridge clients (always 0 on mobile)
+ */
+bridgeConnections: number, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Aktionen auf dem Mikrofon des Geräts.
+ * 
+ * Wie bei der Kamera nimmt das Hauptfenster auf; die Extension bekommt nur die
+ * fertige Datei in ihrem privaten Verzeichnis. `target` ist immer "*".
+ */
+export type MicrophoneAction = "record";
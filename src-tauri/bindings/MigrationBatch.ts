@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MigrationBatchStatus } from "./MigrationBatchStatus";
+
+/**
+ * One run of an extension's bundle migrations on this device
+ */
+export type MigrationBatch = { id: string, extensionId: string, extensionVersion: string, status: MigrationBatchStatus, 
+/**
+ * Tags of the migrations the batch applied, in order
+ */
+migrations: Array<string>, error: string | null, startedAt: string, finishedAt: string | null, 
+/**
+ * Whether the tables as they were before the batch are still saved
+ */
+hasSnapshot: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Outcome of a migration batch
+ */
+export type MigrationBatchStatus = "running" | "applied" | "failed" | "rolled_back";
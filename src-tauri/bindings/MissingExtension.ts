@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Installed extension whose files could not be found on this device
+ */
+export type MissingExtension = { id: string, publicKey: string, name: string, version: string, };
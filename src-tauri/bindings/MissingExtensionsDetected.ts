@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MissingExtension } from "./MissingExtension";
+
+/**
+ * Payload of `extension:missing-detected`
+ */
+export type MissingExtensionsDetected = { 
+/**
+ * Extensions missing since the last load
+ */
+extensions: Array<MissingExtension>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Severity of a notification; the `type` values of `haex_notifications`
+ */
+export type NotificationLevel = "info" | "success" | "warning" | "error";
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NumberStyle } from "./NumberStyle";
+
+/**
+ * Options of `extension_intl_format_number`
+ */
+export type NumberFormatOptions = { style: NumberStyle, 
+/**
+ * Defaults to 0
+ */
+minimumFractionDigits: number | null, 
+/**
+ * Defaults to 3, or 0 for percentages
+ */
+maximumFractionDigits: number | null, 
+/**
+ * Group separators, defaults to `true`
+ */
+useGrouping: boolean | null, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IndexSuggestion } from "./IndexSuggestion";
+
+/**
+ * Result of `database_optimize`.
+ */
+export type OptimizeReport = { 
+/**
+ * Whether a full `ANALYZE` was run in addition to `PRAGMA optimize`
+ */
+analyzed: boolean, 
+/**
+ * Number of recorded slow queries that were inspected
+ */
+inspectedQueries: number, 
+/**
+ * Time spent on optimize/analyze and the advisor pass, in milliseconds
+ */
+durationMs: bigint, 
+/**
+ * Suggested indexes, most frequently needed first
+ */
+suggestions: Array<IndexSuggestion>, };
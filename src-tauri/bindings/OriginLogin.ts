@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AutofillMatch } from "./AutofillMatch";
+
+/**
+ * A login released for an origin.
+ */
+export type OriginLogin = { id: string, title: string | null, username: string | null, password: string | null, 
+/**
+ * The stored URL that matched
+ */
+url: string, match: AutofillMatch, };
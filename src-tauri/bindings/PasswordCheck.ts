@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of checking a password against a policy
+ */
+export type PasswordCheck = { acceptable: boolean, entropyBits: number, 
+/**
+ * Human-readable reasons the password is rejected
+ */
+violations: Array<string>, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Requirements for the vault password
+ */
+export type PasswordPolicy = { 
+/**
+ * Minimum estimated entropy in bits (0 disables the check)
+ */
+minEntropyBits: number, 
+/**
+ * Additional case-insensitive fragments the password must not contain
+ */
+bannedPatterns: Array<string>, 
+/**
+ * Remind the user to change the password after this many days
+ */
+rotationDays: number | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Age of the vault password relative to the rotation policy
+ */
+export type PasswordRotationStatus = { 
+/**
+ * RFC3339 time of the last password change (or of the first open after
+ * this feature was introduced)
+ */
+changedAt: string | null, ageDays: number | null, rotationDays: number | null, due: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What kind of network path a QUIC connection is currently using.
+ */
+export type PathType = "direct" | "relay" | "unknown" | "closed";
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PdfFieldKind } from "./PdfFieldKind";
+
+/**
+ * A terminal AcroForm field.
+ */
+export type PdfFormField = { 
+/**
+ * Fully qualified name (partial names joined by ".")
+ */
+name: string, kind: PdfFieldKind, value?: string, 
+/**
+ * Export values of choices, on-states of checkboxes and radio buttons
+ */
+options: Array<string>, readOnly: boolean, };
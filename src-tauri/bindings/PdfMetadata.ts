@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Document information of a PDF.
+ */
+export type PdfMetadata = { pageCount: number, 
+/**
+ * Header version, e.g. "1.7"
+ */
+version: string, size: number, title?: string, author?: string, subject?: string, keywords?: string, creator?: string, producer?: string, 
+/**
+ * Raw PDF date string, e.g. "D:20261017120000+02'00'"
+ */
+creationDate?: string, modificationDate?: string, encrypted: boolean, 
+/**
+ * The document has an AcroForm with at least one field
+ */
+hasForm: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One output of `pdf_split`: pages `start..=end` (1-based).
+ */
+export type PdfSplitPart = { start: number, end: number, destination: string, };
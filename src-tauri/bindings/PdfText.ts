@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Extracted text, one entry per page.
+ */
+export type PdfText = { pages: Array<string>, };
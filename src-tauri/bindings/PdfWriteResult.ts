@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A written PDF file.
+ */
+export type PdfWriteResult = { path: string, pageCount: number, size: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConnectionDiagnostics } from "./ConnectionDiagnostics";
+
+/**
+ * Payload of `peer-storage:connection-changed`
+ */
+export type PeerConnectionChanged = { 
+/**
+ * Endpoint ID of the remote peer whose path changed
+ */
+nodeId: string, diagnostics: ConnectionDiagnostics, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PeerStorageStateReason } from "./PeerStorageStateReason";
+
+/**
+ * Payload of `peer-storage:state-changed`
+ */
+export type PeerStorageStateChanged = { running: boolean, reason: PeerStorageStateReason, 
+/**
+ * How long the endpoint was alive before it closed, in seconds
+ */
+uptimeSecs: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PermissionDiffEntry } from "./PermissionDiffEntry";
+
+/**
+ * Permission changes between the installed version and an updated bundle.
+ */
+export type PermissionDiff = { added: Array<PermissionDiffEntry>, removed: Array<PermissionDiffEntry>, changed: Array<PermissionDiffEntry>, };
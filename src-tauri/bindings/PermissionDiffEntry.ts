@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RequestedPermission } from "./RequestedPermission";
+import type { ResourceType } from "./ResourceType";
+
+/**
+ * All requests of one `(resource_type, target)` pair before and after an update.
+ */
+export type PermissionDiffEntry = { 
+resourceType: ResourceType, 
+target: string, 
+/**
+ * Requests held by the installed version (empty for added targets)
+ */
+previous: Array<RequestedPermission>, 
+/**
+ * Requests of the new bundle (empty for removed targets)
+ */
+requested: Array<RequestedPermission>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Why an origin looks suspicious compared to the stored URLs.
+ */
+export type PhishingWarning = { "kind": "insecureOrigin", storedUrl: string, } | { "kind": "lookalike", storedHost: string, };
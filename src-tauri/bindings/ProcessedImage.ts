@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImageMetadata } from "./ImageMetadata";
+
+/**
+ * Result of `media_image_process` / `media_image_strip_metadata`.
+ * 
+ * The image was written to `path` if a destination was given, otherwise
+ * it is returned base64-encoded in `data`.
+ */
+export type ProcessedImage = { mimeType: string, width: number, height: number, size: number, 
+/**
+ * Metadata the output no longer carries
+ */
+removed: ImageMetadata, path?: string, data?: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An extension as listed in a profile
+ */
+export type ProfileExtension = { publicKey: string, name: string, version: string, enabled: boolean, };
@@ -0,0 +1,32 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProfileExtension } from "./ProfileExtension";
+
+/**
+ * What `profile_import` applied and what is left for the user
+ */
+export type ProfileImportResult = { 
+/**
+ * Extensions of the profile that are not installed here
+ */
+missingExtensions: Array<ProfileExtension>, 
+/**
+ * Permission decisions written for installed extensions
+ */
+permissionsApplied: number, 
+/**
+ * Names of the storage backends that were added
+ */
+backendsAdded: Array<string>, 
+/**
+ * Ids of added backends that were exported without credentials and are
+ *  therefore disabled
+ */
+backendsNeedingCredentials: Array<string>, 
+/**
+ * Workspaces that replaced this device's layout
+ */
+workspacesRestored: number, 
+/**
+ * Keys of the restored local settings
+ */
+settingsRestored: Array<string>, };
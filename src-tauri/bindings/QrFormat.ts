@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Output format of `codes_generate_qr`.
+ */
+export type QrFormat = "png" | "svg";
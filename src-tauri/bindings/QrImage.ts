@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A rendered QR code. `data` is the base64-encoded PNG or SVG document.
+ */
+export type QrImage = { mimeType: string, 
+/**
+ * Edge length in pixels, quiet zone included
+ */
+size: number, data: string, };
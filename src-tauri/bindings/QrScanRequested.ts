@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of `codes:scan-requested`. The main window opens the camera and
+ * answers with `codes_resolve_scan(requestId, text)`.
+ */
+export type QrScanRequested = { requestId: string, extensionId: string, extensionName: string, };
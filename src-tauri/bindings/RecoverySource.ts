@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where the files of a missing extension come from
+ */
+export type RecoverySource = { "type": "file", fileBytes: Array<number>, } | { "type": "registry", downloadUrl: string, sha256: string | null, };
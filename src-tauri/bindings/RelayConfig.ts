@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Relay settings, stored in the relay store.
+ */
+export type RelayConfig = { 
+/**
+ * Accept relay peers on the external bridge
+ */
+enabled: boolean, 
+/**
+ * Messages are dropped after this many days, or earlier if the pusher
+ *  asked for a shorter TTL
+ */
+retentionDays: number, 
+/**
+ * Upper bound for the stored payloads of one channel
+ */
+maxChannelBytes: number, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A stored message as returned by `relay.pull`.
+ */
+export type RelayMessage = { seq: number, 
+/**
+ * Client id of the pushing peer
+ */
+sender: string, 
+/**
+ * Base64 ciphertext
+ */
+data: string, 
+/**
+ * Unix seconds
+ */
+createdAt: number, };
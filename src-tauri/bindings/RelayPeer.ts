@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A bridge client allowed to use the relay.
+ */
+export type RelayPeer = { 
+/**
+ * Bridge client id (public key fingerprint)
+ */
+clientId: string, label: string, 
+/**
+ * Channels the peer may push to and pull from
+ */
+channels: Array<string>, 
+/**
+ * Unix seconds
+ */
+createdAt: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Size of the relay store.
+ */
+export type RelayStats = { peers: number, messages: number, bytes: number, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RelayConfig } from "./RelayConfig";
+import type { RelayStats } from "./RelayStats";
+
+/**
+ * State of the relay as shown in the settings
+ */
+export type RelayStatus = { 
+/**
+ * Accepting peers (enabled in the config or running headless)
+ */
+active: boolean, headless: boolean, config: RelayConfig, stats: RelayStats, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CredentialPromptKind } from "./CredentialPromptKind";
+
+/**
+ * A decision the user asked to remember.
+ */
+export type RememberedDecision = { clientId: string, origin: string, kind: CredentialPromptKind, allow: boolean, 
+/**
+ * RFC3339
+ */
+decidedAt: string, };
@@ -0,0 +1,29 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RemoteApplyStatus } from "./RemoteApplyStatus";
+
+/**
+ * Progress of a chunked apply. Emitted after every chunk and returned as
+ * the final result.
+ */
+export type RemoteApplyProgress = { 
+sessionId: string, 
+status: RemoteApplyStatus, 
+/**
+ * Column changes passed in for this run.
+ */
+totalChanges: bigint, 
+/**
+ * Column changes applied so far in this run.
+ */
+appliedChanges: bigint, 
+/**
+ * Column changes skipped because an earlier run of the session already
+ * applied them.
+ */
+skippedChanges: bigint, 
+completedChunks: number, 
+totalChunks: number, 
+/**
+ * HLC of the last transaction applied (the resume position).
+ */
+lastAppliedHlc: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PermissionConstraints } from "./PermissionConstraints";
+
+/**
+ * A single action (plus optional constraints) requested for a target.
+ */
+export type RequestedPermission = { action: string, constraints: PermissionConstraints | null, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ResourceKind } from "./ResourceKind";
+
+/**
+ * Payload of `EVENT_EXTENSION_RESOURCE_LIMIT_EXCEEDED`
+ */
+export type ResourceLimitWarning = { windowId: string, extensionId: string, resource: ResourceKind, 
+/**
+ * Measured value (bytes or percent)
+ */
+value: number, 
+/**
+ * Configured limit (bytes or percent)
+ */
+limit: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Why a restore was refused.
+ */
+export type RestoreConflict = { "kind": "snapshotMissing" } | { "kind": "rowExists", currentHlc: string | null, } | { "kind": "deletedAgain", newerId: string, newerHlc: string, };
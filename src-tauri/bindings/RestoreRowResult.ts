@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RestoreConflict } from "./RestoreConflict";
+
+/**
+ * Outcome of `crdt_restore_row`.
+ */
+export type RestoreRowResult = { restored: boolean, conflict: RestoreConflict | null, 
+/**
+ * Snapshot columns that no longer exist in the table and were skipped.
+ */
+droppedColumns: Array<string>, 
+/**
+ * Delete-log ids of child rows restored along with this row because a
+ * cascade rule had deleted them together with it.
+ */
+restoredChildren: Array<string>, };
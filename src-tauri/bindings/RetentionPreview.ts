@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a rule would delete right now (dry run).
+ */
+export type RetentionPreview = { ruleId: string, tableName: string, 
+/**
+ * Rows older than the cutoff
+ */
+matchingRows: number, totalRows: number, 
+/**
+ * Rows the next run deletes (capped at `MAX_DELETES_PER_RUN`)
+ */
+nextRunDeletes: number, };
@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RetentionRuleStats } from "./RetentionRuleStats";
+import type { RetentionTimestampFormat } from "./RetentionTimestampFormat";
+
+/**
+ * Deletes rows of `tableName` whose `timestampColumn` is older than
+ * `maxAgeDays`. Rows without a timestamp are kept.
+ */
+export type RetentionRule = { 
+/**
+ * Assigned on save; leave empty for a new rule
+ */
+id: string, tableName: string, timestampColumn: string, timestampFormat: RetentionTimestampFormat, maxAgeDays: number, enabled: boolean, 
+/**
+ * Maintained by the runs; ignored on save
+ */
+stats: RetentionRuleStats, };
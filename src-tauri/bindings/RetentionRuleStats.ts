@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Statistics of a rule's past runs.
+ */
+export type RetentionRuleStats = { lastRunAt: string | null, 
+/**
+ * Rows deleted by the last run
+ */
+lastDeleted: number, 
+/**
+ * Rows deleted since the rule was created
+ */
+totalDeleted: number, lastError: string | null, };
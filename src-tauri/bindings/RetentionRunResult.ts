@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Outcome of one rule in a run.
+ */
+export type RetentionRunResult = { ruleId: string, tableName: string, deleted: number, error: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How the timestamp column stores its values.
+ */
+export type RetentionTimestampFormat = "iso8601" | "unixSeconds" | "unixMillis";
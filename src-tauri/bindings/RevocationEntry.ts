@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One revoked public key, optionally narrowed to an extension name and versions.
+ */
+export type RevocationEntry = { 
+/**
+ * Hex-encoded Ed25519 key of the revoked extension(s)
+ */
+publicKey: string, 
+/**
+ * Extension name; `None` revokes every extension signed with the key
+ */
+name: string | null, 
+/**
+ * Revoked versions; `None` revokes all versions
+ */
+versions: Array<string> | null, reason: string | null, };
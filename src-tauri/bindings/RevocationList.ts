@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RevocationEntry } from "./RevocationEntry";
+
+/**
+ * Revocation list as published by a registry.
+ */
+export type RevocationList = { 
+/**
+ * Issue time (unix ms); older lists never replace newer ones
+ */
+issuedAt: number, entries: Array<RevocationEntry>, 
+/**
+ * Hex-encoded Ed25519 signature of the registry over `signing_payload`
+ */
+signature: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RevokedExtension } from "./RevokedExtension";
+
+/**
+ * Result of `extension_check_revocations`.
+ */
+export type RevocationReport = { 
+/**
+ * Issue time of the stored list, `None` if no list was imported yet
+ */
+issuedAt: number | null, registryPublicKey: string | null, allowRevoked: boolean, revoked: Array<RevokedExtension>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A registered extension matched by the revocation list.
+ */
+export type RevokedExtension = { id: string, name: string, publicKey: string, version: string, reason: string | null, 
+/**
+ * False if it was refused by the loader, true if `allow_revoked` let it load
+ */
+loaded: boolean, };
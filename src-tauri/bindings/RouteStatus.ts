@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Load of one route, for the settings UI
+ */
+export type RouteStatus = { clientId: string, extensionId: string, 
+/**
+ * Queued requests, including the one being processed
+ */
+pending: number, completed: number, 
+/**
+ * Requests rejected because the queue was full
+ */
+rejected: number, };
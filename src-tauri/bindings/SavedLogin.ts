@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Response of `credentials.saveLogin`.
+ */
+export type SavedLogin = { origin: string, 
+/**
+ * Password item id
+ */
+id: string, 
+/**
+ * False if an existing login got the new password
+ */
+created: boolean, };
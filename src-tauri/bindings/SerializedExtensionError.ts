@@ -4,4 +4,4 @@
  * Serialized representation of ExtensionError for TypeScript.
  * Not constructed in Rust — serves as the schema for the auto-generated TS type via ts_rs.
  */
-export type SerializedExtensionError = { code: number, type: string, message: string, extensionId: string | null, };
+export type SerializedExtensionError = { code: number, type: string, message: string, userMessage: string, extensionId: string | null, details: unknown, };
@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Stored metadata of an issued token (the token itself is not stored)
+ */
+export type SessionTokenInfo = { id: string, clientId: string, clientName: string, 
+/**
+ * SHA-256 of the client's public key (hex)
+ */
+publicKeyHash: string, 
+/**
+ * Extension IDs the token grants access to
+ */
+scopes: Array<string>, 
+/**
+ * Unix seconds
+ */
+issuedAt: number, 
+/**
+ * Unix seconds
+ */
+expiresAt: number, 
+/**
+ * When the user approved the client (unix seconds); refreshing keeps it
+ */
+approvedAt: number, 
+/**
+ * Refreshes since the approval
+ */
+refreshCount: number, };
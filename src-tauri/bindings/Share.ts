@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ShareDirection } from "./ShareDirection";
+import type { ShareMember } from "./ShareMember";
+import type { ShareStatus } from "./ShareStatus";
+
+/**
+ * A share as shown to the user; the share key is never exposed.
+ */
+export type Share = { 
+id: string, 
+direction: ShareDirection, 
+name: string, 
+tables: Array<string>, 
+spaceId: string | null, 
+ownerDid: string, 
+backendId: string, 
+/**
+ * Bumped on every key rotation; part of the object prefix.
+ */
+keyGeneration: number, 
+status: ShareStatus, 
+createdAt: string | null, 
+revokedAt: string | null, 
+/**
+ * Recipients of an outgoing share, including revoked ones. Empty for
+ * incoming shares.
+ */
+members: Array<ShareMember>, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Share } from "./Share";
+import type { ShareInvite } from "./ShareInvite";
+
+/**
+ * Result of `share_create`: the share and one invite per recipient, to be
+ * delivered out of band.
+ */
+export type ShareCreated = { 
+share: Share, 
+invites: Array<ShareInvite>, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ShareTargetCandidate } from "./ShareTargetCandidate";
+import type { SharedFileInfo } from "./SharedFileInfo";
+
+/**
+ * A staged share with the extensions that can receive it.
+ */
+export type ShareIntake = { shareId: string, files: Array<SharedFileInfo>, text: string | null, subject: string | null, targets: Array<ShareTargetCandidate>, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Everything a recipient needs to consume a share, signed by the owner.
+ * The share key is sealed for `recipient_did` only.
+ */
+export type ShareInvite = { 
+shareId: string, 
+name: string, 
+tables: Array<string>, 
+spaceId: string | null, 
+ownerDid: string, 
+recipientDid: string, 
+keyGeneration: number, 
+wrappedKey: string, 
+wrapNonce: string, 
+wrapSalt: string, 
+wrapEphemeralPublicKey: string, 
+/**
+ * Base64 Ed25519 signature of the owner over the invite with an empty
+ * signature.
+ */
+signature: string, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ShareRole } from "./ShareRole";
+
+/**
+ * Role of one member device in a share, signed by the share owner.
+ */
+export type ShareRoleAssignment = { 
+shareId: string, 
+did: string, 
+/**
+ * HLC node id (hex) the device stamps its writes with.
+ */
+deviceNode: string, 
+role: ShareRole, 
+/**
+ * Base64 Ed25519 signature of the share owner.
+ */
+signature: string, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Share-sheet capability of an extension. Shares are offered only to
+ * extensions whose target accepts every shared file.
+ */
+export type ShareTarget = { 
+/**
+ * MIME types of accepted files; `image/*` and `*/*` match a whole group
+ */
+accept: Array<string>, 
+/**
+ * Accepts shared text and links
+ */
+text: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Extension offered in the chooser.
+ */
+export type ShareTargetCandidate = { extensionId: string, name: string, icon: string | null, };
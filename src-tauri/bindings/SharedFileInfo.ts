@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Metadata of a shared file as shown in the chooser.
+ */
+export type SharedFileInfo = { name: string, size: bigint, mimeType: string, };
@@ -1,7 +1,16 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { ConnectionSecurity } from "./ConnectionSecurity";
+import type { MailAuthMethod } from "./MailAuthMethod";
 
 /**
  * SMTP server configuration + credentials.
  */
-export type SmtpConfig = { host: string, port: number, security: ConnectionSecurity, username: string, password: string, };
+export type SmtpConfig = { host: string, port: number, security: ConnectionSecurity, auth: MailAuthMethod, username: string, 
+/**
+ * Password, app password or OAuth2 access token (see `auth`).
+ */
+password: string, 
+/**
+ * Password-vault item to take username and secret from.
+ */
+credentialItemId?: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Usage of one space within one backend
+ */
+export type SpaceUsage = { 
+/**
+ * `None` for objects no space's sync rule covers
+ */
+spaceId: string | null, backendId: string, files: number, bytes: number, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SpeakOptions = { 
+/**
+ * Voice id from `speech_list_voices`; wins over `language`
+ */
+voice?: string, 
+/**
+ * BCP 47 tag; picks the first matching voice
+ */
+language?: string, 
+/**
+ * 0.5 to 2.0, 1.0 is the normal speed
+ */
+rate?: number, };
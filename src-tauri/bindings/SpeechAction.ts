@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Aktionen auf der Sprachausgabe und -erkennung des Betriebssystems.
+ * 
+ * Die Extension bekommt nur gesprochenen Text bzw. das Transkript, nie Zugriff
+ * auf die Engines selbst. `target` ist immer "*".
+ */
+export type SpeechAction = "speak" | "transcribe";
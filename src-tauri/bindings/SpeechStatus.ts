@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What the speech engines of this platform can do right now.
+ */
+export type SpeechStatus = { speak: boolean, transcribe: boolean, 
+/**
+ * Why speaking or transcribing is unavailable
+ */
+reason?: string, };
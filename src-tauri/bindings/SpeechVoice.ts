@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A voice of the system speech synthesizer.
+ */
+export type SpeechVoice = { 
+/**
+ * Pass as `voice` in [`SpeakOptions`]
+ */
+id: string, name: string, 
+/**
+ * BCP 47 tag, e.g. `de-DE`
+ */
+language?: string, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AutoVacuumMode } from "./AutoVacuumMode";
+
+/**
+ * Settings accepted by `database_configure_storage`; omitted fields stay unchanged.
+ */
+export type StorageConfig = { 
+/**
+ * New auto-vacuum mode. Switching from or to `none` runs a full VACUUM once.
+ */
+autoVacuum: AutoVacuumMode | null, 
+/**
+ * Page cache size in KiB
+ */
+cacheSizeKib: number | null, 
+/**
+ * Pages released per idle incremental-vacuum step
+ */
+vacuumBatchPages: number | null, };
@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AutoVacuumMode } from "./AutoVacuumMode";
+
+/**
+ * Current storage settings of the open vault
+ */
+export type StorageInfo = { 
+/**
+ * Active auto-vacuum mode
+ */
+autoVacuum: AutoVacuumMode, 
+/**
+ * Page size in bytes (fixed by SQLCipher at vault creation)
+ */
+pageSize: number, 
+/**
+ * Total number of pages in the database file
+ */
+pageCount: bigint, 
+/**
+ * Number of unused pages that a vacuum can release
+ */
+freelistCount: bigint, 
+/**
+ * Page cache size of the connection in KiB
+ */
+cacheSizeKib: number, 
+/**
+ * Pages released per idle incremental-vacuum step
+ */
+vacuumBatchPages: number, };
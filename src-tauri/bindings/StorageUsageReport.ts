@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BackendUsage } from "./BackendUsage";
+import type { SpaceUsage } from "./SpaceUsage";
+
+/**
+ * Result of `filesync_get_usage`
+ */
+export type StorageUsageReport = { backends: Array<BackendUsage>, spaces: Array<SpaceUsage>, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DeletedRowsInTable } from "./DeletedRowsInTable";
+
+/**
+ * Result of `sql_select_strict`
+ */
+export type StrictSelectResult = { rows: unknown[][], 
+/**
+ * Read CRDT tables that contain deleted rows. With `filter` these rows
+ * were left out, with `warn` they may be part of `rows`.
+ */
+deletedRows: Array<DeletedRowsInTable>, 
+/**
+ * `true` in `warn` mode if `rows` may include deleted rows
+ */
+mayIncludeDeletedRows: boolean, };
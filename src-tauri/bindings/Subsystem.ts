@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Subsystems covered by the health check
+ */
+export type Subsystem = "database" | "hlc" | "extensionManager" | "externalBridge" | "fileWatchers" | "syncOrchestrator" | "storageBackends" | "scheduler";
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HealthStatus } from "./HealthStatus";
+import type { Subsystem } from "./Subsystem";
+
+export type SubsystemHealth = { subsystem: Subsystem, status: HealthStatus, 
+/**
+ * Human-readable explanation, shown next to the status
+ */
+detail: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of `sync:completed`.
+ */
+export type SyncCycleReport = { 
+peerId: string, 
+pushedChanges: bigint, 
+pushedBatches: number, 
+pulledChanges: bigint, 
+pulledBatches: number, };
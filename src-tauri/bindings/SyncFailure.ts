@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of `sync:failed`.
+ */
+export type SyncFailure = { 
+peerId: string, 
+error: string, 
+/**
+ * Consecutive failed cycles, including this one.
+ */
+attempt: number, 
+/**
+ * Seconds until the next attempt.
+ */
+retryInSecs: bigint, };
@@ -1,14 +1,15 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SyncPhase } from "./SyncPhase";
 
 /**
- * Progress update during sync execution
+ * Payload of `sync:progress`, emitted after every batch.
  */
-export type SyncProgress = { currentFile: string, filesDone: number, filesTotal: number, bytesDone: bigint, bytesTotal: bigint, 
+export type SyncProgress = { 
+peerId: string, 
+phase: SyncPhase, 
+completedBatches: number, 
+totalBatches: number, 
 /**
- * Files currently being transferred in parallel
+ * Column changes pushed or pulled so far in this phase.
  */
-activeFiles: Array<string>, 
-/**
- * Current transfer rate in bytes/second
- */
-bytesPerSecond: bigint, };
+changes: bigint, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Durations of one kind of sync run.
+ */
+export type SyncStats = { 
+/**
+ * Finished runs (including failed ones)
+ */
+runs: number, failures: number, 
+/**
+ * Sum of all run durations in milliseconds
+ */
+totalMillis: number, maxMillis: number, lastMillis: number, };
@@ -1,4 +1,23 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
-import type { SyncError } from "./SyncError";
 
-export type SyncStatus = { isSyncing: boolean, pendingUploads: number, pendingDownloads: number, lastSync: string | null, errors: Array<SyncError>, };
+/**
+ * Sync state of one peer.
+ */
+export type SyncStatus = { 
+peerId: string, 
+/**
+ * HLC of the newest change pushed to the peer.
+ */
+lastPushHlcTimestamp: string | null, 
+/**
+ * HLC of the newest change pulled from the peer.
+ */
+lastPullHlcTimestamp: string | null, 
+/**
+ * When a push or pull with the peer last succeeded (UTC, SQLite datetime).
+ */
+lastSyncAt: string | null, 
+/**
+ * Error of the last failed sync attempt, cleared by the next success.
+ */
+error: string | null, };
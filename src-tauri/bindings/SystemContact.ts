@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A contact of the system address book.
+ */
+export type SystemContact = { 
+/**
+ * Platform id, stable on this device only
+ */
+id: string, displayName: string, givenName?: string, familyName?: string, organization?: string, emails: Array<string>, phones: Array<string>, 
+/**
+ * `YYYY-MM-DD`, or `--MM-DD` without a year
+ */
+birthday?: string, note?: string, 
+/**
+ * Name of the address book (account) the contact belongs to
+ */
+addressBook?: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SystemContact } from "./SystemContact";
+
+/**
+ * One page of `extension_system_pim_list_contacts`.
+ */
+export type SystemContactPage = { contacts: Array<SystemContact>, 
+/**
+ * Matching contacts over all pages
+ */
+total: number, };
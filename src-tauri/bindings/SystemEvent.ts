@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An event (or, where the platform expands them, one occurrence of a
+ * recurring event).
+ */
+export type SystemEvent = { id: string, calendarId: string, title: string, description?: string, location?: string, 
+/**
+ * RFC 3339; `YYYY-MM-DD` for all-day events; without offset for local
+ * times in `time_zone`
+ */
+start: string, 
+/**
+ * Same format as `start`; all-day ends are exclusive
+ */
+end?: string, allDay: boolean, 
+/**
+ * IANA zone of `start`/`end` if they carry no offset
+ */
+timeZone?: string, 
+/**
+ * `RRULE` of a recurring event whose occurrences were not expanded
+ */
+recurrence?: string, };
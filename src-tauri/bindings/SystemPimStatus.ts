@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which parts of the system PIM data this platform can provide.
+ */
+export type SystemPimStatus = { contacts: boolean, calendar: boolean, 
+/**
+ * Why contacts or calendars are unavailable
+ */
+reason?: string, };
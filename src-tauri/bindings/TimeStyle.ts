@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Length of a formatted time (`short` without, `medium` with seconds)
+ */
+export type TimeStyle = "short" | "medium";
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What `sql_select_strict` does with rows the delete log marks as deleted:
+ * `filter` leaves them out as if the delete had been applied, `warn` runs
+ * the query unchanged and flags the result.
+ */
+export type TombstoneMode = "filter" | "warn";
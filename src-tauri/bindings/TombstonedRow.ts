@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A deleted row that can still be restored.
+ */
+export type TombstonedRow = { 
+/**
+ * Id of the `haex_deleted_rows` entry; pass this to `crdt_restore_row`.
+ */
+id: string, tableName: string, 
+/**
+ * Primary keys of the deleted row as JSON object.
+ */
+rowPks: string, 
+/**
+ * Full row snapshot as JSON object. BLOBs are `{"$blob": "<hex>"}`.
+ */
+rowData: string, deletedHlc: string, deletedAt: string, };
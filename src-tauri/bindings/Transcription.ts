@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of `speech_transcribe`.
+ */
+export type Transcription = { text: string, 
+/**
+ * Language the recognizer used
+ */
+language?: string, };
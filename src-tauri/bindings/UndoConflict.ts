@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Why an undo or redo was refused.
+ */
+export type UndoConflict = { "kind": "rowChanged", tableName: string, 
+/**
+ * Primary keys of the row as JSON object
+ */
+rowPks: string, };
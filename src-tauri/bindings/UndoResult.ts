@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UndoConflict } from "./UndoConflict";
+import type { UndoStackEntry } from "./UndoStackEntry";
+
+/**
+ * Outcome of `undo_last` / `redo_last`.
+ */
+export type UndoResult = { applied: boolean, 
+/**
+ * The entry that was undone/redone; `None` if the stack was empty
+ */
+entry: UndoStackEntry | null, conflict: UndoConflict | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Stack an undo entry belongs to.
+ */
+export type UndoScope = { "kind": "table", tableName: string, } | { "kind": "extension", extensionId: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UndoStackEntry } from "./UndoStackEntry";
+
+/**
+ * Undo and redo entries of a scope, newest first.
+ */
+export type UndoStack = { undo: Array<UndoStackEntry>, redo: Array<UndoStackEntry>, };
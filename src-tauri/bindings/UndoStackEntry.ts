@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A recorded write as listed by `undo_get_stack`.
+ */
+export type UndoStackEntry = { id: string, 
+/**
+ * Tables the write touched, the target table first
+ */
+tables: Array<string>, inserted: number, updated: number, deleted: number, recordedAt: string, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UniqueConflictStrategy } from "./UniqueConflictStrategy";
+
+/**
+ * Configured strategies: the default and per-table overrides.
+ */
+export type UniqueConflictSettings = { 
+defaultStrategy: UniqueConflictStrategy, 
+tables: { [key in string]?: UniqueConflictStrategy }, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a UNIQUE conflict of a replicated insert is handled.
+ */
+export type UniqueConflictStrategy = "reject" | "rename" | "merge";
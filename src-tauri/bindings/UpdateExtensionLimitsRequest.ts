@@ -19,4 +19,12 @@ maxConcurrentQueries: bigint | null,
 /**
  * Maximum query SQL size in bytes (optional)
  */
-maxQuerySizeBytes: bigint | null, };
+maxQuerySizeBytes: bigint | null, 
+/**
+ * Maximum memory of the extension's webview processes in bytes (optional)
+ */
+maxMemoryBytes: bigint | null, 
+/**
+ * Maximum sustained CPU usage in percent of one core (optional)
+ */
+maxCpuPercent: bigint | null, };
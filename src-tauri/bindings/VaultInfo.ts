@@ -1,3 +1,16 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type VaultInfo = { name: string, lastAccess: bigint, path: string, };
+export type VaultInfo = { name: string, 
+/**
+ * Unix time (seconds) of the last open on this device, the file's
+ * modification time if it was never opened here
+ */
+lastAccess: bigint, path: string, 
+/**
+ * Host name of the device that last opened the vault
+ */
+lastOpenedDevice: string | null, 
+/**
+ * Size of the vault file including its uncheckpointed WAL
+ */
+sizeBytes: bigint, };
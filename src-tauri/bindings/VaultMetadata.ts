@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CipherSettings } from "./CipherSettings";
+
+/**
+ * What is known about a vault without unlocking it
+ */
+export type VaultMetadata = { 
+/**
+ * `null` for vaults created before the metadata sidecar existed
+ */
+formatVersion: number | null, 
+/**
+ * Display name, the file name if none was recorded
+ */
+displayName: string, 
+/**
+ * Creation date (RFC 3339); falls back to the file's creation time
+ */
+createdAt: string | null, 
+/**
+ * Cipher parameters the vault is opened with; `null` = SQLCipher
+ * defaults
+ */
+cipherSettings: CipherSettings | null, };
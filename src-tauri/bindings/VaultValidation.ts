@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VaultValidationStatus } from "./VaultValidationStatus";
+
+export type VaultValidation = { status: VaultValidationStatus, 
+/**
+ * What exactly failed, for everything but `ok` and `wrongPassword`
+ */
+detail: string | null, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of `PRAGMA wal_checkpoint(TRUNCATE)`
+ */
+export type WalCheckpoint = { 
+/**
+ * The checkpoint could not finish because a reader or writer was active
+ */
+busy: boolean, 
+/**
+ * Frames in the WAL before the checkpoint (-1 if not in WAL mode)
+ */
+logFrames: bigint, 
+/**
+ * Frames copied into the database file
+ */
+checkpointedFrames: bigint, };
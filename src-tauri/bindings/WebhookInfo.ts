@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Webhook as shown in the settings (without secret and cursor)
+ */
+export type WebhookInfo = { id: string, name: string | null, url: string, tables: Array<string>, enabled: boolean, createdAt: string, lastDeliveryAt: string | null, 
+/**
+ * Error of the last failed delivery, cleared by the next success
+ */
+lastError: string | null, };
@@ -13,6 +13,8 @@ interface Column {
   name: string
   rustType: string
   isOptional: boolean
+  isPrimary: boolean
+  isJson: boolean
 }
 
 function drizzleToRustType(colDef: AnySQLiteColumn): {
@@ -62,6 +64,8 @@ function extractColumns(table: SQLiteTable): Column[] {
       name: colDef.name,
       rustType: isOptional ? `Option<${rustType}>` : rustType,
       isOptional,
+      isPrimary: colDef.primary,
+      isJson: rustType === 'serde_json::Value',
     })
   }
   return columns
@@ -94,6 +98,92 @@ const RUST_KEYWORDS = new Set([
   'mut',
 ])
 
+function rustFieldName(col: Column): string {
+  const fieldName = toSnakeCase(col.name)
+  return RUST_KEYWORDS.has(fieldName) ? `r#${fieldName}` : fieldName
+}
+
+/** Rust string literal of a double-quoted SQL identifier list */
+function quotedColumns(columns: Column[], suffix = ''): string {
+  return columns.map((col) => `\\"${col.name}\\"${suffix}`).join(', ')
+}
+
+/**
+ * Typed access helpers (Teil 3). Writes go through the SqlExecutor so
+ * CRDT tables get their HLC columns and dirty-table entries.
+ */
+function generateAccessors(tableName: string, columns: Column[]): string {
+  const primary = columns.find((col) => col.isPrimary)
+  const columnList = columns.map((col) => `"${col.name}"`).join(', ')
+
+  let code = `    pub const TABLE: &'static str = "${tableName}";\n`
+  code += `    pub const COLUMNS: &'static [&'static str] = &[${columnList}];\n`
+  code += `    pub const SELECT_SQL: &'static str =\n`
+  code += `        "SELECT ${quotedColumns(columns)} FROM ${tableName}";\n\n`
+
+  code += `    /// Loads all rows matching \`clause\` (e.g. \`"WHERE x = ?1 ORDER BY y"\`, may be empty).\n`
+  code += `    pub fn find<P: rusqlite::Params>(\n`
+  code += `        conn: &rusqlite::Connection,\n`
+  code += `        clause: &str,\n`
+  code += `        params: P,\n`
+  code += `    ) -> rusqlite::Result<Vec<Self>> {\n`
+  code += `        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;\n`
+  code += `        let rows = stmt.query_map(params, Self::from_row)?;\n`
+  code += `        rows.collect()\n`
+  code += `    }\n\n`
+
+  if (primary) {
+    const pkType = primary.rustType === 'String' ? '&str' : primary.rustType
+    code += `    pub fn get(conn: &rusqlite::Connection, ${rustFieldName(primary)}: ${pkType}) -> rusqlite::Result<Option<Self>> {\n`
+    code += `        Ok(Self::find(conn, "WHERE \\"${primary.name}\\" = ?1", [${rustFieldName(primary)}])?\n`
+    code += `            .into_iter()\n`
+    code += `            .next())\n`
+    code += `    }\n\n`
+  }
+
+  // INSERT skips unset optional columns so SQL defaults apply
+  code += `    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {\n`
+  code += `        let mut columns: Vec<&str> = Vec::new();\n`
+  code += `        let mut values: Vec<&dyn ToSql> = Vec::new();\n`
+  for (const col of columns) {
+    const field = rustFieldName(col)
+    if (col.isOptional) {
+      code += `        if let Some(value) = &self.${field} {\n`
+      code += `            columns.push("\\"${col.name}\\"");\n`
+      code += `            values.push(value);\n`
+      code += `        }\n`
+    } else {
+      code += `        columns.push("\\"${col.name}\\"");\n`
+      code += `        values.push(&self.${field});\n`
+    }
+  }
+  code += `        let sql = format!(\n`
+  code += `            "INSERT INTO {} ({}) VALUES ({})",\n`
+  code += `            Self::TABLE,\n`
+  code += `            columns.join(", "),\n`
+  code += `            vec!["?"; columns.len()].join(", ")\n`
+  code += `        );\n`
+  code += `        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;\n`
+  code += `        Ok(())\n`
+  code += `    }\n`
+
+  const updatable = columns.filter((col) => !col.isPrimary)
+  if (primary && updatable.length > 0) {
+    code += `\n    /// Writes all columns of the row identified by the primary key.\n`
+    code += `    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {\n`
+    code += `        SqlExecutor::execute_internal_typed(\n`
+    code += `            tx,\n`
+    code += `            hlc,\n`
+    code += `            "UPDATE ${tableName} SET ${quotedColumns(updatable, ' = ?')} WHERE \\"${primary.name}\\" = ?",\n`
+    code += `            rusqlite::params![${[...updatable, primary].map((col) => `self.${rustFieldName(col)}`).join(', ')}],\n`
+    code += `        )?;\n`
+    code += `        Ok(())\n`
+    code += `    }\n`
+  }
+
+  return code
+}
+
 function generateStruct(name: string, columns: Column[]): string {
   let structName = toPascalCase(name)
 
@@ -102,7 +192,8 @@ function generateStruct(name: string, columns: Column[]): string {
   }
 
   // --- Teil 1: Struct-Definition ---
-  let code = `#[derive(Debug, Clone, Serialize, Deserialize)]\n`
+  let code = `#[derive(Debug, Clone, Serialize, Deserialize, TS)]\n`
+  code += `#[ts(export)]\n`
   code += `#[serde(rename_all = "camelCase")]\n`
   code += `pub struct ${structName} {\n`
 
@@ -117,6 +208,9 @@ function generateStruct(name: string, columns: Column[]): string {
     if (col.isOptional) {
       code += `    #[serde(skip_serializing_if = "Option::is_none")]\n`
     }
+    if (col.isJson) {
+      code += `    #[ts(type = "unknown")]\n`
+    }
     // Wichtig: #[serde(rename = "...")] hinzufügen, falls der Feldname geändert wurde!
     if (fieldName.startsWith('r#')) {
       const originalName = fieldName.substring(2)
@@ -141,7 +235,10 @@ function generateStruct(name: string, columns: Column[]): string {
   })
 
   code += `        })\n`
-  code += `    }\n`
+  code += `    }\n\n`
+
+  // --- Teil 3: Typed access ---
+  code += generateAccessors(name, columns)
   code += `}\n\n`
 
   return code
@@ -154,7 +251,12 @@ function main() {
 
 #![allow(dead_code)]
 
+use crate::crdt::hlc::HlcService;
+use crate::database::error::DatabaseError;
+use crate::extension::database::executor::SqlExecutor;
+use rusqlite::{ToSql, Transaction};
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
 `
 
@@ -182,6 +284,14 @@ use serde::{Deserialize, Serialize};
       name: tablesNames.haex.extension_limits.name,
       table: schema.haexExtensionLimits,
     },
+    {
+      name: tablesNames.haex.external_authorized_clients.name,
+      table: schema.haexExternalAuthorizedClients,
+    },
+    {
+      name: tablesNames.haex.external_blocked_clients.name,
+      table: schema.haexExternalBlockedClients,
+    },
     {
       name: tablesNames.haex.passwords_item_details.name,
       table: schema.haexPasswordsItemDetails,
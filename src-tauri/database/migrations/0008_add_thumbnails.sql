@@ -0,0 +1,33 @@
+-- ---------------------------------------------------------------------------
+-- HAND-WRITTEN MIGRATION (do not regenerate with drizzle-kit)
+-- ---------------------------------------------------------------------------
+-- Creates haex_thumbnails_no_sync — local cache of rendered thumbnails for
+-- FileSync blobs, so file-browser extensions can render a grid without
+-- downloading every full object.
+--
+-- Why in the vault DB (not a cache directory):
+--   Thumbnails leak file content. Keeping them in the SQLCipher database
+--   means they are encrypted at rest with the vault key, like everything
+--   else the vault knows about the user's files.
+--
+-- Why `_no_sync`:
+--   Every device can regenerate thumbnails from the blob. Syncing them would
+--   only duplicate data that peers can derive themselves.
+--
+-- Invalidation:
+--   `source_modified` stores the backend's last-modified timestamp of the
+--   blob the thumbnail was rendered from. A mismatch re-renders the entry.
+-- ---------------------------------------------------------------------------
+
+CREATE TABLE `haex_thumbnails_no_sync` (
+  `backend_id` text NOT NULL,
+  `file_id` text NOT NULL,
+  `size` integer NOT NULL,
+  `source_modified` text,
+  `mime_type` text NOT NULL,
+  `width` integer NOT NULL,
+  `height` integer NOT NULL,
+  `data` blob NOT NULL,
+  `created_at` text NOT NULL,
+  PRIMARY KEY(`backend_id`, `file_id`, `size`)
+);
@@ -0,0 +1,26 @@
+-- ---------------------------------------------------------------------------
+-- HAND-WRITTEN MIGRATION (do not regenerate with drizzle-kit)
+-- ---------------------------------------------------------------------------
+-- Creates haex_crdt_sync_status_no_sync — one row per sync peer (server
+-- backend or local space leader) with the HLC cursors of the last push to
+-- and the last pull from that peer.
+--
+-- Why per peer:
+--   haex_sync_backends only covers server backends, and the local delivery
+--   cursors live in haex_vault_settings keyed by space. A single table keyed
+--   by peer lets every sync path resume incrementally against each peer
+--   instead of re-sending everything.
+--
+-- Why `_no_sync`:
+--   Cursors describe what this device exchanged with a peer. Another device
+--   has its own cursors; syncing them would make it skip changes it never
+--   sent.
+-- ---------------------------------------------------------------------------
+
+CREATE TABLE `haex_crdt_sync_status_no_sync` (
+  `peer_id` text PRIMARY KEY NOT NULL,
+  `last_push_hlc_timestamp` text,
+  `last_pull_hlc_timestamp` text,
+  `last_sync_at` text,
+  `error` text
+);
@@ -0,0 +1,43 @@
+-- ---------------------------------------------------------------------------
+-- HAND-WRITTEN MIGRATION (do not regenerate with drizzle-kit)
+-- ---------------------------------------------------------------------------
+-- Creates haex_shares and haex_share_members for read-only sharing of vault
+-- data with other vaults.
+--
+-- haex_shares holds both directions: `outgoing` rows are shares this vault
+-- publishes (a set of tables, optionally limited to one space, sealed under
+-- the share key), `incoming` rows are shares accepted from another vault.
+-- `share_key` is the current-generation key (base64); revoking a member
+-- rotates it and bumps `key_generation`. haex_share_members lists the
+-- recipients of an outgoing share.
+--
+-- Both tables sync between the devices of this vault so every device can
+-- publish and consume the same shares. They are never part of a share.
+--
+-- CRDT columns (haex_hlc, haex_column_hlcs) are injected automatically by
+-- the Rust CrdtTransformer — do NOT add them here.
+-- ---------------------------------------------------------------------------
+
+CREATE TABLE `haex_shares` (
+  `id` text PRIMARY KEY NOT NULL,
+  `direction` text NOT NULL,
+  `name` text NOT NULL,
+  `tables` text NOT NULL,
+  `space_id` text,
+  `owner_did` text NOT NULL,
+  `backend_id` text NOT NULL,
+  `share_key` text NOT NULL,
+  `key_generation` integer NOT NULL DEFAULT 1,
+  `status` text NOT NULL DEFAULT 'active',
+  `created_at` text DEFAULT (CURRENT_TIMESTAMP),
+  `revoked_at` text
+);
+--> statement-breakpoint
+CREATE TABLE `haex_share_members` (
+  `id` text PRIMARY KEY NOT NULL,
+  `share_id` text NOT NULL,
+  `did` text NOT NULL,
+  `created_at` text DEFAULT (CURRENT_TIMESTAMP),
+  `revoked_at` text,
+  FOREIGN KEY (`share_id`) REFERENCES `haex_shares`(`id`) ON UPDATE no action ON DELETE cascade
+);
@@ -0,0 +1,29 @@
+-- ---------------------------------------------------------------------------
+-- HAND-WRITTEN MIGRATION (do not regenerate with drizzle-kit)
+-- ---------------------------------------------------------------------------
+-- Creates haex_share_roles: collaboration roles (`reader` / `editor`) of the
+-- devices taking part in a share.
+--
+-- Each row binds a member DID and one of its devices (the HLC node id the
+-- device stamps its writes with, hex) to a role, signed by the share owner.
+-- The id is `<share_id>/<did>/<device_node>`, so the rows of one share can
+-- be recognised from their primary key alone — they are published in the
+-- share's own batches, unlike haex_shares and haex_share_members.
+--
+-- Remote writes to a shared table are only applied when stamped by an
+-- editor device with a valid signature (or by a device of this vault).
+--
+-- CRDT columns (haex_hlc, haex_column_hlcs) are injected automatically by
+-- the Rust CrdtTransformer — do NOT add them here.
+-- ---------------------------------------------------------------------------
+
+CREATE TABLE `haex_share_roles` (
+  `id` text PRIMARY KEY NOT NULL,
+  `share_id` text NOT NULL,
+  `did` text NOT NULL,
+  `device_node` text NOT NULL,
+  `role` text NOT NULL,
+  `signature` text NOT NULL,
+  `created_at` text DEFAULT (CURRENT_TIMESTAMP),
+  FOREIGN KEY (`share_id`) REFERENCES `haex_shares`(`id`) ON UPDATE no action ON DELETE cascade
+);
@@ -0,0 +1,24 @@
+-- ---------------------------------------------------------------------------
+-- HAND-WRITTEN MIGRATION (do not regenerate with drizzle-kit)
+-- ---------------------------------------------------------------------------
+-- Creates haex_extension_signing_keys: the signing key currently trusted for
+-- an installed extension, once its developer rotated away from the key the
+-- extension was installed with.
+--
+-- The extension keeps its original `public_key` in haex_extensions — it
+-- namespaces the extension's tables and install directory. Bundles signed by
+-- a new key are only accepted with a rotation chain (each step signed by the
+-- previous key) starting at the key stored here, or at `public_key` if no
+-- row exists. `rotation_chain` keeps the accepted proofs (JSON) for audit.
+--
+-- CRDT columns (haex_hlc, haex_column_hlcs) are injected automatically by
+-- the Rust CrdtTransformer — do NOT add them here.
+-- ---------------------------------------------------------------------------
+
+CREATE TABLE `haex_extension_signing_keys` (
+  `id` text PRIMARY KEY NOT NULL,
+  `public_key` text NOT NULL,
+  `rotation_chain` text NOT NULL,
+  `rotated_at` text DEFAULT (CURRENT_TIMESTAMP),
+  FOREIGN KEY (`id`) REFERENCES `haex_extensions`(`id`) ON UPDATE no action ON DELETE cascade
+);
@@ -0,0 +1,33 @@
+-- ---------------------------------------------------------------------------
+-- HAND-WRITTEN MIGRATION (do not regenerate with drizzle-kit)
+-- ---------------------------------------------------------------------------
+-- Creates haex_automation_rules_no_sync — local "if this, then that" rules
+-- evaluated by `crate::automation`.
+--
+-- A rule pairs one trigger (table change, schedule, file sync event) with
+-- one action (run an extension command, send a notification, call a
+-- webhook). `trigger_config` / `action_config` hold the type-specific
+-- settings as JSON.
+--
+-- Why `_no_sync`:
+--   Rules act on this device (its extensions, its webhooks, its file sync
+--   rules). Syncing them would fire the same action once per device.
+--
+-- `cursor_hlc` is the newest change a table-change rule already reacted to;
+-- `last_run_at` drives the schedule trigger.
+-- ---------------------------------------------------------------------------
+
+CREATE TABLE `haex_automation_rules_no_sync` (
+  `id` text PRIMARY KEY NOT NULL,
+  `name` text NOT NULL,
+  `enabled` integer DEFAULT true NOT NULL,
+  `trigger_type` text NOT NULL,
+  `trigger_config` text NOT NULL,
+  `action_type` text NOT NULL,
+  `action_config` text NOT NULL,
+  `cursor_hlc` text,
+  `last_run_at` text,
+  `last_error` text,
+  `created_at` text NOT NULL,
+  `updated_at` text NOT NULL
+);
@@ -0,0 +1,14 @@
+-- ---------------------------------------------------------------------------
+-- HAND-WRITTEN MIGRATION (do not regenerate with drizzle-kit)
+-- ---------------------------------------------------------------------------
+-- Add process limits to `haex_extension_limits` for the extension resource
+-- monitor (desktop): the memory and CPU usage of an extension's webview
+-- processes above which the user is warned.
+--
+-- Both columns are nullable; NULL means the built-in default
+-- (`ProcessLimits::default()`), so existing rows keep working unchanged.
+-- ---------------------------------------------------------------------------
+
+ALTER TABLE `haex_extension_limits` ADD COLUMN `max_memory_bytes` integer;
+--> statement-breakpoint
+ALTER TABLE `haex_extension_limits` ADD COLUMN `max_cpu_percent` integer;
@@ -0,0 +1,24 @@
+-- ---------------------------------------------------------------------------
+-- HAND-WRITTEN MIGRATION (do not regenerate with drizzle-kit)
+-- ---------------------------------------------------------------------------
+-- Creates haex_extension_row_filters: row filters an extension registered on
+-- its own tables. When another extension reads a filtered table it only sees
+-- the rows matching `predicate` (e.g. `shared = ?`), see
+-- `crate::extension::database::row_filter`.
+--
+-- The id is the filtered table's name, so each table has at most one filter
+-- and two devices registering the same filter converge on one row.
+-- `params` holds the JSON array bound to the predicate's `?` placeholders.
+--
+-- CRDT columns (haex_hlc, haex_column_hlcs) are injected automatically by
+-- the Rust CrdtTransformer — do NOT add them here.
+-- ---------------------------------------------------------------------------
+
+CREATE TABLE `haex_extension_row_filters` (
+  `id` text PRIMARY KEY NOT NULL,
+  `extension_id` text NOT NULL,
+  `predicate` text NOT NULL,
+  `params` text DEFAULT '[]' NOT NULL,
+  `updated_at` text DEFAULT (CURRENT_TIMESTAMP),
+  FOREIGN KEY (`extension_id`) REFERENCES `haex_extensions`(`id`) ON UPDATE no action ON DELETE cascade
+);
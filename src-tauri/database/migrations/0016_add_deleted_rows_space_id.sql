@@ -0,0 +1,14 @@
+-- ---------------------------------------------------------------------------
+-- HAND-WRITTEN MIGRATION (do not regenerate with drizzle-kit)
+-- ---------------------------------------------------------------------------
+-- Add `space_id` to `haex_deleted_rows`. The BEFORE-DELETE trigger copies the
+-- deleted row's space into it (trigger version 7), so a sync scoped to one
+-- space (`SyncScope::space_id`, used by space shares) can send the deletes of
+-- that space instead of none at all.
+--
+-- The column is nullable: entries logged before this migration and deletes
+-- of tables without a `space_id` column have no space, and stay out of
+-- space-scoped syncs.
+-- ---------------------------------------------------------------------------
+
+ALTER TABLE `haex_deleted_rows` ADD COLUMN `space_id` text;
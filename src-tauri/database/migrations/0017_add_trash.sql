@@ -0,0 +1,27 @@
+-- ---------------------------------------------------------------------------
+-- HAND-WRITTEN MIGRATION (do not regenerate with drizzle-kit)
+-- ---------------------------------------------------------------------------
+-- Creates haex_trash_no_sync — the record trash (`crdt::trash`). The
+-- BEFORE-DELETE trigger of every CRDT table stores a JSON snapshot of the
+-- deleted row here, keyed by the id of its `haex_deleted_rows` entry, so the
+-- row can be restored while that entry exists.
+--
+-- Why `_no_sync`:
+--   Snapshots only exist for local deletes. Deletes that arrive via sync run
+--   with triggers disabled, and a peer restores from its own trash.
+--
+-- `IF NOT EXISTS`:
+--   Vaults opened before this migration created the table at runtime when
+--   the first DELETE trigger was set up.
+-- ---------------------------------------------------------------------------
+
+CREATE TABLE IF NOT EXISTS `haex_trash_no_sync` (
+  `deleted_row_id` text PRIMARY KEY NOT NULL,
+  `table_name` text NOT NULL,
+  `row_pks` text NOT NULL,
+  `row_data` text NOT NULL,
+  `deleted_hlc` text NOT NULL,
+  `deleted_at` text NOT NULL
+);
+--> statement-breakpoint
+CREATE INDEX IF NOT EXISTS `haex_trash_no_sync_table_idx` ON `haex_trash_no_sync` (`table_name`,`deleted_hlc`);
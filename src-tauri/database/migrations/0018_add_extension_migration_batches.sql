@@ -0,0 +1,40 @@
+-- ---------------------------------------------------------------------------
+-- HAND-WRITTEN MIGRATION (do not regenerate with drizzle-kit)
+-- ---------------------------------------------------------------------------
+-- Creates the migration batch log of extensions
+-- (`extension::core::migration_batches`):
+--   haex_extension_migration_batches_no_sync   — one row per run of an
+--     extension's bundle migrations, with its outcome
+--   haex_extension_migration_snapshots_no_sync — tables, indexes and views
+--     saved before the latest batch of an extension, for rollbacks
+--
+-- Why `_no_sync`:
+--   A rollback restores the tables of this device only, so batches and
+--   snapshots describe local state.
+--
+-- `IF NOT EXISTS`:
+--   Vaults opened before this migration created the tables at runtime when
+--   the first batch ran or was listed.
+-- ---------------------------------------------------------------------------
+
+CREATE TABLE IF NOT EXISTS `haex_extension_migration_batches_no_sync` (
+  `id` text PRIMARY KEY NOT NULL,
+  `extension_id` text NOT NULL,
+  `extension_version` text NOT NULL,
+  `status` text NOT NULL,
+  `migrations` text DEFAULT '[]' NOT NULL,
+  `error` text,
+  `started_at` text NOT NULL,
+  `finished_at` text,
+  `has_snapshot` integer DEFAULT 0 NOT NULL
+);
+--> statement-breakpoint
+CREATE TABLE IF NOT EXISTS `haex_extension_migration_snapshots_no_sync` (
+  `batch_id` text NOT NULL,
+  `position` integer NOT NULL,
+  `object_type` text NOT NULL,
+  `name` text NOT NULL,
+  `sql` text NOT NULL,
+  `backup_table` text,
+  PRIMARY KEY(`batch_id`, `position`)
+);
@@ -0,0 +1,25 @@
+-- ---------------------------------------------------------------------------
+-- HAND-WRITTEN MIGRATION (do not regenerate with drizzle-kit)
+-- ---------------------------------------------------------------------------
+-- Creates haex_storage_usage_no_sync — the storage usage ledger
+-- (`remote_storage::usage`). Every upload through a storage backend records
+-- the object's size here and every delete removes it again; usage reports
+-- and quota checks are sums over this table. `bucket` is empty for the
+-- backend's own bucket.
+--
+-- Why `_no_sync`:
+--   Each device records what it uploaded itself. Objects of other devices
+--   are picked up by rebuilding the usage of a backend from its listing.
+--
+-- `IF NOT EXISTS`:
+--   Vaults opened before this migration created the table at runtime on the
+--   first upload or usage report.
+-- ---------------------------------------------------------------------------
+
+CREATE TABLE IF NOT EXISTS `haex_storage_usage_no_sync` (
+  `backend_id` text NOT NULL,
+  `bucket` text NOT NULL,
+  `key` text NOT NULL,
+  `size` integer NOT NULL,
+  PRIMARY KEY(`backend_id`, `bucket`, `key`)
+);
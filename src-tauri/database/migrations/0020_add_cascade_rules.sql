@@ -0,0 +1,27 @@
+-- ---------------------------------------------------------------------------
+-- HAND-WRITTEN MIGRATION (do not regenerate with drizzle-kit)
+-- ---------------------------------------------------------------------------
+-- Creates haex_crdt_cascade_rules_no_sync — the cascade delete rules
+-- extensions declare in their migration metadata (`crdt::cascade`). Each rule
+-- becomes an AFTER-DELETE trigger on the parent table; the rows here let the
+-- triggers be recreated and let trash and undo find the children of a row.
+-- `owner` is the table prefix of the extension that registered the rule,
+-- the column lists are JSON arrays.
+--
+-- Why `_no_sync`:
+--   Every device registers the rules again when the extension registers its
+--   migrations, and synced deletes run with triggers disabled.
+--
+-- `IF NOT EXISTS`:
+--   Vaults opened before this migration created the table at runtime when
+--   the first rules were registered.
+-- ---------------------------------------------------------------------------
+
+CREATE TABLE IF NOT EXISTS `haex_crdt_cascade_rules_no_sync` (
+  `owner` text NOT NULL,
+  `child_table` text NOT NULL,
+  `child_columns` text NOT NULL,
+  `parent_table` text NOT NULL,
+  `parent_columns` text NOT NULL,
+  PRIMARY KEY(`child_table`, `child_columns`)
+);
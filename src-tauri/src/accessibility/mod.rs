@@ -0,0 +1,8 @@
+// src-tauri/src/accessibility/mod.rs
+//!
+//! Accessibility
+//!
+//! Host services that make the vault and its extensions usable without
+//! screen or keyboard.
+
+pub mod speech;
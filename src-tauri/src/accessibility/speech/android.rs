@@ -0,0 +1,109 @@
+//! Speech output through the native `SpeechPlugin` (`TextToSpeech`).
+//!
+//! The system recognizer only listens to the microphone, so audio files
+//! can't be transcribed here.
+
+use super::types::{SpeakOptions, SpeechStatus, SpeechVoice, Transcription};
+use super::SpeechError;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::plugin::PluginHandle;
+use tauri::{AppHandle, Manager, Runtime};
+
+const NO_TRANSCRIBE: &str = "Android can't transcribe audio files";
+
+/// Handle to the native `SpeechPlugin`.
+pub struct SpeechPlugin<R: Runtime>(PluginHandle<R>);
+
+/// Registers the native plugin (`space.haex.vault.SpeechPlugin`).
+pub fn init<R: Runtime>() -> tauri::plugin::TauriPlugin<R> {
+    tauri::plugin::Builder::new("speech")
+        .setup(|app, api| {
+            let handle = api.register_android_plugin("space.haex.vault", "SpeechPlugin")?;
+            app.manage(SpeechPlugin(handle));
+            Ok(())
+        })
+        .build()
+}
+
+/// Lists come wrapped, the plugin can only resolve with an object.
+#[derive(Deserialize)]
+struct Items<T> {
+    items: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct Empty {}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpeakArgs {
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    voice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    rate: f32,
+}
+
+async fn run<T: DeserializeOwned + Send + 'static>(
+    app: &AppHandle,
+    command: &'static str,
+    args: impl Serialize + Send + 'static,
+) -> Result<T, SpeechError> {
+    let plugin = app
+        .try_state::<SpeechPlugin<tauri::Wry>>()
+        .ok_or_else(|| SpeechError::Unavailable {
+            reason: "SpeechPlugin is not registered".to_string(),
+        })?
+        .0
+        .clone();
+    tokio::task::spawn_blocking(move || plugin.run_mobile_plugin::<T>(command, args))
+        .await
+        .map_err(|e| SpeechError::Internal {
+            reason: format!("Speech task failed: {e}"),
+        })?
+        .map_err(|e| SpeechError::Unavailable {
+            reason: e.to_string(),
+        })
+}
+
+pub async fn status(_app: &AppHandle) -> SpeechStatus {
+    SpeechStatus {
+        speak: true,
+        transcribe: false,
+        reason: Some(NO_TRANSCRIBE.to_string()),
+    }
+}
+
+pub async fn voices(app: &AppHandle) -> Result<Vec<SpeechVoice>, SpeechError> {
+    let list: Items<SpeechVoice> = run(app, "listVoices", ()).await?;
+    Ok(list.items)
+}
+
+pub async fn speak(app: &AppHandle, text: &str, options: &SpeakOptions) -> Result<(), SpeechError> {
+    let args = SpeakArgs {
+        text: text.to_string(),
+        voice: options.voice.clone(),
+        language: options.language.clone(),
+        rate: options.rate(),
+    };
+    let _: Empty = run(app, "speak", args).await?;
+    Ok(())
+}
+
+pub async fn stop(app: &AppHandle) -> Result<(), SpeechError> {
+    let _: Empty = run(app, "stop", ()).await?;
+    Ok(())
+}
+
+pub async fn transcribe(
+    _app: &AppHandle,
+    _path: &Path,
+    _language: Option<&str>,
+) -> Result<Transcription, SpeechError> {
+    Err(SpeechError::Unsupported {
+        reason: NO_TRANSCRIBE.to_string(),
+    })
+}
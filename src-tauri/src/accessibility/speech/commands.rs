@@ -0,0 +1,164 @@
+// src-tauri/src/accessibility/speech/commands.rs
+//!
+//! Speech Commands
+//!
+//! `speech_*` are for the host UI (read-aloud, voice notes). The
+//! `extension_speech_*` variants check the `speech` permission:
+//! - `extension_speech_speak` / `extension_speech_stop` need `speak`
+//! - `extension_speech_transcribe` needs `transcribe` plus read access to
+//!   the audio file
+//!
+//! Status and voice list reveal nothing about the user and need no
+//! permission.
+
+use super::error::SpeechError;
+use super::types::{SpeakOptions, SpeechStatus, SpeechVoice, Transcription};
+use crate::extension::error::ExtensionError;
+use crate::extension::filesystem::commands::authorize_local_read;
+use crate::extension::permissions::manager::PermissionManager;
+use crate::extension::permissions::types::SpeechAction;
+use crate::extension::utils::{emit_permission_prompt_if_needed, resolve_extension_id};
+use crate::AppState;
+use std::path::Path;
+use tauri::{AppHandle, State, WebviewWindow};
+
+fn to_extension_error(e: SpeechError) -> ExtensionError {
+    ExtensionError::ValidationError {
+        reason: e.to_string(),
+    }
+}
+
+async fn check_permission(
+    app_handle: &AppHandle,
+    state: &State<'_, AppState>,
+    extension_id: &str,
+    action: SpeechAction,
+) -> Result<(), ExtensionError> {
+    let permission_result =
+        PermissionManager::check_speech_permission(state, extension_id, action).await;
+    if let Err(ref e) = permission_result {
+        emit_permission_prompt_if_needed(app_handle, e);
+    }
+    permission_result
+}
+
+/// What the speech engines of this device can do.
+#[tauri::command]
+pub async fn speech_status(app_handle: AppHandle) -> SpeechStatus {
+    super::status(&app_handle).await
+}
+
+#[tauri::command]
+pub async fn speech_list_voices(app_handle: AppHandle) -> Result<Vec<SpeechVoice>, SpeechError> {
+    super::voices(&app_handle).await
+}
+
+/// Speaks `text`; returns once it is queued, interrupting earlier text.
+#[tauri::command]
+pub async fn speech_speak(
+    app_handle: AppHandle,
+    text: String,
+    options: Option<SpeakOptions>,
+) -> Result<(), SpeechError> {
+    super::speak(&app_handle, &text, &options.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn speech_stop(app_handle: AppHandle) -> Result<(), SpeechError> {
+    super::stop(&app_handle).await
+}
+
+/// Transcribes an audio file on disk (e.g. a voice note).
+#[tauri::command]
+pub async fn speech_transcribe(
+    app_handle: AppHandle,
+    path: String,
+    language: Option<String>,
+) -> Result<Transcription, SpeechError> {
+    super::transcribe(&app_handle, Path::new(&path), language.as_deref()).await
+}
+
+/// Speech support on behalf of an extension.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_speech_status(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<SpeechStatus, ExtensionError> {
+    resolve_extension_id(&window, &state, public_key, name)?;
+    Ok(super::status(&app_handle).await)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_speech_list_voices(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<Vec<SpeechVoice>, ExtensionError> {
+    resolve_extension_id(&window, &state, public_key, name)?;
+    super::voices(&app_handle).await.map_err(to_extension_error)
+}
+
+/// Speaks `text` on behalf of an extension (permission-checked).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_speech_speak(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    text: String,
+    options: Option<SpeakOptions>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<(), ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    check_permission(&app_handle, &state, &extension_id, SpeechAction::Speak).await?;
+
+    super::speak(&app_handle, &text, &options.unwrap_or_default())
+        .await
+        .map_err(to_extension_error)
+}
+
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_speech_stop(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<(), ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    check_permission(&app_handle, &state, &extension_id, SpeechAction::Speak).await?;
+
+    super::stop(&app_handle).await.map_err(to_extension_error)
+}
+
+/// Transcribes an audio file the extension may read: a `private://` path
+/// (e.g. a recording from `extension_media_record_audio`) or one covered by
+/// its filesystem permissions.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_speech_transcribe(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    path: String,
+    language: Option<String>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<Transcription, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    check_permission(&app_handle, &state, &extension_id, SpeechAction::Transcribe).await?;
+    let local_path = authorize_local_read(&app_handle, &state, &extension_id, &path).await?;
+
+    super::transcribe(&app_handle, &local_path, language.as_deref())
+        .await
+        .map_err(to_extension_error)
+}
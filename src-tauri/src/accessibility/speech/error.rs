@@ -0,0 +1,90 @@
+// src-tauri/src/accessibility/speech/error.rs
+//!
+//! Speech Error Types
+//!
+
+use crate::error_code::{serialize_tagged, CodedError, ErrorCode};
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Error, Serialize)]
+#[serde(tag = "type", content = "details")]
+#[serde(remote = "Self")]
+pub enum SpeechError {
+    #[error("Not supported on this platform: {reason}")]
+    Unsupported { reason: String },
+
+    #[error("Speech engine unavailable: {reason}")]
+    Unavailable { reason: String },
+
+    #[error("Access to speech recognition was denied")]
+    AccessDenied,
+
+    #[error("Invalid request: {reason}")]
+    InvalidRequest { reason: String },
+
+    #[error("Audio file too large: {size} bytes (max {max})")]
+    TooLarge { size: u64, max: u64 },
+
+    #[error("Speech engine failed: {reason}")]
+    Failed { reason: String },
+
+    #[error("I/O error: {reason}")]
+    Io { reason: String },
+
+    #[error("Internal error: {reason}")]
+    Internal { reason: String },
+}
+
+impl CodedError for SpeechError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            SpeechError::Unsupported { .. } | SpeechError::Unavailable { .. } => {
+                ErrorCode::Unsupported
+            }
+            SpeechError::AccessDenied => ErrorCode::PermissionDenied,
+            SpeechError::InvalidRequest { .. } => ErrorCode::Validation,
+            SpeechError::TooLarge { .. } => ErrorCode::LimitExceeded,
+            SpeechError::Failed { .. } => ErrorCode::ProcessingFailed,
+            SpeechError::Io { .. } => ErrorCode::Filesystem,
+            SpeechError::Internal { .. } => ErrorCode::Internal,
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        self.tagged().get("details").cloned()
+    }
+}
+
+impl SpeechError {
+    /// The derived `{ type, details }` representation
+    fn tagged(&self) -> serde_json::Value {
+        SpeechError::serialize(self, serde_json::value::Serializer).unwrap_or_default()
+    }
+}
+
+impl Serialize for SpeechError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_tagged(self, &self.tagged(), serializer)
+    }
+}
+
+impl From<std::io::Error> for SpeechError {
+    fn from(e: std::io::Error) -> Self {
+        SpeechError::Io {
+            reason: e.to_string(),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl From<windows::core::Error> for SpeechError {
+    fn from(e: windows::core::Error) -> Self {
+        SpeechError::Failed {
+            reason: e.message().to_string(),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Speech on macOS: `say` for output, `SFSpeechRecognizer` for files.
+//!
+//! `say` plays through the default output device and is killed to stop or
+//! interrupt. Recognition prefers the on-device model; languages without
+//! one go through Apple's servers, which the system permission prompt
+//! (`NSSpeechRecognitionUsageDescription`) discloses.
+//!
+//! The recognizer delivers its results on the main queue, so the
+//! recognition blocks a worker thread until the final result arrives.
+
+use super::types::{SpeakOptions, SpeechStatus, SpeechVoice, Transcription};
+use super::SpeechError;
+use block2::RcBlock;
+use objc2::AllocAnyThread;
+use objc2_foundation::{NSError, NSLocale, NSString, NSURL};
+use objc2_speech::{
+    SFSpeechRecognitionResult, SFSpeechRecognizer, SFSpeechRecognizerAuthorizationStatus,
+    SFSpeechURLRecognitionRequest,
+};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::io::AsyncWriteExt;
+use tokio::process::{Child, Command};
+
+/// `say` speaks about 175 words per minute at its default rate
+const SAY_DEFAULT_WPM: f32 = 175.0;
+/// Upper bound for one recognition, the recognizer gives up on long files
+const RECOGNITION_TIMEOUT: Duration = Duration::from_secs(600);
+
+static SPEAKING: Mutex<Option<Child>> = Mutex::new(None);
+
+/// One line of `say -v ?`: `Name   en_US    # Sample sentence`.
+pub fn parse_say_voice(line: &str) -> Option<SpeechVoice> {
+    let (entry, _sample) = line.split_once('#')?;
+    let (name, locale) = entry.trim().rsplit_once(char::is_whitespace)?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some(SpeechVoice {
+        id: name.to_string(),
+        name: name.to_string(),
+        language: Some(locale.replace('_', "-")),
+    })
+}
+
+fn kill_speaking() {
+    if let Ok(mut speaking) = SPEAKING.lock() {
+        if let Some(mut child) = speaking.take() {
+            let _ = child.start_kill();
+        }
+    }
+}
+
+pub async fn status(_app: &AppHandle) -> SpeechStatus {
+    // SAFETY: class method without arguments.
+    let authorization = unsafe { SFSpeechRecognizer::authorizationStatus() };
+    let denied = matches!(
+        authorization,
+        SFSpeechRecognizerAuthorizationStatus::Denied
+            | SFSpeechRecognizerAuthorizationStatus::Restricted
+    );
+    SpeechStatus {
+        speak: true,
+        transcribe: !denied,
+        reason: denied.then(|| "Speech recognition is not allowed in System Settings".to_string()),
+    }
+}
+
+pub async fn voices(_app: &AppHandle) -> Result<Vec<SpeechVoice>, SpeechError> {
+    let output = Command::new("say").args(["-v", "?"]).output().await?;
+    if !output.status.success() {
+        return Err(SpeechError::Failed {
+            reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_say_voice)
+        .collect())
+}
+
+pub async fn speak(app: &AppHandle, text: &str, options: &SpeakOptions) -> Result<(), SpeechError> {
+    let voice = match (&options.voice, &options.language) {
+        (Some(voice), _) => Some(voice.clone()),
+        (None, Some(language)) => voices(app)
+            .await?
+            .into_iter()
+            .find(|v| {
+                v.language
+                    .as_deref()
+                    .is_some_and(|l| super::language_matches(language, l))
+            })
+            .map(|v| v.id),
+        (None, None) => None,
+    };
+
+    let mut command = Command::new("say");
+    command
+        .args([
+            "-r",
+            &(SAY_DEFAULT_WPM * options.rate()).round().to_string(),
+        ])
+        .args(["-f", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Some(voice) = &voice {
+        command.args(["-v", voice]);
+    }
+
+    kill_speaking();
+    let mut child = command.spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).await?;
+    }
+    if let Ok(mut speaking) = SPEAKING.lock() {
+        *speaking = Some(child);
+    }
+    Ok(())
+}
+
+pub async fn stop(_app: &AppHandle) -> Result<(), SpeechError> {
+    kill_speaking();
+    Ok(())
+}
+
+pub async fn transcribe(
+    _app: &AppHandle,
+    path: &Path,
+    language: Option<&str>,
+) -> Result<Transcription, SpeechError> {
+    let path = path.to_string_lossy().to_string();
+    let language = language.map(str::to_string);
+    tokio::task::spawn_blocking(move || {
+        authorize()?;
+        recognize(&path, language.as_deref())
+    })
+    .await
+    .map_err(|e| SpeechError::Internal {
+        reason: e.to_string(),
+    })?
+}
+
+/// Asks for the recognition permission on first use.
+fn authorize() -> Result<(), SpeechError> {
+    // SAFETY: class method without arguments.
+    let mut status = unsafe { SFSpeechRecognizer::authorizationStatus() };
+    if status == SFSpeechRecognizerAuthorizationStatus::NotDetermined {
+        let (tx, rx) = mpsc::channel();
+        let handler = RcBlock::new(move |status: SFSpeechRecognizerAuthorizationStatus| {
+            let _ = tx.send(status);
+        });
+        // SAFETY: the handler is a valid block; it is retained by the call.
+        unsafe { SFSpeechRecognizer::requestAuthorization(&handler) };
+        status = rx
+            .recv()
+            .unwrap_or(SFSpeechRecognizerAuthorizationStatus::Denied);
+    }
+    if status == SFSpeechRecognizerAuthorizationStatus::Authorized {
+        Ok(())
+    } else {
+        Err(SpeechError::AccessDenied)
+    }
+}
+
+fn recognize(path: &str, language: Option<&str>) -> Result<Transcription, SpeechError> {
+    // SAFETY: plain Objective-C object creation and property access; the
+    // recognizer and request stay alive until the result arrived.
+    unsafe {
+        let locale = match language {
+            Some(language) => NSLocale::initWithLocaleIdentifier(
+                NSLocale::alloc(),
+                &NSString::from_str(&language.replace('-', "_")),
+            ),
+            None => NSLocale::currentLocale(),
+        };
+        let recognizer = SFSpeechRecognizer::initWithLocale(SFSpeechRecognizer::alloc(), &locale)
+            .ok_or_else(|| SpeechError::Unsupported {
+            reason: format!("No speech recognizer for '{}'", locale.localeIdentifier()),
+        })?;
+        if !recognizer.isAvailable() {
+            return Err(SpeechError::Unavailable {
+                reason: "The speech recognizer is currently unavailable".to_string(),
+            });
+        }
+
+        let url = NSURL::fileURLWithPath(&NSString::from_str(path));
+        let request = SFSpeechURLRecognitionRequest::initWithURL(
+            SFSpeechURLRecognitionRequest::alloc(),
+            &url,
+        );
+        request.setShouldReportPartialResults(false);
+        if recognizer.supportsOnDeviceRecognition() {
+            request.setRequiresOnDeviceRecognition(true);
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let handler = RcBlock::new(
+            move |result: *mut SFSpeechRecognitionResult, error: *mut NSError| {
+                // SAFETY: Speech passes a valid result/error or null.
+                let outcome = match (result.as_ref(), error.as_ref()) {
+                    (Some(result), _) if result.isFinal() => {
+                        Ok(result.bestTranscription().formattedString().to_string())
+                    }
+                    (_, Some(error)) => Err(SpeechError::Failed {
+                        reason: error.localizedDescription().to_string(),
+                    }),
+                    _ => return,
+                };
+                let _ = tx.send(outcome);
+            },
+        );
+        let task = recognizer.recognitionTaskWithRequest_resultHandler(&request, &handler);
+
+        let text = match rx.recv_timeout(RECOGNITION_TIMEOUT) {
+            Ok(outcome) => outcome?,
+            Err(_) => {
+                task.cancel();
+                return Err(SpeechError::Failed {
+                    reason: "Speech recognition timed out".to_string(),
+                });
+            }
+        };
+        Ok(Transcription {
+            text,
+            language: Some(locale.localeIdentifier().to_string().replace('_', "-")),
+        })
+    }
+}
@@ -0,0 +1,179 @@
+// src-tauri/src/accessibility/speech/mod.rs
+//!
+//! Speech Output and Recognition
+//!
+//! Thin wrapper over the speech engines of the OS, so extensions get voice
+//! output and transcription (voice notes, read-aloud) without shipping their
+//! own engine bindings:
+//! - Linux: speech-dispatcher over its SSIP socket; there is no system
+//!   recognizer
+//! - macOS: `say` for output, `SFSpeechRecognizer` (on-device where the
+//!   language supports it) for transcription
+//! - Windows: `SpeechSynthesizer` played through a `MediaPlayer`; WinRT has no
+//!   recognizer for audio files
+//! - Android: `TextToSpeech` through the native `SpeechPlugin`; the system
+//!   recognizer only listens to the microphone
+//!
+//! `speech_status` tells the frontend which half works. Speaking returns
+//! once the text is queued; a new text interrupts the current one.
+//!
+//! `speech_*` are internal commands for the host UI, `extension_speech_*`
+//! the permission-checked variants.
+
+#[cfg(target_os = "android")]
+mod android;
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "linux")]
+mod speechd;
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "windows"
+)))]
+mod unsupported;
+#[cfg(target_os = "windows")]
+mod winrt;
+
+#[cfg(target_os = "android")]
+use android as platform;
+#[cfg(target_os = "macos")]
+use macos as platform;
+#[cfg(target_os = "linux")]
+use speechd as platform;
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "windows"
+)))]
+use unsupported as platform;
+#[cfg(target_os = "windows")]
+use winrt as platform;
+
+pub mod commands;
+pub mod error;
+pub mod types;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(target_os = "android")]
+pub use android::init;
+pub use error::SpeechError;
+
+use std::path::Path;
+use tauri::AppHandle;
+use types::{SpeakOptions, SpeechStatus, SpeechVoice, Transcription};
+
+/// Longest text for one `speak` call, in characters
+pub const MAX_TEXT_CHARS: usize = 20_000;
+/// Largest audio file `transcribe` accepts
+pub const MAX_AUDIO_BYTES: u64 = 200 * 1024 * 1024;
+pub const MIN_RATE: f32 = 0.5;
+pub const MAX_RATE: f32 = 2.0;
+
+pub fn validate_text(text: &str) -> Result<(), SpeechError> {
+    if text.trim().is_empty() {
+        return Err(SpeechError::InvalidRequest {
+            reason: "text is empty".to_string(),
+        });
+    }
+    let chars = text.chars().count();
+    if chars > MAX_TEXT_CHARS {
+        return Err(SpeechError::InvalidRequest {
+            reason: format!("text has {chars} characters (max {MAX_TEXT_CHARS})"),
+        });
+    }
+    Ok(())
+}
+
+/// Loose BCP 47 check (`de`, `de-DE`, `zh-Hant-TW`); the tag ends up in
+/// engine commands, so only letters, digits and dashes pass.
+pub fn validate_language(language: &str) -> Result<(), SpeechError> {
+    let valid = !language.is_empty()
+        && language.len() <= 35
+        && language
+            .split('-')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()));
+    if valid {
+        Ok(())
+    } else {
+        Err(SpeechError::InvalidRequest {
+            reason: format!("invalid language tag '{language}'"),
+        })
+    }
+}
+
+pub fn validate_options(options: &SpeakOptions) -> Result<(), SpeechError> {
+    if let Some(language) = &options.language {
+        validate_language(language)?;
+    }
+    if let Some(voice) = &options.voice {
+        if voice.trim().is_empty() || voice.len() > 200 || voice.chars().any(char::is_control) {
+            return Err(SpeechError::InvalidRequest {
+                reason: "invalid voice id".to_string(),
+            });
+        }
+    }
+    let rate = options.rate();
+    if !(MIN_RATE..=MAX_RATE).contains(&rate) {
+        return Err(SpeechError::InvalidRequest {
+            reason: format!("rate must be between {MIN_RATE} and {MAX_RATE}"),
+        });
+    }
+    Ok(())
+}
+
+/// Whether this language tag names the same language as `other`
+/// (`de` matches `de-DE`, `en_US` matches `en-us`).
+pub fn language_matches(tag: &str, other: &str) -> bool {
+    let normalize = |s: &str| s.replace('_', "-").to_ascii_lowercase();
+    let (tag, other) = (normalize(tag), normalize(other));
+    tag == other || other.starts_with(&format!("{tag}-")) || tag.starts_with(&format!("{other}-"))
+}
+
+pub async fn status(app: &AppHandle) -> SpeechStatus {
+    platform::status(app).await
+}
+
+pub async fn voices(app: &AppHandle) -> Result<Vec<SpeechVoice>, SpeechError> {
+    let mut voices = platform::voices(app).await?;
+    voices.sort_by(|a, b| {
+        a.language
+            .cmp(&b.language)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    Ok(voices)
+}
+
+/// Speaks `text`, interrupting whatever is being spoken.
+pub async fn speak(app: &AppHandle, text: &str, options: &SpeakOptions) -> Result<(), SpeechError> {
+    validate_text(text)?;
+    validate_options(options)?;
+    platform::speak(app, text, options).await
+}
+
+pub async fn stop(app: &AppHandle) -> Result<(), SpeechError> {
+    platform::stop(app).await
+}
+
+/// Transcribes the audio file at `path` (any format the platform decodes).
+pub async fn transcribe(
+    app: &AppHandle,
+    path: &Path,
+    language: Option<&str>,
+) -> Result<Transcription, SpeechError> {
+    if let Some(language) = language {
+        validate_language(language)?;
+    }
+    let size = tokio::fs::metadata(path).await?.len();
+    if size > MAX_AUDIO_BYTES {
+        return Err(SpeechError::TooLarge {
+            size,
+            max: MAX_AUDIO_BYTES,
+        });
+    }
+    platform::transcribe(app, path, language).await
+}
@@ -0,0 +1,263 @@
+//! Speech output through speech-dispatcher.
+//!
+//! Talks SSIP over the user's speech-dispatcher socket directly instead of
+//! linking libspeechd. The connection stays open: `CANCEL SELF` only stops
+//! messages of the connection that sent them. The daemon is autospawned if
+//! the socket isn't there yet.
+//!
+//! Linux has no system-wide recognizer, so transcription is unsupported.
+
+use super::types::{SpeakOptions, SpeechStatus, SpeechVoice, Transcription};
+use super::SpeechError;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::AppHandle;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::UnixStream;
+use tokio::sync::Mutex;
+
+const NO_TRANSCRIBE: &str = "Linux has no system speech recognizer";
+
+/// One parsed reply line: `NNN-text` continues, `NNN text` ends the reply.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ReplyLine<'a> {
+    pub code: u16,
+    pub last: bool,
+    pub text: &'a str,
+}
+
+pub fn parse_reply_line(line: &str) -> Option<ReplyLine<'_>> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let code = line.get(..3)?.parse().ok()?;
+    let last = match line.as_bytes().get(3) {
+        Some(b'-') => false,
+        Some(b' ') | None => true,
+        _ => return None,
+    };
+    Some(ReplyLine {
+        code,
+        last,
+        text: line.get(4..).unwrap_or_default(),
+    })
+}
+
+/// Message body for `SPEAK`: CRLF line ends, lines starting with `.` doubled
+/// (a lone `.` ends the message), terminated by `CRLF.CRLF`.
+pub fn escape_text(text: &str) -> String {
+    let mut body = String::with_capacity(text.len() + 8);
+    for line in text.lines() {
+        if line.starts_with('.') {
+            body.push('.');
+        }
+        body.push_str(line);
+        body.push_str("\r\n");
+    }
+    body.push_str(".\r\n");
+    body
+}
+
+/// `LIST SYNTHESIS_VOICES` entry: `name<TAB>language<TAB>variant`.
+pub fn parse_voice(text: &str) -> Option<SpeechVoice> {
+    let mut fields = text.split('\t');
+    let name = fields.next()?.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let language = fields
+        .next()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && *l != "none")
+        .map(|l| l.replace('_', "-"));
+    Some(SpeechVoice {
+        id: name.to_string(),
+        name: name.to_string(),
+        language,
+    })
+}
+
+/// SSIP rate from -100 to 100, 0 is the normal speed.
+pub fn ssip_rate(rate: f32) -> i32 {
+    (((rate - 1.0) * 100.0).round() as i32).clamp(-100, 100)
+}
+
+fn socket_path() -> Option<PathBuf> {
+    if let Ok(address) = std::env::var("SPEECHD_ADDRESS") {
+        if let Some(path) = address.strip_prefix("unix_socket:") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")?;
+    Some(PathBuf::from(runtime_dir).join("speech-dispatcher/speechd.sock"))
+}
+
+struct Connection {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+}
+
+impl Connection {
+    async fn open() -> Result<Self, SpeechError> {
+        let path = socket_path().ok_or_else(|| SpeechError::Unavailable {
+            reason: "No speech-dispatcher socket (XDG_RUNTIME_DIR not set)".to_string(),
+        })?;
+        let stream = match UnixStream::connect(&path).await {
+            Ok(stream) => stream,
+            Err(_) => {
+                let spawned = tokio::process::Command::new("speech-dispatcher")
+                    .arg("--spawn")
+                    .status()
+                    .await
+                    .map_err(|e| SpeechError::Unavailable {
+                        reason: format!("speech-dispatcher is not installed: {e}"),
+                    })?;
+                if !spawned.success() {
+                    tracing::debug!("speech-dispatcher --spawn exited with {spawned}");
+                }
+                UnixStream::connect(&path)
+                    .await
+                    .map_err(|e| SpeechError::Unavailable {
+                        reason: format!("Cannot connect to speech-dispatcher: {e}"),
+                    })?
+            }
+        };
+        let (read, writer) = stream.into_split();
+        let mut connection = Self {
+            reader: BufReader::new(read),
+            writer,
+        };
+        connection
+            .command("SET SELF CLIENT_NAME user:haex-vault:main")
+            .await?;
+        Ok(connection)
+    }
+
+    async fn send(&mut self, data: &str) -> Result<Vec<String>, SpeechError> {
+        self.writer.write_all(data.as_bytes()).await?;
+        let mut lines = Vec::new();
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line).await? == 0 {
+                return Err(SpeechError::Unavailable {
+                    reason: "speech-dispatcher closed the connection".to_string(),
+                });
+            }
+            let reply = parse_reply_line(&line).ok_or_else(|| SpeechError::Failed {
+                reason: format!("Unexpected SSIP reply '{}'", line.trim_end()),
+            })?;
+            if !(200..300).contains(&reply.code) {
+                return Err(SpeechError::Failed {
+                    reason: format!("{} {}", reply.code, reply.text),
+                });
+            }
+            if reply.last {
+                return Ok(lines);
+            }
+            lines.push(reply.text.to_string());
+        }
+    }
+
+    async fn command(&mut self, command: &str) -> Result<Vec<String>, SpeechError> {
+        self.send(&format!("{command}\r\n")).await
+    }
+}
+
+fn connection() -> &'static Mutex<Option<Connection>> {
+    static CONNECTION: OnceLock<Mutex<Option<Connection>>> = OnceLock::new();
+    CONNECTION.get_or_init(|| Mutex::new(None))
+}
+
+/// Runs `f` on the shared connection, opening it first if needed. A failed
+/// call drops the connection so the next one starts fresh.
+async fn with_connection<T>(
+    f: impl for<'c> FnOnce(
+        &'c mut Connection,
+    ) -> std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<T, SpeechError>> + Send + 'c>,
+    >,
+) -> Result<T, SpeechError> {
+    let mut guard = connection().lock().await;
+    if guard.is_none() {
+        *guard = Some(Connection::open().await?);
+    }
+    let Some(conn) = guard.as_mut() else {
+        return Err(SpeechError::Internal {
+            reason: "speech-dispatcher connection missing".to_string(),
+        });
+    };
+    let result = f(conn).await;
+    if result.is_err() {
+        *guard = None;
+    }
+    result
+}
+
+pub async fn status(_app: &AppHandle) -> SpeechStatus {
+    match with_connection(|c| Box::pin(c.command("GET OUTPUT_MODULE"))).await {
+        Ok(_) => SpeechStatus {
+            speak: true,
+            transcribe: false,
+            reason: Some(NO_TRANSCRIBE.to_string()),
+        },
+        Err(e) => SpeechStatus {
+            speak: false,
+            transcribe: false,
+            reason: Some(e.to_string()),
+        },
+    }
+}
+
+pub async fn voices(_app: &AppHandle) -> Result<Vec<SpeechVoice>, SpeechError> {
+    let lines = with_connection(|c| Box::pin(c.command("LIST SYNTHESIS_VOICES"))).await?;
+    Ok(lines.iter().filter_map(|l| parse_voice(l)).collect())
+}
+
+pub async fn speak(
+    _app: &AppHandle,
+    text: &str,
+    options: &SpeakOptions,
+) -> Result<(), SpeechError> {
+    let body = escape_text(text);
+    let options = options.clone();
+    with_connection(move |c| {
+        Box::pin(async move {
+            c.command("CANCEL SELF").await?;
+            c.command(&format!("SET SELF RATE {}", ssip_rate(options.rate())))
+                .await?;
+            if let Some(language) = &options.language {
+                c.command(&format!("SET SELF LANGUAGE {language}")).await?;
+            }
+            match &options.voice {
+                Some(voice) => {
+                    c.command(&format!("SET SELF SYNTHESIS_VOICE {voice}"))
+                        .await?
+                }
+                // Resets a voice picked by an earlier call
+                None => c.command("SET SELF VOICE_TYPE MALE1").await?,
+            };
+            c.command("SPEAK").await?;
+            c.send(&body).await?;
+            Ok(())
+        })
+    })
+    .await
+}
+
+pub async fn stop(_app: &AppHandle) -> Result<(), SpeechError> {
+    if connection().lock().await.is_none() {
+        return Ok(());
+    }
+    with_connection(|c| Box::pin(c.command("CANCEL SELF")))
+        .await
+        .map(|_| ())
+}
+
+pub async fn transcribe(
+    _app: &AppHandle,
+    _path: &Path,
+    _language: Option<&str>,
+) -> Result<Transcription, SpeechError> {
+    Err(SpeechError::Unsupported {
+        reason: NO_TRANSCRIBE.to_string(),
+    })
+}
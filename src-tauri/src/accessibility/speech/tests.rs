@@ -0,0 +1,119 @@
+//! Tests for speech request validation and the engine protocol parsing
+
+use super::types::SpeakOptions;
+use super::{language_matches, validate_language, validate_options, validate_text};
+use super::{SpeechError, MAX_TEXT_CHARS};
+
+#[test]
+fn test_validate_text() {
+    assert!(validate_text("Hallo Welt").is_ok());
+    assert!(matches!(
+        validate_text("  \n"),
+        Err(SpeechError::InvalidRequest { .. })
+    ));
+    assert!(validate_text(&"ä".repeat(MAX_TEXT_CHARS)).is_ok());
+    assert!(validate_text(&"ä".repeat(MAX_TEXT_CHARS + 1)).is_err());
+}
+
+#[test]
+fn test_validate_language() {
+    for tag in ["de", "de-DE", "zh-Hant-TW", "en-US"] {
+        assert!(validate_language(tag).is_ok(), "{tag}");
+    }
+    for tag in ["", "de-", "de DE", "de\r\nSPEAK", "en_US", &"a".repeat(36)] {
+        assert!(validate_language(tag).is_err(), "{tag:?}");
+    }
+}
+
+#[test]
+fn test_validate_options() {
+    assert!(validate_options(&SpeakOptions::default()).is_ok());
+    let options = |rate: f32| SpeakOptions {
+        rate: Some(rate),
+        ..Default::default()
+    };
+    assert!(validate_options(&options(0.5)).is_ok());
+    assert!(validate_options(&options(2.0)).is_ok());
+    assert!(validate_options(&options(0.1)).is_err());
+    assert!(validate_options(&options(f32::NAN)).is_err());
+
+    let voice = SpeakOptions {
+        voice: Some("Anna\r\nQUIT".to_string()),
+        ..Default::default()
+    };
+    assert!(validate_options(&voice).is_err());
+}
+
+#[test]
+fn test_language_matches() {
+    assert!(language_matches("de", "de-DE"));
+    assert!(language_matches("en-US", "en_us"));
+    assert!(language_matches("de-DE", "de"));
+    assert!(!language_matches("de", "dsb-DE"));
+    assert!(!language_matches("en-GB", "en-US"));
+}
+
+#[cfg(target_os = "linux")]
+mod speechd {
+    use super::super::speechd::{escape_text, parse_reply_line, parse_voice, ssip_rate, ReplyLine};
+
+    #[test]
+    fn test_parse_reply_line() {
+        assert_eq!(
+            parse_reply_line("225-21\r\n"),
+            Some(ReplyLine {
+                code: 225,
+                last: false,
+                text: "21"
+            })
+        );
+        assert_eq!(
+            parse_reply_line("225 OK MESSAGE QUEUED\r\n"),
+            Some(ReplyLine {
+                code: 225,
+                last: true,
+                text: "OK MESSAGE QUEUED"
+            })
+        );
+        assert!(parse_reply_line("OK\r\n").is_none());
+        assert!(parse_reply_line("2251 x").is_none());
+    }
+
+    #[test]
+    fn test_escape_text_doubles_leading_dots() {
+        assert_eq!(
+            escape_text("Hallo\n.\n..Ende"),
+            "Hallo\r\n..\r\n...Ende\r\n.\r\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_voice_and_rate() {
+        let voice = parse_voice("German\tde_DE\tnone").unwrap();
+        assert_eq!(voice.id, "German");
+        assert_eq!(voice.language.as_deref(), Some("de-DE"));
+        assert_eq!(parse_voice("x\tnone").unwrap().language, None);
+        assert!(parse_voice("").is_none());
+
+        assert_eq!(ssip_rate(1.0), 0);
+        assert_eq!(ssip_rate(0.5), -50);
+        assert_eq!(ssip_rate(2.0), 100);
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod say {
+    use super::super::macos::parse_say_voice;
+
+    #[test]
+    fn test_parse_say_voice() {
+        let voice =
+            parse_say_voice("Anna                de_DE    # Hallo, ich heiße Anna.").unwrap();
+        assert_eq!(voice.name, "Anna");
+        assert_eq!(voice.language.as_deref(), Some("de-DE"));
+
+        let voice = parse_say_voice("Bad News            en_US    # The light you see").unwrap();
+        assert_eq!(voice.name, "Bad News");
+        assert!(parse_say_voice("garbage").is_none());
+    }
+}
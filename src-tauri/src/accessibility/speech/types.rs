@@ -0,0 +1,70 @@
+// src-tauri/src/accessibility/speech/types.rs
+//!
+//! Speech Types
+//!
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// What the speech engines of this platform can do right now.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SpeechStatus {
+    pub speak: bool,
+    pub transcribe: bool,
+    /// Why speaking or transcribing is unavailable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub reason: Option<String>,
+}
+
+/// A voice of the system speech synthesizer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SpeechVoice {
+    /// Pass as `voice` in [`SpeakOptions`]
+    pub id: String,
+    pub name: String,
+    /// BCP 47 tag, e.g. `de-DE`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SpeakOptions {
+    /// Voice id from `speech_list_voices`; wins over `language`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub voice: Option<String>,
+    /// BCP 47 tag; picks the first matching voice
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub language: Option<String>,
+    /// 0.5 to 2.0, 1.0 is the normal speed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub rate: Option<f32>,
+}
+
+impl SpeakOptions {
+    pub fn rate(&self) -> f32 {
+        self.rate.unwrap_or(1.0)
+    }
+}
+
+/// Result of `speech_transcribe`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Transcription {
+    pub text: String,
+    /// Language the recognizer used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub language: Option<String>,
+}
@@ -0,0 +1,46 @@
+//! Platforms without a supported speech engine.
+
+use super::types::{SpeakOptions, SpeechStatus, SpeechVoice, Transcription};
+use super::SpeechError;
+use std::path::Path;
+use tauri::AppHandle;
+
+const REASON: &str = "No speech engine integration for this platform";
+
+fn unsupported() -> SpeechError {
+    SpeechError::Unsupported {
+        reason: REASON.to_string(),
+    }
+}
+
+pub async fn status(_app: &AppHandle) -> SpeechStatus {
+    SpeechStatus {
+        speak: false,
+        transcribe: false,
+        reason: Some(REASON.to_string()),
+    }
+}
+
+pub async fn voices(_app: &AppHandle) -> Result<Vec<SpeechVoice>, SpeechError> {
+    Err(unsupported())
+}
+
+pub async fn speak(
+    _app: &AppHandle,
+    _text: &str,
+    _options: &SpeakOptions,
+) -> Result<(), SpeechError> {
+    Err(unsupported())
+}
+
+pub async fn stop(_app: &AppHandle) -> Result<(), SpeechError> {
+    Ok(())
+}
+
+pub async fn transcribe(
+    _app: &AppHandle,
+    _path: &Path,
+    _language: Option<&str>,
+) -> Result<Transcription, SpeechError> {
+    Err(unsupported())
+}
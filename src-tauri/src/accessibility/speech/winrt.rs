@@ -0,0 +1,147 @@
+//! Speech output via `Windows.Media.SpeechSynthesis`.
+//!
+//! The synthesizer renders into a stream that a `MediaPlayer` plays; the
+//! player is kept so `stop` (or the next text) can interrupt it. WinRT only
+//! recognizes live microphone input, so transcription is unsupported.
+//! The WinRT operations are awaited with their blocking `get()` on a
+//! blocking thread.
+
+use super::types::{SpeakOptions, SpeechStatus, SpeechVoice, Transcription};
+use super::SpeechError;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::AppHandle;
+use windows::core::HSTRING;
+use windows::Media::Core::MediaSource;
+use windows::Media::Playback::MediaPlayer;
+use windows::Media::SpeechSynthesis::{SpeechSynthesizer, VoiceInformation};
+
+const NO_TRANSCRIBE: &str = "Windows can't transcribe audio files";
+
+static PLAYER: Mutex<Option<MediaPlayer>> = Mutex::new(None);
+
+fn to_voice(voice: &VoiceInformation) -> windows::core::Result<SpeechVoice> {
+    Ok(SpeechVoice {
+        id: voice.Id()?.to_string(),
+        name: voice.DisplayName()?.to_string(),
+        language: Some(voice.Language()?.to_string()).filter(|l| !l.is_empty()),
+    })
+}
+
+fn all_voices() -> windows::core::Result<Vec<(VoiceInformation, SpeechVoice)>> {
+    SpeechSynthesizer::AllVoices()?
+        .into_iter()
+        .map(|voice| to_voice(&voice).map(|info| (voice, info)))
+        .collect()
+}
+
+fn stop_player() {
+    if let Ok(mut player) = PLAYER.lock() {
+        if let Some(player) = player.take() {
+            let _ = player.Pause();
+            let _ = player.Close();
+        }
+    }
+}
+
+pub async fn status(_app: &AppHandle) -> SpeechStatus {
+    let voices = tokio::task::spawn_blocking(|| SpeechSynthesizer::AllVoices()?.Size()).await;
+    match voices {
+        Ok(Ok(count)) if count > 0 => SpeechStatus {
+            speak: true,
+            transcribe: false,
+            reason: Some(NO_TRANSCRIBE.to_string()),
+        },
+        Ok(Ok(_)) => SpeechStatus {
+            speak: false,
+            transcribe: false,
+            reason: Some("No speech voices are installed".to_string()),
+        },
+        Ok(Err(e)) => SpeechStatus {
+            speak: false,
+            transcribe: false,
+            reason: Some(format!("Speech synthesis unavailable: {e}")),
+        },
+        Err(e) => SpeechStatus {
+            speak: false,
+            transcribe: false,
+            reason: Some(format!("Speech synthesis unavailable: {e}")),
+        },
+    }
+}
+
+pub async fn voices(_app: &AppHandle) -> Result<Vec<SpeechVoice>, SpeechError> {
+    let voices = tokio::task::spawn_blocking(all_voices)
+        .await
+        .map_err(|e| SpeechError::Internal {
+            reason: e.to_string(),
+        })??;
+    Ok(voices.into_iter().map(|(_, voice)| voice).collect())
+}
+
+pub async fn speak(
+    _app: &AppHandle,
+    text: &str,
+    options: &SpeakOptions,
+) -> Result<(), SpeechError> {
+    let text = HSTRING::from(text);
+    let options = options.clone();
+    let player = tokio::task::spawn_blocking(move || -> Result<MediaPlayer, SpeechError> {
+        let synthesizer = SpeechSynthesizer::new()?;
+        let voice =
+            all_voices()?
+                .into_iter()
+                .find(|(_, v)| match (&options.voice, &options.language) {
+                    (Some(id), _) => &v.id == id,
+                    (None, Some(language)) => v
+                        .language
+                        .as_deref()
+                        .is_some_and(|l| super::language_matches(language, l)),
+                    (None, None) => false,
+                });
+        match voice {
+            Some((voice, _)) => synthesizer.SetVoice(&voice)?,
+            None if options.voice.is_some() => {
+                return Err(SpeechError::InvalidRequest {
+                    reason: "Unknown voice".to_string(),
+                })
+            }
+            None => {}
+        }
+        synthesizer
+            .Options()?
+            .SetSpeakingRate(f64::from(options.rate()))?;
+
+        let stream = synthesizer.SynthesizeTextToStreamAsync(&text)?.get()?;
+        let source = MediaSource::CreateFromStream(&stream, &stream.ContentType()?)?;
+        let player = MediaPlayer::new()?;
+        player.SetSource(&source)?;
+        Ok(player)
+    })
+    .await
+    .map_err(|e| SpeechError::Internal {
+        reason: e.to_string(),
+    })??;
+
+    stop_player();
+    player.Play()?;
+    if let Ok(mut current) = PLAYER.lock() {
+        *current = Some(player);
+    }
+    Ok(())
+}
+
+pub async fn stop(_app: &AppHandle) -> Result<(), SpeechError> {
+    stop_player();
+    Ok(())
+}
+
+pub async fn transcribe(
+    _app: &AppHandle,
+    _path: &Path,
+    _language: Option<&str>,
+) -> Result<Transcription, SpeechError> {
+    Err(SpeechError::Unsupported {
+        reason: NO_TRANSCRIBE.to_string(),
+    })
+}
@@ -0,0 +1,77 @@
+//! Profile commands (main window)
+
+use super::{
+    active_profile_id, profile_dir_in, registry_path, AppProfile, ProfileError, ProfileRegistry,
+};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+/// Profiles for the startup picker and the settings
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct AppProfileList {
+    /// Profile this instance runs with
+    pub active_id: String,
+    /// Profile the next start uses
+    pub startup_id: String,
+    pub profiles: Vec<AppProfile>,
+}
+
+fn load(app_handle: &AppHandle) -> Result<(ProfileRegistry, std::path::PathBuf), ProfileError> {
+    let path = registry_path(app_handle).map_err(|e| ProfileError::Io(e.to_string()))?;
+    Ok((ProfileRegistry::load_from(&path), path))
+}
+
+#[tauri::command]
+pub fn profile_list(app_handle: AppHandle) -> Result<AppProfileList, ProfileError> {
+    let (registry, _) = load(&app_handle)?;
+    Ok(AppProfileList {
+        active_id: active_profile_id().to_string(),
+        startup_id: registry.startup_id().to_string(),
+        profiles: registry.profiles(),
+    })
+}
+
+#[tauri::command]
+pub fn profile_create(app_handle: AppHandle, name: String) -> Result<AppProfile, ProfileError> {
+    let (mut registry, path) = load(&app_handle)?;
+    let profile = registry.create(&name, time::OffsetDateTime::now_utc().unix_timestamp())?;
+    registry.save_to(&path)?;
+    Ok(profile)
+}
+
+/// Makes `profile_id` the startup profile and restarts into it. Choosing the
+/// running profile only updates the registry.
+#[tauri::command]
+pub fn profile_switch(app_handle: AppHandle, profile_id: String) -> Result<(), ProfileError> {
+    let (mut registry, path) = load(&app_handle)?;
+    registry.set_startup(&profile_id)?;
+    registry.save_to(&path)?;
+
+    if profile_id != active_profile_id() {
+        println!("[Profile] Switching to profile '{profile_id}', restarting");
+        app_handle.restart();
+    }
+    Ok(())
+}
+
+/// Deletes a profile together with its data directory
+#[tauri::command]
+pub fn profile_delete(app_handle: AppHandle, profile_id: String) -> Result<(), ProfileError> {
+    let (mut registry, path) = load(&app_handle)?;
+    registry.remove(&profile_id, active_profile_id())?;
+    registry.save_to(&path)?;
+
+    let root = app_handle
+        .path()
+        .app_local_data_dir()
+        .map_err(|e| ProfileError::Io(e.to_string()))?;
+    let dir = profile_dir_in(&root, &profile_id);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)
+            .map_err(|e| ProfileError::Io(format!("{}: {e}", dir.display())))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,274 @@
+//! Isolated app profiles
+//!
+//! One installation can host several profiles, e.g. "Work" and "Personal".
+//! Each profile has its own data directory for everything that belongs to
+//! the user's setup rather than the physical device: vaults (and with them
+//! the external client authorizations stored inside), installed extensions
+//! and their private data, the extension revocation list, local API tokens,
+//! the bridge network and TLS settings, the vault access store and
+//! `instance.json`. The device id file and the relay store stay shared.
+//!
+//! The `default` profile uses the app data directories themselves, so
+//! existing installations keep their data. Other profiles live under
+//! `<app_local_data>/profiles/<id>/`. The registry
+//! (`<app_local_data>/profiles.json`) lists the profiles and the one to
+//! start with.
+//!
+//! The active profile is fixed for the lifetime of the process: paths are
+//! resolved against it everywhere, so switching at runtime would mix data
+//! of two profiles. `profile_switch` records the choice and restarts the
+//! app; `--profile <id>` on the command line overrides it for one start.
+
+pub mod commands;
+#[cfg(test)]
+mod tests;
+
+use crate::error_code::{CodedError, ErrorCode};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+use thiserror::Error;
+use ts_rs::TS;
+
+/// Profile that maps to the plain app data directories
+pub const DEFAULT_PROFILE_ID: &str = "default";
+
+/// Registry file in the app local data directory
+const REGISTRY_FILE: &str = "profiles.json";
+
+/// Parent directory of the non-default profiles
+const PROFILES_DIR: &str = "profiles";
+
+/// Store file of the HLC node id (resolved by the store plugin)
+const INSTANCE_STORE_FILE: &str = "instance.json";
+
+/// Longest accepted profile name
+const MAX_NAME_LEN: usize = 64;
+
+static ACTIVE_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// A profile as listed in the registry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct AppProfile {
+    pub id: String,
+    pub name: String,
+    /// Unix timestamp (s), 0 for the default profile
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+/// Errors of the profile registry and commands
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("{0}")]
+    Invalid(String),
+    #[error("Unknown profile '{0}'")]
+    NotFound(String),
+    #[error("{0}")]
+    Io(String),
+}
+
+impl CodedError for ProfileError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            ProfileError::Invalid(_) => ErrorCode::Validation,
+            ProfileError::NotFound(_) => ErrorCode::ResourceNotFound,
+            ProfileError::Io(_) => ErrorCode::Filesystem,
+        }
+    }
+}
+
+impl Serialize for ProfileError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Serialize::serialize(&self.envelope(), serializer)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileRegistry {
+    /// Profile to start with; `None` means the default profile
+    #[serde(default)]
+    active: Option<String>,
+    /// Profiles besides the default one
+    #[serde(default)]
+    profiles: Vec<AppProfile>,
+}
+
+impl ProfileRegistry {
+    /// Loads the registry from `path`; a missing or corrupt file yields an
+    /// empty registry (only the default profile).
+    pub fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), ProfileError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| ProfileError::Io(e.to_string()))?;
+        }
+        let json =
+            serde_json::to_string_pretty(self).map_err(|e| ProfileError::Io(e.to_string()))?;
+        fs::write(path, json).map_err(|e| ProfileError::Io(e.to_string()))
+    }
+
+    /// All profiles, the default one first
+    pub fn profiles(&self) -> Vec<AppProfile> {
+        let default = AppProfile {
+            id: DEFAULT_PROFILE_ID.to_string(),
+            name: "Default".to_string(),
+            created_at: 0,
+        };
+        std::iter::once(default)
+            .chain(self.profiles.iter().cloned())
+            .collect()
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        id == DEFAULT_PROFILE_ID || self.profiles.iter().any(|p| p.id == id)
+    }
+
+    /// Profile to start with; unknown ids fall back to the default profile
+    pub fn startup_id(&self) -> &str {
+        match self.active.as_deref() {
+            Some(id) if self.contains(id) => id,
+            _ => DEFAULT_PROFILE_ID,
+        }
+    }
+
+    pub fn create(&mut self, name: &str, now: i64) -> Result<AppProfile, ProfileError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+            return Err(ProfileError::Invalid(format!(
+                "Profile names must have 1 to {MAX_NAME_LEN} characters"
+            )));
+        }
+        if self
+            .profiles()
+            .iter()
+            .any(|p| p.name.eq_ignore_ascii_case(name))
+        {
+            return Err(ProfileError::Invalid(format!(
+                "A profile named '{name}' already exists"
+            )));
+        }
+        let profile = AppProfile {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            created_at: now,
+        };
+        self.profiles.push(profile.clone());
+        Ok(profile)
+    }
+
+    pub fn set_startup(&mut self, id: &str) -> Result<(), ProfileError> {
+        if !self.contains(id) {
+            return Err(ProfileError::NotFound(id.to_string()));
+        }
+        self.active = (id != DEFAULT_PROFILE_ID).then(|| id.to_string());
+        Ok(())
+    }
+
+    /// Removes a profile from the registry. The default profile and the
+    /// one this process runs with can't be removed.
+    pub fn remove(&mut self, id: &str, running_id: &str) -> Result<(), ProfileError> {
+        if id == DEFAULT_PROFILE_ID {
+            return Err(ProfileError::Invalid(
+                "The default profile can't be deleted".to_string(),
+            ));
+        }
+        if id == running_id {
+            return Err(ProfileError::Invalid(
+                "The active profile can't be deleted".to_string(),
+            ));
+        }
+        let before = self.profiles.len();
+        self.profiles.retain(|p| p.id != id);
+        if self.profiles.len() == before {
+            return Err(ProfileError::NotFound(id.to_string()));
+        }
+        if self.active.as_deref() == Some(id) {
+            self.active = None;
+        }
+        Ok(())
+    }
+}
+
+/// Data directory of profile `id` below the app local data directory
+pub fn profile_dir_in(root: &Path, id: &str) -> PathBuf {
+    if id == DEFAULT_PROFILE_ID {
+        root.to_path_buf()
+    } else {
+        root.join(PROFILES_DIR).join(id)
+    }
+}
+
+/// Profile id passed as `--profile <id>` or `--profile=<id>`
+pub fn requested_profile(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            return args.next();
+        }
+        if let Some(id) = arg.strip_prefix("--profile=") {
+            return Some(id.to_string());
+        }
+    }
+    None
+}
+
+/// Profile this process runs with
+pub fn active_profile_id() -> &'static str {
+    ACTIVE_PROFILE
+        .get()
+        .map(String::as_str)
+        .unwrap_or(DEFAULT_PROFILE_ID)
+}
+
+pub(crate) fn registry_path(app_handle: &AppHandle) -> tauri::Result<PathBuf> {
+    Ok(app_handle.path().app_local_data_dir()?.join(REGISTRY_FILE))
+}
+
+/// Data directory of the active profile. Use this instead of
+/// `app_local_data_dir()` for anything that belongs to the profile.
+pub fn data_dir(app_handle: &AppHandle) -> tauri::Result<PathBuf> {
+    let root = app_handle.path().app_local_data_dir()?;
+    Ok(profile_dir_in(&root, active_profile_id()))
+}
+
+/// Path of `instance.json` for the store plugin, relative to the app data
+/// directory
+pub fn instance_store_path() -> PathBuf {
+    profile_dir_in(Path::new(""), active_profile_id()).join(INSTANCE_STORE_FILE)
+}
+
+/// Picks the profile for this process. Must run before anything resolves
+/// profile paths, i.e. first thing in `setup`.
+pub fn init(app_handle: &AppHandle) {
+    let registry = match registry_path(app_handle) {
+        Ok(path) => ProfileRegistry::load_from(&path),
+        Err(e) => {
+            eprintln!("[Profile] No app data directory: {e}");
+            ProfileRegistry::default()
+        }
+    };
+    let id = match requested_profile(std::env::args()) {
+        Some(id) if registry.contains(&id) => id,
+        Some(id) => {
+            eprintln!("[Profile] Unknown profile '{id}' requested, using the default profile");
+            DEFAULT_PROFILE_ID.to_string()
+        }
+        None => registry.startup_id().to_string(),
+    };
+    println!("[Profile] Running with profile '{id}'");
+    let _ = ACTIVE_PROFILE.set(id);
+}
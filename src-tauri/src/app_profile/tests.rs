@@ -0,0 +1,89 @@
+//! Tests for the profile registry and path resolution
+
+use super::{profile_dir_in, requested_profile, ProfileRegistry, DEFAULT_PROFILE_ID};
+use std::path::Path;
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|a| a.to_string()).collect()
+}
+
+#[test]
+fn test_default_profile_uses_root_directory() {
+    let root = Path::new("/data/haex");
+    assert_eq!(profile_dir_in(root, DEFAULT_PROFILE_ID), root);
+    assert_eq!(
+        profile_dir_in(root, "abc"),
+        Path::new("/data/haex/profiles/abc")
+    );
+}
+
+#[test]
+fn test_requested_profile_parses_both_forms() {
+    assert_eq!(
+        requested_profile(args(&["haex", "--profile", "work"])),
+        Some("work".to_string())
+    );
+    assert_eq!(
+        requested_profile(args(&["haex", "--headless", "--profile=work"])),
+        Some("work".to_string())
+    );
+    assert_eq!(requested_profile(args(&["haex", "--headless"])), None);
+    assert_eq!(requested_profile(args(&["haex", "--profile"])), None);
+}
+
+#[test]
+fn test_registry_create_and_startup() {
+    let mut registry = ProfileRegistry::default();
+    assert_eq!(registry.profiles().len(), 1);
+    assert_eq!(registry.startup_id(), DEFAULT_PROFILE_ID);
+
+    let work = registry.create("  Work ", 100).unwrap();
+    assert_eq!(work.name, "Work");
+    assert!(registry.create("work", 101).is_err(), "names are unique");
+    assert!(registry.create("default", 101).is_err());
+    assert!(registry.create("", 101).is_err());
+
+    registry.set_startup(&work.id).unwrap();
+    assert_eq!(registry.startup_id(), work.id);
+    assert!(registry.set_startup("missing").is_err());
+
+    registry.set_startup(DEFAULT_PROFILE_ID).unwrap();
+    assert_eq!(registry.startup_id(), DEFAULT_PROFILE_ID);
+}
+
+#[test]
+fn test_registry_remove_guards() {
+    let mut registry = ProfileRegistry::default();
+    let work = registry.create("Work", 100).unwrap();
+    let personal = registry.create("Personal", 100).unwrap();
+    registry.set_startup(&personal.id).unwrap();
+
+    assert!(registry.remove(DEFAULT_PROFILE_ID, &work.id).is_err());
+    assert!(registry.remove(&work.id, &work.id).is_err());
+
+    registry.remove(&personal.id, &work.id).unwrap();
+    assert_eq!(registry.startup_id(), DEFAULT_PROFILE_ID);
+    assert!(!registry.contains(&personal.id));
+    assert!(registry.remove(&personal.id, &work.id).is_err());
+}
+
+#[test]
+fn test_registry_roundtrip_and_corrupt_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("profiles.json");
+
+    let mut registry = ProfileRegistry::default();
+    let work = registry.create("Work", 100).unwrap();
+    registry.set_startup(&work.id).unwrap();
+    registry.save_to(&path).unwrap();
+
+    let loaded = ProfileRegistry::load_from(&path);
+    assert_eq!(loaded.startup_id(), work.id);
+    assert_eq!(loaded.profiles(), registry.profiles());
+
+    std::fs::write(&path, "{ not json").unwrap();
+    assert_eq!(
+        ProfileRegistry::load_from(&path).startup_id(),
+        DEFAULT_PROFILE_ID
+    );
+}
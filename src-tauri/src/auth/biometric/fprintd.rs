@@ -0,0 +1,126 @@
+//! Fingerprint verification through fprintd (libfprint) on the system bus.
+//!
+//! fprintd has no prompt of its own, so the frontend tells the user to touch
+//! the reader while [`authenticate`] is pending.
+
+use super::{BiometricError, BiometricKind, BiometricStatus};
+use futures_util::StreamExt;
+use std::time::Duration;
+use zbus::zvariant::OwnedObjectPath;
+use zbus::{Connection, Proxy};
+
+const SERVICE: &str = "net.reactivated.Fprint";
+const MANAGER_PATH: &str = "/net/reactivated/Fprint/Manager";
+const MANAGER_INTERFACE: &str = "net.reactivated.Fprint.Manager";
+const DEVICE_INTERFACE: &str = "net.reactivated.Fprint.Device";
+/// fprintd treats an empty user name as the caller
+const CURRENT_USER: &str = "";
+/// Time the user has to touch the reader
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(30);
+
+async fn default_device(conn: &Connection) -> Result<Proxy<'static>, String> {
+    let manager = Proxy::new(conn, SERVICE, MANAGER_PATH, MANAGER_INTERFACE)
+        .await
+        .map_err(|e| format!("fprintd is not available: {e}"))?;
+    let path: OwnedObjectPath = manager
+        .call("GetDefaultDevice", &())
+        .await
+        .map_err(|e| format!("No fingerprint reader found: {e}"))?;
+    Proxy::new(conn, SERVICE, path, DEVICE_INTERFACE)
+        .await
+        .map_err(|e| format!("Fingerprint reader not accessible: {e}"))
+}
+
+async fn enrolled_device(conn: &Connection) -> Result<Proxy<'static>, String> {
+    let device = default_device(conn).await?;
+    let fingers: Vec<String> = device
+        .call("ListEnrolledFingers", &(CURRENT_USER,))
+        .await
+        .map_err(|_| "No fingerprints enrolled".to_string())?;
+    if fingers.is_empty() {
+        return Err("No fingerprints enrolled".to_string());
+    }
+    Ok(device)
+}
+
+pub async fn status() -> BiometricStatus {
+    let conn = match Connection::system().await {
+        Ok(conn) => conn,
+        Err(e) => return BiometricStatus::unavailable(format!("System bus unavailable: {e}")),
+    };
+    match enrolled_device(&conn).await {
+        Ok(_) => BiometricStatus::available(BiometricKind::Fingerprint),
+        Err(reason) => BiometricStatus::unavailable(reason),
+    }
+}
+
+pub async fn authenticate(_reason: &str) -> Result<(), BiometricError> {
+    let not_available = |reason: String| BiometricError::NotAvailable { reason };
+    let conn = Connection::system()
+        .await
+        .map_err(|e| not_available(e.to_string()))?;
+    let device = enrolled_device(&conn).await.map_err(not_available)?;
+
+    device
+        .call::<_, _, ()>("Claim", &(CURRENT_USER,))
+        .await
+        .map_err(|e| BiometricError::Failed {
+            reason: format!("Fingerprint reader is in use: {e}"),
+        })?;
+    let result = verify(&device).await;
+    let _ = device.call::<_, _, ()>("Release", &()).await;
+    result
+}
+
+async fn verify(device: &Proxy<'static>) -> Result<(), BiometricError> {
+    let failed = |e: zbus::Error| BiometricError::Failed {
+        reason: e.to_string(),
+    };
+    let mut statuses = device
+        .receive_signal("VerifyStatus")
+        .await
+        .map_err(failed)?;
+    device
+        .call::<_, _, ()>("VerifyStart", &("any",))
+        .await
+        .map_err(failed)?;
+
+    let outcome = tokio::time::timeout(VERIFY_TIMEOUT, async {
+        while let Some(message) = statuses.next().await {
+            let (result, done): (String, bool) = message.body().deserialize().map_err(failed)?;
+            if let Some(outcome) = verify_outcome(&result, done) {
+                return outcome;
+            }
+        }
+        Err(BiometricError::Failed {
+            reason: "fprintd stopped reporting".to_string(),
+        })
+    })
+    .await
+    .unwrap_or_else(|_| {
+        Err(BiometricError::Failed {
+            reason: "Timed out waiting for a fingerprint".to_string(),
+        })
+    });
+
+    let _ = device.call::<_, _, ()>("VerifyStop", &()).await;
+    outcome
+}
+
+/// Maps a `VerifyStatus` signal to the final outcome, or `None` while the
+/// user should scan again (`verify-retry-scan`, `verify-swipe-too-short`, …).
+fn verify_outcome(result: &str, done: bool) -> Option<Result<(), BiometricError>> {
+    match result {
+        "verify-match" => Some(Ok(())),
+        "verify-no-match" if done => Some(Err(BiometricError::Failed {
+            reason: "Fingerprint not recognized".to_string(),
+        })),
+        "verify-disconnected" => Some(Err(BiometricError::NotAvailable {
+            reason: "Fingerprint reader disconnected".to_string(),
+        })),
+        other if done => Some(Err(BiometricError::Failed {
+            reason: other.to_string(),
+        })),
+        _ => None,
+    }
+}
@@ -0,0 +1,98 @@
+//! Desktop biometric verification
+//!
+//! Thin wrapper over the platform authenticators:
+//! - Windows: Windows Hello (`UserConsentVerifier`)
+//! - macOS: Touch ID (`LAContext`, biometrics-only policy)
+//! - Linux: fprintd over the system D-Bus, where a reader is enrolled
+//!
+//! On Android/iOS the frontend uses the biometry plugin directly, so this
+//! module always reports biometrics as unavailable there. Callers treat
+//! every [`BiometricError`] other than [`BiometricError::Cancelled`] as a
+//! reason to fall back to the vault password (see `auth::confirm_user`).
+
+#[cfg(target_os = "linux")]
+mod fprintd;
+#[cfg(target_os = "macos")]
+mod touch_id;
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+mod unsupported;
+#[cfg(target_os = "windows")]
+mod windows_hello;
+
+#[cfg(target_os = "linux")]
+use fprintd as platform;
+#[cfg(target_os = "macos")]
+use touch_id as platform;
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+use unsupported as platform;
+#[cfg(target_os = "windows")]
+use windows_hello as platform;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum BiometricKind {
+    WindowsHello,
+    TouchId,
+    Fingerprint,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricStatus {
+    pub available: bool,
+    pub kind: Option<BiometricKind>,
+    /// Why biometrics can't be used (no reader, nothing enrolled, …)
+    pub reason: Option<String>,
+}
+
+impl BiometricStatus {
+    pub fn available(kind: BiometricKind) -> Self {
+        Self {
+            available: true,
+            kind: Some(kind),
+            reason: None,
+        }
+    }
+
+    pub fn unavailable(reason: impl Into<String>) -> Self {
+        Self {
+            available: false,
+            kind: None,
+            reason: Some(reason.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize)]
+#[serde(tag = "type", content = "details")]
+pub enum BiometricError {
+    #[error("Biometric authentication is not available: {reason}")]
+    NotAvailable { reason: String },
+
+    #[error("Biometric authentication was cancelled")]
+    Cancelled,
+
+    /// The user picked "use password" in the system prompt
+    #[error("Password requested instead of biometrics")]
+    FallbackRequested,
+
+    #[error("Biometric authentication failed: {reason}")]
+    Failed { reason: String },
+}
+
+/// Checks whether a biometric authenticator is set up for the current user.
+pub async fn status() -> BiometricStatus {
+    platform::status().await
+}
+
+/// Asks the user to verify with the platform authenticator. `reason` is
+/// shown in the system prompt where the platform supports it.
+pub async fn authenticate(reason: &str) -> Result<(), BiometricError> {
+    platform::authenticate(reason).await
+}
@@ -0,0 +1,85 @@
+//! Touch ID via `LAContext` with the biometrics-only policy, so the system
+//! prompt never offers the macOS account password instead.
+//!
+//! `LAContext` is not `Send`; the evaluation runs on a blocking thread that
+//! waits for the reply block.
+
+use super::{BiometricError, BiometricKind, BiometricStatus};
+use block2::RcBlock;
+use objc2::runtime::Bool;
+use objc2_foundation::{NSError, NSString};
+use objc2_local_authentication::{LAContext, LAPolicy};
+use std::sync::mpsc;
+
+// LAError codes (LAError.h)
+const LA_ERROR_USER_CANCEL: isize = -2;
+const LA_ERROR_USER_FALLBACK: isize = -3;
+const LA_ERROR_SYSTEM_CANCEL: isize = -4;
+const LA_ERROR_BIOMETRY_NOT_AVAILABLE: isize = -6;
+const LA_ERROR_BIOMETRY_NOT_ENROLLED: isize = -7;
+const LA_ERROR_APP_CANCEL: isize = -9;
+
+const POLICY: LAPolicy = LAPolicy::DeviceOwnerAuthenticationWithBiometrics;
+
+fn map_error(error: &NSError) -> BiometricError {
+    let reason = error.localizedDescription().to_string();
+    match error.code() {
+        LA_ERROR_USER_CANCEL | LA_ERROR_SYSTEM_CANCEL | LA_ERROR_APP_CANCEL => {
+            BiometricError::Cancelled
+        }
+        LA_ERROR_USER_FALLBACK => BiometricError::FallbackRequested,
+        LA_ERROR_BIOMETRY_NOT_AVAILABLE | LA_ERROR_BIOMETRY_NOT_ENROLLED => {
+            BiometricError::NotAvailable { reason }
+        }
+        _ => BiometricError::Failed { reason },
+    }
+}
+
+pub async fn status() -> BiometricStatus {
+    // SAFETY: plain Objective-C calls on a freshly created context.
+    let result = unsafe { LAContext::new().canEvaluatePolicy_error(POLICY) };
+    match result {
+        Ok(()) => BiometricStatus::available(BiometricKind::TouchId),
+        Err(e) => BiometricStatus::unavailable(e.localizedDescription().to_string()),
+    }
+}
+
+pub async fn authenticate(reason: &str) -> Result<(), BiometricError> {
+    let reason = reason.to_string();
+    tokio::task::spawn_blocking(move || evaluate(&reason))
+        .await
+        .map_err(|e| BiometricError::Failed {
+            reason: e.to_string(),
+        })?
+}
+
+fn evaluate(reason: &str) -> Result<(), BiometricError> {
+    let (tx, rx) = mpsc::channel();
+    let reply = RcBlock::new(move |success: Bool, error: *mut NSError| {
+        let outcome = if success.as_bool() {
+            Ok(())
+        } else {
+            // SAFETY: LocalAuthentication passes a valid NSError or null.
+            match unsafe { error.as_ref() } {
+                Some(error) => Err(map_error(error)),
+                None => Err(BiometricError::Failed {
+                    reason: "Unknown error".to_string(),
+                }),
+            }
+        };
+        let _ = tx.send(outcome);
+    });
+
+    // SAFETY: the context outlives the evaluation because we block on the
+    // reply below before it is dropped.
+    let context = unsafe { LAContext::new() };
+    unsafe {
+        context.evaluatePolicy_localizedReason_reply(POLICY, &NSString::from_str(reason), &reply)
+    };
+
+    rx.recv().unwrap_or_else(|_| {
+        Err(BiometricError::Failed {
+            reason: "No reply from LocalAuthentication".to_string(),
+        })
+    })
+}
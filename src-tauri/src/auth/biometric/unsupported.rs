@@ -0,0 +1,16 @@
+//! Platforms without a desktop authenticator. On Android/iOS biometrics are
+//! handled by the biometry plugin in the frontend.
+
+use super::{BiometricError, BiometricStatus};
+
+const REASON: &str = "No desktop biometric authenticator on this platform";
+
+pub async fn status() -> BiometricStatus {
+    BiometricStatus::unavailable(REASON)
+}
+
+pub async fn authenticate(_reason: &str) -> Result<(), BiometricError> {
+    Err(BiometricError::NotAvailable {
+        reason: REASON.to_string(),
+    })
+}
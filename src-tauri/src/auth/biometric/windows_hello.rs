@@ -0,0 +1,70 @@
+//! Windows Hello via `Windows.Security.Credentials.UI.UserConsentVerifier`.
+//! The WinRT operations are awaited with their blocking `get()` on a
+//! blocking thread.
+
+use super::{BiometricError, BiometricKind, BiometricStatus};
+use windows::core::HSTRING;
+use windows::Security::Credentials::UI::{
+    UserConsentVerificationResult, UserConsentVerifier, UserConsentVerifierAvailability,
+};
+
+pub async fn status() -> BiometricStatus {
+    let availability = tokio::task::spawn_blocking(|| {
+        UserConsentVerifier::CheckAvailabilityAsync().and_then(|op| op.get())
+    })
+    .await;
+
+    match availability {
+        Ok(Ok(UserConsentVerifierAvailability::Available)) => {
+            BiometricStatus::available(BiometricKind::WindowsHello)
+        }
+        Ok(Ok(availability)) => BiometricStatus::unavailable(describe(availability)),
+        Ok(Err(e)) => BiometricStatus::unavailable(format!("Windows Hello check failed: {e}")),
+        Err(e) => BiometricStatus::unavailable(format!("Windows Hello check failed: {e}")),
+    }
+}
+
+pub async fn authenticate(reason: &str) -> Result<(), BiometricError> {
+    let message = HSTRING::from(reason);
+    let result = tokio::task::spawn_blocking(move || {
+        UserConsentVerifier::RequestVerificationAsync(&message).and_then(|op| op.get())
+    })
+    .await
+    .map_err(|e| BiometricError::Failed {
+        reason: e.to_string(),
+    })?
+    .map_err(|e| BiometricError::Failed {
+        reason: e.to_string(),
+    })?;
+
+    match result {
+        UserConsentVerificationResult::Verified => Ok(()),
+        UserConsentVerificationResult::Canceled => Err(BiometricError::Cancelled),
+        UserConsentVerificationResult::DeviceNotPresent
+        | UserConsentVerificationResult::NotConfiguredForUser
+        | UserConsentVerificationResult::DisabledByPolicy => Err(BiometricError::NotAvailable {
+            reason: "Windows Hello is not set up".to_string(),
+        }),
+        UserConsentVerificationResult::RetriesExhausted => Err(BiometricError::Failed {
+            reason: "Too many failed attempts".to_string(),
+        }),
+        UserConsentVerificationResult::DeviceBusy => Err(BiometricError::Failed {
+            reason: "Windows Hello device is busy".to_string(),
+        }),
+        other => Err(BiometricError::Failed {
+            reason: format!("Unexpected verification result {}", other.0),
+        }),
+    }
+}
+
+fn describe(availability: UserConsentVerifierAvailability) -> &'static str {
+    match availability {
+        UserConsentVerifierAvailability::DeviceNotPresent => "No Windows Hello device found",
+        UserConsentVerifierAvailability::NotConfiguredForUser => {
+            "Windows Hello is not set up for this user"
+        }
+        UserConsentVerifierAvailability::DisabledByPolicy => "Windows Hello is disabled by policy",
+        UserConsentVerifierAvailability::DeviceBusy => "Windows Hello device is busy",
+        _ => "Windows Hello is not available",
+    }
+}
@@ -0,0 +1,186 @@
+//! Re-authentication for sensitive actions
+//!
+//! Secret reveal flows and other per-action approvals confirm that the
+//! person at the keyboard owns the vault: with the platform biometric
+//! authenticator where one is set up (see [`biometric`]), otherwise with the
+//! vault password. The password is checked by keying a second, read-only
+//! connection to the open vault file, so it is never stored.
+
+pub mod biometric;
+#[cfg(test)]
+mod tests;
+
+use crate::database::cipher;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::error_code::{serialize_tagged, CodedError, ErrorCode, ErrorEnvelope};
+use crate::AppState;
+use biometric::{BiometricError, BiometricStatus};
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::State;
+use thiserror::Error;
+use ts_rs::TS;
+
+/// How the user confirmed an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum ApprovalMethod {
+    Biometric,
+    Password,
+}
+
+/// Serialized as `{ code, type, message, userMessage, details }` (see
+/// [`serialize_tagged`]); [`AuthErrorBinding`] is its TypeScript type.
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, TS)]
+#[ts(rename = "AuthErrorKind")]
+#[serde(tag = "type", content = "details", rename_all_fields = "camelCase")]
+#[serde(remote = "Self")]
+pub enum AuthError {
+    /// Biometrics can't be used (or the user asked for the password); the
+    /// caller should prompt for the vault password and retry with it.
+    #[error("Vault password required: {reason}")]
+    PasswordRequired { reason: String },
+
+    #[error("Invalid vault password")]
+    InvalidPassword,
+
+    #[error("Authentication was cancelled")]
+    Cancelled,
+
+    #[error("No vault is open")]
+    VaultNotOpen,
+
+    #[error("Database error: {reason}")]
+    Database { reason: String },
+}
+
+impl CodedError for AuthError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            AuthError::PasswordRequired { .. } => ErrorCode::PasswordRequired,
+            AuthError::InvalidPassword => ErrorCode::InvalidPassword,
+            AuthError::Cancelled => ErrorCode::Cancelled,
+            AuthError::VaultNotOpen => ErrorCode::VaultNotOpen,
+            AuthError::Database { .. } => ErrorCode::Database,
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        self.tagged().get("details").cloned()
+    }
+}
+
+impl AuthError {
+    /// The derived `{ type, details }` representation
+    fn tagged(&self) -> serde_json::Value {
+        AuthError::serialize(self, serde_json::value::Serializer).unwrap_or_default()
+    }
+}
+
+impl Serialize for AuthError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_tagged(self, &self.tagged(), serializer)
+    }
+}
+
+/// TypeScript type of a serialized [`AuthError`]: the shared envelope and
+/// the `type`/`details` pair of the variant
+#[derive(TS)]
+#[ts(export, rename = "AuthError")]
+#[allow(dead_code)]
+struct AuthErrorBinding {
+    #[ts(flatten)]
+    envelope: ErrorEnvelope,
+    #[ts(flatten)]
+    kind: AuthError,
+}
+
+impl From<DatabaseError> for AuthError {
+    fn from(e: DatabaseError) -> Self {
+        match e {
+            DatabaseError::ConnectionError { .. } => AuthError::VaultNotOpen,
+            e => AuthError::Database {
+                reason: e.to_string(),
+            },
+        }
+    }
+}
+
+impl From<BiometricError> for AuthError {
+    fn from(e: BiometricError) -> Self {
+        match e {
+            BiometricError::Cancelled => AuthError::Cancelled,
+            e => AuthError::PasswordRequired {
+                reason: e.to_string(),
+            },
+        }
+    }
+}
+
+/// Checks `password` against the vault file at `path`.
+pub(crate) fn check_vault_password(path: &str, password: &str) -> Result<(), AuthError> {
+    let database_error = |e: rusqlite::Error| AuthError::Database {
+        reason: e.to_string(),
+    };
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(database_error)?;
+    conn.pragma_update(None, "key", password)
+        .map_err(database_error)?;
+    cipher::apply_stored_settings(&conn, Path::new(path)).map_err(|e| AuthError::Database {
+        reason: e.to_string(),
+    })?;
+
+    // SQLCipher only notices a wrong key when the first page is read.
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map(|_| ())
+    .map_err(|_| AuthError::InvalidPassword)
+}
+
+/// Checks `password` against the currently open vault.
+pub(crate) fn verify_vault_password(state: &AppState, password: &str) -> Result<(), AuthError> {
+    let path = with_connection(&state.db, |conn| Ok(conn.path().map(str::to_string)))?
+        .filter(|path| !path.is_empty())
+        .ok_or(AuthError::VaultNotOpen)?;
+    check_vault_password(&path, password)
+}
+
+/// Confirms the user for a sensitive action. With `password` the vault
+/// password is verified; without it the biometric authenticator is asked,
+/// and [`AuthError::PasswordRequired`] tells the caller to fall back to the
+/// password prompt.
+pub async fn confirm_user(
+    state: &AppState,
+    reason: &str,
+    password: Option<&str>,
+) -> Result<ApprovalMethod, AuthError> {
+    if let Some(password) = password {
+        verify_vault_password(state, password)?;
+        return Ok(ApprovalMethod::Password);
+    }
+
+    biometric::authenticate(reason).await?;
+    Ok(ApprovalMethod::Biometric)
+}
+
+#[tauri::command]
+pub async fn auth_biometric_status() -> Result<BiometricStatus, AuthError> {
+    Ok(biometric::status().await)
+}
+
+/// `reason` is shown in the system prompt (Windows Hello, Touch ID).
+#[tauri::command]
+pub async fn auth_confirm_user(
+    state: State<'_, AppState>,
+    reason: String,
+    password: Option<String>,
+) -> Result<ApprovalMethod, AuthError> {
+    confirm_user(&state, &reason, password.as_deref()).await
+}
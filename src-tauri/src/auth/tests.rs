@@ -0,0 +1,68 @@
+use super::biometric::{BiometricError, BiometricKind, BiometricStatus};
+use super::{check_vault_password, AuthError};
+use crate::database::error::DatabaseError;
+use rusqlite::Connection;
+use std::path::PathBuf;
+
+fn encrypted_vault(password: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("auth-test-{}.db", uuid::Uuid::new_v4()));
+    let conn = Connection::open(&path).unwrap();
+    conn.pragma_update(None, "key", password).unwrap();
+    conn.execute_batch("CREATE TABLE notes (id TEXT PRIMARY KEY);")
+        .unwrap();
+    path
+}
+
+#[test]
+fn test_check_vault_password() {
+    let path = encrypted_vault("correct horse");
+    let path_str = path.to_str().unwrap();
+
+    assert_eq!(check_vault_password(path_str, "correct horse"), Ok(()));
+    assert_eq!(
+        check_vault_password(path_str, "wrong"),
+        Err(AuthError::InvalidPassword)
+    );
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_biometric_errors_fall_back_to_password() {
+    assert_eq!(
+        AuthError::from(BiometricError::Cancelled),
+        AuthError::Cancelled
+    );
+    for error in [
+        BiometricError::FallbackRequested,
+        BiometricError::NotAvailable {
+            reason: "no reader".to_string(),
+        },
+        BiometricError::Failed {
+            reason: "no match".to_string(),
+        },
+    ] {
+        assert!(matches!(
+            AuthError::from(error),
+            AuthError::PasswordRequired { .. }
+        ));
+    }
+}
+
+#[test]
+fn test_closed_vault_maps_to_vault_not_open() {
+    let error = AuthError::from(DatabaseError::ConnectionError {
+        reason: "closed".to_string(),
+    });
+    assert_eq!(error, AuthError::VaultNotOpen);
+}
+
+#[test]
+fn test_status_serialization() {
+    let status = BiometricStatus::available(BiometricKind::TouchId);
+    assert_eq!(
+        serde_json::to_value(&status).unwrap(),
+        serde_json::json!({ "available": true, "kind": "touchId", "reason": null })
+    );
+    assert!(!BiometricStatus::unavailable("none").available);
+}
@@ -0,0 +1,189 @@
+//! Rule evaluation: decides which enabled rules fire for a change scan, a
+//! scheduler tick or a file sync event. Executing the actions is up to the
+//! service.
+
+use crate::crdt::hlc::hlc_max;
+use crate::crdt::scanner::scan_table_for_local_changes;
+use crate::database::error::DatabaseError;
+use crate::table_names::{TABLE_LOGS, TABLE_NOTIFICATIONS};
+use crate::webhooks::delivery::matches_table;
+use rusqlite::Connection;
+use serde_json::{json, Value as JsonValue};
+use std::collections::BTreeSet;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use super::{AutomationAction, AutomationRule, AutomationTrigger, FileEventKind};
+
+/// Emitted by the file sync engine after a successful run
+pub const FILE_SYNC_COMPLETE_EVENT: &str = "file-sync:complete";
+/// Emitted by the file sync engine after a failed run
+pub const FILE_SYNC_ERROR_EVENT: &str = "file-sync:error";
+
+/// Tables a table-change rule never reacts to: failed actions are logged and
+/// notification actions write to the notification center, so watching
+/// these would let a rule trigger itself.
+const EXCLUDED_TABLES: [&str; 2] = [TABLE_LOGS, TABLE_NOTIFICATIONS];
+
+/// A file sync run that finished, parsed from the engine's events
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSyncEvent {
+    pub kind: FileEventKind,
+    pub sync_rule_id: String,
+}
+
+/// One action to execute
+#[derive(Debug, Clone, PartialEq)]
+pub struct Firing {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub action: AutomationAction,
+    /// What triggered the rule; passed on to the action
+    pub context: JsonValue,
+    /// New cursor of a table-change rule
+    pub cursor_hlc: Option<String>,
+}
+
+impl Firing {
+    fn new(rule: &AutomationRule, context: JsonValue, cursor_hlc: Option<String>) -> Self {
+        Self {
+            rule_id: rule.id.clone(),
+            rule_name: rule.name.clone(),
+            action: rule.action.clone(),
+            context,
+            cursor_hlc,
+        }
+    }
+}
+
+/// Parses the payload of `file-sync:complete` / `file-sync:error`. Errors
+/// caused by an unavailable source or target are skipped: the sync loop
+/// retries them with backoff, and a rule would fire on every retry.
+pub fn parse_file_sync_event(event: &str, payload: &str) -> Option<FileSyncEvent> {
+    let payload: JsonValue = serde_json::from_str(payload).ok()?;
+    let sync_rule_id = payload.get("ruleId")?.as_str()?.to_string();
+    let kind = match event {
+        FILE_SYNC_COMPLETE_EVENT => FileEventKind::Completed,
+        FILE_SYNC_ERROR_EVENT => {
+            if payload.get("unavailable").is_some_and(|v| !v.is_null()) {
+                return None;
+            }
+            FileEventKind::Failed
+        }
+        _ => return None,
+    };
+    Some(FileSyncEvent { kind, sync_rule_id })
+}
+
+fn parse_timestamp(value: &str) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(value, &Rfc3339).ok()
+}
+
+/// `true` if a schedule rule's interval has passed since its last run (or,
+/// before the first run, since it was created).
+pub fn is_schedule_due(rule: &AutomationRule, now: OffsetDateTime) -> bool {
+    let AutomationTrigger::Schedule { interval_seconds } = rule.trigger else {
+        return false;
+    };
+    let reference = rule.last_run_at.as_deref().unwrap_or(&rule.created_at);
+    match parse_timestamp(reference) {
+        Some(reference) => (now - reference).whole_seconds() >= interval_seconds as i64,
+        None => true,
+    }
+}
+
+pub fn matches_file_event(trigger: &AutomationTrigger, event: &FileSyncEvent) -> bool {
+    let AutomationTrigger::FileEvent {
+        events,
+        sync_rule_id,
+    } = trigger
+    else {
+        return false;
+    };
+    events.contains(&event.kind)
+        && sync_rule_id
+            .as_deref()
+            .is_none_or(|id| id == event.sync_rule_id)
+}
+
+pub fn schedule_firings(rules: &[AutomationRule], now: OffsetDateTime) -> Vec<Firing> {
+    let fired_at = now.format(&Rfc3339).unwrap_or_default();
+    rules
+        .iter()
+        .filter(|rule| rule.enabled && is_schedule_due(rule, now))
+        .map(|rule| {
+            Firing::new(
+                rule,
+                json!({ "trigger": "schedule", "firedAt": fired_at }),
+                None,
+            )
+        })
+        .collect()
+}
+
+pub fn file_event_firings(rules: &[AutomationRule], event: &FileSyncEvent) -> Vec<Firing> {
+    rules
+        .iter()
+        .filter(|rule| rule.enabled && matches_file_event(&rule.trigger, event))
+        .map(|rule| {
+            Firing::new(
+                rule,
+                json!({
+                    "trigger": "fileEvent",
+                    "event": event.kind,
+                    "syncRuleId": event.sync_rule_id,
+                }),
+                None,
+            )
+        })
+        .collect()
+}
+
+/// Scans the tables of every enabled table-change rule past its cursor.
+/// `tables` are the CRDT tables of the vault.
+pub fn table_change_firings(
+    conn: &Connection,
+    rules: &[AutomationRule],
+    tables: &[String],
+) -> Result<Vec<Firing>, DatabaseError> {
+    let mut firings = Vec::new();
+    for rule in rules.iter().filter(|rule| rule.enabled) {
+        let AutomationTrigger::TableChange { tables: filters } = &rule.trigger else {
+            continue;
+        };
+
+        let mut changes = Vec::new();
+        for table in tables
+            .iter()
+            .filter(|t| !EXCLUDED_TABLES.contains(&t.as_str()) && matches_table(filters, t))
+        {
+            changes.extend(scan_table_for_local_changes(
+                conn,
+                table,
+                rule.cursor_hlc.as_deref(),
+                "",
+            )?);
+        }
+
+        let Some(max_hlc) = hlc_max(changes.iter().map(|c| c.hlc_timestamp.as_str())) else {
+            continue;
+        };
+        let changed_tables: BTreeSet<&str> =
+            changes.iter().map(|c| c.table_name.as_str()).collect();
+        let changed_rows: BTreeSet<(&str, &str)> = changes
+            .iter()
+            .map(|c| (c.table_name.as_str(), c.row_pks.as_str()))
+            .collect();
+
+        firings.push(Firing::new(
+            rule,
+            json!({
+                "trigger": "tableChange",
+                "tables": changed_tables,
+                "changedRows": changed_rows.len(),
+            }),
+            Some(max_hlc.to_string()),
+        ));
+    }
+    Ok(firings)
+}
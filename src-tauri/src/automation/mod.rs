@@ -0,0 +1,414 @@
+//! Local automation rules
+//!
+//! "If this, then that" for the vault: each rule pairs one trigger with one
+//! action.
+//!
+//! Triggers:
+//! - `tableChange`: CRDT changes (local writes and applied sync changes) on
+//!   tables matching the filters (same syntax as webhooks: exact name, `*`,
+//!   or a prefix ending in `*`)
+//! - `schedule`: every `intervalSeconds` (at least one minute)
+//! - `fileEvent`: a file sync run completed or failed
+//!
+//! Actions:
+//! - `runExtensionCommand`: sends [`AUTOMATION_EVENT`] with the command, its
+//!   arguments and the trigger context to an installed extension
+//! - `sendNotification`: adds an entry to the notification center
+//! - `callWebhook`: POSTs a signed `automation.fired` payload to a webhook
+//!   configured in the webhooks settings
+//!
+//! Rules live in `haex_automation_rules_no_sync` and therefore only act on
+//! this device.
+
+mod engine;
+mod service;
+pub mod store;
+#[cfg(test)]
+mod tests;
+
+pub use service::start_automation_service;
+
+use crate::critical::CriticalFailureCode;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::error_code::{CodedError, ErrorCode};
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tauri::State;
+use thiserror::Error;
+use ts_rs::TS;
+
+/// Event delivered to the target extension of a `runExtensionCommand` action.
+/// Matches HAEXTENSION_EVENTS.AUTOMATION in vault-sdk.
+pub const AUTOMATION_EVENT: &str = "haextension:automation";
+
+/// Shortest allowed schedule interval
+pub const MIN_SCHEDULE_INTERVAL_SECS: u64 = 60;
+const MAX_NAME_LENGTH: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum FileEventKind {
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(
+    tag = "type",
+    content = "config",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum AutomationTrigger {
+    /// Exact table names, `*`, or prefixes ending in `*`
+    TableChange { tables: Vec<String> },
+    Schedule {
+        #[ts(type = "number")]
+        interval_seconds: u64,
+    },
+    /// Without `sync_rule_id` every file sync rule matches
+    FileEvent {
+        events: Vec<FileEventKind>,
+        #[serde(default)]
+        sync_rule_id: Option<String>,
+    },
+}
+
+/// Severity of a notification; the `type` values of `haex_notifications`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(
+    tag = "type",
+    content = "config",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum AutomationAction {
+    RunExtensionCommand {
+        extension_id: String,
+        command: String,
+        #[serde(default)]
+        #[ts(type = "unknown")]
+        args: Option<JsonValue>,
+    },
+    SendNotification {
+        title: String,
+        #[serde(default)]
+        text: Option<String>,
+        level: NotificationLevel,
+    },
+    CallWebhook {
+        webhook_id: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationRule {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    pub trigger: AutomationTrigger,
+    pub action: AutomationAction,
+    /// HLC of the newest change a table-change rule already reacted to
+    #[serde(skip)]
+    #[ts(skip)]
+    pub cursor_hlc: Option<String>,
+    pub last_run_at: Option<String>,
+    /// Error of the last failed run, cleared by the next success
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Errors of the automation commands
+#[derive(Debug, Error)]
+pub enum AutomationError {
+    #[error("{0}")]
+    Invalid(String),
+    #[error("Automation rule {0} not found")]
+    NotFound(String),
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+impl CodedError for AutomationError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            AutomationError::Invalid(_) => ErrorCode::Validation,
+            AutomationError::NotFound(_) => ErrorCode::ResourceNotFound,
+            AutomationError::Database(e) => e.error_code(),
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        match self {
+            AutomationError::Database(e) => e.error_details(),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for AutomationError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Serialize::serialize(&self.envelope(), serializer)
+    }
+}
+
+fn now_rfc3339() -> String {
+    time::OffsetDateTime::now_utc()
+        .format(&time::format_description::well_known::Rfc3339)
+        .unwrap_or_default()
+}
+
+pub fn validate_name(name: &str) -> Result<(), AutomationError> {
+    if name.trim().is_empty() {
+        return Err(AutomationError::Invalid(
+            "Rule name must not be empty".to_string(),
+        ));
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(AutomationError::Invalid(format!(
+            "Rule name must be at most {MAX_NAME_LENGTH} characters"
+        )));
+    }
+    Ok(())
+}
+
+pub fn validate_trigger(trigger: &AutomationTrigger) -> Result<(), AutomationError> {
+    match trigger {
+        AutomationTrigger::TableChange { tables } => crate::webhooks::validate_tables(tables)
+            .map_err(|e| AutomationError::Invalid(e.to_string())),
+        AutomationTrigger::Schedule { interval_seconds } => {
+            if *interval_seconds < MIN_SCHEDULE_INTERVAL_SECS {
+                return Err(AutomationError::Invalid(format!(
+                    "Schedule interval must be at least {MIN_SCHEDULE_INTERVAL_SECS} seconds"
+                )));
+            }
+            Ok(())
+        }
+        AutomationTrigger::FileEvent {
+            events,
+            sync_rule_id,
+        } => {
+            if events.is_empty() {
+                return Err(AutomationError::Invalid(
+                    "At least one file event is required".to_string(),
+                ));
+            }
+            if sync_rule_id
+                .as_deref()
+                .is_some_and(|id| id.trim().is_empty())
+            {
+                return Err(AutomationError::Invalid(
+                    "Sync rule ID must not be empty".to_string(),
+                ));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Checks the action's own fields; see [`check_action_target`] for the
+/// lookups of the referenced extension or webhook.
+pub fn validate_action(action: &AutomationAction) -> Result<(), AutomationError> {
+    match action {
+        AutomationAction::RunExtensionCommand {
+            extension_id,
+            command,
+            ..
+        } => {
+            if extension_id.trim().is_empty() {
+                return Err(AutomationError::Invalid(
+                    "Extension ID must not be empty".to_string(),
+                ));
+            }
+            if command.trim().is_empty() {
+                return Err(AutomationError::Invalid(
+                    "Command must not be empty".to_string(),
+                ));
+            }
+            Ok(())
+        }
+        AutomationAction::SendNotification { title, .. } => {
+            if title.trim().is_empty() {
+                return Err(AutomationError::Invalid(
+                    "Notification title must not be empty".to_string(),
+                ));
+            }
+            Ok(())
+        }
+        AutomationAction::CallWebhook { webhook_id } => {
+            if webhook_id.trim().is_empty() {
+                return Err(AutomationError::Invalid(
+                    "Webhook ID must not be empty".to_string(),
+                ));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Ensures the extension or webhook an action refers to exists.
+fn check_action_target(state: &AppState, action: &AutomationAction) -> Result<(), AutomationError> {
+    match action {
+        AutomationAction::RunExtensionCommand { extension_id, .. } => {
+            if state
+                .extension_manager
+                .get_extension(extension_id)
+                .is_none()
+            {
+                return Err(AutomationError::Invalid(format!(
+                    "Extension {extension_id} is not installed"
+                )));
+            }
+            Ok(())
+        }
+        AutomationAction::SendNotification { .. } => Ok(()),
+        AutomationAction::CallWebhook { webhook_id } => {
+            let webhooks = with_connection(&state.db, |conn| crate::webhooks::store::load(conn))?;
+            if !webhooks.iter().any(|w| w.id == *webhook_id) {
+                return Err(AutomationError::Invalid(format!(
+                    "Webhook {webhook_id} not found"
+                )));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Cursor for a new or changed trigger: table-change rules only react to
+/// changes made from now on.
+fn initial_cursor(
+    state: &AppState,
+    trigger: &AutomationTrigger,
+) -> Result<Option<String>, DatabaseError> {
+    if !matches!(trigger, AutomationTrigger::TableChange { .. }) {
+        return Ok(None);
+    }
+    let hlc = state.lock_or_fail(
+        &state.hlc,
+        CriticalFailureCode::HlcMutexPoisoned,
+        "automation::initial_cursor",
+        serde_json::json!({}),
+    )?;
+    hlc.new_timestamp()
+        .map(|ts| Some(ts.to_string()))
+        .map_err(|e| DatabaseError::HlcError {
+            reason: e.to_string(),
+        })
+}
+
+#[tauri::command]
+pub fn automation_list_rules(
+    state: State<'_, AppState>,
+) -> Result<Vec<AutomationRule>, AutomationError> {
+    Ok(with_connection(&state.db, |conn| store::list(conn))?)
+}
+
+#[tauri::command]
+pub fn automation_create_rule(
+    state: State<'_, AppState>,
+    name: String,
+    trigger: AutomationTrigger,
+    action: AutomationAction,
+    enabled: Option<bool>,
+) -> Result<AutomationRule, AutomationError> {
+    validate_name(&name)?;
+    validate_trigger(&trigger)?;
+    validate_action(&action)?;
+    check_action_target(&state, &action)?;
+
+    let now = now_rfc3339();
+    let rule = AutomationRule {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.trim().to_string(),
+        enabled: enabled.unwrap_or(true),
+        cursor_hlc: initial_cursor(&state, &trigger)?,
+        trigger,
+        action,
+        last_run_at: None,
+        last_error: None,
+        created_at: now.clone(),
+        updated_at: now,
+    };
+
+    with_connection(&state.db, |conn| store::insert(conn, &rule))?;
+    Ok(rule)
+}
+
+/// Change name, trigger, action or enabled state; omitted fields stay as
+/// they are. A changed trigger starts from a fresh cursor.
+#[tauri::command]
+pub fn automation_update_rule(
+    state: State<'_, AppState>,
+    id: String,
+    name: Option<String>,
+    trigger: Option<AutomationTrigger>,
+    action: Option<AutomationAction>,
+    enabled: Option<bool>,
+) -> Result<AutomationRule, AutomationError> {
+    if let Some(name) = &name {
+        validate_name(name)?;
+    }
+    if let Some(trigger) = &trigger {
+        validate_trigger(trigger)?;
+    }
+    if let Some(action) = &action {
+        validate_action(action)?;
+        check_action_target(&state, action)?;
+    }
+
+    let mut rule = with_connection(&state.db, |conn| store::get(conn, &id))?
+        .ok_or_else(|| AutomationError::NotFound(id.clone()))?;
+
+    if let Some(name) = name {
+        rule.name = name.trim().to_string();
+    }
+    if let Some(trigger) = trigger {
+        if trigger != rule.trigger {
+            rule.cursor_hlc = initial_cursor(&state, &trigger)?;
+            rule.trigger = trigger;
+        }
+    }
+    if let Some(action) = action {
+        rule.action = action;
+    }
+    if let Some(enabled) = enabled {
+        rule.enabled = enabled;
+    }
+    rule.updated_at = now_rfc3339();
+
+    with_connection(&state.db, |conn| store::update(conn, &rule))?;
+    Ok(rule)
+}
+
+#[tauri::command]
+pub fn automation_delete_rule(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), AutomationError> {
+    let deleted = with_connection(&state.db, |conn| store::delete(conn, &id))?;
+    if !deleted {
+        return Err(AutomationError::NotFound(id));
+    }
+    Ok(())
+}
@@ -0,0 +1,274 @@
+//! Background task that evaluates automation rules and executes their
+//! actions.
+//!
+//! Local writes wake the task through the dirty-tables event (debounced, as
+//! for webhooks); file sync events arrive through a channel; a periodic
+//! tick drives schedules and picks up changes applied by sync. A rule fires
+//! at most once per evaluation — failures are recorded in `last_error` and
+//! the audit log, not retried.
+
+use crate::database::core::with_connection;
+use crate::database::init::discover_crdt_tables;
+use crate::event_names::EVENT_CRDT_DIRTY_TABLES_CHANGED;
+use crate::extension::database::executor::SqlExecutor;
+use crate::table_names::{
+    COL_NOTIFICATIONS_DATE, COL_NOTIFICATIONS_ID, COL_NOTIFICATIONS_READ, COL_NOTIFICATIONS_SOURCE,
+    COL_NOTIFICATIONS_TEXT, COL_NOTIFICATIONS_TITLE, COL_NOTIFICATIONS_TYPE, TABLE_NOTIFICATIONS,
+};
+use crate::webhooks::delivery::{build_client, deliver};
+use crate::AppState;
+use serde_json::{json, Value as JsonValue};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager};
+use tauri_plugin_http::reqwest;
+use tokio::sync::{mpsc, Notify};
+
+use super::engine::{
+    file_event_firings, parse_file_sync_event, schedule_firings, table_change_firings,
+    FileSyncEvent, Firing, FILE_SYNC_COMPLETE_EVENT, FILE_SYNC_ERROR_EVENT,
+};
+use super::{now_rfc3339, store, AutomationAction, NotificationLevel, AUTOMATION_EVENT};
+
+/// Delay between a local write and the evaluation it triggers
+const CHANGE_DEBOUNCE: Duration = Duration::from_secs(2);
+/// Interval of the scheduler tick; well below the minimum schedule interval
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+/// `source` of notifications created by rules
+const NOTIFICATION_SOURCE: &str = "automation";
+
+/// What woke the task up
+enum Wakeup {
+    Tick,
+    LocalChange,
+    FileSync(FileSyncEvent),
+}
+
+/// Starts the automation task. Called once at app start; it idles while no
+/// vault is open or no rule is enabled.
+pub fn start_automation_service(app_handle: AppHandle) {
+    let local_change = Arc::new(Notify::new());
+    let listener_notify = local_change.clone();
+    app_handle.listen_any(EVENT_CRDT_DIRTY_TABLES_CHANGED, move |_| {
+        listener_notify.notify_one();
+    });
+
+    let (file_tx, mut file_rx) = mpsc::unbounded_channel();
+    for event_name in [FILE_SYNC_COMPLETE_EVENT, FILE_SYNC_ERROR_EVENT] {
+        let file_tx = file_tx.clone();
+        app_handle.listen_any(event_name, move |event| {
+            if let Some(parsed) = parse_file_sync_event(event_name, event.payload()) {
+                let _ = file_tx.send(parsed);
+            }
+        });
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let client = match build_client() {
+            Ok(client) => client,
+            Err(e) => {
+                eprintln!("[Automation] Service not started: {e}");
+                return;
+            }
+        };
+        let mut tick = tokio::time::interval(TICK_INTERVAL);
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            let wakeup = tokio::select! {
+                _ = tick.tick() => Wakeup::Tick,
+                _ = local_change.notified() => {
+                    tokio::time::sleep(CHANGE_DEBOUNCE).await;
+                    Wakeup::LocalChange
+                }
+                Some(event) = file_rx.recv() => Wakeup::FileSync(event),
+            };
+            run_rules(&app_handle, &client, wakeup).await;
+        }
+    });
+}
+
+fn is_vault_open(state: &AppState) -> bool {
+    state.db.0.lock().map(|db| db.is_some()).unwrap_or(false)
+}
+
+async fn run_rules(app_handle: &AppHandle, client: &reqwest::Client, wakeup: Wakeup) {
+    let state = app_handle.state::<AppState>();
+    if !is_vault_open(&state) {
+        return;
+    }
+
+    let firings = with_connection(&state.db, |conn| {
+        let rules = store::list(conn)?;
+        if !rules.iter().any(|r| r.enabled) {
+            return Ok(Vec::new());
+        }
+        match &wakeup {
+            Wakeup::FileSync(event) => Ok(file_event_firings(&rules, event)),
+            Wakeup::LocalChange => table_change_firings(conn, &rules, &discover_crdt_tables(conn)?),
+            Wakeup::Tick => {
+                let mut firings = table_change_firings(conn, &rules, &discover_crdt_tables(conn)?)?;
+                firings.extend(schedule_firings(&rules, time::OffsetDateTime::now_utc()));
+                Ok(firings)
+            }
+        }
+    });
+    let firings = match firings {
+        Ok(firings) => firings,
+        Err(e) => {
+            eprintln!("[Automation] Failed to evaluate rules: {e}");
+            return;
+        }
+    };
+
+    for firing in firings {
+        let result = execute(app_handle, &state, client, &firing).await;
+
+        if let Err(e) = &result {
+            let message = format!("Automation rule \"{}\" failed: {e}", firing.rule_name);
+            let metadata = json!({ "ruleId": firing.rule_id, "context": firing.context });
+            let _ = crate::logging::insert_log(
+                &state,
+                "warn",
+                "Automation",
+                None,
+                &message,
+                Some(metadata),
+                "rust",
+            );
+        }
+
+        let recorded = with_connection(&state.db, |conn| {
+            store::record_run(
+                conn,
+                &firing.rule_id,
+                &now_rfc3339(),
+                firing.cursor_hlc.as_deref(),
+                result.as_ref().err().map(String::as_str),
+            )
+        });
+        if let Err(e) = recorded {
+            eprintln!(
+                "[Automation] Failed to record run of {}: {e}",
+                firing.rule_id
+            );
+        }
+    }
+}
+
+async fn execute(
+    app_handle: &AppHandle,
+    state: &AppState,
+    client: &reqwest::Client,
+    firing: &Firing,
+) -> Result<(), String> {
+    match &firing.action {
+        AutomationAction::RunExtensionCommand {
+            extension_id,
+            command,
+            args,
+        } => {
+            if state
+                .extension_manager
+                .get_extension(extension_id)
+                .is_none()
+            {
+                return Err(format!("Extension {extension_id} is not installed"));
+            }
+            let payload = json!({
+                "ruleId": firing.rule_id,
+                "command": command,
+                "args": args,
+                "context": firing.context,
+            });
+            emit_to_extension(app_handle, state, extension_id, &payload)
+                .map_err(|e| format!("Failed to emit command: {e}"))
+        }
+        AutomationAction::SendNotification { title, text, level } => {
+            insert_notification(state, title, text.as_deref(), *level)
+        }
+        AutomationAction::CallWebhook { webhook_id } => {
+            let webhook = with_connection(&state.db, |conn| crate::webhooks::store::load(conn))
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .find(|w| w.id == *webhook_id)
+                .ok_or_else(|| format!("Webhook {webhook_id} no longer exists"))?;
+            if !webhook.enabled {
+                return Err(format!("Webhook {webhook_id} is disabled"));
+            }
+            let body = serde_json::to_vec(&json!({
+                "id": crate::ids::new_uuid().to_string(),
+                "event": "automation.fired",
+                "webhookId": webhook.id,
+                "ruleId": firing.rule_id,
+                "createdAt": now_rfc3339(),
+                "context": firing.context,
+            }))
+            .map_err(|e| e.to_string())?;
+            deliver(client, &webhook.id, &webhook.url, &webhook.secret, &body).await
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn emit_to_extension(
+    app_handle: &AppHandle,
+    state: &AppState,
+    extension_id: &str,
+    payload: &JsonValue,
+) -> Result<(), tauri::Error> {
+    state.extension_webview_manager.emit_to_extension_or_main(
+        app_handle,
+        extension_id,
+        AUTOMATION_EVENT,
+        payload,
+    )
+}
+
+#[cfg(any(target_os = "android", target_os = "ios"))]
+fn emit_to_extension(
+    app_handle: &AppHandle,
+    _state: &AppState,
+    _extension_id: &str,
+    payload: &JsonValue,
+) -> Result<(), tauri::Error> {
+    use tauri::Emitter;
+    app_handle.emit_to("main", AUTOMATION_EVENT, payload)
+}
+
+/// Adds an entry to the notification center (`haex_notifications` is
+/// synced, so it goes through the CRDT executor).
+fn insert_notification(
+    state: &AppState,
+    title: &str,
+    text: Option<&str>,
+    level: NotificationLevel,
+) -> Result<(), String> {
+    with_connection(&state.db, |conn| {
+        let tx = conn.transaction()?;
+        let hlc_service = state.lock_or_fail(
+            &state.hlc,
+            crate::critical::CriticalFailureCode::HlcMutexPoisoned,
+            "automation::service::insert_notification",
+            json!({}),
+        )?;
+        let sql = format!(
+            "INSERT INTO {TABLE_NOTIFICATIONS} ({COL_NOTIFICATIONS_ID}, {COL_NOTIFICATIONS_DATE}, \
+             {COL_NOTIFICATIONS_READ}, {COL_NOTIFICATIONS_SOURCE}, {COL_NOTIFICATIONS_TEXT}, \
+             {COL_NOTIFICATIONS_TITLE}, {COL_NOTIFICATIONS_TYPE}) VALUES (?, ?, ?, ?, ?, ?, ?)"
+        );
+        let params = vec![
+            json!(crate::ids::new_uuid().to_string()),
+            json!(now_rfc3339()),
+            json!(false),
+            json!(NOTIFICATION_SOURCE),
+            json!(text),
+            json!(title),
+            json!(level),
+        ];
+        SqlExecutor::execute_internal(&tx, &hlc_service, &sql, &params)?;
+        tx.commit()?;
+        Ok(())
+    })
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,234 @@
+//! Persistence of automation rules in `haex_automation_rules_no_sync`.
+//!
+//! Trigger and action are stored split into a type column and a JSON config
+//! column, matching the adjacently tagged serde representation of
+//! [`AutomationTrigger`] / [`AutomationAction`].
+
+use crate::database::error::DatabaseError;
+use crate::table_names::{
+    COL_AUTOMATION_RULES_NO_SYNC_ACTION_CONFIG, COL_AUTOMATION_RULES_NO_SYNC_ACTION_TYPE,
+    COL_AUTOMATION_RULES_NO_SYNC_CREATED_AT, COL_AUTOMATION_RULES_NO_SYNC_CURSOR_HLC,
+    COL_AUTOMATION_RULES_NO_SYNC_ENABLED, COL_AUTOMATION_RULES_NO_SYNC_ID,
+    COL_AUTOMATION_RULES_NO_SYNC_LAST_ERROR, COL_AUTOMATION_RULES_NO_SYNC_LAST_RUN_AT,
+    COL_AUTOMATION_RULES_NO_SYNC_NAME, COL_AUTOMATION_RULES_NO_SYNC_TRIGGER_CONFIG,
+    COL_AUTOMATION_RULES_NO_SYNC_TRIGGER_TYPE, COL_AUTOMATION_RULES_NO_SYNC_UPDATED_AT,
+    TABLE_AUTOMATION_RULES_NO_SYNC,
+};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+
+use super::AutomationRule;
+
+fn serialization_error(e: serde_json::Error) -> DatabaseError {
+    DatabaseError::SerializationError {
+        reason: e.to_string(),
+    }
+}
+
+/// Splits `{ "type": ..., "config": ... }` into the two column values.
+fn split_tagged<T: Serialize>(value: &T) -> Result<(String, String), DatabaseError> {
+    let value = serde_json::to_value(value).map_err(serialization_error)?;
+    let kind = value
+        .get("type")
+        .and_then(JsonValue::as_str)
+        .ok_or_else(|| DatabaseError::SerializationError {
+            reason: "Tagged value without type".to_string(),
+        })?
+        .to_string();
+    let config = value.get("config").cloned().unwrap_or(JsonValue::Null);
+    Ok((kind, config.to_string()))
+}
+
+fn join_tagged<T: DeserializeOwned>(kind: &str, config: &str) -> Result<T, DatabaseError> {
+    let config: JsonValue = serde_json::from_str(config).map_err(serialization_error)?;
+    serde_json::from_value(json!({ "type": kind, "config": config })).map_err(serialization_error)
+}
+
+fn select_sql() -> String {
+    format!(
+        "SELECT {COL_AUTOMATION_RULES_NO_SYNC_ID}, {COL_AUTOMATION_RULES_NO_SYNC_NAME}, \
+         {COL_AUTOMATION_RULES_NO_SYNC_ENABLED}, {COL_AUTOMATION_RULES_NO_SYNC_TRIGGER_TYPE}, \
+         {COL_AUTOMATION_RULES_NO_SYNC_TRIGGER_CONFIG}, {COL_AUTOMATION_RULES_NO_SYNC_ACTION_TYPE}, \
+         {COL_AUTOMATION_RULES_NO_SYNC_ACTION_CONFIG}, {COL_AUTOMATION_RULES_NO_SYNC_CURSOR_HLC}, \
+         {COL_AUTOMATION_RULES_NO_SYNC_LAST_RUN_AT}, {COL_AUTOMATION_RULES_NO_SYNC_LAST_ERROR}, \
+         {COL_AUTOMATION_RULES_NO_SYNC_CREATED_AT}, {COL_AUTOMATION_RULES_NO_SYNC_UPDATED_AT} \
+         FROM {TABLE_AUTOMATION_RULES_NO_SYNC}"
+    )
+}
+
+/// Raw column values; decoding the JSON configs happens outside of rusqlite.
+struct RuleRow {
+    id: String,
+    name: String,
+    enabled: bool,
+    trigger_type: String,
+    trigger_config: String,
+    action_type: String,
+    action_config: String,
+    cursor_hlc: Option<String>,
+    last_run_at: Option<String>,
+    last_error: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+impl RuleRow {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            enabled: row.get(2)?,
+            trigger_type: row.get(3)?,
+            trigger_config: row.get(4)?,
+            action_type: row.get(5)?,
+            action_config: row.get(6)?,
+            cursor_hlc: row.get(7)?,
+            last_run_at: row.get(8)?,
+            last_error: row.get(9)?,
+            created_at: row.get(10)?,
+            updated_at: row.get(11)?,
+        })
+    }
+
+    fn decode(self) -> Result<AutomationRule, DatabaseError> {
+        Ok(AutomationRule {
+            trigger: join_tagged(&self.trigger_type, &self.trigger_config)?,
+            action: join_tagged(&self.action_type, &self.action_config)?,
+            id: self.id,
+            name: self.name,
+            enabled: self.enabled,
+            cursor_hlc: self.cursor_hlc,
+            last_run_at: self.last_run_at,
+            last_error: self.last_error,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+/// All rules, oldest first. Rows this version cannot decode (e.g. a trigger
+/// type added by a newer release) are skipped.
+pub fn list(conn: &Connection) -> Result<Vec<AutomationRule>, DatabaseError> {
+    let sql = format!(
+        "{} ORDER BY {COL_AUTOMATION_RULES_NO_SYNC_CREATED_AT}",
+        select_sql()
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt
+        .query_map([], RuleRow::from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut rules = Vec::with_capacity(rows.len());
+    for row in rows {
+        let id = row.id.clone();
+        match row.decode() {
+            Ok(rule) => rules.push(rule),
+            Err(e) => eprintln!("[Automation] Skipping rule {id}: {e}"),
+        }
+    }
+    Ok(rules)
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<Option<AutomationRule>, DatabaseError> {
+    let sql = format!(
+        "{} WHERE {COL_AUTOMATION_RULES_NO_SYNC_ID} = ?",
+        select_sql()
+    );
+    conn.query_row(&sql, params![id], RuleRow::from_row)
+        .optional()?
+        .map(RuleRow::decode)
+        .transpose()
+}
+
+pub fn insert(conn: &Connection, rule: &AutomationRule) -> Result<(), DatabaseError> {
+    let (trigger_type, trigger_config) = split_tagged(&rule.trigger)?;
+    let (action_type, action_config) = split_tagged(&rule.action)?;
+    conn.execute(
+        &format!(
+            "INSERT INTO {TABLE_AUTOMATION_RULES_NO_SYNC} ({COL_AUTOMATION_RULES_NO_SYNC_ID}, \
+             {COL_AUTOMATION_RULES_NO_SYNC_NAME}, {COL_AUTOMATION_RULES_NO_SYNC_ENABLED}, \
+             {COL_AUTOMATION_RULES_NO_SYNC_TRIGGER_TYPE}, {COL_AUTOMATION_RULES_NO_SYNC_TRIGGER_CONFIG}, \
+             {COL_AUTOMATION_RULES_NO_SYNC_ACTION_TYPE}, {COL_AUTOMATION_RULES_NO_SYNC_ACTION_CONFIG}, \
+             {COL_AUTOMATION_RULES_NO_SYNC_CURSOR_HLC}, {COL_AUTOMATION_RULES_NO_SYNC_LAST_RUN_AT}, \
+             {COL_AUTOMATION_RULES_NO_SYNC_LAST_ERROR}, {COL_AUTOMATION_RULES_NO_SYNC_CREATED_AT}, \
+             {COL_AUTOMATION_RULES_NO_SYNC_UPDATED_AT}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        ),
+        params![
+            rule.id,
+            rule.name,
+            rule.enabled,
+            trigger_type,
+            trigger_config,
+            action_type,
+            action_config,
+            rule.cursor_hlc,
+            rule.last_run_at,
+            rule.last_error,
+            rule.created_at,
+            rule.updated_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Writes the user-editable fields (name, enabled, trigger, action) and the
+/// cursor, which is reset when the trigger changes.
+pub fn update(conn: &Connection, rule: &AutomationRule) -> Result<(), DatabaseError> {
+    let (trigger_type, trigger_config) = split_tagged(&rule.trigger)?;
+    let (action_type, action_config) = split_tagged(&rule.action)?;
+    conn.execute(
+        &format!(
+            "UPDATE {TABLE_AUTOMATION_RULES_NO_SYNC} SET {COL_AUTOMATION_RULES_NO_SYNC_NAME} = ?, \
+             {COL_AUTOMATION_RULES_NO_SYNC_ENABLED} = ?, {COL_AUTOMATION_RULES_NO_SYNC_TRIGGER_TYPE} = ?, \
+             {COL_AUTOMATION_RULES_NO_SYNC_TRIGGER_CONFIG} = ?, {COL_AUTOMATION_RULES_NO_SYNC_ACTION_TYPE} = ?, \
+             {COL_AUTOMATION_RULES_NO_SYNC_ACTION_CONFIG} = ?, {COL_AUTOMATION_RULES_NO_SYNC_CURSOR_HLC} = ?, \
+             {COL_AUTOMATION_RULES_NO_SYNC_UPDATED_AT} = ? WHERE {COL_AUTOMATION_RULES_NO_SYNC_ID} = ?"
+        ),
+        params![
+            rule.name,
+            rule.enabled,
+            trigger_type,
+            trigger_config,
+            action_type,
+            action_config,
+            rule.cursor_hlc,
+            rule.updated_at,
+            rule.id,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Returns `false` if no rule with this ID existed.
+pub fn delete(conn: &Connection, id: &str) -> Result<bool, DatabaseError> {
+    let deleted = conn.execute(
+        &format!(
+            "DELETE FROM {TABLE_AUTOMATION_RULES_NO_SYNC} WHERE {COL_AUTOMATION_RULES_NO_SYNC_ID} = ?"
+        ),
+        params![id],
+    )?;
+    Ok(deleted > 0)
+}
+
+/// Records an execution. `cursor_hlc` is only written when given, so
+/// schedule and file event runs keep the cursor untouched.
+pub fn record_run(
+    conn: &Connection,
+    id: &str,
+    ran_at: &str,
+    cursor_hlc: Option<&str>,
+    error: Option<&str>,
+) -> Result<(), DatabaseError> {
+    conn.execute(
+        &format!(
+            "UPDATE {TABLE_AUTOMATION_RULES_NO_SYNC} SET {COL_AUTOMATION_RULES_NO_SYNC_LAST_RUN_AT} = ?, \
+             {COL_AUTOMATION_RULES_NO_SYNC_LAST_ERROR} = ?, \
+             {COL_AUTOMATION_RULES_NO_SYNC_CURSOR_HLC} = COALESCE(?, {COL_AUTOMATION_RULES_NO_SYNC_CURSOR_HLC}) \
+             WHERE {COL_AUTOMATION_RULES_NO_SYNC_ID} = ?"
+        ),
+        params![ran_at, error, cursor_hlc, id],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,298 @@
+use super::engine::{
+    file_event_firings, is_schedule_due, parse_file_sync_event, schedule_firings,
+    table_change_firings, FileSyncEvent, FILE_SYNC_COMPLETE_EVENT, FILE_SYNC_ERROR_EVENT,
+};
+use super::{
+    store, validate_action, validate_name, validate_trigger, AutomationAction, AutomationRule,
+    AutomationTrigger, FileEventKind, NotificationLevel,
+};
+use rusqlite::Connection;
+use serde_json::json;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+fn setup_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(include_str!(
+        "../../database/migrations/0013_add_automation_rules.sql"
+    ))
+    .unwrap();
+    conn
+}
+
+fn rule(id: &str, trigger: AutomationTrigger) -> AutomationRule {
+    AutomationRule {
+        id: id.to_string(),
+        name: format!("Rule {id}"),
+        enabled: true,
+        trigger,
+        action: AutomationAction::SendNotification {
+            title: "Changed".to_string(),
+            text: None,
+            level: NotificationLevel::Info,
+        },
+        cursor_hlc: None,
+        last_run_at: None,
+        last_error: None,
+        created_at: "2026-01-01T00:00:00Z".to_string(),
+        updated_at: "2026-01-01T00:00:00Z".to_string(),
+    }
+}
+
+fn at(timestamp: &str) -> OffsetDateTime {
+    OffsetDateTime::parse(timestamp, &Rfc3339).unwrap()
+}
+
+#[test]
+fn test_trigger_and_action_serde_format() {
+    let trigger = AutomationTrigger::Schedule {
+        interval_seconds: 300,
+    };
+    assert_eq!(
+        serde_json::to_value(&trigger).unwrap(),
+        json!({ "type": "schedule", "config": { "intervalSeconds": 300 } })
+    );
+
+    let action: AutomationAction = serde_json::from_value(json!({
+        "type": "runExtensionCommand",
+        "config": { "extensionId": "ext", "command": "refresh" }
+    }))
+    .unwrap();
+    assert_eq!(
+        action,
+        AutomationAction::RunExtensionCommand {
+            extension_id: "ext".to_string(),
+            command: "refresh".to_string(),
+            args: None,
+        }
+    );
+}
+
+#[test]
+fn test_validation() {
+    assert!(validate_name("Backup reminder").is_ok());
+    assert!(validate_name("  ").is_err());
+    assert!(validate_name(&"x".repeat(201)).is_err());
+
+    assert!(validate_trigger(&AutomationTrigger::Schedule {
+        interval_seconds: 60
+    })
+    .is_ok());
+    assert!(validate_trigger(&AutomationTrigger::Schedule {
+        interval_seconds: 59
+    })
+    .is_err());
+    assert!(validate_trigger(&AutomationTrigger::TableChange { tables: vec![] }).is_err());
+    assert!(validate_trigger(&AutomationTrigger::FileEvent {
+        events: vec![],
+        sync_rule_id: None
+    })
+    .is_err());
+
+    assert!(validate_action(&AutomationAction::CallWebhook {
+        webhook_id: String::new()
+    })
+    .is_err());
+    assert!(validate_action(&AutomationAction::SendNotification {
+        title: " ".to_string(),
+        text: None,
+        level: NotificationLevel::Warning,
+    })
+    .is_err());
+    assert!(validate_action(&AutomationAction::RunExtensionCommand {
+        extension_id: "ext".to_string(),
+        command: String::new(),
+        args: None,
+    })
+    .is_err());
+}
+
+#[test]
+fn test_store_roundtrip() {
+    let conn = setup_db();
+    assert!(store::list(&conn).unwrap().is_empty());
+
+    let mut first = rule(
+        "a",
+        AutomationTrigger::TableChange {
+            tables: vec!["notes".to_string()],
+        },
+    );
+    first.cursor_hlc = Some("1/x".to_string());
+    store::insert(&conn, &first).unwrap();
+    store::insert(
+        &conn,
+        &rule(
+            "b",
+            AutomationTrigger::Schedule {
+                interval_seconds: 600,
+            },
+        ),
+    )
+    .unwrap();
+
+    let loaded = store::get(&conn, "a").unwrap().unwrap();
+    assert_eq!(loaded, first);
+
+    first.enabled = false;
+    first.action = AutomationAction::CallWebhook {
+        webhook_id: "hook".to_string(),
+    };
+    store::update(&conn, &first).unwrap();
+    assert_eq!(store::get(&conn, "a").unwrap().unwrap(), first);
+
+    store::record_run(&conn, "a", "2026-02-01T00:00:00Z", None, Some("boom")).unwrap();
+    let loaded = store::get(&conn, "a").unwrap().unwrap();
+    assert_eq!(loaded.cursor_hlc.as_deref(), Some("1/x"));
+    assert_eq!(loaded.last_error.as_deref(), Some("boom"));
+
+    store::record_run(&conn, "a", "2026-02-02T00:00:00Z", Some("2/x"), None).unwrap();
+    let loaded = store::get(&conn, "a").unwrap().unwrap();
+    assert_eq!(loaded.cursor_hlc.as_deref(), Some("2/x"));
+    assert_eq!(loaded.last_error, None);
+    assert_eq!(loaded.last_run_at.as_deref(), Some("2026-02-02T00:00:00Z"));
+
+    assert!(store::delete(&conn, "a").unwrap());
+    assert!(!store::delete(&conn, "a").unwrap());
+    assert_eq!(store::list(&conn).unwrap().len(), 1);
+}
+
+#[test]
+fn test_store_skips_unknown_trigger_types() {
+    let conn = setup_db();
+    store::insert(
+        &conn,
+        &rule(
+            "a",
+            AutomationTrigger::Schedule {
+                interval_seconds: 600,
+            },
+        ),
+    )
+    .unwrap();
+    conn.execute(
+        "UPDATE haex_automation_rules_no_sync SET trigger_type = 'fromTheFuture' WHERE id = 'a'",
+        [],
+    )
+    .unwrap();
+
+    assert!(store::list(&conn).unwrap().is_empty());
+    assert!(store::get(&conn, "a").is_err());
+}
+
+#[test]
+fn test_schedule_due() {
+    let mut scheduled = rule(
+        "s",
+        AutomationTrigger::Schedule {
+            interval_seconds: 3600,
+        },
+    );
+    assert!(!is_schedule_due(&scheduled, at("2026-01-01T00:59:59Z")));
+    assert!(is_schedule_due(&scheduled, at("2026-01-01T01:00:00Z")));
+
+    scheduled.last_run_at = Some("2026-01-01T01:00:00Z".to_string());
+    assert!(!is_schedule_due(&scheduled, at("2026-01-01T01:30:00Z")));
+    assert!(is_schedule_due(&scheduled, at("2026-01-01T02:00:00Z")));
+
+    let mut disabled = scheduled.clone();
+    disabled.enabled = false;
+    let rules = vec![
+        scheduled,
+        disabled,
+        rule(
+            "t",
+            AutomationTrigger::TableChange {
+                tables: vec!["*".to_string()],
+            },
+        ),
+    ];
+    let firings = schedule_firings(&rules, at("2026-01-01T03:00:00Z"));
+    assert_eq!(firings.len(), 1);
+    assert_eq!(firings[0].rule_id, "s");
+    assert_eq!(firings[0].context["trigger"], "schedule");
+}
+
+#[test]
+fn test_parse_file_sync_event() {
+    assert_eq!(
+        parse_file_sync_event(
+            FILE_SYNC_COMPLETE_EVENT,
+            &json!({ "ruleId": "r1", "result": {} }).to_string()
+        ),
+        Some(FileSyncEvent {
+            kind: FileEventKind::Completed,
+            sync_rule_id: "r1".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_file_sync_event(
+            FILE_SYNC_ERROR_EVENT,
+            &json!({ "ruleId": "r1", "error": "disk full", "unavailable": null }).to_string()
+        )
+        .map(|e| e.kind),
+        Some(FileEventKind::Failed)
+    );
+    // Offline peers are retried by the sync loop and must not fire rules
+    assert_eq!(
+        parse_file_sync_event(
+            FILE_SYNC_ERROR_EVENT,
+            &json!({ "ruleId": "r1", "error": "offline", "unavailable": "target" }).to_string()
+        ),
+        None
+    );
+    assert_eq!(parse_file_sync_event(FILE_SYNC_COMPLETE_EVENT, "{}"), None);
+}
+
+#[test]
+fn test_file_event_firings() {
+    let rules = vec![
+        rule(
+            "any",
+            AutomationTrigger::FileEvent {
+                events: vec![FileEventKind::Failed],
+                sync_rule_id: None,
+            },
+        ),
+        rule(
+            "one",
+            AutomationTrigger::FileEvent {
+                events: vec![FileEventKind::Completed, FileEventKind::Failed],
+                sync_rule_id: Some("r1".to_string()),
+            },
+        ),
+    ];
+
+    let failed_r2 = FileSyncEvent {
+        kind: FileEventKind::Failed,
+        sync_rule_id: "r2".to_string(),
+    };
+    let ids: Vec<_> = file_event_firings(&rules, &failed_r2)
+        .into_iter()
+        .map(|f| f.rule_id)
+        .collect();
+    assert_eq!(ids, vec!["any"]);
+
+    let completed_r1 = FileSyncEvent {
+        kind: FileEventKind::Completed,
+        sync_rule_id: "r1".to_string(),
+    };
+    let firings = file_event_firings(&rules, &completed_r1);
+    assert_eq!(firings.len(), 1);
+    assert_eq!(firings[0].rule_id, "one");
+    assert_eq!(firings[0].context["event"], "completed");
+    assert_eq!(firings[0].context["syncRuleId"], "r1");
+}
+
+#[test]
+fn test_table_change_firings_ignore_non_matching_rules() {
+    let conn = setup_db();
+    let rules = vec![rule(
+        "s",
+        AutomationTrigger::Schedule {
+            interval_seconds: 60,
+        },
+    )];
+    let firings = table_change_firings(&conn, &rules, &["notes".to_string()]).unwrap();
+    assert!(firings.is_empty());
+}
@@ -0,0 +1,119 @@
+// src-tauri/src/codes/commands.rs
+//!
+//! QR Code Commands
+//!
+//! `codes_*` are internal commands for the host UI; the main window also
+//! answers extension scan requests with `codes_resolve_scan`.
+//! `extension_codes_*` are the variants for extensions:
+//! - generating needs no permission
+//! - scanning requires `camera` permission with the `scan` action
+
+use super::error::CodesError;
+use super::qr;
+use super::types::{QrFormat, QrImage, QrScanRequested};
+use crate::events;
+use crate::extension::error::ExtensionError;
+use crate::extension::permissions::manager::PermissionManager;
+use crate::extension::permissions::types::CameraAction;
+use crate::extension::utils::{emit_permission_prompt_if_needed, resolve_extension_id};
+use crate::AppState;
+use tauri::{AppHandle, State, WebviewWindow};
+
+fn to_extension_error(e: CodesError) -> ExtensionError {
+    ExtensionError::ValidationError {
+        reason: e.to_string(),
+    }
+}
+
+/// Render `data` as a QR code.
+#[tauri::command]
+pub fn codes_generate_qr(data: String, format: QrFormat) -> Result<QrImage, CodesError> {
+    qr::generate(&data, format)
+}
+
+/// Decode the QR codes in a base64-encoded image, e.g. a camera frame.
+#[tauri::command]
+pub async fn codes_scan_qr(image: String) -> Result<Vec<String>, CodesError> {
+    tokio::task::spawn_blocking(move || qr::decode_base64(&image))
+        .await
+        .map_err(|e| CodesError::Internal {
+            reason: e.to_string(),
+        })?
+}
+
+/// Answer a `codes:scan-requested` event. `text` is `None` if the user
+/// cancelled.
+#[tauri::command(rename_all = "camelCase")]
+pub fn codes_resolve_scan(
+    state: State<'_, AppState>,
+    request_id: String,
+    text: Option<String>,
+) -> Result<(), CodesError> {
+    state.codes.resolve(&request_id, text)
+}
+
+/// Render a QR code on behalf of an extension.
+#[tauri::command(rename_all = "camelCase")]
+pub fn extension_codes_generate_qr(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    data: String,
+    format: QrFormat,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<QrImage, ExtensionError> {
+    resolve_extension_id(&window, &state, public_key, name)?;
+    qr::generate(&data, format).map_err(to_extension_error)
+}
+
+/// Let the user scan a QR code for an extension (permission-checked).
+///
+/// The main window shows the camera; the extension only gets the decoded
+/// text, or `None` if the user cancelled.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_codes_scan_qr(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<Option<String>, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+
+    let permission_result =
+        PermissionManager::check_camera_permission(&state, &extension_id, CameraAction::Scan).await;
+    if let Err(ref e) = permission_result {
+        emit_permission_prompt_if_needed(&app_handle, e);
+    }
+    permission_result?;
+
+    let extension_name = state
+        .extension_manager
+        .get_extension(&extension_id)
+        .map(|e| e.manifest.name.clone())
+        .unwrap_or_default();
+
+    let (request_id, receiver) = state
+        .codes
+        .begin(&extension_id)
+        .map_err(to_extension_error)?;
+    let request = QrScanRequested {
+        request_id: request_id.clone(),
+        extension_id,
+        extension_name,
+    };
+    if let Err(e) = events::emit_to_main(&app_handle, &request) {
+        state.codes.cancel(&request_id);
+        return Err(ExtensionError::ValidationError {
+            reason: format!("Failed to request scan: {e}"),
+        });
+    }
+
+    state
+        .codes
+        .wait(&request_id, receiver)
+        .await
+        .map_err(to_extension_error)
+}
@@ -0,0 +1,73 @@
+// src-tauri/src/codes/error.rs
+//!
+//! QR Code Error Types
+//!
+
+use crate::error_code::{serialize_tagged, CodedError, ErrorCode};
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Error, Serialize)]
+#[serde(tag = "type", content = "details")]
+#[serde(remote = "Self")]
+pub enum CodesError {
+    #[error("Data too large for a QR code: {size} bytes (max {max})")]
+    DataTooLarge { size: usize, max: usize },
+
+    #[error("QR encoding failed: {reason}")]
+    Encode { reason: String },
+
+    #[error("Invalid image: {reason}")]
+    InvalidImage { reason: String },
+
+    #[error("Image too large: {size} bytes (max {max})")]
+    ImageTooLarge { size: usize, max: usize },
+
+    #[error("A scan is already in progress for this extension")]
+    ScanInProgress,
+
+    #[error("Scan request not found: {id}")]
+    ScanNotFound { id: String },
+
+    #[error("No code was scanned within {timeout_secs} seconds")]
+    ScanTimeout { timeout_secs: u64 },
+
+    #[error("Internal error: {reason}")]
+    Internal { reason: String },
+}
+
+impl CodedError for CodesError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            CodesError::DataTooLarge { .. } => ErrorCode::LimitExceeded,
+            CodesError::Encode { .. } | CodesError::InvalidImage { .. } => {
+                ErrorCode::ProcessingFailed
+            }
+            CodesError::ImageTooLarge { .. } => ErrorCode::LimitExceeded,
+            CodesError::ScanInProgress => ErrorCode::AlreadyInProgress,
+            CodesError::ScanNotFound { .. } => ErrorCode::ResourceNotFound,
+            CodesError::ScanTimeout { .. } => ErrorCode::Timeout,
+            CodesError::Internal { .. } => ErrorCode::Internal,
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        self.tagged().get("details").cloned()
+    }
+}
+
+impl CodesError {
+    /// The derived `{ type, details }` representation
+    fn tagged(&self) -> serde_json::Value {
+        CodesError::serialize(self, serde_json::value::Serializer).unwrap_or_default()
+    }
+}
+
+impl Serialize for CodesError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_tagged(self, &self.tagged(), serializer)
+    }
+}
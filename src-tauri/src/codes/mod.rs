@@ -0,0 +1,26 @@
+// src-tauri/src/codes/mod.rs
+//!
+//! QR Codes
+//!
+//! Renders QR codes (PNG or SVG) and decodes them from camera frames, for
+//! device pairing, TOTP provisioning and payment extensions.
+//!
+//! - Generation is pure and needs no permission.
+//! - Decoding runs on still images; the frames come from the main window,
+//!   which owns the camera. Extension webviews can't reliably use
+//!   `getUserMedia` under the custom protocol, so `extension_codes_scan_qr`
+//!   asks the main window to scan (`codes:scan-requested`) and parks until it
+//!   answers through `codes_resolve_scan`. It needs the `camera` permission
+//!   with the `scan` action.
+
+pub mod commands;
+pub mod error;
+pub mod qr;
+pub mod scanner;
+pub mod types;
+
+#[cfg(test)]
+mod tests;
+
+pub use error::CodesError;
+pub use scanner::ScanBroker;
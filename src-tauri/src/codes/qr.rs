@@ -0,0 +1,121 @@
+// src-tauri/src/codes/qr.rs
+//!
+//! QR code rendering and decoding (no I/O, no permissions)
+
+use super::error::CodesError;
+use super::types::{QrFormat, QrImage};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, Luma};
+use qrcode::render::svg;
+use qrcode::{EcLevel, QrCode};
+use std::io::Cursor;
+
+/// Byte-mode capacity of a version 40 code at error correction level M
+pub const MAX_DATA_BYTES: usize = 2331;
+/// Encoded images accepted by [`decode`]
+pub const MAX_IMAGE_BYTES: usize = 16 * 1024 * 1024;
+/// Frames are scaled down to this edge length before detection; QR codes
+/// stay readable and detection time stays bounded
+pub const MAX_SCAN_DIMENSION: u32 = 1600;
+/// Smallest edge length of a rendered code
+const MIN_SIZE: u32 = 256;
+/// Quiet zone around the code, in modules (required by the spec)
+const QUIET_ZONE: u32 = 4;
+
+/// Renders `data` as a QR code (error correction level M).
+pub fn generate(data: &str, format: QrFormat) -> Result<QrImage, CodesError> {
+    if data.len() > MAX_DATA_BYTES {
+        return Err(CodesError::DataTooLarge {
+            size: data.len(),
+            max: MAX_DATA_BYTES,
+        });
+    }
+    let code = QrCode::with_error_correction_level(data.as_bytes(), EcLevel::M).map_err(|e| {
+        CodesError::Encode {
+            reason: e.to_string(),
+        }
+    })?;
+
+    // Whole pixels per module, so the code stays sharp at any scale
+    let modules = code.width() as u32 + 2 * QUIET_ZONE;
+    let module_px = MIN_SIZE.div_ceil(modules);
+    let size = modules * module_px;
+
+    let bytes = match format {
+        QrFormat::Png => {
+            let image = code
+                .render::<Luma<u8>>()
+                .module_dimensions(module_px, module_px)
+                .build();
+            let mut bytes = Vec::new();
+            DynamicImage::ImageLuma8(image)
+                .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+                .map_err(|e| CodesError::Encode {
+                    reason: e.to_string(),
+                })?;
+            bytes
+        }
+        QrFormat::Svg => code
+            .render::<svg::Color>()
+            .module_dimensions(module_px, module_px)
+            .build()
+            .into_bytes(),
+    };
+
+    Ok(QrImage {
+        mime_type: format.mime_type().to_string(),
+        size,
+        data: BASE64.encode(bytes),
+    })
+}
+
+/// Decodes every QR code found in an encoded image (PNG, JPEG, WebP, ...).
+///
+/// Returns the decoded texts in detection order; an image without a
+/// readable code yields an empty list.
+pub fn decode(bytes: &[u8]) -> Result<Vec<String>, CodesError> {
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return Err(CodesError::ImageTooLarge {
+            size: bytes.len(),
+            max: MAX_IMAGE_BYTES,
+        });
+    }
+    let mut image = image::load_from_memory(bytes).map_err(|e| CodesError::InvalidImage {
+        reason: e.to_string(),
+    })?;
+    if image.width().max(image.height()) > MAX_SCAN_DIMENSION {
+        image = image.resize(MAX_SCAN_DIMENSION, MAX_SCAN_DIMENSION, FilterType::Triangle);
+    }
+    let gray = image.into_luma8();
+
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        gray.width() as usize,
+        gray.height() as usize,
+        |x, y| gray.get_pixel(x as u32, y as u32).0[0],
+    );
+    let texts = prepared
+        .detect_grids()
+        .into_iter()
+        .filter_map(|grid| grid.decode().ok())
+        .map(|(_, text)| text)
+        .collect();
+    Ok(texts)
+}
+
+/// [`decode`] for base64 input, as sent by the frontend.
+pub fn decode_base64(image: &str) -> Result<Vec<String>, CodesError> {
+    // Each base64 character carries 6 bits
+    if image.len() / 4 * 3 > MAX_IMAGE_BYTES {
+        return Err(CodesError::ImageTooLarge {
+            size: image.len() / 4 * 3,
+            max: MAX_IMAGE_BYTES,
+        });
+    }
+    let bytes = BASE64
+        .decode(image.trim())
+        .map_err(|e| CodesError::InvalidImage {
+            reason: e.to_string(),
+        })?;
+    decode(&bytes)
+}
@@ -0,0 +1,123 @@
+// src-tauri/src/codes/scanner.rs
+//!
+//! Scan broker (in-memory)
+//!
+//! Tracks the scans extensions requested from the main window. Each
+//! extension has at most one open request; the extension's command parks
+//! on it until the main window answers, the user cancels or the request
+//! times out.
+
+use super::error::CodesError;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// How long an extension waits for the user to scan a code
+pub const SCAN_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A scan the main window has not answered yet
+#[derive(Debug)]
+struct PendingScan {
+    extension_id: String,
+    /// `None` when the user cancels
+    result: oneshot::Sender<Option<String>>,
+}
+
+/// Pending scan requests by request ID
+#[derive(Debug)]
+pub struct ScanBroker {
+    pending: Mutex<HashMap<String, PendingScan>>,
+    timeout: Duration,
+}
+
+impl Default for ScanBroker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScanBroker {
+    pub fn new() -> Self {
+        Self::with_timeout(SCAN_TIMEOUT)
+    }
+
+    pub(crate) fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            timeout,
+        }
+    }
+
+    /// Opens a scan request for `extension_id` and returns its ID together
+    /// with the receiver to pass to [`wait`](Self::wait).
+    pub fn begin(
+        &self,
+        extension_id: &str,
+    ) -> Result<(String, oneshot::Receiver<Option<String>>), CodesError> {
+        let mut pending = self.lock()?;
+        // Requests whose caller gave up (dropped receiver) don't block
+        pending.retain(|_, scan| !scan.result.is_closed());
+        if pending.values().any(|s| s.extension_id == extension_id) {
+            return Err(CodesError::ScanInProgress);
+        }
+
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let (result, receiver) = oneshot::channel();
+        pending.insert(
+            request_id.clone(),
+            PendingScan {
+                extension_id: extension_id.to_string(),
+                result,
+            },
+        );
+        Ok((request_id, receiver))
+    }
+
+    /// Waits for the answer to `request_id`. `Ok(None)` means the user
+    /// cancelled.
+    pub async fn wait(
+        &self,
+        request_id: &str,
+        receiver: oneshot::Receiver<Option<String>>,
+    ) -> Result<Option<String>, CodesError> {
+        match tokio::time::timeout(self.timeout, receiver).await {
+            Ok(Ok(text)) => Ok(text),
+            // Sender dropped without an answer: treat like a cancel
+            Ok(Err(_)) => Ok(None),
+            Err(_) => {
+                self.cancel(request_id);
+                Err(CodesError::ScanTimeout {
+                    timeout_secs: self.timeout.as_secs(),
+                })
+            }
+        }
+    }
+
+    /// Delivers the scanned text (`None` = cancelled) to the waiting
+    /// extension.
+    pub fn resolve(&self, request_id: &str, text: Option<String>) -> Result<(), CodesError> {
+        let scan = self
+            .lock()?
+            .remove(request_id)
+            .ok_or_else(|| CodesError::ScanNotFound {
+                id: request_id.to_string(),
+            })?;
+        // The extension may have stopped waiting in the meantime
+        let _ = scan.result.send(text);
+        Ok(())
+    }
+
+    /// Drops `request_id` without an answer.
+    pub fn cancel(&self, request_id: &str) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(request_id);
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, PendingScan>>, CodesError> {
+        self.pending.lock().map_err(|e| CodesError::Internal {
+            reason: e.to_string(),
+        })
+    }
+}
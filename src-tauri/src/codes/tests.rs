@@ -0,0 +1,114 @@
+// src-tauri/src/codes/tests.rs
+//!
+//! Tests for QR rendering, decoding and the scan broker
+
+use super::error::CodesError;
+use super::qr::{decode, decode_base64, generate, MAX_DATA_BYTES};
+use super::scanner::ScanBroker;
+use super::types::QrFormat;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::time::Duration;
+
+const TOTP_URI: &str = "otpauth://totp/haex:alice@example.com?secret=JBSWY3DPEHPK3PXP&issuer=haex";
+
+#[test]
+fn test_png_round_trip() {
+    let image = generate(TOTP_URI, QrFormat::Png).unwrap();
+    assert_eq!(image.mime_type, "image/png");
+    assert!(image.size >= 256);
+
+    let bytes = BASE64.decode(&image.data).unwrap();
+    let png = image::load_from_memory(&bytes).unwrap();
+    assert_eq!((png.width(), png.height()), (image.size, image.size));
+    assert_eq!(decode(&bytes).unwrap(), vec![TOTP_URI.to_string()]);
+    assert_eq!(
+        decode_base64(&image.data).unwrap(),
+        vec![TOTP_URI.to_string()]
+    );
+}
+
+#[test]
+fn test_svg_output() {
+    let image = generate("pairing:1234", QrFormat::Svg).unwrap();
+    assert_eq!(image.mime_type, "image/svg+xml");
+    let svg = String::from_utf8(BASE64.decode(&image.data).unwrap()).unwrap();
+    assert!(svg.contains("<svg"));
+    assert!(svg.contains(&format!("width=\"{}\"", image.size)));
+}
+
+#[test]
+fn test_generate_rejects_oversized_data() {
+    let data = "x".repeat(MAX_DATA_BYTES + 1);
+    assert!(matches!(
+        generate(&data, QrFormat::Png),
+        Err(CodesError::DataTooLarge { .. })
+    ));
+}
+
+#[test]
+fn test_decode_without_code_and_invalid_input() {
+    let blank = image::DynamicImage::new_luma8(64, 64);
+    let mut bytes = Vec::new();
+    blank
+        .write_to(
+            &mut std::io::Cursor::new(&mut bytes),
+            image::ImageFormat::Png,
+        )
+        .unwrap();
+    assert!(decode(&bytes).unwrap().is_empty());
+
+    assert!(matches!(
+        decode(b"not an image"),
+        Err(CodesError::InvalidImage { .. })
+    ));
+    assert!(matches!(
+        decode_base64("%%%"),
+        Err(CodesError::InvalidImage { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_broker_delivers_result() {
+    let broker = ScanBroker::new();
+    let (request_id, receiver) = broker.begin("ext-1").unwrap();
+
+    // One open scan per extension; others are independent
+    assert!(matches!(
+        broker.begin("ext-1"),
+        Err(CodesError::ScanInProgress)
+    ));
+    let (other_id, _other) = broker.begin("ext-2").unwrap();
+
+    broker
+        .resolve(&request_id, Some("otpauth://x".to_string()))
+        .unwrap();
+    assert_eq!(
+        broker.wait(&request_id, receiver).await.unwrap().as_deref(),
+        Some("otpauth://x")
+    );
+
+    // Resolved requests are gone
+    assert!(matches!(
+        broker.resolve(&request_id, None),
+        Err(CodesError::ScanNotFound { .. })
+    ));
+    broker.resolve(&other_id, None).unwrap();
+    assert!(broker.begin("ext-1").is_ok());
+}
+
+#[tokio::test]
+async fn test_broker_cancel_and_timeout() {
+    let broker = ScanBroker::with_timeout(Duration::from_millis(20));
+
+    let (request_id, receiver) = broker.begin("ext-1").unwrap();
+    broker.resolve(&request_id, None).unwrap();
+    assert_eq!(broker.wait(&request_id, receiver).await.unwrap(), None);
+
+    let (request_id, receiver) = broker.begin("ext-1").unwrap();
+    assert!(matches!(
+        broker.wait(&request_id, receiver).await,
+        Err(CodesError::ScanTimeout { .. })
+    ));
+    // A timed-out request no longer blocks the extension
+    assert!(broker.begin("ext-1").is_ok());
+}
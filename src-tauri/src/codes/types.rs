@@ -0,0 +1,47 @@
+// src-tauri/src/codes/types.rs
+//!
+//! QR Code Types
+//!
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Output format of `codes_generate_qr`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum QrFormat {
+    Png,
+    Svg,
+}
+
+impl QrFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            QrFormat::Png => "image/png",
+            QrFormat::Svg => "image/svg+xml",
+        }
+    }
+}
+
+/// A rendered QR code. `data` is the base64-encoded PNG or SVG document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct QrImage {
+    pub mime_type: String,
+    /// Edge length in pixels, quiet zone included
+    pub size: u32,
+    pub data: String,
+}
+
+/// Payload of `codes:scan-requested`. The main window opens the camera and
+/// answers with `codes_resolve_scan(requestId, text)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct QrScanRequested {
+    pub request_id: String,
+    pub extension_id: String,
+    pub extension_name: String,
+}
@@ -0,0 +1,104 @@
+// src-tauri/src/command_middleware/metrics.rs
+//!
+//! Per-command call counters and dispatch timings (in-memory, cleared on
+//! restart)
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+use ts_rs::TS;
+
+/// Counters of one command.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandStats {
+    /// Dispatched invocations (including panicked ones)
+    #[ts(type = "number")]
+    pub calls: u64,
+    /// Invocations rejected by a precondition before dispatch
+    #[ts(type = "number")]
+    pub rejected: u64,
+    /// Invocations that panicked during dispatch or in their `async` task
+    #[ts(type = "number")]
+    pub panicked: u64,
+    /// Sum of all dispatch durations in microseconds
+    #[ts(type = "number")]
+    pub total_micros: u64,
+    /// Longest dispatch in microseconds
+    #[ts(type = "number")]
+    pub max_micros: u64,
+}
+
+impl CommandStats {
+    fn add_call(&mut self, elapsed: Duration) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        self.calls += 1;
+        self.total_micros = self.total_micros.saturating_add(micros);
+        self.max_micros = self.max_micros.max(micros);
+    }
+}
+
+/// Counters of all commands, keyed by command name.
+pub struct CommandMetrics {
+    stats: Mutex<HashMap<String, CommandStats>>,
+}
+
+impl Default for CommandMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandMetrics {
+    pub fn new() -> Self {
+        Self {
+            stats: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn record(&self, command: &str, elapsed: Duration) {
+        self.update(command, |stats| stats.add_call(elapsed));
+    }
+
+    pub fn record_rejected(&self, command: &str) {
+        self.update(command, |stats| stats.rejected += 1);
+    }
+
+    pub fn record_panic(&self, command: &str, elapsed: Duration) {
+        self.update(command, |stats| {
+            stats.add_call(elapsed);
+            stats.panicked += 1;
+        });
+    }
+
+    /// Panic in the task of an `async` command whose dispatch was recorded
+    pub fn record_task_panic(&self, command: &str) {
+        self.update(command, |stats| stats.panicked += 1);
+    }
+
+    /// Counters of every command invoked so far, sorted by name.
+    pub fn snapshot(&self) -> BTreeMap<String, CommandStats> {
+        self.stats
+            .lock()
+            .map(|stats| {
+                stats
+                    .iter()
+                    .map(|(name, stats)| (name.clone(), stats.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn update(&self, command: &str, f: impl FnOnce(&mut CommandStats)) {
+        // A poisoned map only loses diagnostics; never fail the command for it.
+        let Ok(mut stats) = self.stats.lock() else {
+            return;
+        };
+        match stats.get_mut(command) {
+            Some(entry) => f(entry),
+            None => f(stats.entry(command.to_string()).or_default()),
+        }
+    }
+}
@@ -0,0 +1,284 @@
+// src-tauri/src/command_middleware/mod.rs
+//!
+//! Middleware around every Tauri command
+//!
+//! `lib.rs` wraps the handler built by `tauri::generate_handler!` with
+//! [`wrap`], so each invocation gets the same treatment before and after it
+//! reaches the command:
+//! - commands of a vault-bound group ([`VAULT_COMMAND_PREFIXES`]) are
+//!   rejected with [`CommandError::VaultNotOpen`] while no vault is open
+//! - the invocation is counted and timed in [`CommandMetrics`]
+//! - a panic during dispatch is caught and returned to the caller as
+//!   [`CommandError::InternalError`] instead of unwinding into the IPC layer
+//! - a panic in the task of an `async` command is answered the same way by
+//!   [`task_panics::TaskPanicLayer`], which `lib.rs` installs in the tracing
+//!   subscriber
+//!
+//! The panic hook from [`install_panic_hook`] logs every panic with a
+//! correlation ID, and the `InternalError` carries the same ID, so a report
+//! from the UI can be matched with the log. The panic message itself stays in
+//! the log.
+//!
+//! Timings cover the synchronous dispatch. For `async` commands that is
+//! argument parsing and spawning the task on the async runtime; the work
+//! itself runs afterwards and isn't included.
+//!
+//! Setting `HAEX_TRACE_COMMANDS=1` logs every invocation with its dispatch
+//! time.
+
+pub mod metrics;
+pub mod task_panics;
+
+#[cfg(test)]
+mod tests;
+
+use crate::error_code::{CodedError, ErrorCode, ErrorEnvelope};
+use crate::AppState;
+use serde::Serialize;
+use std::any::Any;
+use std::cell::RefCell;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tauri::ipc::{Invoke, InvokeResolver};
+use tauri::{AppHandle, Manager, Runtime};
+use thiserror::Error;
+use ts_rs::TS;
+
+pub use metrics::CommandMetrics;
+
+/// Command groups that only work on an open vault. Matched as name prefixes.
+pub const VAULT_COMMAND_PREFIXES: &[&str] = &[
+    "automation_",
+    "crdt_",
+    "database_",
+    "extension_database_",
+    "extension_event_",
+    "log_",
+    "sql_",
+    "webhooks_",
+];
+
+/// Dispatches at or above this duration are always logged.
+pub const SLOW_DISPATCH_THRESHOLD: Duration = Duration::from_millis(200);
+
+static TRACE_COMMANDS: LazyLock<bool> =
+    LazyLock::new(|| std::env::var("HAEX_TRACE_COMMANDS").is_ok_and(|v| v == "1"));
+
+/// What has to hold before a command is dispatched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precondition {
+    None,
+    VaultOpen,
+}
+
+/// Errors returned by the middleware itself (the command didn't run, or
+/// didn't finish). Serialized like `DatabaseError`, as
+/// `{ code, type, message, userMessage, details }`.
+#[derive(Error, Debug, Serialize, TS)]
+#[ts(rename = "CommandErrorKind")]
+#[serde(tag = "type", content = "details", rename_all_fields = "camelCase")]
+#[serde(remote = "Self")]
+pub enum CommandError {
+    #[error("Command '{command}' requires an open vault")]
+    VaultNotOpen { command: String },
+
+    #[error("Internal error in command '{command}' (correlation ID {correlation_id})")]
+    InternalError {
+        command: String,
+        correlation_id: String,
+    },
+}
+
+impl CodedError for CommandError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            CommandError::VaultNotOpen { .. } => ErrorCode::VaultNotOpen,
+            CommandError::InternalError { .. } => ErrorCode::Internal,
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        CommandError::serialize(self, serde_json::value::Serializer)
+            .ok()
+            .and_then(|tagged| tagged.get("details").cloned())
+    }
+}
+
+impl Serialize for CommandError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let variant = match self {
+            CommandError::VaultNotOpen { .. } => "VaultNotOpen",
+            CommandError::InternalError { .. } => "InternalError",
+        };
+        let mut state = serializer.serialize_struct("CommandError", 5)?;
+        state.serialize_field("code", &self.error_code())?;
+        state.serialize_field("type", variant)?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("userMessage", self.user_message())?;
+        state.serialize_field("details", &self.error_details())?;
+        state.end()
+    }
+}
+
+/// TypeScript type of a serialized [`CommandError`]
+#[derive(TS)]
+#[ts(export, rename = "CommandError")]
+#[allow(dead_code)]
+struct CommandErrorBinding {
+    #[ts(flatten)]
+    envelope: ErrorEnvelope,
+    #[ts(flatten)]
+    kind: CommandError,
+}
+
+thread_local! {
+    /// Correlation ID the panic hook assigned to the last panic on this thread
+    static LAST_PANIC_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+pub fn precondition(command: &str) -> Precondition {
+    if VAULT_COMMAND_PREFIXES
+        .iter()
+        .any(|prefix| command.starts_with(prefix))
+    {
+        Precondition::VaultOpen
+    } else {
+        Precondition::None
+    }
+}
+
+/// Text of a panic payload (`panic!` with a literal or a formatted message).
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+pub fn new_correlation_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Logs every panic with a correlation ID before the previous hook runs.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let correlation_id = new_correlation_id();
+        let location = info
+            .location()
+            .map(|location| format!("{}:{}", location.file(), location.line()))
+            .unwrap_or_else(|| "unknown location".to_string());
+        eprintln!(
+            "[Panic] {correlation_id} at {location}: {}",
+            panic_message(info.payload())
+        );
+        LAST_PANIC_ID.with(|slot| *slot.borrow_mut() = Some(correlation_id));
+        previous(info);
+    }));
+}
+
+/// Correlation ID of the last panic on this thread, if the hook logged one
+pub fn take_panic_id() -> Option<String> {
+    LAST_PANIC_ID.with(|slot| slot.borrow_mut().take())
+}
+
+fn vault_is_open(state: &AppState) -> bool {
+    // A poisoned lock is left to the command, which reports it as such
+    state.db.0.lock().map(|db| db.is_some()).unwrap_or(true)
+}
+
+/// Rejecter for a panic in the task of an `async` command. The dispatch was
+/// already counted, so only the panic is recorded.
+fn task_panic_rejecter<R: Runtime>(
+    app: AppHandle<R>,
+    resolver: InvokeResolver<R>,
+    command: String,
+) -> task_panics::Rejecter {
+    Box::new(move |correlation_id| {
+        eprintln!("[Command] {command} panicked in its task, correlation ID {correlation_id}");
+        app.state::<AppState>()
+            .command_metrics
+            .record_task_panic(&command);
+        resolver.reject(CommandError::InternalError {
+            command,
+            correlation_id,
+        });
+    })
+}
+
+/// Wraps a handler generated by `tauri::generate_handler!`.
+pub fn wrap<R, H>(handler: H) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: Runtime,
+    H: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke: Invoke<R>| {
+        let command = invoke.message.command().to_string();
+        let webview = invoke.message.webview();
+        let state = webview.state::<AppState>();
+
+        if precondition(&command) == Precondition::VaultOpen && !vault_is_open(&state) {
+            state.command_metrics.record_rejected(&command);
+            invoke
+                .resolver
+                .reject(CommandError::VaultNotOpen { command });
+            return true;
+        }
+
+        // The handler consumes the invocation; keep a resolver to answer a panic
+        let resolver = invoke.resolver.clone();
+        task_panics::arm(task_panic_rejecter(
+            webview.app_handle().clone(),
+            invoke.resolver.clone(),
+            command.clone(),
+        ));
+        take_panic_id();
+        let started = Instant::now();
+        let outcome = catch_unwind(AssertUnwindSafe(|| handler(invoke)));
+        let elapsed = started.elapsed();
+        task_panics::disarm();
+
+        match outcome {
+            Ok(handled) => {
+                if handled {
+                    state.command_metrics.record(&command, elapsed);
+                }
+                if *TRACE_COMMANDS || elapsed >= SLOW_DISPATCH_THRESHOLD {
+                    eprintln!(
+                        "[Command] {} dispatched in {} ms",
+                        command,
+                        elapsed.as_millis()
+                    );
+                }
+                handled
+            }
+            Err(payload) => {
+                // Without the hook (tests), log the panic here
+                let correlation_id = take_panic_id().unwrap_or_else(|| {
+                    let correlation_id = new_correlation_id();
+                    eprintln!(
+                        "[Panic] {correlation_id}: {}",
+                        panic_message(payload.as_ref())
+                    );
+                    correlation_id
+                });
+                eprintln!("[Command] {command} panicked, correlation ID {correlation_id}");
+                state.command_metrics.record_panic(&command, elapsed);
+                resolver.reject(CommandError::InternalError {
+                    command,
+                    correlation_id,
+                });
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// src-tauri/src/command_middleware/task_panics.rs
+//!
+//! Answers `async` commands whose task panics
+//!
+//! Tauri runs the future of an `async` command in a task of its own and
+//! drops the task's handle, so a panic there never reaches [`super::wrap`].
+//! With Tauri's `tracing` feature every such future is instrumented with an
+//! [`ASYNC_COMMAND_SPAN`] span, created while the command is dispatched.
+//! [`TaskPanicLayer`] links that span to the invocation [`super::wrap`] is
+//! dispatching ([`arm`]) and, when the span is left while its thread
+//! unwinds, hands the correlation ID of the panic to the invocation's
+//! rejecter. A span that closes normally drops its rejecter unused.
+
+use super::{new_correlation_id, take_panic_id};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use tracing::span;
+use tracing::Subscriber;
+use tracing_subscriber::filter::{filter_fn, FilterFn, Filtered};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Span Tauri wraps around the future of every `async` command
+pub const ASYNC_COMMAND_SPAN: &str = "ipc::request::run";
+
+/// Answers an invocation with the correlation ID of its panic
+pub type Rejecter = Box<dyn FnOnce(String) + Send>;
+
+thread_local! {
+    /// Rejecter of the invocation being dispatched on this thread
+    static DISPATCHING: RefCell<Option<Rejecter>> = const { RefCell::new(None) };
+}
+
+/// Rejecters of running command tasks, keyed by their span
+static PENDING: LazyLock<Mutex<HashMap<span::Id, Rejecter>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Makes `reject` the rejecter of the command task the next dispatch on this
+/// thread starts, if it starts one.
+pub fn arm(reject: Rejecter) {
+    DISPATCHING.with(|slot| *slot.borrow_mut() = Some(reject));
+}
+
+/// Drops the rejecter of a dispatch that didn't start a command task.
+pub fn disarm() {
+    DISPATCHING.with(|slot| slot.borrow_mut().take());
+}
+
+/// Tracks the [`ASYNC_COMMAND_SPAN`] spans of dispatched commands.
+pub struct TaskPanicLayer;
+
+impl TaskPanicLayer {
+    /// The layer, filtered to command spans so it doesn't enable any other
+    /// span or event.
+    pub fn filtered<S>() -> Filtered<Self, FilterFn, S>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let is_command_span: fn(&tracing::Metadata<'_>) -> bool =
+            |metadata| metadata.is_span() && metadata.name() == ASYNC_COMMAND_SPAN;
+        TaskPanicLayer.with_filter(filter_fn(is_command_span))
+    }
+}
+
+impl<S> Layer<S> for TaskPanicLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, _ctx: Context<'_, S>) {
+        let Some(reject) = DISPATCHING.with(|slot| slot.borrow_mut().take()) else {
+            return;
+        };
+        if let Ok(mut pending) = PENDING.lock() {
+            pending.insert(id.clone(), reject);
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, _ctx: Context<'_, S>) {
+        // Nothing between the instrumented future and the runtime catches
+        // the panic, so the task ends without answering
+        if !std::thread::panicking() {
+            return;
+        }
+        let Some(reject) = PENDING
+            .lock()
+            .ok()
+            .and_then(|mut pending| pending.remove(id))
+        else {
+            return;
+        };
+        let correlation_id = take_panic_id().unwrap_or_else(new_correlation_id);
+        // A second panic while unwinding would abort; answer from elsewhere
+        std::thread::spawn(move || reject(correlation_id));
+    }
+
+    fn on_close(&self, id: span::Id, _ctx: Context<'_, S>) {
+        if let Ok(mut pending) = PENDING.lock() {
+            pending.remove(&id);
+        }
+    }
+}
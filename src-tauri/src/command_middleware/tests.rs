@@ -0,0 +1,163 @@
+// src-tauri/src/command_middleware/tests.rs
+//!
+//! Tests for command preconditions, panic messages and metrics
+
+use super::task_panics::{arm, disarm, TaskPanicLayer, ASYNC_COMMAND_SPAN};
+use crate::error_code::CodedError;
+use super::{
+    install_panic_hook, panic_message, precondition, take_panic_id, CommandError, CommandMetrics,
+    Precondition,
+};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::time::Duration;
+use tracing_subscriber::layer::SubscriberExt;
+
+#[test]
+fn test_vault_bound_commands_require_open_vault() {
+    for command in [
+        "sql_select",
+        "crdt_get_stats",
+        "database_vacuum",
+        "extension_database_query",
+        "extension_event_publish",
+        "log_read",
+        "webhooks_list",
+        "automation_list_rules",
+    ] {
+        assert_eq!(precondition(command), Precondition::VaultOpen, "{command}");
+    }
+
+    // Opening, creating and listing vaults must work without one
+    for command in [
+        "open_encrypted_database",
+        "create_encrypted_database",
+        "list_vaults",
+        "vault_exists",
+        "get_database_info",
+        "extension_get_info",
+    ] {
+        assert_eq!(precondition(command), Precondition::None, "{command}");
+    }
+}
+
+#[test]
+fn test_panic_message_from_payload() {
+    let payload = std::panic::catch_unwind(|| panic!("static message")).unwrap_err();
+    assert_eq!(panic_message(payload.as_ref()), "static message");
+
+    let id = 7;
+    let payload = std::panic::catch_unwind(|| panic!("row {id} missing")).unwrap_err();
+    assert_eq!(panic_message(payload.as_ref()), "row 7 missing");
+}
+
+#[test]
+fn test_command_error_is_structured() {
+    let error = CommandError::VaultNotOpen {
+        command: "sql_select".to_string(),
+    };
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "code": 8015,
+            "type": "VaultNotOpen",
+            "message": "Command 'sql_select' requires an open vault",
+            "userMessage": error.user_message(),
+            "details": { "command": "sql_select" }
+        })
+    );
+}
+
+#[test]
+fn test_internal_error_carries_correlation_id_only() {
+    let error = CommandError::InternalError {
+        command: "list_vaults".to_string(),
+        correlation_id: "c0ffee".to_string(),
+    };
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(json["code"], serde_json::json!(100));
+    assert_eq!(json["type"], serde_json::json!("InternalError"));
+    assert_eq!(
+        json["details"],
+        serde_json::json!({ "command": "list_vaults", "correlationId": "c0ffee" })
+    );
+    assert_eq!(error.error_details(), Some(json["details"].clone()));
+}
+
+#[test]
+fn test_panic_hook_records_correlation_id() {
+    install_panic_hook();
+    assert!(take_panic_id().is_none());
+
+    let _ = std::panic::catch_unwind(|| panic!("boom"));
+    let correlation_id = take_panic_id().expect("hook stores the ID");
+    assert_eq!(correlation_id.len(), 36);
+    assert!(take_panic_id().is_none(), "the ID is taken once");
+}
+
+#[test]
+fn test_metrics_accumulate_per_command() {
+    let metrics = CommandMetrics::new();
+    metrics.record("sql_select", Duration::from_micros(300));
+    metrics.record("sql_select", Duration::from_micros(100));
+    metrics.record_rejected("sql_select");
+    metrics.record_panic("list_vaults", Duration::from_micros(50));
+
+    let snapshot = metrics.snapshot();
+    let select = &snapshot["sql_select"];
+    assert_eq!(select.calls, 2);
+    assert_eq!(select.rejected, 1);
+    assert_eq!(select.panicked, 0);
+    assert_eq!(select.total_micros, 400);
+    assert_eq!(select.max_micros, 300);
+
+    let list = &snapshot["list_vaults"];
+    assert_eq!(list.calls, 1);
+    assert_eq!(list.panicked, 1);
+    assert_eq!(
+        snapshot.keys().collect::<Vec<_>>(),
+        vec!["list_vaults", "sql_select"]
+    );
+}
+
+#[test]
+fn test_task_panic_rejects_only_its_invocation() {
+    let subscriber = tracing_subscriber::registry().with(TaskPanicLayer::filtered());
+    tracing::subscriber::with_default(subscriber, || {
+        let (sender, answers) = mpsc::channel();
+
+        // A task that finishes drops its rejecter
+        let finished = sender.clone();
+        arm(Box::new(move |_| finished.send("finished").unwrap()));
+        let span = tracing::debug_span!(ASYNC_COMMAND_SPAN);
+        disarm();
+        drop(span.enter());
+        drop(span);
+
+        arm(Box::new(move |_| sender.send("panicked").unwrap()));
+        let span = tracing::debug_span!(ASYNC_COMMAND_SPAN);
+        disarm();
+        let _ = catch_unwind(AssertUnwindSafe(|| {
+            let _entered = span.enter();
+            panic!("boom");
+        }));
+
+        assert_eq!(
+            answers.recv_timeout(Duration::from_secs(5)).unwrap(),
+            "panicked"
+        );
+        assert!(answers.recv_timeout(Duration::from_millis(100)).is_err());
+    });
+}
+
+#[test]
+fn test_task_panic_counts_without_another_call() {
+    let metrics = CommandMetrics::new();
+    metrics.record("sql_select", Duration::from_micros(10));
+    metrics.record_task_panic("sql_select");
+
+    let select = &metrics.snapshot()["sql_select"];
+    assert_eq!(select.calls, 1);
+    assert_eq!(select.panicked, 1);
+}
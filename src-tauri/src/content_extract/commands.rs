@@ -0,0 +1,114 @@
+// src-tauri/src/content_extract/commands.rs
+//!
+//! Content Extraction Commands
+//!
+//! `content_extract_*` are internal commands for the host UI.
+//! `extension_content_extract_*` are the permission-checked variants:
+//! - `path` sources require `fs` read permission for the path
+//! - `blob` sources require `filesync` read permission for backends
+
+use super::error::ContentExtractError;
+use super::extractors;
+use super::types::{ContentSource, ExtractJob};
+use crate::extension::error::ExtensionError;
+use crate::extension::permissions::manager::PermissionManager;
+use crate::extension::permissions::types::{Action, FileSyncAction, FileSyncTarget, FsAction};
+use crate::extension::utils::{emit_permission_prompt_if_needed, resolve_extension_id};
+use crate::AppState;
+use std::path::Path;
+use tauri::{AppHandle, State, WebviewWindow};
+
+/// Queue text extraction for a file or storage blob.
+#[tauri::command]
+pub fn content_extract_text(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    source: ContentSource,
+) -> Result<ExtractJob, ContentExtractError> {
+    state
+        .content_extract
+        .enqueue(app_handle, &state.db, source, None)
+}
+
+/// Get the status (and result, once finished) of an extraction job.
+#[tauri::command]
+pub fn content_extract_get_job(
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<ExtractJob, ContentExtractError> {
+    state.content_extract.get(&job_id, None)
+}
+
+/// Whether OCR (image text extraction) is available on this system.
+#[tauri::command]
+pub async fn content_extract_ocr_available() -> bool {
+    tokio::task::spawn_blocking(extractors::ocr_available)
+        .await
+        .unwrap_or(false)
+}
+
+/// Queue text extraction on behalf of an extension (permission-checked).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_content_extract_text(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    source: ContentSource,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<ExtractJob, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+
+    let permission_result = match &source {
+        ContentSource::Path { path } => {
+            PermissionManager::check_filesystem_permission(
+                &state,
+                &extension_id,
+                Action::Filesystem(FsAction::Read),
+                Path::new(path),
+            )
+            .await
+        }
+        ContentSource::Blob { .. } => {
+            PermissionManager::check_filesync_permission(
+                &state,
+                &extension_id,
+                FileSyncAction::Read,
+                FileSyncTarget::Backends,
+            )
+            .await
+        }
+    };
+    if let Err(ref e) = permission_result {
+        emit_permission_prompt_if_needed(&app_handle, e);
+    }
+    permission_result?;
+
+    state
+        .content_extract
+        .enqueue(app_handle, &state.db, source, Some(extension_id))
+        .map_err(|e| ExtensionError::ValidationError {
+            reason: e.to_string(),
+        })
+}
+
+/// Get an extraction job started by the calling extension.
+#[tauri::command(rename_all = "camelCase")]
+pub fn extension_content_extract_get_job(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    job_id: String,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<ExtractJob, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    state
+        .content_extract
+        .get(&job_id, Some(&extension_id))
+        .map_err(|e| ExtensionError::NotFound {
+            public_key: String::new(),
+            name: e.to_string(),
+        })
+}
@@ -0,0 +1,88 @@
+// src-tauri/src/content_extract/error.rs
+//!
+//! Content Extraction Error Types
+//!
+
+use crate::error_code::{serialize_tagged, CodedError, ErrorCode};
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Error, Serialize)]
+#[serde(tag = "type", content = "details")]
+#[serde(remote = "Self")]
+pub enum ContentExtractError {
+    #[error("Unsupported content type: {mime_type}")]
+    UnsupportedType { mime_type: String },
+
+    #[error("Input too large: {size} bytes (max {max})")]
+    TooLarge { size: u64, max: u64 },
+
+    #[error("OCR is not available on this system (tesseract not found)")]
+    OcrUnavailable,
+
+    #[error("Extraction failed: {reason}")]
+    ExtractionFailed { reason: String },
+
+    #[error("I/O error: {reason}")]
+    Io { reason: String },
+
+    #[error("Storage error: {reason}")]
+    Storage { reason: String },
+
+    #[error("Job not found: {id}")]
+    JobNotFound { id: String },
+
+    #[error("Internal error: {reason}")]
+    Internal { reason: String },
+}
+
+impl CodedError for ContentExtractError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            ContentExtractError::UnsupportedType { .. } => ErrorCode::Unsupported,
+            ContentExtractError::TooLarge { .. } => ErrorCode::LimitExceeded,
+            ContentExtractError::OcrUnavailable => ErrorCode::Unsupported,
+            ContentExtractError::ExtractionFailed { .. } => ErrorCode::ProcessingFailed,
+            ContentExtractError::Io { .. } => ErrorCode::Filesystem,
+            ContentExtractError::Storage { .. } => ErrorCode::Storage,
+            ContentExtractError::JobNotFound { .. } => ErrorCode::ResourceNotFound,
+            ContentExtractError::Internal { .. } => ErrorCode::Internal,
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        self.tagged().get("details").cloned()
+    }
+}
+
+impl ContentExtractError {
+    /// The derived `{ type, details }` representation
+    fn tagged(&self) -> serde_json::Value {
+        ContentExtractError::serialize(self, serde_json::value::Serializer).unwrap_or_default()
+    }
+}
+
+impl Serialize for ContentExtractError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_tagged(self, &self.tagged(), serializer)
+    }
+}
+
+impl From<std::io::Error> for ContentExtractError {
+    fn from(e: std::io::Error) -> Self {
+        ContentExtractError::Io {
+            reason: e.to_string(),
+        }
+    }
+}
+
+impl From<crate::remote_storage::StorageError> for ContentExtractError {
+    fn from(e: crate::remote_storage::StorageError) -> Self {
+        ContentExtractError::Storage {
+            reason: e.to_string(),
+        }
+    }
+}
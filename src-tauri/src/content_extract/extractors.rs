@@ -0,0 +1,137 @@
+// src-tauri/src/content_extract/extractors.rs
+//!
+//! Format-specific text extractors
+//!
+
+use super::error::ContentExtractError;
+use super::types::{ExtractedContent, ExtractionMethod};
+use std::process::Command;
+
+/// Maximum input size accepted for extraction (100 MB).
+pub const MAX_INPUT_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Extracted text is cut after this many characters.
+pub const MAX_TEXT_CHARS: usize = 1_000_000;
+
+const TEXT_LIKE_MIME_TYPES: &[&str] = &[
+    "application/json",
+    "application/xml",
+    "application/javascript",
+    "application/x-yaml",
+    "application/toml",
+];
+
+/// Determines the MIME type from the file name, falling back to magic bytes
+/// for the formats we can extract (file names in blobs are often opaque).
+pub fn detect_mime(file_name: &str, bytes: &[u8]) -> String {
+    if bytes.starts_with(b"%PDF-") {
+        return "application/pdf".to_string();
+    }
+    if bytes.starts_with(&[0x89, b'P', b'N', b'G']) {
+        return "image/png".to_string();
+    }
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return "image/jpeg".to_string();
+    }
+    mime_guess::from_path(file_name)
+        .first_or_octet_stream()
+        .to_string()
+}
+
+fn is_text_like(mime_type: &str) -> bool {
+    mime_type.starts_with("text/") || TEXT_LIKE_MIME_TYPES.contains(&mime_type)
+}
+
+fn finish(text: String, mime_type: &str, method: ExtractionMethod) -> ExtractedContent {
+    let (text, truncated) = match text.char_indices().nth(MAX_TEXT_CHARS) {
+        Some((cut, _)) => (text[..cut].to_string(), true),
+        None => (text, false),
+    };
+    ExtractedContent {
+        text,
+        mime_type: mime_type.to_string(),
+        method,
+        truncated,
+    }
+}
+
+/// Extracts text from raw bytes. Blocking — call from `spawn_blocking`.
+pub fn extract_from_bytes(
+    file_name: &str,
+    bytes: &[u8],
+) -> Result<ExtractedContent, ContentExtractError> {
+    if bytes.len() as u64 > MAX_INPUT_BYTES {
+        return Err(ContentExtractError::TooLarge {
+            size: bytes.len() as u64,
+            max: MAX_INPUT_BYTES,
+        });
+    }
+
+    let mime_type = detect_mime(file_name, bytes);
+
+    if is_text_like(&mime_type) {
+        let text = String::from_utf8_lossy(bytes).into_owned();
+        return Ok(finish(text, &mime_type, ExtractionMethod::PlainText));
+    }
+
+    if mime_type == "application/pdf" {
+        let text = crate::interop::pdf::document::extract_text(bytes)
+            .map_err(|e| ContentExtractError::ExtractionFailed {
+                reason: format!("PDF: {e}"),
+            })?
+            .pages
+            .join("\n");
+        return Ok(finish(text, &mime_type, ExtractionMethod::Pdf));
+    }
+
+    if mime_type.starts_with("image/") {
+        let text = ocr_image(file_name, bytes)?;
+        return Ok(finish(text, &mime_type, ExtractionMethod::Ocr));
+    }
+
+    Err(ContentExtractError::UnsupportedType { mime_type })
+}
+
+/// Returns true if the `tesseract` binary is on PATH.
+pub fn ocr_available() -> bool {
+    Command::new("tesseract")
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Runs tesseract on the image. The bytes are written to a private temp file
+/// because tesseract can't read every image format from stdin.
+fn ocr_image(file_name: &str, bytes: &[u8]) -> Result<String, ContentExtractError> {
+    if !ocr_available() {
+        return Err(ContentExtractError::OcrUnavailable);
+    }
+
+    let suffix = std::path::Path::new(file_name)
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let mut tmp = tempfile::Builder::new()
+        .prefix("haex-ocr-")
+        .suffix(&suffix)
+        .tempfile()?;
+    std::io::Write::write_all(&mut tmp, bytes)?;
+
+    let output = Command::new("tesseract")
+        .arg(tmp.path())
+        .arg("stdout")
+        .output()?;
+
+    if !output.status.success() {
+        return Err(ContentExtractError::ExtractionFailed {
+            reason: format!(
+                "tesseract exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,31 @@
+// src-tauri/src/content_extract/mod.rs
+//!
+//! Content Extraction
+//!
+//! Extracts plain text from attachments (PDFs, images, text-like files) so it
+//! can be fed into search indexing or handed to extensions.
+//!
+//! - Text-like files are decoded directly.
+//! - PDFs go through `interop::pdf` (bundled `pdf-extract` crate).
+//! - Images use OCR via the system `tesseract` binary when it is installed.
+//!   Without it, image extraction fails with `OcrUnavailable` — OCR is an
+//!   optional capability, not a hard dependency.
+//!
+//! Extraction runs in a background queue with bounded concurrency; callers get
+//! a job ID back and either poll it or listen for `CONTENT_EXTRACT_EVENT`.
+
+pub mod commands;
+pub mod error;
+pub mod extractors;
+pub mod queue;
+pub mod types;
+
+#[cfg(test)]
+mod tests;
+
+pub use error::ContentExtractError;
+pub use queue::ContentExtractQueue;
+
+/// Emitted when an extraction job finishes (successfully or not).
+/// Payload: `ExtractJob`.
+pub const CONTENT_EXTRACT_EVENT: &str = "content-extract:job-finished";
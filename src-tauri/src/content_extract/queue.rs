@@ -0,0 +1,174 @@
+// src-tauri/src/content_extract/queue.rs
+//!
+//! Background extraction queue
+//!
+//! Jobs are kept in memory (they are cheap to recompute) and pruned one hour
+//! after they finish. At most `MAX_CONCURRENT_JOBS` extractions run at once so
+//! a batch of large PDFs can't starve the async runtime.
+
+use super::error::ContentExtractError;
+use super::extractors::{self, MAX_INPUT_BYTES};
+use super::types::{ContentSource, ExtractJob, ExtractJobStatus, ExtractedContent};
+use super::CONTENT_EXTRACT_EVENT;
+use crate::database::DbConnection;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Semaphore;
+
+const MAX_CONCURRENT_JOBS: usize = 2;
+const FINISHED_JOB_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone)]
+pub struct ContentExtractQueue {
+    jobs: Arc<Mutex<HashMap<String, (ExtractJob, Option<SystemTime>)>>>,
+    permits: Arc<Semaphore>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+impl ContentExtractQueue {
+    pub fn new() -> Self {
+        Self {
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+            permits: Arc::new(Semaphore::new(MAX_CONCURRENT_JOBS)),
+        }
+    }
+
+    fn lock_jobs(
+        &self,
+    ) -> Result<MutexGuard<'_, HashMap<String, (ExtractJob, Option<SystemTime>)>>, ContentExtractError>
+    {
+        self.jobs.lock().map_err(|e| ContentExtractError::Internal {
+            reason: format!("job registry mutex poisoned: {e}"),
+        })
+    }
+
+    /// Registers a new job in `Queued` state and returns it.
+    pub fn create_job(
+        &self,
+        source: ContentSource,
+        owner: Option<String>,
+    ) -> Result<ExtractJob, ContentExtractError> {
+        let job = ExtractJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            source,
+            owner,
+            status: ExtractJobStatus::Queued,
+            created_at: now_ms(),
+        };
+        let mut jobs = self.lock_jobs()?;
+        jobs.retain(|_, (_, finished)| {
+            finished
+                .and_then(|t| t.elapsed().ok())
+                .is_none_or(|age| age < FINISHED_JOB_TTL)
+        });
+        jobs.insert(job.id.clone(), (job.clone(), None));
+        Ok(job)
+    }
+
+    fn set_status(&self, job_id: &str, status: ExtractJobStatus) -> Option<ExtractJob> {
+        let mut jobs = self.jobs.lock().ok()?;
+        let (job, finished) = jobs.get_mut(job_id)?;
+        if status.is_finished() {
+            *finished = Some(SystemTime::now());
+        }
+        job.status = status;
+        Some(job.clone())
+    }
+
+    /// Returns a job. If `owner` is set, jobs of other owners are reported as
+    /// not found so extensions can't probe each other's work.
+    pub fn get(&self, job_id: &str, owner: Option<&str>) -> Result<ExtractJob, ContentExtractError> {
+        let jobs = self.lock_jobs()?;
+        jobs.get(job_id)
+            .map(|(job, _)| job)
+            .filter(|job| owner.is_none() || job.owner.as_deref() == owner)
+            .cloned()
+            .ok_or_else(|| ContentExtractError::JobNotFound {
+                id: job_id.to_string(),
+            })
+    }
+
+    /// Queues extraction of `source` and returns the job immediately.
+    /// The job is processed in the background; `CONTENT_EXTRACT_EVENT` is
+    /// emitted to the main window when it finishes.
+    pub fn enqueue(
+        &self,
+        app_handle: AppHandle,
+        db: &DbConnection,
+        source: ContentSource,
+        owner: Option<String>,
+    ) -> Result<ExtractJob, ContentExtractError> {
+        let job = self.create_job(source.clone(), owner)?;
+        let queue = self.clone();
+        let db = DbConnection(db.0.clone());
+        let job_id = job.id.clone();
+
+        tauri::async_runtime::spawn(async move {
+            let Ok(_permit) = queue.permits.clone().acquire_owned().await else {
+                return;
+            };
+            queue.set_status(&job_id, ExtractJobStatus::Running);
+
+            let status = match run_extraction(&db, &source).await {
+                Ok(content) => ExtractJobStatus::Completed { content },
+                Err(error) => {
+                    eprintln!("[ContentExtract] Job {job_id} failed: {error}");
+                    ExtractJobStatus::Failed { error }
+                }
+            };
+
+            if let Some(job) = queue.set_status(&job_id, status) {
+                let _ = app_handle.emit_to("main", CONTENT_EXTRACT_EVENT, &job);
+            }
+        });
+
+        Ok(job)
+    }
+}
+
+impl Default for ContentExtractQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Loads the source bytes and runs the matching extractor.
+pub async fn run_extraction(
+    db: &DbConnection,
+    source: &ContentSource,
+) -> Result<ExtractedContent, ContentExtractError> {
+    let bytes = match source {
+        ContentSource::Path { path } => {
+            let size = tokio::fs::metadata(path).await?.len();
+            if size > MAX_INPUT_BYTES {
+                return Err(ContentExtractError::TooLarge {
+                    size,
+                    max: MAX_INPUT_BYTES,
+                });
+            }
+            tokio::fs::read(path).await?
+        }
+        ContentSource::Blob { backend_id, key } => {
+            let backend = crate::remote_storage::commands::get_backend_instance_from_db_with_overrides(
+                db, backend_id, None,
+            )
+            .await?;
+            backend.download(key).await?
+        }
+    };
+
+    let file_name = source.file_name().to_string();
+    tokio::task::spawn_blocking(move || extractors::extract_from_bytes(&file_name, &bytes))
+        .await
+        .map_err(|e| ContentExtractError::Internal {
+            reason: format!("extraction task failed: {e}"),
+        })?
+}
@@ -0,0 +1,69 @@
+// src-tauri/src/content_extract/tests.rs
+//!
+//! Tests for content extraction
+
+use super::error::ContentExtractError;
+use super::extractors::{detect_mime, extract_from_bytes, MAX_TEXT_CHARS};
+use super::queue::ContentExtractQueue;
+use super::types::{ContentSource, ExtractJobStatus, ExtractionMethod};
+
+#[test]
+fn test_detect_mime_prefers_magic_bytes() {
+    assert_eq!(detect_mime("blob-123", b"%PDF-1.7 ..."), "application/pdf");
+    assert_eq!(detect_mime("x", &[0x89, b'P', b'N', b'G', 0x0D]), "image/png");
+    assert_eq!(detect_mime("notes.md", b"# hi"), "text/markdown");
+}
+
+#[test]
+fn test_plain_text_extraction() {
+    let content = extract_from_bytes("notes.txt", "Grüße".as_bytes()).unwrap();
+    assert_eq!(content.text, "Grüße");
+    assert_eq!(content.method, ExtractionMethod::PlainText);
+    assert!(!content.truncated);
+}
+
+#[test]
+fn test_text_is_truncated_on_char_boundary() {
+    let input = "ä".repeat(MAX_TEXT_CHARS + 10);
+    let content = extract_from_bytes("big.txt", input.as_bytes()).unwrap();
+    assert!(content.truncated);
+    assert_eq!(content.text.chars().count(), MAX_TEXT_CHARS);
+}
+
+#[test]
+fn test_unsupported_type() {
+    let err = extract_from_bytes("archive.zip", b"PK\x03\x04").unwrap_err();
+    assert!(matches!(err, ContentExtractError::UnsupportedType { .. }));
+}
+
+#[test]
+fn test_source_file_name() {
+    let path = ContentSource::Path {
+        path: "/home/u/docs/invoice.pdf".to_string(),
+    };
+    assert_eq!(path.file_name(), "invoice.pdf");
+    let blob = ContentSource::Blob {
+        backend_id: "b".to_string(),
+        key: "spaces/1/scan.png".to_string(),
+    };
+    assert_eq!(blob.file_name(), "scan.png");
+}
+
+#[test]
+fn test_jobs_are_scoped_to_owner() {
+    let queue = ContentExtractQueue::new();
+    let job = queue
+        .create_job(
+            ContentSource::Path {
+                path: "/tmp/a.txt".to_string(),
+            },
+            Some("ext-a".to_string()),
+        )
+        .unwrap();
+
+    assert!(matches!(job.status, ExtractJobStatus::Queued));
+    assert!(queue.get(&job.id, Some("ext-a")).is_ok());
+    assert!(queue.get(&job.id, Some("ext-b")).is_err());
+    // Host UI sees every job
+    assert!(queue.get(&job.id, None).is_ok());
+}
@@ -0,0 +1,78 @@
+// src-tauri/src/content_extract/types.rs
+//!
+//! Content Extraction Types
+//!
+
+use super::error::ContentExtractError;
+use serde::{Deserialize, Serialize};
+
+/// Where the content to extract comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ContentSource {
+    /// Local file path.
+    #[serde(rename_all = "camelCase")]
+    Path { path: String },
+    /// Object in a remote storage backend.
+    #[serde(rename_all = "camelCase")]
+    Blob { backend_id: String, key: String },
+}
+
+impl ContentSource {
+    /// File name used for MIME detection.
+    pub fn file_name(&self) -> &str {
+        let raw = match self {
+            ContentSource::Path { path } => path.as_str(),
+            ContentSource::Blob { key, .. } => key.as_str(),
+        };
+        raw.rsplit(['/', '\\']).next().unwrap_or(raw)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExtractionMethod {
+    PlainText,
+    Pdf,
+    Ocr,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedContent {
+    pub text: String,
+    pub mime_type: String,
+    pub method: ExtractionMethod,
+    /// True if the text was cut at `MAX_TEXT_CHARS`.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum ExtractJobStatus {
+    Queued,
+    Running,
+    Completed { content: ExtractedContent },
+    Failed { error: ContentExtractError },
+}
+
+impl ExtractJobStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            ExtractJobStatus::Completed { .. } | ExtractJobStatus::Failed { .. }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractJob {
+    pub id: String,
+    pub source: ContentSource,
+    /// Extension that requested the job (`None` for the host UI).
+    pub owner: Option<String>,
+    pub status: ExtractJobStatus,
+    /// Unix timestamp (ms) when the job was queued.
+    pub created_at: u64,
+}
@@ -0,0 +1,230 @@
+//! Chunked application of large remote change sets.
+//!
+//! `apply_remote_changes_in_transaction` applies a pull in one transaction,
+//! which is fine for incremental syncs but not for the initial sync of a
+//! new device: 100k+ column changes hold the database lock for minutes and
+//! a single failing row throws everything away.
+//!
+//! Here the changes are split into chunks at transaction-HLC boundaries (a
+//! sender-side transaction never straddles two chunks) and every chunk is
+//! applied and committed on its own via `apply_remote_changes_to_db`. After
+//! each chunk the HLC of its last transaction is stored as the session's
+//! position in `haex_crdt_configs` (local-only) under
+//! `remote_apply_position:<session_id>`. Re-running the same session skips
+//! every transaction up to that position, so a cancelled or failed apply
+//! resumes where it stopped. The position is written right after the chunk
+//! commits; a crash in between only re-applies that chunk, which the HLC
+//! comparison turns into a no-op.
+//!
+//! The backend's `last_push_hlc_timestamp` is only advanced together with
+//! the last chunk, so an interrupted apply never marks the pull as done.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::crdt::commands::{
+    apply_remote_changes_to_db, group_by_transaction_hlc, RemoteColumnChange,
+};
+use crate::crdt::hlc::{compare_hlc_strings, HlcService};
+use crate::database::constants::vault_settings_key::REMOTE_APPLY_POSITION_PREFIX;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::database::DbConnection;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, TABLE_CRDT_CONFIGS,
+};
+
+/// Column changes per chunk when the caller doesn't pass a size.
+pub const DEFAULT_CHUNK_SIZE: usize = 2_000;
+
+/// Upper bound for the chunk size; larger chunks defeat the purpose.
+pub const MAX_CHUNK_SIZE: usize = 50_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum RemoteApplyStatus {
+    Running,
+    Completed,
+    Cancelled,
+}
+
+/// Progress of a chunked apply. Emitted after every chunk and returned as
+/// the final result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteApplyProgress {
+    pub session_id: String,
+    pub status: RemoteApplyStatus,
+    /// Column changes passed in for this run.
+    pub total_changes: u64,
+    /// Column changes applied so far in this run.
+    pub applied_changes: u64,
+    /// Column changes skipped because an earlier run of the session already
+    /// applied them.
+    pub skipped_changes: u64,
+    pub completed_chunks: u32,
+    pub total_chunks: u32,
+    /// HLC of the last transaction applied (the resume position).
+    pub last_applied_hlc: Option<String>,
+}
+
+/// One chunk of whole transaction-HLC groups.
+pub struct ApplyChunk {
+    /// HLC of the last transaction in the chunk.
+    pub last_hlc: String,
+    pub changes: Vec<RemoteColumnChange>,
+}
+
+fn position_key(session_id: &str) -> String {
+    format!("{REMOTE_APPLY_POSITION_PREFIX}{session_id}")
+}
+
+/// Clamps a caller-supplied chunk size into `1..=MAX_CHUNK_SIZE`.
+pub fn effective_chunk_size(chunk_size: Option<u32>) -> usize {
+    chunk_size
+        .map(|size| (size as usize).clamp(1, MAX_CHUNK_SIZE))
+        .unwrap_or(DEFAULT_CHUNK_SIZE)
+}
+
+/// Reads the resume position of a session, if an earlier run stopped early.
+pub fn load_position(conn: &Connection, session_id: &str) -> Result<Option<String>, DatabaseError> {
+    Ok(conn
+        .query_row(
+            &format!(
+                "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?1"
+            ),
+            [position_key(session_id)],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+fn save_position(conn: &Connection, session_id: &str, hlc: &str) -> Result<(), DatabaseError> {
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?1, 'system', ?2)"
+        ),
+        params![position_key(session_id), hlc],
+    )?;
+    Ok(())
+}
+
+/// Forgets the resume position of a session, so the next run starts over.
+pub fn clear_position(conn: &Connection, session_id: &str) -> Result<(), DatabaseError> {
+    conn.execute(
+        &format!("DELETE FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?1"),
+        [position_key(session_id)],
+    )?;
+    Ok(())
+}
+
+/// Splits `changes` into chunks of roughly `chunk_size` column changes
+/// without splitting a transaction-HLC group. Groups at or before
+/// `resume_after` are dropped; their change count is returned alongside.
+pub fn plan_chunks(
+    changes: Vec<RemoteColumnChange>,
+    chunk_size: usize,
+    resume_after: Option<&str>,
+) -> (Vec<ApplyChunk>, usize) {
+    let mut chunks: Vec<ApplyChunk> = Vec::new();
+    let mut current: Vec<RemoteColumnChange> = Vec::new();
+    let mut skipped = 0;
+
+    for (hlc, group) in group_by_transaction_hlc(changes) {
+        if let Some(position) = resume_after {
+            if compare_hlc_strings(&hlc, position).is_le() {
+                skipped += group.len();
+                continue;
+            }
+        }
+        current.extend(group);
+        if current.len() >= chunk_size {
+            chunks.push(ApplyChunk {
+                last_hlc: hlc,
+                changes: std::mem::take(&mut current),
+            });
+        }
+    }
+    if let Some(last) = current.last() {
+        chunks.push(ApplyChunk {
+            last_hlc: last.hlc_timestamp.clone(),
+            changes: current,
+        });
+    }
+
+    (chunks, skipped)
+}
+
+/// Applies `changes` chunk by chunk, resuming after the stored position of
+/// `session_id`.
+///
+/// `on_progress` is called once before the first chunk and after every
+/// committed chunk; returning `false` stops before the next chunk, keeping
+/// the position, and the result has status `Cancelled`. On error the chunks
+/// committed so far stay applied and the position points at the last of
+/// them. The database lock is only held while a chunk is applied.
+pub fn apply_in_chunks(
+    db: &DbConnection,
+    hlc_service: Option<&HlcService>,
+    session_id: &str,
+    changes: Vec<RemoteColumnChange>,
+    backend_info: Option<(&str, &str)>,
+    chunk_size: usize,
+    mut on_progress: impl FnMut(&RemoteApplyProgress) -> bool,
+) -> Result<RemoteApplyProgress, DatabaseError> {
+    if session_id.trim().is_empty() {
+        return Err(DatabaseError::ValidationError {
+            reason: "Remote apply session id must not be empty".to_string(),
+        });
+    }
+
+    let total_changes = changes.len() as u64;
+    let resume_after = with_connection(db, |conn| load_position(conn, session_id))?;
+    let (chunks, skipped) = plan_chunks(changes, chunk_size, resume_after.as_deref());
+
+    let mut progress = RemoteApplyProgress {
+        session_id: session_id.to_string(),
+        status: RemoteApplyStatus::Running,
+        total_changes,
+        applied_changes: 0,
+        skipped_changes: skipped as u64,
+        completed_chunks: 0,
+        total_chunks: chunks.len() as u32,
+        last_applied_hlc: resume_after,
+    };
+
+    if chunks.is_empty() {
+        // Nothing left to apply; still record the completed pull.
+        apply_remote_changes_to_db(db, Vec::new(), backend_info, hlc_service)?;
+    }
+
+    let mut keep_going = on_progress(&progress);
+    let last_index = chunks.len().saturating_sub(1);
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        if !keep_going {
+            progress.status = RemoteApplyStatus::Cancelled;
+            return Ok(progress);
+        }
+
+        let applied = chunk.changes.len() as u64;
+        let chunk_backend = if index == last_index {
+            backend_info
+        } else {
+            None
+        };
+        apply_remote_changes_to_db(db, chunk.changes, chunk_backend, hlc_service)?;
+        with_connection(db, |conn| save_position(conn, session_id, &chunk.last_hlc))?;
+
+        progress.applied_changes += applied;
+        progress.completed_chunks += 1;
+        progress.last_applied_hlc = Some(chunk.last_hlc);
+        keep_going = on_progress(&progress);
+    }
+
+    with_connection(db, |conn| clear_position(conn, session_id))?;
+    progress.status = RemoteApplyStatus::Completed;
+    Ok(progress)
+}
@@ -0,0 +1,181 @@
+//! Tests for chunked remote apply in [`super::bulk_apply`]: chunk planning
+//! at transaction boundaries, progress reporting, cancellation and resuming
+//! a session from its stored position.
+
+#![cfg(test)]
+
+use serde_json::Value as JsonValue;
+
+use super::bulk_apply::{
+    apply_in_chunks, effective_chunk_size, load_position, plan_chunks, RemoteApplyStatus,
+    DEFAULT_CHUNK_SIZE, MAX_CHUNK_SIZE,
+};
+use super::commands::RemoteColumnChange;
+use super::hlc::{compare_hlc_strings, HlcService};
+use crate::database::core::with_connection;
+use crate::database::DbConnection;
+use crate::test_support::crdt::{create_crdt_table, open_crdt_connection};
+use crate::test_support::db::db_connection;
+
+const SESSION: &str = "initial-sync";
+
+fn setup_db() -> (DbConnection, HlcService) {
+    let hlc = HlcService::new_for_testing("bulk-test-device");
+    let conn = open_crdt_connection(hlc.clone()).unwrap();
+    create_crdt_table(
+        &conn,
+        "notes",
+        "CREATE TABLE notes (id TEXT PRIMARY KEY NOT NULL, title TEXT, body TEXT)",
+    )
+    .unwrap();
+
+    (db_connection(conn), hlc)
+}
+
+/// One remote transaction inserting note `id` (two column changes).
+fn remote_note(table: &str, id: &str, hlc: &str) -> Vec<RemoteColumnChange> {
+    ["title", "body"]
+        .into_iter()
+        .map(|column| RemoteColumnChange {
+            table_name: table.to_string(),
+            row_pks: format!(r#"{{"id":"{id}"}}"#),
+            column_name: column.to_string(),
+            hlc_timestamp: hlc.to_string(),
+            decrypted_value: JsonValue::from(format!("{column} {id}")),
+        })
+        .collect()
+}
+
+/// HLCs of `count` remote transactions, ascending.
+fn remote_hlcs(count: usize) -> Vec<String> {
+    let remote = HlcService::new_for_testing("remote-device");
+    (0..count)
+        .map(|_| remote.new_timestamp().unwrap().to_string())
+        .collect()
+}
+
+/// One remote transaction per HLC, each inserting one note.
+fn remote_notes(hlcs: &[String]) -> Vec<RemoteColumnChange> {
+    hlcs.iter()
+        .enumerate()
+        .flat_map(|(i, hlc)| remote_note("notes", &format!("n{i}"), hlc))
+        .collect()
+}
+
+fn note_count(db: &DbConnection) -> i64 {
+    with_connection(db, |conn| {
+        Ok(conn.query_row("SELECT COUNT(*) FROM notes", [], |r| r.get(0))?)
+    })
+    .unwrap()
+}
+
+fn position(db: &DbConnection) -> Option<String> {
+    with_connection(db, |conn| load_position(conn, SESSION)).unwrap()
+}
+
+#[test]
+fn test_plan_chunks_keeps_transactions_together() {
+    let hlcs = remote_hlcs(5);
+    let changes = remote_notes(&hlcs);
+
+    // A chunk size of 3 can't split the 2-change transactions: chunks close
+    // once they reach the size, after a whole transaction.
+    let (chunks, skipped) = plan_chunks(changes, 3, None);
+    assert_eq!(skipped, 0);
+    let sizes: Vec<usize> = chunks.iter().map(|c| c.changes.len()).collect();
+    assert_eq!(sizes, vec![4, 4, 2]);
+    assert_eq!(chunks[0].last_hlc, hlcs[1]);
+    assert_eq!(chunks[2].last_hlc, hlcs[4]);
+    assert!(chunks
+        .iter()
+        .all(|c| c
+            .changes
+            .iter()
+            .all(|ch| compare_hlc_strings(&ch.hlc_timestamp, &c.last_hlc).is_le())));
+}
+
+#[test]
+fn test_plan_chunks_skips_up_to_resume_position() {
+    let hlcs = remote_hlcs(4);
+    let (chunks, skipped) = plan_chunks(remote_notes(&hlcs), 100, Some(&hlcs[1]));
+    assert_eq!(skipped, 4);
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].changes.len(), 4);
+    assert!(chunks[0]
+        .changes
+        .iter()
+        .all(|c| compare_hlc_strings(&c.hlc_timestamp, &hlcs[1]).is_gt()));
+}
+
+#[test]
+fn test_effective_chunk_size_is_clamped() {
+    assert_eq!(effective_chunk_size(None), DEFAULT_CHUNK_SIZE);
+    assert_eq!(effective_chunk_size(Some(0)), 1);
+    assert_eq!(effective_chunk_size(Some(u32::MAX)), MAX_CHUNK_SIZE);
+}
+
+#[test]
+fn test_apply_in_chunks_reports_progress_and_clears_position() {
+    let (db, hlc) = setup_db();
+    let changes = remote_notes(&remote_hlcs(6));
+
+    let mut reports = Vec::new();
+    let result = apply_in_chunks(&db, Some(&hlc), SESSION, changes, None, 4, |p| {
+        reports.push((p.completed_chunks, p.applied_changes));
+        true
+    })
+    .unwrap();
+
+    assert_eq!(result.status, RemoteApplyStatus::Completed);
+    assert_eq!(result.total_changes, 12);
+    assert_eq!(result.applied_changes, 12);
+    assert_eq!(result.total_chunks, 3);
+    assert_eq!(reports, vec![(0, 0), (1, 4), (2, 8), (3, 12)]);
+    assert_eq!(note_count(&db), 6);
+    assert_eq!(position(&db), None);
+}
+
+#[test]
+fn test_cancelled_session_resumes_after_last_chunk() {
+    let (db, hlc) = setup_db();
+    let hlcs = remote_hlcs(6);
+
+    // Stop after the first chunk.
+    let changes = remote_notes(&hlcs);
+    let result = apply_in_chunks(&db, Some(&hlc), SESSION, changes, None, 4, |p| {
+        p.completed_chunks == 0
+    })
+    .unwrap();
+    assert_eq!(result.status, RemoteApplyStatus::Cancelled);
+    assert_eq!(result.applied_changes, 4);
+    assert_eq!(note_count(&db), 2);
+    assert_eq!(position(&db), Some(hlcs[1].clone()));
+
+    // The retry gets the same changes again and skips what is applied.
+    let changes = remote_notes(&hlcs);
+    let result = apply_in_chunks(&db, Some(&hlc), SESSION, changes, None, 4, |_| true).unwrap();
+    assert_eq!(result.status, RemoteApplyStatus::Completed);
+    assert_eq!(result.skipped_changes, 4);
+    assert_eq!(result.applied_changes, 8);
+    assert_eq!(note_count(&db), 6);
+    assert_eq!(position(&db), None);
+}
+
+#[test]
+fn test_failed_chunk_keeps_earlier_chunks() {
+    let (db, hlc) = setup_db();
+    let hlcs = remote_hlcs(3);
+    let mut changes = remote_notes(&hlcs[..2]);
+    changes.extend(remote_note("bad table", "x", &hlcs[2]));
+
+    let result = apply_in_chunks(&db, Some(&hlc), SESSION, changes, None, 4, |_| true);
+    assert!(result.is_err());
+    assert_eq!(note_count(&db), 2);
+    assert_eq!(position(&db), Some(hlcs[1].clone()));
+}
+
+#[test]
+fn test_empty_session_id_is_rejected() {
+    let (db, hlc) = setup_db();
+    assert!(apply_in_chunks(&db, Some(&hlc), " ", Vec::new(), None, 4, |_| true).is_err());
+}
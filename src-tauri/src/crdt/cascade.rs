@@ -0,0 +1,386 @@
+// src-tauri/src/crdt/cascade.rs
+//!
+//! Declarative cascade deletes between CRDT tables.
+//!
+//! Extensions declare cascade rules per foreign key in their migration
+//! metadata (`cascades` next to `name` and `sql`). Each rule becomes an
+//! AFTER-DELETE trigger on the parent table that deletes the matching child
+//! rows in the same transaction. The children's delete-log entries and trash
+//! snapshots therefore carry the parent's HLC, which is how
+//! `trash::restore_row` finds and restores them together with the parent.
+//! Native `ON DELETE CASCADE` foreign keys are restored the same way.
+//!
+//! Rules are local (`_no_sync`): every device re-registers them when the
+//! extension registers its migrations, and deletes that arrive via sync run
+//! with triggers disabled, so children are never deleted twice. Cycles
+//! (including self-references) are rejected, because SQLite does not let a
+//! trigger fire itself again and the cascade would silently stop after one
+//! level.
+
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use ts_rs::TS;
+
+use crate::crdt::trigger::{get_table_schema, is_safe_identifier};
+use crate::database::error::DatabaseError;
+use crate::table_names::TABLE_CRDT_CONFIGS;
+
+/// Local-only table with the registered cascade rules, created by migration
+/// `0020_add_cascade_rules`. Never synced (`_no_sync`).
+pub const CASCADE_RULES_TABLE: &str = "haex_crdt_cascade_rules_no_sync";
+
+const CASCADE_TRIGGER_PREFIX: &str = "z_cascade_";
+
+/// Deleting a row of `referencedTable` deletes the rows of `table` whose
+/// `columns` match its `referencedColumns`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct CascadeRule {
+    /// Child table
+    pub table: String,
+    /// Foreign key columns in the child table
+    pub columns: Vec<String>,
+    /// Parent table
+    pub referenced_table: String,
+    /// Referenced columns in the parent table, pairwise with `columns`
+    pub referenced_columns: Vec<String>,
+}
+
+impl CascadeRule {
+    fn trigger_name(&self) -> String {
+        format!(
+            "{CASCADE_TRIGGER_PREFIX}{}_{}",
+            self.table,
+            self.columns.join("_")
+        )
+    }
+
+    fn trigger_sql(&self) -> String {
+        let condition = self
+            .columns
+            .iter()
+            .zip(&self.referenced_columns)
+            .map(|(child, parent)| format!("\"{child}\" = OLD.\"{parent}\""))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS \"{name}\"
+            AFTER DELETE ON \"{parent}\"
+            FOR EACH ROW
+            WHEN (SELECT COALESCE(value, '1') FROM {TABLE_CRDT_CONFIGS} WHERE key = 'triggers_enabled') = '1'
+            BEGIN
+            DELETE FROM \"{child}\" WHERE {condition};
+            END;",
+            name = self.trigger_name(),
+            parent = self.referenced_table,
+            child = self.table,
+        )
+    }
+}
+
+/// Checks identifiers, that both tables start with `table_prefix`, and that
+/// the rules don't form a cycle.
+pub fn validate_rules(table_prefix: &str, rules: &[CascadeRule]) -> Result<(), DatabaseError> {
+    for rule in rules {
+        let invalid = |detail: &str| DatabaseError::ValidationError {
+            reason: format!(
+                "Invalid cascade rule {}({}) -> {}({}): {detail}",
+                rule.table,
+                rule.columns.join(", "),
+                rule.referenced_table,
+                rule.referenced_columns.join(", ")
+            ),
+        };
+
+        if rule.columns.is_empty() || rule.columns.len() != rule.referenced_columns.len() {
+            return Err(invalid(
+                "columns and referencedColumns must be non-empty and of equal length",
+            ));
+        }
+        let identifiers = [&rule.table, &rule.referenced_table]
+            .into_iter()
+            .chain(&rule.columns)
+            .chain(&rule.referenced_columns);
+        if identifiers
+            .into_iter()
+            .any(|name| !is_safe_identifier(name))
+        {
+            return Err(invalid("unsafe table or column name"));
+        }
+        if !rule.table.starts_with(table_prefix) || !rule.referenced_table.starts_with(table_prefix)
+        {
+            return Err(invalid(&format!(
+                "both tables must start with '{table_prefix}'"
+            )));
+        }
+    }
+
+    if let Some(cycle) = find_cycle(rules) {
+        return Err(DatabaseError::ValidationError {
+            reason: format!("Cascade rules form a cycle: {}", cycle.join(" -> ")),
+        });
+    }
+    Ok(())
+}
+
+/// Returns the tables of a parent -> child cycle (first table repeated at
+/// the end), or `None` if the rules are acyclic.
+pub fn find_cycle(rules: &[CascadeRule]) -> Option<Vec<String>> {
+    let mut children: HashMap<&str, Vec<&str>> = HashMap::new();
+    for rule in rules {
+        children
+            .entry(rule.referenced_table.as_str())
+            .or_default()
+            .push(rule.table.as_str());
+    }
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Active,
+        Done,
+    }
+
+    fn visit<'a>(
+        table: &'a str,
+        children: &HashMap<&'a str, Vec<&'a str>>,
+        marks: &mut HashMap<&'a str, Mark>,
+        path: &mut Vec<&'a str>,
+    ) -> Option<Vec<String>> {
+        match marks.get(table) {
+            Some(Mark::Done) => return None,
+            Some(Mark::Active) => {
+                let start = path.iter().position(|t| *t == table).unwrap_or(0);
+                let mut cycle: Vec<String> = path[start..].iter().map(|t| t.to_string()).collect();
+                cycle.push(table.to_string());
+                return Some(cycle);
+            }
+            None => {}
+        }
+        marks.insert(table, Mark::Active);
+        path.push(table);
+        for child in children.get(table).into_iter().flatten() {
+            if let Some(cycle) = visit(child, children, marks, path) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        marks.insert(table, Mark::Done);
+        None
+    }
+
+    let mut parents: Vec<&str> = children.keys().copied().collect();
+    parents.sort_unstable();
+    let mut marks = HashMap::new();
+    parents
+        .into_iter()
+        .find_map(|table| visit(table, &children, &mut marks, &mut Vec::new()))
+}
+
+/// Replaces the rules registered for `table_prefix` and (re)creates their
+/// triggers. Both tables of every rule must exist.
+pub fn replace_cascade_rules(
+    tx: &Transaction,
+    table_prefix: &str,
+    rules: &[CascadeRule],
+) -> Result<(), DatabaseError> {
+    validate_rules(table_prefix, rules)?;
+
+    for old in load_rules(tx, Some(table_prefix))? {
+        tx.execute(
+            &format!("DROP TRIGGER IF EXISTS \"{}\"", old.trigger_name()),
+            [],
+        )?;
+    }
+    tx.execute(
+        &format!("DELETE FROM \"{CASCADE_RULES_TABLE}\" WHERE owner = ?1"),
+        [table_prefix],
+    )?;
+
+    for rule in rules {
+        tx.execute(
+            &format!(
+                "INSERT INTO \"{CASCADE_RULES_TABLE}\"
+                 (owner, child_table, child_columns, parent_table, parent_columns)
+                 VALUES (?1, ?2, ?3, ?4, ?5)"
+            ),
+            params![
+                table_prefix,
+                rule.table,
+                to_json(&rule.columns),
+                rule.referenced_table,
+                to_json(&rule.referenced_columns),
+            ],
+        )?;
+    }
+
+    install_cascade_triggers(tx, table_prefix)?;
+    Ok(())
+}
+
+/// (Re)creates the triggers of all rules registered for `table_prefix`, e.g.
+/// after a migration rebuilt one of the tables. Returns the number of triggers.
+pub fn install_cascade_triggers(
+    tx: &Transaction,
+    table_prefix: &str,
+) -> Result<usize, DatabaseError> {
+    let rules = load_rules(tx, Some(table_prefix))?;
+    for rule in &rules {
+        for (table, columns) in [
+            (&rule.table, &rule.columns),
+            (&rule.referenced_table, &rule.referenced_columns),
+        ] {
+            let schema = get_table_schema(tx, table)?;
+            if let Some(missing) = columns
+                .iter()
+                .find(|column| !schema.iter().any(|c| &c.name == *column))
+            {
+                return Err(DatabaseError::ValidationError {
+                    reason: format!("Cascade rule references missing column {table}.{missing}"),
+                });
+            }
+        }
+        tx.execute(
+            &format!("DROP TRIGGER IF EXISTS \"{}\"", rule.trigger_name()),
+            [],
+        )?;
+        tx.execute_batch(&rule.trigger_sql())?;
+    }
+    Ok(rules.len())
+}
+
+/// Drops the triggers of all rules registered for `table_prefix`. Migrations
+/// do this up front: a trigger body referencing a table that is being rebuilt
+/// (DROP + RENAME) or altered makes SQLite reject the schema change.
+/// `install_cascade_triggers` puts them back.
+pub fn drop_cascade_triggers(tx: &Transaction, table_prefix: &str) -> Result<(), DatabaseError> {
+    for rule in load_rules(tx, Some(table_prefix))? {
+        tx.execute(
+            &format!("DROP TRIGGER IF EXISTS \"{}\"", rule.trigger_name()),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+/// Removes all rules of `table_prefix`. Their triggers go away with the tables.
+pub fn remove_cascade_rules(tx: &Transaction, table_prefix: &str) -> Result<(), DatabaseError> {
+    tx.execute(
+        &format!("DELETE FROM \"{CASCADE_RULES_TABLE}\" WHERE owner = ?1"),
+        [table_prefix],
+    )?;
+    Ok(())
+}
+
+/// Rules whose parent is `parent_table`: declared rules plus native
+/// `ON DELETE CASCADE` foreign keys.
+pub fn cascade_children(
+    conn: &Connection,
+    parent_table: &str,
+) -> Result<Vec<CascadeRule>, DatabaseError> {
+    let mut rules: Vec<CascadeRule> = load_rules(conn, None)?
+        .into_iter()
+        .filter(|rule| rule.referenced_table == parent_table)
+        .collect();
+
+    let mut stmt = conn.prepare(
+        "SELECT m.name, f.id, f.\"from\", f.\"to\"
+         FROM sqlite_master m JOIN pragma_foreign_key_list(m.name) f
+         WHERE m.type = 'table' AND f.\"table\" = ?1 AND f.on_delete = 'CASCADE'
+         ORDER BY m.name, f.id, f.seq",
+    )?;
+    let fk_rows = stmt
+        .query_map([parent_table], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // `to` is NULL when the foreign key references the parent's primary key.
+    let parent_pks: Vec<String> = get_table_schema(conn, parent_table)?
+        .into_iter()
+        .filter(|c| c.is_pk)
+        .map(|c| c.name)
+        .collect();
+
+    let mut native: Vec<((String, i64), CascadeRule)> = Vec::new();
+    for (table, id, from, to) in fk_rows {
+        let key = (table.clone(), id);
+        if !matches!(native.last(), Some((last, _)) if *last == key) {
+            native.push((
+                key,
+                CascadeRule {
+                    table,
+                    columns: Vec::new(),
+                    referenced_table: parent_table.to_string(),
+                    referenced_columns: Vec::new(),
+                },
+            ));
+        }
+        if let Some((_, rule)) = native.last_mut() {
+            let position = rule.columns.len();
+            rule.columns.push(from);
+            rule.referenced_columns.push(
+                to.or_else(|| parent_pks.get(position).cloned())
+                    .unwrap_or_default(),
+            );
+        }
+    }
+    for (_, rule) in native {
+        if !rules.contains(&rule) {
+            rules.push(rule);
+        }
+    }
+
+    Ok(rules)
+}
+
+fn load_rules(
+    conn: &Connection,
+    table_prefix: Option<&str>,
+) -> Result<Vec<CascadeRule>, DatabaseError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT child_table, child_columns, parent_table, parent_columns
+         FROM \"{CASCADE_RULES_TABLE}\"
+         WHERE ?1 IS NULL OR owner = ?1
+         ORDER BY child_table, child_columns"
+    ))?;
+    let rows = stmt
+        .query_map([table_prefix], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    rows.into_iter()
+        .map(|(table, columns, referenced_table, referenced_columns)| {
+            Ok(CascadeRule {
+                table,
+                columns: from_json(&columns)?,
+                referenced_table,
+                referenced_columns: from_json(&referenced_columns)?,
+            })
+        })
+        .collect()
+}
+
+fn to_json(columns: &[String]) -> String {
+    JsonValue::from(columns).to_string()
+}
+
+fn from_json(text: &str) -> Result<Vec<String>, DatabaseError> {
+    serde_json::from_str(text).map_err(|e| DatabaseError::SerializationError {
+        reason: format!("Invalid column list in cascade rule: {e}"),
+    })
+}
@@ -0,0 +1,310 @@
+//! Tests for declarative cascade deletes in [`super::cascade`]: rule
+//! validation, cycle detection, the AFTER-DELETE triggers and restoring
+//! cascaded children together with their parent.
+
+#![cfg(test)]
+
+use rusqlite::Connection;
+use serde_json::Value as JsonValue;
+
+use super::cascade::{find_cycle, replace_cascade_rules, validate_rules, CascadeRule};
+use super::hlc::HlcService;
+use super::trash::{list_tombstoned, restore_row, RestoreRowResult};
+use super::trigger::DELETED_ROWS_TABLE;
+use crate::database::error::DatabaseError;
+use crate::extension::database::executor::SqlExecutor;
+use crate::table_names::TABLE_CRDT_CONFIGS;
+use crate::test_support::crdt::{create_crdt_table, open_crdt_connection};
+
+const PREFIX: &str = "pk__app__";
+const FOLDERS: &str = "pk__app__folders";
+const NOTES: &str = "pk__app__notes";
+const COMMENTS: &str = "pk__app__comments";
+const TAGS: &str = "pk__app__tags";
+
+fn rule(table: &str, column: &str, referenced_table: &str, referenced_column: &str) -> CascadeRule {
+    CascadeRule {
+        table: table.to_string(),
+        columns: vec![column.to_string()],
+        referenced_table: referenced_table.to_string(),
+        referenced_columns: vec![referenced_column.to_string()],
+    }
+}
+
+fn default_rules() -> Vec<CascadeRule> {
+    vec![
+        rule(NOTES, "folder_id", FOLDERS, "id"),
+        rule(COMMENTS, "note_id", NOTES, "id"),
+    ]
+}
+
+fn setup_db() -> (Connection, HlcService) {
+    let hlc = HlcService::new_for_testing("cascade-test-device");
+    let conn = open_crdt_connection(hlc.clone()).unwrap();
+    for (table, columns) in [
+        (FOLDERS, "id TEXT PRIMARY KEY NOT NULL, name TEXT".to_string()),
+        (NOTES, "id TEXT PRIMARY KEY NOT NULL, folder_id TEXT, title TEXT".to_string()),
+        (COMMENTS, "id TEXT PRIMARY KEY NOT NULL, note_id TEXT, body TEXT".to_string()),
+        (
+            TAGS,
+            format!(
+                "id TEXT PRIMARY KEY NOT NULL, note_id TEXT REFERENCES {NOTES}(id) ON DELETE CASCADE, label TEXT"
+            ),
+        ),
+    ] {
+        create_crdt_table(&conn, table, &format!("CREATE TABLE {table} ({columns})")).unwrap();
+    }
+
+    {
+        let tx = conn.unchecked_transaction().unwrap();
+        replace_cascade_rules(&tx, PREFIX, &default_rules()).unwrap();
+        tx.commit().unwrap();
+    }
+
+    (conn, hlc)
+}
+
+fn exec(conn: &mut Connection, hlc: &HlcService, sql: &str) {
+    let tx = conn.transaction().unwrap();
+    SqlExecutor::execute_internal(&tx, hlc, sql, &[] as &[JsonValue]).unwrap();
+    tx.commit().unwrap();
+}
+
+fn seed(conn: &mut Connection, hlc: &HlcService) {
+    exec(
+        conn,
+        hlc,
+        &format!("INSERT INTO {FOLDERS} (id, name) VALUES ('f1', 'Work'), ('f2', 'Home')"),
+    );
+    exec(
+        conn,
+        hlc,
+        &format!(
+            "INSERT INTO {NOTES} (id, folder_id, title) VALUES
+             ('n1', 'f1', 'Plan'), ('n2', 'f1', 'Budget'), ('n3', 'f2', 'Garden')"
+        ),
+    );
+    exec(
+        conn,
+        hlc,
+        &format!("INSERT INTO {COMMENTS} (id, note_id, body) VALUES ('c1', 'n1', 'ok'), ('c2', 'n3', 'later')"),
+    );
+}
+
+fn ids(conn: &Connection, table: &str) -> Vec<String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT id FROM {table} ORDER BY id"))
+        .unwrap();
+    stmt.query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+fn trash_id(conn: &Connection, table: &str) -> String {
+    list_tombstoned(conn, Some(table), None, None)
+        .unwrap()
+        .remove(0)
+        .id
+}
+
+fn restore(conn: &mut Connection, hlc: &HlcService, table: &str, id: &str) -> RestoreRowResult {
+    let tx = conn.transaction().unwrap();
+    let result = restore_row(&tx, hlc, table, id).unwrap();
+    tx.commit().unwrap();
+    result
+}
+
+#[test]
+fn test_find_cycle_detects_loops_and_self_references() {
+    assert_eq!(find_cycle(&default_rules()), None);
+
+    let mut rules = default_rules();
+    rules.push(rule(FOLDERS, "note_id", COMMENTS, "id"));
+    let cycle = find_cycle(&rules).unwrap();
+    assert_eq!(cycle.first(), cycle.last());
+    assert_eq!(cycle.len(), 4);
+
+    let self_ref = vec![rule(NOTES, "parent_id", NOTES, "id")];
+    assert_eq!(
+        find_cycle(&self_ref),
+        Some(vec![NOTES.to_string(), NOTES.to_string()])
+    );
+}
+
+#[test]
+fn test_validate_rules_rejects_foreign_and_malformed_rules() {
+    assert!(validate_rules(PREFIX, &default_rules()).is_ok());
+
+    let foreign = vec![rule(NOTES, "folder_id", "pk__other__folders", "id")];
+    assert!(matches!(
+        validate_rules(PREFIX, &foreign),
+        Err(DatabaseError::ValidationError { .. })
+    ));
+
+    let mut mismatched = rule(NOTES, "folder_id", FOLDERS, "id");
+    mismatched.referenced_columns.push("name".to_string());
+    assert!(validate_rules(PREFIX, &[mismatched]).is_err());
+
+    let unsafe_column = vec![rule(NOTES, "folder_id\" --", FOLDERS, "id")];
+    assert!(validate_rules(PREFIX, &unsafe_column).is_err());
+
+    let cyclic = vec![rule(NOTES, "parent_id", NOTES, "id")];
+    assert!(validate_rules(PREFIX, &cyclic).is_err());
+}
+
+#[test]
+fn test_parent_delete_cascades_with_shared_hlc() {
+    let (mut conn, hlc) = setup_db();
+    seed(&mut conn, &hlc);
+
+    exec(
+        &mut conn,
+        &hlc,
+        &format!("DELETE FROM {FOLDERS} WHERE id = 'f1'"),
+    );
+
+    assert_eq!(ids(&conn, FOLDERS), vec!["f2"]);
+    assert_eq!(ids(&conn, NOTES), vec!["n3"]);
+    assert_eq!(ids(&conn, COMMENTS), vec!["c2"]);
+
+    let hlcs: Vec<String> = {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT DISTINCT haex_hlc FROM {DELETED_ROWS_TABLE}"
+            ))
+            .unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    };
+    assert_eq!(hlcs.len(), 1, "cascaded deletes share the parent's HLC");
+    let logged: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM {DELETED_ROWS_TABLE}"),
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(logged, 4, "folder, two notes and one comment");
+}
+
+#[test]
+fn test_cascade_skipped_when_triggers_disabled() {
+    let (mut conn, hlc) = setup_db();
+    seed(&mut conn, &hlc);
+
+    conn.execute(
+        &format!("UPDATE {TABLE_CRDT_CONFIGS} SET value = '0' WHERE key = 'triggers_enabled'"),
+        [],
+    )
+    .unwrap();
+    conn.execute(&format!("DELETE FROM {FOLDERS} WHERE id = 'f1'"), [])
+        .unwrap();
+
+    assert_eq!(ids(&conn, NOTES), vec!["n1", "n2", "n3"]);
+}
+
+#[test]
+fn test_restore_parent_restores_cascaded_children_only() {
+    let (mut conn, hlc) = setup_db();
+    seed(&mut conn, &hlc);
+
+    // Deleted on its own earlier: must stay in the trash.
+    exec(
+        &mut conn,
+        &hlc,
+        &format!("DELETE FROM {NOTES} WHERE id = 'n2'"),
+    );
+    exec(
+        &mut conn,
+        &hlc,
+        &format!("DELETE FROM {FOLDERS} WHERE id = 'f1'"),
+    );
+
+    let folder_entry = trash_id(&conn, FOLDERS);
+    let result = restore(&mut conn, &hlc, FOLDERS, &folder_entry);
+
+    assert!(result.restored);
+    assert_eq!(result.restored_children.len(), 2, "note n1 and its comment");
+    assert_eq!(ids(&conn, FOLDERS), vec!["f1", "f2"]);
+    assert_eq!(ids(&conn, NOTES), vec!["n1", "n3"]);
+    assert_eq!(ids(&conn, COMMENTS), vec!["c1", "c2"]);
+
+    let remaining = list_tombstoned(&conn, Some(NOTES), None, None).unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert!(remaining[0].row_pks.contains("n2"));
+}
+
+#[test]
+fn test_restore_child_alone_does_not_restore_parent() {
+    let (mut conn, hlc) = setup_db();
+    seed(&mut conn, &hlc);
+    exec(
+        &mut conn,
+        &hlc,
+        &format!("DELETE FROM {NOTES} WHERE id = 'n1'"),
+    );
+
+    let comment_entry = trash_id(&conn, COMMENTS);
+    let result = restore(&mut conn, &hlc, COMMENTS, &comment_entry);
+
+    assert!(result.restored);
+    assert!(result.restored_children.is_empty());
+    assert_eq!(ids(&conn, COMMENTS), vec!["c1", "c2"]);
+    assert_eq!(ids(&conn, NOTES), vec!["n2", "n3"]);
+}
+
+#[test]
+fn test_replacing_rules_drops_old_triggers() {
+    let (mut conn, hlc) = setup_db();
+    seed(&mut conn, &hlc);
+
+    {
+        let tx = conn.transaction().unwrap();
+        replace_cascade_rules(&tx, PREFIX, &[rule(COMMENTS, "note_id", NOTES, "id")]).unwrap();
+        tx.commit().unwrap();
+    }
+    exec(
+        &mut conn,
+        &hlc,
+        &format!("DELETE FROM {FOLDERS} WHERE id = 'f1'"),
+    );
+
+    assert_eq!(ids(&conn, NOTES), vec!["n1", "n2", "n3"]);
+}
+
+#[test]
+fn test_replace_rejects_missing_columns() {
+    let (conn, _hlc) = setup_db();
+    let tx = conn.unchecked_transaction().unwrap();
+    let result = replace_cascade_rules(&tx, PREFIX, &[rule(NOTES, "missing", FOLDERS, "id")]);
+    assert!(matches!(result, Err(DatabaseError::ValidationError { .. })));
+}
+
+#[test]
+fn test_native_on_delete_cascade_is_restored_together() {
+    let (mut conn, hlc) = setup_db();
+    seed(&mut conn, &hlc);
+    exec(
+        &mut conn,
+        &hlc,
+        &format!("INSERT INTO {TAGS} (id, note_id, label) VALUES ('t1', 'n3', 'green')"),
+    );
+
+    exec(
+        &mut conn,
+        &hlc,
+        &format!("DELETE FROM {NOTES} WHERE id = 'n3'"),
+    );
+    assert!(ids(&conn, TAGS).is_empty());
+
+    let note_entry = trash_id(&conn, NOTES);
+    let result = restore(&mut conn, &hlc, NOTES, &note_entry);
+
+    assert!(result.restored);
+    assert_eq!(result.restored_children.len(), 2, "comment c2 and tag t1");
+    assert_eq!(ids(&conn, TAGS), vec!["t1"]);
+    assert_eq!(ids(&conn, COMMENTS), vec!["c1", "c2"]);
+}
@@ -303,6 +303,9 @@ pub fn cleanup_deleted_rows(
         eprintln!("Cleaned up {deleted} entries from {DELETED_ROWS_TABLE}");
     }
 
+    // Trash snapshots live exactly as long as their delete-log entry.
+    crate::crdt::trash::purge_orphaned_snapshots(conn)?;
+
     Ok(CleanupResult {
         tombstones_deleted: deleted,
         applied_deleted: 1,
@@ -1,12 +1,20 @@
-use crate::crdt::hlc::{hlc_is_newer, hlc_max, HlcService};
+use crate::crdt::bulk_apply::{self, RemoteApplyProgress};
+use crate::crdt::hard_delete;
+use crate::crdt::hlc::{compare_hlc_strings, hlc_is_newer, hlc_max, HlcService};
+use crate::crdt::json_patch::{self, JsonPath};
+use crate::crdt::scanner;
+use crate::crdt::sync_status::{self, SyncStatus};
 use crate::crdt::trigger;
+use crate::crdt::unique_conflict::{self, UniqueConflictSettings, UniqueConflictStrategy};
 use crate::crdt::trigger::{
     get_table_schema as get_table_schema_internal, is_safe_identifier, ColumnInfo,
     COLUMN_HLCS_COLUMN, DELETED_ROWS_TABLE, HLC_TIMESTAMP_COLUMN,
 };
 use crate::database::core::{with_connection, ValueConverter};
 use crate::database::error::DatabaseError;
-use crate::table_names::{TABLE_CRDT_CONFIGS, TABLE_CRDT_DIRTY_TABLES, TABLE_CRDT_PENDING_COLUMNS};
+use crate::table_names::{
+    TABLE_CRDT_CONFIGS, TABLE_CRDT_CONFLICTS, TABLE_CRDT_DIRTY_TABLES, TABLE_CRDT_PENDING_COLUMNS,
+};
 use crate::AppState;
 use rusqlite::params;
 use rusqlite::types::Value as SqlValue;
@@ -28,6 +36,26 @@ fn json_values_to_sql_params(values: &[JsonValue]) -> Result<Vec<SqlValue>, Data
         .collect()
 }
 
+/// Reads one column of the row matched by `pk_where_clause` (NULL if the row is absent).
+fn fetch_column_value(
+    tx: &rusqlite::Transaction,
+    table_name: &str,
+    column: &str,
+    pk_where_clause: &str,
+    pk_values: &[JsonValue],
+) -> Result<JsonValue, DatabaseError> {
+    let sql = format!("SELECT \"{column}\" FROM \"{table_name}\" WHERE {pk_where_clause}");
+    let params = json_values_to_sql_params(pk_values)?;
+    let params_refs: Vec<&dyn rusqlite::ToSql> =
+        params.iter().map(|v| v as &dyn rusqlite::ToSql).collect();
+
+    match tx.query_row(&sql, &*params_refs, |row| row.get::<_, SqlValue>(0)) {
+        Ok(value) => Ok(ValueConverter::rusqlite_value_to_json(&value)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(JsonValue::Null),
+        Err(e) => Err(DatabaseError::from(e)),
+    }
+}
+
 /// Builds a WHERE clause for primary key columns, properly handling NULL values.
 ///
 /// In SQL, `column = NULL` is always FALSE because NULL != NULL.
@@ -66,6 +94,14 @@ fn build_pk_where_clause(
 pub struct DirtyTable {
     pub table_name: String,
     pub last_modified: String,
+    /// Column changes the next push of this table would send.
+    pub pending_changes: u64,
+    /// Oldest HLC among the pending changes.
+    pub oldest_hlc: Option<String>,
+    /// Newest HLC among the pending changes.
+    pub newest_hlc: Option<String>,
+    /// Estimated plaintext payload of the pending changes, before encryption.
+    pub estimated_bytes: u64,
 }
 
 /// Gets table schema information (columns and their properties)
@@ -79,25 +115,64 @@ pub fn get_table_schema(
     })
 }
 
-/// Gets all dirty tables that need to be synced
+/// Gets all dirty tables that need to be synced, with the number, HLC range
+/// and estimated size of their pending changes.
+///
+/// `last_push_hlc_timestamp` is the push cursor of the backend the caller is
+/// about to push to; changes at or before it are not counted. Without it
+/// every change of the table counts, as for a first push.
 #[tauri::command]
-pub fn get_dirty_tables(state: State<'_, AppState>) -> Result<Vec<DirtyTable>, DatabaseError> {
+pub fn get_dirty_tables(
+    state: State<'_, AppState>,
+    last_push_hlc_timestamp: Option<String>,
+) -> Result<Vec<DirtyTable>, DatabaseError> {
     with_connection(&state.db, |conn| {
         let mut stmt = conn
             .prepare(&format!("SELECT table_name, last_modified FROM {TABLE_CRDT_DIRTY_TABLES} ORDER BY last_modified ASC"))
             .map_err(DatabaseError::from)?;
 
         let rows = stmt
-            .query_map([], |row| {
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(DatabaseError::from)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(DatabaseError::from)?;
+
+        rows.into_iter()
+            .map(|(table_name, last_modified)| {
+                let summary = scanner::summarize_pending_changes(
+                    conn,
+                    &table_name,
+                    last_push_hlc_timestamp.as_deref(),
+                )?;
                 Ok(DirtyTable {
-                    table_name: row.get(0)?,
-                    last_modified: row.get(1)?,
+                    table_name,
+                    last_modified,
+                    pending_changes: summary.change_count,
+                    oldest_hlc: summary.oldest_hlc,
+                    newest_hlc: summary.newest_hlc,
+                    estimated_bytes: summary.estimated_bytes,
                 })
             })
-            .map_err(DatabaseError::from)?;
+            .collect()
+    })
+}
+
+/// Lists the sync cursors of every peer this device has synced with.
+#[tauri::command]
+pub fn sync_get_status(state: State<'_, AppState>) -> Result<Vec<SyncStatus>, DatabaseError> {
+    with_connection(&state.db, |conn| sync_status::list_statuses(conn))
+}
 
-        rows.collect::<Result<Vec<_>, _>>()
-            .map_err(DatabaseError::from)
+/// Sets the push cursor of `peer_id` after a push, or moves it back to
+/// re-send changes. `None` resets it so the next push sends everything.
+#[tauri::command]
+pub fn sync_set_cursor(
+    state: State<'_, AppState>,
+    peer_id: String,
+    hlc: Option<String>,
+) -> Result<(), DatabaseError> {
+    with_connection(&state.db, |conn| {
+        sync_status::set_push_cursor(conn, &peer_id, hlc.as_deref())
     })
 }
 
@@ -173,7 +248,47 @@ pub fn ensure_extension_triggers(state: State<'_, AppState>) -> Result<usize, Da
     with_connection(&state.db, |conn| ensure_triggers_for_all_tables(conn))
 }
 
-#[derive(Debug, Deserialize)]
+/// Returns the configured strategies for UNIQUE conflicts of synced inserts.
+#[tauri::command]
+pub fn crdt_get_unique_conflict_settings(
+    state: State<'_, AppState>,
+) -> Result<UniqueConflictSettings, DatabaseError> {
+    with_connection(&state.db, |conn| unique_conflict::load_settings(conn))
+}
+
+/// Sets the UNIQUE-conflict strategy for one table, or the default if
+/// `table_name` is omitted. Passing no strategy removes the setting.
+#[tauri::command]
+pub fn crdt_set_unique_conflict_strategy(
+    table_name: Option<String>,
+    strategy: Option<UniqueConflictStrategy>,
+    state: State<'_, AppState>,
+) -> Result<(), DatabaseError> {
+    with_connection(&state.db, |conn| {
+        unique_conflict::set_strategy(conn, table_name.as_deref(), strategy)
+    })
+}
+
+/// Lists the local-only tables whose deletes skip the delete-log and trash.
+#[tauri::command]
+pub fn crdt_get_hard_delete_tables(
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, DatabaseError> {
+    with_connection(&state.db, |conn| hard_delete::list_hard_delete_tables(conn))
+}
+
+/// Enables or disables hard deletes for a table excluded from sync
+/// (`_no_sync`). Synced tables are rejected.
+#[tauri::command]
+pub fn crdt_set_hard_delete_table(
+    table_name: String,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<(), DatabaseError> {
+    with_connection(&state.db, |conn| hard_delete::set_hard_delete(conn, &table_name, enabled))
+}
+
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RemoteColumnChange {
     pub table_name: String,
@@ -252,11 +367,13 @@ fn create_conflict_entry(
     let detected_at = format!("{}", timestamp);
 
     tx.execute(
-        "INSERT INTO haex_crdt_conflicts (
+        &format!(
+            "INSERT INTO {TABLE_CRDT_CONFLICTS} (
             id, table_name, conflict_type, local_row_id, remote_row_id,
             local_row_data, remote_row_data, local_timestamp, remote_timestamp,
             conflict_key, detected_at, resolved
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)"
+        ),
         params![
             &conflict_id,
             table_name,
@@ -286,7 +403,7 @@ fn create_conflict_entry(
 /// returns them sorted ascending by HLC. All writes issued inside the same
 /// sender-side transaction share a timestamp, so `hlc_timestamp` is the
 /// semantic grouping key — there is no separate batch id anymore.
-fn group_by_transaction_hlc(
+pub(crate) fn group_by_transaction_hlc(
     changes: Vec<RemoteColumnChange>,
 ) -> Vec<(String, Vec<RemoteColumnChange>)> {
     let mut groups: HashMap<String, Vec<RemoteColumnChange>> = HashMap::new();
@@ -371,6 +488,101 @@ pub fn apply_remote_changes_in_transaction(
     )
 }
 
+/// Applies a large set of remote changes in chunks, for the initial sync of a
+/// new device. Each chunk commits on its own; progress is emitted as
+/// `crdt:remote-apply-progress` after every chunk. Cancel via
+/// `crdt_cancel_remote_apply`; calling again with the same `session_id`
+/// resumes after the last applied chunk. See [`bulk_apply`].
+#[tauri::command]
+pub async fn apply_remote_changes_chunked(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    session_id: String,
+    changes: Vec<RemoteColumnChange>,
+    backend_id: String,
+    max_hlc: String,
+    chunk_size: Option<u32>,
+) -> Result<RemoteApplyProgress, DatabaseError> {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+    use tokio_util::sync::CancellationToken;
+
+    // Same lock discipline as `apply_remote_changes_in_transaction`; the
+    // clone shares the clock with the app state.
+    let hlc_service = state
+        .lock_or_fail(
+            &state.hlc,
+            crate::critical::CriticalFailureCode::HlcMutexPoisoned,
+            "crdt::commands::apply_remote_changes_chunked",
+            serde_json::json!({}),
+        )?
+        .clone();
+
+    // Cancellation goes through `AppState.transfer_tokens` like the other
+    // long-running transfers; the pause flag is unused here.
+    let cancel = CancellationToken::new();
+    {
+        let mut tokens = state.transfer_tokens.lock().await;
+        if tokens.contains_key(&session_id) {
+            return Err(DatabaseError::ValidationError {
+                reason: format!("Remote apply session {session_id} already in flight"),
+            });
+        }
+        tokens.insert(
+            session_id.clone(),
+            (cancel.clone(), Arc::new(AtomicBool::new(false))),
+        );
+    }
+
+    let db = crate::database::DbConnection(state.db.0.clone());
+    let chunk_size = bulk_apply::effective_chunk_size(chunk_size);
+    let session = session_id.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        bulk_apply::apply_in_chunks(
+            &db,
+            Some(&hlc_service),
+            &session,
+            changes,
+            Some((backend_id.as_str(), max_hlc.as_str())),
+            chunk_size,
+            |progress| {
+                let _ = crate::events::emit(&app_handle, progress);
+                !cancel.is_cancelled()
+            },
+        )
+    })
+    .await;
+
+    state.transfer_tokens.lock().await.remove(&session_id);
+
+    result.map_err(|e| DatabaseError::DatabaseError {
+        reason: format!("Remote apply task failed: {e}"),
+    })?
+}
+
+/// Cancels a running `apply_remote_changes_chunked` session after its
+/// current chunk. No-op for unknown sessions.
+#[tauri::command]
+pub async fn crdt_cancel_remote_apply(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<(), DatabaseError> {
+    if let Some((cancel, _pause)) = state.transfer_tokens.lock().await.get(&session_id) {
+        cancel.cancel();
+    }
+    Ok(())
+}
+
+/// Discards the resume position of a chunked apply session, so the next
+/// call with that `session_id` starts from the beginning.
+#[tauri::command]
+pub fn crdt_reset_remote_apply(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<(), DatabaseError> {
+    with_connection(&state.db, |conn| bulk_apply::clear_position(conn, &session_id))
+}
+
 /// Inner implementation that applies remote CRDT changes to a database connection.
 ///
 /// If `backend_info` is `Some((backend_id, max_hlc))`, updates `haex_sync_backends`
@@ -578,7 +790,7 @@ pub fn apply_remote_changes_to_db(
                 ),
             });
         }
-        if !is_safe_identifier(&change.column_name) {
+        if !json_patch::is_safe_change_column(&change.column_name) {
             return Err(DatabaseError::ValidationError {
                 reason: format!(
                     "Invalid column name '{}' in table '{}'",
@@ -610,6 +822,10 @@ pub fn apply_remote_changes_to_db(
         );
         tx.execute(&disable_sql, []).map_err(DatabaseError::from)?;
 
+        // Writes to shared tables need an editor role (or one of our own
+        // devices as author); see `sync::roles`.
+        let changes = crate::sync::roles::drop_unauthorized_writes(&tx, changes)?;
+
         // Collect side-data needed after the apply loop:
         //   1. all HLC timestamps for advancing the local clock,
         //   2. IDs of haex_deleted_rows entries arriving in this batch so
@@ -733,8 +949,23 @@ pub fn apply_remote_changes_to_db(
             // Collect all column changes that are newer than current
             let mut columns_to_update: Vec<(String, JsonValue, String)> = Vec::new(); // (column_name, json_value, hlc)
             let mut max_hlc_for_row = first_change.hlc_timestamp.clone();
+            // json_set patches ("<column>#<path>"), merged after the whole-column writes
+            let mut path_changes: Vec<(&str, JsonPath, &RemoteColumnChange)> = Vec::new();
 
             for change in &row_change_list {
+                if let Some((column, path)) = json_patch::split_path_key(&change.column_name) {
+                    match JsonPath::parse(path) {
+                        Ok(path) if existing_columns.contains(column) => {
+                            path_changes.push((column, path, change));
+                        }
+                        _ => eprintln!(
+                            "[SYNC RUST] Skipping JSON patch '{}' in table '{}' - column not in local schema",
+                            change.column_name, first_change.table_name
+                        ),
+                    }
+                    continue;
+                }
+
                 // Skip columns that don't exist in the local schema
                 // This handles schema version differences between devices
                 if !existing_columns.contains(change.column_name.as_str()) {
@@ -763,20 +994,39 @@ pub fn apply_remote_changes_to_db(
                     continue;
                 }
 
-                let current_hlc = column_hlcs
-                    .get(&change.column_name)
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("");
+                // For JSON-patched columns this is the HLC of the last whole write
+                let current_hlc =
+                    json_patch::base_hlc(&column_hlcs, &change.column_name).unwrap_or_default();
 
-                if hlc_is_newer(change.hlc_timestamp.as_str(), current_hlc) {
+                if hlc_is_newer(change.hlc_timestamp.as_str(), &current_hlc) {
                     // Remote change is newer, include it
-                    column_hlcs.insert(
-                        change.column_name.clone(),
-                        JsonValue::String(change.hlc_timestamp.clone()),
-                    );
+                    let value = if json_patch::has_path_entries(&column_hlcs, &change.column_name)
+                    {
+                        // Keep local json_set patches that are newer than the remote write
+                        let local_value = fetch_column_value(
+                            &tx,
+                            &first_change.table_name,
+                            &change.column_name,
+                            &pk_where_clause,
+                            &pk_values_for_query,
+                        )?;
+                        json_patch::merge_remote_whole(
+                            &mut column_hlcs,
+                            &change.column_name,
+                            &change.hlc_timestamp,
+                            &change.decrypted_value,
+                            &local_value,
+                        )
+                    } else {
+                        column_hlcs.insert(
+                            change.column_name.clone(),
+                            JsonValue::String(change.hlc_timestamp.clone()),
+                        );
+                        change.decrypted_value.clone()
+                    };
                     columns_to_update.push((
                         change.column_name.clone(),
-                        change.decrypted_value.clone(),
+                        value,
                         change.hlc_timestamp.clone(),
                     ));
 
@@ -787,6 +1037,39 @@ pub fn apply_remote_changes_to_db(
                 }
             }
 
+            // Merge JSON path patches oldest first on top of the current value
+            // (or of a whole-column write from this batch).
+            path_changes.sort_by(|a, b| compare_hlc_strings(&a.2.hlc_timestamp, &b.2.hlc_timestamp));
+            for (column, path, change) in path_changes {
+                let current_value = match columns_to_update.iter().find(|(c, _, _)| c == column) {
+                    Some((_, value, _)) => value.clone(),
+                    None if row_exists => fetch_column_value(
+                        &tx,
+                        &first_change.table_name,
+                        column,
+                        &pk_where_clause,
+                        &pk_values_for_query,
+                    )?,
+                    None => JsonValue::Null,
+                };
+                let Some(value) = json_patch::apply_remote_path(
+                    &mut column_hlcs,
+                    column,
+                    &path,
+                    &change.hlc_timestamp,
+                    &current_value,
+                    &change.decrypted_value,
+                ) else {
+                    continue;
+                };
+
+                columns_to_update.retain(|(c, _, _)| c != column);
+                columns_to_update.push((column.to_string(), value, change.hlc_timestamp.clone()));
+                if hlc_is_newer(&change.hlc_timestamp, &max_hlc_for_row) {
+                    max_hlc_for_row = change.hlc_timestamp.clone();
+                }
+            }
+
             // Only apply if there are columns to update
             if !columns_to_update.is_empty() {
                 let new_hlcs_json = serde_json::to_string(&column_hlcs).map_err(|e| {
@@ -920,6 +1203,29 @@ pub fn apply_remote_changes_to_db(
 
                             // Check if it's a UNIQUE constraint violation
                             if error_msg.contains("UNIQUE constraint failed") {
+                                let strategy = unique_conflict::strategy_for_table(
+                                    &tx,
+                                    &first_change.table_name,
+                                )?;
+                                let remote_insert = unique_conflict::RemoteInsert {
+                                    table_name: &first_change.table_name,
+                                    row_pks: &row_pks_str,
+                                    columns: &columns,
+                                    values: &values,
+                                    changes: &columns_to_update,
+                                    schema: &schema,
+                                };
+                                // Rename/merge resolve in place; reject (and any
+                                // failed resolution) records a conflict entry below.
+                                if unique_conflict::resolve(
+                                    &tx,
+                                    strategy,
+                                    &remote_insert,
+                                    error_msg,
+                                )? {
+                                    continue;
+                                }
+
                                 eprintln!("[SYNC RUST] UNIQUE constraint conflict - creating conflict entry");
 
                                 // Build remote row data from all columns being inserted
@@ -990,6 +1296,10 @@ pub fn apply_remote_changes_to_db(
                 params![max_hlc, backend_id],
             )
             .map_err(DatabaseError::from)?;
+
+            // Same for the per-peer cursors, which never move backwards.
+            sync_status::advance_pull_cursor(&tx, backend_id, max_hlc)?;
+            sync_status::advance_push_cursor(&tx, backend_id, max_hlc)?;
         }
 
         // Re-enable triggers before committing
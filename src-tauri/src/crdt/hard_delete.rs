@@ -0,0 +1,127 @@
+// src-tauri/src/crdt/hard_delete.rs
+//!
+//! Per-table opt-out of the delete-log.
+//!
+//! Every CRDT table gets a BEFORE-DELETE trigger that logs the delete into
+//! `haex_deleted_rows` and keeps a snapshot in the trash. For ephemeral
+//! local tables (caches, session state) that only grows the log. A table
+//! flagged here gets no DELETE trigger, so its deletes are plain hard
+//! DELETEs with nothing to clean up afterwards.
+//!
+//! Only tables excluded from sync (`_no_sync`) can be flagged: a synced
+//! table without delete-log entries would never propagate its deletes.
+//! The flag is a per-device setting in haex_crdt_configs (local-only).
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::crdt::trash::TRASH_TABLE;
+use crate::crdt::trigger::{
+    get_table_schema, is_safe_identifier, setup_triggers_for_table, DELETED_ROWS_TABLE,
+    HLC_TIMESTAMP_COLUMN,
+};
+use crate::database::constants::vault_settings_key;
+use crate::database::error::DatabaseError;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, TABLE_CRDT_CONFIGS,
+};
+
+/// Suffix of tables that are excluded from sync.
+const NO_SYNC_SUFFIX: &str = "_no_sync";
+
+/// Whether deletes on `table_name` skip the delete-log and trash.
+/// Synced tables are never hard-delete tables, whatever is stored.
+pub fn is_hard_delete_table(conn: &Connection, table_name: &str) -> rusqlite::Result<bool> {
+    if !table_name.ends_with(NO_SYNC_SUFFIX) {
+        return Ok(false);
+    }
+    let value: Option<String> = conn
+        .query_row(
+            &format!(
+                "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"
+            ),
+            params![hard_delete_key(table_name)],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value.as_deref() == Some("1"))
+}
+
+/// Lists all tables flagged for hard deletes.
+pub fn list_hard_delete_tables(conn: &Connection) -> Result<Vec<String>, DatabaseError> {
+    let prefix = vault_settings_key::HARD_DELETE_PREFIX;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COL_CRDT_CONFIGS_KEY} FROM {TABLE_CRDT_CONFIGS}
+         WHERE substr({COL_CRDT_CONFIGS_KEY}, 1, length(?1)) = ?1
+           AND {COL_CRDT_CONFIGS_VALUE} = '1'
+         ORDER BY {COL_CRDT_CONFIGS_KEY}"
+    ))?;
+    let keys = stmt
+        .query_map([prefix], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(keys
+        .into_iter()
+        .map(|key| key[prefix.len()..].to_string())
+        .collect())
+}
+
+/// Flags `table_name` for hard deletes (or removes the flag) and rebuilds
+/// its triggers if the table is already tracked. Enabling also drops the
+/// delete-log and trash entries the table has accumulated so far.
+pub fn set_hard_delete(
+    conn: &mut Connection,
+    table_name: &str,
+    enabled: bool,
+) -> Result<(), DatabaseError> {
+    if !is_safe_identifier(table_name) {
+        return Err(DatabaseError::ValidationError {
+            reason: format!("Invalid table name: {table_name}"),
+        });
+    }
+    if !table_name.ends_with(NO_SYNC_SUFFIX) {
+        return Err(DatabaseError::ValidationError {
+            reason: format!(
+                "Hard deletes are only allowed for tables excluded from sync (suffix '{NO_SYNC_SUFFIX}'): {table_name}"
+            ),
+        });
+    }
+
+    let tx = conn.transaction()?;
+    let key = hard_delete_key(table_name);
+    if enabled {
+        tx.execute(
+            &format!(
+                "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?, ?, ?)"
+            ),
+            params![key, "system", "1"],
+        )?;
+    } else {
+        tx.execute(
+            &format!("DELETE FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"),
+            params![key],
+        )?;
+    }
+
+    // Tables without CRDT columns have no triggers to rebuild
+    let columns = get_table_schema(&tx, table_name)?;
+    if columns.iter().any(|c| c.name == HLC_TIMESTAMP_COLUMN) {
+        setup_triggers_for_table(&tx, table_name, true)?;
+    }
+
+    if enabled {
+        tx.execute(
+            &format!("DELETE FROM \"{TRASH_TABLE}\" WHERE table_name = ?"),
+            params![table_name],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM \"{DELETED_ROWS_TABLE}\" WHERE table_name = ?"),
+            params![table_name],
+        )?;
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+fn hard_delete_key(table_name: &str) -> String {
+    format!("{}{table_name}", vault_settings_key::HARD_DELETE_PREFIX)
+}
@@ -0,0 +1,130 @@
+//! Tests for the hard-delete opt-out in [`super::hard_delete`]: only tables
+//! excluded from sync can be flagged, and deletes on flagged tables leave no
+//! delete-log or trash entries behind.
+
+#![cfg(test)]
+
+use rusqlite::Connection;
+use serde_json::Value as JsonValue;
+
+use super::hard_delete::{is_hard_delete_table, list_hard_delete_tables, set_hard_delete};
+use super::hlc::HlcService;
+use super::trash::TRASH_TABLE;
+use super::trigger::DELETED_ROWS_TABLE;
+use crate::database::error::DatabaseError;
+use crate::extension::database::executor::SqlExecutor;
+use crate::table_names::TABLE_CRDT_CONFIGS;
+use crate::test_support::crdt::{create_crdt_table, open_crdt_connection};
+
+const CACHE_TABLE: &str = "cache_no_sync";
+
+fn setup_db() -> (Connection, HlcService) {
+    let hlc = HlcService::new_for_testing("hard-delete-test-device");
+    let conn = open_crdt_connection(hlc.clone()).unwrap();
+    create_crdt_table(
+        &conn,
+        "notes",
+        "CREATE TABLE notes (id TEXT PRIMARY KEY NOT NULL, title TEXT)",
+    )
+    .unwrap();
+    create_crdt_table(
+        &conn,
+        CACHE_TABLE,
+        &format!("CREATE TABLE {CACHE_TABLE} (id TEXT PRIMARY KEY NOT NULL, payload TEXT)"),
+    )
+    .unwrap();
+
+    (conn, hlc)
+}
+
+fn exec(conn: &mut Connection, hlc: &HlcService, sql: &str, params: &[JsonValue]) {
+    let tx = conn.transaction().unwrap();
+    SqlExecutor::execute_internal(&tx, hlc, sql, params).unwrap();
+    tx.commit().unwrap();
+}
+
+fn insert_and_delete(conn: &mut Connection, hlc: &HlcService, table: &str, id: &str) {
+    exec(
+        conn,
+        hlc,
+        &format!("INSERT INTO {table} (id) VALUES (?)"),
+        &[JsonValue::from(id)],
+    );
+    exec(
+        conn,
+        hlc,
+        &format!("DELETE FROM {table} WHERE id = ?"),
+        &[JsonValue::from(id)],
+    );
+}
+
+fn count_for(conn: &Connection, log_table: &str, table: &str) -> i64 {
+    conn.query_row(
+        &format!("SELECT COUNT(*) FROM \"{log_table}\" WHERE table_name = ?"),
+        [table],
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+#[test]
+fn synced_tables_cannot_be_flagged() {
+    let (mut conn, _hlc) = setup_db();
+
+    let err = set_hard_delete(&mut conn, "notes", true).unwrap_err();
+    assert!(matches!(err, DatabaseError::ValidationError { .. }));
+
+    let err = set_hard_delete(&mut conn, "bad name_no_sync", true).unwrap_err();
+    assert!(matches!(err, DatabaseError::ValidationError { .. }));
+
+    assert!(list_hard_delete_tables(&conn).unwrap().is_empty());
+}
+
+#[test]
+fn stored_flag_is_ignored_for_synced_tables() {
+    let (conn, _hlc) = setup_db();
+    conn.execute(
+        &format!("INSERT INTO {TABLE_CRDT_CONFIGS} (key, type, value) VALUES ('hard_delete:notes', 'system', '1')"),
+        [],
+    )
+    .unwrap();
+
+    assert!(!is_hard_delete_table(&conn, "notes").unwrap());
+}
+
+#[test]
+fn flagged_table_deletes_skip_log_and_trash() {
+    let (mut conn, hlc) = setup_db();
+
+    set_hard_delete(&mut conn, CACHE_TABLE, true).unwrap();
+    assert_eq!(
+        list_hard_delete_tables(&conn).unwrap(),
+        vec![CACHE_TABLE.to_string()]
+    );
+
+    insert_and_delete(&mut conn, &hlc, CACHE_TABLE, "c1");
+    insert_and_delete(&mut conn, &hlc, "notes", "n1");
+
+    assert_eq!(count_for(&conn, DELETED_ROWS_TABLE, CACHE_TABLE), 0);
+    assert_eq!(count_for(&conn, TRASH_TABLE, CACHE_TABLE), 0);
+    assert_eq!(count_for(&conn, DELETED_ROWS_TABLE, "notes"), 1);
+    assert_eq!(count_for(&conn, TRASH_TABLE, "notes"), 1);
+}
+
+#[test]
+fn enabling_purges_existing_entries_and_disabling_restores_logging() {
+    let (mut conn, hlc) = setup_db();
+
+    insert_and_delete(&mut conn, &hlc, CACHE_TABLE, "c1");
+    assert_eq!(count_for(&conn, DELETED_ROWS_TABLE, CACHE_TABLE), 1);
+
+    set_hard_delete(&mut conn, CACHE_TABLE, true).unwrap();
+    assert_eq!(count_for(&conn, DELETED_ROWS_TABLE, CACHE_TABLE), 0);
+    assert_eq!(count_for(&conn, TRASH_TABLE, CACHE_TABLE), 0);
+
+    set_hard_delete(&mut conn, CACHE_TABLE, false).unwrap();
+    assert!(!is_hard_delete_table(&conn, CACHE_TABLE).unwrap());
+
+    insert_and_delete(&mut conn, &hlc, CACHE_TABLE, "c2");
+    assert_eq!(count_for(&conn, DELETED_ROWS_TABLE, CACHE_TABLE), 1);
+}
@@ -5,7 +5,6 @@ use rusqlite::{params, Connection, Transaction};
 use serde_json::json;
 use std::{
     fmt::Debug,
-    path::PathBuf,
     str::FromStr,
     sync::{Arc, Mutex},
     time::Duration,
@@ -84,6 +83,35 @@ impl HlcService {
         }
     }
 
+    /// An initialized HLC whose physical time comes from `clock`, e.g.
+    /// `test_support::clock::test_clock`. The node ID is derived from
+    /// `device_id`, so timestamps are identical across runs.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn with_clock(device_id: &str, clock: fn() -> uhlc::NTP64) -> Result<Self, HlcError> {
+        let mut bytes = [0u8; 16];
+        for (i, byte) in device_id.bytes().enumerate() {
+            bytes[i % 16] ^= byte;
+        }
+        // uhlc rejects the all-zero ID (empty device ID)
+        bytes[15] |= 1;
+        let node_id = ID::try_from(bytes)?;
+
+        let hlc = HLCBuilder::new()
+            .with_id(node_id)
+            .with_clock(clock)
+            .with_max_delta(Duration::from_secs(1))
+            .build();
+
+        Ok(HlcService {
+            hlc: Arc::new(Mutex::new(Some(hlc))),
+        })
+    }
+
+    /// Whether the HLC has been initialized from an open vault.
+    pub fn is_initialized(&self) -> Result<bool, HlcError> {
+        Ok(self.hlc.lock().map_err(|_| HlcError::MutexPoisoned)?.is_some())
+    }
+
     /// Initializes this instance in-place from the given DB connection.
     ///
     /// Unlike [`try_initialize`] this mutates the existing `Arc<Mutex<Option<HLC>>>`,
@@ -165,7 +193,7 @@ impl HlcService {
 
     /// Holt die Geräte-ID aus dem Tauri Store oder erstellt eine neue, wenn keine existiert.
     pub fn get_or_create_device_id(app_handle: &AppHandle) -> Result<String, HlcError> {
-        let store_path = PathBuf::from("instance.json");
+        let store_path = crate::app_profile::instance_store_path();
         let store = app_handle
             .store(store_path)
             .map_err(|e| HlcError::DeviceStore(e.to_string()))?;
@@ -195,7 +223,7 @@ impl HlcService {
         // Entweder weil der Schlüssel fehlte oder weil der Wert kein String war.
         // Also erstellen wir eine neue ID.
         if !id_exists {
-            let new_id = Uuid::new_v4().to_string();
+            let new_id = crate::ids::new_uuid().to_string();
 
             store.set("id".to_string(), json!(new_id.clone()));
 
@@ -3,7 +3,10 @@
 
 use crate::crdt::trigger::HLC_TIMESTAMP_COLUMN;
 use crate::database::error::DatabaseError;
-use sqlparser::ast::{Expr, Ident, Insert, ObjectName, SelectItem, SetExpr, Value};
+use sqlparser::ast::{
+    Assignment, Expr, Ident, Insert, ObjectName, OnConflict, OnConflictAction, OnInsert,
+    SelectItem, SetExpr, Value,
+};
 use uhlc::Timestamp;
 
 /// Helper-Struct für INSERT-Transformationen
@@ -57,8 +60,27 @@ impl InsertTransformer {
         }
     }
 
+    /// SET-Liste des `ON CONFLICT ... DO UPDATE`-Zweigs, falls vorhanden.
+    ///
+    /// Der Zweig aktualisiert die bestehende Zeile und feuert damit das
+    /// AFTER-UPDATE-Trigger, nicht das INSERT-Trigger. Der Aufrufer muss die
+    /// Zuweisungen daher wie ein UPDATE mit `haex_hlc` stempeln — sonst
+    /// bekämen die geänderten Spalten den alten HLC der Zeile.
+    /// `DO NOTHING` schreibt nichts und braucht keine Anpassung.
+    pub fn conflict_update_assignments(insert_stmt: &mut Insert) -> Option<&mut Vec<Assignment>> {
+        match insert_stmt.on.as_mut()? {
+            OnInsert::OnConflict(OnConflict {
+                action: OnConflictAction::DoUpdate(do_update),
+                ..
+            }) => Some(&mut do_update.assignments),
+            _ => None,
+        }
+    }
+
     /// Transformiert INSERT-Statements (fügt HLC-Timestamp hinzu)
-    /// Hard Delete: Kein ON CONFLICT mehr nötig - gelöschte Einträge sind wirklich weg
+    /// Hard Delete: gelöschte Einträge sind wirklich weg, es gibt keine
+    /// Tombstones zu reaktivieren. `ON CONFLICT DO UPDATE` stempelt der
+    /// Aufrufer über [`Self::conflict_update_assignments`].
     pub fn transform_insert(
         &self,
         insert_stmt: &mut Insert,
@@ -68,9 +90,7 @@ impl InsertTransformer {
         let hlc_col_index =
             Self::find_or_add_column(&mut insert_stmt.columns, self.hlc_timestamp_column);
 
-        // ON CONFLICT Logik komplett entfernt!
-        // Bei Hard Deletes gibt es keine Tombstone-Einträge mehr zu reaktivieren
-        // UNIQUE Constraint Violations sind echte Fehler
+        // Ohne ON CONFLICT sind UNIQUE Constraint Violations echte Fehler
 
         match insert_stmt.source.as_mut() {
             Some(query) => match &mut *query.body {
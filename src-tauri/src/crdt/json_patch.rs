@@ -0,0 +1,437 @@
+//! Path-level CRDT tracking for JSON columns.
+//!
+//! A `json_set` update on a JSON column records one HLC per touched path in
+//! `haex_column_hlcs`, keyed `"<column>#<path>"` (e.g. `"settings#$.theme"`).
+//! The entry `"<column>#"` keeps the HLC of the last whole-column write, so
+//! the column's own HLC can still be bumped by the UPDATE trigger without
+//! losing track of what a plain write would have to beat.
+//!
+//! Reading rule: if some path entry of a column carries the same HLC as the
+//! column itself, the last write was a patch and the base HLC is the
+//! `"<column>#"` entry; otherwise the column HLC is the base. Paths whose HLC
+//! is newer than the base are "live" and travel as their own changes with
+//! `column_name = "<column>#<path>"` and the path's JSON text as value.
+//! Receivers merge them with `json_set` semantics, so concurrent edits to
+//! different keys of one JSON blob survive on every device.
+
+use crate::crdt::commands::build_pk_where_from_map;
+use crate::crdt::hlc::{compare_hlc_strings, hlc_is_newer};
+use crate::crdt::trigger::{is_safe_identifier, COLUMN_HLCS_COLUMN};
+use crate::database::error::DatabaseError;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use ts_rs::TS;
+
+/// Separates the column name from the JSON path in `haex_column_hlcs` keys
+/// and in the `column_name` of synced path changes.
+pub const PATH_KEY_SEPARATOR: char = '#';
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// A restricted SQLite JSON path: `$` followed by `.key` or `[n]` segments.
+/// Keys are limited to the identifier charset so paths can be embedded in
+/// SQL string literals and quoted `haex_column_hlcs` labels unescaped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    raw: String,
+    segments: Vec<PathSegment>,
+}
+
+impl JsonPath {
+    pub fn parse(raw: &str) -> Result<Self, DatabaseError> {
+        let invalid = |detail: &str| DatabaseError::ValidationError {
+            reason: format!("Invalid JSON path '{raw}': {detail}"),
+        };
+
+        let rest = raw
+            .strip_prefix('$')
+            .ok_or_else(|| invalid("must start with '$'"))?;
+        let mut segments = Vec::new();
+        let mut chars = rest.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '.' => {
+                    let mut key = String::new();
+                    while let Some(&next) = chars.peek() {
+                        if next == '.' || next == '[' {
+                            break;
+                        }
+                        key.push(next);
+                        chars.next();
+                    }
+                    if !is_safe_identifier(&key) {
+                        return Err(invalid(
+                            "keys may only contain letters, digits, '_' and '-'",
+                        ));
+                    }
+                    segments.push(PathSegment::Key(key));
+                }
+                '[' => {
+                    let mut digits = String::new();
+                    for next in chars.by_ref() {
+                        if next == ']' {
+                            break;
+                        }
+                        digits.push(next);
+                    }
+                    let index = digits
+                        .parse::<usize>()
+                        .map_err(|_| invalid("array index must be a non-negative integer"))?;
+                    segments.push(PathSegment::Index(index));
+                }
+                _ => return Err(invalid("expected '.' or '['")),
+            }
+        }
+
+        if segments.is_empty() {
+            return Err(invalid(
+                "the root path replaces the whole column, use a plain UPDATE",
+            ));
+        }
+
+        Ok(Self {
+            raw: raw.to_string(),
+            segments,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    /// True if `self` equals `other` or is one of its ancestors.
+    pub fn covers(&self, other: &JsonPath) -> bool {
+        other.segments.starts_with(&self.segments)
+    }
+
+    pub fn get<'a>(&self, value: &'a JsonValue) -> Option<&'a JsonValue> {
+        self.segments
+            .iter()
+            .try_fold(value, |current, segment| match segment {
+                PathSegment::Key(key) => current.as_object()?.get(key),
+                PathSegment::Index(index) => current.as_array()?.get(*index),
+            })
+    }
+
+    /// Mirrors SQLite `json_set`: missing object members are created, array
+    /// indexes past the end and non-container intermediates are left alone.
+    pub fn set(&self, target: &mut JsonValue, value: JsonValue) {
+        let Some((last, parents)) = self.segments.split_last() else {
+            return;
+        };
+
+        let mut current = target;
+        for segment in parents {
+            let next = match segment {
+                PathSegment::Key(key) => current.as_object_mut().map(|object| {
+                    object
+                        .entry(key.clone())
+                        .or_insert_with(|| JsonValue::Object(Map::new()))
+                }),
+                PathSegment::Index(index) => current
+                    .as_array_mut()
+                    .and_then(|array| array.get_mut(*index)),
+            };
+            match next {
+                Some(next) => current = next,
+                None => return,
+            }
+        }
+
+        match last {
+            PathSegment::Key(key) => {
+                if let Some(object) = current.as_object_mut() {
+                    object.insert(key.clone(), value);
+                }
+            }
+            PathSegment::Index(index) => {
+                if let Some(slot) = current
+                    .as_array_mut()
+                    .and_then(|array| array.get_mut(*index))
+                {
+                    *slot = value;
+                }
+            }
+        }
+    }
+}
+
+/// One `json_set` operation of `sql_execute_json_patch`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonPatchOperation {
+    /// JSON path to set, e.g. `$.theme` or `$.layout.columns[0]`
+    pub path: String,
+    /// New value at `path`
+    #[ts(type = "unknown")]
+    pub value: JsonValue,
+}
+
+/// Builds `UPDATE "<table>" SET "<column>" = json_set(COALESCE("<column>", '{}'), …)`
+/// for one row. The CRDT transformer recognises this shape and records the
+/// touched paths.
+pub fn build_patch_update(
+    table_name: &str,
+    column: &str,
+    row_pks: &Map<String, JsonValue>,
+    patches: &[JsonPatchOperation],
+) -> Result<(String, Vec<JsonValue>), DatabaseError> {
+    if !is_safe_identifier(table_name) || !is_safe_identifier(column) {
+        return Err(DatabaseError::ValidationError {
+            reason: format!("Invalid table or column name '{table_name}.{column}'"),
+        });
+    }
+    if patches.is_empty() {
+        return Err(DatabaseError::ValidationError {
+            reason: "JSON patch needs at least one operation".to_string(),
+        });
+    }
+    let (pk_where, pk_values) =
+        build_pk_where_from_map(row_pks).ok_or_else(|| DatabaseError::ValidationError {
+            reason: "JSON patch needs the primary key of the row".to_string(),
+        })?;
+
+    let mut set_args = Vec::with_capacity(patches.len());
+    let mut params = Vec::with_capacity(patches.len() + pk_values.len());
+    for patch in patches {
+        let path = JsonPath::parse(&patch.path)?;
+        set_args.push(format!("'{}', json(?)", path.as_str()));
+        params.push(JsonValue::String(patch.value.to_string()));
+    }
+    params.extend(pk_values);
+
+    let sql = format!(
+        "UPDATE \"{table_name}\" SET \"{column}\" = json_set(COALESCE(\"{column}\", '{{}}'), {}) WHERE {pk_where}",
+        set_args.join(", ")
+    );
+    Ok((sql, params))
+}
+
+/// `haex_column_hlcs` key of a path entry.
+pub fn path_key(column: &str, path: &JsonPath) -> String {
+    format!("{column}{PATH_KEY_SEPARATOR}{}", path.as_str())
+}
+
+/// `haex_column_hlcs` key holding the base HLC of a patched column.
+pub fn base_key(column: &str) -> String {
+    format!("{column}{PATH_KEY_SEPARATOR}")
+}
+
+/// Splits `"<column>#<path>"` into its parts. Returns `None` for plain column
+/// names and for the `"<column>#"` base entry.
+pub fn split_path_key(key: &str) -> Option<(&str, &str)> {
+    key.split_once(PATH_KEY_SEPARATOR)
+        .filter(|(column, path)| !column.is_empty() && !path.is_empty())
+}
+
+/// Validates a `column_name` of a remote change: either a plain identifier or
+/// a path key whose column is an identifier and whose path parses.
+pub fn is_safe_change_column(name: &str) -> bool {
+    match split_path_key(name) {
+        Some((column, path)) => is_safe_identifier(column) && JsonPath::parse(path).is_ok(),
+        None => is_safe_identifier(name),
+    }
+}
+
+fn hlc_of<'a>(hlcs: &'a Map<String, JsonValue>, key: &str) -> Option<&'a str> {
+    hlcs.get(key).and_then(|v| v.as_str())
+}
+
+fn path_entries<'a>(
+    hlcs: &'a Map<String, JsonValue>,
+    column: &'a str,
+) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+    hlcs.iter().filter_map(move |(key, hlc)| {
+        let (entry_column, path) = split_path_key(key)?;
+        (entry_column == column).then_some((path, hlc.as_str()?))
+    })
+}
+
+/// True if the column has any path bookkeeping.
+pub fn has_path_entries(hlcs: &Map<String, JsonValue>, column: &str) -> bool {
+    hlcs.contains_key(&base_key(column)) || path_entries(hlcs, column).next().is_some()
+}
+
+/// HLC a whole-column write has to beat. Equals the column HLC unless the
+/// last write to the column was a patch.
+pub fn base_hlc(hlcs: &Map<String, JsonValue>, column: &str) -> Option<String> {
+    let column_hlc = hlc_of(hlcs, column)?;
+    if path_entries(hlcs, column).any(|(_, hlc)| hlc == column_hlc) {
+        return hlc_of(hlcs, &base_key(column)).map(str::to_string);
+    }
+    Some(column_hlc.to_string())
+}
+
+/// Path entries newer than the base, oldest first. Unparseable keys are skipped.
+pub fn live_paths(hlcs: &Map<String, JsonValue>, column: &str) -> Vec<(JsonPath, String)> {
+    let base = base_hlc(hlcs, column).unwrap_or_default();
+    let mut paths: Vec<(JsonPath, String)> = path_entries(hlcs, column)
+        .filter(|(_, hlc)| hlc_is_newer(hlc, &base))
+        .filter_map(|(path, hlc)| Some((JsonPath::parse(path).ok()?, hlc.to_string())))
+        .collect();
+    paths.sort_by(|a, b| compare_hlc_strings(&a.1, &b.1));
+    paths
+}
+
+/// Parses a JSON column's SQL value. NULL counts as `{}` (the patch command
+/// writes `json_set(COALESCE(col, '{}'), …)`); anything but JSON text is `None`.
+pub fn decode_json_column(value: &JsonValue) -> Option<JsonValue> {
+    match value {
+        JsonValue::Null => Some(JsonValue::Object(Map::new())),
+        JsonValue::String(text) => serde_json::from_str(text).ok(),
+        _ => None,
+    }
+}
+
+/// Serialises a JSON document back into the TEXT value stored in the column.
+pub fn encode_json_column(value: &JsonValue) -> JsonValue {
+    JsonValue::String(value.to_string())
+}
+
+/// Outbound path changes of one row: `(column_name, hlc, JSON text of the value)`
+/// for every live path of `column`.
+pub fn outbound_path_changes(
+    hlcs: &Map<String, JsonValue>,
+    column: &str,
+    column_value: &JsonValue,
+) -> Vec<(String, String, JsonValue)> {
+    let document = decode_json_column(column_value).unwrap_or(JsonValue::Null);
+    live_paths(hlcs, column)
+        .into_iter()
+        .map(|(path, hlc)| {
+            let value = path.get(&document).cloned().unwrap_or(JsonValue::Null);
+            (
+                path_key(column, &path),
+                hlc,
+                JsonValue::String(value.to_string()),
+            )
+        })
+        .collect()
+}
+
+/// Applies a remote path change. Returns the new column value if the change
+/// wins against the base and every local entry for the same path or one of
+/// its ancestors; `hlcs` is updated accordingly.
+pub fn apply_remote_path(
+    hlcs: &mut Map<String, JsonValue>,
+    column: &str,
+    path: &JsonPath,
+    remote_hlc: &str,
+    current_value: &JsonValue,
+    remote_value_text: &JsonValue,
+) -> Option<JsonValue> {
+    let base = base_hlc(hlcs, column).unwrap_or_default();
+    if !hlc_is_newer(remote_hlc, &base) {
+        return None;
+    }
+    let shadowed = path_entries(hlcs, column).any(|(local_path, local_hlc)| {
+        JsonPath::parse(local_path).is_ok_and(|local| local.covers(path))
+            && !hlc_is_newer(remote_hlc, local_hlc)
+    });
+    if shadowed {
+        return None;
+    }
+
+    let mut document = decode_json_column(current_value)?;
+    let value = remote_value_text
+        .as_str()
+        .and_then(|text| serde_json::from_str(text).ok())
+        .unwrap_or(JsonValue::Null);
+    path.set(&mut document, value);
+
+    let column_hlc = hlc_of(hlcs, column).unwrap_or_default().to_string();
+    if hlc_is_newer(remote_hlc, &column_hlc) {
+        hlcs.insert(base_key(column), JsonValue::String(base));
+        hlcs.insert(
+            column.to_string(),
+            JsonValue::String(remote_hlc.to_string()),
+        );
+    }
+    hlcs.insert(
+        path_key(column, path),
+        JsonValue::String(remote_hlc.to_string()),
+    );
+
+    Some(encode_json_column(&document))
+}
+
+/// Merges a remote whole-column write that already won against the base:
+/// local paths patched after `remote_hlc` are re-applied on top of the remote
+/// value, older path entries are dropped.
+pub fn merge_remote_whole(
+    hlcs: &mut Map<String, JsonValue>,
+    column: &str,
+    remote_hlc: &str,
+    remote_value: &JsonValue,
+    local_value: &JsonValue,
+) -> JsonValue {
+    let newer: Vec<(JsonPath, String)> = live_paths(hlcs, column)
+        .into_iter()
+        .filter(|(_, hlc)| hlc_is_newer(hlc, remote_hlc))
+        .collect();
+
+    hlcs.retain(
+        |key, _| !matches!(split_path_key(key), Some((entry_column, _)) if entry_column == column),
+    );
+    hlcs.insert(base_key(column), JsonValue::String(remote_hlc.to_string()));
+
+    let (Some(mut document), Some(local)) = (
+        decode_json_column(remote_value),
+        decode_json_column(local_value),
+    ) else {
+        hlcs.insert(
+            column.to_string(),
+            JsonValue::String(remote_hlc.to_string()),
+        );
+        return remote_value.clone();
+    };
+
+    let mut column_hlc = remote_hlc.to_string();
+    for (path, hlc) in newer {
+        if let Some(value) = path.get(&local) {
+            path.set(&mut document, value.clone());
+        }
+        hlcs.insert(path_key(column, &path), JsonValue::String(hlc.clone()));
+        column_hlc = hlc;
+    }
+    hlcs.insert(column.to_string(), JsonValue::String(column_hlc));
+
+    encode_json_column(&document)
+}
+
+/// SQL expression for the `haex_column_hlcs` assignment the transformer adds
+/// to a `json_set` UPDATE: stores the current base HLC under `"<column>#"`
+/// and stamps every patched path with `hlc`. The UPDATE trigger then bumps the
+/// column HLC itself to the same value.
+pub fn hlc_bookkeeping_sql(patches: &[(String, Vec<JsonPath>)], hlc: &str) -> String {
+    let mut args = vec![COLUMN_HLCS_COLUMN.to_string()];
+
+    for (column, paths) in patches {
+        let column_hlc = format!("json_extract({COLUMN_HLCS_COLUMN}, '$.\"{column}\"')");
+        let stored_base = format!(
+            "json_extract({COLUMN_HLCS_COLUMN}, '$.\"{}\"')",
+            base_key(column)
+        );
+        // Path keys of `column` sort between "<column>#" and "<column>$".
+        let last_write_was_patch = format!(
+            "EXISTS (SELECT 1 FROM json_each({COLUMN_HLCS_COLUMN}) \
+             WHERE key > '{column}#' AND key < '{column}$' AND value = {column_hlc})"
+        );
+        args.push(format!("'$.\"{}\"'", base_key(column)));
+        args.push(format!(
+            "CASE WHEN {last_write_was_patch} THEN {stored_base} ELSE {column_hlc} END"
+        ));
+        for path in paths {
+            args.push(format!("'$.\"{}\"'", path_key(column, path)));
+            args.push(format!("'{hlc}'"));
+        }
+    }
+
+    format!("json_set({})", args.join(", "))
+}
@@ -0,0 +1,282 @@
+//! Tests for JSON patches in [`super::json_patch`]: path parsing, the
+//! per-path bookkeeping the transformer adds to `json_set` UPDATEs, the
+//! scanner's path changes and the merge on the receiving device.
+//!
+//! Merge tests run two in-memory "devices" and ship changes between them
+//! through `scan_table_for_local_changes` and `apply_remote_changes_to_db`,
+//! the same pair the sync engine uses.
+
+#![cfg(test)]
+
+use serde_json::{json, Map, Value as JsonValue};
+
+use super::commands::{apply_remote_changes_to_db, RemoteColumnChange};
+use super::hlc::HlcService;
+use super::json_patch::{self, build_patch_update, JsonPatchOperation, JsonPath};
+use super::scanner::scan_table_for_local_changes;
+use crate::database::core::with_connection;
+use crate::database::DbConnection;
+use crate::extension::database::executor::SqlExecutor;
+use crate::test_support::crdt::{create_crdt_table, open_crdt_connection};
+use crate::test_support::db::db_connection;
+
+struct Device {
+    name: &'static str,
+    db: DbConnection,
+    hlc: HlcService,
+}
+
+fn setup_device(name: &'static str) -> Device {
+    let hlc = HlcService::new_for_testing(name);
+    let conn = open_crdt_connection(hlc.clone()).unwrap();
+    create_crdt_table(
+        &conn,
+        "notes",
+        "CREATE TABLE notes (id TEXT PRIMARY KEY NOT NULL, title TEXT, settings TEXT)",
+    )
+    .unwrap();
+
+    Device {
+        name,
+        db: db_connection(conn),
+        hlc,
+    }
+}
+
+fn exec(device: &Device, sql: &str, params: &[JsonValue]) {
+    with_connection(&device.db, |conn| {
+        let tx = conn.transaction()?;
+        SqlExecutor::execute_internal(&tx, &device.hlc, sql, params)?;
+        tx.commit()?;
+        Ok(())
+    })
+    .unwrap();
+}
+
+fn insert_note(device: &Device, id: &str, settings: JsonValue) {
+    exec(
+        device,
+        "INSERT INTO notes (id, title, settings) VALUES (?, 'Note', ?)",
+        &[JsonValue::from(id), JsonValue::from(settings.to_string())],
+    );
+}
+
+fn patch_note(device: &Device, id: &str, patches: &[(&str, JsonValue)]) {
+    let mut row_pks = Map::new();
+    row_pks.insert("id".to_string(), JsonValue::from(id));
+    let patches: Vec<JsonPatchOperation> = patches
+        .iter()
+        .map(|(path, value)| JsonPatchOperation {
+            path: path.to_string(),
+            value: value.clone(),
+        })
+        .collect();
+    let (sql, params) = build_patch_update("notes", "settings", &row_pks, &patches).unwrap();
+    exec(device, &sql, &params);
+}
+
+fn read_row(device: &Device, id: &str) -> (JsonValue, Map<String, JsonValue>) {
+    with_connection(&device.db, |conn| {
+        Ok(conn.query_row(
+            "SELECT settings, haex_column_hlcs FROM notes WHERE id = ?",
+            [id],
+            |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)),
+        )?)
+    })
+    .map(|(settings, hlcs)| {
+        (
+            serde_json::from_str(&settings).unwrap(),
+            serde_json::from_str(&hlcs).unwrap(),
+        )
+    })
+    .unwrap()
+}
+
+/// Ships every change of `from` newer than `after` to `to`; returns the
+/// shipped column names.
+fn sync(from: &Device, to: &Device, after: Option<&str>) -> Vec<String> {
+    let changes = with_connection(&from.db, |conn| {
+        scan_table_for_local_changes(conn, "notes", after, from.name)
+    })
+    .unwrap();
+    let columns = changes.iter().map(|c| c.column_name.clone()).collect();
+    let remote = changes
+        .into_iter()
+        .map(|c| RemoteColumnChange {
+            table_name: c.table_name,
+            row_pks: c.row_pks,
+            column_name: c.column_name,
+            hlc_timestamp: c.hlc_timestamp,
+            decrypted_value: c.value,
+        })
+        .collect();
+    apply_remote_changes_to_db(&to.db, remote, None, Some(&to.hlc)).unwrap();
+    columns
+}
+
+fn hlc_str(hlcs: &Map<String, JsonValue>, key: &str) -> String {
+    hlcs[key].as_str().unwrap().to_string()
+}
+
+#[test]
+fn test_json_path_parse_get_and_set() {
+    let path = JsonPath::parse("$.editor.tabs[1]").unwrap();
+    let doc = json!({ "editor": { "tabs": [2, 4] } });
+    assert_eq!(path.get(&doc), Some(&json!(4)));
+    assert!(JsonPath::parse("$.editor").unwrap().covers(&path));
+    assert!(!path.covers(&JsonPath::parse("$.editor").unwrap()));
+
+    for invalid in ["editor", "$", "$.a b", "$.it's", "$[x]", "$.a..b"] {
+        assert!(
+            JsonPath::parse(invalid).is_err(),
+            "{invalid} must be rejected"
+        );
+    }
+
+    let mut target = json!({});
+    JsonPath::parse("$.a.b").unwrap().set(&mut target, json!(1));
+    assert_eq!(target, json!({ "a": { "b": 1 } }));
+
+    // Like json_set: indexes past the end are ignored.
+    let mut list = json!({ "list": [1] });
+    JsonPath::parse("$.list[5]")
+        .unwrap()
+        .set(&mut list, json!(2));
+    assert_eq!(list, json!({ "list": [1] }));
+}
+
+#[test]
+fn test_build_patch_update_rejects_unsafe_input() {
+    let mut row_pks = Map::new();
+    row_pks.insert("id".to_string(), json!("n1"));
+    let op = |path: &str| JsonPatchOperation {
+        path: path.to_string(),
+        value: json!(1),
+    };
+
+    assert!(build_patch_update("notes", "settings", &row_pks, &[]).is_err());
+    assert!(build_patch_update("notes", "settings", &row_pks, &[op("$.it's")]).is_err());
+    assert!(build_patch_update("notes; --", "settings", &row_pks, &[op("$.a")]).is_err());
+    assert!(build_patch_update("notes", "settings", &Map::new(), &[op("$.a")]).is_err());
+}
+
+#[test]
+fn test_patch_stamps_only_touched_paths() {
+    let device = setup_device("device-a");
+    insert_note(&device, "n1", json!({ "theme": "light", "font": "serif" }));
+    let (_, before) = read_row(&device, "n1");
+    let insert_hlc = hlc_str(&before, "settings");
+
+    patch_note(&device, "n1", &[("$.theme", json!("dark"))]);
+
+    let (settings, hlcs) = read_row(&device, "n1");
+    assert_eq!(settings, json!({ "theme": "dark", "font": "serif" }));
+    assert_eq!(
+        hlc_str(&hlcs, "settings#$.theme"),
+        hlc_str(&hlcs, "settings")
+    );
+    assert_eq!(hlc_str(&hlcs, "settings#"), insert_hlc);
+    assert!(!hlcs.contains_key("settings#$.font"));
+    assert_eq!(json_patch::base_hlc(&hlcs, "settings"), Some(insert_hlc));
+    assert_eq!(json_patch::live_paths(&hlcs, "settings").len(), 1);
+
+    // A second patch keeps the original base.
+    patch_note(&device, "n1", &[("$.font", json!("mono"))]);
+    let (_, hlcs) = read_row(&device, "n1");
+    assert_eq!(hlcs["settings#"], before["settings"]);
+    assert_eq!(json_patch::live_paths(&hlcs, "settings").len(), 2);
+}
+
+#[test]
+fn test_scanner_ships_paths_instead_of_whole_column() {
+    let device = setup_device("device-a");
+    insert_note(&device, "n1", json!({ "theme": "light" }));
+    let (_, before) = read_row(&device, "n1");
+    let insert_hlc = hlc_str(&before, "settings");
+
+    patch_note(&device, "n1", &[("$.theme", json!("dark"))]);
+
+    let changes = with_connection(&device.db, |conn| {
+        scan_table_for_local_changes(conn, "notes", Some(&insert_hlc), device.name)
+    })
+    .unwrap();
+    assert_eq!(
+        changes.len(),
+        1,
+        "only the patched path is newer: {changes:?}"
+    );
+    assert_eq!(changes[0].column_name, "settings#$.theme");
+    assert_eq!(changes[0].value, json!("\"dark\""));
+}
+
+#[test]
+fn test_concurrent_patches_to_different_keys_merge() {
+    let a = setup_device("device-a");
+    let b = setup_device("device-b");
+    insert_note(&a, "n1", json!({ "theme": "light", "font": "serif" }));
+    sync(&a, &b, None);
+    let (_, hlcs) = read_row(&a, "n1");
+    let synced_hlc = hlc_str(&hlcs, "settings");
+
+    patch_note(&a, "n1", &[("$.theme", json!("dark"))]);
+    patch_note(&b, "n1", &[("$.font", json!("mono"))]);
+
+    assert_eq!(
+        sync(&a, &b, Some(&synced_hlc)),
+        vec!["settings#$.theme".to_string()]
+    );
+    sync(&b, &a, Some(&synced_hlc));
+
+    let expected = json!({ "theme": "dark", "font": "mono" });
+    assert_eq!(read_row(&a, "n1").0, expected);
+    assert_eq!(read_row(&b, "n1").0, expected);
+}
+
+#[test]
+fn test_newer_patch_survives_older_whole_write() {
+    let a = setup_device("device-a");
+    let b = setup_device("device-b");
+    insert_note(&a, "n1", json!({ "theme": "light", "font": "serif" }));
+    sync(&a, &b, None);
+    let (_, hlcs) = read_row(&a, "n1");
+    let synced_hlc = hlc_str(&hlcs, "settings");
+
+    exec(
+        &b,
+        "UPDATE notes SET settings = ? WHERE id = 'n1'",
+        &[JsonValue::from(json!({ "theme": "solarized" }).to_string())],
+    );
+    patch_note(&a, "n1", &[("$.font", json!("mono"))]);
+
+    sync(&b, &a, Some(&synced_hlc));
+    sync(&a, &b, Some(&synced_hlc));
+
+    let expected = json!({ "theme": "solarized", "font": "mono" });
+    assert_eq!(read_row(&a, "n1").0, expected);
+    assert_eq!(read_row(&b, "n1").0, expected);
+}
+
+#[test]
+fn test_newer_whole_write_replaces_older_patches() {
+    let a = setup_device("device-a");
+    let b = setup_device("device-b");
+    insert_note(&a, "n1", json!({ "theme": "light", "font": "serif" }));
+    sync(&a, &b, None);
+    let (_, hlcs) = read_row(&a, "n1");
+    let synced_hlc = hlc_str(&hlcs, "settings");
+
+    patch_note(&a, "n1", &[("$.font", json!("mono"))]);
+    exec(
+        &b,
+        "UPDATE notes SET settings = ? WHERE id = 'n1'",
+        &[JsonValue::from(json!({ "theme": "solarized" }).to_string())],
+    );
+
+    sync(&a, &b, Some(&synced_hlc));
+    sync(&b, &a, Some(&synced_hlc));
+
+    let expected = json!({ "theme": "solarized" });
+    assert_eq!(read_row(&a, "n1").0, expected);
+    assert_eq!(read_row(&b, "n1").0, expected);
+    assert!(json_patch::live_paths(&read_row(&a, "n1").1, "settings").is_empty());
+}
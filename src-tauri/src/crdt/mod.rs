@@ -1,13 +1,42 @@
+pub mod bulk_apply;
+pub mod cascade;
 pub mod cleanup;
 pub mod commands;
+pub mod hard_delete;
 pub mod hlc;
 pub mod insert_transformer;
+pub mod json_patch;
 //pub mod query_transformer;
+pub mod retention;
 pub mod scanner;
+pub mod sync_status;
 pub mod transformer;
+pub mod trash;
 pub mod trigger;
+pub mod undo;
+pub mod unique_conflict;
 
+#[cfg(test)]
+mod bulk_apply_tests;
+#[cfg(test)]
+mod cascade_tests;
+#[cfg(test)]
+mod hard_delete_tests;
 #[cfg(test)]
 mod hlc_node_tests;
 #[cfg(test)]
+mod json_patch_tests;
+#[cfg(test)]
+mod retention_tests;
+#[cfg(test)]
 mod scanner_origin_tests;
+#[cfg(test)]
+mod sync_status_tests;
+#[cfg(test)]
+mod trash_tests;
+#[cfg(test)]
+mod undo_tests;
+#[cfg(test)]
+mod unique_conflict_tests;
+#[cfg(test)]
+mod upsert_tests;
@@ -0,0 +1,484 @@
+// src-tauri/src/crdt/retention.rs
+//!
+//! Data retention rules per table.
+//!
+//! A rule deletes the rows of one table whose timestamp column is older than
+//! `maxAgeDays`, e.g. to keep a log-style extension table from growing
+//! forever. [`run_retention_loop`] applies all enabled rules every
+//! [`RETENTION_INTERVAL`]; `crdt_preview_retention` shows what a rule would
+//! delete without touching anything.
+//!
+//! Rows are deleted through the CRDT executor, so the deletes get a fresh HLC
+//! and sync like any other local delete (tables flagged in
+//! `crdt::hard_delete` skip the delete-log as usual). A run deletes at most
+//! [`MAX_DELETES_PER_RUN`] rows per rule, oldest first, so a large backlog
+//! is worked off over several runs instead of blocking the connection.
+//!
+//! Rules and their statistics are a per-device setting in haex_crdt_configs
+//! (local-only).
+
+use crate::crdt::hlc::HlcService;
+use crate::crdt::trash::TRASH_TABLE;
+use crate::crdt::trigger::{get_table_schema, is_safe_identifier, DELETED_ROWS_TABLE};
+use crate::database::constants::vault_settings_key;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::events::{self, payloads::DirtyTablesChanged};
+use crate::extension::database::executor::SqlExecutor;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, TABLE_CRDT_CONFIGS,
+};
+use crate::AppState;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+use ts_rs::TS;
+
+/// How often the background loop applies the enabled rules.
+pub const RETENTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Upper bound of rows one rule deletes per run.
+pub const MAX_DELETES_PER_RUN: u32 = 10_000;
+
+/// `type` column value of the rules row
+const CONFIG_TYPE: &str = "system";
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// How the timestamp column stores its values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum RetentionTimestampFormat {
+    /// ISO 8601 / RFC 3339 text, as understood by SQLite's `julianday()`
+    Iso8601,
+    UnixSeconds,
+    UnixMillis,
+}
+
+/// Statistics of a rule's past runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionRuleStats {
+    pub last_run_at: Option<String>,
+    /// Rows deleted by the last run
+    #[ts(type = "number")]
+    pub last_deleted: u64,
+    /// Rows deleted since the rule was created
+    #[ts(type = "number")]
+    pub total_deleted: u64,
+    pub last_error: Option<String>,
+}
+
+/// Deletes rows of `tableName` whose `timestampColumn` is older than
+/// `maxAgeDays`. Rows without a timestamp are kept.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionRule {
+    /// Assigned on save; leave empty for a new rule
+    #[serde(default)]
+    pub id: String,
+    pub table_name: String,
+    pub timestamp_column: String,
+    pub timestamp_format: RetentionTimestampFormat,
+    pub max_age_days: u32,
+    pub enabled: bool,
+    /// Maintained by the runs; ignored on save
+    #[serde(default)]
+    pub stats: RetentionRuleStats,
+}
+
+/// What a rule would delete right now (dry run).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPreview {
+    pub rule_id: String,
+    pub table_name: String,
+    /// Rows older than the cutoff
+    #[ts(type = "number")]
+    pub matching_rows: u64,
+    #[ts(type = "number")]
+    pub total_rows: u64,
+    /// Rows the next run deletes (capped at `MAX_DELETES_PER_RUN`)
+    #[ts(type = "number")]
+    pub next_run_deletes: u64,
+}
+
+/// Outcome of one rule in a run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionRunResult {
+    pub rule_id: String,
+    pub table_name: String,
+    #[ts(type = "number")]
+    pub deleted: u64,
+    pub error: Option<String>,
+}
+
+impl RetentionRule {
+    /// WHERE condition selecting the expired rows; `?1` is the cutoff in
+    /// unix seconds.
+    fn expired_condition(&self) -> String {
+        let column = &self.timestamp_column;
+        match self.timestamp_format {
+            RetentionTimestampFormat::Iso8601 => {
+                format!("julianday(\"{column}\") < julianday(?1, 'unixepoch')")
+            }
+            RetentionTimestampFormat::UnixSeconds => format!("\"{column}\" < ?1"),
+            RetentionTimestampFormat::UnixMillis => format!("\"{column}\" < ?1 * 1000"),
+        }
+    }
+
+    fn cutoff(&self, now: i64) -> i64 {
+        now - i64::from(self.max_age_days) * SECONDS_PER_DAY
+    }
+
+    /// The oldest expired rows a run deletes
+    fn batch_sql(&self) -> String {
+        format!(
+            "SELECT rowid FROM \"{table}\" WHERE {condition} ORDER BY \"{column}\" LIMIT {MAX_DELETES_PER_RUN}",
+            table = self.table_name,
+            condition = self.expired_condition(),
+            column = self.timestamp_column,
+        )
+    }
+}
+
+pub fn load_rules(conn: &Connection) -> Result<Vec<RetentionRule>, DatabaseError> {
+    let value: Option<String> = conn
+        .query_row(
+            &format!(
+                "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"
+            ),
+            params![vault_settings_key::RETENTION_RULES],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match value {
+        None => Ok(Vec::new()),
+        Some(json) => serde_json::from_str(&json).map_err(|e| DatabaseError::SerializationError {
+            reason: format!("Invalid retention rules: {e}"),
+        }),
+    }
+}
+
+pub fn save_rules(conn: &Connection, rules: &[RetentionRule]) -> Result<(), DatabaseError> {
+    let json = serde_json::to_string(rules).map_err(|e| DatabaseError::SerializationError {
+        reason: e.to_string(),
+    })?;
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?, ?, ?)"
+        ),
+        params![vault_settings_key::RETENTION_RULES, CONFIG_TYPE, json],
+    )?;
+    Ok(())
+}
+
+/// Checks that the rule targets an existing table and column.
+pub fn validate_rule(conn: &Connection, rule: &RetentionRule) -> Result<(), DatabaseError> {
+    let invalid = |reason: String| Err(DatabaseError::ValidationError { reason });
+
+    if !is_safe_identifier(&rule.table_name)
+        || [DELETED_ROWS_TABLE, TRASH_TABLE, TABLE_CRDT_CONFIGS].contains(&rule.table_name.as_str())
+    {
+        return invalid(format!("Invalid table for retention: {}", rule.table_name));
+    }
+    if !is_safe_identifier(&rule.timestamp_column) {
+        return invalid(format!("Invalid column: {}", rule.timestamp_column));
+    }
+    if rule.max_age_days == 0 {
+        return invalid("maxAgeDays must be at least 1".to_string());
+    }
+
+    let schema = get_table_schema(conn, &rule.table_name)?;
+    if schema.is_empty() {
+        return invalid(format!("Table not found: {}", rule.table_name));
+    }
+    if !schema.iter().any(|c| c.name == rule.timestamp_column) {
+        return invalid(format!(
+            "Column {} not found in {}",
+            rule.timestamp_column, rule.table_name
+        ));
+    }
+    Ok(())
+}
+
+/// Adds a rule or replaces the one with the same id. Statistics of an
+/// existing rule are kept.
+pub fn save_rule(
+    conn: &Connection,
+    mut rule: RetentionRule,
+) -> Result<RetentionRule, DatabaseError> {
+    validate_rule(conn, &rule)?;
+    let mut rules = load_rules(conn)?;
+    match rules
+        .iter_mut()
+        .find(|r| !rule.id.is_empty() && r.id == rule.id)
+    {
+        Some(existing) => {
+            rule.stats = existing.stats.clone();
+            *existing = rule.clone();
+        }
+        None => {
+            rule.id = uuid::Uuid::new_v4().to_string();
+            rule.stats = RetentionRuleStats::default();
+            rules.push(rule.clone());
+        }
+    }
+    save_rules(conn, &rules)?;
+    Ok(rule)
+}
+
+/// Removes a rule. Returns whether it existed.
+pub fn remove_rule(conn: &Connection, rule_id: &str) -> Result<bool, DatabaseError> {
+    let mut rules = load_rules(conn)?;
+    let before = rules.len();
+    rules.retain(|r| r.id != rule_id);
+    if rules.len() == before {
+        return Ok(false);
+    }
+    save_rules(conn, &rules)?;
+    Ok(true)
+}
+
+/// Counts what `rule` would delete at `now` (unix seconds).
+pub fn preview_rule(
+    conn: &Connection,
+    rule: &RetentionRule,
+    now: i64,
+) -> Result<RetentionPreview, DatabaseError> {
+    validate_rule(conn, rule)?;
+    let cutoff = rule.cutoff(now);
+    let matching_rows: i64 = conn.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM \"{}\" WHERE {}",
+            rule.table_name,
+            rule.expired_condition()
+        ),
+        params![cutoff],
+        |row| row.get(0),
+    )?;
+    let total_rows: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM \"{}\"", rule.table_name),
+        [],
+        |row| row.get(0),
+    )?;
+    let matching_rows = u64::try_from(matching_rows).unwrap_or_default();
+    Ok(RetentionPreview {
+        rule_id: rule.id.clone(),
+        table_name: rule.table_name.clone(),
+        matching_rows,
+        total_rows: u64::try_from(total_rows).unwrap_or_default(),
+        next_run_deletes: matching_rows.min(u64::from(MAX_DELETES_PER_RUN)),
+    })
+}
+
+/// Deletes up to [`MAX_DELETES_PER_RUN`] expired rows of `rule` in one
+/// transaction. Returns the number of deleted rows.
+fn apply_rule(
+    conn: &mut Connection,
+    hlc_service: &HlcService,
+    rule: &RetentionRule,
+    now: i64,
+) -> Result<u64, DatabaseError> {
+    validate_rule(conn, rule)?;
+    let cutoff = rule.cutoff(now);
+    let batch = rule.batch_sql();
+
+    let tx = conn.transaction()?;
+    let deleted: i64 = tx.query_row(
+        &format!("SELECT COUNT(*) FROM ({batch})"),
+        params![cutoff],
+        |row| row.get(0),
+    )?;
+    if deleted > 0 {
+        SqlExecutor::execute_internal_typed(
+            &tx,
+            hlc_service,
+            &format!(
+                "DELETE FROM \"{}\" WHERE rowid IN ({batch})",
+                rule.table_name
+            ),
+            &[&cutoff],
+        )?;
+    }
+    tx.commit()?;
+    Ok(u64::try_from(deleted).unwrap_or_default())
+}
+
+/// Applies the enabled rules (or only `rule_id`, enabled or not) and
+/// records their statistics. A failing rule doesn't stop the others.
+pub fn run_rules(
+    conn: &mut Connection,
+    hlc_service: &HlcService,
+    rule_id: Option<&str>,
+    now: i64,
+) -> Result<Vec<RetentionRunResult>, DatabaseError> {
+    let mut rules = load_rules(conn)?;
+    let run_at = time::OffsetDateTime::from_unix_timestamp(now)
+        .ok()
+        .and_then(|t| {
+            t.format(&time::format_description::well_known::Rfc3339)
+                .ok()
+        });
+
+    let mut results = Vec::new();
+    for rule in rules.iter_mut() {
+        let selected = match rule_id {
+            Some(id) => rule.id == id,
+            None => rule.enabled,
+        };
+        if !selected {
+            continue;
+        }
+
+        let outcome = apply_rule(conn, hlc_service, rule, now);
+        rule.stats.last_run_at = run_at.clone();
+        let (deleted, error) = match outcome {
+            Ok(deleted) => (deleted, None),
+            Err(e) => {
+                eprintln!(
+                    "[Retention] Rule {} on {} failed: {}",
+                    rule.id, rule.table_name, e
+                );
+                (0, Some(e.to_string()))
+            }
+        };
+        rule.stats.last_deleted = deleted;
+        rule.stats.total_deleted += deleted;
+        rule.stats.last_error = error.clone();
+        if deleted > 0 {
+            println!(
+                "[Retention] Deleted {} expired rows from {}",
+                deleted, rule.table_name
+            );
+        }
+        results.push(RetentionRunResult {
+            rule_id: rule.id.clone(),
+            table_name: rule.table_name.clone(),
+            deleted,
+            error,
+        });
+    }
+
+    if !results.is_empty() {
+        save_rules(conn, &rules)?;
+    }
+    Ok(results)
+}
+
+fn now_unix() -> i64 {
+    time::OffsetDateTime::now_utc().unix_timestamp()
+}
+
+/// Background task that runs the enabled rules every [`RETENTION_INTERVAL`].
+/// A run is skipped when no vault is open or the connection is busy.
+pub async fn run_retention_loop(app_handle: AppHandle) {
+    let mut interval = tokio::time::interval(RETENTION_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+        let state = app_handle.state::<AppState>();
+
+        // Busy or poisoned connection: skip this round.
+        let Ok(mut guard) = state.db.0.try_lock() else {
+            continue;
+        };
+        let Some(conn) = guard.as_mut() else {
+            continue;
+        };
+        let Ok(hlc_service) = state.hlc.try_lock() else {
+            continue;
+        };
+
+        match run_rules(conn, &hlc_service, None, now_unix()) {
+            Ok(results) => {
+                if results.iter().any(|r| r.deleted > 0) {
+                    let _ = events::emit_to_main(&app_handle, &DirtyTablesChanged {});
+                }
+            }
+            Err(e) => eprintln!("[Retention] Run failed: {e}"),
+        }
+    }
+}
+
+/// Lists the retention rules with their statistics
+#[tauri::command]
+pub fn crdt_list_retention_rules(
+    state: State<'_, AppState>,
+) -> Result<Vec<RetentionRule>, DatabaseError> {
+    with_connection(&state.db, |conn| load_rules(conn))
+}
+
+/// Adds a retention rule (empty `id`) or updates an existing one
+#[tauri::command]
+pub fn crdt_save_retention_rule(
+    rule: RetentionRule,
+    state: State<'_, AppState>,
+) -> Result<RetentionRule, DatabaseError> {
+    with_connection(&state.db, |conn| save_rule(conn, rule))
+}
+
+/// Removes a retention rule
+#[tauri::command(rename_all = "camelCase")]
+pub fn crdt_remove_retention_rule(
+    rule_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool, DatabaseError> {
+    with_connection(&state.db, |conn| remove_rule(conn, &rule_id))
+}
+
+/// Dry run: what the rules (or `ruleId`, or an unsaved `rule`) would delete now
+#[tauri::command(rename_all = "camelCase")]
+pub fn crdt_preview_retention(
+    rule_id: Option<String>,
+    rule: Option<RetentionRule>,
+    state: State<'_, AppState>,
+) -> Result<Vec<RetentionPreview>, DatabaseError> {
+    with_connection(&state.db, |conn| {
+        let now = now_unix();
+        if let Some(rule) = rule {
+            return Ok(vec![preview_rule(conn, &rule, now)?]);
+        }
+        load_rules(conn)?
+            .iter()
+            .filter(|r| match &rule_id {
+                Some(id) => &r.id == id,
+                None => r.enabled,
+            })
+            .map(|r| preview_rule(conn, r, now))
+            .collect()
+    })
+}
+
+/// Runs the enabled rules (or only `ruleId`) now instead of waiting for the
+/// background loop
+#[tauri::command(rename_all = "camelCase")]
+pub fn crdt_run_retention(
+    app_handle: AppHandle,
+    rule_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<RetentionRunResult>, DatabaseError> {
+    let hlc_service = state.lock_or_fail(
+        &state.hlc,
+        crate::critical::CriticalFailureCode::HlcMutexPoisoned,
+        "crdt::retention::crdt_run_retention",
+        serde_json::json!({}),
+    )?;
+    let results = with_connection(&state.db, |conn| {
+        run_rules(conn, &hlc_service, rule_id.as_deref(), now_unix())
+    })?;
+
+    if results.iter().any(|r| r.deleted > 0) {
+        let _ = events::emit_to_main(&app_handle, &DirtyTablesChanged {});
+    }
+    Ok(results)
+}
@@ -0,0 +1,277 @@
+//! Tests for the retention rules in [`super::retention`]: validation, dry-run
+//! preview, and runs that delete only expired rows through the delete-log
+//! while keeping per-rule statistics.
+
+#![cfg(test)]
+
+use rusqlite::Connection;
+
+use super::hlc::HlcService;
+use super::retention::{
+    load_rules, preview_rule, remove_rule, run_rules, save_rule, RetentionRule,
+    RetentionTimestampFormat, MAX_DELETES_PER_RUN,
+};
+use super::trigger::DELETED_ROWS_TABLE;
+use crate::database::error::DatabaseError;
+use crate::test_support::crdt::{create_crdt_table, open_crdt_connection};
+
+/// 2026-01-01T00:00:00Z
+const NOW: i64 = 1_767_225_600;
+const DAY: i64 = 24 * 60 * 60;
+
+fn setup_db() -> (Connection, HlcService) {
+    let hlc = HlcService::new_for_testing("retention-test-device");
+    let conn = open_crdt_connection(hlc.clone()).unwrap();
+    create_crdt_table(
+        &conn,
+        "logs",
+        "CREATE TABLE logs (id TEXT PRIMARY KEY NOT NULL, message TEXT, created_at TEXT, created_ms INTEGER)",
+    )
+    .unwrap();
+
+    (conn, hlc)
+}
+
+/// Inserts a log row `age_days` before [`NOW`] in both timestamp formats
+fn insert_log(conn: &Connection, id: &str, age_days: i64) {
+    let at = NOW - age_days * DAY;
+    conn.execute(
+        "INSERT INTO logs (id, message, created_at, created_ms)
+         VALUES (?1, 'entry', strftime('%Y-%m-%dT%H:%M:%SZ', ?2, 'unixepoch'), ?2 * 1000)",
+        rusqlite::params![id, at],
+    )
+    .unwrap();
+}
+
+fn rule(column: &str, format: RetentionTimestampFormat, max_age_days: u32) -> RetentionRule {
+    RetentionRule {
+        id: String::new(),
+        table_name: "logs".to_string(),
+        timestamp_column: column.to_string(),
+        timestamp_format: format,
+        max_age_days,
+        enabled: true,
+        stats: Default::default(),
+    }
+}
+
+fn log_ids(conn: &Connection) -> Vec<String> {
+    let mut stmt = conn.prepare("SELECT id FROM logs ORDER BY id").unwrap();
+    stmt.query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+fn logged_deletes(conn: &Connection) -> i64 {
+    conn.query_row(
+        &format!("SELECT COUNT(*) FROM {DELETED_ROWS_TABLE} WHERE table_name = 'logs'"),
+        [],
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_invalid_rules_are_rejected() {
+    let (conn, _hlc) = setup_db();
+
+    let mut unknown_table = rule("created_at", RetentionTimestampFormat::Iso8601, 30);
+    unknown_table.table_name = "missing".to_string();
+    let mut unsafe_table = rule("created_at", RetentionTimestampFormat::Iso8601, 30);
+    unsafe_table.table_name = "logs; DROP TABLE logs".to_string();
+    let mut internal_table = rule("created_at", RetentionTimestampFormat::Iso8601, 30);
+    internal_table.table_name = DELETED_ROWS_TABLE.to_string();
+
+    for invalid in [
+        unknown_table,
+        unsafe_table,
+        internal_table,
+        rule("missing", RetentionTimestampFormat::Iso8601, 30),
+        rule("created_at", RetentionTimestampFormat::Iso8601, 0),
+    ] {
+        assert!(
+            matches!(
+                save_rule(&conn, invalid.clone()),
+                Err(DatabaseError::ValidationError { .. })
+            ),
+            "{invalid:?}"
+        );
+    }
+    assert!(load_rules(&conn).unwrap().is_empty());
+}
+
+#[test]
+fn test_save_update_and_remove_rule() {
+    let (mut conn, hlc) = setup_db();
+    insert_log(&conn, "a", 40);
+
+    let saved = save_rule(
+        &conn,
+        rule("created_at", RetentionTimestampFormat::Iso8601, 30),
+    )
+    .unwrap();
+    assert!(!saved.id.is_empty());
+    run_rules(&mut conn, &hlc, None, NOW).unwrap();
+
+    // An update keeps the statistics of the stored rule
+    let mut update = saved.clone();
+    update.max_age_days = 7;
+    update.stats = Default::default();
+    let updated = save_rule(&conn, update).unwrap();
+    assert_eq!(updated.id, saved.id);
+    assert_eq!(updated.stats.total_deleted, 1);
+
+    let rules = load_rules(&conn).unwrap();
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].max_age_days, 7);
+
+    assert!(remove_rule(&conn, &saved.id).unwrap());
+    assert!(!remove_rule(&conn, &saved.id).unwrap());
+    assert!(load_rules(&conn).unwrap().is_empty());
+}
+
+#[test]
+fn test_preview_does_not_delete() {
+    let (conn, _hlc) = setup_db();
+    insert_log(&conn, "a", 100);
+    insert_log(&conn, "b", 31);
+    insert_log(&conn, "c", 29);
+    insert_log(&conn, "d", 0);
+
+    let preview = preview_rule(
+        &conn,
+        &rule("created_at", RetentionTimestampFormat::Iso8601, 30),
+        NOW,
+    )
+    .unwrap();
+    assert_eq!(preview.matching_rows, 2);
+    assert_eq!(preview.total_rows, 4);
+    assert_eq!(preview.next_run_deletes, 2);
+    assert_eq!(log_ids(&conn).len(), 4);
+}
+
+#[test]
+fn test_run_deletes_expired_rows_through_the_delete_log() {
+    for (column, format) in [
+        ("created_at", RetentionTimestampFormat::Iso8601),
+        ("created_ms", RetentionTimestampFormat::UnixMillis),
+    ] {
+        let (mut conn, hlc) = setup_db();
+        insert_log(&conn, "a", 100);
+        insert_log(&conn, "b", 31);
+        insert_log(&conn, "c", 29);
+        conn.execute(
+            "INSERT INTO logs (id, message) VALUES ('undated', 'no timestamp')",
+            [],
+        )
+        .unwrap();
+        save_rule(&conn, rule(column, format, 30)).unwrap();
+
+        let results = run_rules(&mut conn, &hlc, None, NOW).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].deleted, 2, "{column}");
+        assert!(results[0].error.is_none());
+
+        // Rows without a timestamp are kept
+        assert_eq!(log_ids(&conn), vec!["c", "undated"]);
+        assert_eq!(logged_deletes(&conn), 2);
+    }
+}
+
+#[test]
+fn test_unix_seconds_rule() {
+    let (mut conn, hlc) = setup_db();
+    conn.execute_batch("ALTER TABLE logs ADD COLUMN created_s INTEGER;")
+        .unwrap();
+    insert_log(&conn, "a", 10);
+    insert_log(&conn, "b", 1);
+    conn.execute("UPDATE logs SET created_s = created_ms / 1000", [])
+        .unwrap();
+    save_rule(
+        &conn,
+        rule("created_s", RetentionTimestampFormat::UnixSeconds, 5),
+    )
+    .unwrap();
+
+    let results = run_rules(&mut conn, &hlc, None, NOW).unwrap();
+    assert_eq!(results[0].deleted, 1);
+    assert_eq!(log_ids(&conn), vec!["b"]);
+}
+
+#[test]
+fn test_run_records_statistics_and_skips_disabled_rules() {
+    let (mut conn, hlc) = setup_db();
+    insert_log(&conn, "a", 40);
+    let mut disabled = rule("created_at", RetentionTimestampFormat::Iso8601, 30);
+    disabled.enabled = false;
+    let disabled = save_rule(&conn, disabled).unwrap();
+
+    // The scheduled run only applies enabled rules
+    assert!(run_rules(&mut conn, &hlc, None, NOW).unwrap().is_empty());
+    assert_eq!(log_ids(&conn), vec!["a"]);
+
+    // An explicit run applies the rule regardless
+    let results = run_rules(&mut conn, &hlc, Some(&disabled.id), NOW).unwrap();
+    assert_eq!(results[0].deleted, 1);
+
+    insert_log(&conn, "b", 50);
+    run_rules(&mut conn, &hlc, Some(&disabled.id), NOW).unwrap();
+    run_rules(&mut conn, &hlc, Some(&disabled.id), NOW).unwrap();
+
+    let stats = &load_rules(&conn).unwrap()[0].stats;
+    assert_eq!(stats.last_deleted, 0);
+    assert_eq!(stats.total_deleted, 2);
+    assert_eq!(stats.last_run_at.as_deref(), Some("2026-01-01T00:00:00Z"));
+    assert!(stats.last_error.is_none());
+}
+
+#[test]
+fn test_failing_rule_records_the_error() {
+    let (mut conn, hlc) = setup_db();
+    save_rule(
+        &conn,
+        rule("created_at", RetentionTimestampFormat::Iso8601, 30),
+    )
+    .unwrap();
+    conn.execute_batch("DROP TABLE logs;").unwrap();
+
+    let results = run_rules(&mut conn, &hlc, None, NOW).unwrap();
+    assert_eq!(results[0].deleted, 0);
+    assert!(results[0].error.is_some());
+    assert!(load_rules(&conn).unwrap()[0].stats.last_error.is_some());
+}
+
+#[test]
+fn test_run_is_capped_and_deletes_the_oldest_first() {
+    let (mut conn, hlc) = setup_db();
+    let backlog = i64::from(MAX_DELETES_PER_RUN) + 5;
+    {
+        let tx = conn.transaction().unwrap();
+        for i in 0..backlog {
+            insert_log(&tx, &format!("{i:06}"), 100 + i);
+        }
+        tx.commit().unwrap();
+    }
+    let saved = save_rule(
+        &conn,
+        rule("created_ms", RetentionTimestampFormat::UnixMillis, 30),
+    )
+    .unwrap();
+    assert_eq!(
+        preview_rule(&conn, &saved, NOW).unwrap().next_run_deletes,
+        u64::from(MAX_DELETES_PER_RUN)
+    );
+
+    let results = run_rules(&mut conn, &hlc, None, NOW).unwrap();
+    assert_eq!(results[0].deleted, u64::from(MAX_DELETES_PER_RUN));
+    // The youngest rows are left for the next run
+    assert_eq!(
+        log_ids(&conn),
+        vec!["000000", "000001", "000002", "000003", "000004"]
+    );
+
+    let results = run_rules(&mut conn, &hlc, None, NOW).unwrap();
+    assert_eq!(results[0].deleted, 5);
+    assert!(log_ids(&conn).is_empty());
+}
@@ -4,7 +4,8 @@
 //! It produces unencrypted column-level changes for local space sync over QUIC,
 //! which provides transport encryption.
 
-use crate::crdt::hlc::hlc_is_newer;
+use crate::crdt::hlc::{compare_hlc_strings, hlc_is_newer};
+use crate::crdt::json_patch;
 use crate::crdt::trigger::{get_table_schema, ColumnInfo, COLUMN_HLCS_COLUMN, HLC_TIMESTAMP_COLUMN};
 use crate::database::core::{convert_value_ref_to_json, with_connection};
 use crate::database::error::DatabaseError;
@@ -223,9 +224,9 @@ pub fn scan_table_for_local_changes_scoped(
         }
 
         // Parse haex_column_hlcs JSON
-        let column_hlcs: HashMap<String, String> = match row_map.get(COLUMN_HLCS_COLUMN) {
+        let column_hlcs: serde_json::Map<String, JsonValue> = match row_map.get(COLUMN_HLCS_COLUMN) {
             Some(JsonValue::String(s)) => serde_json::from_str(s).unwrap_or_default(),
-            _ => HashMap::new(),
+            _ => serde_json::Map::new(),
         };
 
         // Build PK JSON string
@@ -245,19 +246,10 @@ pub fn scan_table_for_local_changes_scoped(
             _ => None,
         };
 
-        // For each data column, emit a change if its HLC > after_hlc
-        for col in &data_columns {
-            let col_hlc = column_hlcs.get(&col.name).map(|s| s.as_str());
-            let hlc_to_use = col_hlc.or(row_hlc);
-
-            let hlc_to_use = match hlc_to_use {
-                Some(h) => h,
-                None => continue, // no HLC at all — skip
-            };
-
+        let passes_filters = |hlc: &str| {
             // Check if this column's HLC is newer than after_hlc
             let passes_hlc = match after_hlc {
-                Some(threshold) => hlc_is_newer(hlc_to_use, threshold),
+                Some(threshold) => hlc_is_newer(hlc, threshold),
                 None => true,
             };
 
@@ -265,16 +257,31 @@ pub fn scan_table_for_local_changes_scoped(
             // wrote ourselves. Rows applied from inbound sync carry the
             // remote peer's node-id and must not be pushed back.
             let passes_origin = match origin_node_filter {
-                Some(our_node) => crate::crdt::hlc::hlc_is_from_node(hlc_to_use, our_node),
+                Some(our_node) => crate::crdt::hlc::hlc_is_from_node(hlc, our_node),
                 None => true,
             };
 
-            if passes_hlc && passes_origin {
-                let value = row_map
-                    .get(col.name.as_str())
-                    .cloned()
-                    .unwrap_or(JsonValue::Null);
+            passes_hlc && passes_origin
+        };
 
+        // For each data column, emit a change if its HLC > after_hlc
+        for col in &data_columns {
+            // A column patched via json_set carries the HLC of its last
+            // whole write as base; the patched paths are emitted below.
+            let col_hlc = json_patch::base_hlc(&column_hlcs, &col.name);
+            let hlc_to_use = match col_hlc.as_deref().or(row_hlc) {
+                Some(h) => h,
+                None => continue, // no HLC at all — skip
+            };
+
+            let value = row_map
+                .get(col.name.as_str())
+                .cloned()
+                .unwrap_or(JsonValue::Null);
+
+            let path_changes = json_patch::outbound_path_changes(&column_hlcs, &col.name, &value);
+
+            if passes_filters(hlc_to_use) {
                 changes.push(LocalColumnChange {
                     table_name: table_name.to_string(),
                     row_pks: pk_json.clone(),
@@ -284,6 +291,19 @@ pub fn scan_table_for_local_changes_scoped(
                     device_id: device_id.to_string(),
                 });
             }
+
+            for (column_name, path_hlc, path_value) in path_changes {
+                if passes_filters(&path_hlc) {
+                    changes.push(LocalColumnChange {
+                        table_name: table_name.to_string(),
+                        row_pks: pk_json.clone(),
+                        column_name,
+                        hlc_timestamp: path_hlc,
+                        value: path_value,
+                        device_id: device_id.to_string(),
+                    });
+                }
+            }
         }
     }
 
@@ -353,6 +373,55 @@ pub fn scan_membership_tables_for_local_changes(
         })
 }
 
+/// Pending outbound changes of one table, as the push scanner would emit
+/// them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PendingChangeSummary {
+    pub change_count: u64,
+    pub oldest_hlc: Option<String>,
+    pub newest_hlc: Option<String>,
+    /// Plaintext size of table name, PKs, column name, HLC and JSON value of
+    /// every change. Encryption and transport framing add to this.
+    pub estimated_bytes: u64,
+}
+
+/// Counts the column changes of `table_name` newer than `after_hlc` (all
+/// changes when `None`) and estimates their payload size. Uses the same scan
+/// as the push, so JSON path changes are included.
+pub fn summarize_pending_changes(
+    conn: &Connection,
+    table_name: &str,
+    after_hlc: Option<&str>,
+) -> Result<PendingChangeSummary, DatabaseError> {
+    let changes = scan_table_for_local_changes_scoped(conn, table_name, after_hlc, "", None, None)?;
+
+    let mut summary = PendingChangeSummary::default();
+    for change in changes {
+        summary.change_count += 1;
+        summary.estimated_bytes += (change.table_name.len()
+            + change.row_pks.len()
+            + change.column_name.len()
+            + change.hlc_timestamp.len()
+            + change.value.to_string().len()) as u64;
+
+        if summary
+            .oldest_hlc
+            .as_deref()
+            .is_none_or(|oldest| compare_hlc_strings(&change.hlc_timestamp, oldest).is_lt())
+        {
+            summary.oldest_hlc = Some(change.hlc_timestamp.clone());
+        }
+        if summary
+            .newest_hlc
+            .as_deref()
+            .is_none_or(|newest| compare_hlc_strings(&change.hlc_timestamp, newest).is_gt())
+        {
+            summary.newest_hlc = Some(change.hlc_timestamp);
+        }
+    }
+    Ok(summary)
+}
+
 // `scan_all_crdt_tables_for_local_changes` used to scan every CRDT table
 // without a space filter. That function powered the old peer SyncPull and
 // was the root of a cross-space data leak — a peer asking for space X
@@ -717,4 +786,55 @@ mod tests {
 
         assert!(changes.is_empty());
     }
+
+    #[test]
+    fn test_summarize_pending_changes_counts_sizes_and_hlc_range() {
+        let conn = setup_test_db();
+        insert_row(&conn, "a", "x", 1, "2000000000000000000/aabbccdd");
+        insert_row(&conn, "b", "yy", 22, "3000000000000000000/aabbccdd");
+        insert_row(&conn, "old", "z", 3, "1000000000000000000/aabbccdd");
+
+        let summary =
+            summarize_pending_changes(&conn, "test_items", Some("1500000000000000000/aabbccdd"))
+                .unwrap();
+        assert_eq!(summary.change_count, 4);
+        assert_eq!(
+            summary.oldest_hlc.as_deref(),
+            Some("2000000000000000000/aabbccdd")
+        );
+        assert_eq!(
+            summary.newest_hlc.as_deref(),
+            Some("3000000000000000000/aabbccdd")
+        );
+
+        let changes = scan_table_for_local_changes(
+            &conn,
+            "test_items",
+            Some("1500000000000000000/aabbccdd"),
+            "",
+        )
+        .unwrap();
+        let expected: usize = changes
+            .iter()
+            .map(|c| {
+                c.table_name.len()
+                    + c.row_pks.len()
+                    + c.column_name.len()
+                    + c.hlc_timestamp.len()
+                    + c.value.to_string().len()
+            })
+            .sum();
+        assert_eq!(summary.estimated_bytes, expected as u64);
+    }
+
+    #[test]
+    fn test_summarize_pending_changes_on_clean_table() {
+        let conn = setup_test_db();
+        insert_row(&conn, "a", "x", 1, "1000000000000000000/aabbccdd");
+
+        let summary =
+            summarize_pending_changes(&conn, "test_items", Some("2000000000000000000/aabbccdd"))
+                .unwrap();
+        assert_eq!(summary, PendingChangeSummary::default());
+    }
 }
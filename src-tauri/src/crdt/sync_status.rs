@@ -0,0 +1,197 @@
+//! Per-peer sync cursors in `haex_crdt_sync_status_no_sync`.
+//!
+//! Every peer this device syncs with — a server backend (keyed by its
+//! backend id) or the leader of a local space (keyed by
+//! [`local_space_peer_id`]) — gets one row with the HLC of the last change
+//! pushed to it and the last change pulled from it. Push paths scan from the
+//! push cursor, so each peer only receives what it hasn't seen yet.
+//!
+//! The `advance_*` functions only move a cursor forward and are what the
+//! sync paths call after a successful push or pull. [`set_push_cursor`]
+//! overwrites it unconditionally, e.g. to force a full re-push.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::crdt::hlc::hlc_is_newer;
+use crate::database::error::DatabaseError;
+use crate::table_names::{
+    COL_CRDT_SYNC_STATUS_ERROR, COL_CRDT_SYNC_STATUS_LAST_PULL_HLC_TIMESTAMP,
+    COL_CRDT_SYNC_STATUS_LAST_PUSH_HLC_TIMESTAMP, COL_CRDT_SYNC_STATUS_LAST_SYNC_AT,
+    COL_CRDT_SYNC_STATUS_PEER_ID, TABLE_CRDT_SYNC_STATUS,
+};
+
+/// Sync state of one peer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    pub peer_id: String,
+    /// HLC of the newest change pushed to the peer.
+    pub last_push_hlc_timestamp: Option<String>,
+    /// HLC of the newest change pulled from the peer.
+    pub last_pull_hlc_timestamp: Option<String>,
+    /// When a push or pull with the peer last succeeded (UTC, SQLite datetime).
+    pub last_sync_at: Option<String>,
+    /// Error of the last failed sync attempt, cleared by the next success.
+    pub error: Option<String>,
+}
+
+/// Peer id under which the leader of a local space is tracked.
+pub fn local_space_peer_id(space_id: &str) -> String {
+    format!("space:{space_id}")
+}
+
+fn validate_peer_id(peer_id: &str) -> Result<(), DatabaseError> {
+    if peer_id.trim().is_empty() {
+        return Err(DatabaseError::ValidationError {
+            reason: "Sync peer id must not be empty".to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn select_sql() -> String {
+    format!(
+        "SELECT {COL_CRDT_SYNC_STATUS_PEER_ID}, {COL_CRDT_SYNC_STATUS_LAST_PUSH_HLC_TIMESTAMP}, \
+         {COL_CRDT_SYNC_STATUS_LAST_PULL_HLC_TIMESTAMP}, {COL_CRDT_SYNC_STATUS_LAST_SYNC_AT}, \
+         {COL_CRDT_SYNC_STATUS_ERROR} FROM {TABLE_CRDT_SYNC_STATUS}"
+    )
+}
+
+fn from_row(row: &rusqlite::Row) -> rusqlite::Result<SyncStatus> {
+    Ok(SyncStatus {
+        peer_id: row.get(0)?,
+        last_push_hlc_timestamp: row.get(1)?,
+        last_pull_hlc_timestamp: row.get(2)?,
+        last_sync_at: row.get(3)?,
+        error: row.get(4)?,
+    })
+}
+
+/// All peers, ordered by id.
+pub fn list_statuses(conn: &Connection) -> Result<Vec<SyncStatus>, DatabaseError> {
+    let mut stmt = conn.prepare(&format!(
+        "{} ORDER BY {COL_CRDT_SYNC_STATUS_PEER_ID}",
+        select_sql()
+    ))?;
+    let rows = stmt
+        .query_map([], from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Status of one peer, `None` before the first sync with it.
+pub fn get_status(conn: &Connection, peer_id: &str) -> Result<Option<SyncStatus>, DatabaseError> {
+    Ok(conn
+        .query_row(
+            &format!("{} WHERE {COL_CRDT_SYNC_STATUS_PEER_ID} = ?1", select_sql()),
+            [peer_id],
+            from_row,
+        )
+        .optional()?)
+}
+
+/// Sets the push cursor of `peer_id`, also backwards. `None` clears it, so
+/// the next push sends every change again.
+pub fn set_push_cursor(
+    conn: &Connection,
+    peer_id: &str,
+    hlc: Option<&str>,
+) -> Result<(), DatabaseError> {
+    validate_peer_id(peer_id)?;
+    conn.execute(
+        &format!(
+            "INSERT INTO {TABLE_CRDT_SYNC_STATUS} ({COL_CRDT_SYNC_STATUS_PEER_ID}, {COL_CRDT_SYNC_STATUS_LAST_PUSH_HLC_TIMESTAMP})
+             VALUES (?1, ?2)
+             ON CONFLICT({COL_CRDT_SYNC_STATUS_PEER_ID}) DO UPDATE SET {COL_CRDT_SYNC_STATUS_LAST_PUSH_HLC_TIMESTAMP} = excluded.{COL_CRDT_SYNC_STATUS_LAST_PUSH_HLC_TIMESTAMP}"
+        ),
+        params![peer_id, hlc],
+    )?;
+    Ok(())
+}
+
+/// Moves `column` of `peer_id` to `hlc` unless it already is at or past it,
+/// and marks the peer as synced.
+fn advance_cursor(
+    conn: &Connection,
+    peer_id: &str,
+    column: &str,
+    hlc: &str,
+) -> Result<(), DatabaseError> {
+    validate_peer_id(peer_id)?;
+    if hlc.is_empty() {
+        return Ok(());
+    }
+
+    let current: Option<String> = conn
+        .query_row(
+            &format!(
+                "SELECT {column} FROM {TABLE_CRDT_SYNC_STATUS} WHERE {COL_CRDT_SYNC_STATUS_PEER_ID} = ?1"
+            ),
+            [peer_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    let cursor = match current {
+        Some(current) if !hlc_is_newer(hlc, &current) => current,
+        _ => hlc.to_string(),
+    };
+
+    conn.execute(
+        &format!(
+            "INSERT INTO {TABLE_CRDT_SYNC_STATUS} ({COL_CRDT_SYNC_STATUS_PEER_ID}, {column}, {COL_CRDT_SYNC_STATUS_LAST_SYNC_AT})
+             VALUES (?1, ?2, datetime('now'))
+             ON CONFLICT({COL_CRDT_SYNC_STATUS_PEER_ID}) DO UPDATE SET
+                 {column} = excluded.{column},
+                 {COL_CRDT_SYNC_STATUS_LAST_SYNC_AT} = excluded.{COL_CRDT_SYNC_STATUS_LAST_SYNC_AT},
+                 {COL_CRDT_SYNC_STATUS_ERROR} = NULL"
+        ),
+        params![peer_id, cursor],
+    )?;
+    Ok(())
+}
+
+/// Records a successful push to `peer_id` up to `hlc`.
+pub fn advance_push_cursor(
+    conn: &Connection,
+    peer_id: &str,
+    hlc: &str,
+) -> Result<(), DatabaseError> {
+    advance_cursor(
+        conn,
+        peer_id,
+        COL_CRDT_SYNC_STATUS_LAST_PUSH_HLC_TIMESTAMP,
+        hlc,
+    )
+}
+
+/// Records a successful pull from `peer_id` up to `hlc`.
+pub fn advance_pull_cursor(
+    conn: &Connection,
+    peer_id: &str,
+    hlc: &str,
+) -> Result<(), DatabaseError> {
+    advance_cursor(
+        conn,
+        peer_id,
+        COL_CRDT_SYNC_STATUS_LAST_PULL_HLC_TIMESTAMP,
+        hlc,
+    )
+}
+
+/// Records a failed sync attempt with `peer_id`; the cursors stay as they are.
+pub fn record_error(conn: &Connection, peer_id: &str, error: &str) -> Result<(), DatabaseError> {
+    validate_peer_id(peer_id)?;
+    conn.execute(
+        &format!(
+            "INSERT INTO {TABLE_CRDT_SYNC_STATUS} ({COL_CRDT_SYNC_STATUS_PEER_ID}, {COL_CRDT_SYNC_STATUS_ERROR})
+             VALUES (?1, ?2)
+             ON CONFLICT({COL_CRDT_SYNC_STATUS_PEER_ID}) DO UPDATE SET {COL_CRDT_SYNC_STATUS_ERROR} = excluded.{COL_CRDT_SYNC_STATUS_ERROR}"
+        ),
+        params![peer_id, error],
+    )?;
+    Ok(())
+}
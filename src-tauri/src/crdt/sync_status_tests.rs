@@ -0,0 +1,129 @@
+//! Tests for the per-peer sync cursors in [`super::sync_status`] and their
+//! automatic update when `apply_remote_changes_to_db` pulls from a backend.
+
+#![cfg(test)]
+
+use rusqlite::Connection;
+
+use super::commands::apply_remote_changes_to_db;
+use super::hlc::HlcService;
+use super::sync_status::{
+    advance_pull_cursor, advance_push_cursor, get_status, list_statuses, local_space_peer_id,
+    record_error, set_push_cursor,
+};
+use crate::database::core::with_connection;
+use crate::table_names::TABLE_CRDT_SYNC_STATUS;
+use crate::test_support::crdt::open_crdt_connection;
+use crate::test_support::db::db_connection;
+
+const HLC_1: &str = "1000000000000000000/aabbccdd";
+const HLC_2: &str = "2000000000000000000/aabbccdd";
+const HLC_3: &str = "3000000000000000000/aabbccdd";
+
+fn setup() -> Connection {
+    let conn = open_crdt_connection(HlcService::new_for_testing("sync-status-device")).unwrap();
+    conn.execute_batch(include_str!(
+        "../../database/migrations/0009_add_crdt_sync_status.sql"
+    ))
+    .unwrap();
+    conn
+}
+
+#[test]
+fn test_advance_only_moves_cursors_forward() {
+    let conn = setup();
+    advance_push_cursor(&conn, "backend-1", HLC_2).unwrap();
+    advance_push_cursor(&conn, "backend-1", HLC_1).unwrap();
+    advance_pull_cursor(&conn, "backend-1", HLC_1).unwrap();
+    advance_pull_cursor(&conn, "backend-1", HLC_3).unwrap();
+    advance_pull_cursor(&conn, "backend-1", "").unwrap();
+
+    let status = get_status(&conn, "backend-1").unwrap().unwrap();
+    assert_eq!(status.last_push_hlc_timestamp.as_deref(), Some(HLC_2));
+    assert_eq!(status.last_pull_hlc_timestamp.as_deref(), Some(HLC_3));
+    assert!(status.last_sync_at.is_some());
+}
+
+#[test]
+fn test_cursors_are_tracked_per_peer() {
+    let conn = setup();
+    let space_peer = local_space_peer_id("space-A");
+    advance_push_cursor(&conn, "backend-1", HLC_3).unwrap();
+    advance_push_cursor(&conn, &space_peer, HLC_1).unwrap();
+
+    let statuses = list_statuses(&conn).unwrap();
+    let cursors: Vec<(&str, Option<&str>)> = statuses
+        .iter()
+        .map(|s| (s.peer_id.as_str(), s.last_push_hlc_timestamp.as_deref()))
+        .collect();
+    assert_eq!(
+        cursors,
+        vec![("backend-1", Some(HLC_3)), ("space:space-A", Some(HLC_1))]
+    );
+    assert!(get_status(&conn, "backend-2").unwrap().is_none());
+}
+
+#[test]
+fn test_set_push_cursor_can_move_back_and_reset() {
+    let conn = setup();
+    advance_push_cursor(&conn, "backend-1", HLC_3).unwrap();
+
+    set_push_cursor(&conn, "backend-1", Some(HLC_1)).unwrap();
+    let status = get_status(&conn, "backend-1").unwrap().unwrap();
+    assert_eq!(status.last_push_hlc_timestamp.as_deref(), Some(HLC_1));
+
+    set_push_cursor(&conn, "backend-1", None).unwrap();
+    let status = get_status(&conn, "backend-1").unwrap().unwrap();
+    assert_eq!(status.last_push_hlc_timestamp, None);
+
+    assert!(set_push_cursor(&conn, " ", Some(HLC_1)).is_err());
+}
+
+#[test]
+fn test_error_is_kept_until_next_success() {
+    let conn = setup();
+    advance_pull_cursor(&conn, "backend-1", HLC_1).unwrap();
+    record_error(&conn, "backend-1", "connection reset").unwrap();
+
+    let status = get_status(&conn, "backend-1").unwrap().unwrap();
+    assert_eq!(status.error.as_deref(), Some("connection reset"));
+    assert_eq!(status.last_pull_hlc_timestamp.as_deref(), Some(HLC_1));
+
+    advance_pull_cursor(&conn, "backend-1", HLC_2).unwrap();
+    let status = get_status(&conn, "backend-1").unwrap().unwrap();
+    assert_eq!(status.error, None);
+}
+
+#[test]
+fn test_backend_pull_advances_both_cursors() {
+    let conn = setup();
+    conn.execute_batch(
+        "CREATE TABLE haex_sync_backends (id TEXT PRIMARY KEY, last_push_hlc_timestamp TEXT);
+         INSERT INTO haex_sync_backends (id) VALUES ('backend-1');",
+    )
+    .unwrap();
+    let db = db_connection(conn);
+    let hlc = HlcService::new_for_testing("sync-status-device");
+    let max_hlc = HlcService::new_for_testing("remote-device")
+        .new_timestamp()
+        .unwrap()
+        .to_string();
+
+    apply_remote_changes_to_db(&db, Vec::new(), Some(("backend-1", &max_hlc)), Some(&hlc)).unwrap();
+
+    let status = with_connection(&db, |conn| get_status(conn, "backend-1"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(status.last_pull_hlc_timestamp, Some(max_hlc.clone()));
+    assert_eq!(status.last_push_hlc_timestamp, Some(max_hlc));
+
+    let rows: i64 = with_connection(&db, |conn| {
+        Ok(conn.query_row(
+            &format!("SELECT COUNT(*) FROM {TABLE_CRDT_SYNC_STATUS}"),
+            [],
+            |r| r.get(0),
+        )?)
+    })
+    .unwrap();
+    assert_eq!(rows, 1);
+}
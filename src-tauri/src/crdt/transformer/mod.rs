@@ -1,13 +1,19 @@
 // src-tauri/src/crdt/transformer.rs
 
 use crate::crdt::insert_transformer::InsertTransformer;
+use crate::crdt::json_patch::{self, JsonPath};
 use crate::crdt::trigger::{COLUMN_HLCS_COLUMN, HLC_TIMESTAMP_COLUMN};
 use crate::database::error::DatabaseError;
 use sqlparser::ast::{
-    AlterTable, Assignment, AssignmentTarget, ColumnDef, DataType, Expr, Ident, ObjectName,
-    ObjectNamePart, Query, Select, SetExpr, Statement, TableFactor, TableObject, Value,
+    AlterTable, Assignment, AssignmentTarget, ColumnDef, ColumnOption, CreateIndex, CreateTable,
+    DataType, Expr, Function, FunctionArg, FunctionArgExpr, FunctionArguments, Ident, ObjectName,
+    ObjectNamePart, Query, Select, SetExpr, Statement, TableFactor, TableObject, Value, Visit,
+    Visitor,
 };
+use sqlparser::dialect::SQLiteDialect;
+use sqlparser::parser::Parser;
 use std::borrow::Cow;
+use std::ops::ControlFlow;
 use uhlc::Timestamp;
 
 /// Konfiguration für CRDT-Spalten
@@ -33,27 +39,195 @@ impl CrdtColumns {
         }
     }
 
+    /// Erstellt die `haex_column_hlcs`-Zuweisung für JSON-Pfad-Updates
+    fn create_json_patch_assignment(
+        &self,
+        patches: &[(String, Vec<JsonPath>)],
+        timestamp: &Timestamp,
+    ) -> Result<Assignment, DatabaseError> {
+        let sql = json_patch::hlc_bookkeeping_sql(patches, &timestamp.to_string());
+        let value = Parser::new(&SQLiteDialect {})
+            .try_with_sql(&sql)
+            .and_then(|mut parser| parser.parse_expr())
+            .map_err(|e| DatabaseError::ParseError {
+                reason: e.to_string(),
+                sql,
+            })?;
+
+        Ok(Assignment {
+            target: AssignmentTarget::ColumnName(ObjectName(vec![ObjectNamePart::Identifier(
+                Ident::new(self.column_hlcs),
+            )])),
+            value,
+        })
+    }
+
+    /// Stamps the SET list of an UPDATE or of an UPSERT's `DO UPDATE` branch.
+    ///
+    /// Sets `haex_hlc`; the AFTER-UPDATE trigger derives the column HLCs from
+    /// it, only for columns whose value actually changes.
+    /// `col = json_set(col, '$.path', …)` only touches the listed paths —
+    /// record them so the merge keeps concurrent edits to other keys of the
+    /// same JSON value.
+    fn stamp_assignments(
+        &self,
+        assignments: &mut Vec<Assignment>,
+        timestamp: &Timestamp,
+    ) -> Result<(), DatabaseError> {
+        assignments.push(self.create_hlc_assignment(timestamp));
+
+        let patches = json_patch_assignments(assignments);
+        if !patches.is_empty() {
+            assignments.push(self.create_json_patch_assignment(&patches, timestamp)?);
+        }
+        Ok(())
+    }
+
     /// Fügt CRDT-Spalten zu einer Tabellendefinition hinzu
     /// Überschreibt vorhandene Spalten mit den gleichen Namen, um korrekte Datentypen zu garantieren
-    fn add_to_table_definition(&self, columns: &mut Vec<ColumnDef>) {
+    ///
+    /// STRICT tables only accept the canonical type names, so they get `TEXT`
+    /// instead of `STRING`.
+    fn add_to_table_definition(&self, columns: &mut Vec<ColumnDef>, strict: bool) {
         // Remove existing CRDT columns if present
         columns.retain(|c| {
             c.name.value != self.hlc_timestamp && c.name.value != self.column_hlcs
         });
 
+        let data_type = if strict {
+            DataType::Text
+        } else {
+            DataType::String(None)
+        };
+
         // Add all CRDT columns with correct types
         columns.push(ColumnDef {
             name: Ident::new(self.hlc_timestamp),
-            data_type: DataType::String(None),
+            data_type: data_type.clone(),
             options: vec![],
         });
 
         columns.push(ColumnDef {
             name: Ident::new(self.column_hlcs),
-            data_type: DataType::String(None),
+            data_type,
             options: vec![],
         });
     }
+
+    /// Name of the CRDT column `node` references, if any
+    fn referenced_by<V: Visit>(&self, node: &V) -> Option<&'static str> {
+        let mut finder = ColumnFinder {
+            columns: vec![self.hlc_timestamp, self.column_hlcs],
+            found: None,
+        };
+        let _ = node.visit(&mut finder);
+        finder.found
+    }
+}
+
+/// Finds the first identifier that names one of `columns`
+struct ColumnFinder {
+    columns: Vec<&'static str>,
+    found: Option<&'static str>,
+}
+
+impl Visitor for ColumnFinder {
+    type Break = ();
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<Self::Break> {
+        self.found = self
+            .columns
+            .iter()
+            .copied()
+            .find(|column| is_column_ref(expr, column));
+        if self.found.is_some() {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+/// Name of the column an assignment targets, without quotes or qualifiers.
+fn assignment_column(assignment: &Assignment) -> Option<String> {
+    match &assignment.target {
+        AssignmentTarget::ColumnName(name) => match name.0.last()? {
+            ObjectNamePart::Identifier(ident) => Some(ident.value.clone()),
+            ObjectNamePart::Function(_) => None,
+        },
+        _ => None,
+    }
+}
+
+fn unnamed_args(function: &Function) -> Option<Vec<&Expr>> {
+    let FunctionArguments::List(list) = &function.args else {
+        return None;
+    };
+    list.args
+        .iter()
+        .map(|arg| match arg {
+            FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => Some(expr),
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_column_ref(expr: &Expr, column: &str) -> bool {
+    match expr {
+        Expr::Identifier(ident) => ident.value == column,
+        Expr::CompoundIdentifier(parts) => parts.last().is_some_and(|ident| ident.value == column),
+        Expr::Nested(inner) => is_column_ref(inner, column),
+        _ => false,
+    }
+}
+
+/// Matches `json_set(col, …)` as well as `json_set(COALESCE(col, …), …)`
+/// and `json_set(IFNULL(col, …), …)`.
+fn is_patch_target(expr: &Expr, column: &str) -> bool {
+    if is_column_ref(expr, column) {
+        return true;
+    }
+    let Expr::Function(function) = expr else {
+        return false;
+    };
+    let name = function.name.to_string().to_lowercase();
+    (name == "coalesce" || name == "ifnull")
+        && unnamed_args(function)
+            .and_then(|args| args.first().copied())
+            .is_some_and(|first| is_column_ref(first, column))
+}
+
+/// Collects `col = json_set(col, '<path>', value, …)` assignments whose paths
+/// are all string literals. Any other shape counts as a whole-column write.
+fn json_patch_assignments(assignments: &[Assignment]) -> Vec<(String, Vec<JsonPath>)> {
+    assignments
+        .iter()
+        .filter_map(|assignment| {
+            let column = assignment_column(assignment)?;
+            let Expr::Function(function) = &assignment.value else {
+                return None;
+            };
+            if !function.name.to_string().eq_ignore_ascii_case("json_set") {
+                return None;
+            }
+            let args = unnamed_args(function)?;
+            let (target, pairs) = args.split_first()?;
+            if !is_patch_target(target, &column) || pairs.is_empty() || pairs.len() % 2 != 0 {
+                return None;
+            }
+            let paths = pairs
+                .chunks(2)
+                .map(|pair| match pair[0] {
+                    Expr::Value(value) => match &value.value {
+                        Value::SingleQuotedString(path) => JsonPath::parse(path).ok(),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some((column, paths))
+        })
+        .collect()
 }
 
 pub struct CrdtTransformer {
@@ -111,7 +285,10 @@ impl CrdtTransformer {
     /// Transformiert ein SELECT Statement rekursiv (FROM- und JOIN-Subqueries).
     /// Seit dem Delete-Log-Refactor enthalten Haupt-Tabellen keine Tombstone-Zeilen
     /// mehr, daher gibt es hier nichts mehr zu filtern — die Funktion bleibt aber
-    /// als Rekursionseinstieg für verschachtelte Queries.
+    /// als Rekursionseinstieg für verschachtelte Queries. Views brauchen ebenfalls
+    /// keine Umschreibung: sie lesen aus den Basistabellen und sehen damit nur
+    /// lebende Zeilen. Für Berechtigungen löst
+    /// [`crate::database::core::resolve_view_tables`] sie auf.
     fn transform_select(&self, select: &mut Select) {
         for table_with_joins in &mut select.from {
             self.transform_table_factor(&mut table_with_joins.relation);
@@ -189,9 +366,7 @@ impl CrdtTransformer {
                 Ok(None)
             }
             Statement::CreateTable(create_table) => {
-                if self.is_crdt_sync_table(&create_table.name) {
-                    self.columns
-                        .add_to_table_definition(&mut create_table.columns);
+                if self.transform_create_table(create_table)? {
                     Ok(Some(
                         self.normalize_table_name(&create_table.name).into_owned(),
                     ))
@@ -202,9 +377,16 @@ impl CrdtTransformer {
             Statement::Insert(insert_stmt) => {
                 if let TableObject::TableName(name) = &insert_stmt.table {
                     if self.is_crdt_sync_table(name) {
-                        // Hard Delete: Kein Schema-Lookup mehr nötig (kein ON CONFLICT)
                         let insert_transformer = InsertTransformer::new();
                         insert_transformer.transform_insert(insert_stmt, hlc_timestamp)?;
+
+                        // UPSERT: the conflict branch updates the existing row,
+                        // so it is stamped like a regular UPDATE.
+                        if let Some(assignments) =
+                            InsertTransformer::conflict_update_assignments(insert_stmt)
+                        {
+                            self.columns.stamp_assignments(assignments, hlc_timestamp)?;
+                        }
                     }
                 }
                 Ok(None)
@@ -215,9 +397,8 @@ impl CrdtTransformer {
                         // Add HLC timestamp assignment. With the delete-log model
                         // tombstoned rows no longer live in the target table, so
                         // there is nothing to filter out.
-                        update
-                            .assignments
-                            .push(self.columns.create_hlc_assignment(hlc_timestamp));
+                        self.columns
+                            .stamp_assignments(&mut update.assignments, hlc_timestamp)?;
                     }
                 }
                 Ok(None)
@@ -225,7 +406,11 @@ impl CrdtTransformer {
             Statement::Delete(_) => {
                 // DELETE stays DELETE. The BEFORE-DELETE trigger writes a row
                 // into haex_deleted_rows, and the CRDT apply-path propagates
-                // that to the target table on remotes.
+                // that to the target table on remotes. Cascade rules (see
+                // crdt::cascade) delete children via AFTER-DELETE triggers in
+                // the same transaction, so they share the parent's HLC.
+                // Tables opted into hard deletes (see crdt::hard_delete) have
+                // no DELETE trigger, so nothing is logged or trashed for them.
                 Ok(None)
             }
             Statement::AlterTable(AlterTable { name, .. }) => {
@@ -235,9 +420,12 @@ impl CrdtTransformer {
                     Ok(None)
                 }
             }
-            Statement::CreateIndex(_) => {
+            Statement::CreateIndex(create_index) => {
                 // No partial-index rewrite anymore — UNIQUE indexes stay full so
-                // they remain FK-parent-eligible.
+                // they remain FK-parent-eligible. Partial and expression indexes
+                // pass through unchanged: without tombstone rows in the main
+                // tables, reads need no extra filter that could bypass them.
+                self.check_create_index(create_index)?;
                 Ok(None)
             }
             _ => Ok(None),
@@ -250,9 +438,6 @@ impl CrdtTransformer {
     ///
     /// Returns the transformed SQL string, or the original if no transformation was needed.
     pub fn transform_ddl_statement(&self, sql: &str) -> Result<String, DatabaseError> {
-        use sqlparser::dialect::SQLiteDialect;
-        use sqlparser::parser::Parser;
-
         let dialect = SQLiteDialect {};
         let mut statements = Parser::parse_sql(&dialect, sql).map_err(|e| {
             DatabaseError::ParseError {
@@ -267,16 +452,92 @@ impl CrdtTransformer {
 
         let stmt = &mut statements[0];
 
-        if let Statement::CreateTable(create_table) = stmt {
-            if self.is_crdt_sync_table(&create_table.name) {
-                self.columns
-                    .add_to_table_definition(&mut create_table.columns);
-                return Ok(stmt.to_string());
+        match stmt {
+            Statement::CreateTable(create_table) => {
+                if self.transform_create_table(create_table)? {
+                    return Ok(stmt.to_string());
+                }
             }
+            Statement::CreateIndex(create_index) => self.check_create_index(create_index)?,
+            _ => {}
         }
 
         Ok(sql.to_string())
     }
+
+    /// Adds the CRDT columns to a synced table. Returns `false` for
+    /// `_no_sync` tables, which stay untouched.
+    ///
+    /// Generated columns are kept as they are. They are not listed by
+    /// `PRAGMA table_info`, so triggers and the sync scanner skip them and
+    /// every device computes them locally. They must not be derived from
+    /// the CRDT columns, whose values are bookkeeping and differ between
+    /// devices. `CREATE TABLE ... AS SELECT` is rejected because the CRDT
+    /// columns (and a primary key) can't be added to it.
+    fn transform_create_table(
+        &self,
+        create_table: &mut CreateTable,
+    ) -> Result<bool, DatabaseError> {
+        if !self.is_crdt_sync_table(&create_table.name) {
+            return Ok(false);
+        }
+
+        if create_table.query.is_some() {
+            return Err(DatabaseError::UnsupportedStatement {
+                reason: "CREATE TABLE ... AS SELECT is not supported for synced tables".to_string(),
+                sql: create_table.to_string(),
+            });
+        }
+
+        for column in &create_table.columns {
+            for option in &column.options {
+                let ColumnOption::Generated {
+                    generation_expr: Some(expr),
+                    ..
+                } = &option.option
+                else {
+                    continue;
+                };
+                if let Some(crdt_column) = self.columns.referenced_by(expr) {
+                    return Err(DatabaseError::UnsupportedStatement {
+                        reason: format!(
+                            "Generated column '{}' must not reference the CRDT column '{crdt_column}'",
+                            column.name.value
+                        ),
+                        sql: create_table.to_string(),
+                    });
+                }
+            }
+        }
+
+        self.columns
+            .add_to_table_definition(&mut create_table.columns, create_table.strict);
+        Ok(true)
+    }
+
+    /// Rejects indexes on synced tables that are keyed or filtered on
+    /// `haex_column_hlcs`. The triggers rewrite that JSON on every write, so
+    /// such an index would be rebuilt constantly and tie the extension to an
+    /// internal format. Indexes on `haex_hlc` are fine.
+    fn check_create_index(&self, create_index: &CreateIndex) -> Result<(), DatabaseError> {
+        if !self.is_crdt_sync_table(&create_index.table_name) {
+            return Ok(());
+        }
+
+        let mut finder = ColumnFinder {
+            columns: vec![self.columns.column_hlcs],
+            found: None,
+        };
+        let _ = create_index.columns.visit(&mut finder);
+        let _ = create_index.predicate.visit(&mut finder);
+        if let Some(crdt_column) = finder.found {
+            return Err(DatabaseError::UnsupportedStatement {
+                reason: format!("Indexes must not use the CRDT column '{crdt_column}'"),
+                sql: create_index.to_string(),
+            });
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -5,15 +5,31 @@
 //! remaining responsibilities:
 //! - CREATE TABLE gets `haex_hlc` + `haex_column_hlcs` added
 //! - CREATE UNIQUE INDEX stays untouched (no partial rewrite)
+//! - generated columns and partial/expression indexes pass through, unless
+//!   they depend on the CRDT bookkeeping columns
 //! - DELETE stays a DELETE
 //! - UPDATE gets the HLC timestamp assignment
+//! - `json_set` UPDATEs additionally record per-path HLCs
+//! - the `DO UPDATE` branch of an UPSERT is stamped like an UPDATE
+//! - RETURNING clauses survive every rewrite
 //! - SELECT passes through, including recursion into subqueries
 
 use crate::crdt::transformer::CrdtTransformer;
+use crate::database::error::DatabaseError;
 use sqlparser::dialect::SQLiteDialect;
 use sqlparser::parser::Parser;
 use uhlc::HLC;
 
+fn try_transform_execute(sql: &str) -> Result<String, DatabaseError> {
+    let dialect = SQLiteDialect {};
+    let mut statements = Parser::parse_sql(&dialect, sql).unwrap();
+    let timestamp = HLC::default().new_timestamp();
+
+    CrdtTransformer::new().transform_execute_statement(&mut statements[0], &timestamp)?;
+
+    Ok(statements[0].to_string())
+}
+
 fn parse_and_transform_execute(sql: &str) -> String {
     let dialect = SQLiteDialect {};
     let mut statements = Parser::parse_sql(&dialect, sql).unwrap();
@@ -56,6 +72,32 @@ fn test_update_adds_hlc_assignment() {
     assert!(!result.contains("haex_tombstone"), "Got: {result}");
 }
 
+#[test]
+fn test_json_set_update_records_path_hlcs() {
+    let result = parse_and_transform_execute(
+        "UPDATE items SET settings = json_set(COALESCE(settings, '{}'), '$.theme', json(?)) WHERE id = 'x'",
+    );
+    assert!(
+        result.contains("haex_column_hlcs = json_set(haex_column_hlcs"),
+        "json_set UPDATE must add path bookkeeping. Got: {result}"
+    );
+    assert!(result.contains("'$.\"settings#$.theme\"'"), "Got: {result}");
+    assert!(result.contains("'$.\"settings#\"'"), "Got: {result}");
+}
+
+#[test]
+fn test_plain_update_has_no_path_bookkeeping() {
+    for sql in [
+        "UPDATE items SET settings = '{}' WHERE id = 'x'",
+        // Different source column, or a non-literal path: whole-column write.
+        "UPDATE items SET settings = json_set(other, '$.a', 1) WHERE id = 'x'",
+        "UPDATE items SET settings = json_set(settings, ?, 1) WHERE id = 'x'",
+    ] {
+        let result = parse_and_transform_execute(sql);
+        assert!(!result.contains("haex_column_hlcs"), "Got: {result}");
+    }
+}
+
 #[test]
 fn test_create_table_adds_crdt_columns() {
     let result = parse_and_transform_execute(
@@ -101,6 +143,67 @@ fn test_insert_into_sync_table_gets_hlc_column() {
     assert!(result.contains("haex_hlc"), "Got: {result}");
 }
 
+#[test]
+fn test_upsert_do_update_gets_hlc_assignment() {
+    let result = parse_and_transform_execute(
+        "INSERT INTO items (id, name) VALUES ('a', 'b') ON CONFLICT(id) DO UPDATE SET name = excluded.name",
+    );
+    let (_, conflict_branch) = result.split_once("DO UPDATE SET").unwrap();
+    assert!(
+        conflict_branch.contains("haex_hlc = '"),
+        "DO UPDATE must stamp haex_hlc. Got: {result}"
+    );
+}
+
+#[test]
+fn test_upsert_json_set_records_path_hlcs() {
+    let result = parse_and_transform_execute(
+        "INSERT INTO items (id, settings) VALUES ('a', '{}') ON CONFLICT(id) DO UPDATE SET settings = json_set(settings, '$.theme', 'dark')",
+    );
+    let (_, conflict_branch) = result.split_once("DO UPDATE SET").unwrap();
+    assert!(
+        conflict_branch.contains("haex_column_hlcs = json_set(haex_column_hlcs"),
+        "Got: {result}"
+    );
+}
+
+#[test]
+fn test_upsert_do_nothing_and_no_sync_are_unchanged() {
+    let result = parse_and_transform_execute(
+        "INSERT INTO items (id, name) VALUES ('a', 'b') ON CONFLICT DO NOTHING",
+    );
+    assert!(result.ends_with("ON CONFLICT DO NOTHING"), "Got: {result}");
+
+    let result = parse_and_transform_execute(
+        "INSERT INTO cache_no_sync (id, name) VALUES ('a', 'b') ON CONFLICT(id) DO UPDATE SET name = excluded.name",
+    );
+    assert!(!result.contains("haex_hlc"), "Got: {result}");
+}
+
+#[test]
+fn test_returning_is_preserved() {
+    for (sql, returning) in [
+        ("INSERT INTO items (id, name) VALUES ('a', 'b') RETURNING id, name", "RETURNING id, name"),
+        (
+            "INSERT INTO items (id, name) VALUES ('a', 'b') ON CONFLICT(id) DO UPDATE SET name = excluded.name RETURNING *",
+            "RETURNING *",
+        ),
+        ("UPDATE items SET name = 'foo' WHERE id = 'x' RETURNING haex_hlc", "RETURNING haex_hlc"),
+        (
+            "UPDATE items SET settings = json_set(settings, '$.a', 1) WHERE id = 'x' RETURNING settings",
+            "RETURNING settings",
+        ),
+        ("DELETE FROM items WHERE id = 'x' RETURNING id", "RETURNING id"),
+    ] {
+        let result = parse_and_transform_execute(sql);
+        assert!(
+            result.ends_with(returning),
+            "RETURNING must stay the last clause. Got: {result}"
+        );
+        assert_eq!(result.matches("RETURNING").count(), 1, "Got: {result}");
+    }
+}
+
 #[test]
 fn test_delete_from_sync_table_stays_delete() {
     let result = parse_and_transform_execute("DELETE FROM items WHERE id = 'a'");
@@ -110,3 +213,74 @@ fn test_delete_from_sync_table_stays_delete() {
         "DELETE must not be rewritten. Got: {result}"
     );
 }
+
+#[test]
+fn test_create_table_keeps_generated_columns() {
+    let result = parse_and_transform_execute(
+        "CREATE TABLE items (id TEXT PRIMARY KEY, price REAL, qty INTEGER, \
+         total REAL GENERATED ALWAYS AS (price * qty) STORED, \
+         label TEXT AS (upper(id)) VIRTUAL)",
+    );
+    assert!(result.contains("(price * qty) STORED"), "Got: {result}");
+    assert!(result.contains("(upper(id)) VIRTUAL"), "Got: {result}");
+    assert!(result.contains("haex_hlc"), "Got: {result}");
+}
+
+#[test]
+fn test_create_table_rejects_generated_columns_on_crdt_columns() {
+    let result = try_transform_execute(
+        "CREATE TABLE items (id TEXT PRIMARY KEY, changed TEXT AS (haex_hlc))",
+    );
+    assert!(
+        matches!(result, Err(DatabaseError::UnsupportedStatement { .. })),
+        "Got: {result:?}"
+    );
+
+    // _no_sync tables get no CRDT columns, so nothing to protect
+    assert!(try_transform_execute(
+        "CREATE TABLE cache_no_sync (id TEXT PRIMARY KEY, changed TEXT AS (haex_hlc))"
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_create_strict_table_uses_text_crdt_columns() {
+    let result =
+        parse_and_transform_execute("CREATE TABLE items (id TEXT PRIMARY KEY, name TEXT) STRICT");
+    assert!(result.contains("haex_hlc TEXT"), "Got: {result}");
+    assert!(result.contains("haex_column_hlcs TEXT"), "Got: {result}");
+}
+
+#[test]
+fn test_create_table_as_select_is_rejected() {
+    assert!(try_transform_execute("CREATE TABLE copy AS SELECT * FROM items").is_err());
+}
+
+#[test]
+fn test_partial_and_expression_indexes_pass_through() {
+    let result =
+        parse_and_transform_execute("CREATE INDEX idx_items_open ON items (due_date) WHERE done = 0");
+    assert!(result.ends_with("WHERE done = 0"), "Got: {result}");
+
+    let result =
+        parse_and_transform_execute("CREATE UNIQUE INDEX idx_items_lower ON items (lower(name))");
+    assert!(result.contains("lower(name)"), "Got: {result}");
+    assert!(!result.contains("haex_"), "Got: {result}");
+
+    assert!(try_transform_execute("CREATE INDEX idx_items_hlc ON items (haex_hlc)").is_ok());
+}
+
+#[test]
+fn test_index_on_column_hlcs_is_rejected() {
+    for sql in [
+        "CREATE INDEX idx_items_hlcs ON items (haex_column_hlcs)",
+        "CREATE INDEX idx_items_name ON items (name) WHERE haex_column_hlcs IS NOT NULL",
+    ] {
+        assert!(try_transform_execute(sql).is_err(), "{sql}");
+    }
+
+    let transformer = CrdtTransformer::new();
+    assert!(transformer
+        .transform_ddl_statement("CREATE INDEX idx ON items (json_extract(haex_column_hlcs, '$.name'))")
+        .is_err());
+}
@@ -0,0 +1,411 @@
+// src-tauri/src/crdt/trash.rs
+//!
+//! Record trash built on the delete-log.
+//!
+//! The BEFORE-DELETE trigger (see `trigger::generate_delete_trigger_sql`)
+//! stores a JSON snapshot of every locally deleted row in `TRASH_TABLE`, keyed
+//! by the id of the matching `haex_deleted_rows` entry. As long as that
+//! delete-log entry has not been cleaned up, the row can be restored.
+//!
+//! A restore re-inserts the row through the CRDT executor, so it gets a fresh
+//! HLC that is newer than the delete — peers treat it as an insert-after-delete
+//! ("resurrection", see `should_propagate_delete`). Deletes that arrive via
+//! sync run with triggers disabled and therefore never land in the trash.
+//!
+//! Rows deleted by a cascade (see `cascade`) share the parent's delete HLC
+//! and are restored together with the parent.
+
+use crate::crdt::cascade;
+use crate::crdt::hlc::{compare_hlc_strings, HlcService};
+use crate::crdt::trigger::{
+    get_table_schema, is_safe_identifier, COLUMN_HLCS_COLUMN, DELETED_ROWS_TABLE,
+    HLC_TIMESTAMP_COLUMN,
+};
+use crate::database::error::DatabaseError;
+use crate::extension::database::executor::SqlExecutor;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, Connection, OptionalExtension, ToSql, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::cmp::Ordering;
+use ts_rs::TS;
+
+/// Local-only snapshot table for deleted rows, created by migration
+/// `0017_add_trash`. Never synced (`_no_sync`).
+pub const TRASH_TABLE: &str = "haex_trash_no_sync";
+
+/// Marker key for BLOB values in a snapshot: `{"$blob": "<hex>"}`.
+/// JSON can't hold blobs, so the trigger hex-encodes them.
+pub const BLOB_MARKER_KEY: &str = "$blob";
+
+/// SQL functions are limited to SQLITE_MAX_FUNCTION_ARG arguments; wide rows
+/// are built from one `json_object()` plus chained `json_set()` calls.
+/// (`json_patch()` would drop NULL columns, as per RFC 7396.)
+const SNAPSHOT_COLUMNS_PER_CALL: usize = 50;
+
+const DEFAULT_LIST_LIMIT: u32 = 500;
+
+/// SQL expression (for use inside a trigger) that serializes the `OLD` row
+/// into a JSON object. BLOB columns are wrapped as `{"$blob": hex}`.
+pub fn snapshot_json_sql(columns: &[String]) -> String {
+    row_snapshot_json_sql("OLD", columns)
+}
+
+/// Like [`snapshot_json_sql`], for any row reference (`NEW`, a table alias).
+pub fn row_snapshot_json_sql(row: &str, columns: &[String]) -> String {
+    let value = |name: &str| {
+        format!(
+            "CASE typeof({row}.\"{name}\") \
+             WHEN 'blob' THEN json_object('{BLOB_MARKER_KEY}', hex({row}.\"{name}\")) \
+             ELSE {row}.\"{name}\" END"
+        )
+    };
+
+    let mut chunks = columns.chunks(SNAPSHOT_COLUMNS_PER_CALL);
+    let Some(first) = chunks.next() else {
+        return "'{}'".to_string();
+    };
+    let pairs = first
+        .iter()
+        .map(|name| format!("'{name}', {}", value(name)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut sql = format!("json_object({pairs})");
+
+    for chunk in chunks {
+        let pairs = chunk
+            .iter()
+            .map(|name| format!("'$.\"{name}\"', {}", value(name)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        sql = format!("json_set({sql}, {pairs})");
+    }
+    sql
+}
+
+/// Removes snapshots whose delete-log entry no longer exists (cleaned up or
+/// never synced). Runs with the delete-log cleanup (`cleanup_deleted_rows`).
+pub fn purge_orphaned_snapshots(conn: &Connection) -> Result<usize, rusqlite::Error> {
+    conn.execute(
+        &format!(
+            "DELETE FROM \"{TRASH_TABLE}\"
+             WHERE deleted_row_id NOT IN (SELECT id FROM \"{DELETED_ROWS_TABLE}\")"
+        ),
+        [],
+    )
+}
+
+/// A deleted row that can still be restored.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TombstonedRow {
+    /// Id of the `haex_deleted_rows` entry; pass this to `crdt_restore_row`.
+    pub id: String,
+    pub table_name: String,
+    /// Primary keys of the deleted row as JSON object.
+    pub row_pks: String,
+    /// Full row snapshot as JSON object. BLOBs are `{"$blob": "<hex>"}`.
+    pub row_data: String,
+    pub deleted_hlc: String,
+    pub deleted_at: String,
+}
+
+/// Why a restore was refused.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum RestoreConflict {
+    /// No snapshot for this delete-log entry (unknown id, already restored,
+    /// cleaned up, or deleted on another device).
+    SnapshotMissing,
+    /// A row with the same primary key exists again.
+    #[serde(rename_all = "camelCase")]
+    RowExists { current_hlc: Option<String> },
+    /// The row was re-created and deleted again later; restore the newer
+    /// delete-log entry instead.
+    #[serde(rename_all = "camelCase")]
+    DeletedAgain { newer_id: String, newer_hlc: String },
+}
+
+/// Outcome of `crdt_restore_row`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreRowResult {
+    pub restored: bool,
+    pub conflict: Option<RestoreConflict>,
+    /// Snapshot columns that no longer exist in the table and were skipped.
+    pub dropped_columns: Vec<String>,
+    /// Delete-log ids of child rows restored along with this row because a
+    /// cascade rule had deleted them together with it.
+    pub restored_children: Vec<String>,
+}
+
+impl RestoreRowResult {
+    fn conflict(conflict: RestoreConflict) -> Self {
+        Self {
+            restored: false,
+            conflict: Some(conflict),
+            dropped_columns: Vec::new(),
+            restored_children: Vec::new(),
+        }
+    }
+}
+
+fn validate_table_name(table_name: &str) -> Result<(), DatabaseError> {
+    if !is_safe_identifier(table_name)
+        || table_name == DELETED_ROWS_TABLE
+        || table_name == TRASH_TABLE
+    {
+        return Err(DatabaseError::ValidationError {
+            reason: format!("Invalid table name for trash: {table_name}"),
+        });
+    }
+    Ok(())
+}
+
+/// Converts a snapshot value back into a SQLite value.
+pub fn json_to_sql_value(value: &JsonValue) -> Result<SqlValue, DatabaseError> {
+    Ok(match value {
+        JsonValue::Null => SqlValue::Null,
+        JsonValue::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        JsonValue::String(s) => SqlValue::Text(s.clone()),
+        JsonValue::Object(map) => match (map.len(), map.get(BLOB_MARKER_KEY)) {
+            (1, Some(JsonValue::String(hex_str))) => {
+                SqlValue::Blob(hex::decode(hex_str).map_err(|e| {
+                    DatabaseError::SerializationError {
+                        reason: format!("Invalid blob in trash snapshot: {e}"),
+                    }
+                })?)
+            }
+            _ => SqlValue::Text(value.to_string()),
+        },
+        JsonValue::Array(_) => SqlValue::Text(value.to_string()),
+    })
+}
+
+/// Lists restorable deleted rows, newest first. `since` is an HLC timestamp;
+/// only deletes strictly after it are returned. Snapshots whose delete-log
+/// entry is gone are skipped; the cleanup purges them.
+pub fn list_tombstoned(
+    conn: &Connection,
+    table_name: Option<&str>,
+    since: Option<&str>,
+    limit: Option<u32>,
+) -> Result<Vec<TombstonedRow>, DatabaseError> {
+    if let Some(table) = table_name {
+        validate_table_name(table)?;
+    }
+
+    let hlc_num = |expr: &str| {
+        format!("CAST(substr({expr}, 1, instr({expr} || '/', '/') - 1) AS INTEGER)")
+    };
+    let sql = format!(
+        "SELECT deleted_row_id, table_name, row_pks, row_data, deleted_hlc, deleted_at
+         FROM \"{TRASH_TABLE}\"
+         WHERE deleted_row_id IN (SELECT id FROM \"{DELETED_ROWS_TABLE}\")
+           AND (?1 IS NULL OR table_name = ?1)
+           AND (?2 IS NULL OR {deleted} > {since})
+         ORDER BY {deleted} DESC
+         LIMIT ?3",
+        deleted = hlc_num("deleted_hlc"),
+        since = hlc_num("?2"),
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt
+        .query_map(
+            params![table_name, since, limit.unwrap_or(DEFAULT_LIST_LIMIT)],
+            |row| {
+                Ok(TombstonedRow {
+                    id: row.get(0)?,
+                    table_name: row.get(1)?,
+                    row_pks: row.get(2)?,
+                    row_data: row.get(3)?,
+                    deleted_hlc: row.get(4)?,
+                    deleted_at: row.get(5)?,
+                })
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Restores a deleted row from its snapshot. Refuses (with a conflict, not an
+/// error) if the row exists again or was deleted again later, so a restore
+/// never overwrites newer writes.
+pub fn restore_row(
+    tx: &Transaction,
+    hlc_service: &HlcService,
+    table_name: &str,
+    deleted_row_id: &str,
+) -> Result<RestoreRowResult, DatabaseError> {
+    validate_table_name(table_name)?;
+
+    let snapshot: Option<(String, String, String)> = tx
+        .query_row(
+            &format!(
+                "SELECT row_pks, row_data, deleted_hlc FROM \"{TRASH_TABLE}\"
+                 WHERE deleted_row_id = ?1 AND table_name = ?2
+                   AND deleted_row_id IN (SELECT id FROM \"{DELETED_ROWS_TABLE}\")"
+            ),
+            params![deleted_row_id, table_name],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?;
+    let Some((row_pks_json, row_data_json, deleted_hlc)) = snapshot else {
+        return Ok(RestoreRowResult::conflict(RestoreConflict::SnapshotMissing));
+    };
+
+    // Deleted again after a re-create? Then this snapshot is outdated.
+    let mut stmt = tx.prepare(&format!(
+        "SELECT id, {HLC_TIMESTAMP_COLUMN} FROM \"{DELETED_ROWS_TABLE}\"
+         WHERE table_name = ?1 AND row_pks = ?2 AND id != ?3"
+    ))?;
+    let newer_delete = stmt
+        .query_map(params![table_name, row_pks_json, deleted_row_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter_map(|(id, hlc)| hlc.map(|hlc| (id, hlc)))
+        .filter(|(_, hlc)| compare_hlc_strings(hlc, &deleted_hlc) == Ordering::Greater)
+        .max_by(|(_, a), (_, b)| compare_hlc_strings(a, b));
+    if let Some((newer_id, newer_hlc)) = newer_delete {
+        return Ok(RestoreRowResult::conflict(RestoreConflict::DeletedAgain {
+            newer_id,
+            newer_hlc,
+        }));
+    }
+
+    let row_pks: serde_json::Map<String, JsonValue> = serde_json::from_str(&row_pks_json)
+        .map_err(|e| DatabaseError::SerializationError {
+            reason: format!("Invalid row_pks in trash snapshot: {e}"),
+        })?;
+    let row_data: serde_json::Map<String, JsonValue> = serde_json::from_str(&row_data_json)
+        .map_err(|e| DatabaseError::SerializationError {
+            reason: format!("Invalid row_data in trash snapshot: {e}"),
+        })?;
+
+    // Re-created in the meantime?
+    let (pk_where, pk_values) = crate::crdt::commands::build_pk_where_from_map(&row_pks)
+        .ok_or_else(|| DatabaseError::ValidationError {
+            reason: format!("Unsafe primary key columns in trash snapshot for {table_name}"),
+        })?;
+    let pk_values = pk_values
+        .iter()
+        .map(json_to_sql_value)
+        .collect::<Result<Vec<_>, _>>()?;
+    let current: Option<Option<String>> = tx
+        .query_row(
+            &format!("SELECT {HLC_TIMESTAMP_COLUMN} FROM \"{table_name}\" WHERE {pk_where}"),
+            rusqlite::params_from_iter(pk_values.iter()),
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(current_hlc) = current {
+        return Ok(RestoreRowResult::conflict(RestoreConflict::RowExists {
+            current_hlc,
+        }));
+    }
+
+    // Insert only columns the table still has; CRDT columns are set by the executor.
+    let schema = get_table_schema(tx, table_name)?;
+    let mut columns = Vec::new();
+    let mut values = Vec::new();
+    let mut dropped_columns = Vec::new();
+    for (name, value) in &row_data {
+        if name == HLC_TIMESTAMP_COLUMN || name == COLUMN_HLCS_COLUMN {
+            continue;
+        }
+        if !schema.iter().any(|c| &c.name == name) || !is_safe_identifier(name) {
+            dropped_columns.push(name.clone());
+            continue;
+        }
+        columns.push(format!("\"{name}\""));
+        values.push(json_to_sql_value(value)?);
+    }
+
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    let sql = format!(
+        "INSERT INTO \"{table_name}\" ({}) VALUES ({placeholders})",
+        columns.join(", ")
+    );
+    let param_refs: Vec<&dyn ToSql> = values.iter().map(|v| v as &dyn ToSql).collect();
+    SqlExecutor::execute_internal_typed(tx, hlc_service, &sql, &param_refs)?;
+
+    tx.execute(
+        &format!("DELETE FROM \"{TRASH_TABLE}\" WHERE deleted_row_id = ?1"),
+        params![deleted_row_id],
+    )?;
+
+    eprintln!("[Trash] Restored row {row_pks_json} in {table_name}");
+
+    let restored_children =
+        restore_cascaded_children(tx, hlc_service, table_name, &row_data_json, &deleted_hlc)?;
+
+    Ok(RestoreRowResult {
+        restored: true,
+        conflict: None,
+        dropped_columns,
+        restored_children,
+    })
+}
+
+/// Restores the rows a cascade deleted together with a parent: snapshots in
+/// the child tables with the parent's delete HLC whose foreign key columns
+/// match the parent snapshot. Children that can't be restored (conflicts)
+/// are left in the trash.
+fn restore_cascaded_children(
+    tx: &Transaction,
+    hlc_service: &HlcService,
+    parent_table: &str,
+    parent_data_json: &str,
+    deleted_hlc: &str,
+) -> Result<Vec<String>, DatabaseError> {
+    let mut restored = Vec::new();
+    for rule in cascade::cascade_children(tx, parent_table)? {
+        if !rule
+            .columns
+            .iter()
+            .chain(&rule.referenced_columns)
+            .all(|name| is_safe_identifier(name))
+        {
+            continue;
+        }
+        let condition = rule
+            .columns
+            .iter()
+            .zip(&rule.referenced_columns)
+            .map(|(child, parent)| {
+                format!(
+                    "json_extract(row_data, '$.\"{child}\"') = json_extract(?3, '$.\"{parent}\"')"
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        let mut stmt = tx.prepare(&format!(
+            "SELECT deleted_row_id FROM \"{TRASH_TABLE}\"
+             WHERE table_name = ?1 AND deleted_hlc = ?2 AND {condition}"
+        ))?;
+        let child_ids = stmt
+            .query_map(params![rule.table, deleted_hlc, parent_data_json], |row| {
+                row.get::<_, String>(0)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for child_id in child_ids {
+            let result = restore_row(tx, hlc_service, &rule.table, &child_id)?;
+            if result.restored {
+                restored.push(child_id);
+                restored.extend(result.restored_children);
+            }
+        }
+    }
+    Ok(restored)
+}
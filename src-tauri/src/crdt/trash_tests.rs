@@ -0,0 +1,210 @@
+//! Tests for the record trash in [`super::trash`]: the BEFORE-DELETE trigger
+//! snapshots rows, `list_tombstoned` exposes them and `restore_row`
+//! re-inserts them unless a newer write would be overwritten.
+
+#![cfg(test)]
+
+use rusqlite::Connection;
+use serde_json::Value as JsonValue;
+
+use super::hlc::HlcService;
+use super::trash::{
+    list_tombstoned, purge_orphaned_snapshots, restore_row, snapshot_json_sql, RestoreConflict,
+    TRASH_TABLE,
+};
+use super::trigger::DELETED_ROWS_TABLE;
+use crate::extension::database::executor::SqlExecutor;
+use crate::test_support::crdt::{create_crdt_table, open_crdt_connection};
+
+fn setup_db() -> (Connection, HlcService) {
+    let hlc = HlcService::new_for_testing("trash-test-device");
+    let conn = open_crdt_connection(hlc.clone()).unwrap();
+    create_crdt_table(
+        &conn,
+        "notes",
+        "CREATE TABLE notes (id TEXT PRIMARY KEY NOT NULL, title TEXT, pinned INTEGER, icon BLOB)",
+    )
+    .unwrap();
+
+    (conn, hlc)
+}
+
+fn exec(conn: &mut Connection, hlc: &HlcService, sql: &str, params: &[JsonValue]) {
+    let tx = conn.transaction().unwrap();
+    SqlExecutor::execute_internal(&tx, hlc, sql, params).unwrap();
+    tx.commit().unwrap();
+}
+
+fn insert_note(conn: &mut Connection, hlc: &HlcService, id: &str, title: &str) {
+    exec(
+        conn,
+        hlc,
+        "INSERT INTO notes (id, title, pinned, icon) VALUES (?, ?, 1, X'CAFE')",
+        &[JsonValue::from(id), JsonValue::from(title)],
+    );
+}
+
+fn delete_note(conn: &mut Connection, hlc: &HlcService, id: &str) {
+    exec(conn, hlc, "DELETE FROM notes WHERE id = ?", &[JsonValue::from(id)]);
+}
+
+fn restore(conn: &mut Connection, hlc: &HlcService, id: &str) -> super::trash::RestoreRowResult {
+    let tx = conn.transaction().unwrap();
+    let result = restore_row(&tx, hlc, "notes", id).unwrap();
+    tx.commit().unwrap();
+    result
+}
+
+#[test]
+fn test_delete_snapshots_row_into_trash() {
+    let (mut conn, hlc) = setup_db();
+    insert_note(&mut conn, &hlc, "n1", "Groceries");
+    delete_note(&mut conn, &hlc, "n1");
+
+    let trashed = list_tombstoned(&conn, Some("notes"), None, None).unwrap();
+    assert_eq!(trashed.len(), 1);
+
+    let log_id: String = conn
+        .query_row(&format!("SELECT id FROM {DELETED_ROWS_TABLE}"), [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(trashed[0].id, log_id, "trash entry is keyed by the delete-log id");
+
+    let data: serde_json::Map<String, JsonValue> =
+        serde_json::from_str(&trashed[0].row_data).unwrap();
+    assert_eq!(data["title"], "Groceries");
+    assert_eq!(data["pinned"], 1);
+    assert_eq!(data["icon"], serde_json::json!({ "$blob": "CAFE" }));
+}
+
+#[test]
+fn test_restore_reinserts_row_with_newer_hlc() {
+    let (mut conn, hlc) = setup_db();
+    insert_note(&mut conn, &hlc, "n1", "Groceries");
+    delete_note(&mut conn, &hlc, "n1");
+    let entry = list_tombstoned(&conn, None, None, None).unwrap().remove(0);
+
+    let result = restore(&mut conn, &hlc, &entry.id);
+    assert!(result.restored);
+    assert_eq!(result.conflict, None);
+
+    let (title, icon, row_hlc): (String, Vec<u8>, String) = conn
+        .query_row("SELECT title, icon, haex_hlc FROM notes WHERE id = 'n1'", [], |r| {
+            Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+        })
+        .unwrap();
+    assert_eq!(title, "Groceries");
+    assert_eq!(icon, vec![0xCA, 0xFE]);
+    assert_eq!(
+        super::hlc::compare_hlc_strings(&row_hlc, &entry.deleted_hlc),
+        std::cmp::Ordering::Greater,
+        "restored row must win over the delete on other devices"
+    );
+
+    // Snapshot is consumed; a second restore reports it missing.
+    assert!(list_tombstoned(&conn, None, None, None).unwrap().is_empty());
+    let again = restore(&mut conn, &hlc, &entry.id);
+    assert!(!again.restored);
+    assert_eq!(again.conflict, Some(RestoreConflict::SnapshotMissing));
+}
+
+#[test]
+fn test_restore_refuses_to_overwrite_recreated_row() {
+    let (mut conn, hlc) = setup_db();
+    insert_note(&mut conn, &hlc, "n1", "Old");
+    delete_note(&mut conn, &hlc, "n1");
+    let entry = list_tombstoned(&conn, None, None, None).unwrap().remove(0);
+
+    insert_note(&mut conn, &hlc, "n1", "New");
+
+    let result = restore(&mut conn, &hlc, &entry.id);
+    assert!(!result.restored);
+    assert!(matches!(result.conflict, Some(RestoreConflict::RowExists { .. })));
+
+    let title: String = conn
+        .query_row("SELECT title FROM notes WHERE id = 'n1'", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(title, "New");
+}
+
+#[test]
+fn test_restore_refuses_outdated_snapshot_after_second_delete() {
+    let (mut conn, hlc) = setup_db();
+    insert_note(&mut conn, &hlc, "n1", "First");
+    delete_note(&mut conn, &hlc, "n1");
+    let first = list_tombstoned(&conn, None, None, None).unwrap().remove(0);
+
+    insert_note(&mut conn, &hlc, "n1", "Second");
+    delete_note(&mut conn, &hlc, "n1");
+
+    let trashed = list_tombstoned(&conn, Some("notes"), None, None).unwrap();
+    assert_eq!(trashed.len(), 2);
+    assert_ne!(trashed[0].id, first.id, "newest delete is listed first");
+
+    let result = restore(&mut conn, &hlc, &first.id);
+    assert_eq!(
+        result.conflict,
+        Some(RestoreConflict::DeletedAgain {
+            newer_id: trashed[0].id.clone(),
+            newer_hlc: trashed[0].deleted_hlc.clone(),
+        })
+    );
+
+    // `since` filters by delete HLC.
+    let newer = list_tombstoned(&conn, None, Some(&first.deleted_hlc), None).unwrap();
+    assert_eq!(newer.len(), 1);
+}
+
+#[test]
+fn test_snapshots_follow_delete_log_cleanup() {
+    let (mut conn, hlc) = setup_db();
+    insert_note(&mut conn, &hlc, "n1", "Groceries");
+    delete_note(&mut conn, &hlc, "n1");
+
+    conn.execute(&format!("DELETE FROM {DELETED_ROWS_TABLE}"), [])
+        .unwrap();
+    // Listing hides the orphaned snapshot but leaves purging to the cleanup
+    assert!(list_tombstoned(&conn, None, None, None).unwrap().is_empty());
+    let entry_id: String = conn
+        .query_row(
+            &format!("SELECT deleted_row_id FROM {TRASH_TABLE}"),
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    let result = restore(&mut conn, &hlc, &entry_id);
+    assert_eq!(result.conflict, Some(RestoreConflict::SnapshotMissing));
+
+    assert_eq!(purge_orphaned_snapshots(&conn).unwrap(), 1);
+    assert_eq!(purge_orphaned_snapshots(&conn).unwrap(), 0);
+}
+
+#[test]
+fn test_list_rejects_unsafe_table_name() {
+    let (conn, _hlc) = setup_db();
+    assert!(list_tombstoned(&conn, Some("notes; DROP TABLE notes"), None, None).is_err());
+    assert!(list_tombstoned(&conn, Some(DELETED_ROWS_TABLE), None, None).is_err());
+}
+
+#[test]
+fn test_snapshot_sql_splits_wide_rows() {
+    let columns: Vec<String> = (0..120).map(|i| format!("c{i}")).collect();
+    let sql = snapshot_json_sql(&columns);
+    assert_eq!(sql.matches("json_set(").count(), 2);
+
+    let conn = Connection::open_in_memory().unwrap();
+    let cols = columns.join(" TEXT, ");
+    conn.execute_batch(&format!(
+        "CREATE TABLE wide ({cols} TEXT);
+         CREATE TABLE out (data TEXT);
+         CREATE TRIGGER snap BEFORE DELETE ON wide BEGIN INSERT INTO out VALUES ({sql}); END;
+         INSERT INTO wide (c0, c119) VALUES ('a', 'z');
+         DELETE FROM wide;"
+    ))
+    .unwrap();
+    let data: String = conn.query_row("SELECT data FROM out", [], |r| r.get(0)).unwrap();
+    let data: serde_json::Map<String, JsonValue> = serde_json::from_str(&data).unwrap();
+    assert_eq!(data.len(), 120, "NULL columns must be kept");
+    assert_eq!(data["c0"], "a");
+    assert_eq!(data["c60"], JsonValue::Null);
+    assert_eq!(data["c119"], "z");
+}
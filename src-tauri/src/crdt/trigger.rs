@@ -3,6 +3,7 @@
 // New approach: Instead of logging changes to haex_crdt_changes table,
 // we just mark tables as "dirty" in haex_crdt_dirty_tables.
 // Actual sync happens by scanning the dirty tables directly.
+use super::trash::{snapshot_json_sql, TRASH_TABLE};
 use crate::table_names::{TABLE_CRDT_CONFIGS, TABLE_CRDT_DIRTY_TABLES};
 use rusqlite::{Connection, Result as RusqliteResult, Row, Transaction};
 use serde::Serialize;
@@ -173,9 +174,15 @@ pub fn setup_triggers_for_table(
     // Der BEFORE-DELETE-Trigger loggt gelöschte Rows nach haex_deleted_rows.
     // Auf der Log-Tabelle selbst würde das Cleanup-DELETEs rekursiv ins Log
     // zurückschreiben — also legen wir für sie keinen DELETE-Trigger an.
-    // Sie ist die einzige Tabelle mit dieser Ausnahme.
-    if table_name != DELETED_ROWS_TABLE {
-        let delete_trigger_sql = generate_delete_trigger_sql(table_name, &pks);
+    // Außerdem ausgenommen: lokale Tabellen mit Hard-Delete-Opt-out
+    // (siehe crdt::hard_delete), deren Deletes weder geloggt noch getrasht werden.
+    if table_name != DELETED_ROWS_TABLE
+        && !super::hard_delete::is_hard_delete_table(tx, table_name)?
+    {
+        // Der DELETE-Trigger schreibt zusätzlich einen Snapshot in den Papierkorb
+        // (angelegt von Migration 0017_add_trash).
+        let all_columns: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
+        let delete_trigger_sql = generate_delete_trigger_sql(table_name, &pks, &all_columns);
         tx.execute_batch(&delete_trigger_sql)?;
     }
 
@@ -399,14 +406,18 @@ fn generate_update_trigger_sql(
 ///
 /// Two things happen in one trigger:
 /// 1. A row is appended to `haex_deleted_rows` — with a fresh uuid as id, the
-///    table name, the deleted row's PKs as a JSON object, and the current
-///    transaction HLC. This is the sync-visible "delete event".
-/// 2. The table is marked dirty so the scanner picks up the haex_deleted_rows
+///    table name, the deleted row's PKs as a JSON object, its `space_id` (for
+///    tables that have one), and the current transaction HLC. This is the
+///    sync-visible "delete event".
+/// 2. A JSON snapshot of the full row is stored in the local trash table,
+///    keyed by the delete-log id, so the delete can be undone until cleanup
+///    (see `crdt::trash`).
+/// 3. The table is marked dirty so the scanner picks up the haex_deleted_rows
 ///    change on the next sync cycle.
 ///
-/// Both are gated by `triggers_enabled` so the sync-receive path can bulk-delete
+/// All are gated by `triggers_enabled` so the sync-receive path can bulk-delete
 /// without re-logging.
-fn generate_delete_trigger_sql(table_name: &str, pks: &[String]) -> String {
+fn generate_delete_trigger_sql(table_name: &str, pks: &[String], columns: &[String]) -> String {
     let trigger_name = DELETE_TRIGGER_TPL.replace("{TABLE_NAME}", table_name);
 
     // Build JSON object for row_pks: json_object('pk1', OLD."pk1", ...)
@@ -415,6 +426,13 @@ fn generate_delete_trigger_sql(table_name: &str, pks: &[String]) -> String {
         .map(|name| format!("'{name}', OLD.\"{name}\""))
         .collect::<Vec<_>>()
         .join(", ");
+    let snapshot_json = snapshot_json_sql(columns);
+    // Space-scoped syncs only send deletes logged with their space
+    let space_id = if columns.iter().any(|c| c == "space_id") {
+        "OLD.\"space_id\""
+    } else {
+        "NULL"
+    };
 
     format!(
         "CREATE TRIGGER IF NOT EXISTS \"{trigger_name}\"
@@ -422,8 +440,12 @@ fn generate_delete_trigger_sql(table_name: &str, pks: &[String]) -> String {
             FOR EACH ROW
             WHEN (SELECT COALESCE(value, '1') FROM {TABLE_CRDT_CONFIGS} WHERE key = 'triggers_enabled') = '1'
             BEGIN
-            INSERT INTO {DELETED_ROWS_TABLE} (id, table_name, row_pks, {HLC_TIMESTAMP_COLUMN}, {COLUMN_HLCS_COLUMN})
-            VALUES ({UUID_FUNCTION_NAME}(), '{table_name}', json_object({row_pks_json}), {HLC_FUNCTION_NAME}(), '{{}}');
+            INSERT INTO {DELETED_ROWS_TABLE} (id, table_name, row_pks, space_id, {HLC_TIMESTAMP_COLUMN}, {COLUMN_HLCS_COLUMN})
+            VALUES ({UUID_FUNCTION_NAME}(), '{table_name}', json_object({row_pks_json}), {space_id}, {HLC_FUNCTION_NAME}(), '{{}}');
+            INSERT OR REPLACE INTO {TRASH_TABLE} (deleted_row_id, table_name, row_pks, row_data, deleted_hlc, deleted_at)
+            VALUES (
+                COALESCE((SELECT id FROM {DELETED_ROWS_TABLE} WHERE rowid = last_insert_rowid()), {UUID_FUNCTION_NAME}()),
+                '{table_name}', json_object({row_pks_json}), {snapshot_json}, {HLC_FUNCTION_NAME}(), datetime('now'));
             INSERT OR REPLACE INTO {TABLE_CRDT_DIRTY_TABLES} (table_name, last_modified)
             VALUES ('{DELETED_ROWS_TABLE}', datetime('now'));
             END;"
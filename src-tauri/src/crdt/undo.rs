@@ -0,0 +1,655 @@
+// src-tauri/src/crdt/undo.rs
+//!
+//! Session undo/redo for local CRDT writes.
+//!
+//! While a local write runs, [`UndoCapture`] watches its target table (and
+//! the tables a cascade deletes from) with TEMP triggers that copy the
+//! before- and after-image of every touched row into a temp table. The
+//! images are collapsed per primary key into [`RowChange`]s and kept as one
+//! [`UndoEntry`] on the stack of the write's [`UndoScope`]: per table for
+//! writes of the vault itself, per extension for extension writes.
+//!
+//! Undo and redo never restore old CRDT metadata. They replay the inverse
+//! (or the original) change through the CRDT executor, so the rows get a
+//! fresh HLC that is newer than the recorded write and the change syncs like
+//! any other local edit. Re-inserting a deleted row is an insert-after-delete
+//! ("resurrection", see `should_propagate_delete`).
+//!
+//! A row that was changed after the recorded write (locally or by sync) is
+//! never overwritten: the whole entry is refused with an [`UndoConflict`] and
+//! dropped from the stack. Stacks live in memory only and are cleared when
+//! the vault is closed.
+
+use crate::crdt::cascade;
+use crate::crdt::hlc::HlcService;
+use crate::crdt::trash::{json_to_sql_value, row_snapshot_json_sql};
+use crate::crdt::trigger::{
+    get_table_schema, is_safe_identifier, COLUMN_HLCS_COLUMN, HLC_TIMESTAMP_COLUMN,
+};
+use crate::database::core::extract_table_names_from_statement;
+use crate::database::error::DatabaseError;
+use crate::extension::database::executor::SqlExecutor;
+use rusqlite::{params_from_iter, Connection, OptionalExtension, ToSql, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use sqlparser::ast::Statement;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use time::format_description::well_known::Rfc3339;
+use ts_rs::TS;
+
+/// Undo entries kept per scope (oldest are dropped first).
+pub const UNDO_STACK_CAPACITY: usize = 50;
+
+/// Temp table the capture triggers write into. Lives in the connection's
+/// `temp` schema, so it is neither synced nor persisted.
+const CAPTURE_TABLE: &str = "haex_undo_capture";
+
+const CAPTURE_TRIGGER_PREFIX: &str = "haex_undo_";
+
+type RowImage = Map<String, JsonValue>;
+
+/// Stack an undo entry belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum UndoScope {
+    /// Writes of the vault itself to one table
+    #[serde(rename_all = "camelCase")]
+    Table { table_name: String },
+    /// Writes of one extension, to any of its tables
+    #[serde(rename_all = "camelCase")]
+    Extension { extension_id: String },
+}
+
+/// One row touched by a recorded write. `before` is `None` for an inserted
+/// row, `after` is `None` for a deleted one. CRDT columns are not part of
+/// the images.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowChange {
+    pub table_name: String,
+    pub pk_columns: Vec<String>,
+    pub before: Option<RowImage>,
+    pub after: Option<RowImage>,
+}
+
+impl RowChange {
+    fn pks(&self, image: &RowImage) -> RowImage {
+        self.pk_columns
+            .iter()
+            .map(|name| {
+                (
+                    name.clone(),
+                    image.get(name).cloned().unwrap_or(JsonValue::Null),
+                )
+            })
+            .collect()
+    }
+
+    /// Primary keys of the row as it is after the write
+    fn current_pks(&self) -> Option<RowImage> {
+        self.after
+            .as_ref()
+            .or(self.before.as_ref())
+            .map(|image| self.pks(image))
+    }
+}
+
+/// A recorded write.
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub id: String,
+    pub changes: Vec<RowChange>,
+    pub recorded_at: String,
+}
+
+impl UndoEntry {
+    pub fn new(changes: Vec<RowChange>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            changes,
+            recorded_at: time::OffsetDateTime::now_utc()
+                .format(&Rfc3339)
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn summary(&self) -> UndoStackEntry {
+        let mut tables: Vec<String> = Vec::new();
+        let (mut inserted, mut updated, mut deleted) = (0, 0, 0);
+        for change in &self.changes {
+            if !tables.contains(&change.table_name) {
+                tables.push(change.table_name.clone());
+            }
+            match (&change.before, &change.after) {
+                (None, _) => inserted += 1,
+                (_, None) => deleted += 1,
+                _ => updated += 1,
+            }
+        }
+        UndoStackEntry {
+            id: self.id.clone(),
+            tables,
+            inserted,
+            updated,
+            deleted,
+            recorded_at: self.recorded_at.clone(),
+        }
+    }
+}
+
+/// A recorded write as listed by `undo_get_stack`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoStackEntry {
+    pub id: String,
+    /// Tables the write touched, the target table first
+    pub tables: Vec<String>,
+    pub inserted: u32,
+    pub updated: u32,
+    pub deleted: u32,
+    pub recorded_at: String,
+}
+
+/// Undo and redo entries of a scope, newest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoStack {
+    pub undo: Vec<UndoStackEntry>,
+    pub redo: Vec<UndoStackEntry>,
+}
+
+/// Why an undo or redo was refused.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum UndoConflict {
+    /// The row no longer is as the write left it (changed locally or by sync
+    /// since). Nothing was applied and the entry was dropped.
+    #[serde(rename_all = "camelCase")]
+    RowChanged {
+        table_name: String,
+        /// Primary keys of the row as JSON object
+        row_pks: String,
+    },
+}
+
+/// Outcome of `undo_last` / `redo_last`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoResult {
+    pub applied: bool,
+    /// The entry that was undone/redone; `None` if the stack was empty
+    pub entry: Option<UndoStackEntry>,
+    pub conflict: Option<UndoConflict>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UndoDirection {
+    Undo,
+    Redo,
+}
+
+#[derive(Debug, Default)]
+struct ScopeStacks {
+    undo: VecDeque<UndoEntry>,
+    redo: Vec<UndoEntry>,
+}
+
+/// In-memory undo/redo stacks of the current session.
+#[derive(Debug, Default)]
+pub struct UndoService {
+    stacks: Mutex<HashMap<UndoScope, ScopeStacks>>,
+}
+
+impl UndoService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pushes a new write. Empty writes are ignored; any other write clears
+    /// the scope's redo stack.
+    pub fn record(&self, scope: UndoScope, changes: Vec<RowChange>) {
+        if changes.is_empty() {
+            return;
+        }
+        // A poisoned stack only loses undo history; never fail the write for it.
+        let Ok(mut stacks) = self.stacks.lock() else {
+            return;
+        };
+        let stacks = stacks.entry(scope).or_default();
+        if stacks.undo.len() >= UNDO_STACK_CAPACITY {
+            stacks.undo.pop_front();
+        }
+        stacks.undo.push_back(UndoEntry::new(changes));
+        stacks.redo.clear();
+    }
+
+    /// Takes the newest entry to undo (or redo) off its stack.
+    pub fn take(&self, scope: &UndoScope, direction: UndoDirection) -> Option<UndoEntry> {
+        let mut stacks = self.stacks.lock().ok()?;
+        let stacks = stacks.get_mut(scope)?;
+        match direction {
+            UndoDirection::Undo => stacks.undo.pop_back(),
+            UndoDirection::Redo => stacks.redo.pop(),
+        }
+    }
+
+    /// Files an applied entry on the opposite stack.
+    pub fn complete(&self, scope: &UndoScope, direction: UndoDirection, entry: UndoEntry) {
+        let Ok(mut stacks) = self.stacks.lock() else {
+            return;
+        };
+        let stacks = stacks.entry(scope.clone()).or_default();
+        match direction {
+            UndoDirection::Undo => stacks.redo.push(entry),
+            UndoDirection::Redo => {
+                if stacks.undo.len() >= UNDO_STACK_CAPACITY {
+                    stacks.undo.pop_front();
+                }
+                stacks.undo.push_back(entry);
+            }
+        }
+    }
+
+    /// Puts back an entry whose undo (or redo) failed with an error.
+    pub fn restore(&self, scope: &UndoScope, direction: UndoDirection, entry: UndoEntry) {
+        let Ok(mut stacks) = self.stacks.lock() else {
+            return;
+        };
+        let stacks = stacks.entry(scope.clone()).or_default();
+        match direction {
+            UndoDirection::Undo => stacks.undo.push_back(entry),
+            UndoDirection::Redo => stacks.redo.push(entry),
+        }
+    }
+
+    pub fn stack(&self, scope: &UndoScope) -> UndoStack {
+        let stacks = self.stacks.lock().ok();
+        let Some(stacks) = stacks.as_ref().and_then(|s| s.get(scope)) else {
+            return UndoStack {
+                undo: Vec::new(),
+                redo: Vec::new(),
+            };
+        };
+        UndoStack {
+            undo: stacks.undo.iter().rev().map(UndoEntry::summary).collect(),
+            redo: stacks.redo.iter().rev().map(UndoEntry::summary).collect(),
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut stacks) = self.stacks.lock() {
+            stacks.clear();
+        }
+    }
+}
+
+/// Target table of an INSERT/UPDATE/DELETE, `None` for other statements.
+pub fn write_target_table(statement: &Statement) -> Option<String> {
+    if !matches!(
+        statement,
+        Statement::Insert(_) | Statement::Update(_) | Statement::Delete(_)
+    ) {
+        return None;
+    }
+    let name = extract_table_names_from_statement(statement)
+        .into_iter()
+        .next()?;
+    let name = name.rsplit('.').next().unwrap_or(&name);
+    Some(name.trim_matches('"').trim_matches('`').to_string())
+}
+
+/// Columns of `table_name` without the CRDT columns, and its primary key
+/// columns. `None` for tables that are no CRDT tables or have no primary key.
+fn undo_columns(
+    conn: &Connection,
+    table_name: &str,
+) -> Result<Option<(Vec<String>, Vec<String>)>, DatabaseError> {
+    if !is_safe_identifier(table_name) {
+        return Ok(None);
+    }
+    let schema = get_table_schema(conn, table_name)?;
+    if !schema.iter().any(|c| c.name == HLC_TIMESTAMP_COLUMN) {
+        return Ok(None);
+    }
+    let columns: Vec<String> = schema
+        .iter()
+        .filter(|c| c.name != HLC_TIMESTAMP_COLUMN && c.name != COLUMN_HLCS_COLUMN)
+        .map(|c| c.name.clone())
+        .collect();
+    let pks: Vec<String> = schema
+        .iter()
+        .filter(|c| c.is_pk)
+        .map(|c| c.name.clone())
+        .collect();
+    if pks.is_empty() || !columns.iter().all(|c| is_safe_identifier(c)) {
+        return Ok(None);
+    }
+    Ok(Some((columns, pks)))
+}
+
+/// Records the rows one local write touches. Create it inside the write's
+/// transaction, [`watch`](Self::watch) the tables before the statements run
+/// and [`finish`](Self::finish) before the commit.
+#[derive(Debug, Default)]
+pub struct UndoCapture {
+    /// Watched table → primary key columns
+    watched: HashMap<String, Vec<String>>,
+}
+
+impl UndoCapture {
+    pub fn start(conn: &Connection) -> Result<Self, DatabaseError> {
+        conn.execute_batch(&format!(
+            "CREATE TEMP TABLE IF NOT EXISTS \"{CAPTURE_TABLE}\" (
+                seq INTEGER PRIMARY KEY,
+                table_name TEXT NOT NULL,
+                before TEXT,
+                after TEXT
+            );
+            DELETE FROM temp.\"{CAPTURE_TABLE}\";"
+        ))?;
+        Ok(Self::default())
+    }
+
+    /// Watches the target table of `statement`, if it is a write.
+    pub fn watch_statement(
+        &mut self,
+        conn: &Connection,
+        statement: &Statement,
+    ) -> Result<(), DatabaseError> {
+        match write_target_table(statement) {
+            Some(table_name) => self.watch(conn, &table_name),
+            None => Ok(()),
+        }
+    }
+
+    /// Watches `table_name` and, recursively, the tables its cascade rules
+    /// delete from. Tables that can't be undone are skipped.
+    pub fn watch(&mut self, conn: &Connection, table_name: &str) -> Result<(), DatabaseError> {
+        if self.watched.contains_key(table_name) {
+            return Ok(());
+        }
+        let Some((columns, pks)) = undo_columns(conn, table_name)? else {
+            return Ok(());
+        };
+
+        let new_json = row_snapshot_json_sql("NEW", &columns);
+        let old_json = row_snapshot_json_sql("OLD", &columns);
+        let trigger = |suffix: &str| format!("{CAPTURE_TRIGGER_PREFIX}{table_name}_{suffix}");
+        conn.execute_batch(&format!(
+            "CREATE TEMP TRIGGER \"{ins}\" AFTER INSERT ON main.\"{table_name}\" BEGIN
+                INSERT INTO \"{CAPTURE_TABLE}\" (table_name, before, after)
+                VALUES ('{table_name}', NULL, {new_json});
+            END;
+            CREATE TEMP TRIGGER \"{upd}\" AFTER UPDATE ON main.\"{table_name}\" BEGIN
+                INSERT INTO \"{CAPTURE_TABLE}\" (table_name, before, after)
+                VALUES ('{table_name}', {old_json}, {new_json});
+            END;
+            CREATE TEMP TRIGGER \"{del}\" BEFORE DELETE ON main.\"{table_name}\" BEGIN
+                INSERT INTO \"{CAPTURE_TABLE}\" (table_name, before, after)
+                VALUES ('{table_name}', {old_json}, NULL);
+            END;",
+            ins = trigger("insert"),
+            upd = trigger("update"),
+            del = trigger("delete"),
+        ))?;
+        self.watched.insert(table_name.to_string(), pks);
+
+        for rule in cascade::cascade_children(conn, table_name)? {
+            self.watch(conn, &rule.table)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the capture triggers and returns the touched rows, in write order.
+    pub fn finish(self, conn: &Connection) -> Result<Vec<RowChange>, DatabaseError> {
+        let captured = {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT table_name, before, after FROM temp.\"{CAPTURE_TABLE}\" ORDER BY seq"
+            ))?;
+            stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let mut cleanup = format!("DELETE FROM temp.\"{CAPTURE_TABLE}\";");
+        for table_name in self.watched.keys() {
+            for suffix in ["insert", "update", "delete"] {
+                cleanup.push_str(&format!(
+                    "DROP TRIGGER IF EXISTS temp.\"{CAPTURE_TRIGGER_PREFIX}{table_name}_{suffix}\";"
+                ));
+            }
+        }
+        conn.execute_batch(&cleanup)?;
+
+        let parse = |json: Option<String>| -> Result<Option<RowImage>, DatabaseError> {
+            json.map(|json| {
+                serde_json::from_str(&json).map_err(|e| DatabaseError::SerializationError {
+                    reason: format!("Invalid undo snapshot: {e}"),
+                })
+            })
+            .transpose()
+        };
+
+        let mut changes: Vec<RowChange> = Vec::new();
+        for (table_name, before, after) in captured {
+            let Some(pk_columns) = self.watched.get(&table_name) else {
+                continue;
+            };
+            let change = RowChange {
+                table_name,
+                pk_columns: pk_columns.clone(),
+                before: parse(before)?,
+                after: parse(after)?,
+            };
+            // A row touched again within the same write keeps its first
+            // before-image and takes the latest after-image.
+            let key = change.before.as_ref().map(|image| change.pks(image));
+            let earlier = key.as_ref().and_then(|key| {
+                changes.iter_mut().rev().find(|c| {
+                    c.table_name == change.table_name && c.current_pks().as_ref() == Some(key)
+                })
+            });
+            match earlier {
+                Some(earlier) => earlier.after = change.after,
+                None => changes.push(change),
+            }
+        }
+        changes.retain(|c| c.before != c.after);
+        Ok(changes)
+    }
+}
+
+/// The row as currently stored, with the same columns as `image`. `None` if
+/// it doesn't exist or lost one of the columns.
+fn current_image(
+    conn: &Connection,
+    change: &RowChange,
+    pks: &RowImage,
+    image: &RowImage,
+) -> Result<Option<RowImage>, DatabaseError> {
+    let schema = get_table_schema(conn, &change.table_name)?;
+    if !image
+        .keys()
+        .all(|name| schema.iter().any(|c| &c.name == name) && is_safe_identifier(name))
+    {
+        return Ok(None);
+    }
+    let columns: Vec<String> = image.keys().cloned().collect();
+    let (pk_where, pk_values) = pk_filter(change, pks)?;
+    let json: Option<String> = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM \"{}\" AS cur WHERE {pk_where}",
+                row_snapshot_json_sql("cur", &columns),
+                change.table_name
+            ),
+            params_from_iter(pk_values.iter()),
+            |row| row.get(0),
+        )
+        .optional()?;
+    json.map(|json| {
+        serde_json::from_str(&json).map_err(|e| DatabaseError::SerializationError {
+            reason: format!("Invalid undo snapshot: {e}"),
+        })
+    })
+    .transpose()
+}
+
+fn row_exists(
+    conn: &Connection,
+    change: &RowChange,
+    pks: &RowImage,
+) -> Result<bool, DatabaseError> {
+    let (pk_where, pk_values) = pk_filter(change, pks)?;
+    Ok(conn
+        .query_row(
+            &format!("SELECT 1 FROM \"{}\" WHERE {pk_where}", change.table_name),
+            params_from_iter(pk_values.iter()),
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+fn pk_filter(
+    change: &RowChange,
+    pks: &RowImage,
+) -> Result<(String, Vec<rusqlite::types::Value>), DatabaseError> {
+    let (pk_where, pk_values) =
+        crate::crdt::commands::build_pk_where_from_map(pks).ok_or_else(|| {
+            DatabaseError::ValidationError {
+                reason: format!(
+                    "Unsafe primary key columns in undo entry for {}",
+                    change.table_name
+                ),
+            }
+        })?;
+    let pk_values = pk_values
+        .iter()
+        .map(json_to_sql_value)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((pk_where, pk_values))
+}
+
+/// Turns one row from `from` into `to` through the CRDT executor, so the
+/// row gets the transaction's fresh HLC. Refuses if the row is no longer
+/// in the `from` state.
+fn apply_change(
+    tx: &Transaction,
+    hlc_service: &HlcService,
+    change: &RowChange,
+    from: Option<&RowImage>,
+    to: Option<&RowImage>,
+) -> Result<Option<UndoConflict>, DatabaseError> {
+    let conflict = |image: &RowImage| UndoConflict::RowChanged {
+        table_name: change.table_name.clone(),
+        row_pks: JsonValue::Object(change.pks(image)).to_string(),
+    };
+
+    let (sql, values) = match (from, to) {
+        (None, None) => return Ok(None),
+        (None, Some(to)) => {
+            if row_exists(tx, change, &change.pks(to))? {
+                return Ok(Some(conflict(to)));
+            }
+            let columns: Vec<String> = to.keys().map(|name| format!("\"{name}\"")).collect();
+            let values = to
+                .values()
+                .map(json_to_sql_value)
+                .collect::<Result<Vec<_>, _>>()?;
+            let sql = format!(
+                "INSERT INTO \"{}\" ({}) VALUES ({})",
+                change.table_name,
+                columns.join(", "),
+                vec!["?"; columns.len()].join(", ")
+            );
+            (sql, values)
+        }
+        (Some(from), to) => {
+            let pks = change.pks(from);
+            let current = current_image(tx, change, &pks, from)?;
+            // Already gone, e.g. deleted by the parent's cascade on redo
+            if current.is_none() && to.is_none() && !row_exists(tx, change, &pks)? {
+                return Ok(None);
+            }
+            if current.as_ref() != Some(from) {
+                return Ok(Some(conflict(from)));
+            }
+            let (pk_where, pk_values) = pk_filter(change, &pks)?;
+            match to {
+                None => (
+                    format!("DELETE FROM \"{}\" WHERE {pk_where}", change.table_name),
+                    pk_values,
+                ),
+                Some(to) => {
+                    let changed: Vec<(&String, &JsonValue)> = to
+                        .iter()
+                        .filter(|(name, value)| from.get(*name) != Some(*value))
+                        .collect();
+                    if changed.is_empty() {
+                        return Ok(None);
+                    }
+                    let assignments: Vec<String> = changed
+                        .iter()
+                        .map(|(name, _)| format!("\"{name}\" = ?"))
+                        .collect();
+                    let mut values = changed
+                        .iter()
+                        .map(|(_, value)| json_to_sql_value(value))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    values.extend(pk_values);
+                    (
+                        format!(
+                            "UPDATE \"{}\" SET {} WHERE {pk_where}",
+                            change.table_name,
+                            assignments.join(", ")
+                        ),
+                        values,
+                    )
+                }
+            }
+        }
+    };
+
+    let param_refs: Vec<&dyn ToSql> = values.iter().map(|v| v as &dyn ToSql).collect();
+    SqlExecutor::execute_internal_typed(tx, hlc_service, &sql, &param_refs)?;
+    Ok(None)
+}
+
+/// Undoes (reverse order, after → before) or redoes (write order,
+/// before → after) an entry. On a conflict nothing must be committed: the
+/// caller drops `tx`, which rolls back the changes applied so far.
+pub fn apply_entry(
+    tx: &Transaction,
+    hlc_service: &HlcService,
+    entry: &UndoEntry,
+    direction: UndoDirection,
+) -> Result<Option<UndoConflict>, DatabaseError> {
+    // Cascaded children are re-inserted before their parent on undo
+    tx.execute_batch("PRAGMA defer_foreign_keys = ON")?;
+
+    let ordered: Vec<&RowChange> = match direction {
+        UndoDirection::Undo => entry.changes.iter().rev().collect(),
+        UndoDirection::Redo => entry.changes.iter().collect(),
+    };
+    for change in ordered {
+        let (from, to) = match direction {
+            UndoDirection::Undo => (change.after.as_ref(), change.before.as_ref()),
+            UndoDirection::Redo => (change.before.as_ref(), change.after.as_ref()),
+        };
+        if let Some(conflict) = apply_change(tx, hlc_service, change, from, to)? {
+            return Ok(Some(conflict));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,318 @@
+//! Tests for the session undo in [`super::undo`]: captured writes are
+//! collapsed per row, undone and redone through the CRDT executor with fresh
+//! HLCs, and refused when a row changed in the meantime.
+
+#![cfg(test)]
+
+use rusqlite::Connection;
+use serde_json::{json, Value as JsonValue};
+use std::cmp::Ordering;
+
+use super::hlc::{compare_hlc_strings, HlcService};
+use super::trigger::{DELETED_ROWS_TABLE, HLC_TIMESTAMP_COLUMN};
+use super::undo::{
+    apply_entry, write_target_table, RowChange, UndoCapture, UndoConflict, UndoDirection,
+    UndoEntry, UndoScope, UndoService, UNDO_STACK_CAPACITY,
+};
+use crate::database::core::parse_single_statement;
+use crate::extension::database::executor::SqlExecutor;
+use crate::test_support::crdt::{create_crdt_table, open_crdt_connection};
+
+fn setup_db() -> (Connection, HlcService) {
+    let hlc = HlcService::new_for_testing("undo-test-device");
+    let conn = open_crdt_connection(hlc.clone()).unwrap();
+    create_crdt_table(
+        &conn,
+        "notes",
+        "CREATE TABLE notes (id TEXT PRIMARY KEY NOT NULL, title TEXT, pinned INTEGER, icon BLOB)",
+    )
+    .unwrap();
+
+    (conn, hlc)
+}
+
+/// Runs the statements as one recorded write
+fn write(
+    conn: &mut Connection,
+    hlc: &HlcService,
+    statements: &[(&str, &[JsonValue])],
+) -> UndoEntry {
+    let tx = conn.transaction().unwrap();
+    let mut capture = UndoCapture::start(&tx).unwrap();
+    capture.watch(&tx, "notes").unwrap();
+    for (sql, params) in statements {
+        SqlExecutor::execute_internal(&tx, hlc, sql, params).unwrap();
+    }
+    let changes = capture.finish(&tx).unwrap();
+    tx.commit().unwrap();
+    UndoEntry::new(changes)
+}
+
+fn apply(
+    conn: &mut Connection,
+    hlc: &HlcService,
+    entry: &UndoEntry,
+    direction: UndoDirection,
+) -> Option<UndoConflict> {
+    let tx = conn.transaction().unwrap();
+    let conflict = apply_entry(&tx, hlc, entry, direction).unwrap();
+    if conflict.is_none() {
+        tx.commit().unwrap();
+    }
+    conflict
+}
+
+fn title(conn: &Connection, id: &str) -> Option<String> {
+    conn.query_row("SELECT title FROM notes WHERE id = ?", [id], |r| r.get(0))
+        .ok()
+}
+
+fn row_hlc(conn: &Connection, id: &str) -> String {
+    conn.query_row(
+        &format!("SELECT {HLC_TIMESTAMP_COLUMN} FROM notes WHERE id = ?"),
+        [id],
+        |r| r.get(0),
+    )
+    .unwrap()
+}
+
+fn insert_note(conn: &mut Connection, hlc: &HlcService, id: &str, title: &str) -> UndoEntry {
+    write(
+        conn,
+        hlc,
+        &[(
+            "INSERT INTO notes (id, title, pinned, icon) VALUES (?, ?, 1, X'CAFE')",
+            &[JsonValue::from(id), JsonValue::from(title)],
+        )],
+    )
+}
+
+fn rename_note(conn: &mut Connection, hlc: &HlcService, id: &str, title: &str) -> UndoEntry {
+    write(
+        conn,
+        hlc,
+        &[(
+            "UPDATE notes SET title = ? WHERE id = ?",
+            &[JsonValue::from(title), JsonValue::from(id)],
+        )],
+    )
+}
+
+#[test]
+fn test_undo_update_restores_value_with_newer_hlc() {
+    let (mut conn, hlc) = setup_db();
+    insert_note(&mut conn, &hlc, "n1", "Old");
+    let entry = rename_note(&mut conn, &hlc, "n1", "New");
+    assert_eq!(entry.changes.len(), 1);
+    assert!(!entry.changes[0]
+        .after
+        .as_ref()
+        .unwrap()
+        .contains_key(HLC_TIMESTAMP_COLUMN));
+    let written_hlc = row_hlc(&conn, "n1");
+
+    assert_eq!(apply(&mut conn, &hlc, &entry, UndoDirection::Undo), None);
+    assert_eq!(title(&conn, "n1").as_deref(), Some("Old"));
+    let undone_hlc = row_hlc(&conn, "n1");
+    assert_eq!(
+        compare_hlc_strings(&undone_hlc, &written_hlc),
+        Ordering::Greater,
+        "the undo must win over the undone write on other devices"
+    );
+
+    assert_eq!(apply(&mut conn, &hlc, &entry, UndoDirection::Redo), None);
+    assert_eq!(title(&conn, "n1").as_deref(), Some("New"));
+    assert_eq!(
+        compare_hlc_strings(&row_hlc(&conn, "n1"), &undone_hlc),
+        Ordering::Greater
+    );
+}
+
+#[test]
+fn test_undo_insert_deletes_through_delete_log() {
+    let (mut conn, hlc) = setup_db();
+    let entry = insert_note(&mut conn, &hlc, "n1", "Groceries");
+    assert_eq!(entry.summary().inserted, 1);
+
+    assert_eq!(apply(&mut conn, &hlc, &entry, UndoDirection::Undo), None);
+    assert_eq!(title(&conn, "n1"), None);
+    let logged: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM {DELETED_ROWS_TABLE}"),
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(logged, 1, "the undo delete syncs like any other delete");
+
+    assert_eq!(apply(&mut conn, &hlc, &entry, UndoDirection::Redo), None);
+    assert_eq!(title(&conn, "n1").as_deref(), Some("Groceries"));
+}
+
+#[test]
+fn test_undo_delete_reinserts_row_with_blob() {
+    let (mut conn, hlc) = setup_db();
+    insert_note(&mut conn, &hlc, "n1", "Groceries");
+    let entry = write(
+        &mut conn,
+        &hlc,
+        &[("DELETE FROM notes WHERE id = ?", &[JsonValue::from("n1")])],
+    );
+    assert_eq!(entry.summary().deleted, 1);
+
+    assert_eq!(apply(&mut conn, &hlc, &entry, UndoDirection::Undo), None);
+    let (title, pinned, icon): (String, i64, Vec<u8>) = conn
+        .query_row(
+            "SELECT title, pinned, icon FROM notes WHERE id = 'n1'",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )
+        .unwrap();
+    assert_eq!(title, "Groceries");
+    assert_eq!(pinned, 1);
+    assert_eq!(icon, vec![0xCA, 0xFE]);
+}
+
+#[test]
+fn test_undo_refuses_to_overwrite_newer_change() {
+    let (mut conn, hlc) = setup_db();
+    insert_note(&mut conn, &hlc, "n1", "First");
+    let entry = rename_note(&mut conn, &hlc, "n1", "Second");
+    rename_note(&mut conn, &hlc, "n1", "Third");
+
+    assert_eq!(
+        apply(&mut conn, &hlc, &entry, UndoDirection::Undo),
+        Some(UndoConflict::RowChanged {
+            table_name: "notes".to_string(),
+            row_pks: json!({ "id": "n1" }).to_string(),
+        })
+    );
+    assert_eq!(title(&conn, "n1").as_deref(), Some("Third"));
+
+    // An insert is not undone once the row was edited
+    let (mut conn, hlc) = setup_db();
+    let entry = insert_note(&mut conn, &hlc, "n1", "First");
+    rename_note(&mut conn, &hlc, "n1", "Edited");
+    assert!(apply(&mut conn, &hlc, &entry, UndoDirection::Undo).is_some());
+    assert_eq!(title(&conn, "n1").as_deref(), Some("Edited"));
+}
+
+#[test]
+fn test_conflict_rolls_back_the_whole_entry() {
+    let (mut conn, hlc) = setup_db();
+    insert_note(&mut conn, &hlc, "n1", "A");
+    insert_note(&mut conn, &hlc, "n2", "B");
+    let entry = write(
+        &mut conn,
+        &hlc,
+        &[("UPDATE notes SET title = title || '!'", &[])],
+    );
+    assert_eq!(entry.summary().updated, 2);
+    // Only one of the two rows is changed again
+    rename_note(&mut conn, &hlc, "n1", "Edited");
+
+    assert!(apply(&mut conn, &hlc, &entry, UndoDirection::Undo).is_some());
+    assert_eq!(title(&conn, "n1").as_deref(), Some("Edited"));
+    assert_eq!(title(&conn, "n2").as_deref(), Some("B!"));
+}
+
+#[test]
+fn test_rows_touched_twice_are_collapsed() {
+    let (mut conn, hlc) = setup_db();
+    insert_note(&mut conn, &hlc, "n1", "A");
+    let entry = write(
+        &mut conn,
+        &hlc,
+        &[
+            ("UPDATE notes SET title = 'B' WHERE id = 'n1'", &[]),
+            ("UPDATE notes SET title = 'C' WHERE id = 'n1'", &[]),
+            ("INSERT INTO notes (id, title) VALUES ('tmp', 'x')", &[]),
+            ("DELETE FROM notes WHERE id = 'tmp'", &[]),
+        ],
+    );
+    assert_eq!(
+        entry.changes.len(),
+        1,
+        "an inserted and deleted row is no change"
+    );
+    assert_eq!(entry.changes[0].before.as_ref().unwrap()["title"], "A");
+    assert_eq!(entry.changes[0].after.as_ref().unwrap()["title"], "C");
+
+    assert_eq!(apply(&mut conn, &hlc, &entry, UndoDirection::Undo), None);
+    assert_eq!(title(&conn, "n1").as_deref(), Some("A"));
+}
+
+#[test]
+fn test_capture_triggers_are_removed() {
+    let (mut conn, hlc) = setup_db();
+    insert_note(&mut conn, &hlc, "n1", "A");
+    let temp_triggers: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_temp_master WHERE type = 'trigger'",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(temp_triggers, 0);
+}
+
+fn change(id: &str) -> RowChange {
+    RowChange {
+        table_name: "notes".to_string(),
+        pk_columns: vec!["id".to_string()],
+        before: None,
+        after: json!({ "id": id }).as_object().cloned(),
+    }
+}
+
+#[test]
+fn test_service_stacks_are_bounded_and_scoped() {
+    let service = UndoService::new();
+    let notes = UndoScope::Table {
+        table_name: "notes".to_string(),
+    };
+    let extension = UndoScope::Extension {
+        extension_id: "ext-1".to_string(),
+    };
+
+    for i in 0..UNDO_STACK_CAPACITY + 5 {
+        service.record(notes.clone(), vec![change(&i.to_string())]);
+    }
+    service.record(notes.clone(), Vec::new());
+    assert_eq!(service.stack(&notes).undo.len(), UNDO_STACK_CAPACITY);
+    assert!(service.stack(&extension).undo.is_empty());
+
+    let newest = service.take(&notes, UndoDirection::Undo).unwrap();
+    assert_eq!(
+        newest.changes,
+        vec![change(&(UNDO_STACK_CAPACITY + 4).to_string())]
+    );
+    service.complete(&notes, UndoDirection::Undo, newest);
+    assert_eq!(service.stack(&notes).redo.len(), 1);
+
+    // A new write drops the redo stack
+    service.record(notes.clone(), vec![change("new")]);
+    assert!(service.stack(&notes).redo.is_empty());
+    assert!(service.take(&notes, UndoDirection::Redo).is_none());
+
+    service.clear();
+    assert!(service.stack(&notes).undo.is_empty());
+}
+
+#[test]
+fn test_write_target_table() {
+    let target = |sql: &str| write_target_table(&parse_single_statement(sql).unwrap());
+    assert_eq!(
+        target("INSERT INTO \"notes\" (id) VALUES ('a')").as_deref(),
+        Some("notes")
+    );
+    assert_eq!(
+        target("UPDATE notes SET title = (SELECT 'x' FROM other)").as_deref(),
+        Some("notes")
+    );
+    assert_eq!(
+        target("DELETE FROM main.notes WHERE id IN (SELECT id FROM other)").as_deref(),
+        Some("notes")
+    );
+    assert_eq!(target("SELECT * FROM notes"), None);
+}
@@ -0,0 +1,505 @@
+// src-tauri/src/crdt/unique_conflict.rs
+//!
+//! UNIQUE-constraint conflicts of replicated inserts.
+//!
+//! Two devices can insert rows with different primary keys but the same
+//! value in a UNIQUE column. When such a row arrives via sync, the INSERT in
+//! `apply_remote_changes_to_db` fails. Instead of failing the whole sync
+//! transaction, the conflict is handled with a configurable strategy:
+//!
+//! - `reject`: skip the remote row and record it in `haex_crdt_conflicts`
+//!   for the user to resolve (default).
+//! - `rename`: keep both rows. The row with the greater primary key gets its
+//!   conflicting TEXT values suffixed with a short tag derived from its
+//!   primary key. The choice and the new value only depend on the two rows,
+//!   so every device ends up with the same result without syncing the rename.
+//! - `merge`: fold the remote row into the existing local row. Columns whose
+//!   remote HLC is newer than the local column HLC overwrite the local value;
+//!   the remote primary key is dropped.
+//!
+//! Strategies are per-device settings in haex_crdt_configs (local-only),
+//! with an optional override per table.
+
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+use crate::crdt::hlc::{hlc_is_newer, hlc_max};
+use crate::crdt::trigger::{
+    is_safe_identifier, ColumnInfo, COLUMN_HLCS_COLUMN, HLC_TIMESTAMP_COLUMN,
+};
+use crate::database::constants::vault_settings_key;
+use crate::database::core::ValueConverter;
+use crate::database::error::DatabaseError;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, TABLE_CRDT_CONFIGS,
+};
+
+const SAVEPOINT: &str = "crdt_unique_conflict";
+
+/// Length of the primary-key tag appended by the `rename` strategy.
+const RENAME_TAG_LEN: usize = 8;
+
+/// How a UNIQUE conflict of a replicated insert is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum UniqueConflictStrategy {
+    /// Skip the remote row and record a conflict entry
+    #[default]
+    Reject,
+    /// Keep both rows, suffix the conflicting values of one of them
+    Rename,
+    /// Merge the remote row into the existing one by column HLC
+    Merge,
+}
+
+impl UniqueConflictStrategy {
+    fn as_str(self) -> &'static str {
+        match self {
+            UniqueConflictStrategy::Reject => "reject",
+            UniqueConflictStrategy::Rename => "rename",
+            UniqueConflictStrategy::Merge => "merge",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "reject" => Some(UniqueConflictStrategy::Reject),
+            "rename" => Some(UniqueConflictStrategy::Rename),
+            "merge" => Some(UniqueConflictStrategy::Merge),
+            _ => None,
+        }
+    }
+}
+
+/// Configured strategies: the default and per-table overrides.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct UniqueConflictSettings {
+    pub default_strategy: UniqueConflictStrategy,
+    pub tables: BTreeMap<String, UniqueConflictStrategy>,
+}
+
+/// Strategy for `table_name`: its override, else the default, else `reject`.
+pub fn strategy_for_table(
+    conn: &Connection,
+    table_name: &str,
+) -> Result<UniqueConflictStrategy, DatabaseError> {
+    let table_key = format!(
+        "{}{table_name}",
+        vault_settings_key::UNIQUE_CONFLICT_STRATEGY_PREFIX
+    );
+    for key in [
+        table_key.as_str(),
+        vault_settings_key::UNIQUE_CONFLICT_STRATEGY,
+    ] {
+        if let Some(strategy) = read_strategy(conn, key)? {
+            return Ok(strategy);
+        }
+    }
+    Ok(UniqueConflictStrategy::Reject)
+}
+
+/// Reads the default strategy and all per-table overrides.
+pub fn load_settings(conn: &Connection) -> Result<UniqueConflictSettings, DatabaseError> {
+    let mut settings = UniqueConflictSettings {
+        default_strategy: read_strategy(conn, vault_settings_key::UNIQUE_CONFLICT_STRATEGY)?
+            .unwrap_or_default(),
+        tables: BTreeMap::new(),
+    };
+
+    let prefix = vault_settings_key::UNIQUE_CONFLICT_STRATEGY_PREFIX;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS}
+         WHERE substr({COL_CRDT_CONFIGS_KEY}, 1, length(?1)) = ?1"
+    ))?;
+    let rows = stmt
+        .query_map([prefix], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (key, value) in rows {
+        if let Some(strategy) = UniqueConflictStrategy::parse(&value) {
+            settings
+                .tables
+                .insert(key[prefix.len()..].to_string(), strategy);
+        }
+    }
+    Ok(settings)
+}
+
+/// Sets the strategy for `table_name` (or the default if `None`).
+/// `strategy = None` removes the setting.
+pub fn set_strategy(
+    conn: &Connection,
+    table_name: Option<&str>,
+    strategy: Option<UniqueConflictStrategy>,
+) -> Result<(), DatabaseError> {
+    let key = match table_name {
+        Some(table) if is_safe_identifier(table) => format!(
+            "{}{table}",
+            vault_settings_key::UNIQUE_CONFLICT_STRATEGY_PREFIX
+        ),
+        Some(table) => {
+            return Err(DatabaseError::ValidationError {
+                reason: format!("Invalid table name: {table}"),
+            })
+        }
+        None => vault_settings_key::UNIQUE_CONFLICT_STRATEGY.to_string(),
+    };
+
+    match strategy {
+        Some(strategy) => conn.execute(
+            &format!(
+                "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?, ?, ?)"
+            ),
+            params![key, "system", strategy.as_str()],
+        )?,
+        None => conn.execute(
+            &format!("DELETE FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"),
+            params![key],
+        )?,
+    };
+    Ok(())
+}
+
+fn read_strategy(
+    conn: &Connection,
+    key: &str,
+) -> Result<Option<UniqueConflictStrategy>, DatabaseError> {
+    let value: Option<String> = conn
+        .query_row(
+            &format!(
+                "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"
+            ),
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value.as_deref().and_then(UniqueConflictStrategy::parse))
+}
+
+/// Extracts the column names from a SQLite UNIQUE error message, e.g.
+/// `UNIQUE constraint failed: notes.slug, notes.owner` -> `[slug, owner]`.
+/// Returns an empty list for expression indexes (`index 'name'`).
+pub fn conflict_columns(error_msg: &str, table_name: &str) -> Vec<String> {
+    let Some(list) = error_msg.strip_prefix("UNIQUE constraint failed: ") else {
+        return Vec::new();
+    };
+    let table_prefix = format!("{table_name}.");
+    let columns: Vec<String> = list
+        .split(", ")
+        .filter_map(|entry| entry.trim().strip_prefix(&table_prefix))
+        .map(str::to_string)
+        .collect();
+    if columns.iter().all(|c| is_safe_identifier(c)) {
+        columns
+    } else {
+        Vec::new()
+    }
+}
+
+/// Primary-key values of a `row_pks` JSON object in column-name order.
+/// Decides which row of a conflict gets renamed, independent of JSON formatting.
+fn pk_key(row_pks_json: &str) -> String {
+    let Ok(pks) = serde_json::from_str::<serde_json::Map<String, JsonValue>>(row_pks_json) else {
+        return row_pks_json.to_string();
+    };
+    let mut entries: Vec<(&String, &JsonValue)> = pks.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+        .into_iter()
+        .map(|(_, v)| match v {
+            JsonValue::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\u{1f}")
+}
+
+/// Value with the rename tag of the row identified by `row_pks_json`.
+pub fn renamed_value(value: &str, row_pks_json: &str) -> String {
+    let tag: String = pk_key(row_pks_json)
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .take(RENAME_TAG_LEN)
+        .collect();
+    format!("{value} ({tag})")
+}
+
+/// A remote row whose INSERT failed with a UNIQUE violation.
+pub(crate) struct RemoteInsert<'a> {
+    pub table_name: &'a str,
+    /// Primary keys of the remote row as JSON object
+    pub row_pks: &'a str,
+    /// Columns and values of the failed INSERT
+    pub columns: &'a [String],
+    pub values: &'a [SqlValue],
+    /// Remote column changes `(column, value, hlc)` that won against local state
+    pub changes: &'a [(String, JsonValue, String)],
+    pub schema: &'a [ColumnInfo],
+}
+
+/// The local row that holds the conflicting values.
+struct LocalRow {
+    pk_where: String,
+    pk_values: Vec<SqlValue>,
+    row_pks_json: String,
+    column_hlcs: serde_json::Map<String, JsonValue>,
+    unique_values: Vec<SqlValue>,
+}
+
+/// Applies `strategy` to a failed insert. Returns `true` if the conflict was
+/// resolved; `false` means the caller should reject and record it. All
+/// writes happen in a savepoint and are rolled back if resolution fails.
+pub(crate) fn resolve(
+    tx: &Transaction,
+    strategy: UniqueConflictStrategy,
+    insert: &RemoteInsert,
+    error_msg: &str,
+) -> Result<bool, DatabaseError> {
+    if strategy == UniqueConflictStrategy::Reject {
+        return Ok(false);
+    }
+    let unique_columns = conflict_columns(error_msg, insert.table_name);
+    if unique_columns.is_empty() {
+        return Ok(false);
+    }
+    let Some(local) = find_local_row(tx, insert, &unique_columns)? else {
+        return Ok(false);
+    };
+
+    tx.execute_batch(&format!("SAVEPOINT {SAVEPOINT}"))?;
+    let outcome = match strategy {
+        UniqueConflictStrategy::Rename => rename(tx, insert, &unique_columns, &local),
+        UniqueConflictStrategy::Merge => merge(tx, insert, &local),
+        UniqueConflictStrategy::Reject => Ok(false),
+    };
+    match outcome {
+        Ok(true) => {
+            tx.execute_batch(&format!("RELEASE {SAVEPOINT}"))?;
+            eprintln!(
+                "[SYNC RUST] UNIQUE conflict in '{}' resolved with strategy '{}'",
+                insert.table_name,
+                strategy.as_str()
+            );
+            Ok(true)
+        }
+        Ok(false) => {
+            tx.execute_batch(&format!("ROLLBACK TO {SAVEPOINT}; RELEASE {SAVEPOINT}"))?;
+            Ok(false)
+        }
+        Err(e) => {
+            tx.execute_batch(&format!("ROLLBACK TO {SAVEPOINT}; RELEASE {SAVEPOINT}"))?;
+            Err(e)
+        }
+    }
+}
+
+fn find_local_row(
+    tx: &Transaction,
+    insert: &RemoteInsert,
+    unique_columns: &[String],
+) -> Result<Option<LocalRow>, DatabaseError> {
+    let mut remote_values = Vec::with_capacity(unique_columns.len());
+    for column in unique_columns {
+        match insert.columns.iter().position(|c| c == column) {
+            Some(i) => remote_values.push(insert.values[i].clone()),
+            None => return Ok(None),
+        }
+    }
+
+    let pk_columns: Vec<&str> = insert
+        .schema
+        .iter()
+        .filter(|c| c.is_pk)
+        .map(|c| c.name.as_str())
+        .collect();
+    if pk_columns.is_empty() {
+        return Ok(None);
+    }
+    let select = pk_columns
+        .iter()
+        .chain(unique_columns.iter().map(String::as_str))
+        .map(|c| format!("\"{c}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let condition = unique_columns
+        .iter()
+        .map(|c| format!("\"{c}\" = ?"))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let sql = format!(
+        "SELECT {select}, {COLUMN_HLCS_COLUMN} FROM \"{}\" WHERE {condition} LIMIT 1",
+        insert.table_name
+    );
+
+    let row = tx
+        .query_row(
+            &sql,
+            rusqlite::params_from_iter(remote_values.iter()),
+            |row| {
+                let mut values = Vec::with_capacity(pk_columns.len() + unique_columns.len() + 1);
+                for i in 0..pk_columns.len() + unique_columns.len() {
+                    values.push(row.get::<_, SqlValue>(i)?);
+                }
+                let hlcs: Option<String> = row.get(values.len())?;
+                Ok((values, hlcs))
+            },
+        )
+        .optional()?;
+    let Some((mut values, hlcs)) = row else {
+        return Ok(None);
+    };
+    let unique_values = values.split_off(pk_columns.len());
+
+    let mut pks = serde_json::Map::new();
+    let mut where_parts = Vec::new();
+    let mut pk_values = Vec::new();
+    for (column, value) in pk_columns.iter().zip(values) {
+        pks.insert(
+            column.to_string(),
+            ValueConverter::rusqlite_value_to_json(&value),
+        );
+        if value == SqlValue::Null {
+            where_parts.push(format!("\"{column}\" IS NULL"));
+        } else {
+            where_parts.push(format!("\"{column}\" = ?"));
+            pk_values.push(value);
+        }
+    }
+
+    Ok(Some(LocalRow {
+        pk_where: where_parts.join(" AND "),
+        pk_values,
+        row_pks_json: JsonValue::Object(pks).to_string(),
+        column_hlcs: hlcs
+            .and_then(|h| serde_json::from_str(&h).ok())
+            .unwrap_or_default(),
+        unique_values,
+    }))
+}
+
+fn rename(
+    tx: &Transaction,
+    insert: &RemoteInsert,
+    unique_columns: &[String],
+    local: &LocalRow,
+) -> Result<bool, DatabaseError> {
+    // Only TEXT values can carry a suffix
+    let local_texts: Option<Vec<&str>> = local
+        .unique_values
+        .iter()
+        .map(|v| match v {
+            SqlValue::Text(s) => Some(s.as_str()),
+            _ => None,
+        })
+        .collect();
+    let Some(local_texts) = local_texts else {
+        return Ok(false);
+    };
+
+    let remote_loses = pk_key(insert.row_pks) > pk_key(&local.row_pks_json);
+    let mut values = insert.values.to_vec();
+
+    if remote_loses {
+        for column in unique_columns {
+            let i = insert.columns.iter().position(|c| c == column);
+            match i.and_then(|i| values.get_mut(i)) {
+                Some(SqlValue::Text(s)) => *s = renamed_value(s, insert.row_pks),
+                _ => return Ok(false),
+            }
+        }
+    } else {
+        let set_clause = unique_columns
+            .iter()
+            .map(|c| format!("\"{c}\" = ?"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut params: Vec<SqlValue> = local_texts
+            .iter()
+            .map(|s| SqlValue::Text(renamed_value(s, &local.row_pks_json)))
+            .collect();
+        params.extend(local.pk_values.iter().cloned());
+        let sql = format!(
+            "UPDATE \"{}\" SET {set_clause} WHERE {}",
+            insert.table_name, local.pk_where
+        );
+        if !execute_unless_constraint(tx, &sql, &params)? {
+            return Ok(false);
+        }
+    }
+
+    let columns = insert
+        .columns
+        .iter()
+        .map(|c| format!("\"{c}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let placeholders = vec!["?"; insert.columns.len()].join(", ");
+    let sql = format!(
+        "INSERT INTO \"{}\" ({columns}) VALUES ({placeholders})",
+        insert.table_name
+    );
+    execute_unless_constraint(tx, &sql, &values)
+}
+
+fn merge(tx: &Transaction, insert: &RemoteInsert, local: &LocalRow) -> Result<bool, DatabaseError> {
+    let mut column_hlcs = local.column_hlcs.clone();
+    let mut assignments = Vec::new();
+    let mut params = Vec::new();
+
+    for (column, value, hlc) in insert.changes {
+        let is_pk = insert.schema.iter().any(|c| c.is_pk && &c.name == column);
+        let local_hlc = column_hlcs
+            .get(column)
+            .and_then(JsonValue::as_str)
+            .unwrap_or_default();
+        if is_pk || !hlc_is_newer(hlc, local_hlc) {
+            continue;
+        }
+        column_hlcs.insert(column.clone(), JsonValue::String(hlc.clone()));
+        assignments.push(format!("\"{column}\" = ?"));
+        params.push(ValueConverter::json_to_rusqlite_value(value)?);
+    }
+
+    if assignments.is_empty() {
+        return Ok(true);
+    }
+
+    let row_hlc = hlc_max(column_hlcs.values().filter_map(JsonValue::as_str))
+        .unwrap_or_default()
+        .to_string();
+    params.push(SqlValue::Text(JsonValue::Object(column_hlcs).to_string()));
+    params.push(SqlValue::Text(row_hlc));
+    params.extend(local.pk_values.iter().cloned());
+    let sql = format!(
+        "UPDATE \"{}\" SET {}, {COLUMN_HLCS_COLUMN} = ?, {HLC_TIMESTAMP_COLUMN} = ? WHERE {}",
+        insert.table_name,
+        assignments.join(", "),
+        local.pk_where
+    );
+    execute_unless_constraint(tx, &sql, &params)
+}
+
+/// Executes `sql`; a constraint violation yields `Ok(false)` instead of an error.
+fn execute_unless_constraint(
+    tx: &Transaction,
+    sql: &str,
+    params: &[SqlValue],
+) -> Result<bool, DatabaseError> {
+    match tx.execute(sql, rusqlite::params_from_iter(params.iter())) {
+        Ok(_) => Ok(true),
+        Err(rusqlite::Error::SqliteFailure(err, _))
+            if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+        {
+            Ok(false)
+        }
+        Err(e) => Err(DatabaseError::from(e)),
+    }
+}
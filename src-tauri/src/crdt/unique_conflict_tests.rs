@@ -0,0 +1,245 @@
+//! Tests for UNIQUE conflicts of synced inserts in [`super::unique_conflict`]:
+//! the strategy settings and how `apply_remote_changes_to_db` rejects,
+//! renames or merges a remote row that collides with a local one.
+
+#![cfg(test)]
+
+use serde_json::Value as JsonValue;
+
+use super::commands::{apply_remote_changes_to_db, RemoteColumnChange};
+use super::hlc::HlcService;
+use super::unique_conflict::{
+    conflict_columns, load_settings, renamed_value, set_strategy, strategy_for_table,
+    UniqueConflictStrategy,
+};
+use crate::database::core::with_connection;
+use crate::database::DbConnection;
+use crate::extension::database::executor::SqlExecutor;
+use crate::table_names::TABLE_CRDT_CONFLICTS;
+use crate::test_support::crdt::{create_crdt_table, open_crdt_connection};
+use crate::test_support::db::db_connection;
+
+fn setup_db() -> (DbConnection, HlcService) {
+    let hlc = HlcService::new_for_testing("unique-test-device");
+    let conn = open_crdt_connection(hlc.clone()).unwrap();
+    create_crdt_table(
+        &conn,
+        "tags",
+        "CREATE TABLE tags (id TEXT PRIMARY KEY NOT NULL, name TEXT NOT NULL UNIQUE, color TEXT)",
+    )
+    .unwrap();
+
+    (db_connection(conn), hlc)
+}
+
+fn insert_local(db: &DbConnection, hlc: &HlcService, id: &str, name: &str, color: &str) {
+    with_connection(db, |conn| {
+        let tx = conn.transaction()?;
+        SqlExecutor::execute_internal(
+            &tx,
+            hlc,
+            "INSERT INTO tags (id, name, color) VALUES (?, ?, ?)",
+            &[
+                JsonValue::from(id),
+                JsonValue::from(name),
+                JsonValue::from(color),
+            ],
+        )?;
+        tx.commit()?;
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Column changes of a remote insert, all at `hlc`.
+fn remote_insert(id: &str, name: &str, color: &str, hlc: &str) -> Vec<RemoteColumnChange> {
+    [("name", name), ("color", color)]
+        .into_iter()
+        .map(|(column, value)| RemoteColumnChange {
+            table_name: "tags".to_string(),
+            row_pks: format!(r#"{{"id":"{id}"}}"#),
+            column_name: column.to_string(),
+            hlc_timestamp: hlc.to_string(),
+            decrypted_value: JsonValue::from(value),
+        })
+        .collect()
+}
+
+fn remote_hlc(remote: &HlcService) -> String {
+    remote.new_timestamp().unwrap().to_string()
+}
+
+fn rows(db: &DbConnection) -> Vec<(String, String, String)> {
+    with_connection(db, |conn| {
+        let mut stmt = conn.prepare("SELECT id, name, color FROM tags ORDER BY id")?;
+        let rows = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    })
+    .unwrap()
+}
+
+fn row(id: &str, name: &str, color: &str) -> (String, String, String) {
+    (id.to_string(), name.to_string(), color.to_string())
+}
+
+fn conflict_count(db: &DbConnection) -> i64 {
+    with_connection(db, |conn| {
+        Ok(conn.query_row(
+            &format!("SELECT COUNT(*) FROM {TABLE_CRDT_CONFLICTS}"),
+            [],
+            |r| r.get(0),
+        )?)
+    })
+    .unwrap()
+}
+
+fn configure(db: &DbConnection, table: Option<&str>, strategy: UniqueConflictStrategy) {
+    with_connection(db, |conn| set_strategy(conn, table, Some(strategy))).unwrap();
+}
+
+#[test]
+fn test_conflict_columns_and_rename_tag() {
+    assert_eq!(
+        conflict_columns("UNIQUE constraint failed: tags.name, tags.owner", "tags"),
+        vec!["name", "owner"]
+    );
+    assert!(conflict_columns("UNIQUE constraint failed: index 'tags_lower'", "tags").is_empty());
+    assert!(conflict_columns("NOT NULL constraint failed: tags.name", "tags").is_empty());
+
+    assert_eq!(
+        renamed_value("work", r#"{"id":"7f3a-91bc-0042"}"#),
+        "work (7f3a91bc)"
+    );
+    // Key order and formatting of row_pks don't change the tag.
+    assert_eq!(
+        renamed_value("work", r#"{"b": "2", "a": "1"}"#),
+        renamed_value("work", r#"{"a":"1","b":"2"}"#)
+    );
+}
+
+#[test]
+fn test_strategy_settings_prefer_table_override() {
+    let (db, _hlc) = setup_db();
+    with_connection(&db, |conn| {
+        assert_eq!(
+            strategy_for_table(conn, "tags")?,
+            UniqueConflictStrategy::Reject
+        );
+
+        set_strategy(conn, None, Some(UniqueConflictStrategy::Merge))?;
+        set_strategy(conn, Some("tags"), Some(UniqueConflictStrategy::Rename))?;
+        assert_eq!(
+            strategy_for_table(conn, "tags")?,
+            UniqueConflictStrategy::Rename
+        );
+        assert_eq!(
+            strategy_for_table(conn, "notes")?,
+            UniqueConflictStrategy::Merge
+        );
+
+        let settings = load_settings(conn)?;
+        assert_eq!(settings.default_strategy, UniqueConflictStrategy::Merge);
+        assert_eq!(
+            settings.tables.get("tags"),
+            Some(&UniqueConflictStrategy::Rename)
+        );
+
+        set_strategy(conn, Some("tags"), None)?;
+        assert_eq!(
+            strategy_for_table(conn, "tags")?,
+            UniqueConflictStrategy::Merge
+        );
+        assert!(set_strategy(conn, Some("tags; --"), None).is_err());
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_reject_records_conflict_and_keeps_syncing() {
+    let (db, hlc) = setup_db();
+    let remote = HlcService::new_for_testing("remote-device");
+    insert_local(&db, &hlc, "a", "work", "red");
+
+    let ts = remote_hlc(&remote);
+    let mut changes = remote_insert("b", "work", "blue", &ts);
+    changes.extend(remote_insert("c", "home", "green", &ts));
+    apply_remote_changes_to_db(&db, changes, None, Some(&hlc)).unwrap();
+
+    assert_eq!(
+        rows(&db),
+        vec![row("a", "work", "red"), row("c", "home", "green")]
+    );
+    assert_eq!(conflict_count(&db), 1);
+}
+
+#[test]
+fn test_rename_suffixes_the_remote_row_with_greater_pk() {
+    let (db, hlc) = setup_db();
+    let remote = HlcService::new_for_testing("remote-device");
+    configure(&db, Some("tags"), UniqueConflictStrategy::Rename);
+    insert_local(&db, &hlc, "a", "work", "red");
+
+    let changes = remote_insert("b", "work", "blue", &remote_hlc(&remote));
+    apply_remote_changes_to_db(&db, changes, None, Some(&hlc)).unwrap();
+
+    assert_eq!(
+        rows(&db),
+        vec![row("a", "work", "red"), row("b", "work (b)", "blue")]
+    );
+    assert_eq!(conflict_count(&db), 0);
+}
+
+#[test]
+fn test_rename_suffixes_the_local_row_with_greater_pk() {
+    let (db, hlc) = setup_db();
+    let remote = HlcService::new_for_testing("remote-device");
+    configure(&db, None, UniqueConflictStrategy::Rename);
+    insert_local(&db, &hlc, "b", "work", "red");
+
+    let changes = remote_insert("a", "work", "blue", &remote_hlc(&remote));
+    apply_remote_changes_to_db(&db, changes, None, Some(&hlc)).unwrap();
+
+    // Same outcome as on the device where "a" is local: the greater PK is renamed.
+    assert_eq!(
+        rows(&db),
+        vec![row("a", "work", "blue"), row("b", "work (b)", "red")]
+    );
+}
+
+#[test]
+fn test_merge_applies_newer_remote_columns_to_existing_row() {
+    let (db, hlc) = setup_db();
+    let remote = HlcService::new_for_testing("remote-device");
+    configure(&db, Some("tags"), UniqueConflictStrategy::Merge);
+    insert_local(&db, &hlc, "a", "work", "red");
+
+    let changes = remote_insert("b", "work", "blue", &remote_hlc(&remote));
+    apply_remote_changes_to_db(&db, changes, None, Some(&hlc)).unwrap();
+
+    assert_eq!(rows(&db), vec![row("a", "work", "blue")]);
+    assert_eq!(conflict_count(&db), 0);
+}
+
+#[test]
+fn test_merge_keeps_newer_local_columns() {
+    let (db, hlc) = setup_db();
+    let remote = HlcService::new_for_testing("remote-device");
+    configure(&db, Some("tags"), UniqueConflictStrategy::Merge);
+
+    // Remote insert happened before the local one.
+    let older = remote_hlc(&remote);
+    insert_local(&db, &hlc, "a", "work", "red");
+
+    apply_remote_changes_to_db(
+        &db,
+        remote_insert("b", "work", "blue", &older),
+        None,
+        Some(&hlc),
+    )
+    .unwrap();
+
+    assert_eq!(rows(&db), vec![row("a", "work", "red")]);
+}
@@ -0,0 +1,123 @@
+//! Tests for `INSERT ... ON CONFLICT` on CRDT tables: the conflict branch is
+//! stamped like an UPDATE, so only the columns it actually changes get a new
+//! column HLC.
+
+#![cfg(test)]
+
+use rusqlite::Connection;
+use serde_json::Value as JsonValue;
+
+use super::hlc::HlcService;
+use crate::extension::database::executor::SqlExecutor;
+use crate::test_support::crdt::{create_crdt_table, open_crdt_connection};
+
+fn setup_db() -> (Connection, HlcService) {
+    let hlc = HlcService::new_for_testing("upsert-test-device");
+    let conn = open_crdt_connection(hlc.clone()).unwrap();
+    create_crdt_table(
+        &conn,
+        "notes",
+        "CREATE TABLE notes (id TEXT PRIMARY KEY NOT NULL, title TEXT, pinned INTEGER)",
+    )
+    .unwrap();
+
+    (conn, hlc)
+}
+
+fn exec(conn: &mut Connection, hlc: &HlcService, sql: &str, params: &[JsonValue]) {
+    let tx = conn.transaction().unwrap();
+    SqlExecutor::execute_internal(&tx, hlc, sql, params).unwrap();
+    tx.commit().unwrap();
+}
+
+/// Row HLC and column HLCs of note `id`.
+fn hlcs(conn: &Connection, id: &str) -> (String, serde_json::Map<String, JsonValue>) {
+    let (row_hlc, column_hlcs): (String, String) = conn
+        .query_row(
+            "SELECT haex_hlc, haex_column_hlcs FROM notes WHERE id = ?",
+            [id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap();
+    (row_hlc, serde_json::from_str(&column_hlcs).unwrap())
+}
+
+const UPSERT_TITLE: &str = "INSERT INTO notes (id, title, pinned) VALUES (?, ?, ?)
+     ON CONFLICT(id) DO UPDATE SET title = excluded.title";
+
+#[test]
+fn test_upsert_inserts_missing_row() {
+    let (mut conn, hlc) = setup_db();
+    exec(
+        &mut conn,
+        &hlc,
+        UPSERT_TITLE,
+        &[
+            JsonValue::from("n1"),
+            JsonValue::from("First"),
+            JsonValue::from(1),
+        ],
+    );
+
+    let (row_hlc, column_hlcs) = hlcs(&conn, "n1");
+    assert_eq!(column_hlcs["title"], row_hlc.as_str());
+    assert_eq!(column_hlcs["pinned"], row_hlc.as_str());
+}
+
+#[test]
+fn test_upsert_conflict_only_stamps_written_columns() {
+    let (mut conn, hlc) = setup_db();
+    exec(
+        &mut conn,
+        &hlc,
+        "INSERT INTO notes (id, title, pinned) VALUES ('n1', 'First', 1)",
+        &[],
+    );
+    let (insert_hlc, _) = hlcs(&conn, "n1");
+
+    // `pinned` is in the VALUES list but not in the conflict branch.
+    exec(
+        &mut conn,
+        &hlc,
+        UPSERT_TITLE,
+        &[
+            JsonValue::from("n1"),
+            JsonValue::from("Second"),
+            JsonValue::from(0),
+        ],
+    );
+
+    let (title, pinned): (String, i64) = conn
+        .query_row("SELECT title, pinned FROM notes WHERE id = 'n1'", [], |r| {
+            Ok((r.get(0)?, r.get(1)?))
+        })
+        .unwrap();
+    assert_eq!(title, "Second");
+    assert_eq!(pinned, 1);
+
+    let (row_hlc, column_hlcs) = hlcs(&conn, "n1");
+    assert_ne!(row_hlc, insert_hlc, "conflict branch must bump the row HLC");
+    assert_eq!(column_hlcs["title"], row_hlc.as_str());
+    assert_eq!(column_hlcs["pinned"], insert_hlc.as_str());
+}
+
+#[test]
+fn test_upsert_do_nothing_leaves_row_untouched() {
+    let (mut conn, hlc) = setup_db();
+    exec(
+        &mut conn,
+        &hlc,
+        "INSERT INTO notes (id, title, pinned) VALUES ('n1', 'First', 1)",
+        &[],
+    );
+    let before = hlcs(&conn, "n1");
+
+    exec(
+        &mut conn,
+        &hlc,
+        "INSERT INTO notes (id, title) VALUES ('n1', 'Second') ON CONFLICT DO NOTHING",
+        &[],
+    );
+
+    assert_eq!(hlcs(&conn, "n1"), before);
+}
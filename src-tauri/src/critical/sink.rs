@@ -74,6 +74,8 @@ pub enum SinkError {
     Time(#[from] time::error::Format),
     #[error("SQLCipher is not active on the sink connection — `PRAGMA cipher_version` returned empty; sink would write plaintext to an encrypted file")]
     SqlcipherInactive,
+    #[error("Cipher settings of the vault could not be read: {0}")]
+    CipherSettings(String),
 }
 
 #[derive(Clone)]
@@ -108,6 +110,11 @@ impl CriticalNotificationSink {
         // documented SQLCipher pattern — same as
         // `database::mod::create_encrypted_database_inner`.
         conn.pragma_update(None, "key", cipher_key)?;
+        if let Some(settings) = crate::database::cipher::read_settings(db_path)
+            .map_err(|e| SinkError::CipherSettings(e.to_string()))?
+        {
+            settings.apply(&conn, None)?;
+        }
 
         // Verify SQLCipher is active before the first write — see doc
         // comment above for the silent-plaintext-fallback risk.
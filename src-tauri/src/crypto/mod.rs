@@ -126,9 +126,19 @@ pub fn encrypt_for_identity(
         .decode(&identity_public_key_b64)
         .map_err(|e| format!("Invalid public key base64: {e}"))?;
 
-    // Parse Ed25519 SPKI → raw bytes → convert to X25519
+    // Parse Ed25519 SPKI → raw bytes
     let ed25519_raw = extract_ed25519_public_key_from_spki(&spki_bytes)?;
-    let x25519_pk_bytes = ed25519_public_to_x25519(&ed25519_raw)?;
+    seal_for_ed25519_public_key(&plaintext, &ed25519_raw)
+}
+
+/// Rust-side counterpart of [`encrypt_for_identity`] for a raw Ed25519
+/// public key (e.g. taken from a `did:key`).
+pub fn seal_for_ed25519_public_key(
+    plaintext: &[u8],
+    ed25519_raw: &[u8; 32],
+) -> Result<IdentitySealedData, String> {
+    // Convert Ed25519 → X25519
+    let x25519_pk_bytes = ed25519_public_to_x25519(ed25519_raw)?;
     let recipient_pk = PublicKey::from(x25519_pk_bytes);
 
     // Generate ephemeral X25519 keypair
@@ -157,7 +167,7 @@ pub fn encrypt_for_identity(
     rand::fill(&mut iv);
     let nonce = Nonce::from_slice(&iv);
     let ciphertext = cipher
-        .encrypt(nonce, plaintext.as_ref())
+        .encrypt(nonce, plaintext)
         .map_err(|e| format!("Encryption failed: {e}"))?;
 
     // Wrap ephemeral public key in X25519 SPKI
@@ -200,18 +210,32 @@ pub fn decrypt_for_identity(
 
     // Parse ephemeral X25519 SPKI → raw bytes
     let eph_raw = extract_x25519_public_key_from_spki(&eph_spki)?;
-    let ephemeral_pk = PublicKey::from(eph_raw);
-
-    // Convert Ed25519 PKCS8 seed → X25519 private key
     let seed = extract_ed25519_seed_from_pkcs8(&pkcs8_bytes)?;
-    let x25519_sk_bytes = ed25519_seed_to_x25519(&seed);
+
+    let plaintext = open_with_ed25519_seed(&ciphertext, &iv_bytes, &salt, &eph_raw, &seed)?;
+    Ok(BASE64.encode(&plaintext))
+}
+
+/// Rust-side counterpart of [`decrypt_for_identity`] for raw key material:
+/// the X25519 ephemeral public key and the recipient's Ed25519 seed.
+pub fn open_with_ed25519_seed(
+    ciphertext: &[u8],
+    iv_bytes: &[u8],
+    salt: &[u8],
+    ephemeral_x25519_raw: &[u8; 32],
+    seed: &[u8; 32],
+) -> Result<Vec<u8>, String> {
+    let ephemeral_pk = PublicKey::from(*ephemeral_x25519_raw);
+
+    // Convert Ed25519 seed → X25519 private key
+    let x25519_sk_bytes = ed25519_seed_to_x25519(seed);
     let private_key = StaticSecret::from(x25519_sk_bytes);
 
     // ECDH shared secret
     let shared_secret = private_key.diffie_hellman(&ephemeral_pk);
 
     // HKDF-SHA256 with salt
-    let hk = Hkdf::<Sha256>::new(Some(&salt), shared_secret.as_bytes());
+    let hk = Hkdf::<Sha256>::new(Some(salt), shared_secret.as_bytes());
     let mut aes_key = [0u8; 32];
     hk.expand(HKDF_INFO, &mut aes_key)
         .map_err(|e| format!("HKDF expand failed: {e}"))?;
@@ -225,17 +249,37 @@ pub fn decrypt_for_identity(
             iv_bytes.len()
         ));
     }
-    let nonce = Nonce::from_slice(&iv_bytes);
+    let nonce = Nonce::from_slice(iv_bytes);
     let plaintext = cipher
-        .decrypt(nonce, ciphertext.as_ref())
+        .decrypt(nonce, ciphertext)
         .map_err(|e| format!("Decryption failed: {e}"))?;
 
-    Ok(BASE64.encode(&plaintext))
+    Ok(plaintext)
+}
+
+/// Opens [`IdentitySealedData`] (as produced by
+/// [`seal_for_ed25519_public_key`]) with the recipient's Ed25519 seed.
+pub fn open_sealed_with_ed25519_seed(
+    sealed: &IdentitySealedData,
+    seed: &[u8; 32],
+) -> Result<Vec<u8>, String> {
+    let decode = |field: &str, value: &str| {
+        BASE64
+            .decode(value)
+            .map_err(|e| format!("Invalid {field} base64: {e}"))
+    };
+    let ciphertext = decode("ciphertext", &sealed.encrypted_data)?;
+    let iv_bytes = decode("nonce", &sealed.nonce)?;
+    let salt = decode("salt", &sealed.salt)?;
+    let eph_spki = decode("ephemeral key", &sealed.ephemeral_public_key)?;
+
+    let eph_raw = extract_x25519_public_key_from_spki(&eph_spki)?;
+    open_with_ed25519_seed(&ciphertext, &iv_bytes, &salt, &eph_raw, seed)
 }
 
 // ── Types ───────────────────────────────────────────────────────────
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct IdentitySealedData {
     pub encrypted_data: String,
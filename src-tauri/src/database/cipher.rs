@@ -0,0 +1,452 @@
+// src-tauri/src/database/cipher.rs
+//!
+//! SQLCipher tuning per vault.
+//!
+//! KDF iterations, page size and HMAC algorithm have to be known before the
+//! first page can be decrypted, so they can't live inside the vault. They are
+//! kept in a sidecar next to it (`<vault>.db.cipher.json`) and applied right
+//! after `PRAGMA key` on every connection. A vault without sidecar uses
+//! SQLCipher's built-in defaults.
+//!
+//! The settings are fixed at creation. Changing them later means
+//! re-encrypting the whole file ([`reencrypt_vault`]); vaults still in the
+//! SQLCipher 3 format are upgraded with `PRAGMA cipher_migrate` on the way.
+
+use crate::database::error::DatabaseError;
+use crate::database::sidecar;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+/// Suffix appended to the vault DB path for the cipher sidecar.
+const SIDECAR_SUFFIX: &str = ".cipher.json";
+
+/// Suffix of the temporary file a vault is re-encrypted into.
+const REKEY_SUFFIX: &str = ".rekey.tmp";
+
+/// Schema name the re-encryption target is attached as.
+const REKEY_SCHEMA: &str = "haex_rekey";
+
+/// SQLCipher 4 defaults.
+pub const DEFAULT_KDF_ITER: u32 = 256_000;
+pub const DEFAULT_PAGE_SIZE: u32 = 4096;
+
+/// SQLCipher 3 used 64k iterations; anything below is not accepted.
+pub const MIN_KDF_ITER: u32 = 64_000;
+
+/// Upper bound so a typo can't make the vault take minutes to unlock.
+pub const MAX_KDF_ITER: u32 = 10_000_000;
+
+/// HMAC used for page authentication (and the matching PBKDF2 variant)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum HmacAlgorithm {
+    HmacSha1,
+    HmacSha256,
+    HmacSha512,
+}
+
+impl HmacAlgorithm {
+    fn hmac_pragma_value(self) -> &'static str {
+        match self {
+            Self::HmacSha1 => "HMAC_SHA1",
+            Self::HmacSha256 => "HMAC_SHA256",
+            Self::HmacSha512 => "HMAC_SHA512",
+        }
+    }
+
+    fn kdf_pragma_value(self) -> &'static str {
+        match self {
+            Self::HmacSha1 => "PBKDF2_HMAC_SHA1",
+            Self::HmacSha256 => "PBKDF2_HMAC_SHA256",
+            Self::HmacSha512 => "PBKDF2_HMAC_SHA512",
+        }
+    }
+}
+
+/// SQLCipher parameters of a vault
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct CipherSettings {
+    /// PBKDF2 iterations used to derive the key from the password
+    pub kdf_iter: u32,
+    /// Page size in bytes (power of two, 512 – 65536)
+    pub cipher_page_size: u32,
+    pub hmac_algorithm: HmacAlgorithm,
+}
+
+impl Default for CipherSettings {
+    fn default() -> Self {
+        Self {
+            kdf_iter: DEFAULT_KDF_ITER,
+            cipher_page_size: DEFAULT_PAGE_SIZE,
+            hmac_algorithm: HmacAlgorithm::HmacSha512,
+        }
+    }
+}
+
+impl CipherSettings {
+    pub fn validate(&self) -> Result<(), DatabaseError> {
+        if !(MIN_KDF_ITER..=MAX_KDF_ITER).contains(&self.kdf_iter) {
+            return Err(DatabaseError::ValidationError {
+                reason: format!(
+                    "kdf_iter must be between {MIN_KDF_ITER} and {MAX_KDF_ITER}, got {}",
+                    self.kdf_iter
+                ),
+            });
+        }
+        if !self.cipher_page_size.is_power_of_two()
+            || !(512..=65536).contains(&self.cipher_page_size)
+        {
+            return Err(DatabaseError::ValidationError {
+                reason: format!(
+                    "cipher_page_size must be a power of two between 512 and 65536, got {}",
+                    self.cipher_page_size
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Applies the settings to `schema` (`None` = main). Must run after
+    /// `PRAGMA key` and before the first read.
+    pub fn apply(&self, conn: &Connection, schema: Option<&str>) -> rusqlite::Result<()> {
+        conn.pragma_update(schema, "cipher_page_size", self.cipher_page_size)?;
+        conn.pragma_update(schema, "kdf_iter", self.kdf_iter)?;
+        conn.pragma_update(
+            schema,
+            "cipher_hmac_algorithm",
+            self.hmac_algorithm.hmac_pragma_value(),
+        )?;
+        conn.pragma_update(
+            schema,
+            "cipher_kdf_algorithm",
+            self.hmac_algorithm.kdf_pragma_value(),
+        )
+    }
+}
+
+/// Location of the cipher settings sidecar of a vault
+pub fn sidecar_path(vault_path: &Path) -> PathBuf {
+    sidecar::sidecar_path(vault_path, SIDECAR_SUFFIX)
+}
+
+/// Stored settings of a vault, `None` if it uses SQLCipher's defaults.
+///
+/// Unlike the lockout sidecar a corrupt file is an error: guessing would
+/// only turn it into a misleading "wrong password".
+pub fn read_settings(vault_path: &Path) -> Result<Option<CipherSettings>, DatabaseError> {
+    let path = sidecar_path(vault_path);
+    let json = match fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(DatabaseError::IoError {
+                path: path.display().to_string(),
+                reason: e.to_string(),
+            })
+        }
+    };
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| DatabaseError::SerializationError {
+            reason: format!("Invalid cipher settings in {}: {e}", path.display()),
+        })
+}
+
+pub fn write_settings(vault_path: &Path, settings: &CipherSettings) -> Result<(), DatabaseError> {
+    let path = sidecar_path(vault_path);
+    let json = serde_json::to_string(settings).map_err(|e| DatabaseError::SerializationError {
+        reason: e.to_string(),
+    })?;
+    fs::write(&path, json).map_err(|e| DatabaseError::IoError {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Removes the sidecar, e.g. when the vault itself is deleted.
+pub fn remove_sidecar(vault_path: &Path) -> Result<(), DatabaseError> {
+    sidecar::remove_sidecar(vault_path, SIDECAR_SUFFIX)
+}
+
+/// Applies the stored settings of the vault at `vault_path` to `conn`.
+/// Call right after `PRAGMA key`.
+pub fn apply_stored_settings(conn: &Connection, vault_path: &Path) -> Result<(), DatabaseError> {
+    let Some(settings) = read_settings(vault_path)? else {
+        return Ok(());
+    };
+    settings
+        .apply(conn, None)
+        .map_err(|e| DatabaseError::PragmaError {
+            pragma: "cipher settings".to_string(),
+            reason: e.to_string(),
+        })
+}
+
+fn pragma_error(pragma: &str) -> impl Fn(rusqlite::Error) -> DatabaseError + '_ {
+    move |e| DatabaseError::PragmaError {
+        pragma: pragma.to_string(),
+        reason: e.to_string(),
+    }
+}
+
+/// Opens the vault with its current settings and checks the key. A vault
+/// without sidecar that doesn't open with the defaults is tried once more
+/// through `PRAGMA cipher_migrate` (SQLCipher 3 → 4, in place).
+fn open_for_rekey(vault_path: &Path, key: &str) -> Result<Connection, DatabaseError> {
+    let current = read_settings(vault_path)?;
+    let open = || -> Result<Connection, DatabaseError> {
+        let conn = Connection::open(vault_path).map_err(|e| DatabaseError::ConnectionFailed {
+            path: vault_path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+        conn.pragma_update(None, "key", key)
+            .map_err(pragma_error("key"))?;
+        if let Some(settings) = &current {
+            settings
+                .apply(&conn, None)
+                .map_err(pragma_error("cipher settings"))?;
+        }
+        Ok(conn)
+    };
+    let probe = |conn: &Connection| {
+        conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+            row.get::<_, i64>(0)
+        })
+    };
+
+    let conn = open()?;
+    let Err(probe_error) = probe(&conn) else {
+        return Ok(conn);
+    };
+    if current.is_some() {
+        return Err(pragma_error("key")(probe_error));
+    }
+
+    // `cipher_migrate` has to be the first statement after the key.
+    let conn = open()?;
+    let migrated: String = conn
+        .query_row("PRAGMA cipher_migrate", [], |row| row.get(0))
+        .map_err(pragma_error("cipher_migrate"))?;
+    if migrated != "0" {
+        // Neither format accepts the key: report it like the original failure
+        return Err(pragma_error("key")(probe_error));
+    }
+    println!(
+        "[CIPHER] Migrated legacy vault {} to the current format",
+        vault_path.display()
+    );
+    Ok(conn)
+}
+
+/// Re-encrypts the closed vault at `vault_path` with `settings` and stores
+/// them in the sidecar. The caller must make sure nobody has the vault open.
+///
+/// The vault is exported into a temporary file next to it, which then
+/// replaces the original; the original stays untouched if anything fails
+/// before that.
+pub fn reencrypt_vault(
+    vault_path: &Path,
+    key: &str,
+    settings: &CipherSettings,
+) -> Result<(), DatabaseError> {
+    settings.validate()?;
+
+    let mut tmp_name = vault_path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(REKEY_SUFFIX);
+    let tmp_path = vault_path.with_file_name(tmp_name);
+    let io_error = |path: &Path, e: std::io::Error| DatabaseError::IoError {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    };
+    if tmp_path.exists() {
+        fs::remove_file(&tmp_path).map_err(|e| io_error(&tmp_path, e))?;
+    }
+
+    let export = || -> Result<(), DatabaseError> {
+        let conn = open_for_rekey(vault_path, key)?;
+        conn.execute(
+            &format!("ATTACH DATABASE ?1 AS {REKEY_SCHEMA} KEY ?2"),
+            params![tmp_path.to_string_lossy(), key],
+        )
+        .map_err(pragma_error("attach"))?;
+        settings
+            .apply(&conn, Some(REKEY_SCHEMA))
+            .map_err(pragma_error("cipher settings"))?;
+        conn.query_row(
+            &format!("SELECT sqlcipher_export('{REKEY_SCHEMA}')"),
+            [],
+            |_| Ok(()),
+        )
+        .map_err(pragma_error("sqlcipher_export"))?;
+        conn.execute(&format!("DETACH DATABASE {REKEY_SCHEMA}"), [])
+            .map_err(pragma_error("detach"))?;
+        conn.close()
+            .map_err(|(_, e)| DatabaseError::ConnectionFailed {
+                path: vault_path.display().to_string(),
+                reason: e.to_string(),
+            })
+    };
+    if let Err(e) = export() {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    // Closing the last connection checkpoints the WAL; leftovers would be
+    // replayed onto the new file, so they must go before the swap.
+    for suffix in ["-wal", "-shm"] {
+        let mut aux = vault_path.as_os_str().to_os_string();
+        aux.push(suffix);
+        let aux = PathBuf::from(aux);
+        if aux.exists() {
+            fs::remove_file(&aux).map_err(|e| io_error(&aux, e))?;
+        }
+    }
+    fs::rename(&tmp_path, vault_path).map_err(|e| io_error(vault_path, e))?;
+    write_settings(vault_path, settings)
+}
+
+/// Cipher settings of the vault at `vault_path` (the defaults if it has no
+/// sidecar).
+#[tauri::command]
+pub fn vault_get_cipher_settings(vault_path: String) -> Result<CipherSettings, DatabaseError> {
+    Ok(read_settings(Path::new(&vault_path))?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_vault(path: &Path, key: &str, settings: Option<&CipherSettings>) {
+        let conn = Connection::open(path).unwrap();
+        conn.pragma_update(None, "key", key).unwrap();
+        if let Some(settings) = settings {
+            settings.apply(&conn, None).unwrap();
+            write_settings(path, settings).unwrap();
+        }
+        conn.execute_batch(
+            "CREATE TABLE notes (id INTEGER PRIMARY KEY, title TEXT);
+             INSERT INTO notes (title) VALUES ('kept');",
+        )
+        .unwrap();
+    }
+
+    fn read_title(path: &Path, key: &str) -> rusqlite::Result<String> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "key", key)?;
+        apply_stored_settings(&conn, path).unwrap();
+        conn.query_row("SELECT title FROM notes", [], |row| row.get(0))
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(CipherSettings::default().validate().is_ok());
+        for settings in [
+            CipherSettings {
+                kdf_iter: MIN_KDF_ITER - 1,
+                ..Default::default()
+            },
+            CipherSettings {
+                kdf_iter: MAX_KDF_ITER + 1,
+                ..Default::default()
+            },
+            CipherSettings {
+                cipher_page_size: 3000,
+                ..Default::default()
+            },
+            CipherSettings {
+                cipher_page_size: 256,
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                settings.validate(),
+                Err(DatabaseError::ValidationError { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_sidecar_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("my.db");
+        fs::write(&vault, b"").unwrap();
+        assert_eq!(
+            sidecar_path(&vault).file_name().unwrap(),
+            "my.db.cipher.json"
+        );
+        assert_eq!(read_settings(&vault).unwrap(), None);
+
+        let settings = CipherSettings {
+            kdf_iter: 500_000,
+            cipher_page_size: 8192,
+            hmac_algorithm: HmacAlgorithm::HmacSha256,
+        };
+        write_settings(&vault, &settings).unwrap();
+        assert_eq!(read_settings(&vault).unwrap(), Some(settings));
+
+        fs::write(sidecar_path(&vault), "not json").unwrap();
+        assert!(read_settings(&vault).is_err());
+
+        remove_sidecar(&vault).unwrap();
+        assert_eq!(
+            vault_get_cipher_settings(vault.display().to_string()).unwrap(),
+            CipherSettings::default()
+        );
+    }
+
+    #[test]
+    fn test_custom_settings_are_required_to_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("custom.db");
+        let settings = CipherSettings {
+            kdf_iter: MIN_KDF_ITER,
+            cipher_page_size: 8192,
+            hmac_algorithm: HmacAlgorithm::HmacSha256,
+        };
+        create_vault(&vault, "secret", Some(&settings));
+
+        assert_eq!(read_title(&vault, "secret").unwrap(), "kept");
+
+        // Without the sidecar the defaults don't decrypt the file
+        let stored = fs::read_to_string(sidecar_path(&vault)).unwrap();
+        remove_sidecar(&vault).unwrap();
+        assert!(read_title(&vault, "secret").is_err());
+        fs::write(sidecar_path(&vault), stored).unwrap();
+    }
+
+    #[test]
+    fn test_reencrypt_changes_settings_and_keeps_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("rekey.db");
+        create_vault(&vault, "secret", None);
+
+        let settings = CipherSettings {
+            kdf_iter: 300_000,
+            cipher_page_size: 16384,
+            hmac_algorithm: HmacAlgorithm::HmacSha512,
+        };
+        reencrypt_vault(&vault, "secret", &settings).unwrap();
+
+        assert_eq!(read_settings(&vault).unwrap(), Some(settings));
+        assert_eq!(read_title(&vault, "secret").unwrap(), "kept");
+        assert!(!dir.path().join("rekey.db.rekey.tmp").exists());
+    }
+
+    #[test]
+    fn test_reencrypt_rejects_wrong_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("wrong.db");
+        create_vault(&vault, "secret", None);
+
+        let err = reencrypt_vault(&vault, "guess", &CipherSettings::default()).unwrap_err();
+        assert!(crate::database::unlock_throttle::is_wrong_password(&err));
+        assert_eq!(read_settings(&vault).unwrap(), None);
+        assert_eq!(read_title(&vault, "secret").unwrap(), "kept");
+    }
+}
@@ -34,6 +34,88 @@ pub mod vault_settings_key {
     /// decimal string); the next sync-loop session fetches only newer messages
     /// and avoids triggering a spurious External Commit rejoin on every restart.
     pub const LOCAL_SYNC_MLS_CURSOR_PREFIX: &str = "local_sync_mls_cursor:";
+
+    /// Per-device SQLite page cache size in KiB, applied on every vault
+    /// open (`database::storage`). Stored in haex_crdt_configs (local-only).
+    pub const STORAGE_CACHE_SIZE_KIB: &str = "storage_cache_size_kib";
+
+    /// Pages freed per idle incremental-vacuum step (`database::storage`).
+    /// Stored in haex_crdt_configs (local-only).
+    pub const STORAGE_VACUUM_BATCH_PAGES: &str = "storage_vacuum_batch_pages";
+
+    /// Per-device background sync schedule (`sync::background`), stored as
+    /// JSON in haex_crdt_configs (local-only).
+    pub const BACKGROUND_SYNC: &str = "background_sync";
+
+    /// Matrix/Nostr sync transports with their credentials
+    /// (`sync::messaging`), stored as JSON in haex_crdt_configs (local-only).
+    pub const SYNC_MESSAGING_TRANSPORTS: &str = "sync_messaging_transports";
+
+    /// Default strategy for UNIQUE conflicts of replicated inserts
+    /// (`crdt::unique_conflict`). Per-table overrides use the key
+    /// `unique_conflict_strategy:<table>`. Stored in haex_crdt_configs (local-only).
+    pub const UNIQUE_CONFLICT_STRATEGY: &str = "unique_conflict_strategy";
+    pub const UNIQUE_CONFLICT_STRATEGY_PREFIX: &str = "unique_conflict_strategy:";
+
+    /// Per-table opt-out of the delete-log and trash (`crdt::hard_delete`).
+    /// Full key is `hard_delete:<table>`; only `_no_sync` tables qualify.
+    /// Stored in haex_crdt_configs (local-only).
+    pub const HARD_DELETE_PREFIX: &str = "hard_delete:";
+
+    /// Prefix for the resume position of a chunked remote apply
+    /// (`crdt::bulk_apply`). Full key is `remote_apply_position:<session_id>`;
+    /// the value is the HLC of the last applied transaction. Removed once the
+    /// session completes. Stored in haex_crdt_configs (local-only).
+    pub const REMOTE_APPLY_POSITION_PREFIX: &str = "remote_apply_position:";
+
+    /// Outbound webhooks (`webhooks`) as one JSON array, including their
+    /// signing secrets and delivery cursors. Stored in haex_crdt_configs
+    /// (local-only).
+    pub const WEBHOOKS: &str = "webhooks";
+
+    /// Vault password policy (`database::password_policy`) as JSON. Stored in
+    /// haex_crdt_configs (local-only).
+    pub const PASSWORD_POLICY: &str = "password_policy";
+
+    /// RFC3339 time of the last vault password change, drives the rotation
+    /// reminder. Stored in haex_crdt_configs (local-only).
+    pub const PASSWORD_CHANGED_AT: &str = "password_changed_at";
+
+    /// Extension event bus policy (`extension::event_bus`) as JSON. Stored
+    /// in haex_crdt_configs (local-only).
+    pub const EXTENSION_EVENT_POLICY: &str = "extension_event_policy";
+
+    /// Prefix for the CalDAV/CardDAV sync state of an extension
+    /// (`extension::dav`). Full key is `dav_sync_state:<extension_id>:<sync_id>`;
+    /// the value holds the sync token and the known hrefs as JSON. Stored in
+    /// haex_crdt_configs (local-only).
+    pub const DAV_SYNC_STATE_PREFIX: &str = "dav_sync_state:";
+
+    /// Remembered allow/deny decisions of the bridge credential protocol
+    /// (`external_bridge::credentials`) per client, origin and kind, as one
+    /// JSON array. Stored in haex_crdt_configs (local-only).
+    pub const EXTERNAL_BRIDGE_CREDENTIAL_DECISIONS: &str = "external_bridge_credential_decisions";
+
+    /// HMAC key (hex) the bridge signs session tokens with
+    /// (`external_bridge::session_tokens`). Stored in haex_crdt_configs
+    /// (local-only).
+    pub const EXTERNAL_BRIDGE_SESSION_TOKEN_KEY: &str = "external_bridge_session_token_key";
+
+    /// Metadata of the issued bridge session tokens as one JSON array.
+    /// Stored in haex_crdt_configs (local-only).
+    pub const EXTERNAL_BRIDGE_SESSION_TOKENS: &str = "external_bridge_session_tokens";
+
+    /// Ed25519 key (hex) deep-link action results are signed with
+    /// (`deep_link`). Stored in haex_crdt_configs (local-only).
+    pub const DEEP_LINK_SIGNING_KEY: &str = "deep_link_signing_key";
+
+    /// Data retention rules (`crdt::retention`) with their run statistics,
+    /// as one JSON array. Stored in haex_crdt_configs (local-only).
+    pub const RETENTION_RULES: &str = "retention_rules";
+
+    /// Storage quota in bytes per backend id (`remote_storage::usage`), as
+    /// one JSON object. Stored in haex_crdt_configs (local-only).
+    pub const STORAGE_QUOTAS: &str = "storage_quotas";
 }
 
 #[cfg(test)]
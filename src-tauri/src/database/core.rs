@@ -5,8 +5,10 @@ pub const DRIZZLE_STATEMENT_BREAKPOINT: &str = "--> statement-breakpoint";
 
 use crate::crdt::hlc::HlcService;
 use crate::crdt::trigger::{HLC_FUNCTION_NAME, UUID_FUNCTION_NAME};
+use crate::database::cipher;
 use crate::database::connection_context::ConnectionContext;
 use crate::database::error::DatabaseError;
+use crate::database::wal;
 use crate::database::DbConnection;
 use crate::extension::database::executor::SqlExecutor;
 use crate::table_names::TABLE_CRDT_CONFIGS;
@@ -16,7 +18,7 @@ use rusqlite::functions::FunctionFlags;
 use rusqlite::types::Value as SqlValue;
 use rusqlite::{
     types::{Value as RusqliteValue, ValueRef},
-    Connection, OpenFlags, ToSql,
+    Connection, OpenFlags, OptionalExtension, ToSql,
 };
 use serde_json::Value as JsonValue;
 use sqlparser::ast::{
@@ -25,8 +27,8 @@ use sqlparser::ast::{
 };
 use sqlparser::dialect::SQLiteDialect;
 use sqlparser::parser::Parser;
+use std::path::Path;
 use std::sync::LazyLock;
-use uuid::Uuid;
 
 /// Removes the "main." schema prefix that sqlparser-rs adds when serializing SQL.
 /// SQLite doesn't need this prefix and it causes "no such table" errors.
@@ -226,7 +228,8 @@ fn strip_main_from_expr(expr: &mut Expr) {
 ///
 /// Registers the `gen_uuid` and `current_hlc` UDFs and wires commit/rollback
 /// hooks so the transaction-scoped HLC slot is cleared at the end of every
-/// transaction.
+/// transaction. WAL files left behind by a crashed process are recovered on
+/// the way (see [`wal`]); callers must hold the vault lock.
 pub fn open_and_init_db(
     path: &str,
     key: &str,
@@ -240,6 +243,8 @@ pub fn open_and_init_db(
         OpenFlags::SQLITE_OPEN_READ_WRITE
     };
 
+    let leftovers = wal::prepare_open(Path::new(path))?;
+
     let conn =
         Connection::open_with_flags(path, flags).map_err(|e| DatabaseError::ConnectionFailed {
             path: path.to_string(),
@@ -251,6 +256,10 @@ pub fn open_and_init_db(
             pragma: "key".to_string(),
             reason: e.to_string(),
         })?;
+    cipher::apply_stored_settings(&conn, Path::new(path))?;
+
+    // The first read replays a leftover WAL; retried while the file is busy
+    wal::first_read(&conn, Path::new(path))?;
 
     // Enable foreign key constraints
     // This must be set for PRAGMA defer_foreign_keys to work
@@ -275,15 +284,7 @@ pub fn open_and_init_db(
     }
 
     // Register custom UUID function for SQLite triggers
-    conn.create_scalar_function(
-        UUID_FUNCTION_NAME,
-        0,
-        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_INNOCUOUS,
-        |_ctx| Ok(Uuid::new_v4().to_string()),
-    )
-    .map_err(|e| DatabaseError::DatabaseError {
-        reason: format!("Failed to register {UUID_FUNCTION_NAME} function: {e}"),
-    })?;
+    register_uuid_udf(&conn)?;
 
     // Register transaction-scoped HLC UDF. All calls within a single SQLite
     // transaction (explicit or auto-commit) return the same timestamp.
@@ -303,9 +304,37 @@ pub fn open_and_init_db(
         eprintln!("Failed to enable WAL mode, journal_mode is '{journal_mode}'.");
     }
 
+    // Fold a crashed session's WAL into the main file now instead of at the
+    // next auto-checkpoint. Its content is readable either way, so a failed
+    // checkpoint doesn't fail the open.
+    if leftovers.wal {
+        match wal::checkpoint_truncate(&conn) {
+            Ok(checkpoint) => println!(
+                "[WAL] Recovered leftover WAL: {} of {} frames checkpointed",
+                checkpoint.checkpointed_frames, checkpoint.log_frames
+            ),
+            Err(e) => eprintln!("[WAL] Checkpoint of leftover WAL failed: {e}"),
+        }
+    }
+
     Ok(conn)
 }
 
+/// Registers the `gen_uuid()` UDF used by triggers and column defaults.
+/// IDs come from [`crate::ids::new_uuid`], so tests can make them
+/// deterministic.
+pub fn register_uuid_udf(conn: &Connection) -> Result<(), DatabaseError> {
+    conn.create_scalar_function(
+        UUID_FUNCTION_NAME,
+        0,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_INNOCUOUS,
+        |_ctx| Ok(crate::ids::new_uuid().to_string()),
+    )
+    .map_err(|e| DatabaseError::DatabaseError {
+        reason: format!("Failed to register {UUID_FUNCTION_NAME} function: {e}"),
+    })
+}
+
 /// Registers the `current_hlc()` UDF on a connection. Extracted so tests that
 /// create bare in-memory connections can use the same registration logic.
 pub fn register_current_hlc_udf(
@@ -473,13 +502,49 @@ pub fn execute_with_crdt(
     connection: &DbConnection,
     hlc_service: &std::sync::MutexGuard<crate::crdt::hlc::HlcService>,
 ) -> Result<Vec<Vec<JsonValue>>, DatabaseError> {
+    execute_with_crdt_inner(sql, params, connection, hlc_service, None)
+}
+
+/// Wie `execute_with_crdt`, zeichnet den Write zusätzlich für Undo auf
+///
+/// The touched rows land on the undo stack of the target table
+/// (see `crdt::undo`). Statements that are no writes are executed as usual.
+pub fn execute_with_crdt_undoable(
+    sql: String,
+    params: Vec<JsonValue>,
+    connection: &DbConnection,
+    hlc_service: &std::sync::MutexGuard<crate::crdt::hlc::HlcService>,
+    undo: &crate::crdt::undo::UndoService,
+) -> Result<Vec<Vec<JsonValue>>, DatabaseError> {
+    execute_with_crdt_inner(sql, params, connection, hlc_service, Some(undo))
+}
+
+fn execute_with_crdt_inner(
+    sql: String,
+    params: Vec<JsonValue>,
+    connection: &DbConnection,
+    hlc_service: &std::sync::MutexGuard<crate::crdt::hlc::HlcService>,
+    undo: Option<&crate::crdt::undo::UndoService>,
+) -> Result<Vec<Vec<JsonValue>>, DatabaseError> {
+    use crate::crdt::undo::{write_target_table, UndoCapture, UndoScope};
+
     // Parse statement to check for RETURNING clause (AST-basiert)
     let statement = parse_single_statement(&sql)?;
     let has_returning = statement_has_returning(&statement);
+    let undo_table = undo.and(write_target_table(&statement));
 
     with_connection(connection, |conn| {
         let tx = conn.transaction().map_err(DatabaseError::from)?;
 
+        let capture = match &undo_table {
+            Some(table_name) => {
+                let mut capture = UndoCapture::start(&tx)?;
+                capture.watch(&tx, table_name)?;
+                Some(capture)
+            }
+            None => None,
+        };
+
         let result = if has_returning {
             let (_modified_tables, rows) =
                 SqlExecutor::query_internal(&tx, hlc_service, &sql, &params)?;
@@ -490,7 +555,13 @@ pub fn execute_with_crdt(
             vec![]
         };
 
+        let changes = capture.map(|capture| capture.finish(&tx)).transpose()?;
+
         tx.commit().map_err(DatabaseError::from)?;
+
+        if let (Some(undo), Some(table_name), Some(changes)) = (undo, undo_table, changes) {
+            undo.record(UndoScope::Table { table_name }, changes);
+        }
         Ok(result)
     })
 }
@@ -753,6 +824,9 @@ pub fn extract_table_names_from_statement(statement: &Statement) -> Vec<String>
         Statement::CreateIndex(create_index) => {
             tables.push(create_index.table_name.to_string());
         }
+        Statement::CreateView(create_view) => {
+            tables.push(create_view.name.to_string());
+        }
         Statement::Truncate(truncate) => {
             for table_name in &truncate.table_names {
                 tables.push(table_name.to_string());
@@ -767,6 +841,62 @@ pub fn extract_table_names_from_statement(statement: &Statement) -> Vec<String>
     tables
 }
 
+/// Tables read by the query of a `CREATE VIEW` statement, or `None` for any
+/// other statement.
+pub fn extract_view_source_tables(statement: &Statement) -> Option<Vec<String>> {
+    let Statement::CreateView(create_view) = statement else {
+        return None;
+    };
+    let mut tables = Vec::new();
+    extract_tables_from_query_recursive(&create_view.query, &mut tables);
+    Some(tables)
+}
+
+/// Resolves views among `names` to the tables they read from, following
+/// views over views. Returns only the names reached through a view (tables
+/// and nested views), without duplicates; names that are not views add
+/// nothing.
+pub fn resolve_view_tables(
+    conn: &Connection,
+    names: &[String],
+) -> Result<Vec<String>, DatabaseError> {
+    let mut stmt =
+        conn.prepare("SELECT sql FROM sqlite_master WHERE type = 'view' AND name = ?1")?;
+    let mut resolved: Vec<String> = Vec::new();
+    let mut pending: Vec<String> = names.iter().map(|n| normalize_object_name(n)).collect();
+    let mut visited: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+    while let Some(name) = pending.pop() {
+        if !visited.insert(name.to_lowercase()) {
+            continue;
+        }
+        let view_sql: Option<String> = stmt.query_row([&name], |row| row.get(0)).optional()?;
+        let Some(view_sql) = view_sql else {
+            continue;
+        };
+        let statement = parse_single_statement(&view_sql)?;
+        for source in extract_view_source_tables(&statement).unwrap_or_default() {
+            let source = normalize_object_name(&source);
+            if !resolved.iter().any(|r| r.eq_ignore_ascii_case(&source)) {
+                resolved.push(source.clone());
+            }
+            pending.push(source);
+        }
+    }
+
+    Ok(resolved)
+}
+
+/// Strips quotes and a schema qualifier (`main.`) from a table or view name.
+fn normalize_object_name(name: &str) -> String {
+    name.rsplit('.')
+        .next()
+        .unwrap_or(name)
+        .trim_matches('"')
+        .trim_matches('`')
+        .to_string()
+}
+
 /// Extrahiert Tabellennamen rekursiv aus Query-Strukturen
 fn extract_tables_from_query_recursive(query: &Query, tables: &mut Vec<String>) {
     extract_tables_from_set_expr_recursive(&query.body, tables);
@@ -891,6 +1021,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use uuid::Uuid;
     #[test]
     fn test_extract_simple_select() {
         let sql = "SELECT * FROM users";
@@ -940,6 +1071,44 @@ mod tests {
         assert_eq!(tables, vec!["users"]);
     }
 
+    #[test]
+    fn test_extract_view_source_tables() {
+        let statement = parse_single_statement(
+            "CREATE VIEW v AS SELECT u.name FROM users u JOIN posts p ON u.id = p.user_id",
+        )
+        .unwrap();
+        assert_eq!(
+            extract_view_source_tables(&statement),
+            Some(vec!["users".to_string(), "posts".to_string()])
+        );
+        assert_eq!(extract_table_names_from_statement(&statement), vec!["v"]);
+
+        let select = parse_single_statement("SELECT * FROM users").unwrap();
+        assert_eq!(extract_view_source_tables(&select), None);
+    }
+
+    #[test]
+    fn test_resolve_view_tables_follows_nested_views() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE users (id TEXT PRIMARY KEY, name TEXT);
+             CREATE TABLE posts (id TEXT PRIMARY KEY, user_id TEXT);
+             CREATE VIEW user_names AS SELECT id, name FROM users;
+             CREATE VIEW \"post_authors\" AS
+                 SELECT p.id, n.name FROM posts p JOIN user_names n ON n.id = p.user_id;",
+        )
+        .unwrap();
+
+        let resolved =
+            resolve_view_tables(&conn, &["main.\"post_authors\"".to_string()]).unwrap();
+        assert_eq!(resolved, vec!["posts", "user_names", "users"]);
+
+        // Plain tables resolve to nothing
+        assert!(resolve_view_tables(&conn, &["users".to_string()])
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_extract_primary_table() {
         let sql = "SELECT u.name FROM users u JOIN posts p ON u.id = p.user_id";
@@ -1,13 +1,19 @@
 // src-tauri/src/database/error.rs
 
 use crate::crdt::trigger::CrdtSetupError;
+use crate::error_code::{CodedError, ErrorCode, ErrorEnvelope};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use ts_rs::TS;
 
+/// Serialized as `{ code, type, message, userMessage, details }`. The derived
+/// `type`/`details` representation is generated as inherent functions
+/// (`remote = "Self"`) and wrapped by the impls at the end of this file;
+/// [`DatabaseErrorBinding`] is its TypeScript type.
 #[derive(Error, Debug, Serialize, Deserialize, TS)]
-#[ts(export)]
+#[ts(rename = "DatabaseErrorKind")]
 #[serde(tag = "type", content = "details", rename_all_fields = "camelCase")]
+#[serde(remote = "Self")]
 pub enum DatabaseError {
     /// Der SQL-Code konnte nicht geparst werden.
     #[error("Failed to parse SQL: {reason} - SQL: {sql}")]
@@ -21,6 +27,17 @@ pub enum DatabaseError {
         sql: String,
     },
 
+    /// A bound parameter does not match the type or NOT NULL constraint of
+    /// the column it is written to. `position` is 1-based.
+    #[error("Parameter {position} does not fit column '{table}.{column}': expected {expected}, got {provided}")]
+    ParameterTypeError {
+        position: usize,
+        table: String,
+        column: String,
+        expected: String,
+        provided: String,
+    },
+
     #[error("No table provided in SQL Statement: {sql}")]
     NoTableError { sql: String },
 
@@ -82,16 +99,16 @@ pub enum DatabaseError {
     #[error("Mutex Poisoned error: {reason}")]
     MutexPoisoned { reason: String },
 
-    #[error("Datenbankverbindung fehlgeschlagen für Pfad '{path}': {reason}")]
+    #[error("Database connection failed for path '{path}': {reason}")]
     ConnectionFailed { path: String, reason: String },
 
-    #[error("PRAGMA-Befehl '{pragma}' konnte nicht gesetzt werden: {reason}")]
+    #[error("Failed to set PRAGMA '{pragma}': {reason}")]
     PragmaError { pragma: String, reason: String },
 
-    #[error("Fehler beim Auflösen des Dateipfads: {reason}")]
+    #[error("Failed to resolve file path: {reason}")]
     PathResolutionError { reason: String },
 
-    #[error("Datei-I/O-Fehler für Pfad '{path}': {reason}")]
+    #[error("File I/O error for path '{path}': {reason}")]
     IoError { path: String, reason: String },
 
     #[error("CRDT setup failed: {0}")]
@@ -108,8 +125,22 @@ pub enum DatabaseError {
     /// a generic IoError) so the UI can display a user-facing
     /// "vault already open in another window" message instead of a raw
     /// filesystem error.
+    ///
+    /// `holder_pid` / `holder_hostname` identify the instance holding the
+    /// lock when it could be read from the lock file.
     #[error("Vault at '{path}' is already open in another instance")]
-    VaultAlreadyOpenElsewhere { path: String, reason: String },
+    VaultAlreadyOpenElsewhere {
+        path: String,
+        reason: String,
+        holder_pid: Option<u32>,
+        holder_hostname: Option<String>,
+    },
+
+    /// The vault file stayed locked while opening it, even after retrying
+    /// (`database::wal`). Usually another process that doesn't honour the
+    /// vault lock, e.g. an external SQLite tool.
+    #[error("Vault at '{path}' is busy: {reason}")]
+    VaultBusy { path: String, reason: String },
 
     /// This process already has a vault mounted in AppState — typically
     /// because the caller forgot to invoke `close_database` before
@@ -123,6 +154,14 @@ pub enum DatabaseError {
         requested_path: String,
     },
 
+    /// Too many failed unlock attempts (`database::unlock_throttle`); the
+    /// next attempt is accepted after `retry_after_secs`.
+    #[error("Too many failed unlock attempts ({failed_attempts}); try again in {retry_after_secs} seconds")]
+    VaultLockedOut {
+        failed_attempts: u32,
+        retry_after_secs: u32,
+    },
+
     #[error("Validation error: {reason}")]
     ValidationError { reason: String },
 
@@ -187,6 +226,87 @@ impl DatabaseError {
         }
     }
 }
+
+impl CodedError for DatabaseError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            DatabaseError::ParseError { .. }
+            | DatabaseError::ParameterMismatchError { .. }
+            | DatabaseError::ParameterTypeError { .. }
+            | DatabaseError::NoTableError { .. }
+            | DatabaseError::StatementError { .. }
+            | DatabaseError::PrepareError { .. }
+            | DatabaseError::UnsupportedStatement { .. }
+            | DatabaseError::QueryError { .. } => ErrorCode::InvalidQuery,
+            DatabaseError::DatabaseError { .. }
+            | DatabaseError::ExecutionError { .. }
+            | DatabaseError::RowProcessingError { .. } => ErrorCode::Database,
+            DatabaseError::TransactionError { .. } => ErrorCode::Transaction,
+            DatabaseError::HlcError { .. } | DatabaseError::CrdtSetup(_) => ErrorCode::Crdt,
+            DatabaseError::LockError { .. } | DatabaseError::MutexPoisoned { .. } => {
+                ErrorCode::MutexPoisoned
+            }
+            DatabaseError::ConnectionError { .. }
+            | DatabaseError::ConnectionFailed { .. }
+            | DatabaseError::PragmaError { .. } => ErrorCode::Connection,
+            DatabaseError::SerializationError { .. } => ErrorCode::Serialization,
+            DatabaseError::PermissionError { .. } => ErrorCode::PermissionDenied,
+            DatabaseError::PathResolutionError { .. } | DatabaseError::IoError { .. } => {
+                ErrorCode::Filesystem
+            }
+            DatabaseError::MigrationError { .. } => ErrorCode::Migration,
+            DatabaseError::VaultAlreadyExists { .. } => ErrorCode::VaultAlreadyExists,
+            DatabaseError::VaultAlreadyOpenElsewhere { .. } => ErrorCode::VaultAlreadyOpenElsewhere,
+            DatabaseError::VaultBusy { .. } => ErrorCode::VaultBusy,
+            DatabaseError::VaultAlreadyMountedInProcess { .. } => {
+                ErrorCode::VaultAlreadyMountedInProcess
+            }
+            DatabaseError::VaultLockedOut { .. } => ErrorCode::VaultLockedOut,
+            DatabaseError::ValidationError { .. } => ErrorCode::Validation,
+            DatabaseError::LimitExceeded { .. } => ErrorCode::LimitExceeded,
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        self.tagged().get("details").cloned()
+    }
+}
+
+impl DatabaseError {
+    /// The derived `{ type, details }` representation
+    fn tagged(&self) -> serde_json::Value {
+        DatabaseError::serialize(self, serde_json::value::Serializer).unwrap_or_default()
+    }
+}
+
+// `type` and `details` stay where the frontend reads them (vault/open.vue);
+// `code` and `message` complete the shared envelope.
+impl Serialize for DatabaseError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let tagged = self.tagged();
+        let mut state = serializer.serialize_struct("DatabaseError", 5)?;
+        state.serialize_field("code", &self.error_code())?;
+        state.serialize_field("type", &tagged["type"])?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("userMessage", self.user_message())?;
+        state.serialize_field("details", &tagged["details"])?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for DatabaseError {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        DatabaseError::deserialize(deserializer)
+    }
+}
 /* impl From<crate::extension::database::ExtensionDatabaseError> for DatabaseError {
     fn from(err: crate::extension::database::ExtensionDatabaseError) -> Self {
         match err {
@@ -233,3 +353,15 @@ impl DatabaseError {
         }
     }
 } */
+
+/// TypeScript type of a serialized [`DatabaseError`]: the shared envelope
+/// and the `type`/`details` pair of the variant
+#[derive(TS)]
+#[ts(export, rename = "DatabaseError")]
+#[allow(dead_code)]
+struct DatabaseErrorBinding {
+    #[ts(flatten)]
+    envelope: ErrorEnvelope,
+    #[ts(flatten)]
+    kind: DatabaseError,
+}
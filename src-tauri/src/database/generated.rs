@@ -4,9 +4,15 @@
 
 #![allow(dead_code)]
 
+use crate::crdt::hlc::HlcService;
+use crate::database::error::DatabaseError;
+use crate::extension::database::executor::SqlExecutor;
+use rusqlite::{ToSql, Transaction};
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexVaultSettings {
     pub id: String,
@@ -26,9 +32,68 @@ impl HaexVaultSettings {
             device_id: row.get(3)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_vault_settings";
+    pub const COLUMNS: &'static [&'static str] = &["id", "key", "value", "device_id"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"key\", \"value\", \"device_id\" FROM haex_vault_settings";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"key\"");
+        values.push(&self.key);
+        if let Some(value) = &self.value {
+            columns.push("\"value\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.device_id {
+            columns.push("\"device_id\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_vault_settings SET \"key\" = ?, \"value\" = ?, \"device_id\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.key, self.value, self.device_id, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexExtensions {
     pub id: String,
@@ -84,9 +149,114 @@ impl HaexExtensions {
             updated_at: row.get(16)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_extensions";
+    pub const COLUMNS: &'static [&'static str] = &["id", "public_key", "name", "version", "author", "description", "entry", "homepage", "enabled", "icon", "signature", "single_instance", "display_mode", "i18n", "dev_path", "created_at", "updated_at"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"public_key\", \"name\", \"version\", \"author\", \"description\", \"entry\", \"homepage\", \"enabled\", \"icon\", \"signature\", \"single_instance\", \"display_mode\", \"i18n\", \"dev_path\", \"created_at\", \"updated_at\" FROM haex_extensions";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"public_key\"");
+        values.push(&self.public_key);
+        columns.push("\"name\"");
+        values.push(&self.name);
+        columns.push("\"version\"");
+        values.push(&self.version);
+        if let Some(value) = &self.author {
+            columns.push("\"author\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.description {
+            columns.push("\"description\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.entry {
+            columns.push("\"entry\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.homepage {
+            columns.push("\"homepage\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.enabled {
+            columns.push("\"enabled\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.icon {
+            columns.push("\"icon\"");
+            values.push(value);
+        }
+        columns.push("\"signature\"");
+        values.push(&self.signature);
+        if let Some(value) = &self.single_instance {
+            columns.push("\"single_instance\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.display_mode {
+            columns.push("\"display_mode\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.i18n {
+            columns.push("\"i18n\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.dev_path {
+            columns.push("\"dev_path\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.created_at {
+            columns.push("\"created_at\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.updated_at {
+            columns.push("\"updated_at\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_extensions SET \"public_key\" = ?, \"name\" = ?, \"version\" = ?, \"author\" = ?, \"description\" = ?, \"entry\" = ?, \"homepage\" = ?, \"enabled\" = ?, \"icon\" = ?, \"signature\" = ?, \"single_instance\" = ?, \"display_mode\" = ?, \"i18n\" = ?, \"dev_path\" = ?, \"created_at\" = ?, \"updated_at\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.public_key, self.name, self.version, self.author, self.description, self.entry, self.homepage, self.enabled, self.icon, self.signature, self.single_instance, self.display_mode, self.i18n, self.dev_path, self.created_at, self.updated_at, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexExtensionPermissions {
     pub id: String,
@@ -120,9 +290,86 @@ impl HaexExtensionPermissions {
             updated_at: row.get(8)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_extension_permissions";
+    pub const COLUMNS: &'static [&'static str] = &["id", "extension_id", "resource_type", "action", "target", "constraints", "status", "created_at", "updated_at"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"extension_id\", \"resource_type\", \"action\", \"target\", \"constraints\", \"status\", \"created_at\", \"updated_at\" FROM haex_extension_permissions";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"extension_id\"");
+        values.push(&self.extension_id);
+        if let Some(value) = &self.resource_type {
+            columns.push("\"resource_type\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.action {
+            columns.push("\"action\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.target {
+            columns.push("\"target\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.constraints {
+            columns.push("\"constraints\"");
+            values.push(value);
+        }
+        columns.push("\"status\"");
+        values.push(&self.status);
+        if let Some(value) = &self.created_at {
+            columns.push("\"created_at\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.updated_at {
+            columns.push("\"updated_at\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_extension_permissions SET \"extension_id\" = ?, \"resource_type\" = ?, \"action\" = ?, \"target\" = ?, \"constraints\" = ?, \"status\" = ?, \"created_at\" = ?, \"updated_at\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.extension_id, self.resource_type, self.action, self.target, self.constraints, self.status, self.created_at, self.updated_at, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexCrdtConfigsNoSync {
     pub key: String,
@@ -139,9 +386,62 @@ impl HaexCrdtConfigsNoSync {
             value: row.get(2)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_crdt_configs_no_sync";
+    pub const COLUMNS: &'static [&'static str] = &["key", "type", "value"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"key\", \"type\", \"value\" FROM haex_crdt_configs_no_sync";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, key: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"key\" = ?1", [key])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"key\"");
+        values.push(&self.key);
+        columns.push("\"type\"");
+        values.push(&self.r#type);
+        columns.push("\"value\"");
+        values.push(&self.value);
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_crdt_configs_no_sync SET \"type\" = ?, \"value\" = ? WHERE \"key\" = ?",
+            rusqlite::params![self.r#type, self.value, self.key],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexDesktopItemsNoSync {
     pub id: String,
@@ -167,9 +467,74 @@ impl HaexDesktopItemsNoSync {
             position_y: row.get(6)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_desktop_items_no_sync";
+    pub const COLUMNS: &'static [&'static str] = &["id", "workspace_id", "item_type", "extension_id", "system_window_id", "position_x", "position_y"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"workspace_id\", \"item_type\", \"extension_id\", \"system_window_id\", \"position_x\", \"position_y\" FROM haex_desktop_items_no_sync";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"workspace_id\"");
+        values.push(&self.workspace_id);
+        columns.push("\"item_type\"");
+        values.push(&self.item_type);
+        if let Some(value) = &self.extension_id {
+            columns.push("\"extension_id\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.system_window_id {
+            columns.push("\"system_window_id\"");
+            values.push(value);
+        }
+        columns.push("\"position_x\"");
+        values.push(&self.position_x);
+        columns.push("\"position_y\"");
+        values.push(&self.position_y);
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_desktop_items_no_sync SET \"workspace_id\" = ?, \"item_type\" = ?, \"extension_id\" = ?, \"system_window_id\" = ?, \"position_x\" = ?, \"position_y\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.workspace_id, self.item_type, self.extension_id, self.system_window_id, self.position_x, self.position_y, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexWorkspacesNoSync {
     pub id: String,
@@ -190,9 +555,68 @@ impl HaexWorkspacesNoSync {
             background: row.get(4)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_workspaces_no_sync";
+    pub const COLUMNS: &'static [&'static str] = &["id", "device_id", "name", "position", "background"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"device_id\", \"name\", \"position\", \"background\" FROM haex_workspaces_no_sync";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"device_id\"");
+        values.push(&self.device_id);
+        columns.push("\"name\"");
+        values.push(&self.name);
+        columns.push("\"position\"");
+        values.push(&self.position);
+        if let Some(value) = &self.background {
+            columns.push("\"background\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_workspaces_no_sync SET \"device_id\" = ?, \"name\" = ?, \"position\" = ?, \"background\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.device_id, self.name, self.position, self.background, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexCrdtMigrationsNoSync {
     pub id: String,
@@ -213,9 +637,68 @@ impl HaexCrdtMigrationsNoSync {
             applied_at: row.get(4)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_crdt_migrations_no_sync";
+    pub const COLUMNS: &'static [&'static str] = &["id", "extension_id", "migration_name", "migration_content", "applied_at"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"extension_id\", \"migration_name\", \"migration_content\", \"applied_at\" FROM haex_crdt_migrations_no_sync";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        if let Some(value) = &self.extension_id {
+            columns.push("\"extension_id\"");
+            values.push(value);
+        }
+        columns.push("\"migration_name\"");
+        values.push(&self.migration_name);
+        columns.push("\"migration_content\"");
+        values.push(&self.migration_content);
+        columns.push("\"applied_at\"");
+        values.push(&self.applied_at);
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_crdt_migrations_no_sync SET \"extension_id\" = ?, \"migration_name\" = ?, \"migration_content\" = ?, \"applied_at\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.extension_id, self.migration_name, self.migration_content, self.applied_at, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexExtensionLimits {
     pub id: String,
@@ -225,6 +708,10 @@ pub struct HaexExtensionLimits {
     pub max_concurrent_queries: i64,
     pub max_query_size_bytes: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_memory_bytes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_cpu_percent: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub created_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub updated_at: Option<String>,
@@ -239,13 +726,265 @@ impl HaexExtensionLimits {
             max_result_rows: row.get(3)?,
             max_concurrent_queries: row.get(4)?,
             max_query_size_bytes: row.get(5)?,
-            created_at: row.get(6)?,
-            updated_at: row.get(7)?,
+            max_memory_bytes: row.get(6)?,
+            max_cpu_percent: row.get(7)?,
+            created_at: row.get(8)?,
+            updated_at: row.get(9)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_extension_limits";
+    pub const COLUMNS: &'static [&'static str] = &["id", "extension_id", "query_timeout_ms", "max_result_rows", "max_concurrent_queries", "max_query_size_bytes", "max_memory_bytes", "max_cpu_percent", "created_at", "updated_at"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"extension_id\", \"query_timeout_ms\", \"max_result_rows\", \"max_concurrent_queries\", \"max_query_size_bytes\", \"max_memory_bytes\", \"max_cpu_percent\", \"created_at\", \"updated_at\" FROM haex_extension_limits";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"extension_id\"");
+        values.push(&self.extension_id);
+        columns.push("\"query_timeout_ms\"");
+        values.push(&self.query_timeout_ms);
+        columns.push("\"max_result_rows\"");
+        values.push(&self.max_result_rows);
+        columns.push("\"max_concurrent_queries\"");
+        values.push(&self.max_concurrent_queries);
+        columns.push("\"max_query_size_bytes\"");
+        values.push(&self.max_query_size_bytes);
+        if let Some(value) = &self.max_memory_bytes {
+            columns.push("\"max_memory_bytes\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.max_cpu_percent {
+            columns.push("\"max_cpu_percent\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.created_at {
+            columns.push("\"created_at\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.updated_at {
+            columns.push("\"updated_at\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_extension_limits SET \"extension_id\" = ?, \"query_timeout_ms\" = ?, \"max_result_rows\" = ?, \"max_concurrent_queries\" = ?, \"max_query_size_bytes\" = ?, \"max_memory_bytes\" = ?, \"max_cpu_percent\" = ?, \"created_at\" = ?, \"updated_at\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.extension_id, self.query_timeout_ms, self.max_result_rows, self.max_concurrent_queries, self.max_query_size_bytes, self.max_memory_bytes, self.max_cpu_percent, self.created_at, self.updated_at, self.id],
+        )?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct HaexExternalAuthorizedClientsNoSync {
+    pub id: String,
+    pub client_id: String,
+    pub client_name: String,
+    pub public_key: String,
+    pub extension_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorized_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_seen: Option<String>,
+}
+
+impl HaexExternalAuthorizedClientsNoSync {
+    pub fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            client_id: row.get(1)?,
+            client_name: row.get(2)?,
+            public_key: row.get(3)?,
+            extension_id: row.get(4)?,
+            authorized_at: row.get(5)?,
+            last_seen: row.get(6)?,
+        })
+    }
+
+    pub const TABLE: &'static str = "haex_external_authorized_clients_no_sync";
+    pub const COLUMNS: &'static [&'static str] = &["id", "client_id", "client_name", "public_key", "extension_id", "authorized_at", "last_seen"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"client_id\", \"client_name\", \"public_key\", \"extension_id\", \"authorized_at\", \"last_seen\" FROM haex_external_authorized_clients_no_sync";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"client_id\"");
+        values.push(&self.client_id);
+        columns.push("\"client_name\"");
+        values.push(&self.client_name);
+        columns.push("\"public_key\"");
+        values.push(&self.public_key);
+        columns.push("\"extension_id\"");
+        values.push(&self.extension_id);
+        if let Some(value) = &self.authorized_at {
+            columns.push("\"authorized_at\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.last_seen {
+            columns.push("\"last_seen\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_external_authorized_clients_no_sync SET \"client_id\" = ?, \"client_name\" = ?, \"public_key\" = ?, \"extension_id\" = ?, \"authorized_at\" = ?, \"last_seen\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.client_id, self.client_name, self.public_key, self.extension_id, self.authorized_at, self.last_seen, self.id],
+        )?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct HaexExternalBlockedClientsNoSync {
+    pub id: String,
+    pub client_id: String,
+    pub client_name: String,
+    pub public_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocked_at: Option<String>,
+}
+
+impl HaexExternalBlockedClientsNoSync {
+    pub fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            client_id: row.get(1)?,
+            client_name: row.get(2)?,
+            public_key: row.get(3)?,
+            blocked_at: row.get(4)?,
+        })
+    }
+
+    pub const TABLE: &'static str = "haex_external_blocked_clients_no_sync";
+    pub const COLUMNS: &'static [&'static str] = &["id", "client_id", "client_name", "public_key", "blocked_at"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"client_id\", \"client_name\", \"public_key\", \"blocked_at\" FROM haex_external_blocked_clients_no_sync";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"client_id\"");
+        values.push(&self.client_id);
+        columns.push("\"client_name\"");
+        values.push(&self.client_name);
+        columns.push("\"public_key\"");
+        values.push(&self.public_key);
+        if let Some(value) = &self.blocked_at {
+            columns.push("\"blocked_at\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_external_blocked_clients_no_sync SET \"client_id\" = ?, \"client_name\" = ?, \"public_key\" = ?, \"blocked_at\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.client_id, self.client_name, self.public_key, self.blocked_at, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexPasswordsItemDetails {
     pub id: String,
@@ -302,9 +1041,118 @@ impl HaexPasswordsItemDetails {
             updated_at: row.get(15)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_passwords_item_details";
+    pub const COLUMNS: &'static [&'static str] = &["id", "title", "username", "password", "note", "icon", "color", "url", "otp_secret", "otp_digits", "otp_period", "otp_algorithm", "expires_at", "autofill_aliases", "created_at", "updated_at"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"title\", \"username\", \"password\", \"note\", \"icon\", \"color\", \"url\", \"otp_secret\", \"otp_digits\", \"otp_period\", \"otp_algorithm\", \"expires_at\", \"autofill_aliases\", \"created_at\", \"updated_at\" FROM haex_passwords_item_details";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        if let Some(value) = &self.title {
+            columns.push("\"title\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.username {
+            columns.push("\"username\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.password {
+            columns.push("\"password\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.note {
+            columns.push("\"note\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.icon {
+            columns.push("\"icon\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.color {
+            columns.push("\"color\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.url {
+            columns.push("\"url\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.otp_secret {
+            columns.push("\"otp_secret\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.otp_digits {
+            columns.push("\"otp_digits\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.otp_period {
+            columns.push("\"otp_period\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.otp_algorithm {
+            columns.push("\"otp_algorithm\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.expires_at {
+            columns.push("\"expires_at\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.autofill_aliases {
+            columns.push("\"autofill_aliases\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.created_at {
+            columns.push("\"created_at\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.updated_at {
+            columns.push("\"updated_at\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_passwords_item_details SET \"title\" = ?, \"username\" = ?, \"password\" = ?, \"note\" = ?, \"icon\" = ?, \"color\" = ?, \"url\" = ?, \"otp_secret\" = ?, \"otp_digits\" = ?, \"otp_period\" = ?, \"otp_algorithm\" = ?, \"expires_at\" = ?, \"autofill_aliases\" = ?, \"created_at\" = ?, \"updated_at\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.title, self.username, self.password, self.note, self.icon, self.color, self.url, self.otp_secret, self.otp_digits, self.otp_period, self.otp_algorithm, self.expires_at, self.autofill_aliases, self.created_at, self.updated_at, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexPasswordsItemKeyValues {
     pub id: String,
@@ -327,9 +1175,72 @@ impl HaexPasswordsItemKeyValues {
             updated_at: row.get(4)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_passwords_item_key_values";
+    pub const COLUMNS: &'static [&'static str] = &["id", "item_id", "key", "value", "updated_at"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"item_id\", \"key\", \"value\", \"updated_at\" FROM haex_passwords_item_key_values";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"item_id\"");
+        values.push(&self.item_id);
+        if let Some(value) = &self.key {
+            columns.push("\"key\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.value {
+            columns.push("\"value\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.updated_at {
+            columns.push("\"updated_at\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_passwords_item_key_values SET \"item_id\" = ?, \"key\" = ?, \"value\" = ?, \"updated_at\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.item_id, self.key, self.value, self.updated_at, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexPasswordsGroups {
     pub id: String,
@@ -365,9 +1276,90 @@ impl HaexPasswordsGroups {
             updated_at: row.get(8)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_passwords_groups";
+    pub const COLUMNS: &'static [&'static str] = &["id", "name", "description", "icon", "sort_order", "color", "parent_id", "created_at", "updated_at"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"name\", \"description\", \"icon\", \"sort_order\", \"color\", \"parent_id\", \"created_at\", \"updated_at\" FROM haex_passwords_groups";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        if let Some(value) = &self.name {
+            columns.push("\"name\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.description {
+            columns.push("\"description\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.icon {
+            columns.push("\"icon\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.sort_order {
+            columns.push("\"sort_order\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.color {
+            columns.push("\"color\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.parent_id {
+            columns.push("\"parent_id\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.created_at {
+            columns.push("\"created_at\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.updated_at {
+            columns.push("\"updated_at\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_passwords_groups SET \"name\" = ?, \"description\" = ?, \"icon\" = ?, \"sort_order\" = ?, \"color\" = ?, \"parent_id\" = ?, \"created_at\" = ?, \"updated_at\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.name, self.description, self.icon, self.sort_order, self.color, self.parent_id, self.created_at, self.updated_at, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexPasswordsGroupItems {
     pub item_id: String,
@@ -382,9 +1374,62 @@ impl HaexPasswordsGroupItems {
             group_id: row.get(1)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_passwords_group_items";
+    pub const COLUMNS: &'static [&'static str] = &["item_id", "group_id"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"item_id\", \"group_id\" FROM haex_passwords_group_items";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, item_id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"item_id\" = ?1", [item_id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"item_id\"");
+        values.push(&self.item_id);
+        if let Some(value) = &self.group_id {
+            columns.push("\"group_id\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_passwords_group_items SET \"group_id\" = ? WHERE \"item_id\" = ?",
+            rusqlite::params![self.group_id, self.item_id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexPasswordsBinaries {
     pub hash: String,
@@ -407,9 +1452,70 @@ impl HaexPasswordsBinaries {
             created_at: row.get(4)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_passwords_binaries";
+    pub const COLUMNS: &'static [&'static str] = &["hash", "data", "size", "type", "created_at"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"hash\", \"data\", \"size\", \"type\", \"created_at\" FROM haex_passwords_binaries";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, hash: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"hash\" = ?1", [hash])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"hash\"");
+        values.push(&self.hash);
+        columns.push("\"data\"");
+        values.push(&self.data);
+        columns.push("\"size\"");
+        values.push(&self.size);
+        if let Some(value) = &self.r#type {
+            columns.push("\"type\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.created_at {
+            columns.push("\"created_at\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_passwords_binaries SET \"data\" = ?, \"size\" = ?, \"type\" = ?, \"created_at\" = ? WHERE \"hash\" = ?",
+            rusqlite::params![self.data, self.size, self.r#type, self.created_at, self.hash],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexPasswordsItemBinaries {
     pub id: String,
@@ -427,9 +1533,64 @@ impl HaexPasswordsItemBinaries {
             file_name: row.get(3)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_passwords_item_binaries";
+    pub const COLUMNS: &'static [&'static str] = &["id", "item_id", "binary_hash", "file_name"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"item_id\", \"binary_hash\", \"file_name\" FROM haex_passwords_item_binaries";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"item_id\"");
+        values.push(&self.item_id);
+        columns.push("\"binary_hash\"");
+        values.push(&self.binary_hash);
+        columns.push("\"file_name\"");
+        values.push(&self.file_name);
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_passwords_item_binaries SET \"item_id\" = ?, \"binary_hash\" = ?, \"file_name\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.item_id, self.binary_hash, self.file_name, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexPasswordsItemSnapshots {
     pub id: String,
@@ -451,9 +1612,70 @@ impl HaexPasswordsItemSnapshots {
             modified_at: row.get(4)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_passwords_item_snapshots";
+    pub const COLUMNS: &'static [&'static str] = &["id", "item_id", "snapshot_data", "created_at", "modified_at"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"item_id\", \"snapshot_data\", \"created_at\", \"modified_at\" FROM haex_passwords_item_snapshots";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"item_id\"");
+        values.push(&self.item_id);
+        columns.push("\"snapshot_data\"");
+        values.push(&self.snapshot_data);
+        if let Some(value) = &self.created_at {
+            columns.push("\"created_at\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.modified_at {
+            columns.push("\"modified_at\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_passwords_item_snapshots SET \"item_id\" = ?, \"snapshot_data\" = ?, \"created_at\" = ?, \"modified_at\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.item_id, self.snapshot_data, self.created_at, self.modified_at, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexPasswordsSnapshotBinaries {
     pub id: String,
@@ -471,9 +1693,64 @@ impl HaexPasswordsSnapshotBinaries {
             file_name: row.get(3)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_passwords_snapshot_binaries";
+    pub const COLUMNS: &'static [&'static str] = &["id", "snapshot_id", "binary_hash", "file_name"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"snapshot_id\", \"binary_hash\", \"file_name\" FROM haex_passwords_snapshot_binaries";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"snapshot_id\"");
+        values.push(&self.snapshot_id);
+        columns.push("\"binary_hash\"");
+        values.push(&self.binary_hash);
+        columns.push("\"file_name\"");
+        values.push(&self.file_name);
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_passwords_snapshot_binaries SET \"snapshot_id\" = ?, \"binary_hash\" = ?, \"file_name\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.snapshot_id, self.binary_hash, self.file_name, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexPasswordsGeneratorPresets {
     pub id: String,
@@ -513,9 +1790,90 @@ impl HaexPasswordsGeneratorPresets {
             updated_at: row.get(12)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_passwords_generator_presets";
+    pub const COLUMNS: &'static [&'static str] = &["id", "name", "length", "uppercase", "lowercase", "numbers", "symbols", "exclude_chars", "use_pattern", "pattern", "is_default", "created_at", "updated_at"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"name\", \"length\", \"uppercase\", \"lowercase\", \"numbers\", \"symbols\", \"exclude_chars\", \"use_pattern\", \"pattern\", \"is_default\", \"created_at\", \"updated_at\" FROM haex_passwords_generator_presets";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"name\"");
+        values.push(&self.name);
+        columns.push("\"length\"");
+        values.push(&self.length);
+        columns.push("\"uppercase\"");
+        values.push(&self.uppercase);
+        columns.push("\"lowercase\"");
+        values.push(&self.lowercase);
+        columns.push("\"numbers\"");
+        values.push(&self.numbers);
+        columns.push("\"symbols\"");
+        values.push(&self.symbols);
+        if let Some(value) = &self.exclude_chars {
+            columns.push("\"exclude_chars\"");
+            values.push(value);
+        }
+        columns.push("\"use_pattern\"");
+        values.push(&self.use_pattern);
+        if let Some(value) = &self.pattern {
+            columns.push("\"pattern\"");
+            values.push(value);
+        }
+        columns.push("\"is_default\"");
+        values.push(&self.is_default);
+        if let Some(value) = &self.created_at {
+            columns.push("\"created_at\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.updated_at {
+            columns.push("\"updated_at\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_passwords_generator_presets SET \"name\" = ?, \"length\" = ?, \"uppercase\" = ?, \"lowercase\" = ?, \"numbers\" = ?, \"symbols\" = ?, \"exclude_chars\" = ?, \"use_pattern\" = ?, \"pattern\" = ?, \"is_default\" = ?, \"created_at\" = ?, \"updated_at\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.name, self.length, self.uppercase, self.lowercase, self.numbers, self.symbols, self.exclude_chars, self.use_pattern, self.pattern, self.is_default, self.created_at, self.updated_at, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexPasswordsTags {
     pub id: String,
@@ -535,9 +1893,68 @@ impl HaexPasswordsTags {
             created_at: row.get(3)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_passwords_tags";
+    pub const COLUMNS: &'static [&'static str] = &["id", "name", "color", "created_at"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"name\", \"color\", \"created_at\" FROM haex_passwords_tags";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"name\"");
+        values.push(&self.name);
+        if let Some(value) = &self.color {
+            columns.push("\"color\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.created_at {
+            columns.push("\"created_at\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_passwords_tags SET \"name\" = ?, \"color\" = ?, \"created_at\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.name, self.color, self.created_at, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexPasswordsItemTags {
     pub id: String,
@@ -553,9 +1970,62 @@ impl HaexPasswordsItemTags {
             tag_id: row.get(2)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_passwords_item_tags";
+    pub const COLUMNS: &'static [&'static str] = &["id", "item_id", "tag_id"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"item_id\", \"tag_id\" FROM haex_passwords_item_tags";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        columns.push("\"item_id\"");
+        values.push(&self.item_id);
+        columns.push("\"tag_id\"");
+        values.push(&self.tag_id);
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_passwords_item_tags SET \"item_id\" = ?, \"tag_id\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.item_id, self.tag_id, self.id],
+        )?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct HaexPasswordsPasskeys {
     pub id: String,
@@ -610,5 +2080,105 @@ impl HaexPasswordsPasskeys {
             last_used_at: row.get(17)?,
         })
     }
+
+    pub const TABLE: &'static str = "haex_passwords_passkeys";
+    pub const COLUMNS: &'static [&'static str] = &["id", "item_id", "credential_id", "relying_party_id", "relying_party_name", "user_handle", "user_name", "user_display_name", "private_key", "public_key", "algorithm", "sign_count", "is_discoverable", "icon", "color", "nickname", "created_at", "last_used_at"];
+    pub const SELECT_SQL: &'static str =
+        "SELECT \"id\", \"item_id\", \"credential_id\", \"relying_party_id\", \"relying_party_name\", \"user_handle\", \"user_name\", \"user_display_name\", \"private_key\", \"public_key\", \"algorithm\", \"sign_count\", \"is_discoverable\", \"icon\", \"color\", \"nickname\", \"created_at\", \"last_used_at\" FROM haex_passwords_passkeys";
+
+    /// Loads all rows matching `clause` (e.g. `"WHERE x = ?1 ORDER BY y"`, may be empty).
+    pub fn find<P: rusqlite::Params>(
+        conn: &rusqlite::Connection,
+        clause: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<Self>> {
+        let mut stmt = conn.prepare(&format!("{} {}", Self::SELECT_SQL, clause))?;
+        let rows = stmt.query_map(params, Self::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Option<Self>> {
+        Ok(Self::find(conn, "WHERE \"id\" = ?1", [id])?
+            .into_iter()
+            .next())
+    }
+
+    pub fn insert(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        let mut columns: Vec<&str> = Vec::new();
+        let mut values: Vec<&dyn ToSql> = Vec::new();
+        columns.push("\"id\"");
+        values.push(&self.id);
+        if let Some(value) = &self.item_id {
+            columns.push("\"item_id\"");
+            values.push(value);
+        }
+        columns.push("\"credential_id\"");
+        values.push(&self.credential_id);
+        columns.push("\"relying_party_id\"");
+        values.push(&self.relying_party_id);
+        if let Some(value) = &self.relying_party_name {
+            columns.push("\"relying_party_name\"");
+            values.push(value);
+        }
+        columns.push("\"user_handle\"");
+        values.push(&self.user_handle);
+        if let Some(value) = &self.user_name {
+            columns.push("\"user_name\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.user_display_name {
+            columns.push("\"user_display_name\"");
+            values.push(value);
+        }
+        columns.push("\"private_key\"");
+        values.push(&self.private_key);
+        columns.push("\"public_key\"");
+        values.push(&self.public_key);
+        columns.push("\"algorithm\"");
+        values.push(&self.algorithm);
+        columns.push("\"sign_count\"");
+        values.push(&self.sign_count);
+        columns.push("\"is_discoverable\"");
+        values.push(&self.is_discoverable);
+        if let Some(value) = &self.icon {
+            columns.push("\"icon\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.color {
+            columns.push("\"color\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.nickname {
+            columns.push("\"nickname\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.created_at {
+            columns.push("\"created_at\"");
+            values.push(value);
+        }
+        if let Some(value) = &self.last_used_at {
+            columns.push("\"last_used_at\"");
+            values.push(value);
+        }
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            Self::TABLE,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        );
+        SqlExecutor::execute_internal_typed(tx, hlc, &sql, &values)?;
+        Ok(())
+    }
+
+    /// Writes all columns of the row identified by the primary key.
+    pub fn update(&self, tx: &Transaction, hlc: &HlcService) -> Result<(), DatabaseError> {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            "UPDATE haex_passwords_passkeys SET \"item_id\" = ?, \"credential_id\" = ?, \"relying_party_id\" = ?, \"relying_party_name\" = ?, \"user_handle\" = ?, \"user_name\" = ?, \"user_display_name\" = ?, \"private_key\" = ?, \"public_key\" = ?, \"algorithm\" = ?, \"sign_count\" = ?, \"is_discoverable\" = ?, \"icon\" = ?, \"color\" = ?, \"nickname\" = ?, \"created_at\" = ?, \"last_used_at\" = ? WHERE \"id\" = ?",
+            rusqlite::params![self.item_id, self.credential_id, self.relying_party_id, self.relying_party_name, self.user_handle, self.user_name, self.user_display_name, self.private_key, self.public_key, self.algorithm, self.sign_count, self.is_discoverable, self.icon, self.color, self.nickname, self.created_at, self.last_used_at, self.id],
+        )?;
+        Ok(())
+    }
 }
 
@@ -0,0 +1,99 @@
+//! Tests for the typed accessors emitted into [`super::generated`]:
+//! `find`/`get` read through `from_row`, `insert` skips unset optional
+//! columns so SQL defaults apply, and `update` rewrites by primary key.
+
+#![cfg(test)]
+
+use rusqlite::Connection;
+
+use super::connection_context::ConnectionContext;
+use super::generated::HaexExternalAuthorizedClientsNoSync;
+use crate::crdt::hlc::HlcService;
+use crate::test_support::db::open_in_memory_vault;
+
+fn setup_db() -> (Connection, HlcService) {
+    let hlc = HlcService::new_for_testing("generated-test-device");
+    let conn = open_in_memory_vault(hlc.clone(), ConnectionContext::new()).unwrap();
+
+    conn.execute_batch(&format!(
+        "CREATE TABLE {} (
+             id TEXT PRIMARY KEY NOT NULL,
+             client_id TEXT NOT NULL,
+             client_name TEXT NOT NULL,
+             public_key TEXT NOT NULL,
+             extension_id TEXT NOT NULL,
+             authorized_at TEXT DEFAULT (CURRENT_TIMESTAMP),
+             last_seen TEXT
+         );",
+        HaexExternalAuthorizedClientsNoSync::TABLE
+    ))
+    .unwrap();
+
+    (conn, hlc)
+}
+
+fn client(id: &str, client_id: &str) -> HaexExternalAuthorizedClientsNoSync {
+    HaexExternalAuthorizedClientsNoSync {
+        id: id.to_string(),
+        client_id: client_id.to_string(),
+        client_name: "Browser".to_string(),
+        public_key: "pk".to_string(),
+        extension_id: "ext".to_string(),
+        authorized_at: None,
+        last_seen: None,
+    }
+}
+
+fn insert(conn: &mut Connection, hlc: &HlcService, row: &HaexExternalAuthorizedClientsNoSync) {
+    let tx = conn.transaction().unwrap();
+    row.insert(&tx, hlc).unwrap();
+    tx.commit().unwrap();
+}
+
+#[test]
+fn test_insert_applies_sql_defaults_for_unset_columns() {
+    let (mut conn, hlc) = setup_db();
+    insert(&mut conn, &hlc, &client("r1", "c1"));
+
+    let row = HaexExternalAuthorizedClientsNoSync::get(&conn, "r1")
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.client_id, "c1");
+    assert!(row.authorized_at.is_some(), "DEFAULT must fill authorized_at");
+    assert!(row.last_seen.is_none());
+    assert!(HaexExternalAuthorizedClientsNoSync::get(&conn, "missing")
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_update_rewrites_row_by_primary_key() {
+    let (mut conn, hlc) = setup_db();
+    insert(&mut conn, &hlc, &client("r1", "c1"));
+    insert(&mut conn, &hlc, &client("r2", "c2"));
+
+    let mut row = HaexExternalAuthorizedClientsNoSync::get(&conn, "r1")
+        .unwrap()
+        .unwrap();
+    row.client_name = "Renamed".to_string();
+    row.last_seen = Some("2024-01-02 00:00:00".to_string());
+    {
+        let tx = conn.transaction().unwrap();
+        row.update(&tx, &hlc).unwrap();
+        tx.commit().unwrap();
+    }
+
+    let rows = HaexExternalAuthorizedClientsNoSync::find(
+        &conn,
+        "WHERE client_name = ?1",
+        ["Renamed"],
+    )
+    .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].id, "r1");
+    assert_eq!(rows[0].last_seen.as_deref(), Some("2024-01-02 00:00:00"));
+
+    let all = HaexExternalAuthorizedClientsNoSync::find(&conn, "ORDER BY id", []).unwrap();
+    let ids: Vec<&str> = all.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, ["r1", "r2"]);
+}
@@ -17,7 +17,9 @@ use rusqlite::{params, Connection};
 /// - 3: Track haex_tombstone column to enable proper sync of soft-deletes
 /// - 4: Delete-log architecture — DELETE trigger logs to haex_deleted_rows, no tombstone column
 /// - 5: haex_deleted_rows is exempt from the BEFORE-DELETE trigger (cleanup must not recurse)
-const TRIGGER_VERSION: i32 = 5;
+/// - 6: DELETE trigger also snapshots the row into haex_trash_no_sync (record trash)
+/// - 7: DELETE trigger records the row's space_id in haex_deleted_rows
+const TRIGGER_VERSION: i32 = 7;
 
 /// Scans the database for all sync-relevant tables (those that have a `haex_hlc` column).
 /// Tables ending in `_no_sync` are excluded by the naming convention.
@@ -0,0 +1,190 @@
+// src-tauri/src/database/metadata.rs
+//!
+//! Vault metadata readable before unlock.
+//!
+//! Format version, creation date and display name are kept in a plaintext
+//! sidecar next to the vault (`<vault>.db.meta.json`) so the vault picker can
+//! show them without the password. Together with the cipher settings from
+//! [`super::cipher`] they make up what [`vault_peek_metadata`] returns.
+//!
+//! Nothing in here is secret, and nothing depends on it: a missing or
+//! corrupt sidecar only means less to show (vaults created before it
+//! existed have none).
+
+use crate::database::cipher::{self, CipherSettings};
+use crate::database::error::DatabaseError;
+use crate::database::sidecar;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use ts_rs::TS;
+
+/// Suffix appended to the vault DB path for the metadata sidecar.
+const SIDECAR_SUFFIX: &str = ".meta.json";
+
+/// Version of the on-disk vault format written by this build.
+pub const VAULT_FORMAT_VERSION: u32 = 1;
+
+/// Contents of the sidecar file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MetadataRecord {
+    format_version: u32,
+    /// RFC 3339
+    created_at: String,
+    display_name: String,
+}
+
+/// What is known about a vault without unlocking it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultMetadata {
+    /// `null` for vaults created before the metadata sidecar existed
+    pub format_version: Option<u32>,
+    /// Display name, the file name if none was recorded
+    pub display_name: String,
+    /// Creation date (RFC 3339); falls back to the file's creation time
+    pub created_at: Option<String>,
+    /// Cipher parameters the vault is opened with; `null` = SQLCipher
+    /// defaults
+    pub cipher_settings: Option<CipherSettings>,
+}
+
+/// Location of the metadata sidecar of a vault
+pub fn sidecar_path(vault_path: &Path) -> PathBuf {
+    sidecar::sidecar_path(vault_path, SIDECAR_SUFFIX)
+}
+
+fn read_record(vault_path: &Path) -> Option<MetadataRecord> {
+    fs::read_to_string(sidecar_path(vault_path))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+}
+
+/// Records a newly created vault.
+pub fn write_metadata(vault_path: &Path, display_name: &str) -> Result<(), DatabaseError> {
+    let path = sidecar_path(vault_path);
+    let record = MetadataRecord {
+        format_version: VAULT_FORMAT_VERSION,
+        created_at: OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .unwrap_or_default(),
+        display_name: display_name.to_string(),
+    };
+    let json = serde_json::to_string(&record).map_err(|e| DatabaseError::SerializationError {
+        reason: e.to_string(),
+    })?;
+    fs::write(&path, json).map_err(|e| DatabaseError::IoError {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Removes the sidecar, e.g. when the vault itself is deleted.
+pub fn remove_sidecar(vault_path: &Path) -> Result<(), DatabaseError> {
+    sidecar::remove_sidecar(vault_path, SIDECAR_SUFFIX)
+}
+
+pub fn peek_metadata(vault_path: &Path) -> Result<VaultMetadata, DatabaseError> {
+    let file = fs::metadata(vault_path).map_err(|e| DatabaseError::IoError {
+        path: vault_path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    let cipher_settings = cipher::read_settings(vault_path)?;
+
+    let fallback_name = || {
+        vault_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    let file_created_at = || {
+        file.created()
+            .ok()
+            .and_then(|created| OffsetDateTime::from(created).format(&Rfc3339).ok())
+    };
+
+    Ok(match read_record(vault_path) {
+        Some(record) => VaultMetadata {
+            format_version: Some(record.format_version),
+            display_name: record.display_name,
+            created_at: Some(record.created_at),
+            cipher_settings,
+        },
+        None => VaultMetadata {
+            format_version: None,
+            display_name: fallback_name(),
+            created_at: file_created_at(),
+            cipher_settings,
+        },
+    })
+}
+
+/// Metadata of the vault at `vault_path`, readable without the password.
+#[tauri::command]
+pub fn vault_peek_metadata(vault_path: String) -> Result<VaultMetadata, DatabaseError> {
+    peek_metadata(Path::new(&vault_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peek_without_sidecars() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("legacy.db");
+        fs::write(&vault, b"").unwrap();
+
+        let metadata = peek_metadata(&vault).unwrap();
+        assert_eq!(metadata.format_version, None);
+        assert_eq!(metadata.display_name, "legacy");
+        assert_eq!(metadata.cipher_settings, None);
+
+        // Garbage in the sidecar is treated like no sidecar
+        fs::write(sidecar_path(&vault), "not json").unwrap();
+        assert_eq!(peek_metadata(&vault).unwrap().format_version, None);
+    }
+
+    #[test]
+    fn test_peek_with_sidecars() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("work.db");
+        fs::write(&vault, b"").unwrap();
+        assert_eq!(
+            sidecar_path(&vault).file_name().unwrap(),
+            "work.db.meta.json"
+        );
+
+        write_metadata(&vault, "Work Vault").unwrap();
+        let settings = CipherSettings {
+            kdf_iter: 400_000,
+            ..Default::default()
+        };
+        cipher::write_settings(&vault, &settings).unwrap();
+
+        let metadata = vault_peek_metadata(vault.display().to_string()).unwrap();
+        assert_eq!(metadata.format_version, Some(VAULT_FORMAT_VERSION));
+        assert_eq!(metadata.display_name, "Work Vault");
+        assert!(metadata
+            .created_at
+            .as_deref()
+            .is_some_and(|at| OffsetDateTime::parse(at, &Rfc3339).is_ok()));
+        assert_eq!(metadata.cipher_settings, Some(settings));
+
+        remove_sidecar(&vault).unwrap();
+        assert!(!sidecar_path(&vault).exists());
+    }
+
+    #[test]
+    fn test_peek_missing_vault() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            peek_metadata(&dir.path().join("missing.db")),
+            Err(DatabaseError::IoError { .. })
+        ));
+    }
+}
@@ -290,7 +290,7 @@ fn migrations_table_exists(conn: &Connection) -> Result<bool, DatabaseError> {
 }
 
 /// Reads the migration journal to get the list of all migration file names
-fn load_migration_journal(app_handle: &tauri::AppHandle) -> Result<Vec<String>, DatabaseError> {
+pub(crate) fn load_migration_journal(app_handle: &tauri::AppHandle) -> Result<Vec<String>, DatabaseError> {
     let fs = app_handle.fs();
 
     let journal_path = app_handle
@@ -385,7 +385,7 @@ fn load_bundled_migrations(
 
 /// Gets the list of already applied migration names only
 /// Returns empty Vec if migrations table doesn't exist yet
-fn get_applied_migration_names(conn: &Connection) -> Result<Vec<String>, DatabaseError> {
+pub(crate) fn get_applied_migration_names(conn: &Connection) -> Result<Vec<String>, DatabaseError> {
     println!("[MIGRATIONS] get_applied_migration_names: checking if table exists...");
 
     if !migrations_table_exists(conn)? {
@@ -1,20 +1,38 @@
 // src-tauri/src/database/mod.rs
 
+pub mod cipher;
 pub mod connection_context;
 pub mod constants;
 pub mod core;
 pub mod error;
 pub mod generated;
 pub mod init;
+pub mod metadata;
 pub mod migrations;
+pub mod optimize;
 pub mod row;
+pub mod sidecar;
 pub mod stats;
+pub mod password_policy;
+pub mod profile;
+pub mod storage;
+pub mod strict_select;
+pub mod unlock_throttle;
+pub mod validate;
+pub mod vault_access;
 pub mod vault_lock;
+pub mod wal;
+
+#[cfg(test)]
+mod generated_tests;
+#[cfg(test)]
+mod strict_select_tests;
 
 use crate::crdt::hlc::HlcService;
+use crate::crdt::json_patch::{self, JsonPatchOperation};
 use crate::database::core::with_connection;
 use crate::database::error::DatabaseError;
-use crate::event_names::EVENT_CRDT_DIRTY_TABLES_CHANGED;
+use crate::events::{self, payloads::DirtyTablesChanged};
 use crate::extension::database::executor::SqlExecutor;
 use crate::table_names::{COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, TABLE_CRDT_CONFIGS};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
@@ -28,7 +46,7 @@ use std::path::Path;
 use std::sync::Mutex;
 use std::time::UNIX_EPOCH;
 use std::{fs, sync::Arc};
-use tauri::{path::BaseDirectory, AppHandle, Emitter, Manager, State};
+use tauri::{path::BaseDirectory, AppHandle, Manager, State};
 #[cfg(not(target_os = "android"))]
 use trash;
 use ts_rs::TS;
@@ -47,6 +65,19 @@ pub fn sql_select(
     core::select(sql, params, &state.db)
 }
 
+/// `sql_select` that accounts for the delete log of CRDT tables: rows a sync
+/// batch brought back after their delete are filtered out or reported,
+/// depending on `mode` (see `strict_select`)
+#[tauri::command]
+pub fn sql_select_strict(
+    sql: String,
+    params: Vec<JsonValue>,
+    mode: strict_select::TombstoneMode,
+    state: State<'_, AppState>,
+) -> Result<strict_select::StrictSelectResult, DatabaseError> {
+    strict_select::select_strict(sql, params, &state.db, mode)
+}
+
 #[tauri::command]
 pub fn sql_execute(
     sql: String,
@@ -78,14 +109,43 @@ pub fn sql_execute_with_crdt(
         "database::sql_execute_with_crdt",
         serde_json::json!({}),
     )?;
-    let result = core::execute_with_crdt(sql, params, &state.db, &hlc_service)?;
+    let result =
+        core::execute_with_crdt_undoable(sql, params, &state.db, &hlc_service, &state.undo)?;
 
     // Emit event to notify frontend that dirty tables may have changed
-    let _ = app_handle.emit_to("main", EVENT_CRDT_DIRTY_TABLES_CHANGED, ());
+    let _ = events::emit_to_main(&app_handle, &DirtyTablesChanged {});
 
     Ok(result)
 }
 
+/// Partially updates a JSON column of one row via `json_set`. Only the touched
+/// paths get fresh HLCs, so concurrent patches to other keys of the same
+/// value merge on sync instead of overwriting each other.
+#[tauri::command]
+pub fn sql_execute_json_patch(
+    table_name: String,
+    column: String,
+    row_pks: serde_json::Map<String, JsonValue>,
+    patches: Vec<JsonPatchOperation>,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), DatabaseError> {
+    let (sql, params) = json_patch::build_patch_update(&table_name, &column, &row_pks, &patches)?;
+
+    let hlc_service = state.lock_or_fail(
+        &state.hlc,
+        crate::critical::CriticalFailureCode::HlcMutexPoisoned,
+        "database::sql_execute_json_patch",
+        serde_json::json!({}),
+    )?;
+    core::execute_with_crdt(sql, params, &state.db, &hlc_service)?;
+
+    // Emit event to notify frontend that dirty tables may have changed
+    let _ = events::emit_to_main(&app_handle, &DirtyTablesChanged {});
+
+    Ok(())
+}
+
 /// DEPRECATED: Use sql_with_crdt instead
 /// This command is kept for backwards compatibility
 #[tauri::command]
@@ -111,7 +171,7 @@ pub fn sql_query_with_crdt(
     })?;
 
     // Emit event to notify frontend that dirty tables may have changed
-    let _ = app_handle.emit_to("main", EVENT_CRDT_DIRTY_TABLES_CHANGED, ());
+    let _ = events::emit_to_main(&app_handle, &DirtyTablesChanged {});
 
     Ok(result)
 }
@@ -150,10 +210,16 @@ pub fn sql_with_crdt(
                 serde_json::json!({}),
             )?;
 
-            let result = core::execute_with_crdt(sql, params, &state.db, &hlc_service)?;
+            let result = core::execute_with_crdt_undoable(
+                sql,
+                params,
+                &state.db,
+                &hlc_service,
+                &state.undo,
+            )?;
 
             // Emit event to notify frontend that dirty tables may have changed
-            let _ = app_handle.emit_to("main", EVENT_CRDT_DIRTY_TABLES_CHANGED, ());
+            let _ = events::emit_to_main(&app_handle, &DirtyTablesChanged {});
 
             Ok(result)
         }
@@ -197,9 +263,8 @@ fn get_vault_path(app_handle: &AppHandle, vault_name: &str) -> Result<String, Da
 /// Returns the vaults directory path
 #[tauri::command]
 pub fn get_vaults_directory(app_handle: &AppHandle) -> Result<String, DatabaseError> {
-    let vaults_dir = app_handle
-        .path()
-        .resolve(VAULT_DIRECTORY, BaseDirectory::AppLocalData)
+    let vaults_dir = crate::app_profile::data_dir(app_handle)
+        .map(|dir| dir.join(VAULT_DIRECTORY))
         .map_err(|e| DatabaseError::PathResolutionError {
             reason: e.to_string(),
         })?;
@@ -212,8 +277,14 @@ pub fn get_vaults_directory(app_handle: &AppHandle) -> Result<String, DatabaseEr
 #[serde(rename_all = "camelCase")]
 pub struct VaultInfo {
     name: String,
+    /// Unix time (seconds) of the last open on this device, the file's
+    /// modification time if it was never opened here
     last_access: u64,
     path: String,
+    /// Host name of the device that last opened the vault
+    last_opened_device: Option<String>,
+    /// Size of the vault file including its uncheckpointed WAL
+    size_bytes: u64,
 }
 
 /// Lists all vault databases in the vaults directory
@@ -225,6 +296,7 @@ pub fn list_vaults(app_handle: AppHandle) -> Result<Vec<VaultInfo>, DatabaseErro
     println!("Suche vaults in {}", vaults_dir.display());
 
     let mut vaults: Vec<VaultInfo> = vec![];
+    let access_store = vault_access::load(&app_handle)?;
 
     if !vaults_dir.exists() {
         println!("Vaults-Verzeichnis existiert nicht, gebe leere Liste zurück.");
@@ -250,18 +322,28 @@ pub fn list_vaults(app_handle: AppHandle) -> Result<Vec<VaultInfo>, DatabaseErro
 
                     let metadata = fs::metadata(&path).map_err(|e| DatabaseError::IoError {
                         path: path.to_string_lossy().to_string(),
-                        reason: format!("Metadaten konnten nicht gelesen werden: {e}"),
+                        reason: format!("Failed to read metadata: {e}"),
                     })?;
 
-                    let last_access_timestamp = metadata
-                        .accessed()
-                        .map_err(|e| DatabaseError::IoError {
-                            path: path.to_string_lossy().to_string(),
-                            reason: format!("Zugriffszeit konnte nicht gelesen werden: {e}"),
-                        })?
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap_or_default() // Fallback für den seltenen Fall einer Zeit vor 1970
-                        .as_secs();
+                    // atime is unreliable (noatime mounts, Android); prefer the
+                    // recorded open and fall back to the last write.
+                    let access = access_store.get(&path);
+                    let last_access_timestamp = match access {
+                        Some(access) => access.last_opened_at,
+                        None => metadata
+                            .modified()
+                            .map_err(|e| DatabaseError::IoError {
+                                path: path.to_string_lossy().to_string(),
+                                reason: format!("Failed to read modification time: {e}"),
+                            })?
+                            .duration_since(UNIX_EPOCH)
+                            .unwrap_or_default() // Fallback für den seltenen Fall einer Zeit vor 1970
+                            .as_secs(),
+                    };
+
+                    let wal_size = fs::metadata(format!("{}-wal", path.to_string_lossy()))
+                        .map(|wal| wal.len())
+                        .unwrap_or_default();
 
                     let vault_name = filename.trim_end_matches(VAULT_EXTENSION).to_string();
 
@@ -269,6 +351,8 @@ pub fn list_vaults(app_handle: AppHandle) -> Result<Vec<VaultInfo>, DatabaseErro
                         name: vault_name,
                         last_access: last_access_timestamp,
                         path: path.to_string_lossy().to_string(),
+                        last_opened_device: access.and_then(|a| a.last_opened_device.clone()),
+                        size_bytes: metadata.len() + wal_size,
                     });
                 }
             }
@@ -341,6 +425,19 @@ pub fn import_vault(app_handle: AppHandle, source_path: String, vault_name: Opti
         reason: format!("Failed to copy vault file: {e}"),
     })?;
 
+    // Without its cipher sidecar a tuned vault can't be opened; the
+    // metadata sidecar just comes along.
+    for sidecar_path in [cipher::sidecar_path, metadata::sidecar_path] {
+        let source_sidecar = sidecar_path(source);
+        if source_sidecar.exists() {
+            let target_sidecar = sidecar_path(Path::new(&target_path));
+            fs::copy(&source_sidecar, &target_sidecar).map_err(|e| DatabaseError::IoError {
+                path: target_sidecar.display().to_string(),
+                reason: format!("Failed to copy vault sidecar: {e}"),
+            })?;
+        }
+    }
+
     println!(
         "Vault '{}' successfully imported to '{}'",
         vault_name, target_path
@@ -436,6 +533,10 @@ pub fn move_vault_to_trash(
             return Ok(format!("Vault '{vault_name}' already removed"));
         }
 
+        // The lockout sidecar is meaningless without its vault
+        let _ = unlock_throttle::remove_sidecar(Path::new(&vault_path));
+        let _ = vault_access::forget(&app_handle, Path::new(&vault_path));
+
         // Try to move to trash first (works on desktop systems)
         let moved_to_trash = trash::delete(&vault_path).is_ok();
 
@@ -443,6 +544,9 @@ pub fn move_vault_to_trash(
             // Also try to move auxiliary files to trash (ignore errors as they might not exist)
             let _ = trash::delete(&vault_shm_path);
             let _ = trash::delete(&vault_wal_path);
+            // A restored vault needs its cipher settings back
+            let _ = trash::delete(cipher::sidecar_path(Path::new(&vault_path)));
+            let _ = trash::delete(metadata::sidecar_path(Path::new(&vault_path)));
 
             Ok(format!("Vault '{vault_name}' successfully moved to trash"))
         } else {
@@ -481,6 +585,13 @@ pub fn delete_vault(app_handle: AppHandle, vault_name: String) -> Result<String,
         })?;
     }
 
+    unlock_throttle::remove_sidecar(Path::new(&vault_path))?;
+    cipher::remove_sidecar(Path::new(&vault_path))?;
+    metadata::remove_sidecar(Path::new(&vault_path))?;
+    if let Err(e) = vault_access::forget(&app_handle, Path::new(&vault_path)) {
+        eprintln!("Failed to forget vault access of '{vault_name}': {e}");
+    }
+
     fs::remove_file(&vault_path).map_err(|e| DatabaseError::IoError {
         path: vault_path.clone(),
         reason: format!("Failed to delete vault: {e}"),
@@ -596,10 +707,20 @@ pub fn create_encrypted_database(
     vault_name: String,
     key: String,
     space_id: Option<String>,
+    password_policy: Option<password_policy::PasswordPolicy>,
+    cipher_settings: Option<cipher::CipherSettings>,
     state: State<'_, AppState>,
 ) -> Result<String, DatabaseError> {
     println!("Creating encrypted vault with name: {vault_name}");
 
+    // Reject weak passwords before anything touches the disk.
+    let password_policy = password_policy.unwrap_or_default();
+    password_policy.validate()?;
+    password_policy::enforce(&password_policy, &key, &[&vault_name])?;
+    if let Some(settings) = &cipher_settings {
+        settings.validate()?;
+    }
+
     let vault_path = get_vault_path(&app_handle, &vault_name)?;
     println!("Resolved vault path: {vault_path}");
 
@@ -631,11 +752,22 @@ pub fn create_encrypted_database(
     // leave a half-initialized session (connection, HLC, ctx) in AppState
     // which breaks subsequent `open_encrypted_database` retries.
     let outcome: Result<String, DatabaseError> = (|| {
-        create_encrypted_database_inner(&app_handle, &vault_path, &key, space_id, &state)
+        create_encrypted_database_inner(
+            &app_handle,
+            &vault_name,
+            &vault_path,
+            &key,
+            space_id,
+            &password_policy,
+            cipher_settings.as_ref(),
+            &state,
+        )
     })();
 
     if outcome.is_err() {
         let _ = close_database(state.clone());
+    } else if let Err(e) = vault_access::record_open(&app_handle, Path::new(&vault_path)) {
+        eprintln!("[CREATE_DB] Failed to record vault access: {e}");
     }
 
     outcome
@@ -643,9 +775,12 @@ pub fn create_encrypted_database(
 
 fn create_encrypted_database_inner(
     app_handle: &AppHandle,
+    vault_name: &str,
     vault_path: &str,
     key: &str,
     space_id: Option<String>,
+    password_policy: &password_policy::PasswordPolicy,
+    cipher_settings: Option<&cipher::CipherSettings>,
     state: &State<'_, AppState>,
 ) -> Result<String, DatabaseError> {
     let vault_path = vault_path.to_string();
@@ -665,6 +800,18 @@ fn create_encrypted_database_inner(
                 reason: e.to_string(),
             })?;
 
+        // Cipher parameters are fixed once the first page is written; the
+        // sidecar lets every later connection use the same ones.
+        if let Some(settings) = cipher_settings {
+            settings
+                .apply(&conn, None)
+                .map_err(|e| DatabaseError::PragmaError {
+                    pragma: "cipher settings".to_string(),
+                    reason: e.to_string(),
+                })?;
+            cipher::write_settings(Path::new(&vault_path), settings)?;
+        }
+
         // Verify SQLCipher is active
         println!("Verifying SQLCipher encryption...");
         match conn.query_row("PRAGMA cipher_version;", [], |row| {
@@ -709,6 +856,10 @@ fn create_encrypted_database_inner(
 
     println!("[CREATE_DB] ✅ Empty encrypted database created successfully");
 
+    if let Err(e) = metadata::write_metadata(Path::new(&vault_path), vault_name) {
+        eprintln!("[CREATE_DB] Failed to write vault metadata: {e}");
+    }
+
     // Step 2: Open the database and store connection in AppState (without full initialization)
     // We need the connection available for migrations, but can't initialize HLC yet
     // because haex_crdt_configs table doesn't exist until migrations run
@@ -791,6 +942,13 @@ fn create_encrypted_database_inner(
     ensure_default_identity(state)?;
     println!("[CREATE_DB] ✅ default identity ensured");
 
+    // Step 7: Persist the password policy and start the rotation clock
+    core::with_connection(&state.db, |conn| {
+        password_policy::save_policy(conn, password_policy)?;
+        password_policy::record_password_change(conn, time::OffsetDateTime::now_utc())
+    })?;
+    println!("[CREATE_DB] ✅ password policy stored");
+
     // `space_id` is intentionally NOT seeded anymore. The legacy vault-UUID
     // setting was conceptually a device identity proxy; the device-identity
     // refactor moves that into the `haex_devices` table + <app_data>/device_id
@@ -823,7 +981,8 @@ pub fn close_database(state: State<'_, AppState>) -> Result<(), DatabaseError> {
             cancel.cancel();
         }
     });
-    println!("[CLOSE_DB] Runtime state cleared (sync loops, leaders, transfers)");
+    state.undo.clear();
+    println!("[CLOSE_DB] Runtime state cleared (sync loops, leaders, transfers, undo stacks)");
 
     // 1. Drop the critical-notification sink FIRST — its rusqlite
     //    connection is held independently of `state.db`, so closing it
@@ -901,6 +1060,10 @@ pub fn close_database(state: State<'_, AppState>) -> Result<(), DatabaseError> {
             missing.clear();
             println!("[CLOSE_DB] Missing extensions list cleared");
         }
+        if let Ok(mut revoked) = state.extension_manager.revoked_extensions.lock() {
+            revoked.clear();
+            println!("[CLOSE_DB] Revoked extensions list cleared");
+        }
     }
 
     // 4. Release the per-vault advisory lock so another instance (or a
@@ -920,23 +1083,70 @@ fn acquire_vault_lock_or_error(
     vault_path: &str,
     state: &State<'_, AppState>,
 ) -> Result<(), DatabaseError> {
-    let lock = vault_lock::VaultLock::try_acquire(Path::new(vault_path)).map_err(|e| match e {
-        vault_lock::VaultLockError::AlreadyHeld { path, source } => {
+    let lock = try_acquire_vault_lock(vault_path)?;
+
+    let mut guard = state.vault_lock.lock().map_err(|e| DatabaseError::LockError {
+        reason: e.to_string(),
+    })?;
+    *guard = Some(lock);
+    Ok(())
+}
+
+/// Grabs the per-vault advisory lock without mounting anything.
+fn try_acquire_vault_lock(vault_path: &str) -> Result<vault_lock::VaultLock, DatabaseError> {
+    vault_lock::VaultLock::try_acquire(Path::new(vault_path)).map_err(vault_lock_error)
+}
+
+fn vault_lock_error(error: vault_lock::VaultLockError) -> DatabaseError {
+    match error {
+        vault_lock::VaultLockError::AlreadyHeld {
+            path,
+            source,
+            holder,
+        } => DatabaseError::VaultAlreadyOpenElsewhere {
+            path,
+            reason: source.to_string(),
+            holder_pid: holder.as_ref().map(|h| h.pid),
+            holder_hostname: holder.map(|h| h.hostname),
+        },
+        vault_lock::VaultLockError::HolderRunning { path, holder } => {
             DatabaseError::VaultAlreadyOpenElsewhere {
+                reason: format!(
+                    "process {} on this host still holds the lock; close that instance instead",
+                    holder.pid
+                ),
                 path,
-                reason: source.to_string(),
+                holder_pid: Some(holder.pid),
+                holder_hostname: Some(holder.hostname),
             }
         }
         vault_lock::VaultLockError::Io { path, source } => DatabaseError::IoError {
             path,
             reason: format!("vault lock file: {source}"),
         },
-    })?;
+    }
+}
 
-    let mut guard = state.vault_lock.lock().map_err(|e| DatabaseError::LockError {
-        reason: e.to_string(),
-    })?;
-    *guard = Some(lock);
+/// Breaks a vault lock left behind by an instance that is gone, e.g. on a
+/// network filesystem that didn't release it. Refused while the holder is
+/// still running on this host. The UI must have the user confirm that no
+/// other instance has the vault open: a live holder on another host would
+/// keep writing to it.
+#[tauri::command]
+pub fn vault_force_takeover(
+    vault_path: String,
+    state: State<'_, AppState>,
+) -> Result<(), DatabaseError> {
+    reject_if_vault_already_mounted(&state, &vault_path)?;
+    match vault_lock::VaultLock::force_takeover(Path::new(&vault_path))
+        .map_err(vault_lock_error)?
+    {
+        Some(holder) => println!(
+            "[VAULT_LOCK] Took over {vault_path} from process {} on '{}'",
+            holder.pid, holder.hostname
+        ),
+        None => println!("[VAULT_LOCK] Took over {vault_path} (previous holder unknown)"),
+    }
     Ok(())
 }
 
@@ -1063,6 +1273,9 @@ pub fn open_encrypted_database(
         });
     }
 
+    // Refuse the attempt while earlier wrong passwords are still throttled.
+    unlock_throttle::check_unlock_allowed(Path::new(&vault_path))?;
+
     // Acquire the per-vault exclusive lock BEFORE touching SQLite. If another
     // instance holds it, bail out with a dedicated error variant the frontend
     // recognises — opening the DB anyway would race the other instance's WAL
@@ -1092,9 +1305,26 @@ pub fn open_encrypted_database(
 
     if let Err(err) = outcome {
         let _ = close_database(state.clone());
+        if unlock_throttle::is_wrong_password(&err) {
+            if let Err(e) = unlock_throttle::record_failure(Path::new(&vault_path)) {
+                eprintln!("[OPEN_DB] Failed to record failed unlock: {e}");
+            }
+        }
         return Err(err);
     }
 
+    if let Err(e) = unlock_throttle::record_success(Path::new(&vault_path)) {
+        eprintln!("[OPEN_DB] Failed to reset unlock throttling: {e}");
+    }
+    if let Err(e) = vault_access::record_open(&app_handle, Path::new(&vault_path)) {
+        eprintln!("[OPEN_DB] Failed to record vault access: {e}");
+    }
+
+    // A failed rotation check must not block unlocking the vault.
+    if let Err(e) = password_policy::check_rotation_on_open(&app_handle, &state) {
+        eprintln!("[OPEN_DB] Password rotation check failed: {e}");
+    }
+
     println!("[OPEN_DB] ✅ Vault opened successfully");
     Ok(format!("Vault '{vault_path}' opened successfully"))
 }
@@ -1174,6 +1404,11 @@ fn initialize_session(
     // 2. Ensure CRDT triggers are initialized
     let _triggers_were_already_initialized = init::ensure_triggers_initialized(&mut conn)?;
 
+    // Per-device storage settings (cache size); a failure only costs performance.
+    if let Err(e) = storage::apply_connection_settings(&conn) {
+        eprintln!("[STORAGE] Failed to apply storage settings: {e}");
+    }
+
     // 3. Initialize the HLC service *in place* on the AppState instance — the
     //    connection already holds a clone inside the `current_hlc()` UDF.
     {
@@ -1220,6 +1455,154 @@ pub fn crdt_cleanup_deleted_rows(
     })
 }
 
+/// Lists locally deleted rows that can still be restored, newest first.
+/// `table_name = None` lists the trash of the whole vault; `since` is an HLC
+/// timestamp.
+#[tauri::command(rename_all = "camelCase")]
+pub fn crdt_list_tombstoned(
+    table_name: Option<String>,
+    since: Option<String>,
+    limit: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::crdt::trash::TombstonedRow>, DatabaseError> {
+    core::with_connection(&state.db, |conn| {
+        crate::crdt::trash::list_tombstoned(conn, table_name.as_deref(), since.as_deref(), limit)
+    })
+}
+
+/// Restores a deleted row from the trash with a fresh HLC. Conflicts with
+/// newer writes are reported in the result instead of being overwritten.
+#[tauri::command(rename_all = "camelCase")]
+pub fn crdt_restore_row(
+    app_handle: AppHandle,
+    table_name: String,
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<crate::crdt::trash::RestoreRowResult, DatabaseError> {
+    let hlc_service = state.lock_or_fail(
+        &state.hlc,
+        crate::critical::CriticalFailureCode::HlcMutexPoisoned,
+        "database::crdt_restore_row",
+        serde_json::json!({}),
+    )?;
+    let result = core::with_connection(&state.db, |conn| {
+        let tx = conn.transaction()?;
+        let result = crate::crdt::trash::restore_row(&tx, &hlc_service, &table_name, &id)?;
+        tx.commit()?;
+        Ok(result)
+    })?;
+
+    if result.restored {
+        let _ = events::emit_to_main(&app_handle, &DirtyTablesChanged {});
+    }
+
+    Ok(result)
+}
+
+fn run_undo(
+    app_handle: &AppHandle,
+    state: &AppState,
+    scope: crate::crdt::undo::UndoScope,
+    direction: crate::crdt::undo::UndoDirection,
+) -> Result<crate::crdt::undo::UndoResult, DatabaseError> {
+    use crate::crdt::undo::{apply_entry, UndoResult};
+
+    let Some(entry) = state.undo.take(&scope, direction) else {
+        return Ok(UndoResult {
+            applied: false,
+            entry: None,
+            conflict: None,
+        });
+    };
+
+    let applied = {
+        let hlc_service = state.lock_or_fail(
+            &state.hlc,
+            crate::critical::CriticalFailureCode::HlcMutexPoisoned,
+            "database::run_undo",
+            serde_json::json!({}),
+        )?;
+        core::with_connection(&state.db, |conn| {
+            let tx = conn.transaction()?;
+            let conflict = apply_entry(&tx, &hlc_service, &entry, direction)?;
+            // On a conflict `tx` is dropped, rolling back what was applied
+            if conflict.is_none() {
+                tx.commit()?;
+            }
+            Ok(conflict)
+        })
+    };
+
+    let summary = entry.summary();
+    match applied {
+        Err(e) => {
+            state.undo.restore(&scope, direction, entry);
+            Err(e)
+        }
+        Ok(Some(conflict)) => {
+            eprintln!(
+                "[Undo] Dropped entry {} of {:?}: {:?}",
+                summary.id, scope, conflict
+            );
+            Ok(UndoResult {
+                applied: false,
+                entry: Some(summary),
+                conflict: Some(conflict),
+            })
+        }
+        Ok(None) => {
+            state.undo.complete(&scope, direction, entry);
+            let _ = events::emit_to_main(app_handle, &DirtyTablesChanged {});
+            Ok(UndoResult {
+                applied: true,
+                entry: Some(summary),
+                conflict: None,
+            })
+        }
+    }
+}
+
+/// Undoes the newest recorded write of `scope` with fresh HLCs, so the
+/// undo syncs like a regular edit. Rows changed since are reported as a
+/// conflict instead of being overwritten.
+#[tauri::command]
+pub fn undo_last(
+    app_handle: AppHandle,
+    scope: crate::crdt::undo::UndoScope,
+    state: State<'_, AppState>,
+) -> Result<crate::crdt::undo::UndoResult, DatabaseError> {
+    run_undo(
+        &app_handle,
+        &state,
+        scope,
+        crate::crdt::undo::UndoDirection::Undo,
+    )
+}
+
+/// Re-applies the newest undone write of `scope`.
+#[tauri::command]
+pub fn redo_last(
+    app_handle: AppHandle,
+    scope: crate::crdt::undo::UndoScope,
+    state: State<'_, AppState>,
+) -> Result<crate::crdt::undo::UndoResult, DatabaseError> {
+    run_undo(
+        &app_handle,
+        &state,
+        scope,
+        crate::crdt::undo::UndoDirection::Redo,
+    )
+}
+
+/// Lists the undo and redo entries of `scope`, newest first.
+#[tauri::command]
+pub fn undo_get_stack(
+    scope: crate::crdt::undo::UndoScope,
+    state: State<'_, AppState>,
+) -> crate::crdt::undo::UndoStack {
+    state.undo.stack(&scope)
+}
+
 /// Gets statistics about CRDT tables (total entries, tombstoned entries, etc.)
 #[tauri::command]
 pub fn crdt_get_stats(
@@ -1266,6 +1649,16 @@ pub fn change_vault_password(
     core::with_connection(&state.db, |conn| {
         println!("[REKEY] Starting vault password change...");
 
+        // Step 0: The new password has to satisfy the vault's policy
+        let policy = password_policy::load_policy(conn)?;
+        let vault_name = conn
+            .path()
+            .and_then(|path| Path::new(path).file_stem())
+            .and_then(|stem| stem.to_str())
+            .map(str::to_string)
+            .unwrap_or_default();
+        password_policy::enforce(&policy, &new_password, &[&vault_name])?;
+
         // Step 1: Checkpoint the WAL file to ensure all data is in the main database
         println!("[REKEY] Checkpointing WAL file (TRUNCATE mode)...");
         conn.pragma_update(None, "wal_checkpoint", "TRUNCATE")
@@ -1302,7 +1695,51 @@ pub fn change_vault_password(
                 reason: e.to_string(),
             })?;
 
+        let changed_at = time::OffsetDateTime::now_utc();
+        if let Err(e) = password_policy::record_password_change(conn, changed_at) {
+            eprintln!("[REKEY] Failed to record password change time: {e}");
+        }
+
         println!("✅ Vault password changed successfully via SQLCipher rekey");
         Ok("Vault password changed successfully".to_string())
     })
 }
+
+/// Re-encrypts a closed vault with new SQLCipher parameters (KDF iterations,
+/// page size, HMAC algorithm). Vaults still in the SQLCipher 3 format are
+/// migrated in place first.
+///
+/// The whole file is rewritten, so the vault must not be mounted — neither
+/// in this process nor in another instance.
+#[tauri::command]
+pub fn vault_set_cipher_settings(
+    vault_path: String,
+    password: String,
+    settings: cipher::CipherSettings,
+    state: State<'_, AppState>,
+) -> Result<cipher::CipherSettings, DatabaseError> {
+    settings.validate()?;
+    if !Path::new(&vault_path).exists() {
+        return Err(DatabaseError::IoError {
+            path: vault_path,
+            reason: "Vault does not exist".to_string(),
+        });
+    }
+    unlock_throttle::check_unlock_allowed(Path::new(&vault_path))?;
+    reject_if_vault_already_mounted(&state, &vault_path)?;
+
+    // Held until the new file is in place
+    let _lock = try_acquire_vault_lock(&vault_path)?;
+
+    println!("[CIPHER] Re-encrypting {vault_path} with {settings:?}...");
+    if let Err(err) = cipher::reencrypt_vault(Path::new(&vault_path), &password, &settings) {
+        if unlock_throttle::is_wrong_password(&err) {
+            if let Err(e) = unlock_throttle::record_failure(Path::new(&vault_path)) {
+                eprintln!("[CIPHER] Failed to record failed attempt: {e}");
+            }
+        }
+        return Err(err);
+    }
+    println!("✅ Vault re-encrypted with new cipher settings");
+    Ok(settings)
+}
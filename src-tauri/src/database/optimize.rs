@@ -0,0 +1,742 @@
+// src-tauri/src/database/optimize.rs
+//!
+//! Query planner maintenance and index advice.
+//!
+//! `extension_database_query` records SELECTs that take longer than
+//! [`SLOW_QUERY_THRESHOLD`] in the in-memory [`SlowQueryLog`].
+//! `database_optimize` refreshes the planner statistics (`PRAGMA optimize`,
+//! optionally a bounded `ANALYZE`) and runs an advisor pass over those
+//! queries: every recorded statement is explained with `EXPLAIN QUERY PLAN`,
+//! and for each full table scan on one of the recording extension's tables
+//! the WHERE/JOIN/ORDER BY columns are turned into a suggested
+//! `CREATE INDEX` statement.
+//!
+//! Suggestions are never applied here. Extension tables are owned by their
+//! extension, so indexes have to ship through the extension's own migrations
+//! (`extension_database_register_migrations`) to stay in sync across devices.
+
+use crate::database::core::{parse_sql_statements, with_connection};
+use crate::database::error::DatabaseError;
+use crate::AppState;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sqlparser::ast::{
+    BinaryOperator, Expr, JoinConstraint, JoinOperator, ObjectName, ObjectNamePart, OrderByKind,
+    Query, Select, SetExpr, Statement, TableFactor,
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::State;
+use ts_rs::TS;
+
+/// Queries at or above this duration are recorded for the index advisor.
+pub const SLOW_QUERY_THRESHOLD: Duration = Duration::from_millis(100);
+
+/// Maximum number of slow queries kept in memory (oldest are dropped first).
+pub const SLOW_QUERY_LOG_CAPACITY: usize = 200;
+
+/// Upper bound on the number of columns in a suggested index.
+const MAX_INDEX_COLUMNS: usize = 4;
+
+/// Rows sampled per index by `ANALYZE` (see `PRAGMA analysis_limit`).
+const ANALYSIS_LIMIT: u32 = 1000;
+
+/// A recorded slow extension query.
+#[derive(Debug, Clone)]
+pub struct SlowQuery {
+    pub extension_id: String,
+    /// Table prefix of the extension; only these tables get suggestions.
+    pub table_prefix: String,
+    pub sql: String,
+    pub duration: Duration,
+}
+
+/// Bounded in-memory log of slow extension queries (cleared on restart).
+pub struct SlowQueryLog {
+    entries: Mutex<VecDeque<SlowQuery>>,
+}
+
+impl Default for SlowQueryLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SlowQueryLog {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Records `sql` if `duration` reaches [`SLOW_QUERY_THRESHOLD`].
+    pub fn record_if_slow(
+        &self,
+        extension_id: &str,
+        table_prefix: &str,
+        sql: &str,
+        duration: Duration,
+    ) {
+        if duration < SLOW_QUERY_THRESHOLD {
+            return;
+        }
+        eprintln!(
+            "[DB_OPTIMIZE] Slow query from extension {} ({} ms): {}",
+            extension_id,
+            duration.as_millis(),
+            sql
+        );
+        self.record(SlowQuery {
+            extension_id: extension_id.to_string(),
+            table_prefix: table_prefix.to_string(),
+            sql: sql.to_string(),
+            duration,
+        });
+    }
+
+    pub fn record(&self, query: SlowQuery) {
+        // A poisoned log only loses diagnostics; never fail the query for it.
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        if entries.len() >= SLOW_QUERY_LOG_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(query);
+    }
+
+    pub fn snapshot(&self) -> Vec<SlowQuery> {
+        self.entries
+            .lock()
+            .map(|entries| entries.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().map(|entries| entries.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
+
+/// A suggested index for an extension table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexSuggestion {
+    /// Extension that owns the table and issued the slow queries
+    pub extension_id: String,
+    /// Table that is scanned without an index
+    pub table_name: String,
+    /// Indexed columns, in index order
+    pub columns: Vec<String>,
+    /// `CREATE INDEX IF NOT EXISTS` statement for the extension's migrations
+    pub sql: String,
+    /// Number of recorded slow queries this index would serve
+    pub query_count: u32,
+    /// Slowest recorded query this index would serve, in milliseconds
+    pub max_duration_ms: u64,
+    /// Example query (the slowest one)
+    pub example_query: String,
+}
+
+/// Result of `database_optimize`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizeReport {
+    /// Whether a full `ANALYZE` was run in addition to `PRAGMA optimize`
+    pub analyzed: bool,
+    /// Number of recorded slow queries that were inspected
+    pub inspected_queries: u32,
+    /// Time spent on optimize/analyze and the advisor pass, in milliseconds
+    pub duration_ms: u64,
+    /// Suggested indexes, most frequently needed first
+    pub suggestions: Vec<IndexSuggestion>,
+}
+
+/// Runs `PRAGMA optimize` (and `ANALYZE` if requested) and returns index
+/// suggestions for the slow extension queries recorded since startup.
+/// `clear_log` resets the slow-query log afterwards.
+#[tauri::command(rename_all = "camelCase")]
+pub fn database_optimize(
+    state: State<'_, AppState>,
+    analyze: Option<bool>,
+    clear_log: Option<bool>,
+) -> Result<OptimizeReport, DatabaseError> {
+    let started = Instant::now();
+    let analyze = analyze.unwrap_or(false);
+    let slow_queries = state.slow_queries.snapshot();
+
+    let suggestions = with_connection(&state.db, |conn| {
+        run_optimize(conn, analyze)?;
+        advise_indexes(conn, &slow_queries)
+    })?;
+
+    if clear_log.unwrap_or(false) {
+        state.slow_queries.clear();
+    }
+
+    eprintln!(
+        "[DB_OPTIMIZE] Inspected {} slow queries, {} index suggestions",
+        slow_queries.len(),
+        suggestions.len()
+    );
+
+    Ok(OptimizeReport {
+        analyzed: analyze,
+        inspected_queries: slow_queries.len() as u32,
+        duration_ms: started.elapsed().as_millis() as u64,
+        suggestions,
+    })
+}
+
+/// Refreshes planner statistics. `PRAGMA optimize` only analyzes tables
+/// whose statistics are stale; `ANALYZE` is bounded by `analysis_limit`
+/// so it stays fast on large vaults.
+pub fn run_optimize(conn: &Connection, analyze: bool) -> Result<(), DatabaseError> {
+    if analyze {
+        conn.execute_batch(&format!(
+            "PRAGMA analysis_limit = {ANALYSIS_LIMIT}; ANALYZE;"
+        ))
+        .map_err(|e| DatabaseError::PragmaError {
+            pragma: "ANALYZE".to_string(),
+            reason: e.to_string(),
+        })?;
+    }
+    conn.execute_batch("PRAGMA optimize;")
+        .map_err(|e| DatabaseError::PragmaError {
+            pragma: "optimize".to_string(),
+            reason: e.to_string(),
+        })
+}
+
+/// Builds index suggestions for `queries`. Queries that fail to parse or
+/// plan (e.g. because a table was dropped since) are skipped.
+pub fn advise_indexes(
+    conn: &Connection,
+    queries: &[SlowQuery],
+) -> Result<Vec<IndexSuggestion>, DatabaseError> {
+    let mut suggestions: Vec<IndexSuggestion> = Vec::new();
+    let mut schemas = SchemaCache::default();
+
+    for query in queries {
+        for (table, columns) in suggest_for_query(conn, &mut schemas, query) {
+            let duration_ms = query.duration.as_millis() as u64;
+            match suggestions
+                .iter_mut()
+                .find(|s| s.table_name == table && s.columns == columns)
+            {
+                Some(existing) => {
+                    existing.query_count += 1;
+                    if duration_ms > existing.max_duration_ms {
+                        existing.max_duration_ms = duration_ms;
+                        existing.example_query = query.sql.clone();
+                    }
+                }
+                None => suggestions.push(IndexSuggestion {
+                    extension_id: query.extension_id.clone(),
+                    sql: create_index_sql(&table, &columns),
+                    table_name: table,
+                    columns,
+                    query_count: 1,
+                    max_duration_ms: duration_ms,
+                    example_query: query.sql.clone(),
+                }),
+            }
+        }
+    }
+
+    suggestions.sort_by(|a, b| {
+        b.query_count
+            .cmp(&a.query_count)
+            .then(b.max_duration_ms.cmp(&a.max_duration_ms))
+    });
+    Ok(suggestions)
+}
+
+fn create_index_sql(table: &str, columns: &[String]) -> String {
+    let index_name = format!("{}_{}_idx", table, columns.join("_"));
+    let column_list = columns
+        .iter()
+        .map(|c| quote_ident(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "CREATE INDEX IF NOT EXISTS {} ON {} ({});",
+        quote_ident(&index_name),
+        quote_ident(table),
+        column_list
+    )
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Returns `(table, columns)` pairs for every table the query scans fully.
+fn suggest_for_query(
+    conn: &Connection,
+    schemas: &mut SchemaCache,
+    query: &SlowQuery,
+) -> Vec<(String, Vec<String>)> {
+    let Some(shape) = QueryShape::parse(&query.sql) else {
+        return Vec::new();
+    };
+    let Ok(plan) = explain_plan(conn, &query.sql) else {
+        return Vec::new();
+    };
+
+    let mut result = Vec::new();
+    for scanned in &plan.scans {
+        let Some(table) = shape.resolve_table(scanned) else {
+            continue;
+        };
+        if !table.starts_with(&query.table_prefix) {
+            continue;
+        }
+        let Some(table_columns) = schemas.columns(conn, &table) else {
+            continue;
+        };
+
+        let mut columns: Vec<String> = Vec::new();
+        let mut equality = shape.columns_for(&table, scanned, &shape.equality, &table_columns);
+        if equality.is_empty() {
+            // Join keys only help when the table becomes the inner loop.
+            equality = shape.columns_for(&table, scanned, &shape.join_keys, &table_columns);
+        }
+        for column in &equality {
+            push_column(&mut columns, column);
+        }
+        // Only one range column can use the index; anything after it is not.
+        let ranges = shape.columns_for(&table, scanned, &shape.range, &table_columns);
+        if let Some(column) = ranges.first() {
+            push_column(&mut columns, column);
+        } else if plan.temp_order_by && shape.tables.len() == 1 {
+            for column in shape.columns_for(&table, scanned, &shape.order_by, &table_columns) {
+                push_column(&mut columns, &column);
+            }
+        }
+
+        if columns.is_empty() || schemas.is_covered(conn, &table, &columns) {
+            continue;
+        }
+        result.push((table, columns));
+    }
+    result
+}
+
+fn push_column(columns: &mut Vec<String>, column: &str) {
+    if columns.len() < MAX_INDEX_COLUMNS && !columns.iter().any(|c| c == column) {
+        columns.push(column.to_string());
+    }
+}
+
+/// Relevant parts of an `EXPLAIN QUERY PLAN` result.
+struct PlanSummary {
+    /// Table names or aliases that are scanned without an index, or for
+    /// which SQLite builds a throwaway automatic index on every run.
+    scans: Vec<String>,
+    /// Whether the result is sorted in a temporary b-tree.
+    temp_order_by: bool,
+}
+
+fn explain_plan(conn: &Connection, sql: &str) -> Result<PlanSummary, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {sql}"))?;
+    // Unbound parameters are NULL, which is fine for planning.
+    let details = stmt
+        .query_map([], |row| row.get::<_, String>(3))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut summary = PlanSummary {
+        scans: Vec::new(),
+        temp_order_by: false,
+    };
+    for detail in details {
+        if detail.starts_with("USE TEMP B-TREE FOR ORDER BY") {
+            summary.temp_order_by = true;
+        } else if let Some(rest) = detail.strip_prefix("SCAN ") {
+            // "SCAN t USING COVERING INDEX ..." still reads the whole index
+            // but is already served by one; only plain scans get advice.
+            if rest.contains(" USING ") {
+                continue;
+            }
+            if let Some(name) = rest.split_whitespace().next() {
+                summary.scans.push(name.to_string());
+            }
+        } else if let Some(rest) = detail.strip_prefix("SEARCH ") {
+            if rest.contains(" USING AUTOMATIC ") {
+                if let Some(name) = rest.split_whitespace().next() {
+                    summary.scans.push(name.to_string());
+                }
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// A column reference, optionally qualified by table name or alias.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ColumnRef {
+    qualifier: Option<String>,
+    name: String,
+}
+
+/// Tables and filter/sort columns of a single SELECT.
+#[derive(Debug, Default)]
+struct QueryShape {
+    /// (table name, alias) for every table in FROM/JOIN
+    tables: Vec<(String, Option<String>)>,
+    /// Columns compared with a value (`col = ?`, `col IN (...)`, `col IS NULL`)
+    equality: Vec<ColumnRef>,
+    /// Columns compared with another column (`a.x = b.y`)
+    join_keys: Vec<ColumnRef>,
+    range: Vec<ColumnRef>,
+    order_by: Vec<ColumnRef>,
+}
+
+impl QueryShape {
+    fn parse(sql: &str) -> Option<Self> {
+        let statements = parse_sql_statements(sql).ok()?;
+        let Some(Statement::Query(query)) = statements.last() else {
+            return None;
+        };
+        let SetExpr::Select(select) = query.body.as_ref() else {
+            return None;
+        };
+        let mut shape = QueryShape::default();
+        shape.collect_select(select);
+        shape.collect_order_by(query);
+        Some(shape)
+    }
+
+    fn collect_select(&mut self, select: &Select) {
+        for table_with_joins in &select.from {
+            self.collect_table(&table_with_joins.relation);
+            for join in &table_with_joins.joins {
+                self.collect_table(&join.relation);
+                if let Some(JoinConstraint::On(expr)) = join_constraint(&join.join_operator) {
+                    self.collect_predicates(expr);
+                }
+            }
+        }
+        if let Some(selection) = &select.selection {
+            self.collect_predicates(selection);
+        }
+    }
+
+    fn collect_table(&mut self, factor: &TableFactor) {
+        if let TableFactor::Table { name, alias, .. } = factor {
+            if let Some(table) = object_name(name) {
+                let alias = alias.as_ref().map(|a| a.name.value.clone());
+                self.tables.push((table, alias));
+            }
+        }
+    }
+
+    fn collect_order_by(&mut self, query: &Query) {
+        let Some(order_by) = &query.order_by else {
+            return;
+        };
+        if let OrderByKind::Expressions(exprs) = &order_by.kind {
+            for order in exprs {
+                match column_ref(&order.expr) {
+                    Some(column) => self.order_by.push(column),
+                    // An index can only serve a prefix of the sort keys.
+                    None => break,
+                }
+            }
+        }
+    }
+
+    fn collect_predicates(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Nested(inner) => self.collect_predicates(inner),
+            Expr::BinaryOp { left, op, right } => match op {
+                BinaryOperator::And => {
+                    self.collect_predicates(left);
+                    self.collect_predicates(right);
+                }
+                BinaryOperator::Eq => match (column_ref(left), column_ref(right)) {
+                    (Some(a), Some(b)) => {
+                        self.join_keys.push(a);
+                        self.join_keys.push(b);
+                    }
+                    (Some(column), None) | (None, Some(column)) => self.equality.push(column),
+                    (None, None) => {}
+                },
+                BinaryOperator::Lt
+                | BinaryOperator::LtEq
+                | BinaryOperator::Gt
+                | BinaryOperator::GtEq => {
+                    self.range.extend(column_ref(left));
+                    self.range.extend(column_ref(right));
+                }
+                // OR branches need separate indexes; not worth guessing.
+                _ => {}
+            },
+            Expr::InList {
+                expr,
+                negated: false,
+                ..
+            }
+            | Expr::IsNull(expr) => self.equality.extend(column_ref(expr)),
+            Expr::Between {
+                expr,
+                negated: false,
+                ..
+            } => self.range.extend(column_ref(expr)),
+            _ => {}
+        }
+    }
+
+    /// Maps a name from the query plan (table name or alias) to the table.
+    fn resolve_table(&self, scanned: &str) -> Option<String> {
+        self.tables
+            .iter()
+            .find(|(table, alias)| {
+                alias.as_deref() == Some(scanned) || (alias.is_none() && table == scanned)
+            })
+            .map(|(table, _)| table.clone())
+    }
+
+    /// Columns from `refs` that belong to `table` (referenced as `scanned`).
+    /// Unqualified columns are only attributed in single-table queries;
+    /// without the other tables' schemas they cannot be assigned reliably.
+    /// Columns the table does not have (e.g. result aliases) are dropped.
+    fn columns_for(
+        &self,
+        table: &str,
+        scanned: &str,
+        refs: &[ColumnRef],
+        table_columns: &[String],
+    ) -> Vec<String> {
+        refs.iter()
+            .filter(|r| match &r.qualifier {
+                Some(q) => q == scanned || q == table,
+                None => self.tables.len() == 1,
+            })
+            .filter_map(|r| {
+                table_columns
+                    .iter()
+                    .find(|c| c.eq_ignore_ascii_case(&r.name))
+                    .cloned()
+            })
+            .collect()
+    }
+}
+
+fn join_constraint(op: &JoinOperator) -> Option<&JoinConstraint> {
+    match op {
+        JoinOperator::Join(constraint)
+        | JoinOperator::Inner(constraint)
+        | JoinOperator::Left(constraint)
+        | JoinOperator::LeftOuter(constraint) => Some(constraint),
+        _ => None,
+    }
+}
+
+fn object_name(name: &ObjectName) -> Option<String> {
+    match name.0.last()? {
+        ObjectNamePart::Identifier(ident) => Some(ident.value.clone()),
+        _ => None,
+    }
+}
+
+fn column_ref(expr: &Expr) -> Option<ColumnRef> {
+    match expr {
+        Expr::Identifier(ident) => Some(ColumnRef {
+            qualifier: None,
+            name: ident.value.clone(),
+        }),
+        Expr::CompoundIdentifier(parts) if parts.len() >= 2 => Some(ColumnRef {
+            qualifier: Some(parts[parts.len() - 2].value.clone()),
+            name: parts[parts.len() - 1].value.clone(),
+        }),
+        Expr::Nested(inner) => column_ref(inner),
+        _ => None,
+    }
+}
+
+/// Per-pass cache of table columns and existing index column lists.
+#[derive(Default)]
+struct SchemaCache {
+    columns: HashMap<String, Option<Vec<String>>>,
+    indexes: HashMap<String, Vec<Vec<String>>>,
+}
+
+impl SchemaCache {
+    fn columns(&mut self, conn: &Connection, table: &str) -> Option<Vec<String>> {
+        self.columns
+            .entry(table.to_string())
+            .or_insert_with(|| {
+                let columns = pragma_names(conn, "table_info", table, 1).ok()?;
+                (!columns.is_empty()).then_some(columns)
+            })
+            .clone()
+    }
+
+    /// Whether an existing index already starts with `columns`.
+    fn is_covered(&mut self, conn: &Connection, table: &str, columns: &[String]) -> bool {
+        let indexes = self.indexes.entry(table.to_string()).or_insert_with(|| {
+            pragma_names(conn, "index_list", table, 1)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|index| pragma_names(conn, "index_info", index, 2).ok())
+                .collect()
+        });
+        indexes.iter().any(|index| {
+            index.len() >= columns.len()
+                && index
+                    .iter()
+                    .zip(columns)
+                    .all(|(a, b)| a.eq_ignore_ascii_case(b))
+        })
+    }
+}
+
+/// Reads one text column of a table-valued pragma for `name`.
+fn pragma_names(
+    conn: &Connection,
+    pragma: &str,
+    name: &str,
+    column: usize,
+) -> Result<Vec<String>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!("SELECT * FROM pragma_{pragma}(?1)"))?;
+    let names = stmt
+        .query_map([name], |row| row.get::<_, Option<String>>(column))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(names.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREFIX: &str = "pk__notes__";
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE pk__notes__items (id TEXT PRIMARY KEY, folder TEXT, title TEXT, updated INTEGER);
+             CREATE TABLE pk__notes__tags (id TEXT PRIMARY KEY, item_id TEXT, tag TEXT);
+             CREATE TABLE haex_other (id TEXT PRIMARY KEY, value TEXT);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn slow(sql: &str, ms: u64) -> SlowQuery {
+        SlowQuery {
+            extension_id: "ext-1".to_string(),
+            table_prefix: PREFIX.to_string(),
+            sql: sql.to_string(),
+            duration: Duration::from_millis(ms),
+        }
+    }
+
+    #[test]
+    fn test_log_ignores_fast_queries_and_is_bounded() {
+        let log = SlowQueryLog::new();
+        log.record_if_slow("ext", PREFIX, "SELECT 1", Duration::from_millis(1));
+        assert!(log.is_empty());
+
+        for i in 0..SLOW_QUERY_LOG_CAPACITY + 5 {
+            log.record_if_slow("ext", PREFIX, &format!("SELECT {i}"), SLOW_QUERY_THRESHOLD);
+        }
+        let entries = log.snapshot();
+        assert_eq!(entries.len(), SLOW_QUERY_LOG_CAPACITY);
+        assert_eq!(entries[0].sql, "SELECT 5", "oldest entries are dropped first");
+
+        log.clear();
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn test_suggests_equality_then_range_columns() {
+        let conn = setup();
+        let queries = [
+            slow("SELECT * FROM pk__notes__items WHERE folder = ? AND updated > ?", 300),
+            slow("SELECT id FROM pk__notes__items i WHERE i.folder = ? AND i.updated >= ?", 150),
+        ];
+        let suggestions = advise_indexes(&conn, &queries).unwrap();
+        assert_eq!(suggestions.len(), 1);
+
+        let s = &suggestions[0];
+        assert_eq!(s.table_name, "pk__notes__items");
+        assert_eq!(s.columns, vec!["folder", "updated"]);
+        assert_eq!(s.query_count, 2);
+        assert_eq!(s.max_duration_ms, 300);
+        assert_eq!(
+            s.sql,
+            "CREATE INDEX IF NOT EXISTS \"pk__notes__items_folder_updated_idx\" ON \"pk__notes__items\" (\"folder\", \"updated\");"
+        );
+
+        // Applying the suggestion removes the scan.
+        conn.execute_batch(&s.sql).unwrap();
+        assert!(advise_indexes(&conn, &queries).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_suggests_order_by_columns_for_sorted_scans() {
+        let conn = setup();
+        let queries = [slow(
+            "SELECT * FROM pk__notes__items ORDER BY updated DESC, title",
+            200,
+        )];
+        let suggestions = advise_indexes(&conn, &queries).unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].columns, vec!["updated", "title"]);
+    }
+
+    #[test]
+    fn test_suggests_join_columns_on_scanned_table() {
+        let conn = setup();
+        let queries = [slow(
+            "SELECT i.title FROM pk__notes__items i JOIN pk__notes__tags t ON t.item_id = i.id WHERE t.tag = ?",
+            400,
+        )];
+        let suggestions = advise_indexes(&conn, &queries).unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].table_name, "pk__notes__tags");
+        assert_eq!(suggestions[0].columns, vec!["tag"]);
+
+        // Without a value filter on the scanned table, its join key is suggested.
+        let queries = [slow(
+            "SELECT i.title FROM pk__notes__items i JOIN pk__notes__tags t ON t.item_id = i.id WHERE i.folder = ?",
+            400,
+        )];
+        let suggestions = advise_indexes(&conn, &queries).unwrap();
+        assert!(suggestions
+            .iter()
+            .any(|s| s.table_name == "pk__notes__tags" && s.columns == vec!["item_id"]));
+    }
+
+    #[test]
+    fn test_skips_foreign_tables_and_unknown_columns() {
+        let conn = setup();
+        let queries = [
+            slow("SELECT * FROM haex_other WHERE value = ?", 500),
+            slow("SELECT * FROM pk__notes__items WHERE lower(title) = ?", 500),
+            slow("SELECT * FROM pk__notes__missing WHERE a = ?", 500),
+            slow("not even sql", 500),
+        ];
+        assert!(advise_indexes(&conn, &queries).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_run_optimize() {
+        let conn = setup();
+        run_optimize(&conn, true).unwrap();
+        run_optimize(&conn, false).unwrap();
+    }
+}
@@ -0,0 +1,478 @@
+// src-tauri/src/database/password_policy.rs
+//!
+//! Vault password policy and rotation reminders.
+//!
+//! `create_encrypted_database` and `change_vault_password` reject passwords
+//! below the policy's minimum entropy or containing a banned pattern. The
+//! entropy estimate is deliberately simple (character pool × length, with
+//! repeats and ascending/descending runs not counted) — it catches weak
+//! passwords, it does not prove strong ones.
+//!
+//! With `rotation_days` set, opening a vault whose password is older than
+//! that emits `EVENT_VAULT_PASSWORD_ROTATION_DUE`. Policy and the time of the
+//! last password change are per-device settings in haex_crdt_configs: every
+//! device has its own vault file and therefore its own password.
+
+use crate::database::constants::vault_settings_key;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, TABLE_CRDT_CONFIGS,
+};
+use crate::AppState;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use ts_rs::TS;
+
+/// Minimum estimated entropy unless configured otherwise.
+pub const DEFAULT_MIN_ENTROPY_BITS: u32 = 50;
+
+/// Upper bound for the configurable minimum entropy.
+pub const MAX_MIN_ENTROPY_BITS: u32 = 128;
+
+/// Allowed rotation interval range in days.
+pub const MIN_ROTATION_DAYS: u32 = 1;
+pub const MAX_ROTATION_DAYS: u32 = 3650;
+
+/// Patterns shorter than this are ignored, so single characters can't ban
+/// every password.
+const MIN_PATTERN_LENGTH: usize = 3;
+
+/// Always-banned fragments of the most common passwords.
+const BUILTIN_BANNED_PATTERNS: &[&str] = &[
+    "password", "passwort", "123456", "qwerty", "qwertz", "letmein", "welcome", "iloveyou",
+    "admin", "haex",
+];
+
+/// Requirements for the vault password
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordPolicy {
+    /// Minimum estimated entropy in bits (0 disables the check)
+    pub min_entropy_bits: u32,
+    /// Additional case-insensitive fragments the password must not contain
+    #[serde(default)]
+    pub banned_patterns: Vec<String>,
+    /// Remind the user to change the password after this many days
+    #[serde(default)]
+    pub rotation_days: Option<u32>,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_entropy_bits: DEFAULT_MIN_ENTROPY_BITS,
+            banned_patterns: Vec::new(),
+            rotation_days: None,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    pub fn validate(&self) -> Result<(), DatabaseError> {
+        if self.min_entropy_bits > MAX_MIN_ENTROPY_BITS {
+            return Err(DatabaseError::ValidationError {
+                reason: format!(
+                    "Minimum entropy must be at most {MAX_MIN_ENTROPY_BITS} bits, got {}",
+                    self.min_entropy_bits
+                ),
+            });
+        }
+        if let Some(days) = self.rotation_days {
+            if !(MIN_ROTATION_DAYS..=MAX_ROTATION_DAYS).contains(&days) {
+                return Err(DatabaseError::ValidationError {
+                    reason: format!(
+                        "Rotation interval must be between {MIN_ROTATION_DAYS} and {MAX_ROTATION_DAYS} days, got {days}"
+                    ),
+                });
+            }
+        }
+        if let Some(pattern) = self
+            .banned_patterns
+            .iter()
+            .find(|p| p.trim().chars().count() < MIN_PATTERN_LENGTH)
+        {
+            return Err(DatabaseError::ValidationError {
+                reason: format!(
+                    "Banned patterns need at least {MIN_PATTERN_LENGTH} characters, got '{pattern}'"
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Result of checking a password against a policy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordCheck {
+    pub acceptable: bool,
+    pub entropy_bits: u32,
+    /// Human-readable reasons the password is rejected
+    pub violations: Vec<String>,
+}
+
+/// Age of the vault password relative to the rotation policy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordRotationStatus {
+    /// RFC3339 time of the last password change (or of the first open after
+    /// this feature was introduced)
+    pub changed_at: Option<String>,
+    pub age_days: Option<u32>,
+    pub rotation_days: Option<u32>,
+    pub due: bool,
+}
+
+fn charset_size(password: &str) -> u32 {
+    let mut lower = false;
+    let mut upper = false;
+    let mut digit = false;
+    let mut symbol = false;
+    let mut other = false;
+    for c in password.chars() {
+        match c {
+            'a'..='z' => lower = true,
+            'A'..='Z' => upper = true,
+            '0'..='9' => digit = true,
+            c if c.is_ascii() => symbol = true,
+            _ => other = true,
+        }
+    }
+    [
+        (lower, 26),
+        (upper, 26),
+        (digit, 10),
+        (symbol, 33),
+        (other, 100),
+    ]
+    .iter()
+    .filter(|(present, _)| *present)
+    .map(|(_, size)| size)
+    .sum()
+}
+
+/// Characters that continue a repeat (`aaa`) or a run (`abc`, `321`) add
+/// nothing; every other character adds `log2(pool)` bits.
+pub fn estimate_entropy_bits(password: &str) -> u32 {
+    let pool = charset_size(password);
+    if pool == 0 {
+        return 0;
+    }
+
+    let mut effective_length = 0u32;
+    let mut previous: Option<u32> = None;
+    let mut previous_delta: Option<i64> = None;
+    for c in password.chars().map(u32::from) {
+        let delta = previous.map(|p| i64::from(c) - i64::from(p));
+        let predictable = matches!(delta, Some(-1..=1)) && delta == previous_delta;
+        if !predictable {
+            effective_length += 1;
+        }
+        previous = Some(c);
+        previous_delta = delta;
+    }
+
+    (f64::from(effective_length) * f64::from(pool).log2()).floor() as u32
+}
+
+/// Checks `password` against `policy`. `context` holds further words that
+/// must not appear in it, e.g. the vault name.
+pub fn check_password(policy: &PasswordPolicy, password: &str, context: &[&str]) -> PasswordCheck {
+    let entropy_bits = estimate_entropy_bits(password);
+    let mut violations = Vec::new();
+
+    if entropy_bits < policy.min_entropy_bits {
+        violations.push(format!(
+            "Password is too weak (about {entropy_bits} bits, at least {} required)",
+            policy.min_entropy_bits
+        ));
+    }
+
+    let lowered = password.to_lowercase();
+    let patterns = BUILTIN_BANNED_PATTERNS
+        .iter()
+        .copied()
+        .chain(policy.banned_patterns.iter().map(String::as_str))
+        .chain(context.iter().copied())
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| p.chars().count() >= MIN_PATTERN_LENGTH);
+    for pattern in patterns {
+        if lowered.contains(&pattern) {
+            violations.push(format!("Password contains the banned pattern '{pattern}'"));
+        }
+    }
+
+    PasswordCheck {
+        acceptable: violations.is_empty(),
+        entropy_bits,
+        violations,
+    }
+}
+
+/// Like [`check_password`], but fails with a validation error listing all
+/// violations.
+pub fn enforce(
+    policy: &PasswordPolicy,
+    password: &str,
+    context: &[&str],
+) -> Result<(), DatabaseError> {
+    let check = check_password(policy, password, context);
+    if check.acceptable {
+        return Ok(());
+    }
+    Err(DatabaseError::ValidationError {
+        reason: check.violations.join("; "),
+    })
+}
+
+/// `true` once the password is at least `rotation_days` old.
+pub fn is_rotation_due(rotation_days: Option<u32>, age_days: Option<u32>) -> bool {
+    matches!((rotation_days, age_days), (Some(max), Some(age)) if age >= max)
+}
+
+fn read_config(conn: &Connection, key: &str) -> Result<Option<String>, DatabaseError> {
+    Ok(conn
+        .query_row(
+            &format!(
+                "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"
+            ),
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+fn write_config(conn: &Connection, key: &str, value: &str) -> Result<(), DatabaseError> {
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?, ?, ?)"
+        ),
+        params![key, "system", value],
+    )?;
+    Ok(())
+}
+
+/// The stored policy, or the default if none was configured.
+pub fn load_policy(conn: &Connection) -> Result<PasswordPolicy, DatabaseError> {
+    match read_config(conn, vault_settings_key::PASSWORD_POLICY)? {
+        None => Ok(PasswordPolicy::default()),
+        Some(json) => serde_json::from_str(&json).map_err(|e| DatabaseError::SerializationError {
+            reason: format!("Invalid password policy: {e}"),
+        }),
+    }
+}
+
+pub fn save_policy(conn: &Connection, policy: &PasswordPolicy) -> Result<(), DatabaseError> {
+    policy.validate()?;
+    let json = serde_json::to_string(policy).map_err(|e| DatabaseError::SerializationError {
+        reason: e.to_string(),
+    })?;
+    write_config(conn, vault_settings_key::PASSWORD_POLICY, &json)
+}
+
+/// Starts the rotation clock; called after the password was set or changed.
+pub fn record_password_change(conn: &Connection, at: OffsetDateTime) -> Result<(), DatabaseError> {
+    let at = at
+        .format(&Rfc3339)
+        .map_err(|e| DatabaseError::SerializationError {
+            reason: e.to_string(),
+        })?;
+    write_config(conn, vault_settings_key::PASSWORD_CHANGED_AT, &at)
+}
+
+pub fn rotation_status(
+    conn: &Connection,
+    now: OffsetDateTime,
+) -> Result<PasswordRotationStatus, DatabaseError> {
+    let rotation_days = load_policy(conn)?.rotation_days;
+    let changed_at = read_config(conn, vault_settings_key::PASSWORD_CHANGED_AT)?;
+    let age_days = changed_at
+        .as_deref()
+        .and_then(|value| OffsetDateTime::parse(value, &Rfc3339).ok())
+        .map(|changed| (now - changed).whole_days().max(0) as u32);
+
+    Ok(PasswordRotationStatus {
+        due: is_rotation_due(rotation_days, age_days),
+        changed_at,
+        age_days,
+        rotation_days,
+    })
+}
+
+/// Runs after a vault was opened: vaults created before this feature get
+/// their rotation clock started, and an overdue password emits
+/// `EVENT_VAULT_PASSWORD_ROTATION_DUE`.
+pub fn check_rotation_on_open(
+    app_handle: &AppHandle,
+    state: &AppState,
+) -> Result<(), DatabaseError> {
+    let now = OffsetDateTime::now_utc();
+    let status = with_connection(&state.db, |conn| {
+        if read_config(conn, vault_settings_key::PASSWORD_CHANGED_AT)?.is_none() {
+            record_password_change(conn, now)?;
+        }
+        rotation_status(conn, now)
+    })?;
+
+    if status.due {
+        if let Err(e) = crate::events::emit_to_main(app_handle, &status) {
+            eprintln!("[PASSWORD_POLICY] Failed to emit rotation reminder: {e}");
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn vault_get_password_policy(
+    state: State<'_, AppState>,
+) -> Result<PasswordPolicy, DatabaseError> {
+    with_connection(&state.db, |conn| load_policy(conn))
+}
+
+#[tauri::command]
+pub fn vault_set_password_policy(
+    state: State<'_, AppState>,
+    policy: PasswordPolicy,
+) -> Result<PasswordPolicy, DatabaseError> {
+    with_connection(&state.db, |conn| {
+        save_policy(conn, &policy)?;
+        Ok(policy)
+    })
+}
+
+/// Checks a candidate password for the strength meter. Uses `policy` if
+/// given, otherwise the open vault's policy, or the default while no vault
+/// is open (e.g. on the create screen).
+#[tauri::command]
+pub fn vault_check_password(
+    state: State<'_, AppState>,
+    password: String,
+    policy: Option<PasswordPolicy>,
+    vault_name: Option<String>,
+) -> Result<PasswordCheck, DatabaseError> {
+    let policy = match policy {
+        Some(policy) => policy,
+        None => match with_connection(&state.db, |conn| load_policy(conn)) {
+            Err(DatabaseError::ConnectionError { .. }) => PasswordPolicy::default(),
+            result => result?,
+        },
+    };
+    let context: Vec<&str> = vault_name.as_deref().into_iter().collect();
+    Ok(check_password(&policy, &password, &context))
+}
+
+#[tauri::command]
+pub fn vault_get_password_rotation_status(
+    state: State<'_, AppState>,
+) -> Result<PasswordRotationStatus, DatabaseError> {
+    with_connection(&state.db, |conn| {
+        rotation_status(conn, OffsetDateTime::now_utc())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crdt::hlc::HlcService;
+    use crate::test_support::crdt::open_crdt_connection;
+    use time::Duration;
+
+    fn setup() -> Connection {
+        let conn = open_crdt_connection(HlcService::new_for_testing("password-policy-test-device"))
+            .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_entropy_ignores_repeats_and_runs() {
+        assert_eq!(estimate_entropy_bits(""), 0);
+        // "aaaaaaaa" and "abcdefgh" count as two characters each
+        assert_eq!(
+            estimate_entropy_bits("aaaaaaaa"),
+            estimate_entropy_bits("ab")
+        );
+        assert_eq!(
+            estimate_entropy_bits("abcdefgh"),
+            estimate_entropy_bits("ab")
+        );
+        assert!(estimate_entropy_bits("correct horse battery staple") > 100);
+        assert!(estimate_entropy_bits("Tr0ub4dor&3") > DEFAULT_MIN_ENTROPY_BITS);
+        assert!(estimate_entropy_bits("12345678") < DEFAULT_MIN_ENTROPY_BITS);
+    }
+
+    #[test]
+    fn test_check_password_reports_all_violations() {
+        let policy = PasswordPolicy {
+            banned_patterns: vec!["Acme".to_string()],
+            ..Default::default()
+        };
+
+        let check = check_password(&policy, "acme-Password-2026", &["work"]);
+        assert!(!check.acceptable);
+        assert_eq!(check.violations.len(), 2);
+        assert!(check.violations.iter().any(|v| v.contains("'acme'")));
+        assert!(check.violations.iter().any(|v| v.contains("'password'")));
+
+        let check = check_password(&policy, "my WORK vault x9!k2#Lq", &["work"]);
+        assert_eq!(
+            check.violations,
+            vec!["Password contains the banned pattern 'work'"]
+        );
+
+        assert!(check_password(&policy, "blue-Falcon!eats-7-lemons", &["work"]).acceptable);
+        assert!(enforce(&policy, "short", &[]).is_err());
+    }
+
+    #[test]
+    fn test_policy_validation() {
+        assert!(PasswordPolicy::default().validate().is_ok());
+        let too_strict = PasswordPolicy {
+            min_entropy_bits: MAX_MIN_ENTROPY_BITS + 1,
+            ..Default::default()
+        };
+        assert!(too_strict.validate().is_err());
+        let bad_rotation = PasswordPolicy {
+            rotation_days: Some(0),
+            ..Default::default()
+        };
+        assert!(bad_rotation.validate().is_err());
+        let short_pattern = PasswordPolicy {
+            banned_patterns: vec!["a".to_string()],
+            ..Default::default()
+        };
+        assert!(short_pattern.validate().is_err());
+    }
+
+    #[test]
+    fn test_policy_roundtrip_and_rotation() {
+        let conn = setup();
+        assert_eq!(load_policy(&conn).unwrap(), PasswordPolicy::default());
+
+        let policy = PasswordPolicy {
+            rotation_days: Some(90),
+            ..Default::default()
+        };
+        save_policy(&conn, &policy).unwrap();
+        assert_eq!(load_policy(&conn).unwrap(), policy);
+
+        let now = OffsetDateTime::now_utc();
+        let status = rotation_status(&conn, now).unwrap();
+        assert_eq!(status.changed_at, None);
+        assert!(!status.due);
+
+        record_password_change(&conn, now - Duration::days(89)).unwrap();
+        let status = rotation_status(&conn, now).unwrap();
+        assert_eq!(status.age_days, Some(89));
+        assert!(!status.due);
+
+        let status = rotation_status(&conn, now + Duration::days(1)).unwrap();
+        assert!(status.due);
+    }
+}
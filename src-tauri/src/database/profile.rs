@@ -0,0 +1,989 @@
+// src-tauri/src/database/profile.rs
+//!
+//! Encrypted app profile export and import.
+//!
+//! A profile carries the settings a user sets up once per installation:
+//! the installed extensions with their permission decisions, the storage
+//! backends, the workspace layout of this device and the local-only
+//! settings from haex_crdt_configs. It is meant for moving to a new machine
+//! with a fresh vault — vault data itself syncs via CRDT and is not part of
+//! the profile.
+//!
+//! The bundle is JSON: a PBKDF2-HMAC-SHA256 derived key encrypts the payload
+//! with AES-256-GCM; salt, nonce and iteration count travel alongside. Backend
+//! credentials are stripped unless `include_secrets` is set, in which case
+//! they are only ever stored under the profile password.
+//!
+//! Extensions cannot be installed from a profile (it holds no code). Import
+//! reports the missing ones and applies permissions only to extensions that
+//! are already installed. Backends whose credentials were stripped are added
+//! disabled and reported so the user can enter them again.
+
+use crate::crdt::hlc::HlcService;
+use crate::database::constants::vault_settings_key;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::database::generated::{
+    HaexDesktopItemsNoSync, HaexExtensionPermissions, HaexExtensions, HaexWorkspacesNoSync,
+};
+use crate::events::{self, payloads::DirtyTablesChanged};
+use crate::extension::database::executor::SqlExecutor;
+use crate::external_bridge::CORE_EXTENSION_ID;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE,
+    COL_STORAGE_BACKENDS_CONFIG, COL_STORAGE_BACKENDS_ENABLED, COL_STORAGE_BACKENDS_ID,
+    COL_STORAGE_BACKENDS_NAME, COL_STORAGE_BACKENDS_TYPE, TABLE_CRDT_CONFIGS,
+    TABLE_STORAGE_BACKENDS,
+};
+use crate::AppState;
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use hmac::{Hmac, Mac};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use tauri::{AppHandle, State};
+use ts_rs::TS;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const PROFILE_FORMAT: &str = "haex-profile";
+const PROFILE_VERSION: u32 = 1;
+
+/// PBKDF2 iterations for new bundles (OWASP recommendation for SHA-256)
+const PBKDF2_ITERATIONS: u32 = 600_000;
+
+/// Bundles asking for more iterations are rejected, so a crafted file can't
+/// keep the import busy for minutes.
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+const CONFIG_TYPE: &str = "system";
+
+/// Local-only settings that describe the user's setup rather than the state
+/// of this vault file. Cursors, signing keys and transport credentials stay
+/// behind.
+const PROFILE_SETTINGS: &[&str] = &[
+    vault_settings_key::STORAGE_CACHE_SIZE_KIB,
+    vault_settings_key::STORAGE_VACUUM_BATCH_PAGES,
+    vault_settings_key::BACKGROUND_SYNC,
+    vault_settings_key::UNIQUE_CONFLICT_STRATEGY,
+    vault_settings_key::PASSWORD_POLICY,
+    vault_settings_key::EXTENSION_EVENT_POLICY,
+    vault_settings_key::RETENTION_RULES,
+    vault_settings_key::STORAGE_QUOTAS,
+];
+
+/// Backend config fields holding credentials
+const SECRET_CONFIG_KEYS: &[&str] = &["accessKeyId", "secretAccessKey"];
+
+/// An extension as listed in a profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileExtension {
+    pub public_key: String,
+    pub name: String,
+    pub version: String,
+    pub enabled: bool,
+}
+
+/// What `profile_import` applied and what is left for the user
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileImportResult {
+    /// Extensions of the profile that are not installed here
+    pub missing_extensions: Vec<ProfileExtension>,
+    /// Permission decisions written for installed extensions
+    pub permissions_applied: u32,
+    /// Names of the storage backends that were added
+    pub backends_added: Vec<String>,
+    /// Ids of added backends that were exported without credentials and are
+    /// therefore disabled
+    pub backends_needing_credentials: Vec<String>,
+    /// Workspaces that replaced this device's layout
+    pub workspaces_restored: u32,
+    /// Keys of the restored local settings
+    pub settings_restored: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilePermission {
+    pub public_key: String,
+    pub name: String,
+    pub resource_type: Option<String>,
+    pub action: Option<String>,
+    pub target: Option<String>,
+    pub constraints: Option<String>,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileBackend {
+    pub id: String,
+    pub r#type: String,
+    pub name: String,
+    pub enabled: bool,
+    pub config: JsonValue,
+    pub credentials_included: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileDesktopItem {
+    pub item_type: String,
+    /// `(public_key, name)` of the extension for extension items
+    pub extension: Option<(String, String)>,
+    pub system_window_id: Option<String>,
+    pub position_x: i64,
+    pub position_y: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileWorkspace {
+    pub name: String,
+    pub position: i64,
+    pub background: Option<String>,
+    pub items: Vec<ProfileDesktopItem>,
+}
+
+/// Decrypted content of a profile bundle
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilePayload {
+    pub extensions: Vec<ProfileExtension>,
+    pub permissions: Vec<ProfilePermission>,
+    pub backends: Vec<ProfileBackend>,
+    pub workspaces: Vec<ProfileWorkspace>,
+    pub settings: BTreeMap<String, String>,
+}
+
+/// Unencrypted wrapper written to disk
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileEnvelope {
+    format: String,
+    version: u32,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+// ============================================================================
+// Encryption
+// ============================================================================
+
+/// PBKDF2-HMAC-SHA256 with a single 32-byte output block (RFC 8018)
+fn derive_key(password: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mac = <HmacSha256 as Mac>::new_from_slice(password.as_bytes())
+        .expect("HMAC accepts keys of any length");
+
+    let mut block = mac.clone();
+    block.update(salt);
+    block.update(&1u32.to_be_bytes());
+    let mut u = block.finalize().into_bytes();
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&u);
+
+    for _ in 1..iterations {
+        let mut block = mac.clone();
+        block.update(&u);
+        u = block.finalize().into_bytes();
+        key.iter_mut().zip(u.iter()).for_each(|(k, b)| *k ^= b);
+    }
+    key
+}
+
+fn seal(
+    payload: &ProfilePayload,
+    password: &str,
+    iterations: u32,
+) -> Result<Vec<u8>, DatabaseError> {
+    let plaintext = serde_json::to_vec(payload).map_err(|e| DatabaseError::SerializationError {
+        reason: e.to_string(),
+    })?;
+
+    let mut salt = [0u8; SALT_LENGTH];
+    rand::fill(&mut salt);
+    let mut nonce = [0u8; NONCE_LENGTH];
+    rand::fill(&mut nonce);
+
+    let key = derive_key(password, &salt, iterations);
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| DatabaseError::DatabaseError {
+        reason: format!("AES init failed: {e}"),
+    })?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+        .map_err(|e| DatabaseError::DatabaseError {
+            reason: format!("Profile encryption failed: {e}"),
+        })?;
+
+    let envelope = ProfileEnvelope {
+        format: PROFILE_FORMAT.to_string(),
+        version: PROFILE_VERSION,
+        iterations,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+    serde_json::to_vec_pretty(&envelope).map_err(|e| DatabaseError::SerializationError {
+        reason: e.to_string(),
+    })
+}
+
+fn open(bundle: &[u8], password: &str) -> Result<ProfilePayload, DatabaseError> {
+    let invalid = |reason: String| DatabaseError::ValidationError { reason };
+
+    let envelope: ProfileEnvelope = serde_json::from_slice(bundle)
+        .map_err(|e| invalid(format!("Not a profile bundle: {e}")))?;
+    if envelope.format != PROFILE_FORMAT {
+        return Err(invalid(format!(
+            "Unknown bundle format '{}'",
+            envelope.format
+        )));
+    }
+    if envelope.version > PROFILE_VERSION {
+        return Err(invalid(format!(
+            "Profile version {} is newer than supported version {PROFILE_VERSION}",
+            envelope.version
+        )));
+    }
+    if envelope.iterations == 0 || envelope.iterations > MAX_PBKDF2_ITERATIONS {
+        return Err(invalid(format!(
+            "Unsupported iteration count {}",
+            envelope.iterations
+        )));
+    }
+
+    let decode = |field: &str, value: &str| {
+        BASE64
+            .decode(value)
+            .map_err(|e| invalid(format!("Invalid {field} base64: {e}")))
+    };
+    let salt = decode("salt", &envelope.salt)?;
+    let nonce = decode("nonce", &envelope.nonce)?;
+    let ciphertext = decode("ciphertext", &envelope.ciphertext)?;
+    if nonce.len() != NONCE_LENGTH {
+        return Err(invalid(format!(
+            "Invalid nonce length: expected {NONCE_LENGTH}, got {}",
+            nonce.len()
+        )));
+    }
+
+    let key = derive_key(password, &salt, envelope.iterations);
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| DatabaseError::DatabaseError {
+        reason: format!("AES init failed: {e}"),
+    })?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| invalid("Wrong password or damaged profile bundle".to_string()))?;
+
+    serde_json::from_slice(&plaintext).map_err(|e| DatabaseError::SerializationError {
+        reason: format!("Invalid profile content: {e}"),
+    })
+}
+
+// ============================================================================
+// Collecting
+// ============================================================================
+
+/// Removes credential fields, returns whether any were present
+fn strip_secrets(config: &mut JsonValue) -> bool {
+    let Some(object) = config.as_object_mut() else {
+        return false;
+    };
+    let mut stripped = false;
+    for key in SECRET_CONFIG_KEYS {
+        stripped |= object.remove(*key).is_some();
+    }
+    stripped
+}
+
+/// Reads the profile of the open vault. `device_id` selects the workspace
+/// layout to include.
+pub fn collect_profile(
+    conn: &Connection,
+    device_id: &str,
+    include_secrets: bool,
+) -> Result<ProfilePayload, DatabaseError> {
+    // Dev extensions point at local paths and don't carry over
+    let installed = HaexExtensions::find(
+        conn,
+        "WHERE \"id\" != ?1 AND \"dev_path\" IS NULL ORDER BY \"name\"",
+        [CORE_EXTENSION_ID],
+    )?;
+    let by_id: BTreeMap<&str, &HaexExtensions> =
+        installed.iter().map(|ext| (ext.id.as_str(), ext)).collect();
+
+    let extensions = installed
+        .iter()
+        .map(|ext| ProfileExtension {
+            public_key: ext.public_key.clone(),
+            name: ext.name.clone(),
+            version: ext.version.clone(),
+            enabled: ext.enabled.unwrap_or(true),
+        })
+        .collect();
+
+    let permissions = HaexExtensionPermissions::find(conn, "ORDER BY \"id\"", [])?
+        .into_iter()
+        .filter_map(|permission| {
+            let ext = by_id.get(permission.extension_id.as_str())?;
+            Some(ProfilePermission {
+                public_key: ext.public_key.clone(),
+                name: ext.name.clone(),
+                resource_type: permission.resource_type,
+                action: permission.action,
+                target: permission.target,
+                constraints: permission.constraints,
+                status: permission.status,
+            })
+        })
+        .collect();
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COL_STORAGE_BACKENDS_ID}, {COL_STORAGE_BACKENDS_TYPE}, {COL_STORAGE_BACKENDS_NAME}, \
+         {COL_STORAGE_BACKENDS_ENABLED}, {COL_STORAGE_BACKENDS_CONFIG} \
+         FROM {TABLE_STORAGE_BACKENDS} ORDER BY {COL_STORAGE_BACKENDS_NAME}"
+    ))?;
+    let backends = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<bool>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(|(id, r#type, name, enabled, config)| {
+            let mut config = config
+                .and_then(|c| serde_json::from_str(&c).ok())
+                .unwrap_or(JsonValue::Null);
+            let credentials_included = include_secrets || !strip_secrets(&mut config);
+            ProfileBackend {
+                id,
+                r#type,
+                name,
+                enabled: enabled.unwrap_or(true),
+                config,
+                credentials_included,
+            }
+        })
+        .collect();
+
+    let mut workspaces = Vec::new();
+    for workspace in HaexWorkspacesNoSync::find(
+        conn,
+        "WHERE \"device_id\" = ?1 ORDER BY \"position\"",
+        [device_id],
+    )? {
+        let items = HaexDesktopItemsNoSync::find(
+            conn,
+            "WHERE \"workspace_id\" = ?1 ORDER BY \"id\"",
+            [&workspace.id],
+        )?
+        .into_iter()
+        .filter_map(|item| {
+            let extension = match &item.extension_id {
+                Some(id) => {
+                    let ext = by_id.get(id.as_str())?;
+                    Some((ext.public_key.clone(), ext.name.clone()))
+                }
+                None => None,
+            };
+            Some(ProfileDesktopItem {
+                item_type: item.item_type,
+                extension,
+                system_window_id: item.system_window_id,
+                position_x: item.position_x,
+                position_y: item.position_y,
+            })
+        })
+        .collect();
+        workspaces.push(ProfileWorkspace {
+            name: workspace.name,
+            position: workspace.position,
+            background: workspace.background,
+            items,
+        });
+    }
+
+    let mut settings = BTreeMap::new();
+    for key in PROFILE_SETTINGS {
+        let value: Option<String> = conn
+            .query_row(
+                &format!(
+                    "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"
+                ),
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(value) = value {
+            settings.insert(key.to_string(), value);
+        }
+    }
+
+    Ok(ProfilePayload {
+        extensions,
+        permissions,
+        backends,
+        workspaces,
+        settings,
+    })
+}
+
+// ============================================================================
+// Applying
+// ============================================================================
+
+fn installed_extension_id(
+    conn: &Connection,
+    public_key: &str,
+    name: &str,
+) -> Result<Option<String>, DatabaseError> {
+    Ok(HaexExtensions::find(
+        conn,
+        "WHERE \"public_key\" = ?1 AND \"name\" = ?2",
+        [public_key, name],
+    )?
+    .into_iter()
+    .next()
+    .map(|ext| ext.id))
+}
+
+fn apply_permission(
+    tx: &Transaction,
+    hlc: &HlcService,
+    extension_id: &str,
+    permission: &ProfilePermission,
+) -> Result<(), DatabaseError> {
+    let existing = HaexExtensionPermissions::find(
+        tx,
+        "WHERE \"extension_id\" = ?1 AND \"resource_type\" IS ?2 AND \"action\" IS ?3 AND \"target\" IS ?4",
+        params![
+            extension_id,
+            permission.resource_type,
+            permission.action,
+            permission.target
+        ],
+    )?
+    .into_iter()
+    .next();
+
+    match existing {
+        Some(mut row) => {
+            row.constraints = permission.constraints.clone();
+            row.status = permission.status.clone();
+            row.update(tx, hlc)
+        }
+        None => HaexExtensionPermissions {
+            id: uuid::Uuid::new_v4().to_string(),
+            extension_id: extension_id.to_string(),
+            resource_type: permission.resource_type.clone(),
+            action: permission.action.clone(),
+            target: permission.target.clone(),
+            constraints: permission.constraints.clone(),
+            status: permission.status.clone(),
+            created_at: None,
+            updated_at: None,
+        }
+        .insert(tx, hlc),
+    }
+}
+
+/// Adds a backend unless one with the same id or name exists. Returns
+/// whether it was added.
+fn apply_backend(
+    tx: &Transaction,
+    hlc: &HlcService,
+    backend: &ProfileBackend,
+) -> Result<bool, DatabaseError> {
+    let exists: bool = tx.query_row(
+        &format!(
+            "SELECT EXISTS(SELECT 1 FROM {TABLE_STORAGE_BACKENDS} \
+             WHERE {COL_STORAGE_BACKENDS_ID} = ?1 OR {COL_STORAGE_BACKENDS_NAME} = ?2)"
+        ),
+        params![backend.id, backend.name],
+        |row| row.get(0),
+    )?;
+    if exists {
+        return Ok(false);
+    }
+
+    let enabled = backend.enabled && backend.credentials_included;
+    SqlExecutor::execute_internal_typed(
+        tx,
+        hlc,
+        &format!(
+            "INSERT INTO {TABLE_STORAGE_BACKENDS} \
+             ({COL_STORAGE_BACKENDS_ID}, {COL_STORAGE_BACKENDS_TYPE}, {COL_STORAGE_BACKENDS_NAME}, \
+              {COL_STORAGE_BACKENDS_CONFIG}, {COL_STORAGE_BACKENDS_ENABLED}) \
+             VALUES (?, ?, ?, ?, ?)"
+        ),
+        params![
+            backend.id,
+            backend.r#type,
+            backend.name,
+            backend.config.to_string(),
+            enabled
+        ],
+    )?;
+    Ok(true)
+}
+
+/// Replaces the workspaces of `device_id` with those of the profile.
+/// Returns the number of restored workspaces.
+fn apply_layout(
+    tx: &Transaction,
+    hlc: &HlcService,
+    device_id: &str,
+    workspaces: &[ProfileWorkspace],
+    extension_ids: &BTreeMap<(String, String), String>,
+) -> Result<u32, DatabaseError> {
+    for old in HaexWorkspacesNoSync::find(tx, "WHERE \"device_id\" = ?1", [device_id])? {
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc,
+            &format!(
+                "DELETE FROM {} WHERE \"workspace_id\" = ?",
+                HaexDesktopItemsNoSync::TABLE
+            ),
+            params![old.id],
+        )?;
+    }
+    SqlExecutor::execute_internal_typed(
+        tx,
+        hlc,
+        &format!(
+            "DELETE FROM {} WHERE \"device_id\" = ?",
+            HaexWorkspacesNoSync::TABLE
+        ),
+        params![device_id],
+    )?;
+
+    for workspace in workspaces {
+        let workspace_id = uuid::Uuid::new_v4().to_string();
+        HaexWorkspacesNoSync {
+            id: workspace_id.clone(),
+            device_id: device_id.to_string(),
+            name: workspace.name.clone(),
+            position: workspace.position,
+            background: workspace.background.clone(),
+        }
+        .insert(tx, hlc)?;
+
+        for item in &workspace.items {
+            // Icons of extensions that aren't installed here are dropped
+            let extension_id = match &item.extension {
+                Some(key) => match extension_ids.get(key) {
+                    Some(id) => Some(id.clone()),
+                    None => continue,
+                },
+                None => None,
+            };
+            HaexDesktopItemsNoSync {
+                id: uuid::Uuid::new_v4().to_string(),
+                workspace_id: workspace_id.clone(),
+                item_type: item.item_type.clone(),
+                extension_id,
+                system_window_id: item.system_window_id.clone(),
+                position_x: item.position_x,
+                position_y: item.position_y,
+            }
+            .insert(tx, hlc)?;
+        }
+    }
+    Ok(workspaces.len() as u32)
+}
+
+/// Replays a profile into the open vault. Runs inside the caller's
+/// transaction so a failing step leaves nothing half-applied.
+pub fn apply_profile(
+    tx: &Transaction,
+    hlc: &HlcService,
+    device_id: &str,
+    payload: &ProfilePayload,
+) -> Result<ProfileImportResult, DatabaseError> {
+    let mut result = ProfileImportResult::default();
+
+    let mut extension_ids = BTreeMap::new();
+    for ext in &payload.extensions {
+        match installed_extension_id(tx, &ext.public_key, &ext.name)? {
+            Some(id) => {
+                extension_ids.insert((ext.public_key.clone(), ext.name.clone()), id);
+            }
+            None => result.missing_extensions.push(ext.clone()),
+        }
+    }
+
+    for permission in &payload.permissions {
+        let key = (permission.public_key.clone(), permission.name.clone());
+        if let Some(extension_id) = extension_ids.get(&key) {
+            apply_permission(tx, hlc, extension_id, permission)?;
+            result.permissions_applied += 1;
+        }
+    }
+
+    for backend in &payload.backends {
+        if apply_backend(tx, hlc, backend)? {
+            result.backends_added.push(backend.name.clone());
+            if !backend.credentials_included {
+                result.backends_needing_credentials.push(backend.id.clone());
+            }
+        }
+    }
+
+    result.workspaces_restored =
+        apply_layout(tx, hlc, device_id, &payload.workspaces, &extension_ids)?;
+
+    for (key, value) in &payload.settings {
+        // Keys outside the allow-list would come from a newer or edited bundle
+        if !PROFILE_SETTINGS.contains(&key.as_str()) {
+            continue;
+        }
+        tx.execute(
+            &format!(
+                "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?, ?, ?)"
+            ),
+            params![key, CONFIG_TYPE, value],
+        )?;
+        result.settings_restored.push(key.clone());
+    }
+
+    Ok(result)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Exports the app profile as an encrypted bundle. `device_id` is the device
+/// whose workspace layout is included; backend credentials are only included
+/// with `include_secrets`.
+#[tauri::command]
+pub fn profile_export(
+    state: State<'_, AppState>,
+    password: String,
+    device_id: String,
+    include_secrets: Option<bool>,
+) -> Result<Vec<u8>, DatabaseError> {
+    if password.is_empty() {
+        return Err(DatabaseError::ValidationError {
+            reason: "A password is required to export the profile".to_string(),
+        });
+    }
+    let payload = with_connection(&state.db, |conn| {
+        collect_profile(conn, &device_id, include_secrets.unwrap_or(false))
+    })?;
+    seal(&payload, &password, PBKDF2_ITERATIONS)
+}
+
+/// Decrypts a bundle from `profile_export` and replays it into the open
+/// vault, replacing the workspace layout of `device_id`.
+#[tauri::command]
+pub fn profile_import(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    password: String,
+    device_id: String,
+    bundle: Vec<u8>,
+) -> Result<ProfileImportResult, DatabaseError> {
+    let payload = open(&bundle, &password)?;
+    let result = with_connection(&state.db, |conn| {
+        let tx = conn.transaction()?;
+        let hlc_service = state.lock_or_fail(
+            &state.hlc,
+            crate::critical::CriticalFailureCode::HlcMutexPoisoned,
+            "database::profile::profile_import",
+            serde_json::json!({}),
+        )?;
+        let result = apply_profile(&tx, &hlc_service, &device_id, &payload)?;
+        tx.commit()?;
+        Ok(result)
+    })?;
+
+    println!(
+        "[Profile] Imported {} permissions, {} backends, {} workspaces; {} extensions missing",
+        result.permissions_applied,
+        result.backends_added.len(),
+        result.workspaces_restored,
+        result.missing_extensions.len()
+    );
+    let _ = events::emit_to_main(&app_handle, &DirtyTablesChanged {});
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::crdt::open_crdt_connection;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    /// In-memory vault with the tables a profile touches
+    fn vault(device: &str) -> (Connection, HlcService) {
+        let hlc = HlcService::new_for_testing(device);
+        let conn = open_crdt_connection(hlc.clone()).unwrap();
+        conn.execute_batch(&format!(
+            "CREATE TABLE {ext} (
+                 id TEXT PRIMARY KEY, public_key TEXT NOT NULL, name TEXT NOT NULL,
+                 version TEXT NOT NULL, author TEXT, description TEXT, entry TEXT, homepage TEXT,
+                 enabled INTEGER, icon TEXT, signature TEXT NOT NULL, single_instance INTEGER,
+                 display_mode TEXT, i18n TEXT, dev_path TEXT, created_at TEXT, updated_at TEXT,
+                 haex_hlc TEXT, haex_column_hlcs TEXT NOT NULL DEFAULT '{{}}'
+             );
+             CREATE TABLE {perm} (
+                 id TEXT PRIMARY KEY, extension_id TEXT NOT NULL, resource_type TEXT,
+                 action TEXT, target TEXT, constraints TEXT, status TEXT NOT NULL,
+                 created_at TEXT, updated_at TEXT,
+                 haex_hlc TEXT, haex_column_hlcs TEXT NOT NULL DEFAULT '{{}}'
+             );
+             CREATE TABLE {TABLE_STORAGE_BACKENDS} (
+                 id TEXT PRIMARY KEY, type TEXT NOT NULL, name TEXT NOT NULL UNIQUE,
+                 config TEXT, enabled INTEGER DEFAULT 1, created_at TEXT,
+                 haex_hlc TEXT, haex_column_hlcs TEXT NOT NULL DEFAULT '{{}}'
+             );
+             CREATE TABLE {ws} (
+                 id TEXT PRIMARY KEY, device_id TEXT NOT NULL, name TEXT NOT NULL,
+                 position INTEGER NOT NULL, background TEXT
+             );
+             CREATE TABLE {items} (
+                 id TEXT PRIMARY KEY, workspace_id TEXT NOT NULL, item_type TEXT NOT NULL,
+                 extension_id TEXT, system_window_id TEXT,
+                 position_x INTEGER NOT NULL, position_y INTEGER NOT NULL
+             );",
+            ext = HaexExtensions::TABLE,
+            perm = HaexExtensionPermissions::TABLE,
+            ws = HaexWorkspacesNoSync::TABLE,
+            items = HaexDesktopItemsNoSync::TABLE,
+        ))
+        .unwrap();
+        (conn, hlc)
+    }
+
+    fn install(conn: &Connection, id: &str, public_key: &str, name: &str, dev_path: Option<&str>) {
+        conn.execute(
+            &format!(
+                "INSERT INTO {} (id, public_key, name, version, signature, enabled, dev_path)
+                 VALUES (?1, ?2, ?3, '1.0.0', 'sig', 1, ?4)",
+                HaexExtensions::TABLE
+            ),
+            params![id, public_key, name, dev_path],
+        )
+        .unwrap();
+    }
+
+    /// Source vault: one regular and one dev extension, a permission each,
+    /// an S3 backend, a workspace with icons and two settings
+    fn source_vault() -> Connection {
+        let (conn, _) = vault("source-device");
+        install(&conn, "ext-1", "pk-notes", "notes", None);
+        install(
+            &conn,
+            "ext-dev",
+            "pk-dev",
+            "playground",
+            Some("/home/me/playground"),
+        );
+        conn.execute_batch(&format!(
+            "INSERT INTO {perm} (id, extension_id, resource_type, action, target, status) VALUES
+                 ('p1', 'ext-1', 'db', 'read', 'haex_notes', 'granted'),
+                 ('p2', 'ext-dev', 'db', 'read', '*', 'granted');
+             INSERT INTO {TABLE_STORAGE_BACKENDS} (id, type, name, config, enabled) VALUES
+                 ('b1', 's3', 'Main', '{{\"endpoint\":\"https://s3.example\",\"bucket\":\"vault\",\"accessKeyId\":\"AK\",\"secretAccessKey\":\"SK\"}}', 1);
+             INSERT INTO {ws} (id, device_id, name, position) VALUES
+                 ('w1', 'source-device', 'Home', 0),
+                 ('w-other', 'other-device', 'Elsewhere', 0);
+             INSERT INTO {items} (id, workspace_id, item_type, extension_id, system_window_id, position_x, position_y) VALUES
+                 ('i1', 'w1', 'extension', 'ext-1', NULL, 10, 20),
+                 ('i2', 'w1', 'system', NULL, 'settings', 30, 40),
+                 ('i3', 'w1', 'extension', 'ext-dev', NULL, 50, 60);
+             INSERT INTO {TABLE_CRDT_CONFIGS} (key, type, value) VALUES
+                 ('{quotas}', 'system', '{{\"b1\":1000}}'),
+                 ('{transports}', 'system', '[{{\"token\":\"secret\"}}]');",
+            perm = HaexExtensionPermissions::TABLE,
+            ws = HaexWorkspacesNoSync::TABLE,
+            items = HaexDesktopItemsNoSync::TABLE,
+            quotas = vault_settings_key::STORAGE_QUOTAS,
+            transports = vault_settings_key::SYNC_MESSAGING_TRANSPORTS,
+        ))
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_derive_key_matches_pbkdf2_vectors() {
+        assert_eq!(
+            hex(&derive_key("password", b"salt", 1)),
+            "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"
+        );
+        assert_eq!(
+            hex(&derive_key("password", b"salt", 2)),
+            "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"
+        );
+    }
+
+    #[test]
+    fn test_seal_open_roundtrip_and_wrong_password() {
+        let mut payload = ProfilePayload::default();
+        payload.settings.insert("k".to_string(), "v".to_string());
+
+        let bundle = seal(&payload, "correct horse", 10).unwrap();
+        assert!(!String::from_utf8_lossy(&bundle).contains("\"k\""));
+
+        let opened = open(&bundle, "correct horse").unwrap();
+        assert_eq!(opened.settings.get("k").map(String::as_str), Some("v"));
+
+        let err = open(&bundle, "wrong").unwrap_err();
+        assert!(err.to_string().contains("Wrong password"));
+        assert!(open(b"not json", "correct horse").is_err());
+    }
+
+    #[test]
+    fn test_open_rejects_excessive_iterations() {
+        let bundle = seal(&ProfilePayload::default(), "pw", 1).unwrap();
+        let mut envelope: JsonValue = serde_json::from_slice(&bundle).unwrap();
+        envelope["iterations"] = JsonValue::from(MAX_PBKDF2_ITERATIONS + 1);
+
+        let err = open(envelope.to_string().as_bytes(), "pw").unwrap_err();
+        assert!(err.to_string().contains("iteration count"));
+    }
+
+    #[test]
+    fn test_collect_profile_strips_secrets_and_dev_extensions() {
+        let conn = source_vault();
+        let payload = collect_profile(&conn, "source-device", false).unwrap();
+
+        assert_eq!(payload.extensions.len(), 1);
+        assert_eq!(payload.extensions[0].name, "notes");
+        assert_eq!(payload.permissions.len(), 1);
+        assert_eq!(payload.permissions[0].public_key, "pk-notes");
+
+        let backend = &payload.backends[0];
+        assert!(!backend.credentials_included);
+        assert!(backend.config.get("secretAccessKey").is_none());
+        assert_eq!(backend.config["bucket"], "vault");
+
+        assert_eq!(payload.workspaces.len(), 1);
+        let items = &payload.workspaces[0].items;
+        assert_eq!(items.len(), 2, "the dev extension icon is left out");
+        assert_eq!(
+            items[0].extension,
+            Some(("pk-notes".to_string(), "notes".to_string()))
+        );
+
+        assert_eq!(
+            payload.settings.keys().collect::<Vec<_>>(),
+            vec![vault_settings_key::STORAGE_QUOTAS]
+        );
+
+        let with_secrets = collect_profile(&conn, "source-device", true).unwrap();
+        assert!(with_secrets.backends[0].credentials_included);
+        assert_eq!(with_secrets.backends[0].config["secretAccessKey"], "SK");
+    }
+
+    #[test]
+    fn test_apply_profile_on_new_vault() {
+        let payload = collect_profile(&source_vault(), "source-device", false).unwrap();
+
+        let (mut conn, hlc) = vault("new-device");
+        // The extension is installed under a different local id
+        install(&conn, "local-notes", "pk-notes", "notes", None);
+        conn.execute_batch(&format!(
+            "INSERT INTO {ws} (id, device_id, name, position) VALUES
+                 ('old', 'new-device', 'Default', 0),
+                 ('keep', 'third-device', 'Other', 0);
+             INSERT INTO {items} (id, workspace_id, item_type, system_window_id, position_x, position_y)
+                 VALUES ('old-item', 'old', 'system', 'settings', 0, 0);",
+            ws = HaexWorkspacesNoSync::TABLE,
+            items = HaexDesktopItemsNoSync::TABLE,
+        ))
+        .unwrap();
+
+        let tx = conn.transaction().unwrap();
+        let result = apply_profile(&tx, &hlc, "new-device", &payload).unwrap();
+        tx.commit().unwrap();
+
+        assert!(result.missing_extensions.is_empty());
+        assert_eq!(result.permissions_applied, 1);
+        assert_eq!(result.backends_added, vec!["Main"]);
+        assert_eq!(result.backends_needing_credentials, vec!["b1"]);
+        assert_eq!(result.workspaces_restored, 1);
+        assert_eq!(
+            result.settings_restored,
+            vec![vault_settings_key::STORAGE_QUOTAS]
+        );
+
+        let permission = HaexExtensionPermissions::find(&conn, "", []).unwrap();
+        assert_eq!(permission.len(), 1);
+        assert_eq!(permission[0].extension_id, "local-notes");
+
+        let enabled: bool = conn
+            .query_row(
+                &format!("SELECT enabled FROM {TABLE_STORAGE_BACKENDS} WHERE id = 'b1'"),
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!enabled, "backends without credentials start disabled");
+
+        let workspaces = HaexWorkspacesNoSync::find(&conn, "ORDER BY \"device_id\"", []).unwrap();
+        assert_eq!(
+            workspaces
+                .iter()
+                .map(|w| w.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Home", "Other"]
+        );
+        let items = HaexDesktopItemsNoSync::find(&conn, "ORDER BY \"position_x\"", []).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].extension_id.as_deref(), Some("local-notes"));
+        assert_eq!(items[1].system_window_id.as_deref(), Some("settings"));
+    }
+
+    #[test]
+    fn test_apply_profile_reports_missing_and_keeps_existing() {
+        let payload = collect_profile(&source_vault(), "source-device", true).unwrap();
+
+        let (mut conn, hlc) = vault("new-device");
+        conn.execute(
+            &format!(
+                "INSERT INTO {TABLE_STORAGE_BACKENDS} (id, type, name, config) VALUES ('mine', 's3', 'Main', '{{}}')"
+            ),
+            [],
+        )
+        .unwrap();
+
+        let tx = conn.transaction().unwrap();
+        let result = apply_profile(&tx, &hlc, "new-device", &payload).unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(result.missing_extensions.len(), 1);
+        assert_eq!(result.missing_extensions[0].public_key, "pk-notes");
+        assert_eq!(result.permissions_applied, 0);
+        assert!(result.backends_added.is_empty(), "same name already exists");
+
+        // The icon of the missing extension is dropped, the system icon stays
+        let items = HaexDesktopItemsNoSync::find(&conn, "", []).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].item_type, "system");
+    }
+}
@@ -0,0 +1,63 @@
+// src-tauri/src/database/sidecar.rs
+//!
+//! Files kept next to a vault (`<vault>.db<suffix>`)
+//!
+//! Cipher settings, unlock throttling and metadata each store a small JSON
+//! file beside the vault, because they are needed before the vault can be
+//! decrypted. This module locates and removes them; reading and writing
+//! stays with the owning module.
+
+use crate::database::error::DatabaseError;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Location of the sidecar with `suffix`; different spellings of the same
+/// vault path map to the same file.
+pub fn sidecar_path(vault_path: &Path, suffix: &str) -> PathBuf {
+    let vault_path = fs::canonicalize(vault_path).unwrap_or_else(|_| vault_path.to_path_buf());
+    let mut file_name = vault_path
+        .file_name()
+        .map(|s| s.to_os_string())
+        .unwrap_or_default();
+    file_name.push(suffix);
+    vault_path.with_file_name(file_name)
+}
+
+/// Removes the sidecar with `suffix`; a missing file is not an error.
+pub fn remove_sidecar(vault_path: &Path, suffix: &str) -> Result<(), DatabaseError> {
+    let path = sidecar_path(vault_path, suffix);
+    match fs::remove_file(&path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(DatabaseError::IoError {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spellings_of_a_path_share_the_sidecar() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("my.db");
+        fs::write(&vault, b"").unwrap();
+        let dotted = dir.path().join(".").join("my.db");
+
+        assert_eq!(
+            sidecar_path(&vault, ".x.json"),
+            sidecar_path(&dotted, ".x.json")
+        );
+        assert_eq!(
+            sidecar_path(&vault, ".x.json").file_name().unwrap(),
+            "my.db.x.json"
+        );
+
+        fs::write(sidecar_path(&vault, ".x.json"), b"{}").unwrap();
+        remove_sidecar(&dotted, ".x.json").unwrap();
+        assert!(!sidecar_path(&vault, ".x.json").exists());
+        remove_sidecar(&vault, ".x.json").unwrap();
+    }
+}
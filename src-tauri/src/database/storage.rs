@@ -0,0 +1,411 @@
+// src-tauri/src/database/storage.rs
+//!
+//! Storage tuning: auto-vacuum mode, idle incremental vacuum and page cache.
+//!
+//! A full `VACUUM` rewrites the whole vault while holding the single
+//! connection, which freezes the app for large vaults. With
+//! `auto_vacuum = INCREMENTAL` SQLite keeps freed pages on the freelist and
+//! [`run_idle_vacuum_loop`] returns them to the OS in small batches whenever
+//! the connection is not in use.
+//!
+//! Switching between `NONE` and `FULL`/`INCREMENTAL` requires one final
+//! `VACUUM`, which `database_configure_storage` runs. The page size is
+//! reported but not configurable: SQLCipher fixes it (`cipher_page_size`)
+//! when the vault is created.
+//!
+//! Cache size and vacuum batch size are per-device settings stored in
+//! haex_crdt_configs (local-only, not synced).
+
+use crate::database::constants::vault_settings_key;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, TABLE_CRDT_CONFIGS,
+};
+use crate::AppState;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+use ts_rs::TS;
+
+/// Pages freed per idle step unless configured otherwise (1 MiB at 4 KiB pages).
+pub const DEFAULT_VACUUM_BATCH_PAGES: u32 = 256;
+
+/// Upper bound for the vacuum batch size, keeps a single step short.
+pub const MAX_VACUUM_BATCH_PAGES: u32 = 4096;
+
+/// Allowed page cache range in KiB (1 MiB – 512 MiB).
+pub const MIN_CACHE_SIZE_KIB: u32 = 1024;
+pub const MAX_CACHE_SIZE_KIB: u32 = 512 * 1024;
+
+/// How often the idle vacuum loop checks the freelist.
+pub const IDLE_VACUUM_INTERVAL: Duration = Duration::from_secs(30);
+
+/// SQLite `auto_vacuum` mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum AutoVacuumMode {
+    None,
+    Full,
+    Incremental,
+}
+
+impl AutoVacuumMode {
+    fn from_pragma(value: i64) -> Self {
+        match value {
+            1 => AutoVacuumMode::Full,
+            2 => AutoVacuumMode::Incremental,
+            _ => AutoVacuumMode::None,
+        }
+    }
+
+    fn pragma_value(self) -> i64 {
+        match self {
+            AutoVacuumMode::None => 0,
+            AutoVacuumMode::Full => 1,
+            AutoVacuumMode::Incremental => 2,
+        }
+    }
+}
+
+/// Current storage settings of the open vault
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageInfo {
+    /// Active auto-vacuum mode
+    pub auto_vacuum: AutoVacuumMode,
+    /// Page size in bytes (fixed by SQLCipher at vault creation)
+    pub page_size: u32,
+    /// Total number of pages in the database file
+    pub page_count: i64,
+    /// Number of unused pages that a vacuum can release
+    pub freelist_count: i64,
+    /// Page cache size of the connection in KiB
+    pub cache_size_kib: u32,
+    /// Pages released per idle incremental-vacuum step
+    pub vacuum_batch_pages: u32,
+}
+
+/// Settings accepted by `database_configure_storage`; omitted fields stay unchanged.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageConfig {
+    /// New auto-vacuum mode. Switching from or to `none` runs a full VACUUM once.
+    pub auto_vacuum: Option<AutoVacuumMode>,
+    /// Page cache size in KiB
+    pub cache_size_kib: Option<u32>,
+    /// Pages released per idle incremental-vacuum step
+    pub vacuum_batch_pages: Option<u32>,
+}
+
+impl StorageConfig {
+    pub fn validate(&self) -> Result<(), DatabaseError> {
+        if let Some(kib) = self.cache_size_kib {
+            if !(MIN_CACHE_SIZE_KIB..=MAX_CACHE_SIZE_KIB).contains(&kib) {
+                return Err(DatabaseError::ValidationError {
+                    reason: format!(
+                        "Cache size must be between {MIN_CACHE_SIZE_KIB} and {MAX_CACHE_SIZE_KIB} KiB, got {kib}"
+                    ),
+                });
+            }
+        }
+        if let Some(pages) = self.vacuum_batch_pages {
+            if !(1..=MAX_VACUUM_BATCH_PAGES).contains(&pages) {
+                return Err(DatabaseError::ValidationError {
+                    reason: format!(
+                        "Vacuum batch size must be between 1 and {MAX_VACUUM_BATCH_PAGES} pages, got {pages}"
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the storage settings of the open vault.
+#[tauri::command]
+pub fn database_get_storage_info(state: State<'_, AppState>) -> Result<StorageInfo, DatabaseError> {
+    with_connection(&state.db, |conn| read_storage_info(conn))
+}
+
+/// Applies storage settings to the open vault and persists the per-device ones.
+#[tauri::command]
+pub fn database_configure_storage(
+    state: State<'_, AppState>,
+    config: StorageConfig,
+) -> Result<StorageInfo, DatabaseError> {
+    with_connection(&state.db, |conn| configure_storage(conn, &config))
+}
+
+pub fn configure_storage(
+    conn: &Connection,
+    config: &StorageConfig,
+) -> Result<StorageInfo, DatabaseError> {
+    config.validate()?;
+
+    if let Some(kib) = config.cache_size_kib {
+        apply_cache_size(conn, kib)?;
+        write_setting(conn, vault_settings_key::STORAGE_CACHE_SIZE_KIB, kib)?;
+    }
+    if let Some(pages) = config.vacuum_batch_pages {
+        write_setting(conn, vault_settings_key::STORAGE_VACUUM_BATCH_PAGES, pages)?;
+    }
+    if let Some(mode) = config.auto_vacuum {
+        set_auto_vacuum(conn, mode)?;
+    }
+
+    read_storage_info(conn)
+}
+
+pub fn read_storage_info(conn: &Connection) -> Result<StorageInfo, DatabaseError> {
+    let auto_vacuum: i64 = read_pragma(conn, "auto_vacuum")?;
+    let page_size: i64 = read_pragma(conn, "page_size")?;
+    let page_count: i64 = read_pragma(conn, "page_count")?;
+    let freelist_count: i64 = read_pragma(conn, "freelist_count")?;
+    let cache_size: i64 = read_pragma(conn, "cache_size")?;
+
+    // Negative cache_size is KiB, positive is a page count.
+    let cache_size_kib = if cache_size < 0 {
+        -cache_size
+    } else {
+        cache_size * page_size / 1024
+    };
+
+    Ok(StorageInfo {
+        auto_vacuum: AutoVacuumMode::from_pragma(auto_vacuum),
+        page_size: page_size as u32,
+        page_count,
+        freelist_count,
+        cache_size_kib: cache_size_kib as u32,
+        vacuum_batch_pages: vacuum_batch_pages(conn)?,
+    })
+}
+
+/// Applies per-device settings to a freshly opened connection.
+pub fn apply_connection_settings(conn: &Connection) -> Result<(), DatabaseError> {
+    if let Some(kib) = read_setting(conn, vault_settings_key::STORAGE_CACHE_SIZE_KIB)? {
+        if (MIN_CACHE_SIZE_KIB..=MAX_CACHE_SIZE_KIB).contains(&kib) {
+            apply_cache_size(conn, kib)?;
+        }
+    }
+    Ok(())
+}
+
+fn apply_cache_size(conn: &Connection, kib: u32) -> Result<(), DatabaseError> {
+    conn.pragma_update(None, "cache_size", -i64::from(kib))
+        .map_err(|e| DatabaseError::PragmaError {
+            pragma: "cache_size".to_string(),
+            reason: e.to_string(),
+        })
+}
+
+/// Changes `auto_vacuum`. Moving between `NONE` and an auto mode only takes
+/// effect after a full VACUUM; between `FULL` and `INCREMENTAL` it is instant.
+fn set_auto_vacuum(conn: &Connection, mode: AutoVacuumMode) -> Result<(), DatabaseError> {
+    let current = AutoVacuumMode::from_pragma(read_pragma(conn, "auto_vacuum")?);
+    if current == mode {
+        return Ok(());
+    }
+
+    conn.pragma_update(None, "auto_vacuum", mode.pragma_value())
+        .map_err(|e| DatabaseError::PragmaError {
+            pragma: "auto_vacuum".to_string(),
+            reason: e.to_string(),
+        })?;
+
+    if current == AutoVacuumMode::None || mode == AutoVacuumMode::None {
+        println!("[STORAGE] Switching auto_vacuum {current:?} -> {mode:?}, running VACUUM...");
+        conn.execute("VACUUM", [])
+            .map_err(|e| DatabaseError::ExecutionError {
+                sql: "VACUUM".to_string(),
+                reason: e.to_string(),
+                table: None,
+            })?;
+    }
+    Ok(())
+}
+
+/// Releases up to `max_pages` free pages. Returns the number of pages freed;
+/// always 0 unless the vault uses `auto_vacuum = INCREMENTAL`.
+pub fn incremental_vacuum(conn: &Connection, max_pages: u32) -> Result<u32, DatabaseError> {
+    let mode = AutoVacuumMode::from_pragma(read_pragma(conn, "auto_vacuum")?);
+    if mode != AutoVacuumMode::Incremental {
+        return Ok(0);
+    }
+    let free: i64 = read_pragma(conn, "freelist_count")?;
+    if free == 0 {
+        return Ok(0);
+    }
+
+    // incremental_vacuum frees one page per result row, so the statement
+    // has to be stepped to completion.
+    let sql = format!("PRAGMA incremental_vacuum({})", max_pages.min(MAX_VACUUM_BATCH_PAGES));
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query([])?;
+    let mut freed = 0u32;
+    while rows.next()?.is_some() {
+        freed += 1;
+    }
+    Ok(freed)
+}
+
+/// Background task that runs [`incremental_vacuum`] every
+/// [`IDLE_VACUUM_INTERVAL`]. A step is skipped when no vault is open or the
+/// connection is busy, so it never queues up behind user work.
+pub async fn run_idle_vacuum_loop(app_handle: AppHandle) {
+    let mut interval = tokio::time::interval(IDLE_VACUUM_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+        let state = app_handle.state::<AppState>();
+
+        // Busy or poisoned connection: skip this round.
+        let Ok(guard) = state.db.0.try_lock() else {
+            continue;
+        };
+        let Some(conn) = guard.as_ref() else {
+            continue;
+        };
+
+        let result = vacuum_batch_pages(conn).and_then(|pages| incremental_vacuum(conn, pages));
+        match result {
+            Ok(0) => {}
+            Ok(freed) => println!("[STORAGE] Idle incremental vacuum released {freed} pages"),
+            Err(e) => eprintln!("[STORAGE] Idle incremental vacuum failed: {e}"),
+        }
+    }
+}
+
+fn vacuum_batch_pages(conn: &Connection) -> Result<u32, DatabaseError> {
+    Ok(read_setting(conn, vault_settings_key::STORAGE_VACUUM_BATCH_PAGES)?
+        .filter(|pages| (1..=MAX_VACUUM_BATCH_PAGES).contains(pages))
+        .unwrap_or(DEFAULT_VACUUM_BATCH_PAGES))
+}
+
+fn read_pragma(conn: &Connection, pragma: &str) -> Result<i64, DatabaseError> {
+    conn.query_row(&format!("PRAGMA {pragma}"), [], |row| row.get(0))
+        .map_err(|e| DatabaseError::PragmaError {
+            pragma: pragma.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+fn read_setting(conn: &Connection, key: &str) -> Result<Option<u32>, DatabaseError> {
+    let value: Option<String> = conn
+        .query_row(
+            &format!(
+                "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"
+            ),
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value.and_then(|v| v.parse().ok()))
+}
+
+fn write_setting(conn: &Connection, key: &str, value: u32) -> Result<(), DatabaseError> {
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?, ?, ?)"
+        ),
+        params![key, "system", value.to_string()],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (tempfile::TempDir, Connection) {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = Connection::open(dir.path().join("vault.db")).unwrap();
+        conn.execute_batch(&format!(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE {TABLE_CRDT_CONFIGS} (key TEXT PRIMARY KEY, type TEXT NOT NULL, value TEXT NOT NULL);
+             CREATE TABLE blobs (data BLOB);
+             WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 500)
+             INSERT INTO blobs SELECT randomblob(4000) FROM n;"
+        ))
+        .unwrap();
+        (dir, conn)
+    }
+
+    #[test]
+    fn test_switch_to_incremental_and_vacuum_in_batches() {
+        let (_dir, conn) = setup();
+        assert_eq!(read_storage_info(&conn).unwrap().auto_vacuum, AutoVacuumMode::None);
+
+        let info = configure_storage(
+            &conn,
+            &StorageConfig {
+                auto_vacuum: Some(AutoVacuumMode::Incremental),
+                vacuum_batch_pages: Some(100),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(info.auto_vacuum, AutoVacuumMode::Incremental);
+        assert_eq!(info.vacuum_batch_pages, 100);
+
+        conn.execute("DELETE FROM blobs", []).unwrap();
+        let free_before = read_storage_info(&conn).unwrap().freelist_count;
+        assert!(free_before > 100);
+
+        assert_eq!(incremental_vacuum(&conn, 100).unwrap(), 100);
+        let info = read_storage_info(&conn).unwrap();
+        assert_eq!(info.freelist_count, free_before - 100);
+
+        while incremental_vacuum(&conn, info.vacuum_batch_pages).unwrap() > 0 {}
+        assert_eq!(read_storage_info(&conn).unwrap().freelist_count, 0);
+    }
+
+    #[test]
+    fn test_incremental_vacuum_is_noop_without_incremental_mode() {
+        let (_dir, conn) = setup();
+        conn.execute("DELETE FROM blobs", []).unwrap();
+        assert_eq!(incremental_vacuum(&conn, 100).unwrap(), 0);
+        assert!(read_storage_info(&conn).unwrap().freelist_count > 0);
+    }
+
+    #[test]
+    fn test_cache_size_is_persisted_and_reapplied() {
+        let (dir, conn) = setup();
+        let info = configure_storage(
+            &conn,
+            &StorageConfig {
+                cache_size_kib: Some(16 * 1024),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(info.cache_size_kib, 16 * 1024);
+        drop(conn);
+
+        let reopened = Connection::open(dir.path().join("vault.db")).unwrap();
+        assert_ne!(read_storage_info(&reopened).unwrap().cache_size_kib, 16 * 1024);
+        apply_connection_settings(&reopened).unwrap();
+        assert_eq!(read_storage_info(&reopened).unwrap().cache_size_kib, 16 * 1024);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let too_small = StorageConfig {
+            cache_size_kib: Some(1),
+            ..Default::default()
+        };
+        assert!(too_small.validate().is_err());
+
+        let too_many_pages = StorageConfig {
+            vacuum_batch_pages: Some(MAX_VACUUM_BATCH_PAGES + 1),
+            ..Default::default()
+        };
+        assert!(too_many_pages.validate().is_err());
+        assert!(StorageConfig::default().validate().is_ok());
+    }
+}
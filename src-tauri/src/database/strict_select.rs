@@ -0,0 +1,321 @@
+// src-tauri/src/database/strict_select.rs
+//!
+//! Delete-log-aware reads for raw `sql_select` queries
+//!
+//! Deleted CRDT rows leave their table and are recorded in
+//! `haex_deleted_rows`. Inbound sync doesn't consult that log when it
+//! writes a row, so a batch carrying an older insert or update can bring a
+//! row back after its delete already arrived. Such a row stays in the table
+//! until a delete is propagated again, and raw reads include it — counts
+//! and aggregates come out too high without any error.
+//!
+//! [`select_strict`] looks for these rows in the CRDT tables a query reads:
+//! a row counts as deleted when the log holds an entry for its primary key
+//! that is not older than the row's `haex_hlc` (the rule delete propagation
+//! in `apply_remote_changes_to_db` follows). Depending on [`TombstoneMode`]
+//! they are left out of the result or reported next to it.
+
+use crate::crdt::hlc::compare_hlc_strings;
+use crate::crdt::trigger::{
+    get_table_schema, is_safe_identifier, DELETED_ROWS_TABLE, HLC_TIMESTAMP_COLUMN,
+};
+use crate::database::core::{
+    convert_value_ref_to_json, parse_single_statement, with_connection, ValueConverter,
+};
+use crate::database::error::DatabaseError;
+use crate::database::init::discover_crdt_tables;
+use crate::database::DbConnection;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{Connection, ToSql};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlparser::ast::{
+    visit_relations, visit_relations_mut, ObjectName, ObjectNamePart, Query, Statement,
+};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::ControlFlow;
+use ts_rs::TS;
+
+/// What `sql_select_strict` does with rows the delete log marks as deleted:
+/// `filter` leaves them out as if the delete had been applied, `warn` runs
+/// the query unchanged and flags the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum TombstoneMode {
+    Filter,
+    Warn,
+}
+
+/// Deleted rows still present in one CRDT table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedRowsInTable {
+    pub table_name: String,
+    /// Number of rows in the table (not only those the query matches)
+    pub rows: u32,
+}
+
+/// Result of `sql_select_strict`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct StrictSelectResult {
+    #[ts(type = "unknown[][]")]
+    pub rows: Vec<Vec<JsonValue>>,
+    /// Read CRDT tables that contain deleted rows. With `filter` these rows
+    /// were left out, with `warn` they may be part of `rows`.
+    pub deleted_rows: Vec<DeletedRowsInTable>,
+    /// `true` in `warn` mode if `rows` may include deleted rows
+    pub may_include_deleted_rows: bool,
+}
+
+/// Primary keys of the deleted rows of one table
+struct DeletedRows {
+    pk_columns: Vec<String>,
+    keys: Vec<Vec<SqlValue>>,
+}
+
+/// Runs a SELECT like `core::select`, handling deleted CRDT rows per `mode`
+pub fn select_strict(
+    sql: String,
+    params: Vec<JsonValue>,
+    connection: &DbConnection,
+    mode: TombstoneMode,
+) -> Result<StrictSelectResult, DatabaseError> {
+    let Statement::Query(mut query) = parse_single_statement(&sql)? else {
+        return Err(DatabaseError::StatementError {
+            reason: "Only SELECT statements are allowed in select_strict".to_string(),
+        });
+    };
+
+    let params_converted: Vec<SqlValue> = params
+        .iter()
+        .map(ValueConverter::json_to_rusqlite_value)
+        .collect::<Result<Vec<_>, _>>()?;
+    let params_sql: Vec<&dyn ToSql> = params_converted.iter().map(|v| v as &dyn ToSql).collect();
+
+    with_connection(connection, |conn| {
+        let mut deleted = BTreeMap::new();
+        for table_name in read_crdt_tables(conn, &query)? {
+            let rows = find_deleted_rows(conn, &table_name)?;
+            if !rows.keys.is_empty() {
+                deleted.insert(table_name, rows);
+            }
+        }
+
+        // Without deleted rows the query runs exactly as written
+        let sql = if mode == TombstoneMode::Filter && !deleted.is_empty() {
+            exclude_deleted_rows(&mut query, &deleted)?;
+            query.to_string()
+        } else {
+            sql
+        };
+
+        let mut stmt = conn.prepare(&sql)?;
+        let num_columns = stmt.column_count();
+        let mut rows = stmt.query(&params_sql[..])?;
+        let mut result_vec: Vec<Vec<JsonValue>> = Vec::new();
+        while let Some(row) = rows.next()? {
+            let mut row_values: Vec<JsonValue> = Vec::with_capacity(num_columns);
+            for i in 0..num_columns {
+                row_values.push(convert_value_ref_to_json(row.get_ref(i)?)?);
+            }
+            result_vec.push(row_values);
+        }
+
+        Ok(StrictSelectResult {
+            rows: result_vec,
+            may_include_deleted_rows: mode == TombstoneMode::Warn && !deleted.is_empty(),
+            deleted_rows: deleted
+                .into_iter()
+                .map(|(table_name, rows)| DeletedRowsInTable {
+                    table_name,
+                    rows: u32::try_from(rows.keys.len()).unwrap_or(u32::MAX),
+                })
+                .collect(),
+        })
+    })
+}
+
+/// Table of a relation in the main schema (`items` or `main.items`)
+fn main_table_name(name: &ObjectName) -> Option<&str> {
+    match name.0.as_slice() {
+        [ObjectNamePart::Identifier(table)] => Some(&table.value),
+        [ObjectNamePart::Identifier(schema), ObjectNamePart::Identifier(table)]
+            if schema.value.eq_ignore_ascii_case("main") =>
+        {
+            Some(&table.value)
+        }
+        _ => None,
+    }
+}
+
+/// CRDT tables `query` reads, anywhere in it (joins, subqueries, CTEs).
+/// Names the query defines as its own CTEs are not tables.
+fn read_crdt_tables(conn: &Connection, query: &Query) -> Result<Vec<String>, DatabaseError> {
+    let crdt_tables: HashMap<String, String> = discover_crdt_tables(conn)?
+        .into_iter()
+        .filter(|table| table != DELETED_ROWS_TABLE && is_safe_identifier(table))
+        .map(|table| (table.to_lowercase(), table))
+        .collect();
+    let own_ctes: HashSet<String> = query
+        .with
+        .iter()
+        .flat_map(|with| &with.cte_tables)
+        .map(|cte| cte.alias.name.value.to_lowercase())
+        .collect();
+
+    let mut tables: Vec<String> = Vec::new();
+    let _ = visit_relations(query, |name| {
+        let key = main_table_name(name).map(str::to_lowercase);
+        if let Some(table) = key.as_ref().and_then(|key| crdt_tables.get(key)) {
+            if !own_ctes.contains(&table.to_lowercase()) && !tables.contains(table) {
+                tables.push(table.clone());
+            }
+        }
+        ControlFlow::<()>::Continue(())
+    });
+    Ok(tables)
+}
+
+/// Rows of `table_name` with a delete-log entry that is not older than the row
+fn find_deleted_rows(conn: &Connection, table_name: &str) -> Result<DeletedRows, DatabaseError> {
+    let pk_columns: Vec<String> = get_table_schema(conn, table_name)?
+        .into_iter()
+        .filter(|column| column.is_pk)
+        .map(|column| column.name)
+        .collect();
+    // Delete-log keys are built from the declared primary key
+    if pk_columns.is_empty() || !pk_columns.iter().all(|pk| is_safe_identifier(pk)) {
+        return Ok(DeletedRows {
+            pk_columns,
+            keys: Vec::new(),
+        });
+    }
+
+    let select = pk_columns
+        .iter()
+        .map(|pk| format!("t.\"{pk}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let join = pk_columns
+        .iter()
+        .map(|pk| format!("t.\"{pk}\" = json_extract(d.row_pks, '$.\"{pk}\"')"))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {select}, t.\"{HLC_TIMESTAMP_COLUMN}\", d.\"{HLC_TIMESTAMP_COLUMN}\"
+         FROM \"{DELETED_ROWS_TABLE}\" d
+         JOIN \"{table_name}\" t ON {join}
+         WHERE d.table_name = ?1"
+    ))?;
+
+    let pk_count = pk_columns.len();
+    let mut keys: Vec<Vec<SqlValue>> = Vec::new();
+    let mut rows = stmt.query([table_name])?;
+    while let Some(row) = rows.next()? {
+        let row_hlc: Option<String> = row.get(pk_count)?;
+        let delete_hlc: Option<String> = row.get(pk_count + 1)?;
+        let is_deleted = match (row_hlc.as_deref(), delete_hlc.as_deref()) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(row_hlc), Some(delete_hlc)) => {
+                compare_hlc_strings(row_hlc, delete_hlc) != Ordering::Greater
+            }
+        };
+        if !is_deleted {
+            continue;
+        }
+
+        let key = (0..pk_count)
+            .map(|i| row.get::<_, SqlValue>(i))
+            .collect::<Result<Vec<_>, _>>()?;
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    Ok(DeletedRows { pk_columns, keys })
+}
+
+/// Shadows every table in `deleted` with a CTE of the same name that reads
+/// the table without its deleted rows
+fn exclude_deleted_rows(
+    query: &mut Query,
+    deleted: &BTreeMap<String, DeletedRows>,
+) -> Result<(), DatabaseError> {
+    // `main.items` would read past the CTE
+    let shadowed: HashSet<String> = deleted.keys().map(|table| table.to_lowercase()).collect();
+    let _ = visit_relations_mut(query, |name| {
+        let is_shadowed =
+            main_table_name(name).is_some_and(|table| shadowed.contains(&table.to_lowercase()));
+        if is_shadowed && name.0.len() == 2 {
+            name.0.remove(0);
+        }
+        ControlFlow::<()>::Continue(())
+    });
+
+    let ctes = deleted
+        .iter()
+        .map(|(table, rows)| shadowing_cte(table, rows))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let cte_sql = format!("WITH {ctes} SELECT 1");
+    let shadowing = match parse_single_statement(&cte_sql)? {
+        Statement::Query(shadowing) => shadowing.with,
+        _ => None,
+    }
+    .ok_or_else(|| DatabaseError::ParseError {
+        reason: "Failed to build the deleted-rows filter".to_string(),
+        sql: cte_sql.clone(),
+    })?;
+
+    // Our CTEs go first so the query's own CTEs read the filtered tables too
+    match &mut query.with {
+        Some(with) => {
+            let mut cte_tables = shadowing.cte_tables;
+            cte_tables.append(&mut with.cte_tables);
+            with.cte_tables = cte_tables;
+        }
+        None => query.with = Some(shadowing),
+    }
+    Ok(())
+}
+
+fn shadowing_cte(table: &str, rows: &DeletedRows) -> String {
+    let columns = rows
+        .pk_columns
+        .iter()
+        .map(|pk| format!("\"{pk}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let keys = rows
+        .keys
+        .iter()
+        .map(|key| {
+            let values = key.iter().map(sql_literal).collect::<Vec<_>>().join(", ");
+            format!("({values})")
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "\"{table}\" AS (SELECT * FROM main.\"{table}\" WHERE ({columns}) NOT IN (VALUES {keys}))"
+    )
+}
+
+/// SQL literal of a primary-key value
+fn sql_literal(value: &SqlValue) -> String {
+    match value {
+        SqlValue::Null => "NULL".to_string(),
+        SqlValue::Integer(i) => i.to_string(),
+        SqlValue::Real(f) => format!("{f:?}"),
+        SqlValue::Text(text) => format!("'{}'", text.replace('\'', "''")),
+        SqlValue::Blob(bytes) => {
+            let hex: String = bytes.iter().map(|byte| format!("{byte:02X}")).collect();
+            format!("X'{hex}'")
+        }
+    }
+}
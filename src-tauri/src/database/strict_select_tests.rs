@@ -0,0 +1,151 @@
+//! Tests for [`super::strict_select`]: rows a sync batch brought back after
+//! their delete are left out (`filter`) or flagged (`warn`), rows written
+//! after the delete stay.
+
+#![cfg(test)]
+
+use std::time::Duration;
+
+use serde_json::{json, Value as JsonValue};
+
+use super::core::execute_with_crdt;
+use super::strict_select::{select_strict, DeletedRowsInTable, StrictSelectResult, TombstoneMode};
+use crate::test_support::clock::TestClock;
+use crate::test_support::crdt::{CrdtVault, RemoteColumnChange};
+
+fn execute(vault: &CrdtVault, sql: &str, params: Vec<JsonValue>) {
+    execute_with_crdt(
+        sql.to_string(),
+        params,
+        &vault.db,
+        &vault.hlc.lock().unwrap(),
+    )
+    .unwrap();
+}
+
+fn remote_insert(id: &str, title: &str, hlc: &str) -> Vec<RemoteColumnChange> {
+    vec![RemoteColumnChange {
+        table_name: "notes".to_string(),
+        row_pks: json!({ "id": id }).to_string(),
+        column_name: "title".to_string(),
+        hlc_timestamp: hlc.to_string(),
+        decrypted_value: json!(title),
+    }]
+}
+
+/// Vault with notes `a`, `b`, `c`; `b` is deleted locally and then written
+/// again by a remote change from before the delete, or from after it with
+/// `written_after_delete`
+fn vault_with_rewritten_note(clock: &TestClock, written_after_delete: bool) -> CrdtVault {
+    let vault = CrdtVault::open("device-a").unwrap();
+    let remote = CrdtVault::open("device-b").unwrap();
+    vault
+        .create_crdt_table(
+            "notes",
+            "CREATE TABLE notes (id TEXT PRIMARY KEY NOT NULL, title TEXT)",
+        )
+        .unwrap();
+    execute(
+        &vault,
+        "INSERT INTO notes (id, title) VALUES ('a', 'A'), ('b', 'B'), ('c', 'C')",
+        vec![],
+    );
+
+    let before_delete = remote.new_hlc_timestamp().unwrap();
+    clock.advance(Duration::from_secs(1));
+    execute(&vault, "DELETE FROM notes WHERE id = 'b'", vec![]);
+    clock.advance(Duration::from_secs(1));
+    let remote_hlc = if written_after_delete {
+        remote.new_hlc_timestamp().unwrap()
+    } else {
+        before_delete
+    };
+
+    vault
+        .apply_remote_changes(remote_insert("b", "B again", &remote_hlc))
+        .unwrap();
+    vault
+}
+
+fn select(vault: &CrdtVault, sql: &str, mode: TombstoneMode) -> StrictSelectResult {
+    select_strict(sql.to_string(), vec![], &vault.db, mode).unwrap()
+}
+
+#[test]
+fn test_filter_leaves_out_rows_older_than_their_delete() {
+    let clock = TestClock::install();
+    let vault = vault_with_rewritten_note(&clock, false);
+
+    let result = select(&vault, "SELECT COUNT(*) FROM notes", TombstoneMode::Filter);
+    assert_eq!(result.rows, vec![vec![json!(2)]]);
+    assert!(!result.may_include_deleted_rows);
+    assert_eq!(
+        result.deleted_rows,
+        vec![DeletedRowsInTable {
+            table_name: "notes".to_string(),
+            rows: 1,
+        }]
+    );
+}
+
+#[test]
+fn test_warn_returns_rows_unchanged_and_flags_them() {
+    let clock = TestClock::install();
+    let vault = vault_with_rewritten_note(&clock, false);
+
+    let result = select(&vault, "SELECT COUNT(*) FROM notes", TombstoneMode::Warn);
+    assert_eq!(result.rows, vec![vec![json!(3)]]);
+    assert!(result.may_include_deleted_rows);
+    assert_eq!(result.deleted_rows.len(), 1);
+}
+
+#[test]
+fn test_rows_written_after_their_delete_are_kept() {
+    let clock = TestClock::install();
+    let vault = vault_with_rewritten_note(&clock, true);
+
+    for mode in [TombstoneMode::Filter, TombstoneMode::Warn] {
+        let result = select(&vault, "SELECT COUNT(*) FROM notes", mode);
+        assert_eq!(result.rows, vec![vec![json!(3)]]);
+        assert!(!result.may_include_deleted_rows);
+        assert!(result.deleted_rows.is_empty());
+    }
+}
+
+#[test]
+fn test_filter_applies_to_every_reference() {
+    let clock = TestClock::install();
+    let vault = vault_with_rewritten_note(&clock, false);
+
+    let queries = [
+        "SELECT COUNT(*) FROM main.notes",
+        "SELECT COUNT(*) FROM (SELECT id FROM notes WHERE title IS NOT NULL)",
+        "WITH titled AS (SELECT * FROM notes) SELECT COUNT(*) FROM titled",
+        "SELECT COUNT(*) FROM notes n1 JOIN notes n2 ON n1.id = n2.id",
+    ];
+    for sql in queries {
+        let result = select(&vault, sql, TombstoneMode::Filter);
+        assert_eq!(result.rows, vec![vec![json!(2)]], "{sql}");
+    }
+
+    let result = select_strict(
+        "SELECT id FROM notes WHERE id <> ? ORDER BY id".to_string(),
+        vec![json!("a")],
+        &vault.db,
+        TombstoneMode::Filter,
+    )
+    .unwrap();
+    assert_eq!(result.rows, vec![vec![json!("c")]]);
+}
+
+#[test]
+fn test_only_select_is_allowed() {
+    let vault = CrdtVault::open("device-a").unwrap();
+    assert!(select_strict(
+        "DELETE FROM haex_deleted_rows".to_string(),
+        vec![],
+        &vault.db,
+        TombstoneMode::Filter,
+    )
+    .is_err());
+}
@@ -0,0 +1,324 @@
+// src-tauri/src/database/unlock_throttle.rs
+//!
+//! Throttling of failed vault unlocks.
+//!
+//! Failed attempts are counted in a sidecar file next to the vault
+//! (`<vault>.db.lockout.json`) — the encrypted DB can't be read before the
+//! password is known. After [`FREE_ATTEMPTS`] failures every further attempt
+//! has to wait, starting at [`BASE_DELAY_SECS`] and doubling per failure up
+//! to [`MAX_DELAY_SECS`]. A successful unlock removes the sidecar.
+//!
+//! After `wipe_quick_unlock_after` failures the status reports
+//! `quick_unlock_revoked`; the frontend then removes the password it keeps in
+//! the biometric keyring, so only the typed password unlocks the vault again.
+//!
+//! The sidecar only slows down guessing through the app. Someone with access
+//! to the file can delete it, but can then also attack the vault file
+//! directly — SQLCipher's key derivation is the actual protection there.
+
+use crate::database::error::DatabaseError;
+use crate::database::sidecar;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use ts_rs::TS;
+
+/// Suffix appended to the vault DB path for the lockout sidecar.
+const SIDECAR_SUFFIX: &str = ".lockout.json";
+
+/// Failed attempts allowed without any delay.
+pub const FREE_ATTEMPTS: u32 = 3;
+
+/// Delay after the first throttled failure; doubles with every further one.
+pub const BASE_DELAY_SECS: u32 = 5;
+
+/// Upper bound for the delay (15 minutes).
+pub const MAX_DELAY_SECS: u32 = 15 * 60;
+
+/// Failures after which the quick-unlock entry is revoked, unless
+/// configured otherwise.
+pub const DEFAULT_WIPE_QUICK_UNLOCK_AFTER: u32 = 5;
+
+/// Error message SQLCipher reports when the key doesn't match.
+const WRONG_KEY_MESSAGE: &str = "file is not a database";
+
+/// Contents of the sidecar file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LockoutRecord {
+    #[serde(default)]
+    failed_attempts: u32,
+    /// Unix time (seconds) of the last failed attempt
+    #[serde(default)]
+    last_failure_at: u64,
+    #[serde(default)]
+    quick_unlock_revoked: bool,
+    /// `None` disables revoking the quick-unlock entry
+    #[serde(default = "default_wipe_after")]
+    wipe_quick_unlock_after: Option<u32>,
+}
+
+fn default_wipe_after() -> Option<u32> {
+    Some(DEFAULT_WIPE_QUICK_UNLOCK_AFTER)
+}
+
+impl LockoutRecord {
+    fn new() -> Self {
+        Self {
+            wipe_quick_unlock_after: default_wipe_after(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Unlock throttling state of a vault
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LockoutStatus {
+    /// Failed unlock attempts since the last successful one
+    pub failed_attempts: u32,
+    /// Seconds until the next attempt is accepted (0 = now)
+    pub retry_after_secs: u32,
+    /// The quick-unlock (biometric) entry must be removed; only the typed
+    /// password is accepted until the next successful unlock
+    pub quick_unlock_revoked: bool,
+    /// Failures after which quick unlock is revoked (`null` = never)
+    pub wipe_quick_unlock_after: Option<u32>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Location of the lockout record sidecar of a vault
+pub fn sidecar_path(vault_path: &Path) -> PathBuf {
+    sidecar::sidecar_path(vault_path, SIDECAR_SUFFIX)
+}
+
+/// Delay imposed after `failed_attempts` consecutive failures.
+pub fn delay_secs(failed_attempts: u32) -> u32 {
+    if failed_attempts < FREE_ATTEMPTS {
+        return 0;
+    }
+    // Beyond 16 doublings the cap applies anyway; keeps the shift in range.
+    let doublings = (failed_attempts - FREE_ATTEMPTS).min(16);
+    (BASE_DELAY_SECS << doublings).min(MAX_DELAY_SECS)
+}
+
+/// `true` if an unlock failed because of a wrong password (as opposed to a
+/// missing file, a held lock, a failed migration, ...).
+pub fn is_wrong_password(error: &DatabaseError) -> bool {
+    error.to_string().contains(WRONG_KEY_MESSAGE)
+}
+
+/// A corrupt or unreadable sidecar counts as no failures; it must never keep
+/// the user out of the vault.
+fn read_record(vault_path: &Path) -> LockoutRecord {
+    fs::read_to_string(sidecar_path(vault_path))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_else(LockoutRecord::new)
+}
+
+fn write_record(vault_path: &Path, record: &LockoutRecord) -> Result<(), DatabaseError> {
+    let path = sidecar_path(vault_path);
+    let json = serde_json::to_string(record).map_err(|e| DatabaseError::SerializationError {
+        reason: e.to_string(),
+    })?;
+    fs::write(&path, json).map_err(|e| DatabaseError::IoError {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })
+}
+
+fn status_at(record: &LockoutRecord, now: u64) -> LockoutStatus {
+    let unlocks_at = record
+        .last_failure_at
+        .saturating_add(u64::from(delay_secs(record.failed_attempts)));
+    LockoutStatus {
+        failed_attempts: record.failed_attempts,
+        retry_after_secs: u32::try_from(unlocks_at.saturating_sub(now)).unwrap_or(MAX_DELAY_SECS),
+        quick_unlock_revoked: record.quick_unlock_revoked,
+        wipe_quick_unlock_after: record.wipe_quick_unlock_after,
+    }
+}
+
+pub fn lockout_status(vault_path: &Path) -> LockoutStatus {
+    status_at(&read_record(vault_path), now_secs())
+}
+
+/// Rejects the attempt while the vault is still throttled.
+pub fn check_unlock_allowed(vault_path: &Path) -> Result<(), DatabaseError> {
+    let status = lockout_status(vault_path);
+    if status.retry_after_secs == 0 {
+        return Ok(());
+    }
+    Err(DatabaseError::VaultLockedOut {
+        failed_attempts: status.failed_attempts,
+        retry_after_secs: status.retry_after_secs,
+    })
+}
+
+fn apply_failure(record: &mut LockoutRecord, now: u64) {
+    record.failed_attempts = record.failed_attempts.saturating_add(1);
+    record.last_failure_at = now;
+    if let Some(limit) = record.wipe_quick_unlock_after {
+        if record.failed_attempts >= limit {
+            record.quick_unlock_revoked = true;
+        }
+    }
+}
+
+pub fn record_failure(vault_path: &Path) -> Result<LockoutStatus, DatabaseError> {
+    let mut record = read_record(vault_path);
+    let now = now_secs();
+    apply_failure(&mut record, now);
+    write_record(vault_path, &record)?;
+    Ok(status_at(&record, now))
+}
+
+/// Resets the counter after a successful unlock. A configured
+/// `wipe_quick_unlock_after` is kept.
+pub fn record_success(vault_path: &Path) -> Result<(), DatabaseError> {
+    let record = read_record(vault_path);
+    if record.wipe_quick_unlock_after == default_wipe_after() {
+        return remove_sidecar(vault_path);
+    }
+    write_record(
+        vault_path,
+        &LockoutRecord {
+            wipe_quick_unlock_after: record.wipe_quick_unlock_after,
+            ..Default::default()
+        },
+    )
+}
+
+/// Removes the sidecar, e.g. when the vault itself is deleted.
+pub fn remove_sidecar(vault_path: &Path) -> Result<(), DatabaseError> {
+    sidecar::remove_sidecar(vault_path, SIDECAR_SUFFIX)
+}
+
+#[tauri::command]
+pub fn vault_get_lockout_status(vault_path: String) -> LockoutStatus {
+    lockout_status(Path::new(&vault_path))
+}
+
+/// Sets after how many failures quick unlock is revoked (`None` = never).
+#[tauri::command]
+pub fn vault_set_quick_unlock_wipe_threshold(
+    vault_path: String,
+    wipe_quick_unlock_after: Option<u32>,
+) -> Result<LockoutStatus, DatabaseError> {
+    if wipe_quick_unlock_after == Some(0) {
+        return Err(DatabaseError::ValidationError {
+            reason: "The quick-unlock wipe threshold must be at least 1".to_string(),
+        });
+    }
+    let vault_path = Path::new(&vault_path);
+    let mut record = read_record(vault_path);
+    record.wipe_quick_unlock_after = wipe_quick_unlock_after;
+    write_record(vault_path, &record)?;
+    Ok(status_at(&record, now_secs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_grows_exponentially_and_is_capped() {
+        assert_eq!(delay_secs(0), 0);
+        assert_eq!(delay_secs(FREE_ATTEMPTS - 1), 0);
+        assert_eq!(delay_secs(FREE_ATTEMPTS), BASE_DELAY_SECS);
+        assert_eq!(delay_secs(FREE_ATTEMPTS + 1), BASE_DELAY_SECS * 2);
+        assert_eq!(delay_secs(FREE_ATTEMPTS + 2), BASE_DELAY_SECS * 4);
+        assert_eq!(delay_secs(FREE_ATTEMPTS + 20), MAX_DELAY_SECS);
+        assert_eq!(delay_secs(u32::MAX), MAX_DELAY_SECS);
+    }
+
+    #[test]
+    fn test_status_counts_down() {
+        let mut record = LockoutRecord::new();
+        for _ in 0..FREE_ATTEMPTS {
+            apply_failure(&mut record, 1_000);
+        }
+        assert_eq!(status_at(&record, 1_000).retry_after_secs, BASE_DELAY_SECS);
+        assert_eq!(
+            status_at(&record, 1_002).retry_after_secs,
+            BASE_DELAY_SECS - 2
+        );
+        assert_eq!(status_at(&record, 1_010).retry_after_secs, 0);
+    }
+
+    #[test]
+    fn test_quick_unlock_revoked_after_threshold() {
+        let mut record = LockoutRecord::new();
+        for _ in 1..DEFAULT_WIPE_QUICK_UNLOCK_AFTER {
+            apply_failure(&mut record, 0);
+        }
+        assert!(!record.quick_unlock_revoked);
+        apply_failure(&mut record, 0);
+        assert!(record.quick_unlock_revoked);
+
+        let mut record = LockoutRecord {
+            wipe_quick_unlock_after: None,
+            ..LockoutRecord::new()
+        };
+        for _ in 0..100 {
+            apply_failure(&mut record, 0);
+        }
+        assert!(!record.quick_unlock_revoked);
+    }
+
+    #[test]
+    fn test_sidecar_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("my.db");
+        fs::write(&vault, b"").unwrap();
+        assert_eq!(
+            sidecar_path(&vault).file_name().unwrap(),
+            "my.db.lockout.json"
+        );
+
+        for _ in 0..FREE_ATTEMPTS {
+            record_failure(&vault).unwrap();
+        }
+        assert!(matches!(
+            check_unlock_allowed(&vault),
+            Err(DatabaseError::VaultLockedOut { failed_attempts, .. }) if failed_attempts == FREE_ATTEMPTS
+        ));
+
+        record_success(&vault).unwrap();
+        assert!(!sidecar_path(&vault).exists());
+        assert!(check_unlock_allowed(&vault).is_ok());
+
+        // A custom threshold survives a successful unlock
+        vault_set_quick_unlock_wipe_threshold(vault.display().to_string(), None).unwrap();
+        record_failure(&vault).unwrap();
+        record_success(&vault).unwrap();
+        let status = lockout_status(&vault);
+        assert_eq!(status.failed_attempts, 0);
+        assert_eq!(status.wipe_quick_unlock_after, None);
+
+        // Garbage in the sidecar doesn't lock the user out
+        fs::write(sidecar_path(&vault), "not json").unwrap();
+        assert!(check_unlock_allowed(&vault).is_ok());
+    }
+
+    #[test]
+    fn test_wrong_password_detection() {
+        let wrong_key = DatabaseError::PragmaError {
+            pragma: "journal_mode=WAL".to_string(),
+            reason: "file is not a database".to_string(),
+        };
+        assert!(is_wrong_password(&wrong_key));
+        assert!(!is_wrong_password(&DatabaseError::LockError {
+            reason: "poisoned".to_string(),
+        }));
+    }
+}
@@ -0,0 +1,316 @@
+// src-tauri/src/database/validate.rs
+//!
+//! Pre-open vault validation.
+//!
+//! [`vault_validate`] checks a vault and its password on a throwaway
+//! read-only connection — no session, no migrations, nothing in `AppState`
+//! changes — so the UI can tell the user precisely what is wrong before
+//! `open_encrypted_database` starts mounting it.
+//!
+//! SQLCipher can't tell a wrong password from a damaged first page: both
+//! fail the HMAC of page 1. Those cases are reported as a wrong password;
+//! everything after page 1 is covered by `PRAGMA quick_check`. Wrong
+//! passwords count towards [`super::unlock_throttle`] like failed unlocks,
+//! otherwise this would be a free guessing oracle.
+
+use crate::database::cipher;
+use crate::database::error::DatabaseError;
+use crate::database::metadata::{self, VAULT_FORMAT_VERSION};
+use crate::database::migrations;
+use crate::database::unlock_throttle;
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use ts_rs::TS;
+
+use VaultValidationStatus as Status;
+
+/// First 16 bytes of every unencrypted SQLite file.
+const PLAINTEXT_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Outcome of a vault validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum VaultValidationStatus {
+    /// Password is correct and the file passed the integrity check
+    Ok,
+    Missing,
+    WrongPassword,
+    Corrupt,
+    /// The vault was written by a newer app version or in a format this
+    /// build doesn't open (unencrypted, SQLCipher 3)
+    UnsupportedVersion,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultValidation {
+    pub status: VaultValidationStatus,
+    /// What exactly failed, for everything but `ok` and `wrongPassword`
+    pub detail: Option<String>,
+}
+
+impl VaultValidation {
+    fn new(status: VaultValidationStatus, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            detail: Some(detail.into()),
+        }
+    }
+
+    fn status(status: VaultValidationStatus) -> Self {
+        Self {
+            status,
+            detail: None,
+        }
+    }
+}
+
+fn has_plaintext_header(vault_path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    File::open(vault_path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok_and(|_| &header == PLAINTEXT_HEADER)
+}
+
+/// Keys a read-only connection; `compatibility` selects an older SQLCipher
+/// format instead of the stored settings.
+fn open_keyed(
+    vault_path: &Path,
+    key: &str,
+    settings: Option<&cipher::CipherSettings>,
+    compatibility: Option<u32>,
+) -> rusqlite::Result<Connection> {
+    let conn = Connection::open_with_flags(vault_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    conn.pragma_update(None, "key", key)?;
+    match (compatibility, settings) {
+        (Some(version), _) => conn.pragma_update(None, "cipher_compatibility", version)?,
+        (None, Some(settings)) => settings.apply(&conn, None)?,
+        (None, None) => {}
+    }
+    Ok(conn)
+}
+
+fn probe(conn: &Connection) -> rusqlite::Result<i64> {
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| row.get(0))
+}
+
+/// Validates the vault at `vault_path` with `key`. `known_migrations` are
+/// the core migrations this build ships; applied migrations outside that
+/// list mean a newer app version wrote the vault. `None` skips that check.
+pub fn validate_vault(
+    vault_path: &Path,
+    key: &str,
+    known_migrations: Option<&[String]>,
+) -> VaultValidation {
+    if !vault_path.is_file() {
+        return VaultValidation::status(Status::Missing);
+    }
+
+    if let Some(version) = metadata::peek_metadata(vault_path)
+        .ok()
+        .and_then(|m| m.format_version)
+    {
+        if version > VAULT_FORMAT_VERSION {
+            return VaultValidation::new(
+                Status::UnsupportedVersion,
+                format!(
+                    "Vault format {version} is newer than the supported {VAULT_FORMAT_VERSION}"
+                ),
+            );
+        }
+    }
+
+    if has_plaintext_header(vault_path) {
+        return VaultValidation::new(
+            Status::UnsupportedVersion,
+            "The vault file is not encrypted",
+        );
+    }
+
+    let settings = match cipher::read_settings(vault_path) {
+        Ok(settings) => settings,
+        Err(e) => return VaultValidation::new(Status::Corrupt, e.to_string()),
+    };
+
+    let conn = match open_keyed(vault_path, key, settings.as_ref(), None) {
+        Ok(conn) => conn,
+        Err(e) => return VaultValidation::new(Status::Corrupt, e.to_string()),
+    };
+    match conn.query_row("PRAGMA cipher_version", [], |row| row.get::<_, String>(0)) {
+        Ok(version) if !version.is_empty() => {}
+        _ => return VaultValidation::new(Status::Corrupt, "SQLCipher is not active"),
+    }
+
+    if let Err(e) = probe(&conn) {
+        let error = DatabaseError::PragmaError {
+            pragma: "key".to_string(),
+            reason: e.to_string(),
+        };
+        if !unlock_throttle::is_wrong_password(&error) {
+            return VaultValidation::new(Status::Corrupt, e.to_string());
+        }
+        // Without stored settings it may still be a SQLCipher 3 vault
+        let legacy = settings.is_none()
+            && open_keyed(vault_path, key, None, Some(3))
+                .and_then(|conn| probe(&conn))
+                .is_ok();
+        if legacy {
+            return VaultValidation::new(
+                Status::UnsupportedVersion,
+                "The vault uses the SQLCipher 3 format and has to be migrated",
+            );
+        }
+        return VaultValidation::status(Status::WrongPassword);
+    }
+
+    let quick_check: rusqlite::Result<String> =
+        conn.query_row("PRAGMA quick_check(1)", [], |row| row.get(0));
+    match quick_check {
+        Ok(result) if result == "ok" => {}
+        Ok(result) => return VaultValidation::new(Status::Corrupt, result),
+        Err(e) => return VaultValidation::new(Status::Corrupt, e.to_string()),
+    }
+
+    if let Some(known) = known_migrations {
+        let applied = match migrations::get_applied_migration_names(&conn) {
+            Ok(applied) => applied,
+            Err(e) => return VaultValidation::new(Status::Corrupt, e.to_string()),
+        };
+        let unknown: Vec<_> = applied
+            .iter()
+            .filter(|name| !known.contains(name))
+            .cloned()
+            .collect();
+        if !unknown.is_empty() {
+            return VaultValidation::new(
+                Status::UnsupportedVersion,
+                format!(
+                    "The vault has migrations this version doesn't know: {}",
+                    unknown.join(", ")
+                ),
+            );
+        }
+    }
+
+    VaultValidation::status(Status::Ok)
+}
+
+/// Checks `key` and the integrity of the vault at `vault_path` without
+/// opening a session.
+#[tauri::command]
+pub fn vault_validate(
+    app_handle: tauri::AppHandle,
+    vault_path: String,
+    key: String,
+) -> Result<VaultValidation, DatabaseError> {
+    let path = Path::new(&vault_path);
+    unlock_throttle::check_unlock_allowed(path)?;
+
+    let known_migrations = match migrations::load_migration_journal(&app_handle) {
+        Ok(names) => Some(names),
+        Err(e) => {
+            eprintln!("[VALIDATE] Migration journal unavailable, skipping version check: {e}");
+            None
+        }
+    };
+    let validation = validate_vault(path, &key, known_migrations.as_deref());
+
+    if validation.status == VaultValidationStatus::WrongPassword {
+        unlock_throttle::record_failure(path)?;
+    }
+    Ok(validation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table_names::TABLE_CRDT_MIGRATIONS;
+    use std::fs;
+    use std::path::PathBuf;
+
+    const KEY: &str = "correct horse battery staple";
+
+    fn create_vault(dir: &Path) -> PathBuf {
+        let path = dir.join("vault.db");
+        let conn = Connection::open(&path).unwrap();
+        conn.pragma_update(None, "key", KEY).unwrap();
+        conn.execute_batch(&format!(
+            "CREATE TABLE {TABLE_CRDT_MIGRATIONS} (id TEXT, migration_name TEXT, applied_at TEXT);
+             INSERT INTO {TABLE_CRDT_MIGRATIONS} VALUES ('1', '0000_init', '2026-01-01');
+             CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT);
+             WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 2000)
+             INSERT INTO notes (body) SELECT printf('%0500d', i) FROM n;"
+        ))
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_valid_vault_and_wrong_password() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = create_vault(dir.path());
+
+        let known = vec!["0000_init".to_string()];
+        assert_eq!(
+            validate_vault(&vault, KEY, Some(&known)).status,
+            VaultValidationStatus::Ok
+        );
+        assert_eq!(
+            validate_vault(&vault, "guess", None).status,
+            VaultValidationStatus::WrongPassword
+        );
+        assert_eq!(
+            validate_vault(&dir.path().join("missing.db"), KEY, None).status,
+            VaultValidationStatus::Missing
+        );
+    }
+
+    #[test]
+    fn test_unknown_migrations_are_unsupported() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = create_vault(dir.path());
+
+        let validation = validate_vault(&vault, KEY, Some(&[]));
+        assert_eq!(validation.status, VaultValidationStatus::UnsupportedVersion);
+        assert!(validation.detail.unwrap().contains("0000_init"));
+    }
+
+    #[test]
+    fn test_plaintext_vault_is_unsupported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plain.db");
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch("CREATE TABLE t (x)")
+            .unwrap();
+
+        assert_eq!(
+            validate_vault(&path, KEY, None).status,
+            VaultValidationStatus::UnsupportedVersion
+        );
+    }
+
+    #[test]
+    fn test_damaged_page_is_corrupt() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = create_vault(dir.path());
+
+        // Page 1 stays intact so the key still verifies
+        let mut bytes = fs::read(&vault).unwrap();
+        let offset = bytes.len() / 2;
+        for byte in &mut bytes[offset..offset + 64] {
+            *byte = !*byte;
+        }
+        fs::write(&vault, bytes).unwrap();
+
+        assert_eq!(
+            validate_vault(&vault, KEY, None).status,
+            VaultValidationStatus::Corrupt
+        );
+    }
+}
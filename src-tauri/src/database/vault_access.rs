@@ -0,0 +1,166 @@
+// src-tauri/src/database/vault_access.rs
+//!
+//! When and where each vault was last opened.
+//!
+//! `list_vaults` used to report the file's access time, which stays frozen
+//! on `noatime` mounts and isn't maintained on Android at all. Successful
+//! opens are recorded here instead, in an app-level store
+//! (`<app_local_data>/vault_access.json`) keyed by the canonical vault path.
+//! The store is device-local and only informational: a missing or corrupt
+//! file means "never opened", and failing to write it never fails an open.
+
+use crate::database::error::DatabaseError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+const VAULT_ACCESS_FILE: &str = "vault_access.json";
+
+/// Last open of a single vault
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultAccess {
+    /// Unix time (seconds)
+    pub last_opened_at: u64,
+    /// Host name of the device that opened it
+    #[serde(default)]
+    pub last_opened_device: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultAccessStore {
+    #[serde(default)]
+    vaults: HashMap<String, VaultAccess>,
+}
+
+/// Store key for a vault; different spellings of the same path map to the
+/// same entry.
+fn vault_key(vault_path: &Path) -> String {
+    fs::canonicalize(vault_path)
+        .unwrap_or_else(|_| vault_path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+fn store_path(app_handle: &AppHandle) -> Result<PathBuf, DatabaseError> {
+    let dir = crate::app_profile::data_dir(app_handle).map_err(|e| {
+        DatabaseError::PathResolutionError {
+            reason: e.to_string(),
+        }
+    })?;
+    Ok(dir.join(VAULT_ACCESS_FILE))
+}
+
+impl VaultAccessStore {
+    /// Loads the store from `path`; a missing or corrupt file yields an
+    /// empty store.
+    pub fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), DatabaseError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| DatabaseError::IoError {
+                path: parent.display().to_string(),
+                reason: e.to_string(),
+            })?;
+        }
+        let json = serde_json::to_string(self).map_err(|e| DatabaseError::SerializationError {
+            reason: e.to_string(),
+        })?;
+        fs::write(path, json).map_err(|e| DatabaseError::IoError {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    pub fn get(&self, vault_path: &Path) -> Option<&VaultAccess> {
+        self.vaults.get(&vault_key(vault_path))
+    }
+
+    pub fn record(&mut self, vault_path: &Path, access: VaultAccess) {
+        self.vaults.insert(vault_key(vault_path), access);
+    }
+
+    pub fn forget(&mut self, vault_path: &Path) -> bool {
+        self.vaults.remove(&vault_key(vault_path)).is_some()
+    }
+}
+
+pub fn load(app_handle: &AppHandle) -> Result<VaultAccessStore, DatabaseError> {
+    Ok(VaultAccessStore::load_from(&store_path(app_handle)?))
+}
+
+/// Records a successful open of `vault_path` by this device.
+pub fn record_open(app_handle: &AppHandle, vault_path: &Path) -> Result<(), DatabaseError> {
+    let path = store_path(app_handle)?;
+    let mut store = VaultAccessStore::load_from(&path);
+    store.record(
+        vault_path,
+        VaultAccess {
+            last_opened_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            last_opened_device: Some(tauri_plugin_os::hostname()).filter(|name| !name.is_empty()),
+        },
+    );
+    store.save_to(&path)
+}
+
+/// Drops the entry of a deleted vault.
+pub fn forget(app_handle: &AppHandle, vault_path: &Path) -> Result<(), DatabaseError> {
+    let path = store_path(app_handle)?;
+    let mut store = VaultAccessStore::load_from(&path);
+    if store.forget(vault_path) {
+        store.save_to(&path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store_file = dir.path().join("state").join(VAULT_ACCESS_FILE);
+        let vault = dir.path().join("my.db");
+        fs::write(&vault, b"").unwrap();
+
+        let mut store = VaultAccessStore::load_from(&store_file);
+        assert!(store.get(&vault).is_none());
+
+        let access = VaultAccess {
+            last_opened_at: 1_700_000_000,
+            last_opened_device: Some("laptop".to_string()),
+        };
+        store.record(&vault, access.clone());
+        store.save_to(&store_file).unwrap();
+
+        // Another spelling of the same path finds the entry
+        let alias = dir.path().join(".").join("my.db");
+        let mut store = VaultAccessStore::load_from(&store_file);
+        assert_eq!(store.get(&alias), Some(&access));
+
+        assert!(store.forget(&vault));
+        assert!(!store.forget(&vault));
+        assert!(store.get(&vault).is_none());
+    }
+
+    #[test]
+    fn test_corrupt_store_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let store_file = dir.path().join(VAULT_ACCESS_FILE);
+        fs::write(&store_file, "not json").unwrap();
+        assert!(VaultAccessStore::load_from(&store_file).vaults.is_empty());
+    }
+}
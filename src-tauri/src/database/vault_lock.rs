@@ -11,17 +11,68 @@
 //! closing the file (on Drop) releases it automatically. On abrupt process
 //! termination the OS drops the handle for us so stale locks cannot strand
 //! a vault permanently unreachable.
+//!
+//! The holder writes its PID and host name into the lock file, so a
+//! contending instance can tell the user who has the vault open. Network
+//! filesystems can keep a lock alive after its holder is gone; for that
+//! case [`VaultLock::force_takeover`] breaks the lock, unless the holder is
+//! a process still running on this host.
 
 use std::fs::{File, OpenOptions};
-use std::io;
+use std::io::{self, Read, Seek, Write};
 use std::path::{Path, PathBuf};
 
 use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
 
 /// Suffix appended to the vault DB path for the lock file.
 /// Kept separate from the DB so SQLite's own WAL/SHM files are not confused.
 const LOCK_SUFFIX: &str = ".lock";
 
+/// Who holds a vault lock, as recorded in the lock file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockHolder {
+    pub pid: u32,
+    pub hostname: String,
+}
+
+impl LockHolder {
+    fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            hostname: tauri_plugin_os::hostname(),
+        }
+    }
+
+    /// `true` if the holder is a running process on this host. Holders on
+    /// other hosts can't be checked and count as not running.
+    fn is_running_here(&self) -> bool {
+        if self.hostname != tauri_plugin_os::hostname() {
+            return false;
+        }
+        let pid = Pid::from_u32(self.pid);
+        let mut system = System::new();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+        system.process(pid).is_some()
+    }
+}
+
+/// Reads the holder recorded in the lock file. `None` if it is empty,
+/// unreadable (Windows denies reads of locked ranges) or not ours.
+fn read_holder(lock_path: &Path) -> Option<LockHolder> {
+    let mut content = String::new();
+    File::open(lock_path)
+        .and_then(|mut file| file.read_to_string(&mut content))
+        .ok()?;
+    serde_json::from_str(&content).ok()
+}
+
 /// Holds an acquired exclusive advisory lock on a vault's `.lock` file for
 /// the lifetime of this handle. Dropping it releases the lock.
 #[derive(Debug)]
@@ -84,7 +135,7 @@ impl VaultLock {
             })?;
         }
 
-        let handle = OpenOptions::new()
+        let mut handle = OpenOptions::new()
             .create(true)
             .read(true)
             .write(true)
@@ -99,12 +150,52 @@ impl VaultLock {
             .try_lock_exclusive()
             .map_err(|source| classify_try_lock_error(&lock_path, source))?;
 
+        // Best-effort: a lock without holder info still locks.
+        if let Ok(json) = serde_json::to_string(&LockHolder::current()) {
+            let _ = handle
+                .set_len(0)
+                .and_then(|_| handle.rewind())
+                .and_then(|_| handle.write_all(json.as_bytes()));
+        }
+
         Ok(Self {
             handle,
             lock_path,
             vault_path: normalized,
         })
     }
+
+    /// Breaks the lock on `vault_path` so the next [`Self::try_acquire`]
+    /// succeeds, and returns the holder it displaced (`None` if the lock
+    /// was free or the holder unknown).
+    ///
+    /// Refuses while the holder is a process still running on this host.
+    /// Anywhere else the caller must have made sure the holder is gone: a
+    /// holder that is in fact alive keeps its lock on the old, now unlinked
+    /// file and is no longer excluded.
+    pub fn force_takeover(vault_path: &Path) -> Result<Option<LockHolder>, VaultLockError> {
+        let holder = match Self::try_acquire(vault_path) {
+            Ok(_free) => return Ok(None),
+            Err(VaultLockError::AlreadyHeld { holder, .. }) => holder,
+            Err(e) => return Err(e),
+        };
+
+        let lock_path = lock_path_for(&normalize_vault_path(vault_path)?);
+        if let Some(holder) = holder.as_ref().filter(|h| h.is_running_here()) {
+            return Err(VaultLockError::HolderRunning {
+                path: lock_path.display().to_string(),
+                holder: holder.clone(),
+            });
+        }
+
+        // Unlinking detaches the stale lock from the path; the next acquire
+        // creates a fresh file.
+        std::fs::remove_file(&lock_path).map_err(|source| VaultLockError::Io {
+            path: lock_path.display().to_string(),
+            source,
+        })?;
+        Ok(holder)
+    }
 }
 
 /// Resolve `vault_path` to an absolute, symlink-resolved form so two callers
@@ -169,6 +260,7 @@ fn classify_try_lock_error(lock_path: &Path, source: io::Error) -> VaultLockErro
         VaultLockError::AlreadyHeld {
             path: lock_path.display().to_string(),
             source,
+            holder: read_holder(lock_path),
         }
     } else {
         VaultLockError::Io {
@@ -194,7 +286,14 @@ impl Drop for VaultLock {
 #[derive(Debug, thiserror::Error)]
 pub enum VaultLockError {
     #[error("Vault is already open in another instance (lock at '{path}')")]
-    AlreadyHeld { path: String, source: io::Error },
+    AlreadyHeld {
+        path: String,
+        source: io::Error,
+        holder: Option<LockHolder>,
+    },
+
+    #[error("Vault lock at '{path}' is held by process {} on this host, which is still running", holder.pid)]
+    HolderRunning { path: String, holder: LockHolder },
 
     #[error("Failed to prepare lock file at '{path}': {source}")]
     Io { path: String, source: io::Error },
@@ -234,6 +333,62 @@ mod tests {
         let _third = VaultLock::try_acquire(&vault).expect("post-release acquire");
     }
 
+    #[test]
+    fn contender_sees_the_holder() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let vault = dir.path().join("my.db");
+
+        let _first = VaultLock::try_acquire(&vault).expect("first acquire");
+        match VaultLock::try_acquire(&vault) {
+            // Windows doesn't let the contender read the locked file
+            Err(VaultLockError::AlreadyHeld { holder, .. }) => {
+                if cfg!(unix) {
+                    assert_eq!(holder, Some(LockHolder::current()));
+                }
+            }
+            other => panic!("expected AlreadyHeld, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn takeover_of_free_lock_is_a_noop() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let vault = dir.path().join("my.db");
+        assert_eq!(VaultLock::force_takeover(&vault).expect("takeover"), None);
+        let _lock = VaultLock::try_acquire(&vault).expect("acquire after takeover");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn takeover_refuses_running_holder_and_breaks_stale_one() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let vault = dir.path().join("my.db");
+
+        // This process is alive, so its lock can't be taken over
+        let first = VaultLock::try_acquire(&vault).expect("first acquire");
+        assert!(matches!(
+            VaultLock::force_takeover(&vault),
+            Err(VaultLockError::HolderRunning { .. })
+        ));
+
+        // Pretend the holder was a process that no longer exists
+        let stale = LockHolder {
+            pid: u32::MAX - 1,
+            hostname: tauri_plugin_os::hostname(),
+        };
+        std::fs::write(
+            lock_path_for(first.vault_path()),
+            serde_json::to_string(&stale).expect("json"),
+        )
+        .expect("rewrite holder");
+
+        assert_eq!(
+            VaultLock::force_takeover(&vault).expect("takeover"),
+            Some(stale)
+        );
+        let _second = VaultLock::try_acquire(&vault).expect("acquire after takeover");
+    }
+
     #[test]
     fn different_vaults_can_lock_independently() {
         let dir = tempfile::tempdir().expect("tempdir");
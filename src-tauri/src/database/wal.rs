@@ -0,0 +1,246 @@
+// src-tauri/src/database/wal.rs
+//!
+//! WAL recovery and checkpoints.
+//!
+//! A process that crashes with the vault open leaves its `-wal` (and `-shm`)
+//! file behind. The next open replays the WAL on its first read; this module
+//! makes that step explicit in [`open_and_init_db`](super::core::open_and_init_db):
+//!
+//! - a `-shm` without `-wal` carries no data and is removed before opening,
+//! - the first read is retried with backoff while the file is busy, and
+//!   reported as [`DatabaseError::VaultBusy`] if it stays that way,
+//! - a leftover `-wal` is checkpointed into the main file right after the
+//!   open, so the crash remnants don't linger until the next
+//!   auto-checkpoint.
+//!
+//! The open runs under the vault's advisory lock, so no other connection
+//! can be using the files being cleaned up.
+//!
+//! `database_checkpoint` forces the same checkpoint on the open vault.
+
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::AppState;
+use rusqlite::{Connection, ErrorCode};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use tauri::State;
+use ts_rs::TS;
+
+/// Waits between attempts while the vault file is busy (~1.5 s in total).
+const BUSY_RETRY_DELAYS: [Duration; 5] = [
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(200),
+    Duration::from_millis(400),
+    Duration::from_millis(800),
+];
+
+/// Result of `PRAGMA wal_checkpoint(TRUNCATE)`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct WalCheckpoint {
+    /// The checkpoint could not finish because a reader or writer was active
+    pub busy: bool,
+    /// Frames in the WAL before the checkpoint (-1 if not in WAL mode)
+    pub log_frames: i64,
+    /// Frames copied into the database file
+    pub checkpointed_frames: i64,
+}
+
+fn aux_path(vault_path: &Path, suffix: &str) -> PathBuf {
+    let mut path = vault_path.as_os_str().to_os_string();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// `true` for the errors SQLite reports while another connection holds
+/// the file.
+pub fn is_busy(error: &rusqlite::Error) -> bool {
+    matches!(
+        error.sqlite_error_code(),
+        Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+/// Runs `op`, retrying with backoff as long as it fails with a busy error.
+pub fn retry_busy<T>(mut op: impl FnMut() -> rusqlite::Result<T>) -> rusqlite::Result<T> {
+    for delay in BUSY_RETRY_DELAYS {
+        match op() {
+            Err(e) if is_busy(&e) => thread::sleep(delay),
+            result => return result,
+        }
+    }
+    op()
+}
+
+/// State of the WAL files before a vault is opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalLeftovers {
+    /// A non-empty `-wal` exists and has to be replayed
+    pub wal: bool,
+    /// A `-shm` without `-wal` was removed
+    pub removed_orphaned_shm: bool,
+}
+
+/// Inspects the WAL files of a closed vault and removes an orphaned `-shm`.
+/// Must only be called while holding the vault lock.
+pub fn prepare_open(vault_path: &Path) -> Result<WalLeftovers, DatabaseError> {
+    let wal_path = aux_path(vault_path, "-wal");
+    let shm_path = aux_path(vault_path, "-shm");
+
+    let wal = fs::metadata(&wal_path).is_ok_and(|meta| meta.len() > 0);
+    let mut removed_orphaned_shm = false;
+    if !wal_path.exists() && shm_path.exists() {
+        fs::remove_file(&shm_path).map_err(|e| DatabaseError::IoError {
+            path: shm_path.display().to_string(),
+            reason: format!("Failed to remove stale shared-memory file: {e}"),
+        })?;
+        removed_orphaned_shm = true;
+        println!("[WAL] Removed orphaned {}", shm_path.display());
+    }
+
+    Ok(WalLeftovers {
+        wal,
+        removed_orphaned_shm,
+    })
+}
+
+/// First read on a freshly keyed connection. Replays a leftover WAL and
+/// surfaces a wrong key; retried while another connection holds the file.
+pub fn first_read(conn: &Connection, vault_path: &Path) -> Result<(), DatabaseError> {
+    retry_busy(|| conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))).map_err(
+        |e| {
+            if is_busy(&e) {
+                DatabaseError::VaultBusy {
+                    path: vault_path.display().to_string(),
+                    reason: format!(
+                        "still locked after {} retries, another process may be using it: {e}",
+                        BUSY_RETRY_DELAYS.len()
+                    ),
+                }
+            } else {
+                DatabaseError::PragmaError {
+                    pragma: "key".to_string(),
+                    reason: e.to_string(),
+                }
+            }
+        },
+    )
+}
+
+/// Copies the whole WAL into the database file and truncates it. Retried
+/// while readers or writers keep the checkpoint from finishing; the last
+/// result is returned with `busy` set if they never let go.
+pub fn checkpoint_truncate(conn: &Connection) -> Result<WalCheckpoint, DatabaseError> {
+    let run = || {
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| {
+            Ok(WalCheckpoint {
+                busy: row.get::<_, i64>(0)? != 0,
+                log_frames: row.get(1)?,
+                checkpointed_frames: row.get(2)?,
+            })
+        })
+    };
+    let map_err = |e: rusqlite::Error| DatabaseError::PragmaError {
+        pragma: "wal_checkpoint(TRUNCATE)".to_string(),
+        reason: e.to_string(),
+    };
+
+    for delay in BUSY_RETRY_DELAYS {
+        match retry_busy(run).map_err(map_err)? {
+            checkpoint if checkpoint.busy => thread::sleep(delay),
+            checkpoint => return Ok(checkpoint),
+        }
+    }
+    retry_busy(run).map_err(map_err)
+}
+
+/// Forces a WAL checkpoint on the open vault and truncates the WAL file.
+#[tauri::command]
+pub fn database_checkpoint(state: State<'_, AppState>) -> Result<WalCheckpoint, DatabaseError> {
+    with_connection(&state.db, |conn| checkpoint_truncate(conn))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_wal(path: &Path) -> Connection {
+        let conn = Connection::open(path).unwrap();
+        conn.pragma_update(None, "key", "secret").unwrap();
+        let _: String = conn
+            .pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get(0))
+            .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_orphaned_shm_is_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("vault.db");
+        fs::write(&vault, b"").unwrap();
+        fs::write(aux_path(&vault, "-shm"), b"stale").unwrap();
+
+        let leftovers = prepare_open(&vault).unwrap();
+        assert!(leftovers.removed_orphaned_shm);
+        assert!(!leftovers.wal);
+        assert!(!aux_path(&vault, "-shm").exists());
+
+        // With a WAL next to it the -shm is left alone
+        fs::write(aux_path(&vault, "-wal"), b"frames").unwrap();
+        fs::write(aux_path(&vault, "-shm"), b"index").unwrap();
+        let leftovers = prepare_open(&vault).unwrap();
+        assert!(leftovers.wal);
+        assert!(!leftovers.removed_orphaned_shm);
+        assert!(aux_path(&vault, "-shm").exists());
+    }
+
+    #[test]
+    fn test_checkpoint_truncates_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("vault.db");
+        let conn = open_wal(&vault);
+        conn.execute_batch(
+            "CREATE TABLE notes (body TEXT);
+             INSERT INTO notes VALUES ('a'), ('b');",
+        )
+        .unwrap();
+        assert!(fs::metadata(aux_path(&vault, "-wal")).unwrap().len() > 0);
+
+        let checkpoint = checkpoint_truncate(&conn).unwrap();
+        assert!(!checkpoint.busy);
+        assert!(checkpoint.log_frames > 0);
+        assert_eq!(checkpoint.log_frames, checkpoint.checkpointed_frames);
+        assert_eq!(fs::metadata(aux_path(&vault, "-wal")).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_retry_busy_gives_up_on_other_errors() {
+        let mut calls = 0;
+        let result: rusqlite::Result<()> = retry_busy(|| {
+            calls += 1;
+            Err(rusqlite::Error::QueryReturnedNoRows)
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+
+        let mut calls = 0;
+        let result = retry_busy(|| {
+            calls += 1;
+            if calls < 3 {
+                Err(rusqlite::Error::SqliteFailure(
+                    rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                    None,
+                ))
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+    }
+}
@@ -0,0 +1,210 @@
+//! CalDAV/CardDAV collection sync over HTTP.
+//!
+//! Uses the WebDAV `sync-collection` report (RFC 6578) for incremental
+//! rounds and `calendar-multiget` / `addressbook-multiget` for servers that
+//! report changes without their data. Each call builds a fresh client,
+//! same as the mail module.
+
+use std::time::Duration;
+
+use quick_xml::escape::escape;
+use reqwest::{Method, StatusCode};
+
+use crate::dav::error::DavError;
+use crate::dav::parsing::{normalize_href, parse_multistatus, Multistatus};
+use crate::dav::types::{DavAuthMethod, DavChanges, DavCollectionKind, DavConfig, DavResource};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Runs one `sync-collection` round from `sync_token` (`None` = initial).
+///
+/// An expired or unknown token silently falls back to an initial round,
+/// which the result reports via `initial`.
+pub async fn sync_collection(
+    config: &DavConfig,
+    sync_token: Option<&str>,
+) -> Result<DavChanges, DavError> {
+    let collection = url::Url::parse(&config.url).map_err(|e| DavError::InvalidConfig {
+        reason: format!("Invalid collection URL: {e}"),
+    })?;
+    if !matches!(collection.scheme(), "https" | "http") {
+        return Err(DavError::InvalidConfig {
+            reason: format!("Unsupported URL scheme '{}'", collection.scheme()),
+        });
+    }
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(http_error)?;
+
+    let mut token = sync_token.filter(|t| !t.is_empty());
+    let (status, body) = loop {
+        let body = sync_collection_body(config.kind, token);
+        let (status, text) = report(&client, config, &collection, "0", body).await?;
+        // RFC 6578 3.2: DAV:valid-sync-token precondition failed
+        let invalid_token =
+            matches!(status.as_u16(), 403 | 409) && text.contains("valid-sync-token");
+        if invalid_token && token.is_some() {
+            token = None;
+            continue;
+        }
+        break (status, text);
+    };
+    check_status(config, status, &body, "sync-collection (RFC 6578)")?;
+
+    let multistatus = parse_multistatus(&body)?;
+    let collection_path = collection.path().trim_end_matches('/').to_string();
+    let mut changes = DavChanges {
+        sync_token: multistatus.sync_token,
+        initial: token.is_none(),
+        ..Default::default()
+    };
+    let mut missing_data = Vec::new();
+
+    for response in multistatus.responses {
+        if response.href.trim_end_matches('/') == collection_path {
+            changes.truncated |= response.status == Some(507);
+            continue;
+        }
+        match (response.status, response.data) {
+            (Some(404), _) => changes.deleted.push(response.href),
+            (_, Some(data)) => changes.changed.push(DavResource {
+                href: response.href,
+                etag: response.etag,
+                data,
+            }),
+            (_, None) => missing_data.push(response.href),
+        }
+    }
+
+    if !missing_data.is_empty() {
+        let fetched = multiget(&client, config, &collection, &missing_data).await?;
+        changes.changed.extend(fetched);
+    }
+
+    Ok(changes)
+}
+
+/// Fetches the data of `hrefs` in one multiget report.
+async fn multiget(
+    client: &reqwest::Client,
+    config: &DavConfig,
+    collection: &url::Url,
+    hrefs: &[String],
+) -> Result<Vec<DavResource>, DavError> {
+    let body = multiget_body(config.kind, hrefs);
+    let (status, text) = report(client, config, collection, "1", body).await?;
+    check_status(config, status, &text, "multiget")?;
+
+    let Multistatus { responses, .. } = parse_multistatus(&text)?;
+    Ok(responses
+        .into_iter()
+        .filter_map(|r| {
+            Some(DavResource {
+                data: r.data?,
+                href: r.href,
+                etag: r.etag,
+            })
+        })
+        .collect())
+}
+
+async fn report(
+    client: &reqwest::Client,
+    config: &DavConfig,
+    collection: &url::Url,
+    depth: &str,
+    body: String,
+) -> Result<(StatusCode, String), DavError> {
+    let method = Method::from_bytes(b"REPORT").map_err(http_error)?;
+    let request = client
+        .request(method, collection.clone())
+        .header("Depth", depth)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/xml; charset=utf-8",
+        )
+        .body(body);
+    let request = match config.auth {
+        DavAuthMethod::Password => request.basic_auth(&config.username, Some(&config.password)),
+        DavAuthMethod::OAuth2 => request.bearer_auth(&config.password),
+    };
+    let response = request.send().await.map_err(http_error)?;
+    let status = response.status();
+    let text = response.text().await.map_err(http_error)?;
+    Ok((status, text))
+}
+
+fn check_status(
+    config: &DavConfig,
+    status: StatusCode,
+    body: &str,
+    feature: &str,
+) -> Result<(), DavError> {
+    match status.as_u16() {
+        207 => Ok(()),
+        401 => Err(DavError::Auth {
+            username: config.username.clone(),
+            status: 401,
+        }),
+        code @ (400 | 403 | 405 | 415 | 501) => Err(DavError::Unsupported {
+            feature: feature.to_string(),
+            status: code,
+        }),
+        code => Err(DavError::Status {
+            status: code,
+            body: body.chars().take(200).collect(),
+        }),
+    }
+}
+
+fn namespace(kind: DavCollectionKind) -> (&'static str, &'static str) {
+    match kind {
+        DavCollectionKind::Calendar => ("urn:ietf:params:xml:ns:caldav", "calendar-data"),
+        DavCollectionKind::AddressBook => ("urn:ietf:params:xml:ns:carddav", "address-data"),
+    }
+}
+
+pub fn sync_collection_body(kind: DavCollectionKind, sync_token: Option<&str>) -> String {
+    let (ns, data) = namespace(kind);
+    let token = sync_token
+        .map(|t| escape(t).into_owned())
+        .unwrap_or_default();
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<d:sync-collection xmlns:d="DAV:" xmlns:x="{ns}">
+  <d:sync-token>{token}</d:sync-token>
+  <d:sync-level>1</d:sync-level>
+  <d:prop><d:getetag/><x:{data}/></d:prop>
+</d:sync-collection>"#
+    )
+}
+
+pub fn multiget_body(kind: DavCollectionKind, hrefs: &[String]) -> String {
+    let (ns, data) = namespace(kind);
+    let report = match kind {
+        DavCollectionKind::Calendar => "calendar-multiget",
+        DavCollectionKind::AddressBook => "addressbook-multiget",
+    };
+    let hrefs: String = hrefs
+        .iter()
+        .map(|href| {
+            format!(
+                "  <d:href>{}</d:href>\n",
+                escape(normalize_href(href).as_str())
+            )
+        })
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<x:{report} xmlns:d="DAV:" xmlns:x="{ns}">
+  <d:prop><d:getetag/><x:{data}/></d:prop>
+{hrefs}</x:{report}>"#
+    )
+}
+
+fn http_error(e: impl std::fmt::Display) -> DavError {
+    DavError::Http {
+        reason: e.to_string(),
+    }
+}
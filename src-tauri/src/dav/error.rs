@@ -0,0 +1,40 @@
+//! Errors for the core CalDAV/CardDAV module.
+//!
+//! Kept separate from `ExtensionError` like `MailError`; the extension
+//! wrapper in `extension/dav/` converts them.
+
+use crate::error_code::{CodedError, ErrorCode};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DavError {
+    #[error("Invalid configuration: {reason}")]
+    InvalidConfig { reason: String },
+
+    #[error("HTTP request failed: {reason}")]
+    Http { reason: String },
+
+    #[error("Authentication failed for user '{username}' (HTTP {status})")]
+    Auth { username: String, status: u16 },
+
+    #[error("Server does not support {feature} (HTTP {status})")]
+    Unsupported { feature: String, status: u16 },
+
+    #[error("Server returned HTTP {status}: {body}")]
+    Status { status: u16, body: String },
+
+    #[error("Invalid multistatus response: {reason}")]
+    Xml { reason: String },
+}
+
+impl CodedError for DavError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            DavError::InvalidConfig { .. } => ErrorCode::Validation,
+            DavError::Http { .. } | DavError::Status { .. } => ErrorCode::Http,
+            DavError::Auth { .. } => ErrorCode::RemoteAuthentication,
+            DavError::Unsupported { .. } => ErrorCode::Unsupported,
+            DavError::Xml { .. } => ErrorCode::Serialization,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Core CalDAV/CardDAV module.
+//!
+//! Incremental, read-only sync of a single calendar or address book
+//! collection. Like `mail`, it lives outside `extension/` because nothing
+//! here is extension-specific; extensions use it through the wrapper in
+//! `extension/dav/`, which adds permission checks, sync-token bookkeeping
+//! and the mapping of resources into extension tables.
+//!
+//! Credentials are passed in per call; this module never persists them.
+
+pub mod client;
+pub mod error;
+pub mod parsing;
+pub mod types;
+
+#[cfg(test)]
+mod tests;
+
+pub use error::DavError;
+pub use types::{DavAuthMethod, DavChanges, DavCollectionKind, DavConfig, DavObject, DavResource};
@@ -0,0 +1,331 @@
+//! Parsing of WebDAV multistatus responses and iCalendar/vCard text.
+//!
+//! Only what collection sync needs: hrefs, etags, statuses, the embedded
+//! `calendar-data` / `address-data` and the `sync-token`. Element names
+//! are matched by local name so any namespace prefix works.
+
+use std::collections::BTreeMap;
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use crate::dav::error::DavError;
+use crate::dav::types::DavObject;
+
+/// Components whose properties [`parse_object`] returns.
+const OBJECT_COMPONENTS: &[&str] = &["VEVENT", "VTODO", "VJOURNAL", "VCARD"];
+
+/// One `<response>` of a multistatus.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MultistatusResponse {
+    pub href: String,
+    /// Response-level status; `404` marks a deleted resource in
+    /// `sync-collection`, `507` a truncated result
+    pub status: Option<u16>,
+    pub etag: Option<String>,
+    /// `calendar-data` or `address-data`
+    pub data: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Multistatus {
+    pub responses: Vec<MultistatusResponse>,
+    pub sync_token: Option<String>,
+}
+
+#[derive(Default)]
+struct PropStat {
+    status: Option<u16>,
+    etag: Option<String>,
+    data: Option<String>,
+}
+
+pub fn parse_multistatus(xml: &str) -> Result<Multistatus, DavError> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut result = Multistatus::default();
+    let mut stack: Vec<String> = Vec::new();
+    let mut response: Option<MultistatusResponse> = None;
+    let mut propstat: Option<PropStat> = None;
+
+    loop {
+        let event = reader.read_event().map_err(|e| DavError::Xml {
+            reason: format!("at byte {}: {e}", reader.buffer_position()),
+        })?;
+        let text = match event {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_ascii_lowercase();
+                match name.as_str() {
+                    "response" => response = Some(MultistatusResponse::default()),
+                    "propstat" => propstat = Some(PropStat::default()),
+                    _ => {}
+                }
+                stack.push(name);
+                continue;
+            }
+            Event::End(_) => {
+                match stack.pop().as_deref() {
+                    Some("propstat") => {
+                        let (Some(ps), Some(resp)) = (propstat.take(), response.as_mut()) else {
+                            continue;
+                        };
+                        // Props reported with 404/403 are missing, not empty
+                        if ps.status.is_none_or(|s| (200..300).contains(&s)) {
+                            resp.etag = ps.etag.or(resp.etag.take());
+                            resp.data = ps.data.or(resp.data.take());
+                        }
+                    }
+                    Some("response") => {
+                        if let Some(resp) = response.take() {
+                            result.responses.push(resp);
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            Event::Text(e) => e
+                .unescape()
+                .map_err(|e| DavError::Xml {
+                    reason: e.to_string(),
+                })?
+                .into_owned(),
+            Event::CData(e) => String::from_utf8_lossy(&e).into_owned(),
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        let parent = stack.len().checked_sub(2).and_then(|i| stack.get(i));
+        match (stack.last().map(String::as_str), parent.map(String::as_str)) {
+            (Some("href"), Some("response")) => {
+                if let Some(resp) = response.as_mut() {
+                    resp.href = normalize_href(text.trim());
+                }
+            }
+            (Some("status"), Some("response")) => {
+                if let Some(resp) = response.as_mut() {
+                    resp.status = parse_status(&text);
+                }
+            }
+            (Some("status"), Some("propstat")) => {
+                if let Some(ps) = propstat.as_mut() {
+                    ps.status = parse_status(&text);
+                }
+            }
+            (Some("getetag"), _) => {
+                if let Some(ps) = propstat.as_mut() {
+                    ps.etag = Some(text.trim().to_string());
+                }
+            }
+            (Some("calendar-data" | "address-data"), _) => {
+                // Text and CDATA sections of one element arrive separately
+                if let Some(ps) = propstat.as_mut() {
+                    ps.data.get_or_insert_with(String::new).push_str(&text);
+                }
+            }
+            (Some("sync-token"), Some("multistatus")) => {
+                result.sync_token = Some(text.trim().to_string());
+            }
+            _ => {}
+        }
+    }
+
+    Ok(result)
+}
+
+/// `HTTP/1.1 404 Not Found` -> `404`
+fn parse_status(line: &str) -> Option<u16> {
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Absolute hrefs are reduced to their path so all hrefs compare alike.
+pub fn normalize_href(href: &str) -> String {
+    match url::Url::parse(href) {
+        Ok(url) if url.has_host() => url.path().to_string(),
+        _ => href.to_string(),
+    }
+}
+
+/// Properties of the first event/todo/journal/card in `data`.
+///
+/// For recurring events the first `VEVENT` is the master; overridden
+/// instances and nested components (`VALARM`) are ignored. A vCard group
+/// prefix (`item1.EMAIL`) is stripped.
+pub fn parse_object(data: &str) -> Option<DavObject> {
+    let (component, lines) = parse_object_lines(data)?;
+    let mut properties = BTreeMap::new();
+    for line in lines {
+        properties
+            .entry(line.name)
+            .or_insert_with(|| unescape_text(&line.value));
+    }
+    Some(DavObject {
+        component,
+        properties,
+    })
+}
+
+/// One property of an iCalendar/vCard object with its parameters.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContentLine {
+    /// Upper case, without group prefix
+    pub name: String,
+    /// Upper-case parameter name to its (unquoted) value
+    pub params: BTreeMap<String, String>,
+    /// Raw value, still TEXT-escaped
+    pub value: String,
+}
+
+impl ContentLine {
+    /// The value with TEXT escaping undone.
+    pub fn text(&self) -> String {
+        unescape_text(&self.value)
+    }
+
+    /// The components of a structured value (`N`, `ADR`), unescaped.
+    pub fn components(&self) -> Vec<String> {
+        let mut parts = Vec::new();
+        let mut current = String::new();
+        let mut escaped = false;
+        for c in self.value.chars() {
+            if escaped {
+                current.push(if matches!(c, 'n' | 'N') { '\n' } else { c });
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == ';' {
+                parts.push(std::mem::take(&mut current));
+            } else {
+                current.push(c);
+            }
+        }
+        parts.push(current);
+        parts
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
+}
+
+/// All properties of the first event/todo/journal/card in `data`, in
+/// order; multi-valued properties (`EMAIL`, `TEL`) appear once per value.
+/// Nested components are skipped like in [`parse_object`].
+pub fn parse_object_lines(data: &str) -> Option<(String, Vec<ContentLine>)> {
+    let mut object: Option<(String, Vec<ContentLine>)> = None;
+    // Nesting depth inside the selected component
+    let mut depth = 0usize;
+
+    for line in unfold(data) {
+        let Some(line) = split_content_line(&line) else {
+            continue;
+        };
+        let Some((_, lines)) = object.as_mut() else {
+            let component = line.value.to_ascii_uppercase();
+            if line.name == "BEGIN" && OBJECT_COMPONENTS.contains(&component.as_str()) {
+                object = Some((component, Vec::new()));
+            }
+            continue;
+        };
+        if line.name == "BEGIN" {
+            depth += 1;
+        } else if line.name == "END" {
+            if depth == 0 {
+                break;
+            }
+            depth -= 1;
+        } else if depth == 0 {
+            lines.push(line);
+        }
+    }
+
+    object
+}
+
+/// Lines with RFC 5545 folding undone.
+fn unfold(data: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in data.lines() {
+        match raw.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => {
+                if let Some(last) = lines.last_mut() {
+                    last.push_str(rest);
+                }
+            }
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// `NAME;PARAM="a:b":value` -> name `NAME`, params and `value`, upper-cased
+/// name without group prefix. Colons inside quoted parameter values don't
+/// split.
+fn split_content_line(line: &str) -> Option<ContentLine> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = split_unquoted(head, ';').into_iter();
+    let name = parts.next()?;
+    let name = name.rsplit('.').next()?.trim().to_ascii_uppercase();
+    if name.is_empty() {
+        return None;
+    }
+    let params = parts
+        .filter_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            Some((
+                key.trim().to_ascii_uppercase(),
+                value.trim().trim_matches('"').to_string(),
+            ))
+        })
+        .collect();
+    Some(ContentLine {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+/// Splits at `separator` outside of double quotes.
+fn split_unquoted(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        } else if c == separator && !in_quotes {
+            parts.push(&s[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Undoes TEXT escaping (`\n`, `\,`, `\;`, `\\`).
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
@@ -0,0 +1,160 @@
+//! Tests for multistatus and iCalendar/vCard parsing (without network)
+
+use super::client::{multiget_body, sync_collection_body};
+use super::parsing::{normalize_href, parse_multistatus, parse_object, parse_object_lines};
+use super::types::DavCollectionKind;
+
+const SYNC_RESPONSE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">
+  <d:response>
+    <d:href>/dav/calendars/alice/personal/event1.ics</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:getetag>"etag-1"</d:getetag>
+        <cal:calendar-data><![CDATA[BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:event1
+SUMMARY:Lunch
+END:VEVENT
+END:VCALENDAR]]></cal:calendar-data>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>https://cloud.example/dav/calendars/alice/personal/event2.ics</d:href>
+    <d:propstat>
+      <d:prop><d:getetag>"etag-2"</d:getetag></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><cal:calendar-data/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/calendars/alice/personal/gone.ics</d:href>
+    <d:status>HTTP/1.1 404 Not Found</d:status>
+  </d:response>
+  <d:response>
+    <d:href>/dav/calendars/alice/personal/</d:href>
+    <d:status>HTTP/1.1 507 Insufficient Storage</d:status>
+  </d:response>
+  <d:sync-token>http://cloud.example/ns/sync/42</d:sync-token>
+</d:multistatus>"#;
+
+#[test]
+fn test_parse_sync_collection_response() {
+    let multistatus = parse_multistatus(SYNC_RESPONSE).unwrap();
+    assert_eq!(
+        multistatus.sync_token.as_deref(),
+        Some("http://cloud.example/ns/sync/42")
+    );
+    assert_eq!(multistatus.responses.len(), 4);
+
+    let changed = &multistatus.responses[0];
+    assert_eq!(changed.href, "/dav/calendars/alice/personal/event1.ics");
+    assert_eq!(changed.etag.as_deref(), Some("\"etag-1\""));
+    assert!(changed.data.as_deref().unwrap().contains("SUMMARY:Lunch"));
+
+    // Data reported as 404 in its own propstat is missing, the etag isn't
+    let without_data = &multistatus.responses[1];
+    assert_eq!(
+        without_data.href,
+        "/dav/calendars/alice/personal/event2.ics"
+    );
+    assert_eq!(without_data.etag.as_deref(), Some("\"etag-2\""));
+    assert_eq!(without_data.data, None);
+
+    assert_eq!(multistatus.responses[2].status, Some(404));
+    assert_eq!(multistatus.responses[3].status, Some(507));
+}
+
+#[test]
+fn test_parse_multistatus_unescapes_text_data() {
+    let xml = r#"<multistatus xmlns="DAV:"><response><href>/c/1.vcf</href><propstat>
+        <prop><address-data xmlns="urn:ietf:params:xml:ns:carddav">BEGIN:VCARD&#13;
+FN:Tom &amp; Jerry&#13;
+END:VCARD</address-data></prop></propstat></response></multistatus>"#;
+    let multistatus = parse_multistatus(xml).unwrap();
+    let data = multistatus.responses[0].data.as_deref().unwrap();
+    assert!(data.contains("FN:Tom & Jerry\r\n"));
+    assert_eq!(multistatus.sync_token, None);
+
+    assert!(parse_multistatus("<multistatus><response></multistatus>").is_err());
+}
+
+#[test]
+fn test_parse_event_with_folding_escapes_and_alarm() {
+    let data = "BEGIN:VCALENDAR\r\n\
+                BEGIN:VTIMEZONE\r\nTZID:Europe/Berlin\r\nEND:VTIMEZONE\r\n\
+                BEGIN:VEVENT\r\n\
+                UID:abc-123\r\n\
+                SUMMARY:Team meeting\\, weekly\r\n\
+                DESCRIPTION:First line\\nsecond \r\n line\r\n\
+                DTSTART;TZID=Europe/Berlin:20261020T090000\r\n\
+                LOCATION;ALTREP=\"http://example.com/room:1\":Room 1\r\n\
+                BEGIN:VALARM\r\nDESCRIPTION:Reminder\r\nEND:VALARM\r\n\
+                END:VEVENT\r\n\
+                BEGIN:VEVENT\r\nUID:abc-123\r\nSUMMARY:Moved instance\r\nEND:VEVENT\r\n\
+                END:VCALENDAR\r\n";
+    let object = parse_object(data).unwrap();
+    assert_eq!(object.component, "VEVENT");
+    assert_eq!(object.uid(), Some("abc-123"));
+    assert_eq!(object.properties["SUMMARY"], "Team meeting, weekly");
+    assert_eq!(object.properties["DESCRIPTION"], "First line\nsecond line");
+    assert_eq!(object.properties["DTSTART"], "20261020T090000");
+    assert_eq!(object.properties["LOCATION"], "Room 1");
+    assert!(!object.properties.contains_key("TZID"));
+}
+
+#[test]
+fn test_parse_vcard_strips_groups_and_keeps_first_value() {
+    let data = "BEGIN:VCARD\nVERSION:4.0\nUID:urn:uuid:42\nFN:Alice Example\n\
+                item1.EMAIL;TYPE=work:alice@work.example\nEMAIL:alice@home.example\nEND:VCARD\n";
+    let object = parse_object(data).unwrap();
+    assert_eq!(object.component, "VCARD");
+    assert_eq!(object.uid(), Some("urn:uuid:42"));
+    assert_eq!(object.properties["EMAIL"], "alice@work.example");
+
+    assert_eq!(parse_object("BEGIN:VCALENDAR\nEND:VCALENDAR\n"), None);
+}
+
+#[test]
+fn test_parse_object_lines_keeps_params_and_repeated_properties() {
+    let data = "BEGIN:VCARD\nUID:1\nN:Doe\\;Smith;Jane;;;\n\
+                TEL;TYPE=\"cell,voice\";PREF=1:+1 555\nTEL:+1 556\nEND:VCARD\n";
+    let (component, lines) = parse_object_lines(data).unwrap();
+    assert_eq!(component, "VCARD");
+    let names: Vec<_> = lines.iter().map(|l| l.name.as_str()).collect();
+    assert_eq!(names, vec!["UID", "N", "TEL", "TEL"]);
+    assert_eq!(lines[1].components(), vec!["Doe;Smith", "Jane", "", "", ""]);
+    assert_eq!(lines[2].param("TYPE"), Some("cell,voice"));
+    assert_eq!(lines[2].param("PREF"), Some("1"));
+    assert_eq!(lines[2].value, "+1 555");
+}
+
+#[test]
+fn test_request_bodies() {
+    let body = sync_collection_body(DavCollectionKind::Calendar, Some("a<b"));
+    assert!(body.contains("<d:sync-token>a&lt;b</d:sync-token>"));
+    assert!(body.contains("<x:calendar-data/>"));
+
+    let body = sync_collection_body(DavCollectionKind::AddressBook, None);
+    assert!(body.contains("<d:sync-token></d:sync-token>"));
+    assert!(body.contains("urn:ietf:params:xml:ns:carddav"));
+
+    let body = multiget_body(
+        DavCollectionKind::AddressBook,
+        &["https://dav.example/book/1.vcf".to_string()],
+    );
+    assert!(body.starts_with("<?xml"));
+    assert!(body.contains("<x:addressbook-multiget"));
+    assert!(body.contains("<d:href>/book/1.vcf</d:href>"));
+}
+
+#[test]
+fn test_normalize_href() {
+    assert_eq!(normalize_href("https://dav.example/a/b.ics"), "/a/b.ics");
+    assert_eq!(normalize_href("/a/b.ics"), "/a/b.ics");
+}
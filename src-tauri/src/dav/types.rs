@@ -0,0 +1,114 @@
+//! Data structures for CalDAV/CardDAV collection sync.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// What a collection holds; selects the CalDAV or CardDAV vocabulary.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum DavCollectionKind {
+    /// CalDAV calendar (iCalendar resources: events, todos, journals)
+    Calendar,
+    /// CardDAV address book (vCard resources)
+    AddressBook,
+}
+
+/// How requests authenticate.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum DavAuthMethod {
+    /// HTTP Basic with the account or app password.
+    #[default]
+    Password,
+    /// `Authorization: Bearer`; `password` holds the OAuth2 access token.
+    #[serde(rename = "oauth2")]
+    OAuth2,
+}
+
+/// A CalDAV/CardDAV collection + credentials.
+#[derive(Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct DavConfig {
+    /// URL of the calendar or address book collection itself (not the
+    /// principal or home set), e.g.
+    /// `https://cloud.example/remote.php/dav/calendars/alice/personal/`
+    pub url: String,
+    pub kind: DavCollectionKind,
+    #[serde(default)]
+    pub auth: DavAuthMethod,
+    #[serde(default)]
+    pub username: String,
+    /// Password, app password or OAuth2 access token (see `auth`). May be
+    /// left empty when `credential_item_id` is set.
+    #[serde(default)]
+    pub password: String,
+    /// Password-vault item to take username and secret from, resolved by
+    /// the extension wrapper within the extension's tag scope.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub credential_item_id: Option<String>,
+}
+
+impl fmt::Debug for DavConfig {
+    // Manual impl so accidental tracing/dbg!/println! never leaks the password.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DavConfig")
+            .field("url", &self.url)
+            .field("kind", &self.kind)
+            .field("auth", &self.auth)
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .field("credential_item_id", &self.credential_item_id)
+            .finish()
+    }
+}
+
+/// A calendar object or vCard as stored on the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DavResource {
+    /// Path of the resource, as returned by the server
+    pub href: String,
+    pub etag: Option<String>,
+    /// The iCalendar / vCard text
+    pub data: String,
+}
+
+/// Result of one `sync-collection` round.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DavChanges {
+    /// Token to pass to the next round
+    pub sync_token: Option<String>,
+    /// New or modified resources
+    pub changed: Vec<DavResource>,
+    /// Hrefs of removed resources
+    pub deleted: Vec<String>,
+    /// The server truncated the result (507); another round with
+    /// `sync_token` returns the rest
+    pub truncated: bool,
+    /// The round started from scratch, either because no token was given
+    /// or because the server no longer accepted it. `deleted` is empty
+    /// then; the caller has to diff against what it knows.
+    pub initial: bool,
+}
+
+/// The first component of an iCalendar/vCard resource, flattened.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DavObject {
+    /// `VEVENT`, `VTODO`, `VJOURNAL` or `VCARD`
+    pub component: String,
+    /// Property name (upper case) to the unescaped value of its first
+    /// occurrence; parameters are dropped
+    pub properties: BTreeMap<String, String>,
+}
+
+impl DavObject {
+    pub fn uid(&self) -> Option<&str> {
+        self.properties.get("UID").map(String::as_str)
+    }
+}
@@ -0,0 +1,161 @@
+//! Parsing of action links and their callback URLs
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use ts_rs::TS;
+use url::Url;
+
+use super::DeepLinkError;
+
+pub const SCHEME: &str = "haexvault";
+/// Host of action links: `haexvault://action/<extension-id>/<action>`
+pub const ACTION_HOST: &str = "action";
+/// Longest `state` echoed back to the caller
+pub const MAX_STATE_LEN: usize = 512;
+
+/// Schemes a callback must never use: web pages other than loopback,
+/// script and local-content URLs, and the vault itself
+const FORBIDDEN_CALLBACK_SCHEMES: &[&str] = &[
+    "https",
+    "http",
+    "javascript",
+    "data",
+    "file",
+    "vbscript",
+    "about",
+    "blob",
+    SCHEME,
+];
+
+/// How the result reaches the caller
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Callback {
+    /// `http://127.0.0.1:<port>/...` of a local program (e.g. a CLI); the
+    /// result is POSTed as JSON, no browser involved
+    Loopback(Url),
+    /// Private-use scheme of an app (`com.example.app:/done`); opened with
+    /// the result in the query, like an OAuth redirect
+    Redirect(Url),
+}
+
+impl Callback {
+    pub fn url(&self) -> &Url {
+        match self {
+            Callback::Loopback(url) | Callback::Redirect(url) => url,
+        }
+    }
+}
+
+/// Parsed action link
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionLink {
+    pub extension_id: String,
+    pub action: String,
+    pub payload: JsonValue,
+    pub callback: Option<Callback>,
+    pub state: Option<String>,
+}
+
+/// What an action link asks for, shown to the user before it runs
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkAction {
+    pub extension_id: String,
+    pub action: String,
+    #[ts(type = "unknown")]
+    pub payload: JsonValue,
+    pub callback_url: Option<String>,
+    /// Whether the callback is a local program rather than an app redirect
+    pub callback_is_loopback: bool,
+}
+
+impl From<&ActionLink> for DeepLinkAction {
+    fn from(link: &ActionLink) -> Self {
+        Self {
+            extension_id: link.extension_id.clone(),
+            action: link.action.clone(),
+            payload: link.payload.clone(),
+            callback_url: link.callback.as_ref().map(|c| c.url().to_string()),
+            callback_is_loopback: matches!(link.callback, Some(Callback::Loopback(_))),
+        }
+    }
+}
+
+fn is_url_segment(s: &str) -> bool {
+    !s.is_empty()
+        && s.len() <= 128
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Parses `haexvault://action/<extension-id>/<action>?payload=<json>&callback=<url>&state=<s>`
+pub fn parse_action_link(link: &str) -> Result<ActionLink, DeepLinkError> {
+    let url = Url::parse(link).map_err(|e| DeepLinkError::InvalidLink(e.to_string()))?;
+    if url.scheme() != SCHEME || url.host_str() != Some(ACTION_HOST) {
+        return Err(DeepLinkError::InvalidLink("Not an action link".to_string()));
+    }
+
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|s| s.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    let [extension_id, action] = segments.as_slice() else {
+        return Err(DeepLinkError::InvalidLink(
+            "Expected /<extension-id>/<action>".to_string(),
+        ));
+    };
+    if !is_url_segment(extension_id) || !is_url_segment(action) {
+        return Err(DeepLinkError::InvalidLink(
+            "Invalid extension ID or action".to_string(),
+        ));
+    }
+
+    let mut payload = JsonValue::Null;
+    let mut callback = None;
+    let mut state = None;
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "payload" => {
+                payload = serde_json::from_str(&value)
+                    .map_err(|e| DeepLinkError::InvalidLink(format!("Invalid payload: {e}")))?;
+            }
+            "callback" => callback = Some(parse_callback(&value)?),
+            "state" => {
+                if value.len() > MAX_STATE_LEN {
+                    return Err(DeepLinkError::InvalidLink("State too long".to_string()));
+                }
+                state = Some(value.into_owned());
+            }
+            _ => {}
+        }
+    }
+
+    Ok(ActionLink {
+        extension_id: extension_id.to_string(),
+        action: action.to_string(),
+        payload,
+        callback,
+        state,
+    })
+}
+
+/// Accepts loopback HTTP callbacks and private-use app schemes. Anything
+/// that would send the result to a website is rejected: any site can open
+/// a `haexvault://` link.
+pub fn parse_callback(callback: &str) -> Result<Callback, DeepLinkError> {
+    let url = Url::parse(callback).map_err(|e| DeepLinkError::InvalidCallback(e.to_string()))?;
+    let loopback = matches!(
+        url.host(),
+        Some(url::Host::Domain("localhost"))
+            | Some(url::Host::Ipv4(std::net::Ipv4Addr::LOCALHOST))
+            | Some(url::Host::Ipv6(std::net::Ipv6Addr::LOCALHOST))
+    );
+    match url.scheme() {
+        "http" if loopback => Ok(Callback::Loopback(url)),
+        scheme if FORBIDDEN_CALLBACK_SCHEMES.contains(&scheme) => Err(
+            DeepLinkError::InvalidCallback(format!("Callback scheme not allowed: {scheme}")),
+        ),
+        _ => Ok(Callback::Redirect(url)),
+    }
+}
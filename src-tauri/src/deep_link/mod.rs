@@ -0,0 +1,296 @@
+//! Two-way deep-link actions
+//!
+//! Besides `haexvault://extension/<id>` (open an extension, handled in the
+//! frontend), a link can ask an extension to perform an action and report
+//! back, e.g. a CLI that needs a token from an extension:
+//!
+//! `haexvault://action/<extension-id>/<action>?payload=<json>&callback=<url>&state=<s>`
+//!
+//! The frontend shows the parsed request ([`deep_link_parse_action`]) and
+//! runs it once the user confirmed ([`deep_link_execute_action`]): the core
+//! routes it to the owning extension like a bridge request
+//! (`haextension:external:request` with `source: "deepLink"`), waits for the
+//! extension's `external_bridge_respond` and hands the result to the
+//! callback, which makes OAuth-like flows through the vault possible.
+//!
+//! The result is signed so the caller can tell it came from this vault:
+//! `result` is the base64url JSON of [`SignedResult`] (echoing `state`),
+//! `signature` the base64url Ed25519 signature over the `result` string,
+//! checked against the key from [`deep_link_get_public_key`] the caller
+//! pinned. Loopback callbacks (`http://127.0.0.1:<port>/...`) receive
+//! `{ result, signature, state }` as a POST; app callbacks (private-use
+//! schemes) are opened with the same fields in the query. Web callbacks are
+//! refused, since any website can open a `haexvault://` link.
+
+pub mod link;
+mod signing;
+#[cfg(test)]
+mod tests;
+
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::error_code::{CodedError, ErrorCode};
+use crate::external_bridge::{dispatch_to_target, get_extension_by_id, RouteTarget};
+use crate::AppState;
+use link::{parse_action_link, ActionLink, Callback, DeepLinkAction};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::time::Duration;
+use tauri::{AppHandle, State};
+use tauri_plugin_http::reqwest;
+use thiserror::Error;
+use ts_rs::TS;
+
+/// Value of `source` in requests that came from a deep link
+pub const DEEP_LINK_SOURCE: &str = "deepLink";
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum DeepLinkError {
+    #[error("Invalid deep link: {0}")]
+    InvalidLink(String),
+
+    #[error("Invalid callback URL: {0}")]
+    InvalidCallback(String),
+
+    #[error("Extension not found: {0}")]
+    ExtensionNotFound(String),
+
+    #[error("Callback failed: {0}")]
+    CallbackFailed(String),
+
+    #[error("{0}")]
+    Internal(String),
+
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+impl CodedError for DeepLinkError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            DeepLinkError::InvalidLink(_) | DeepLinkError::InvalidCallback(_) => {
+                ErrorCode::Validation
+            }
+            DeepLinkError::ExtensionNotFound(_) => ErrorCode::NotFound,
+            DeepLinkError::CallbackFailed(_) => ErrorCode::Http,
+            DeepLinkError::Internal(_) => ErrorCode::Internal,
+            DeepLinkError::Database(e) => e.error_code(),
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        match self {
+            DeepLinkError::Database(e) => e.error_details(),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for DeepLinkError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Serialize::serialize(&self.envelope(), serializer)
+    }
+}
+
+/// Result as signed for the caller
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedResult {
+    pub request_id: String,
+    pub extension_id: String,
+    pub action: String,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<JsonValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The caller's `state`, binding the result to its request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    /// Unix seconds
+    pub issued_at: i64,
+}
+
+impl SignedResult {
+    /// Builds the result from an extension response (`{ success, data, error }`)
+    pub fn from_response(
+        request_id: &str,
+        link: &ActionLink,
+        response: &JsonValue,
+        issued_at: i64,
+    ) -> Self {
+        let success = response
+            .get("success")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        Self {
+            request_id: request_id.to_string(),
+            extension_id: link.extension_id.clone(),
+            action: link.action.clone(),
+            success,
+            data: response.get("data").filter(|v| !v.is_null()).cloned(),
+            error: response
+                .get("error")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+                .or_else(|| (!success).then(|| "Action failed".to_string())),
+            state: link.state.clone(),
+            issued_at,
+        }
+    }
+}
+
+/// Outcome of an executed action link
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkActionResult {
+    pub request_id: String,
+    pub success: bool,
+    #[ts(type = "unknown")]
+    pub data: Option<JsonValue>,
+    pub error: Option<String>,
+    /// Whether the result was delivered to the callback
+    pub callback_invoked: bool,
+    pub callback_error: Option<String>,
+}
+
+/// The callback URL with `result`, `signature` and `state` in the query
+pub fn redirect_url(
+    callback: &url::Url,
+    result: &str,
+    signature: &str,
+    state: Option<&str>,
+) -> url::Url {
+    let mut url = callback.clone();
+    {
+        let mut query = url.query_pairs_mut();
+        query.append_pair("result", result);
+        query.append_pair("signature", signature);
+        if let Some(state) = state {
+            query.append_pair("state", state);
+        }
+    }
+    url
+}
+
+async fn invoke_callback(
+    callback: &Callback,
+    result: &str,
+    signature: &str,
+    state: Option<&str>,
+) -> Result<(), DeepLinkError> {
+    match callback {
+        Callback::Loopback(url) => {
+            let response = reqwest::Client::new()
+                .post(url.clone())
+                .timeout(CALLBACK_TIMEOUT)
+                .json(&serde_json::json!({
+                    "result": result,
+                    "signature": signature,
+                    "state": state,
+                }))
+                .send()
+                .await
+                .map_err(|e| DeepLinkError::CallbackFailed(e.to_string()))?;
+            if !response.status().is_success() {
+                return Err(DeepLinkError::CallbackFailed(format!(
+                    "HTTP {}",
+                    response.status()
+                )));
+            }
+            Ok(())
+        }
+        Callback::Redirect(url) => {
+            tauri_plugin_opener::open_url(redirect_url(url, result, signature, state), None::<&str>)
+                .map_err(|e| DeepLinkError::CallbackFailed(e.to_string()))
+        }
+    }
+}
+
+/// Parse an action link for the confirmation dialog
+#[tauri::command]
+pub fn deep_link_parse_action(url: String) -> Result<DeepLinkAction, DeepLinkError> {
+    parse_action_link(&url).map(|link| DeepLinkAction::from(&link))
+}
+
+/// Run a confirmed action link: route it to the extension, wait for the
+/// answer and deliver the signed result to the callback
+#[tauri::command]
+pub async fn deep_link_execute_action(
+    app: AppHandle,
+    url: String,
+    state: State<'_, AppState>,
+) -> Result<DeepLinkActionResult, DeepLinkError> {
+    let link = parse_action_link(&url)?;
+    let target = if link.extension_id == crate::external_bridge::CORE_EXTENSION_ID {
+        RouteTarget::core()
+    } else {
+        get_extension_by_id(&app, &link.extension_id)
+            .await
+            .ok_or_else(|| DeepLinkError::ExtensionNotFound(link.extension_id.clone()))?
+    };
+
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let external_request = serde_json::json!({
+        "requestId": request_id,
+        "source": DEEP_LINK_SOURCE,
+        "publicKey": JsonValue::Null,
+        "action": link.action,
+        "payload": link.payload,
+        "extensionId": target.extension_id,
+        "extensionPublicKey": target.public_key,
+        "extensionName": target.name
+    });
+    let pending_responses = state.external_bridge.lock().await.get_pending_responses();
+    let response = dispatch_to_target(
+        &app,
+        &target,
+        &request_id,
+        external_request,
+        pending_responses,
+    )
+    .await;
+
+    let result = SignedResult::from_response(
+        &request_id,
+        &link,
+        &response,
+        time::OffsetDateTime::now_utc().unix_timestamp(),
+    );
+    let (callback_invoked, callback_error) = match &link.callback {
+        None => (false, None),
+        Some(callback) => {
+            let key = with_connection(&state.db, |conn| signing::signing_key(conn))?;
+            let (encoded, signature) =
+                signing::sign(&key, &result).map_err(|e| DeepLinkError::Internal(e.to_string()))?;
+            match invoke_callback(callback, &encoded, &signature, link.state.as_deref()).await {
+                Ok(()) => (true, None),
+                Err(e) => {
+                    eprintln!("[DeepLink] Callback of {} failed: {}", request_id, e);
+                    (false, Some(e.to_string()))
+                }
+            }
+        }
+    };
+
+    Ok(DeepLinkActionResult {
+        request_id,
+        success: result.success,
+        data: result.data,
+        error: result.error,
+        callback_invoked,
+        callback_error,
+    })
+}
+
+/// Public key (hex) callers pin to verify signed results
+#[tauri::command]
+pub fn deep_link_get_public_key(state: State<'_, AppState>) -> Result<String, DeepLinkError> {
+    let key = with_connection(&state.db, |conn| signing::signing_key(conn))?;
+    Ok(hex::encode(key.verifying_key().to_bytes()))
+}
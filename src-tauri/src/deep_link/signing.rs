@@ -0,0 +1,63 @@
+//! Signing key of action results, kept in `haex_crdt_configs` (local-only)
+
+use crate::database::constants::vault_settings_key;
+use crate::database::error::DatabaseError;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, TABLE_CRDT_CONFIGS,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL, Engine};
+use ed25519_dalek::{Signer, SigningKey};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::SignedResult;
+
+/// `type` column value of the key row
+const CONFIG_TYPE: &str = "deep_link";
+
+/// Ed25519 key the results are signed with; created on first use
+pub fn signing_key(conn: &Connection) -> Result<SigningKey, DatabaseError> {
+    let stored: Option<String> = conn
+        .query_row(
+            &format!(
+                "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"
+            ),
+            params![vault_settings_key::DEEP_LINK_SIGNING_KEY],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    if let Some(stored) = stored {
+        let bytes: [u8; 32] = hex::decode(&stored)
+            .ok()
+            .and_then(|key| key.try_into().ok())
+            .ok_or_else(|| DatabaseError::SerializationError {
+                reason: "Invalid deep link signing key".to_string(),
+            })?;
+        return Ok(SigningKey::from_bytes(&bytes));
+    }
+
+    let mut bytes = [0u8; 32];
+    rand::fill(&mut bytes);
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?, ?, ?)"
+        ),
+        params![
+            vault_settings_key::DEEP_LINK_SIGNING_KEY,
+            CONFIG_TYPE,
+            hex::encode(bytes)
+        ],
+    )?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+/// `(result, signature)`: the result as base64url JSON and the Ed25519
+/// signature over exactly that string, also base64url
+pub fn sign(
+    key: &SigningKey,
+    result: &SignedResult,
+) -> Result<(String, String), serde_json::Error> {
+    let encoded = BASE64_URL.encode(serde_json::to_vec(result)?);
+    let signature = BASE64_URL.encode(key.sign(encoded.as_bytes()).to_bytes());
+    Ok((encoded, signature))
+}
@@ -0,0 +1,162 @@
+use super::link::{parse_action_link, parse_callback, Callback, DeepLinkAction};
+use super::{redirect_url, signing, SignedResult};
+use crate::crdt::hlc::HlcService;
+use crate::test_support::crdt::open_crdt_connection;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL, Engine};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use rusqlite::Connection;
+use serde_json::json;
+
+fn vault() -> Connection {
+    open_crdt_connection(HlcService::new_for_testing("deep-link-test-device")).unwrap()
+}
+
+/// What a caller does with the pinned public key
+fn verify(public_key: &[u8; 32], encoded: &str, signature: &str) -> Option<SignedResult> {
+    let key = VerifyingKey::from_bytes(public_key).ok()?;
+    let signature: [u8; 64] = BASE64_URL.decode(signature).ok()?.try_into().ok()?;
+    key.verify(encoded.as_bytes(), &Signature::from_bytes(&signature))
+        .ok()?;
+    serde_json::from_slice(&BASE64_URL.decode(encoded).ok()?).ok()
+}
+
+#[test]
+fn test_parse_action_link() {
+    let link = parse_action_link(
+        "haexvault://action/ext-123/get-token?payload=%7B%22scope%22%3A%22read%22%7D&callback=http%3A%2F%2F127.0.0.1%3A8765%2Fcb&state=xyz",
+    )
+    .unwrap();
+    assert_eq!(link.extension_id, "ext-123");
+    assert_eq!(link.action, "get-token");
+    assert_eq!(link.payload, json!({ "scope": "read" }));
+    assert_eq!(link.state.as_deref(), Some("xyz"));
+    assert!(matches!(link.callback, Some(Callback::Loopback(_))));
+
+    let action = DeepLinkAction::from(&link);
+    assert_eq!(
+        action.callback_url.as_deref(),
+        Some("http://127.0.0.1:8765/cb")
+    );
+    assert!(action.callback_is_loopback);
+
+    // Payload, callback and state are optional
+    let bare = parse_action_link("haexvault://action/ext-123/ping").unwrap();
+    assert!(bare.payload.is_null());
+    assert!(bare.callback.is_none());
+}
+
+#[test]
+fn test_invalid_action_links() {
+    for link in [
+        "haexvault://extension/ext-123",
+        "otherapp://action/ext-123/ping",
+        "haexvault://action/ext-123",
+        "haexvault://action/ext-123/ping/extra",
+        "haexvault://action/ext%20123/ping",
+        "haexvault://action/ext-123/ping?payload=%7Bnot-json",
+        "not a url",
+    ] {
+        assert!(parse_action_link(link).is_err(), "{link}");
+    }
+
+    let long_state = format!("haexvault://action/ext/ping?state={}", "s".repeat(513));
+    assert!(parse_action_link(&long_state).is_err());
+}
+
+#[test]
+fn test_callback_must_not_reach_the_web() {
+    assert!(matches!(
+        parse_callback("http://localhost:9000/done").unwrap(),
+        Callback::Loopback(_)
+    ));
+    assert!(matches!(
+        parse_callback("http://[::1]:9000/done").unwrap(),
+        Callback::Loopback(_)
+    ));
+    assert!(matches!(
+        parse_callback("com.example.cli:/oauth").unwrap(),
+        Callback::Redirect(_)
+    ));
+
+    for callback in [
+        "https://evil.example/collect",
+        "http://192.168.1.5:9000/done",
+        "https://127.0.0.1/done",
+        "javascript:alert(1)",
+        "data:text/html,hi",
+        "file:///tmp/result",
+        "haexvault://action/ext/ping",
+    ] {
+        assert!(parse_callback(callback).is_err(), "{callback}");
+    }
+}
+
+#[test]
+fn test_signed_result_roundtrip() {
+    let conn = vault();
+    let key = signing::signing_key(&conn).unwrap();
+    // Created once, then reused
+    assert_eq!(
+        signing::signing_key(&conn).unwrap().to_bytes(),
+        key.to_bytes()
+    );
+    let public_key = key.verifying_key().to_bytes();
+
+    let link = parse_action_link("haexvault://action/ext-123/get-token?state=abc").unwrap();
+    let result = SignedResult::from_response(
+        "req-1",
+        &link,
+        &json!({ "requestId": "req-1", "success": true, "data": { "token": "t" } }),
+        1_800_000_000,
+    );
+    assert_eq!(result.state.as_deref(), Some("abc"));
+    assert!(result.error.is_none());
+
+    let (encoded, signature) = signing::sign(&key, &result).unwrap();
+    assert_eq!(verify(&public_key, &encoded, &signature), Some(result));
+
+    // A changed result or another key fails
+    let tampered = format!("{encoded}A");
+    assert!(verify(&public_key, &tampered, &signature).is_none());
+    let other = signing::signing_key(&vault())
+        .unwrap()
+        .verifying_key()
+        .to_bytes();
+    assert!(verify(&other, &encoded, &signature).is_none());
+}
+
+#[test]
+fn test_failed_response_carries_an_error() {
+    let link = parse_action_link("haexvault://action/ext-123/get-token").unwrap();
+    let timeout = SignedResult::from_response(
+        "req-1",
+        &link,
+        &json!({ "requestId": "req-1", "success": false, "error": "Request timeout" }),
+        0,
+    );
+    assert!(!timeout.success);
+    assert_eq!(timeout.error.as_deref(), Some("Request timeout"));
+
+    let malformed = SignedResult::from_response("req-2", &link, &json!({}), 0);
+    assert!(!malformed.success);
+    assert!(malformed.error.is_some());
+}
+
+#[test]
+fn test_redirect_url_keeps_the_callback_query() {
+    let callback = url::Url::parse("com.example.cli:/oauth?session=1").unwrap();
+    let url = redirect_url(&callback, "cmVzdWx0", "c2ln", Some("a b"));
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    assert_eq!(
+        pairs,
+        vec![
+            ("session".to_string(), "1".to_string()),
+            ("result".to_string(), "cmVzdWx0".to_string()),
+            ("signature".to_string(), "c2ln".to_string()),
+            ("state".to_string(), "a b".to_string()),
+        ]
+    );
+}
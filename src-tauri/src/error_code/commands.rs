@@ -0,0 +1,12 @@
+// src-tauri/src/error_code/commands.rs
+//!
+//! Tauri command for the locale of user-facing error messages
+
+use super::messages;
+
+/// Sets the locale of `userMessage`. The frontend calls this on start and
+/// whenever the i18n locale changes, on every platform.
+#[tauri::command]
+pub fn error_locale_set(locale: String) {
+    messages::set_error_locale(&locale);
+}
@@ -0,0 +1,290 @@
+// src-tauri/src/error_code/messages.rs
+//!
+//! User-facing error messages per [`ErrorCode`]
+//!
+//! `message` of a serialized error is the technical description (English,
+//! with paths, SQL and causes); `userMessage` comes from this catalog in the
+//! language of the UI. The catalog covers the app languages, other locales
+//! fall back to English. Until the frontend has set a locale, messages are
+//! in German, the app's default language.
+
+use super::ErrorCode;
+use std::sync::RwLock;
+
+/// Locale of the user-facing messages, empty until the frontend sets one
+static ERROR_LOCALE: RwLock<String> = RwLock::new(String::new());
+
+/// Languages of the catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageLanguage {
+    En,
+    De,
+}
+
+impl MessageLanguage {
+    /// Language for a BCP 47 tag (`de-CH` → German), English if unsupported
+    pub fn from_locale(locale: &str) -> Self {
+        let language = locale.split(['-', '_']).next().unwrap_or_default();
+        if language.eq_ignore_ascii_case("de") {
+            MessageLanguage::De
+        } else {
+            MessageLanguage::En
+        }
+    }
+}
+
+/// Sets the locale used for `userMessage` (see
+/// [`super::commands::error_locale_set`]).
+pub fn set_error_locale(locale: &str) {
+    if let Ok(mut current) = ERROR_LOCALE.write() {
+        *current = locale.to_string();
+    }
+}
+
+/// Language of the current locale, German while none is set
+pub fn current_language() -> MessageLanguage {
+    ERROR_LOCALE
+        .read()
+        .map(|locale| {
+            if locale.is_empty() {
+                MessageLanguage::De
+            } else {
+                MessageLanguage::from_locale(&locale)
+            }
+        })
+        .unwrap_or(MessageLanguage::De)
+}
+
+/// User-facing message for `code` in `language`
+pub fn user_message(code: ErrorCode, language: MessageLanguage) -> &'static str {
+    let (en, de) = catalog(code);
+    match language {
+        MessageLanguage::En => en,
+        MessageLanguage::De => de,
+    }
+}
+
+/// English and German message per code. The match is exhaustive so a new
+/// code doesn't compile without its messages.
+fn catalog(code: ErrorCode) -> (&'static str, &'static str) {
+    match code {
+        ErrorCode::Internal => (
+            "An unexpected error occurred.",
+            "Ein unerwarteter Fehler ist aufgetreten.",
+        ),
+        ErrorCode::Serialization => (
+            "The data could not be processed.",
+            "Die Daten konnten nicht verarbeitet werden.",
+        ),
+        ErrorCode::Timeout => (
+            "The operation timed out.",
+            "Der Vorgang hat zu lange gedauert.",
+        ),
+        ErrorCode::ResourceNotFound => (
+            "The requested item could not be found.",
+            "Der angeforderte Eintrag wurde nicht gefunden.",
+        ),
+        ErrorCode::Unsupported => (
+            "This is not supported on this device.",
+            "Das wird auf diesem Gerät nicht unterstützt.",
+        ),
+        ErrorCode::Cancelled => ("The action was cancelled.", "Die Aktion wurde abgebrochen."),
+        ErrorCode::AlreadyInProgress => (
+            "Another request is already in progress.",
+            "Eine andere Anfrage läuft bereits.",
+        ),
+        ErrorCode::ProcessingFailed => (
+            "The file could not be processed.",
+            "Die Datei konnte nicht verarbeitet werden.",
+        ),
+        ErrorCode::SecurityViolation => (
+            "The action was blocked for security reasons.",
+            "Die Aktion wurde aus Sicherheitsgründen blockiert.",
+        ),
+        ErrorCode::NotFound => (
+            "The extension could not be found.",
+            "Die Erweiterung wurde nicht gefunden.",
+        ),
+        ErrorCode::PermissionDenied => ("Permission denied.", "Zugriff verweigert."),
+        ErrorCode::MutexPoisoned => (
+            "An internal state is inconsistent. Please restart the app.",
+            "Ein interner Zustand ist inkonsistent. Bitte starte die App neu.",
+        ),
+        ErrorCode::PermissionPromptRequired => (
+            "The extension needs your permission for this action.",
+            "Die Erweiterung benötigt deine Erlaubnis für diese Aktion.",
+        ),
+        ErrorCode::PermissionPromptTimeout => (
+            "The permission request was not answered in time.",
+            "Die Berechtigungsanfrage wurde nicht rechtzeitig beantwortet.",
+        ),
+        ErrorCode::PasswordRequired => (
+            "Please enter the vault password.",
+            "Bitte gib das Vault-Passwort ein.",
+        ),
+        ErrorCode::InvalidPassword => ("The password is incorrect.", "Das Passwort ist falsch."),
+        ErrorCode::Database => (
+            "A database operation failed.",
+            "Ein Datenbankvorgang ist fehlgeschlagen.",
+        ),
+        ErrorCode::Filesystem | ErrorCode::FilesystemWithPath => (
+            "A file could not be read or written.",
+            "Eine Datei konnte nicht gelesen oder geschrieben werden.",
+        ),
+        ErrorCode::Http | ErrorCode::Web => (
+            "The network request failed.",
+            "Die Netzwerkanfrage ist fehlgeschlagen.",
+        ),
+        ErrorCode::RemoteAuthentication => (
+            "The server rejected the login.",
+            "Der Server hat die Anmeldung abgelehnt.",
+        ),
+        ErrorCode::PortUnavailable => (
+            "The port is already in use.",
+            "Der Port wird bereits verwendet.",
+        ),
+        ErrorCode::Shell => (
+            "The command could not be run.",
+            "Der Befehl konnte nicht ausgeführt werden.",
+        ),
+        ErrorCode::Manifest => (
+            "The extension manifest is invalid.",
+            "Das Manifest der Erweiterung ist ungültig.",
+        ),
+        ErrorCode::Validation => ("The input is invalid.", "Die Eingabe ist ungültig."),
+        ErrorCode::IncompatibleHostVersion => (
+            "The extension is not compatible with this version of the app.",
+            "Die Erweiterung ist mit dieser App-Version nicht kompatibel.",
+        ),
+        ErrorCode::InvalidPublicKey => (
+            "The public key of the extension is invalid.",
+            "Der öffentliche Schlüssel der Erweiterung ist ungültig.",
+        ),
+        ErrorCode::InvalidSignature | ErrorCode::SignatureVerificationFailed => (
+            "The signature of the extension could not be verified.",
+            "Die Signatur der Erweiterung konnte nicht überprüft werden.",
+        ),
+        ErrorCode::CalculateHash => (
+            "The checksum could not be calculated.",
+            "Die Prüfsumme konnte nicht berechnet werden.",
+        ),
+        ErrorCode::InvalidActionString => (
+            "The requested permission is invalid.",
+            "Die angeforderte Berechtigung ist ungültig.",
+        ),
+        ErrorCode::Crypto => (
+            "Encryption or decryption failed.",
+            "Ver- oder Entschlüsselung ist fehlgeschlagen.",
+        ),
+        ErrorCode::Installation => (
+            "The extension could not be installed.",
+            "Die Erweiterung konnte nicht installiert werden.",
+        ),
+        ErrorCode::Storage => (
+            "The storage operation failed.",
+            "Der Speichervorgang ist fehlgeschlagen.",
+        ),
+        ErrorCode::StorageConnection => (
+            "The storage is not reachable.",
+            "Der Speicher ist nicht erreichbar.",
+        ),
+        ErrorCode::StorageQuotaExceeded => ("The storage is full.", "Der Speicher ist voll."),
+        ErrorCode::LimitExceeded => (
+            "A limit was exceeded. Please try again later.",
+            "Ein Limit wurde überschritten. Bitte versuche es später erneut.",
+        ),
+        ErrorCode::InvalidQuery => (
+            "The database query is invalid.",
+            "Die Datenbankabfrage ist ungültig.",
+        ),
+        ErrorCode::Transaction => (
+            "The changes could not be saved.",
+            "Die Änderungen konnten nicht gespeichert werden.",
+        ),
+        ErrorCode::Migration => (
+            "The database could not be updated.",
+            "Die Datenbank konnte nicht aktualisiert werden.",
+        ),
+        ErrorCode::Connection => (
+            "The vault could not be opened.",
+            "Der Vault konnte nicht geöffnet werden.",
+        ),
+        ErrorCode::Crdt => (
+            "Synchronization data could not be prepared.",
+            "Die Synchronisationsdaten konnten nicht vorbereitet werden.",
+        ),
+        ErrorCode::VaultAlreadyExists => (
+            "A vault with this name already exists.",
+            "Ein Vault mit diesem Namen existiert bereits.",
+        ),
+        ErrorCode::VaultAlreadyOpenElsewhere => (
+            "This vault is already open in another window.",
+            "Dieser Vault ist bereits in einem anderen Fenster geöffnet.",
+        ),
+        ErrorCode::VaultBusy => (
+            "The vault is in use by another program.",
+            "Der Vault wird von einem anderen Programm verwendet.",
+        ),
+        ErrorCode::VaultAlreadyMountedInProcess => (
+            "Another vault is still open. Close it first.",
+            "Ein anderer Vault ist noch geöffnet. Schließe ihn zuerst.",
+        ),
+        ErrorCode::VaultLockedOut => (
+            "Too many failed attempts. Please wait before trying again.",
+            "Zu viele Fehlversuche. Bitte warte, bevor du es erneut versuchst.",
+        ),
+        ErrorCode::VaultNotOpen => ("No vault is open.", "Es ist kein Vault geöffnet."),
+        ErrorCode::SyncInvalidConfig => (
+            "The sync rule is not configured correctly.",
+            "Die Synchronisationsregel ist nicht richtig konfiguriert.",
+        ),
+        ErrorCode::SyncProvider => (
+            "The sync source or target is not reachable.",
+            "Quelle oder Ziel der Synchronisation ist nicht erreichbar.",
+        ),
+        ErrorCode::SyncEngine => (
+            "Synchronization failed.",
+            "Die Synchronisation ist fehlgeschlagen.",
+        ),
+        ErrorCode::SyncNotRunning => (
+            "The sync rule is not running.",
+            "Die Synchronisationsregel läuft nicht.",
+        ),
+        ErrorCode::BridgeNotRunning => (
+            "The browser connection is not running.",
+            "Die Browser-Verbindung ist nicht aktiv.",
+        ),
+        ErrorCode::BridgeAlreadyRunning => (
+            "The browser connection is already running.",
+            "Die Browser-Verbindung ist bereits aktiv.",
+        ),
+        ErrorCode::BridgeUnauthorized | ErrorCode::BridgeAuthorizationDenied => (
+            "The browser extension is not authorized.",
+            "Die Browser-Erweiterung ist nicht autorisiert.",
+        ),
+        ErrorCode::BridgeConnection => (
+            "The connection to the browser failed.",
+            "Die Verbindung zum Browser ist fehlgeschlagen.",
+        ),
+        ErrorCode::LocalApiNotRunning => (
+            "The local API is not running.",
+            "Die lokale API ist nicht aktiv.",
+        ),
+        ErrorCode::LocalApiAlreadyRunning => (
+            "The local API is already running.",
+            "Die lokale API ist bereits aktiv.",
+        ),
+        ErrorCode::VaultSyncTransport => (
+            "The sync server is not reachable.",
+            "Der Sync-Server ist nicht erreichbar.",
+        ),
+        ErrorCode::VaultSyncEnvelope => (
+            "Received sync data is invalid.",
+            "Empfangene Synchronisationsdaten sind ungültig.",
+        ),
+        ErrorCode::VaultSyncShare => (
+            "The shared space could not be synchronized.",
+            "Der geteilte Space konnte nicht synchronisiert werden.",
+        ),
+    }
+}
@@ -0,0 +1,194 @@
+// src-tauri/src/error_code/mod.rs
+//!
+//! Stable error codes shared by all command errors.
+//!
+//! Every error type that reaches the frontend or an extension implements
+//! [`CodedError`] and serializes at least `code`, `message` and `details`
+//! (see [`ErrorEnvelope`]). Callers branch on the numeric `code` instead of
+//! matching message strings, which change with wording and locale.
+//!
+//! Codes are grouped by range and never reused:
+//!
+//! | Range  | Area                                  |
+//! |--------|---------------------------------------|
+//! | 1xx    | Generic (internal, serialization)     |
+//! | 1xxx   | Security and permissions              |
+//! | 2xxx   | I/O (database, filesystem, network)   |
+//! | 3xxx   | Manifests and validation              |
+//! | 4xxx   | Keys, signatures and hashes           |
+//! | 5xxx   | Extension installation                |
+//! | 6xxx   | Remote storage                        |
+//! | 7xxx   | Limits                                |
+//! | 8xxx   | Vault database                        |
+//! | 9xxx   | File sync                             |
+//! | 10xxx  | Browser bridge                        |
+//! | 11xxx  | Vault sync                            |
+//!
+//! The 1xxx to 7xxx codes are the former `ExtensionErrorCode` values, so
+//! extensions checking e.g. `code === 1002` keep working.
+//!
+//! `message` stays technical; `userMessage` is the localized text to show
+//! (see [`messages`]).
+//!
+//! The TypeScript binding is an `enum` with the same names and values, so
+//! the frontend compares against `ErrorCode.VaultBusy` instead of `8012`.
+
+pub mod commands;
+pub mod messages;
+
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Stable error codes for frontend and SDK handling. Serialized as a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, TS)]
+#[ts(export, repr(enum))]
+pub enum ErrorCode {
+    Internal = 100,
+    Serialization = 101,
+    Timeout = 102,
+    ResourceNotFound = 103,
+    Unsupported = 104,
+    Cancelled = 105,
+    AlreadyInProgress = 106,
+    ProcessingFailed = 107,
+
+    SecurityViolation = 1000,
+    NotFound = 1001,
+    PermissionDenied = 1002,
+    MutexPoisoned = 1003,
+    PermissionPromptRequired = 1004,
+    PermissionPromptTimeout = 1005,
+    PasswordRequired = 1006,
+    InvalidPassword = 1007,
+
+    Database = 2000,
+    Filesystem = 2001,
+    Http = 2002,
+    Shell = 2003,
+    FilesystemWithPath = 2004,
+    Web = 2005,
+    RemoteAuthentication = 2006,
+    PortUnavailable = 2007,
+
+    Manifest = 3000,
+    Validation = 3001,
+    IncompatibleHostVersion = 3002,
+
+    InvalidPublicKey = 4000,
+    InvalidSignature = 4001,
+    SignatureVerificationFailed = 4002,
+    CalculateHash = 4003,
+    InvalidActionString = 4004,
+    Crypto = 4005,
+
+    Installation = 5000,
+    Storage = 6000,
+    StorageConnection = 6001,
+    StorageQuotaExceeded = 6002,
+    LimitExceeded = 7000,
+
+    InvalidQuery = 8000,
+    Transaction = 8001,
+    Migration = 8002,
+    Connection = 8003,
+    Crdt = 8004,
+    VaultAlreadyExists = 8010,
+    VaultAlreadyOpenElsewhere = 8011,
+    VaultBusy = 8012,
+    VaultAlreadyMountedInProcess = 8013,
+    VaultLockedOut = 8014,
+    VaultNotOpen = 8015,
+
+    SyncInvalidConfig = 9000,
+    SyncProvider = 9001,
+    SyncEngine = 9002,
+    SyncNotRunning = 9003,
+
+    BridgeNotRunning = 10000,
+    BridgeAlreadyRunning = 10001,
+    BridgeUnauthorized = 10002,
+    BridgeAuthorizationDenied = 10003,
+    BridgeConnection = 10004,
+    LocalApiNotRunning = 10005,
+    LocalApiAlreadyRunning = 10006,
+
+    VaultSyncTransport = 11000,
+    VaultSyncEnvelope = 11001,
+    VaultSyncShare = 11002,
+}
+
+impl Serialize for ErrorCode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u16(*self as u16)
+    }
+}
+
+/// Fields every serialized command error carries. Error types may add
+/// their own fields next to these (e.g. `type`) for older callers.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorEnvelope {
+    #[ts(type = "number")]
+    pub code: ErrorCode,
+    /// Technical description (English)
+    pub message: String,
+    /// Localized message for the user, in the locale of the UI
+    pub user_message: String,
+    /// Structured context of the error, `null` if there is none
+    #[ts(type = "unknown")]
+    pub details: Option<serde_json::Value>,
+}
+
+/// An error with a stable [`ErrorCode`]
+pub trait CodedError: std::fmt::Display {
+    fn error_code(&self) -> ErrorCode;
+
+    /// Structured context for the frontend, e.g. the path of a locked vault
+    fn error_details(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Localized message for the user
+    fn user_message(&self) -> &'static str {
+        messages::user_message(self.error_code(), messages::current_language())
+    }
+
+    fn envelope(&self) -> ErrorEnvelope {
+        ErrorEnvelope {
+            code: self.error_code(),
+            message: self.to_string(),
+            user_message: self.user_message().to_string(),
+            details: self.error_details(),
+        }
+    }
+}
+
+/// Serializes an error as `{ code, type, message, userMessage, details }`.
+/// `tagged` is its derived `{ type, details }` representation, generated as
+/// inherent functions with `remote = "Self"` like for `DatabaseError`.
+pub fn serialize_tagged<E, S>(
+    error: &E,
+    tagged: &serde_json::Value,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    E: CodedError + ?Sized,
+    S: serde::Serializer,
+{
+    use serde::ser::SerializeStruct;
+
+    let mut state = serializer.serialize_struct("Error", 5)?;
+    state.serialize_field("code", &error.error_code())?;
+    state.serialize_field("type", &tagged["type"])?;
+    state.serialize_field("message", &error.to_string())?;
+    state.serialize_field("userMessage", error.user_message())?;
+    state.serialize_field("details", &tagged["details"])?;
+    state.end()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,153 @@
+// src-tauri/src/error_code/tests.rs
+//!
+//! Tests for the shared error envelope
+
+use super::messages::{current_language, set_error_locale, user_message, MessageLanguage};
+use super::{CodedError, ErrorCode};
+use crate::auth::AuthError;
+use crate::database::error::DatabaseError;
+use crate::extension::error::ExtensionError;
+use crate::external_bridge::BridgeError;
+use crate::file_sync::commands::FileSyncCommandError;
+use crate::passwords::autofill::error::AutofillError;
+use crate::remote_storage::StorageError;
+use serde_json::json;
+
+#[test]
+fn test_error_code_serializes_as_number() {
+    assert_eq!(
+        serde_json::to_value(ErrorCode::PermissionDenied).unwrap(),
+        json!(1002)
+    );
+    assert_eq!(
+        serde_json::to_value(ErrorCode::VaultBusy).unwrap(),
+        json!(8012)
+    );
+}
+
+#[test]
+fn test_database_error_keeps_type_and_details() {
+    let error = DatabaseError::VaultBusy {
+        path: "/vaults/a.db".to_string(),
+        reason: "database is locked".to_string(),
+    };
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(json["code"], json!(8012));
+    assert_eq!(json["type"], json!("VaultBusy"));
+    assert_eq!(
+        json["message"],
+        json!("Vault at '/vaults/a.db' is busy: database is locked")
+    );
+    assert_eq!(
+        json["details"],
+        json!({ "path": "/vaults/a.db", "reason": "database is locked" })
+    );
+    assert_eq!(json["userMessage"], json!(error.user_message()));
+    assert_eq!(error.error_details(), Some(json["details"].clone()));
+}
+
+#[test]
+fn test_extension_error_nests_database_cause() {
+    let error = ExtensionError::from(DatabaseError::VaultLockedOut {
+        failed_attempts: 5,
+        retry_after_secs: 30,
+    });
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(json["code"], json!(2000));
+    assert_eq!(json["details"]["cause"]["code"], json!(8014));
+    assert_eq!(json["userMessage"], json["details"]["cause"]["userMessage"]);
+    assert_eq!(
+        json["details"]["cause"]["details"]["retryAfterSecs"],
+        json!(30)
+    );
+}
+
+#[test]
+fn test_string_errors_serialize_as_envelope() {
+    let error = FileSyncCommandError::NotRunning("rule-1".into());
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(
+        json,
+        json!({
+            "code": 9003,
+            "message": "Not running: rule-1",
+            "userMessage": error.user_message(),
+            "details": null
+        })
+    );
+
+    let error = BridgeError::AuthorizationDenied;
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(
+        json,
+        json!({
+            "code": 10003,
+            "message": "Authorization denied",
+            "userMessage": error.user_message(),
+            "details": null
+        })
+    );
+}
+
+#[test]
+fn test_tagged_errors_keep_type_and_details() {
+    let error = StorageError::QuotaExceeded {
+        backend_id: "b1".to_string(),
+        quota: 100,
+        used: 90,
+        needed: 20,
+    };
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(json["code"], json!(6002));
+    assert_eq!(json["type"], json!("QuotaExceeded"));
+    assert_eq!(
+        json["details"],
+        json!({ "backend_id": "b1", "quota": 100, "used": 90, "needed": 20 })
+    );
+    assert_eq!(json["userMessage"], json!(error.user_message()));
+
+    let error = StorageError::Internal {
+        reason: "boom".to_string(),
+    };
+    assert_eq!(error.error_details(), Some(json!({ "reason": "boom" })));
+}
+
+#[test]
+fn test_wrapped_errors_keep_the_inner_code() {
+    let error = AutofillError::Auth(AuthError::InvalidPassword);
+    assert_eq!(error.error_code(), ErrorCode::InvalidPassword);
+
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(json["code"], json!(1007));
+    assert_eq!(json["type"], json!("Auth"));
+    assert_eq!(json["details"]["type"], json!("InvalidPassword"));
+    assert_eq!(json["details"]["details"], json!(null));
+}
+
+#[test]
+fn test_message_language_from_locale() {
+    assert_eq!(MessageLanguage::from_locale("de"), MessageLanguage::De);
+    assert_eq!(MessageLanguage::from_locale("de-CH"), MessageLanguage::De);
+    assert_eq!(MessageLanguage::from_locale("DE_at"), MessageLanguage::De);
+    assert_eq!(MessageLanguage::from_locale("en-US"), MessageLanguage::En);
+    assert_eq!(MessageLanguage::from_locale("fr"), MessageLanguage::En);
+    assert_eq!(MessageLanguage::from_locale(""), MessageLanguage::En);
+}
+
+#[test]
+fn test_user_messages_are_localized() {
+    assert_eq!(
+        user_message(ErrorCode::VaultAlreadyOpenElsewhere, MessageLanguage::En),
+        "This vault is already open in another window."
+    );
+    assert_eq!(
+        user_message(ErrorCode::VaultAlreadyOpenElsewhere, MessageLanguage::De),
+        "Dieser Vault ist bereits in einem anderen Fenster geöffnet."
+    );
+}
+
+#[test]
+fn test_messages_are_german_until_a_locale_is_set() {
+    set_error_locale("");
+    assert_eq!(current_language(), MessageLanguage::De);
+}
@@ -0,0 +1,115 @@
+//! Typed event registry
+//!
+//! Event names are generated from `src/constants/eventNames.json` into
+//! [`crate::event_names`]; this module pins each of them to one payload type
+//! (exported via ts-rs) and a schema version. Every event reaches the
+//! frontend wrapped in an [`EventEnvelope`] (`{ v, payload }`), so listeners
+//! can tell which payload shape they got and old shapes can be phased out
+//! by bumping the version instead of silently changing fields.
+//!
+//! Emit through [`emit_to_main`] / [`emit`] rather than `Emitter::emit_to`
+//! with a name constant, so the payload type can't drift from the name.
+//! The `haextension:*` events pushed into extension webviews are part of the
+//! vault-sdk protocol and are not wrapped.
+
+pub mod payloads;
+
+#[cfg(test)]
+mod tests;
+
+use crate::codes::types::QrScanRequested;
+use crate::crdt::bulk_apply::RemoteApplyProgress;
+use crate::database::password_policy::PasswordRotationStatus;
+use crate::event_names::*;
+use crate::extension::dev_logs::DevLogEntry;
+use crate::media::capture::types::MediaCaptureRequested;
+use crate::remote_storage::migration::BackendMigrationProgress;
+use crate::sync::orchestrator::{SyncCycleReport, SyncFailure, SyncProgress};
+use payloads::*;
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Runtime};
+use ts_rs::TS;
+
+/// An event with a registered name and payload schema.
+pub trait VaultEvent: Serialize {
+    /// Event name from eventNames.json
+    const NAME: &'static str;
+    /// Payload schema version, bumped on breaking payload changes
+    const VERSION: u32;
+}
+
+/// Wire format of every registered event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct EventEnvelope<T> {
+    /// Payload schema version
+    pub v: u32,
+    pub payload: T,
+}
+
+impl<'a, E: VaultEvent> EventEnvelope<&'a E> {
+    pub fn of(event: &'a E) -> Self {
+        Self {
+            v: E::VERSION,
+            payload: event,
+        }
+    }
+}
+
+/// Emits `event` to the main window only. Extension webviews never see it.
+pub fn emit_to_main<R: Runtime, E: VaultEvent>(
+    emitter: &impl Emitter<R>,
+    event: &E,
+) -> tauri::Result<()> {
+    emitter.emit_to("main", E::NAME, EventEnvelope::of(event))
+}
+
+/// Emits `event` to every listener.
+pub fn emit<R: Runtime, E: VaultEvent>(emitter: &impl Emitter<R>, event: &E) -> tauri::Result<()> {
+    emitter.emit(E::NAME, EventEnvelope::of(event))
+}
+
+/// `Payload => EVENT_NAME, version;` — implements [`VaultEvent`] and adds
+/// the event to [`REGISTERED_EVENTS`]. Platform-specific payloads keep their
+/// `#[cfg]`; their names stay registered everywhere.
+macro_rules! register_events {
+    ($($(#[$attr:meta])* $payload:ty => $name:ident, $version:literal;)*) => {
+        $(
+            $(#[$attr])*
+            impl VaultEvent for $payload {
+                const NAME: &'static str = $name;
+                const VERSION: u32 = $version;
+            }
+        )*
+
+        /// Name and schema version of every registered event.
+        pub const REGISTERED_EVENTS: &[(&str, u32)] = &[$(($name, $version)),*];
+    };
+}
+
+register_events! {
+    ExtensionWindowClosed => EVENT_EXTENSION_WINDOW_CLOSED, 1;
+    ExtensionAutoStartRequest => EVENT_EXTENSION_AUTO_START_REQUEST, 1;
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    crate::extension::webview::monitor::ResourceLimitWarning => EVENT_EXTENSION_RESOURCE_LIMIT_EXCEEDED, 1;
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    crate::extension::webview::monitor::ExtensionTerminated => EVENT_EXTENSION_TERMINATED, 1;
+    crate::extension::recovery::MissingExtensionsDetected => EVENT_EXTENSION_MISSING_DETECTED, 1;
+    DirtyTablesChanged => EVENT_CRDT_DIRTY_TABLES_CHANGED, 1;
+    RemoteApplyProgress => EVENT_CRDT_REMOTE_APPLY_PROGRESS, 1;
+    PeerStorageStateChanged => EVENT_PEER_STORAGE_STATE_CHANGED, 1;
+    PeerConnectionChanged => EVENT_PEER_CONNECTION_CHANGED, 1;
+    LocalSyncCompleted => EVENT_LOCAL_SYNC_COMPLETED, 1;
+    LocalSyncError => EVENT_LOCAL_SYNC_ERROR, 1;
+    SyncStarted => EVENT_SYNC_STARTED, 1;
+    SyncProgress => EVENT_SYNC_PROGRESS, 1;
+    SyncCycleReport => EVENT_SYNC_COMPLETED, 1;
+    SyncFailure => EVENT_SYNC_FAILED, 1;
+    PasswordRotationStatus => EVENT_VAULT_PASSWORD_ROTATION_DUE, 1;
+    DevLogEntry => EVENT_DEV_EXTENSION_LOG, 1;
+    QrScanRequested => EVENT_CODES_SCAN_REQUESTED, 1;
+    MediaCaptureRequested => EVENT_MEDIA_CAPTURE_REQUESTED, 1;
+    BackendMigrationProgress => EVENT_STORAGE_MIGRATION_PROGRESS, 1;
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    crate::external_bridge::credentials::CredentialPromptRequested => EVENT_EXTERNAL_BRIDGE_CREDENTIAL_PROMPT, 1;
+}
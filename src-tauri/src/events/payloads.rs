@@ -0,0 +1,87 @@
+//! Payloads of registered events that have no home type in their module.
+
+use crate::peer_storage::endpoint::ConnectionDiagnostics;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Payload of `extension:window-closed`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionWindowClosed {
+    pub window_id: String,
+}
+
+/// Payload of `extension:auto-start-request`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionAutoStartRequest {
+    pub extension_id: String,
+}
+
+/// Payload of `crdt:dirty-tables-changed`. Listeners re-read the dirty
+/// tables themselves.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DirtyTablesChanged {}
+
+/// Why the peer storage endpoint changed state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "kebab-case")]
+pub enum PeerStorageStateReason {
+    EndpointClosed,
+    UserStopped,
+}
+
+/// Payload of `peer-storage:state-changed`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerStorageStateChanged {
+    pub running: bool,
+    pub reason: PeerStorageStateReason,
+    /// How long the endpoint was alive before it closed, in seconds
+    #[ts(type = "number")]
+    pub uptime_secs: u64,
+}
+
+/// Payload of `peer-storage:connection-changed`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerConnectionChanged {
+    /// Endpoint ID of the remote peer whose path changed
+    pub node_id: String,
+    pub diagnostics: ConnectionDiagnostics,
+}
+
+/// Payload of `local-sync-completed`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalSyncCompleted {
+    pub space_id: String,
+    pub tables: Vec<String>,
+}
+
+/// Payload of `local-sync-error`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalSyncError {
+    pub space_id: String,
+    pub error: String,
+    pub reconnecting: bool,
+    pub endpoint_closed: bool,
+    pub attempt: u32,
+}
+
+/// Payload of `sync:started`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStarted {
+    pub peer_id: String,
+}
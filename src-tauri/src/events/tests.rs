@@ -0,0 +1,50 @@
+// src-tauri/src/events/tests.rs
+//!
+//! Tests for the event registry and envelope
+
+use super::payloads::{DirtyTablesChanged, PeerStorageStateChanged, PeerStorageStateReason};
+use super::{EventEnvelope, VaultEvent, REGISTERED_EVENTS};
+use crate::event_names::EVENT_PEER_STORAGE_STATE_CHANGED;
+use std::collections::HashSet;
+
+#[test]
+fn test_envelope_wraps_payload_with_version() {
+    let event = PeerStorageStateChanged {
+        running: false,
+        reason: PeerStorageStateReason::EndpointClosed,
+        uptime_secs: 42,
+    };
+
+    let json = serde_json::to_value(EventEnvelope::of(&event)).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "v": 1,
+            "payload": {
+                "running": false,
+                "reason": "endpoint-closed",
+                "uptimeSecs": 42,
+            },
+        })
+    );
+    assert_eq!(
+        PeerStorageStateChanged::NAME,
+        EVENT_PEER_STORAGE_STATE_CHANGED
+    );
+}
+
+#[test]
+fn test_unit_payload_serializes_as_empty_object() {
+    let json = serde_json::to_value(EventEnvelope::of(&DirtyTablesChanged {})).unwrap();
+    assert_eq!(json, serde_json::json!({ "v": 1, "payload": {} }));
+}
+
+#[test]
+fn test_registered_events_are_unique() {
+    let mut seen = HashSet::new();
+    for (name, version) in REGISTERED_EVENTS {
+        assert!(!name.is_empty());
+        assert!(*version >= 1, "{name} has version 0");
+        assert!(seen.insert(*name), "{name} registered twice");
+    }
+}
@@ -0,0 +1,13 @@
+// src-tauri/src/extension/capabilities/commands.rs
+
+use super::ExtensionCapabilities;
+use crate::auth::biometric;
+use crate::extension::core::compatibility::HOST_API_VERSION;
+
+/// Optional subsystems available on this platform and build
+#[tauri::command]
+pub async fn extension_get_capabilities() -> ExtensionCapabilities {
+    let platform = std::env::consts::OS;
+    let desktop_biometrics = !cfg!(mobile) && biometric::status().await.available;
+    ExtensionCapabilities::for_platform(platform, HOST_API_VERSION, desktop_biometrics)
+}
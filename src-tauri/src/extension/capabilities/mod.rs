@@ -0,0 +1,63 @@
+// src-tauri/src/extension/capabilities/mod.rs
+//!
+//! Capability discovery for extensions
+//!
+//! Several subsystems only exist on some platforms: native webview windows
+//! and the external bridge are desktop-only, PTY shells are missing on iOS.
+//! `extension_get_capabilities` tells the SDK what this host offers, so
+//! extensions can feature-detect at runtime instead of guessing from the
+//! platform name.
+
+pub mod commands;
+
+#[cfg(test)]
+mod tests;
+
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Optional subsystems available on this host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionCapabilities {
+    /// `linux`, `macos`, `windows`, `android` or `ios`
+    pub platform: String,
+    /// Host API version the extension is checked against
+    pub host_api_version: String,
+    /// Extensions can open in native windows (otherwise iframe only)
+    pub webview_windows: bool,
+    /// PTY shell sessions
+    pub shell: bool,
+    /// LocalSend-compatible file transfer; not part of this build
+    pub localsend: bool,
+    /// External bridge for browser extensions and local clients
+    pub external_bridge: bool,
+    /// Biometric authentication: on desktop a platform authenticator is set
+    /// up, on mobile the biometry plugin is present
+    pub biometrics: bool,
+    /// Photo capture and QR scanning through the main window
+    pub camera: bool,
+}
+
+fn is_mobile(platform: &str) -> bool {
+    matches!(platform, "android" | "ios")
+}
+
+impl ExtensionCapabilities {
+    /// Capabilities of a host running on `platform`. `desktop_biometrics`
+    /// is the runtime status of the desktop authenticator, everything else
+    /// follows from the platform.
+    pub fn for_platform(platform: &str, host_api_version: &str, desktop_biometrics: bool) -> Self {
+        Self {
+            platform: platform.to_string(),
+            host_api_version: host_api_version.to_string(),
+            webview_windows: !is_mobile(platform),
+            shell: platform != "ios",
+            localsend: false,
+            external_bridge: !is_mobile(platform),
+            biometrics: is_mobile(platform) || desktop_biometrics,
+            camera: true,
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! Tests for capability discovery
+
+use super::*;
+
+#[test]
+fn test_desktop_capabilities() {
+    let capabilities = ExtensionCapabilities::for_platform("linux", "3.2.0", false);
+    assert!(capabilities.webview_windows);
+    assert!(capabilities.shell);
+    assert!(capabilities.external_bridge);
+    assert!(!capabilities.biometrics);
+    assert!(!capabilities.localsend);
+}
+
+#[test]
+fn test_mobile_capabilities() {
+    let android = ExtensionCapabilities::for_platform("android", "3.2.0", false);
+    assert!(!android.webview_windows);
+    assert!(!android.external_bridge);
+    assert!(android.shell, "Android ships its own PTY shell");
+    assert!(android.biometrics, "the biometry plugin is always present");
+
+    let ios = ExtensionCapabilities::for_platform("ios", "3.2.0", true);
+    assert!(!ios.shell);
+    assert!(ios.camera);
+}
@@ -0,0 +1,140 @@
+// src-tauri/src/extension/core/compatibility.rs
+//
+// Host API version gating.
+//
+// Extensions declare the range of host API versions they work with through
+// `minHostVersion` and `maxHostVersion` in their manifest. Bundles outside
+// the range are rejected at preview and install, and skipped when loading,
+// so an extension built against a newer SDK fails with a clear error instead
+// of calling commands this host doesn't have.
+
+use crate::extension::core::manifest::ExtensionManifest;
+use crate::extension::error::ExtensionError;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+
+use super::versions::compare_versions;
+
+/// Version of the API this host provides to extensions. Follows the major
+/// and minor version of `@haex-space/vault-sdk`; bump it together with
+/// commands added to or removed from the extension API.
+pub const HOST_API_VERSION: &str = "3.2.0";
+
+/// Checks that `version` consists of numeric components with an optional
+/// pre-release suffix (`3`, `3.2`, `3.2.0-beta.1`).
+fn validate_host_version(field: &str, version: &str) -> Result<(), ExtensionError> {
+    let core = version.split_once('-').map_or(version, |(core, _)| core);
+    let valid = !core.is_empty()
+        && core
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    if valid {
+        Ok(())
+    } else {
+        Err(ExtensionError::ManifestError {
+            reason: format!("Invalid {field} '{version}'"),
+        })
+    }
+}
+
+/// Whether `host_version` lies within `min..=max`. A maximum only limits the
+/// components it names: `3` accepts every 3.x host, `3.2` every 3.2.x.
+pub fn host_version_in_range(host_version: &str, min: Option<&str>, max: Option<&str>) -> bool {
+    if min.is_some_and(|min| compare_versions(host_version, min) == Ordering::Less) {
+        return false;
+    }
+    if let Some(max) = max {
+        let components = max.split('-').next().unwrap_or(max).split('.').count();
+        let host_core = host_version.split('-').next().unwrap_or(host_version);
+        let truncated: Vec<&str> = host_core.split('.').take(components).collect();
+        if compare_versions(&truncated.join("."), max) == Ordering::Greater {
+            return false;
+        }
+    }
+    true
+}
+
+/// Checks the host version range of a manifest against `host_version`
+pub fn check_host_compatibility(
+    manifest: &ExtensionManifest,
+    host_version: &str,
+) -> Result<(), ExtensionError> {
+    let min = manifest.min_host_version.as_deref();
+    let max = manifest.max_host_version.as_deref();
+    if let Some(min) = min {
+        validate_host_version("minHostVersion", min)?;
+    }
+    if let Some(max) = max {
+        validate_host_version("maxHostVersion", max)?;
+    }
+
+    if host_version_in_range(host_version, min, max) {
+        Ok(())
+    } else {
+        Err(ExtensionError::IncompatibleHostVersion {
+            extension_name: manifest.name.clone(),
+            min_host_version: manifest.min_host_version.clone(),
+            max_host_version: manifest.max_host_version.clone(),
+            host_version: host_version.to_string(),
+        })
+    }
+}
+
+/// Reads `minHostVersion` and `maxHostVersion` of a bundle's manifest.json.
+/// The loader builds manifests from the database, which doesn't store them.
+pub fn read_manifest_host_versions(manifest_path: &Path) -> (Option<String>, Option<String>) {
+    let Some(value) = fs::read_to_string(manifest_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    else {
+        return (None, None);
+    };
+    let field = |name: &str| value.get(name).and_then(|v| v.as_str()).map(String::from);
+    (field("minHostVersion"), field("maxHostVersion"))
+}
+
+/// Host API version extensions are checked against
+#[tauri::command]
+pub fn host_get_api_version() -> String {
+    HOST_API_VERSION.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_version_in_range() {
+        assert!(host_version_in_range("3.2.0", None, None));
+        assert!(host_version_in_range("3.2.0", Some("3.0"), None));
+        assert!(host_version_in_range("3.2.0", Some("3.2.0"), Some("3.2.0")));
+        assert!(!host_version_in_range("3.2.0", Some("3.3"), None));
+        assert!(!host_version_in_range("3.2.0", Some("4.0.0-beta"), None));
+    }
+
+    #[test]
+    fn test_max_host_version_limits_named_components() {
+        assert!(host_version_in_range("3.2.0", None, Some("3")));
+        assert!(host_version_in_range("3.9.4", None, Some("3.9")));
+        assert!(!host_version_in_range("3.2.0", None, Some("3.1")));
+        assert!(!host_version_in_range("4.0.0", None, Some("3")));
+        assert!(!host_version_in_range("3.2.1", None, Some("3.2.0")));
+    }
+
+    #[test]
+    fn test_validate_host_version() {
+        for valid in ["3", "3.2", "3.2.0", "3.2.0-beta.1"] {
+            assert!(
+                validate_host_version("minHostVersion", valid).is_ok(),
+                "{valid}"
+            );
+        }
+        for invalid in ["", "v3", "3.x", "3..0", "-beta"] {
+            assert!(
+                validate_host_version("minHostVersion", invalid).is_err(),
+                "{invalid}"
+            );
+        }
+    }
+}
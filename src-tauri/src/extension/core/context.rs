@@ -3,14 +3,33 @@
 //! Manages the application context (theme, locale, platform, device_id)
 //! that is shared with extensions. Extensions can query this context
 //! and receive updates when it changes.
+//!
+//! Every `extension_context_set` pushes a `CONTEXT_CHANGED_EVENT` into all
+//! open extension webviews. Iframe extensions get the same payload from the
+//! frontend, which posts it to them when it sets the context
+//! (`broadcastContext` in stores/extensions/broadcast.ts). Newly created
+//! webviews receive the current context through an initialization script
+//! before any extension code runs.
+//!
+//! Mobile has no extension webviews, only iframes, so there the commands that
+//! target webviews reach nothing and the frontend relays to the iframes as
+//! usual.
 
-#[cfg(desktop)]
 use crate::extension::error::ExtensionError;
-#[cfg(desktop)]
 use crate::AppState;
 use serde::{Deserialize, Serialize};
-#[cfg(desktop)]
+use std::collections::BTreeMap;
 use tauri::{AppHandle, State};
+use ts_rs::TS;
+
+/// Event pushed to extension webviews when the application context changes.
+/// Matches HAEXTENSION_EVENTS.CONTEXT_CHANGED in vault-sdk.
+pub const CONTEXT_CHANGED_EVENT: &str = "haextension:context:changed";
+
+/// Upper bound for custom CSS variables per context. The variables are
+/// injected into every extension webview, so an unbounded map would turn
+/// each window open into an arbitrarily large script.
+const MAX_CSS_VARIABLES: usize = 256;
 
 // ============================================================================
 // Types
@@ -18,7 +37,8 @@ use tauri::{AppHandle, State};
 
 /// Application context shared with extensions.
 /// Contains theme, locale, platform, and device information.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct ApplicationContext {
     pub theme: String,
@@ -26,6 +46,86 @@ pub struct ApplicationContext {
     #[serde(default)]
     pub platform: String,
     pub device_id: String,
+    /// Custom CSS variables (e.g. `--haex-primary` → `#3b82f6`) applied to
+    /// the extension document root so extensions can follow the host theme.
+    #[serde(default)]
+    pub css_variables: BTreeMap<String, String>,
+}
+
+/// Payload of `CONTEXT_CHANGED_EVENT`. Same shape the frontend posts to
+/// iframe extensions (`{ context }`).
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextChangedPayload {
+    pub context: ApplicationContext,
+}
+
+impl ApplicationContext {
+    /// Validates the custom CSS variables before they are stored or injected.
+    ///
+    /// Names must be custom properties (`--foo-bar`), values must not be able
+    /// to break out of a declaration (`;`, braces, angle brackets).
+    pub fn validate(&self) -> Result<(), ExtensionError> {
+        if self.css_variables.len() > MAX_CSS_VARIABLES {
+            return Err(ExtensionError::ValidationError {
+                reason: format!(
+                    "Too many CSS variables: {} (max {MAX_CSS_VARIABLES})",
+                    self.css_variables.len()
+                ),
+            });
+        }
+
+        for (name, value) in &self.css_variables {
+            let valid_name = name.len() > 2
+                && name.starts_with("--")
+                && name[2..]
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid_name {
+                return Err(ExtensionError::ValidationError {
+                    reason: format!("Invalid CSS variable name: {name}"),
+                });
+            }
+
+            if value
+                .chars()
+                .any(|c| matches!(c, ';' | '{' | '}' | '<' | '>') || c.is_control())
+            {
+                return Err(ExtensionError::ValidationError {
+                    reason: format!("Invalid value for CSS variable {name}"),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds the initialization script injected into new extension webviews.
+    ///
+    /// Exposes the context as `window.__HAEX_CONTEXT__` before any page script
+    /// runs and applies the CSS variables to `document.documentElement`, so
+    /// the first paint already matches the host theme.
+    pub fn to_init_script(&self) -> Result<String, ExtensionError> {
+        let context_json = serde_json::to_string(self)?;
+        Ok(format!(
+            r#"(function () {{
+  var ctx = {context_json};
+  window.__HAEX_CONTEXT__ = ctx;
+  var apply = function () {{
+    var root = document.documentElement;
+    if (!root) return;
+    root.dataset.haexTheme = ctx.theme;
+    root.lang = ctx.locale;
+    Object.keys(ctx.cssVariables || {{}}).forEach(function (name) {{
+      root.style.setProperty(name, ctx.cssVariables[name]);
+    }});
+  }};
+  if (document.documentElement) apply();
+  else document.addEventListener("DOMContentLoaded", apply, {{ once: true }});
+}})();"#
+        ))
+    }
 }
 
 impl Default for ApplicationContext {
@@ -35,17 +135,40 @@ impl Default for ApplicationContext {
             locale: "en".to_string(),
             platform: String::new(),
             device_id: String::new(),
+            css_variables: BTreeMap::new(),
         }
     }
 }
 
+/// Pushes the context to every open extension webview. Iframes have no
+/// Tauri label to target; the frontend posts the context to them itself.
+pub fn broadcast_context(
+    app_handle: &AppHandle,
+    state: &AppState,
+    context: &ApplicationContext,
+) -> Result<(), ExtensionError> {
+    let payload = ContextChangedPayload {
+        context: context.clone(),
+    };
+
+    #[cfg(desktop)]
+    state.extension_webview_manager.emit_to_all_extensions(
+        app_handle,
+        CONTEXT_CHANGED_EVENT,
+        &payload,
+    )?;
+    #[cfg(mobile)]
+    let _ = (app_handle, state, payload);
+
+    Ok(())
+}
+
 // ============================================================================
 // Tauri Commands
 // ============================================================================
 
 /// Get application context (theme, locale, platform, device_id).
 /// Used by extensions to get current application state.
-#[cfg(not(any(target_os = "android", target_os = "ios")))]
 #[tauri::command]
 pub fn extension_context_get(
     state: State<'_, AppState>,
@@ -64,27 +187,37 @@ pub fn extension_context_get(
     Ok(context.clone())
 }
 
-/// Stores the current application context in state for extension access.
-/// This is called when the theme/locale changes so webview extensions can query it.
-#[cfg(not(any(target_os = "android", target_os = "ios")))]
+/// Stores the current application context in state for extension access
+/// and pushes it live to every open extension webview.
+/// This is called when the theme/locale changes.
 #[tauri::command]
 pub fn extension_context_set(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
     context: ApplicationContext,
 ) -> Result<(), ExtensionError> {
     eprintln!(
-        "[Extension] extension_context_set called: theme={}, locale={}, platform={}, device_id={}",
-        context.theme, context.locale, context.platform, context.device_id
+        "[Extension] extension_context_set called: theme={}, locale={}, platform={}, device_id={}, css_variables={}",
+        context.theme,
+        context.locale,
+        context.platform,
+        context.device_id,
+        context.css_variables.len()
     );
-    let mut ctx = state
-        .context
-        .lock()
-        .map_err(|e| ExtensionError::MutexPoisoned {
-            reason: e.to_string(),
-        })?;
-    *ctx = context;
+    context.validate()?;
+
+    {
+        let mut ctx = state
+            .context
+            .lock()
+            .map_err(|e| ExtensionError::MutexPoisoned {
+                reason: e.to_string(),
+            })?;
+        *ctx = context.clone();
+    }
     eprintln!("[Extension] Context updated in state");
-    Ok(())
+
+    broadcast_context(&app_handle, &state, &context)
 }
 
 /// Broadcasts an event to ALL extension webview windows.
@@ -140,3 +273,77 @@ pub fn extension_webview_emit(
         .extension_webview_manager
         .emit_to_all_extension_windows(&app_handle, &extension_id, &event, payload)
 }
+
+/// Mobile variant of [`extension_webview_broadcast`]: there are no extension
+/// webviews to reach, iframes get the event from the frontend.
+#[cfg(any(target_os = "android", target_os = "ios"))]
+#[tauri::command]
+pub fn extension_webview_broadcast(event: String) -> Result<(), ExtensionError> {
+    eprintln!("[Extension] No extension webviews on mobile, not broadcasting '{event}'");
+    Ok(())
+}
+
+/// Mobile variant of [`extension_webview_emit`]. Always `false`, which tells
+/// the caller to deliver the event to the extension's iframe itself.
+#[cfg(any(target_os = "android", target_os = "ios"))]
+#[tauri::command]
+pub fn extension_webview_emit(extension_id: String, event: String) -> Result<bool, ExtensionError> {
+    eprintln!(
+        "[Extension] No webviews on mobile for '{}', event '{}' goes to the iframe",
+        extension_id, event
+    );
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context_with_vars(vars: &[(&str, &str)]) -> ApplicationContext {
+        ApplicationContext {
+            css_variables: vars
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..ApplicationContext::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_accepts_custom_properties() {
+        let ctx = context_with_vars(&[("--haex-primary", "#3b82f6"), ("--radius_lg", "0.5rem")]);
+        assert!(ctx.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_non_custom_property_names() {
+        assert!(context_with_vars(&[("color", "red")]).validate().is_err());
+        assert!(context_with_vars(&[("--", "red")]).validate().is_err());
+        assert!(context_with_vars(&[("--a b", "red")]).validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_declaration_breakout() {
+        assert!(context_with_vars(&[("--x", "red; background: url(x)")])
+            .validate()
+            .is_err());
+        assert!(context_with_vars(&[("--x", "</script>")]).validate().is_err());
+    }
+
+    #[test]
+    fn test_css_variables_default_when_missing() {
+        let ctx: ApplicationContext = serde_json::from_str(
+            r#"{"theme":"light","locale":"de","platform":"linux","deviceId":"d1"}"#,
+        )
+        .unwrap();
+        assert!(ctx.css_variables.is_empty());
+    }
+
+    #[test]
+    fn test_init_script_embeds_context_as_json() {
+        let ctx = context_with_vars(&[("--haex-primary", "#fff")]);
+        let script = ctx.to_init_script().unwrap();
+        assert!(script.contains("window.__HAEX_CONTEXT__"));
+        assert!(script.contains(r#""cssVariables":{"--haex-primary":"#fff"}"#));
+    }
+}
@@ -4,25 +4,34 @@
 
 use crate::database::core::{select_with_crdt, with_connection};
 use crate::database::error::DatabaseError;
-use crate::extension::core::manifest::{EditablePermissions, ExtensionManifest, ExtensionPreview};
+use crate::extension::core::compatibility::{check_host_compatibility, HOST_API_VERSION};
+use crate::extension::core::manifest::{
+    EditablePermissions, ExtensionFilesInstallResult, ExtensionManifest, ExtensionPreview,
+};
 use crate::extension::core::path_utils::{find_icon, validate_path_in_directory};
 use crate::extension::core::types::{copy_directory, Extension, ExtensionSource};
 use crate::extension::crypto::ExtensionCrypto;
 use crate::extension::database::executor::SqlExecutor;
 use crate::extension::error::ExtensionError;
+use crate::extension::filesystem::archive::{extract_zip, ArchiveLimits};
+use crate::extension::permissions::diff::{diff_permissions, PermissionDiff};
 use crate::extension::permissions::manager::PermissionManager;
+use crate::extension::permissions::types::{ExtensionPermission, PermissionStatus};
 use crate::extension::utils::validate_public_key;
 use super::queries::{
-    SQL_INSERT_EXTENSION, SQL_INSERT_EXTENSION_PERMISSION, SQL_SELECT_EXTENSION_ID_BY_PUBKEY_NAME,
-    SQL_UPDATE_EXTENSION_METADATA, SQL_UPDATE_EXTENSION_ON_INSTALL,
+    SQL_DELETE_EXTENSION_PERMISSIONS_FOR_TARGET, SQL_INSERT_EXTENSION,
+    SQL_INSERT_EXTENSION_PERMISSION, SQL_INSERT_EXTENSION_SIGNING_KEY,
+    SQL_SELECT_EXTENSION_IDENTITY, SQL_SELECT_EXTENSION_ID_BY_PUBKEY_NAME,
+    SQL_SELECT_EXTENSION_SIGNING_KEY, SQL_UPDATE_EXTENSION_METADATA,
+    SQL_UPDATE_EXTENSION_ON_INSTALL, SQL_UPDATE_EXTENSION_SIGNING_KEY,
 };
 use crate::AppState;
+use rusqlite::OptionalExtension;
 use serde_json::Value as JsonValue;
 use std::fs;
 use std::path::PathBuf;
 use std::time::SystemTime;
 use tauri::{AppHandle, Manager, State};
-use zip::ZipArchive;
 
 use super::manager::ExtensionManager;
 use super::migrations::register_bundle_migrations;
@@ -40,6 +49,16 @@ impl Drop for ExtractedExtension {
     }
 }
 
+/// Version and keys of an already registered extension, read before an update.
+pub(crate) struct InstalledIdentity {
+    pub version: String,
+    /// Original key; namespaces the extension's tables and install directory
+    pub public_key: String,
+    pub name: String,
+    /// Key bundles must currently be signed with (differs after a rotation)
+    pub signing_key: String,
+}
+
 impl ExtensionManager {
     /// Extracts an extension ZIP file and validates the manifest.
     pub(crate) fn extract_and_validate_extension(
@@ -72,21 +91,12 @@ impl ExtensionManager {
         fs::create_dir_all(&temp)
             .map_err(|e| ExtensionError::filesystem_with_path(temp.display().to_string(), e))?;
 
-        // Open ZIP file from disk (more reliable on Android than from memory)
-        let zip_file = fs::File::open(&zip_file_path).map_err(|e| {
-            ExtensionError::filesystem_with_path(zip_file_path.display().to_string(), e)
-        })?;
-
-        let mut archive =
-            ZipArchive::new(zip_file).map_err(|e| ExtensionError::InstallationFailed {
-                reason: format!("Invalid ZIP: {e}"),
-            })?;
-
-        archive
-            .extract(&temp)
-            .map_err(|e| ExtensionError::InstallationFailed {
+        // Extract from disk (more reliable on Android than from memory)
+        extract_zip(&zip_file_path, &temp, &ArchiveLimits::INSTALLER).map_err(|e| {
+            ExtensionError::InstallationFailed {
                 reason: format!("Cannot extract ZIP: {e}"),
-            })?;
+            }
+        })?;
 
         // Clean up temporary ZIP file
         let _ = fs::remove_file(&zip_file_path);
@@ -147,11 +157,14 @@ impl ExtensionManager {
                 }
             })?;
 
-        Ok(ExtractedExtension {
+        let extracted = ExtractedExtension {
             temp_dir: actual_dir,
             manifest,
             content_hash,
-        })
+        };
+        // Dropping `extracted` on error removes the temp directory
+        check_host_compatibility(&extracted.manifest, HOST_API_VERSION)?;
+        Ok(extracted)
     }
 
     pub async fn preview_extension_internal(
@@ -424,14 +437,23 @@ impl ExtensionManager {
     /// Use when extension is already registered in DB (e.g., from sync or update).
     /// Validates signature, extracts files, registers migrations.
     /// Also updates the version in the database to the new version from the manifest.
+    ///
+    /// A bundle signed by another key than the one trusted for the installed
+    /// extension must carry a valid key rotation chain; the new key is then
+    /// stored as trusted signing key together with the metadata update.
+    ///
+    /// Returns the permission diff between the installed version and the bundle.
+    /// On a version change the diff is applied: removed targets are dropped and
+    /// added/changed targets are granted, or set to `ask` if `block_auto_grant`.
     pub async fn install_extension_files_from_bytes(
         &self,
         app_handle: &AppHandle,
         file_bytes: Vec<u8>,
         extension_id: &str,
+        block_auto_grant: bool,
         state: &State<'_, AppState>,
-    ) -> Result<String, ExtensionError> {
-        let extracted =
+    ) -> Result<ExtensionFilesInstallResult, ExtensionError> {
+        let mut extracted =
             Self::extract_and_validate_extension(file_bytes, "haexspace_ext", app_handle)?;
 
         // Validate that the public key is a valid Ed25519 key format
@@ -445,25 +467,189 @@ impl ExtensionManager {
         )
         .map_err(|e| ExtensionError::SignatureVerificationFailed { reason: e })?;
 
+        let installed = Self::installed_identity(extension_id, state)?;
+
+        // A bundle signed by a key other than the trusted one needs a rotation chain
+        let mut rotated_signing_key = None;
+        if let Some(identity) = &installed {
+            if !extracted
+                .manifest
+                .public_key
+                .eq_ignore_ascii_case(&identity.signing_key)
+            {
+                ExtensionCrypto::verify_key_rotation(
+                    &extracted.manifest.name,
+                    &identity.signing_key,
+                    &extracted.manifest.public_key,
+                    extracted.manifest.key_rotations.as_deref().unwrap_or_default(),
+                )
+                .map_err(|e| ExtensionError::SignatureVerificationFailed { reason: e })?;
+                rotated_signing_key = Some(extracted.manifest.public_key.clone());
+            }
+            // The original key keeps namespacing tables and the install directory
+            extracted.manifest.public_key = identity.public_key.clone();
+        }
+
+        // Compare requested permissions with the installed ones before anything changes
+        let installed_permissions = PermissionManager::get_permissions(state, extension_id).await?;
+        let requested_permissions = extracted
+            .manifest
+            .permissions
+            .to_internal_permissions(extension_id);
+        let permission_diff = diff_permissions(&installed_permissions, &requested_permissions);
+        let is_update = installed
+            .as_ref()
+            .is_some_and(|identity| identity.version != extracted.manifest.version);
+
         // Install files locally
         let extensions_dir = self.install_extension_files(app_handle, &extracted, extension_id)?;
 
         // Update version and other metadata in DB (for updates)
-        self.update_extension_version_in_database(&extracted.manifest, extension_id, state)?;
+        self.update_extension_version_in_database(
+            &extracted.manifest,
+            extension_id,
+            rotated_signing_key.as_deref(),
+            state,
+        )?;
+
+        if is_update && !permission_diff.is_empty() {
+            Self::apply_permission_diff(
+                extension_id,
+                &permission_diff,
+                &requested_permissions,
+                block_auto_grant,
+                state,
+            )?;
+        }
 
         // Register and apply migrations from the bundle
         register_bundle_migrations(&extensions_dir, &extracted.manifest, extension_id, state)
             .await?;
 
-        Ok(extension_id.to_string())
+        let auto_granted =
+            is_update && !block_auto_grant && permission_diff.requests_new_permissions();
+
+        Ok(ExtensionFilesInstallResult {
+            extension_id: extension_id.to_string(),
+            permission_diff,
+            auto_granted,
+        })
+    }
+
+    /// Reads version and keys stored for an extension, if it is registered.
+    pub(crate) fn installed_identity(
+        extension_id: &str,
+        state: &State<'_, AppState>,
+    ) -> Result<Option<InstalledIdentity>, ExtensionError> {
+        let params = vec![JsonValue::String(extension_id.to_string())];
+        let rows = select_with_crdt(
+            SQL_SELECT_EXTENSION_IDENTITY.clone(),
+            params.clone(),
+            &state.db,
+        )?;
+        let Some(row) = rows.first() else {
+            return Ok(None);
+        };
+        let text = |value: Option<&JsonValue>| {
+            value
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let public_key = text(row.get(1));
+
+        let signing_rows =
+            select_with_crdt(SQL_SELECT_EXTENSION_SIGNING_KEY.clone(), params, &state.db)?;
+        let signing_key = signing_rows
+            .first()
+            .and_then(|row| row.first())
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| public_key.clone());
+
+        Ok(Some(InstalledIdentity {
+            version: text(row.first()),
+            public_key,
+            name: text(row.get(2)),
+            signing_key,
+        }))
+    }
+
+    /// Brings the stored permissions in line with an updated manifest.
+    /// Unchanged targets keep their current status so user decisions survive updates.
+    fn apply_permission_diff(
+        extension_id: &str,
+        diff: &PermissionDiff,
+        requested: &[ExtensionPermission],
+        block_auto_grant: bool,
+        state: &State<'_, AppState>,
+    ) -> Result<(), ExtensionError> {
+        let status = if block_auto_grant {
+            PermissionStatus::Ask
+        } else {
+            PermissionStatus::Granted
+        };
+
+        with_connection(&state.db, |conn| {
+            let tx = conn.transaction().map_err(DatabaseError::from)?;
+
+            let hlc_service_guard = state.lock_or_fail(
+                &state.hlc,
+                crate::critical::CriticalFailureCode::HlcMutexPoisoned,
+                "extension::core::installer::apply_permission_diff",
+                serde_json::json!({}),
+            )?;
+            let hlc_service = hlc_service_guard.clone();
+            drop(hlc_service_guard);
+
+            for entry in diff.removed.iter().chain(&diff.changed) {
+                SqlExecutor::execute_internal_typed(
+                    &tx,
+                    &hlc_service,
+                    &SQL_DELETE_EXTENSION_PERMISSIONS_FOR_TARGET,
+                    rusqlite::params![extension_id, entry.resource_type.as_str(), entry.target],
+                )?;
+            }
+
+            for entry in diff.added.iter().chain(&diff.changed) {
+                for perm in requested.iter().filter(|p| {
+                    p.resource_type == entry.resource_type && p.target == entry.target
+                }) {
+                    let mut perm = perm.clone();
+                    perm.status = status;
+                    let db_perm =
+                        crate::database::generated::HaexExtensionPermissions::from(&perm);
+                    SqlExecutor::execute_internal_typed(
+                        &tx,
+                        &hlc_service,
+                        &SQL_INSERT_EXTENSION_PERMISSION,
+                        rusqlite::params![
+                            db_perm.id,
+                            db_perm.extension_id,
+                            db_perm.resource_type,
+                            db_perm.action,
+                            db_perm.target,
+                            db_perm.constraints,
+                            db_perm.status,
+                        ],
+                    )?;
+                }
+            }
+
+            tx.commit().map_err(DatabaseError::from)?;
+            Ok(())
+        })
+        .map_err(ExtensionError::from)
     }
 
     /// Update extension version and metadata in database.
     /// Used when installing a new version of an existing extension.
-    fn update_extension_version_in_database(
+    /// A rotated signing key is stored in the same transaction.
+    pub(crate) fn update_extension_version_in_database(
         &self,
         manifest: &ExtensionManifest,
         extension_id: &str,
+        rotated_signing_key: Option<&str>,
         state: &State<'_, AppState>,
     ) -> Result<(), ExtensionError> {
         with_connection(&state.db, |conn| {
@@ -502,6 +688,34 @@ impl ExtensionManager {
                 ],
             )?;
 
+            if let Some(signing_key) = rotated_signing_key {
+                let chain = serde_json::to_string(&manifest.key_rotations).map_err(|e| {
+                    DatabaseError::SerializationError {
+                        reason: e.to_string(),
+                    }
+                })?;
+                let exists = tx
+                    .query_row(&SQL_SELECT_EXTENSION_SIGNING_KEY, [extension_id], |_| Ok(()))
+                    .optional()
+                    .map_err(DatabaseError::from)?
+                    .is_some();
+                if exists {
+                    SqlExecutor::execute_internal_typed(
+                        &tx,
+                        &hlc_service,
+                        &SQL_UPDATE_EXTENSION_SIGNING_KEY,
+                        rusqlite::params![signing_key, chain, extension_id],
+                    )?;
+                } else {
+                    SqlExecutor::execute_internal_typed(
+                        &tx,
+                        &hlc_service,
+                        &SQL_INSERT_EXTENSION_SIGNING_KEY,
+                        rusqlite::params![extension_id, signing_key, chain],
+                    )?;
+                }
+            }
+
             tx.commit().map_err(DatabaseError::from)?;
             Ok(())
         })
@@ -2,12 +2,21 @@
 //
 // Extension loading from database and filesystem.
 
-use crate::database::core::select_with_crdt;
+use crate::database::core::with_connection;
+use crate::database::generated::HaexExtensions;
+use crate::extension::core::compatibility::{
+    check_host_compatibility, read_manifest_host_versions, HOST_API_VERSION,
+};
+use crate::extension::core::locales::read_manifest_locales;
 use crate::extension::core::manifest::{DisplayMode, ExtensionManifest, ExtensionPermissions};
 use crate::extension::core::path_utils::validate_path_in_directory;
 use crate::extension::core::types::{Extension, ExtensionSource};
 use crate::extension::error::ExtensionError;
-use super::queries::SQL_LIST_EXTENSIONS;
+use crate::extension::event_bus::read_manifest_event_channels;
+use crate::extension::revocation::{RevocationStore, RevokedExtension};
+use crate::extension::share::read_manifest_share_target;
+use crate::external_bridge::CORE_EXTENSION_ID;
+use crate::table_names::COL_EXTENSIONS_ID;
 use crate::AppState;
 use serde_json;
 use std::path::PathBuf;
@@ -17,10 +26,10 @@ use tauri::{AppHandle, State};
 use super::manager::{ExtensionManager, MissingExtension};
 
 /// Config parsed from haextension.config.json
-struct HaextensionConfig {
-    host: String,
-    port: u16,
-    haextension_dir: String,
+pub(crate) struct HaextensionConfig {
+    pub host: String,
+    pub port: u16,
+    pub haextension_dir: String,
 }
 
 impl Default for HaextensionConfig {
@@ -34,7 +43,7 @@ impl Default for HaextensionConfig {
 }
 
 /// Read haextension.config.json from a directory.
-fn read_haextension_config(base_path: &PathBuf) -> HaextensionConfig {
+pub(crate) fn read_haextension_config(base_path: &PathBuf) -> HaextensionConfig {
     let config_path = base_path.join("haextension.config.json");
     if !config_path.exists() {
         return HaextensionConfig::default();
@@ -104,78 +113,72 @@ impl ExtensionManager {
                 reason: e.to_string(),
             })?
             .clear();
+        self.revoked_extensions
+            .lock()
+            .map_err(|e| ExtensionError::MutexPoisoned {
+                reason: e.to_string(),
+            })?
+            .clear();
 
-        // Load all data from database
-        // Use select_with_crdt to automatically filter out tombstoned (soft-deleted) entries
-        // Load all extensions - dev_path determines if it's a dev extension
-        eprintln!(
-            "DEBUG: SQL Query (will be transformed by select_with_crdt): {}",
-            *SQL_LIST_EXTENSIONS
-        );
-
-        let results = select_with_crdt(SQL_LIST_EXTENSIONS.clone(), vec![], &state.db)?;
-        eprintln!("DEBUG: Query returned {} results", results.len());
+        // An unreadable revocation store must not keep all extensions from loading
+        let revocations = RevocationStore::load(app_handle).unwrap_or_else(|e| {
+            eprintln!("WARNING: Cannot read extension revocation list: {e}");
+            RevocationStore::default()
+        });
 
-        let mut extensions = Vec::new();
-        for row in results {
-            // Values in the order of the SELECT statement
-            let id = row[0]
-                .as_str()
-                .ok_or_else(|| ExtensionError::ManifestError {
-                    reason: "Missing id field in database row".to_string(),
-                })?
-                .to_string();
-
-            let manifest = ExtensionManifest {
-                name: row[1]
-                    .as_str()
-                    .ok_or_else(|| ExtensionError::ManifestError {
-                        reason: "Missing name field in database row".to_string(),
-                    })?
-                    .to_string(),
-                version: row[2]
-                    .as_str()
-                    .ok_or_else(|| ExtensionError::ManifestError {
-                        reason: "Missing version field in database row".to_string(),
-                    })?
-                    .to_string(),
-                author: row[3].as_str().map(String::from),
-                entry: row[4].as_str().map(String::from),
-                icon: row[5].as_str().map(String::from),
-                public_key: row[6].as_str().unwrap_or("").to_string(),
-                signature: row[7].as_str().unwrap_or("").to_string(),
-                permissions: ExtensionPermissions::default(),
-                homepage: row[8].as_str().map(String::from),
-                description: row[9].as_str().map(String::from),
-                single_instance: row[11]
-                    .as_bool()
-                    .or_else(|| row[11].as_i64().map(|v| v != 0)),
-                display_mode: row[12].as_str().and_then(|s| match s {
-                    "window" => Some(DisplayMode::Window),
-                    "iframe" => Some(DisplayMode::Iframe),
-                    "auto" | _ => Some(DisplayMode::Auto),
-                }),
-                migrations_dir: None,
-                i18n: row.get(14)
-                    .and_then(|v| v.as_str())
-                    .and_then(|s| serde_json::from_str(s).ok()),
-            };
-
-            let enabled = row[10]
-                .as_bool()
-                .or_else(|| row[10].as_i64().map(|v| v != 0))
-                .unwrap_or(false);
-
-            // dev_path is at index 13
-            let dev_path = row.get(13).and_then(|v| v.as_str()).map(String::from);
-
-            extensions.push(ExtensionDataFromDb {
-                id,
-                manifest,
-                enabled,
-                dev_path,
-            });
-        }
+        // Load all extensions - dev_path determines if it's a dev extension.
+        // Excludes the phantom "__core__" row, which represents the haex-vault core
+        // itself as an external-bridge target — not a real, installable extension.
+        let rows = with_connection(&state.db, |conn| {
+            Ok(HaexExtensions::find(
+                conn,
+                &format!("WHERE \"{COL_EXTENSIONS_ID}\" != '{CORE_EXTENSION_ID}'"),
+                [],
+            )?)
+        })?;
+        eprintln!("DEBUG: Query returned {} results", rows.len());
+
+        let extensions: Vec<ExtensionDataFromDb> = rows
+            .into_iter()
+            .map(|row| {
+                let manifest = ExtensionManifest {
+                    name: row.name,
+                    version: row.version,
+                    author: row.author,
+                    entry: row.entry,
+                    icon: row.icon,
+                    public_key: row.public_key,
+                    signature: row.signature,
+                    permissions: ExtensionPermissions::default(),
+                    homepage: row.homepage,
+                    description: row.description,
+                    single_instance: row.single_instance,
+                    display_mode: row.display_mode.as_deref().map(|s| match s {
+                        "window" => DisplayMode::Window,
+                        "iframe" => DisplayMode::Iframe,
+                        _ => DisplayMode::Auto,
+                    }),
+                    migrations_dir: None,
+                    i18n: row
+                        .i18n
+                        .as_deref()
+                        .and_then(|s| serde_json::from_str(s).ok()),
+                    key_rotations: None,
+                    locales: None,
+                    share_target: None,
+                    events: None,
+                    min_host_version: None,
+                    max_host_version: None,
+                };
+
+                ExtensionDataFromDb {
+                    id: row.id,
+                    manifest,
+                    enabled: row.enabled.unwrap_or(false),
+                    dev_path: row.dev_path,
+                }
+            })
+            .collect();
 
         // Step 2: Process the collected data (filesystem, state mutations).
         let mut loaded_extension_ids = Vec::new();
@@ -208,6 +211,14 @@ impl ExtensionManager {
                     }
                 }
             } else {
+                if self.is_blocked_by_revocation(
+                    &extension_id,
+                    &extension_data.manifest,
+                    &revocations,
+                )? {
+                    continue;
+                }
+
                 // Production extension - load from extensions directory
                 match self.load_production_extension(
                     app_handle,
@@ -231,6 +242,44 @@ impl ExtensionManager {
         Ok(loaded_extension_ids)
     }
 
+    /// Checks a production extension against the revocation list.
+    /// Returns true if the loader must skip it (revoked and no override set).
+    fn is_blocked_by_revocation(
+        &self,
+        extension_id: &str,
+        manifest: &ExtensionManifest,
+        revocations: &RevocationStore,
+    ) -> Result<bool, ExtensionError> {
+        let Some(entry) =
+            revocations.revocation_for(&manifest.public_key, &manifest.name, &manifest.version)
+        else {
+            return Ok(false);
+        };
+
+        eprintln!(
+            "WARNING: Extension {extension_id} ({} {}) is revoked: {}",
+            manifest.name,
+            manifest.version,
+            entry.reason.as_deref().unwrap_or("no reason given")
+        );
+
+        self.revoked_extensions
+            .lock()
+            .map_err(|e| ExtensionError::MutexPoisoned {
+                reason: e.to_string(),
+            })?
+            .push(RevokedExtension {
+                id: extension_id.to_string(),
+                name: manifest.name.clone(),
+                public_key: manifest.public_key.clone(),
+                version: manifest.version.clone(),
+                reason: entry.reason.clone(),
+                loaded: revocations.allow_revoked,
+            });
+
+        Ok(!revocations.allow_revoked)
+    }
+
     /// Load a dev extension from its project path.
     /// Returns Ok(true) if loaded, Ok(false) if path doesn't exist (synced from another device).
     fn load_dev_extension_from_path(
@@ -260,6 +309,14 @@ impl ExtensionManager {
         manifest.icon = manifest.icon.as_ref().map(|rel_path| {
             dev_path_buf.join(rel_path).to_string_lossy().to_string()
         });
+        // Locale, share target and event channel metadata are not stored in
+        // the DB, read them from the bundle
+        manifest.locales = read_manifest_locales(&manifest_path);
+        manifest.share_target = read_manifest_share_target(&manifest_path);
+        manifest.events = read_manifest_event_channels(&manifest_path);
+        (manifest.min_host_version, manifest.max_host_version) =
+            read_manifest_host_versions(&manifest_path);
+        check_host_compatibility(&manifest, HOST_API_VERSION)?;
 
         let extension = Extension {
             id: extension_id.to_string(),
@@ -319,7 +376,9 @@ impl ExtensionManager {
 
         // Validate manifest.json path using helper function
         let manifest_relative_path = format!("{}/manifest.json", config.haextension_dir);
-        if validate_path_in_directory(&extension_path, &manifest_relative_path, true)?.is_none() {
+        let Some(manifest_path) =
+            validate_path_in_directory(&extension_path, &manifest_relative_path, true)?
+        else {
             eprintln!(
                 "DEBUG: manifest.json missing or invalid for: {extension_id} at {manifest_relative_path}"
             );
@@ -335,7 +394,7 @@ impl ExtensionManager {
                     version: manifest.version.clone(),
                 });
             return Ok(false);
-        }
+        };
 
         eprintln!("DEBUG: Extension loaded successfully: {extension_id}");
 
@@ -344,6 +403,14 @@ impl ExtensionManager {
         manifest.icon = manifest.icon.as_ref().map(|rel_path| {
             extension_path.join(rel_path).to_string_lossy().to_string()
         });
+        // Locale, share target and event channel metadata are not stored in
+        // the DB, read them from the bundle
+        manifest.locales = read_manifest_locales(&manifest_path);
+        manifest.share_target = read_manifest_share_target(&manifest_path);
+        manifest.events = read_manifest_event_channels(&manifest_path);
+        (manifest.min_host_version, manifest.max_host_version) =
+            read_manifest_host_versions(&manifest_path);
+        check_host_compatibility(&manifest, HOST_API_VERSION)?;
 
         let extension = Extension {
             id: extension_id.to_string(),
@@ -0,0 +1,207 @@
+// src-tauri/src/extension/core/locales.rs
+//
+// Locale negotiation for extension assets.
+//
+// Extensions ship translations as `locales/<locale>.json` (directory
+// configurable via the manifest). The protocol handler resolves requests for
+// those files through a fallback chain built from the requested locale, the
+// ApplicationContext locale and the manifest's default locale, so extensions
+// can always fetch `locales/current.json` or a specific language without
+// knowing which translations exist.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use ts_rs::TS;
+
+/// Directory holding locale files when the manifest does not specify one.
+pub const DEFAULT_LOCALES_DIR: &str = "locales";
+
+/// Virtual file name resolved to the current ApplicationContext locale.
+pub const CURRENT_LOCALE_FILE: &str = "current";
+
+/// Last resort when neither requested, context nor default locale exist.
+const FALLBACK_LOCALE: &str = "en";
+
+/// Locale metadata declared in the extension manifest.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestLocales {
+    /// Locale used when none of the user's locales is available (e.g. "en")
+    #[serde(default)]
+    pub default_locale: Option<String>,
+    /// Locales the extension ships translations for (e.g. ["en", "de"])
+    #[serde(default)]
+    pub supported: Vec<String>,
+    /// Directory of the `<locale>.json` files relative to the extension root
+    #[serde(default)]
+    pub dir: Option<String>,
+}
+
+impl ManifestLocales {
+    pub fn dir(&self) -> &str {
+        self.dir
+            .as_deref()
+            .map(|d| d.trim_matches('/'))
+            .filter(|d| !d.is_empty())
+            .unwrap_or(DEFAULT_LOCALES_DIR)
+    }
+}
+
+/// Normalizes a locale tag: `de_AT` → `de-at`. Returns `None` for anything
+/// that is not a plain BCP 47-style tag, so tags are safe to use in paths.
+pub fn normalize_locale(locale: &str) -> Option<String> {
+    let tag = locale.trim().replace('_', "-").to_ascii_lowercase();
+    let valid = !tag.is_empty()
+        && tag.len() <= 35
+        && tag
+            .split('-')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()));
+    valid.then_some(tag)
+}
+
+/// Pushes `locale` and its less specific parents (`de-at-x` → `de-at` → `de`).
+fn push_with_parents(chain: &mut Vec<String>, locale: &str) {
+    let Some(tag) = normalize_locale(locale) else {
+        return;
+    };
+    let mut current = tag.as_str();
+    loop {
+        if !chain.iter().any(|c| c == current) {
+            chain.push(current.to_string());
+        }
+        match current.rfind('-') {
+            Some(idx) => current = &current[..idx],
+            None => break,
+        }
+    }
+}
+
+/// Builds the ordered list of locales to try: requested locale, context
+/// locale, manifest default and `en`, each followed by its parent tags.
+pub fn locale_fallback_chain(
+    requested: Option<&str>,
+    context_locale: &str,
+    default_locale: Option<&str>,
+) -> Vec<String> {
+    let mut chain = Vec::new();
+    if let Some(requested) = requested {
+        push_with_parents(&mut chain, requested);
+    }
+    push_with_parents(&mut chain, context_locale);
+    if let Some(default_locale) = default_locale {
+        push_with_parents(&mut chain, default_locale);
+    }
+    push_with_parents(&mut chain, FALLBACK_LOCALE);
+    chain
+}
+
+/// Picks the first entry of `chain` the extension provides. Also matches a
+/// more specific available locale (`de` → `de-DE`) before moving on.
+/// Returns the available locale as written by the extension.
+pub fn negotiate_locale(chain: &[String], available: &[String]) -> Option<String> {
+    let normalized: Vec<(String, &String)> = available
+        .iter()
+        .filter_map(|a| normalize_locale(a).map(|n| (n, a)))
+        .collect();
+
+    for wanted in chain {
+        if let Some((_, original)) = normalized.iter().find(|(n, _)| n == wanted) {
+            return Some((*original).clone());
+        }
+        let prefix = format!("{wanted}-");
+        if let Some((_, original)) = normalized.iter().find(|(n, _)| n.starts_with(&prefix)) {
+            return Some((*original).clone());
+        }
+    }
+    None
+}
+
+/// Locales found as `<locale>.json` files in `locales_dir`.
+pub fn list_locale_files(locales_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(locales_dir) else {
+        return Vec::new();
+    };
+    let mut locales: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                return None;
+            }
+            let stem = path.file_stem()?.to_str()?;
+            normalize_locale(stem).map(|_| stem.to_string())
+        })
+        .collect();
+    locales.sort();
+    locales
+}
+
+/// Reads the `locales` section of a bundle's manifest.json.
+pub fn read_manifest_locales(manifest_path: &Path) -> Option<ManifestLocales> {
+    let content = fs::read_to_string(manifest_path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+    serde_json::from_value(value.get("locales")?.clone()).ok()
+}
+
+/// Splits an asset path into the locale requested from the locales directory.
+///
+/// `locales/de.json` → `Some("de")`; returns `None` for any other asset.
+pub fn requested_locale_asset<'a>(asset_path: &'a str, locales_dir: &str) -> Option<&'a str> {
+    let file = asset_path
+        .trim_start_matches('/')
+        .strip_prefix(locales_dir)?
+        .strip_prefix('/')?;
+    let locale = file.strip_suffix(".json")?;
+    (!locale.contains('/')).then_some(locale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_chain_order() {
+        let chain = locale_fallback_chain(Some("de_AT"), "fr-CA", Some("es"));
+        assert_eq!(chain, vec!["de-at", "de", "fr-ca", "fr", "es", "en"]);
+    }
+
+    #[test]
+    fn test_fallback_chain_skips_invalid_and_duplicates() {
+        let chain = locale_fallback_chain(Some("../etc"), "en-US", Some("en"));
+        assert_eq!(chain, vec!["en-us", "en"]);
+    }
+
+    #[test]
+    fn test_negotiate_prefers_chain_order() {
+        let available = vec!["en".to_string(), "de-DE".to_string()];
+        let chain = locale_fallback_chain(Some("de"), "en", None);
+        assert_eq!(
+            negotiate_locale(&chain, &available).as_deref(),
+            Some("de-DE")
+        );
+
+        let chain = locale_fallback_chain(Some("it"), "fr", Some("en"));
+        assert_eq!(negotiate_locale(&chain, &available).as_deref(), Some("en"));
+
+        assert_eq!(negotiate_locale(&chain, &[]), None);
+    }
+
+    #[test]
+    fn test_requested_locale_asset() {
+        assert_eq!(
+            requested_locale_asset("locales/de.json", "locales"),
+            Some("de")
+        );
+        assert_eq!(
+            requested_locale_asset("/i18n/current.json", "i18n"),
+            Some("current")
+        );
+        assert_eq!(
+            requested_locale_asset("locales/de/app.json", "locales"),
+            None
+        );
+        assert_eq!(requested_locale_asset("assets/de.json", "locales"), None);
+    }
+}
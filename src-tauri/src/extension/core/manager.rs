@@ -12,8 +12,10 @@ use crate::database::core::{execute_with_crdt, with_connection};
 use crate::database::error::DatabaseError;
 use crate::extension::core::types::Extension;
 use crate::extension::database::executor::SqlExecutor;
+use crate::extension::dev_logs::DevLogBuffer;
 use crate::extension::error::ExtensionError;
 use crate::extension::permissions::types::ExtensionPermission;
+use crate::extension::revocation::RevokedExtension;
 use super::queries::{SQL_UPDATE_EXTENSION_DISPLAY_MODE, SQL_UPDATE_EXTENSION_ENABLED};
 use crate::AppState;
 use serde_json::Value as JsonValue;
@@ -22,7 +24,7 @@ use std::fs;
 use std::path::PathBuf;
 use std::sync::Mutex;
 use std::time::{Duration, SystemTime};
-use tauri::{AppHandle, Manager, State};
+use tauri::{AppHandle, State};
 
 #[derive(Debug, Clone)]
 pub struct CachedPermission {
@@ -31,7 +33,10 @@ pub struct CachedPermission {
     pub ttl: Duration,
 }
 
-#[derive(Debug, Clone)]
+/// Installed extension whose files could not be found on this device
+#[derive(Debug, Clone, serde::Serialize, ts_rs::TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
 pub struct MissingExtension {
     pub id: String,
     pub public_key: String,
@@ -44,6 +49,10 @@ pub struct ExtensionManager {
     pub available_extensions: Mutex<HashMap<String, Extension>>,
     pub permission_cache: Mutex<HashMap<String, CachedPermission>>,
     pub missing_extensions: Mutex<Vec<MissingExtension>>,
+    /// Extensions matched by the revocation list during the last load
+    pub revoked_extensions: Mutex<Vec<RevokedExtension>>,
+    /// Captured console output of dev extensions
+    pub dev_logs: DevLogBuffer,
 }
 
 impl ExtensionManager {
@@ -55,9 +64,7 @@ impl ExtensionManager {
         &self,
         app_handle: &AppHandle,
     ) -> Result<PathBuf, ExtensionError> {
-        let path = app_handle
-            .path()
-            .app_local_data_dir()
+        let path = crate::app_profile::data_dir(app_handle)
             .map_err(|e| ExtensionError::Filesystem {
                 source: std::io::Error::new(std::io::ErrorKind::NotFound, e.to_string()),
             })?
@@ -311,6 +318,17 @@ impl ExtensionManager {
         if let Some(ext) = extensions.get_mut(extension_id) {
             ext.enabled = enabled;
         }
+        drop(extensions);
+
+        // A disabled extension is unloaded: stop its watches, streams and drops
+        if !enabled {
+            state
+                .file_watcher
+                .unwatch_extension(extension_id)
+                .map_err(|reason| ExtensionError::FilesystemError { reason })?;
+            state.file_streams.close_all_for_extension(extension_id)?;
+            state.file_drops.release_all_for_extension(extension_id)?;
+        }
 
         Ok(())
     }
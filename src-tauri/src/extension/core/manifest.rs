@@ -1,8 +1,11 @@
+use crate::extension::core::locales::ManifestLocales;
 use crate::extension::error::ExtensionError;
+use crate::extension::permissions::diff::PermissionDiff;
 use crate::extension::permissions::types::{
-    Action, DbAction, ExtensionPermission, FileSyncAction, FsAction, IdentityAction, MailAction,
-    PasswordsAction, PermissionConstraints, PermissionStatus, ResourceType, ShellAction,
-    SpaceAction, WebAction,
+    Action, CalendarAction, CameraAction, ContactsAction, DbAction, ExtensionPermission,
+    FileSyncAction, FsAction, IdentityAction, MailAction, MicrophoneAction, PasswordsAction,
+    PermissionConstraints, PermissionStatus, ResourceType, ShellAction, SpaceAction, SpeechAction,
+    WebAction,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -72,6 +75,18 @@ pub struct ExtensionPreview {
     pub is_valid_signature: bool,
     pub editable_permissions: EditablePermissions,
 }
+
+/// Result of installing extension files for an already registered extension.
+#[derive(Serialize, Deserialize, Clone, Debug, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionFilesInstallResult {
+    pub extension_id: String,
+    /// Permission changes of the bundle compared to the installed version
+    pub permission_diff: PermissionDiff,
+    /// True if added or changed permissions were granted without a prompt
+    pub auto_granted: bool,
+}
 /// Definiert die einheitliche Struktur für alle Berechtigungsarten im Manifest und UI.
 #[derive(Serialize, Deserialize, Clone, Debug, Default, TS)]
 #[ts(export)]
@@ -94,6 +109,16 @@ pub struct ExtensionPermissions {
     pub passwords: Option<Vec<PermissionEntry>>,
     #[serde(default)]
     pub mail: Option<Vec<PermissionEntry>>,
+    #[serde(default)]
+    pub camera: Option<Vec<PermissionEntry>>,
+    #[serde(default)]
+    pub contacts: Option<Vec<PermissionEntry>>,
+    #[serde(default)]
+    pub calendar: Option<Vec<PermissionEntry>>,
+    #[serde(default)]
+    pub microphone: Option<Vec<PermissionEntry>>,
+    #[serde(default)]
+    pub speech: Option<Vec<PermissionEntry>>,
 }
 
 /// Typ-Alias für bessere Lesbarkeit, wenn die Struktur als UI-Modell verwendet wird.
@@ -155,6 +180,125 @@ pub struct ExtensionManifest {
     /// Key is locale code (e.g. "de", "en"), value contains localized fields.
     #[serde(default)]
     pub i18n: Option<HashMap<String, ManifestI18nEntry>>,
+    /// Key rotation chain from a previously used signing key to `public_key`.
+    /// Lets installed extensions accept bundles signed by a rotated key.
+    #[serde(default)]
+    pub key_rotations: Option<Vec<KeyRotationProof>>,
+    /// Supported locales and default locale for the extension's
+    /// `locales/<locale>.json` assets.
+    #[serde(default)]
+    pub locales: Option<ManifestLocales>,
+    /// Content the extension accepts from the OS share sheet (mobile).
+    /// Also accepted as `share-target`.
+    #[serde(default, alias = "share-target")]
+    pub share_target: Option<ShareTarget>,
+    /// Event bus channels the extension publishes to and listens on
+    #[serde(default)]
+    pub events: Option<EventChannels>,
+    /// Lowest host API version the extension works with (e.g. "3.1")
+    #[serde(default)]
+    pub min_host_version: Option<String>,
+    /// Highest host API version the extension works with; "3" accepts
+    /// every 3.x host
+    #[serde(default)]
+    pub max_host_version: Option<String>,
+}
+
+/// One step of a signing key rotation, signed by the previous key.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRotationProof {
+    /// Hex-encoded Ed25519 key being rotated away from
+    pub previous_public_key: String,
+    /// Hex-encoded Ed25519 key taking over
+    pub public_key: String,
+    /// Hex-encoded signature of the previous key over the rotation payload
+    pub signature: String,
+}
+
+/// Share-sheet capability of an extension. Shares are offered only to
+/// extensions whose target accepts every shared file.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareTarget {
+    /// MIME types of accepted files; `image/*` and `*/*` match a whole group
+    #[serde(default)]
+    pub accept: Vec<String>,
+    /// Accepts shared text and links
+    #[serde(default)]
+    pub text: bool,
+}
+
+impl ShareTarget {
+    /// Whether a share with these file types (and text, if any) fits this target.
+    pub fn accepts(&self, mime_types: &[String], has_text: bool) -> bool {
+        if mime_types.is_empty() {
+            return has_text && self.text;
+        }
+        mime_types.iter().all(|mime| {
+            self.accept
+                .iter()
+                .any(|pattern| mime_matches(pattern, mime))
+        })
+    }
+}
+
+fn mime_matches(pattern: &str, mime: &str) -> bool {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    let mime = mime.to_ascii_lowercase();
+    match pattern.strip_suffix("/*") {
+        Some("*") => true,
+        Some(group) => mime.split('/').next() == Some(group),
+        None => pattern == "*" || pattern == mime,
+    }
+}
+
+/// Event bus channels of an extension. Publishing needs the exact channel
+/// name; subscriptions may end in `.*` (or be `*`), but such wildcards are
+/// only honoured when the vault's event policy allows them.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct EventChannels {
+    /// Channels the extension may publish to
+    #[serde(default)]
+    pub publish: Vec<String>,
+    /// Channels (or wildcard patterns) the extension receives events from
+    #[serde(default)]
+    pub subscribe: Vec<String>,
+}
+
+impl EventChannels {
+    /// Whether the extension declared `channel` for publishing.
+    pub fn may_publish(&self, channel: &str) -> bool {
+        self.publish.iter().any(|declared| declared == channel)
+    }
+
+    /// Whether an event on `channel` is delivered to the extension.
+    pub fn subscribes_to(&self, channel: &str, allow_wildcards: bool) -> bool {
+        self.subscribe.iter().any(|pattern| {
+            if is_wildcard_channel(pattern) {
+                allow_wildcards && channel_matches(pattern, channel)
+            } else {
+                pattern == channel
+            }
+        })
+    }
+}
+
+/// Whether a channel pattern is a wildcard (`*` or `prefix.*`).
+pub fn is_wildcard_channel(pattern: &str) -> bool {
+    pattern == "*" || pattern.ends_with(".*")
+}
+
+fn channel_matches(pattern: &str, channel: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some("") => true,
+        Some(prefix) => channel.starts_with(prefix) && channel.len() > prefix.len(),
+        None => pattern == channel,
+    }
 }
 
 fn default_entry_value() -> Option<String> {
@@ -188,6 +332,11 @@ impl ExtensionManifest {
         set_status_for_list(editable.identities.as_mut());
         set_status_for_list(editable.passwords.as_mut());
         set_status_for_list(editable.mail.as_mut());
+        set_status_for_list(editable.camera.as_mut());
+        set_status_for_list(editable.contacts.as_mut());
+        set_status_for_list(editable.calendar.as_mut());
+        set_status_for_list(editable.microphone.as_mut());
+        set_status_for_list(editable.speech.as_mut());
 
         editable
     }
@@ -261,6 +410,42 @@ impl ExtensionPermissions {
                 }
             }
         }
+        if let Some(entries) = &self.camera {
+            for p in entries {
+                if let Some(perm) = Self::create_internal(extension_id, ResourceType::Camera, p) {
+                    permissions.push(perm);
+                }
+            }
+        }
+        if let Some(entries) = &self.contacts {
+            for p in entries {
+                if let Some(perm) = Self::create_internal(extension_id, ResourceType::Contacts, p) {
+                    permissions.push(perm);
+                }
+            }
+        }
+        if let Some(entries) = &self.calendar {
+            for p in entries {
+                if let Some(perm) = Self::create_internal(extension_id, ResourceType::Calendar, p) {
+                    permissions.push(perm);
+                }
+            }
+        }
+        if let Some(entries) = &self.microphone {
+            for p in entries {
+                if let Some(perm) = Self::create_internal(extension_id, ResourceType::Microphone, p)
+                {
+                    permissions.push(perm);
+                }
+            }
+        }
+        if let Some(entries) = &self.speech {
+            for p in entries {
+                if let Some(perm) = Self::create_internal(extension_id, ResourceType::Speech, p) {
+                    permissions.push(perm);
+                }
+            }
+        }
 
         permissions
     }
@@ -302,6 +487,21 @@ impl ExtensionPermissions {
             ResourceType::Mail => {
                 MailAction::from_str(operation_str).ok().map(Action::Mail)
             }
+            ResourceType::Camera => {
+                CameraAction::from_str(operation_str).ok().map(Action::Camera)
+            }
+            ResourceType::Contacts => {
+                ContactsAction::from_str(operation_str).ok().map(Action::Contacts)
+            }
+            ResourceType::Calendar => {
+                CalendarAction::from_str(operation_str).ok().map(Action::Calendar)
+            }
+            ResourceType::Microphone => {
+                MicrophoneAction::from_str(operation_str).ok().map(Action::Microphone)
+            }
+            ResourceType::Speech => {
+                SpeechAction::from_str(operation_str).ok().map(Action::Speech)
+            }
         };
 
         action.map(|act| ExtensionPermission {
@@ -339,6 +539,8 @@ pub struct ExtensionInfoResponse {
     pub dev_server_url: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub i18n: Option<HashMap<String, ManifestI18nEntry>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locales: Option<ManifestLocales>,
 }
 
 impl ExtensionInfoResponse {
@@ -367,6 +569,7 @@ impl ExtensionInfoResponse {
             display_mode: extension.manifest.display_mode.clone(),
             dev_server_url,
             i18n: extension.manifest.i18n.clone(),
+            locales: extension.manifest.locales.clone(),
         })
     }
 }
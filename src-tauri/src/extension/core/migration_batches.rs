@@ -0,0 +1,622 @@
+// src-tauri/src/extension/core/migration_batches.rs
+//
+// Migration batches of extensions and the table snapshots taken before them.
+//
+// Every run of `register_bundle_migrations` is one batch. Before its first
+// statement, the extension's tables are copied into local backup tables
+// together with the SQL of their indexes and views. A failed migration
+// restores the snapshot right away; an applied batch can be rolled back later
+// with `extension_rollback_last_migration_batch`. Only the snapshot of the
+// latest batch per extension is kept.
+//
+// Batches, snapshots and backups are local (`_no_sync`). A rollback restores
+// the tables of this device only: other devices keep their migrated schema,
+// and rows a migration rewrote may already have been synced.
+
+use crate::crdt::{cascade, trigger};
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::extension::error::ExtensionError;
+use crate::extension::utils::get_extension_table_prefix;
+use crate::table_names::TABLE_CRDT_DIRTY_TABLES;
+use crate::AppState;
+use rusqlite::{params, Connection, OptionalExtension, Row, Transaction};
+use serde::Serialize;
+use tauri::State;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use ts_rs::TS;
+
+use super::manager::ExtensionManager;
+
+/// Local-only log of migration batches, created by migration
+/// `0018_add_extension_migration_batches`. Never synced (`_no_sync`).
+pub const BATCHES_TABLE: &str = "haex_extension_migration_batches_no_sync";
+
+/// Local-only list of the objects saved for a batch, created by the same
+/// migration. Never synced (`_no_sync`).
+pub const SNAPSHOTS_TABLE: &str = "haex_extension_migration_snapshots_no_sync";
+
+const BACKUP_TABLE_PREFIX: &str = "haex_migration_backup_";
+
+/// Outcome of a migration batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationBatchStatus {
+    /// Started and not finished, e.g. the app quit during the migrations
+    Running,
+    Applied,
+    /// A migration failed and the snapshot was restored
+    Failed,
+    RolledBack,
+}
+
+impl MigrationBatchStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Applied => "applied",
+            Self::Failed => "failed",
+            Self::RolledBack => "rolled_back",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "applied" => Self::Applied,
+            "failed" => Self::Failed,
+            "rolled_back" => Self::RolledBack,
+            _ => Self::Running,
+        }
+    }
+}
+
+/// One run of an extension's bundle migrations on this device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationBatch {
+    pub id: String,
+    pub extension_id: String,
+    pub extension_version: String,
+    pub status: MigrationBatchStatus,
+    /// Tags of the migrations the batch applied, in order
+    pub migrations: Vec<String>,
+    pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// Whether the tables as they were before the batch are still saved
+    pub has_snapshot: bool,
+}
+
+fn now() -> String {
+    OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .unwrap_or_default()
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Tables, indexes and views of the extension with `table_prefix`, tables
+/// first. Indexes SQLite creates itself have no SQL and come back with
+/// their table.
+fn extension_objects(
+    conn: &Connection,
+    table_prefix: &str,
+) -> Result<Vec<(String, String, String)>, DatabaseError> {
+    let mut stmt = conn.prepare(
+        "SELECT type, name, tbl_name, sql FROM sqlite_master
+         WHERE type IN ('table', 'index', 'view') AND sql IS NOT NULL
+         ORDER BY CASE type WHEN 'table' THEN 0 WHEN 'index' THEN 1 ELSE 2 END, rowid",
+    )?;
+    let objects = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(objects
+        .into_iter()
+        .filter(|(_, _, table, _)| table.starts_with(table_prefix))
+        .map(|(object_type, name, _, sql)| (object_type, name, sql))
+        .collect())
+}
+
+/// Drops the backups of all saved batches of an extension
+fn discard_snapshots(tx: &Transaction, extension_id: &str) -> Result<(), DatabaseError> {
+    let backups: Vec<String> = tx
+        .prepare(&format!(
+            "SELECT s.backup_table FROM \"{SNAPSHOTS_TABLE}\" s
+             JOIN \"{BATCHES_TABLE}\" b ON b.id = s.batch_id
+             WHERE b.extension_id = ?1 AND s.backup_table IS NOT NULL"
+        ))?
+        .query_map([extension_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for backup in &backups {
+        tx.execute(&format!("DROP TABLE IF EXISTS {}", quote(backup)), [])?;
+    }
+    tx.execute(
+        &format!(
+            "DELETE FROM \"{SNAPSHOTS_TABLE}\" WHERE batch_id IN
+             (SELECT id FROM \"{BATCHES_TABLE}\" WHERE extension_id = ?1)"
+        ),
+        [extension_id],
+    )?;
+    tx.execute(
+        &format!("UPDATE \"{BATCHES_TABLE}\" SET has_snapshot = 0 WHERE extension_id = ?1"),
+        [extension_id],
+    )?;
+    Ok(())
+}
+
+/// Starts a batch: saves the extension's tables and the SQL of their indexes
+/// and views, replacing the snapshot of the previous batch. Returns the
+/// batch id.
+pub fn begin_batch(
+    tx: &Transaction,
+    extension_id: &str,
+    extension_version: &str,
+    table_prefix: &str,
+    started_at: &str,
+) -> Result<String, DatabaseError> {
+    discard_snapshots(tx, extension_id)?;
+
+    let batch_id = uuid::Uuid::new_v4().to_string();
+    tx.execute(
+        &format!(
+            "INSERT INTO \"{BATCHES_TABLE}\"
+             (id, extension_id, extension_version, status, started_at, has_snapshot)
+             VALUES (?1, ?2, ?3, ?4, ?5, 1)"
+        ),
+        params![
+            batch_id,
+            extension_id,
+            extension_version,
+            MigrationBatchStatus::Running.as_str(),
+            started_at
+        ],
+    )?;
+
+    let backup_base = format!("{BACKUP_TABLE_PREFIX}{}", batch_id.replace('-', ""));
+    for (position, (object_type, name, sql)) in
+        extension_objects(tx, table_prefix)?.into_iter().enumerate()
+    {
+        let backup_table =
+            (object_type == "table").then(|| format!("{backup_base}_{position}_no_sync"));
+        if let Some(backup) = &backup_table {
+            tx.execute(
+                &format!(
+                    "CREATE TABLE {} AS SELECT * FROM {}",
+                    quote(backup),
+                    quote(&name)
+                ),
+                [],
+            )?;
+        }
+        tx.execute(
+            &format!(
+                "INSERT INTO \"{SNAPSHOTS_TABLE}\" (batch_id, position, object_type, name, sql, backup_table)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+            ),
+            params![batch_id, position as i64, object_type, name, sql, backup_table],
+        )?;
+    }
+    Ok(batch_id)
+}
+
+/// Records the outcome of a batch
+pub fn finish_batch(
+    conn: &Connection,
+    batch_id: &str,
+    status: MigrationBatchStatus,
+    migrations: &[String],
+    error: Option<&str>,
+    finished_at: &str,
+) -> Result<(), DatabaseError> {
+    let migrations = serde_json::to_string(migrations).unwrap_or_else(|_| "[]".to_string());
+    conn.execute(
+        &format!(
+            "UPDATE \"{BATCHES_TABLE}\"
+             SET status = ?2, migrations = ?3, error = ?4, finished_at = ?5
+             WHERE id = ?1"
+        ),
+        params![batch_id, status.as_str(), migrations, error, finished_at],
+    )?;
+    Ok(())
+}
+
+/// Puts the extension's tables back to the snapshot of `batch_id` and drops
+/// the snapshot. Tables and views created after the snapshot are dropped.
+/// CRDT and cascade triggers are left to the caller. Returns the restored
+/// tables.
+///
+/// Foreign keys must be disabled before the transaction starts.
+pub fn restore_snapshot(
+    tx: &Transaction,
+    batch_id: &str,
+    table_prefix: &str,
+) -> Result<Vec<String>, DatabaseError> {
+    let snapshot: Vec<(String, String, String, Option<String>)> = tx
+        .prepare(&format!(
+            "SELECT object_type, name, sql, backup_table FROM \"{SNAPSHOTS_TABLE}\"
+             WHERE batch_id = ?1 ORDER BY position"
+        ))?
+        .query_map([batch_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let has_snapshot: bool = tx
+        .query_row(
+            &format!("SELECT has_snapshot FROM \"{BATCHES_TABLE}\" WHERE id = ?1"),
+            [batch_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(false);
+    if !has_snapshot {
+        return Err(DatabaseError::ValidationError {
+            reason: format!("No snapshot saved for migration batch {batch_id}"),
+        });
+    }
+
+    // Views first, they may select from the tables
+    let current = extension_objects(tx, table_prefix)?;
+    for (_, name, _) in current.iter().filter(|(t, _, _)| t == "view") {
+        tx.execute(&format!("DROP VIEW IF EXISTS {}", quote(name)), [])?;
+    }
+    let mut dropped = Vec::new();
+    for (_, name, _) in current.iter().filter(|(t, _, _)| t == "table") {
+        tx.execute(&format!("DROP TABLE IF EXISTS {}", quote(name)), [])?;
+        dropped.push(name.clone());
+    }
+
+    let mut restored = Vec::new();
+    for (object_type, name, sql, backup_table) in &snapshot {
+        tx.execute_batch(sql)?;
+        if let (true, Some(backup)) = (object_type == "table", backup_table) {
+            tx.execute(
+                &format!(
+                    "INSERT INTO {} SELECT * FROM {}",
+                    quote(name),
+                    quote(backup)
+                ),
+                [],
+            )?;
+            tx.execute(&format!("DROP TABLE IF EXISTS {}", quote(backup)), [])?;
+            restored.push(name.clone());
+        }
+    }
+
+    // Tables the batch created have nothing left to sync
+    for name in dropped.iter().filter(|name| !restored.contains(name)) {
+        tx.execute(
+            &format!("DELETE FROM {TABLE_CRDT_DIRTY_TABLES} WHERE table_name = ?1"),
+            [name],
+        )?;
+    }
+    tx.execute(
+        &format!("DELETE FROM \"{SNAPSHOTS_TABLE}\" WHERE batch_id = ?1"),
+        [batch_id],
+    )?;
+    tx.execute(
+        &format!("UPDATE \"{BATCHES_TABLE}\" SET has_snapshot = 0 WHERE id = ?1"),
+        [batch_id],
+    )?;
+    Ok(restored)
+}
+
+fn batch_from_row(row: &Row) -> Result<MigrationBatch, rusqlite::Error> {
+    let status: String = row.get(3)?;
+    let migrations: String = row.get(4)?;
+    Ok(MigrationBatch {
+        id: row.get(0)?,
+        extension_id: row.get(1)?,
+        extension_version: row.get(2)?,
+        status: MigrationBatchStatus::parse(&status),
+        migrations: serde_json::from_str(&migrations).unwrap_or_default(),
+        error: row.get(5)?,
+        started_at: row.get(6)?,
+        finished_at: row.get(7)?,
+        has_snapshot: row.get(8)?,
+    })
+}
+
+fn select_batches_sql(filter: &str) -> String {
+    format!(
+        "SELECT b.id, b.extension_id, b.extension_version, b.status, b.migrations, b.error,
+                b.started_at, b.finished_at, b.has_snapshot
+         FROM \"{BATCHES_TABLE}\" b
+         WHERE {filter}
+         ORDER BY b.rowid DESC"
+    )
+}
+
+/// Batches of an extension, latest first
+pub fn list_batches(
+    conn: &Connection,
+    extension_id: &str,
+) -> Result<Vec<MigrationBatch>, DatabaseError> {
+    let batches = conn
+        .prepare(&select_batches_sql("b.extension_id = ?1"))?
+        .query_map([extension_id], batch_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(batches)
+}
+
+/// Latest batch of an extension
+pub fn latest_batch(
+    conn: &Connection,
+    extension_id: &str,
+) -> Result<Option<MigrationBatch>, DatabaseError> {
+    Ok(conn
+        .query_row(
+            &format!("{} LIMIT 1", select_batches_sql("b.extension_id = ?1")),
+            [extension_id],
+            batch_from_row,
+        )
+        .optional()?)
+}
+
+/// Starts a batch for an extension's bundle migrations
+pub(crate) fn start_batch(
+    state: &AppState,
+    extension_id: &str,
+    extension_version: &str,
+    table_prefix: &str,
+) -> Result<String, ExtensionError> {
+    Ok(with_connection(&state.db, |conn| {
+        let tx = conn.transaction()?;
+        let batch_id = begin_batch(&tx, extension_id, extension_version, table_prefix, &now())?;
+        tx.commit()?;
+        Ok(batch_id)
+    })?)
+}
+
+/// Records the outcome of a batch started with [`start_batch`]
+pub(crate) fn end_batch(
+    state: &AppState,
+    batch_id: &str,
+    status: MigrationBatchStatus,
+    migrations: &[String],
+    error: Option<&str>,
+) -> Result<(), ExtensionError> {
+    Ok(with_connection(&state.db, |conn| {
+        finish_batch(conn, batch_id, status, migrations, error, &now())
+    })?)
+}
+
+/// Restores the snapshot of `batch_id` and sets the CRDT and cascade
+/// triggers of the restored tables up again.
+pub(crate) fn rollback_batch(
+    state: &AppState,
+    batch_id: &str,
+    public_key: &str,
+    extension_name: &str,
+) -> Result<Vec<String>, ExtensionError> {
+    let table_prefix = get_extension_table_prefix(public_key, extension_name);
+    Ok(with_connection(&state.db, |conn| {
+        // PRAGMA changes don't take effect within an active transaction
+        conn.execute_batch("PRAGMA foreign_keys = OFF")?;
+        let result = (|| {
+            let tx = conn.transaction()?;
+            cascade::drop_cascade_triggers(&tx, &table_prefix)?;
+            let restored = restore_snapshot(&tx, batch_id, &table_prefix)?;
+            for table in &restored {
+                if let Err(e) = trigger::ensure_crdt_columns_and_triggers(&tx, table) {
+                    eprintln!("[MIGRATION_ROLLBACK] Failed to set up CRDT for '{table}': {e}");
+                }
+            }
+            if let Err(e) = cascade::install_cascade_triggers(&tx, &table_prefix) {
+                eprintln!(
+                    "[MIGRATION_ROLLBACK] Failed to install cascade triggers for '{table_prefix}': {e}"
+                );
+            }
+            tx.commit()?;
+            Ok::<_, DatabaseError>(restored)
+        })();
+        conn.execute_batch("PRAGMA foreign_keys = ON")?;
+        result
+    })?)
+}
+
+impl ExtensionManager {
+    /// Migration batches of an extension on this device, latest first
+    pub fn migration_batches(
+        extension_id: &str,
+        state: &State<'_, AppState>,
+    ) -> Result<Vec<MigrationBatch>, ExtensionError> {
+        Ok(with_connection(&state.db, |conn| {
+            list_batches(conn, extension_id)
+        })?)
+    }
+
+    /// Restores the extension's tables to their state before the latest
+    /// applied migration batch.
+    ///
+    /// The migrations of the batch stay recorded in the vault and the active
+    /// version is unchanged; pin the previous version with
+    /// `set_extension_active_version` so the code matches the schema again.
+    pub fn rollback_last_migration_batch(
+        extension_id: &str,
+        state: &State<'_, AppState>,
+    ) -> Result<MigrationBatch, ExtensionError> {
+        let identity = Self::installed_identity(extension_id, state)?.ok_or_else(|| {
+            ExtensionError::ValidationError {
+                reason: format!("Extension {extension_id} is not installed"),
+            }
+        })?;
+        let batch = with_connection(&state.db, |conn| latest_batch(conn, extension_id))?
+            .filter(|batch| batch.status == MigrationBatchStatus::Applied && batch.has_snapshot)
+            .ok_or_else(|| ExtensionError::ValidationError {
+                reason: format!("No migration batch of {extension_id} can be rolled back"),
+            })?;
+
+        let restored = rollback_batch(state, &batch.id, &identity.public_key, &identity.name)?;
+        end_batch(
+            state,
+            &batch.id,
+            MigrationBatchStatus::RolledBack,
+            &batch.migrations,
+            None,
+        )?;
+        eprintln!(
+            "[MIGRATION_ROLLBACK] Rolled back batch {} of {extension_id} ({} tables restored)",
+            batch.id,
+            restored.len()
+        );
+
+        with_connection(&state.db, |conn| latest_batch(conn, extension_id))?.ok_or_else(|| {
+            ExtensionError::ValidationError {
+                reason: format!("Migration batch {} disappeared", batch.id),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREFIX: &str = "pk__ext__";
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(&format!(
+            "CREATE TABLE {TABLE_CRDT_DIRTY_TABLES} (table_name TEXT PRIMARY KEY, last_modified TEXT);
+             CREATE TABLE \"pk__ext__notes\" (id TEXT PRIMARY KEY, title TEXT NOT NULL);
+             CREATE INDEX \"pk__ext__notes_title_idx\" ON \"pk__ext__notes\" (title);
+             CREATE VIEW \"pk__ext__titles\" AS SELECT title FROM \"pk__ext__notes\";
+             CREATE TABLE \"other__ext__notes\" (id TEXT PRIMARY KEY);
+             INSERT INTO \"pk__ext__notes\" VALUES ('1', 'first'), ('2', 'second');"
+        ))
+        .unwrap();
+        conn.execute_batch(include_str!(
+            "../../../database/migrations/0018_add_extension_migration_batches.sql"
+        ))
+        .unwrap();
+        conn
+    }
+
+    fn begin(conn: &mut Connection) -> String {
+        let tx = conn.transaction().unwrap();
+        let batch_id = begin_batch(&tx, "ext-id", "1.0.0", PREFIX, "2026-01-01T00:00:00Z").unwrap();
+        tx.commit().unwrap();
+        batch_id
+    }
+
+    fn schema(conn: &Connection) -> Vec<(String, String)> {
+        conn.prepare(
+            "SELECT type, name FROM sqlite_master WHERE name LIKE 'pk%' ORDER BY type, name",
+        )
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_restore_reverts_schema_and_data() {
+        let mut conn = setup();
+        let before = schema(&conn);
+        let batch_id = begin(&mut conn);
+
+        conn.execute_batch(&format!(
+            "ALTER TABLE \"pk__ext__notes\" ADD COLUMN body TEXT;
+             UPDATE \"pk__ext__notes\" SET title = 'changed';
+             DELETE FROM \"pk__ext__notes\" WHERE id = '2';
+             CREATE TABLE \"pk__ext__tags\" (id TEXT PRIMARY KEY);
+             INSERT INTO {TABLE_CRDT_DIRTY_TABLES} VALUES ('pk__ext__tags', 'now');"
+        ))
+        .unwrap();
+
+        let tx = conn.transaction().unwrap();
+        let restored = restore_snapshot(&tx, &batch_id, PREFIX).unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(restored, vec!["pk__ext__notes".to_string()]);
+        assert_eq!(schema(&conn), before);
+        let titles: Vec<String> = conn
+            .prepare("SELECT title FROM \"pk__ext__titles\" ORDER BY title")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(titles, ["first", "second"]);
+        let dirty: i64 = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM {TABLE_CRDT_DIRTY_TABLES}"),
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(dirty, 0, "dropped tables are no longer dirty");
+    }
+
+    #[test]
+    fn test_restore_drops_snapshot_and_backups() {
+        let mut conn = setup();
+        let batch_id = begin(&mut conn);
+        let backups = |conn: &Connection| -> i64 {
+            conn.query_row(
+                &format!(
+                    "SELECT COUNT(*) FROM sqlite_master WHERE name LIKE '{BACKUP_TABLE_PREFIX}%'"
+                ),
+                [],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            backups(&conn),
+            1,
+            "one backup per table, other prefixes untouched"
+        );
+
+        let tx = conn.transaction().unwrap();
+        restore_snapshot(&tx, &batch_id, PREFIX).unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(backups(&conn), 0);
+        let tx = conn.transaction().unwrap();
+        assert!(restore_snapshot(&tx, &batch_id, PREFIX).is_err());
+    }
+
+    #[test]
+    fn test_new_batch_replaces_previous_snapshot() {
+        let mut conn = setup();
+        let first = begin(&mut conn);
+        finish_batch(
+            &conn,
+            &first,
+            MigrationBatchStatus::Applied,
+            &["0000_init".to_string()],
+            None,
+            "2026-01-01T00:00:01Z",
+        )
+        .unwrap();
+        let second = begin(&mut conn);
+
+        let batches = list_batches(&conn, "ext-id").unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].id, second);
+        assert_eq!(batches[0].status, MigrationBatchStatus::Running);
+        assert!(batches[0].has_snapshot);
+        assert_eq!(batches[1].id, first);
+        assert_eq!(batches[1].migrations, ["0000_init"]);
+        assert!(!batches[1].has_snapshot);
+        assert_eq!(
+            latest_batch(&conn, "ext-id").unwrap().map(|batch| batch.id),
+            Some(second)
+        );
+        assert!(latest_batch(&conn, "other").unwrap().is_none());
+    }
+}
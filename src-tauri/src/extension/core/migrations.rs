@@ -4,11 +4,13 @@
 
 use crate::database::core::with_connection;
 use crate::database::error::DatabaseError;
-use crate::extension::core::manifest::{ExtensionManifest, MigrationJournal};
+use crate::extension::core::manifest::{ExtensionManifest, MigrationJournal, MigrationJournalEntry};
 use crate::extension::core::path_utils::validate_path_in_directory;
 use crate::extension::database::executor::SqlExecutor;
 use crate::extension::database::{execute_migration_statements, ExtensionSqlContext};
 use crate::extension::error::ExtensionError;
+use crate::extension::utils::get_extension_table_prefix;
+use super::migration_batches::{self, MigrationBatchStatus};
 use super::queries::SQL_INSERT_EXTENSION_MIGRATION;
 use crate::AppState;
 use serde_json::Value as JsonValue;
@@ -21,6 +23,11 @@ use tauri::State;
 /// This reads the migrations from the bundle's migrations_dir (specified in manifest),
 /// validates them, executes them, and stores them as applied in the database.
 ///
+/// All migrations of one call form a batch (see `migration_batches`): the
+/// extension's tables are saved before the first migration and restored if
+/// one of them fails. Migrations are only recorded once the whole batch has
+/// been applied.
+///
 /// # Arguments
 /// * `extension_dir` - Path to the installed extension directory
 /// * `manifest` - The extension manifest
@@ -96,41 +103,51 @@ pub async fn register_bundle_migrations(
     let mut entries = journal.entries.clone();
     entries.sort_by_key(|e| e.idx);
 
-    // Process each migration in order
-    for entry in &entries {
-        // Validate SQL file path to prevent path traversal
-        let sql_relative_path = format!("{}/{}.sql", migrations_dir, entry.tag);
-        let sql_file_path =
-            match validate_path_in_directory(extension_dir, &sql_relative_path, true)? {
-                Some(path) => path,
-                None => {
-                    eprintln!(
-                        "[INSTALL_MIGRATIONS] SQL file not found: {}",
-                        sql_relative_path
-                    );
-                    continue;
-                }
-            };
-
-        let sql_content = fs::read_to_string(&sql_file_path).map_err(|e| {
-            ExtensionError::filesystem_with_path(sql_file_path.display().to_string(), e)
-        })?;
-
-        eprintln!("[INSTALL_MIGRATIONS] Processing migration: {}", entry.tag);
-
-        // Create context for SQL execution
-        let ctx = ExtensionSqlContext::new(manifest.public_key.clone(), manifest.name.clone());
-
-        // Execute all statements using the helper function
-        // This validates table prefixes and executes with CRDT support
-        let stmt_count = execute_migration_statements(&ctx, &sql_content, state.inner())?;
-
+    let table_prefix = get_extension_table_prefix(&manifest.public_key, &manifest.name);
+    let batch_id = migration_batches::start_batch(
+        state.inner(),
+        extension_id,
+        &manifest.version,
+        &table_prefix,
+    )?;
+
+    let mut applied: Vec<(String, String)> = Vec::new();
+    if let Err(e) = apply_bundle_migrations(
+        extension_dir,
+        manifest,
+        migrations_dir,
+        &entries,
+        state,
+        &mut applied,
+    ) {
         eprintln!(
-            "[INSTALL_MIGRATIONS] Migration '{}' executed ({} statements)",
-            entry.tag, stmt_count
+            "[INSTALL_MIGRATIONS] Migration batch {} failed, restoring tables: {}",
+            batch_id, e
         );
+        if let Err(restore_error) = migration_batches::rollback_batch(
+            state.inner(),
+            &batch_id,
+            &manifest.public_key,
+            &manifest.name,
+        ) {
+            eprintln!(
+                "[INSTALL_MIGRATIONS] Failed to restore tables of batch {}: {}",
+                batch_id, restore_error
+            );
+        }
+        let tags: Vec<String> = applied.into_iter().map(|(tag, _)| tag).collect();
+        migration_batches::end_batch(
+            state.inner(),
+            &batch_id,
+            MigrationBatchStatus::Failed,
+            &tags,
+            Some(&e.to_string()),
+        )?;
+        return Err(e);
+    }
 
-        // Store migration as applied in the database
+    // Store migrations as applied in the database
+    for (tag, sql_content) in &applied {
         with_connection(&state.db, |conn| {
             let tx = conn.transaction().map_err(DatabaseError::from)?;
             let migration_id = uuid::Uuid::new_v4().to_string();
@@ -146,7 +163,7 @@ pub async fn register_bundle_migrations(
                 JsonValue::String(migration_id),
                 JsonValue::String(extension_id.to_string()),
                 JsonValue::String(manifest.version.clone()),
-                JsonValue::String(entry.tag.clone()),
+                JsonValue::String(tag.clone()),
                 JsonValue::String(sql_content.clone()),
             ];
             SqlExecutor::execute_internal(&tx, &hlc_service, &SQL_INSERT_EXTENSION_MIGRATION, &params)?;
@@ -156,11 +173,20 @@ pub async fn register_bundle_migrations(
         })?;
 
         eprintln!(
-            "[INSTALL_MIGRATIONS] Migration '{}' applied and stored",
-            entry.tag
+            "[INSTALL_MIGRATIONS] Migration '{}' stored",
+            tag
         );
     }
 
+    let tags: Vec<String> = applied.into_iter().map(|(tag, _)| tag).collect();
+    migration_batches::end_batch(
+        state.inner(),
+        &batch_id,
+        MigrationBatchStatus::Applied,
+        &tags,
+        None,
+    )?;
+
     eprintln!(
         "[INSTALL_MIGRATIONS] ✅ Completed migration registration for {}::{}",
         manifest.public_key, manifest.name
@@ -168,3 +194,53 @@ pub async fn register_bundle_migrations(
 
     Ok(())
 }
+
+/// Executes the journal entries in order. Applied migrations are pushed to
+/// `applied` as (tag, sql) so the caller can record or report them.
+fn apply_bundle_migrations(
+    extension_dir: &PathBuf,
+    manifest: &ExtensionManifest,
+    migrations_dir: &str,
+    entries: &[MigrationJournalEntry],
+    state: &State<'_, AppState>,
+    applied: &mut Vec<(String, String)>,
+) -> Result<(), ExtensionError> {
+    // Process each migration in order
+    for entry in entries {
+        // Validate SQL file path to prevent path traversal
+        let sql_relative_path = format!("{}/{}.sql", migrations_dir, entry.tag);
+        let sql_file_path =
+            match validate_path_in_directory(extension_dir, &sql_relative_path, true)? {
+                Some(path) => path,
+                None => {
+                    eprintln!(
+                        "[INSTALL_MIGRATIONS] SQL file not found: {}",
+                        sql_relative_path
+                    );
+                    continue;
+                }
+            };
+
+        let sql_content = fs::read_to_string(&sql_file_path).map_err(|e| {
+            ExtensionError::filesystem_with_path(sql_file_path.display().to_string(), e)
+        })?;
+
+        eprintln!("[INSTALL_MIGRATIONS] Processing migration: {}", entry.tag);
+
+        // Create context for SQL execution
+        let ctx = ExtensionSqlContext::new(manifest.public_key.clone(), manifest.name.clone());
+
+        // Execute all statements using the helper function
+        // This validates table prefixes and executes with CRDT support
+        let stmt_count = execute_migration_statements(&ctx, &sql_content, state.inner())?;
+
+        eprintln!(
+            "[INSTALL_MIGRATIONS] Migration '{}' executed ({} statements)",
+            entry.tag, stmt_count
+        );
+
+        applied.push((entry.tag.clone(), sql_content));
+    }
+
+    Ok(())
+}
@@ -1,16 +1,20 @@
 // src-tauri/src/extension/core/mod.rs
 
+pub mod compatibility;
 pub mod context;
 pub mod installer;
 pub mod loader;
+pub mod locales;
 pub mod manager;
 pub mod manifest;
+pub mod migration_batches;
 pub mod migrations;
 pub mod path_utils;
 pub mod protocol;
 mod queries;
 pub mod removal;
 pub mod types;
+pub mod versions;
 
 pub use manager::*;
 pub use manifest::*;
@@ -1,6 +1,10 @@
 // src-tauri/src/extension/core/protocol.rs
 
-use crate::extension::core::types::get_tauri_origin;
+use crate::extension::core::locales::{
+    list_locale_files, locale_fallback_chain, negotiate_locale, requested_locale_asset,
+    CURRENT_LOCALE_FILE,
+};
+use crate::extension::core::types::{get_tauri_origin, Extension, ExtensionSource};
 use crate::extension::error::ExtensionError;
 use crate::AppState;
 use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
@@ -31,6 +35,22 @@ pub struct ExtensionInfo {
     pub version: String,
 }
 
+impl ExtensionInfo {
+    /// Info of an installed extension; development extensions report `dev`
+    /// as their version.
+    pub fn from_extension(extension: &Extension) -> Self {
+        let version = match &extension.source {
+            ExtensionSource::Production { version, .. } => version.clone(),
+            ExtensionSource::Development { .. } => "dev".to_string(),
+        };
+        Self {
+            public_key: extension.manifest.public_key.clone(),
+            name: extension.manifest.name.clone(),
+            version,
+        }
+    }
+}
+
 #[derive(Debug)]
 enum DataProcessingError {
     HexDecoding(hex::FromHexError),
@@ -42,12 +62,12 @@ enum DataProcessingError {
 impl fmt::Display for DataProcessingError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            DataProcessingError::HexDecoding(e) => write!(f, "Hex-Dekodierungsfehler: {e}"),
+            DataProcessingError::HexDecoding(e) => write!(f, "Hex decoding error: {e}"),
             DataProcessingError::Utf8Conversion(e) => {
-                write!(f, "UTF-8-Konvertierungsfehler: {e}")
+                write!(f, "UTF-8 conversion error: {e}")
             }
-            DataProcessingError::JsonParsing(e) => write!(f, "JSON-Parsing-Fehler: {e}"),
-            DataProcessingError::Custom(msg) => write!(f, "Datenverarbeitungsfehler: {msg}"),
+            DataProcessingError::JsonParsing(e) => write!(f, "JSON parsing error: {e}"),
+            DataProcessingError::Custom(msg) => write!(f, "Data processing error: {msg}"),
         }
     }
 }
@@ -87,6 +107,56 @@ impl From<serde_json::Error> for DataProcessingError {
     }
 }
 
+/// Origins extension pages are served from: the custom scheme where the
+/// webview loads it natively (Linux, macOS, iOS), `http(s)://haex-extension.localhost`
+/// where Tauri maps it (Android, Windows).
+fn is_extension_origin(origin: &str) -> bool {
+    let mapped_host = format!("{EXTENSION_PROTOCOL_NAME}.localhost");
+    match origin.split_once("://") {
+        Some((scheme, _)) if scheme == EXTENSION_PROTOCOL_NAME => true,
+        Some(("http" | "https", host)) => host == mapped_host,
+        _ => false,
+    }
+}
+
+/// The extension an extension page URL belongs to, or `None` for any other
+/// URL. Accepts both `haex-extension://<base64>/…` and
+/// `http://haex-extension.localhost/<base64>/…`.
+fn extension_info_from_url(url: &str) -> Option<ExtensionInfo> {
+    let (scheme, rest) = url.split_once("://")?;
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let encoded = if scheme == EXTENSION_PROTOCOL_NAME {
+        host
+    } else if host == format!("{EXTENSION_PROTOCOL_NAME}.localhost") {
+        path.split('/').next()?
+    } else {
+        return None;
+    };
+    let json = BASE64_STANDARD.decode(encoded).ok()?;
+    serde_json::from_slice(&json).ok()
+}
+
+/// URL of an extension asset in the form the webview on this platform
+/// loads: through `haex-extension.localhost` on Android and Windows, the
+/// custom scheme everywhere else. Mobile hosts extensions in iframes only,
+/// so this is the URL the frontend points the iframe at.
+pub fn extension_asset_url(
+    info: &ExtensionInfo,
+    asset_path: &str,
+) -> Result<String, ExtensionError> {
+    let encoded = BASE64_STANDARD.encode(serde_json::to_string(info)?);
+    let asset_path = asset_path.trim_start_matches('/');
+    if cfg!(any(target_os = "android", target_os = "windows")) {
+        Ok(format!(
+            "http://{EXTENSION_PROTOCOL_NAME}.localhost/{encoded}/{asset_path}"
+        ))
+    } else {
+        Ok(format!(
+            "{EXTENSION_PROTOCOL_NAME}://{encoded}/{asset_path}"
+        ))
+    }
+}
+
 pub fn resolve_secure_extension_asset_path(
     app_handle: &AppHandle,
     state: &State<AppState>,
@@ -173,6 +243,61 @@ pub fn resolve_secure_extension_asset_path(
     }
 }
 
+/// Maps a request for `<locales dir>/<locale>.json` to the best available
+/// locale file. `current` stands for the ApplicationContext locale; any
+/// other locale falls back through its parents, the context locale, the
+/// manifest default and `en`.
+///
+/// Returns the asset path to serve and the chosen locale, or `None` if the
+/// request is not a locale asset.
+fn resolve_locale_asset(
+    app_handle: &AppHandle,
+    state: &State<AppState>,
+    info: &ExtensionInfo,
+    asset_path: &str,
+) -> Result<Option<(String, String)>, ExtensionError> {
+    let manifest_locales = state
+        .extension_manager
+        .get_extension_by_public_key_and_name(&info.public_key, &info.name)?
+        .and_then(|ext| ext.manifest.locales)
+        .unwrap_or_default();
+    let locales_dir = manifest_locales.dir();
+
+    let Some(requested) = requested_locale_asset(asset_path, locales_dir) else {
+        return Ok(None);
+    };
+    let requested = (requested != CURRENT_LOCALE_FILE).then_some(requested);
+
+    let context_locale = state
+        .context
+        .lock()
+        .map_err(|e| ExtensionError::MutexPoisoned {
+            reason: e.to_string(),
+        })?
+        .locale
+        .clone();
+
+    let available = if manifest_locales.supported.is_empty() {
+        let extension_dir = state.extension_manager.get_extension_dir(
+            app_handle,
+            &info.public_key,
+            &info.name,
+            &info.version,
+        )?;
+        list_locale_files(&extension_dir.join(locales_dir))
+    } else {
+        manifest_locales.supported.clone()
+    };
+
+    let chain = locale_fallback_chain(
+        requested,
+        &context_locale,
+        manifest_locales.default_locale.as_deref(),
+    );
+    Ok(negotiate_locale(&chain, &available)
+        .map(|locale| (format!("{locales_dir}/{locale}.json"), locale)))
+}
+
 pub fn extension_protocol_handler(
     state: State<AppState>,
     app_handle: &AppHandle,
@@ -187,8 +312,7 @@ pub fn extension_protocol_handler(
 
     // Only allow same-protocol requests or tauri origin
     // For null/empty origin (initial load), use wildcard
-    let protocol_prefix = format!("{EXTENSION_PROTOCOL_NAME}://");
-    let allowed_origin = if origin.starts_with(&protocol_prefix) || origin == get_tauri_origin() {
+    let allowed_origin = if is_extension_origin(origin) || origin == get_tauri_origin() {
         origin
     } else if origin.is_empty() || origin == "null" {
         "*" // Allow initial load without origin
@@ -351,6 +475,23 @@ pub fn extension_protocol_handler(
             parse_extension_info_from_path(path_str, origin, uri_ref, referer)?
         };
 
+    // Where extensions share the `haex-extension.localhost` origin (Android,
+    // Windows) the origin check can't keep one extension from loading
+    // another one's assets, so the referring extension page has to match.
+    if let Some(referrer) = extension_info_from_url(referer) {
+        if referrer.public_key != info.public_key || referrer.name != info.name {
+            eprintln!(
+                "SECURITY WARNING: {}::{} requested assets of {}::{}",
+                referrer.public_key, referrer.name, info.public_key, info.name
+            );
+            return Response::builder()
+                .status(403)
+                .header("Access-Control-Allow-Origin", allowed_origin)
+                .body(Vec::from("Cross-extension asset request"))
+                .map_err(|e| e.into());
+        }
+    }
+
     // Construct asset path from remaining segments
     let raw_asset_path = segments_after_version.join("/");
 
@@ -362,6 +503,13 @@ pub fn extension_protocol_handler(
         &raw_asset_path
     };
 
+    // `locales/<locale>.json` is resolved through the locale fallback chain
+    let localized = resolve_locale_asset(app_handle, &state, &info, asset_to_load)?;
+    let (asset_to_load, content_language) = match &localized {
+        Some((path, locale)) => (path.as_str(), Some(locale.as_str())),
+        None => (asset_to_load, None),
+    };
+
     println!("Path: {path_str}");
     println!("Asset to load: {asset_to_load}");
 
@@ -394,7 +542,11 @@ pub fn extension_protocol_handler(
                     mime_type,
                     content_length
                 );
-                Response::builder()
+                let mut builder = Response::builder();
+                if let Some(locale) = content_language {
+                    builder = builder.header("Content-Language", locale);
+                }
+                builder
                     .status(200)
                     .header("Content-Type", &mime_type)
                     .header("Content-Length", content_length.to_string())
@@ -408,7 +560,7 @@ pub fn extension_protocol_handler(
             }
             Err(e) => {
                 eprintln!(
-                    "Fehler beim Lesen der Datei {}: {}",
+                    "Failed to read file {}: {}",
                     absolute_secure_path.display(),
                     e
                 );
@@ -433,7 +585,7 @@ pub fn extension_protocol_handler(
         // This allows client-side routing to work (e.g., /settings -> index.html)
         if asset_to_load != "index.html" {
             eprintln!(
-                "Asset nicht gefunden: {}, versuche index.html fallback für SPA routing",
+                "Asset not found: {}, trying index.html fallback for SPA routing",
                 absolute_secure_path.display()
             );
 
@@ -475,7 +627,7 @@ pub fn extension_protocol_handler(
 
         // No fallback available - return 404
         eprintln!(
-            "Asset nicht gefunden oder ist kein File: {}",
+            "Asset not found or not a file: {}",
             absolute_secure_path.display()
         );
         Response::builder()
@@ -542,11 +694,9 @@ fn parse_encoded_info_from_origin_or_uri_or_referer_or_cache(
         return Ok(cached_info);
     }
 
-    Err(
-        "Kein gültiger Hex in Origin, URI, Referer oder Cache gefunden"
-            .to_string()
-            .into(),
-    )
+    Err("No valid hex found in origin, URI, referer or cache"
+        .to_string()
+        .into())
 }
 
 // NEU: Cache-Helper (Mutex-sicher)
@@ -610,10 +760,10 @@ fn validate_and_return_hex(segment: &str) -> Result<String, DataProcessingError>
         return Err("Kein Extension-Info (hex) im Path".to_string().into());
     }
     if segment.len() % 2 != 0 {
-        return Err("Ungültiger Hex: Ungerade Länge".to_string().into());
+        return Err("Invalid hex: odd length".to_string().into());
     }
     if !segment.chars().all(|c| c.is_ascii_hexdigit()) {
-        return Err("Ungültiger Hex: Ungültige Zeichen".to_string().into());
+        return Err("Invalid hex: invalid characters".to_string().into());
     }
     Ok(segment.to_string())
 }
@@ -677,10 +827,57 @@ fn parse_extension_info_from_path(
                     Ok((decoded, segments))
                 }
                 Err(e) => {
-                    eprintln!("Fehler beim Parsen (alle Fallbacks): {e}");
-                    Err(format!("Ungültige Anfrage: {e}").into())
+                    eprintln!("Failed to parse (all fallbacks): {e}");
+                    Err(format!("Invalid request: {e}").into())
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info() -> ExtensionInfo {
+        ExtensionInfo {
+            public_key: "ab".repeat(32),
+            name: "notes".to_string(),
+            version: "1.2.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_extension_origins() {
+        assert!(is_extension_origin("haex-extension://eyJ9"));
+        assert!(is_extension_origin("http://haex-extension.localhost"));
+        assert!(is_extension_origin("https://haex-extension.localhost"));
+        assert!(!is_extension_origin(
+            "http://haex-extension.localhost.evil.com"
+        ));
+        assert!(!is_extension_origin("https://example.com"));
+        assert!(!is_extension_origin(""));
+    }
+
+    #[test]
+    fn test_asset_url_round_trip() {
+        let url = extension_asset_url(&info(), "/assets/app.js").unwrap();
+        assert!(url.ends_with("/assets/app.js"));
+
+        let parsed = extension_info_from_url(&url).unwrap();
+        assert_eq!(parsed.public_key, info().public_key);
+        assert_eq!(parsed.name, "notes");
+
+        // Both URL forms resolve, whatever the current platform builds
+        let encoded = BASE64_STANDARD.encode(serde_json::to_string(&info()).unwrap());
+        for url in [
+            format!("haex-extension://{encoded}/index.html"),
+            format!("http://haex-extension.localhost/{encoded}/index.html"),
+        ] {
+            assert_eq!(extension_info_from_url(&url).unwrap().name, "notes");
+        }
+
+        assert!(extension_info_from_url("http://tauri.localhost/index.html").is_none());
+        assert!(extension_info_from_url("").is_none());
+    }
+}
@@ -3,8 +3,9 @@
 //! Centralized SQL for extension CRUD. Follows the `lazy_static!` +
 //! `format!` pattern established in `remote_storage/queries.rs`.
 //!
-//! Queries sourced from `manager.rs`, `installer.rs`, `loader.rs`,
-//! `removal.rs`, and `migrations.rs`. The two near-duplicate
+//! Queries sourced from `manager.rs`, `installer.rs`, `removal.rs`,
+//! and `migrations.rs`. `loader.rs` reads through the typed
+//! `database::generated::HaexExtensions` model instead. The two near-duplicate
 //! `UPDATE ... SET enabled = ? WHERE id = ?` patterns (manager/removal)
 //! are consolidated into `SQL_UPDATE_EXTENSION_ENABLED`.
 
@@ -18,14 +19,14 @@ use crate::table_names::{
     COL_EXTENSION_MIGRATIONS_SQL_STATEMENT, COL_EXTENSION_PERMISSIONS_ACTION,
     COL_EXTENSION_PERMISSIONS_CONSTRAINTS, COL_EXTENSION_PERMISSIONS_EXTENSION_ID,
     COL_EXTENSION_PERMISSIONS_ID, COL_EXTENSION_PERMISSIONS_RESOURCE_TYPE,
-    COL_EXTENSION_PERMISSIONS_STATUS, COL_EXTENSION_PERMISSIONS_TARGET, TABLE_EXTENSIONS,
-    TABLE_EXTENSION_MIGRATIONS, TABLE_EXTENSION_PERMISSIONS,
+    COL_EXTENSION_PERMISSIONS_STATUS, COL_EXTENSION_PERMISSIONS_TARGET,
+    COL_EXTENSION_SIGNING_KEYS_ID, COL_EXTENSION_SIGNING_KEYS_PUBLIC_KEY,
+    COL_EXTENSION_SIGNING_KEYS_ROTATED_AT, COL_EXTENSION_SIGNING_KEYS_ROTATION_CHAIN,
+    TABLE_EXTENSIONS, TABLE_EXTENSION_MIGRATIONS, TABLE_EXTENSION_PERMISSIONS,
+    TABLE_EXTENSION_SIGNING_KEYS,
 };
 use lazy_static::lazy_static;
 
-// Column alias — loader.rs selects `dev_path` (not in the installer/manager writes).
-use crate::table_names::COL_EXTENSIONS_DEV_PATH;
-
 lazy_static! {
     // installer.rs — lookup + write
 
@@ -73,18 +74,41 @@ lazy_static! {
          WHERE {COL_EXTENSIONS_ID} = ?"
     );
 
-    // loader.rs — read
-
-    // Excludes the phantom "__core__" row, which represents the haex-vault core
-    // itself as an external-bridge target — not a real, installable extension.
-    pub static ref SQL_LIST_EXTENSIONS: String = format!(
-        "SELECT {COL_EXTENSIONS_ID}, {COL_EXTENSIONS_NAME}, {COL_EXTENSIONS_VERSION}, {COL_EXTENSIONS_AUTHOR}, \
-                {COL_EXTENSIONS_ENTRY}, {COL_EXTENSIONS_ICON}, {COL_EXTENSIONS_PUBLIC_KEY}, {COL_EXTENSIONS_SIGNATURE}, \
-                {COL_EXTENSIONS_HOMEPAGE}, {COL_EXTENSIONS_DESCRIPTION}, {COL_EXTENSIONS_ENABLED}, \
-                {COL_EXTENSIONS_SINGLE_INSTANCE}, {COL_EXTENSIONS_DISPLAY_MODE}, {COL_EXTENSIONS_DEV_PATH}, \
-                {COL_EXTENSIONS_I18N} \
-         FROM {TABLE_EXTENSIONS} \
-         WHERE {COL_EXTENSIONS_ID} != '__core__'"
+    /// Installed version and original public key, read before an update to
+    /// tell updates from re-installs and to check the bundle's signing key.
+    pub static ref SQL_SELECT_EXTENSION_IDENTITY: String = format!(
+        "SELECT {COL_EXTENSIONS_VERSION}, {COL_EXTENSIONS_PUBLIC_KEY}, {COL_EXTENSIONS_NAME} \
+         FROM {TABLE_EXTENSIONS} WHERE {COL_EXTENSIONS_ID} = ?"
+    );
+
+    // installer.rs — signing key rotation
+
+    pub static ref SQL_SELECT_EXTENSION_SIGNING_KEY: String = format!(
+        "SELECT {COL_EXTENSION_SIGNING_KEYS_PUBLIC_KEY} FROM {TABLE_EXTENSION_SIGNING_KEYS} \
+         WHERE {COL_EXTENSION_SIGNING_KEYS_ID} = ?"
+    );
+
+    pub static ref SQL_INSERT_EXTENSION_SIGNING_KEY: String = format!(
+        "INSERT INTO {TABLE_EXTENSION_SIGNING_KEYS} \
+         ({COL_EXTENSION_SIGNING_KEYS_ID}, {COL_EXTENSION_SIGNING_KEYS_PUBLIC_KEY}, \
+          {COL_EXTENSION_SIGNING_KEYS_ROTATION_CHAIN}) \
+         VALUES (?, ?, ?)"
+    );
+
+    pub static ref SQL_UPDATE_EXTENSION_SIGNING_KEY: String = format!(
+        "UPDATE {TABLE_EXTENSION_SIGNING_KEYS} SET \
+         {COL_EXTENSION_SIGNING_KEYS_PUBLIC_KEY} = ?, {COL_EXTENSION_SIGNING_KEYS_ROTATION_CHAIN} = ?, \
+         {COL_EXTENSION_SIGNING_KEYS_ROTATED_AT} = CURRENT_TIMESTAMP \
+         WHERE {COL_EXTENSION_SIGNING_KEYS_ID} = ?"
+    );
+
+    /// Drops all permissions of one `(resource_type, target)` pair when an
+    /// update removes or changes it.
+    pub static ref SQL_DELETE_EXTENSION_PERMISSIONS_FOR_TARGET: String = format!(
+        "DELETE FROM {TABLE_EXTENSION_PERMISSIONS} \
+         WHERE {COL_EXTENSION_PERMISSIONS_EXTENSION_ID} = ? \
+           AND {COL_EXTENSION_PERMISSIONS_RESOURCE_TYPE} = ? \
+           AND {COL_EXTENSION_PERMISSIONS_TARGET} = ?"
     );
 
     // manager.rs + removal.rs — toggles (consolidated)
@@ -6,6 +6,7 @@ use crate::database::core::with_connection;
 use crate::database::error::DatabaseError;
 use crate::extension::database::executor::SqlExecutor;
 use crate::extension::error::ExtensionError;
+use crate::extension::filesystem::private_dir::remove_private_dir;
 use crate::extension::permissions::manager::PermissionManager;
 use crate::extension::utils::drop_extension_tables;
 use super::queries::{SQL_DELETE_EXTENSION, SQL_UPDATE_EXTENSION_ENABLED};
@@ -22,7 +23,7 @@ impl ExtensionManager {
     /// * `public_key` - Extension's public key
     /// * `extension_name` - Extension name
     /// * `extension_version` - Extension version
-    /// * `delete_data` - If true, deletes all extension tables and data. If false, only removes the extension entry (data persists for sync).
+    /// * `delete_data` - If true, deletes all extension tables, data and the private data directory. If false, only removes the extension entry (data persists for sync).
     /// * `state` - App state
     pub async fn remove_extension_internal(
         &self,
@@ -116,6 +117,9 @@ impl ExtensionManager {
             })?;
 
             eprintln!("DEBUG: Transaction committed successfully");
+
+            // Wipe the extension's private data directory (private://)
+            remove_private_dir(app_handle, public_key, extension_name)?;
         } else {
             eprintln!(
                 "DEBUG: Keeping DB entry and permissions (delete_data=false, update mode)"
@@ -125,6 +129,14 @@ impl ExtensionManager {
         // Remove from in-memory manager
         self.remove_extension(public_key, extension_name)?;
 
+        // Drop the extension's open file streams, watches and file drops
+        state.file_streams.close_all_for_extension(&extension.id)?;
+        state
+            .file_watcher
+            .unwatch_extension(&extension.id)
+            .map_err(|reason| ExtensionError::FilesystemError { reason })?;
+        state.file_drops.release_all_for_extension(&extension.id)?;
+
         // Delete only the specific version folder: public_key/name/version
         let extension_dir =
             self.get_extension_dir(app_handle, public_key, extension_name, extension_version)?;
@@ -0,0 +1,315 @@
+// src-tauri/src/extension/core/versions.rs
+//
+// Installed versions of production extensions.
+//
+// Every version is installed into its own `<public_key>/<name>/<version>`
+// directory and installs never remove other versions, so earlier versions
+// stay on disk after an update. The version registered in the vault is the
+// active one on every device; pinning another installed version rolls back a
+// bad update without downloading the old bundle again.
+
+use crate::extension::core::compatibility::{check_host_compatibility, HOST_API_VERSION};
+use crate::extension::core::loader::read_haextension_config;
+use crate::extension::core::manifest::ExtensionManifest;
+use crate::extension::core::path_utils::{find_icon, validate_path_in_directory};
+use crate::extension::crypto::ExtensionCrypto;
+use crate::extension::error::ExtensionError;
+use crate::AppState;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+use ts_rs::TS;
+
+use super::installer::InstalledIdentity;
+use super::manager::ExtensionManager;
+
+/// One version of an extension installed on this device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledExtensionVersion {
+    pub version: String,
+    /// Version registered in the vault, the one that gets loaded
+    pub active: bool,
+}
+
+/// Orders versions by their numeric components (`1.10.0` after `1.9.2`).
+/// A pre-release (`1.0.0-beta`) sorts before its release.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn split(version: &str) -> (Vec<u64>, Option<&str>) {
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        };
+        let parts = core
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect();
+        (parts, pre)
+    }
+
+    let (a_parts, a_pre) = split(a);
+    let (b_parts, b_pre) = split(b);
+    let len = a_parts.len().max(b_parts.len());
+    for i in 0..len {
+        let x = a_parts.get(i).copied().unwrap_or(0);
+        let y = b_parts.get(i).copied().unwrap_or(0);
+        match x.cmp(&y) {
+            Ordering::Equal => {}
+            other => return other,
+        }
+    }
+    match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(x), Some(y)) => x.cmp(y),
+    }
+}
+
+/// Installed versions, newest first, with `active_version` flagged
+pub fn sort_installed_versions(
+    mut versions: Vec<String>,
+    active_version: &str,
+) -> Vec<InstalledExtensionVersion> {
+    versions.sort_by(|a, b| compare_versions(b, a));
+    versions
+        .into_iter()
+        .map(|version| InstalledExtensionVersion {
+            active: version == active_version,
+            version,
+        })
+        .collect()
+}
+
+/// Rejects versions that are not a single directory name
+pub fn validate_version_segment(version: &str) -> Result<(), ExtensionError> {
+    if version.is_empty()
+        || version == "."
+        || version.contains("..")
+        || version.contains(['/', '\\'])
+    {
+        return Err(ExtensionError::ValidationError {
+            reason: format!("Invalid version '{version}'"),
+        });
+    }
+    Ok(())
+}
+
+impl ExtensionManager {
+    fn registered_identity(
+        extension_id: &str,
+        state: &State<'_, AppState>,
+    ) -> Result<InstalledIdentity, ExtensionError> {
+        Self::installed_identity(extension_id, state)?.ok_or_else(|| {
+            ExtensionError::ValidationError {
+                reason: format!("Extension {extension_id} is not installed"),
+            }
+        })
+    }
+
+    /// Directory holding all installed versions of an extension
+    fn versions_dir(
+        &self,
+        app_handle: &AppHandle,
+        identity: &InstalledIdentity,
+    ) -> Result<PathBuf, ExtensionError> {
+        Ok(self
+            .get_base_extension_dir(app_handle)?
+            .join(&identity.public_key)
+            .join(&identity.name))
+    }
+
+    /// Versions of an extension installed on this device, newest first.
+    pub fn installed_versions(
+        &self,
+        app_handle: &AppHandle,
+        extension_id: &str,
+        state: &State<'_, AppState>,
+    ) -> Result<Vec<InstalledExtensionVersion>, ExtensionError> {
+        let identity = Self::registered_identity(extension_id, state)?;
+        let dir = self.versions_dir(app_handle, &identity)?;
+
+        let mut versions = Vec::new();
+        if dir.exists() {
+            for entry in fs::read_dir(&dir)
+                .map_err(|e| ExtensionError::filesystem_with_path(dir.display().to_string(), e))?
+            {
+                let entry = entry.map_err(|e| ExtensionError::Filesystem { source: e })?;
+                if entry.path().is_dir() {
+                    versions.push(entry.file_name().to_string_lossy().to_string());
+                }
+            }
+        }
+        Ok(sort_installed_versions(versions, &identity.version))
+    }
+
+    /// Reads and verifies the manifest of an installed version. The bundle
+    /// has to be signed by the key currently trusted for the extension.
+    fn verified_installed_manifest(
+        &self,
+        app_handle: &AppHandle,
+        identity: &InstalledIdentity,
+        version: &str,
+    ) -> Result<ExtensionManifest, ExtensionError> {
+        validate_version_segment(version)?;
+        let extension_dir =
+            self.get_extension_dir(app_handle, &identity.public_key, &identity.name, version)?;
+        if !extension_dir.is_dir() {
+            return Err(ExtensionError::ValidationError {
+                reason: format!(
+                    "Version {version} of {} is not installed on this device",
+                    identity.name
+                ),
+            });
+        }
+
+        let config = read_haextension_config(&extension_dir);
+        let manifest_relative_path = format!("{}/manifest.json", config.haextension_dir);
+        let manifest_path =
+            validate_path_in_directory(&extension_dir, &manifest_relative_path, true)?.ok_or_else(
+                || ExtensionError::ManifestError {
+                    reason: format!("manifest.json not found for version {version}"),
+                },
+            )?;
+        let manifest_content =
+            fs::read_to_string(&manifest_path).map_err(|e| ExtensionError::ManifestError {
+                reason: format!("Cannot read manifest: {e}"),
+            })?;
+        let mut manifest: ExtensionManifest = serde_json::from_str(&manifest_content)?;
+
+        if manifest.name != identity.name || manifest.version != version {
+            return Err(ExtensionError::ManifestError {
+                reason: format!(
+                    "Directory of {} {version} holds {} {}",
+                    identity.name, manifest.name, manifest.version
+                ),
+            });
+        }
+        check_host_compatibility(&manifest, HOST_API_VERSION)?;
+        if !manifest
+            .public_key
+            .eq_ignore_ascii_case(&identity.signing_key)
+        {
+            return Err(ExtensionError::SignatureVerificationFailed {
+                reason: format!(
+                    "Version {version} is signed by a key that is no longer trusted for {}",
+                    identity.name
+                ),
+            });
+        }
+
+        // Files may have been modified since the install
+        let content_hash = ExtensionCrypto::hash_directory(&extension_dir, &manifest_path)?;
+        ExtensionCrypto::verify_signature(&manifest.public_key, &content_hash, &manifest.signature)
+            .map_err(|e| ExtensionError::SignatureVerificationFailed { reason: e })?;
+
+        manifest.icon = find_icon(
+            app_handle,
+            &extension_dir,
+            &config.haextension_dir,
+            manifest.icon.as_deref(),
+        );
+        // The original key keeps namespacing tables and the install directory
+        manifest.public_key = identity.public_key.clone();
+        Ok(manifest)
+    }
+
+    /// Makes an installed version the active one for this vault and reloads
+    /// the extensions.
+    ///
+    /// Permissions stay as they are, and migrations already applied by a
+    /// newer version are not reverted: older versions have to cope with the
+    /// newer schema of their tables.
+    pub async fn set_active_version(
+        &self,
+        app_handle: &AppHandle,
+        extension_id: &str,
+        version: &str,
+        state: &State<'_, AppState>,
+    ) -> Result<Vec<InstalledExtensionVersion>, ExtensionError> {
+        let identity = Self::registered_identity(extension_id, state)?;
+        if identity.version != version {
+            let manifest = self.verified_installed_manifest(app_handle, &identity, version)?;
+            self.update_extension_version_in_database(&manifest, extension_id, None, state)?;
+            eprintln!(
+                "Extension {extension_id} pinned to version {version} (was {})",
+                identity.version
+            );
+            self.load_installed_extensions(app_handle, state).await?;
+        }
+        self.installed_versions(app_handle, extension_id, state)
+    }
+
+    /// Deletes an installed version other than the active one.
+    pub fn remove_installed_version(
+        &self,
+        app_handle: &AppHandle,
+        extension_id: &str,
+        version: &str,
+        state: &State<'_, AppState>,
+    ) -> Result<Vec<InstalledExtensionVersion>, ExtensionError> {
+        validate_version_segment(version)?;
+        let identity = Self::registered_identity(extension_id, state)?;
+        if identity.version == version {
+            return Err(ExtensionError::ValidationError {
+                reason: format!("Version {version} is active and cannot be removed"),
+            });
+        }
+
+        let versions_dir = self.versions_dir(app_handle, &identity)?;
+        let Some(version_dir) = validate_path_in_directory(&versions_dir, version, true)? else {
+            return Err(ExtensionError::ValidationError {
+                reason: format!("Version {version} is not installed on this device"),
+            });
+        };
+        fs::remove_dir_all(&version_dir).map_err(|e| {
+            ExtensionError::filesystem_with_path(version_dir.display().to_string(), e)
+        })?;
+        self.installed_versions(app_handle, extension_id, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.10.0", "1.9.2"), Ordering::Greater);
+        assert_eq!(compare_versions("1.2", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("2.0.0-beta", "2.0.0"), Ordering::Less);
+        assert_eq!(
+            compare_versions("2.0.0-alpha", "2.0.0-beta"),
+            Ordering::Less
+        );
+        assert_eq!(compare_versions("0.9.0", "1.0.0-rc.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_validate_version_segment() {
+        assert!(validate_version_segment("1.2.0").is_ok());
+        assert!(validate_version_segment("2.0.0-beta.1").is_ok());
+        for invalid in ["", ".", "..", "../1.0.0", "1.0/evil", "1.0\\evil"] {
+            assert!(validate_version_segment(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_sort_installed_versions() {
+        let versions = sort_installed_versions(
+            vec!["1.2.0".into(), "1.10.0".into(), "1.9.0".into()],
+            "1.9.0",
+        );
+        let order: Vec<&str> = versions.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(order, ["1.10.0", "1.9.0", "1.2.0"]);
+        assert_eq!(
+            versions.iter().filter(|v| v.active).count(),
+            1,
+            "only the registered version is active"
+        );
+        assert!(versions[1].active);
+    }
+}
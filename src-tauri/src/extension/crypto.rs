@@ -4,6 +4,7 @@ use std::{
 };
 
 // src-tauri/src/extension/crypto.rs
+use crate::extension::core::manifest::KeyRotationProof;
 use crate::extension::error::ExtensionError;
 use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 use sha2::{Digest, Sha256};
@@ -17,27 +18,105 @@ impl ExtensionCrypto {
         content_hash_hex: &str,
         signature_hex: &str,
     ) -> Result<(), String> {
+        let public_key = Self::parse_public_key(public_key_hex)?;
+        let signature = Self::parse_signature(signature_hex)?;
+
+        let content_hash =
+            hex::decode(content_hash_hex).map_err(|e| format!("Invalid content hash: {e}"))?;
+
+        public_key
+            .verify(&content_hash, &signature)
+            .map_err(|e| format!("Signature verification failed: {e}"))
+    }
+
+    /// Verifiziert eine Signatur über beliebige Bytes (z.B. Revocation-Listen)
+    pub fn verify_message(
+        public_key_hex: &str,
+        message: &[u8],
+        signature_hex: &str,
+    ) -> Result<(), String> {
+        let public_key = Self::parse_public_key(public_key_hex)?;
+        let signature = Self::parse_signature(signature_hex)?;
+        public_key
+            .verify(message, &signature)
+            .map_err(|e| format!("Signature verification failed: {e}"))
+    }
+
+    /// Payload a previous key signs to hand an extension over to a new key.
+    pub fn key_rotation_payload(
+        extension_name: &str,
+        previous_public_key_hex: &str,
+        public_key_hex: &str,
+    ) -> String {
+        format!(
+            "haex-extension-key-rotation-v1|{extension_name}|{}|{}",
+            previous_public_key_hex.to_lowercase(),
+            public_key_hex.to_lowercase()
+        )
+    }
+
+    /// Verifiziert eine Key-Rotation-Kette vom vertrauten Schlüssel bis zum
+    /// Schlüssel des neuen Bundles. Jeder Schritt muss vom vorherigen
+    /// Schlüssel signiert sein; Schritte vor dem vertrauten Schlüssel werden
+    /// übersprungen, damit ältere Installationen die ganze Kette nachholen können.
+    pub fn verify_key_rotation(
+        extension_name: &str,
+        trusted_public_key_hex: &str,
+        new_public_key_hex: &str,
+        proofs: &[KeyRotationProof],
+    ) -> Result<(), String> {
+        let mut current = trusted_public_key_hex.to_lowercase();
+        let target = new_public_key_hex.to_lowercase();
+
+        let start = proofs
+            .iter()
+            .position(|p| p.previous_public_key.to_lowercase() == current)
+            .ok_or_else(|| format!("No key rotation proof starts at the trusted key {current}"))?;
+
+        for proof in &proofs[start..] {
+            if current == target {
+                break;
+            }
+            if proof.previous_public_key.to_lowercase() != current {
+                return Err(format!(
+                    "Key rotation chain is broken: expected a step from {current}"
+                ));
+            }
+
+            let previous_key = Self::parse_public_key(&current)?;
+            let signature = Self::parse_signature(&proof.signature)?;
+            let payload = Self::key_rotation_payload(extension_name, &current, &proof.public_key);
+            previous_key
+                .verify(payload.as_bytes(), &signature)
+                .map_err(|e| format!("Key rotation proof verification failed: {e}"))?;
+
+            current = proof.public_key.to_lowercase();
+        }
+
+        if current != target {
+            return Err(format!(
+                "Key rotation chain ends at {current}, not at the bundle key {target}"
+            ));
+        }
+        Ok(())
+    }
+
+    fn parse_public_key(public_key_hex: &str) -> Result<VerifyingKey, String> {
         let public_key_bytes =
             hex::decode(public_key_hex).map_err(|e| format!("Invalid public key: {e}"))?;
         let public_key_array: [u8; 32] = public_key_bytes
             .try_into()
             .map_err(|_| "Invalid public key: expected 32 bytes".to_string())?;
-        let public_key = VerifyingKey::from_bytes(&public_key_array)
-            .map_err(|e| format!("Invalid public key: {e}"))?;
+        VerifyingKey::from_bytes(&public_key_array).map_err(|e| format!("Invalid public key: {e}"))
+    }
 
+    fn parse_signature(signature_hex: &str) -> Result<Signature, String> {
         let signature_bytes =
             hex::decode(signature_hex).map_err(|e| format!("Invalid signature: {e}"))?;
         let signature_array: [u8; 64] = signature_bytes
             .try_into()
             .map_err(|_| "Invalid signature: expected 64 bytes".to_string())?;
-        let signature = Signature::from_bytes(&signature_array);
-
-        let content_hash =
-            hex::decode(content_hash_hex).map_err(|e| format!("Invalid content hash: {e}"))?;
-
-        public_key
-            .verify(&content_hash, &signature)
-            .map_err(|e| format!("Signature verification failed: {e}"))
+        Ok(Signature::from_bytes(&signature_array))
     }
 
     /// Berechnet Hash eines Verzeichnisses (für Verifikation)
@@ -7,10 +7,12 @@
 //! - iframe: extension_id is resolved from public_key/name parameters
 //!           (verified by frontend via origin check)
 
+use crate::crdt::cascade::{self, CascadeRule};
 use crate::crdt::transformer::CrdtTransformer;
+use crate::crdt::undo::{UndoCapture, UndoScope};
 use crate::database::core::{parse_sql_statements, with_connection, ValueConverter};
 use crate::database::error::DatabaseError;
-use crate::event_names::EVENT_CRDT_DIRTY_TABLES_CHANGED;
+use crate::events::{self, payloads::DirtyTablesChanged};
 use crate::extension::database::executor::SqlExecutor;
 use crate::extension::database::helpers::{
     execute_migration_statements, execute_sql_with_context, is_allowed_pragma,
@@ -18,20 +20,23 @@ use crate::extension::database::helpers::{
     ExtensionSqlContext,
 };
 use crate::extension::database::queries::{
-    SQL_COUNT_APPLIED_MIGRATIONS, SQL_GET_PENDING_MIGRATIONS, SQL_GET_SYNCED_PENDING_MIGRATIONS,
-    SQL_INSERT_CRDT_MIGRATION, SQL_INSERT_EXTENSION_MIGRATION,
+    SQL_COUNT_APPLIED_MIGRATIONS, SQL_DELETE_ROW_FILTER, SQL_GET_PENDING_MIGRATIONS,
+    SQL_GET_ROW_FILTER, SQL_GET_SYNCED_PENDING_MIGRATIONS, SQL_INSERT_CRDT_MIGRATION,
+    SQL_INSERT_EXTENSION_MIGRATION, SQL_INSERT_ROW_FILTER, SQL_UPDATE_ROW_FILTER,
 };
+use crate::extension::database::row_filter::{self, ExtensionRowFilter};
+use crate::extension::database::schema::{read_extension_schema, ExtensionTableSchema};
 use crate::extension::database::types::{DatabaseQueryResult, MigrationResult};
 use crate::extension::error::ExtensionError;
 use crate::extension::limits::LimitError;
 use crate::extension::permissions::validator::SqlPermissionValidator;
-use crate::extension::utils::resolve_extension_id;
+use crate::extension::utils::{get_extension_table_prefix, resolve_extension_id};
 use crate::AppState;
 
-use rusqlite::params_from_iter;
+use rusqlite::{params_from_iter, OptionalExtension};
 use serde_json::Value as JsonValue;
 use sqlparser::ast::Statement;
-use tauri::{Emitter, Manager, State, WebviewWindow};
+use tauri::{Manager, State, WebviewWindow};
 
 /// Executes a SQL statement for an extension with full permission validation.
 #[tauri::command]
@@ -78,12 +83,20 @@ pub async fn extension_database_execute(
         extension.manifest.public_key.clone(),
         extension.manifest.name.clone(),
     );
-    let rows = execute_sql_with_context(&ctx, &sql, &params, state.inner())?;
+    let rows = execute_sql_with_context(
+        &ctx,
+        &sql,
+        &params,
+        state.inner(),
+        Some(UndoScope::Extension {
+            extension_id: extension_id.clone(),
+        }),
+    )?;
 
     // Emit event to notify frontend that dirty tables may have changed
     // This triggers the sync orchestrator to push changes to the server
     let app_handle = window.app_handle();
-    let _ = app_handle.emit_to("main", EVENT_CRDT_DIRTY_TABLES_CHANGED, ());
+    let _ = events::emit_to_main(app_handle, &DirtyTablesChanged {});
 
     Ok(DatabaseQueryResult {
         rows_affected: rows.len(),
@@ -160,30 +173,46 @@ pub async fn extension_database_transaction(
             serde_json::json!({}),
         )?;
 
+        let table_prefix = ctx.get_table_prefix();
         let mut total = 0usize;
+        // The whole transaction is one undo entry
+        let mut undo_capture = UndoCapture::start(&tx)?;
         for (sql, params) in &statements {
+            // Other extensions' tables are only reachable through their row filters
+            let sql = row_filter::apply_row_filters_to_sql(&tx, &table_prefix, sql)?;
             let has_returning = {
-                let stmt = crate::database::core::parse_single_statement(sql)?;
+                let stmt = crate::database::core::parse_single_statement(&sql)?;
+                let sql_values = ValueConverter::convert_params(params)?;
+                SqlExecutor::validate_param_types(&tx, &stmt, &sql_values)?;
+                undo_capture.watch_statement(&tx, &stmt)?;
                 crate::database::core::statement_has_returning(&stmt)
             };
 
             if has_returning {
-                let (_, rows) = SqlExecutor::query_internal(&tx, &hlc_service, sql, params)?;
+                let (_, rows) = SqlExecutor::query_internal(&tx, &hlc_service, &sql, params)?;
                 total += rows.len();
             } else {
-                SqlExecutor::execute_internal(&tx, &hlc_service, sql, params)?;
+                SqlExecutor::execute_internal(&tx, &hlc_service, &sql, params)?;
                 total += 1;
             }
         }
 
+        let undo_changes = undo_capture.finish(&tx)?;
+
         tx.commit().map_err(DatabaseError::from)?;
+        state.undo.record(
+            UndoScope::Extension {
+                extension_id: extension_id.clone(),
+            },
+            undo_changes,
+        );
         Ok(total)
     })
     .map_err(ExtensionError::from)?;
 
     // Emit event to notify frontend that dirty tables may have changed
     let app_handle = window.app_handle();
-    let _ = app_handle.emit_to("main", EVENT_CRDT_DIRTY_TABLES_CHANGED, ());
+    let _ = events::emit_to_main(app_handle, &DirtyTablesChanged {});
 
     Ok(DatabaseQueryResult {
         rows_affected: total_affected,
@@ -207,16 +236,28 @@ pub async fn extension_database_query(
     let extension_id = resolve_extension_id(&window, &state, public_key.clone(), name.clone())?;
     eprintln!("[EXT_QUERY] extension_id: {}, public_key: {:?}, name: {:?}", extension_id, public_key, name);
 
-    let _extension = state
+    query_as_extension(&state, &extension_id, sql, params).await
+}
+
+/// Runs a SELECT on behalf of `extension_id` with the same limits,
+/// permission checks and tombstone filtering as `extension_database_query`.
+/// Shared with callers that authenticate the extension themselves (local API).
+pub(crate) async fn query_as_extension(
+    state: &State<'_, AppState>,
+    extension_id: &str,
+    sql: String,
+    params: Vec<JsonValue>,
+) -> Result<DatabaseQueryResult, ExtensionError> {
+    let extension = state
         .extension_manager
-        .get_extension(&extension_id)
+        .get_extension(extension_id)
         .ok_or_else(|| ExtensionError::ValidationError {
             reason: format!("Extension with ID {} not found", extension_id),
         })?;
 
     // Get extension limits
     let limits = with_connection(&state.db, |conn| {
-        state.limits.get_limits(conn, &extension_id)
+        state.limits.get_limits(conn, extension_id)
     })?;
 
     // Validate query size
@@ -230,10 +271,10 @@ pub async fn extension_database_query(
     let _query_guard = state
         .limits
         .database()
-        .acquire_query_slot(&extension_id, &limits.database)
+        .acquire_query_slot(extension_id, &limits.database)
         .map_err(|e: LimitError| ExtensionError::Database { source: e.into() })?;
 
-    SqlPermissionValidator::validate_sql(&state, &extension_id, &sql).await?;
+    SqlPermissionValidator::validate_sql(state, extension_id, &sql).await?;
 
     // Use the comment/string-aware counter so a literal '?' inside a quoted
     // string (or a comment) is not mistaken for a real parameter placeholder.
@@ -273,7 +314,10 @@ pub async fn extension_database_query(
 
     // Store max_result_rows for use inside the closure
     let max_result_rows = limits.database.max_result_rows;
+    let table_prefix =
+        get_extension_table_prefix(&extension.manifest.public_key, &extension.manifest.name);
 
+    let started = std::time::Instant::now();
     let rows = with_connection(&state.db, |conn| {
         let sql_params = ValueConverter::convert_params(&params)?;
         let mut stmt_to_execute = ast_vec.pop().ok_or_else(|| {
@@ -283,6 +327,9 @@ pub async fn extension_database_query(
             }
         })?;
 
+        // Other extensions' tables are only reachable through their row filters
+        row_filter::apply_row_filters(conn, &table_prefix, &mut stmt_to_execute)?;
+
         // Apply CRDT tombstone filter to SELECT queries
         // This ensures tombstoned (soft-deleted) rows are filtered out
         if let Statement::Query(ref mut query) = stmt_to_execute {
@@ -340,6 +387,11 @@ pub async fn extension_database_query(
     })
     .map_err(ExtensionError::from)?;
 
+    // Feed the index advisor (`database_optimize`)
+    state
+        .slow_queries
+        .record_if_slow(extension_id, &table_prefix, &sql, started.elapsed());
+
     eprintln!("[EXT_QUERY] Result: {} rows returned", rows.len());
     Ok(DatabaseQueryResult {
         rows,
@@ -348,6 +400,172 @@ pub async fn extension_database_query(
     })
 }
 
+/// Returns the schema of the extension's own tables (columns, indexes and
+/// foreign keys), so SDK tooling can validate queries and generate types.
+#[tauri::command]
+pub async fn extension_database_get_schema(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<Vec<ExtensionTableSchema>, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+
+    let extension = state
+        .extension_manager
+        .get_extension(&extension_id)
+        .ok_or_else(|| ExtensionError::ValidationError {
+            reason: format!("Extension with ID {} not found", extension_id),
+        })?;
+
+    let ctx = ExtensionSqlContext::new(
+        extension.manifest.public_key.clone(),
+        extension.manifest.name.clone(),
+    );
+    let table_prefix = ctx.get_table_prefix();
+
+    let tables = with_connection(&state.db, |conn| read_extension_schema(conn, &table_prefix))?;
+    Ok(tables)
+}
+
+/// Registers (or replaces) a row filter on one of the extension's own tables.
+/// Other extensions querying the table only see rows matching `predicate`,
+/// e.g. `shared = ?` with `[1]`.
+#[tauri::command]
+pub async fn extension_database_set_row_filter(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    table_name: String,
+    predicate: String,
+    params: Vec<JsonValue>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<(), ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let table_name = owned_table_name(&state, &extension_id, &table_name)?;
+
+    let params_json =
+        serde_json::to_string(&params).map_err(|e| DatabaseError::SerializationError {
+            reason: e.to_string(),
+        })?;
+
+    with_connection(&state.db, |conn| {
+        row_filter::validate_row_filter(conn, &table_name, &predicate, &params)?;
+
+        let tx = conn.transaction().map_err(DatabaseError::from)?;
+        let hlc_service = state.lock_or_fail(
+            &state.hlc,
+            crate::critical::CriticalFailureCode::HlcMutexPoisoned,
+            "extension::database::commands::extension_database_set_row_filter",
+            serde_json::json!({}),
+        )?;
+
+        let exists = tx
+            .query_row(SQL_GET_ROW_FILTER.as_str(), [&table_name], |_| Ok(()))
+            .optional()?
+            .is_some();
+        let sql = if exists {
+            SQL_UPDATE_ROW_FILTER.as_str()
+        } else {
+            SQL_INSERT_ROW_FILTER.as_str()
+        };
+        SqlExecutor::execute_internal_typed(
+            &tx,
+            &hlc_service,
+            sql,
+            rusqlite::params![table_name, extension_id, predicate, params_json],
+        )?;
+
+        tx.commit().map_err(DatabaseError::from)?;
+        Ok(())
+    })?;
+
+    let _ = events::emit_to_main(window.app_handle(), &DirtyTablesChanged {});
+    Ok(())
+}
+
+/// Removes the row filter from one of the extension's own tables.
+/// Afterwards other extensions with a permission on the table see all rows.
+#[tauri::command]
+pub async fn extension_database_remove_row_filter(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    table_name: String,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<(), ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let table_name = owned_table_name(&state, &extension_id, &table_name)?;
+
+    with_connection(&state.db, |conn| {
+        let tx = conn.transaction().map_err(DatabaseError::from)?;
+        let hlc_service = state.lock_or_fail(
+            &state.hlc,
+            crate::critical::CriticalFailureCode::HlcMutexPoisoned,
+            "extension::database::commands::extension_database_remove_row_filter",
+            serde_json::json!({}),
+        )?;
+        SqlExecutor::execute_internal_typed(
+            &tx,
+            &hlc_service,
+            SQL_DELETE_ROW_FILTER.as_str(),
+            rusqlite::params![table_name, extension_id],
+        )?;
+        tx.commit().map_err(DatabaseError::from)?;
+        Ok(())
+    })?;
+
+    let _ = events::emit_to_main(window.app_handle(), &DirtyTablesChanged {});
+    Ok(())
+}
+
+/// Lists the row filters the extension registered on its tables.
+#[tauri::command]
+pub async fn extension_database_get_row_filters(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<Vec<ExtensionRowFilter>, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let filters = with_connection(&state.db, |conn| {
+        row_filter::list_row_filters(conn, &extension_id)
+    })?;
+    Ok(filters)
+}
+
+/// Resolves `table_name` (with or without surrounding quotes) and checks
+/// that it belongs to the extension. Row filters can only be set on tables
+/// the extension owns.
+fn owned_table_name(
+    state: &State<'_, AppState>,
+    extension_id: &str,
+    table_name: &str,
+) -> Result<String, ExtensionError> {
+    let extension = state
+        .extension_manager
+        .get_extension(extension_id)
+        .ok_or_else(|| ExtensionError::ValidationError {
+            reason: format!("Extension with ID {} not found", extension_id),
+        })?;
+
+    let table_name = table_name.trim_matches('"').trim_matches('`');
+    let table_prefix =
+        get_extension_table_prefix(&extension.manifest.public_key, &extension.manifest.name);
+    if !table_name.starts_with(&table_prefix) {
+        return Err(ExtensionError::ValidationError {
+            reason: format!(
+                "Row filters can only be set on the extension's own tables (prefix '{table_prefix}'), got '{table_name}'"
+            ),
+        });
+    }
+    Ok(table_name.to_string())
+}
+
 /// Registers and applies extension migrations
 #[tauri::command]
 pub async fn extension_database_register_migrations(
@@ -370,6 +588,21 @@ pub async fn extension_database_register_migrations(
 
     let ext_public_key = extension.manifest.public_key.clone();
     let ext_name = extension.manifest.name.clone();
+    let table_prefix = get_extension_table_prefix(&ext_public_key, &ext_name);
+
+    // Cascade rules of all migrations (optional `cascades` field)
+    let mut cascade_rules: Vec<CascadeRule> = Vec::new();
+    for migration_obj in &migrations {
+        if let Some(cascades) = migration_obj.get("cascades") {
+            let rules: Vec<CascadeRule> = serde_json::from_value(cascades.clone()).map_err(|e| {
+                ExtensionError::ValidationError {
+                    reason: format!("Invalid 'cascades' in migration: {}", e),
+                }
+            })?;
+            cascade_rules.extend(rules);
+        }
+    }
+    cascade::validate_rules(&table_prefix, &cascade_rules)?;
 
     // Store and track migrations in database
     for migration_obj in &migrations {
@@ -452,6 +685,8 @@ pub async fn extension_database_register_migrations(
     })?;
 
     if pending_migrations.is_empty() {
+        register_cascade_rules(&state, &table_prefix, &cascade_rules)?;
+
         // Signal extension ready even if no migrations to apply
         // This is crucial for ExternalBridge to know the extension is ready
         #[cfg(not(any(target_os = "android", target_os = "ios")))]
@@ -492,6 +727,8 @@ pub async fn extension_database_register_migrations(
         applied_names.push(migration_name.clone());
     }
 
+    register_cascade_rules(&state, &table_prefix, &cascade_rules)?;
+
     // Signal that the extension is ready after successful migration registration
     // This is for native webview mode - iframe mode signals from the frontend
     #[cfg(not(any(target_os = "android", target_os = "ios")))]
@@ -511,6 +748,21 @@ pub async fn extension_database_register_migrations(
     })
 }
 
+/// Replaces the extension's cascade rules once its tables exist.
+fn register_cascade_rules(
+    state: &AppState,
+    table_prefix: &str,
+    rules: &[CascadeRule],
+) -> Result<(), ExtensionError> {
+    with_connection(&state.db, |conn| {
+        let tx = conn.transaction()?;
+        cascade::replace_cascade_rules(&tx, table_prefix, rules)?;
+        tx.commit()?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
 /// Applies pending extension migrations that were synced from another device
 #[tauri::command]
 pub fn apply_synced_extension_migrations(
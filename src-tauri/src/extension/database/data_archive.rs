@@ -0,0 +1,549 @@
+// src-tauri/src/extension/database/data_archive.rs
+//!
+//! Export and import of one extension's data
+//!
+//! `extension_export_data` packs every table owned by an extension (the
+//! tables carrying its prefix) into a ZIP archive:
+//!
+//! - `manifest.json`: format version, the extension's public key and name,
+//!   and per table its `CREATE` statement, columns and primary key
+//! - `tables/<n>.jsonl`: one JSON object per row; CRDT and generated columns
+//!   are left out
+//! - `blobs/<sha256>`: BLOB values, referenced from rows as
+//!   `{"$blob": "<sha256>"}`
+//!
+//! `extension_import_data` restores such an archive into the same extension
+//! in this or another vault. All table names must carry the extension's
+//! prefix, and the tables must already exist: they are created by the
+//! extension's migrations, never from the archive. Columns are matched by
+//! name, archived columns the table no longer has are skipped. Rows are
+//! upserted by primary key through the CRDT executor, so they get fresh HLCs
+//! and sync like local writes.
+//!
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{Cursor, Read, Write};
+
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{Connection, ToSql, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, State};
+use ts_rs::TS;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::crdt::hlc::HlcService;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::events::{self, payloads::DirtyTablesChanged};
+use crate::extension::database::executor::SqlExecutor;
+use crate::extension::database::schema::read_extension_schema;
+use crate::extension::error::ExtensionError;
+use crate::extension::utils::get_extension_table_prefix;
+use crate::AppState;
+
+/// Version of the archive layout written by [`export_extension_data`]
+pub const DATA_ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+/// Uncompressed bytes an imported archive may expand to
+const MAX_IMPORT_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const BLOB_KEY: &str = "$blob";
+
+/// `manifest.json` of a data archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DataArchiveManifest {
+    format_version: u32,
+    public_key: String,
+    extension_name: String,
+    extension_version: String,
+    exported_at: String,
+    tables: Vec<ArchivedTable>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchivedTable {
+    name: String,
+    /// `CREATE TABLE` statement at export time, for reference
+    sql: String,
+    columns: Vec<String>,
+    primary_key: Vec<String>,
+    rows: u64,
+    /// Entry holding the rows
+    file: String,
+}
+
+/// Result of restoring one table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionDataImportTable {
+    pub table_name: String,
+    #[ts(type = "number")]
+    pub rows: u64,
+    /// Archived columns the table doesn't have (anymore)
+    pub skipped_columns: Vec<String>,
+}
+
+/// Result of `extension_import_data`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionDataImportResult {
+    /// Version of the extension that exported the data
+    pub source_version: String,
+    pub exported_at: String,
+    pub tables: Vec<ExtensionDataImportTable>,
+    #[ts(type = "number")]
+    pub total_rows: u64,
+}
+
+fn invalid(reason: String) -> DatabaseError {
+    DatabaseError::ValidationError { reason }
+}
+
+fn archive_error(e: impl std::fmt::Display) -> DatabaseError {
+    DatabaseError::SerializationError {
+        reason: format!("Invalid data archive: {e}"),
+    }
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn blob_hash(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Tables (not views, virtual or shadow tables) carrying `table_prefix`
+fn plain_tables(conn: &Connection, table_prefix: &str) -> Result<HashSet<String>, DatabaseError> {
+    let mut stmt = conn.prepare(
+        "SELECT name FROM pragma_table_list
+         WHERE schema = 'main' AND type = 'table' AND substr(name, 1, length(?1)) = ?1",
+    )?;
+    let names = stmt
+        .query_map([table_prefix], |row| row.get(0))?
+        .collect::<Result<HashSet<String>, _>>()?;
+    Ok(names)
+}
+
+/// Packs all tables of the extension identified by `public_key` / `name`
+/// into a data archive.
+pub fn export_extension_data(
+    conn: &Connection,
+    public_key: &str,
+    extension_name: &str,
+    extension_version: &str,
+    exported_at: &str,
+) -> Result<Vec<u8>, DatabaseError> {
+    let table_prefix = get_extension_table_prefix(public_key, extension_name);
+    let tables = plain_tables(conn, &table_prefix)?;
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let mut written_blobs = HashSet::new();
+    let mut archived = Vec::new();
+
+    for schema in read_extension_schema(conn, &table_prefix)?
+        .into_iter()
+        .filter(|t| tables.contains(&t.name))
+    {
+        let columns: Vec<String> = schema
+            .columns
+            .iter()
+            .filter(|c| !c.crdt && !c.generated)
+            .map(|c| c.name.clone())
+            .collect();
+        let mut primary_key: Vec<_> = schema
+            .columns
+            .iter()
+            .filter(|c| c.primary_key_position > 0)
+            .collect();
+        primary_key.sort_by_key(|c| c.primary_key_position);
+
+        let mut lines = String::new();
+        let mut new_blobs = Vec::new();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM {}",
+            columns
+                .iter()
+                .map(|c| quote(c))
+                .collect::<Vec<_>>()
+                .join(", "),
+            quote(&schema.name)
+        ))?;
+        let mut rows = stmt.query([])?;
+        let mut row_count = 0u64;
+        while let Some(row) = rows.next()? {
+            let mut object = Map::new();
+            for (index, column) in columns.iter().enumerate() {
+                let value = match row.get_ref(index)? {
+                    ValueRef::Null => JsonValue::Null,
+                    ValueRef::Integer(i) => JsonValue::from(i),
+                    ValueRef::Real(f) => JsonValue::from(f),
+                    ValueRef::Text(text) => {
+                        JsonValue::String(String::from_utf8_lossy(text).into_owned())
+                    }
+                    ValueRef::Blob(bytes) => {
+                        let hash = blob_hash(bytes);
+                        if written_blobs.insert(hash.clone()) {
+                            new_blobs.push((hash.clone(), bytes.to_vec()));
+                        }
+                        serde_json::json!({ BLOB_KEY: hash })
+                    }
+                };
+                object.insert(column.clone(), value);
+            }
+            lines.push_str(&JsonValue::Object(object).to_string());
+            lines.push('\n');
+            row_count += 1;
+        }
+
+        let file = format!("tables/{}.jsonl", archived.len());
+        zip.start_file(file.as_str(), options)
+            .map_err(archive_error)?;
+        zip.write_all(lines.as_bytes()).map_err(archive_error)?;
+        for (hash, bytes) in new_blobs {
+            zip.start_file(format!("blobs/{hash}"), options)
+                .map_err(archive_error)?;
+            zip.write_all(&bytes).map_err(archive_error)?;
+        }
+
+        archived.push(ArchivedTable {
+            name: schema.name,
+            sql: schema.sql,
+            columns,
+            primary_key: primary_key.into_iter().map(|c| c.name.clone()).collect(),
+            rows: row_count,
+            file,
+        });
+    }
+
+    let manifest = DataArchiveManifest {
+        format_version: DATA_ARCHIVE_FORMAT_VERSION,
+        public_key: public_key.to_string(),
+        extension_name: extension_name.to_string(),
+        extension_version: extension_version.to_string(),
+        exported_at: exported_at.to_string(),
+        tables: archived,
+    };
+    zip.start_file(MANIFEST_ENTRY, options)
+        .map_err(archive_error)?;
+    zip.write_all(
+        serde_json::to_string_pretty(&manifest)
+            .map_err(archive_error)?
+            .as_bytes(),
+    )
+    .map_err(archive_error)?;
+
+    Ok(zip.finish().map_err(archive_error)?.into_inner())
+}
+
+/// Reads the archive entries into memory, refusing archives that expand to
+/// more than [`MAX_IMPORT_BYTES`].
+fn read_entries(bytes: &[u8]) -> Result<HashMap<String, Vec<u8>>, DatabaseError> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(archive_error)?;
+    let mut entries = HashMap::new();
+    let mut total = 0u64;
+    for index in 0..archive.len() {
+        let entry = archive.by_index(index).map_err(archive_error)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        // Read at most one byte past the budget to detect lying headers
+        let budget = MAX_IMPORT_BYTES - total;
+        let mut content = Vec::new();
+        entry
+            .take(budget + 1)
+            .read_to_end(&mut content)
+            .map_err(archive_error)?;
+        if content.len() as u64 > budget {
+            return Err(invalid(format!(
+                "Data archive expands to more than {MAX_IMPORT_BYTES} bytes"
+            )));
+        }
+        total += content.len() as u64;
+        entries.insert(name, content);
+    }
+    Ok(entries)
+}
+
+/// Converts an archived JSON value back to an SQLite value
+fn archived_value(
+    value: &JsonValue,
+    entries: &HashMap<String, Vec<u8>>,
+) -> Result<Value, DatabaseError> {
+    Ok(match value {
+        JsonValue::Null => Value::Null,
+        JsonValue::Bool(b) => Value::Integer(i64::from(*b)),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        JsonValue::String(s) => Value::Text(s.clone()),
+        JsonValue::Object(object) => {
+            let hash = object
+                .get(BLOB_KEY)
+                .and_then(|h| h.as_str())
+                .filter(|_| object.len() == 1)
+                .ok_or_else(|| archive_error(format!("unexpected value {value}")))?;
+            let bytes = entries
+                .get(&format!("blobs/{hash}"))
+                .ok_or_else(|| archive_error(format!("missing blob {hash}")))?;
+            if blob_hash(bytes) != hash {
+                return Err(archive_error(format!("blob {hash} is corrupted")));
+            }
+            Value::Blob(bytes.clone())
+        }
+        JsonValue::Array(_) => return Err(archive_error(format!("unexpected value {value}"))),
+    })
+}
+
+/// Upsert of `columns` keyed by `primary_key`
+fn upsert_sql(table: &str, columns: &[String], primary_key: &[String]) -> String {
+    let insert = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        quote(table),
+        columns
+            .iter()
+            .map(|c| quote(c))
+            .collect::<Vec<_>>()
+            .join(", "),
+        vec!["?"; columns.len()].join(", ")
+    );
+    if primary_key.is_empty() {
+        return insert;
+    }
+
+    let conflict_target = primary_key
+        .iter()
+        .map(|c| quote(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let updates: Vec<String> = columns
+        .iter()
+        .filter(|c| !primary_key.contains(c))
+        .map(|c| format!("{0} = excluded.{0}", quote(c)))
+        .collect();
+    if updates.is_empty() {
+        format!("{insert} ON CONFLICT ({conflict_target}) DO NOTHING")
+    } else {
+        format!(
+            "{insert} ON CONFLICT ({conflict_target}) DO UPDATE SET {}",
+            updates.join(", ")
+        )
+    }
+}
+
+/// Restores a data archive into the tables of the extension identified by
+/// `public_key` / `name`. With `replace_existing`, the current rows of the
+/// archived tables are deleted first. Runs inside `tx`; nothing is written
+/// when the archive is rejected.
+pub fn import_extension_data(
+    tx: &Transaction,
+    hlc_service: &HlcService,
+    public_key: &str,
+    extension_name: &str,
+    bytes: &[u8],
+    replace_existing: bool,
+) -> Result<ExtensionDataImportResult, DatabaseError> {
+    let entries = read_entries(bytes)?;
+    let manifest: DataArchiveManifest = serde_json::from_slice(
+        entries
+            .get(MANIFEST_ENTRY)
+            .ok_or_else(|| archive_error("manifest.json is missing"))?,
+    )
+    .map_err(archive_error)?;
+
+    if manifest.format_version > DATA_ARCHIVE_FORMAT_VERSION {
+        return Err(invalid(format!(
+            "Data archive format {} is newer than the supported format {}",
+            manifest.format_version, DATA_ARCHIVE_FORMAT_VERSION
+        )));
+    }
+    if manifest.public_key != public_key || manifest.extension_name != extension_name {
+        return Err(invalid(format!(
+            "Data archive belongs to extension '{}' ({}), not '{}' ({})",
+            manifest.extension_name, manifest.public_key, extension_name, public_key
+        )));
+    }
+
+    // Validate everything before the first write
+    let table_prefix = get_extension_table_prefix(public_key, extension_name);
+    let existing = plain_tables(tx, &table_prefix)?;
+    let schemas: BTreeMap<String, HashSet<String>> = read_extension_schema(tx, &table_prefix)?
+        .into_iter()
+        .filter(|t| existing.contains(&t.name))
+        .map(|t| {
+            let columns = t
+                .columns
+                .into_iter()
+                .filter(|c| !c.crdt && !c.generated)
+                .map(|c| c.name)
+                .collect();
+            (t.name, columns)
+        })
+        .collect();
+
+    let mut missing = Vec::new();
+    for table in &manifest.tables {
+        if !table.name.starts_with(&table_prefix) {
+            return Err(invalid(format!(
+                "Table '{}' in the data archive lacks the extension prefix '{}'",
+                table.name, table_prefix
+            )));
+        }
+        if !schemas.contains_key(&table.name) {
+            missing.push(table.name.clone());
+        }
+        if !entries.contains_key(&table.file) {
+            return Err(archive_error(format!("{} is missing", table.file)));
+        }
+    }
+    if !missing.is_empty() {
+        return Err(invalid(format!(
+            "Tables missing in this vault (apply the extension's migrations first): {}",
+            missing.join(", ")
+        )));
+    }
+
+    // Parents and children may be restored in any order
+    tx.execute_batch("PRAGMA defer_foreign_keys = ON")?;
+
+    if replace_existing {
+        for table in &manifest.tables {
+            SqlExecutor::execute_internal_typed(
+                tx,
+                hlc_service,
+                &format!("DELETE FROM {}", quote(&table.name)),
+                &[],
+            )?;
+        }
+    }
+
+    let mut result = ExtensionDataImportResult {
+        source_version: manifest.extension_version.clone(),
+        exported_at: manifest.exported_at.clone(),
+        tables: Vec::new(),
+        total_rows: 0,
+    };
+    for table in &manifest.tables {
+        let table_columns = &schemas[&table.name];
+        let (columns, skipped_columns): (Vec<String>, Vec<String>) = table
+            .columns
+            .iter()
+            .cloned()
+            .partition(|c| table_columns.contains(c));
+        let primary_key: Vec<String> = table
+            .primary_key
+            .iter()
+            .filter(|c| columns.contains(c))
+            .cloned()
+            .collect();
+        let sql = upsert_sql(&table.name, &columns, &primary_key);
+
+        let content = std::str::from_utf8(&entries[&table.file]).map_err(archive_error)?;
+        let mut rows = 0u64;
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            let row: Map<String, JsonValue> = serde_json::from_str(line).map_err(archive_error)?;
+            let values = columns
+                .iter()
+                .map(|c| archived_value(row.get(c).unwrap_or(&JsonValue::Null), &entries))
+                .collect::<Result<Vec<_>, _>>()?;
+            let params: Vec<&dyn ToSql> = values.iter().map(|v| v as &dyn ToSql).collect();
+            SqlExecutor::execute_internal_typed(tx, hlc_service, &sql, &params)?;
+            rows += 1;
+        }
+
+        result.total_rows += rows;
+        result.tables.push(ExtensionDataImportTable {
+            table_name: table.name.clone(),
+            rows,
+            skipped_columns,
+        });
+    }
+
+    Ok(result)
+}
+
+fn installed_extension(
+    state: &State<'_, AppState>,
+    extension_id: &str,
+) -> Result<crate::extension::core::types::Extension, ExtensionError> {
+    state
+        .extension_manager
+        .get_extension(extension_id)
+        .ok_or_else(|| ExtensionError::ValidationError {
+            reason: format!("Extension with ID {} not found", extension_id),
+        })
+}
+
+/// Exports all tables of an extension as a data archive (ZIP bytes)
+#[tauri::command]
+pub fn extension_export_data(
+    extension_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<u8>, ExtensionError> {
+    let extension = installed_extension(&state, &extension_id)?;
+    let exported_at = time::OffsetDateTime::now_utc()
+        .format(&time::format_description::well_known::Rfc3339)
+        .unwrap_or_default();
+    let archive = with_connection(&state.db, |conn| {
+        export_extension_data(
+            conn,
+            &extension.manifest.public_key,
+            &extension.manifest.name,
+            &extension.manifest.version,
+            &exported_at,
+        )
+    })?;
+    Ok(archive)
+}
+
+/// Restores a data archive from `extension_export_data` into the extension's
+/// tables. `replaceExisting` deletes the current rows of the archived tables
+/// first; otherwise rows are merged by primary key.
+#[tauri::command]
+pub fn extension_import_data(
+    app_handle: AppHandle,
+    extension_id: String,
+    file_bytes: Vec<u8>,
+    replace_existing: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<ExtensionDataImportResult, ExtensionError> {
+    let extension = installed_extension(&state, &extension_id)?;
+    let result = with_connection(&state.db, |conn| {
+        let tx = conn.transaction()?;
+        let hlc_service = state.lock_or_fail(
+            &state.hlc,
+            crate::critical::CriticalFailureCode::HlcMutexPoisoned,
+            "extension::database::data_archive::extension_import_data",
+            serde_json::json!({}),
+        )?;
+        let result = import_extension_data(
+            &tx,
+            &hlc_service,
+            &extension.manifest.public_key,
+            &extension.manifest.name,
+            &file_bytes,
+            replace_existing.unwrap_or(false),
+        )?;
+        tx.commit()?;
+        Ok(result)
+    })?;
+
+    println!(
+        "[ExtensionData] Imported {} rows into {} tables of {}",
+        result.total_rows,
+        result.tables.len(),
+        extension.manifest.name
+    );
+    let _ = events::emit_to_main(&app_handle, &DirtyTablesChanged {});
+    Ok(result)
+}
@@ -1,15 +1,20 @@
 // src-tauri/src/extension/database/executor.rs
 
-use super::planner::SqlExecutionPlanner;
+use super::planner::{value_type_name, ColumnAffinity, SqlExecutionPlanner};
 use crate::crdt::hlc::{HlcError, HlcService};
 use crate::crdt::transformer::CrdtTransformer;
 use crate::crdt::trigger::HLC_FUNCTION_NAME;
-use crate::database::core::{convert_value_ref_to_json, strip_main_schema_prefix};
+use crate::database::core::{
+    convert_value_ref_to_json, statement_has_returning, strip_main_schema_prefix,
+};
 use crate::database::error::DatabaseError;
-use rusqlite::{params_from_iter, ToSql, Transaction};
+use rusqlite::types::Value as SqliteValue;
+use rusqlite::{params_from_iter, Connection, ToSql, Transaction};
 use serde_json::Value as JsonValue;
-use std::collections::HashSet;
+use sqlparser::ast::Statement;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use uhlc::Timestamp;
 
 /// Returns the transaction-scoped HLC for this statement. All statements that
@@ -46,13 +51,62 @@ fn tx_scoped_hlc(
     Ok(timestamp)
 }
 
+/// Declared type and constraints of a column, from `pragma_table_info`
+struct ColumnType {
+    affinity: ColumnAffinity,
+    not_null: bool,
+    /// `INTEGER PRIMARY KEY` aliases the rowid; NULL assigns the next id
+    rowid_alias: bool,
+}
+
+fn read_column_types(
+    conn: &Connection,
+    table: &str,
+) -> Result<HashMap<String, ColumnType>, DatabaseError> {
+    let mut stmt = conn.prepare("SELECT name, type, \"notnull\", pk FROM pragma_table_info(?1)")?;
+    let rows = stmt.query_map([table], |row| {
+        let declared_type: String = row.get(1)?;
+        Ok((
+            row.get::<_, String>(0)?.to_lowercase(),
+            ColumnType {
+                affinity: ColumnAffinity::from_declared_type(&declared_type),
+                not_null: row.get(2)?,
+                rowid_alias: row.get::<_, i64>(3)? > 0
+                    && declared_type.eq_ignore_ascii_case("INTEGER"),
+            },
+        ))
+    })?;
+    rows.collect::<Result<_, _>>().map_err(DatabaseError::from)
+}
+
+/// Counter for unique savepoint names on a connection
+static NEXT_SAVEPOINT: AtomicU64 = AtomicU64::new(0);
+
+/// A nested transactional scope (SQLite savepoint) inside a CRDT transaction
+///
+/// Opened with [`SqlExecutor::begin_nested`] and closed by exactly one of
+/// [`SqlExecutor::release`] or [`SqlExecutor::rollback_to`]. Scopes nest and
+/// must be closed innermost first; closing an outer scope also closes the
+/// inner ones.
+#[derive(Debug)]
+#[must_use = "a nested transaction must be released or rolled back"]
+pub struct NestedTransaction {
+    name: String,
+    /// Tables whose schema changed inside this scope. `release` hands them
+    /// to the caller; `rollback_to` drops them together with the changes.
+    pub modified_schema_tables: HashSet<String>,
+}
+
 /// SQL-Executor OHNE Berechtigungsprüfung - für interne Nutzung
 pub struct SqlExecutor;
 
 impl SqlExecutor {
-    /// Führt ein SQL Statement OHNE RETURNING aus (mit CRDT)
+    /// Führt ein SQL Statement aus (mit CRDT)
     /// Returns: modified_schema_tables
     ///
+    /// A RETURNING clause is allowed; its rows are discarded. Callers that
+    /// need them use `query_internal_typed`.
+    ///
     /// Note: This function does NOT automatically create CRDT triggers for CREATE TABLE.
     /// The caller is responsible for setting up triggers using `trigger::setup_triggers_for_table`
     /// when needed (e.g., for production extensions but not for dev mode extensions).
@@ -78,13 +132,22 @@ impl SqlExecutor {
         let raw_sql = statement.to_string();
         let sql_str = strip_main_schema_prefix(&raw_sql);
 
-        // Führe Statement aus
-        tx.execute(&sql_str, params)
-            .map_err(|e| DatabaseError::ExecutionError {
-                sql: sql_str.clone(),
-                table: None,
-                reason: format!("Execute failed: {e}"),
-            })?;
+        // Führe Statement aus. `execute` lehnt Statements ab, die Zeilen
+        // liefern — bei RETURNING werden die Zeilen daher abgerufen und verworfen.
+        let executed = if statement_has_returning(&statement) {
+            tx.prepare(&sql_str).and_then(|mut stmt| {
+                let mut rows = stmt.query(params)?;
+                while rows.next()?.is_some() {}
+                Ok(())
+            })
+        } else {
+            tx.execute(&sql_str, params).map(|_| ())
+        };
+        executed.map_err(|e| DatabaseError::ExecutionError {
+            sql: sql_str.clone(),
+            table: None,
+            reason: format!("Execute failed: {e}"),
+        })?;
 
         Ok(modified_schema_tables)
     }
@@ -161,6 +224,55 @@ impl SqlExecutor {
         Ok((modified_schema_tables, result_vec))
     }
 
+    /// Prüft gebundene Parameter gegen die Spalten, in die sie geschrieben werden
+    ///
+    /// SQLite converts or keeps mismatched values silently (`'abc'` stays text
+    /// in an INTEGER column, `42` becomes `'42'` in a TEXT column). This
+    /// rejects them, and NULL for NOT NULL columns, before execution with
+    /// the parameter position and column. Only parameters bound directly to
+    /// a column are checked (see [`SqlExecutionPlanner::column_params`]).
+    pub fn validate_param_types(
+        conn: &Connection,
+        statement: &Statement,
+        params: &[SqliteValue],
+    ) -> Result<(), DatabaseError> {
+        let mut tables: HashMap<String, HashMap<String, ColumnType>> = HashMap::new();
+
+        for binding in SqlExecutionPlanner::column_params(statement) {
+            // A count mismatch is reported by the execution itself
+            let Some(value) = params.get(binding.index) else {
+                continue;
+            };
+            if !tables.contains_key(&binding.table) {
+                let columns = read_column_types(conn, &binding.table)?;
+                tables.insert(binding.table.clone(), columns);
+            }
+            let Some(column) = tables
+                .get(&binding.table)
+                .and_then(|columns| columns.get(&binding.column.to_lowercase()))
+            else {
+                continue;
+            };
+
+            let expected = if matches!(value, SqliteValue::Null) {
+                (column.not_null && !column.rowid_alias).then_some("a NOT NULL value")
+            } else {
+                (!column.affinity.accepts(value)).then(|| column.affinity.as_str())
+            };
+            if let Some(expected) = expected {
+                return Err(DatabaseError::ParameterTypeError {
+                    position: binding.index + 1,
+                    table: binding.table,
+                    column: binding.column,
+                    expected: expected.to_string(),
+                    provided: value_type_name(value).to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Führt ein einzelnes SQL Statement OHNE Typinformationen aus (JSON params)
     pub fn execute_internal(
         tx: &Transaction,
@@ -185,6 +297,55 @@ impl SqlExecutor {
         Self::query_internal_typed(tx, hlc_service, sql, &param_refs)
     }
 
+    /// Öffnet einen verschachtelten Scope (SAVEPOINT) in `tx`
+    ///
+    /// The transaction's HLC is drawn and persisted before the savepoint, so
+    /// every write inside the scope shares the outer transaction's timestamp
+    /// and a rollback of the scope can't roll the persisted clock back.
+    /// Dirty-table marks, delete-log and trash entries are written by the
+    /// CRDT triggers inside the scope and are undone together with it.
+    pub fn begin_nested(
+        tx: &Transaction,
+        hlc_service: &HlcService,
+    ) -> Result<NestedTransaction, DatabaseError> {
+        tx_scoped_hlc(tx, hlc_service)?;
+
+        let name = format!(
+            "haex_nested_{}",
+            NEXT_SAVEPOINT.fetch_add(1, Ordering::Relaxed)
+        );
+        tx.execute_batch(&format!("SAVEPOINT {name}"))?;
+
+        Ok(NestedTransaction {
+            name,
+            modified_schema_tables: HashSet::new(),
+        })
+    }
+
+    /// Übernimmt die Änderungen des Scopes in die umgebende Transaktion
+    ///
+    /// Returns the scope's modified schema tables for the caller to merge
+    /// into its own set.
+    pub fn release(
+        tx: &Transaction,
+        nested: NestedTransaction,
+    ) -> Result<HashSet<String>, DatabaseError> {
+        tx.execute_batch(&format!("RELEASE {}", nested.name))?;
+        Ok(nested.modified_schema_tables)
+    }
+
+    /// Verwirft alle Änderungen seit `begin_nested` und schließt den Scope
+    ///
+    /// The outer transaction stays open and keeps everything written before
+    /// the scope was opened.
+    pub fn rollback_to(tx: &Transaction, nested: NestedTransaction) -> Result<(), DatabaseError> {
+        tx.execute_batch(&format!(
+            "ROLLBACK TO {name}; RELEASE {name}",
+            name = nested.name
+        ))?;
+        Ok(())
+    }
+
     /// Query für SELECT-Statements (read-only, kein CRDT nötig außer Filter)
     #[allow(dead_code)]
     pub fn query_select(
@@ -7,8 +7,10 @@ use rusqlite::params_from_iter;
 use serde_json::Value as JsonValue;
 use sqlparser::ast::Statement;
 
+use crate::crdt::cascade;
 use crate::crdt::transformer::CrdtTransformer;
 use crate::crdt::trigger;
+use crate::crdt::undo::{UndoCapture, UndoScope};
 use crate::database::core::{
     parse_sql_statements, with_connection, ValueConverter, DRIZZLE_STATEMENT_BREAKPOINT,
 };
@@ -45,7 +47,7 @@ pub fn validate_sql_table_prefix(
     ctx: &ExtensionSqlContext,
     sql: &str,
 ) -> Result<(), ExtensionError> {
-    use crate::database::core::parse_single_statement;
+    use crate::database::core::{extract_view_source_tables, parse_single_statement};
 
     let statement = parse_single_statement(sql).map_err(|e| DatabaseError::ParseError {
         reason: e.to_string(),
@@ -66,6 +68,14 @@ pub fn validate_sql_table_prefix(
         Statement::CreateIndex(create_index) => {
             vec![create_index.table_name.to_string()]
         }
+        // A view may only read from the extension's own tables and views.
+        // Otherwise it would expose tables the extension has no permission
+        // for under a name that is auto-allowed.
+        Statement::CreateView(create_view) => {
+            let mut names = vec![create_view.name.to_string()];
+            names.extend(extract_view_source_tables(&statement).unwrap_or_default());
+            names
+        }
         // For other statements (like INSERT, UPDATE, DELETE, SELECT), skip prefix validation
         // as these would be blocked by permission checks at runtime
         _ => return Ok(()),
@@ -117,9 +127,15 @@ fn validate_params(sql: &str, params: &[JsonValue]) -> Result<(), DatabaseError>
 /// Skips placeholders inside single-quoted strings (with SQL-style `''` escaping),
 /// `--` line comments, and `/* */` block comments.
 pub(super) fn count_sql_placeholders(sql: &str) -> usize {
+    placeholder_offsets(sql).len()
+}
+
+/// Byte offsets of the SQL placeholders (?) in a statement, with the same
+/// skipping rules as [`count_sql_placeholders`].
+pub(super) fn placeholder_offsets(sql: &str) -> Vec<usize> {
     let bytes = sql.as_bytes();
     let len = bytes.len();
-    let mut count = 0;
+    let mut offsets = Vec::new();
     let mut i = 0;
 
     while i < len {
@@ -161,7 +177,7 @@ pub(super) fn count_sql_placeholders(sql: &str) -> usize {
             }
             // Placeholder
             b'?' => {
-                count += 1;
+                offsets.push(i);
                 i += 1;
             }
             _ => {
@@ -170,7 +186,7 @@ pub(super) fn count_sql_placeholders(sql: &str) -> usize {
         }
     }
 
-    count
+    offsets
 }
 
 /// Executes a SQL statement with CRDT support using the provided extension context.
@@ -179,11 +195,15 @@ pub(super) fn count_sql_placeholders(sql: &str) -> usize {
 /// Unlike `extension_sql_execute`, this function does NOT perform full permission validation.
 /// It only validates that table operations use the correct extension prefix.
 /// Use this for trusted internal operations like migrations from signed bundles.
+///
+/// With an `undo_scope`, INSERT/UPDATE/DELETE statements are recorded on that
+/// undo stack (see `crdt::undo`). Migrations pass `None`.
 pub fn execute_sql_with_context(
     ctx: &ExtensionSqlContext,
     sql: &str,
     params: &[JsonValue],
     state: &AppState,
+    undo_scope: Option<UndoScope>,
 ) -> Result<Vec<Vec<JsonValue>>, ExtensionError> {
     // Validate table prefix
     validate_sql_table_prefix(ctx, sql)?;
@@ -209,6 +229,12 @@ pub fn execute_sql_with_context(
         .pop()
         .expect("invariant: ast_vec.len() == 1 checked at the guard above");
 
+    // Other extensions' tables are only reachable through their row filters
+    let table_prefix = ctx.get_table_prefix();
+    with_connection(&state.db, |conn| {
+        super::row_filter::apply_row_filters(conn, &table_prefix, &mut statement)
+    })?;
+
     // If this is a SELECT statement, apply tombstone filter and execute
     if let Statement::Query(ref mut query) = statement {
         // Apply CRDT tombstone filter to SELECT queries
@@ -264,6 +290,7 @@ pub fn execute_sql_with_context(
 
         // Convert parameters to references
         let sql_values = ValueConverter::convert_params(params)?;
+        SqlExecutor::validate_param_types(&tx, &statement, &sql_values)?;
         let param_refs: Vec<&dyn rusqlite::ToSql> = sql_values
             .iter()
             .map(|v| v as &dyn rusqlite::ToSql)
@@ -356,6 +383,15 @@ pub fn execute_sql_with_context(
         // SqlExecutor::execute_internal_typed / query_internal_typed.
         // Do NOT transform here to avoid double transformation!
 
+        let undo_capture = match undo_scope {
+            Some(_) => {
+                let mut capture = UndoCapture::start(&tx)?;
+                capture.watch_statement(&tx, &statement)?;
+                Some(capture)
+            }
+            None => None,
+        };
+
         let result = if has_returning {
             eprintln!(
                 "DEBUG: [execute_sql_with_context] Using query_internal_typed (has RETURNING)"
@@ -424,9 +460,17 @@ pub fn execute_sql_with_context(
             );
         }
 
+        let undo_changes = undo_capture
+            .map(|capture| capture.finish(&tx))
+            .transpose()?;
+
         // Commit transaction
         tx.commit().map_err(DatabaseError::from)?;
 
+        if let (Some(scope), Some(changes)) = (undo_scope, undo_changes) {
+            state.undo.record(scope, changes);
+        }
+
         Ok(result)
     })
     .map_err(ExtensionError::from)
@@ -517,6 +561,16 @@ pub fn execute_migration_statements(
         .filter(|s| !s.is_empty())
         .collect();
 
+    // Cascade triggers reference other tables of the extension; drop them so
+    // table rebuilds and column changes don't fail. They are re-created by
+    // ensure_extension_tables_have_crdt below.
+    with_connection(&state.db, |conn| {
+        let tx = conn.transaction()?;
+        cascade::drop_cascade_triggers(&tx, &ctx.get_table_prefix())?;
+        tx.commit()?;
+        Ok(())
+    })?;
+
     for statement in &statements {
         // Handle PRAGMA statements separately (not supported by sqlparser)
         // PRAGMA is used by Drizzle for table reconstruction with foreign keys
@@ -534,13 +588,14 @@ pub fn execute_migration_statements(
 
         // Execute statement with CRDT support and trigger creation
         // Ignore idempotent errors (duplicate column, table already exists)
-        match execute_sql_with_context(ctx, statement, &[], state) {
+        match execute_sql_with_context(ctx, statement, &[], state, None) {
             Ok(_) => {}
             Err(ExtensionError::Database { source }) => {
                 let error_msg = format!("{:?}", source);
                 // Check for idempotent schema errors that can be safely ignored
                 if error_msg.contains("duplicate column name")
-                    || error_msg.contains("table") && error_msg.contains("already exists")
+                    || (error_msg.contains("table") || error_msg.contains("view"))
+                        && error_msg.contains("already exists")
                 {
                     println!(
                         "[MIGRATION] Skipping already-applied schema change: {}",
@@ -610,6 +665,13 @@ fn ensure_extension_tables_have_crdt(
             }
         }
 
+        if let Err(e) = cascade::install_cascade_triggers(&tx, &ctx.get_table_prefix()) {
+            eprintln!(
+                "[CRDT] Warning: Failed to install cascade triggers for '{}::{}': {}",
+                ctx.public_key, ctx.name, e
+            );
+        }
+
         tx.commit()?;
 
         if total_columns_added > 0 || total_triggers_created > 0 {
@@ -6,10 +6,13 @@
 //!
 
 pub mod commands;
+pub mod data_archive;
 pub mod executor;
 pub mod helpers;
 pub mod planner;
 pub mod queries;
+pub mod row_filter;
+pub mod schema;
 #[cfg(test)]
 mod tests;
 pub mod types;
@@ -1,11 +1,17 @@
 // src-tauri/src/extension/database/planner.rs
 // Testable SQL execution planning logic without infrastructure dependencies
 
+use super::helpers::placeholder_offsets;
 use crate::database::core::{parse_sql_statements, ValueConverter};
 use crate::database::error::DatabaseError;
 use rusqlite::types::Value as SqliteValue;
 use serde_json::Value as JsonValue;
-use sqlparser::ast::Statement;
+use sqlparser::ast::{
+    AssignmentTarget, BinaryOperator, Expr, FromTable, Query, SelectItem, SetExpr, Statement,
+    TableFactor, TableObject, UpdateTableFromKind, Value, Visit, VisitMut, Visitor, VisitorMut,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::ControlFlow;
 
 /// Testable SQL execution planner that doesn't depend on database infrastructure
 ///
@@ -46,6 +52,169 @@ impl SqlExecutionPlanner {
             .collect()
     }
 
+    /// Collects every column a statement reads or writes
+    ///
+    /// Used for column-level permission checks. Resolve the result against
+    /// the table schemas with [`ColumnReferences::resolve`].
+    pub fn column_references(statement: &Statement) -> ColumnReferences {
+        let mut references = ColumnReferences::default();
+        let _ = statement.visit(&mut references);
+        references
+    }
+
+    /// Compiles a row filter predicate into a self-contained expression
+    ///
+    /// `predicate` is a boolean expression over the filtered table's own
+    /// columns with `?` placeholders for `params`. The parameters are inlined
+    /// as literals, since the filter ends up inside statements that bring
+    /// their own parameters. Subqueries, qualified columns and other
+    /// placeholder styles are rejected: a filter must not reach beyond the
+    /// row it is evaluated on.
+    pub fn compile_row_filter(
+        predicate: &str,
+        params: &[JsonValue],
+    ) -> Result<Expr, DatabaseError> {
+        let offsets = placeholder_offsets(predicate);
+        if offsets.len() != params.len() {
+            return Err(DatabaseError::ParameterMismatchError {
+                expected: offsets.len(),
+                provided: params.len(),
+                sql: predicate.to_string(),
+            });
+        }
+
+        let mut inlined = String::with_capacity(predicate.len());
+        let mut last = 0;
+        for (offset, param) in offsets.iter().zip(params) {
+            inlined.push_str(&predicate[last..*offset]);
+            inlined.push_str(&sql_literal(param)?);
+            last = offset + 1;
+        }
+        inlined.push_str(&predicate[last..]);
+
+        let invalid = |reason: &str| DatabaseError::StatementError {
+            reason: format!("Invalid row filter '{predicate}': {reason}"),
+        };
+
+        let wrapped = format!("SELECT 1 WHERE {inlined}");
+        let mut statements = parse_sql_statements(&wrapped)?;
+        let rendered: Vec<String> = statements.iter().map(|s| s.to_string()).collect();
+        let expr = match statements.pop() {
+            Some(Statement::Query(query)) if statements.is_empty() => match *query.body {
+                SetExpr::Select(select) => select.selection,
+                _ => None,
+            },
+            _ => None,
+        }
+        // Anything after the expression (ORDER BY, LIMIT, ...) shows up in
+        // the rendered statement.
+        .filter(|expr| rendered == [format!("SELECT 1 WHERE {expr}")])
+        .ok_or_else(|| invalid("expected a single boolean expression"))?;
+
+        let mut check = RowFilterCheck::default();
+        let _ = Visit::visit(&expr, &mut check);
+        match check.violation {
+            Some(reason) => Err(invalid(reason)),
+            None => Ok(expr),
+        }
+    }
+
+    /// Restricts a statement to the rows the row filters allow
+    ///
+    /// `filters` maps table names to predicates from
+    /// [`Self::compile_row_filter`]. Filtered tables in FROM and JOIN clauses
+    /// are replaced by `(SELECT * FROM table WHERE predicate) AS table`;
+    /// UPDATE and DELETE targets get the predicate added to their WHERE
+    /// clause. INSERT OR REPLACE and upserts into a filtered table are
+    /// rejected, since they can overwrite rows outside the filter.
+    pub fn apply_row_filters(
+        statement: &mut Statement,
+        filters: &HashMap<String, Expr>,
+    ) -> Result<(), DatabaseError> {
+        if filters.is_empty() {
+            return Ok(());
+        }
+        let lookup = |name: &str| {
+            filters
+                .iter()
+                .find(|(table, _)| table.eq_ignore_ascii_case(name))
+                .map(|(_, predicate)| predicate.clone())
+        };
+
+        if let Statement::Insert(insert) = &*statement {
+            if let TableObject::TableName(name) = &insert.table {
+                let overwrites = insert.or.is_some() || insert.replace_into || insert.on.is_some();
+                if overwrites && lookup(&normalize_name(&name.to_string())).is_some() {
+                    return Err(DatabaseError::StatementError {
+                        reason: format!(
+                            "Table '{name}' is shared through a row filter; \
+                             INSERT OR REPLACE and upserts are not allowed"
+                        ),
+                    });
+                }
+            }
+        }
+
+        // UPDATE and DELETE targets have to stay plain tables
+        let update_target = match &*statement {
+            Statement::Update(update) => Some(update.table.relation.clone()),
+            _ => None,
+        };
+        let delete_target = match &*statement {
+            Statement::Delete(delete) => Some(delete.from.clone()),
+            _ => None,
+        };
+
+        let mut rewriter = RowFilterRewriter {
+            filters,
+            error: None,
+        };
+        let _ = VisitMut::visit(statement, &mut rewriter);
+        if let Some(error) = rewriter.error {
+            return Err(error);
+        }
+
+        match statement {
+            Statement::Update(update) => {
+                if let Some(relation) = update_target {
+                    if let Some(predicate) = table_factor_name(&relation).and_then(|t| lookup(&t)) {
+                        update.selection = Some(and_predicate(update.selection.take(), predicate));
+                    }
+                    update.table.relation = relation;
+                }
+            }
+            Statement::Delete(delete) => {
+                if let Some(from) = delete_target {
+                    let (FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables)) =
+                        &from;
+                    for table in tables {
+                        if let Some(predicate) =
+                            table_factor_name(&table.relation).and_then(|t| lookup(&t))
+                        {
+                            delete.selection =
+                                Some(and_predicate(delete.selection.take(), predicate));
+                        }
+                    }
+                    delete.from = from;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Finds the parameters that are bound directly to a column
+    ///
+    /// Covers `INSERT INTO t (a, b) VALUES (?, ?)` and `UPDATE t SET a = ?`;
+    /// parameters inside larger expressions (`a = ? + 1`) are not attributed
+    /// to a column. Statements whose parameter numbering can't be followed
+    /// reliably (named parameters, CTEs, `UPDATE ... FROM` before `SET`)
+    /// yield no bindings.
+    pub fn column_params(statement: &Statement) -> Vec<ColumnParam> {
+        collect_column_params(statement).unwrap_or_default()
+    }
+
     /// Extracts table name from CREATE TABLE statement
     #[allow(dead_code)]
     pub fn extract_create_table_name(statement: &Statement) -> Option<String> {
@@ -59,6 +228,598 @@ impl SqlExecutionPlanner {
     }
 }
 
+/// Column references of a single statement, before schema resolution
+#[derive(Debug, Default)]
+pub struct ColumnReferences {
+    /// (table name, alias) for every table the statement touches
+    tables: Vec<(String, Option<String>)>,
+    /// `qualifier.column` with the qualifier being a table name or alias
+    qualified: Vec<(String, String)>,
+    /// Bare column names; they may belong to any table in the statement
+    unqualified: Vec<String>,
+    /// Tables or aliases all of whose columns are used (`*`, `t.*`,
+    /// INSERT without a column list)
+    all_columns: Vec<String>,
+}
+
+impl ColumnReferences {
+    /// Names of all tables the statement touches
+    pub fn table_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for (table, _) in &self.tables {
+            if !names.iter().any(|n| n.eq_ignore_ascii_case(table)) {
+                names.push(table.clone());
+            }
+        }
+        names
+    }
+
+    /// Maps the references to real columns, per table
+    ///
+    /// `schema` holds the columns of each table. Bare column names are
+    /// attributed to every table that has such a column, which over-reports
+    /// in joins but never misses a column. Names that match no column
+    /// (result aliases, CTE columns) are dropped; the CTE body is checked on
+    /// its own.
+    pub fn resolve(
+        &self,
+        schema: &HashMap<String, Vec<String>>,
+    ) -> BTreeMap<String, BTreeSet<String>> {
+        let mut resolved: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let columns_of = |table: &str| {
+            schema
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(table))
+                .map(|(_, columns)| columns.as_slice())
+                .unwrap_or_default()
+        };
+
+        let mut add = |table: &str, column: &str| {
+            if let Some(column) = columns_of(table)
+                .iter()
+                .find(|c| c.eq_ignore_ascii_case(column))
+            {
+                resolved
+                    .entry(table.to_string())
+                    .or_default()
+                    .insert(column.clone());
+            }
+        };
+
+        for (qualifier, column) in &self.qualified {
+            for table in self.resolve_qualifier(qualifier) {
+                add(&table, column);
+            }
+        }
+        for column in &self.unqualified {
+            for table in self.table_names() {
+                add(&table, column);
+            }
+        }
+        for qualifier in &self.all_columns {
+            for table in self.resolve_qualifier(qualifier) {
+                for column in columns_of(&table).to_vec() {
+                    add(&table, &column);
+                }
+            }
+        }
+
+        resolved
+    }
+
+    /// Tables a qualifier refers to: the aliased table, or the table itself
+    /// when it is used without an alias
+    fn resolve_qualifier(&self, qualifier: &str) -> Vec<String> {
+        let by_alias: Vec<String> = self
+            .tables
+            .iter()
+            .filter(|(_, alias)| {
+                alias
+                    .as_deref()
+                    .is_some_and(|a| a.eq_ignore_ascii_case(qualifier))
+            })
+            .map(|(table, _)| table.clone())
+            .collect();
+        if !by_alias.is_empty() {
+            return by_alias;
+        }
+        self.table_names()
+            .into_iter()
+            .filter(|table| table.eq_ignore_ascii_case(qualifier))
+            .collect()
+    }
+
+    fn add_table(&mut self, table: String, alias: Option<String>) {
+        if !self
+            .tables
+            .iter()
+            .any(|(t, a)| t.eq_ignore_ascii_case(&table) && *a == alias)
+        {
+            self.tables.push((table, alias));
+        }
+    }
+
+    /// Records the wildcards in the projections of `set_expr`. Nested
+    /// queries are visited on their own.
+    fn collect_wildcards(&mut self, set_expr: &SetExpr) {
+        match set_expr {
+            SetExpr::Select(select) => {
+                let mut tables = Vec::new();
+                for table_with_joins in &select.from {
+                    collect_from_names(&table_with_joins.relation, &mut tables);
+                    for join in &table_with_joins.joins {
+                        collect_from_names(&join.relation, &mut tables);
+                    }
+                }
+                self.collect_item_wildcards(&select.projection, &tables);
+            }
+            SetExpr::SetOperation { left, right, .. } => {
+                self.collect_wildcards(left);
+                self.collect_wildcards(right);
+            }
+            _ => {}
+        }
+    }
+
+    /// Records `*` (all of `tables`) and `t.*` in a projection or
+    /// RETURNING list
+    fn collect_item_wildcards(&mut self, items: &[SelectItem], tables: &[String]) {
+        for item in items {
+            match item {
+                SelectItem::Wildcard(_) => self.all_columns.extend(tables.iter().cloned()),
+                SelectItem::QualifiedWildcard(..) => {
+                    let item = item.to_string();
+                    let qualifier = item.split(".*").next().unwrap_or_default();
+                    self.all_columns.push(normalize_name(qualifier));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Visitor for ColumnReferences {
+    type Break = ();
+
+    fn pre_visit_statement(&mut self, statement: &Statement) -> ControlFlow<Self::Break> {
+        match statement {
+            Statement::Insert(insert) => {
+                if let TableObject::TableName(name) = &insert.table {
+                    let table = normalize_name(&name.to_string());
+                    if insert.columns.is_empty() || insert.on.is_some() {
+                        // Every column is written (or may be, by the upsert)
+                        self.all_columns.push(table.clone());
+                    }
+                    for column in &insert.columns {
+                        self.qualified
+                            .push((table.clone(), normalize_name(&column.to_string())));
+                    }
+                    if let Some(returning) = &insert.returning {
+                        self.collect_item_wildcards(returning, std::slice::from_ref(&table));
+                    }
+                    self.add_table(table, None);
+                }
+            }
+            Statement::Update(update) => {
+                for assignment in &update.assignments {
+                    // `SET col = ...` and `SET (a, b) = ...`
+                    let target = assignment.target.to_string();
+                    for column in target.trim_matches(|c| c == '(' || c == ')').split(',') {
+                        self.unqualified.push(normalize_name(column.trim()));
+                    }
+                }
+                if let Some(returning) = &update.returning {
+                    let mut tables = Vec::new();
+                    collect_from_names(&update.table.relation, &mut tables);
+                    self.collect_item_wildcards(returning, &tables);
+                }
+            }
+            Statement::Delete(delete) => {
+                if let Some(returning) = &delete.returning {
+                    let (FromTable::WithFromKeyword(from) | FromTable::WithoutKeyword(from)) =
+                        &delete.from;
+                    let mut tables = Vec::new();
+                    for table_with_joins in from {
+                        collect_from_names(&table_with_joins.relation, &mut tables);
+                    }
+                    self.collect_item_wildcards(returning, &tables);
+                }
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<Self::Break> {
+        self.collect_wildcards(&query.body);
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_table_factor(&mut self, table_factor: &TableFactor) -> ControlFlow<Self::Break> {
+        if let TableFactor::Table { name, alias, .. } = table_factor {
+            let alias = alias.as_ref().map(|a| a.name.value.clone());
+            self.add_table(normalize_name(&name.to_string()), alias);
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<Self::Break> {
+        match expr {
+            Expr::Identifier(ident) => self.unqualified.push(ident.value.clone()),
+            Expr::CompoundIdentifier(parts) if parts.len() >= 2 => self.qualified.push((
+                parts[parts.len() - 2].value.clone(),
+                parts[parts.len() - 1].value.clone(),
+            )),
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// A parameter written to a column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnParam {
+    /// 0-based position in the parameter list
+    pub index: usize,
+    pub table: String,
+    pub column: String,
+}
+
+/// SQLite type affinity of a column, derived from its declared type
+/// (<https://www.sqlite.org/datatype3.html#determination_of_column_affinity>)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnAffinity {
+    Integer,
+    Text,
+    Blob,
+    Real,
+    Numeric,
+}
+
+impl ColumnAffinity {
+    pub fn from_declared_type(declared_type: &str) -> Self {
+        let declared_type = declared_type.to_uppercase();
+        if declared_type.contains("INT") {
+            Self::Integer
+        } else if ["CHAR", "CLOB", "TEXT"]
+            .iter()
+            .any(|t| declared_type.contains(t))
+        {
+            Self::Text
+        } else if declared_type.is_empty() || declared_type.contains("BLOB") {
+            Self::Blob
+        } else if ["REAL", "FLOA", "DOUB"]
+            .iter()
+            .any(|t| declared_type.contains(t))
+        {
+            Self::Real
+        } else {
+            Self::Numeric
+        }
+    }
+
+    /// Whether a non-NULL `value` is stored as is, i.e. SQLite neither
+    /// converts it nor keeps a value of a foreign type in the column.
+    /// NUMERIC columns take text as well, since dates and booleans declared
+    /// as `DATETIME` or `BOOLEAN` end up with that affinity.
+    pub fn accepts(self, value: &SqliteValue) -> bool {
+        match (self, value) {
+            (_, SqliteValue::Null) | (Self::Blob, _) => true,
+            (Self::Integer, SqliteValue::Integer(_)) => true,
+            // Stored as integer without loss
+            (Self::Integer, SqliteValue::Real(f)) => f.is_finite() && f.fract() == 0.0,
+            (Self::Real | Self::Numeric, SqliteValue::Integer(_) | SqliteValue::Real(_)) => true,
+            (Self::Text | Self::Numeric, SqliteValue::Text(_)) => true,
+            _ => false,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Integer => "INTEGER",
+            Self::Text => "TEXT",
+            Self::Blob => "BLOB",
+            Self::Real => "REAL",
+            Self::Numeric => "NUMERIC",
+        }
+    }
+}
+
+/// Storage class name of a bound value
+pub fn value_type_name(value: &SqliteValue) -> &'static str {
+    match value {
+        SqliteValue::Null => "NULL",
+        SqliteValue::Integer(_) => "INTEGER",
+        SqliteValue::Real(_) => "REAL",
+        SqliteValue::Text(_) => "TEXT",
+        SqliteValue::Blob(_) => "BLOB",
+    }
+}
+
+fn collect_column_params(statement: &Statement) -> Option<Vec<ColumnParam>> {
+    let mut numbering = ParamNumbering::default();
+    let mut bindings = Vec::new();
+
+    match statement {
+        Statement::Insert(insert) => {
+            let TableObject::TableName(name) = &insert.table else {
+                return None;
+            };
+            let source = insert.source.as_ref()?;
+            if source.with.is_some() || insert.columns.is_empty() {
+                return None;
+            }
+            let SetExpr::Values(values) = &*source.body else {
+                return None;
+            };
+            let table = normalize_name(&name.to_string());
+            for row in &values.rows {
+                for (position, expr) in row.iter().enumerate() {
+                    match insert.columns.get(position) {
+                        Some(column) => numbering.bind(
+                            expr,
+                            &table,
+                            &normalize_name(&column.to_string()),
+                            &mut bindings,
+                        )?,
+                        None => numbering.skip(expr)?,
+                    }
+                }
+            }
+        }
+        Statement::Update(update) => {
+            let TableFactor::Table { name, .. } = &update.table.relation else {
+                return None;
+            };
+            if !update.table.joins.is_empty()
+                || matches!(update.from, Some(UpdateTableFromKind::BeforeSet(_)))
+            {
+                return None;
+            }
+            let table = normalize_name(&name.to_string());
+            for assignment in &update.assignments {
+                match &assignment.target {
+                    AssignmentTarget::ColumnName(column) => numbering.bind(
+                        &assignment.value,
+                        &table,
+                        &normalize_name(&column.to_string()),
+                        &mut bindings,
+                    )?,
+                    _ => numbering.skip(&assignment.value)?,
+                }
+            }
+        }
+        _ => return None,
+    }
+
+    Some(bindings)
+}
+
+/// Follows SQLite's parameter numbering: `?NNN` is parameter NNN, a bare
+/// `?` is one more than the largest number used so far.
+#[derive(Default)]
+struct ParamNumbering {
+    largest: usize,
+}
+
+impl ParamNumbering {
+    /// 0-based index of `placeholder`, `None` for named parameters
+    fn number(&mut self, placeholder: &str) -> Option<usize> {
+        let number = match placeholder.strip_prefix('?')? {
+            "" => self.largest + 1,
+            digits => digits.parse::<usize>().ok().filter(|n| *n > 0)?,
+        };
+        self.largest = self.largest.max(number);
+        Some(number - 1)
+    }
+
+    /// Records `expr` as written to `column` if it is a bare parameter
+    fn bind(
+        &mut self,
+        expr: &Expr,
+        table: &str,
+        column: &str,
+        bindings: &mut Vec<ColumnParam>,
+    ) -> Option<()> {
+        if let Expr::Value(value) = expr {
+            if let Value::Placeholder(placeholder) = &value.value {
+                bindings.push(ColumnParam {
+                    index: self.number(placeholder)?,
+                    table: table.to_string(),
+                    column: column.to_string(),
+                });
+                return Some(());
+            }
+        }
+        self.skip(expr)
+    }
+
+    /// Numbers the parameters inside `expr` without binding them
+    fn skip(&mut self, expr: &Expr) -> Option<()> {
+        let mut placeholders = PlaceholderCollector::default();
+        let _ = Visit::visit(expr, &mut placeholders);
+        for placeholder in placeholders.0 {
+            self.number(&placeholder)?;
+        }
+        Some(())
+    }
+}
+
+#[derive(Default)]
+struct PlaceholderCollector(Vec<String>);
+
+impl Visitor for PlaceholderCollector {
+    type Break = ();
+
+    fn pre_visit_value(&mut self, value: &Value) -> ControlFlow<Self::Break> {
+        if let Value::Placeholder(placeholder) = value {
+            self.0.push(placeholder.clone());
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// Finds constructs a row filter must not use
+#[derive(Default)]
+struct RowFilterCheck {
+    violation: Option<&'static str>,
+}
+
+impl Visitor for RowFilterCheck {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, _query: &Query) -> ControlFlow<Self::Break> {
+        self.violation = Some("subqueries are not allowed");
+        ControlFlow::Break(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<Self::Break> {
+        if matches!(expr, Expr::CompoundIdentifier(_)) {
+            self.violation = Some("columns must not be qualified");
+            return ControlFlow::Break(());
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_value(&mut self, value: &Value) -> ControlFlow<Self::Break> {
+        if matches!(value, Value::Placeholder(_)) {
+            self.violation = Some("only ? placeholders are supported");
+            return ControlFlow::Break(());
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// Wraps filtered tables in a filtering subquery
+struct RowFilterRewriter<'a> {
+    filters: &'a HashMap<String, Expr>,
+    error: Option<DatabaseError>,
+}
+
+impl VisitorMut for RowFilterRewriter<'_> {
+    type Break = ();
+
+    // post_visit, so the new subquery is not visited (and wrapped) again
+    fn post_visit_table_factor(
+        &mut self,
+        table_factor: &mut TableFactor,
+    ) -> ControlFlow<Self::Break> {
+        let TableFactor::Table { name, alias, .. } = &*table_factor else {
+            return ControlFlow::Continue(());
+        };
+        let table = normalize_name(&name.to_string());
+        let Some(predicate) = self
+            .filters
+            .iter()
+            .find(|(filtered, _)| filtered.eq_ignore_ascii_case(&table))
+            .map(|(_, predicate)| predicate)
+        else {
+            return ControlFlow::Continue(());
+        };
+        let alias = alias
+            .as_ref()
+            .map(|a| a.name.value.clone())
+            .unwrap_or_else(|| table.clone());
+
+        let sql = format!(
+            "SELECT * FROM (SELECT * FROM {} WHERE {predicate}) AS {}",
+            quote_identifier(&table),
+            quote_identifier(&alias)
+        );
+        let derived =
+            parse_sql_statements(&sql)
+                .ok()
+                .and_then(|mut statements| match statements.pop() {
+                    Some(Statement::Query(query)) => match *query.body {
+                        SetExpr::Select(mut select) if !select.from.is_empty() => {
+                            Some(select.from.swap_remove(0).relation)
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                });
+
+        match derived {
+            Some(derived) => {
+                *table_factor = derived;
+                ControlFlow::Continue(())
+            }
+            None => {
+                self.error = Some(DatabaseError::ParseError {
+                    reason: format!("Failed to apply row filter to '{table}'"),
+                    sql,
+                });
+                ControlFlow::Break(())
+            }
+        }
+    }
+}
+
+/// Renders a JSON parameter as a SQL literal
+fn sql_literal(value: &JsonValue) -> Result<String, DatabaseError> {
+    match value {
+        JsonValue::Null => Ok("NULL".to_string()),
+        JsonValue::Bool(b) => Ok(if *b { "1" } else { "0" }.to_string()),
+        JsonValue::Number(n) => Ok(n.to_string()),
+        JsonValue::String(s) => Ok(format!("'{}'", s.replace('\'', "''"))),
+        _ => Err(DatabaseError::StatementError {
+            reason: "Row filter parameters must be strings, numbers, booleans or null".to_string(),
+        }),
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn table_factor_name(table_factor: &TableFactor) -> Option<String> {
+    match table_factor {
+        TableFactor::Table { name, .. } => Some(normalize_name(&name.to_string())),
+        _ => None,
+    }
+}
+
+/// `(existing) AND (predicate)`
+fn and_predicate(existing: Option<Expr>, predicate: Expr) -> Expr {
+    match existing {
+        Some(existing) => Expr::BinaryOp {
+            left: Box::new(Expr::Nested(Box::new(existing))),
+            op: BinaryOperator::And,
+            right: Box::new(Expr::Nested(Box::new(predicate))),
+        },
+        None => predicate,
+    }
+}
+
+/// Table names (or their aliases) directly in a FROM clause
+fn collect_from_names(table_factor: &TableFactor, names: &mut Vec<String>) {
+    match table_factor {
+        TableFactor::Table { name, alias, .. } => names.push(match alias {
+            Some(alias) => alias.name.value.clone(),
+            None => normalize_name(&name.to_string()),
+        }),
+        TableFactor::NestedJoin {
+            table_with_joins, ..
+        } => {
+            collect_from_names(&table_with_joins.relation, names);
+            for join in &table_with_joins.joins {
+                collect_from_names(&join.relation, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Strips quotes and a schema or table qualifier from a name
+fn normalize_name(name: &str) -> String {
+    name.rsplit('.')
+        .next()
+        .unwrap_or(name)
+        .trim_matches('"')
+        .trim_matches('`')
+        .to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,4 +875,287 @@ mod tests {
 
         assert_eq!(table_name, None);
     }
+
+    fn resolve(sql: &str, schema: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        let statement = SqlExecutionPlanner::parse_single_statement(sql).unwrap();
+        let schema: HashMap<String, Vec<String>> = schema
+            .iter()
+            .map(|(t, cols)| (t.to_string(), cols.iter().map(|c| c.to_string()).collect()))
+            .collect();
+        SqlExecutionPlanner::column_references(&statement)
+            .resolve(&schema)
+            .into_iter()
+            .map(|(t, cols)| (t, cols.into_iter().collect()))
+            .collect()
+    }
+
+    const ITEMS: (&str, &[&str]) = ("items", &["id", "title", "password"]);
+    const TAGS: (&str, &[&str]) = ("tags", &["id", "item_id", "name"]);
+
+    #[test]
+    fn test_column_references_select() {
+        let columns = resolve("SELECT title FROM items WHERE id = ?", &[ITEMS]);
+        assert_eq!(columns["items"], vec!["id", "title"]);
+    }
+
+    #[test]
+    fn test_column_references_wildcard() {
+        let columns = resolve("SELECT * FROM items", &[ITEMS]);
+        assert_eq!(columns["items"], vec!["id", "password", "title"]);
+
+        let columns = resolve(
+            "SELECT t.* FROM items i JOIN tags t ON t.item_id = i.id",
+            &[ITEMS, TAGS],
+        );
+        assert_eq!(columns["items"], vec!["id"]);
+        assert_eq!(columns["tags"], vec!["id", "item_id", "name"]);
+    }
+
+    #[test]
+    fn test_column_references_aliases_and_subqueries() {
+        let columns = resolve(
+            "SELECT i.title FROM items i WHERE i.id IN (SELECT item_id FROM tags WHERE name = ?)",
+            &[ITEMS, TAGS],
+        );
+        assert_eq!(columns["items"], vec!["id", "title"]);
+        assert_eq!(columns["tags"], vec!["item_id", "name"]);
+    }
+
+    #[test]
+    fn test_column_references_ignores_result_aliases() {
+        let columns = resolve("SELECT title AS label FROM items ORDER BY label", &[ITEMS]);
+        assert_eq!(columns["items"], vec!["title"]);
+    }
+
+    #[test]
+    fn test_column_references_writes() {
+        let columns = resolve("INSERT INTO items (id, title) VALUES (?, ?)", &[ITEMS]);
+        assert_eq!(columns["items"], vec!["id", "title"]);
+
+        let columns = resolve("INSERT INTO items VALUES (?, ?, ?)", &[ITEMS]);
+        assert_eq!(columns["items"], vec!["id", "password", "title"]);
+
+        let columns = resolve("UPDATE items SET password = ? WHERE id = ?", &[ITEMS]);
+        assert_eq!(columns["items"], vec!["id", "password"]);
+    }
+
+    #[test]
+    fn test_column_references_returning() {
+        let columns = resolve(
+            "INSERT INTO items (id, title) VALUES (?, ?) RETURNING *",
+            &[ITEMS],
+        );
+        assert_eq!(columns["items"], vec!["id", "password", "title"]);
+
+        let columns = resolve(
+            "UPDATE items SET title = ? WHERE id = ? RETURNING *",
+            &[ITEMS],
+        );
+        assert_eq!(columns["items"], vec!["id", "password", "title"]);
+
+        let columns = resolve("DELETE FROM items WHERE id = ? RETURNING *", &[ITEMS]);
+        assert_eq!(columns["items"], vec!["id", "password", "title"]);
+
+        let columns = resolve(
+            "DELETE FROM items WHERE id = ? RETURNING password",
+            &[ITEMS],
+        );
+        assert_eq!(columns["items"], vec!["id", "password"]);
+    }
+
+    /// `items` with rows 1 and 3 shared, filtered to `shared = 1`
+    fn shared_items() -> (rusqlite::Connection, HashMap<String, Expr>) {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE items (id INTEGER PRIMARY KEY, title TEXT, shared INTEGER);
+             INSERT INTO items VALUES (1, 'a', 1), (2, 'b', 0), (3, 'c', 1);",
+        )
+        .unwrap();
+        let predicate =
+            SqlExecutionPlanner::compile_row_filter("shared = ?", &[json!(true)]).unwrap();
+        (conn, HashMap::from([("items".to_string(), predicate)]))
+    }
+
+    fn filtered(sql: &str, filters: &HashMap<String, Expr>) -> String {
+        let mut statement = SqlExecutionPlanner::parse_single_statement(sql).unwrap();
+        SqlExecutionPlanner::apply_row_filters(&mut statement, filters).unwrap();
+        statement.to_string()
+    }
+
+    #[test]
+    fn test_compile_row_filter_inlines_params() {
+        let expr = SqlExecutionPlanner::compile_row_filter(
+            "owner = ? AND shared = ? AND note IS NOT ?",
+            &[json!("it's"), json!(true), json!(null)],
+        )
+        .unwrap();
+        assert_eq!(
+            expr.to_string(),
+            "owner = 'it''s' AND shared = 1 AND note IS NOT NULL"
+        );
+    }
+
+    #[test]
+    fn test_compile_row_filter_rejects_invalid_predicates() {
+        for predicate in [
+            "id IN (SELECT id FROM other)",
+            "items.shared = 1",
+            "shared = 1 ORDER BY id",
+            "shared = 1; DROP TABLE items",
+            "shared = :flag",
+        ] {
+            assert!(
+                SqlExecutionPlanner::compile_row_filter(predicate, &[]).is_err(),
+                "{predicate}"
+            );
+        }
+
+        assert!(matches!(
+            SqlExecutionPlanner::compile_row_filter("shared = ?", &[]),
+            Err(DatabaseError::ParameterMismatchError { .. })
+        ));
+        assert!(SqlExecutionPlanner::compile_row_filter("shared = ?", &[json!([1])]).is_err());
+    }
+
+    #[test]
+    fn test_apply_row_filters_select() {
+        let (conn, filters) = shared_items();
+
+        let count = |sql: &str| -> i64 {
+            conn.query_row(&filtered(sql, &filters), [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count("SELECT COUNT(*) FROM items"), 2);
+        assert_eq!(
+            count("SELECT COUNT(*) FROM items i WHERE i.title != 'a'"),
+            1
+        );
+        assert_eq!(
+            count(
+                "SELECT COUNT(*) FROM (SELECT id FROM items) sub JOIN items ON items.id = sub.id"
+            ),
+            2
+        );
+
+        // Tables without a filter stay untouched
+        assert_eq!(
+            filtered("SELECT * FROM tags", &filters),
+            "SELECT * FROM tags"
+        );
+    }
+
+    #[test]
+    fn test_apply_row_filters_update_and_delete() {
+        let (conn, filters) = shared_items();
+
+        let changed = conn
+            .execute(&filtered("UPDATE items SET title = 'x'", &filters), [])
+            .unwrap();
+        assert_eq!(changed, 2);
+
+        let deleted = conn
+            .execute(&filtered("DELETE FROM items WHERE id < 3", &filters), [])
+            .unwrap();
+        assert_eq!(deleted, 1);
+
+        let title: String = conn
+            .query_row("SELECT title FROM items WHERE id = 2", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(title, "b");
+    }
+
+    #[test]
+    fn test_apply_row_filters_rejects_overwriting_inserts() {
+        let (_, filters) = shared_items();
+
+        for sql in [
+            "INSERT OR REPLACE INTO items (id, title) VALUES (2, 'x')",
+            "REPLACE INTO items (id, title) VALUES (2, 'x')",
+            "INSERT INTO items (id, title) VALUES (2, 'x') ON CONFLICT(id) DO UPDATE SET title = excluded.title",
+        ] {
+            let mut statement = SqlExecutionPlanner::parse_single_statement(sql).unwrap();
+            assert!(
+                SqlExecutionPlanner::apply_row_filters(&mut statement, &filters).is_err(),
+                "{sql}"
+            );
+        }
+
+        // Plain inserts cannot touch existing rows
+        let mut statement =
+            SqlExecutionPlanner::parse_single_statement("INSERT INTO items (id) VALUES (4)")
+                .unwrap();
+        assert!(SqlExecutionPlanner::apply_row_filters(&mut statement, &filters).is_ok());
+    }
+
+    fn column_params(sql: &str) -> Vec<(usize, String)> {
+        let statement = SqlExecutionPlanner::parse_single_statement(sql).unwrap();
+        SqlExecutionPlanner::column_params(&statement)
+            .into_iter()
+            .map(|p| (p.index, format!("{}.{}", p.table, p.column)))
+            .collect()
+    }
+
+    #[test]
+    fn test_column_params_insert() {
+        assert_eq!(
+            column_params("INSERT INTO items (id, title) VALUES (?, ?), (?, lower(?))"),
+            vec![
+                (0, "items.id".to_string()),
+                (1, "items.title".to_string()),
+                (2, "items.id".to_string()),
+            ]
+        );
+        assert_eq!(
+            column_params("INSERT INTO \"items\" (\"title\", id) VALUES (?2, ?1)"),
+            vec![(1, "items.title".to_string()), (0, "items.id".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_column_params_update() {
+        assert_eq!(
+            column_params("UPDATE items SET title = ? || '!', shared = ? WHERE id = ?"),
+            vec![(1, "items.shared".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_column_params_unsupported() {
+        assert!(column_params("INSERT INTO items VALUES (?, ?)").is_empty());
+        assert!(
+            column_params("INSERT INTO items (id) SELECT id FROM tags WHERE name = ?").is_empty()
+        );
+        assert!(column_params("UPDATE items SET title = :title").is_empty());
+        assert!(column_params("DELETE FROM items WHERE id = ?").is_empty());
+    }
+
+    #[test]
+    fn test_column_affinity() {
+        assert_eq!(
+            ColumnAffinity::from_declared_type("BIGINT"),
+            ColumnAffinity::Integer
+        );
+        assert_eq!(
+            ColumnAffinity::from_declared_type("varchar(20)"),
+            ColumnAffinity::Text
+        );
+        assert_eq!(ColumnAffinity::from_declared_type(""), ColumnAffinity::Blob);
+        assert_eq!(
+            ColumnAffinity::from_declared_type("DOUBLE"),
+            ColumnAffinity::Real
+        );
+        assert_eq!(
+            ColumnAffinity::from_declared_type("DATETIME"),
+            ColumnAffinity::Numeric
+        );
+
+        let text = SqliteValue::Text("abc".to_string());
+        assert!(!ColumnAffinity::Integer.accepts(&text));
+        assert!(ColumnAffinity::Integer.accepts(&SqliteValue::Real(2.0)));
+        assert!(!ColumnAffinity::Integer.accepts(&SqliteValue::Real(2.5)));
+        assert!(!ColumnAffinity::Text.accepts(&SqliteValue::Integer(42)));
+        assert!(ColumnAffinity::Numeric.accepts(&text));
+        assert!(ColumnAffinity::Blob.accepts(&text));
+        assert!(ColumnAffinity::Text.accepts(&SqliteValue::Null));
+    }
 }
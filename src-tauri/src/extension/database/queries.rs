@@ -12,6 +12,10 @@ use crate::table_names::{
     COL_EXTENSION_MIGRATIONS_EXTENSION_ID, COL_EXTENSION_MIGRATIONS_EXTENSION_VERSION,
     COL_EXTENSION_MIGRATIONS_ID, COL_EXTENSION_MIGRATIONS_MIGRATION_NAME,
     COL_EXTENSION_MIGRATIONS_SQL_STATEMENT, TABLE_EXTENSION_MIGRATIONS,
+    // Extension row filters table (synced)
+    COL_EXTENSION_ROW_FILTERS_EXTENSION_ID, COL_EXTENSION_ROW_FILTERS_ID,
+    COL_EXTENSION_ROW_FILTERS_PARAMS, COL_EXTENSION_ROW_FILTERS_PREDICATE,
+    COL_EXTENSION_ROW_FILTERS_UPDATED_AT, TABLE_EXTENSION_ROW_FILTERS,
     // Extensions table
     COL_EXTENSIONS_ID, COL_EXTENSIONS_NAME, COL_EXTENSIONS_PUBLIC_KEY, TABLE_EXTENSIONS,
 };
@@ -70,4 +74,48 @@ lazy_static::lazy_static! {
           {COL_EXTENSION_MIGRATIONS_SQL_STATEMENT}) \
          VALUES (?, ?, ?, ?, ?)"
     );
+
+    // ============================================================================
+    // Extension Row Filter Queries
+    // ============================================================================
+
+    /// Get the row filter of a table (predicate, params JSON)
+    pub static ref SQL_GET_ROW_FILTER: String = format!(
+        "SELECT {COL_EXTENSION_ROW_FILTERS_PREDICATE}, {COL_EXTENSION_ROW_FILTERS_PARAMS} \
+         FROM {TABLE_EXTENSION_ROW_FILTERS} \
+         WHERE {COL_EXTENSION_ROW_FILTERS_ID} = ?1 COLLATE NOCASE"
+    );
+
+    /// Get all row filters an extension registered (table, predicate, params JSON)
+    pub static ref SQL_GET_EXTENSION_ROW_FILTERS: String = format!(
+        "SELECT {COL_EXTENSION_ROW_FILTERS_ID}, {COL_EXTENSION_ROW_FILTERS_PREDICATE}, \
+         {COL_EXTENSION_ROW_FILTERS_PARAMS} \
+         FROM {TABLE_EXTENSION_ROW_FILTERS} \
+         WHERE {COL_EXTENSION_ROW_FILTERS_EXTENSION_ID} = ?1 \
+         ORDER BY {COL_EXTENSION_ROW_FILTERS_ID} ASC"
+    );
+
+    /// Register the row filter of a table
+    pub static ref SQL_INSERT_ROW_FILTER: String = format!(
+        "INSERT INTO {TABLE_EXTENSION_ROW_FILTERS} \
+         ({COL_EXTENSION_ROW_FILTERS_ID}, {COL_EXTENSION_ROW_FILTERS_EXTENSION_ID}, \
+          {COL_EXTENSION_ROW_FILTERS_PREDICATE}, {COL_EXTENSION_ROW_FILTERS_PARAMS}) \
+         VALUES (?1, ?2, ?3, ?4)"
+    );
+
+    /// Replace the row filter of a table owned by the given extension
+    pub static ref SQL_UPDATE_ROW_FILTER: String = format!(
+        "UPDATE {TABLE_EXTENSION_ROW_FILTERS} \
+         SET {COL_EXTENSION_ROW_FILTERS_PREDICATE} = ?3, {COL_EXTENSION_ROW_FILTERS_PARAMS} = ?4, \
+             {COL_EXTENSION_ROW_FILTERS_UPDATED_AT} = CURRENT_TIMESTAMP \
+         WHERE {COL_EXTENSION_ROW_FILTERS_ID} = ?1 \
+           AND {COL_EXTENSION_ROW_FILTERS_EXTENSION_ID} = ?2"
+    );
+
+    /// Remove the row filter of a table owned by the given extension
+    pub static ref SQL_DELETE_ROW_FILTER: String = format!(
+        "DELETE FROM {TABLE_EXTENSION_ROW_FILTERS} \
+         WHERE {COL_EXTENSION_ROW_FILTERS_ID} = ?1 \
+           AND {COL_EXTENSION_ROW_FILTERS_EXTENSION_ID} = ?2"
+    );
 }
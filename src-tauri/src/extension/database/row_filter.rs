@@ -0,0 +1,167 @@
+// src-tauri/src/extension/database/row_filter.rs
+//!
+//! Row filters for cross-extension table access
+//!
+//! An extension can register a predicate on one of its tables (e.g.
+//! `shared = ?` with `[1]`). Every statement another extension runs against
+//! that table is rewritten so it only sees and modifies matching rows; the
+//! owning extension itself is never filtered. Predicates are compiled and
+//! validated by [`SqlExecutionPlanner::compile_row_filter`].
+//!
+//! A view's query is stored in the schema and can't be rewritten per
+//! caller, so statements that reach a filtered table through a view are
+//! rejected; other extensions have to query the table itself.
+//!
+
+use std::collections::HashMap;
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use sqlparser::ast::{Expr, Statement};
+use ts_rs::TS;
+
+use crate::database::core::{parse_single_statement, resolve_view_tables};
+use crate::database::error::DatabaseError;
+use crate::extension::database::planner::SqlExecutionPlanner;
+use crate::extension::database::queries::{SQL_GET_EXTENSION_ROW_FILTERS, SQL_GET_ROW_FILTER};
+
+/// A row filter registered by an extension on one of its tables.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionRowFilter {
+    pub table_name: String,
+    /// Boolean SQL expression over the table's columns, `?` for parameters
+    pub predicate: String,
+    /// Values bound to the predicate's placeholders
+    #[ts(type = "Array<unknown>")]
+    pub params: Vec<JsonValue>,
+}
+
+/// Loads and compiles the row filters for the tables `statement` touches,
+/// skipping the caller's own tables (those starting with `caller_prefix`).
+///
+/// A stored filter that no longer compiles fails the statement instead of
+/// silently exposing the whole table, as does a filtered table the
+/// statement reaches through a view.
+pub fn load_row_filters(
+    conn: &Connection,
+    caller_prefix: &str,
+    statement: &Statement,
+) -> Result<HashMap<String, Expr>, DatabaseError> {
+    let mut filters = HashMap::new();
+    let tables = SqlExecutionPlanner::column_references(statement).table_names();
+
+    for source in resolve_view_tables(conn, &tables)? {
+        if !source.starts_with(caller_prefix) && stored_row_filter(conn, &source)?.is_some() {
+            return Err(DatabaseError::StatementError {
+                reason: format!(
+                    "'{source}' has a row filter and can't be read through a view; query the table directly"
+                ),
+            });
+        }
+    }
+
+    for table in tables {
+        if table.starts_with(caller_prefix) {
+            continue;
+        }
+        let Some((predicate, params)) = stored_row_filter(conn, &table)? else {
+            continue;
+        };
+
+        let params: Vec<JsonValue> =
+            serde_json::from_str(&params).map_err(|e| DatabaseError::SerializationError {
+                reason: format!("Invalid row filter parameters for '{table}': {e}"),
+            })?;
+        let expr = SqlExecutionPlanner::compile_row_filter(&predicate, &params)?;
+        filters.insert(table, expr);
+    }
+
+    Ok(filters)
+}
+
+/// Predicate and parameter JSON of the row filter on `table`, if any
+fn stored_row_filter(
+    conn: &Connection,
+    table: &str,
+) -> Result<Option<(String, String)>, DatabaseError> {
+    Ok(conn
+        .query_row(SQL_GET_ROW_FILTER.as_str(), [table], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .optional()?)
+}
+
+/// Rewrites `statement` so the caller only reaches rows the row filters of
+/// other extensions' tables allow.
+pub fn apply_row_filters(
+    conn: &Connection,
+    caller_prefix: &str,
+    statement: &mut Statement,
+) -> Result<(), DatabaseError> {
+    let filters = load_row_filters(conn, caller_prefix, statement)?;
+    SqlExecutionPlanner::apply_row_filters(statement, &filters)
+}
+
+/// Like [`apply_row_filters`], for callers that execute SQL text. Returns
+/// `sql` unchanged when no filter applies.
+pub fn apply_row_filters_to_sql(
+    conn: &Connection,
+    caller_prefix: &str,
+    sql: &str,
+) -> Result<String, DatabaseError> {
+    let mut statement = parse_single_statement(sql)?;
+    let filters = load_row_filters(conn, caller_prefix, &statement)?;
+    if filters.is_empty() {
+        return Ok(sql.to_string());
+    }
+    SqlExecutionPlanner::apply_row_filters(&mut statement, &filters)?;
+    Ok(statement.to_string())
+}
+
+/// Lists the row filters `extension_id` registered.
+pub fn list_row_filters(
+    conn: &Connection,
+    extension_id: &str,
+) -> Result<Vec<ExtensionRowFilter>, DatabaseError> {
+    let mut stmt = conn.prepare(SQL_GET_EXTENSION_ROW_FILTERS.as_str())?;
+    let rows = stmt.query_map([extension_id], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+
+    let mut filters = Vec::new();
+    for row in rows {
+        let (table_name, predicate, params) = row?;
+        filters.push(ExtensionRowFilter {
+            table_name,
+            predicate,
+            params: serde_json::from_str(&params).unwrap_or_default(),
+        });
+    }
+    Ok(filters)
+}
+
+/// Checks that `predicate` compiles and only uses columns of `table_name`.
+pub fn validate_row_filter(
+    conn: &Connection,
+    table_name: &str,
+    predicate: &str,
+    params: &[JsonValue],
+) -> Result<(), DatabaseError> {
+    let expr = SqlExecutionPlanner::compile_row_filter(predicate, params)?;
+    let probe = format!(
+        "SELECT 1 FROM \"{}\" WHERE {expr} LIMIT 0",
+        table_name.replace('"', "\"\"")
+    );
+    conn.prepare(&probe)
+        .map_err(|e| DatabaseError::StatementError {
+            reason: format!("Invalid row filter '{predicate}' for '{table_name}': {e}"),
+        })?;
+    Ok(())
+}
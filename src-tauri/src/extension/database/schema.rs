@@ -0,0 +1,213 @@
+// src-tauri/src/extension/database/schema.rs
+//!
+//! Schema introspection for extension tables
+//!
+//! Lists an extension's own tables and views from `sqlite_master` (filtered
+//! by the extension's table prefix) together with their columns, indexes and
+//! foreign keys, so SDK tooling can validate queries and generate types
+//! against the live database instead of shipping schema files.
+//!
+
+use rusqlite::Connection;
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::crdt::trigger::{COLUMN_HLCS_COLUMN, HLC_TIMESTAMP_COLUMN};
+use crate::database::error::DatabaseError;
+
+/// A column of an extension table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionColumnSchema {
+    pub name: String,
+    /// Declared type as written in `CREATE TABLE` (may be empty)
+    pub data_type: String,
+    pub not_null: bool,
+    /// Default value expression as SQL text
+    pub default_value: Option<String>,
+    /// 1-based position in the primary key, 0 if not part of it
+    pub primary_key_position: u32,
+    /// Generated column (`GENERATED ALWAYS AS`)
+    pub generated: bool,
+    /// CRDT bookkeeping column added by the vault (`haex_hlc`, `haex_column_hlcs`)
+    pub crdt: bool,
+}
+
+/// An index of an extension table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionIndexSchema {
+    pub name: String,
+    pub unique: bool,
+    /// `c` for `CREATE INDEX`, `u` for a UNIQUE constraint, `pk` for the primary key
+    pub origin: String,
+    /// Partial index (`CREATE INDEX ... WHERE`)
+    pub partial: bool,
+    /// Indexed columns in index order; expression entries are `null`
+    pub columns: Vec<Option<String>>,
+}
+
+/// A foreign key of an extension table. Composite keys list their columns
+/// pairwise in `columns` / `referencedColumns`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionForeignKeySchema {
+    pub columns: Vec<String>,
+    pub referenced_table: String,
+    /// Empty entries reference the primary key of `referencedTable`
+    pub referenced_columns: Vec<Option<String>>,
+    pub on_update: String,
+    pub on_delete: String,
+}
+
+/// Schema of one extension table or view.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionTableSchema {
+    pub name: String,
+    /// Read-only view (`CREATE VIEW`); views have no indexes or foreign keys
+    pub view: bool,
+    /// `CREATE TABLE` / `CREATE VIEW` statement from `sqlite_master`
+    pub sql: String,
+    pub columns: Vec<ExtensionColumnSchema>,
+    pub indexes: Vec<ExtensionIndexSchema>,
+    pub foreign_keys: Vec<ExtensionForeignKeySchema>,
+}
+
+/// Reads the schema of every table and view whose name starts with
+/// `table_prefix`, ordered by name.
+pub fn read_extension_schema(
+    conn: &Connection,
+    table_prefix: &str,
+) -> Result<Vec<ExtensionTableSchema>, DatabaseError> {
+    // substr() instead of LIKE: extension names may contain '_', which LIKE
+    // treats as a wildcard.
+    let mut stmt = conn.prepare(
+        "SELECT name, sql, type = 'view' FROM sqlite_master
+         WHERE type IN ('table', 'view') AND substr(name, 1, length(?1)) = ?1
+         ORDER BY name",
+    )?;
+    let tables = stmt
+        .query_map([table_prefix], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, bool>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    tables
+        .into_iter()
+        .map(|(name, sql, view)| {
+            Ok(ExtensionTableSchema {
+                view,
+                columns: read_columns(conn, &name)?,
+                indexes: read_indexes(conn, &name)?,
+                foreign_keys: read_foreign_keys(conn, &name)?,
+                sql: sql.unwrap_or_default(),
+                name,
+            })
+        })
+        .collect()
+}
+
+fn read_columns(
+    conn: &Connection,
+    table: &str,
+) -> Result<Vec<ExtensionColumnSchema>, DatabaseError> {
+    // table_xinfo also lists generated columns (hidden = 2 or 3); hidden = 1
+    // marks virtual-table internals, which extensions never see.
+    let mut stmt = conn.prepare(
+        "SELECT name, type, \"notnull\", dflt_value, pk, hidden
+         FROM pragma_table_xinfo(?1) WHERE hidden != 1 ORDER BY cid",
+    )?;
+    let columns = stmt
+        .query_map([table], |row| {
+            let name: String = row.get(0)?;
+            let hidden: i64 = row.get(5)?;
+            Ok(ExtensionColumnSchema {
+                crdt: name == HLC_TIMESTAMP_COLUMN || name == COLUMN_HLCS_COLUMN,
+                name,
+                data_type: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                not_null: row.get::<_, i64>(2)? != 0,
+                default_value: row.get(3)?,
+                primary_key_position: row.get(4)?,
+                generated: hidden >= 2,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(columns)
+}
+
+fn read_indexes(
+    conn: &Connection,
+    table: &str,
+) -> Result<Vec<ExtensionIndexSchema>, DatabaseError> {
+    let mut stmt = conn.prepare(
+        "SELECT name, \"unique\", origin, partial FROM pragma_index_list(?1) ORDER BY name",
+    )?;
+    let indexes = stmt
+        .query_map([table], |row| {
+            Ok(ExtensionIndexSchema {
+                name: row.get(0)?,
+                unique: row.get::<_, i64>(1)? != 0,
+                origin: row.get(2)?,
+                partial: row.get::<_, i64>(3)? != 0,
+                columns: Vec::new(),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut columns_stmt = conn.prepare("SELECT name FROM pragma_index_info(?1) ORDER BY seqno")?;
+    indexes
+        .into_iter()
+        .map(|mut index| {
+            index.columns = columns_stmt
+                .query_map([&index.name], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(index)
+        })
+        .collect()
+}
+
+fn read_foreign_keys(
+    conn: &Connection,
+    table: &str,
+) -> Result<Vec<ExtensionForeignKeySchema>, DatabaseError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, \"table\", \"from\", \"to\", on_update, on_delete
+         FROM pragma_foreign_key_list(?1) ORDER BY id, seq",
+    )?;
+    let rows = stmt
+        .query_map([table], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                ExtensionForeignKeySchema {
+                    referenced_table: row.get(1)?,
+                    columns: vec![row.get(2)?],
+                    referenced_columns: vec![row.get(3)?],
+                    on_update: row.get(4)?,
+                    on_delete: row.get(5)?,
+                },
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // One row per column; rows of a composite key share the same id.
+    let mut foreign_keys: Vec<(i64, ExtensionForeignKeySchema)> = Vec::new();
+    for (id, fk) in rows {
+        match foreign_keys.last_mut() {
+            Some((last_id, last)) if *last_id == id => {
+                last.columns.extend(fk.columns);
+                last.referenced_columns.extend(fk.referenced_columns);
+            }
+            _ => foreign_keys.push((id, fk)),
+        }
+    }
+    Ok(foreign_keys.into_iter().map(|(_, fk)| fk).collect())
+}
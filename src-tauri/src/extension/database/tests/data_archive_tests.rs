@@ -0,0 +1,299 @@
+// src-tauri/src/extension/database/tests/data_archive_tests.rs
+//!
+//! Tests for the per-extension data archive (export / import)
+//!
+
+use rusqlite::types::Value;
+use rusqlite::Connection;
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::crdt::hlc::HlcService;
+use crate::crdt::trigger::DELETED_ROWS_TABLE;
+use crate::database::error::DatabaseError;
+use crate::extension::database::data_archive::{
+    export_extension_data, import_extension_data, ExtensionDataImportResult,
+};
+use crate::test_support::crdt::{create_crdt_table, open_crdt_connection};
+
+const PUBLIC_KEY: &str = "pk";
+const NAME: &str = "my_app";
+
+/// A vault with the tables of `pk__my_app__` as created by its migrations
+fn vault(device: &str) -> (Connection, HlcService) {
+    let hlc = HlcService::new_for_testing(device);
+    let conn = open_crdt_connection(hlc.clone()).unwrap();
+    for (table, create_sql) in [
+        (
+            "pk__my_app__folders",
+            "CREATE TABLE pk__my_app__folders (id TEXT PRIMARY KEY NOT NULL, name TEXT NOT NULL)",
+        ),
+        (
+            "pk__my_app__files",
+            "CREATE TABLE pk__my_app__files (
+                 id TEXT PRIMARY KEY NOT NULL,
+                 folder_id TEXT NOT NULL REFERENCES pk__my_app__folders(id),
+                 size REAL,
+                 content BLOB,
+                 name_lower TEXT GENERATED ALWAYS AS (lower(id)) VIRTUAL
+             )",
+        ),
+        (
+            "pk__other__secrets",
+            "CREATE TABLE pk__other__secrets (id TEXT PRIMARY KEY NOT NULL, secret TEXT)",
+        ),
+    ] {
+        create_crdt_table(&conn, table, create_sql).unwrap();
+    }
+    conn.execute_batch(
+        "CREATE VIEW pk__my_app__sizes AS SELECT folder_id, sum(size) FROM pk__my_app__files GROUP BY folder_id;",
+    )
+    .unwrap();
+
+    (conn, hlc)
+}
+
+fn seed(conn: &Connection) {
+    conn.execute_batch(
+        "INSERT INTO pk__my_app__folders (id, name) VALUES ('f1', 'Docs'), ('f2', 'Pics');
+         INSERT INTO pk__my_app__files (id, folder_id, size, content) VALUES
+             ('a', 'f1', 1.5, X'CAFE'),
+             ('b', 'f2', 2, X'CAFE'),
+             ('c', 'f2', NULL, NULL);
+         INSERT INTO pk__other__secrets (id, secret) VALUES ('s', 'hidden');",
+    )
+    .unwrap();
+}
+
+fn export(conn: &Connection) -> Vec<u8> {
+    export_extension_data(conn, PUBLIC_KEY, NAME, "1.2.0", "2026-01-01T00:00:00Z").unwrap()
+}
+
+fn import(
+    conn: &mut Connection,
+    hlc: &HlcService,
+    bytes: &[u8],
+    replace: bool,
+) -> Result<ExtensionDataImportResult, DatabaseError> {
+    let tx = conn.transaction().unwrap();
+    let result = import_extension_data(&tx, hlc, PUBLIC_KEY, NAME, bytes, replace)?;
+    tx.commit().unwrap();
+    Ok(result)
+}
+
+fn rows(conn: &Connection, sql: &str) -> Vec<Vec<JsonValue>> {
+    let mut stmt = conn.prepare(sql).unwrap();
+    let columns = stmt.column_count();
+    stmt.query_map([], |row| {
+        (0..columns)
+            .map(|i| {
+                Ok(match row.get::<_, Value>(i)? {
+                    Value::Null => JsonValue::Null,
+                    Value::Integer(v) => JsonValue::from(v),
+                    Value::Real(v) => JsonValue::from(v),
+                    Value::Text(v) => JsonValue::from(v),
+                    Value::Blob(v) => JsonValue::from(hex::encode(v)),
+                })
+            })
+            .collect()
+    })
+    .unwrap()
+    .collect::<Result<_, _>>()
+    .unwrap()
+}
+
+/// Entry names of an archive
+fn entry_names(bytes: &[u8]) -> Vec<String> {
+    let archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+    let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    names.sort();
+    names
+}
+
+/// Rebuilds an archive with `manifest.json` passed through `edit`
+fn with_manifest(bytes: &[u8], edit: impl Fn(&mut JsonValue)) -> Vec<u8> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).unwrap();
+        let mut content = Vec::new();
+        std::io::Read::read_to_end(&mut entry, &mut content).unwrap();
+        if entry.name() == "manifest.json" {
+            let mut manifest: JsonValue = serde_json::from_slice(&content).unwrap();
+            edit(&mut manifest);
+            content = serde_json::to_vec(&manifest).unwrap();
+        }
+        zip.start_file(entry.name(), SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(&content).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+#[test]
+fn test_export_contains_only_the_extensions_tables() {
+    let (conn, _hlc) = vault("source");
+    seed(&conn);
+    let bytes = export(&conn);
+
+    // One row file per table, the shared blob stored once, no view
+    assert_eq!(
+        entry_names(&bytes),
+        vec![
+            format!("blobs/{}", hex::encode(Sha256::digest([0xCAu8, 0xFE]))),
+            "manifest.json".to_string(),
+            "tables/0.jsonl".to_string(),
+            "tables/1.jsonl".to_string(),
+        ]
+    );
+
+    let mut archive = ZipArchive::new(Cursor::new(&bytes)).unwrap();
+    let manifest: JsonValue =
+        serde_json::from_reader(archive.by_name("manifest.json").unwrap()).unwrap();
+    assert_eq!(manifest["extensionVersion"], "1.2.0");
+    let tables: Vec<&str> = manifest["tables"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(tables, vec!["pk__my_app__files", "pk__my_app__folders"]);
+    // CRDT and generated columns are left out
+    assert_eq!(
+        manifest["tables"][0]["columns"],
+        serde_json::json!(["id", "folder_id", "size", "content"])
+    );
+    assert_eq!(manifest["tables"][0]["rows"], 3);
+}
+
+#[test]
+fn test_roundtrip_into_another_vault() {
+    let (source, _) = vault("source");
+    seed(&source);
+    let bytes = export(&source);
+
+    let (mut target, hlc) = vault("target");
+    let result = import(&mut target, &hlc, &bytes, false).unwrap();
+    assert_eq!(result.total_rows, 5);
+    assert_eq!(result.source_version, "1.2.0");
+
+    let query = "SELECT id, folder_id, size, content FROM pk__my_app__files ORDER BY id";
+    assert_eq!(rows(&target, query), rows(&source, query));
+    assert_eq!(
+        rows(&target, "SELECT name FROM pk__my_app__folders ORDER BY id"),
+        vec![vec![JsonValue::from("Docs")], vec![JsonValue::from("Pics")]]
+    );
+    // Other extensions' tables are untouched
+    assert!(rows(&target, "SELECT * FROM pk__other__secrets").is_empty());
+
+    // Imported rows carry the target's fresh HLCs
+    let unstamped = rows(
+        &target,
+        "SELECT haex_hlc FROM pk__my_app__files WHERE haex_hlc IS NULL",
+    );
+    assert!(unstamped.is_empty());
+}
+
+#[test]
+fn test_merge_and_replace() {
+    let (source, _) = vault("source");
+    seed(&source);
+    let bytes = export(&source);
+
+    let (mut target, hlc) = vault("target");
+    target
+        .execute_batch(
+            "INSERT INTO pk__my_app__folders (id, name) VALUES ('f1', 'Old'), ('local', 'Local');",
+        )
+        .unwrap();
+
+    // Merge: archived rows win by primary key, local-only rows stay
+    import(&mut target, &hlc, &bytes, false).unwrap();
+    assert_eq!(
+        rows(
+            &target,
+            "SELECT id, name FROM pk__my_app__folders ORDER BY id"
+        ),
+        vec![
+            vec![JsonValue::from("f1"), JsonValue::from("Docs")],
+            vec![JsonValue::from("f2"), JsonValue::from("Pics")],
+            vec![JsonValue::from("local"), JsonValue::from("Local")],
+        ]
+    );
+
+    // Replace: local-only rows are deleted through the delete-log
+    import(&mut target, &hlc, &bytes, true).unwrap();
+    assert_eq!(
+        rows(&target, "SELECT id FROM pk__my_app__folders ORDER BY id"),
+        vec![vec![JsonValue::from("f1")], vec![JsonValue::from("f2")]]
+    );
+    let logged = rows(
+        &target,
+        &format!(
+            "SELECT 1 FROM {DELETED_ROWS_TABLE} WHERE table_name = 'pk__my_app__folders' AND row_pks LIKE '%local%'"
+        ),
+    );
+    assert_eq!(logged.len(), 1);
+}
+
+#[test]
+fn test_import_skips_dropped_columns() {
+    let (source, _) = vault("source");
+    seed(&source);
+    source
+        .execute_batch("ALTER TABLE pk__my_app__folders ADD COLUMN color TEXT;")
+        .unwrap();
+    let bytes = export(&source);
+
+    let (mut target, hlc) = vault("target");
+    let result = import(&mut target, &hlc, &bytes, false).unwrap();
+    let folders = result
+        .tables
+        .iter()
+        .find(|t| t.table_name == "pk__my_app__folders")
+        .unwrap();
+    assert_eq!(folders.rows, 2);
+    assert_eq!(folders.skipped_columns, vec!["color".to_string()]);
+}
+
+#[test]
+fn test_import_validates_the_archive() {
+    let (source, _) = vault("source");
+    seed(&source);
+    let bytes = export(&source);
+    let (mut target, hlc) = vault("target");
+
+    // Archive of another extension
+    let tx = target.transaction().unwrap();
+    assert!(matches!(
+        import_extension_data(&tx, &hlc, PUBLIC_KEY, "other", &bytes, false),
+        Err(DatabaseError::ValidationError { .. })
+    ));
+    drop(tx);
+
+    // A table outside the prefix, even if the manifest claims the extension
+    let foreign = with_manifest(&bytes, |m| {
+        m["tables"][0]["name"] = JsonValue::from("pk__other__secrets");
+    });
+    assert!(matches!(
+        import(&mut target, &hlc, &foreign, false),
+        Err(DatabaseError::ValidationError { .. })
+    ));
+
+    // Tables the extension's migrations haven't created
+    target
+        .execute_batch("DROP TABLE pk__my_app__files;")
+        .unwrap();
+    assert!(matches!(
+        import(&mut target, &hlc, &bytes, false),
+        Err(DatabaseError::ValidationError { .. })
+    ));
+    // Nothing was written by the rejected imports
+    assert!(rows(&target, "SELECT * FROM pk__my_app__folders").is_empty());
+    assert!(rows(&target, "SELECT * FROM pk__other__secrets").is_empty());
+
+    assert!(import(&mut target, &hlc, b"not a zip", false).is_err());
+}
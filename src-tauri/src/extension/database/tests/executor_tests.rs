@@ -19,6 +19,18 @@
 
 #[cfg(test)]
 mod tests {
+    use crate::crdt::hlc::HlcService;
+    use crate::crdt::trigger::{ensure_crdt_columns, setup_triggers_for_table};
+    use crate::database::connection_context::ConnectionContext;
+    use crate::database::core::{install_tx_hlc_hooks, register_current_hlc_udf};
+    use crate::database::error::DatabaseError;
+    use crate::extension::database::executor::SqlExecutor;
+    use crate::extension::database::planner::SqlExecutionPlanner;
+    use crate::table_names::{TABLE_CRDT_CONFIGS, TABLE_CRDT_DIRTY_TABLES};
+    use rusqlite::types::Value;
+    use rusqlite::Connection;
+    use serde_json::{json, Value as JsonValue};
+
     // Placeholder for future unit tests
     // These would require setting up:
     // - In-memory SQLite database
@@ -36,10 +48,184 @@ mod tests {
         // 5. Verify result
     }
 
+    /// In-memory vault with a CRDT table `items`, without triggers
+    fn crdt_fixture() -> (Connection, HlcService) {
+        let conn = Connection::open_in_memory().unwrap();
+        let hlc = HlcService::new_for_testing("executor-test-device");
+        let ctx = ConnectionContext::new();
+        register_current_hlc_udf(&conn, hlc.clone(), ctx.clone()).unwrap();
+        install_tx_hlc_hooks(&conn, ctx).unwrap();
+        conn.execute_batch(&format!(
+            "CREATE TABLE {TABLE_CRDT_CONFIGS} (key TEXT PRIMARY KEY, type TEXT NOT NULL, value TEXT NOT NULL);
+             CREATE TABLE items (
+                 id TEXT PRIMARY KEY NOT NULL,
+                 title TEXT,
+                 haex_hlc TEXT,
+                 haex_column_hlcs TEXT NOT NULL DEFAULT '{{}}'
+             );"
+        ))
+        .unwrap();
+        (conn, hlc)
+    }
+
+    fn query(conn: &mut Connection, hlc: &HlcService, sql: &str) -> Vec<Vec<JsonValue>> {
+        let tx = conn.transaction().unwrap();
+        let (_, rows) = SqlExecutor::query_internal(&tx, hlc, sql, &[]).unwrap();
+        tx.commit().unwrap();
+        rows
+    }
+
     #[test]
-    #[ignore] // Requires infrastructure setup
     fn test_query_internal_typed_with_returning() {
-        // TODO: Test RETURNING clause handling
+        let (mut conn, hlc) = crdt_fixture();
+
+        let rows = query(
+            &mut conn,
+            &hlc,
+            "INSERT INTO items (id, title) VALUES ('a', 'first') RETURNING id, title, haex_hlc",
+        );
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0][..2], [json!("a"), json!("first")]);
+        let insert_hlc = rows[0][2].clone();
+        assert!(insert_hlc.is_string(), "RETURNING sees the stamped HLC");
+
+        let rows = query(
+            &mut conn,
+            &hlc,
+            "INSERT INTO items (id, title) VALUES ('a', 'second')
+             ON CONFLICT(id) DO UPDATE SET title = excluded.title RETURNING title, haex_hlc",
+        );
+        assert_eq!(rows[0][0], json!("second"));
+        assert_ne!(rows[0][1], insert_hlc, "conflict branch is stamped");
+
+        let rows = query(
+            &mut conn,
+            &hlc,
+            "UPDATE items SET title = 'third' WHERE id = 'a' RETURNING title",
+        );
+        assert_eq!(rows, vec![vec![json!("third")]]);
+
+        let rows = query(
+            &mut conn,
+            &hlc,
+            "DELETE FROM items WHERE id = 'a' RETURNING id",
+        );
+        assert_eq!(rows, vec![vec![json!("a")]]);
+
+        let rows = query(
+            &mut conn,
+            &hlc,
+            "DELETE FROM items WHERE id = 'a' RETURNING id",
+        );
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn test_execute_internal_typed_discards_returning_rows() {
+        let (mut conn, hlc) = crdt_fixture();
+
+        let tx = conn.transaction().unwrap();
+        SqlExecutor::execute_internal(
+            &tx,
+            &hlc,
+            "INSERT INTO items (id, title) VALUES ('a', 'first') RETURNING *",
+            &[],
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    /// Names of the tables marked dirty by the CRDT triggers
+    fn dirty_tables(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT table_name FROM {TABLE_CRDT_DIRTY_TABLES} ORDER BY table_name"
+            ))
+            .unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn titles(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn.prepare("SELECT title FROM items ORDER BY id").unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_nested_transactions_roll_back_writes_and_dirty_marks() {
+        let (mut conn, hlc) = crdt_fixture();
+        conn.execute_batch(&format!(
+            "INSERT INTO {TABLE_CRDT_CONFIGS} (key, type, value) VALUES ('triggers_enabled', 'system', '1');
+             CREATE TABLE {TABLE_CRDT_DIRTY_TABLES} (table_name TEXT PRIMARY KEY, last_modified TEXT);
+             CREATE TABLE tags (id TEXT PRIMARY KEY NOT NULL, name TEXT);"
+        ))
+        .unwrap();
+        {
+            let tx = conn.transaction().unwrap();
+            ensure_crdt_columns(&tx, "tags").unwrap();
+            for table in ["items", "tags"] {
+                setup_triggers_for_table(&tx, table, false).unwrap();
+            }
+            tx.commit().unwrap();
+        }
+
+        let tx = conn.transaction().unwrap();
+        let insert = |sql: &str| SqlExecutor::execute_internal(&tx, &hlc, sql, &[]).unwrap();
+
+        insert("INSERT INTO items (id, title) VALUES ('a', 'outer')");
+
+        let kept = SqlExecutor::begin_nested(&tx, &hlc).unwrap();
+        insert("INSERT INTO items (id, title) VALUES ('b', 'released')");
+
+        let dropped = SqlExecutor::begin_nested(&tx, &hlc).unwrap();
+        insert("INSERT INTO items (id, title) VALUES ('c', 'rolled back')");
+        insert("INSERT INTO tags (id, name) VALUES ('t', 'rolled back')");
+        SqlExecutor::rollback_to(&tx, dropped).unwrap();
+
+        SqlExecutor::release(&tx, kept).unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(titles(&conn), vec!["outer", "released"]);
+        assert_eq!(dirty_tables(&conn), vec!["items"]);
+
+        let hlcs: Vec<String> = conn
+            .prepare("SELECT DISTINCT haex_hlc FROM items")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(hlcs.len(), 1, "nested scopes share the transaction HLC");
+    }
+
+    #[test]
+    fn test_rolling_back_outer_scope_closes_inner_scopes() {
+        let (mut conn, hlc) = crdt_fixture();
+
+        let tx = conn.transaction().unwrap();
+        let outer = SqlExecutor::begin_nested(&tx, &hlc).unwrap();
+        SqlExecutor::execute_internal(
+            &tx,
+            &hlc,
+            "INSERT INTO items (id, title) VALUES ('a', 'first')",
+            &[],
+        )
+        .unwrap();
+        let _inner = SqlExecutor::begin_nested(&tx, &hlc).unwrap();
+        SqlExecutor::rollback_to(&tx, outer).unwrap();
+        tx.commit().unwrap();
+
+        assert!(titles(&conn).is_empty());
     }
 
     #[test]
@@ -53,4 +239,87 @@ mod tests {
     fn test_query_select() {
         // TODO: Test SELECT query execution
     }
+
+    fn validate(conn: &Connection, sql: &str, params: &[Value]) -> Result<(), DatabaseError> {
+        let statement = SqlExecutionPlanner::parse_single_statement(sql).unwrap();
+        SqlExecutor::validate_param_types(conn, &statement, params)
+    }
+
+    fn typed_table() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE notes (
+                 id INTEGER PRIMARY KEY,
+                 title TEXT NOT NULL,
+                 rating REAL,
+                 created_at DATETIME,
+                 payload BLOB
+             );",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_validate_param_types_accepts_matching_values() {
+        let conn = typed_table();
+        let result = validate(
+            &conn,
+            "INSERT INTO notes (id, title, rating, created_at, payload) VALUES (?, ?, ?, ?, ?)",
+            &[
+                Value::Null,
+                Value::Text("hello".to_string()),
+                Value::Integer(4),
+                Value::Text("2024-01-01".to_string()),
+                Value::Integer(1),
+            ],
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_validate_param_types_reports_position_and_column() {
+        let conn = typed_table();
+
+        let result = validate(
+            &conn,
+            "INSERT INTO notes (title, rating) VALUES (?, ?)",
+            &[
+                Value::Text("hello".to_string()),
+                Value::Text("great".to_string()),
+            ],
+        );
+        match result {
+            Err(DatabaseError::ParameterTypeError {
+                position,
+                column,
+                expected,
+                provided,
+                ..
+            }) => {
+                assert_eq!(position, 2);
+                assert_eq!(column, "rating");
+                assert_eq!(expected, "REAL");
+                assert_eq!(provided, "TEXT");
+            }
+            other => panic!("expected ParameterTypeError, got {other:?}"),
+        }
+
+        let result = validate(
+            &conn,
+            "UPDATE notes SET title = ? WHERE id = ?",
+            &[Value::Null, Value::Integer(1)],
+        );
+        assert!(matches!(
+            result,
+            Err(DatabaseError::ParameterTypeError { position: 1, .. })
+        ));
+
+        let result = validate(
+            &conn,
+            "UPDATE notes SET title = ? WHERE id = ?",
+            &[Value::Integer(42), Value::Integer(1)],
+        );
+        assert!(result.is_err());
+    }
 }
@@ -1,9 +1,15 @@
 // src-tauri/src/extension/database/tests/mod.rs
 // Test modules for extension database security
 
+#[cfg(test)]
+mod data_archive_tests;
 #[cfg(test)]
 mod executor_tests;
 #[cfg(test)]
+mod row_filter_tests;
+#[cfg(test)]
+mod schema_tests;
+#[cfg(test)]
 mod sql_injection_tests;
 #[cfg(test)]
 mod sql_parsing_tests;
@@ -0,0 +1,85 @@
+// src-tauri/src/extension/database/tests/row_filter_tests.rs
+// Tests for loading row filters, including tables reached through views
+
+#[cfg(test)]
+mod tests {
+    use crate::database::core::parse_single_statement;
+    use crate::extension::database::queries::SQL_INSERT_ROW_FILTER;
+    use crate::extension::database::row_filter::load_row_filters;
+    use crate::table_names::TABLE_EXTENSION_ROW_FILTERS;
+    use rusqlite::Connection;
+
+    /// `ext_b__notes` is filtered to `shared = 1`; `ext_a__all_notes` and
+    /// `ext_a__recent_notes` (a view over a view) read it
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(&format!(
+            "CREATE TABLE {TABLE_EXTENSION_ROW_FILTERS} (
+                 id TEXT PRIMARY KEY NOT NULL,
+                 extension_id TEXT NOT NULL,
+                 predicate TEXT NOT NULL,
+                 params TEXT NOT NULL,
+                 updated_at TEXT
+             );
+             CREATE TABLE ext_b__notes (id TEXT PRIMARY KEY, shared INTEGER);
+             CREATE TABLE ext_b__tags (id TEXT PRIMARY KEY);
+             CREATE VIEW ext_a__all_notes AS SELECT * FROM ext_b__notes;
+             CREATE VIEW ext_a__recent_notes AS SELECT * FROM ext_a__all_notes;
+             CREATE VIEW ext_a__all_tags AS SELECT * FROM ext_b__tags;"
+        ))
+        .unwrap();
+        conn.execute(
+            SQL_INSERT_ROW_FILTER.as_str(),
+            ["ext_b__notes", "ext-b", "shared = ?", "[1]"],
+        )
+        .unwrap();
+        conn
+    }
+
+    fn load(
+        conn: &Connection,
+        caller_prefix: &str,
+        sql: &str,
+    ) -> Result<Vec<String>, crate::database::error::DatabaseError> {
+        let statement = parse_single_statement(sql).unwrap();
+        load_row_filters(conn, caller_prefix, &statement)
+            .map(|filters| filters.into_keys().collect())
+    }
+
+    #[test]
+    fn test_filters_tables_of_other_extensions() {
+        let conn = setup_db();
+        assert_eq!(
+            load(&conn, "ext_a__", "SELECT * FROM ext_b__notes").unwrap(),
+            vec!["ext_b__notes".to_string()]
+        );
+        assert!(load(&conn, "ext_b__", "SELECT * FROM ext_b__notes")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_rejects_filtered_tables_behind_views() {
+        let conn = setup_db();
+        assert!(load(&conn, "ext_a__", "SELECT * FROM ext_a__all_notes").is_err());
+        assert!(load(&conn, "ext_a__", "SELECT COUNT(*) FROM ext_a__recent_notes").is_err());
+        assert!(load(
+            &conn,
+            "ext_a__",
+            "SELECT * FROM ext_b__tags WHERE id IN (SELECT id FROM ext_a__all_notes)"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_allows_views_over_unfiltered_or_own_tables() {
+        let conn = setup_db();
+        assert!(load(&conn, "ext_a__", "SELECT * FROM ext_a__all_tags")
+            .unwrap()
+            .is_empty());
+        // The owner is never filtered, not even through another view
+        assert!(load(&conn, "ext_b__", "SELECT * FROM ext_a__all_notes")
+            .unwrap()
+            .is_empty());
+    }
+}
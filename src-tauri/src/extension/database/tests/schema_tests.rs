@@ -0,0 +1,167 @@
+// src-tauri/src/extension/database/tests/schema_tests.rs
+//!
+//! Tests for extension schema introspection
+//!
+
+use rusqlite::Connection;
+
+use crate::extension::database::schema::read_extension_schema;
+
+const PREFIX: &str = "pk__my_app__";
+
+fn setup() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE pk__my_app__folders (
+             id TEXT PRIMARY KEY NOT NULL,
+             name TEXT NOT NULL DEFAULT 'Untitled',
+             haex_hlc TEXT,
+             haex_column_hlcs TEXT NOT NULL DEFAULT '{}'
+         );
+         CREATE TABLE pk__my_app__items (
+             folder_id TEXT NOT NULL REFERENCES pk__my_app__folders(id) ON DELETE CASCADE,
+             position INTEGER NOT NULL,
+             title TEXT,
+             title_lower TEXT GENERATED ALWAYS AS (lower(title)) VIRTUAL,
+             owner_a TEXT,
+             owner_b TEXT,
+             PRIMARY KEY (folder_id, position),
+             UNIQUE (title),
+             FOREIGN KEY (owner_a, owner_b) REFERENCES pk__my_app__owners(a, b)
+         );
+         CREATE INDEX pk__my_app__items_title_idx ON pk__my_app__items (title, position);
+         CREATE INDEX pk__my_app__items_lower_idx ON pk__my_app__items (lower(title)) WHERE title IS NOT NULL;
+         CREATE VIEW pk__my_app__titles AS SELECT folder_id, title FROM pk__my_app__items;
+         CREATE TABLE pk__my_appx__other (id TEXT PRIMARY KEY);
+         CREATE TABLE pkx_my_app__other (id TEXT PRIMARY KEY);
+         CREATE TABLE haex_settings (id TEXT PRIMARY KEY);",
+    )
+    .unwrap();
+    conn
+}
+
+#[test]
+fn test_lists_only_prefixed_tables() {
+    let conn = setup();
+    let tables = read_extension_schema(&conn, PREFIX).unwrap();
+    let names: Vec<&str> = tables.iter().map(|t| t.name.as_str()).collect();
+    // '_' in the prefix must not act as a LIKE wildcard ("pkx_my_app__").
+    assert_eq!(
+        names,
+        vec![
+            "pk__my_app__folders",
+            "pk__my_app__items",
+            "pk__my_app__titles"
+        ]
+    );
+    assert!(tables[0]
+        .sql
+        .starts_with("CREATE TABLE pk__my_app__folders"));
+    assert!(!tables[0].view);
+}
+
+#[test]
+fn test_views_are_listed_with_columns() {
+    let conn = setup();
+    let tables = read_extension_schema(&conn, PREFIX).unwrap();
+    let view = &tables[2];
+    assert!(view.view);
+    let columns: Vec<&str> = view.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(columns, vec!["folder_id", "title"]);
+    assert!(view.indexes.is_empty());
+    assert!(view.foreign_keys.is_empty());
+}
+
+#[test]
+fn test_columns_include_types_defaults_and_flags() {
+    let conn = setup();
+    let tables = read_extension_schema(&conn, PREFIX).unwrap();
+
+    let folders = &tables[0];
+    let name = folders.columns.iter().find(|c| c.name == "name").unwrap();
+    assert_eq!(name.data_type, "TEXT");
+    assert!(name.not_null);
+    assert_eq!(name.default_value.as_deref(), Some("'Untitled'"));
+    assert_eq!(name.primary_key_position, 0);
+    assert!(!name.crdt);
+    assert!(folders
+        .columns
+        .iter()
+        .filter(|c| c.name.starts_with("haex_"))
+        .all(|c| c.crdt));
+
+    let items = &tables[1];
+    let pk: Vec<(&str, u32)> = items
+        .columns
+        .iter()
+        .filter(|c| c.primary_key_position > 0)
+        .map(|c| (c.name.as_str(), c.primary_key_position))
+        .collect();
+    assert_eq!(pk, vec![("folder_id", 1), ("position", 2)]);
+
+    let generated = items
+        .columns
+        .iter()
+        .find(|c| c.name == "title_lower")
+        .unwrap();
+    assert!(generated.generated);
+}
+
+#[test]
+fn test_indexes_cover_constraints_and_expressions() {
+    let conn = setup();
+    let tables = read_extension_schema(&conn, PREFIX).unwrap();
+    let items = &tables[1];
+
+    let title_idx = items
+        .indexes
+        .iter()
+        .find(|i| i.name == "pk__my_app__items_title_idx")
+        .unwrap();
+    assert_eq!(title_idx.origin, "c");
+    assert!(!title_idx.unique);
+    assert_eq!(
+        title_idx.columns,
+        vec![Some("title".to_string()), Some("position".to_string())]
+    );
+
+    let lower_idx = items
+        .indexes
+        .iter()
+        .find(|i| i.name == "pk__my_app__items_lower_idx")
+        .unwrap();
+    assert!(lower_idx.partial);
+    assert_eq!(lower_idx.columns, vec![None]);
+
+    assert!(items.indexes.iter().any(|i| i.origin == "u" && i.unique));
+    assert!(items.indexes.iter().any(|i| i.origin == "pk"));
+}
+
+#[test]
+fn test_foreign_keys_group_composite_columns() {
+    let conn = setup();
+    let tables = read_extension_schema(&conn, PREFIX).unwrap();
+    let items = &tables[1];
+    assert_eq!(items.foreign_keys.len(), 2);
+
+    let folder_fk = items
+        .foreign_keys
+        .iter()
+        .find(|fk| fk.referenced_table == "pk__my_app__folders")
+        .unwrap();
+    assert_eq!(folder_fk.columns, vec!["folder_id"]);
+    assert_eq!(folder_fk.referenced_columns, vec![Some("id".to_string())]);
+    assert_eq!(folder_fk.on_delete, "CASCADE");
+    assert_eq!(folder_fk.on_update, "NO ACTION");
+
+    let owner_fk = items
+        .foreign_keys
+        .iter()
+        .find(|fk| fk.referenced_table == "pk__my_app__owners")
+        .unwrap();
+    assert_eq!(owner_fk.columns, vec!["owner_a", "owner_b"]);
+    assert_eq!(
+        owner_fk.referenced_columns,
+        vec![Some("a".to_string()), Some("b".to_string())]
+    );
+}
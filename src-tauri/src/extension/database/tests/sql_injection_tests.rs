@@ -336,6 +336,34 @@ fn test_system_table_prefix_variations() {
     }
 }
 
+#[test]
+fn test_table_prefix_validation_create_view() {
+    let ctx = create_test_context();
+    let expected = get_expected_prefix();
+
+    // Valid: view over the extension's own tables
+    let valid_sql = format!(
+        "CREATE VIEW {expected}open_tasks AS SELECT t.id, l.name FROM {expected}tasks t \
+         JOIN {expected}lists l ON l.id = t.list_id WHERE t.done = 0"
+    );
+    assert!(validate_sql_table_prefix(&ctx, &valid_sql).is_ok());
+
+    // Invalid: view without prefix
+    let no_prefix = format!("CREATE VIEW open_tasks AS SELECT id FROM {expected}tasks");
+    assert!(validate_sql_table_prefix(&ctx, &no_prefix).is_err());
+
+    // Invalid: own view exposing a system table
+    let system_source = format!("CREATE VIEW {expected}leak AS SELECT * FROM haex_passwords_items");
+    assert!(validate_sql_table_prefix(&ctx, &system_source).is_err());
+
+    // Invalid: system table hidden in a subquery
+    let subquery_source = format!(
+        "CREATE VIEW {expected}leak AS SELECT * FROM {expected}tasks \
+         WHERE id IN (SELECT id FROM otherpubkey__otherext__secrets)"
+    );
+    assert!(validate_sql_table_prefix(&ctx, &subquery_source).is_err());
+}
+
 // ============================================================================
 // Cross-Extension Access Tests
 // ============================================================================
@@ -376,6 +404,11 @@ fn test_permission_checker_cross_extension() {
                 identities: None,
                 passwords: None,
                 mail: None,
+                camera: None,
+                contacts: None,
+                calendar: None,
+                microphone: None,
+                speech: None,
             },
             homepage: None,
             description: None,
@@ -383,6 +416,12 @@ fn test_permission_checker_cross_extension() {
             display_mode: Some(DisplayMode::Iframe),
             migrations_dir: None,
             i18n: None,
+            key_rotations: None,
+            locales: None,
+            share_target: None,
+            events: None,
+            min_host_version: None,
+            max_host_version: None,
         },
         source: ExtensionSource::Production {
             path: PathBuf::from("/tmp/test"),
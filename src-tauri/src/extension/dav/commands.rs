@@ -0,0 +1,182 @@
+//! Tauri commands for extension CalDAV/CardDAV sync.
+//!
+//! Used by both WebView and iframe modes, like the mail commands. The
+//! protocol work is delegated to `crate::dav`.
+
+use std::collections::HashSet;
+
+use tauri::{AppHandle, Manager, State, WebviewWindow};
+
+use crate::database::core::with_connection;
+use crate::dav::{DavConfig, DavError};
+use crate::events::{self, payloads::DirtyTablesChanged};
+use crate::extension::database::helpers::{execute_sql_with_context, ExtensionSqlContext};
+use crate::extension::dav::mapping::{DavMapping, DavSyncResult, SyncPlan};
+use crate::extension::dav::store;
+use crate::extension::error::ExtensionError;
+use crate::extension::permissions::manager::PermissionManager;
+use crate::extension::utils::{emit_permission_prompt_if_needed, resolve_extension_id};
+use crate::passwords::commands::fill_credentials_as_extension;
+use crate::AppState;
+
+/// Upper bound of `sync-collection` rounds per call when the server
+/// truncates its results; the next call continues from the saved token.
+const MAX_ROUNDS: usize = 50;
+const MAX_SYNC_ID_LENGTH: usize = 100;
+
+fn map_dav_error(err: DavError) -> ExtensionError {
+    ExtensionError::WebError {
+        reason: err.to_string(),
+    }
+}
+
+fn validate_sync_id(sync_id: &str) -> Result<(), ExtensionError> {
+    if sync_id.trim().is_empty() || sync_id.len() > MAX_SYNC_ID_LENGTH {
+        return Err(ExtensionError::ValidationError {
+            reason: format!("Sync id must be 1 to {MAX_SYNC_ID_LENGTH} characters"),
+        });
+    }
+    Ok(())
+}
+
+/// Pulls the changes of a calendar or address book into an extension table.
+///
+/// `sync_id` names the subscription (e.g. one per collection); the sync
+/// token and the known resources are kept per extension and sync id on this
+/// device. Requires web permission for the collection URL. Writes go
+/// through the CRDT layer, so the rows sync to other devices like any
+/// extension data and concurrent edits resolve per column by HLC.
+#[tauri::command]
+pub async fn extension_dav_sync(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    sync_id: String,
+    mut config: DavConfig,
+    mapping: DavMapping,
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<DavSyncResult, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    validate_sync_id(&sync_id)?;
+    mapping.validate()?;
+
+    let perm_result =
+        PermissionManager::check_web_permission(&state, &extension_id, &config.url).await;
+    if let Err(ref e) = perm_result {
+        emit_permission_prompt_if_needed(&app_handle, e);
+    }
+    perm_result?;
+
+    if let Some(item_id) = config.credential_item_id.take() {
+        fill_credentials_as_extension(
+            &app_handle,
+            &state,
+            &extension_id,
+            &item_id,
+            &mut config.username,
+            &mut config.password,
+        )
+        .await?;
+    }
+
+    let extension = state
+        .extension_manager
+        .get_extension(&extension_id)
+        .ok_or_else(|| ExtensionError::ValidationError {
+            reason: format!("Extension with ID {} not found", extension_id),
+        })?;
+    let ctx = ExtensionSqlContext::new(
+        extension.manifest.public_key.clone(),
+        extension.manifest.name.clone(),
+    );
+    let table_prefix = ctx.get_table_prefix();
+
+    let mut sync_state =
+        with_connection(&state.db, |conn| store::load(conn, &extension_id, &sync_id))?;
+    let mut result = DavSyncResult {
+        full_resync: sync_state.sync_token.is_none(),
+        ..Default::default()
+    };
+    let mut seen = HashSet::new();
+    let mut complete = false;
+
+    for _ in 0..MAX_ROUNDS {
+        let changes =
+            crate::dav::client::sync_collection(&config, sync_state.sync_token.as_deref())
+                .await
+                .map_err(map_dav_error)?;
+        result.full_resync |= changes.initial;
+        seen.extend(changes.changed.iter().map(|r| r.href.clone()));
+
+        let mut plan = SyncPlan::default();
+        mapping.plan_changes(&table_prefix, &mut sync_state, &changes, &mut plan);
+        sync_state.sync_token = changes.sync_token;
+        // The token is only saved once its changes are written, so a failed
+        // write is retried by the next call
+        run_plan(&ctx, &state, &plan)?;
+        with_connection(&state.db, |conn| {
+            store::save(conn, &extension_id, &sync_id, &sync_state)
+        })?;
+        add_counts(&mut result, &plan.result);
+
+        if !changes.truncated {
+            complete = true;
+            break;
+        }
+    }
+
+    if result.full_resync && complete {
+        let mut plan = SyncPlan::default();
+        mapping.plan_removals(&table_prefix, &mut sync_state, &seen, &mut plan);
+        run_plan(&ctx, &state, &plan)?;
+        with_connection(&state.db, |conn| {
+            store::save(conn, &extension_id, &sync_id, &sync_state)
+        })?;
+        add_counts(&mut result, &plan.result);
+    }
+
+    if result.upserted + result.deleted > 0 {
+        let _ = events::emit_to_main(window.app_handle(), &DirtyTablesChanged {});
+    }
+    Ok(result)
+}
+
+/// Drops the sync token so the next `extension_dav_sync` lists the whole
+/// collection again. Rows stay; the ones of resources gone from the server
+/// are removed by that full round.
+#[tauri::command]
+pub async fn extension_dav_reset_sync(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    sync_id: String,
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<(), ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    validate_sync_id(&sync_id)?;
+    with_connection(&state.db, |conn| {
+        let mut sync_state = store::load(conn, &extension_id, &sync_id)?;
+        sync_state.sync_token = None;
+        store::save(conn, &extension_id, &sync_id, &sync_state)
+    })?;
+    Ok(())
+}
+
+fn run_plan(
+    ctx: &ExtensionSqlContext,
+    state: &State<'_, AppState>,
+    plan: &SyncPlan,
+) -> Result<(), ExtensionError> {
+    for (sql, params) in &plan.statements {
+        execute_sql_with_context(ctx, sql, params, state.inner(), None)?;
+    }
+    Ok(())
+}
+
+fn add_counts(total: &mut DavSyncResult, round: &DavSyncResult) {
+    total.upserted += round.upserted;
+    total.deleted += round.deleted;
+    total.unchanged += round.unchanged;
+    total.skipped += round.skipped;
+}
@@ -0,0 +1,297 @@
+//! Mapping of CalDAV/CardDAV resources onto an extension table.
+//!
+//! The extension declares which column receives which value; the host
+//! builds the upsert/delete statements against the extension's own
+//! (prefixed) table. Column sources are either an iCalendar/vCard property
+//! name (`SUMMARY`, `DTSTART`, `FN`, `EMAIL`, ...) or one of the
+//! [`SOURCE_*`](SOURCE_HREF) pseudo properties.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use ts_rs::TS;
+
+use crate::dav::parsing::parse_object;
+use crate::dav::{DavChanges, DavObject, DavResource};
+use crate::extension::dav::store::{DavSyncState, KnownResource};
+use crate::extension::error::ExtensionError;
+
+/// Path of the resource on the server
+pub const SOURCE_HREF: &str = "$href";
+pub const SOURCE_ETAG: &str = "$etag";
+/// `UID` property
+pub const SOURCE_UID: &str = "$uid";
+/// `VEVENT`, `VTODO`, `VJOURNAL` or `VCARD`
+pub const SOURCE_COMPONENT: &str = "$component";
+/// The complete iCalendar / vCard text
+pub const SOURCE_DATA: &str = "$data";
+
+/// Where the resources of a collection are stored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct DavMapping {
+    /// Table name without the extension prefix
+    pub table: String,
+    /// Column identifying the row; needs a PRIMARY KEY or UNIQUE constraint
+    pub key_column: String,
+    /// Source of the key, `$href` if not set. `$uid` keeps rows stable
+    /// when the server renames resources.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub key_source: Option<String>,
+    /// Column name to source; properties missing in a resource become NULL
+    pub columns: BTreeMap<String, String>,
+}
+
+impl DavMapping {
+    pub fn key_source(&self) -> &str {
+        self.key_source.as_deref().unwrap_or(SOURCE_HREF)
+    }
+
+    /// Checks identifiers and sources before anything is written.
+    pub fn validate(&self) -> Result<(), ExtensionError> {
+        for name in std::iter::once(&self.table)
+            .chain(std::iter::once(&self.key_column))
+            .chain(self.columns.keys())
+        {
+            validate_identifier(name)?;
+        }
+        for source in
+            std::iter::once(self.key_source()).chain(self.columns.values().map(String::as_str))
+        {
+            validate_source(source)?;
+        }
+        Ok(())
+    }
+
+    /// The row key of `resource`, `None` if its key source is missing.
+    pub fn key(&self, resource: &DavResource, object: Option<&DavObject>) -> Option<String> {
+        resolve(self.key_source(), resource, object)
+    }
+
+    /// `INSERT ... ON CONFLICT (key) DO UPDATE` for one resource.
+    ///
+    /// Runs through the CRDT layer like any extension write, so only the
+    /// mapped columns are stamped; columns the extension maintains itself
+    /// keep their values and HLCs.
+    pub fn upsert_statement(
+        &self,
+        table_prefix: &str,
+        key: &str,
+        resource: &DavResource,
+        object: Option<&DavObject>,
+    ) -> (String, Vec<JsonValue>) {
+        let mut columns = vec![quote(&self.key_column)];
+        let mut params = vec![JsonValue::String(key.to_string())];
+        for (column, source) in &self.columns {
+            if column == &self.key_column {
+                continue;
+            }
+            columns.push(quote(column));
+            params.push(
+                resolve(source, resource, object)
+                    .map(JsonValue::String)
+                    .unwrap_or(JsonValue::Null),
+            );
+        }
+
+        let updates: Vec<String> = columns[1..]
+            .iter()
+            .map(|c| format!("{c} = excluded.{c}"))
+            .collect();
+        let action = if updates.is_empty() {
+            "DO NOTHING".to_string()
+        } else {
+            format!("DO UPDATE SET {}", updates.join(", "))
+        };
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT ({}) {}",
+            quote(&format!("{table_prefix}{}", self.table)),
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", "),
+            quote(&self.key_column),
+            action
+        );
+        (sql, params)
+    }
+
+    pub fn delete_statement(&self, table_prefix: &str, key: &str) -> (String, Vec<JsonValue>) {
+        let sql = format!(
+            "DELETE FROM {} WHERE {} = ?",
+            quote(&format!("{table_prefix}{}", self.table)),
+            quote(&self.key_column)
+        );
+        (sql, vec![JsonValue::String(key.to_string())])
+    }
+}
+
+fn resolve(source: &str, resource: &DavResource, object: Option<&DavObject>) -> Option<String> {
+    match source {
+        SOURCE_HREF => Some(resource.href.clone()),
+        SOURCE_ETAG => resource.etag.clone(),
+        SOURCE_DATA => Some(resource.data.clone()),
+        SOURCE_UID => object?.uid().map(str::to_string),
+        SOURCE_COMPONENT => object.map(|o| o.component.clone()),
+        property => object?
+            .properties
+            .get(&property.to_ascii_uppercase())
+            .cloned(),
+    }
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn validate_identifier(name: &str) -> Result<(), ExtensionError> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ExtensionError::ValidationError {
+            reason: format!("Invalid identifier '{name}' in DAV mapping"),
+        })
+    }
+}
+
+fn validate_source(source: &str) -> Result<(), ExtensionError> {
+    let valid = match source.strip_prefix('$') {
+        Some(_) => [
+            SOURCE_HREF,
+            SOURCE_ETAG,
+            SOURCE_UID,
+            SOURCE_COMPONENT,
+            SOURCE_DATA,
+        ]
+        .contains(&source),
+        None => {
+            !source.is_empty()
+                && source
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        }
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(ExtensionError::ValidationError {
+            reason: format!("Unknown DAV mapping source '{source}'"),
+        })
+    }
+}
+
+/// Counts of one `extension_dav_sync` call.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct DavSyncResult {
+    pub upserted: u32,
+    pub deleted: u32,
+    /// Resources the server reported again with an unchanged etag
+    pub unchanged: u32,
+    /// Resources without a value for the key source (e.g. no `UID`)
+    pub skipped: u32,
+    /// The server had no usable sync token, so the whole collection was
+    /// listed and rows of vanished resources were removed
+    pub full_resync: bool,
+}
+
+/// Statements to run for a batch of changes, in order.
+#[derive(Debug, Default)]
+pub struct SyncPlan {
+    pub statements: Vec<(String, Vec<JsonValue>)>,
+    pub result: DavSyncResult,
+}
+
+impl DavMapping {
+    /// Plans the writes for `changes` and updates the known resources.
+    pub fn plan_changes(
+        &self,
+        table_prefix: &str,
+        state: &mut DavSyncState,
+        changes: &DavChanges,
+        plan: &mut SyncPlan,
+    ) {
+        let mut written_keys = HashSet::new();
+        let mut stale_keys = Vec::new();
+
+        for resource in &changes.changed {
+            let object = parse_object(&resource.data);
+            let Some(key) = self.key(resource, object.as_ref()) else {
+                plan.result.skipped += 1;
+                continue;
+            };
+            let known = state.known.get(&resource.href);
+            if resource.etag.is_some()
+                && known.is_some_and(|k| k.etag == resource.etag && k.key == key)
+            {
+                plan.result.unchanged += 1;
+                written_keys.insert(key);
+                continue;
+            }
+            if let Some(old) = known.filter(|k| k.key != key) {
+                stale_keys.push(old.key.clone());
+            }
+
+            plan.statements.push(self.upsert_statement(
+                table_prefix,
+                &key,
+                resource,
+                object.as_ref(),
+            ));
+            plan.result.upserted += 1;
+            state.known.insert(
+                resource.href.clone(),
+                KnownResource {
+                    etag: resource.etag.clone(),
+                    key: key.clone(),
+                },
+            );
+            written_keys.insert(key);
+        }
+
+        // A resource moved to another href shows up as delete + add with the
+        // same key; its row must survive
+        let removed = changes
+            .deleted
+            .iter()
+            .filter_map(|href| state.known.remove(href).map(|k| k.key));
+        for key in stale_keys.into_iter().chain(removed) {
+            if written_keys.insert(key.clone()) {
+                plan.statements
+                    .push(self.delete_statement(table_prefix, &key));
+                plan.result.deleted += 1;
+            }
+        }
+    }
+
+    /// After a full listing: removes the rows of known resources that were
+    /// not listed (`seen`).
+    pub fn plan_removals(
+        &self,
+        table_prefix: &str,
+        state: &mut DavSyncState,
+        seen: &HashSet<String>,
+        plan: &mut SyncPlan,
+    ) {
+        let (live, vanished): (BTreeMap<_, _>, BTreeMap<_, _>) = std::mem::take(&mut state.known)
+            .into_iter()
+            .partition(|(href, _)| seen.contains(href));
+        let live_keys: HashSet<&String> = live.values().map(|k| &k.key).collect();
+        let mut deleted = HashSet::new();
+        for known in vanished.values() {
+            if !live_keys.contains(&known.key) && deleted.insert(&known.key) {
+                plan.statements
+                    .push(self.delete_statement(table_prefix, &known.key));
+                plan.result.deleted += 1;
+            }
+        }
+        state.known = live;
+    }
+}
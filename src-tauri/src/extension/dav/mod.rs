@@ -0,0 +1,19 @@
+//! Extension-facing wrapper for the core CalDAV/CardDAV module.
+//!
+//! Syncs a calendar or address book into a table of the calling extension:
+//! the extension describes the target table with a [`mapping::DavMapping`],
+//! the host fetches changes with `sync-collection` and writes them through
+//! the CRDT layer. Requires web permission for the collection URL.
+//!
+//! Credentials are passed per call or resolved from the passwords vault
+//! (`credential_item_id`), same as for mail. Sync tokens and the hrefs
+//! already written are kept per device in `haex_crdt_configs` (see
+//! [`store`]); a server that forgot a token leads to a full listing, after
+//! which rows of resources that no longer exist are deleted.
+
+pub mod commands;
+pub mod mapping;
+pub mod store;
+
+#[cfg(test)]
+mod tests;
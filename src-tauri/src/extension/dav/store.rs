@@ -0,0 +1,86 @@
+//! Per-device sync state of DAV collections in `haex_crdt_configs`.
+//!
+//! Local-only: each device talks to the server itself and keeps its own
+//! sync token. The rows written into extension tables are what syncs.
+
+use std::collections::BTreeMap;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::database::constants::vault_settings_key;
+use crate::database::error::DatabaseError;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, TABLE_CRDT_CONFIGS,
+};
+
+/// `type` column value of the state rows
+const CONFIG_TYPE: &str = "dav";
+
+/// A resource as last written into the extension table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnownResource {
+    #[serde(default)]
+    pub etag: Option<String>,
+    /// Row key used for it, needed to delete the row by href
+    pub key: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DavSyncState {
+    #[serde(default)]
+    pub sync_token: Option<String>,
+    /// By href
+    #[serde(default)]
+    pub known: BTreeMap<String, KnownResource>,
+}
+
+fn config_key(extension_id: &str, sync_id: &str) -> String {
+    format!(
+        "{}{extension_id}:{sync_id}",
+        vault_settings_key::DAV_SYNC_STATE_PREFIX
+    )
+}
+
+pub fn load(
+    conn: &Connection,
+    extension_id: &str,
+    sync_id: &str,
+) -> Result<DavSyncState, DatabaseError> {
+    let value: Option<String> = conn
+        .query_row(
+            &format!(
+                "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"
+            ),
+            params![config_key(extension_id, sync_id)],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match value {
+        None => Ok(DavSyncState::default()),
+        Some(json) => serde_json::from_str(&json).map_err(|e| DatabaseError::SerializationError {
+            reason: format!("Invalid DAV sync state: {e}"),
+        }),
+    }
+}
+
+pub fn save(
+    conn: &Connection,
+    extension_id: &str,
+    sync_id: &str,
+    state: &DavSyncState,
+) -> Result<(), DatabaseError> {
+    let json = serde_json::to_string(state).map_err(|e| DatabaseError::SerializationError {
+        reason: e.to_string(),
+    })?;
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?, ?, ?)"
+        ),
+        params![config_key(extension_id, sync_id), CONFIG_TYPE, json],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,205 @@
+//! Tests for the DAV mapping, sync planning and state store
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde_json::{json, Value as JsonValue};
+
+use super::mapping::{DavMapping, SyncPlan};
+use super::store::{self, DavSyncState, KnownResource};
+use crate::crdt::hlc::HlcService;
+use crate::dav::{DavChanges, DavResource};
+use crate::test_support::crdt::open_crdt_connection;
+
+const PREFIX: &str = "abc__calendar__";
+
+fn mapping(key_source: Option<&str>) -> DavMapping {
+    DavMapping {
+        table: "events".to_string(),
+        key_column: "id".to_string(),
+        key_source: key_source.map(str::to_string),
+        columns: BTreeMap::from([
+            ("title".to_string(), "SUMMARY".to_string()),
+            ("starts_at".to_string(), "dtstart".to_string()),
+            ("etag".to_string(), "$etag".to_string()),
+        ]),
+    }
+}
+
+fn event(href: &str, etag: &str, uid: &str, summary: &str) -> DavResource {
+    DavResource {
+        href: href.to_string(),
+        etag: Some(etag.to_string()),
+        data: format!(
+            "BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:{uid}\nSUMMARY:{summary}\nEND:VEVENT\nEND:VCALENDAR\n"
+        ),
+    }
+}
+
+fn known(etag: &str, key: &str) -> KnownResource {
+    KnownResource {
+        etag: Some(etag.to_string()),
+        key: key.to_string(),
+    }
+}
+
+fn statements(plan: &SyncPlan) -> Vec<(&str, &[JsonValue])> {
+    plan.statements
+        .iter()
+        .map(|(sql, params)| (sql.as_str(), params.as_slice()))
+        .collect()
+}
+
+#[test]
+fn test_validate_rejects_bad_identifiers_and_sources() {
+    assert!(mapping(None).validate().is_ok());
+    assert!(mapping(Some("$uid")).validate().is_ok());
+
+    let mut bad_table = mapping(None);
+    bad_table.table = "events\"; DROP TABLE x; --".to_string();
+    assert!(bad_table.validate().is_err());
+
+    assert!(mapping(Some("$nope")).validate().is_err());
+
+    let mut bad_source = mapping(None);
+    bad_source
+        .columns
+        .insert("note".to_string(), "DESCRIPTION;X".to_string());
+    assert!(bad_source.validate().is_err());
+}
+
+#[test]
+fn test_upsert_and_delete_statements() {
+    let resource = event("/cal/1.ics", "\"e1\"", "uid-1", "Lunch");
+    let object = crate::dav::parsing::parse_object(&resource.data);
+    let (sql, params) =
+        mapping(None).upsert_statement(PREFIX, "/cal/1.ics", &resource, object.as_ref());
+    assert_eq!(
+        sql,
+        "INSERT INTO \"abc__calendar__events\" (\"id\", \"etag\", \"starts_at\", \"title\") \
+         VALUES (?, ?, ?, ?) ON CONFLICT (\"id\") DO UPDATE SET \
+         \"etag\" = excluded.\"etag\", \"starts_at\" = excluded.\"starts_at\", \
+         \"title\" = excluded.\"title\""
+    );
+    assert_eq!(
+        params,
+        vec![
+            json!("/cal/1.ics"),
+            json!("\"e1\""),
+            JsonValue::Null,
+            json!("Lunch")
+        ]
+    );
+
+    let (sql, params) = mapping(None).delete_statement(PREFIX, "/cal/1.ics");
+    assert_eq!(
+        sql,
+        "DELETE FROM \"abc__calendar__events\" WHERE \"id\" = ?"
+    );
+    assert_eq!(params, vec![json!("/cal/1.ics")]);
+}
+
+#[test]
+fn test_plan_changes_skips_unchanged_and_deletes_removed() {
+    let mut state = DavSyncState {
+        sync_token: Some("t1".to_string()),
+        known: BTreeMap::from([
+            ("/cal/1.ics".to_string(), known("\"e1\"", "/cal/1.ics")),
+            ("/cal/2.ics".to_string(), known("\"e2\"", "/cal/2.ics")),
+        ]),
+    };
+    let changes = DavChanges {
+        changed: vec![
+            event("/cal/1.ics", "\"e1\"", "uid-1", "Lunch"),
+            event("/cal/3.ics", "\"e3\"", "uid-3", "Dinner"),
+        ],
+        deleted: vec!["/cal/2.ics".to_string(), "/cal/unknown.ics".to_string()],
+        ..Default::default()
+    };
+
+    let mut plan = SyncPlan::default();
+    mapping(None).plan_changes(PREFIX, &mut state, &changes, &mut plan);
+
+    assert_eq!(plan.result.unchanged, 1);
+    assert_eq!(plan.result.upserted, 1);
+    assert_eq!(plan.result.deleted, 1);
+    let statements = statements(&plan);
+    assert!(statements[0].0.starts_with("INSERT"));
+    assert_eq!(statements[0].1[0], json!("/cal/3.ics"));
+    assert!(statements[1].0.starts_with("DELETE"));
+    assert_eq!(statements[1].1, &[json!("/cal/2.ics")]);
+    assert_eq!(
+        state.known.keys().collect::<Vec<_>>(),
+        vec!["/cal/1.ics", "/cal/3.ics"]
+    );
+}
+
+#[test]
+fn test_plan_changes_keeps_uid_keyed_row_of_moved_resource() {
+    let mut state = DavSyncState {
+        sync_token: Some("t1".to_string()),
+        known: BTreeMap::from([("/cal/old.ics".to_string(), known("\"e1\"", "uid-1"))]),
+    };
+    let changes = DavChanges {
+        changed: vec![
+            event("/cal/new.ics", "\"e2\"", "uid-1", "Lunch"),
+            DavResource {
+                href: "/cal/broken.ics".to_string(),
+                etag: None,
+                data: "garbage".to_string(),
+            },
+        ],
+        deleted: vec!["/cal/old.ics".to_string()],
+        ..Default::default()
+    };
+
+    let mut plan = SyncPlan::default();
+    mapping(Some("$uid")).plan_changes(PREFIX, &mut state, &changes, &mut plan);
+
+    assert_eq!(plan.result.upserted, 1);
+    assert_eq!(plan.result.deleted, 0);
+    assert_eq!(plan.result.skipped, 1);
+    assert_eq!(state.known["/cal/new.ics"].key, "uid-1");
+    assert!(!state.known.contains_key("/cal/old.ics"));
+}
+
+#[test]
+fn test_plan_removals_after_full_listing() {
+    let mut state = DavSyncState {
+        sync_token: Some("t2".to_string()),
+        known: BTreeMap::from([
+            ("/cal/1.ics".to_string(), known("\"e1\"", "uid-1")),
+            ("/cal/2.ics".to_string(), known("\"e2\"", "uid-2")),
+            // Same uid as a listed resource, e.g. a moved copy
+            ("/cal/1-copy.ics".to_string(), known("\"e1\"", "uid-1")),
+        ]),
+    };
+    let seen = HashSet::from(["/cal/1.ics".to_string()]);
+
+    let mut plan = SyncPlan::default();
+    mapping(Some("$uid")).plan_removals(PREFIX, &mut state, &seen, &mut plan);
+
+    assert_eq!(plan.result.deleted, 1);
+    assert_eq!(statements(&plan)[0].1, &[json!("uid-2")]);
+    assert_eq!(state.known.keys().collect::<Vec<_>>(), vec!["/cal/1.ics"]);
+}
+
+#[test]
+fn test_sync_state_round_trip() {
+    let conn = open_crdt_connection(HlcService::new_for_testing("dav-test-device")).unwrap();
+    assert_eq!(
+        store::load(&conn, "ext-1", "personal").unwrap(),
+        DavSyncState::default()
+    );
+
+    let state = DavSyncState {
+        sync_token: Some("http://example/sync/1".to_string()),
+        known: BTreeMap::from([("/cal/1.ics".to_string(), known("\"e1\"", "/cal/1.ics"))]),
+    };
+    store::save(&conn, "ext-1", "personal", &state).unwrap();
+    assert_eq!(store::load(&conn, "ext-1", "personal").unwrap(), state);
+    // Other extensions and sync ids don't share the state
+    assert_eq!(
+        store::load(&conn, "ext-2", "personal").unwrap(),
+        DavSyncState::default()
+    );
+}
@@ -0,0 +1,86 @@
+// src-tauri/src/extension/dev_logs/commands.rs
+
+use crate::extension::core::types::ExtensionSource;
+use crate::extension::dev_logs::DevLogEntry;
+use crate::extension::error::ExtensionError;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+use crate::extension::webview::helpers::get_extension_id;
+use crate::AppState;
+use tauri::{State, WebviewWindow};
+
+/// Records console output of a dev extension.
+///
+/// Called by the capture script in dev extension windows (the extension is
+/// derived from the window label) or by the main window on behalf of an
+/// iframe dev extension, which then has to pass `extension_id`.
+#[tauri::command]
+pub fn dev_extension_log(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    level: String,
+    message: String,
+    source: Option<String>,
+    stack: Option<String>,
+    extension_id: Option<String>,
+) -> Result<(), ExtensionError> {
+    let (extension_id, window_id) = if window.label() == "main" {
+        let extension_id = extension_id.ok_or_else(|| ExtensionError::ValidationError {
+            reason: "extension_id is required when logging from the main window".to_string(),
+        })?;
+        (extension_id, None)
+    } else {
+        #[cfg(not(any(target_os = "android", target_os = "ios")))]
+        {
+            (
+                get_extension_id(&window, &state)?,
+                Some(window.label().to_string()),
+            )
+        }
+        // Extension windows only exist on desktop
+        #[cfg(any(target_os = "android", target_os = "ios"))]
+        return Err(ExtensionError::ValidationError {
+            reason: format!("Window {} is not an extension window", window.label()),
+        });
+    };
+
+    let extension = state
+        .extension_manager
+        .get_extension(&extension_id)
+        .ok_or_else(|| ExtensionError::NotFound {
+            public_key: String::new(),
+            name: extension_id.clone(),
+        })?;
+    if !matches!(extension.source, ExtensionSource::Development { .. }) {
+        return Err(ExtensionError::ValidationError {
+            reason: "Console capture is only available for dev extensions".to_string(),
+        });
+    }
+
+    let entry = DevLogEntry::new(extension_id, window_id, &level, message, source, stack);
+    let _ = crate::events::emit_to_main(&window, &entry);
+    state.extension_manager.dev_logs.push(entry);
+    Ok(())
+}
+
+/// Returns captured output of a dev extension, oldest first.
+#[tauri::command]
+pub fn dev_extension_get_logs(
+    state: State<'_, AppState>,
+    extension_id: String,
+    since: Option<u64>,
+    limit: Option<usize>,
+) -> Vec<DevLogEntry> {
+    state
+        .extension_manager
+        .dev_logs
+        .get(&extension_id, since, limit)
+}
+
+/// Clears captured output of one dev extension, or of all if `extension_id` is `None`.
+#[tauri::command]
+pub fn dev_extension_clear_logs(state: State<'_, AppState>, extension_id: Option<String>) {
+    state
+        .extension_manager
+        .dev_logs
+        .clear(extension_id.as_deref());
+}
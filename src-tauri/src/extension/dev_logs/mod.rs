@@ -0,0 +1,175 @@
+// src-tauri/src/extension/dev_logs/mod.rs
+//!
+//! Console capture for dev extensions
+//!
+//! Dev extension webviews get `CAPTURE_SCRIPT` injected, which forwards
+//! `console.*` calls, uncaught errors and unhandled promise rejections to
+//! `dev_extension_log`. Entries are emitted as `dev:extension-log` to the
+//! main window and kept in a per-extension ring buffer that
+//! `dev_extension_get_logs` reads, so SDK users can debug without opening
+//! devtools in every window.
+
+pub mod commands;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use ts_rs::TS;
+
+/// Entries kept per extension; older entries are dropped first.
+pub const DEV_LOG_CAPACITY: usize = 500;
+
+/// Longest message stored; longer ones are truncated.
+const MAX_MESSAGE_LEN: usize = 16 * 1024;
+
+/// One captured console call or error of a dev extension.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct DevLogEntry {
+    pub extension_id: String,
+    /// Label of the extension window, `None` for iframe extensions
+    pub window_id: Option<String>,
+    /// `log`, `info`, `warn`, `error` or `debug`
+    pub level: String,
+    pub message: String,
+    /// `url:line:column` of uncaught errors
+    pub source: Option<String>,
+    pub stack: Option<String>,
+    #[ts(type = "number")]
+    pub timestamp: u64,
+}
+
+impl DevLogEntry {
+    pub fn new(
+        extension_id: String,
+        window_id: Option<String>,
+        level: &str,
+        mut message: String,
+        source: Option<String>,
+        stack: Option<String>,
+    ) -> Self {
+        if message.len() > MAX_MESSAGE_LEN {
+            let mut end = MAX_MESSAGE_LEN;
+            while !message.is_char_boundary(end) {
+                end -= 1;
+            }
+            message.truncate(end);
+            message.push('…');
+        }
+        let level = match level {
+            "info" | "warn" | "error" | "debug" => level,
+            _ => "log",
+        };
+        Self {
+            extension_id,
+            window_id,
+            level: level.to_string(),
+            message,
+            source,
+            stack,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// In-memory ring buffers of captured dev extension output.
+#[derive(Default)]
+pub struct DevLogBuffer {
+    entries: Mutex<HashMap<String, VecDeque<DevLogEntry>>>,
+}
+
+impl DevLogBuffer {
+    pub fn push(&self, entry: DevLogEntry) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        let buffer = entries.entry(entry.extension_id.clone()).or_default();
+        if buffer.len() >= DEV_LOG_CAPACITY {
+            buffer.pop_front();
+        }
+        buffer.push_back(entry);
+    }
+
+    /// Entries of one extension, oldest first. `since` (unix ms) skips older entries,
+    /// `limit` keeps only the newest ones.
+    pub fn get(
+        &self,
+        extension_id: &str,
+        since: Option<u64>,
+        limit: Option<usize>,
+    ) -> Vec<DevLogEntry> {
+        let Ok(entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        let Some(buffer) = entries.get(extension_id) else {
+            return Vec::new();
+        };
+        let matching: Vec<DevLogEntry> = buffer
+            .iter()
+            .filter(|e| since.is_none_or(|since| e.timestamp >= since))
+            .cloned()
+            .collect();
+        let skip = limit.map_or(0, |limit| matching.len().saturating_sub(limit));
+        matching.into_iter().skip(skip).collect()
+    }
+
+    pub fn clear(&self, extension_id: Option<&str>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            match extension_id {
+                Some(id) => {
+                    entries.remove(id);
+                }
+                None => entries.clear(),
+            }
+        }
+    }
+}
+
+/// Initialization script for dev extension webviews. Wraps the console and
+/// error handlers; a re-entrancy guard keeps forwarding failures from looping.
+pub const CAPTURE_SCRIPT: &str = r#"(function () {
+  if (window.__HAEX_DEV_LOG__) return;
+  window.__HAEX_DEV_LOG__ = true;
+  var internals = window.__TAURI_INTERNALS__;
+  if (!internals || typeof internals.invoke !== "function") return;
+  var sending = false;
+  var format = function (value) {
+    if (typeof value === "string") return value;
+    if (value instanceof Error) return value.stack || String(value);
+    try { return JSON.stringify(value); } catch (e) { return String(value); }
+  };
+  var send = function (level, message, source, stack) {
+    if (sending) return;
+    sending = true;
+    try {
+      internals.invoke("dev_extension_log", {
+        level: level, message: message, source: source || null, stack: stack || null
+      }).catch(function () {});
+    } catch (e) {}
+    sending = false;
+  };
+  ["log", "info", "warn", "error", "debug"].forEach(function (level) {
+    var original = console[level];
+    console[level] = function () {
+      var args = Array.prototype.slice.call(arguments);
+      send(level, args.map(format).join(" "));
+      return original.apply(console, arguments);
+    };
+  });
+  window.addEventListener("error", function (event) {
+    var source = event.filename ? event.filename + ":" + event.lineno + ":" + event.colno : null;
+    send("error", event.message, source, event.error && event.error.stack);
+  });
+  window.addEventListener("unhandledrejection", function (event) {
+    var reason = event.reason;
+    send("error", "Unhandled rejection: " + format(reason), null, reason && reason.stack);
+  });
+})();"#;
+
+#[cfg(test)]
+mod tests;
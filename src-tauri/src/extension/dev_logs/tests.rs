@@ -0,0 +1,77 @@
+// src-tauri/src/extension/dev_logs/tests.rs
+
+use super::{DevLogBuffer, DevLogEntry, DEV_LOG_CAPACITY};
+
+fn entry(extension_id: &str, message: &str) -> DevLogEntry {
+    DevLogEntry::new(
+        extension_id.to_string(),
+        None,
+        "log",
+        message.to_string(),
+        None,
+        None,
+    )
+}
+
+#[test]
+fn test_buffer_keeps_newest_entries() {
+    let buffer = DevLogBuffer::default();
+    for i in 0..DEV_LOG_CAPACITY + 10 {
+        buffer.push(entry("ext", &format!("line {i}")));
+    }
+
+    let logs = buffer.get("ext", None, None);
+    assert_eq!(logs.len(), DEV_LOG_CAPACITY);
+    assert_eq!(logs[0].message, "line 10");
+    assert_eq!(
+        logs.last().map(|e| e.message.as_str()),
+        Some(format!("line {}", DEV_LOG_CAPACITY + 9).as_str())
+    );
+}
+
+#[test]
+fn test_buffers_are_per_extension() {
+    let buffer = DevLogBuffer::default();
+    buffer.push(entry("a", "from a"));
+    buffer.push(entry("b", "from b"));
+
+    assert_eq!(buffer.get("a", None, None).len(), 1);
+    buffer.clear(Some("a"));
+    assert!(buffer.get("a", None, None).is_empty());
+    assert_eq!(buffer.get("b", None, None).len(), 1);
+    buffer.clear(None);
+    assert!(buffer.get("b", None, None).is_empty());
+}
+
+#[test]
+fn test_limit_and_since() {
+    let buffer = DevLogBuffer::default();
+    for i in 0..5 {
+        let mut e = entry("ext", &format!("line {i}"));
+        e.timestamp = i;
+        buffer.push(e);
+    }
+
+    let newest: Vec<_> = buffer
+        .get("ext", None, Some(2))
+        .into_iter()
+        .map(|e| e.message)
+        .collect();
+    assert_eq!(newest, vec!["line 3", "line 4"]);
+    assert_eq!(buffer.get("ext", Some(3), None).len(), 2);
+}
+
+#[test]
+fn test_entry_normalizes_level_and_truncates() {
+    let e = DevLogEntry::new(
+        "ext".to_string(),
+        None,
+        "trace",
+        "x".repeat(20_000),
+        None,
+        None,
+    );
+    assert_eq!(e.level, "log");
+    assert!(e.message.len() < 20_000);
+    assert!(e.message.ends_with('…'));
+}
@@ -3,35 +3,10 @@ use thiserror::Error;
 use ts_rs::TS;
 
 use crate::database::error::DatabaseError;
+use crate::error_code::messages::{current_language, user_message};
+use crate::error_code::{CodedError, ErrorCode};
 use crate::remote_storage::StorageError;
 
-/// Error codes for frontend handling
-#[derive(Debug, Clone, Copy, PartialEq, Eq, TS)]
-#[ts(export)]
-pub enum ExtensionErrorCode {
-    SecurityViolation = 1000,
-    NotFound = 1001,
-    PermissionDenied = 1002,
-    MutexPoisoned = 1003,
-    PermissionPromptRequired = 1004,
-    Database = 2000,
-    Filesystem = 2001,
-    FilesystemWithPath = 2004,
-    Http = 2002,
-    Web = 2005,
-    Shell = 2003,
-    Manifest = 3000,
-    Validation = 3001,
-    InvalidPublicKey = 4000,
-    InvalidSignature = 4001,
-    InvalidActionString = 4004,
-    SignatureVerificationFailed = 4002,
-    CalculateHash = 4003,
-    Installation = 5000,
-    Storage = 6000,
-    LimitExceeded = 7000,
-}
-
 /// Serialized representation of ExtensionError for TypeScript.
 /// Not constructed in Rust — serves as the schema for the auto-generated TS type via ts_rs.
 #[allow(dead_code)]
@@ -43,16 +18,10 @@ pub struct SerializedExtensionError {
     #[serde(rename = "type")]
     pub error_type: String,
     pub message: String,
+    pub user_message: String,
     pub extension_id: Option<String>,
-}
-
-impl serde::Serialize for ExtensionErrorCode {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_u16(*self as u16)
-    }
+    #[ts(type = "unknown")]
+    pub details: Option<serde_json::Value>,
 }
 
 #[derive(Error, Debug)]
@@ -79,6 +48,15 @@ pub enum ExtensionError {
         target: String,
     },
 
+    #[error("Permission prompt timed out after {timeout_secs}s: {extension_id} wanted to {action} on {target}")]
+    PermissionPromptTimeout {
+        extension_id: String,
+        resource_type: String,
+        action: String,
+        target: String,
+        timeout_secs: u64,
+    },
+
     #[error("Database operation failed: {source}")]
     Database {
         #[from]
@@ -112,6 +90,17 @@ pub enum ExtensionError {
     #[error("Manifest error: {reason}")]
     ManifestError { reason: String },
 
+    #[error(
+        "{extension_name} requires host API {}, this host provides {host_version}",
+        host_version_range(min_host_version.as_deref(), max_host_version.as_deref())
+    )]
+    IncompatibleHostVersion {
+        extension_name: String,
+        min_host_version: Option<String>,
+        max_host_version: Option<String>,
+        host_version: String,
+    },
+
     #[error("Validation error: {reason}")]
     ValidationError { reason: String },
 
@@ -151,34 +140,34 @@ pub enum ExtensionError {
 
 impl ExtensionError {
     /// Get error code for this error
-    pub fn code(&self) -> ExtensionErrorCode {
+    pub fn code(&self) -> ErrorCode {
         match self {
-            ExtensionError::SecurityViolation { .. } => ExtensionErrorCode::SecurityViolation,
-            ExtensionError::NotFound { .. } => ExtensionErrorCode::NotFound,
-            ExtensionError::PermissionDenied { .. } => ExtensionErrorCode::PermissionDenied,
-            ExtensionError::PermissionPromptRequired { .. } => {
-                ExtensionErrorCode::PermissionPromptRequired
-            }
-            ExtensionError::Database { .. } => ExtensionErrorCode::Database,
-            ExtensionError::Filesystem { .. } => ExtensionErrorCode::Filesystem,
-            ExtensionError::FilesystemWithPath { .. } => ExtensionErrorCode::FilesystemWithPath,
-            ExtensionError::Http { .. } => ExtensionErrorCode::Http,
-            ExtensionError::WebError { .. } => ExtensionErrorCode::Web,
-            ExtensionError::Shell { .. } => ExtensionErrorCode::Shell,
-            ExtensionError::ManifestError { .. } => ExtensionErrorCode::Manifest,
-            ExtensionError::ValidationError { .. } => ExtensionErrorCode::Validation,
-            ExtensionError::InvalidPublicKey { .. } => ExtensionErrorCode::InvalidPublicKey,
-            ExtensionError::InvalidSignature { .. } => ExtensionErrorCode::InvalidSignature,
+            ExtensionError::SecurityViolation { .. } => ErrorCode::SecurityViolation,
+            ExtensionError::NotFound { .. } => ErrorCode::NotFound,
+            ExtensionError::PermissionDenied { .. } => ErrorCode::PermissionDenied,
+            ExtensionError::PermissionPromptRequired { .. } => ErrorCode::PermissionPromptRequired,
+            ExtensionError::PermissionPromptTimeout { .. } => ErrorCode::PermissionPromptTimeout,
+            ExtensionError::Database { .. } => ErrorCode::Database,
+            ExtensionError::Filesystem { .. } => ErrorCode::Filesystem,
+            ExtensionError::FilesystemWithPath { .. } => ErrorCode::FilesystemWithPath,
+            ExtensionError::Http { .. } => ErrorCode::Http,
+            ExtensionError::WebError { .. } => ErrorCode::Web,
+            ExtensionError::Shell { .. } => ErrorCode::Shell,
+            ExtensionError::ManifestError { .. } => ErrorCode::Manifest,
+            ExtensionError::ValidationError { .. } => ErrorCode::Validation,
+            ExtensionError::IncompatibleHostVersion { .. } => ErrorCode::IncompatibleHostVersion,
+            ExtensionError::InvalidPublicKey { .. } => ErrorCode::InvalidPublicKey,
+            ExtensionError::InvalidSignature { .. } => ErrorCode::InvalidSignature,
             ExtensionError::SignatureVerificationFailed { .. } => {
-                ExtensionErrorCode::SignatureVerificationFailed
+                ErrorCode::SignatureVerificationFailed
             }
-            ExtensionError::InstallationFailed { .. } => ExtensionErrorCode::Installation,
-            ExtensionError::CalculateHashError { .. } => ExtensionErrorCode::CalculateHash,
-            ExtensionError::MutexPoisoned { .. } => ExtensionErrorCode::MutexPoisoned,
-            ExtensionError::InvalidActionString { .. } => ExtensionErrorCode::InvalidActionString,
-            ExtensionError::StorageError { .. } => ExtensionErrorCode::Storage,
-            ExtensionError::FilesystemError { .. } => ExtensionErrorCode::Filesystem,
-            ExtensionError::LimitExceeded { .. } => ExtensionErrorCode::LimitExceeded,
+            ExtensionError::InstallationFailed { .. } => ErrorCode::Installation,
+            ExtensionError::CalculateHashError { .. } => ErrorCode::CalculateHash,
+            ExtensionError::MutexPoisoned { .. } => ErrorCode::MutexPoisoned,
+            ExtensionError::InvalidActionString { .. } => ErrorCode::InvalidActionString,
+            ExtensionError::StorageError { .. } => ErrorCode::Storage,
+            ExtensionError::FilesystemError { .. } => ErrorCode::Filesystem,
+            ExtensionError::LimitExceeded { .. } => ErrorCode::LimitExceeded,
         }
     }
 
@@ -201,6 +190,7 @@ impl ExtensionError {
         match self {
             ExtensionError::PermissionDenied { extension_id, .. } => Some(extension_id),
             ExtensionError::PermissionPromptRequired { extension_id, .. } => Some(extension_id),
+            ExtensionError::PermissionPromptTimeout { extension_id, .. } => Some(extension_id),
             _ => None,
         }
     }
@@ -247,34 +237,139 @@ impl serde::Serialize for ExtensionError {
             target,
         } = self
         {
-            let mut state = serializer.serialize_struct("ExtensionError", 8)?;
+            let mut state = serializer.serialize_struct("ExtensionError", 10)?;
             state.serialize_field("code", &self.code())?;
             state.serialize_field("type", &format!("{self:?}"))?;
             state.serialize_field("message", &self.to_string())?;
+            state.serialize_field("userMessage", self.user_message())?;
             state.serialize_field("extensionId", extension_id)?;
             state.serialize_field("extensionName", extension_name)?;
             state.serialize_field("resourceType", resource_type)?;
             state.serialize_field("action", action)?;
             state.serialize_field("target", target)?;
+            state.serialize_field("details", &self.error_details())?;
             return state.end();
         }
 
-        let mut state = serializer.serialize_struct("ExtensionError", 4)?;
+        // The frontend shows the required range next to the host version
+        if let ExtensionError::IncompatibleHostVersion {
+            min_host_version,
+            max_host_version,
+            host_version,
+            ..
+        } = self
+        {
+            let mut state = serializer.serialize_struct("ExtensionError", 9)?;
+            state.serialize_field("code", &self.code())?;
+            state.serialize_field("type", &format!("{self:?}"))?;
+            state.serialize_field("message", &self.to_string())?;
+            state.serialize_field("userMessage", self.user_message())?;
+            state.serialize_field("extensionId", &Option::<String>::None)?;
+            state.serialize_field("minHostVersion", min_host_version)?;
+            state.serialize_field("maxHostVersion", max_host_version)?;
+            state.serialize_field("hostVersion", host_version)?;
+            state.serialize_field("details", &self.error_details())?;
+            return state.end();
+        }
+
+        let mut state = serializer.serialize_struct("ExtensionError", 6)?;
 
         state.serialize_field("code", &self.code())?;
         state.serialize_field("type", &format!("{self:?}"))?;
         state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("userMessage", self.user_message())?;
 
         if let Some(ext_id) = self.extension_id() {
             state.serialize_field("extensionId", ext_id)?;
         } else {
             state.serialize_field("extensionId", &Option::<String>::None)?;
         }
+        state.serialize_field("details", &self.error_details())?;
 
         state.end()
     }
 }
 
+impl CodedError for ExtensionError {
+    fn error_code(&self) -> ErrorCode {
+        self.code()
+    }
+
+    fn user_message(&self) -> &'static str {
+        match self {
+            ExtensionError::Database { source } => source.user_message(),
+            _ => user_message(self.code(), current_language()),
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        use serde_json::json;
+
+        match self {
+            ExtensionError::PermissionDenied {
+                extension_id,
+                operation,
+                resource,
+            } => Some(json!({
+                "extensionId": extension_id,
+                "operation": operation,
+                "resource": resource,
+            })),
+            ExtensionError::PermissionPromptRequired {
+                extension_id,
+                extension_name,
+                resource_type,
+                action,
+                target,
+            } => Some(json!({
+                "extensionId": extension_id,
+                "extensionName": extension_name,
+                "resourceType": resource_type,
+                "action": action,
+                "target": target,
+            })),
+            ExtensionError::PermissionPromptTimeout {
+                extension_id,
+                resource_type,
+                action,
+                target,
+                timeout_secs,
+            } => Some(json!({
+                "extensionId": extension_id,
+                "resourceType": resource_type,
+                "action": action,
+                "target": target,
+                "timeoutSecs": timeout_secs,
+            })),
+            ExtensionError::IncompatibleHostVersion {
+                extension_name,
+                min_host_version,
+                max_host_version,
+                host_version,
+            } => Some(json!({
+                "extensionName": extension_name,
+                "minHostVersion": min_host_version,
+                "maxHostVersion": max_host_version,
+                "hostVersion": host_version,
+            })),
+            // The database error keeps its own, more specific code
+            ExtensionError::Database { source } => Some(json!({ "cause": source.envelope() })),
+            ExtensionError::FilesystemWithPath { path, .. } => Some(json!({ "path": path })),
+            ExtensionError::Shell { exit_code, .. } => Some(json!({ "exitCode": exit_code })),
+            _ => None,
+        }
+    }
+}
+
+fn host_version_range(min: Option<&str>, max: Option<&str>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("{min} to {max}"),
+        (Some(min), None) => format!("{min} or newer"),
+        (None, Some(max)) => format!("{max} or older"),
+        (None, None) => "any version".to_string(),
+    }
+}
+
 impl From<ExtensionError> for String {
     fn from(error: ExtensionError) -> Self {
         serde_json::to_string(&error).unwrap_or_else(|_| error.to_string())
@@ -0,0 +1,168 @@
+// src-tauri/src/extension/event_bus/commands.rs
+//!
+//! Event bus commands
+//!
+//! `extension_event_publish` is called by extension webviews directly and by
+//! the main window on behalf of iframe extensions. The policy commands are
+//! main-window only.
+
+use super::{
+    load_policy, save_policy, subscribers, validate_channel, ExtensionEventPolicy, EXTENSION_EVENT,
+    MAX_PAYLOAD_BYTES,
+};
+use crate::database::core::with_connection;
+use crate::extension::error::ExtensionError;
+use crate::extension::utils::resolve_extension_id;
+use crate::AppState;
+use serde::Serialize;
+use tauri::{AppHandle, State, WebviewWindow};
+use ts_rs::TS;
+
+/// Event as delivered to a subscriber.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionEventMessage {
+    pub channel: String,
+    /// Public key of the publishing extension
+    pub source_public_key: String,
+    /// Name of the publishing extension
+    pub source_name: String,
+    #[ts(type = "unknown")]
+    pub payload: serde_json::Value,
+}
+
+/// Payload of `EXTENSION_EVENT` sent to the main window for iframe
+/// subscribers. The frontend delivers it to these extensions only and strips
+/// `subscriberExtensionIds` before forwarding.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionEventBroadcast {
+    #[serde(flatten)]
+    pub message: ExtensionEventMessage,
+    pub subscriber_extension_ids: Vec<String>,
+}
+
+fn ensure_main_window(window: &WebviewWindow) -> Result<(), ExtensionError> {
+    if window.label() != "main" {
+        return Err(ExtensionError::SecurityViolation {
+            reason: "Only the main window can manage the event policy".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Publishes an event on `channel` to every subscribed extension. The caller
+/// must have declared the channel in its manifest's `events.publish`.
+/// Returns the number of extensions the event was addressed to.
+#[tauri::command(rename_all = "camelCase")]
+pub fn extension_event_publish(
+    window: WebviewWindow,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    channel: String,
+    payload: serde_json::Value,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<u32, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    validate_channel(&channel, false)?;
+
+    let publisher = state
+        .extension_manager
+        .get_extension(&extension_id)
+        .filter(|ext| ext.enabled)
+        .ok_or_else(|| ExtensionError::NotFound {
+            public_key: String::new(),
+            name: extension_id.clone(),
+        })?;
+    let may_publish = publisher
+        .manifest
+        .events
+        .as_ref()
+        .is_some_and(|events| events.may_publish(&channel));
+    if !may_publish {
+        return Err(ExtensionError::PermissionDenied {
+            extension_id,
+            operation: "publish".to_string(),
+            resource: format!("event channel '{channel}'"),
+        });
+    }
+
+    let size = serde_json::to_vec(&payload).map(|b| b.len()).unwrap_or(0);
+    if size > MAX_PAYLOAD_BYTES {
+        return Err(ExtensionError::ValidationError {
+            reason: format!("Event payload is {size} bytes, the limit is {MAX_PAYLOAD_BYTES}"),
+        });
+    }
+
+    let policy = with_connection(&state.db, |conn| load_policy(conn))?;
+    let extensions = state.extension_manager.get_all_extensions()?;
+    let targets = subscribers(&extensions, &extension_id, &channel, &policy);
+    if targets.is_empty() {
+        return Ok(0);
+    }
+
+    let message = ExtensionEventMessage {
+        channel,
+        source_public_key: publisher.manifest.public_key,
+        source_name: publisher.manifest.name,
+        payload,
+    };
+    let delivered = targets.len() as u32;
+
+    // Subscribers with native windows get the event directly; everyone else
+    // runs as an iframe and is reached through the main window.
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    let iframe_targets: Vec<String> = targets
+        .into_iter()
+        .filter(|target| {
+            !matches!(
+                state
+                    .extension_webview_manager
+                    .emit_to_all_extension_windows(&app_handle, target, EXTENSION_EVENT, &message,),
+                Ok(true)
+            )
+        })
+        .collect();
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    let iframe_targets = targets;
+
+    if !iframe_targets.is_empty() {
+        use tauri::Emitter;
+
+        let broadcast = ExtensionEventBroadcast {
+            message,
+            subscriber_extension_ids: iframe_targets,
+        };
+        if let Err(e) = app_handle.emit_to("main", EXTENSION_EVENT, &broadcast) {
+            eprintln!("[EventBus] Failed to forward event to iframes: {e}");
+        }
+    }
+
+    Ok(delivered)
+}
+
+/// Returns the vault's event bus policy.
+#[tauri::command]
+pub fn extension_event_get_policy(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+) -> Result<ExtensionEventPolicy, ExtensionError> {
+    ensure_main_window(&window)?;
+    Ok(with_connection(&state.db, |conn| load_policy(conn))?)
+}
+
+/// Stores the vault's event bus policy. Applies to the next published event.
+#[tauri::command]
+pub fn extension_event_set_policy(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    policy: ExtensionEventPolicy,
+) -> Result<ExtensionEventPolicy, ExtensionError> {
+    ensure_main_window(&window)?;
+    with_connection(&state.db, |conn| save_policy(conn, &policy))?;
+    Ok(policy)
+}
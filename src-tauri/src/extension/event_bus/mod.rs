@@ -0,0 +1,161 @@
+// src-tauri/src/extension/event_bus/mod.rs
+//!
+//! Event bus between extensions
+//!
+//! Extensions declare in their manifest (`events.publish` / `events.subscribe`)
+//! which channels they publish to and listen on. `extension_event_publish`
+//! only accepts channels the caller declared for publishing, and the event
+//! reaches exactly the enabled extensions subscribed to the channel: their
+//! webview windows directly, iframe extensions through the main window,
+//! which receives the subscriber list and strips it before fan-out.
+//!
+//! Wildcard subscriptions (`*`, `notes.*`) would let an extension listen to
+//! channels it doesn't know about, so they are ignored unless the vault's
+//! [`ExtensionEventPolicy`] allows them.
+
+pub mod commands;
+
+#[cfg(test)]
+mod tests;
+
+use crate::database::constants::vault_settings_key;
+use crate::database::error::DatabaseError;
+use crate::extension::core::manifest::{is_wildcard_channel, EventChannels};
+use crate::extension::core::types::Extension;
+use crate::extension::error::ExtensionError;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, TABLE_CRDT_CONFIGS,
+};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use ts_rs::TS;
+
+/// Event delivered to subscribers (and to the main window for iframes).
+/// Matches HAEXTENSION_EVENTS.EVENT in vault-sdk.
+pub const EXTENSION_EVENT: &str = "haextension:event";
+
+/// Longest accepted channel name
+pub const MAX_CHANNEL_LEN: usize = 128;
+
+/// Largest accepted payload (serialized JSON)
+pub const MAX_PAYLOAD_BYTES: usize = 256 * 1024;
+
+/// Vault-wide rules of the event bus.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExtensionEventPolicy {
+    /// Honour wildcard subscriptions (`*`, `prefix.*`) from manifests
+    pub allow_wildcard_subscriptions: bool,
+}
+
+/// Checks a channel name: dot-separated segments of `[a-z0-9_-]`. With
+/// `allow_wildcard`, `*` is accepted alone or as the last segment.
+pub fn validate_channel(channel: &str, allow_wildcard: bool) -> Result<(), ExtensionError> {
+    let invalid = |reason: &str| ExtensionError::ValidationError {
+        reason: format!("Invalid event channel '{channel}': {reason}"),
+    };
+
+    if channel.is_empty() || channel.len() > MAX_CHANNEL_LEN {
+        return Err(invalid("must be 1 to 128 characters"));
+    }
+    if is_wildcard_channel(channel) && !allow_wildcard {
+        return Err(invalid("wildcards are not allowed here"));
+    }
+
+    let segments: Vec<&str> = channel.split('.').collect();
+    let last = segments.len() - 1;
+    for (i, segment) in segments.iter().enumerate() {
+        if *segment == "*" && i == last && allow_wildcard {
+            continue;
+        }
+        let valid = !segment.is_empty()
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+        if !valid {
+            return Err(invalid("segments may only contain a-z, 0-9, '_' and '-'"));
+        }
+    }
+    Ok(())
+}
+
+/// Reads the event channels of a bundle's manifest.json. Invalid entries are
+/// dropped; `publish` never keeps wildcards.
+pub fn read_manifest_event_channels(manifest_path: &Path) -> Option<EventChannels> {
+    let content = fs::read_to_string(manifest_path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let channels: EventChannels = serde_json::from_value(value.get("events")?.clone()).ok()?;
+    Some(sanitize_channels(channels))
+}
+
+/// Drops invalid channel entries (and wildcards from `publish`).
+pub fn sanitize_channels(channels: EventChannels) -> EventChannels {
+    let keep = |allow_wildcard: bool| {
+        move |channel: &String| match validate_channel(channel, allow_wildcard) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("[EventBus] Ignoring manifest entry: {e}");
+                false
+            }
+        }
+    };
+    EventChannels {
+        publish: channels.publish.into_iter().filter(keep(false)).collect(),
+        subscribe: channels.subscribe.into_iter().filter(keep(true)).collect(),
+    }
+}
+
+/// Ids of the enabled extensions (other than the publisher) that receive an
+/// event on `channel`.
+pub fn subscribers(
+    extensions: &[Extension],
+    publisher_id: &str,
+    channel: &str,
+    policy: &ExtensionEventPolicy,
+) -> Vec<String> {
+    extensions
+        .iter()
+        .filter(|ext| ext.enabled && ext.id != publisher_id)
+        .filter(|ext| {
+            ext.manifest.events.as_ref().is_some_and(|events| {
+                events.subscribes_to(channel, policy.allow_wildcard_subscriptions)
+            })
+        })
+        .map(|ext| ext.id.clone())
+        .collect()
+}
+
+/// The stored policy, or the default (no wildcards) if none was saved.
+pub fn load_policy(conn: &Connection) -> Result<ExtensionEventPolicy, DatabaseError> {
+    let stored: Option<String> = conn
+        .query_row(
+            &format!(
+                "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"
+            ),
+            params![vault_settings_key::EXTENSION_EVENT_POLICY],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match stored {
+        None => Ok(ExtensionEventPolicy::default()),
+        Some(json) => serde_json::from_str(&json).map_err(|e| DatabaseError::SerializationError {
+            reason: format!("Invalid extension event policy: {e}"),
+        }),
+    }
+}
+
+pub fn save_policy(conn: &Connection, policy: &ExtensionEventPolicy) -> Result<(), DatabaseError> {
+    let json = serde_json::to_string(policy).map_err(|e| DatabaseError::SerializationError {
+        reason: e.to_string(),
+    })?;
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?, ?, ?)"
+        ),
+        params![vault_settings_key::EXTENSION_EVENT_POLICY, "system", json],
+    )?;
+    Ok(())
+}
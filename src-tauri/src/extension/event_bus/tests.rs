@@ -0,0 +1,148 @@
+// src-tauri/src/extension/event_bus/tests.rs
+//!
+//! Tests for event bus channel checks and subscriber filtering
+
+use super::{read_manifest_event_channels, subscribers, validate_channel, ExtensionEventPolicy};
+use crate::extension::core::manifest::{EventChannels, ExtensionManifest, ExtensionPermissions};
+use crate::extension::core::types::{Extension, ExtensionSource};
+use std::path::PathBuf;
+
+fn extension(id: &str, enabled: bool, events: Option<EventChannels>) -> Extension {
+    Extension {
+        id: id.to_string(),
+        manifest: ExtensionManifest {
+            name: id.to_string(),
+            version: "1.0.0".to_string(),
+            author: None,
+            entry: Some("index.html".to_string()),
+            icon: None,
+            public_key: "pk".to_string(),
+            signature: "sig".to_string(),
+            permissions: ExtensionPermissions::default(),
+            homepage: None,
+            description: None,
+            single_instance: None,
+            display_mode: None,
+            migrations_dir: None,
+            i18n: None,
+            key_rotations: None,
+            locales: None,
+            share_target: None,
+            events,
+            min_host_version: None,
+            max_host_version: None,
+        },
+        source: ExtensionSource::Production {
+            path: PathBuf::from("/tmp/test"),
+            version: "1.0.0".to_string(),
+        },
+        enabled,
+        last_accessed: std::time::SystemTime::now(),
+    }
+}
+
+fn subscribing(patterns: &[&str]) -> Option<EventChannels> {
+    Some(EventChannels {
+        publish: Vec::new(),
+        subscribe: patterns.iter().map(|p| p.to_string()).collect(),
+    })
+}
+
+#[test]
+fn test_validate_channel() {
+    assert!(validate_channel("notes.updated", false).is_ok());
+    assert!(validate_channel("calendar-sync.event_created", false).is_ok());
+
+    assert!(validate_channel("", false).is_err());
+    assert!(validate_channel("Notes.Updated", false).is_err());
+    assert!(validate_channel("notes..updated", false).is_err());
+    assert!(validate_channel("notes.up dated", false).is_err());
+    assert!(validate_channel(&"a".repeat(129), false).is_err());
+
+    // Wildcards only where allowed, and only as the last segment
+    assert!(validate_channel("notes.*", false).is_err());
+    assert!(validate_channel("*", false).is_err());
+    assert!(validate_channel("notes.*", true).is_ok());
+    assert!(validate_channel("*", true).is_ok());
+    assert!(validate_channel("*.updated", true).is_err());
+    assert!(validate_channel("notes*", true).is_err());
+}
+
+#[test]
+fn test_publish_requires_exact_declaration() {
+    let channels = EventChannels {
+        publish: vec!["notes.updated".to_string()],
+        subscribe: Vec::new(),
+    };
+    assert!(channels.may_publish("notes.updated"));
+    assert!(!channels.may_publish("notes.deleted"));
+    assert!(!channels.may_publish("notes"));
+}
+
+#[test]
+fn test_wildcard_subscriptions_denied_by_default() {
+    let extensions = vec![
+        extension("exact", true, subscribing(&["notes.updated"])),
+        extension("prefix", true, subscribing(&["notes.*"])),
+        extension("all", true, subscribing(&["*"])),
+        extension("other", true, subscribing(&["calendar.changed"])),
+        extension("none", true, None),
+    ];
+
+    let default_policy = ExtensionEventPolicy::default();
+    assert!(!default_policy.allow_wildcard_subscriptions);
+    assert_eq!(
+        subscribers(&extensions, "publisher", "notes.updated", &default_policy),
+        vec!["exact"]
+    );
+
+    let permissive = ExtensionEventPolicy {
+        allow_wildcard_subscriptions: true,
+    };
+    assert_eq!(
+        subscribers(&extensions, "publisher", "notes.updated", &permissive),
+        vec!["exact", "prefix", "all"]
+    );
+    // `notes.*` doesn't match the bare prefix
+    assert_eq!(
+        subscribers(&extensions, "publisher", "notes", &permissive),
+        vec!["all"]
+    );
+}
+
+#[test]
+fn test_subscribers_skip_publisher_and_disabled() {
+    let extensions = vec![
+        extension("publisher", true, subscribing(&["notes.updated"])),
+        extension("disabled", false, subscribing(&["notes.updated"])),
+        extension("listener", true, subscribing(&["notes.updated"])),
+    ];
+
+    assert_eq!(
+        subscribers(
+            &extensions,
+            "publisher",
+            "notes.updated",
+            &ExtensionEventPolicy::default()
+        ),
+        vec!["listener"]
+    );
+}
+
+#[test]
+fn test_read_manifest_drops_invalid_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = dir.path().join("manifest.json");
+    std::fs::write(
+        &manifest,
+        r#"{"events": {"publish": ["notes.updated", "notes.*", "Bad Name"], "subscribe": ["calendar.*", "*.x"]}}"#,
+    )
+    .unwrap();
+
+    let channels = read_manifest_event_channels(&manifest).unwrap();
+    assert_eq!(channels.publish, vec!["notes.updated"]);
+    assert_eq!(channels.subscribe, vec!["calendar.*"]);
+
+    std::fs::write(&manifest, r#"{"name": "no-events"}"#).unwrap();
+    assert!(read_manifest_event_channels(&manifest).is_none());
+}
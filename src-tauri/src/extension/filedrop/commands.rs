@@ -0,0 +1,114 @@
+// src-tauri/src/extension/filedrop/commands.rs
+//!
+//! File drop commands
+//!
+//! `extension_filedrop_set_target` is called by the main window whenever the
+//! focused iframe extension changes, `extension_filedrop_unload` when the
+//! last iframe of an extension goes away. The read/release commands are
+//! called by extensions (WebView or iframe) with the drop ID from
+//! `FILEDROP_EVENT`.
+
+use crate::extension::error::ExtensionError;
+use crate::extension::utils::resolve_extension_id;
+use crate::AppState;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use std::io::{Read, Seek, SeekFrom};
+use tauri::{State, WebviewWindow};
+
+/// Maximum bytes returned by a single `extension_filedrop_read` call.
+const MAX_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Sets (or clears) the extension that receives files dropped onto the main
+/// window. Only the main window may call this.
+#[tauri::command(rename_all = "camelCase")]
+pub fn extension_filedrop_set_target(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    extension_id: Option<String>,
+) -> Result<(), ExtensionError> {
+    if window.label() != "main" {
+        return Err(ExtensionError::SecurityViolation {
+            reason: "Only the main window can set the file drop target".to_string(),
+        });
+    }
+    state.file_drops.set_main_target(extension_id)
+}
+
+/// Releases all pending drops of an iframe extension whose last iframe was
+/// unloaded, and clears it as drop target. Only the main window may call this.
+#[tauri::command(rename_all = "camelCase")]
+pub fn extension_filedrop_unload(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    extension_id: String,
+) -> Result<(), ExtensionError> {
+    if window.label() != "main" {
+        return Err(ExtensionError::SecurityViolation {
+            reason: "Only the main window can unload file drops".to_string(),
+        });
+    }
+    state.file_drops.release_all_for_extension(&extension_id)
+}
+
+/// Reads a chunk of a dropped file as base64.
+///
+/// `offset` defaults to 0, `length` defaults to (and is capped at) 4 MiB so
+/// large files can be streamed without loading them into memory at once.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_filedrop_read(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    drop_id: String,
+    file_index: usize,
+    offset: Option<u64>,
+    length: Option<u64>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<String, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let (path, info) = state
+        .file_drops
+        .resolve_file(&extension_id, &drop_id, file_index)?;
+
+    let offset = offset.unwrap_or(0);
+    let length = length.unwrap_or(MAX_CHUNK_SIZE).min(MAX_CHUNK_SIZE);
+    if offset >= info.size {
+        return Ok(String::new());
+    }
+    let to_read = length.min(info.size - offset);
+
+    let bytes = tokio::task::spawn_blocking(move || -> Result<Vec<u8>, ExtensionError> {
+        let path_str = path.display().to_string();
+        let mut file = std::fs::File::open(&path)
+            .map_err(|e| ExtensionError::filesystem_with_path(path_str.clone(), e))?;
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| ExtensionError::filesystem_with_path(path_str.clone(), e))?;
+        let mut buf = Vec::with_capacity(to_read as usize);
+        file.take(to_read)
+            .read_to_end(&mut buf)
+            .map_err(|e| ExtensionError::filesystem_with_path(path_str, e))?;
+        Ok(buf)
+    })
+    .await
+    .map_err(|e| ExtensionError::FilesystemError {
+        reason: format!("File drop read task failed: {e}"),
+    })??;
+
+    Ok(BASE64.encode(bytes))
+}
+
+/// Releases a drop once the extension has consumed it. Returns false if the
+/// drop was unknown, expired or owned by another extension.
+#[tauri::command(rename_all = "camelCase")]
+pub fn extension_filedrop_release(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    drop_id: String,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<bool, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    state.file_drops.release(&extension_id, &drop_id)
+}
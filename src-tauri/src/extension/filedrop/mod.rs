@@ -0,0 +1,95 @@
+// src-tauri/src/extension/filedrop/mod.rs
+//!
+//! Drag-and-drop file ingestion for extensions
+//!
+//! Files dropped onto an extension window (or onto the main window while an
+//! iframe extension is focused) are registered under a short-lived drop ID
+//! and announced to the target extension via `FILEDROP_EVENT`. The event only
+//! carries metadata — the extension reads the bytes through
+//! `extension_filedrop_read`, which checks that the caller owns the drop.
+//! Dropping a file is an explicit user gesture, so no `fs` permission is
+//! required for the dropped files themselves.
+
+pub mod commands;
+pub mod registry;
+
+#[cfg(test)]
+mod tests;
+
+pub use registry::FileDropRegistry;
+
+use crate::AppState;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+/// Event delivered to the target extension after a drop.
+/// Matches HAEXTENSION_EVENTS.FILE_DROP in vault-sdk.
+pub const FILEDROP_EVENT: &str = "haextension:filedrop";
+
+/// Routes a Tauri drag-drop event to the owning extension.
+///
+/// `window_label` is the Tauri label of the window the files were dropped on:
+/// - a registered extension window → that extension
+/// - `"main"` → the extension the frontend marked as focused via
+///   `extension_filedrop_set_target` (iframe mode)
+///
+/// Drops without a target are ignored; the main window handles its own
+/// drops (e.g. vault import) in the frontend.
+pub fn handle_drop(app_handle: &AppHandle, window_label: &str, paths: Vec<PathBuf>) {
+    let state = app_handle.state::<AppState>();
+
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    let window_extension = state
+        .extension_webview_manager
+        .windows
+        .lock()
+        .ok()
+        .and_then(|windows| windows.get(window_label).cloned());
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    let window_extension: Option<String> = None;
+
+    let extension_id = match window_extension {
+        Some(id) => id,
+        None if window_label == "main" => match state.file_drops.main_target() {
+            Some(id) => id,
+            None => return,
+        },
+        None => return,
+    };
+
+    let max_file_size = state.limits.defaults().filesystem.max_file_size_bytes;
+    let payload = match state
+        .file_drops
+        .register_drop(&extension_id, paths, max_file_size.max(0) as u64)
+    {
+        Ok(payload) => payload,
+        Err(e) => {
+            eprintln!("[FileDrop] Failed to register drop: {e}");
+            return;
+        }
+    };
+
+    eprintln!(
+        "[FileDrop] {} file(s) dropped for extension {} ({} rejected)",
+        payload.files.len(),
+        extension_id,
+        payload.rejected.len()
+    );
+
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    let result = state.extension_webview_manager.emit_to_extension_or_main(
+        app_handle,
+        &extension_id,
+        FILEDROP_EVENT,
+        &payload,
+    );
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    let result = {
+        use tauri::Emitter;
+        app_handle.emit_to("main", FILEDROP_EVENT, &payload)
+    };
+
+    if let Err(e) = result {
+        eprintln!("[FileDrop] Failed to emit drop event: {e}");
+    }
+}
@@ -0,0 +1,244 @@
+// src-tauri/src/extension/filedrop/registry.rs
+//!
+//! In-memory registry of pending file drops (drop_id → files + owner).
+
+use crate::extension::error::ExtensionError;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Maximum number of files accepted from a single drop.
+pub const MAX_FILES_PER_DROP: usize = 64;
+
+/// How long a drop stays readable before it is pruned.
+pub const DROP_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Metadata of a dropped file as sent to the extension.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedFileInfo {
+    /// Index inside the drop, used with `extension_filedrop_read`.
+    pub index: usize,
+    pub name: String,
+    pub size: u64,
+    pub mime_type: String,
+    /// Last modification time (ms since epoch), if available.
+    pub modified: Option<u64>,
+}
+
+/// A file that was dropped but not made available to the extension.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedFile {
+    pub name: String,
+    pub reason: String,
+}
+
+/// Payload of `FILEDROP_EVENT`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDropPayload {
+    pub drop_id: String,
+    pub extension_id: String,
+    pub files: Vec<DroppedFileInfo>,
+    pub rejected: Vec<RejectedFile>,
+}
+
+struct DropEntry {
+    extension_id: String,
+    files: Vec<(PathBuf, DroppedFileInfo)>,
+    created_at: Instant,
+}
+
+pub struct FileDropRegistry {
+    drops: Mutex<HashMap<String, DropEntry>>,
+    /// Extension currently focused in the main window (iframe mode).
+    main_target: Mutex<Option<String>>,
+}
+
+impl FileDropRegistry {
+    pub fn new() -> Self {
+        Self {
+            drops: Mutex::new(HashMap::new()),
+            main_target: Mutex::new(None),
+        }
+    }
+
+    fn lock_drops(&self) -> Result<MutexGuard<'_, HashMap<String, DropEntry>>, ExtensionError> {
+        self.drops.lock().map_err(|e| ExtensionError::MutexPoisoned {
+            reason: e.to_string(),
+        })
+    }
+
+    pub fn set_main_target(&self, extension_id: Option<String>) -> Result<(), ExtensionError> {
+        let mut target = self
+            .main_target
+            .lock()
+            .map_err(|e| ExtensionError::MutexPoisoned {
+                reason: e.to_string(),
+            })?;
+        *target = extension_id;
+        Ok(())
+    }
+
+    pub fn main_target(&self) -> Option<String> {
+        self.main_target.lock().ok().and_then(|t| t.clone())
+    }
+
+    /// Registers the dropped paths for `extension_id`. Directories, unreadable
+    /// entries, files above `max_file_size` and everything past
+    /// `MAX_FILES_PER_DROP` end up in `rejected`.
+    pub fn register_drop(
+        &self,
+        extension_id: &str,
+        paths: Vec<PathBuf>,
+        max_file_size: u64,
+    ) -> Result<FileDropPayload, ExtensionError> {
+        let mut files = Vec::new();
+        let mut rejected = Vec::new();
+
+        for path in paths {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string_lossy().to_string());
+
+            let reject = |reason: &str| RejectedFile {
+                name: name.clone(),
+                reason: reason.to_string(),
+            };
+
+            if files.len() >= MAX_FILES_PER_DROP {
+                rejected.push(reject("too many files in one drop"));
+                continue;
+            }
+
+            let metadata = match std::fs::metadata(&path) {
+                Ok(m) => m,
+                Err(e) => {
+                    rejected.push(reject(&format!("unreadable: {e}")));
+                    continue;
+                }
+            };
+            if !metadata.is_file() {
+                rejected.push(reject("not a regular file"));
+                continue;
+            }
+            if metadata.len() > max_file_size {
+                rejected.push(reject(&format!(
+                    "file exceeds size limit of {max_file_size} bytes"
+                )));
+                continue;
+            }
+
+            let info = DroppedFileInfo {
+                index: files.len(),
+                mime_type: mime_guess::from_path(&path)
+                    .first_or_octet_stream()
+                    .to_string(),
+                name,
+                size: metadata.len(),
+                modified: metadata
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as u64),
+            };
+            files.push((path, info));
+        }
+
+        let drop_id = uuid::Uuid::new_v4().to_string();
+        let payload = FileDropPayload {
+            drop_id: drop_id.clone(),
+            extension_id: extension_id.to_string(),
+            files: files.iter().map(|(_, info)| info.clone()).collect(),
+            rejected,
+        };
+
+        let mut drops = self.lock_drops()?;
+        Self::prune_locked(&mut drops);
+        if !files.is_empty() {
+            drops.insert(
+                drop_id,
+                DropEntry {
+                    extension_id: extension_id.to_string(),
+                    files,
+                    created_at: Instant::now(),
+                },
+            );
+        }
+
+        Ok(payload)
+    }
+
+    /// Returns the path of a dropped file after verifying that the caller
+    /// owns the drop and the drop hasn't expired.
+    pub fn resolve_file(
+        &self,
+        extension_id: &str,
+        drop_id: &str,
+        index: usize,
+    ) -> Result<(PathBuf, DroppedFileInfo), ExtensionError> {
+        let mut drops = self.lock_drops()?;
+        Self::prune_locked(&mut drops);
+
+        let entry = drops
+            .get(drop_id)
+            .filter(|entry| entry.extension_id == extension_id)
+            .ok_or_else(|| ExtensionError::NotFound {
+                public_key: String::new(),
+                name: format!("file drop {drop_id}"),
+            })?;
+
+        entry
+            .files
+            .get(index)
+            .cloned()
+            .ok_or_else(|| ExtensionError::ValidationError {
+                reason: format!("File index {index} out of range for drop {drop_id}"),
+            })
+    }
+
+    /// Releases a drop early (the extension has consumed it).
+    pub fn release(&self, extension_id: &str, drop_id: &str) -> Result<bool, ExtensionError> {
+        let mut drops = self.lock_drops()?;
+        match drops.get(drop_id) {
+            Some(entry) if entry.extension_id == extension_id => {
+                drops.remove(drop_id);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Drops all pending entries of an extension and stops routing main-window
+    /// drops to it (its last window closed, it was disabled or removed).
+    pub fn release_all_for_extension(&self, extension_id: &str) -> Result<(), ExtensionError> {
+        let mut drops = self.lock_drops()?;
+        drops.retain(|_, entry| entry.extension_id != extension_id);
+        drop(drops);
+
+        let mut target = self
+            .main_target
+            .lock()
+            .map_err(|e| ExtensionError::MutexPoisoned {
+                reason: e.to_string(),
+            })?;
+        if target.as_deref() == Some(extension_id) {
+            *target = None;
+        }
+        Ok(())
+    }
+
+    fn prune_locked(drops: &mut HashMap<String, DropEntry>) {
+        drops.retain(|_, entry| entry.created_at.elapsed() < DROP_TTL);
+    }
+}
+
+impl Default for FileDropRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
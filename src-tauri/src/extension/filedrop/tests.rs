@@ -0,0 +1,115 @@
+// src-tauri/src/extension/filedrop/tests.rs
+//!
+//! Tests for the file drop registry
+
+use super::registry::{FileDropRegistry, MAX_FILES_PER_DROP};
+use std::path::PathBuf;
+
+fn write_file(dir: &tempfile::TempDir, name: &str, len: usize) -> PathBuf {
+    let path = dir.path().join(name);
+    std::fs::write(&path, vec![b'x'; len]).unwrap();
+    path
+}
+
+#[test]
+fn test_register_drop_collects_metadata() {
+    let dir = tempfile::tempdir().unwrap();
+    let registry = FileDropRegistry::new();
+    let path = write_file(&dir, "receipt.pdf", 10);
+
+    let payload = registry.register_drop("ext-a", vec![path], 1024).unwrap();
+
+    assert_eq!(payload.extension_id, "ext-a");
+    assert_eq!(payload.files.len(), 1);
+    assert_eq!(payload.files[0].name, "receipt.pdf");
+    assert_eq!(payload.files[0].size, 10);
+    assert_eq!(payload.files[0].mime_type, "application/pdf");
+    assert!(payload.rejected.is_empty());
+}
+
+#[test]
+fn test_register_drop_rejects_oversized_and_directories() {
+    let dir = tempfile::tempdir().unwrap();
+    let registry = FileDropRegistry::new();
+    let big = write_file(&dir, "big.bin", 2048);
+
+    let payload = registry
+        .register_drop("ext-a", vec![big, dir.path().to_path_buf()], 1024)
+        .unwrap();
+
+    assert!(payload.files.is_empty());
+    assert_eq!(payload.rejected.len(), 2);
+}
+
+#[test]
+fn test_register_drop_caps_file_count() {
+    let dir = tempfile::tempdir().unwrap();
+    let registry = FileDropRegistry::new();
+    let paths: Vec<PathBuf> = (0..MAX_FILES_PER_DROP + 2)
+        .map(|i| write_file(&dir, &format!("f{i}.txt"), 1))
+        .collect();
+
+    let payload = registry.register_drop("ext-a", paths, 1024).unwrap();
+
+    assert_eq!(payload.files.len(), MAX_FILES_PER_DROP);
+    assert_eq!(payload.rejected.len(), 2);
+}
+
+#[test]
+fn test_resolve_file_requires_owner() {
+    let dir = tempfile::tempdir().unwrap();
+    let registry = FileDropRegistry::new();
+    let path = write_file(&dir, "a.txt", 3);
+    let payload = registry.register_drop("ext-a", vec![path.clone()], 1024).unwrap();
+
+    let (resolved, info) = registry.resolve_file("ext-a", &payload.drop_id, 0).unwrap();
+    assert_eq!(resolved, path);
+    assert_eq!(info.size, 3);
+
+    assert!(registry.resolve_file("ext-b", &payload.drop_id, 0).is_err());
+    assert!(registry.resolve_file("ext-a", &payload.drop_id, 1).is_err());
+}
+
+#[test]
+fn test_release_only_by_owner() {
+    let dir = tempfile::tempdir().unwrap();
+    let registry = FileDropRegistry::new();
+    let path = write_file(&dir, "a.txt", 3);
+    let payload = registry.register_drop("ext-a", vec![path], 1024).unwrap();
+
+    assert!(!registry.release("ext-b", &payload.drop_id).unwrap());
+    assert!(registry.release("ext-a", &payload.drop_id).unwrap());
+    assert!(registry.resolve_file("ext-a", &payload.drop_id, 0).is_err());
+}
+
+#[test]
+fn test_main_target() {
+    let registry = FileDropRegistry::new();
+    assert!(registry.main_target().is_none());
+    registry.set_main_target(Some("ext-a".to_string())).unwrap();
+    assert_eq!(registry.main_target().as_deref(), Some("ext-a"));
+    registry.set_main_target(None).unwrap();
+    assert!(registry.main_target().is_none());
+}
+
+#[test]
+fn test_release_all_for_extension_clears_drops_and_target() {
+    let dir = tempfile::tempdir().unwrap();
+    let registry = FileDropRegistry::new();
+    let a = registry
+        .register_drop("ext-a", vec![write_file(&dir, "a.txt", 1)], 1024)
+        .unwrap();
+    let b = registry
+        .register_drop("ext-b", vec![write_file(&dir, "b.txt", 1)], 1024)
+        .unwrap();
+    registry.set_main_target(Some("ext-a".to_string())).unwrap();
+
+    registry.release_all_for_extension("ext-b").unwrap();
+    assert!(registry.resolve_file("ext-b", &b.drop_id, 0).is_err());
+    assert!(registry.resolve_file("ext-a", &a.drop_id, 0).is_ok());
+    assert_eq!(registry.main_target().as_deref(), Some("ext-a"));
+
+    registry.release_all_for_extension("ext-a").unwrap();
+    assert!(registry.resolve_file("ext-a", &a.drop_id, 0).is_err());
+    assert!(registry.main_target().is_none());
+}
@@ -0,0 +1,116 @@
+// src-tauri/src/extension/filesync/commands.rs
+//!
+//! FileSync Thumbnail and Usage Commands
+//!
+//! `filesync_*` are internal commands for the host UI.
+//! `extension_filesync_get_thumbnail` is the permission-checked variant and
+//! requires `filesync` read permission for backends — the same permission
+//! that allows downloading the full blob.
+
+use super::thumbnails::{self, Thumbnail, ThumbnailError};
+use crate::database::core::with_connection;
+use crate::extension::error::ExtensionError;
+use crate::extension::permissions::manager::PermissionManager;
+use crate::extension::permissions::types::{FileSyncAction, FileSyncTarget};
+use crate::extension::utils::{emit_permission_prompt_if_needed, resolve_extension_id};
+use crate::remote_storage::usage::{self, StorageUsageReport};
+use crate::remote_storage::StorageError;
+use crate::AppState;
+use tauri::{AppHandle, State, WebviewWindow};
+
+/// Get (and cache) the thumbnail of a file stored in a backend.
+/// `size` is rounded up to the next supported bucket (64–1024 px).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn filesync_get_thumbnail(
+    state: State<'_, AppState>,
+    backend_id: String,
+    file_id: String,
+    size: Option<u32>,
+) -> Result<Thumbnail, ThumbnailError> {
+    thumbnails::get_or_render(&state.db, &backend_id, &file_id, size).await
+}
+
+/// Drop cached thumbnails for one backend, or all if `backend_id` is omitted.
+#[tauri::command(rename_all = "camelCase")]
+pub fn filesync_clear_thumbnails(
+    state: State<'_, AppState>,
+    backend_id: Option<String>,
+) -> Result<usize, ThumbnailError> {
+    Ok(with_connection(&state.db, |conn| {
+        Ok(thumbnails::clear_cached(conn, backend_id.as_deref())?)
+    })?)
+}
+
+/// Bytes and file counts per backend and per space, from the usage ledger
+#[tauri::command]
+pub fn filesync_get_usage(state: State<'_, AppState>) -> Result<StorageUsageReport, StorageError> {
+    with_connection(&state.db, |conn| usage::usage_report(conn)).map_err(|e| {
+        StorageError::DatabaseError {
+            reason: e.to_string(),
+        }
+    })
+}
+
+/// Set the quota of a backend in bytes, or remove it with `None`
+#[tauri::command(rename_all = "camelCase")]
+pub fn filesync_set_backend_quota(
+    state: State<'_, AppState>,
+    backend_id: String,
+    quota_bytes: Option<u64>,
+) -> Result<(), StorageError> {
+    with_connection(&state.db, |conn| {
+        usage::set_quota(conn, &backend_id, quota_bytes)
+    })
+    .map_err(|e| StorageError::DatabaseError {
+        reason: e.to_string(),
+    })
+}
+
+/// Rebuild the usage ledger of a backend from a full listing. Only needed
+/// for objects written before accounting or by other devices.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn filesync_rebuild_usage(
+    state: State<'_, AppState>,
+    backend_id: String,
+) -> Result<StorageUsageReport, StorageError> {
+    usage::rebuild_backend_usage(&state.db, &backend_id).await?;
+    with_connection(&state.db, |conn| usage::usage_report(conn)).map_err(|e| {
+        StorageError::DatabaseError {
+            reason: e.to_string(),
+        }
+    })
+}
+
+/// Get a file thumbnail on behalf of an extension (requires filesync:backends:read).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_filesync_get_thumbnail(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    backend_id: String,
+    file_id: String,
+    size: Option<u32>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<Thumbnail, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+
+    let permission_result = PermissionManager::check_filesync_permission(
+        &state,
+        &extension_id,
+        FileSyncAction::Read,
+        FileSyncTarget::Backends,
+    )
+    .await;
+    if let Err(ref e) = permission_result {
+        emit_permission_prompt_if_needed(&app_handle, e);
+    }
+    permission_result?;
+
+    thumbnails::get_or_render(&state.db, &backend_id, &file_id, size)
+        .await
+        .map_err(|e| ExtensionError::ValidationError {
+            reason: e.to_string(),
+        })
+}
@@ -0,0 +1,14 @@
+// src-tauri/src/extension/filesync/mod.rs
+//!
+//! FileSync services for file-browser extensions
+//!
+//! Currently provides cached thumbnails for images and PDFs stored in
+//! storage backends, so a grid view doesn't need to download full blobs,
+//! and storage usage reports per backend and space
+//! (see `remote_storage::usage`).
+
+pub mod commands;
+pub mod thumbnails;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,136 @@
+// src-tauri/src/extension/filesync/tests.rs
+//!
+//! Tests for FileSync thumbnails
+
+use super::thumbnails::{
+    clear_cached, evict_oldest, load_cached, normalize_size, remove_cached_file, render_thumbnail,
+    store_cached, RenderedThumbnail, ThumbnailError, MAX_THUMBNAIL_SIZE,
+};
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use rusqlite::Connection;
+use std::io::Cursor;
+
+fn encode(image: DynamicImage, format: ImageFormat) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    image.write_to(&mut Cursor::new(&mut bytes), format).unwrap();
+    bytes
+}
+
+fn setup_cache_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(include_str!(
+        "../../../database/migrations/0008_add_thumbnails.sql"
+    ))
+    .unwrap();
+    conn
+}
+
+#[test]
+fn test_normalize_size_rounds_up_to_bucket() {
+    assert_eq!(normalize_size(None), 256);
+    assert_eq!(normalize_size(Some(1)), 64);
+    assert_eq!(normalize_size(Some(200)), 256);
+    assert_eq!(normalize_size(Some(256)), 256);
+    assert_eq!(normalize_size(Some(10_000)), MAX_THUMBNAIL_SIZE);
+}
+
+#[test]
+fn test_opaque_image_is_resized_to_jpeg() {
+    let source = DynamicImage::new_rgb8(800, 400);
+    let bytes = encode(source, ImageFormat::Png);
+
+    let thumb = render_thumbnail("photo.png", &bytes, 128).unwrap();
+    assert_eq!(thumb.mime_type, "image/jpeg");
+    assert_eq!((thumb.width, thumb.height), (128, 64));
+    assert!(thumb.bytes.starts_with(&[0xFF, 0xD8, 0xFF]));
+}
+
+#[test]
+fn test_transparent_image_keeps_png() {
+    let source = RgbaImage::from_pixel(300, 300, Rgba([0, 0, 0, 0]));
+    let bytes = encode(DynamicImage::ImageRgba8(source), ImageFormat::Png);
+
+    let thumb = render_thumbnail("blob-without-extension", &bytes, 64).unwrap();
+    assert_eq!(thumb.mime_type, "image/png");
+    assert_eq!((thumb.width, thumb.height), (64, 64));
+}
+
+#[test]
+fn test_unsupported_type_is_rejected() {
+    let err = render_thumbnail("notes.txt", b"hello", 64).unwrap_err();
+    assert!(matches!(err, ThumbnailError::UnsupportedType { .. }));
+}
+
+#[test]
+fn test_cache_is_keyed_by_source_version() {
+    let conn = setup_cache_db();
+    let thumb = RenderedThumbnail {
+        mime_type: "image/jpeg".to_string(),
+        width: 64,
+        height: 32,
+        bytes: vec![1, 2, 3],
+    };
+
+    store_cached(&conn, "b1", "photos/a.jpg", 64, Some("2026-01-01T00:00:00Z"), &thumb).unwrap();
+
+    let hit = load_cached(&conn, "b1", "photos/a.jpg", 64, Some("2026-01-01T00:00:00Z")).unwrap();
+    assert_eq!(hit, Some(thumb.clone()));
+
+    // Blob changed on the backend → stale entry is ignored
+    let stale = load_cached(&conn, "b1", "photos/a.jpg", 64, Some("2026-02-01T00:00:00Z")).unwrap();
+    assert_eq!(stale, None);
+
+    // Other size bucket is a separate entry
+    assert_eq!(load_cached(&conn, "b1", "photos/a.jpg", 128, Some("2026-01-01T00:00:00Z")).unwrap(), None);
+
+    // Re-rendering replaces the row instead of violating the primary key
+    store_cached(&conn, "b1", "photos/a.jpg", 64, Some("2026-02-01T00:00:00Z"), &thumb).unwrap();
+    store_cached(&conn, "b2", "photos/a.jpg", 64, None, &thumb).unwrap();
+    assert_eq!(load_cached(&conn, "b2", "photos/a.jpg", 64, None).unwrap(), Some(thumb));
+
+    assert_eq!(clear_cached(&conn, Some("b1")).unwrap(), 1);
+    assert_eq!(clear_cached(&conn, None).unwrap(), 1);
+}
+
+#[test]
+fn test_cache_evicts_oldest_entries_beyond_the_cap() {
+    let conn = setup_cache_db();
+    let thumb = RenderedThumbnail {
+        mime_type: "image/jpeg".to_string(),
+        width: 64,
+        height: 64,
+        bytes: vec![0; 100],
+    };
+    for file in ["a.jpg", "b.jpg", "c.jpg"] {
+        store_cached(&conn, "b1", file, 64, None, &thumb).unwrap();
+    }
+
+    assert_eq!(evict_oldest(&conn, 250).unwrap(), 1);
+    assert_eq!(load_cached(&conn, "b1", "a.jpg", 64, None).unwrap(), None);
+    assert_ne!(load_cached(&conn, "b1", "b.jpg", 64, None).unwrap(), None);
+    assert_ne!(load_cached(&conn, "b1", "c.jpg", 64, None).unwrap(), None);
+
+    // Within the cap nothing is evicted
+    assert_eq!(evict_oldest(&conn, 200).unwrap(), 0);
+}
+
+#[test]
+fn test_removing_a_file_drops_all_its_sizes() {
+    let conn = setup_cache_db();
+    let thumb = RenderedThumbnail {
+        mime_type: "image/jpeg".to_string(),
+        width: 64,
+        height: 64,
+        bytes: vec![1, 2, 3],
+    };
+    for size in [64, 256] {
+        store_cached(&conn, "b1", "a.jpg", size, None, &thumb).unwrap();
+    }
+    store_cached(&conn, "b1", "b.jpg", 64, None, &thumb).unwrap();
+    store_cached(&conn, "b2", "a.jpg", 64, None, &thumb).unwrap();
+
+    assert_eq!(remove_cached_file(&conn, "b1", "a.jpg").unwrap(), 2);
+    assert_eq!(load_cached(&conn, "b1", "a.jpg", 256, None).unwrap(), None);
+    assert_ne!(load_cached(&conn, "b1", "b.jpg", 64, None).unwrap(), None);
+    assert_ne!(load_cached(&conn, "b2", "a.jpg", 64, None).unwrap(), None);
+}
@@ -0,0 +1,496 @@
+// src-tauri/src/extension/filesync/thumbnails.rs
+//!
+//! Thumbnail generation and caching for FileSync blobs
+//!
+//! Thumbnails are rendered from the blob in its storage backend and cached in
+//! `haex_thumbnails_no_sync`, i.e. inside the encrypted vault database. A
+//! cached entry is reused as long as the backend reports the same
+//! last-modified timestamp for the blob — checking that is a metadata-only
+//! listing, so a warm grid never downloads full objects.
+//!
+//! The cache holds at most [`MAX_CACHE_BYTES`] of thumbnail data; storing a
+//! new entry evicts the oldest ones beyond that. Entries of a file the
+//! backend no longer lists are dropped when the file is requested.
+//!
+//! - Raster images are decoded and resized with the `image` crate.
+//! - PDFs render their first page via the system `pdftoppm` binary (poppler)
+//!   when it is installed; otherwise they fail with `RendererUnavailable`.
+
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::database::DbConnection;
+use crate::error_code::{serialize_tagged, CodedError, ErrorCode};
+use crate::remote_storage::StorageError;
+use crate::table_names::{
+    COL_THUMBNAILS_NO_SYNC_BACKEND_ID, COL_THUMBNAILS_NO_SYNC_CREATED_AT,
+    COL_THUMBNAILS_NO_SYNC_DATA, COL_THUMBNAILS_NO_SYNC_FILE_ID, COL_THUMBNAILS_NO_SYNC_HEIGHT,
+    COL_THUMBNAILS_NO_SYNC_MIME_TYPE, COL_THUMBNAILS_NO_SYNC_SIZE,
+    COL_THUMBNAILS_NO_SYNC_SOURCE_MODIFIED, COL_THUMBNAILS_NO_SYNC_WIDTH, TABLE_THUMBNAILS_NO_SYNC,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use image::{DynamicImage, ImageFormat};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::io::Cursor;
+use std::process::Command;
+use thiserror::Error;
+
+/// Requested sizes are rounded up to one of these bounding boxes so the cache
+/// holds at most a handful of variants per file.
+pub const THUMBNAIL_SIZES: &[u32] = &[64, 128, 256, 512, 1024];
+
+pub const DEFAULT_THUMBNAIL_SIZE: u32 = 256;
+pub const MAX_THUMBNAIL_SIZE: u32 = 1024;
+
+/// Blobs larger than this are not downloaded for thumbnailing (50 MB).
+pub const MAX_SOURCE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Total size of cached thumbnail data per vault (256 MB).
+pub const MAX_CACHE_BYTES: u64 = 256 * 1024 * 1024;
+
+const JPEG_QUALITY: u8 = 80;
+
+#[derive(Debug, Clone, Error, Serialize)]
+#[serde(tag = "type", content = "details")]
+#[serde(remote = "Self")]
+pub enum ThumbnailError {
+    #[error("File not found: {file_id}")]
+    NotFound { file_id: String },
+
+    #[error("Unsupported content type for thumbnails: {mime_type}")]
+    UnsupportedType { mime_type: String },
+
+    #[error("Source too large for thumbnailing: {size} bytes (max {max})")]
+    TooLarge { size: u64, max: u64 },
+
+    #[error("No renderer available for {mime_type} on this system")]
+    RendererUnavailable { mime_type: String },
+
+    #[error("Thumbnail rendering failed: {reason}")]
+    RenderFailed { reason: String },
+
+    #[error("Storage error: {reason}")]
+    Storage { reason: String },
+
+    #[error("Database error: {reason}")]
+    Database { reason: String },
+}
+
+impl CodedError for ThumbnailError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            ThumbnailError::NotFound { .. } => ErrorCode::ResourceNotFound,
+            ThumbnailError::UnsupportedType { .. } => ErrorCode::Unsupported,
+            ThumbnailError::TooLarge { .. } => ErrorCode::LimitExceeded,
+            ThumbnailError::RendererUnavailable { .. } => ErrorCode::Unsupported,
+            ThumbnailError::RenderFailed { .. } => ErrorCode::ProcessingFailed,
+            ThumbnailError::Storage { .. } => ErrorCode::Storage,
+            ThumbnailError::Database { .. } => ErrorCode::Database,
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        self.tagged().get("details").cloned()
+    }
+}
+
+impl ThumbnailError {
+    /// The derived `{ type, details }` representation
+    fn tagged(&self) -> serde_json::Value {
+        ThumbnailError::serialize(self, serde_json::value::Serializer).unwrap_or_default()
+    }
+}
+
+impl Serialize for ThumbnailError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_tagged(self, &self.tagged(), serializer)
+    }
+}
+
+impl From<StorageError> for ThumbnailError {
+    fn from(e: StorageError) -> Self {
+        ThumbnailError::Storage {
+            reason: e.to_string(),
+        }
+    }
+}
+
+impl From<DatabaseError> for ThumbnailError {
+    fn from(e: DatabaseError) -> Self {
+        ThumbnailError::Database {
+            reason: e.to_string(),
+        }
+    }
+}
+
+impl From<std::io::Error> for ThumbnailError {
+    fn from(e: std::io::Error) -> Self {
+        ThumbnailError::RenderFailed {
+            reason: e.to_string(),
+        }
+    }
+}
+
+/// A rendered thumbnail. `data` is the base64-encoded image.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Thumbnail {
+    pub file_id: String,
+    pub size: u32,
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+    pub data: String,
+}
+
+/// Raw thumbnail as stored in the cache.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedThumbnail {
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+    pub bytes: Vec<u8>,
+}
+
+impl RenderedThumbnail {
+    fn into_thumbnail(self, file_id: &str, size: u32) -> Thumbnail {
+        Thumbnail {
+            file_id: file_id.to_string(),
+            size,
+            mime_type: self.mime_type,
+            width: self.width,
+            height: self.height,
+            data: BASE64.encode(&self.bytes),
+        }
+    }
+}
+
+/// Rounds a requested size up to the nearest supported bucket.
+pub fn normalize_size(requested: Option<u32>) -> u32 {
+    let requested = requested.unwrap_or(DEFAULT_THUMBNAIL_SIZE);
+    THUMBNAIL_SIZES
+        .iter()
+        .copied()
+        .find(|s| *s >= requested)
+        .unwrap_or(MAX_THUMBNAIL_SIZE)
+}
+
+/// Renders a thumbnail that fits into `size`×`size`. Blocking — call from
+/// `spawn_blocking`.
+pub fn render_thumbnail(
+    file_name: &str,
+    bytes: &[u8],
+    size: u32,
+) -> Result<RenderedThumbnail, ThumbnailError> {
+    let mime_type = crate::content_extract::extractors::detect_mime(file_name, bytes);
+
+    let image = if mime_type == "application/pdf" {
+        render_pdf_first_page(bytes, size)?
+    } else if mime_type.starts_with("image/") {
+        image::load_from_memory(bytes).map_err(|e| match e {
+            image::ImageError::Unsupported(_) => ThumbnailError::UnsupportedType {
+                mime_type: mime_type.clone(),
+            },
+            other => ThumbnailError::RenderFailed {
+                reason: other.to_string(),
+            },
+        })?
+    } else {
+        return Err(ThumbnailError::UnsupportedType { mime_type });
+    };
+
+    encode_thumbnail(&image.thumbnail(size, size))
+}
+
+/// Encodes as JPEG, or PNG when the image has transparency.
+fn encode_thumbnail(image: &DynamicImage) -> Result<RenderedThumbnail, ThumbnailError> {
+    let mut bytes = Vec::new();
+    let mime_type = if image.color().has_alpha() {
+        image
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .map_err(|e| ThumbnailError::RenderFailed {
+                reason: e.to_string(),
+            })?;
+        "image/png"
+    } else {
+        let encoder =
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY);
+        image
+            .to_rgb8()
+            .write_with_encoder(encoder)
+            .map_err(|e| ThumbnailError::RenderFailed {
+                reason: e.to_string(),
+            })?;
+        "image/jpeg"
+    };
+
+    Ok(RenderedThumbnail {
+        mime_type: mime_type.to_string(),
+        width: image.width(),
+        height: image.height(),
+        bytes,
+    })
+}
+
+/// Returns true if the `pdftoppm` binary is on PATH.
+pub fn pdf_renderer_available() -> bool {
+    Command::new("pdftoppm")
+        .arg("-v")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Rasterizes the first PDF page with `pdftoppm`. Input and output go through
+/// a private temp directory that is removed when this returns.
+fn render_pdf_first_page(bytes: &[u8], size: u32) -> Result<DynamicImage, ThumbnailError> {
+    if !pdf_renderer_available() {
+        return Err(ThumbnailError::RendererUnavailable {
+            mime_type: "application/pdf".to_string(),
+        });
+    }
+
+    let dir = tempfile::Builder::new().prefix("haex-thumb-").tempdir()?;
+    let input = dir.path().join("source.pdf");
+    let output_prefix = dir.path().join("page");
+    std::fs::write(&input, bytes)?;
+
+    let output = Command::new("pdftoppm")
+        .args(["-png", "-singlefile", "-f", "1", "-l", "1", "-scale-to"])
+        .arg(size.to_string())
+        .arg(&input)
+        .arg(&output_prefix)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(ThumbnailError::RenderFailed {
+            reason: format!(
+                "pdftoppm exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+
+    let page = std::fs::read(output_prefix.with_extension("png"))?;
+    image::load_from_memory(&page).map_err(|e| ThumbnailError::RenderFailed {
+        reason: e.to_string(),
+    })
+}
+
+// ============================================================================
+// Cache
+// ============================================================================
+
+/// Returns the cached thumbnail if it was rendered from the same blob version.
+pub fn load_cached(
+    conn: &Connection,
+    backend_id: &str,
+    file_id: &str,
+    size: u32,
+    source_modified: Option<&str>,
+) -> Result<Option<RenderedThumbnail>, rusqlite::Error> {
+    let sql = format!(
+        "SELECT {mime}, {width}, {height}, {data} FROM {table} \
+         WHERE {backend} = ?1 AND {file} = ?2 AND {size} = ?3 AND {modified} IS ?4",
+        mime = COL_THUMBNAILS_NO_SYNC_MIME_TYPE,
+        width = COL_THUMBNAILS_NO_SYNC_WIDTH,
+        height = COL_THUMBNAILS_NO_SYNC_HEIGHT,
+        data = COL_THUMBNAILS_NO_SYNC_DATA,
+        table = TABLE_THUMBNAILS_NO_SYNC,
+        backend = COL_THUMBNAILS_NO_SYNC_BACKEND_ID,
+        file = COL_THUMBNAILS_NO_SYNC_FILE_ID,
+        size = COL_THUMBNAILS_NO_SYNC_SIZE,
+        modified = COL_THUMBNAILS_NO_SYNC_SOURCE_MODIFIED,
+    );
+    conn.query_row(
+        &sql,
+        params![backend_id, file_id, size, source_modified],
+        |row| {
+            Ok(RenderedThumbnail {
+                mime_type: row.get(0)?,
+                width: row.get(1)?,
+                height: row.get(2)?,
+                bytes: row.get(3)?,
+            })
+        },
+    )
+    .optional()
+}
+
+/// Inserts or replaces the cache entry for (backend, file, size).
+pub fn store_cached(
+    conn: &Connection,
+    backend_id: &str,
+    file_id: &str,
+    size: u32,
+    source_modified: Option<&str>,
+    thumbnail: &RenderedThumbnail,
+) -> Result<(), rusqlite::Error> {
+    let created_at = time::OffsetDateTime::now_utc()
+        .format(&time::format_description::well_known::Rfc3339)
+        .unwrap_or_default();
+    let sql = format!(
+        "INSERT OR REPLACE INTO {table} \
+         ({backend}, {file}, {size}, {modified}, {mime}, {width}, {height}, {data}, {created}) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        table = TABLE_THUMBNAILS_NO_SYNC,
+        backend = COL_THUMBNAILS_NO_SYNC_BACKEND_ID,
+        file = COL_THUMBNAILS_NO_SYNC_FILE_ID,
+        size = COL_THUMBNAILS_NO_SYNC_SIZE,
+        modified = COL_THUMBNAILS_NO_SYNC_SOURCE_MODIFIED,
+        mime = COL_THUMBNAILS_NO_SYNC_MIME_TYPE,
+        width = COL_THUMBNAILS_NO_SYNC_WIDTH,
+        height = COL_THUMBNAILS_NO_SYNC_HEIGHT,
+        data = COL_THUMBNAILS_NO_SYNC_DATA,
+        created = COL_THUMBNAILS_NO_SYNC_CREATED_AT,
+    );
+    conn.execute(
+        &sql,
+        params![
+            backend_id,
+            file_id,
+            size,
+            source_modified,
+            thumbnail.mime_type,
+            thumbnail.width,
+            thumbnail.height,
+            thumbnail.bytes,
+            created_at
+        ],
+    )?;
+    Ok(())
+}
+
+/// Removes cached thumbnails, either for one backend or all of them.
+/// Returns the number of removed rows.
+pub fn clear_cached(conn: &Connection, backend_id: Option<&str>) -> Result<usize, rusqlite::Error> {
+    match backend_id {
+        Some(id) => conn.execute(
+            &format!(
+                "DELETE FROM {} WHERE {} = ?1",
+                TABLE_THUMBNAILS_NO_SYNC, COL_THUMBNAILS_NO_SYNC_BACKEND_ID
+            ),
+            params![id],
+        ),
+        None => conn.execute(&format!("DELETE FROM {}", TABLE_THUMBNAILS_NO_SYNC), []),
+    }
+}
+
+/// Removes the cached thumbnails of one file, in every size. Returns the
+/// number of removed rows.
+pub fn remove_cached_file(
+    conn: &Connection,
+    backend_id: &str,
+    file_id: &str,
+) -> Result<usize, rusqlite::Error> {
+    conn.execute(
+        &format!(
+            "DELETE FROM {} WHERE {} = ?1 AND {} = ?2",
+            TABLE_THUMBNAILS_NO_SYNC,
+            COL_THUMBNAILS_NO_SYNC_BACKEND_ID,
+            COL_THUMBNAILS_NO_SYNC_FILE_ID
+        ),
+        params![backend_id, file_id],
+    )
+}
+
+/// Evicts the oldest cache entries until the thumbnail data fits into
+/// `max_bytes`. Returns the number of removed rows.
+pub fn evict_oldest(conn: &Connection, max_bytes: u64) -> Result<usize, rusqlite::Error> {
+    // Keeps the newest entries whose sizes add up to at most `max_bytes`
+    let sql = format!(
+        "DELETE FROM {table} WHERE rowid IN ( \
+             SELECT rowid FROM ( \
+                 SELECT rowid, SUM(length({data})) OVER ( \
+                     ORDER BY julianday({created}) DESC, rowid DESC \
+                 ) AS newer_bytes \
+                 FROM {table} \
+             ) WHERE newer_bytes > ?1 \
+         )",
+        table = TABLE_THUMBNAILS_NO_SYNC,
+        data = COL_THUMBNAILS_NO_SYNC_DATA,
+        created = COL_THUMBNAILS_NO_SYNC_CREATED_AT,
+    );
+    conn.execute(&sql, params![i64::try_from(max_bytes).unwrap_or(i64::MAX)])
+}
+
+/// Returns the thumbnail for `file_id` in `backend_id`, rendering and caching
+/// it first if there is no up-to-date cache entry.
+pub async fn get_or_render(
+    db: &DbConnection,
+    backend_id: &str,
+    file_id: &str,
+    size: Option<u32>,
+) -> Result<Thumbnail, ThumbnailError> {
+    let size = normalize_size(size);
+    let backend =
+        crate::remote_storage::commands::get_backend_instance_from_db_with_overrides(
+            db, backend_id, None,
+        )
+        .await?;
+
+    // Metadata only: tells us whether the cache is stale and guards the download size.
+    let Some(info) = backend
+        .list(Some(file_id))
+        .await?
+        .into_iter()
+        .find(|o| o.key == file_id)
+    else {
+        // The file is gone, so are its thumbnails
+        with_connection(db, |conn| {
+            Ok(remove_cached_file(conn, backend_id, file_id)?)
+        })?;
+        return Err(ThumbnailError::NotFound {
+            file_id: file_id.to_string(),
+        });
+    };
+    let source_modified = info.last_modified;
+
+    let cached = with_connection(db, |conn| {
+        Ok(load_cached(
+            conn,
+            backend_id,
+            file_id,
+            size,
+            source_modified.as_deref(),
+        )?)
+    })?;
+    crate::metrics::record_cache(crate::metrics::CACHE_THUMBNAIL, cached.is_some());
+    if let Some(cached) = cached {
+        return Ok(cached.into_thumbnail(file_id, size));
+    }
+
+    if info.size > MAX_SOURCE_BYTES {
+        return Err(ThumbnailError::TooLarge {
+            size: info.size,
+            max: MAX_SOURCE_BYTES,
+        });
+    }
+
+    let bytes = backend.download(file_id).await?;
+    let file_name = file_id.rsplit('/').next().unwrap_or(file_id).to_string();
+    let rendered = tokio::task::spawn_blocking(move || render_thumbnail(&file_name, &bytes, size))
+        .await
+        .map_err(|e| ThumbnailError::RenderFailed {
+            reason: format!("thumbnail task failed: {e}"),
+        })??;
+
+    with_connection(db, |conn| {
+        store_cached(
+            conn,
+            backend_id,
+            file_id,
+            size,
+            source_modified.as_deref(),
+            &rendered,
+        )?;
+        evict_oldest(conn, MAX_CACHE_BYTES)?;
+        Ok(())
+    })?;
+
+    Ok(rendered.into_thumbnail(file_id, size))
+}
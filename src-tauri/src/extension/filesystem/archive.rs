@@ -0,0 +1,453 @@
+// src-tauri/src/extension/filesystem/archive.rs
+//!
+//! Safe ZIP extraction and creation.
+//!
+//! Used by the extension installer and by the `extension_filesystem_zip` /
+//! `extension_filesystem_unzip` commands. Extraction never trusts the
+//! archive: entry names must stay inside the destination, symlinks are
+//! rejected, and the entry count and uncompressed size are capped — both as
+//! declared in the central directory and as actually written, so a lying
+//! header can't be used as a zip bomb.
+
+use crate::extension::error::ExtensionError;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Caps applied while extracting or creating an archive
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveLimits {
+    pub max_entries: usize,
+    /// Uncompressed bytes over all entries
+    pub max_total_bytes: u64,
+}
+
+/// Maximum entries per archive handled for extensions
+pub const MAX_EXTENSION_ARCHIVE_ENTRIES: usize = 10_000;
+
+impl ArchiveLimits {
+    /// Limits for extension bundles unpacked by the installer
+    pub const INSTALLER: Self = Self {
+        max_entries: 50_000,
+        max_total_bytes: 2 * 1024 * 1024 * 1024,
+    };
+}
+
+/// What was packed or unpacked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveSummary {
+    /// Files and directories
+    pub entries: usize,
+    /// Uncompressed bytes of all files
+    pub total_bytes: u64,
+}
+
+fn invalid(reason: String) -> ExtensionError {
+    ExtensionError::ValidationError { reason }
+}
+
+fn io_error(path: &Path, e: io::Error) -> ExtensionError {
+    ExtensionError::filesystem_with_path(path.display().to_string(), e)
+}
+
+fn zip_error(path: &Path, e: zip::result::ZipError) -> ExtensionError {
+    ExtensionError::FilesystemError {
+        reason: format!("Invalid ZIP archive '{}': {}", path.display(), e),
+    }
+}
+
+fn open_archive(archive_path: &Path) -> Result<ZipArchive<File>, ExtensionError> {
+    let file = File::open(archive_path).map_err(|e| io_error(archive_path, e))?;
+    ZipArchive::new(file).map_err(|e| zip_error(archive_path, e))
+}
+
+/// Checks every entry of the archive against `limits` without writing
+/// anything and returns the declared totals.
+pub fn inspect_zip(
+    archive_path: &Path,
+    limits: &ArchiveLimits,
+) -> Result<ArchiveSummary, ExtensionError> {
+    let mut archive = open_archive(archive_path)?;
+    if archive.len() > limits.max_entries {
+        return Err(invalid(format!(
+            "Archive has {} entries, at most {} are allowed",
+            archive.len(),
+            limits.max_entries
+        )));
+    }
+
+    let mut summary = ArchiveSummary::default();
+    for index in 0..archive.len() {
+        let entry = archive
+            .by_index(index)
+            .map_err(|e| zip_error(archive_path, e))?;
+        if entry.enclosed_name().is_none() {
+            return Err(ExtensionError::SecurityViolation {
+                reason: format!("Archive entry escapes the destination: {}", entry.name()),
+            });
+        }
+        if entry.is_symlink() {
+            return Err(ExtensionError::SecurityViolation {
+                reason: format!("Archive entry is a symlink: {}", entry.name()),
+            });
+        }
+
+        summary.entries += 1;
+        summary.total_bytes = summary.total_bytes.saturating_add(entry.size());
+        if summary.total_bytes > limits.max_total_bytes {
+            return Err(invalid(format!(
+                "Archive expands to more than {} bytes",
+                limits.max_total_bytes
+            )));
+        }
+    }
+    Ok(summary)
+}
+
+/// Extracts `archive_path` into `destination` (created if missing).
+///
+/// Entry names, symlink entries and declared sizes ([`inspect_zip`]) and
+/// symlinks already in the destination are checked before the first file is
+/// written; an archive failing them leaves the destination untouched. Only an
+/// entry holding more data than its header declares is caught while writing:
+/// extraction stops there, removes that file and keeps the ones written
+/// before it.
+pub fn extract_zip(
+    archive_path: &Path,
+    destination: &Path,
+    limits: &ArchiveLimits,
+) -> Result<ArchiveSummary, ExtensionError> {
+    inspect_zip(archive_path, limits)?;
+
+    let mut archive = open_archive(archive_path)?;
+    let mut relatives = Vec::with_capacity(archive.len());
+    for index in 0..archive.len() {
+        let entry = archive
+            .by_index(index)
+            .map_err(|e| zip_error(archive_path, e))?;
+        let relative = entry
+            .enclosed_name()
+            .ok_or_else(|| ExtensionError::SecurityViolation {
+                reason: format!("Archive entry escapes the destination: {}", entry.name()),
+            })?;
+        refuse_symlinks(destination, &relative)?;
+        relatives.push(relative);
+    }
+
+    fs::create_dir_all(destination).map_err(|e| io_error(destination, e))?;
+    let mut summary = ArchiveSummary::default();
+
+    for (index, relative) in relatives.iter().enumerate() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| zip_error(archive_path, e))?;
+        let target = destination.join(relative);
+        summary.entries += 1;
+        // Checked again in case the destination changed in the meantime
+        refuse_symlinks(destination, relative)?;
+
+        if entry.is_dir() {
+            fs::create_dir_all(&target).map_err(|e| io_error(&target, e))?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| io_error(parent, e))?;
+        }
+
+        // Read at most one byte past the budget to detect lying headers
+        let budget = limits.max_total_bytes - summary.total_bytes;
+        let mut out = File::create(&target).map_err(|e| io_error(&target, e))?;
+        let written = io::copy(&mut (&mut entry).take(budget + 1), &mut out)
+            .map_err(|e| io_error(&target, e))?;
+        if written > budget {
+            drop(out);
+            let _ = fs::remove_file(&target);
+            return Err(invalid(format!(
+                "Archive expands to more than {} bytes",
+                limits.max_total_bytes
+            )));
+        }
+        summary.total_bytes += written;
+    }
+
+    Ok(summary)
+}
+
+/// Refuses an entry if an existing symlink lies on its path below
+/// `destination`: writing through it would land outside the destination.
+fn refuse_symlinks(destination: &Path, relative: &Path) -> Result<(), ExtensionError> {
+    let mut path = destination.to_path_buf();
+    for component in relative.components() {
+        path.push(component);
+        match fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                return Err(ExtensionError::SecurityViolation {
+                    reason: format!(
+                        "Archive entry would be written through a symlink: {}",
+                        path.display()
+                    ),
+                });
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => break,
+            Err(e) => return Err(io_error(&path, e)),
+        }
+    }
+    Ok(())
+}
+
+/// Files and directories below `root` in a stable order, relative to `root`.
+fn collect_entries(root: &Path, limits: &ArchiveLimits) -> Result<Vec<PathBuf>, ExtensionError> {
+    let mut entries = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut children: Vec<PathBuf> = fs::read_dir(&dir)
+            .map_err(|e| io_error(&dir, e))?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .collect();
+        children.sort();
+
+        for child in children {
+            let metadata = fs::symlink_metadata(&child).map_err(|e| io_error(&child, e))?;
+            if metadata.file_type().is_symlink() {
+                continue;
+            }
+            if metadata.is_dir() {
+                pending.push(child.clone());
+            }
+            let relative = child.strip_prefix(root).unwrap_or(&child).to_path_buf();
+            entries.push(relative);
+            if entries.len() > limits.max_entries {
+                return Err(invalid(format!(
+                    "More than {} entries to archive",
+                    limits.max_entries
+                )));
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Name of an entry inside the archive (always `/`-separated)
+fn entry_name(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Packs `source` (a file or a directory with everything below it) into a
+/// new ZIP file at `archive_path`. Symlinks are skipped.
+pub fn create_zip(
+    source: &Path,
+    archive_path: &Path,
+    limits: &ArchiveLimits,
+) -> Result<ArchiveSummary, ExtensionError> {
+    let metadata = fs::metadata(source).map_err(|e| io_error(source, e))?;
+    let (root, entries) = if metadata.is_dir() {
+        (source.to_path_buf(), collect_entries(source, limits)?)
+    } else {
+        let root = source.parent().unwrap_or(Path::new("")).to_path_buf();
+        let name = source
+            .file_name()
+            .map(PathBuf::from)
+            .ok_or_else(|| invalid(format!("Not a file: {}", source.display())))?;
+        (root, vec![name])
+    };
+
+    let total_bytes: u64 = entries
+        .iter()
+        .filter_map(|relative| fs::metadata(root.join(relative)).ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum();
+    if total_bytes > limits.max_total_bytes {
+        return Err(invalid(format!(
+            "{} bytes to archive, at most {} are allowed",
+            total_bytes, limits.max_total_bytes
+        )));
+    }
+
+    if let Some(parent) = archive_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| io_error(parent, e))?;
+    }
+    let file = File::create(archive_path).map_err(|e| io_error(archive_path, e))?;
+    let mut writer = ZipWriter::new(file);
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(total_bytes > u64::from(u32::MAX));
+
+    for relative in &entries {
+        let path = root.join(relative);
+        let name = entry_name(relative);
+        if path.is_dir() {
+            writer
+                .add_directory(name, options)
+                .map_err(|e| zip_error(archive_path, e))?;
+        } else {
+            writer
+                .start_file(name, options)
+                .map_err(|e| zip_error(archive_path, e))?;
+            let mut input = File::open(&path).map_err(|e| io_error(&path, e))?;
+            io::copy(&mut input, &mut writer).map_err(|e| io_error(archive_path, e))?;
+        }
+    }
+    writer.finish().map_err(|e| zip_error(archive_path, e))?;
+
+    Ok(ArchiveSummary {
+        entries: entries.len(),
+        total_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const LIMITS: ArchiveLimits = ArchiveLimits {
+        max_entries: 100,
+        max_total_bytes: 1024,
+    };
+
+    fn write_raw_zip(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut writer = ZipWriter::new(File::create(path).unwrap());
+        for (name, data) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("notes");
+        fs::create_dir_all(source.join("sub")).unwrap();
+        fs::write(source.join("a.txt"), b"alpha").unwrap();
+        fs::write(source.join("sub").join("b.txt"), b"beta").unwrap();
+
+        let archive = dir.path().join("out").join("notes.zip");
+        let packed = create_zip(&source, &archive, &LIMITS).unwrap();
+        assert_eq!(packed.entries, 3);
+        assert_eq!(packed.total_bytes, 9);
+
+        let target = dir.path().join("restored");
+        let unpacked = extract_zip(&archive, &target, &LIMITS).unwrap();
+        assert_eq!(unpacked.total_bytes, 9);
+        assert_eq!(fs::read(target.join("a.txt")).unwrap(), b"alpha");
+        assert_eq!(fs::read(target.join("sub").join("b.txt")).unwrap(), b"beta");
+    }
+
+    #[test]
+    fn test_single_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("report.csv");
+        fs::write(&source, b"a,b").unwrap();
+
+        let archive = dir.path().join("report.zip");
+        create_zip(&source, &archive, &LIMITS).unwrap();
+
+        let target = dir.path().join("x");
+        extract_zip(&archive, &target, &LIMITS).unwrap();
+        assert_eq!(fs::read(target.join("report.csv")).unwrap(), b"a,b");
+    }
+
+    #[test]
+    fn test_traversal_is_rejected_before_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("evil.zip");
+        write_raw_zip(&archive, &[("ok.txt", b"fine"), ("../escape.txt", b"bad")]);
+
+        let target = dir.path().join("target");
+        assert!(matches!(
+            extract_zip(&archive, &target, &LIMITS),
+            Err(ExtensionError::SecurityViolation { .. })
+        ));
+        assert!(!target.join("ok.txt").exists());
+        assert!(!dir.path().join("escape.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_existing_symlinks_are_not_followed() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = dir.path().join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("victim.txt"), b"keep").unwrap();
+
+        let target = dir.path().join("target");
+        fs::create_dir_all(&target).unwrap();
+        std::os::unix::fs::symlink(outside.join("victim.txt"), target.join("file.txt")).unwrap();
+        std::os::unix::fs::symlink(&outside, target.join("dir")).unwrap();
+
+        for name in ["file.txt", "dir/new.txt"] {
+            let archive = dir.path().join("links.zip");
+            write_raw_zip(&archive, &[(name, b"overwritten")]);
+            assert!(
+                matches!(
+                    extract_zip(&archive, &target, &LIMITS),
+                    Err(ExtensionError::SecurityViolation { .. })
+                ),
+                "{name}"
+            );
+        }
+        assert_eq!(fs::read(outside.join("victim.txt")).unwrap(), b"keep");
+        assert!(!outside.join("new.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_are_refused_before_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = dir.path().join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        let target = dir.path().join("target");
+        fs::create_dir_all(&target).unwrap();
+        std::os::unix::fs::symlink(&outside, target.join("dir")).unwrap();
+
+        let archive = dir.path().join("links.zip");
+        write_raw_zip(&archive, &[("ok.txt", b"fine"), ("dir/new.txt", b"bad")]);
+        assert!(matches!(
+            extract_zip(&archive, &target, &LIMITS),
+            Err(ExtensionError::SecurityViolation { .. })
+        ));
+        assert!(!target.join("ok.txt").exists());
+    }
+
+    #[test]
+    fn test_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("big.zip");
+        write_raw_zip(&archive, &[("a", &[0u8; 600]), ("b", &[0u8; 600])]);
+        assert!(extract_zip(&archive, &dir.path().join("t"), &LIMITS).is_err());
+
+        let few = ArchiveLimits {
+            max_entries: 1,
+            max_total_bytes: 10_000,
+        };
+        assert!(inspect_zip(&archive, &few).is_err());
+        assert_eq!(
+            inspect_zip(
+                &archive,
+                &ArchiveLimits {
+                    max_entries: 2,
+                    ..few
+                }
+            )
+            .unwrap(),
+            ArchiveSummary {
+                entries: 2,
+                total_bytes: 1200
+            }
+        );
+    }
+}
@@ -5,19 +5,26 @@
 //! Permission-checked wrappers around the internal filesystem API.
 //! Extensions must have `fs` permission with appropriate path targets to access the filesystem.
 //!
+//! Paths under `private://` address the extension's own data directory
+//! (see [`super::private_dir`]) and need no permission.
+//!
 //! These commands work for both WebView and iframe extensions:
 //! - WebView: extension_id is resolved from the window context
 //! - iframe: extension_id is resolved from public_key/name parameters
 //!           (verified by frontend via origin check)
 
+use super::archive::{self, ArchiveLimits, ArchiveSummary, MAX_EXTENSION_ARCHIVE_ENTRIES};
+use super::private_dir;
+use super::streams::{FileStreamInfo, StreamMode, MAX_CHUNK_SIZE};
 use crate::extension::error::ExtensionError;
 use crate::extension::limits::types::LimitError;
 use crate::extension::permissions::manager::PermissionManager;
 use crate::extension::permissions::types::{Action, FsAction};
 use crate::extension::utils::{emit_permission_prompt_if_needed, resolve_extension_id};
-use crate::filesystem::{DirEntry, FileStat};
+use crate::filesystem::{DirEntry, FileStat, FsError, VaultPath};
 use crate::AppState;
-use std::path::Path;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager, State, WebviewWindow};
 
 /// Check filesystem rate limits for an extension
@@ -40,6 +47,116 @@ impl From<LimitError> for ExtensionError {
     }
 }
 
+/// A path the extension was allowed to access
+struct AuthorizedPath {
+    /// Absolute path to hand to the filesystem layer
+    path: String,
+    /// The extension's private directory, for `private://` paths
+    private_root: Option<PathBuf>,
+}
+
+impl AuthorizedPath {
+    /// The path as a local file, for operations that can't go through a
+    /// Content URI (streams, archives).
+    fn local_path(&self, operation: &str) -> Result<&Path, ExtensionError> {
+        if VaultPath::is_content_uri(&self.path) {
+            return Err(ExtensionError::FilesystemError {
+                reason: FsError::UnsupportedForContentUri {
+                    operation: operation.to_string(),
+                }
+                .to_string(),
+            });
+        }
+        Ok(Path::new(&self.path))
+    }
+}
+
+/// Resolves `path` for a filesystem operation.
+///
+/// `private://` paths are mapped into the extension's private directory and
+/// need no permission. Everything else requires `fs` permission for the path;
+/// a missing permission triggers the permission prompt.
+async fn authorize_path(
+    app_handle: &AppHandle,
+    state: &State<'_, AppState>,
+    extension_id: &str,
+    action: FsAction,
+    path: &str,
+) -> Result<AuthorizedPath, ExtensionError> {
+    if let Some(relative) = private_dir::strip_private_root(path) {
+        let extension = state
+            .extension_manager
+            .get_extension(extension_id)
+            .ok_or_else(|| ExtensionError::ValidationError {
+                reason: format!("Extension not found: {}", extension_id),
+            })?;
+        let root = private_dir::private_dir(
+            app_handle,
+            &extension.manifest.public_key,
+            &extension.manifest.name,
+        )?;
+        let resolved = private_dir::resolve_in(&root, relative)?;
+        return Ok(AuthorizedPath {
+            path: resolved.to_string_lossy().into_owned(),
+            private_root: Some(root),
+        });
+    }
+
+    let permission_result = PermissionManager::check_filesystem_permission(
+        state,
+        extension_id,
+        Action::Filesystem(action),
+        Path::new(path),
+    )
+    .await;
+
+    if let Err(ref e) = permission_result {
+        emit_permission_prompt_if_needed(app_handle, e);
+    }
+    permission_result?;
+
+    Ok(AuthorizedPath {
+        path: path.to_string(),
+        private_root: None,
+    })
+}
+
+/// A local file the extension may read, for host modules that take file
+/// paths from extensions: `private://` paths, or paths with `fs` read
+/// permission. Content URIs are rejected.
+pub(crate) async fn authorize_local_read(
+    app_handle: &AppHandle,
+    state: &State<'_, AppState>,
+    extension_id: &str,
+    path: &str,
+) -> Result<PathBuf, ExtensionError> {
+    let authorized = authorize_path(app_handle, state, extension_id, FsAction::Read, path).await?;
+    Ok(authorized.local_path("read")?.to_path_buf())
+}
+
+/// Checks that the private directory at `root` stays within the storage quota
+/// after `added_bytes` are written and `replaced_bytes` are overwritten.
+pub(crate) fn check_private_quota(
+    state: &AppState,
+    root: &Path,
+    added_bytes: u64,
+    replaced_bytes: u64,
+) -> Result<(), ExtensionError> {
+    let limits = state.limits.defaults().filesystem.clone();
+    let usage = private_dir::disk_usage(root).saturating_sub(replaced_bytes);
+    state
+        .limits
+        .filesystem()
+        .validate_storage_quota(usage as i64, added_bytes as i64, &limits)?;
+    Ok(())
+}
+
+/// Size of the data encoded in `data` (standard base64 with padding).
+fn base64_decoded_len(data: &str) -> u64 {
+    let padding = data.bytes().rev().take_while(|&b| b == b'=').count();
+    ((data.len() / 4 * 3).saturating_sub(padding)) as u64
+}
+
 // ============================================================================
 // Read Operations (require fs:read permission)
 // ============================================================================
@@ -61,18 +178,9 @@ pub async fn extension_filesystem_read_file(
     check_filesystem_limits(&state, &extension_id)?;
 
     // Check fs permission for this path (read)
-    let permission_result = PermissionManager::check_filesystem_permission(
-        &state,
-        &extension_id,
-        Action::Filesystem(FsAction::Read),
-        Path::new(&path),
-    )
-    .await;
-
-    if let Err(ref e) = permission_result {
-        emit_permission_prompt_if_needed(&app_handle, e);
-    }
-    permission_result?;
+    let path = authorize_path(&app_handle, &state, &extension_id, FsAction::Read, &path)
+        .await?
+        .path;
 
     // Delegate to internal filesystem command
     crate::filesystem::filesystem_read_file(state, path, app_handle)
@@ -99,18 +207,9 @@ pub async fn extension_filesystem_read_dir(
     check_filesystem_limits(&state, &extension_id)?;
 
     // Check fs permission for this path (read)
-    let permission_result = PermissionManager::check_filesystem_permission(
-        &state,
-        &extension_id,
-        Action::Filesystem(FsAction::Read),
-        Path::new(&path),
-    )
-    .await;
-
-    if let Err(ref e) = permission_result {
-        emit_permission_prompt_if_needed(&app_handle, e);
-    }
-    permission_result?;
+    let path = authorize_path(&app_handle, &state, &extension_id, FsAction::Read, &path)
+        .await?
+        .path;
 
     // Delegate to internal filesystem command (no pagination for extensions)
     crate::filesystem::filesystem_read_dir(state, path, None, None, app_handle)
@@ -138,21 +237,12 @@ pub async fn extension_filesystem_exists(
     check_filesystem_limits(&state, &extension_id)?;
 
     // Check fs permission for this path (read)
-    let permission_result = PermissionManager::check_filesystem_permission(
-        &state,
-        &extension_id,
-        Action::Filesystem(FsAction::Read),
-        Path::new(&path),
-    )
-    .await;
-
-    if let Err(ref e) = permission_result {
-        emit_permission_prompt_if_needed(&app_handle, e);
-    }
-    permission_result?;
+    let path = authorize_path(&app_handle, &state, &extension_id, FsAction::Read, &path)
+        .await?
+        .path;
 
     // Delegate to internal filesystem command
-    crate::filesystem::filesystem_exists(state, path)
+    crate::filesystem::filesystem_exists(state, path, app_handle)
         .await
         .map_err(|e| ExtensionError::FilesystemError {
             reason: e.to_string(),
@@ -176,21 +266,12 @@ pub async fn extension_filesystem_stat(
     check_filesystem_limits(&state, &extension_id)?;
 
     // Check fs permission for this path (read)
-    let permission_result = PermissionManager::check_filesystem_permission(
-        &state,
-        &extension_id,
-        Action::Filesystem(FsAction::Read),
-        Path::new(&path),
-    )
-    .await;
-
-    if let Err(ref e) = permission_result {
-        emit_permission_prompt_if_needed(&app_handle, e);
-    }
-    permission_result?;
+    let path = authorize_path(&app_handle, &state, &extension_id, FsAction::Read, &path)
+        .await?
+        .path;
 
     // Delegate to internal filesystem command
-    crate::filesystem::filesystem_stat(state, path)
+    crate::filesystem::filesystem_stat(state, path, app_handle)
         .await
         .map_err(|e| ExtensionError::FilesystemError {
             reason: e.to_string(),
@@ -219,21 +300,22 @@ pub async fn extension_filesystem_write_file(
     check_filesystem_limits(&state, &extension_id)?;
 
     // Check fs permission for this path (write)
-    let permission_result = PermissionManager::check_filesystem_permission(
+    let target = authorize_path(
+        &app_handle,
         &state,
         &extension_id,
-        Action::Filesystem(FsAction::ReadWrite),
-        Path::new(&path),
+        FsAction::ReadWrite,
+        &path,
     )
-    .await;
+    .await?;
 
-    if let Err(ref e) = permission_result {
-        emit_permission_prompt_if_needed(&app_handle, e);
+    if let Some(root) = &target.private_root {
+        let replaced = private_dir::disk_usage(Path::new(&target.path));
+        check_private_quota(&state, root, base64_decoded_len(&data), replaced)?;
     }
-    permission_result?;
 
     // Delegate to internal filesystem command
-    crate::filesystem::filesystem_write_file(state, path, data)
+    crate::filesystem::filesystem_write_file(state, target.path, data, app_handle)
         .await
         .map_err(|e| ExtensionError::FilesystemError {
             reason: e.to_string(),
@@ -257,21 +339,18 @@ pub async fn extension_filesystem_mkdir(
     check_filesystem_limits(&state, &extension_id)?;
 
     // Check fs permission for this path (write)
-    let permission_result = PermissionManager::check_filesystem_permission(
+    let path = authorize_path(
+        &app_handle,
         &state,
         &extension_id,
-        Action::Filesystem(FsAction::ReadWrite),
-        Path::new(&path),
+        FsAction::ReadWrite,
+        &path,
     )
-    .await;
-
-    if let Err(ref e) = permission_result {
-        emit_permission_prompt_if_needed(&app_handle, e);
-    }
-    permission_result?;
+    .await?
+    .path;
 
     // Delegate to internal filesystem command
-    crate::filesystem::filesystem_mkdir(state, path)
+    crate::filesystem::filesystem_mkdir(state, path, app_handle)
         .await
         .map_err(|e| ExtensionError::FilesystemError {
             reason: e.to_string(),
@@ -296,21 +375,18 @@ pub async fn extension_filesystem_remove(
     check_filesystem_limits(&state, &extension_id)?;
 
     // Check fs permission for this path (write)
-    let permission_result = PermissionManager::check_filesystem_permission(
+    let path = authorize_path(
+        &app_handle,
         &state,
         &extension_id,
-        Action::Filesystem(FsAction::ReadWrite),
-        Path::new(&path),
+        FsAction::ReadWrite,
+        &path,
     )
-    .await;
-
-    if let Err(ref e) = permission_result {
-        emit_permission_prompt_if_needed(&app_handle, e);
-    }
-    permission_result?;
+    .await?
+    .path;
 
     // Delegate to internal filesystem command
-    crate::filesystem::filesystem_remove(state, path, recursive)
+    crate::filesystem::filesystem_remove(state, path, recursive, app_handle)
         .await
         .map_err(|e| ExtensionError::FilesystemError {
             reason: e.to_string(),
@@ -335,35 +411,30 @@ pub async fn extension_filesystem_rename(
     check_filesystem_limits(&state, &extension_id)?;
 
     // Check fs permission for source path (write - we're removing from here)
-    let permission_result = PermissionManager::check_filesystem_permission(
+    let from = authorize_path(
+        &app_handle,
         &state,
         &extension_id,
-        Action::Filesystem(FsAction::ReadWrite),
-        Path::new(&from),
+        FsAction::ReadWrite,
+        &from,
     )
-    .await;
-
-    if let Err(ref e) = permission_result {
-        emit_permission_prompt_if_needed(&app_handle, e);
-    }
-    permission_result?;
+    .await?;
 
     // Check fs permission for destination path (write - we're creating here)
-    let permission_result = PermissionManager::check_filesystem_permission(
-        &state,
-        &extension_id,
-        Action::Filesystem(FsAction::ReadWrite),
-        Path::new(&to),
-    )
-    .await;
-
-    if let Err(ref e) = permission_result {
-        emit_permission_prompt_if_needed(&app_handle, e);
+    let to = authorize_path(&app_handle, &state, &extension_id, FsAction::ReadWrite, &to).await?;
+
+    // Moving within the private directory doesn't change its usage
+    if let Some(root) = to
+        .private_root
+        .as_ref()
+        .filter(|&root| from.private_root.as_ref() != Some(root))
+    {
+        let moved = private_dir::disk_usage(Path::new(&from.path));
+        check_private_quota(&state, root, moved, 0)?;
     }
-    permission_result?;
 
     // Delegate to internal filesystem command
-    crate::filesystem::filesystem_rename(state, from, to)
+    crate::filesystem::filesystem_rename(state, from.path, to.path)
         .await
         .map_err(|e| ExtensionError::FilesystemError {
             reason: e.to_string(),
@@ -388,39 +459,348 @@ pub async fn extension_filesystem_copy(
     check_filesystem_limits(&state, &extension_id)?;
 
     // Check fs permission for source path (read)
-    let permission_result = PermissionManager::check_filesystem_permission(
+    let from = authorize_path(&app_handle, &state, &extension_id, FsAction::Read, &from).await?;
+
+    // Check fs permission for destination path (write)
+    let to = authorize_path(&app_handle, &state, &extension_id, FsAction::ReadWrite, &to).await?;
+
+    if let Some(root) = &to.private_root {
+        let copied = private_dir::disk_usage(Path::new(&from.path));
+        let replaced = private_dir::disk_usage(Path::new(&to.path));
+        check_private_quota(&state, root, copied, replaced)?;
+    }
+
+    // Delegate to internal filesystem command
+    crate::filesystem::filesystem_copy(state, from.path, to.path, app_handle)
+        .await
+        .map_err(|e| ExtensionError::FilesystemError {
+            reason: e.to_string(),
+        })
+}
+
+// ============================================================================
+// Stream Operations (permission checked when the stream is opened)
+// ============================================================================
+
+/// Open a stream for the filesystem or a private path and register it
+async fn open_stream(
+    app_handle: &AppHandle,
+    state: &State<'_, AppState>,
+    extension_id: &str,
+    path: &str,
+    mode: StreamMode,
+    append: bool,
+) -> Result<FileStreamInfo, ExtensionError> {
+    check_filesystem_limits(state, extension_id)?;
+
+    let action = match mode {
+        StreamMode::Read => FsAction::Read,
+        StreamMode::Write => FsAction::ReadWrite,
+    };
+    let target = authorize_path(app_handle, state, extension_id, action, path).await?;
+
+    let max_open = state.limits.defaults().filesystem.max_concurrent_operations;
+    state.file_streams.open(
+        extension_id,
+        target.local_path("streams")?,
+        mode,
+        append,
+        target.private_root.clone(),
+        max_open.max(0) as usize,
+    )
+}
+
+/// Open a file for chunked reading (requires fs:read permission for path)
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_filesystem_open_read_stream(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    path: String,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<FileStreamInfo, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    open_stream(
+        &app_handle,
         &state,
         &extension_id,
-        Action::Filesystem(FsAction::Read),
-        Path::new(&from),
+        &path,
+        StreamMode::Read,
+        false,
     )
-    .await;
+    .await
+}
 
-    if let Err(ref e) = permission_result {
-        emit_permission_prompt_if_needed(&app_handle, e);
+/// Read the next chunk of a read stream as base64
+///
+/// `length` defaults to (and is capped at) 4 MiB. An empty string marks the
+/// end of the file.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_filesystem_read_chunk(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    stream_id: String,
+    length: Option<u64>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<String, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let stream = state.file_streams.get(&extension_id, &stream_id)?;
+
+    let limits = state.limits.defaults().filesystem.clone();
+    let _slot = state
+        .limits
+        .filesystem()
+        .acquire_op_slot(&extension_id, &limits)?;
+
+    let length = length.map_or(MAX_CHUNK_SIZE, |len| {
+        len.min(MAX_CHUNK_SIZE as u64) as usize
+    });
+    let bytes = tokio::task::spawn_blocking(move || {
+        stream
+            .lock()
+            .map_err(|e| ExtensionError::MutexPoisoned {
+                reason: e.to_string(),
+            })?
+            .read_chunk(length)
+    })
+    .await
+    .map_err(|e| ExtensionError::FilesystemError {
+        reason: format!("Stream read task failed: {e}"),
+    })??;
+
+    Ok(BASE64.encode(bytes))
+}
+
+/// Open a file for chunked writing (requires fs:readWrite permission for path)
+///
+/// The file is created if missing and truncated unless `append` is set.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_filesystem_open_write_stream(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    path: String,
+    append: Option<bool>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<FileStreamInfo, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    open_stream(
+        &app_handle,
+        &state,
+        &extension_id,
+        &path,
+        StreamMode::Write,
+        append.unwrap_or(false),
+    )
+    .await
+}
+
+/// Append a base64 chunk (at most 4 MiB decoded) to a write stream
+///
+/// Returns the number of bytes written to the stream so far.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_filesystem_write_chunk(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    stream_id: String,
+    data: String,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<u64, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let stream = state.file_streams.get(&extension_id, &stream_id)?;
+
+    let bytes = BASE64
+        .decode(&data)
+        .map_err(|e| ExtensionError::ValidationError {
+            reason: format!("Invalid base64 data: {e}"),
+        })?;
+
+    let private_root = stream
+        .lock()
+        .map_err(|e| ExtensionError::MutexPoisoned {
+            reason: e.to_string(),
+        })?
+        .private_root()
+        .map(Path::to_path_buf);
+    if let Some(root) = private_root {
+        check_private_quota(&state, &root, bytes.len() as u64, 0)?;
     }
-    permission_result?;
+
+    let limits = state.limits.defaults().filesystem.clone();
+    let _slot = state
+        .limits
+        .filesystem()
+        .acquire_op_slot(&extension_id, &limits)?;
+
+    tokio::task::spawn_blocking(move || {
+        stream
+            .lock()
+            .map_err(|e| ExtensionError::MutexPoisoned {
+                reason: e.to_string(),
+            })?
+            .write_chunk(&bytes)
+    })
+    .await
+    .map_err(|e| ExtensionError::FilesystemError {
+        reason: format!("Stream write task failed: {e}"),
+    })?
+}
+
+/// Close a read or write stream; written data is flushed to disk
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_filesystem_close_stream(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    stream_id: String,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<bool, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    state.file_streams.close(&extension_id, &stream_id)
+}
+
+// ============================================================================
+// Archive Operations (require fs:read for source, fs:readWrite for destination)
+// ============================================================================
+
+/// Archive limits for extensions: a fixed entry cap, and no archive may
+/// expand beyond the storage quota
+fn extension_archive_limits(state: &AppState) -> ArchiveLimits {
+    ArchiveLimits {
+        max_entries: MAX_EXTENSION_ARCHIVE_ENTRIES,
+        max_total_bytes: state.limits.defaults().filesystem.max_storage_bytes.max(0) as u64,
+    }
+}
+
+/// Pack a file or directory into a ZIP archive
+/// (requires fs:read for source, fs:readWrite for destination)
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_filesystem_zip(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    source: String,
+    destination: String,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<ArchiveSummary, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+
+    // Check rate limits
+    check_filesystem_limits(&state, &extension_id)?;
+
+    // Check fs permission for source path (read)
+    let from = authorize_path(&app_handle, &state, &extension_id, FsAction::Read, &source).await?;
 
     // Check fs permission for destination path (write)
-    let permission_result = PermissionManager::check_filesystem_permission(
+    let to = authorize_path(
+        &app_handle,
         &state,
         &extension_id,
-        Action::Filesystem(FsAction::ReadWrite),
-        Path::new(&to),
+        FsAction::ReadWrite,
+        &destination,
     )
-    .await;
+    .await?;
 
-    if let Err(ref e) = permission_result {
-        emit_permission_prompt_if_needed(&app_handle, e);
+    from.local_path("zip")?;
+    to.local_path("zip")?;
+
+    // The uncompressed size is an upper bound for the archive
+    if let Some(root) = &to.private_root {
+        let packed = private_dir::disk_usage(Path::new(&from.path));
+        let replaced = private_dir::disk_usage(Path::new(&to.path));
+        check_private_quota(&state, root, packed, replaced)?;
     }
-    permission_result?;
 
-    // Delegate to internal filesystem command
-    crate::filesystem::filesystem_copy(state, from, to)
+    let limits = state.limits.defaults().filesystem.clone();
+    let _slot = state
+        .limits
+        .filesystem()
+        .acquire_op_slot(&extension_id, &limits)?;
+
+    let archive_limits = extension_archive_limits(&state);
+    tokio::task::spawn_blocking(move || {
+        archive::create_zip(Path::new(&from.path), Path::new(&to.path), &archive_limits)
+    })
+    .await
+    .map_err(|e| ExtensionError::FilesystemError {
+        reason: format!("Zip task failed: {e}"),
+    })?
+}
+
+/// Extract a ZIP archive into a directory
+/// (requires fs:read for source, fs:readWrite for destination)
+///
+/// Entries that would land outside the destination, symlinks, and archives
+/// above the entry or size limits are rejected before anything is written.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_filesystem_unzip(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    source: String,
+    destination: String,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<ArchiveSummary, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+
+    // Check rate limits
+    check_filesystem_limits(&state, &extension_id)?;
+
+    // Check fs permission for source path (read)
+    let from = authorize_path(&app_handle, &state, &extension_id, FsAction::Read, &source).await?;
+
+    // Check fs permission for destination path (write)
+    let to = authorize_path(
+        &app_handle,
+        &state,
+        &extension_id,
+        FsAction::ReadWrite,
+        &destination,
+    )
+    .await?;
+
+    from.local_path("unzip")?;
+    to.local_path("unzip")?;
+
+    let archive_limits = extension_archive_limits(&state);
+    if let Some(root) = &to.private_root {
+        let archive_path = PathBuf::from(&from.path);
+        let declared = tokio::task::spawn_blocking(move || {
+            archive::inspect_zip(&archive_path, &archive_limits)
+        })
         .await
         .map_err(|e| ExtensionError::FilesystemError {
-            reason: e.to_string(),
-        })
+            reason: format!("Unzip task failed: {e}"),
+        })??;
+        check_private_quota(&state, root, declared.total_bytes, 0)?;
+    }
+
+    let limits = state.limits.defaults().filesystem.clone();
+    let _slot = state
+        .limits
+        .filesystem()
+        .acquire_op_slot(&extension_id, &limits)?;
+
+    tokio::task::spawn_blocking(move || {
+        archive::extract_zip(Path::new(&from.path), Path::new(&to.path), &archive_limits)
+    })
+    .await
+    .map_err(|e| ExtensionError::FilesystemError {
+        reason: format!("Unzip task failed: {e}"),
+    })?
 }
 
 // ============================================================================
@@ -517,6 +897,8 @@ pub async fn extension_filesystem_known_paths(
     if let Ok(p) = path_resolver.video_dir() {
         paths.insert("videos".into(), p.to_string_lossy().into_owned());
     }
+    // The extension's own data directory, usable without permission
+    paths.insert("private".into(), private_dir::PRIVATE_ROOT.into());
 
     Ok(paths)
 }
@@ -525,8 +907,13 @@ pub async fn extension_filesystem_known_paths(
 // File Watcher Operations (require fs:read permission)
 // ============================================================================
 
-/// Start watching a directory for changes (requires fs:read permission)
-/// Emits "filesync:file-changed" events when files change
+/// Start watching a file or directory for changes (requires fs:read permission)
+///
+/// `rule_id` identifies the watch within the calling extension; watching the
+/// same ID again replaces it. `recursive` defaults to true. Changes are
+/// delivered to the calling extension only, as debounced
+/// "haextension:file-changed" events. Each extension can hold up to
+/// `MAX_WATCHES_PER_EXTENSION` watches; they end when the extension unloads.
 #[tauri::command(rename_all = "camelCase")]
 pub async fn extension_filesystem_watch(
     app_handle: AppHandle,
@@ -534,6 +921,7 @@ pub async fn extension_filesystem_watch(
     state: State<'_, AppState>,
     rule_id: String,
     path: String,
+    recursive: Option<bool>,
     // Optional parameters for iframe mode (verified by frontend via origin)
     public_key: Option<String>,
     name: Option<String>,
@@ -544,29 +932,26 @@ pub async fn extension_filesystem_watch(
     check_filesystem_limits(&state, &extension_id)?;
 
     // Check fs permission for this path (read - we're watching for changes)
-    let permission_result = PermissionManager::check_filesystem_permission(
-        &state,
-        &extension_id,
-        Action::Filesystem(FsAction::Read),
-        Path::new(&path),
-    )
-    .await;
-
-    if let Err(ref e) = permission_result {
-        emit_permission_prompt_if_needed(&app_handle, e);
-    }
-    permission_result?;
+    let target = authorize_path(&app_handle, &state, &extension_id, FsAction::Read, &path).await?;
 
-    // Start watching the directory (no-op on Android)
+    // Start watching (no-op on Android). Private paths need no permission,
+    // so there is nothing to re-check on delivery.
     state
         .file_watcher
-        .watch(app_handle, rule_id, path, None)
+        .watch_for_extension(
+            app_handle,
+            extension_id,
+            rule_id,
+            target.path,
+            recursive.unwrap_or(true),
+            target.private_root.is_none(),
+        )
         .map_err(|e| ExtensionError::FilesystemError { reason: e })?;
 
     Ok(())
 }
 
-/// Stop watching a directory
+/// Stop one of the calling extension's watches
 #[tauri::command(rename_all = "camelCase")]
 pub async fn extension_filesystem_unwatch(
     window: WebviewWindow,
@@ -576,19 +961,18 @@ pub async fn extension_filesystem_unwatch(
     public_key: Option<String>,
     name: Option<String>,
 ) -> Result<(), ExtensionError> {
-    // Verify extension exists
-    let _extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
 
     // Stop watching (no-op on Android)
     state
         .file_watcher
-        .unwatch(&rule_id)
+        .unwatch_for_extension(&extension_id, &rule_id)
         .map_err(|e| ExtensionError::FilesystemError { reason: e })?;
 
     Ok(())
 }
 
-/// Check if a directory is being watched
+/// Check if the calling extension has a watch with this ID
 #[tauri::command(rename_all = "camelCase")]
 pub async fn extension_filesystem_is_watching(
     window: WebviewWindow,
@@ -598,8 +982,9 @@ pub async fn extension_filesystem_is_watching(
     public_key: Option<String>,
     name: Option<String>,
 ) -> Result<bool, ExtensionError> {
-    // Verify extension exists
-    let _extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
 
-    Ok(state.file_watcher.is_watching(&rule_id))
+    Ok(state
+        .file_watcher
+        .is_watching_for_extension(&extension_id, &rule_id))
 }
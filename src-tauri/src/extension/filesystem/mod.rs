@@ -2,9 +2,14 @@
 //!
 //! Filesystem Module for extensions
 //!
-//! Provides local filesystem operations like file watching and unified file I/O.
-//! Also provides permission-checked filesystem commands for extensions.
+//! Provides local filesystem operations like file watching, chunked file
+//! streams, ZIP archives and unified file I/O.
+//! Also provides permission-checked filesystem commands for extensions and
+//! their private data directories.
 //!
 
+pub mod archive;
 pub mod commands;
+pub mod private_dir;
+pub mod streams;
 pub mod watcher;
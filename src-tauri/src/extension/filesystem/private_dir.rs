@@ -0,0 +1,155 @@
+// src-tauri/src/extension/filesystem/private_dir.rs
+//!
+//! Extension-private data directories.
+//!
+//! Every extension gets an app-managed directory at
+//! `<app_local_data>/extensions-data/<public_key>/<name>` for caches and
+//! other files it doesn't want to ask the user about. Extensions address it
+//! through the `private://` root (`private://cache/thumb.png`); paths under
+//! that root skip the permission check, are confined to the directory, and
+//! count against the extension's filesystem storage quota.
+//!
+//! The directory outlives updates and is wiped only when the extension is
+//! removed with `delete_data`.
+
+use crate::extension::core::path_utils::validate_path_in_directory;
+use crate::extension::error::ExtensionError;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+/// Path prefix extensions use to address their private directory.
+pub const PRIVATE_ROOT: &str = "private://";
+
+const EXTENSIONS_DATA_DIR: &str = "extensions-data";
+
+/// Location of an extension's private directory. The directory may not
+/// exist yet.
+pub fn private_dir(
+    app_handle: &AppHandle,
+    public_key: &str,
+    extension_name: &str,
+) -> Result<PathBuf, ExtensionError> {
+    let base =
+        crate::app_profile::data_dir(app_handle).map_err(|e| ExtensionError::FilesystemError {
+            reason: e.to_string(),
+        })?;
+    Ok(base
+        .join(EXTENSIONS_DATA_DIR)
+        .join(public_key)
+        .join(extension_name))
+}
+
+/// The part after `private://`, or `None` for any other path.
+pub fn strip_private_root(path: &str) -> Option<&str> {
+    path.strip_prefix(PRIVATE_ROOT)
+}
+
+/// Maps `relative` (without the `private://` prefix) to an absolute path
+/// inside `root`, creating `root` on first use. Traversal out of the
+/// directory is rejected.
+pub fn resolve_in(root: &Path, relative: &str) -> Result<PathBuf, ExtensionError> {
+    fs::create_dir_all(root)
+        .map_err(|e| ExtensionError::filesystem_with_path(root.display().to_string(), e))?;
+    let canonical_root = root
+        .canonicalize()
+        .map_err(|e| ExtensionError::filesystem_with_path(root.display().to_string(), e))?;
+
+    validate_path_in_directory(&canonical_root, relative, false)?.ok_or_else(|| {
+        ExtensionError::SecurityViolation {
+            reason: format!("Invalid private path: {relative}"),
+        }
+    })
+}
+
+/// Bytes used by a file, or by all files below a directory. Symlinks are
+/// not followed; a missing path uses nothing.
+pub fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| disk_usage(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Deletes an extension's private directory and the public key folder if
+/// that leaves it empty.
+pub fn remove_private_dir(
+    app_handle: &AppHandle,
+    public_key: &str,
+    extension_name: &str,
+) -> Result<(), ExtensionError> {
+    let dir = private_dir(app_handle, public_key, extension_name)?;
+    match fs::remove_dir_all(&dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            return Err(ExtensionError::filesystem_with_path(
+                dir.display().to_string(),
+                e,
+            ));
+        }
+        _ => {}
+    }
+
+    if let Some(key_dir) = dir.parent() {
+        // Fails harmlessly while other extensions of this key have data
+        let _ = fs::remove_dir(key_dir);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_private_root() {
+        assert_eq!(
+            strip_private_root("private://cache/a.png"),
+            Some("cache/a.png")
+        );
+        assert_eq!(strip_private_root("private://"), Some(""));
+        assert_eq!(strip_private_root("/home/user/private://x"), None);
+    }
+
+    #[test]
+    fn test_resolve_stays_inside_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("extensions-data").join("key").join("ext");
+
+        let resolved = resolve_in(&root, "cache/thumb.png").unwrap();
+        assert!(root.is_dir());
+        assert!(resolved.starts_with(root.canonicalize().unwrap()));
+        assert!(resolved.ends_with("cache/thumb.png"));
+
+        // The root itself
+        assert_eq!(resolve_in(&root, "").unwrap(), root.canonicalize().unwrap());
+
+        assert!(matches!(
+            resolve_in(&root, "../other/secret"),
+            Err(ExtensionError::SecurityViolation { .. })
+        ));
+    }
+
+    #[test]
+    fn test_disk_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("data");
+        assert_eq!(disk_usage(&root), 0);
+
+        fs::create_dir_all(root.join("nested")).unwrap();
+        fs::write(root.join("a.bin"), [0u8; 100]).unwrap();
+        fs::write(root.join("nested").join("b.bin"), [0u8; 23]).unwrap();
+
+        assert_eq!(disk_usage(&root), 123);
+        assert_eq!(disk_usage(&root.join("a.bin")), 100);
+    }
+}
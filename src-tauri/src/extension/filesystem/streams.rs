@@ -0,0 +1,390 @@
+// src-tauri/src/extension/filesystem/streams.rs
+//!
+//! Chunked file streams for extensions.
+//!
+//! `extension_filesystem_read_file` / `write_file` move a whole file as one
+//! base64 string, which breaks down for large media. A stream is opened once
+//! — the `fs` permission (or `private://`) is checked at that point — and is
+//! then read or written chunk by chunk through its stream ID. Streams belong
+//! to the extension that opened them and are closed automatically after
+//! `STREAM_IDLE_TTL` without use.
+
+use crate::extension::error::ExtensionError;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Maximum bytes moved by a single chunk call.
+pub const MAX_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// How long an unused stream stays open before it is pruned.
+pub const STREAM_IDLE_TTL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StreamMode {
+    Read,
+    Write,
+}
+
+/// Returned to the extension when a stream is opened.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileStreamInfo {
+    pub stream_id: String,
+    pub mode: StreamMode,
+    /// File size when the stream was opened
+    pub size: u64,
+}
+
+pub struct FileStream {
+    mode: StreamMode,
+    file: File,
+    path: PathBuf,
+    /// Private directory the file lives in, for quota checks on writes
+    private_root: Option<PathBuf>,
+    /// Bytes read or written so far
+    position: u64,
+    last_used: Instant,
+}
+
+impl FileStream {
+    pub fn private_root(&self) -> Option<&Path> {
+        self.private_root.as_deref()
+    }
+
+    /// Reads the next chunk of at most `max_len` bytes (capped at
+    /// `MAX_CHUNK_SIZE`). An empty chunk means end of file.
+    pub fn read_chunk(&mut self, max_len: usize) -> Result<Vec<u8>, ExtensionError> {
+        self.expect_mode(StreamMode::Read)?;
+        let max_len = max_len.min(MAX_CHUNK_SIZE);
+
+        let mut buf = Vec::with_capacity(max_len);
+        (&mut self.file)
+            .take(max_len as u64)
+            .read_to_end(&mut buf)
+            .map_err(|e| {
+                ExtensionError::filesystem_with_path(self.path.display().to_string(), e)
+            })?;
+
+        self.position += buf.len() as u64;
+        self.last_used = Instant::now();
+        Ok(buf)
+    }
+
+    /// Appends `bytes` to the file and returns the stream position.
+    pub fn write_chunk(&mut self, bytes: &[u8]) -> Result<u64, ExtensionError> {
+        self.expect_mode(StreamMode::Write)?;
+        if bytes.len() > MAX_CHUNK_SIZE {
+            return Err(ExtensionError::ValidationError {
+                reason: format!(
+                    "Chunk of {} bytes exceeds the maximum of {MAX_CHUNK_SIZE}",
+                    bytes.len()
+                ),
+            });
+        }
+
+        self.file.write_all(bytes).map_err(|e| {
+            ExtensionError::filesystem_with_path(self.path.display().to_string(), e)
+        })?;
+
+        self.position += bytes.len() as u64;
+        self.last_used = Instant::now();
+        Ok(self.position)
+    }
+
+    fn expect_mode(&self, mode: StreamMode) -> Result<(), ExtensionError> {
+        if self.mode != mode {
+            return Err(ExtensionError::ValidationError {
+                reason: format!("Stream was opened for {:?}, not {mode:?}", self.mode),
+            });
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), ExtensionError> {
+        if self.mode == StreamMode::Write {
+            self.file.sync_all().map_err(|e| {
+                ExtensionError::filesystem_with_path(self.path.display().to_string(), e)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+struct StreamEntry {
+    extension_id: String,
+    /// Locked for the duration of a chunk call only
+    stream: Arc<Mutex<FileStream>>,
+}
+
+/// In-memory registry of open streams (stream_id → stream + owner).
+pub struct FileStreamRegistry {
+    streams: Mutex<HashMap<String, StreamEntry>>,
+}
+
+impl FileStreamRegistry {
+    pub fn new() -> Self {
+        Self {
+            streams: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock_streams(&self) -> Result<MutexGuard<'_, HashMap<String, StreamEntry>>, ExtensionError> {
+        self.streams
+            .lock()
+            .map_err(|e| ExtensionError::MutexPoisoned {
+                reason: e.to_string(),
+            })
+    }
+
+    /// Opens `path` for `extension_id`. Write streams create the file (and
+    /// its parent directories) and truncate it unless `append` is set. At
+    /// most `max_open` streams per extension can be open at once.
+    pub fn open(
+        &self,
+        extension_id: &str,
+        path: &Path,
+        mode: StreamMode,
+        append: bool,
+        private_root: Option<PathBuf>,
+        max_open: usize,
+    ) -> Result<FileStreamInfo, ExtensionError> {
+        let mut streams = self.lock_streams()?;
+        Self::prune_locked(&mut streams);
+
+        let open_count = streams
+            .values()
+            .filter(|entry| entry.extension_id == extension_id)
+            .count();
+        if open_count >= max_open {
+            return Err(ExtensionError::LimitExceeded {
+                reason: format!("Too many open file streams ({open_count}/{max_open})"),
+            });
+        }
+
+        let path_str = path.display().to_string();
+        let file = match mode {
+            StreamMode::Read => {
+                if !path.is_file() {
+                    return Err(ExtensionError::FilesystemError {
+                        reason: format!("Not a file: {path_str}"),
+                    });
+                }
+                File::open(path)
+            }
+            StreamMode::Write => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| {
+                        ExtensionError::filesystem_with_path(parent.display().to_string(), e)
+                    })?;
+                }
+                OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .append(append)
+                    .truncate(!append)
+                    .open(path)
+            }
+        }
+        .map_err(|e| ExtensionError::filesystem_with_path(path_str.clone(), e))?;
+
+        let size = file
+            .metadata()
+            .map_err(|e| ExtensionError::filesystem_with_path(path_str, e))?
+            .len();
+
+        let stream_id = uuid::Uuid::new_v4().to_string();
+        streams.insert(
+            stream_id.clone(),
+            StreamEntry {
+                extension_id: extension_id.to_string(),
+                stream: Arc::new(Mutex::new(FileStream {
+                    mode,
+                    file,
+                    path: path.to_path_buf(),
+                    private_root,
+                    position: 0,
+                    last_used: Instant::now(),
+                })),
+            },
+        );
+
+        Ok(FileStreamInfo {
+            stream_id,
+            mode,
+            size,
+        })
+    }
+
+    /// Returns a stream after verifying that the caller owns it.
+    pub fn get(
+        &self,
+        extension_id: &str,
+        stream_id: &str,
+    ) -> Result<Arc<Mutex<FileStream>>, ExtensionError> {
+        let mut streams = self.lock_streams()?;
+        Self::prune_locked(&mut streams);
+
+        streams
+            .get(stream_id)
+            .filter(|entry| entry.extension_id == extension_id)
+            .map(|entry| Arc::clone(&entry.stream))
+            .ok_or_else(|| ExtensionError::NotFound {
+                public_key: String::new(),
+                name: format!("file stream {stream_id}"),
+            })
+    }
+
+    /// Closes a stream; write streams are flushed to disk. Returns `false`
+    /// if the caller has no stream with that ID.
+    pub fn close(&self, extension_id: &str, stream_id: &str) -> Result<bool, ExtensionError> {
+        let entry = {
+            let mut streams = self.lock_streams()?;
+            match streams.get(stream_id) {
+                Some(entry) if entry.extension_id == extension_id => streams.remove(stream_id),
+                _ => return Ok(false),
+            }
+        };
+
+        // A chunk call still holding the stream keeps the file open until
+        // it returns; the stream is unreachable either way.
+        if let Some(stream) = entry.and_then(|entry| Arc::into_inner(entry.stream)) {
+            stream
+                .into_inner()
+                .map_err(|e| ExtensionError::MutexPoisoned {
+                    reason: e.to_string(),
+                })?
+                .finish()?;
+        }
+        Ok(true)
+    }
+
+    /// Closes all streams of an extension (e.g. on removal).
+    pub fn close_all_for_extension(&self, extension_id: &str) -> Result<(), ExtensionError> {
+        let mut streams = self.lock_streams()?;
+        streams.retain(|_, entry| entry.extension_id != extension_id);
+        Ok(())
+    }
+
+    fn prune_locked(streams: &mut HashMap<String, StreamEntry>) {
+        // A stream that is locked right now is in use, not idle
+        streams.retain(|_, entry| match entry.stream.try_lock() {
+            Ok(stream) => stream.last_used.elapsed() < STREAM_IDLE_TTL,
+            Err(_) => true,
+        });
+    }
+}
+
+impl Default for FileStreamRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_then_read_in_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("media").join("clip.bin");
+        let registry = FileStreamRegistry::new();
+
+        let writer = registry
+            .open("ext-a", &path, StreamMode::Write, false, None, 4)
+            .unwrap();
+        {
+            let stream = registry.get("ext-a", &writer.stream_id).unwrap();
+            let mut stream = stream.lock().unwrap();
+            assert_eq!(stream.write_chunk(b"hello ").unwrap(), 6);
+            assert_eq!(stream.write_chunk(b"world").unwrap(), 11);
+            assert!(stream.read_chunk(4).is_err());
+        }
+        assert!(registry.close("ext-a", &writer.stream_id).unwrap());
+
+        let reader = registry
+            .open("ext-a", &path, StreamMode::Read, false, None, 4)
+            .unwrap();
+        assert_eq!(reader.size, 11);
+        let stream = registry.get("ext-a", &reader.stream_id).unwrap();
+        let mut stream = stream.lock().unwrap();
+        assert_eq!(stream.read_chunk(6).unwrap(), b"hello ");
+        assert_eq!(stream.read_chunk(100).unwrap(), b"world");
+        assert!(stream.read_chunk(100).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_append_keeps_existing_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        fs::write(&path, b"one,").unwrap();
+        let registry = FileStreamRegistry::new();
+
+        let info = registry
+            .open("ext-a", &path, StreamMode::Write, true, None, 4)
+            .unwrap();
+        assert_eq!(info.size, 4);
+        registry
+            .get("ext-a", &info.stream_id)
+            .unwrap()
+            .lock()
+            .unwrap()
+            .write_chunk(b"two")
+            .unwrap();
+        registry.close("ext-a", &info.stream_id).unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"one,two");
+    }
+
+    #[test]
+    fn test_streams_are_owned_and_limited() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        fs::write(&path, b"data").unwrap();
+        let registry = FileStreamRegistry::new();
+
+        let info = registry
+            .open("ext-a", &path, StreamMode::Read, false, None, 1)
+            .unwrap();
+        assert!(matches!(
+            registry.open("ext-a", &path, StreamMode::Read, false, None, 1),
+            Err(ExtensionError::LimitExceeded { .. })
+        ));
+        // Other extensions have their own budget
+        registry
+            .open("ext-b", &path, StreamMode::Read, false, None, 1)
+            .unwrap();
+
+        assert!(registry.get("ext-b", &info.stream_id).is_err());
+        assert!(!registry.close("ext-b", &info.stream_id).unwrap());
+        assert!(registry.get("ext-a", &info.stream_id).is_ok());
+
+        registry.close_all_for_extension("ext-a").unwrap();
+        assert!(registry.get("ext-a", &info.stream_id).is_err());
+    }
+
+    #[test]
+    fn test_read_stream_requires_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = FileStreamRegistry::new();
+        assert!(registry
+            .open("ext-a", dir.path(), StreamMode::Read, false, None, 4)
+            .is_err());
+        assert!(registry
+            .open(
+                "ext-a",
+                &dir.path().join("missing"),
+                StreamMode::Read,
+                false,
+                None,
+                4
+            )
+            .is_err());
+    }
+}
@@ -5,6 +5,11 @@
 //! Monitors sync rule directories for file changes and emits events to the frontend.
 //! Only available on desktop platforms (not Android).
 //!
+//! Extensions get their own watches (`extension_filesystem_watch`), kept apart
+//! from the sync rule watchers: they are namespaced per extension, limited to
+//! `MAX_WATCHES_PER_EXTENSION`, deliver `EXTENSION_FILE_CHANGE_EVENT` only to
+//! the extension that subscribed, and are dropped when the extension unloads.
+//!
 
 #[cfg(desktop)]
 use notify_debouncer_mini::{new_debouncer, DebouncedEventKind, Debouncer};
@@ -13,12 +18,14 @@ use notify::RecursiveMode;
 #[cfg(desktop)]
 use std::collections::HashMap;
 #[cfg(desktop)]
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 #[cfg(desktop)]
 use std::sync::{Arc, Mutex};
 #[cfg(desktop)]
 use std::time::Duration;
 #[cfg(desktop)]
+use std::fs;
+#[cfg(desktop)]
 use tauri::{AppHandle, Emitter, Manager};
 
 use serde::{Deserialize, Serialize};
@@ -63,10 +70,53 @@ pub enum FileChangeType {
     Any,
 }
 
+/// A single change reported to an extension watch
+#[cfg_attr(any(target_os = "android", target_os = "ios"), allow(dead_code))]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct FileChange {
+    /// Path relative to the watched path (empty for the watched path itself)
+    pub path: String,
+    pub change_type: FileChangeType,
+}
+
+/// Event delivered to an extension for one of its watches (desktop only)
+#[cfg_attr(any(target_os = "android", target_os = "ios"), allow(dead_code))]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ExtensionFileChangeEvent {
+    /// Extension that owns the watch; the main window routes iframe
+    /// extensions by it
+    pub extension_id: String,
+    /// ID the extension passed to `extension_filesystem_watch`
+    pub watch_id: String,
+    /// Changes collected during one debounce interval
+    pub changes: Vec<FileChange>,
+}
+
 /// Event name for file change events
 #[cfg(desktop)]
 pub const FILE_CHANGE_EVENT: &str = "filesync:file-changed";
 
+/// Event name for extension watch events.
+/// Matches HAEXTENSION_EVENTS.FILE_CHANGED in vault-sdk.
+#[cfg(desktop)]
+pub const EXTENSION_FILE_CHANGE_EVENT: &str = "haextension:file-changed";
+
+/// Maximum number of concurrent watches per extension
+pub const MAX_WATCHES_PER_EXTENSION: usize = 16;
+
+/// Debounce interval for extension watches
+#[cfg(desktop)]
+const EXTENSION_WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Files created this recently when an event is delivered count as created
+/// rather than modified
+#[cfg(desktop)]
+const CREATED_WINDOW: Duration = Duration::from_secs(2);
+
 #[cfg(desktop)]
 type WatcherHandle = Debouncer<notify::RecommendedWatcher>;
 
@@ -77,6 +127,8 @@ pub struct FileWatcherManager {
     watchers: Arc<Mutex<HashMap<String, WatcherHandle>>>,
     /// Map of path -> rule_id for reverse lookup
     path_to_rule: Arc<Mutex<HashMap<PathBuf, String>>>,
+    /// Map of extension_id -> (watch_id -> watcher handle)
+    extension_watches: Arc<Mutex<HashMap<String, HashMap<String, WatcherHandle>>>>,
 }
 
 #[cfg(desktop)]
@@ -85,6 +137,7 @@ impl FileWatcherManager {
         Self {
             watchers: Arc::new(Mutex::new(HashMap::new())),
             path_to_rule: Arc::new(Mutex::new(HashMap::new())),
+            extension_watches: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -289,6 +342,200 @@ impl FileWatcherManager {
             .map(|w| w.contains_key(rule_id))
             .unwrap_or(false)
     }
+
+    /// Number of active sync rule watches and extension watches
+    pub fn watch_counts(&self) -> Result<(usize, usize), String> {
+        let rules = self.watchers.lock().map_err(|e| e.to_string())?.len();
+        let extensions = self
+            .extension_watches
+            .lock()
+            .map_err(|e| e.to_string())?
+            .values()
+            .map(HashMap::len)
+            .sum();
+        Ok((rules, extensions))
+    }
+
+    /// Start an extension watch on a file or directory.
+    ///
+    /// Watching an existing `watch_id` again replaces that watch. If
+    /// `check_permission` is set, fs read permission is re-checked (silently)
+    /// before each delivery, so revoking it stops the events.
+    pub fn watch_for_extension(
+        &self,
+        app_handle: AppHandle,
+        extension_id: String,
+        watch_id: String,
+        path: String,
+        recursive: bool,
+        check_permission: bool,
+    ) -> Result<(), String> {
+        let path_buf = PathBuf::from(&path);
+        if !path_buf.exists() {
+            return Err(format!("Path does not exist: {}", path));
+        }
+
+        {
+            let watches = self.extension_watches.lock().map_err(|e| e.to_string())?;
+            let own = watches.get(&extension_id);
+            let count = own.map_or(0, |w| w.len());
+            let replaces = own.is_some_and(|w| w.contains_key(&watch_id));
+            if !replaces && count >= MAX_WATCHES_PER_EXTENSION {
+                return Err(format!(
+                    "Too many file watches ({}/{})",
+                    count, MAX_WATCHES_PER_EXTENSION
+                ));
+            }
+        }
+
+        let base_path = path_buf.clone();
+        let extension_for_event = extension_id.clone();
+        let watch_for_event = watch_id.clone();
+
+        let mut debouncer = new_debouncer(
+            EXTENSION_WATCH_DEBOUNCE,
+            move |result: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
+                let events = match result {
+                    Ok(events) if !events.is_empty() => events,
+                    Ok(_) => return,
+                    Err(e) => {
+                        eprintln!(
+                            "[FileWatcher] Watch error for extension {} ({}): {:?}",
+                            extension_for_event, watch_for_event, e
+                        );
+                        return;
+                    }
+                };
+
+                let changes: Vec<FileChange> = events
+                    .iter()
+                    .map(|event| FileChange {
+                        path: event
+                            .path
+                            .strip_prefix(&base_path)
+                            .map(|p| p.to_string_lossy().to_string())
+                            .unwrap_or_default(),
+                        change_type: classify_change(&event.path),
+                    })
+                    .collect();
+
+                let app_handle = app_handle.clone();
+                let base_path = base_path.clone();
+                let payload = ExtensionFileChangeEvent {
+                    extension_id: extension_for_event.clone(),
+                    watch_id: watch_for_event.clone(),
+                    changes,
+                };
+                tauri::async_runtime::spawn(async move {
+                    use crate::extension::permissions::manager::PermissionManager;
+
+                    let state = app_handle.state::<crate::AppState>();
+                    if check_permission
+                        && !PermissionManager::is_fs_read_allowed_silently(
+                            &state,
+                            &payload.extension_id,
+                            &base_path,
+                        )
+                        .await
+                    {
+                        return;
+                    }
+
+                    if let Err(e) = state.extension_webview_manager.emit_to_extension_or_main(
+                        &app_handle,
+                        &payload.extension_id,
+                        EXTENSION_FILE_CHANGE_EVENT,
+                        &payload,
+                    ) {
+                        eprintln!("[FileWatcher] Failed to emit extension watch event: {}", e);
+                    }
+                });
+            },
+        )
+        .map_err(|e| format!("Failed to create watcher: {}", e))?;
+
+        let mode = if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        debouncer
+            .watcher()
+            .watch(&path_buf, mode)
+            .map_err(|e| format!("Failed to watch path: {}", e))?;
+
+        {
+            let mut watches = self.extension_watches.lock().map_err(|e| e.to_string())?;
+            watches
+                .entry(extension_id.clone())
+                .or_default()
+                .insert(watch_id.clone(), debouncer);
+        }
+
+        println!(
+            "[FileWatcher] Extension {} started watch {} at path: {}",
+            extension_id, watch_id, path
+        );
+        Ok(())
+    }
+
+    /// Stop one watch of an extension. Returns `false` if it had none with
+    /// that ID.
+    pub fn unwatch_for_extension(&self, extension_id: &str, watch_id: &str) -> Result<bool, String> {
+        let mut watches = self.extension_watches.lock().map_err(|e| e.to_string())?;
+        let Some(own) = watches.get_mut(extension_id) else {
+            return Ok(false);
+        };
+        let removed = own.remove(watch_id).is_some();
+        if own.is_empty() {
+            watches.remove(extension_id);
+        }
+        Ok(removed)
+    }
+
+    /// Check if an extension has a watch with this ID
+    pub fn is_watching_for_extension(&self, extension_id: &str, watch_id: &str) -> bool {
+        self.extension_watches
+            .lock()
+            .map(|w| w.get(extension_id).is_some_and(|own| own.contains_key(watch_id)))
+            .unwrap_or(false)
+    }
+
+    /// Stop all watches of an extension (unload, disable, removal)
+    pub fn unwatch_extension(&self, extension_id: &str) -> Result<(), String> {
+        let removed = {
+            let mut watches = self.extension_watches.lock().map_err(|e| e.to_string())?;
+            watches.remove(extension_id).map_or(0, |own| own.len())
+        };
+        if removed > 0 {
+            println!(
+                "[FileWatcher] Stopped {} watch(es) of extension {}",
+                removed, extension_id
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Classify a debounced event by looking at the path after the fact:
+/// gone means removed, freshly created means created, anything else modified.
+#[cfg(desktop)]
+fn classify_change(path: &Path) -> FileChangeType {
+    match fs::metadata(path) {
+        Err(_) => FileChangeType::Removed,
+        Ok(metadata) => {
+            let fresh = metadata
+                .created()
+                .ok()
+                .and_then(|created| created.elapsed().ok())
+                .is_some_and(|age| age < CREATED_WINDOW);
+            if fresh {
+                FileChangeType::Created
+            } else {
+                FileChangeType::Modified
+            }
+        }
+    }
 }
 
 #[cfg(desktop)]
@@ -361,6 +608,34 @@ impl FileWatcherManager {
     pub fn is_watching(&self, _rule_id: &str) -> bool {
         false
     }
+
+    pub fn watch_counts(&self) -> Result<(usize, usize), String> {
+        Ok((0, 0))
+    }
+
+    pub fn watch_for_extension(
+        &self,
+        _app_handle: tauri::AppHandle,
+        _extension_id: String,
+        _watch_id: String,
+        _path: String,
+        _recursive: bool,
+        _check_permission: bool,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn unwatch_for_extension(&self, _extension_id: &str, _watch_id: &str) -> Result<bool, String> {
+        Ok(false)
+    }
+
+    pub fn is_watching_for_extension(&self, _extension_id: &str, _watch_id: &str) -> bool {
+        false
+    }
+
+    pub fn unwatch_extension(&self, _extension_id: &str) -> Result<(), String> {
+        Ok(())
+    }
 }
 
 #[cfg(target_os = "android")]
@@ -369,3 +644,20 @@ impl Default for FileWatcherManager {
         Self::new()
     }
 }
+
+#[cfg(all(test, desktop))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("new.txt");
+        assert!(matches!(classify_change(&path), FileChangeType::Removed));
+
+        // Created or Modified, depending on whether the filesystem records
+        // creation times
+        fs::write(&path, b"x").unwrap();
+        assert!(!matches!(classify_change(&path), FileChangeType::Removed));
+    }
+}
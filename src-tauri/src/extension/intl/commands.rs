@@ -0,0 +1,131 @@
+// src-tauri/src/extension/intl/commands.rs
+//!
+//! `extension_intl_*` commands. `locale` overrides the ApplicationContext
+//! locale for a single call.
+
+use super::{
+    format_currency, format_date, format_number, locale_data, parse_date, parse_number,
+    DateFormatOptions, LocaleData, NumberFormatOptions,
+};
+use crate::extension::core::locales::locale_fallback_chain;
+use crate::extension::error::ExtensionError;
+use crate::AppState;
+use tauri::State;
+use time::format_description::well_known::Rfc3339;
+use time::{Date, OffsetDateTime, UtcOffset};
+
+/// Locale data for `locale`, falling back to the context locale and `en`
+fn resolve_locale(
+    state: &State<'_, AppState>,
+    locale: Option<&str>,
+) -> Result<&'static LocaleData, ExtensionError> {
+    let context_locale = state
+        .context
+        .lock()
+        .map_err(|e| ExtensionError::MutexPoisoned {
+            reason: e.to_string(),
+        })?
+        .locale
+        .clone();
+    Ok(locale_data(&locale_fallback_chain(
+        locale,
+        &context_locale,
+        None,
+    )))
+}
+
+/// Accepts RFC 3339 timestamps and plain `yyyy-MM-dd` dates (midnight UTC)
+fn parse_timestamp(timestamp: &str) -> Result<OffsetDateTime, ExtensionError> {
+    if let Ok(at) = OffsetDateTime::parse(timestamp, &Rfc3339) {
+        return Ok(at);
+    }
+    let date = parse_iso_date(timestamp).ok_or_else(|| ExtensionError::ValidationError {
+        reason: format!("Invalid timestamp '{timestamp}', expected RFC 3339"),
+    })?;
+    Ok(date.midnight().assume_utc())
+}
+
+fn parse_iso_date(text: &str) -> Option<Date> {
+    let mut parts = text.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month: u8 = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    Date::from_calendar_date(year, month.try_into().ok()?, day).ok()
+}
+
+#[tauri::command]
+pub fn extension_intl_format_number(
+    state: State<'_, AppState>,
+    value: f64,
+    options: Option<NumberFormatOptions>,
+    locale: Option<String>,
+) -> Result<String, ExtensionError> {
+    let data = resolve_locale(&state, locale.as_deref())?;
+    format_number(data, value, &options.unwrap_or_default())
+}
+
+/// Formats an amount of an ISO 4217 currency (`EUR`, `USD`, ...)
+#[tauri::command]
+pub fn extension_intl_format_currency(
+    state: State<'_, AppState>,
+    value: f64,
+    currency: String,
+    locale: Option<String>,
+) -> Result<String, ExtensionError> {
+    let data = resolve_locale(&state, locale.as_deref())?;
+    format_currency(data, value, &currency)
+}
+
+/// Formats an RFC 3339 timestamp or a `yyyy-MM-dd` date
+#[tauri::command]
+pub fn extension_intl_format_date(
+    state: State<'_, AppState>,
+    timestamp: String,
+    options: Option<DateFormatOptions>,
+    locale: Option<String>,
+) -> Result<String, ExtensionError> {
+    let data = resolve_locale(&state, locale.as_deref())?;
+    let options = options.unwrap_or_default();
+    let mut at = parse_timestamp(&timestamp)?;
+    if let Some(minutes) = options.utc_offset_minutes {
+        let offset = UtcOffset::from_whole_seconds(minutes.saturating_mul(60)).map_err(|e| {
+            ExtensionError::ValidationError {
+                reason: format!("Invalid UTC offset {minutes}: {e}"),
+            }
+        })?;
+        at = at.to_offset(offset);
+    }
+    Ok(format_date(
+        data,
+        &at,
+        options.date_style,
+        options.time_style,
+    ))
+}
+
+#[tauri::command]
+pub fn extension_intl_parse_number(
+    state: State<'_, AppState>,
+    text: String,
+    locale: Option<String>,
+) -> Result<f64, ExtensionError> {
+    let data = resolve_locale(&state, locale.as_deref())?;
+    parse_number(data, &text)
+}
+
+/// Parses a localized date and returns it as `yyyy-MM-dd`
+#[tauri::command]
+pub fn extension_intl_parse_date(
+    state: State<'_, AppState>,
+    text: String,
+    locale: Option<String>,
+) -> Result<String, ExtensionError> {
+    let data = resolve_locale(&state, locale.as_deref())?;
+    let date = parse_date(data, &text)?;
+    Ok(format!(
+        "{:04}-{:02}-{:02}",
+        date.year(),
+        u8::from(date.month()),
+        date.day()
+    ))
+}
@@ -0,0 +1,852 @@
+// src-tauri/src/extension/intl/mod.rs
+//!
+//! Locale-aware number, currency and date formatting for extensions
+//!
+//! Embedded webviews ship whatever `Intl` data the system WebKit has; older
+//! versions format `de-CH` numbers like `de` or lack month names entirely,
+//! so the same extension renders differently per platform. The
+//! `extension_intl_*` commands format on the host instead, using the
+//! ApplicationContext locale unless the caller asks for another one.
+//!
+//! The locale data below follows CLDR for the supported locales (the app
+//! languages plus the most common neighbours); other tags fall back through
+//! their parents to `en`. Dates are handled with `time`; the caller passes
+//! the UTC offset to format in, since the webview knows the user's zone.
+
+pub mod commands;
+
+#[cfg(test)]
+mod tests;
+
+use crate::extension::error::ExtensionError;
+use serde::{Deserialize, Serialize};
+use time::{Date, Month, OffsetDateTime};
+use ts_rs::TS;
+
+/// Upper bound for requested fraction digits (same as `Intl.NumberFormat`)
+pub const MAX_FRACTION_DIGITS: u32 = 20;
+
+/// Default maximum fraction digits of decimal numbers
+const DEFAULT_MAX_FRACTION_DIGITS: u32 = 3;
+
+const NBSP: char = '\u{a0}';
+const NARROW_NBSP: char = '\u{202f}';
+
+/// Formatting conventions of one locale
+#[derive(Debug)]
+pub struct LocaleData {
+    /// Normalized tag (see `normalize_locale`)
+    pub tag: &'static str,
+    pub decimal: char,
+    pub group: char,
+    /// Group only numbers with at least `3 + min_grouping` integer digits
+    pub min_grouping: usize,
+    /// Currency symbol before the amount
+    pub currency_before: bool,
+    /// Non-breaking space between symbol and amount
+    pub currency_space: bool,
+    /// Non-breaking space before `%`
+    pub percent_space: bool,
+    pub date_short: &'static str,
+    pub date_medium: &'static str,
+    pub date_long: &'static str,
+    pub date_full: &'static str,
+    pub time_short: &'static str,
+    pub time_medium: &'static str,
+    /// Between date and time when both are formatted
+    pub date_time_separator: &'static str,
+    pub months: [&'static str; 12],
+    pub months_short: [&'static str; 12],
+    /// Monday first
+    pub weekdays: [&'static str; 7],
+    pub am_pm: [&'static str; 2],
+}
+
+const MONTHS_EN: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const MONTHS_SHORT_EN: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+const WEEKDAYS_EN: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+const MONTHS_DE: [&str; 12] = [
+    "Januar",
+    "Februar",
+    "März",
+    "April",
+    "Mai",
+    "Juni",
+    "Juli",
+    "August",
+    "September",
+    "Oktober",
+    "November",
+    "Dezember",
+];
+const MONTHS_SHORT_DE: [&str; 12] = [
+    "Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.", "Dez.",
+];
+const WEEKDAYS_DE: [&str; 7] = [
+    "Montag",
+    "Dienstag",
+    "Mittwoch",
+    "Donnerstag",
+    "Freitag",
+    "Samstag",
+    "Sonntag",
+];
+const AM_PM: [&str; 2] = ["AM", "PM"];
+
+static LOCALES: &[LocaleData] = &[
+    LocaleData {
+        tag: "en",
+        decimal: '.',
+        group: ',',
+        min_grouping: 1,
+        currency_before: true,
+        currency_space: false,
+        percent_space: false,
+        date_short: "M/d/yy",
+        date_medium: "MMM d, yyyy",
+        date_long: "MMMM d, yyyy",
+        date_full: "EEEE, MMMM d, yyyy",
+        time_short: "h:mm a",
+        time_medium: "h:mm:ss a",
+        date_time_separator: ", ",
+        months: MONTHS_EN,
+        months_short: MONTHS_SHORT_EN,
+        weekdays: WEEKDAYS_EN,
+        am_pm: AM_PM,
+    },
+    LocaleData {
+        tag: "en-gb",
+        decimal: '.',
+        group: ',',
+        min_grouping: 1,
+        currency_before: true,
+        currency_space: false,
+        percent_space: false,
+        date_short: "dd/MM/yyyy",
+        date_medium: "d MMM yyyy",
+        date_long: "d MMMM yyyy",
+        date_full: "EEEE d MMMM yyyy",
+        time_short: "HH:mm",
+        time_medium: "HH:mm:ss",
+        date_time_separator: ", ",
+        months: MONTHS_EN,
+        months_short: MONTHS_SHORT_EN,
+        weekdays: WEEKDAYS_EN,
+        am_pm: AM_PM,
+    },
+    LocaleData {
+        tag: "de",
+        decimal: ',',
+        group: '.',
+        min_grouping: 1,
+        currency_before: false,
+        currency_space: true,
+        percent_space: true,
+        date_short: "dd.MM.yy",
+        date_medium: "dd.MM.yyyy",
+        date_long: "d. MMMM yyyy",
+        date_full: "EEEE, d. MMMM yyyy",
+        time_short: "HH:mm",
+        time_medium: "HH:mm:ss",
+        date_time_separator: ", ",
+        months: MONTHS_DE,
+        months_short: MONTHS_SHORT_DE,
+        weekdays: WEEKDAYS_DE,
+        am_pm: AM_PM,
+    },
+    LocaleData {
+        tag: "de-ch",
+        decimal: '.',
+        group: '\u{2019}',
+        min_grouping: 1,
+        currency_before: true,
+        currency_space: true,
+        percent_space: false,
+        date_short: "dd.MM.yy",
+        date_medium: "dd.MM.yyyy",
+        date_long: "d. MMMM yyyy",
+        date_full: "EEEE, d. MMMM yyyy",
+        time_short: "HH:mm",
+        time_medium: "HH:mm:ss",
+        date_time_separator: ", ",
+        months: MONTHS_DE,
+        months_short: MONTHS_SHORT_DE,
+        weekdays: WEEKDAYS_DE,
+        am_pm: AM_PM,
+    },
+    LocaleData {
+        tag: "fr",
+        decimal: ',',
+        group: NARROW_NBSP,
+        min_grouping: 1,
+        currency_before: false,
+        currency_space: true,
+        percent_space: true,
+        date_short: "dd/MM/yyyy",
+        date_medium: "d MMM yyyy",
+        date_long: "d MMMM yyyy",
+        date_full: "EEEE d MMMM yyyy",
+        time_short: "HH:mm",
+        time_medium: "HH:mm:ss",
+        date_time_separator: " ",
+        months: [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        months_short: [
+            "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.",
+            "nov.", "déc.",
+        ],
+        weekdays: [
+            "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+        ],
+        am_pm: AM_PM,
+    },
+    LocaleData {
+        tag: "es",
+        decimal: ',',
+        group: '.',
+        min_grouping: 2,
+        currency_before: false,
+        currency_space: true,
+        percent_space: true,
+        date_short: "d/M/yy",
+        date_medium: "d MMM yyyy",
+        date_long: "d 'de' MMMM 'de' yyyy",
+        date_full: "EEEE, d 'de' MMMM 'de' yyyy",
+        time_short: "H:mm",
+        time_medium: "H:mm:ss",
+        date_time_separator: ", ",
+        months: [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ],
+        months_short: [
+            "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic",
+        ],
+        weekdays: [
+            "lunes",
+            "martes",
+            "miércoles",
+            "jueves",
+            "viernes",
+            "sábado",
+            "domingo",
+        ],
+        am_pm: ["a. m.", "p. m."],
+    },
+    LocaleData {
+        tag: "it",
+        decimal: ',',
+        group: '.',
+        min_grouping: 1,
+        currency_before: false,
+        currency_space: true,
+        percent_space: false,
+        date_short: "dd/MM/yy",
+        date_medium: "d MMM yyyy",
+        date_long: "d MMMM yyyy",
+        date_full: "EEEE d MMMM yyyy",
+        time_short: "HH:mm",
+        time_medium: "HH:mm:ss",
+        date_time_separator: ", ",
+        months: [
+            "gennaio",
+            "febbraio",
+            "marzo",
+            "aprile",
+            "maggio",
+            "giugno",
+            "luglio",
+            "agosto",
+            "settembre",
+            "ottobre",
+            "novembre",
+            "dicembre",
+        ],
+        months_short: [
+            "gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic",
+        ],
+        weekdays: [
+            "lunedì",
+            "martedì",
+            "mercoledì",
+            "giovedì",
+            "venerdì",
+            "sabato",
+            "domenica",
+        ],
+        am_pm: AM_PM,
+    },
+    LocaleData {
+        tag: "nl",
+        decimal: ',',
+        group: '.',
+        min_grouping: 1,
+        currency_before: true,
+        currency_space: true,
+        percent_space: false,
+        date_short: "dd-MM-yyyy",
+        date_medium: "d MMM yyyy",
+        date_long: "d MMMM yyyy",
+        date_full: "EEEE d MMMM yyyy",
+        time_short: "HH:mm",
+        time_medium: "HH:mm:ss",
+        date_time_separator: ", ",
+        months: [
+            "januari",
+            "februari",
+            "maart",
+            "april",
+            "mei",
+            "juni",
+            "juli",
+            "augustus",
+            "september",
+            "oktober",
+            "november",
+            "december",
+        ],
+        months_short: [
+            "jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov", "dec",
+        ],
+        weekdays: [
+            "maandag",
+            "dinsdag",
+            "woensdag",
+            "donderdag",
+            "vrijdag",
+            "zaterdag",
+            "zondag",
+        ],
+        am_pm: AM_PM,
+    },
+];
+
+/// `Intl.NumberFormat` style
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum NumberStyle {
+    #[default]
+    Decimal,
+    Percent,
+}
+
+/// Options of `extension_intl_format_number`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct NumberFormatOptions {
+    #[serde(default)]
+    pub style: NumberStyle,
+    /// Defaults to 0
+    #[serde(default)]
+    pub minimum_fraction_digits: Option<u32>,
+    /// Defaults to 3, or 0 for percentages
+    #[serde(default)]
+    pub maximum_fraction_digits: Option<u32>,
+    /// Group separators, defaults to `true`
+    #[serde(default)]
+    pub use_grouping: Option<bool>,
+}
+
+/// Length of a formatted date, like `Intl.DateTimeFormat`'s `dateStyle`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum DateStyle {
+    Short,
+    Medium,
+    Long,
+    Full,
+}
+
+/// Length of a formatted time (`short` without, `medium` with seconds)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeStyle {
+    Short,
+    Medium,
+}
+
+/// Options of `extension_intl_format_date`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct DateFormatOptions {
+    /// Defaults to `medium` when neither style is given
+    #[serde(default)]
+    pub date_style: Option<DateStyle>,
+    #[serde(default)]
+    pub time_style: Option<TimeStyle>,
+    /// Offset to format in (`-new Date().getTimezoneOffset()`); defaults to
+    /// the offset of the timestamp
+    #[serde(default)]
+    pub utc_offset_minutes: Option<i32>,
+}
+
+fn invalid(reason: String) -> ExtensionError {
+    ExtensionError::ValidationError { reason }
+}
+
+/// Locale data for the first tag of `chain` that has any
+pub fn locale_data(chain: &[String]) -> &'static LocaleData {
+    chain
+        .iter()
+        .find_map(|tag| LOCALES.iter().find(|data| data.tag == tag))
+        .unwrap_or(&LOCALES[0])
+}
+
+// ============================================================================
+// Numbers
+// ============================================================================
+
+/// Joins sign, grouped integer digits and fraction digits
+fn compose_number(
+    data: &LocaleData,
+    negative: bool,
+    integer: &str,
+    fraction: &str,
+    grouping: bool,
+) -> String {
+    let mut out = String::new();
+    if negative {
+        out.push('-');
+    }
+    let len = integer.len();
+    let group = grouping && len >= 3 + data.min_grouping;
+    for (i, digit) in integer.chars().enumerate() {
+        if group && i > 0 && (len - i) % 3 == 0 {
+            out.push(data.group);
+        }
+        out.push(digit);
+    }
+    if !fraction.is_empty() {
+        out.push(data.decimal);
+        out.push_str(fraction);
+    }
+    out
+}
+
+/// Rounds `value` to `max` fraction digits and drops trailing zeros down to
+/// `min`. Returns `(negative, integer digits, fraction digits)`.
+fn round_digits(value: f64, min: u32, max: u32) -> (bool, String, String) {
+    let rounded = format!("{:.*}", max as usize, value.abs());
+    let (integer, fraction) = rounded.split_once('.').unwrap_or((&rounded, ""));
+    let mut fraction = fraction.to_string();
+    while fraction.len() > min as usize && fraction.ends_with('0') {
+        fraction.pop();
+    }
+    let is_zero = integer.chars().chain(fraction.chars()).all(|c| c == '0');
+    (
+        value.is_sign_negative() && !is_zero,
+        integer.to_string(),
+        fraction,
+    )
+}
+
+pub fn format_number(
+    data: &LocaleData,
+    value: f64,
+    options: &NumberFormatOptions,
+) -> Result<String, ExtensionError> {
+    if !value.is_finite() {
+        return Err(invalid(format!("Cannot format {value}")));
+    }
+    let (value, default_max) = match options.style {
+        NumberStyle::Decimal => (value, DEFAULT_MAX_FRACTION_DIGITS),
+        NumberStyle::Percent => (value * 100.0, 0),
+    };
+    let min = options.minimum_fraction_digits.unwrap_or(0);
+    let max = options
+        .maximum_fraction_digits
+        .unwrap_or(default_max)
+        .max(min);
+    if max > MAX_FRACTION_DIGITS {
+        return Err(invalid(format!(
+            "At most {MAX_FRACTION_DIGITS} fraction digits are supported"
+        )));
+    }
+
+    let (negative, integer, fraction) = round_digits(value, min, max);
+    let mut out = compose_number(
+        data,
+        negative,
+        &integer,
+        &fraction,
+        options.use_grouping.unwrap_or(true),
+    );
+    if options.style == NumberStyle::Percent {
+        if data.percent_space {
+            out.push(NBSP);
+        }
+        out.push('%');
+    }
+    Ok(out)
+}
+
+/// Symbol and fraction digits of an ISO 4217 code
+fn currency_details(code: &str) -> (&str, u32) {
+    match code {
+        "EUR" => ("€", 2),
+        "USD" => ("$", 2),
+        "GBP" => ("£", 2),
+        "JPY" => ("¥", 0),
+        "KRW" => ("₩", 0),
+        "INR" => ("₹", 2),
+        _ => (code, 2),
+    }
+}
+
+pub fn format_currency(
+    data: &LocaleData,
+    value: f64,
+    currency: &str,
+) -> Result<String, ExtensionError> {
+    if !value.is_finite() {
+        return Err(invalid(format!("Cannot format {value}")));
+    }
+    let code = currency.trim().to_ascii_uppercase();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(invalid(format!("Invalid currency code '{currency}'")));
+    }
+    let (symbol, digits) = currency_details(&code);
+
+    let (negative, integer, fraction) = round_digits(value, digits, digits);
+    let amount = compose_number(data, false, &integer, &fraction, true);
+    // Letter codes never touch the digits
+    let space = data.currency_space || symbol.chars().all(|c| c.is_ascii_alphabetic());
+
+    let mut out = String::new();
+    if negative {
+        out.push('-');
+    }
+    if data.currency_before {
+        out.push_str(symbol);
+        if space {
+            out.push(NBSP);
+        }
+        out.push_str(&amount);
+    } else {
+        out.push_str(&amount);
+        if space {
+            out.push(NBSP);
+        }
+        out.push_str(symbol);
+    }
+    Ok(out)
+}
+
+fn is_space(c: char) -> bool {
+    c.is_whitespace() || c == NBSP || c == NARROW_NBSP
+}
+
+/// Parses a number written in the locale's notation (`1.234,5` in `de`).
+/// A trailing `%` divides by 100.
+pub fn parse_number(data: &LocaleData, text: &str) -> Result<f64, ExtensionError> {
+    let fail = || invalid(format!("'{text}' is not a number in locale {}", data.tag));
+
+    let mut rest = text.trim_matches(is_space);
+    let mut divisor = 1.0;
+    if let Some(stripped) = rest.strip_suffix('%') {
+        rest = stripped.trim_end_matches(is_space);
+        divisor = 100.0;
+    }
+    let (negative, unsigned) = match rest.chars().next() {
+        Some(sign @ ('-' | '\u{2212}' | '+')) => (sign != '+', &rest[sign.len_utf8()..]),
+        _ => (false, rest),
+    };
+    let value = parse_unsigned(data, unsigned).ok_or_else(fail)?;
+    let signed = if negative { -value } else { value };
+    Ok(signed / divisor)
+}
+
+/// Group separators accepted when parsing: the locale's own, any space for
+/// space-grouped locales and the ASCII apostrophe for `’`
+fn is_group(data: &LocaleData, c: char) -> bool {
+    c == data.group
+        || (is_space(data.group) && is_space(c))
+        || (data.group == '\u{2019}' && c == '\'')
+}
+
+fn parse_unsigned(data: &LocaleData, text: &str) -> Option<f64> {
+    let (integer, fraction) = match text.split_once(data.decimal) {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (text, None),
+    };
+    let digits: String = integer.chars().filter(|&c| !is_group(data, c)).collect();
+    let fraction = fraction.unwrap_or("");
+    let valid = !(digits.is_empty() && fraction.is_empty())
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && fraction.chars().all(|c| c.is_ascii_digit());
+    if !valid {
+        return None;
+    }
+    format!(
+        "{}.{}",
+        if digits.is_empty() { "0" } else { &digits },
+        fraction
+    )
+    .trim_end_matches('.')
+    .parse()
+    .ok()
+}
+
+// ============================================================================
+// Dates
+// ============================================================================
+
+/// Piece of a date pattern: a field (letter and repeat count) or literal text
+#[derive(Debug, PartialEq)]
+enum Token {
+    Field(char, usize),
+    Literal(String),
+}
+
+/// Splits a CLDR-style pattern; text in single quotes is literal
+fn tokenize(pattern: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\'' {
+            let literal: String = chars.by_ref().take_while(|&c| c != '\'').collect();
+            tokens.push(Token::Literal(literal));
+        } else if c.is_ascii_alphabetic() {
+            let mut count = 1;
+            while chars.peek() == Some(&c) {
+                chars.next();
+                count += 1;
+            }
+            tokens.push(Token::Field(c, count));
+        } else {
+            match tokens.last_mut() {
+                Some(Token::Literal(text)) => text.push(c),
+                _ => tokens.push(Token::Literal(c.to_string())),
+            }
+        }
+    }
+    tokens
+}
+
+fn render(pattern: &str, data: &LocaleData, at: &OffsetDateTime) -> String {
+    let mut out = String::new();
+    for token in tokenize(pattern) {
+        match token {
+            Token::Literal(text) => out.push_str(&text),
+            Token::Field('d', 1) => out.push_str(&at.day().to_string()),
+            Token::Field('d', _) => out.push_str(&format!("{:02}", at.day())),
+            Token::Field('M', 1) => out.push_str(&u8::from(at.month()).to_string()),
+            Token::Field('M', 2) => out.push_str(&format!("{:02}", u8::from(at.month()))),
+            Token::Field('M', 3) => {
+                out.push_str(data.months_short[usize::from(u8::from(at.month())) - 1])
+            }
+            Token::Field('M', _) => {
+                out.push_str(data.months[usize::from(u8::from(at.month())) - 1])
+            }
+            Token::Field('y', 2) => out.push_str(&format!("{:02}", at.year().rem_euclid(100))),
+            Token::Field('y', _) => out.push_str(&at.year().to_string()),
+            Token::Field('E', _) => {
+                out.push_str(data.weekdays[usize::from(at.weekday().number_days_from_monday())])
+            }
+            Token::Field('H', 1) => out.push_str(&at.hour().to_string()),
+            Token::Field('H', _) => out.push_str(&format!("{:02}", at.hour())),
+            Token::Field('h', _) => {
+                let hour = match at.hour() % 12 {
+                    0 => 12,
+                    hour => hour,
+                };
+                out.push_str(&hour.to_string());
+            }
+            Token::Field('m', _) => out.push_str(&format!("{:02}", at.minute())),
+            Token::Field('s', _) => out.push_str(&format!("{:02}", at.second())),
+            Token::Field('a', _) => out.push_str(data.am_pm[usize::from(at.hour() >= 12)]),
+            Token::Field(letter, count) => {
+                for _ in 0..count {
+                    out.push(letter);
+                }
+            }
+        }
+    }
+    out
+}
+
+pub fn format_date(
+    data: &LocaleData,
+    at: &OffsetDateTime,
+    date_style: Option<DateStyle>,
+    time_style: Option<TimeStyle>,
+) -> String {
+    let date_style = match (date_style, time_style) {
+        (None, None) => Some(DateStyle::Medium),
+        (date_style, _) => date_style,
+    };
+    let date = date_style.map(|style| {
+        let pattern = match style {
+            DateStyle::Short => data.date_short,
+            DateStyle::Medium => data.date_medium,
+            DateStyle::Long => data.date_long,
+            DateStyle::Full => data.date_full,
+        };
+        render(pattern, data, at)
+    });
+    let time = time_style.map(|style| {
+        let pattern = match style {
+            TimeStyle::Short => data.time_short,
+            TimeStyle::Medium => data.time_medium,
+        };
+        render(pattern, data, at)
+    });
+    match (date, time) {
+        (Some(date), Some(time)) => format!("{date}{}{time}", data.date_time_separator),
+        (Some(text), None) | (None, Some(text)) => text,
+        (None, None) => String::new(),
+    }
+}
+
+/// Reads up to `max` ASCII digits, at least `min`
+fn take_digits(input: &[char], pos: &mut usize, min: usize, max: usize) -> Option<u32> {
+    let digits: String = input[*pos..]
+        .iter()
+        .take(max)
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    if digits.len() < min {
+        return None;
+    }
+    *pos += digits.len();
+    digits.parse().ok()
+}
+
+/// Case-insensitive match of `word` at `pos`; returns the position after it
+fn match_word(input: &[char], pos: usize, word: &str) -> Option<usize> {
+    let mut end = pos;
+    for expected in word.chars() {
+        let actual = *input.get(end)?;
+        if !actual.to_lowercase().eq(expected.to_lowercase()) {
+            return None;
+        }
+        end += 1;
+    }
+    Some(end)
+}
+
+/// Longest name of `names` at `pos`, a trailing dot is optional either way.
+/// Returns the index of the name and the position after it.
+fn match_name(input: &[char], pos: usize, names: &[&str]) -> Option<(usize, usize)> {
+    names
+        .iter()
+        .enumerate()
+        .filter_map(|(index, name)| {
+            let bare = name.trim_end_matches('.');
+            let mut end = match_word(input, pos, bare)?;
+            if input.get(end) == Some(&'.') {
+                end += 1;
+            }
+            Some((index, end))
+        })
+        .max_by_key(|(_, end)| *end)
+}
+
+/// Matches `text` against a date pattern, returning the calendar date
+fn match_pattern(pattern: &str, data: &LocaleData, text: &str) -> Option<Date> {
+    let input: Vec<char> = text.chars().collect();
+    let mut pos = 0;
+    let (mut year, mut month, mut day) = (None, None, None);
+
+    for token in tokenize(pattern) {
+        match token {
+            Token::Literal(literal) => {
+                for expected in literal.chars() {
+                    if is_space(expected) {
+                        while input.get(pos).is_some_and(|&c| is_space(c)) {
+                            pos += 1;
+                        }
+                    } else {
+                        pos = match_word(&input, pos, &expected.to_string())?;
+                    }
+                }
+            }
+            Token::Field('d', _) => day = Some(take_digits(&input, &mut pos, 1, 2)?),
+            Token::Field('M', count) if count <= 2 => {
+                month = Some(take_digits(&input, &mut pos, 1, 2)?)
+            }
+            Token::Field('M', _) => {
+                let (index, end) = match_name(&input, pos, &data.months)
+                    .into_iter()
+                    .chain(match_name(&input, pos, &data.months_short))
+                    .max_by_key(|(_, end)| *end)?;
+                month = Some(index as u32 + 1);
+                pos = end;
+            }
+            Token::Field('y', 2) => year = Some(2000 + take_digits(&input, &mut pos, 2, 2)? as i32),
+            Token::Field('y', _) => year = Some(take_digits(&input, &mut pos, 4, 4)? as i32),
+            Token::Field('E', _) => pos = match_name(&input, pos, &data.weekdays)?.1,
+            Token::Field(..) => return None,
+        }
+    }
+    if pos != input.len() {
+        return None;
+    }
+    let month = Month::try_from(u8::try_from(month?).ok()?).ok()?;
+    Date::from_calendar_date(year?, month, u8::try_from(day?).ok()?).ok()
+}
+
+/// Parses a date written in one of the locale's date styles, or as ISO
+/// `yyyy-MM-dd`.
+pub fn parse_date(data: &LocaleData, text: &str) -> Result<Date, ExtensionError> {
+    let text = text.trim_matches(is_space);
+    std::iter::once("yyyy-MM-dd")
+        .chain([
+            data.date_short,
+            data.date_medium,
+            data.date_long,
+            data.date_full,
+        ])
+        .find_map(|pattern| match_pattern(pattern, data, text))
+        .ok_or_else(|| invalid(format!("'{text}' is not a date in locale {}", data.tag)))
+}
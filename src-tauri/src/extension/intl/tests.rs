@@ -0,0 +1,147 @@
+//! Tests for locale-aware formatting and parsing
+
+use super::*;
+use time::Time;
+
+fn locale(tag: &str) -> &'static LocaleData {
+    locale_data(&[tag.to_string()])
+}
+
+fn number(tag: &str, value: f64) -> String {
+    format_number(locale(tag), value, &NumberFormatOptions::default()).unwrap()
+}
+
+#[test]
+fn test_locale_data_falls_back() {
+    assert_eq!(locale_data(&["de-at".into(), "de".into()]).tag, "de");
+    assert_eq!(locale_data(&["xx".into()]).tag, "en");
+    assert_eq!(locale("de-ch").tag, "de-ch");
+}
+
+#[test]
+fn test_format_number_grouping_and_decimals() {
+    assert_eq!(number("en", 1234567.891), "1,234,567.891");
+    assert_eq!(number("de", 1234567.891), "1.234.567,891");
+    assert_eq!(number("de-ch", 1234567.5), "1\u{2019}234\u{2019}567.5");
+    assert_eq!(number("fr", 1234.5), "1\u{202f}234,5");
+    // Spanish groups only from five integer digits
+    assert_eq!(number("es", 1234.0), "1234");
+    assert_eq!(number("es", 12345.0), "12.345");
+    assert_eq!(number("en", -0.0001), "0");
+    assert_eq!(number("en", -2.5), "-2.5");
+
+    let options = NumberFormatOptions {
+        minimum_fraction_digits: Some(2),
+        maximum_fraction_digits: Some(2),
+        use_grouping: Some(false),
+        ..Default::default()
+    };
+    assert_eq!(
+        format_number(locale("de"), 1234.0, &options).unwrap(),
+        "1234,00"
+    );
+    assert!(format_number(locale("en"), f64::NAN, &options).is_err());
+}
+
+#[test]
+fn test_format_percent() {
+    let options = NumberFormatOptions {
+        style: NumberStyle::Percent,
+        ..Default::default()
+    };
+    assert_eq!(format_number(locale("en"), 0.256, &options).unwrap(), "26%");
+    assert_eq!(
+        format_number(locale("de"), 0.256, &options).unwrap(),
+        "26\u{a0}%"
+    );
+}
+
+#[test]
+fn test_format_currency() {
+    assert_eq!(
+        format_currency(locale("en"), 1234.5, "usd").unwrap(),
+        "$1,234.50"
+    );
+    assert_eq!(
+        format_currency(locale("de"), -1234.5, "EUR").unwrap(),
+        "-1.234,50\u{a0}€"
+    );
+    assert_eq!(
+        format_currency(locale("en"), 1234.0, "CHF").unwrap(),
+        "CHF\u{a0}1,234.00"
+    );
+    assert_eq!(
+        format_currency(locale("de-ch"), 1234.0, "CHF").unwrap(),
+        "CHF\u{a0}1\u{2019}234.00"
+    );
+    assert_eq!(
+        format_currency(locale("en"), 1234.4, "JPY").unwrap(),
+        "¥1,234"
+    );
+    assert!(format_currency(locale("en"), 1.0, "EURO").is_err());
+}
+
+#[test]
+fn test_format_date_styles() {
+    let at = Date::from_calendar_date(2024, Month::January, 5)
+        .unwrap()
+        .with_time(Time::from_hms(15, 4, 9).unwrap())
+        .assume_utc();
+    let en = locale("en");
+    assert_eq!(format_date(en, &at, Some(DateStyle::Short), None), "1/5/24");
+    assert_eq!(format_date(en, &at, None, None), "Jan 5, 2024");
+    assert_eq!(
+        format_date(en, &at, Some(DateStyle::Full), Some(TimeStyle::Short)),
+        "Friday, January 5, 2024, 3:04 PM"
+    );
+
+    let de = locale("de");
+    assert_eq!(
+        format_date(de, &at, Some(DateStyle::Short), None),
+        "05.01.24"
+    );
+    assert_eq!(
+        format_date(de, &at, Some(DateStyle::Long), Some(TimeStyle::Medium)),
+        "5. Januar 2024, 15:04:09"
+    );
+    assert_eq!(
+        format_date(locale("es"), &at, Some(DateStyle::Long), None),
+        "5 de enero de 2024"
+    );
+    assert_eq!(format_date(de, &at, None, Some(TimeStyle::Short)), "15:04");
+}
+
+#[test]
+fn test_parse_number() {
+    assert_eq!(parse_number(locale("de"), "1.234,5").unwrap(), 1234.5);
+    assert_eq!(parse_number(locale("en"), "-1,234.5").unwrap(), -1234.5);
+    assert_eq!(parse_number(locale("fr"), "1 234,5").unwrap(), 1234.5);
+    assert_eq!(parse_number(locale("de-ch"), "1'234.5").unwrap(), 1234.5);
+    assert_eq!(parse_number(locale("de"), "26 %").unwrap(), 0.26);
+    assert_eq!(parse_number(locale("en"), ".5").unwrap(), 0.5);
+
+    assert!(parse_number(locale("de"), "1,2,3").is_err());
+    assert!(parse_number(locale("en"), "12abc").is_err());
+    assert!(parse_number(locale("en"), "").is_err());
+}
+
+#[test]
+fn test_parse_date() {
+    let expected = Date::from_calendar_date(2024, Month::January, 5).unwrap();
+    assert_eq!(parse_date(locale("de"), "05.01.2024").unwrap(), expected);
+    assert_eq!(
+        parse_date(locale("de"), "5. januar 2024").unwrap(),
+        expected
+    );
+    assert_eq!(parse_date(locale("de"), "05.01.24").unwrap(), expected);
+    assert_eq!(parse_date(locale("en"), "1/5/24").unwrap(), expected);
+    assert_eq!(parse_date(locale("en"), "Jan. 5, 2024").unwrap(), expected);
+    assert_eq!(
+        parse_date(locale("es"), "5 de enero de 2024").unwrap(),
+        expected
+    );
+    assert_eq!(parse_date(locale("fr"), "2024-01-05").unwrap(), expected);
+
+    assert!(parse_date(locale("de"), "31.02.2024").is_err());
+    assert!(parse_date(locale("en"), "tomorrow").is_err());
+}
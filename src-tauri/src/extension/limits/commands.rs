@@ -30,6 +30,12 @@ pub struct UpdateExtensionLimitsRequest {
     /// Maximum query SQL size in bytes (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_query_size_bytes: Option<i64>,
+    /// Maximum memory of the extension's webview processes in bytes (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_memory_bytes: Option<i64>,
+    /// Maximum sustained CPU usage in percent of one core (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_cpu_percent: Option<i64>,
 }
 
 /// Response with extension limits
@@ -42,6 +48,8 @@ pub struct ExtensionLimitsResponse {
     pub max_result_rows: i64,
     pub max_concurrent_queries: i64,
     pub max_query_size_bytes: i64,
+    pub max_memory_bytes: i64,
+    pub max_cpu_percent: i64,
     /// Whether custom limits are configured (false = using defaults)
     pub is_custom: bool,
 }
@@ -54,6 +62,8 @@ impl ExtensionLimitsResponse {
             max_result_rows: limits.database.max_result_rows,
             max_concurrent_queries: limits.database.max_concurrent_queries,
             max_query_size_bytes: limits.database.max_query_size_bytes,
+            max_memory_bytes: limits.process.max_memory_bytes,
+            max_cpu_percent: limits.process.max_cpu_percent,
             is_custom,
         }
     }
@@ -127,6 +137,12 @@ pub fn update_extension_limits(
     let new_max_query_size_bytes = request
         .max_query_size_bytes
         .unwrap_or(current_limits.database.max_query_size_bytes);
+    let new_max_memory_bytes = request
+        .max_memory_bytes
+        .unwrap_or(current_limits.process.max_memory_bytes);
+    let new_max_cpu_percent = request
+        .max_cpu_percent
+        .unwrap_or(current_limits.process.max_cpu_percent);
 
     // Validate limits
     if new_query_timeout < 1000 {
@@ -149,6 +165,16 @@ pub fn update_extension_limits(
             reason: "Max query size must be at least 1024 bytes (1KB)".to_string(),
         });
     }
+    if new_max_memory_bytes < 64 * 1024 * 1024 {
+        return Err(ExtensionError::ValidationError {
+            reason: "Max memory must be at least 64MB".to_string(),
+        });
+    }
+    if new_max_cpu_percent < 10 {
+        return Err(ExtensionError::ValidationError {
+            reason: "Max CPU usage must be at least 10%".to_string(),
+        });
+    }
 
     // Insert or update in database using CRDT executor
     with_connection(&state.db, |conn| {
@@ -176,7 +202,9 @@ pub fn update_extension_limits(
                 query_timeout_ms = ?, \
                 max_result_rows = ?, \
                 max_concurrent_queries = ?, \
-                max_query_size_bytes = ? \
+                max_query_size_bytes = ?, \
+                max_memory_bytes = ?, \
+                max_cpu_percent = ? \
                 WHERE extension_id = ?";
 
             let params: Vec<serde_json::Value> = vec![
@@ -184,6 +212,8 @@ pub fn update_extension_limits(
                 serde_json::json!(new_max_result_rows),
                 serde_json::json!(new_max_concurrent_queries),
                 serde_json::json!(new_max_query_size_bytes),
+                serde_json::json!(new_max_memory_bytes),
+                serde_json::json!(new_max_cpu_percent),
                 serde_json::json!(request.extension_id),
             ];
 
@@ -192,8 +222,9 @@ pub fn update_extension_limits(
             // Insert new record
             let id = uuid::Uuid::new_v4().to_string();
             let sql = "INSERT INTO haex_extension_limits \
-                (id, extension_id, query_timeout_ms, max_result_rows, max_concurrent_queries, max_query_size_bytes) \
-                VALUES (?, ?, ?, ?, ?, ?)";
+                (id, extension_id, query_timeout_ms, max_result_rows, max_concurrent_queries, max_query_size_bytes, \
+                max_memory_bytes, max_cpu_percent) \
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)";
 
             let params: Vec<serde_json::Value> = vec![
                 serde_json::json!(id),
@@ -202,6 +233,8 @@ pub fn update_extension_limits(
                 serde_json::json!(new_max_result_rows),
                 serde_json::json!(new_max_concurrent_queries),
                 serde_json::json!(new_max_query_size_bytes),
+                serde_json::json!(new_max_memory_bytes),
+                serde_json::json!(new_max_cpu_percent),
             ];
 
             SqlExecutor::execute_internal(&tx, &hlc_service, sql, &params)?;
@@ -52,7 +52,8 @@ impl LimitsService {
     ) -> Result<ExtensionLimits, DatabaseError> {
         let result: Result<HaexExtensionLimits, _> = conn.query_row(
             "SELECT id, extension_id, query_timeout_ms, max_result_rows, \
-             max_concurrent_queries, max_query_size_bytes, created_at, updated_at \
+             max_concurrent_queries, max_query_size_bytes, max_memory_bytes, max_cpu_percent, \
+             created_at, updated_at \
              FROM haex_extension_limits \
              WHERE extension_id = ?",
             [extension_id],
@@ -106,7 +107,9 @@ mod tests {
 
     #[test]
     fn test_limits_service_with_custom_defaults() {
-        use crate::extension::limits::types::{DatabaseLimits, FilesystemLimits, WebLimits};
+        use crate::extension::limits::types::{
+            DatabaseLimits, FilesystemLimits, ProcessLimits, WebLimits,
+        };
 
         let custom_defaults = DefaultLimits {
             database: DatabaseLimits {
@@ -117,6 +120,7 @@ mod tests {
             },
             filesystem: FilesystemLimits::default(),
             web: WebLimits::default(),
+            process: ProcessLimits::default(),
         };
 
         let service = LimitsService::with_defaults(custom_defaults);
@@ -74,12 +74,32 @@ impl Default for WebLimits {
     }
 }
 
+/// Process limits for the webview processes of an extension window (desktop).
+/// Exceeding them produces a warning, see `webview::monitor`.
+#[derive(Debug, Clone)]
+pub struct ProcessLimits {
+    /// Maximum resident memory in bytes (default: 1GB)
+    pub max_memory_bytes: i64,
+    /// Maximum sustained CPU usage in percent of one core (default: 90)
+    pub max_cpu_percent: i64,
+}
+
+impl Default for ProcessLimits {
+    fn default() -> Self {
+        Self {
+            max_memory_bytes: 1024 * 1024 * 1024, // 1GB
+            max_cpu_percent: 90,
+        }
+    }
+}
+
 /// Default limits for all resource types
 #[derive(Debug, Clone, Default)]
 pub struct DefaultLimits {
     pub database: DatabaseLimits,
     pub filesystem: FilesystemLimits,
     pub web: WebLimits,
+    pub process: ProcessLimits,
 }
 
 /// Resolved limits for a specific extension (all resource types)
@@ -88,10 +108,12 @@ pub struct ExtensionLimits {
     pub database: DatabaseLimits,
     pub filesystem: FilesystemLimits,
     pub web: WebLimits,
+    pub process: ProcessLimits,
 }
 
 impl From<HaexExtensionLimits> for ExtensionLimits {
     fn from(db: HaexExtensionLimits) -> Self {
+        let process_defaults = ProcessLimits::default();
         Self {
             database: DatabaseLimits {
                 query_timeout_ms: db.query_timeout_ms,
@@ -102,6 +124,14 @@ impl From<HaexExtensionLimits> for ExtensionLimits {
             // Use defaults for other resource types until we add columns for them
             filesystem: FilesystemLimits::default(),
             web: WebLimits::default(),
+            process: ProcessLimits {
+                max_memory_bytes: db
+                    .max_memory_bytes
+                    .unwrap_or(process_defaults.max_memory_bytes),
+                max_cpu_percent: db
+                    .max_cpu_percent
+                    .unwrap_or(process_defaults.max_cpu_percent),
+            },
         }
     }
 }
@@ -112,6 +142,7 @@ impl From<&DefaultLimits> for ExtensionLimits {
             database: defaults.database.clone(),
             filesystem: defaults.filesystem.clone(),
             web: defaults.web.clone(),
+            process: defaults.process.clone(),
         }
     }
 }
@@ -274,6 +305,7 @@ mod tests {
         assert_eq!(limits.database.query_timeout_ms, 30_000);
         assert_eq!(limits.filesystem.max_storage_bytes, 100 * 1024 * 1024);
         assert_eq!(limits.web.max_requests_per_minute, 60);
+        assert_eq!(limits.process.max_cpu_percent, 90);
     }
 
     #[test]
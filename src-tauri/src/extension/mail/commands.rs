@@ -15,6 +15,7 @@ use crate::mail::error::MailError;
 use crate::mail::types::{
     FetchRange, ImapConfig, MailboxInfo, Message, MessageEnvelope, OutgoingMessage, SmtpConfig,
 };
+use crate::passwords::commands::fill_credentials_as_extension;
 use crate::AppState;
 
 /// Convert mail-module errors to ExtensionError. Auth failures map to
@@ -67,6 +68,50 @@ async fn check_send_permission(
     result
 }
 
+/// Fetch permission check plus credential resolution for an IMAP config.
+async fn authorize_imap(
+    app_handle: &AppHandle,
+    state: &State<'_, AppState>,
+    extension_id: &str,
+    mut imap: ImapConfig,
+) -> Result<ImapConfig, ExtensionError> {
+    check_fetch_permission(app_handle, state, extension_id, &imap.host).await?;
+    if let Some(item_id) = imap.credential_item_id.take() {
+        fill_credentials_as_extension(
+            app_handle,
+            state,
+            extension_id,
+            &item_id,
+            &mut imap.username,
+            &mut imap.password,
+        )
+        .await?;
+    }
+    Ok(imap)
+}
+
+/// Send permission check plus credential resolution for an SMTP config.
+async fn authorize_smtp(
+    app_handle: &AppHandle,
+    state: &State<'_, AppState>,
+    extension_id: &str,
+    mut smtp: SmtpConfig,
+) -> Result<SmtpConfig, ExtensionError> {
+    check_send_permission(app_handle, state, extension_id, &smtp.host).await?;
+    if let Some(item_id) = smtp.credential_item_id.take() {
+        fill_credentials_as_extension(
+            app_handle,
+            state,
+            extension_id,
+            &item_id,
+            &mut smtp.username,
+            &mut smtp.password,
+        )
+        .await?;
+    }
+    Ok(smtp)
+}
+
 // ---------------------------------------------------------------------------
 // IMAP operations (require MailAction::Fetch on imap.host)
 // ---------------------------------------------------------------------------
@@ -84,7 +129,7 @@ pub async fn extension_mail_list_mailboxes(
     name: Option<String>,
 ) -> Result<Vec<MailboxInfo>, ExtensionError> {
     let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
-    check_fetch_permission(&app_handle, &state, &extension_id, &imap.host).await?;
+    let imap = authorize_imap(&app_handle, &state, &extension_id, imap).await?;
 
     crate::mail::imap::list_mailboxes(
         &imap,
@@ -108,7 +153,7 @@ pub async fn extension_mail_fetch_envelopes(
     name: Option<String>,
 ) -> Result<Vec<MessageEnvelope>, ExtensionError> {
     let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
-    check_fetch_permission(&app_handle, &state, &extension_id, &imap.host).await?;
+    let imap = authorize_imap(&app_handle, &state, &extension_id, imap).await?;
 
     crate::mail::imap::fetch_envelopes(&imap, &mailbox, &range)
         .await
@@ -127,7 +172,7 @@ pub async fn extension_mail_fetch_message(
     name: Option<String>,
 ) -> Result<Message, ExtensionError> {
     let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
-    check_fetch_permission(&app_handle, &state, &extension_id, &imap.host).await?;
+    let imap = authorize_imap(&app_handle, &state, &extension_id, imap).await?;
 
     crate::mail::imap::fetch_message(&imap, &mailbox, uid)
         .await
@@ -148,7 +193,7 @@ pub async fn extension_mail_set_flags(
     name: Option<String>,
 ) -> Result<(), ExtensionError> {
     let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
-    check_fetch_permission(&app_handle, &state, &extension_id, &imap.host).await?;
+    let imap = authorize_imap(&app_handle, &state, &extension_id, imap).await?;
 
     crate::mail::imap::set_flags(&imap, &mailbox, &uids, &flags, add)
         .await
@@ -168,7 +213,7 @@ pub async fn extension_mail_move_messages(
     name: Option<String>,
 ) -> Result<(), ExtensionError> {
     let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
-    check_fetch_permission(&app_handle, &state, &extension_id, &imap.host).await?;
+    let imap = authorize_imap(&app_handle, &state, &extension_id, imap).await?;
 
     crate::mail::imap::move_messages(&imap, &source_mailbox, &destination_mailbox, &uids)
         .await
@@ -193,7 +238,7 @@ pub async fn extension_mail_append_message(
     use base64::Engine as _;
 
     let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
-    check_fetch_permission(&app_handle, &state, &extension_id, &imap.host).await?;
+    let imap = authorize_imap(&app_handle, &state, &extension_id, imap).await?;
 
     let bytes = STANDARD
         .decode(&rfc822_base64)
@@ -221,7 +266,7 @@ pub async fn extension_mail_send_message(
     name: Option<String>,
 ) -> Result<String, ExtensionError> {
     let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
-    check_send_permission(&app_handle, &state, &extension_id, &smtp.host).await?;
+    let smtp = authorize_smtp(&app_handle, &state, &extension_id, smtp).await?;
 
     crate::mail::smtp::send_message(&smtp, &message)
         .await
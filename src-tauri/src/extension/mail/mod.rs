@@ -4,8 +4,10 @@
 //! `Send`) and extension-id resolution (window vs. iframe parameters)
 //! around the general-purpose IMAP/SMTP API in `crate::mail`.
 //!
-//! Account credentials are NOT stored here — extensions load them from
-//! the core passwords vault (filtered by tag scope) and pass them in
-//! per call. The wrapper has no notion of "accounts" beyond this.
+//! Account credentials are NOT stored here. Extensions either pass them in
+//! per call, or set `credential_item_id` on the IMAP/SMTP config to let the
+//! host read username + password/OAuth2 token from the core passwords vault
+//! (filtered by the extension's tag scope) without the secret ever reaching
+//! extension code. The wrapper has no notion of "accounts" beyond this.
 
 pub mod commands;
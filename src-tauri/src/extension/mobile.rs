@@ -0,0 +1,56 @@
+// src-tauri/src/extension/mobile.rs
+//!
+//! Mobile variants of the webview-only extension commands.
+//!
+//! Android and iOS have no extension webview windows: every extension runs
+//! in an iframe inside the main window, served by the extension protocol
+//! (see [`extension_asset_url`](crate::extension::core::protocol::extension_asset_url)).
+//! The commands below keep the names of their desktop counterparts in
+//! `extension::webview`, so extensions and the frontend call the same API on
+//! every platform. Iframe calls identify themselves with `public_key`/`name`,
+//! as they do on desktop.
+
+use crate::extension::core::protocol::ExtensionInfo;
+use crate::extension::error::ExtensionError;
+use crate::extension::utils::resolve_extension_id;
+use crate::AppState;
+use tauri::{State, WebviewWindow};
+
+/// Get extension info for the calling iframe extension.
+#[tauri::command]
+pub fn extension_get_info(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    // Parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<ExtensionInfo, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let extension = state
+        .extension_manager
+        .get_extension(&extension_id)
+        .ok_or_else(|| ExtensionError::ValidationError {
+            reason: format!("Extension not found: {}", extension_id),
+        })?;
+
+    Ok(ExtensionInfo::from_extension(&extension))
+}
+
+/// Extensions can't get their own window on mobile; the frontend opens them
+/// in an iframe at `extension_get_host_url` instead.
+#[tauri::command]
+pub fn open_extension_webview_window(extension_id: String) -> Result<String, ExtensionError> {
+    Err(ExtensionError::ValidationError {
+        reason: format!(
+            "Extension windows are not available on mobile, open {} in an iframe",
+            extension_id
+        ),
+    })
+}
+
+/// Nothing to close on mobile. Kept so vault close/reload runs the same
+/// cleanup on every platform.
+#[tauri::command]
+pub fn close_all_extension_webview_windows() -> Result<(), ExtensionError> {
+    Ok(())
+}
@@ -6,10 +6,12 @@ use crate::{
     extension::{
         core::{
             find_icon,
+            migration_batches::MigrationBatch,
             path_utils::validate_path_in_directory,
             types::{Extension, ExtensionSource},
-            EditablePermissions, ExtensionInfoResponse, ExtensionManifest, ExtensionPreview,
-            PermissionEntry,
+            versions::InstalledExtensionVersion,
+            EditablePermissions, ExtensionFilesInstallResult, ExtensionInfoResponse,
+            ExtensionManifest, ExtensionPreview, PermissionEntry,
         },
         database::executor::SqlExecutor,
         error::ExtensionError,
@@ -24,15 +26,25 @@ use crate::{
 use std::path::PathBuf;
 use std::time::SystemTime;
 use tauri::{AppHandle, State};
+pub mod capabilities;
 pub mod core;
 pub mod crypto;
 pub mod database;
+pub mod dav;
+pub mod dev_logs;
 pub mod error;
+pub mod event_bus;
+pub mod filedrop;
+pub mod filesync;
 pub mod filesystem;
+pub mod intl;
 pub mod limits;
 pub mod logging;
 pub mod permissions;
+pub mod recovery;
 pub mod remote_storage;
+pub mod revocation;
+pub mod share;
 pub mod spaces;
 pub mod shell;
 pub mod utils;
@@ -42,6 +54,9 @@ pub mod web;
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
 pub mod webview;
 
+#[cfg(any(target_os = "android", target_os = "ios"))]
+pub mod mobile;
+
 #[cfg(test)]
 mod tests;
 
@@ -120,17 +135,25 @@ pub fn register_extension_in_database(
 /// Install extension files to local filesystem.
 /// Use this after register_extension_in_database or when extension
 /// already exists in DB (e.g., from sync).
-/// Returns the extension ID.
+/// Returns the extension ID and the permission diff against the installed version.
+/// With `block_auto_grant`, permissions added by an update are stored as `ask`.
 #[tauri::command]
 pub async fn install_extension_files(
     app_handle: AppHandle,
     file_bytes: Vec<u8>,
     extension_id: String,
+    block_auto_grant: Option<bool>,
     state: State<'_, AppState>,
-) -> Result<String, ExtensionError> {
+) -> Result<ExtensionFilesInstallResult, ExtensionError> {
     state
         .extension_manager
-        .install_extension_files_from_bytes(&app_handle, file_bytes, &extension_id, &state)
+        .install_extension_files_from_bytes(
+            &app_handle,
+            file_bytes,
+            &extension_id,
+            block_auto_grant.unwrap_or(false),
+            &state,
+        )
         .await
 }
 
@@ -256,6 +279,16 @@ struct PartialManifest {
     migrations_dir: Option<String>,
     #[serde(default)]
     i18n: Option<std::collections::HashMap<String, core::manifest::ManifestI18nEntry>>,
+    #[serde(default)]
+    locales: Option<core::locales::ManifestLocales>,
+    #[serde(default, alias = "share-target")]
+    share_target: Option<core::manifest::ShareTarget>,
+    #[serde(default)]
+    events: Option<core::manifest::EventChannels>,
+    #[serde(default)]
+    min_host_version: Option<String>,
+    #[serde(default)]
+    max_host_version: Option<String>,
 }
 
 /// Check if a dev server is reachable by making a simple HTTP request
@@ -400,10 +433,20 @@ pub async fn load_dev_extension(
         display_mode: partial_manifest.display_mode,
         migrations_dir: partial_manifest.migrations_dir,
         i18n: partial_manifest.i18n,
+        key_rotations: None,
+        locales: partial_manifest.locales,
+        share_target: partial_manifest.share_target,
+        events: partial_manifest.events.map(event_bus::sanitize_channels),
+        min_host_version: partial_manifest.min_host_version,
+        max_host_version: partial_manifest.max_host_version,
     };
 
-    // 3.5. Validate public key format
+    // 3.5. Validate public key format and host API compatibility
     utils::validate_public_key(&manifest.public_key)?;
+    core::compatibility::check_host_compatibility(
+        &manifest,
+        core::compatibility::HOST_API_VERSION,
+    )?;
 
     // 4. Check if extension already exists in DB (UPSERT pattern)
     let check_sql = format!(
@@ -672,6 +715,11 @@ fn convert_to_editable_permissions(permissions: Vec<ExtensionPermission>) -> Edi
     let mut identities = Vec::new();
     let mut passwords = Vec::new();
     let mut mail = Vec::new();
+    let mut camera = Vec::new();
+    let mut contacts = Vec::new();
+    let mut calendar = Vec::new();
+    let mut microphone = Vec::new();
+    let mut speech = Vec::new();
 
     for perm in permissions {
         let entry = PermissionEntry {
@@ -693,6 +741,11 @@ fn convert_to_editable_permissions(permissions: Vec<ExtensionPermission>) -> Edi
             ResourceType::Identities => identities.push(entry),
             ResourceType::Passwords => passwords.push(entry),
             ResourceType::Mail => mail.push(entry),
+            ResourceType::Camera => camera.push(entry),
+            ResourceType::Contacts => contacts.push(entry),
+            ResourceType::Calendar => calendar.push(entry),
+            ResourceType::Microphone => microphone.push(entry),
+            ResourceType::Speech => speech.push(entry),
         }
     }
 
@@ -730,6 +783,31 @@ fn convert_to_editable_permissions(permissions: Vec<ExtensionPermission>) -> Edi
             Some(passwords)
         },
         mail: if mail.is_empty() { None } else { Some(mail) },
+        camera: if camera.is_empty() {
+            None
+        } else {
+            Some(camera)
+        },
+        contacts: if contacts.is_empty() {
+            None
+        } else {
+            Some(contacts)
+        },
+        calendar: if calendar.is_empty() {
+            None
+        } else {
+            Some(calendar)
+        },
+        microphone: if microphone.is_empty() {
+            None
+        } else {
+            Some(microphone)
+        },
+        speech: if speech.is_empty() {
+            None
+        } else {
+            Some(speech)
+        },
     }
 }
 
@@ -770,6 +848,94 @@ pub fn update_extension_display_mode(
         .update_display_mode(&extension_id, display_mode, &state)
 }
 
+/// Versions of an extension installed on this device, newest first.
+#[tauri::command]
+pub fn get_extension_versions(
+    app_handle: AppHandle,
+    extension_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<InstalledExtensionVersion>, ExtensionError> {
+    state
+        .extension_manager
+        .installed_versions(&app_handle, &extension_id, &state)
+}
+
+/// Pins an installed version as the active one for this vault, e.g. to roll
+/// back a bad update. The version must still be installed on this device.
+#[tauri::command]
+pub async fn set_extension_active_version(
+    app_handle: AppHandle,
+    extension_id: String,
+    version: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<InstalledExtensionVersion>, ExtensionError> {
+    state
+        .extension_manager
+        .set_active_version(&app_handle, &extension_id, &version, &state)
+        .await
+}
+
+/// Deletes an installed version that is not active.
+#[tauri::command]
+pub fn remove_extension_version(
+    app_handle: AppHandle,
+    extension_id: String,
+    version: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<InstalledExtensionVersion>, ExtensionError> {
+    state
+        .extension_manager
+        .remove_installed_version(&app_handle, &extension_id, &version, &state)
+}
+
+/// Migration batches of an extension on this device, latest first.
+#[tauri::command]
+pub fn extension_list_migration_batches(
+    extension_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<MigrationBatch>, ExtensionError> {
+    core::manager::ExtensionManager::migration_batches(&extension_id, &state)
+}
+
+/// Restores the tables of an extension to their state before its latest
+/// applied migration batch.
+#[tauri::command]
+pub fn extension_rollback_last_migration_batch(
+    extension_id: String,
+    state: State<'_, AppState>,
+) -> Result<MigrationBatch, ExtensionError> {
+    core::manager::ExtensionManager::rollback_last_migration_batch(&extension_id, &state)
+}
+
+/// URL to load an extension from in an iframe, in the form this platform's
+/// webview accepts. Development extensions load from their dev server.
+#[tauri::command]
+pub fn extension_get_host_url(
+    state: State<'_, AppState>,
+    extension_id: String,
+    asset_path: Option<String>,
+) -> Result<String, ExtensionError> {
+    let extension = state
+        .extension_manager
+        .get_extension(&extension_id)
+        .ok_or_else(|| ExtensionError::ValidationError {
+            reason: format!("Extension not found: {}", extension_id),
+        })?;
+    let asset_path = asset_path.as_deref().unwrap_or("index.html");
+
+    match &extension.source {
+        ExtensionSource::Development { dev_server_url, .. } => Ok(format!(
+            "{}/{}",
+            dev_server_url.trim_end_matches('/'),
+            asset_path.trim_start_matches('/')
+        )),
+        ExtensionSource::Production { .. } => core::protocol::extension_asset_url(
+            &core::protocol::ExtensionInfo::from_extension(&extension),
+            asset_path,
+        ),
+    }
+}
+
 // ============================================================================
 // WebviewWindow Commands (Desktop only)
 // ============================================================================
@@ -786,11 +952,22 @@ pub fn open_extension_webview_window(
     x: Option<f64>,
     y: Option<f64>,
     minimized: Option<bool>,
+    placement: Option<webview::placement::WindowPlacementOptions>,
 ) -> Result<String, ExtensionError> {
     eprintln!(
-        "[open_extension_webview_window] Received extension_id: {}, minimized: {:?}",
-        extension_id, minimized
+        "[open_extension_webview_window] Received extension_id: {}, minimized: {:?}, placement: {:?}",
+        extension_id, minimized, placement
     );
+    // Snapshot the context so the new webview starts with the current
+    // theme/locale instead of waiting for the next context:changed event.
+    let context = state
+        .context
+        .lock()
+        .map_err(|e| ExtensionError::MutexPoisoned {
+            reason: e.to_string(),
+        })?
+        .clone();
+
     // Returns the window_id (generated UUID without dashes)
     state.extension_webview_manager.open_extension_window(
         &app_handle,
@@ -802,9 +979,23 @@ pub fn open_extension_webview_window(
         x,
         y,
         minimized,
+        placement,
+        &context,
     )
 }
 
+/// Lists connected monitors (logical bounds, work areas, scale factors) so
+/// the frontend can restore extension windows onto the right screen.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+#[tauri::command]
+pub fn extension_webview_get_monitors(
+    app_handle: AppHandle,
+) -> Result<Vec<webview::placement::MonitorInfo>, ExtensionError> {
+    webview::placement::list_monitors(&app_handle).map_err(|e| ExtensionError::ValidationError {
+        reason: format!("Failed to enumerate monitors: {}", e),
+    })
+}
+
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
 #[tauri::command]
 pub fn close_extension_webview_window(
@@ -817,6 +1008,36 @@ pub fn close_extension_webview_window(
         .close_extension_window(&app_handle, &window_id)
 }
 
+/// Latest CPU/memory sample of the open extension windows, optionally for
+/// one extension only.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+#[tauri::command]
+pub fn extension_get_resource_usage(
+    state: State<'_, AppState>,
+    extension_id: Option<String>,
+) -> Result<Vec<webview::monitor::ExtensionResourceUsage>, ExtensionError> {
+    Ok(state
+        .extension_webview_manager
+        .monitor
+        .usage(extension_id.as_deref()))
+}
+
+/// Kill switch: destroys an extension window regardless of its state.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+#[tauri::command]
+pub fn extension_terminate_window(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    window_id: String,
+    reason: Option<String>,
+) -> Result<(), ExtensionError> {
+    state.extension_webview_manager.terminate_extension_window(
+        &app_handle,
+        &window_id,
+        reason.as_deref().unwrap_or("Terminated by user"),
+    )
+}
+
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
 #[tauri::command]
 pub fn focus_extension_webview_window(
@@ -921,11 +1142,14 @@ pub async fn extension_filter_sync_tables(
         tables
     );
 
-    // Load extensions if not already loaded
+    // Load extensions if not already loaded. Extensions registered by the
+    // sync but without files on this device are announced to the main window.
+    let previously_missing = recovery::commands::missing_extension_ids(&state)?;
     state
         .extension_manager
         .load_installed_extensions(&app_handle, &state)
         .await?;
+    recovery::commands::announce_newly_missing(&app_handle, &state, &previously_missing)?;
 
     // Get all installed extensions
     let all_extensions = state.extension_manager.get_all_extensions()?;
@@ -0,0 +1,194 @@
+// src-tauri/src/extension/permissions/broker.rs
+//!
+//! Permission prompt broker (in-memory)
+//!
+//! An extension that fires many permission-gated calls at once would
+//! otherwise get one PermissionPromptRequired error per call, and the
+//! frontend would stack up identical dialogs. The broker coalesces them:
+//! the first caller for a given (extension, resource type, action, target)
+//! surfaces the prompt as before, every identical caller that arrives while
+//! it is pending is parked until the user decides, and parked callers give
+//! up with `PermissionPromptTimeout` when nobody answers.
+
+use super::types::PermissionStatus;
+use crate::extension::error::ExtensionError;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// How long a prompt stays pending before parked callers time out and the
+/// next identical request raises a fresh prompt.
+pub const PROMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Key for pending prompt lookup
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+struct PromptKey {
+    extension_id: String,
+    resource_type: String,
+    action: String,
+    target: String,
+}
+
+/// A prompt that has been shown to the user and not answered yet
+#[derive(Debug)]
+struct PendingPrompt {
+    created_at: Instant,
+    /// `None` until the user decides
+    decision: watch::Sender<Option<PermissionStatus>>,
+}
+
+/// Outcome of registering a prompt with the broker
+enum Registration {
+    /// No identical prompt is pending; the caller must surface it
+    Raise,
+    /// An identical prompt is pending; wait on its decision until `deadline`
+    Park {
+        decision: watch::Receiver<Option<PermissionStatus>>,
+        deadline: Instant,
+    },
+}
+
+/// Coalesces identical pending permission prompts
+#[derive(Debug)]
+pub struct PermissionPromptBroker {
+    pending: Mutex<HashMap<PromptKey, PendingPrompt>>,
+    timeout: Duration,
+}
+
+impl Default for PermissionPromptBroker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PermissionPromptBroker {
+    pub fn new() -> Self {
+        Self::with_timeout(PROMPT_TIMEOUT)
+    }
+
+    pub(crate) fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            timeout,
+        }
+    }
+
+    /// Routes a `PermissionPromptRequired` error through the broker.
+    ///
+    /// The first caller gets the error back unchanged so the frontend shows
+    /// the dialog. Identical callers arriving while that prompt is pending
+    /// wait for the decision instead: `Ok(())` on grant, `PermissionDenied`
+    /// on deny, `PermissionPromptTimeout` if the prompt expires first. Any
+    /// other error is passed through.
+    pub async fn request(&self, prompt: ExtensionError) -> Result<(), ExtensionError> {
+        let key = match &prompt {
+            ExtensionError::PermissionPromptRequired {
+                extension_id,
+                resource_type,
+                action,
+                target,
+                ..
+            } => PromptKey {
+                extension_id: extension_id.clone(),
+                resource_type: resource_type.clone(),
+                action: action.clone(),
+                target: target.clone(),
+            },
+            _ => return Err(prompt),
+        };
+
+        let (mut decision, deadline) = match self.register(&key) {
+            Registration::Raise => return Err(prompt),
+            Registration::Park { decision, deadline } => (decision, deadline),
+        };
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let outcome =
+            tokio::time::timeout(remaining, decision.wait_for(|status| status.is_some())).await;
+
+        match outcome {
+            Ok(Ok(status)) if *status == Some(PermissionStatus::Granted) => Ok(()),
+            Ok(Ok(_)) => Err(ExtensionError::permission_denied(
+                &key.extension_id,
+                &key.action,
+                &key.target,
+            )),
+            // Sender dropped (prompt expired and was replaced) or deadline hit
+            Ok(Err(_)) | Err(_) => {
+                self.expire(&key);
+                Err(ExtensionError::PermissionPromptTimeout {
+                    extension_id: key.extension_id,
+                    resource_type: key.resource_type,
+                    action: key.action,
+                    target: key.target,
+                    timeout_secs: self.timeout.as_secs(),
+                })
+            }
+        }
+    }
+
+    /// Delivers the user's decision to every caller parked on the prompt.
+    /// Returns the number of parked callers that were released.
+    pub fn resolve(
+        &self,
+        extension_id: &str,
+        resource_type: &str,
+        action: &str,
+        target: &str,
+        status: PermissionStatus,
+    ) -> usize {
+        let key = PromptKey {
+            extension_id: extension_id.to_string(),
+            resource_type: resource_type.to_string(),
+            action: action.to_string(),
+            target: target.to_string(),
+        };
+
+        let Some(prompt) = self.pending.lock().ok().and_then(|mut p| p.remove(&key)) else {
+            return 0;
+        };
+        let waiters = prompt.decision.receiver_count();
+        let _ = prompt.decision.send(Some(status));
+        waiters
+    }
+
+    fn register(&self, key: &PromptKey) -> Registration {
+        let Ok(mut pending) = self.pending.lock() else {
+            // A poisoned broker must not block permission checks.
+            return Registration::Raise;
+        };
+
+        if let Some(prompt) = pending.get(key) {
+            let deadline = prompt.created_at + self.timeout;
+            if Instant::now() < deadline {
+                return Registration::Park {
+                    decision: prompt.decision.subscribe(),
+                    deadline,
+                };
+            }
+        }
+
+        let (decision, _) = watch::channel(None);
+        pending.insert(
+            key.clone(),
+            PendingPrompt {
+                created_at: Instant::now(),
+                decision,
+            },
+        );
+        Registration::Raise
+    }
+
+    /// Removes `key` if its prompt has outlived the timeout
+    fn expire(&self, key: &PromptKey) {
+        if let Ok(mut pending) = self.pending.lock() {
+            if pending
+                .get(key)
+                .is_some_and(|p| p.created_at.elapsed() >= self.timeout)
+            {
+                pending.remove(key);
+            }
+        }
+    }
+}
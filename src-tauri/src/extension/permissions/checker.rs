@@ -56,6 +56,57 @@ impl PermissionChecker {
         self.has_explicit_permission(clean_table_name, action)
     }
 
+    /// Returns the columns of `table_name` that no granted permission covers
+    ///
+    /// # Column Rules
+    /// 1. Extensions can use every column of their own tables
+    /// 2. A table-wide target ("table", "prefix__*", "table.*") covers every
+    ///    column except secret ones (see [`is_secret_column`])
+    /// 3. "table.column" and "table.{a,b}" cover exactly the named columns,
+    ///    secret or not
+    pub fn uncovered_columns(
+        &self,
+        table_name: &str,
+        action: DbAction,
+        columns: &[String],
+    ) -> Vec<String> {
+        if self.is_auto_allowed_table(table_name) {
+            return Vec::new();
+        }
+        let clean_table_name = table_name.trim_matches('"').trim_matches('`');
+
+        let granted: Vec<ColumnSet> = self
+            .permissions
+            .iter()
+            .filter(|perm| perm.status == PermissionStatus::Granted)
+            .filter(|perm| perm.resource_type == ResourceType::Db)
+            .filter(|perm| matches_action(&perm.action, action))
+            .filter(|perm| matches_target(&perm.target, clean_table_name))
+            .map(|perm| split_column_target(&perm.target).1)
+            .collect();
+
+        columns
+            .iter()
+            .filter(|column| !granted.iter().any(|set| set.allows(column)))
+            .cloned()
+            .collect()
+    }
+
+    /// Checks if a permission explicitly denies this column
+    pub fn is_column_denied(&self, table_name: &str, action: DbAction, column: &str) -> bool {
+        let clean_table_name = table_name.trim_matches('"').trim_matches('`');
+        self.permissions
+            .iter()
+            .filter(|perm| perm.status == PermissionStatus::Denied)
+            .filter(|perm| perm.resource_type == ResourceType::Db)
+            .filter(|perm| matches_action(&perm.action, action))
+            .filter(|perm| matches_target(&perm.target, clean_table_name))
+            .any(|perm| match split_column_target(&perm.target).1 {
+                ColumnSet::Named(names) => names.iter().any(|n| n.eq_ignore_ascii_case(column)),
+                ColumnSet::All => false,
+            })
+    }
+
     /// Checks if there's an explicit permission for the table and action
     fn has_explicit_permission(&self, table_name: &str, action: DbAction) -> bool {
         self.permissions
@@ -76,6 +127,11 @@ impl PermissionChecker {
         )
     }
 
+    /// Checks if a target applies to the whole table rather than to named columns
+    pub fn is_table_wide_target(&self, target: &str) -> bool {
+        matches!(split_column_target(target).1, ColumnSet::All)
+    }
+
     /// Checks if a target pattern matches a table name (public wrapper)
     pub fn matches_table_pattern(&self, target: &str, table_name: &str) -> bool {
         let clean_table_name = table_name.trim_matches('"').trim_matches('`');
@@ -186,7 +242,11 @@ pub(crate) fn matches_action(permission_action: &Action, required: DbAction) ->
 /// - "*" - matches all non-system tables
 /// - "prefix__*" - matches all tables starting with "prefix__"
 /// - "exact_table" - matches exact table name
+///
+/// A column suffix ("table.column", see [`split_column_target`]) is ignored here.
 pub(crate) fn matches_target(target: &str, table_name: &str) -> bool {
+    let target = split_column_target(target).0;
+
     // System tables are never matched by any pattern
     if is_system_table(table_name) {
         return false;
@@ -211,3 +271,60 @@ pub(crate) fn is_system_table(table_name: &str) -> bool {
     table_name.starts_with("haex_")
         || table_name.starts_with("sqlite_") // Covers sqlite_master, sqlite_sequence, sqlite_stat1, etc.
 }
+
+/// Columns a database permission target covers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ColumnSet {
+    /// Every non-secret column ("table" or "table.*")
+    All,
+    /// Exactly these columns ("table.column" or "table.{a,b}")
+    Named(Vec<String>),
+}
+
+impl ColumnSet {
+    pub(crate) fn allows(&self, column: &str) -> bool {
+        match self {
+            ColumnSet::All => !is_secret_column(column),
+            ColumnSet::Named(names) => names.iter().any(|n| n.eq_ignore_ascii_case(column)),
+        }
+    }
+}
+
+/// Splits a database target into its table pattern and column set
+///
+/// - "notes" / "notes.*" - all non-secret columns of notes
+/// - "notes.title" - only notes.title
+/// - "notes.{title,body}" - only notes.title and notes.body
+pub(crate) fn split_column_target(target: &str) -> (&str, ColumnSet) {
+    let Some((table, columns)) = target.split_once('.') else {
+        return (target, ColumnSet::All);
+    };
+
+    if columns == "*" {
+        return (table, ColumnSet::All);
+    }
+
+    let names = columns
+        .strip_prefix('{')
+        .and_then(|c| c.strip_suffix('}'))
+        .unwrap_or(columns)
+        .split(',')
+        .map(|c| c.trim().trim_matches('"').trim_matches('`').to_string())
+        .filter(|c| !c.is_empty())
+        .collect();
+    (table, ColumnSet::Named(names))
+}
+
+/// Column names (and `_`-suffixes) that hold credentials
+const SECRET_COLUMN_NAMES: &[&str] = &["password", "secret", "private_key", "api_key", "token"];
+
+/// Checks if a column holds a credential
+///
+/// Secret columns are never covered by a table-wide grant; the permission
+/// target has to name them ("vault__items.password").
+pub(crate) fn is_secret_column(column: &str) -> bool {
+    let column = column.trim_matches('"').trim_matches('`').to_lowercase();
+    SECRET_COLUMN_NAMES
+        .iter()
+        .any(|name| column == *name || column.ends_with(&format!("_{name}")))
+}
@@ -125,6 +125,12 @@ pub fn notify_extension_permission_decision(
         });
     }
 
+    // Release every identical call that was parked on this prompt.
+    let status = PermissionStatus::from_str(&decision)?;
+    state
+        .permission_prompts
+        .resolve(&extension_id, &resource_type, &action, &target, status);
+
     let payload = PermissionResolvedPayload {
         extension_id: extension_id.clone(),
         resource_type,
@@ -240,6 +246,11 @@ pub async fn resolve_permission_prompt(
         "identities" => ResourceType::Identities,
         "passwords" => ResourceType::Passwords,
         "mail" => ResourceType::Mail,
+        "camera" => ResourceType::Camera,
+        "contacts" => ResourceType::Contacts,
+        "calendar" => ResourceType::Calendar,
+        "microphone" => ResourceType::Microphone,
+        "speech" => ResourceType::Speech,
         _ => {
             return Err(ExtensionError::ValidationError {
                 reason: format!("Invalid resource type: {}", resource_type),
@@ -309,6 +320,53 @@ pub async fn resolve_permission_prompt(
             };
             Action::Mail(mail_action)
         }
+        ResourceType::Camera => {
+            let camera_action = match action.to_lowercase().as_str() {
+                "scan" => crate::extension::permissions::types::CameraAction::Scan,
+                "capture" => crate::extension::permissions::types::CameraAction::Capture,
+                _ => return Err(ExtensionError::ValidationError {
+                    reason: format!("Invalid camera action: {action}"),
+                }),
+            };
+            Action::Camera(camera_action)
+        }
+        ResourceType::Contacts => {
+            let contacts_action = match action.to_lowercase().as_str() {
+                "read" => crate::extension::permissions::types::ContactsAction::Read,
+                _ => return Err(ExtensionError::ValidationError {
+                    reason: format!("Invalid contacts action: {action}"),
+                }),
+            };
+            Action::Contacts(contacts_action)
+        }
+        ResourceType::Calendar => {
+            let calendar_action = match action.to_lowercase().as_str() {
+                "read" => crate::extension::permissions::types::CalendarAction::Read,
+                _ => return Err(ExtensionError::ValidationError {
+                    reason: format!("Invalid calendar action: {action}"),
+                }),
+            };
+            Action::Calendar(calendar_action)
+        }
+        ResourceType::Microphone => {
+            let microphone_action = match action.to_lowercase().as_str() {
+                "record" => crate::extension::permissions::types::MicrophoneAction::Record,
+                _ => return Err(ExtensionError::ValidationError {
+                    reason: format!("Invalid microphone action: {action}"),
+                }),
+            };
+            Action::Microphone(microphone_action)
+        }
+        ResourceType::Speech => {
+            let speech_action = match action.to_lowercase().as_str() {
+                "speak" => crate::extension::permissions::types::SpeechAction::Speak,
+                "transcribe" => crate::extension::permissions::types::SpeechAction::Transcribe,
+                _ => return Err(ExtensionError::ValidationError {
+                    reason: format!("Invalid speech action: {action}"),
+                }),
+            };
+            Action::Speech(speech_action)
+        }
     };
 
     // Check if permission already exists.
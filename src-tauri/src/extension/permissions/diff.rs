@@ -0,0 +1,142 @@
+// src-tauri/src/extension/permissions/diff.rs
+//
+// Structured comparison between the permissions an installed extension holds
+// and the permissions requested by an updated bundle.
+
+use crate::extension::permissions::types::{
+    ExtensionPermission, PermissionConstraints, ResourceType,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+/// A single action (plus optional constraints) requested for a target.
+#[derive(Serialize, Deserialize, Clone, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct RequestedPermission {
+    pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub constraints: Option<PermissionConstraints>,
+}
+
+impl RequestedPermission {
+    fn from_permission(perm: &ExtensionPermission) -> Self {
+        Self {
+            action: perm.action.as_str().to_string(),
+            constraints: perm.constraints.clone(),
+        }
+    }
+
+    /// Stable key used to compare requests independent of their order.
+    fn sort_key(&self) -> (String, String) {
+        let constraints = self
+            .constraints
+            .as_ref()
+            .and_then(|c| serde_json::to_string(c).ok())
+            .unwrap_or_default();
+        (self.action.clone(), constraints)
+    }
+}
+
+/// All requests of one `(resource_type, target)` pair before and after an update.
+#[derive(Serialize, Deserialize, Clone, Debug, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PermissionDiffEntry {
+    pub resource_type: ResourceType,
+    pub target: String,
+    /// Requests held by the installed version (empty for added targets)
+    pub previous: Vec<RequestedPermission>,
+    /// Requests of the new bundle (empty for removed targets)
+    pub requested: Vec<RequestedPermission>,
+}
+
+/// Permission changes between the installed version and an updated bundle.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PermissionDiff {
+    pub added: Vec<PermissionDiffEntry>,
+    pub removed: Vec<PermissionDiffEntry>,
+    pub changed: Vec<PermissionDiffEntry>,
+}
+
+impl PermissionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// True if the update asks for anything the installed version did not hold.
+    pub fn requests_new_permissions(&self) -> bool {
+        !self.added.is_empty() || !self.changed.is_empty()
+    }
+}
+
+type TargetKey = (String, String);
+
+fn group_by_target(
+    permissions: &[ExtensionPermission],
+) -> BTreeMap<TargetKey, (ResourceType, Vec<RequestedPermission>)> {
+    let mut grouped: BTreeMap<TargetKey, (ResourceType, Vec<RequestedPermission>)> =
+        BTreeMap::new();
+    for perm in permissions {
+        grouped
+            .entry((perm.resource_type.as_str().to_string(), perm.target.clone()))
+            .or_insert_with(|| (perm.resource_type, Vec::new()))
+            .1
+            .push(RequestedPermission::from_permission(perm));
+    }
+    for (_, requests) in grouped.values_mut() {
+        requests.sort_by_key(RequestedPermission::sort_key);
+        requests.dedup_by_key(|r| r.sort_key());
+    }
+    grouped
+}
+
+/// Compares installed permissions with the ones requested by a new manifest.
+///
+/// Targets are matched by `(resource_type, target)`; the status of installed
+/// permissions is ignored so user decisions never show up as a change.
+pub fn diff_permissions(
+    installed: &[ExtensionPermission],
+    requested: &[ExtensionPermission],
+) -> PermissionDiff {
+    let mut previous = group_by_target(installed);
+    let mut diff = PermissionDiff::default();
+
+    for (key, (resource_type, requests)) in group_by_target(requested) {
+        let (_, target) = key.clone();
+        match previous.remove(&key) {
+            None => diff.added.push(PermissionDiffEntry {
+                resource_type,
+                target,
+                previous: Vec::new(),
+                requested: requests,
+            }),
+            Some((_, old)) => {
+                let old_keys: Vec<_> = old.iter().map(RequestedPermission::sort_key).collect();
+                let new_keys: Vec<_> = requests.iter().map(RequestedPermission::sort_key).collect();
+                if old_keys != new_keys {
+                    diff.changed.push(PermissionDiffEntry {
+                        resource_type,
+                        target,
+                        previous: old,
+                        requested: requests,
+                    });
+                }
+            }
+        }
+    }
+
+    for ((_, target), (resource_type, old)) in previous {
+        diff.removed.push(PermissionDiffEntry {
+            resource_type,
+            target,
+            previous: old,
+            requested: Vec::new(),
+        });
+    }
+
+    diff
+}
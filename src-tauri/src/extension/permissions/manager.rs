@@ -3,10 +3,11 @@ use crate::database::error::DatabaseError;
 use crate::database::generated::HaexExtensionPermissions;
 use crate::extension::database::executor::SqlExecutor;
 use crate::extension::error::ExtensionError;
-use crate::extension::permissions::checker::PermissionChecker;
+use crate::extension::permissions::checker::{is_secret_column, PermissionChecker};
 use crate::extension::permissions::types::{
-    Action, ExtensionPermission, FileSyncAction, FileSyncTarget, MailAction, PasswordsAction,
-    PasswordsScope, PermissionConstraints, PermissionStatus, ResourceType, SpaceAction,
+    Action, CalendarAction, CameraAction, ContactsAction, ExtensionPermission, FileSyncAction,
+    FileSyncTarget, MailAction, MicrophoneAction, PasswordsAction, PasswordsScope,
+    PermissionConstraints, PermissionStatus, ResourceType, SpaceAction, SpeechAction,
 };
 use crate::table_names::TABLE_EXTENSION_PERMISSIONS;
 use crate::AppState;
@@ -266,11 +267,14 @@ impl PermissionManager {
             return Ok(());
         }
 
-        // Find matching permission for this table and action
+        // Find matching permission for this table and action. Denying single
+        // columns does not deny the table; check_database_columns handles it.
         let matching_permission = permissions.iter().find(|perm| {
             perm.resource_type == ResourceType::Db
                 && checker.matches_table_pattern(&perm.target, table_name)
                 && checker.action_allows_db_action(&perm.action, db_action)
+                && (perm.status != PermissionStatus::Denied
+                    || checker.is_table_wide_target(&perm.target))
         });
 
         match matching_permission {
@@ -281,13 +285,18 @@ impl PermissionManager {
                     db_action.as_str(),
                     &format!("database table '{table_name}'"),
                 )),
-                PermissionStatus::Ask => Err(ExtensionError::permission_prompt_required(
-                    extension_id,
-                    &extension.manifest.name,
-                    "db",
-                    db_action.as_str(),
-                    table_name,
-                )),
+                PermissionStatus::Ask => {
+                    app_state
+                        .permission_prompts
+                        .request(ExtensionError::permission_prompt_required(
+                            extension_id,
+                            &extension.manifest.name,
+                            "db",
+                            db_action.as_str(),
+                            table_name,
+                        ))
+                        .await
+                }
             },
             // No matching permission in database - check session permissions
             None => {
@@ -309,15 +318,92 @@ impl PermissionManager {
                 }
 
                 // No session permission either - prompt the user
-                Err(ExtensionError::permission_prompt_required(
+                app_state
+                    .permission_prompts
+                    .request(ExtensionError::permission_prompt_required(
+                        extension_id,
+                        &extension.manifest.name,
+                        "db",
+                        db_action.as_str(),
+                        table_name,
+                    ))
+                    .await
+            }
+        }
+    }
+
+    /// Prüft Spaltenberechtigungen für eine Tabelle
+    /// Called after check_database_permission has allowed the table itself.
+    /// Columns without a covering grant fall back to session permissions
+    /// (the whole table for non-secret columns, otherwise "table.column") and
+    /// finally prompt with target "table.column".
+    pub async fn check_database_columns(
+        app_state: &State<'_, AppState>,
+        extension_id: &str,
+        action: Action,
+        table_name: &str,
+        columns: &[String],
+    ) -> Result<(), ExtensionError> {
+        let db_action = match action {
+            Action::Database(db_action) => db_action,
+            _ => {
+                return Err(ExtensionError::ValidationError {
+                    reason: "Expected database action".to_string(),
+                });
+            }
+        };
+
+        let extension = app_state
+            .extension_manager
+            .get_extension(extension_id)
+            .ok_or_else(|| ExtensionError::ValidationError {
+                reason: format!("Extension with ID {extension_id} not found"),
+            })?
+            .clone();
+
+        let permissions = Self::get_permissions(app_state, extension_id).await?;
+        let checker = PermissionChecker::new(extension.clone(), permissions);
+
+        for column in checker.uncovered_columns(table_name, db_action, columns) {
+            let column_target = format!("{table_name}.{column}");
+
+            if checker.is_column_denied(table_name, db_action, &column)
+                || app_state
+                    .session_permissions
+                    .is_denied(extension_id, ResourceType::Db, &column_target)
+            {
+                return Err(ExtensionError::permission_denied(
+                    extension_id,
+                    db_action.as_str(),
+                    &format!("database column '{column_target}'"),
+                ));
+            }
+
+            let table_granted = !is_secret_column(&column)
+                && app_state
+                    .session_permissions
+                    .is_granted(extension_id, ResourceType::Db, table_name);
+            if table_granted
+                || app_state
+                    .session_permissions
+                    .is_granted(extension_id, ResourceType::Db, &column_target)
+            {
+                continue;
+            }
+
+            app_state
+                .permission_prompts
+                .request(ExtensionError::permission_prompt_required(
                     extension_id,
                     &extension.manifest.name,
                     "db",
                     db_action.as_str(),
-                    table_name,
+                    &column_target,
                 ))
-            }
+                .await?;
         }
+
+        Ok(())
     }
 
     /// Prüft Web-Berechtigungen für Requests
@@ -407,13 +493,18 @@ impl PermissionManager {
                     "web request",
                     url,
                 )),
-                PermissionStatus::Ask => Err(ExtensionError::permission_prompt_required(
-                    extension_id,
-                    &extension.manifest.name,
-                    "web",
-                    "request",
-                    url,
-                )),
+                PermissionStatus::Ask => {
+                    app_state
+                        .permission_prompts
+                        .request(ExtensionError::permission_prompt_required(
+                            extension_id,
+                            &extension.manifest.name,
+                            "web",
+                            "request",
+                            url,
+                        ))
+                        .await
+                }
             },
             // No matching permission in database - check session permissions
             None => {
@@ -435,13 +526,16 @@ impl PermissionManager {
                 }
 
                 // No session permission either - prompt the user
-                Err(ExtensionError::permission_prompt_required(
-                    extension_id,
-                    &extension.manifest.name,
-                    "web",
-                    "request",
-                    url,
-                ))
+                app_state
+                    .permission_prompts
+                    .request(ExtensionError::permission_prompt_required(
+                        extension_id,
+                        &extension.manifest.name,
+                        "web",
+                        "request",
+                        url,
+                    ))
+                    .await
             }
         }
     }
@@ -507,13 +601,18 @@ impl PermissionManager {
                         &action.as_str(),
                         &format!("filesystem path '{}'", file_path_str),
                     )),
-                    PermissionStatus::Ask => Err(ExtensionError::permission_prompt_required(
-                        extension_id,
-                        &extension.manifest.name,
-                        "fs",
-                        &action.as_str(),
-                        &file_path_str,
-                    )),
+                    PermissionStatus::Ask => {
+                        app_state
+                            .permission_prompts
+                            .request(ExtensionError::permission_prompt_required(
+                                extension_id,
+                                &extension.manifest.name,
+                                "fs",
+                                &action.as_str(),
+                                &file_path_str,
+                            ))
+                            .await
+                    }
                 }
             }
             // No matching permission in database - check session permissions
@@ -536,13 +635,16 @@ impl PermissionManager {
                 }
 
                 // No session permission either - prompt the user
-                Err(ExtensionError::permission_prompt_required(
-                    extension_id,
-                    &extension.manifest.name,
-                    "fs",
-                    &action.as_str(),
-                    &file_path_str,
-                ))
+                app_state
+                    .permission_prompts
+                    .request(ExtensionError::permission_prompt_required(
+                        extension_id,
+                        &extension.manifest.name,
+                        "fs",
+                        &action.as_str(),
+                        &file_path_str,
+                    ))
+                    .await
             }
         }
     }
@@ -670,13 +772,18 @@ impl PermissionManager {
                         "execute",
                         &format!("shell command '{}' with args {:?}", command, args),
                     )),
-                    PermissionStatus::Ask => Err(ExtensionError::permission_prompt_required(
-                        extension_id,
-                        &extension.manifest.name,
-                        "shell",
-                        "execute",
-                        command,
-                    )),
+                    PermissionStatus::Ask => {
+                        app_state
+                            .permission_prompts
+                            .request(ExtensionError::permission_prompt_required(
+                                extension_id,
+                                &extension.manifest.name,
+                                "shell",
+                                "execute",
+                                command,
+                            ))
+                            .await
+                    }
                 }
             }
             // No matching permission in database - check session permissions
@@ -699,13 +806,16 @@ impl PermissionManager {
                 }
 
                 // No session permission either - prompt the user
-                Err(ExtensionError::permission_prompt_required(
-                    extension_id,
-                    &extension.manifest.name,
-                    "shell",
-                    "execute",
-                    command,
-                ))
+                app_state
+                    .permission_prompts
+                    .request(ExtensionError::permission_prompt_required(
+                        extension_id,
+                        &extension.manifest.name,
+                        "shell",
+                        "execute",
+                        command,
+                    ))
+                    .await
             }
         }
     }
@@ -778,13 +888,18 @@ impl PermissionManager {
                     action_str,
                     &format!("filesync:{}", target_str),
                 )),
-                PermissionStatus::Ask => Err(ExtensionError::permission_prompt_required(
-                    extension_id,
-                    &extension.manifest.name,
-                    "filesync",
-                    action_str,
-                    target_str,
-                )),
+                PermissionStatus::Ask => {
+                    app_state
+                        .permission_prompts
+                        .request(ExtensionError::permission_prompt_required(
+                            extension_id,
+                            &extension.manifest.name,
+                            "filesync",
+                            action_str,
+                            target_str,
+                        ))
+                        .await
+                }
             },
             // No matching permission in database - check session permissions
             None => {
@@ -807,13 +922,16 @@ impl PermissionManager {
                 }
 
                 // No session permission either - prompt the user
-                Err(ExtensionError::permission_prompt_required(
-                    extension_id,
-                    &extension.manifest.name,
-                    "filesync",
-                    action_str,
-                    target_str,
-                ))
+                app_state
+                    .permission_prompts
+                    .request(ExtensionError::permission_prompt_required(
+                        extension_id,
+                        &extension.manifest.name,
+                        "filesync",
+                        action_str,
+                        target_str,
+                    ))
+                    .await
             }
         }
     }
@@ -863,13 +981,18 @@ impl PermissionManager {
                     action_str,
                     "spaces:*",
                 )),
-                PermissionStatus::Ask => Err(ExtensionError::permission_prompt_required(
-                    extension_id,
-                    &extension.manifest.name,
-                    "spaces",
-                    action_str,
-                    "*",
-                )),
+                PermissionStatus::Ask => {
+                    app_state
+                        .permission_prompts
+                        .request(ExtensionError::permission_prompt_required(
+                            extension_id,
+                            &extension.manifest.name,
+                            "spaces",
+                            action_str,
+                            "*",
+                        ))
+                        .await
+                }
             },
             None => {
                 if app_state
@@ -889,13 +1012,16 @@ impl PermissionManager {
                     ));
                 }
 
-                Err(ExtensionError::permission_prompt_required(
-                    extension_id,
-                    &extension.manifest.name,
-                    "spaces",
-                    action_str,
-                    "*",
-                ))
+                app_state
+                    .permission_prompts
+                    .request(ExtensionError::permission_prompt_required(
+                        extension_id,
+                        &extension.manifest.name,
+                        "spaces",
+                        action_str,
+                        "*",
+                    ))
+                    .await
             }
         }
     }
@@ -950,13 +1076,16 @@ impl PermissionManager {
         };
 
         if matching.is_empty() {
-            return Err(ExtensionError::permission_prompt_required(
-                extension_id,
-                &extension.manifest.name,
-                "passwords",
-                action_str,
-                "*",
-            ));
+            return app_state
+                .permission_prompts
+                .request(ExtensionError::permission_prompt_required(
+                    extension_id,
+                    &extension.manifest.name,
+                    "passwords",
+                    action_str,
+                    "*",
+                ))
+                .await;
         }
 
         // Prüfe auf ein Denied — ein einziges Denied blockiert alles.
@@ -978,13 +1107,16 @@ impl PermissionManager {
 
         if granted.is_empty() {
             // Alle matchings sind Ask → Prompt.
-            return Err(ExtensionError::permission_prompt_required(
-                extension_id,
-                &extension.manifest.name,
-                "passwords",
-                action_str,
-                "*",
-            ));
+            return app_state
+                .permission_prompts
+                .request(ExtensionError::permission_prompt_required(
+                    extension_id,
+                    &extension.manifest.name,
+                    "passwords",
+                    action_str,
+                    "*",
+                ))
+                .await;
         }
 
         // Wildcard "*" schlägt alle Tags — Vollzugriff.
@@ -1093,13 +1225,16 @@ impl PermissionManager {
                 }
                 _ => {}
             }
-            return Err(ExtensionError::permission_prompt_required(
-                extension_id,
-                &extension.manifest.name,
-                "mail",
-                action.as_str(),
-                host,
-            ));
+            return app_state
+                .permission_prompts
+                .request(ExtensionError::permission_prompt_required(
+                    extension_id,
+                    &extension.manifest.name,
+                    "mail",
+                    action.as_str(),
+                    host,
+                ))
+                .await;
         }
 
         // Single Denied blocks. (Granted/Ask are evaluated next.)
@@ -1133,13 +1268,154 @@ impl PermissionManager {
             }
             _ => {}
         }
-        Err(ExtensionError::permission_prompt_required(
+        app_state
+            .permission_prompts
+            .request(ExtensionError::permission_prompt_required(
+                extension_id,
+                &extension.manifest.name,
+                "mail",
+                action.as_str(),
+                host,
+            ))
+            .await
+    }
+
+    /// Prüft Kamera-Berechtigungen. Es gibt kein Ziel — target ist immer "*".
+    pub async fn check_camera_permission(
+        app_state: &State<'_, AppState>,
+        extension_id: &str,
+        action: CameraAction,
+    ) -> Result<(), ExtensionError> {
+        Self::check_device_permission(
+            app_state,
+            extension_id,
+            ResourceType::Camera,
+            Action::Camera(action),
+        )
+        .await
+    }
+
+    /// Prüft den Lesezugriff auf die Kontakte des Betriebssystems.
+    pub async fn check_contacts_permission(
+        app_state: &State<'_, AppState>,
+        extension_id: &str,
+        action: ContactsAction,
+    ) -> Result<(), ExtensionError> {
+        Self::check_device_permission(
+            app_state,
+            extension_id,
+            ResourceType::Contacts,
+            Action::Contacts(action),
+        )
+        .await
+    }
+
+    /// Prüft den Lesezugriff auf die Kalender des Betriebssystems.
+    pub async fn check_calendar_permission(
+        app_state: &State<'_, AppState>,
+        extension_id: &str,
+        action: CalendarAction,
+    ) -> Result<(), ExtensionError> {
+        Self::check_device_permission(
+            app_state,
+            extension_id,
+            ResourceType::Calendar,
+            Action::Calendar(action),
+        )
+        .await
+    }
+
+    /// Prüft Mikrofon-Berechtigungen. Es gibt kein Ziel — target ist immer "*".
+    pub async fn check_microphone_permission(
+        app_state: &State<'_, AppState>,
+        extension_id: &str,
+        action: MicrophoneAction,
+    ) -> Result<(), ExtensionError> {
+        Self::check_device_permission(
+            app_state,
+            extension_id,
+            ResourceType::Microphone,
+            Action::Microphone(action),
+        )
+        .await
+    }
+
+    /// Prüft Berechtigungen für Sprachausgabe und -erkennung.
+    pub async fn check_speech_permission(
+        app_state: &State<'_, AppState>,
+        extension_id: &str,
+        action: SpeechAction,
+    ) -> Result<(), ExtensionError> {
+        Self::check_device_permission(
+            app_state,
             extension_id,
-            &extension.manifest.name,
-            "mail",
-            action.as_str(),
-            host,
-        ))
+            ResourceType::Speech,
+            Action::Speech(action),
+        )
+        .await
+    }
+
+    /// Gemeinsame Prüfung für Geräte-Ressourcen ohne Ziel (Kamera, Mikrofon,
+    /// Kontakte, Kalender, Sprache) — target ist immer "*".
+    ///
+    /// Denied gewinnt vor Granted, Session-Entscheidungen ("einmal erlauben")
+    /// gelten wie bei Mail, ohne Treffer wird über den Broker gefragt.
+    async fn check_device_permission(
+        app_state: &State<'_, AppState>,
+        extension_id: &str,
+        resource_type: ResourceType,
+        action: Action,
+    ) -> Result<(), ExtensionError> {
+        let extension = app_state
+            .extension_manager
+            .get_extension(extension_id)
+            .ok_or_else(|| ExtensionError::ValidationError {
+                reason: format!("Extension not found: {}", extension_id),
+            })?
+            .clone();
+
+        let is_match = |p: &ExtensionPermission| -> bool {
+            p.resource_type == resource_type && p.action == action
+        };
+
+        let stored = Self::get_permissions(app_state, extension_id).await?;
+        let session = app_state
+            .session_permissions
+            .get_permissions_for_extension(extension_id);
+
+        // Gespeicherte Entscheidungen zuerst, dann die der Session
+        for matching in [
+            stored.iter().filter(|p| is_match(p)).collect::<Vec<_>>(),
+            session.iter().filter(|p| is_match(p)).collect::<Vec<_>>(),
+        ] {
+            if matching
+                .iter()
+                .any(|p| matches!(p.status, PermissionStatus::Denied))
+            {
+                return Err(ExtensionError::permission_denied(
+                    extension_id,
+                    &action.as_str(),
+                    &format!("{}:*", resource_type.as_str()),
+                ));
+            }
+            if matching
+                .iter()
+                .any(|p| matches!(p.status, PermissionStatus::Granted))
+            {
+                return Ok(());
+            }
+        }
+
+        app_state
+            .permission_prompts
+            .request(ExtensionError::permission_prompt_required(
+                extension_id,
+                &extension.manifest.name,
+                resource_type.as_str(),
+                &action.as_str(),
+                "*",
+            ))
+            .await
     }
 
     // Helper-Methoden - müssen DatabaseError statt ExtensionError zurückgeben
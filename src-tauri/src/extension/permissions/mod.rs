@@ -1,5 +1,7 @@
+pub mod broker;
 pub mod checker;
 pub mod commands;
+pub mod diff;
 pub mod manager;
 pub mod session;
 #[cfg(test)]
@@ -0,0 +1,112 @@
+// src-tauri/src/extension/permissions/tests/broker_tests.rs
+
+use crate::extension::error::ExtensionError;
+use crate::extension::permissions::broker::PermissionPromptBroker;
+use crate::extension::permissions::types::PermissionStatus;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn prompt(target: &str) -> ExtensionError {
+    ExtensionError::permission_prompt_required("test_ext", "Test", "db", "read", target)
+}
+
+/// Spawns a duplicate request and waits until it is parked on the prompt.
+async fn park(
+    broker: &Arc<PermissionPromptBroker>,
+    target: &str,
+) -> tokio::task::JoinHandle<Result<(), ExtensionError>> {
+    let broker = broker.clone();
+    let target = target.to_string();
+    let handle = tokio::spawn(async move { broker.request(prompt(&target)).await });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    handle
+}
+
+#[tokio::test]
+async fn test_first_request_raises_prompt() {
+    let broker = PermissionPromptBroker::new();
+    let result = broker.request(prompt("notes")).await;
+    assert!(matches!(
+        result,
+        Err(ExtensionError::PermissionPromptRequired { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_duplicates_share_grant() {
+    let broker = Arc::new(PermissionPromptBroker::new());
+    assert!(broker.request(prompt("notes")).await.is_err());
+
+    let first = park(&broker, "notes").await;
+    let second = park(&broker, "notes").await;
+
+    let released = broker.resolve("test_ext", "db", "read", "notes", PermissionStatus::Granted);
+    assert_eq!(released, 2);
+    assert!(first.await.unwrap().is_ok());
+    assert!(second.await.unwrap().is_ok());
+}
+
+#[tokio::test]
+async fn test_duplicates_share_denial() {
+    let broker = Arc::new(PermissionPromptBroker::new());
+    assert!(broker.request(prompt("notes")).await.is_err());
+
+    let parked = park(&broker, "notes").await;
+    broker.resolve("test_ext", "db", "read", "notes", PermissionStatus::Denied);
+
+    assert!(matches!(
+        parked.await.unwrap(),
+        Err(ExtensionError::PermissionDenied { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_different_targets_prompt_separately() {
+    let broker = PermissionPromptBroker::new();
+    assert!(matches!(
+        broker.request(prompt("notes")).await,
+        Err(ExtensionError::PermissionPromptRequired { .. })
+    ));
+    assert!(matches!(
+        broker.request(prompt("contacts")).await,
+        Err(ExtensionError::PermissionPromptRequired { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_unanswered_prompt_times_out() {
+    let broker = PermissionPromptBroker::with_timeout(Duration::from_millis(50));
+    assert!(broker.request(prompt("notes")).await.is_err());
+
+    match broker.request(prompt("notes")).await {
+        Err(ExtensionError::PermissionPromptTimeout { target, .. }) => {
+            assert_eq!(target, "notes")
+        }
+        other => panic!("expected timeout, got {other:?}"),
+    }
+
+    // The expired prompt is gone, so the next caller raises a fresh one.
+    assert!(matches!(
+        broker.request(prompt("notes")).await,
+        Err(ExtensionError::PermissionPromptRequired { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_resolve_without_pending_prompt() {
+    let broker = PermissionPromptBroker::new();
+    assert_eq!(
+        broker.resolve("test_ext", "db", "read", "notes", PermissionStatus::Granted),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_other_errors_pass_through() {
+    let broker = PermissionPromptBroker::new();
+    let error = ExtensionError::permission_denied("test_ext", "read", "notes");
+    assert!(matches!(
+        broker.request(error).await,
+        Err(ExtensionError::PermissionDenied { .. })
+    ));
+}
@@ -0,0 +1,146 @@
+// src-tauri/src/extension/permissions/tests/diff_tests.rs
+
+use crate::extension::permissions::diff::diff_permissions;
+use crate::extension::permissions::types::{
+    Action, DbAction, DbConstraints, ExtensionPermission, PermissionConstraints, PermissionStatus,
+    ResourceType, WebAction,
+};
+
+fn db_permission(action: DbAction, target: &str, status: PermissionStatus) -> ExtensionPermission {
+    ExtensionPermission {
+        id: uuid::Uuid::new_v4().to_string(),
+        extension_id: "test_ext".to_string(),
+        resource_type: ResourceType::Db,
+        action: Action::Database(action),
+        target: target.to_string(),
+        constraints: None,
+        status,
+    }
+}
+
+fn web_permission(target: &str) -> ExtensionPermission {
+    ExtensionPermission {
+        id: uuid::Uuid::new_v4().to_string(),
+        extension_id: "test_ext".to_string(),
+        resource_type: ResourceType::Web,
+        action: Action::Web(WebAction::All),
+        target: target.to_string(),
+        constraints: None,
+        status: PermissionStatus::Ask,
+    }
+}
+
+#[test]
+fn test_identical_permissions_produce_empty_diff() {
+    let installed = vec![db_permission(
+        DbAction::Read,
+        "notes",
+        PermissionStatus::Granted,
+    )];
+    let requested = vec![db_permission(
+        DbAction::Read,
+        "notes",
+        PermissionStatus::Ask,
+    )];
+
+    let diff = diff_permissions(&installed, &requested);
+    assert!(
+        diff.is_empty(),
+        "status differences must not count as a change"
+    );
+}
+
+#[test]
+fn test_new_target_is_added() {
+    let installed = vec![db_permission(
+        DbAction::Read,
+        "notes",
+        PermissionStatus::Granted,
+    )];
+    let requested = vec![
+        db_permission(DbAction::Read, "notes", PermissionStatus::Ask),
+        web_permission("https://api.example.com/*"),
+    ];
+
+    let diff = diff_permissions(&installed, &requested);
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.added[0].resource_type, ResourceType::Web);
+    assert_eq!(diff.added[0].target, "https://api.example.com/*");
+    assert!(diff.added[0].previous.is_empty());
+    assert!(diff.removed.is_empty());
+    assert!(diff.changed.is_empty());
+    assert!(diff.requests_new_permissions());
+}
+
+#[test]
+fn test_dropped_target_is_removed() {
+    let installed = vec![
+        db_permission(DbAction::Read, "notes", PermissionStatus::Granted),
+        web_permission("https://api.example.com/*"),
+    ];
+    let requested = vec![db_permission(
+        DbAction::Read,
+        "notes",
+        PermissionStatus::Ask,
+    )];
+
+    let diff = diff_permissions(&installed, &requested);
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].target, "https://api.example.com/*");
+    assert!(diff.removed[0].requested.is_empty());
+    assert!(!diff.requests_new_permissions());
+}
+
+#[test]
+fn test_broader_action_is_changed() {
+    let installed = vec![db_permission(
+        DbAction::Read,
+        "notes",
+        PermissionStatus::Granted,
+    )];
+    let requested = vec![db_permission(
+        DbAction::ReadWrite,
+        "notes",
+        PermissionStatus::Ask,
+    )];
+
+    let diff = diff_permissions(&installed, &requested);
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].previous[0].action, "read");
+    assert_eq!(diff.changed[0].requested[0].action, "readWrite");
+    assert!(diff.added.is_empty());
+    assert!(diff.removed.is_empty());
+}
+
+#[test]
+fn test_constraint_change_is_changed() {
+    let installed = vec![db_permission(
+        DbAction::Read,
+        "notes",
+        PermissionStatus::Granted,
+    )];
+    let mut constrained = db_permission(DbAction::Read, "notes", PermissionStatus::Ask);
+    constrained.constraints = Some(PermissionConstraints::Database(DbConstraints {
+        limit: Some(10),
+        ..Default::default()
+    }));
+
+    let diff = diff_permissions(&installed, &[constrained]);
+    assert_eq!(diff.changed.len(), 1);
+    assert!(diff.changed[0].previous[0].constraints.is_none());
+    assert!(diff.changed[0].requested[0].constraints.is_some());
+}
+
+#[test]
+fn test_request_order_does_not_matter() {
+    let installed = vec![
+        db_permission(DbAction::Read, "notes", PermissionStatus::Granted),
+        db_permission(DbAction::Delete, "notes", PermissionStatus::Granted),
+    ];
+    let requested = vec![
+        db_permission(DbAction::Delete, "notes", PermissionStatus::Ask),
+        db_permission(DbAction::Read, "notes", PermissionStatus::Ask),
+    ];
+
+    assert!(diff_permissions(&installed, &requested).is_empty());
+}
@@ -1,9 +1,13 @@
 // src-tauri/src/extension/permissions/tests/mod.rs
 // Test modules for extension permission system
 
+#[cfg(test)]
+mod broker_tests;
 #[cfg(test)]
 mod checker_tests;
 #[cfg(test)]
+mod diff_tests;
+#[cfg(test)]
 mod path_traversal_tests;
 #[cfg(test)]
 mod permission_enforcement_tests;
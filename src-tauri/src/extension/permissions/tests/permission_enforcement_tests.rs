@@ -7,7 +7,10 @@
 
 use crate::extension::core::manifest::{DisplayMode, ExtensionManifest, ExtensionPermissions};
 use crate::extension::core::types::{Extension, ExtensionSource};
-use crate::extension::permissions::checker::{is_system_table, matches_target, PermissionChecker};
+use crate::extension::permissions::checker::{
+    is_secret_column, is_system_table, matches_target, split_column_target, ColumnSet,
+    PermissionChecker,
+};
 use crate::extension::permissions::types::{
     Action, DbAction, ExtensionPermission, FsAction, PermissionStatus, ResourceType, WebAction, FileSyncAction,
 };
@@ -38,6 +41,11 @@ fn create_extension(public_key: &str, name: &str) -> Extension {
                 identities: None,
                 passwords: None,
                 mail: None,
+                camera: None,
+                contacts: None,
+                calendar: None,
+                microphone: None,
+                speech: None,
             },
             homepage: None,
             description: None,
@@ -45,6 +53,12 @@ fn create_extension(public_key: &str, name: &str) -> Extension {
             display_mode: Some(DisplayMode::Iframe),
             migrations_dir: None,
             i18n: None,
+            key_rotations: None,
+            locales: None,
+            share_target: None,
+            events: None,
+            min_host_version: None,
+            max_host_version: None,
         },
         source: ExtensionSource::Production {
             path: PathBuf::from("/tmp/test"),
@@ -371,6 +385,113 @@ fn test_matches_target_does_not_match_system_tables() {
     }
 }
 
+// ============================================================================
+// Column Permission Tests
+// ============================================================================
+
+fn columns(names: &[&str]) -> Vec<String> {
+    names.iter().map(|n| n.to_string()).collect()
+}
+
+#[test]
+fn test_split_column_target() {
+    assert_eq!(split_column_target("vault__items"), ("vault__items", ColumnSet::All));
+    assert_eq!(split_column_target("vault__items.*"), ("vault__items", ColumnSet::All));
+    assert_eq!(
+        split_column_target("vault__items.notes"),
+        ("vault__items", ColumnSet::Named(columns(&["notes"])))
+    );
+    assert_eq!(
+        split_column_target("vault__*.{title, notes}"),
+        ("vault__*", ColumnSet::Named(columns(&["title", "notes"])))
+    );
+}
+
+#[test]
+fn test_matches_target_ignores_column_suffix() {
+    assert!(matches_target("vault__items.notes", "vault__items"));
+    assert!(matches_target("vault__*.{title,notes}", "vault__items"));
+    assert!(!matches_target("vault__items.notes", "vault__other"));
+    assert!(!matches_target("*.password", "haex_passwords"));
+}
+
+#[test]
+fn test_secret_columns() {
+    assert!(is_secret_column("password"));
+    assert!(is_secret_column("totp_secret"));
+    assert!(is_secret_column("\"refresh_token\""));
+    assert!(!is_secret_column("notes"));
+    assert!(!is_secret_column("password_hint_shown"));
+}
+
+#[test]
+fn test_table_wide_grant_excludes_secret_columns() {
+    let extension = create_extension("pubkey", "myext");
+    let permissions = vec![create_db_permission(
+        &extension.id,
+        DbAction::Read,
+        "vault__items",
+        PermissionStatus::Granted,
+    )];
+    let checker = PermissionChecker::new(extension, permissions);
+
+    let uncovered = checker.uncovered_columns(
+        "vault__items",
+        DbAction::Read,
+        &columns(&["title", "notes", "password"]),
+    );
+    assert_eq!(uncovered, columns(&["password"]));
+}
+
+#[test]
+fn test_column_grant_covers_only_named_columns() {
+    let extension = create_extension("pubkey", "myext");
+    let permissions = vec![
+        create_db_permission(&extension.id, DbAction::Read, "vault__items.notes", PermissionStatus::Granted),
+        create_db_permission(&extension.id, DbAction::Read, "vault__items.password", PermissionStatus::Granted),
+    ];
+    let checker = PermissionChecker::new(extension, permissions);
+
+    let uncovered = checker.uncovered_columns(
+        "vault__items",
+        DbAction::Read,
+        &columns(&["notes", "password", "title"]),
+    );
+    assert_eq!(uncovered, columns(&["title"]));
+
+    // Read grants don't cover writes
+    let uncovered = checker.uncovered_columns("vault__items", DbAction::ReadWrite, &columns(&["notes"]));
+    assert_eq!(uncovered, columns(&["notes"]));
+}
+
+#[test]
+fn test_own_table_columns_always_covered() {
+    let extension = create_extension("pubkey", "myext");
+    let checker = PermissionChecker::new(extension, vec![]);
+
+    let uncovered = checker.uncovered_columns(
+        "pubkey__myext__accounts",
+        DbAction::ReadWrite,
+        &columns(&["id", "password", "api_key"]),
+    );
+    assert!(uncovered.is_empty());
+}
+
+#[test]
+fn test_column_denial() {
+    let extension = create_extension("pubkey", "myext");
+    let permissions = vec![
+        create_db_permission(&extension.id, DbAction::Read, "vault__items", PermissionStatus::Granted),
+        create_db_permission(&extension.id, DbAction::Read, "vault__items.password", PermissionStatus::Denied),
+    ];
+    let checker = PermissionChecker::new(extension, permissions);
+
+    assert!(checker.is_column_denied("vault__items", DbAction::Read, "password"));
+    assert!(!checker.is_column_denied("vault__items", DbAction::Read, "notes"));
+    assert!(!checker.is_table_wide_target("vault__items.password"));
+    assert!(checker.is_table_wide_target("vault__items.*"));
+}
+
 // ============================================================================
 // Permission Status Tests
 // ============================================================================
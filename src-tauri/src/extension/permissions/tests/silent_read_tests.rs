@@ -49,6 +49,11 @@ fn make_extension() -> Extension {
                 identities: None,
                 passwords: None,
                 mail: None,
+                camera: None,
+                contacts: None,
+                calendar: None,
+                microphone: None,
+                speech: None,
             },
             homepage: None,
             description: None,
@@ -56,6 +61,12 @@ fn make_extension() -> Extension {
             display_mode: Some(DisplayMode::Iframe),
             migrations_dir: None,
             i18n: None,
+            key_rotations: None,
+            locales: None,
+            share_target: None,
+            events: None,
+            min_host_version: None,
+            max_host_version: None,
         },
         source: ExtensionSource::Production {
             path: PathBuf::from("/tmp/test"),
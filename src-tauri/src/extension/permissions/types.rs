@@ -346,6 +346,185 @@ impl FromStr for MailAction {
     }
 }
 
+/// Aktionen auf der Kamera des Geräts.
+///
+/// Die Aufnahme läuft immer im Hauptfenster (Extension-Webviews haben unter
+/// dem Custom-Protocol keinen verlässlichen `getUserMedia`-Zugriff); die
+/// Extension bekommt nur das Ergebnis. `target` ist immer "*".
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum CameraAction {
+    /// QR-/Barcodes scannen, die Extension erhält nur den dekodierten Text
+    Scan,
+    /// Fotos aufnehmen, abgelegt im privaten Verzeichnis der Extension
+    Capture,
+}
+
+impl CameraAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CameraAction::Scan => "scan",
+            CameraAction::Capture => "capture",
+        }
+    }
+}
+
+impl FromStr for CameraAction {
+    type Err = ExtensionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "scan" => Ok(CameraAction::Scan),
+            "capture" => Ok(CameraAction::Capture),
+            _ => Err(ExtensionError::InvalidActionString {
+                input: s.to_string(),
+                resource_type: "camera".to_string(),
+            }),
+        }
+    }
+}
+
+/// Lesezugriff auf die Kontakte des Betriebssystems.
+///
+/// Die Extension bekommt normalisierte Einträge (`system_pim`), nie Zugriff auf
+/// die OS-APIs selbst. `target` ist immer "*".
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum ContactsAction {
+    /// Kontakte auflisten und durchsuchen
+    Read,
+}
+
+impl ContactsAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContactsAction::Read => "read",
+        }
+    }
+}
+
+impl FromStr for ContactsAction {
+    type Err = ExtensionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "read" => Ok(ContactsAction::Read),
+            _ => Err(ExtensionError::InvalidActionString {
+                input: s.to_string(),
+                resource_type: "contacts".to_string(),
+            }),
+        }
+    }
+}
+
+/// Lesezugriff auf die Kalender des Betriebssystems.
+///
+/// Die Extension bekommt normalisierte Termine (`system_pim`), nie Zugriff auf
+/// die OS-APIs selbst. `target` ist immer "*".
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum CalendarAction {
+    /// Kalender und Termine eines Zeitraums auflisten
+    Read,
+}
+
+impl CalendarAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CalendarAction::Read => "read",
+        }
+    }
+}
+
+impl FromStr for CalendarAction {
+    type Err = ExtensionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "read" => Ok(CalendarAction::Read),
+            _ => Err(ExtensionError::InvalidActionString {
+                input: s.to_string(),
+                resource_type: "calendar".to_string(),
+            }),
+        }
+    }
+}
+
+/// Aktionen auf dem Mikrofon des Geräts.
+///
+/// Wie bei der Kamera nimmt das Hauptfenster auf; die Extension bekommt nur die
+/// fertige Datei in ihrem privaten Verzeichnis. `target` ist immer "*".
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum MicrophoneAction {
+    /// Audio aufnehmen
+    Record,
+}
+
+impl MicrophoneAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MicrophoneAction::Record => "record",
+        }
+    }
+}
+
+impl FromStr for MicrophoneAction {
+    type Err = ExtensionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "record" => Ok(MicrophoneAction::Record),
+            _ => Err(ExtensionError::InvalidActionString {
+                input: s.to_string(),
+                resource_type: "microphone".to_string(),
+            }),
+        }
+    }
+}
+
+/// Aktionen auf der Sprachausgabe und -erkennung des Betriebssystems.
+///
+/// Die Extension bekommt nur gesprochenen Text bzw. das Transkript, nie Zugriff
+/// auf die Engines selbst. `target` ist immer "*".
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum SpeechAction {
+    /// Text vorlesen lassen
+    Speak,
+    /// Audiodateien transkribieren; der Pfad braucht zusätzlich Lesezugriff
+    Transcribe,
+}
+
+impl SpeechAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SpeechAction::Speak => "speak",
+            SpeechAction::Transcribe => "transcribe",
+        }
+    }
+}
+
+impl FromStr for SpeechAction {
+    type Err = ExtensionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "speak" => Ok(SpeechAction::Speak),
+            "transcribe" => Ok(SpeechAction::Transcribe),
+            _ => Err(ExtensionError::InvalidActionString {
+                input: s.to_string(),
+                resource_type: "speech".to_string(),
+            }),
+        }
+    }
+}
+
 /// Aktionen auf dem Core-Passworttresor.
 ///
 /// Scope wird über `ExtensionPermission.target` als Tag-Filter gesteuert
@@ -414,6 +593,11 @@ pub enum Action {
     Identities(IdentityAction),
     Passwords(PasswordsAction),
     Mail(MailAction),
+    Camera(CameraAction),
+    Contacts(ContactsAction),
+    Calendar(CalendarAction),
+    Microphone(MicrophoneAction),
+    Speech(SpeechAction),
 }
 
 /// Die interne Repräsentation einer einzelnen, gewährten Berechtigung.
@@ -444,6 +628,11 @@ pub enum ResourceType {
     Identities,
     Passwords,
     Mail,
+    Camera,
+    Contacts,
+    Calendar,
+    Microphone,
+    Speech,
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, TS)]
@@ -530,6 +719,11 @@ impl ResourceType {
             ResourceType::Identities => "identities",
             ResourceType::Passwords => "passwords",
             ResourceType::Mail => "mail",
+            ResourceType::Camera => "camera",
+            ResourceType::Contacts => "contacts",
+            ResourceType::Calendar => "calendar",
+            ResourceType::Microphone => "microphone",
+            ResourceType::Speech => "speech",
         }
     }
 
@@ -544,6 +738,11 @@ impl ResourceType {
             "identities" => Ok(ResourceType::Identities),
             "passwords" => Ok(ResourceType::Passwords),
             "mail" => Ok(ResourceType::Mail),
+            "camera" => Ok(ResourceType::Camera),
+            "contacts" => Ok(ResourceType::Contacts),
+            "calendar" => Ok(ResourceType::Calendar),
+            "microphone" => Ok(ResourceType::Microphone),
+            "speech" => Ok(ResourceType::Speech),
             _ => Err(ExtensionError::ValidationError {
                 reason: format!("Unknown resource type: {s}"),
             }),
@@ -590,6 +789,26 @@ impl Action {
                 .unwrap_or_default()
                 .trim_matches('"')
                 .to_string(),
+            Action::Camera(action) => serde_json::to_string(action)
+                .unwrap_or_default()
+                .trim_matches('"')
+                .to_string(),
+            Action::Contacts(action) => serde_json::to_string(action)
+                .unwrap_or_default()
+                .trim_matches('"')
+                .to_string(),
+            Action::Calendar(action) => serde_json::to_string(action)
+                .unwrap_or_default()
+                .trim_matches('"')
+                .to_string(),
+            Action::Microphone(action) => serde_json::to_string(action)
+                .unwrap_or_default()
+                .trim_matches('"')
+                .to_string(),
+            Action::Speech(action) => serde_json::to_string(action)
+                .unwrap_or_default()
+                .trim_matches('"')
+                .to_string(),
         }
     }
 
@@ -613,6 +832,11 @@ impl Action {
             ResourceType::Identities => Ok(Action::Identities(IdentityAction::from_str(s)?)),
             ResourceType::Passwords => Ok(Action::Passwords(PasswordsAction::from_str(s)?)),
             ResourceType::Mail => Ok(Action::Mail(MailAction::from_str(s)?)),
+            ResourceType::Camera => Ok(Action::Camera(CameraAction::from_str(s)?)),
+            ResourceType::Contacts => Ok(Action::Contacts(ContactsAction::from_str(s)?)),
+            ResourceType::Calendar => Ok(Action::Calendar(CalendarAction::from_str(s)?)),
+            ResourceType::Microphone => Ok(Action::Microphone(MicrophoneAction::from_str(s)?)),
+            ResourceType::Speech => Ok(Action::Speech(SpeechAction::from_str(s)?)),
         }
     }
 }
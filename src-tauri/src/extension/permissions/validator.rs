@@ -2,13 +2,17 @@
 
 use crate::database::core::{
     extract_table_names_from_sql, extract_table_names_from_statement, parse_single_statement,
+    resolve_view_tables, with_connection,
 };
 use crate::database::error::DatabaseError;
+use crate::extension::database::planner::SqlExecutionPlanner;
 use crate::extension::error::ExtensionError;
 use crate::extension::permissions::manager::PermissionManager;
-use crate::extension::permissions::types::Action;
+use crate::extension::permissions::types::{Action, DbAction};
 use crate::AppState;
+use rusqlite::Connection;
 use sqlparser::ast::Statement;
+use std::collections::HashMap;
 use tauri::State;
 
 pub struct SqlPermissionValidator;
@@ -28,10 +32,13 @@ impl SqlPermissionValidator {
 
         match &statement {
             Statement::Query(_) => {
-                Self::validate_read_statement(app_state, extension_id, sql).await
+                Self::validate_read_statement(app_state, extension_id, sql).await?;
+                Self::validate_columns(app_state, extension_id, &statement, DbAction::Read).await
             }
             Statement::Insert(_) | Statement::Update { .. } | Statement::Delete(_) => {
-                Self::validate_write_statement(app_state, extension_id, &statement).await
+                Self::validate_write_statement(app_state, extension_id, &statement).await?;
+                Self::validate_columns(app_state, extension_id, &statement, DbAction::ReadWrite)
+                    .await
             }
             // Schema modification statements (CREATE TABLE, ALTER TABLE, DROP) are NOT allowed
             // through regular SQL execution. They can only be executed during:
@@ -54,7 +61,8 @@ impl SqlPermissionValidator {
         extension_id: &str,
         sql: &str,
     ) -> Result<(), ExtensionError> {
-        let tables = extract_table_names_from_sql(sql)?;
+        let mut tables = extract_table_names_from_sql(sql)?;
+        tables.extend(Self::view_source_tables(app_state, &tables)?);
 
         for table_name in tables {
             PermissionManager::check_database_permission(
@@ -87,6 +95,18 @@ impl SqlPermissionValidator {
             .await?;
         }
 
+        // Views read in INSERT ... SELECT or subqueries only need read access
+        // to the tables behind them.
+        for table_name in Self::view_source_tables(app_state, &table_names)? {
+            PermissionManager::check_database_permission(
+                app_state,
+                extension_id,
+                Action::Database(super::types::DbAction::Read),
+                &table_name,
+            )
+            .await?;
+        }
+
         Ok(())
     }
 
@@ -186,8 +206,75 @@ impl SqlPermissionValidator {
         Ok(())
     }
 
+    /// Validiert die Spalten, die ein Statement liest oder schreibt
+    /// Runs after the table checks. Tables read through a view are checked
+    /// with all their columns, since view columns can't be traced back.
+    async fn validate_columns(
+        app_state: &State<'_, AppState>,
+        extension_id: &str,
+        statement: &Statement,
+        action: DbAction,
+    ) -> Result<(), ExtensionError> {
+        let references = SqlExecutionPlanner::column_references(statement);
+        let tables = references.table_names();
+        let view_sources = Self::view_source_tables(app_state, &tables)?;
+
+        let schema = with_connection(&app_state.db, |conn| {
+            let mut schema = HashMap::new();
+            for table in tables.iter().chain(&view_sources) {
+                schema.insert(table.clone(), table_columns(conn, table)?);
+            }
+            Ok(schema)
+        })?;
+
+        let mut columns = references.resolve(&schema);
+        for source in &view_sources {
+            if let Some(source_columns) = schema.get(source) {
+                columns
+                    .entry(source.clone())
+                    .or_default()
+                    .extend(source_columns.iter().cloned());
+            }
+        }
+
+        for (table_name, table_columns) in columns {
+            let table_columns: Vec<String> = table_columns.into_iter().collect();
+            PermissionManager::check_database_columns(
+                app_state,
+                extension_id,
+                Action::Database(action),
+                &table_name,
+                &table_columns,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Tables behind the views among `table_names`. A view named with the
+    /// extension's prefix is auto-allowed, so the tables it reads from have to
+    /// be checked separately.
+    fn view_source_tables(
+        app_state: &State<'_, AppState>,
+        table_names: &[String],
+    ) -> Result<Vec<String>, ExtensionError> {
+        Ok(with_connection(&app_state.db, |conn| {
+            resolve_view_tables(conn, table_names)
+        })?)
+    }
+
     /// Delegates to core::extract_table_names_from_statement for full recursive extraction
     fn extract_table_names(statement: &Statement) -> Vec<String> {
         extract_table_names_from_statement(statement)
     }
 }
+
+/// Column names of a table or view (empty if it doesn't exist)
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, DatabaseError> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1)")?;
+    let columns = stmt
+        .query_map([table], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(columns)
+}
@@ -0,0 +1,148 @@
+// src-tauri/src/extension/recovery/commands.rs
+
+use super::{
+    newly_missing, validate_bundle, verify_sha256, MissingExtensionsDetected, RecoverySource,
+};
+use crate::extension::core::manager::{ExtensionManager, MissingExtension};
+use crate::extension::core::ExtensionFilesInstallResult;
+use crate::extension::error::ExtensionError;
+use crate::AppState;
+use std::time::Duration;
+use tauri::{AppHandle, State};
+use tauri_plugin_http::reqwest;
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+fn missing_extensions(state: &AppState) -> Result<Vec<MissingExtension>, ExtensionError> {
+    Ok(state
+        .extension_manager
+        .missing_extensions
+        .lock()
+        .map_err(|e| ExtensionError::MutexPoisoned {
+            reason: e.to_string(),
+        })?
+        .clone())
+}
+
+/// Ids of the extensions currently recorded as missing
+pub(crate) fn missing_extension_ids(state: &AppState) -> Result<Vec<String>, ExtensionError> {
+    Ok(missing_extensions(state)?
+        .into_iter()
+        .map(|missing| missing.id)
+        .collect())
+}
+
+/// Emits `extension:missing-detected` for extensions missing since
+/// `previous_ids` was taken.
+pub(crate) fn announce_newly_missing(
+    app_handle: &AppHandle,
+    state: &AppState,
+    previous_ids: &[String],
+) -> Result<(), ExtensionError> {
+    let extensions = newly_missing(previous_ids, &missing_extensions(state)?);
+    if extensions.is_empty() {
+        return Ok(());
+    }
+    eprintln!(
+        "WARNING: {} extension(s) registered in the vault have no files on this device",
+        extensions.len()
+    );
+    if let Err(e) =
+        crate::events::emit_to_main(app_handle, &MissingExtensionsDetected { extensions })
+    {
+        eprintln!("Failed to emit missing extensions event: {e}");
+    }
+    Ok(())
+}
+
+async fn download_bundle(url: &str, sha256: Option<&str>) -> Result<Vec<u8>, ExtensionError> {
+    let client = reqwest::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| ExtensionError::Http {
+            reason: format!("Failed to create HTTP client: {e}"),
+        })?;
+    let bytes = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| ExtensionError::Http {
+            reason: format!("Failed to download extension bundle: {e}"),
+        })?
+        .bytes()
+        .await
+        .map_err(|e| ExtensionError::Http {
+            reason: format!("Failed to read extension bundle: {e}"),
+        })?;
+
+    if let Some(expected) = sha256 {
+        verify_sha256(&bytes, expected)?;
+    }
+    Ok(bytes.to_vec())
+}
+
+/// Extensions registered in the vault whose files are missing on this device
+#[tauri::command]
+pub fn get_missing_extensions(
+    state: State<'_, AppState>,
+) -> Result<Vec<MissingExtension>, ExtensionError> {
+    missing_extensions(&state)
+}
+
+/// Reinstalls the files of a missing extension.
+///
+/// The bundle must match the registered name, version and public key; its
+/// signature is verified like on every install. Permissions are left as
+/// they are registered in the vault.
+#[tauri::command]
+pub async fn recover_missing_extension(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    extension_id: String,
+    source: RecoverySource,
+) -> Result<ExtensionFilesInstallResult, ExtensionError> {
+    let missing = missing_extensions(&state)?
+        .into_iter()
+        .find(|missing| missing.id == extension_id)
+        .ok_or_else(|| ExtensionError::ValidationError {
+            reason: format!("Extension {extension_id} is not missing on this device"),
+        })?;
+
+    let file_bytes = match source {
+        RecoverySource::File { file_bytes } => file_bytes,
+        RecoverySource::Registry {
+            download_url,
+            sha256,
+        } => download_bundle(&download_url, sha256.as_deref()).await?,
+    };
+
+    {
+        let extracted = ExtensionManager::extract_and_validate_extension(
+            file_bytes.clone(),
+            "haexspace_recover",
+            &app_handle,
+        )?;
+        validate_bundle(&missing, &extracted.manifest)?;
+    }
+
+    let result = state
+        .extension_manager
+        .install_extension_files_from_bytes(&app_handle, file_bytes, &extension_id, false, &state)
+        .await?;
+
+    state
+        .extension_manager
+        .missing_extensions
+        .lock()
+        .map_err(|e| ExtensionError::MutexPoisoned {
+            reason: e.to_string(),
+        })?
+        .retain(|entry| entry.id != extension_id);
+
+    eprintln!(
+        "Recovered missing extension {extension_id} ({} {})",
+        missing.name, missing.version
+    );
+    Ok(result)
+}
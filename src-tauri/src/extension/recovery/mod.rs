@@ -0,0 +1,118 @@
+// src-tauri/src/extension/recovery/mod.rs
+//!
+//! Missing extension recovery
+//!
+//! Extensions registered in the vault but without files on this device
+//! (typically installed on another device and synced) are recorded as
+//! missing by the loader. `recover_missing_extension` reinstalls their files
+//! from a bundle the user picked or from a registry download URL.
+//!
+//! The bundle must be the registered extension: same name and version,
+//! signed by its public key or by a key it was rotated to. The rotation
+//! chain itself is verified by the installer against the trusted key.
+//!
+//! When a reload after sync finds extensions that were not missing before,
+//! `extension:missing-detected` announces them to the main window.
+
+pub mod commands;
+
+#[cfg(test)]
+mod tests;
+
+use crate::extension::core::manager::MissingExtension;
+use crate::extension::core::manifest::ExtensionManifest;
+use crate::extension::error::ExtensionError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ts_rs::TS;
+
+/// Where the files of a missing extension come from
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RecoverySource {
+    /// Bundle (`.haextension`) picked by the user
+    #[serde(rename_all = "camelCase")]
+    File { file_bytes: Vec<u8> },
+    /// Bundle downloaded from a registry, optionally checked against the
+    /// hex SHA-256 the registry lists for it
+    #[serde(rename_all = "camelCase")]
+    Registry {
+        download_url: String,
+        sha256: Option<String>,
+    },
+}
+
+/// Payload of `extension:missing-detected`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingExtensionsDetected {
+    /// Extensions missing since the last load
+    pub extensions: Vec<MissingExtension>,
+}
+
+/// Checks that `manifest` belongs to the registered extension `missing`
+pub fn validate_bundle(
+    missing: &MissingExtension,
+    manifest: &ExtensionManifest,
+) -> Result<(), ExtensionError> {
+    if manifest.name != missing.name {
+        return Err(ExtensionError::ValidationError {
+            reason: format!(
+                "Bundle contains '{}', expected '{}'",
+                manifest.name, missing.name
+            ),
+        });
+    }
+    if manifest.version != missing.version {
+        return Err(ExtensionError::ValidationError {
+            reason: format!(
+                "Bundle has version {}, expected {} as registered in the vault",
+                manifest.version, missing.version
+            ),
+        });
+    }
+
+    let same_key = manifest
+        .public_key
+        .eq_ignore_ascii_case(&missing.public_key);
+    let rotated_to_key = manifest
+        .key_rotations
+        .as_deref()
+        .and_then(|chain| chain.last())
+        .is_some_and(|last| last.public_key.eq_ignore_ascii_case(&manifest.public_key));
+    if !same_key && !rotated_to_key {
+        return Err(ExtensionError::ValidationError {
+            reason: format!(
+                "Bundle of '{}' is signed by another public key",
+                manifest.name
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Checks `bytes` against a hex SHA-256 digest
+pub fn verify_sha256(bytes: &[u8], expected: &str) -> Result<(), ExtensionError> {
+    let actual = hex::encode(Sha256::digest(bytes));
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(ExtensionError::ValidationError {
+            reason: format!("Bundle hash mismatch: expected {expected}, got {actual}"),
+        })
+    }
+}
+
+/// Entries of `current` whose id is not in `previous_ids`
+pub fn newly_missing(
+    previous_ids: &[String],
+    current: &[MissingExtension],
+) -> Vec<MissingExtension> {
+    current
+        .iter()
+        .filter(|missing| !previous_ids.contains(&missing.id))
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,105 @@
+// src-tauri/src/extension/recovery/tests.rs
+
+use super::{newly_missing, validate_bundle, verify_sha256, RecoverySource};
+use crate::extension::core::manager::MissingExtension;
+use crate::extension::core::manifest::{ExtensionManifest, ExtensionPermissions, KeyRotationProof};
+
+fn missing(id: &str) -> MissingExtension {
+    MissingExtension {
+        id: id.to_string(),
+        public_key: "aa11".to_string(),
+        name: "notes".to_string(),
+        version: "1.2.0".to_string(),
+    }
+}
+
+fn manifest(name: &str, version: &str, public_key: &str) -> ExtensionManifest {
+    ExtensionManifest {
+        name: name.to_string(),
+        version: version.to_string(),
+        author: None,
+        entry: Some("index.html".to_string()),
+        icon: None,
+        public_key: public_key.to_string(),
+        signature: "sig".to_string(),
+        permissions: ExtensionPermissions::default(),
+        homepage: None,
+        description: None,
+        single_instance: None,
+        display_mode: None,
+        migrations_dir: None,
+        i18n: None,
+        key_rotations: None,
+        locales: None,
+        share_target: None,
+        events: None,
+        min_host_version: None,
+        max_host_version: None,
+    }
+}
+
+#[test]
+fn test_validate_bundle_accepts_registered_extension() {
+    assert!(validate_bundle(&missing("a"), &manifest("notes", "1.2.0", "aa11")).is_ok());
+    // Keys are hex, case does not matter
+    assert!(validate_bundle(&missing("a"), &manifest("notes", "1.2.0", "AA11")).is_ok());
+}
+
+#[test]
+fn test_validate_bundle_rejects_other_extension() {
+    assert!(validate_bundle(&missing("a"), &manifest("tasks", "1.2.0", "aa11")).is_err());
+    assert!(validate_bundle(&missing("a"), &manifest("notes", "1.3.0", "aa11")).is_err());
+    assert!(validate_bundle(&missing("a"), &manifest("notes", "1.2.0", "bb22")).is_err());
+}
+
+#[test]
+fn test_validate_bundle_accepts_rotated_key() {
+    let mut rotated = manifest("notes", "1.2.0", "bb22");
+    rotated.key_rotations = Some(vec![KeyRotationProof {
+        previous_public_key: "aa11".to_string(),
+        public_key: "bb22".to_string(),
+        signature: "proof".to_string(),
+    }]);
+    assert!(validate_bundle(&missing("a"), &rotated).is_ok());
+
+    // The chain has to end at the signing key
+    rotated.public_key = "cc33".to_string();
+    assert!(validate_bundle(&missing("a"), &rotated).is_err());
+
+    rotated.key_rotations = Some(Vec::new());
+    assert!(validate_bundle(&missing("a"), &rotated).is_err());
+}
+
+#[test]
+fn test_verify_sha256() {
+    let digest = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    assert!(verify_sha256(b"abc", digest).is_ok());
+    assert!(verify_sha256(b"abc", &digest.to_uppercase()).is_ok());
+    assert!(verify_sha256(b"abd", digest).is_err());
+}
+
+#[test]
+fn test_newly_missing() {
+    let current = vec![missing("a"), missing("b")];
+    let new = newly_missing(&["a".to_string()], &current);
+    assert_eq!(new.len(), 1);
+    assert_eq!(new[0].id, "b");
+    assert!(newly_missing(&["a".to_string(), "b".to_string()], &current).is_empty());
+}
+
+#[test]
+fn test_recovery_source_deserializes() {
+    let source: RecoverySource = serde_json::from_str(
+        r#"{"type":"registry","downloadUrl":"https://example.com/notes.haextension","sha256":null}"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        source,
+        RecoverySource::Registry { ref download_url, sha256: None }
+            if download_url == "https://example.com/notes.haextension"
+    ));
+
+    let source: RecoverySource =
+        serde_json::from_str(r#"{"type":"file","fileBytes":[1,2,3]}"#).unwrap();
+    assert!(matches!(source, RecoverySource::File { ref file_bytes } if file_bytes == &[1, 2, 3]));
+}
@@ -0,0 +1,156 @@
+// src-tauri/src/extension/revocation/commands.rs
+
+use crate::extension::core::types::ExtensionSource;
+use crate::extension::error::ExtensionError;
+use crate::extension::revocation::store::RevocationStore;
+use crate::extension::revocation::types::{RevocationList, RevocationReport, RevokedExtension};
+use crate::AppState;
+use std::time::Duration;
+use tauri::{AppHandle, State};
+use tauri_plugin_http::reqwest;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+async fn fetch_revocation_list(url: &str) -> Result<String, ExtensionError> {
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| ExtensionError::Http {
+            reason: format!("Failed to create HTTP client: {e}"),
+        })?;
+    client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| ExtensionError::Http {
+            reason: format!("Failed to fetch revocation list: {e}"),
+        })?
+        .text()
+        .await
+        .map_err(|e| ExtensionError::Http {
+            reason: format!("Failed to read revocation list: {e}"),
+        })
+}
+
+/// Unloads loaded production extensions revoked by `store` (unless
+/// `allow_revoked`) and records every match in `revoked_extensions`.
+fn apply_revocations(state: &AppState, store: &RevocationStore) -> Result<(), ExtensionError> {
+    let manager = &state.extension_manager;
+    let mut available =
+        manager
+            .available_extensions
+            .lock()
+            .map_err(|e| ExtensionError::MutexPoisoned {
+                reason: e.to_string(),
+            })?;
+    let mut revoked =
+        manager
+            .revoked_extensions
+            .lock()
+            .map_err(|e| ExtensionError::MutexPoisoned {
+                reason: e.to_string(),
+            })?;
+
+    let matched: Vec<(String, Option<String>)> = available
+        .values()
+        .filter(|ext| matches!(ext.source, ExtensionSource::Production { .. }))
+        .filter_map(|ext| {
+            let manifest = &ext.manifest;
+            store
+                .revocation_for(&manifest.public_key, &manifest.name, &manifest.version)
+                .map(|entry| (ext.id.clone(), entry.reason.clone()))
+        })
+        .collect();
+
+    for (id, reason) in matched {
+        let extension = if store.allow_revoked {
+            available.get(&id).cloned()
+        } else {
+            available.remove(&id)
+        };
+        let Some(extension) = extension else {
+            continue;
+        };
+        eprintln!(
+            "WARNING: Extension {id} ({} {}) is revoked",
+            extension.manifest.name, extension.manifest.version
+        );
+        revoked.retain(|r| r.id != id);
+        revoked.push(RevokedExtension {
+            id,
+            name: extension.manifest.name,
+            public_key: extension.manifest.public_key,
+            version: extension.manifest.version,
+            reason,
+            loaded: store.allow_revoked,
+        });
+    }
+    Ok(())
+}
+
+fn report(state: &AppState, store: &RevocationStore) -> Result<RevocationReport, ExtensionError> {
+    let revoked = state
+        .extension_manager
+        .revoked_extensions
+        .lock()
+        .map_err(|e| ExtensionError::MutexPoisoned {
+            reason: e.to_string(),
+        })?
+        .clone();
+    Ok(RevocationReport {
+        issued_at: store.list.as_ref().map(|list| list.issued_at),
+        registry_public_key: store.registry_public_key.clone(),
+        allow_revoked: store.allow_revoked,
+        revoked,
+    })
+}
+
+/// Updates the local revocation list and applies it to the loaded extensions.
+///
+/// The list is fetched from `url` or, for air-gapped setups, taken from
+/// `list_json`. `registry_public_key` pins the registry key on first use.
+/// Without either source the stored list is re-applied.
+#[tauri::command]
+pub async fn extension_check_revocations(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    url: Option<String>,
+    list_json: Option<String>,
+    registry_public_key: Option<String>,
+) -> Result<RevocationReport, ExtensionError> {
+    let mut store = RevocationStore::load(&app_handle)?;
+
+    let body = match (list_json, url) {
+        (Some(json), _) => Some(json),
+        (None, Some(url)) => Some(fetch_revocation_list(&url).await?),
+        (None, None) => None,
+    };
+    if let Some(body) = body {
+        let list: RevocationList =
+            serde_json::from_str(&body).map_err(|e| ExtensionError::ValidationError {
+                reason: format!("Invalid revocation list: {e}"),
+            })?;
+        store.accept(list, registry_public_key.as_deref())?;
+        store.save(&app_handle)?;
+    }
+
+    apply_revocations(&state, &store)?;
+    report(&state, &store)
+}
+
+/// Sets whether revoked extensions may still be loaded (with a warning).
+/// Disallowing unloads them right away; allowing takes effect on the next load.
+#[tauri::command]
+pub fn extension_set_revocation_override(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    allow_revoked: bool,
+) -> Result<RevocationReport, ExtensionError> {
+    let mut store = RevocationStore::load(&app_handle)?;
+    store.allow_revoked = allow_revoked;
+    store.save(&app_handle)?;
+
+    apply_revocations(&state, &store)?;
+    report(&state, &store)
+}
@@ -0,0 +1,21 @@
+// src-tauri/src/extension/revocation/mod.rs
+//!
+//! Extension revocation list
+//!
+//! Registries publish a signed list of revoked extension public keys (optionally
+//! limited to names and versions). The list is verified against the pinned
+//! registry key, stored device-locally in `extension_revocations.json` and
+//! consulted by the loader, which refuses to load revoked extensions.
+//!
+//! Air-gapped setups can import a list by hand (`list_json`) or set
+//! `allow_revoked` to keep loading revoked extensions with a warning.
+
+pub mod commands;
+pub mod store;
+pub mod types;
+
+pub use store::RevocationStore;
+pub use types::{RevocationEntry, RevocationList, RevocationReport, RevokedExtension};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,122 @@
+// src-tauri/src/extension/revocation/store.rs
+//
+// Device-local storage of the verified revocation list.
+
+use crate::extension::crypto::ExtensionCrypto;
+use crate::extension::error::ExtensionError;
+use crate::extension::revocation::types::{RevocationEntry, RevocationList};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+const REVOCATION_STORE_FILE: &str = "extension_revocations.json";
+
+/// Verified revocation list plus the local override flag.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RevocationStore {
+    /// Registry key pinned when the first list was accepted
+    #[serde(default)]
+    pub registry_public_key: Option<String>,
+    #[serde(default)]
+    pub list: Option<RevocationList>,
+    /// Load revoked extensions anyway (with a warning)
+    #[serde(default)]
+    pub allow_revoked: bool,
+}
+
+impl RevocationStore {
+    fn path(app_handle: &AppHandle) -> Result<PathBuf, ExtensionError> {
+        let dir =
+            crate::app_profile::data_dir(app_handle).map_err(|e| ExtensionError::Filesystem {
+                source: std::io::Error::new(std::io::ErrorKind::NotFound, e.to_string()),
+            })?;
+        Ok(dir.join(REVOCATION_STORE_FILE))
+    }
+
+    /// Loads the store; a missing file yields an empty store.
+    pub fn load(app_handle: &AppHandle) -> Result<Self, ExtensionError> {
+        let path = Self::path(app_handle)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| ExtensionError::filesystem_with_path(path.display().to_string(), e))?;
+        serde_json::from_str(&content).map_err(|e| ExtensionError::ValidationError {
+            reason: format!("Invalid revocation store: {e}"),
+        })
+    }
+
+    pub fn save(&self, app_handle: &AppHandle) -> Result<(), ExtensionError> {
+        let path = Self::path(app_handle)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                ExtensionError::filesystem_with_path(parent.display().to_string(), e)
+            })?;
+        }
+        let content =
+            serde_json::to_string_pretty(self).map_err(|e| ExtensionError::ValidationError {
+                reason: format!("Cannot serialize revocation store: {e}"),
+            })?;
+        fs::write(&path, content)
+            .map_err(|e| ExtensionError::filesystem_with_path(path.display().to_string(), e))
+    }
+
+    /// Verifies `list` and makes it the stored list.
+    ///
+    /// The first accepted list pins `registry_public_key`; later lists must be
+    /// signed by the pinned key and must not be older than the stored one.
+    pub fn accept(
+        &mut self,
+        list: RevocationList,
+        registry_public_key: Option<&str>,
+    ) -> Result<(), ExtensionError> {
+        let key = match (&self.registry_public_key, registry_public_key) {
+            (Some(pinned), Some(given)) if !pinned.eq_ignore_ascii_case(given) => {
+                return Err(ExtensionError::SecurityViolation {
+                    reason: "Revocation list registry key differs from the pinned key".to_string(),
+                });
+            }
+            (Some(pinned), _) => pinned.clone(),
+            (None, Some(given)) => given.to_lowercase(),
+            (None, None) => {
+                return Err(ExtensionError::ValidationError {
+                    reason: "No registry key to verify the revocation list with".to_string(),
+                });
+            }
+        };
+
+        ExtensionCrypto::verify_message(&key, &list.signing_payload(), &list.signature)
+            .map_err(|reason| ExtensionError::SignatureVerificationFailed { reason })?;
+
+        if let Some(stored) = &self.list {
+            if list.issued_at < stored.issued_at {
+                return Err(ExtensionError::ValidationError {
+                    reason: format!(
+                        "Revocation list from {} is older than the stored list from {}",
+                        list.issued_at, stored.issued_at
+                    ),
+                });
+            }
+        }
+
+        self.registry_public_key = Some(key);
+        self.list = Some(list);
+        Ok(())
+    }
+
+    /// Entry revoking the given extension, if any.
+    pub fn revocation_for(
+        &self,
+        public_key: &str,
+        name: &str,
+        version: &str,
+    ) -> Option<&RevocationEntry> {
+        self.list
+            .as_ref()?
+            .entries
+            .iter()
+            .find(|entry| entry.matches(public_key, name, version))
+    }
+}
@@ -0,0 +1,112 @@
+// src-tauri/src/extension/revocation/tests.rs
+
+use super::store::RevocationStore;
+use super::types::{RevocationEntry, RevocationList};
+use crate::extension::error::ExtensionError;
+use ed25519_dalek::{Signer, SigningKey};
+
+const EXT_KEY: &str = "aa11";
+
+fn registry(seed: u8) -> (SigningKey, String) {
+    let key = SigningKey::from_bytes(&[seed; 32]);
+    let public = hex::encode(key.verifying_key().to_bytes());
+    (key, public)
+}
+
+fn entry(name: Option<&str>, versions: Option<&[&str]>) -> RevocationEntry {
+    RevocationEntry {
+        public_key: EXT_KEY.to_string(),
+        name: name.map(str::to_string),
+        versions: versions.map(|v| v.iter().map(|s| s.to_string()).collect()),
+        reason: Some("compromised".to_string()),
+    }
+}
+
+fn signed_list(key: &SigningKey, issued_at: i64, entries: Vec<RevocationEntry>) -> RevocationList {
+    let mut list = RevocationList {
+        issued_at,
+        entries,
+        signature: String::new(),
+    };
+    list.signature = hex::encode(key.sign(&list.signing_payload()).to_bytes());
+    list
+}
+
+#[test]
+fn test_entry_matching() {
+    let whole_key = entry(None, None);
+    assert!(whole_key.matches(EXT_KEY, "any", "1.0.0"));
+    assert!(whole_key.matches(&EXT_KEY.to_uppercase(), "any", "1.0.0"));
+    assert!(!whole_key.matches("bb22", "any", "1.0.0"));
+
+    let one_version = entry(Some("notes"), Some(&["1.2.0"]));
+    assert!(one_version.matches(EXT_KEY, "notes", "1.2.0"));
+    assert!(!one_version.matches(EXT_KEY, "notes", "1.3.0"));
+    assert!(!one_version.matches(EXT_KEY, "calendar", "1.2.0"));
+}
+
+#[test]
+fn test_first_list_pins_registry_key() {
+    let (key, public) = registry(1);
+    let mut store = RevocationStore::default();
+
+    store
+        .accept(signed_list(&key, 1, vec![entry(None, None)]), Some(&public))
+        .expect("valid list is accepted");
+
+    assert_eq!(store.registry_public_key.as_deref(), Some(public.as_str()));
+    assert!(store.revocation_for(EXT_KEY, "notes", "1.0.0").is_some());
+}
+
+#[test]
+fn test_list_from_other_registry_is_rejected() {
+    let (key, public) = registry(1);
+    let (other, other_public) = registry(2);
+    let mut store = RevocationStore::default();
+    store
+        .accept(signed_list(&key, 1, vec![]), Some(&public))
+        .expect("valid list is accepted");
+
+    // Signed by another key, whether announced or not
+    let result = store.accept(signed_list(&other, 2, vec![]), Some(&other_public));
+    assert!(matches!(
+        result,
+        Err(ExtensionError::SecurityViolation { .. })
+    ));
+    let result = store.accept(signed_list(&other, 2, vec![]), None);
+    assert!(matches!(
+        result,
+        Err(ExtensionError::SignatureVerificationFailed { .. })
+    ));
+}
+
+#[test]
+fn test_tampered_list_is_rejected() {
+    let (key, public) = registry(1);
+    let mut list = signed_list(&key, 1, vec![entry(None, None)]);
+    list.entries.clear();
+
+    let mut store = RevocationStore::default();
+    assert!(store.accept(list, Some(&public)).is_err());
+    assert!(store.list.is_none());
+    assert!(store.registry_public_key.is_none());
+}
+
+#[test]
+fn test_older_list_does_not_replace_newer() {
+    let (key, public) = registry(1);
+    let mut store = RevocationStore::default();
+    store
+        .accept(
+            signed_list(&key, 10, vec![entry(None, None)]),
+            Some(&public),
+        )
+        .expect("valid list is accepted");
+
+    let result = store.accept(signed_list(&key, 5, vec![]), None);
+    assert!(matches!(
+        result,
+        Err(ExtensionError::ValidationError { .. })
+    ));
+    assert!(store.revocation_for(EXT_KEY, "notes", "1.0.0").is_some());
+}
@@ -0,0 +1,89 @@
+// src-tauri/src/extension/revocation/types.rs
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Domain separator of the signed revocation payload.
+const REVOCATION_PAYLOAD_TAG: &str = "haex-extension-revocations-v1";
+
+/// One revoked public key, optionally narrowed to an extension name and versions.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RevocationEntry {
+    /// Hex-encoded Ed25519 key of the revoked extension(s)
+    pub public_key: String,
+    /// Extension name; `None` revokes every extension signed with the key
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Revoked versions; `None` revokes all versions
+    #[serde(default)]
+    pub versions: Option<Vec<String>>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl RevocationEntry {
+    pub fn matches(&self, public_key: &str, name: &str, version: &str) -> bool {
+        self.public_key.eq_ignore_ascii_case(public_key)
+            && self.name.as_deref().is_none_or(|n| n == name)
+            && self
+                .versions
+                .as_ref()
+                .is_none_or(|versions| versions.iter().any(|v| v == version))
+    }
+}
+
+/// Revocation list as published by a registry.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RevocationList {
+    /// Issue time (unix ms); older lists never replace newer ones
+    #[ts(type = "number")]
+    pub issued_at: i64,
+    pub entries: Vec<RevocationEntry>,
+    /// Hex-encoded Ed25519 signature of the registry over `signing_payload`
+    pub signature: String,
+}
+
+impl RevocationList {
+    /// Bytes the registry signs: a JSON array of the tag, `issued_at` and one
+    /// `[publicKey, name, versions, reason]` array per entry. Arrays keep the
+    /// encoding independent of object key order.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .map(|e| (&e.public_key, &e.name, &e.versions, &e.reason))
+            .collect();
+        serde_json::to_vec(&(REVOCATION_PAYLOAD_TAG, self.issued_at, entries)).unwrap_or_default()
+    }
+}
+
+/// A registered extension matched by the revocation list.
+#[derive(Serialize, Deserialize, Clone, Debug, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokedExtension {
+    pub id: String,
+    pub name: String,
+    pub public_key: String,
+    pub version: String,
+    pub reason: Option<String>,
+    /// False if it was refused by the loader, true if `allow_revoked` let it load
+    pub loaded: bool,
+}
+
+/// Result of `extension_check_revocations`.
+#[derive(Serialize, Deserialize, Clone, Debug, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RevocationReport {
+    /// Issue time of the stored list, `None` if no list was imported yet
+    #[ts(type = "number | null")]
+    pub issued_at: Option<i64>,
+    pub registry_public_key: Option<String>,
+    pub allow_revoked: bool,
+    pub revoked: Vec<RevokedExtension>,
+}
@@ -0,0 +1,225 @@
+// src-tauri/src/extension/share/commands.rs
+//!
+//! Share intake commands
+//!
+//! All commands are called by the main window: it fetches a pending share
+//! with `share_intake_take`, shows the chooser and then forwards or cancels
+//! the share. Extensions only ever see the forwarded file drop.
+
+use super::registry::SharedFileInfo;
+use super::SHARE_EVENT;
+use crate::extension::error::ExtensionError;
+use crate::extension::filedrop::registry::FileDropPayload;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State, WebviewWindow};
+use ts_rs::TS;
+
+/// Share as handed over by the native intake.
+#[cfg_attr(not(target_os = "android"), allow(dead_code))]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NativeShare {
+    /// Files copied into the share intake directory
+    pub paths: Vec<String>,
+    pub text: Option<String>,
+    pub subject: Option<String>,
+}
+
+/// Extension offered in the chooser.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareTargetCandidate {
+    pub extension_id: String,
+    pub name: String,
+    pub icon: Option<String>,
+}
+
+/// A staged share with the extensions that can receive it.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareIntake {
+    pub share_id: String,
+    pub files: Vec<SharedFileInfo>,
+    pub text: Option<String>,
+    pub subject: Option<String>,
+    pub targets: Vec<ShareTargetCandidate>,
+}
+
+/// Payload of `SHARE_EVENT`. Files are read with `extension_filedrop_read`
+/// using `dropId`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharePayload {
+    #[serde(flatten)]
+    pub drop: FileDropPayload,
+    pub text: Option<String>,
+    pub subject: Option<String>,
+}
+
+fn ensure_main_window(window: &WebviewWindow) -> Result<(), ExtensionError> {
+    if window.label() != "main" {
+        return Err(ExtensionError::SecurityViolation {
+            reason: "Only the main window can handle shares".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Stages a native share and collects the enabled extensions accepting it.
+#[cfg_attr(not(target_os = "android"), allow(dead_code))]
+pub fn stage_share(
+    state: &AppState,
+    intake_dir: &Path,
+    native: NativeShare,
+) -> Result<ShareIntake, ExtensionError> {
+    let paths = native.paths.into_iter().map(PathBuf::from).collect();
+    let (share_id, share) =
+        state
+            .share_intake
+            .stage(intake_dir, paths, native.text, native.subject)?;
+
+    let mime_types = share.mime_types();
+    let targets = state
+        .extension_manager
+        .get_all_extensions()?
+        .into_iter()
+        .filter(|ext| ext.enabled)
+        .filter(|ext| {
+            ext.manifest
+                .share_target
+                .as_ref()
+                .is_some_and(|target| target.accepts(&mime_types, share.text.is_some()))
+        })
+        .map(|ext| ShareTargetCandidate {
+            extension_id: ext.id,
+            name: ext.manifest.name,
+            icon: ext.manifest.icon,
+        })
+        .collect();
+
+    Ok(ShareIntake {
+        share_id,
+        files: share.files.into_iter().map(|(_, info)| info).collect(),
+        text: share.text,
+        subject: share.subject,
+        targets,
+    })
+}
+
+/// Takes the share the app was opened with (or received while running) and
+/// returns it with its chooser targets. `None` if nothing was shared.
+#[tauri::command]
+pub fn share_intake_take(
+    window: WebviewWindow,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<ShareIntake>, ExtensionError> {
+    ensure_main_window(&window)?;
+    take_native_share(&app_handle, &state)
+}
+
+#[cfg(target_os = "android")]
+fn take_native_share(
+    app_handle: &AppHandle,
+    state: &AppState,
+) -> Result<Option<ShareIntake>, ExtensionError> {
+    use tauri::Manager;
+
+    let Some(plugin) = app_handle.try_state::<super::ShareIntakePlugin<tauri::Wry>>() else {
+        return Ok(None);
+    };
+    let native = plugin.take_pending()?;
+    if native.paths.is_empty() && native.text.is_none() {
+        return Ok(None);
+    }
+    let intake_dir = super::intake_dir(app_handle)?;
+    stage_share(state, &intake_dir, native).map(Some)
+}
+
+/// Shares only arrive through the Android intake so far.
+#[cfg(not(target_os = "android"))]
+fn take_native_share(
+    _app_handle: &AppHandle,
+    _state: &AppState,
+) -> Result<Option<ShareIntake>, ExtensionError> {
+    Ok(None)
+}
+
+/// Forwards a staged share to the chosen extension as a file drop and emits
+/// `SHARE_EVENT` to it.
+#[tauri::command(rename_all = "camelCase")]
+pub fn share_intake_forward(
+    window: WebviewWindow,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    share_id: String,
+    extension_id: String,
+) -> Result<SharePayload, ExtensionError> {
+    ensure_main_window(&window)?;
+
+    let extension = state
+        .extension_manager
+        .get_extension(&extension_id)
+        .filter(|ext| ext.enabled)
+        .ok_or_else(|| ExtensionError::NotFound {
+            public_key: String::new(),
+            name: extension_id.clone(),
+        })?;
+    let target = extension.manifest.share_target.as_ref().ok_or_else(|| {
+        ExtensionError::PermissionDenied {
+            extension_id: extension_id.clone(),
+            operation: "receive".to_string(),
+            resource: "shared content".to_string(),
+        }
+    })?;
+
+    let share = state
+        .share_intake
+        .take_for_forward(&share_id, &extension_id, target)?;
+
+    let max_file_size = state.limits.defaults().filesystem.max_file_size_bytes;
+    let drop = state.file_drops.register_drop(
+        &extension_id,
+        share.paths(),
+        max_file_size.max(0) as u64,
+    )?;
+    let payload = SharePayload {
+        drop,
+        text: share.text.filter(|_| target.text),
+        subject: share.subject,
+    };
+
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    let result = state.extension_webview_manager.emit_to_extension_or_main(
+        &app_handle,
+        &extension_id,
+        SHARE_EVENT,
+        &payload,
+    );
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    let result = {
+        use tauri::Emitter;
+        app_handle.emit_to("main", SHARE_EVENT, &payload)
+    };
+
+    if let Err(e) = result {
+        eprintln!("[ShareIntake] Failed to emit share event: {e}");
+    }
+
+    Ok(payload)
+}
+
+/// Discards a staged share (chooser dismissed) and deletes its files.
+#[tauri::command(rename_all = "camelCase")]
+pub fn share_intake_cancel(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    share_id: String,
+) -> Result<bool, ExtensionError> {
+    ensure_main_window(&window)?;
+    state.share_intake.discard(&share_id)
+}
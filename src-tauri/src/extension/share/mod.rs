@@ -0,0 +1,100 @@
+// src-tauri/src/extension/share/mod.rs
+//!
+//! Share-sheet intake into extensions (mobile)
+//!
+//! On Android the native `ShareIntakePlugin` (copied from `.github/` into the
+//! generated Android project at build time) handles `ACTION_SEND` /
+//! `ACTION_SEND_MULTIPLE`: it copies shared content URIs into
+//! `<app cache>/share-intake/<uuid>/` and emits its `share` event. The main
+//! window then calls `share_intake_take`, which stages the files and returns
+//! the extensions whose manifest `shareTarget` accepts them, and forwards the
+//! user's choice with `share_intake_forward`.
+//!
+//! Forwarding registers the files as a file drop owned by the chosen
+//! extension and announces it via `SHARE_EVENT`; the extension reads the
+//! bytes through `extension_filedrop_read`, which checks ownership. Only
+//! files inside the intake directory are ever staged, and they are deleted
+//! when the share is cancelled or expires.
+//!
+//! iOS would need a share extension target in the Xcode project, which is
+//! not part of this repository yet.
+
+pub mod commands;
+pub mod registry;
+
+#[cfg(test)]
+mod tests;
+
+pub use registry::ShareIntakeRegistry;
+
+use crate::extension::core::manifest::ShareTarget;
+use crate::extension::error::ExtensionError;
+use std::fs;
+use std::path::Path;
+
+/// Event delivered to the chosen extension.
+/// Matches HAEXTENSION_EVENTS.SHARE in vault-sdk.
+pub const SHARE_EVENT: &str = "haextension:share";
+
+/// Directory below the app cache dir that the native side copies shares to.
+#[cfg(target_os = "android")]
+pub const SHARE_INTAKE_DIR: &str = "share-intake";
+
+/// Directory shared files are staged in.
+#[cfg(target_os = "android")]
+pub fn intake_dir(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, ExtensionError> {
+    use tauri::Manager;
+
+    let cache_dir =
+        app_handle
+            .path()
+            .app_cache_dir()
+            .map_err(|e| ExtensionError::FilesystemError {
+                reason: format!("Cannot get app cache dir: {e}"),
+            })?;
+    Ok(cache_dir.join(SHARE_INTAKE_DIR))
+}
+
+/// Reads the share target of a bundle's manifest.json.
+pub fn read_manifest_share_target(manifest_path: &Path) -> Option<ShareTarget> {
+    let content = fs::read_to_string(manifest_path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let target = value
+        .get("shareTarget")
+        .or_else(|| value.get("share-target"))?;
+    serde_json::from_value(target.clone()).ok()
+}
+
+// ============================================================================
+// Android intake plugin
+// ============================================================================
+
+/// Handle to the native `ShareIntakePlugin`.
+#[cfg(target_os = "android")]
+pub struct ShareIntakePlugin<R: tauri::Runtime>(tauri::plugin::PluginHandle<R>);
+
+#[cfg(target_os = "android")]
+impl<R: tauri::Runtime> ShareIntakePlugin<R> {
+    /// Takes the share the native side received last, if any.
+    pub fn take_pending(&self) -> Result<commands::NativeShare, ExtensionError> {
+        self.0
+            .run_mobile_plugin::<commands::NativeShare>("takePending", ())
+            .map_err(|e| ExtensionError::FilesystemError {
+                reason: format!("Share intake failed: {e}"),
+            })
+    }
+}
+
+/// Registers the native intake (`space.haex.vault.ShareIntakePlugin`).
+#[cfg(target_os = "android")]
+pub fn init<R: tauri::Runtime>() -> tauri::plugin::TauriPlugin<R> {
+    use tauri::Manager;
+
+    tauri::plugin::Builder::new("share-intake")
+        .setup(|app, api| {
+            let handle = api.register_android_plugin("space.haex.vault", "ShareIntakePlugin")?;
+            app.manage(ShareIntakePlugin(handle));
+            Ok(())
+        })
+        .build()
+}
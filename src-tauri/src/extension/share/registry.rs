@@ -0,0 +1,226 @@
+// src-tauri/src/extension/share/registry.rs
+//!
+//! In-memory registry of staged shares (share_id → temporary files + text).
+
+use crate::extension::core::manifest::ShareTarget;
+use crate::extension::error::ExtensionError;
+use crate::extension::filedrop::registry::{DROP_TTL, MAX_FILES_PER_DROP};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Instant;
+use ts_rs::TS;
+
+/// Maximum number of files accepted from a single share; a forwarded share
+/// becomes one file drop.
+pub const MAX_FILES_PER_SHARE: usize = MAX_FILES_PER_DROP;
+
+/// Metadata of a shared file as shown in the chooser.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedFileInfo {
+    pub name: String,
+    pub size: u64,
+    pub mime_type: String,
+}
+
+/// A staged share, ready to be forwarded.
+#[derive(Debug, Clone)]
+pub struct StagedShare {
+    pub files: Vec<(PathBuf, SharedFileInfo)>,
+    pub text: Option<String>,
+    pub subject: Option<String>,
+}
+
+impl StagedShare {
+    pub fn mime_types(&self) -> Vec<String> {
+        self.files
+            .iter()
+            .map(|(_, info)| info.mime_type.clone())
+            .collect()
+    }
+
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.files.iter().map(|(path, _)| path.clone()).collect()
+    }
+}
+
+struct ShareEntry {
+    share: StagedShare,
+    forwarded: bool,
+    /// Staging time, reset on forwarding so the extension gets a full
+    /// `DROP_TTL` to read the files.
+    touched_at: Instant,
+}
+
+pub struct ShareIntakeRegistry {
+    shares: Mutex<HashMap<String, ShareEntry>>,
+}
+
+impl ShareIntakeRegistry {
+    pub fn new() -> Self {
+        Self {
+            shares: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock_shares(&self) -> Result<MutexGuard<'_, HashMap<String, ShareEntry>>, ExtensionError> {
+        self.shares
+            .lock()
+            .map_err(|e| ExtensionError::MutexPoisoned {
+                reason: e.to_string(),
+            })
+    }
+
+    /// Stages the files the native side copied into `intake_dir`. Paths
+    /// outside of it, directories and files past `MAX_FILES_PER_SHARE` are
+    /// refused, so a share can never expose arbitrary files to an extension.
+    pub fn stage(
+        &self,
+        intake_dir: &Path,
+        paths: Vec<PathBuf>,
+        text: Option<String>,
+        subject: Option<String>,
+    ) -> Result<(String, StagedShare), ExtensionError> {
+        if paths.len() > MAX_FILES_PER_SHARE {
+            return Err(ExtensionError::ValidationError {
+                reason: format!("A share may contain at most {MAX_FILES_PER_SHARE} files"),
+            });
+        }
+
+        let root = intake_dir.canonicalize().map_err(|e| {
+            ExtensionError::filesystem_with_path(intake_dir.display().to_string(), e)
+        })?;
+
+        let mut files = Vec::with_capacity(paths.len());
+        for path in paths {
+            let path_str = path.display().to_string();
+            let canonical = path
+                .canonicalize()
+                .map_err(|e| ExtensionError::filesystem_with_path(path_str.clone(), e))?;
+            if !canonical.starts_with(&root) {
+                return Err(ExtensionError::SecurityViolation {
+                    reason: format!(
+                        "Shared file is outside the share intake directory: {path_str}"
+                    ),
+                });
+            }
+            let metadata = std::fs::metadata(&canonical)
+                .map_err(|e| ExtensionError::filesystem_with_path(path_str.clone(), e))?;
+            if !metadata.is_file() {
+                return Err(ExtensionError::ValidationError {
+                    reason: format!("Shared path is not a file: {path_str}"),
+                });
+            }
+
+            let info = SharedFileInfo {
+                name: canonical
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or(path_str),
+                size: metadata.len(),
+                mime_type: mime_guess::from_path(&canonical)
+                    .first_or_octet_stream()
+                    .to_string(),
+            };
+            files.push((canonical, info));
+        }
+
+        let share = StagedShare {
+            files,
+            text: text.filter(|t| !t.is_empty()),
+            subject: subject.filter(|s| !s.is_empty()),
+        };
+        let share_id = uuid::Uuid::new_v4().to_string();
+
+        let mut shares = self.lock_shares()?;
+        Self::prune_locked(&mut shares);
+        shares.insert(
+            share_id.clone(),
+            ShareEntry {
+                share: share.clone(),
+                forwarded: false,
+                touched_at: Instant::now(),
+            },
+        );
+
+        Ok((share_id, share))
+    }
+
+    /// Returns a staged share for forwarding to `extension_id`. A share can
+    /// be forwarded once, and only to an extension whose `target` accepts it;
+    /// its files stay on disk until it expires.
+    pub fn take_for_forward(
+        &self,
+        share_id: &str,
+        extension_id: &str,
+        target: &ShareTarget,
+    ) -> Result<StagedShare, ExtensionError> {
+        let mut shares = self.lock_shares()?;
+        Self::prune_locked(&mut shares);
+
+        let entry = shares
+            .get_mut(share_id)
+            .filter(|entry| !entry.forwarded)
+            .ok_or_else(|| ExtensionError::ValidationError {
+                reason: format!("Unknown or already forwarded share: {share_id}"),
+            })?;
+
+        let has_text = target.text && entry.share.text.is_some();
+        if !target.accepts(&entry.share.mime_types(), has_text) {
+            return Err(ExtensionError::PermissionDenied {
+                extension_id: extension_id.to_string(),
+                operation: "receive".to_string(),
+                resource: "shared content".to_string(),
+            });
+        }
+
+        entry.forwarded = true;
+        entry.touched_at = Instant::now();
+        Ok(entry.share.clone())
+    }
+
+    /// Discards a share and deletes its files. Returns false if it was unknown.
+    pub fn discard(&self, share_id: &str) -> Result<bool, ExtensionError> {
+        let mut shares = self.lock_shares()?;
+        match shares.remove(share_id) {
+            Some(entry) => {
+                remove_files(&entry.share);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn prune_locked(shares: &mut HashMap<String, ShareEntry>) {
+        shares.retain(|_, entry| {
+            let alive = entry.touched_at.elapsed() < DROP_TTL;
+            if !alive {
+                remove_files(&entry.share);
+            }
+            alive
+        });
+    }
+}
+
+impl Default for ShareIntakeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Deletes the temporary files of a share and the per-share directories the
+/// native side created for them.
+fn remove_files(share: &StagedShare) {
+    for (path, _) in &share.files {
+        if let Err(e) = std::fs::remove_file(path) {
+            eprintln!("[ShareIntake] Failed to remove {}: {e}", path.display());
+        }
+        // Only succeeds once the directory is empty
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::remove_dir(parent);
+        }
+    }
+}
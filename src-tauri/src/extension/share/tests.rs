@@ -0,0 +1,147 @@
+// src-tauri/src/extension/share/tests.rs
+//!
+//! Tests for share targets and the share intake registry
+
+use super::read_manifest_share_target;
+use super::registry::ShareIntakeRegistry;
+use crate::extension::core::manifest::ShareTarget;
+use std::path::PathBuf;
+
+fn write_file(dir: &std::path::Path, name: &str, len: usize) -> PathBuf {
+    let path = dir.join(name);
+    std::fs::write(&path, vec![b'x'; len]).unwrap();
+    path
+}
+
+fn images_target() -> ShareTarget {
+    ShareTarget {
+        accept: vec!["image/*".to_string(), "application/pdf".to_string()],
+        text: false,
+    }
+}
+
+#[test]
+fn test_share_target_matches_mime_patterns() {
+    let target = images_target();
+    let mimes = |list: &[&str]| list.iter().map(|m| m.to_string()).collect::<Vec<_>>();
+
+    assert!(target.accepts(&mimes(&["image/png", "IMAGE/JPEG"]), false));
+    assert!(target.accepts(&mimes(&["application/pdf"]), true));
+    assert!(!target.accepts(&mimes(&["image/png", "text/plain"]), false));
+    assert!(!target.accepts(&[], true));
+
+    let any = ShareTarget {
+        accept: vec!["*/*".to_string()],
+        text: true,
+    };
+    assert!(any.accepts(&mimes(&["application/zip"]), false));
+    assert!(any.accepts(&[], true));
+}
+
+#[test]
+fn test_read_manifest_share_target_accepts_both_spellings() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = dir.path().join("manifest.json");
+
+    std::fs::write(&manifest, r#"{"shareTarget": {"accept": ["image/*"]}}"#).unwrap();
+    let target = read_manifest_share_target(&manifest).unwrap();
+    assert_eq!(target.accept, vec!["image/*"]);
+    assert!(!target.text);
+
+    std::fs::write(&manifest, r#"{"share-target": {"text": true}}"#).unwrap();
+    assert!(read_manifest_share_target(&manifest).unwrap().text);
+
+    std::fs::write(&manifest, r#"{"name": "no-share"}"#).unwrap();
+    assert!(read_manifest_share_target(&manifest).is_none());
+}
+
+#[test]
+fn test_stage_rejects_files_outside_intake_dir() {
+    let intake = tempfile::tempdir().unwrap();
+    let outside = tempfile::tempdir().unwrap();
+    let registry = ShareIntakeRegistry::new();
+    let path = write_file(outside.path(), "secret.txt", 4);
+
+    assert!(registry
+        .stage(intake.path(), vec![path], None, None)
+        .is_err());
+
+    let escaping = intake.path().join("..").join(
+        outside
+            .path()
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .to_string(),
+    );
+    assert!(registry
+        .stage(intake.path(), vec![escaping.join("secret.txt")], None, None)
+        .is_err());
+}
+
+#[test]
+fn test_stage_collects_metadata() {
+    let intake = tempfile::tempdir().unwrap();
+    let registry = ShareIntakeRegistry::new();
+    let path = write_file(intake.path(), "photo.png", 12);
+
+    let (_, share) = registry
+        .stage(
+            intake.path(),
+            vec![path],
+            Some("look".to_string()),
+            Some(String::new()),
+        )
+        .unwrap();
+
+    assert_eq!(share.files.len(), 1);
+    assert_eq!(share.files[0].1.name, "photo.png");
+    assert_eq!(share.files[0].1.size, 12);
+    assert_eq!(share.files[0].1.mime_type, "image/png");
+    assert_eq!(share.text.as_deref(), Some("look"));
+    assert!(share.subject.is_none());
+}
+
+#[test]
+fn test_forward_requires_matching_target_and_happens_once() {
+    let intake = tempfile::tempdir().unwrap();
+    let registry = ShareIntakeRegistry::new();
+    let path = write_file(intake.path(), "notes.txt", 3);
+    let (share_id, _) = registry
+        .stage(intake.path(), vec![path], None, None)
+        .unwrap();
+
+    // text/plain isn't accepted by an image target
+    assert!(registry
+        .take_for_forward(&share_id, "ext-a", &images_target())
+        .is_err());
+
+    let text_files = ShareTarget {
+        accept: vec!["text/*".to_string()],
+        text: false,
+    };
+    assert!(registry
+        .take_for_forward(&share_id, "ext-b", &text_files)
+        .is_ok());
+    assert!(registry
+        .take_for_forward(&share_id, "ext-b", &text_files)
+        .is_err());
+}
+
+#[test]
+fn test_discard_deletes_files() {
+    let intake = tempfile::tempdir().unwrap();
+    let share_dir = intake.path().join("share-1");
+    std::fs::create_dir(&share_dir).unwrap();
+    let registry = ShareIntakeRegistry::new();
+    let path = write_file(&share_dir, "photo.jpg", 5);
+
+    let (share_id, _) = registry
+        .stage(intake.path(), vec![path.clone()], None, None)
+        .unwrap();
+
+    assert!(registry.discard(&share_id).unwrap());
+    assert!(!path.exists());
+    assert!(!share_dir.exists());
+    assert!(!registry.discard(&share_id).unwrap());
+}
@@ -63,12 +63,17 @@ mod tests {
                 id TEXT PRIMARY KEY NOT NULL,
                 table_name TEXT NOT NULL,
                 row_pks TEXT NOT NULL,
+                space_id TEXT,
                 haex_hlc TEXT,
                 haex_column_hlcs TEXT NOT NULL DEFAULT '{{}}'
             )",
             DELETED_ROWS_TABLE
         ))
         .unwrap();
+        conn.execute_batch(include_str!(
+            "../../../database/migrations/0017_add_trash.sql"
+        ))
+        .unwrap();
 
         conn.execute_batch(
             "CREATE TABLE haex_spaces (
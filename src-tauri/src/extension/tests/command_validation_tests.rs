@@ -43,6 +43,11 @@ fn create_test_extension(public_key: &str, name: &str) -> Extension {
                 identities: None,
                 passwords: None,
                 mail: None,
+                camera: None,
+                contacts: None,
+                calendar: None,
+                microphone: None,
+                speech: None,
             },
             homepage: None,
             description: Some("Test extension".to_string()),
@@ -50,6 +55,12 @@ fn create_test_extension(public_key: &str, name: &str) -> Extension {
             display_mode: Some(DisplayMode::Iframe),
             migrations_dir: None,
             i18n: None,
+            key_rotations: None,
+            locales: None,
+            share_target: None,
+            events: None,
+            min_host_version: None,
+            max_host_version: None,
         },
         source: ExtensionSource::Production {
             path: PathBuf::from("/tmp/test-extension"),
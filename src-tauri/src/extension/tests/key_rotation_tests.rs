@@ -0,0 +1,130 @@
+// src-tauri/src/extension/tests/key_rotation_tests.rs
+//!
+//! Tests for extension signing key rotation chains
+//!
+
+use crate::extension::core::manifest::KeyRotationProof;
+use crate::extension::crypto::ExtensionCrypto;
+use ed25519_dalek::{Signer, SigningKey};
+
+const EXTENSION_NAME: &str = "demo-extension";
+
+fn key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32])
+}
+
+fn public_hex(key: &SigningKey) -> String {
+    hex::encode(key.verifying_key().to_bytes())
+}
+
+fn rotate(from: &SigningKey, to: &SigningKey) -> KeyRotationProof {
+    let payload =
+        ExtensionCrypto::key_rotation_payload(EXTENSION_NAME, &public_hex(from), &public_hex(to));
+    KeyRotationProof {
+        previous_public_key: public_hex(from),
+        public_key: public_hex(to),
+        signature: hex::encode(from.sign(payload.as_bytes()).to_bytes()),
+    }
+}
+
+#[test]
+fn test_single_rotation_is_accepted() {
+    let (old, new) = (key(1), key(2));
+    let proofs = vec![rotate(&old, &new)];
+
+    assert!(ExtensionCrypto::verify_key_rotation(
+        EXTENSION_NAME,
+        &public_hex(&old),
+        &public_hex(&new),
+        &proofs
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_chain_can_be_joined_midway() {
+    let (first, second, third) = (key(1), key(2), key(3));
+    let proofs = vec![rotate(&first, &second), rotate(&second, &third)];
+
+    // An installation that already trusts the second key only needs the last step
+    assert!(ExtensionCrypto::verify_key_rotation(
+        EXTENSION_NAME,
+        &public_hex(&second),
+        &public_hex(&third),
+        &proofs
+    )
+    .is_ok());
+    assert!(ExtensionCrypto::verify_key_rotation(
+        EXTENSION_NAME,
+        &public_hex(&first),
+        &public_hex(&third),
+        &proofs
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_rotation_without_proof_from_trusted_key_is_rejected() {
+    let (old, new, attacker) = (key(1), key(2), key(9));
+    let proofs = vec![rotate(&attacker, &new)];
+
+    assert!(ExtensionCrypto::verify_key_rotation(
+        EXTENSION_NAME,
+        &public_hex(&old),
+        &public_hex(&new),
+        &proofs
+    )
+    .is_err());
+}
+
+#[test]
+fn test_proof_signed_by_wrong_key_is_rejected() {
+    let (old, new, attacker) = (key(1), key(2), key(9));
+    let mut proof = rotate(&attacker, &new);
+    proof.previous_public_key = public_hex(&old);
+
+    assert!(ExtensionCrypto::verify_key_rotation(
+        EXTENSION_NAME,
+        &public_hex(&old),
+        &public_hex(&new),
+        &[proof]
+    )
+    .is_err());
+}
+
+#[test]
+fn test_proof_for_other_extension_is_rejected() {
+    let (old, new) = (key(1), key(2));
+    let payload = ExtensionCrypto::key_rotation_payload(
+        "other-extension",
+        &public_hex(&old),
+        &public_hex(&new),
+    );
+    let proof = KeyRotationProof {
+        previous_public_key: public_hex(&old),
+        public_key: public_hex(&new),
+        signature: hex::encode(old.sign(payload.as_bytes()).to_bytes()),
+    };
+
+    assert!(ExtensionCrypto::verify_key_rotation(
+        EXTENSION_NAME,
+        &public_hex(&old),
+        &public_hex(&new),
+        &[proof]
+    )
+    .is_err());
+}
+
+#[test]
+fn test_chain_must_end_at_bundle_key() {
+    let (old, new, other) = (key(1), key(2), key(3));
+    let proofs = vec![rotate(&old, &new)];
+
+    assert!(ExtensionCrypto::verify_key_rotation(
+        EXTENSION_NAME,
+        &public_hex(&old),
+        &public_hex(&other),
+        &proofs
+    )
+    .is_err());
+}
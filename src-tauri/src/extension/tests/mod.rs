@@ -12,6 +12,8 @@
 #[cfg(test)]
 mod command_validation_tests;
 #[cfg(test)]
+mod key_rotation_tests;
+#[cfg(test)]
 mod request_types_tests;
 #[cfg(test)]
 mod security_tests;
@@ -43,6 +43,11 @@ fn create_test_extension(public_key: &str, name: &str) -> Extension {
                 identities: None,
                 passwords: None,
                 mail: None,
+                camera: None,
+                contacts: None,
+                calendar: None,
+                microphone: None,
+                speech: None,
             },
             homepage: None,
             description: None,
@@ -50,6 +55,12 @@ fn create_test_extension(public_key: &str, name: &str) -> Extension {
             display_mode: Some(DisplayMode::Iframe),
             migrations_dir: None,
             i18n: None,
+            key_rotations: None,
+            locales: None,
+            share_target: None,
+            events: None,
+            min_host_version: None,
+            max_host_version: None,
         },
         source: ExtensionSource::Production {
             path: PathBuf::from("/tmp/test"),
@@ -112,6 +123,11 @@ mod manifest_tests {
                 identities: None,
                 passwords: None,
                 mail: None,
+                camera: None,
+                contacts: None,
+                calendar: None,
+                microphone: None,
+                speech: None,
             },
             homepage: Some("https://example.com".to_string()),
             description: Some("Test description".to_string()),
@@ -119,6 +135,12 @@ mod manifest_tests {
             display_mode: Some(DisplayMode::Window),
             migrations_dir: Some("migrations".to_string()),
             i18n: None,
+            key_rotations: None,
+            locales: None,
+            share_target: None,
+            events: None,
+            min_host_version: None,
+            max_host_version: None,
         };
 
         assert_eq!(manifest.name, "test");
@@ -147,6 +169,11 @@ mod manifest_tests {
                 identities: None,
                 passwords: None,
                 mail: None,
+                camera: None,
+                contacts: None,
+                calendar: None,
+                microphone: None,
+                speech: None,
             },
             homepage: None,
             description: None,
@@ -154,6 +181,12 @@ mod manifest_tests {
             display_mode: None,
             migrations_dir: None,
             i18n: None,
+            key_rotations: None,
+            locales: None,
+            share_target: None,
+            events: None,
+            min_host_version: None,
+            max_host_version: None,
         };
 
         assert!(manifest.permissions.database.is_none());
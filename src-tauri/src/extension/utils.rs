@@ -203,7 +203,9 @@ pub fn discover_extension_tables(
 /// 1. Find all tables with the extension's prefix
 /// 2. Drop CRDT triggers for each table (to prevent trigger errors)
 /// 3. Remove entries from haex_crdt_dirty_tables (to prevent sync errors)
-/// 4. Drop the tables themselves
+/// 4. Drop the extension's views
+/// 5. Remove the extension's cascade rules
+/// 6. Drop the tables themselves
 ///
 /// # Arguments
 /// * `tx` - Database transaction
@@ -237,6 +239,22 @@ pub fn drop_extension_tables(
         prefix
     );
 
+    // Drop the extension's views first; they would otherwise outlive their
+    // tables and make a reinstall fail with "view already exists".
+    let mut view_stmt =
+        tx.prepare("SELECT name FROM sqlite_master WHERE type = 'view' AND name LIKE ?1")?;
+    let view_names: Vec<String> = view_stmt
+        .query_map([&dirty_pattern], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for view_name in &view_names {
+        let drop_view_sql = format!("DROP VIEW IF EXISTS \"{}\"", view_name);
+        println!("[EXTENSION_CLEANUP] Executing: {}", drop_view_sql);
+        tx.execute(&drop_view_sql, [])?;
+    }
+
+    // Cascade rules go away with the tables (their triggers are dropped with them)
+    crate::crdt::cascade::remove_cascade_rules(tx, &prefix)?;
+
     // Find all tables with this extension's prefix
     let mut stmt =
         tx.prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name LIKE ?1")?;
@@ -54,16 +54,5 @@ pub fn get_extension_info_from_window(
             reason: format!("Extension with ID {} not found", extension_id),
         })?;
 
-    let version = match &extension.source {
-        crate::extension::core::types::ExtensionSource::Production { version, .. } => {
-            version.clone()
-        }
-        crate::extension::core::types::ExtensionSource::Development { .. } => "dev".to_string(),
-    };
-
-    Ok(ExtensionInfo {
-        public_key: extension.manifest.public_key,
-        name: extension.manifest.name,
-        version,
-    })
+    Ok(ExtensionInfo::from_extension(&extension))
 }
@@ -1,5 +1,6 @@
-use crate::event_names::EVENT_EXTENSION_WINDOW_CLOSED;
+use crate::extension::core::context::ApplicationContext;
 use crate::extension::error::ExtensionError;
+use crate::extension::webview::placement::{self, WindowPlacementOptions};
 use crate::extension::ExtensionManager;
 use crate::window::focus_window;
 use std::collections::HashMap;
@@ -12,12 +13,17 @@ pub struct ExtensionWebviewManager {
     /// Das window_id ist ein eindeutiger Identifier (Tauri-kompatibel, keine Bindestriche)
     /// und wird gleichzeitig als Tauri WebviewWindow label verwendet
     pub windows: Arc<Mutex<HashMap<String, String>>>,
+    /// CPU-/Speicher-Überwachung der Extension-Fenster (siehe `monitor`)
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    pub monitor: Arc<crate::extension::webview::monitor::ResourceMonitor>,
 }
 
 impl ExtensionWebviewManager {
     pub fn new() -> Self {
         Self {
             windows: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            monitor: Arc::new(crate::extension::webview::monitor::ResourceMonitor::new()),
         }
     }
 
@@ -54,6 +60,10 @@ impl ExtensionWebviewManager {
     /// * `x` - X-Position (optional)
     /// * `y` - Y-Position (optional)
     /// * `minimized` - Fenster minimiert öffnen (optional, default: false)
+    /// * `placement` - Monitor-/Snap-Optionen; Position wird immer auf den sichtbaren
+    ///   Arbeitsbereich eines angeschlossenen Monitors geklemmt (außer `skip_clamp`)
+    /// * `context` - Aktueller ApplicationContext, wird per Initialization-Script
+    ///   injiziert, damit Theme/Locale schon beim ersten Paint stimmen
     ///
     /// # Returns
     /// Das window_id des erstellten Fensters
@@ -68,6 +78,8 @@ impl ExtensionWebviewManager {
         x: Option<f64>,
         y: Option<f64>,
         minimized: Option<bool>,
+        placement: Option<WindowPlacementOptions>,
+        context: &ApplicationContext,
     ) -> Result<String, ExtensionError> {
         // Extension aus Manager holen
         let extension = extension_manager
@@ -134,23 +146,58 @@ impl ExtensionWebviewManager {
                 reason: format!("Invalid URL: {}", e),
             })?);
 
+        let context_script = context.to_init_script()?;
+        let is_dev_extension = matches!(extension.source, ExtensionSource::Development { .. });
+
+        // Zielrechteck auf einem tatsächlich angeschlossenen Monitor bestimmen.
+        // Gespeicherte Positionen können auf einen inzwischen getrennten
+        // Monitor zeigen — ohne Klemmen öffnet das Fenster dann unsichtbar.
+        let monitors = placement::list_monitors(app_handle).unwrap_or_else(|e| {
+            eprintln!("[ExtensionWebviewManager] Failed to enumerate monitors: {}", e);
+            Vec::new()
+        });
+        let placed = placement::resolve_placement(
+            &monitors,
+            x.zip(y),
+            width,
+            height,
+            &placement.unwrap_or_default(),
+        );
+
         #[cfg(not(any(target_os = "android", target_os = "ios")))]
         let mut builder = WebviewWindowBuilder::new(app_handle, &window_id, webview_url)
+            .initialization_script(&context_script)
             .title(&title)
             .inner_size(width, height)
             .decorations(true) // Native Decorations (Titlebar, etc.)
             .resizable(true)
-            .skip_taskbar(false) // In Taskbar anzeigen
-            .center(); // Fenster zentrieren
+            .skip_taskbar(false); // In Taskbar anzeigen
 
         #[cfg(any(target_os = "android", target_os = "ios"))]
         let mut builder = WebviewWindowBuilder::new(app_handle, &window_id, webview_url)
+            .initialization_script(&context_script)
             .inner_size(width, height);
 
-        // Position setzen, falls angegeben (nur Desktop)
+        // Dev-Extensions: Konsole und Fehler an den Host weiterleiten
+        if is_dev_extension {
+            builder = builder.initialization_script(crate::extension::dev_logs::CAPTURE_SCRIPT);
+        }
+
+        // Position/Größe setzen (nur Desktop). Ohne Monitor-Info (z.B. headless)
+        // fallen wir auf die angeforderte Position bzw. Zentrieren zurück.
         #[cfg(not(any(target_os = "android", target_os = "ios")))]
-        if let (Some(x_pos), Some(y_pos)) = (x, y) {
-            builder = builder.position(x_pos, y_pos);
+        match (placed, x, y) {
+            (Some(rect), _, _) => {
+                builder = builder
+                    .inner_size(rect.width, rect.height)
+                    .position(rect.x, rect.y);
+            }
+            (None, Some(x_pos), Some(y_pos)) => {
+                builder = builder.position(x_pos, y_pos);
+            }
+            (None, _, _) => {
+                builder = builder.center();
+            }
         }
 
         // Fenster erstellen
@@ -193,21 +240,44 @@ impl ExtensionWebviewManager {
         let windows_for_event = self.windows.clone();
 
         webview_window.on_window_event(move |event| {
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                crate::extension::filedrop::handle_drop(
+                    &app_handle_for_event,
+                    &window_id_for_event,
+                    paths.clone(),
+                );
+            }
+
             if let tauri::WindowEvent::Destroyed = event {
                 eprintln!("WebviewWindow destroyed: {}", window_id_for_event);
 
                 // Registry cleanup
-                if let Ok(mut windows) = windows_for_event.lock() {
-                    windows.remove(&window_id_for_event);
+                let unloaded_extension = windows_for_event.lock().ok().and_then(|mut windows| {
+                    let extension_id = windows.remove(&window_id_for_event)?;
+                    // Unloaded only once its last window is gone
+                    (!windows.values().any(|id| *id == extension_id)).then_some(extension_id)
+                });
+
+                // File watches and pending drops of the extension end with
+                // its last window
+                if let Some(extension_id) = unloaded_extension {
+                    let state = app_handle_for_event.state::<crate::AppState>();
+                    if let Err(e) = state.file_watcher.unwatch_extension(&extension_id) {
+                        eprintln!("Failed to stop file watches of {}: {}", extension_id, e);
+                    }
+                    if let Err(e) = state.file_drops.release_all_for_extension(&extension_id) {
+                        eprintln!("Failed to release file drops of {}: {}", extension_id, e);
+                    }
                 }
 
                 // Emit event an Frontend, damit das Tracking aktualisiert wird.
                 // Nur Main-Window — Extensions müssen nicht erfahren, welche
                 // anderen Extension-Fenster geschlossen werden.
-                let _ = app_handle_for_event.emit_to(
-                    "main",
-                    EVENT_EXTENSION_WINDOW_CLOSED,
-                    &window_id_for_event,
+                let _ = crate::events::emit_to_main(
+                    &app_handle_for_event,
+                    &crate::events::payloads::ExtensionWindowClosed {
+                        window_id: window_id_for_event.clone(),
+                    },
                 );
             }
         });
@@ -260,6 +330,49 @@ impl ExtensionWebviewManager {
         }
     }
 
+    /// Beendet ein Extension-Fenster hart (Kill-Switch für hängende oder
+    /// ausufernde Extensions). Anders als `close_extension_window` wird das
+    /// Fenster zerstört, ohne dass die Extension das Schließen verzögern kann.
+    /// Das Main-Window erhält `EVENT_EXTENSION_TERMINATED` und zeigt die
+    /// Extension als gestoppt an; der Destroyed-Handler räumt die Registry auf.
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    pub fn terminate_extension_window(
+        &self,
+        app_handle: &AppHandle,
+        window_id: &str,
+        reason: &str,
+    ) -> Result<(), ExtensionError> {
+        let extension_id = self
+            .windows
+            .lock()
+            .map_err(|e| ExtensionError::MutexPoisoned {
+                reason: e.to_string(),
+            })?
+            .get(window_id)
+            .cloned()
+            .ok_or_else(|| ExtensionError::NotFound {
+                public_key: "".to_string(),
+                name: window_id.to_string(),
+            })?;
+
+        if let Some(window) = app_handle.get_webview_window(window_id) {
+            window
+                .destroy()
+                .map_err(|e| ExtensionError::ValidationError {
+                    reason: format!("Failed to terminate window: {}", e),
+                })?;
+        }
+
+        let payload = crate::extension::webview::monitor::ExtensionTerminated {
+            window_id: window_id.to_string(),
+            extension_id,
+            reason: reason.to_string(),
+        };
+        let _ = crate::events::emit_to_main(app_handle, &payload);
+        eprintln!("Extension window terminated: {} ({})", window_id, reason);
+        Ok(())
+    }
+
     /// Fokussiert ein Extension-Fenster (stellt es auch wieder her wenn minimiert)
     pub fn focus_extension_window(
         &self,
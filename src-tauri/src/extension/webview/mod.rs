@@ -1,6 +1,10 @@
 pub mod filesystem;
 pub mod helpers;
 pub mod manager;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub mod monitor;
+pub mod placement;
+pub mod print;
 pub mod web;
 
 #[cfg(test)]
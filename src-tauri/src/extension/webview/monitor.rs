@@ -0,0 +1,427 @@
+// src-tauri/src/extension/webview/monitor.rs
+//!
+//! Resource monitor for extension windows (desktop only)
+//!
+//! Samples memory and CPU of the webview processes behind each extension
+//! window and warns when the process limits from `haex_extension_limits` are
+//! exceeded. A runaway window can then be terminated with
+//! `extension_terminate_window`.
+//!
+//! No webview engine reports which process renders which window, so
+//! processes are attributed by their appearance: a child process of the app
+//! that shows up while exactly one extension window is freshly opened belongs
+//! to that window. Processes shared between windows (e.g. the WebView2
+//! browser process) or spawned while several windows open at once stay
+//! unattributed — the usage is a lower bound, never someone else's load.
+
+use crate::extension::limits::types::ProcessLimits;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+/// Interval between two samples
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// New processes are attributed to a window only this long after it opened.
+const ATTRIBUTION_WINDOW: Duration = Duration::from_secs(20);
+
+/// Consecutive samples above the CPU limit before warning; short spikes
+/// (page load, large render) are normal.
+const CPU_STRIKES: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum ResourceKind {
+    Memory,
+    Cpu,
+}
+
+/// Latest sample for one extension window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionResourceUsage {
+    pub window_id: String,
+    pub extension_id: String,
+    /// Processes attributed to the window (0 = not attributed yet)
+    pub process_count: u32,
+    /// Resident memory in bytes
+    #[ts(type = "number")]
+    pub memory_bytes: u64,
+    /// CPU usage in percent of one core (can exceed 100)
+    pub cpu_percent: f32,
+    /// Resources currently above their limit
+    pub exceeded: Vec<ResourceKind>,
+}
+
+/// Payload of `EVENT_EXTENSION_RESOURCE_LIMIT_EXCEEDED`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceLimitWarning {
+    pub window_id: String,
+    pub extension_id: String,
+    pub resource: ResourceKind,
+    /// Measured value (bytes or percent)
+    pub value: f64,
+    /// Configured limit (bytes or percent)
+    pub limit: f64,
+}
+
+/// Payload of `EVENT_EXTENSION_TERMINATED`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionTerminated {
+    pub window_id: String,
+    pub extension_id: String,
+    pub reason: String,
+}
+
+#[derive(Debug)]
+struct TrackedWindow {
+    extension_id: String,
+    first_seen: Instant,
+    pids: HashSet<u32>,
+    cpu_strikes: u32,
+    /// Resources already warned about; cleared once back under the limit so
+    /// every crossing warns exactly once.
+    warned: HashSet<ResourceKind>,
+}
+
+impl TrackedWindow {
+    fn new(extension_id: String, now: Instant) -> Self {
+        Self {
+            extension_id,
+            first_seen: now,
+            pids: HashSet::new(),
+            cpu_strikes: 0,
+            warned: HashSet::new(),
+        }
+    }
+}
+
+struct MonitorState {
+    system: System,
+    /// Every descendant process seen so far, attributed or not
+    known: HashSet<u32>,
+    windows: HashMap<String, TrackedWindow>,
+    usage: Vec<ExtensionResourceUsage>,
+}
+
+pub struct ResourceMonitor {
+    state: Mutex<MonitorState>,
+}
+
+impl Default for ResourceMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// All transitive children of `root`; `parents` maps pid -> parent pid.
+fn descendants(parents: &HashMap<u32, u32>, root: u32) -> HashSet<u32> {
+    let mut result = HashSet::new();
+    let mut frontier = vec![root];
+    while let Some(parent) = frontier.pop() {
+        for (pid, _) in parents.iter().filter(|(_, p)| **p == parent) {
+            if result.insert(*pid) {
+                frontier.push(*pid);
+            }
+        }
+    }
+    result
+}
+
+/// Hands `new_pids` to the only window still inside its attribution window.
+fn attribute(windows: &mut HashMap<String, TrackedWindow>, new_pids: &HashSet<u32>, now: Instant) {
+    if new_pids.is_empty() {
+        return;
+    }
+    let mut young = windows
+        .values_mut()
+        .filter(|w| now.duration_since(w.first_seen) <= ATTRIBUTION_WINDOW);
+    if let (Some(window), None) = (young.next(), young.next()) {
+        window.pids.extend(new_pids);
+    }
+}
+
+/// Updates strike counters and returns the resources that newly crossed
+/// their limit, plus all resources currently above it.
+fn check_limits(
+    window: &mut TrackedWindow,
+    memory_bytes: u64,
+    cpu_percent: f32,
+    limits: &ProcessLimits,
+) -> (Vec<ResourceKind>, Vec<ResourceKind>) {
+    let mut exceeded = Vec::new();
+
+    if memory_bytes as f64 > limits.max_memory_bytes as f64 {
+        exceeded.push(ResourceKind::Memory);
+    }
+
+    if f64::from(cpu_percent) > limits.max_cpu_percent as f64 {
+        window.cpu_strikes = window.cpu_strikes.saturating_add(1);
+    } else {
+        window.cpu_strikes = 0;
+    }
+    if window.cpu_strikes >= CPU_STRIKES {
+        exceeded.push(ResourceKind::Cpu);
+    }
+
+    window.warned.retain(|kind| exceeded.contains(kind));
+    let newly = exceeded
+        .iter()
+        .copied()
+        .filter(|kind| window.warned.insert(*kind))
+        .collect();
+    (newly, exceeded)
+}
+
+impl ResourceMonitor {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(MonitorState {
+                system: System::new(),
+                known: HashSet::new(),
+                windows: HashMap::new(),
+                usage: Vec::new(),
+            }),
+        }
+    }
+
+    /// Takes one sample. `registered` maps window_id -> extension_id of the
+    /// open extension windows; `limits_for` resolves an extension's limits.
+    /// Returns the limit crossings since the previous sample.
+    pub fn sample(
+        &self,
+        registered: &HashMap<String, String>,
+        limits_for: impl Fn(&str) -> ProcessLimits,
+    ) -> Vec<ResourceLimitWarning> {
+        let Ok(mut guard) = self.state.lock() else {
+            return Vec::new();
+        };
+        let state = &mut *guard;
+        let Ok(root) = sysinfo::get_current_pid() else {
+            return Vec::new();
+        };
+
+        state.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().with_memory().with_cpu(),
+        );
+        let processes = state.system.processes();
+        let parents: HashMap<u32, u32> = processes
+            .iter()
+            .filter_map(|(pid, p)| p.parent().map(|parent| (pid.as_u32(), parent.as_u32())))
+            .collect();
+        let alive = descendants(&parents, root.as_u32());
+
+        let now = Instant::now();
+        state
+            .windows
+            .retain(|window_id, _| registered.contains_key(window_id));
+        for (window_id, extension_id) in registered {
+            state
+                .windows
+                .entry(window_id.clone())
+                .or_insert_with(|| TrackedWindow::new(extension_id.clone(), now));
+        }
+
+        let new_pids: HashSet<u32> = alive.difference(&state.known).copied().collect();
+        attribute(&mut state.windows, &new_pids, now);
+        state.known = alive;
+
+        let mut warnings = Vec::new();
+        let mut usage = Vec::with_capacity(state.windows.len());
+        for (window_id, window) in state.windows.iter_mut() {
+            window.pids.retain(|pid| state.known.contains(pid));
+            let (memory_bytes, cpu_percent) = window
+                .pids
+                .iter()
+                .filter_map(|pid| processes.get(&Pid::from_u32(*pid)))
+                .fold((0u64, 0f32), |(memory, cpu), p| {
+                    (memory + p.memory(), cpu + p.cpu_usage())
+                });
+
+            let limits = limits_for(&window.extension_id);
+            let (newly, exceeded) = check_limits(window, memory_bytes, cpu_percent, &limits);
+            for resource in newly {
+                let (value, limit) = match resource {
+                    ResourceKind::Memory => (memory_bytes as f64, limits.max_memory_bytes as f64),
+                    ResourceKind::Cpu => (f64::from(cpu_percent), limits.max_cpu_percent as f64),
+                };
+                warnings.push(ResourceLimitWarning {
+                    window_id: window_id.clone(),
+                    extension_id: window.extension_id.clone(),
+                    resource,
+                    value,
+                    limit,
+                });
+            }
+
+            usage.push(ExtensionResourceUsage {
+                window_id: window_id.clone(),
+                extension_id: window.extension_id.clone(),
+                process_count: window.pids.len() as u32,
+                memory_bytes,
+                cpu_percent,
+                exceeded,
+            });
+        }
+        state.usage = usage;
+        warnings
+    }
+
+    /// Usage from the latest sample, optionally for one extension only.
+    pub fn usage(&self, extension_id: Option<&str>) -> Vec<ExtensionResourceUsage> {
+        self.state
+            .lock()
+            .map(|state| {
+                state
+                    .usage
+                    .iter()
+                    .filter(|u| extension_id.is_none_or(|id| u.extension_id == id))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Starts the sampling task. Called once at app start.
+pub fn start_resource_monitor(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            let app_handle = app_handle.clone();
+            let sampled = tauri::async_runtime::spawn_blocking(move || sample_once(&app_handle));
+            if let Err(e) = sampled.await {
+                eprintln!("[ResourceMonitor] Sampling failed: {e}");
+            }
+        }
+    });
+}
+
+fn sample_once(app_handle: &AppHandle) {
+    let state = app_handle.state::<AppState>();
+    let manager = &state.extension_webview_manager;
+    let registered = match manager.windows.lock() {
+        Ok(windows) => windows.clone(),
+        Err(_) => return,
+    };
+
+    let warnings = manager.monitor.sample(&registered, |extension_id| {
+        crate::database::core::with_connection(&state.db, |conn| {
+            state.limits.get_limits(conn, extension_id)
+        })
+        .map(|limits| limits.process)
+        .unwrap_or_default()
+    });
+
+    for warning in warnings {
+        let message = format!(
+            "Extension window {} exceeds its {:?} limit ({:.0} > {:.0})",
+            warning.window_id, warning.resource, warning.value, warning.limit
+        );
+        let _ = crate::logging::insert_log(
+            &state,
+            "warn",
+            "ResourceMonitor",
+            Some(&warning.extension_id),
+            &message,
+            serde_json::to_value(&warning).ok(),
+            "rust",
+        );
+        if let Err(e) = crate::events::emit_to_main(app_handle, &warning) {
+            eprintln!("[ResourceMonitor] Failed to emit warning: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_descendants_follow_the_tree() {
+        // 1 -> 2 -> 3, 1 -> 4, 5 -> 6 (unrelated)
+        let parents = HashMap::from([(2, 1), (3, 2), (4, 1), (6, 5)]);
+        assert_eq!(descendants(&parents, 1), HashSet::from([2, 3, 4]));
+        assert!(descendants(&parents, 3).is_empty());
+    }
+
+    #[test]
+    fn test_new_processes_go_to_the_only_young_window() {
+        let start = Instant::now();
+        let mut windows =
+            HashMap::from([("w1".to_string(), TrackedWindow::new("ext".into(), start))]);
+
+        attribute(&mut windows, &HashSet::from([10, 11]), start);
+        assert_eq!(windows["w1"].pids, HashSet::from([10, 11]));
+
+        // Two young windows: ambiguous, nobody gets the process
+        windows.insert("w2".to_string(), TrackedWindow::new("ext2".into(), start));
+        attribute(&mut windows, &HashSet::from([12]), start);
+        assert!(!windows.values().any(|w| w.pids.contains(&12)));
+
+        // Too old: processes spawned later are not the window's
+        let mut old = HashMap::from([("w3".to_string(), TrackedWindow::new("ext".into(), start))]);
+        attribute(
+            &mut old,
+            &HashSet::from([13]),
+            start + ATTRIBUTION_WINDOW * 2,
+        );
+        assert!(old["w3"].pids.is_empty());
+    }
+
+    #[test]
+    fn test_limits_warn_once_per_crossing() {
+        let limits = ProcessLimits {
+            max_memory_bytes: 100,
+            max_cpu_percent: 50,
+        };
+        let mut window = TrackedWindow::new("ext".into(), Instant::now());
+
+        let (newly, exceeded) = check_limits(&mut window, 200, 0.0, &limits);
+        assert_eq!(newly, vec![ResourceKind::Memory]);
+        assert_eq!(exceeded, vec![ResourceKind::Memory]);
+
+        let (newly, exceeded) = check_limits(&mut window, 200, 0.0, &limits);
+        assert!(newly.is_empty());
+        assert_eq!(exceeded, vec![ResourceKind::Memory]);
+
+        // Back under the limit, then over again: warns again
+        check_limits(&mut window, 50, 0.0, &limits);
+        let (newly, _) = check_limits(&mut window, 200, 0.0, &limits);
+        assert_eq!(newly, vec![ResourceKind::Memory]);
+    }
+
+    #[test]
+    fn test_cpu_needs_sustained_load() {
+        let limits = ProcessLimits {
+            max_memory_bytes: i64::MAX,
+            max_cpu_percent: 50,
+        };
+        let mut window = TrackedWindow::new("ext".into(), Instant::now());
+
+        for _ in 1..CPU_STRIKES {
+            assert!(check_limits(&mut window, 0, 99.0, &limits).1.is_empty());
+        }
+        let (newly, _) = check_limits(&mut window, 0, 99.0, &limits);
+        assert_eq!(newly, vec![ResourceKind::Cpu]);
+
+        // A single calm sample resets the counter
+        check_limits(&mut window, 0, 10.0, &limits);
+        assert!(check_limits(&mut window, 0, 99.0, &limits).1.is_empty());
+    }
+}
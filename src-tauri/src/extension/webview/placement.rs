@@ -0,0 +1,413 @@
+//! Monitor-aware placement for extension windows.
+//!
+//! All geometry here is in **logical** pixels per monitor (physical / scale
+//! factor), which is what `WebviewWindowBuilder::position` / `inner_size`
+//! expect. Restored positions come from the frontend and may reference a
+//! monitor that is no longer connected or whose scale factor changed — the
+//! helpers below make sure a window never ends up off-screen.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Monitor};
+
+/// Minimum part of a window (in logical px) that must stay inside a work
+/// area so the title bar can still be grabbed.
+const MIN_VISIBLE: f64 = 48.0;
+
+/// Distance (in logical px) within which a window edge snaps to the work
+/// area edge when `snap_threshold` placement is used.
+const DEFAULT_SNAP_THRESHOLD: f64 = 16.0;
+
+/// Logical rectangle (x/y = top-left corner).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogicalRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl LogicalRect {
+    fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    fn contains_point(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    fn intersection_area(&self, other: &LogicalRect) -> f64 {
+        let w = self.right().min(other.right()) - self.x.max(other.x);
+        let h = self.bottom().min(other.bottom()) - self.y.max(other.y);
+        if w <= 0.0 || h <= 0.0 {
+            0.0
+        } else {
+            w * h
+        }
+    }
+}
+
+/// Monitor description returned by `extension_webview_get_monitors`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub scale_factor: f64,
+    /// Full monitor bounds in logical pixels.
+    pub bounds: LogicalRect,
+    /// Usable area (without taskbar/dock) in logical pixels.
+    pub work_area: LogicalRect,
+    pub is_primary: bool,
+}
+
+impl MonitorInfo {
+    pub fn from_tauri(monitor: &Monitor, primary_name: Option<&String>) -> Self {
+        let scale = monitor.scale_factor();
+        let pos = monitor.position();
+        let size = monitor.size();
+        let work = monitor.work_area();
+        Self {
+            name: monitor.name().cloned(),
+            scale_factor: scale,
+            bounds: LogicalRect {
+                x: pos.x as f64 / scale,
+                y: pos.y as f64 / scale,
+                width: size.width as f64 / scale,
+                height: size.height as f64 / scale,
+            },
+            work_area: LogicalRect {
+                x: work.position.x as f64 / scale,
+                y: work.position.y as f64 / scale,
+                width: work.size.width as f64 / scale,
+                height: work.size.height as f64 / scale,
+            },
+            is_primary: primary_name.is_some() && monitor.name() == primary_name,
+        }
+    }
+}
+
+/// Edge (or corner) of the work area a window should snap to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapEdge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    /// Fill the whole work area.
+    Fill,
+}
+
+/// Placement options for `open_extension_webview_window`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowPlacementOptions {
+    /// Allow the window to extend past the visible work area. By default
+    /// windows are clamped so they can't open off-screen.
+    #[serde(default)]
+    pub skip_clamp: bool,
+    /// Snap explicitly to an edge/corner of the target monitor's work area.
+    /// Left/Right/Top/Bottom use half of the work area.
+    #[serde(default)]
+    pub snap_to: Option<SnapEdge>,
+    /// Snap edges that are closer than this many logical pixels to a work
+    /// area edge. `None` disables proximity snapping, `Some(0)` uses the default.
+    #[serde(default)]
+    pub snap_threshold: Option<f64>,
+    /// Target monitor by name. Falls back to the monitor containing the
+    /// requested position, then to the primary monitor.
+    #[serde(default)]
+    pub monitor: Option<String>,
+}
+
+/// Enumerates the currently connected monitors.
+pub fn list_monitors(app_handle: &AppHandle) -> Result<Vec<MonitorInfo>, tauri::Error> {
+    let primary = app_handle.primary_monitor()?;
+    let primary_name = primary.as_ref().and_then(|m| m.name().cloned());
+    Ok(app_handle
+        .available_monitors()?
+        .iter()
+        .map(|m| MonitorInfo::from_tauri(m, primary_name.as_ref()))
+        .collect())
+}
+
+/// Picks the monitor a window should be placed on.
+fn pick_monitor<'a>(
+    monitors: &'a [MonitorInfo],
+    rect: &LogicalRect,
+    preferred: Option<&str>,
+) -> Option<&'a MonitorInfo> {
+    if let Some(name) = preferred {
+        if let Some(m) = monitors.iter().find(|m| m.name.as_deref() == Some(name)) {
+            return Some(m);
+        }
+    }
+
+    // Monitor with the largest overlap, then the one containing the top-left
+    // corner, then primary, then whatever is first.
+    monitors
+        .iter()
+        .map(|m| (m, m.bounds.intersection_area(rect)))
+        .filter(|(_, area)| *area > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(m, _)| m)
+        .or_else(|| monitors.iter().find(|m| m.bounds.contains_point(rect.x, rect.y)))
+        .or_else(|| monitors.iter().find(|m| m.is_primary))
+        .or_else(|| monitors.first())
+}
+
+/// Clamps a window rect so it fits the work area. Oversized windows are
+/// shrunk; otherwise at least `MIN_VISIBLE` px stay visible on each axis and
+/// the title bar is never above the work area.
+pub fn clamp_to_work_area(rect: LogicalRect, work_area: &LogicalRect) -> LogicalRect {
+    let width = rect.width.min(work_area.width);
+    let height = rect.height.min(work_area.height);
+
+    let min_x = work_area.x - width + MIN_VISIBLE.min(width);
+    let max_x = work_area.right() - MIN_VISIBLE.min(width);
+    let min_y = work_area.y;
+    let max_y = work_area.bottom() - MIN_VISIBLE.min(height);
+
+    LogicalRect {
+        x: rect.x.clamp(min_x, max_x.max(min_x)),
+        y: rect.y.clamp(min_y, max_y.max(min_y)),
+        width,
+        height,
+    }
+}
+
+/// Computes the rect for an explicit snap target inside a work area.
+pub fn snap_rect(edge: SnapEdge, work_area: &LogicalRect) -> LogicalRect {
+    let half_w = work_area.width / 2.0;
+    let half_h = work_area.height / 2.0;
+    let (x, y, width, height) = match edge {
+        SnapEdge::Left => (work_area.x, work_area.y, half_w, work_area.height),
+        SnapEdge::Right => (work_area.x + half_w, work_area.y, half_w, work_area.height),
+        SnapEdge::Top => (work_area.x, work_area.y, work_area.width, half_h),
+        SnapEdge::Bottom => (work_area.x, work_area.y + half_h, work_area.width, half_h),
+        SnapEdge::TopLeft => (work_area.x, work_area.y, half_w, half_h),
+        SnapEdge::TopRight => (work_area.x + half_w, work_area.y, half_w, half_h),
+        SnapEdge::BottomLeft => (work_area.x, work_area.y + half_h, half_w, half_h),
+        SnapEdge::BottomRight => (work_area.x + half_w, work_area.y + half_h, half_w, half_h),
+        SnapEdge::Fill => (work_area.x, work_area.y, work_area.width, work_area.height),
+    };
+    LogicalRect {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+/// Moves window edges that are within `threshold` of a work area edge onto
+/// that edge. Size is left untouched.
+pub fn snap_to_nearby_edges(rect: LogicalRect, work_area: &LogicalRect, threshold: f64) -> LogicalRect {
+    let mut out = rect;
+    if (rect.x - work_area.x).abs() <= threshold {
+        out.x = work_area.x;
+    } else if (work_area.right() - rect.right()).abs() <= threshold {
+        out.x = work_area.right() - rect.width;
+    }
+    if (rect.y - work_area.y).abs() <= threshold {
+        out.y = work_area.y;
+    } else if (work_area.bottom() - rect.bottom()).abs() <= threshold {
+        out.y = work_area.bottom() - rect.height;
+    }
+    out
+}
+
+/// Resolves the final window rect.
+///
+/// `position` is `None` when the caller wants a centered window; in that case
+/// the window is centered on the chosen monitor's work area (instead of the
+/// OS default, which can be a disconnected monitor).
+pub fn resolve_placement(
+    monitors: &[MonitorInfo],
+    position: Option<(f64, f64)>,
+    width: f64,
+    height: f64,
+    options: &WindowPlacementOptions,
+) -> Option<LogicalRect> {
+    let requested = LogicalRect {
+        x: position.map(|p| p.0).unwrap_or(0.0),
+        y: position.map(|p| p.1).unwrap_or(0.0),
+        width,
+        height,
+    };
+
+    let monitor = pick_monitor(monitors, &requested, options.monitor.as_deref())?;
+    let work = &monitor.work_area;
+
+    if let Some(edge) = options.snap_to {
+        return Some(snap_rect(edge, work));
+    }
+
+    let mut rect = if position.is_some() {
+        requested
+    } else {
+        LogicalRect {
+            x: work.x + (work.width - width).max(0.0) / 2.0,
+            y: work.y + (work.height - height).max(0.0) / 2.0,
+            width,
+            height,
+        }
+    };
+
+    if let Some(threshold) = options.snap_threshold {
+        let threshold = if threshold > 0.0 {
+            threshold
+        } else {
+            DEFAULT_SNAP_THRESHOLD
+        };
+        rect = snap_to_nearby_edges(rect, work, threshold);
+    }
+
+    if !options.skip_clamp {
+        rect = clamp_to_work_area(rect, work);
+    }
+
+    Some(rect)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: f64, width: f64, primary: bool) -> MonitorInfo {
+        let bounds = LogicalRect {
+            x,
+            y: 0.0,
+            width,
+            height: 1080.0,
+        };
+        MonitorInfo {
+            name: Some(name.to_string()),
+            scale_factor: 1.0,
+            bounds,
+            // 40px taskbar at the bottom
+            work_area: LogicalRect {
+                height: 1040.0,
+                ..bounds
+            },
+            is_primary: primary,
+        }
+    }
+
+    #[test]
+    fn test_offscreen_position_is_moved_to_primary() {
+        let monitors = vec![monitor("a", 0.0, 1920.0, true)];
+        let rect = resolve_placement(
+            &monitors,
+            Some((5000.0, 3000.0)),
+            800.0,
+            600.0,
+            &WindowPlacementOptions::default(),
+        )
+        .unwrap();
+        assert!(rect.x <= 1920.0 - MIN_VISIBLE);
+        assert!(rect.y <= 1040.0 - MIN_VISIBLE);
+    }
+
+    #[test]
+    fn test_title_bar_never_above_work_area() {
+        let work = monitor("a", 0.0, 1920.0, true).work_area;
+        let rect = clamp_to_work_area(
+            LogicalRect {
+                x: 100.0,
+                y: -200.0,
+                width: 400.0,
+                height: 300.0,
+            },
+            &work,
+        );
+        assert_eq!(rect.y, 0.0);
+    }
+
+    #[test]
+    fn test_oversized_window_is_shrunk() {
+        let work = monitor("a", 0.0, 1280.0, true).work_area;
+        let rect = clamp_to_work_area(
+            LogicalRect {
+                x: 0.0,
+                y: 0.0,
+                width: 4000.0,
+                height: 4000.0,
+            },
+            &work,
+        );
+        assert_eq!(rect.width, 1280.0);
+        assert_eq!(rect.height, 1040.0);
+    }
+
+    #[test]
+    fn test_window_on_second_monitor_stays_there() {
+        let monitors = vec![monitor("a", 0.0, 1920.0, true), monitor("b", 1920.0, 1920.0, false)];
+        let rect = resolve_placement(
+            &monitors,
+            Some((2000.0, 100.0)),
+            800.0,
+            600.0,
+            &WindowPlacementOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(rect.x, 2000.0);
+        assert_eq!(rect.y, 100.0);
+    }
+
+    #[test]
+    fn test_centered_on_named_monitor() {
+        let monitors = vec![monitor("a", 0.0, 1920.0, true), monitor("b", 1920.0, 1920.0, false)];
+        let rect = resolve_placement(
+            &monitors,
+            None,
+            800.0,
+            600.0,
+            &WindowPlacementOptions {
+                monitor: Some("b".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(rect.x, 1920.0 + 560.0);
+        assert_eq!(rect.y, 220.0);
+    }
+
+    #[test]
+    fn test_snap_right_half() {
+        let work = monitor("a", 0.0, 1920.0, true).work_area;
+        let rect = snap_rect(SnapEdge::Right, &work);
+        assert_eq!(rect.x, 960.0);
+        assert_eq!(rect.width, 960.0);
+        assert_eq!(rect.height, 1040.0);
+    }
+
+    #[test]
+    fn test_proximity_snap() {
+        let work = monitor("a", 0.0, 1920.0, true).work_area;
+        let rect = snap_to_nearby_edges(
+            LogicalRect {
+                x: 10.0,
+                y: 300.0,
+                width: 400.0,
+                height: 735.0,
+            },
+            &work,
+            DEFAULT_SNAP_THRESHOLD,
+        );
+        assert_eq!(rect.x, 0.0);
+        assert_eq!(rect.y, 1040.0 - 735.0);
+    }
+
+    #[test]
+    fn test_no_monitors_yields_none() {
+        assert!(resolve_placement(&[], None, 800.0, 600.0, &WindowPlacementOptions::default()).is_none());
+    }
+}
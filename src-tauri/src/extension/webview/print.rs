@@ -0,0 +1,371 @@
+//! Print and PDF export for native extension windows (desktop only).
+//!
+//! `extension_webview_print` opens the system print dialog for the calling
+//! extension window. `extension_export_pdf` renders the window's current
+//! document to a PDF file at a path the extension has `fs` write permission
+//! for — the same check `extension_filesystem_write_file` applies.
+//!
+//! PDF export uses WebKitGTK's print-to-file backend and is currently
+//! available on Linux only; other platforms return a validation error so
+//! SDKs can fall back to generating the PDF themselves.
+
+use crate::extension::error::ExtensionError;
+use crate::extension::permissions::manager::PermissionManager;
+use crate::extension::permissions::types::{Action, FsAction};
+use crate::extension::utils::emit_permission_prompt_if_needed;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State, WebviewWindow};
+
+use super::helpers::get_extension_id;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PageOrientation {
+    #[default]
+    Portrait,
+    Landscape,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PaperSize {
+    #[default]
+    A4,
+    A3,
+    A5,
+    Letter,
+    Legal,
+}
+
+impl PaperSize {
+    /// PWG 5101.1 media name as understood by GTK.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn pwg_name(self) -> &'static str {
+        match self {
+            PaperSize::A4 => "iso_a4",
+            PaperSize::A3 => "iso_a3",
+            PaperSize::A5 => "iso_a5",
+            PaperSize::Letter => "na_letter",
+            PaperSize::Legal => "na_legal",
+        }
+    }
+}
+
+/// Page margins in millimeters.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageMargins {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+/// Page setup shared by print and PDF export.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageSetup {
+    #[serde(default)]
+    pub orientation: PageOrientation,
+    #[serde(default)]
+    pub paper_size: PaperSize,
+    #[serde(default)]
+    pub margins: Option<PageMargins>,
+    /// Scale factor in percent (10–400). Defaults to 100.
+    #[serde(default)]
+    pub scale: Option<f64>,
+}
+
+impl PageSetup {
+    pub fn validate(&self) -> Result<(), ExtensionError> {
+        if let Some(scale) = self.scale {
+            if !(10.0..=400.0).contains(&scale) {
+                return Err(ExtensionError::ValidationError {
+                    reason: format!("Print scale must be between 10 and 400, got {scale}"),
+                });
+            }
+        }
+        if let Some(m) = self.margins {
+            if [m.top, m.right, m.bottom, m.left]
+                .iter()
+                .any(|v| !v.is_finite() || *v < 0.0 || *v > 100.0)
+            {
+                return Err(ExtensionError::ValidationError {
+                    reason: "Page margins must be between 0 and 100 mm".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPdfResult {
+    pub path: String,
+    pub size: u64,
+}
+
+/// Opens the system print dialog for the calling extension window.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_webview_print(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    page_setup: Option<PageSetup>,
+) -> Result<(), ExtensionError> {
+    let extension_id = get_extension_id(&window, &state)?;
+    let page_setup = page_setup.unwrap_or_default();
+    page_setup.validate()?;
+
+    eprintln!(
+        "[Print] Extension {} requested print ({:?})",
+        extension_id, page_setup
+    );
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::run_print_dialog(&window, &page_setup)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        // Page setup can only be applied from the native dialog here.
+        window.print().map_err(|e| ExtensionError::ValidationError {
+            reason: format!("Failed to print: {e}"),
+        })
+    }
+}
+
+/// Exports the calling extension window's document as a PDF file.
+/// Requires `fs` write permission for `path`.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_export_pdf(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    path: String,
+    page_setup: Option<PageSetup>,
+) -> Result<ExportPdfResult, ExtensionError> {
+    let extension_id = get_extension_id(&window, &state)?;
+    let page_setup = page_setup.unwrap_or_default();
+    page_setup.validate()?;
+
+    let target = validate_pdf_path(&path)?;
+
+    let permission_result = PermissionManager::check_filesystem_permission(
+        &state,
+        &extension_id,
+        Action::Filesystem(FsAction::ReadWrite),
+        &target,
+    )
+    .await;
+    if let Err(ref e) = permission_result {
+        emit_permission_prompt_if_needed(&app_handle, e);
+    }
+    permission_result?;
+
+    eprintln!(
+        "[Print] Extension {} exporting PDF to {}",
+        extension_id,
+        target.display()
+    );
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::export_pdf(&window, &target, &page_setup).await?;
+        let size = std::fs::metadata(&target)
+            .map_err(|e| ExtensionError::filesystem_with_path(target.display().to_string(), e))?
+            .len();
+        Ok(ExportPdfResult {
+            path: target.to_string_lossy().to_string(),
+            size,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (&window, &target);
+        Err(ExtensionError::ValidationError {
+            reason: "PDF export is not supported on this platform".to_string(),
+        })
+    }
+}
+
+/// PDF targets must be absolute, end in `.pdf` and live in an existing directory.
+fn validate_pdf_path(path: &str) -> Result<PathBuf, ExtensionError> {
+    let target = PathBuf::from(path);
+    if !target.is_absolute() {
+        return Err(ExtensionError::ValidationError {
+            reason: format!("PDF path must be absolute: {path}"),
+        });
+    }
+    let is_pdf = target
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("pdf"));
+    if !is_pdf {
+        return Err(ExtensionError::ValidationError {
+            reason: format!("PDF path must end with .pdf: {path}"),
+        });
+    }
+    let parent_exists = target.parent().is_some_and(Path::is_dir);
+    if !parent_exists {
+        return Err(ExtensionError::ValidationError {
+            reason: format!("Parent directory does not exist: {path}"),
+        });
+    }
+    Ok(target)
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::{PageOrientation, PageSetup};
+    use crate::extension::error::ExtensionError;
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+    use tauri::WebviewWindow;
+
+    fn gtk_page_setup(setup: &PageSetup) -> (gtk::PageSetup, gtk::PrintSettings) {
+        let paper = gtk::PaperSize::new(Some(setup.paper_size.pwg_name()));
+        let orientation = match setup.orientation {
+            PageOrientation::Portrait => gtk::PageOrientation::Portrait,
+            PageOrientation::Landscape => gtk::PageOrientation::Landscape,
+        };
+
+        let page_setup = gtk::PageSetup::new();
+        page_setup.set_paper_size(&paper);
+        page_setup.set_orientation(orientation);
+        if let Some(m) = setup.margins {
+            page_setup.set_top_margin(m.top, gtk::Unit::Mm);
+            page_setup.set_right_margin(m.right, gtk::Unit::Mm);
+            page_setup.set_bottom_margin(m.bottom, gtk::Unit::Mm);
+            page_setup.set_left_margin(m.left, gtk::Unit::Mm);
+        }
+
+        let settings = gtk::PrintSettings::new();
+        settings.set_paper_size(&paper);
+        settings.set_orientation(orientation);
+        settings.set_scale(setup.scale.unwrap_or(100.0));
+
+        (page_setup, settings)
+    }
+
+    pub fn run_print_dialog(window: &WebviewWindow, setup: &PageSetup) -> Result<(), ExtensionError> {
+        let setup = setup.clone();
+        window
+            .with_webview(move |webview| {
+                use webkit2gtk::PrintOperationExt;
+                let (page_setup, settings) = gtk_page_setup(&setup);
+                let op = webkit2gtk::PrintOperation::new(&webview.inner());
+                op.set_page_setup(&page_setup);
+                op.set_print_settings(&settings);
+                op.run_dialog(None::<&gtk::Window>);
+            })
+            .map_err(|e| ExtensionError::ValidationError {
+                reason: format!("Failed to open print dialog: {e}"),
+            })
+    }
+
+    pub async fn export_pdf(
+        window: &WebviewWindow,
+        target: &Path,
+        setup: &PageSetup,
+    ) -> Result<(), ExtensionError> {
+        let uri = url::Url::from_file_path(target)
+            .map_err(|_| ExtensionError::ValidationError {
+                reason: format!("Invalid PDF path: {}", target.display()),
+            })?
+            .to_string();
+        let setup = setup.clone();
+
+        // The print operation completes asynchronously on the GTK main loop;
+        // `finished`/`failed` are `Fn` callbacks, hence the Option slot.
+        let (tx, rx) = tokio::sync::oneshot::channel::<Result<(), String>>();
+        let tx = Arc::new(Mutex::new(Some(tx)));
+
+        window
+            .with_webview(move |webview| {
+                use webkit2gtk::PrintOperationExt;
+                let (page_setup, settings) = gtk_page_setup(&setup);
+                settings.set_printer("Print to File");
+                settings.set("output-file-format", Some("pdf"));
+                settings.set("output-uri", Some(&uri));
+
+                let op = webkit2gtk::PrintOperation::new(&webview.inner());
+                op.set_page_setup(&page_setup);
+                op.set_print_settings(&settings);
+
+                let tx_failed = tx.clone();
+                op.connect_failed(move |_, err| {
+                    if let Some(tx) = tx_failed.lock().ok().and_then(|mut t| t.take()) {
+                        let _ = tx.send(Err(err.to_string()));
+                    }
+                });
+                op.connect_finished(move |_| {
+                    if let Some(tx) = tx.lock().ok().and_then(|mut t| t.take()) {
+                        let _ = tx.send(Ok(()));
+                    }
+                });
+                op.print();
+            })
+            .map_err(|e| ExtensionError::ValidationError {
+                reason: format!("Failed to start PDF export: {e}"),
+            })?;
+
+        match rx.await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(reason)) => Err(ExtensionError::ValidationError {
+                reason: format!("PDF export failed: {reason}"),
+            }),
+            Err(_) => Err(ExtensionError::ValidationError {
+                reason: "PDF export was aborted".to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_setup_defaults() {
+        let setup: PageSetup = serde_json::from_str("{}").unwrap();
+        assert_eq!(setup.orientation, PageOrientation::Portrait);
+        assert_eq!(setup.paper_size, PaperSize::A4);
+        assert!(setup.validate().is_ok());
+    }
+
+    #[test]
+    fn test_page_setup_rejects_invalid_scale_and_margins() {
+        let setup = PageSetup {
+            scale: Some(1000.0),
+            ..Default::default()
+        };
+        assert!(setup.validate().is_err());
+
+        let setup = PageSetup {
+            margins: Some(PageMargins {
+                top: -1.0,
+                right: 0.0,
+                bottom: 0.0,
+                left: 0.0,
+            }),
+            ..Default::default()
+        };
+        assert!(setup.validate().is_err());
+    }
+
+    #[test]
+    fn test_pdf_path_validation() {
+        let dir = tempfile::tempdir().unwrap();
+        let ok = dir.path().join("invoice.PDF");
+        assert!(validate_pdf_path(ok.to_str().unwrap()).is_ok());
+
+        assert!(validate_pdf_path("relative.pdf").is_err());
+        assert!(validate_pdf_path(dir.path().join("x.txt").to_str().unwrap()).is_err());
+        assert!(validate_pdf_path(dir.path().join("missing/x.pdf").to_str().unwrap()).is_err());
+    }
+}
@@ -4,17 +4,17 @@
 //! managed via Drizzle migrations.
 //! All SQL operations use CRDT-compatible execution via the core database functions.
 //! The CRDT functions automatically handle tombstone filtering.
+//! Row reads and inserts use the typed models from `database::generated`.
 
+use crate::database::generated::{
+    HaexExternalAuthorizedClientsNoSync, HaexExternalBlockedClientsNoSync,
+};
 use crate::table_names::{
     // Authorized clients table and columns
-    COL_EXTERNAL_AUTHORIZED_CLIENTS_AUTHORIZED_AT, COL_EXTERNAL_AUTHORIZED_CLIENTS_CLIENT_ID,
-    COL_EXTERNAL_AUTHORIZED_CLIENTS_CLIENT_NAME, COL_EXTERNAL_AUTHORIZED_CLIENTS_EXTENSION_ID,
-    COL_EXTERNAL_AUTHORIZED_CLIENTS_ID, COL_EXTERNAL_AUTHORIZED_CLIENTS_LAST_SEEN,
-    COL_EXTERNAL_AUTHORIZED_CLIENTS_PUBLIC_KEY, TABLE_EXTERNAL_AUTHORIZED_CLIENTS,
+    COL_EXTERNAL_AUTHORIZED_CLIENTS_CLIENT_ID, COL_EXTERNAL_AUTHORIZED_CLIENTS_EXTENSION_ID,
+    COL_EXTERNAL_AUTHORIZED_CLIENTS_LAST_SEEN, TABLE_EXTERNAL_AUTHORIZED_CLIENTS,
     // Blocked clients table and columns
-    COL_EXTERNAL_BLOCKED_CLIENTS_BLOCKED_AT, COL_EXTERNAL_BLOCKED_CLIENTS_CLIENT_ID,
-    COL_EXTERNAL_BLOCKED_CLIENTS_CLIENT_NAME, COL_EXTERNAL_BLOCKED_CLIENTS_ID,
-    COL_EXTERNAL_BLOCKED_CLIENTS_PUBLIC_KEY, TABLE_EXTERNAL_BLOCKED_CLIENTS,
+    COL_EXTERNAL_BLOCKED_CLIENTS_CLIENT_ID, TABLE_EXTERNAL_BLOCKED_CLIENTS,
     // Extensions table
     TABLE_EXTENSIONS,
 };
@@ -96,32 +96,6 @@ lazy_static::lazy_static! {
          WHERE {COL_EXTERNAL_AUTHORIZED_CLIENTS_CLIENT_ID} = ?1"
     );
 
-    pub static ref SQL_GET_CLIENT: String = format!(
-        "SELECT {COL_EXTERNAL_AUTHORIZED_CLIENTS_ID}, {COL_EXTERNAL_AUTHORIZED_CLIENTS_CLIENT_ID}, \
-         {COL_EXTERNAL_AUTHORIZED_CLIENTS_CLIENT_NAME}, {COL_EXTERNAL_AUTHORIZED_CLIENTS_PUBLIC_KEY}, \
-         {COL_EXTERNAL_AUTHORIZED_CLIENTS_EXTENSION_ID}, {COL_EXTERNAL_AUTHORIZED_CLIENTS_AUTHORIZED_AT}, \
-         {COL_EXTERNAL_AUTHORIZED_CLIENTS_LAST_SEEN}
-         FROM {TABLE_EXTERNAL_AUTHORIZED_CLIENTS}
-         WHERE {COL_EXTERNAL_AUTHORIZED_CLIENTS_CLIENT_ID} = ?1"
-    );
-
-    pub static ref SQL_GET_ALL_CLIENTS: String = format!(
-        "SELECT {COL_EXTERNAL_AUTHORIZED_CLIENTS_ID}, {COL_EXTERNAL_AUTHORIZED_CLIENTS_CLIENT_ID}, \
-         {COL_EXTERNAL_AUTHORIZED_CLIENTS_CLIENT_NAME}, {COL_EXTERNAL_AUTHORIZED_CLIENTS_PUBLIC_KEY}, \
-         {COL_EXTERNAL_AUTHORIZED_CLIENTS_EXTENSION_ID}, {COL_EXTERNAL_AUTHORIZED_CLIENTS_AUTHORIZED_AT}, \
-         {COL_EXTERNAL_AUTHORIZED_CLIENTS_LAST_SEEN}
-         FROM {TABLE_EXTERNAL_AUTHORIZED_CLIENTS}
-         ORDER BY {COL_EXTERNAL_AUTHORIZED_CLIENTS_AUTHORIZED_AT} DESC"
-    );
-
-    pub static ref SQL_INSERT_CLIENT: String = format!(
-        "INSERT INTO {TABLE_EXTERNAL_AUTHORIZED_CLIENTS} \
-         ({COL_EXTERNAL_AUTHORIZED_CLIENTS_ID}, {COL_EXTERNAL_AUTHORIZED_CLIENTS_CLIENT_ID}, \
-          {COL_EXTERNAL_AUTHORIZED_CLIENTS_CLIENT_NAME}, {COL_EXTERNAL_AUTHORIZED_CLIENTS_PUBLIC_KEY}, \
-          {COL_EXTERNAL_AUTHORIZED_CLIENTS_EXTENSION_ID})
-         VALUES (?1, ?2, ?3, ?4, ?5)"
-    );
-
     pub static ref SQL_UPDATE_LAST_SEEN: String = format!(
         "UPDATE {TABLE_EXTERNAL_AUTHORIZED_CLIENTS}
          SET {COL_EXTERNAL_AUTHORIZED_CLIENTS_LAST_SEEN} = datetime('now')
@@ -144,29 +118,6 @@ lazy_static::lazy_static! {
          WHERE {COL_EXTERNAL_BLOCKED_CLIENTS_CLIENT_ID} = ?1"
     );
 
-    pub static ref SQL_GET_BLOCKED_CLIENT: String = format!(
-        "SELECT {COL_EXTERNAL_BLOCKED_CLIENTS_ID}, {COL_EXTERNAL_BLOCKED_CLIENTS_CLIENT_ID}, \
-         {COL_EXTERNAL_BLOCKED_CLIENTS_CLIENT_NAME}, {COL_EXTERNAL_BLOCKED_CLIENTS_PUBLIC_KEY}, \
-         {COL_EXTERNAL_BLOCKED_CLIENTS_BLOCKED_AT}
-         FROM {TABLE_EXTERNAL_BLOCKED_CLIENTS}
-         WHERE {COL_EXTERNAL_BLOCKED_CLIENTS_CLIENT_ID} = ?1"
-    );
-
-    pub static ref SQL_GET_ALL_BLOCKED_CLIENTS: String = format!(
-        "SELECT {COL_EXTERNAL_BLOCKED_CLIENTS_ID}, {COL_EXTERNAL_BLOCKED_CLIENTS_CLIENT_ID}, \
-         {COL_EXTERNAL_BLOCKED_CLIENTS_CLIENT_NAME}, {COL_EXTERNAL_BLOCKED_CLIENTS_PUBLIC_KEY}, \
-         {COL_EXTERNAL_BLOCKED_CLIENTS_BLOCKED_AT}
-         FROM {TABLE_EXTERNAL_BLOCKED_CLIENTS}
-         ORDER BY {COL_EXTERNAL_BLOCKED_CLIENTS_BLOCKED_AT} DESC"
-    );
-
-    pub static ref SQL_INSERT_BLOCKED_CLIENT: String = format!(
-        "INSERT INTO {TABLE_EXTERNAL_BLOCKED_CLIENTS} \
-         ({COL_EXTERNAL_BLOCKED_CLIENTS_ID}, {COL_EXTERNAL_BLOCKED_CLIENTS_CLIENT_ID}, \
-          {COL_EXTERNAL_BLOCKED_CLIENTS_CLIENT_NAME}, {COL_EXTERNAL_BLOCKED_CLIENTS_PUBLIC_KEY})
-         VALUES (?1, ?2, ?3, ?4)"
-    );
-
     pub static ref SQL_DELETE_BLOCKED_CLIENT: String = format!(
         "DELETE FROM {TABLE_EXTERNAL_BLOCKED_CLIENTS}
          WHERE {COL_EXTERNAL_BLOCKED_CLIENTS_CLIENT_ID} = ?1"
@@ -191,36 +142,36 @@ lazy_static::lazy_static! {
         "SELECT id FROM {TABLE_EXTENSIONS}
          WHERE public_key = ?1 AND name = ?2"
     );
+
+    /// Get an extension's public_key and name by ID (requests addressed by `extensionId`)
+    pub static ref SQL_GET_EXTENSION_BY_ID: String = format!(
+        "SELECT public_key, name FROM {TABLE_EXTENSIONS}
+         WHERE id = ?1"
+    );
 }
 
-/// Helper to parse authorized client from query result row
-pub fn parse_authorized_client(row: &[serde_json::Value]) -> Option<AuthorizedClient> {
-    if row.len() < 7 {
-        return None;
+impl From<HaexExternalAuthorizedClientsNoSync> for AuthorizedClient {
+    fn from(row: HaexExternalAuthorizedClientsNoSync) -> Self {
+        Self {
+            id: row.id,
+            client_id: row.client_id,
+            client_name: row.client_name,
+            public_key: row.public_key,
+            extension_id: row.extension_id,
+            authorized_at: row.authorized_at,
+            last_seen: row.last_seen,
+        }
     }
-
-    Some(AuthorizedClient {
-        id: row[0].as_str()?.to_string(),
-        client_id: row[1].as_str()?.to_string(),
-        client_name: row[2].as_str()?.to_string(),
-        public_key: row[3].as_str()?.to_string(),
-        extension_id: row[4].as_str()?.to_string(),
-        authorized_at: row[5].as_str().map(|s| s.to_string()),
-        last_seen: row[6].as_str().map(|s| s.to_string()),
-    })
 }
 
-/// Helper to parse blocked client from query result row
-pub fn parse_blocked_client(row: &[serde_json::Value]) -> Option<BlockedClient> {
-    if row.len() < 5 {
-        return None;
+impl From<HaexExternalBlockedClientsNoSync> for BlockedClient {
+    fn from(row: HaexExternalBlockedClientsNoSync) -> Self {
+        Self {
+            id: row.id,
+            client_id: row.client_id,
+            client_name: row.client_name,
+            public_key: row.public_key,
+            blocked_at: row.blocked_at,
+        }
     }
-
-    Some(BlockedClient {
-        id: row[0].as_str()?.to_string(),
-        client_id: row[1].as_str()?.to_string(),
-        client_name: row[2].as_str()?.to_string(),
-        public_key: row[3].as_str()?.to_string(),
-        blocked_at: row[4].as_str().map(|s| s.to_string()),
-    })
 }
@@ -0,0 +1,83 @@
+//! Error types of the bridge credential protocol
+
+use crate::database::error::DatabaseError;
+use crate::passwords::autofill::error::AutofillError;
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize)]
+#[serde(tag = "type", content = "details")]
+pub enum CredentialError {
+    #[error("Unknown credential action: {action}")]
+    UnknownAction { action: String },
+
+    #[error("Invalid request: {reason}")]
+    InvalidRequest { reason: String },
+
+    #[error("Invalid origin: {reason}")]
+    InvalidOrigin { reason: String },
+
+    /// The user declined, now or in a remembered decision
+    #[error("The user denied the request")]
+    Denied,
+
+    #[error("A prompt for this origin is already open")]
+    PromptInProgress,
+
+    #[error("No answer from the user within {timeout_secs} seconds")]
+    Timeout { timeout_secs: u64 },
+
+    #[error("Credential prompt not found: {id}")]
+    PromptNotFound { id: String },
+
+    /// No vault is open
+    #[error("The vault is locked")]
+    VaultLocked,
+
+    #[error("Database error: {reason}")]
+    Database { reason: String },
+
+    #[error("Internal error: {reason}")]
+    Internal { reason: String },
+}
+
+impl CredentialError {
+    /// Stable code sent to the client next to the message
+    pub fn code(&self) -> &'static str {
+        match self {
+            CredentialError::UnknownAction { .. } => "UNKNOWN_ACTION",
+            CredentialError::InvalidRequest { .. } => "INVALID_REQUEST",
+            CredentialError::InvalidOrigin { .. } => "INVALID_ORIGIN",
+            CredentialError::Denied => "DENIED",
+            CredentialError::PromptInProgress => "PROMPT_IN_PROGRESS",
+            CredentialError::Timeout { .. } => "TIMEOUT",
+            CredentialError::PromptNotFound { .. } => "PROMPT_NOT_FOUND",
+            CredentialError::VaultLocked => "VAULT_LOCKED",
+            CredentialError::Database { .. } => "DATABASE_ERROR",
+            CredentialError::Internal { .. } => "INTERNAL_ERROR",
+        }
+    }
+}
+
+impl From<DatabaseError> for CredentialError {
+    fn from(e: DatabaseError) -> Self {
+        match e {
+            DatabaseError::ConnectionError { .. } => CredentialError::VaultLocked,
+            e => CredentialError::Database {
+                reason: e.to_string(),
+            },
+        }
+    }
+}
+
+impl From<AutofillError> for CredentialError {
+    fn from(e: AutofillError) -> Self {
+        match e {
+            AutofillError::VaultLocked => CredentialError::VaultLocked,
+            AutofillError::InvalidTarget { reason } => CredentialError::InvalidOrigin { reason },
+            e => CredentialError::Database {
+                reason: e.to_string(),
+            },
+        }
+    }
+}
@@ -0,0 +1,782 @@
+//! Credential protocol for browser extensions
+//!
+//! Password manager extensions in a browser reach the vault through the
+//! external bridge like any other client. Instead of inventing their own
+//! message shapes they use these actions (payloads of encrypted `request`
+//! envelopes, answered like extension requests with
+//! `{ requestId, success, data }` or `{ requestId, success, error, errorCode }`):
+//! - `credentials.getLoginsForOrigin` — `{ requestId, origin }`; returns
+//!   `{ origin, logins, warnings }`
+//! - `credentials.saveLogin` — `{ requestId, origin, username?, password,
+//!   title? }`; updates the login with the same username for the origin or
+//!   creates one, returns `{ origin, id, created }`
+//! - `credentials.generatePassword` — `{ requestId, length?, lowercase?,
+//!   uppercase?, digits?, symbols? }`; returns `{ password }`
+//!
+//! Only clients authorized for the core target
+//! ([`CORE_EXTENSION_ID`](super::CORE_EXTENSION_ID)) may use them.
+//!
+//! Requests are bound to the origin of the page (`scheme://host[:port]`,
+//! nothing else): only items whose stored URL belongs to that host or a
+//! parent domain of it are returned (`passwords::autofill::match_url`),
+//! responses echo the origin so the extension can check it fills the tab
+//! it asked for, and a saved login is stored under its origin.
+//!
+//! Phishing protection compares the origin with the stored URLs: logins
+//! stored for https are withheld from an http page of the same host, and a
+//! host that imitates a stored one (`paypa1.com`, `paypal.com.example.net`,
+//! punycode look-alikes) is reported in `warnings`.
+//!
+//! Releasing logins and saving need the user's consent, asked in the main
+//! window ([`CredentialPromptRequested`], answered with
+//! `external_bridge_credential_respond`). The user can remember the
+//! decision per client, origin and kind ([`store`]); a remembered allow is
+//! ignored while there are phishing warnings.
+
+pub mod error;
+pub mod prompts;
+pub(crate) mod store;
+#[cfg(test)]
+mod tests;
+
+use crate::database::core::{execute_with_crdt, select_with_crdt, with_connection};
+use crate::database::row::get_string;
+use crate::events::{self, payloads::DirtyTablesChanged};
+use crate::passwords::autofill::{
+    self, normalize_host, parse_item_url, AutofillCandidate, AutofillMatch, AutofillTarget,
+};
+use crate::AppState;
+use error::CredentialError;
+use prompts::CredentialPrompts;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+use std::fmt;
+use tauri::{AppHandle, Manager};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use ts_rs::TS;
+use url::Url;
+
+/// Prefix of the bridge actions handled here
+pub const CREDENTIALS_ACTION_PREFIX: &str = "credentials.";
+pub const ACTION_GET_LOGINS_FOR_ORIGIN: &str = "credentials.getLoginsForOrigin";
+pub const ACTION_SAVE_LOGIN: &str = "credentials.saveLogin";
+pub const ACTION_GENERATE_PASSWORD: &str = "credentials.generatePassword";
+
+/// Bounds of generated passwords
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+pub const MAX_PASSWORD_LENGTH: usize = 128;
+const DEFAULT_PASSWORD_LENGTH: usize = 20;
+/// Longest accepted username, password or title
+const MAX_FIELD_CHARS: usize = 4096;
+/// Shorter labels are too common to flag look-alikes by edit distance
+const MIN_LOOKALIKE_LABEL: usize = 4;
+
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &[u8] = b"0123456789";
+const SYMBOLS: &[u8] = b"!#$%&()*+,-./:;<=>?@[]^_{|}~";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum CredentialPromptKind {
+    /// Release stored logins to the client
+    Fill,
+    /// Store a login the client submitted
+    Save,
+}
+
+/// Why an origin looks suspicious compared to the stored URLs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+#[ts(export)]
+pub enum PhishingWarning {
+    /// A login stored for https was withheld from this http page
+    InsecureOrigin {
+        #[serde(rename = "storedUrl")]
+        stored_url: String,
+    },
+    /// The page's host imitates a stored host without being it
+    Lookalike {
+        #[serde(rename = "storedHost")]
+        stored_host: String,
+    },
+}
+
+/// A login released for an origin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct OriginLogin {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// The stored URL that matched
+    pub url: String,
+    #[serde(rename = "match")]
+    pub match_kind: AutofillMatch,
+}
+
+/// Response of `credentials.getLoginsForOrigin`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct LoginsForOrigin {
+    /// The origin the logins are bound to
+    pub origin: String,
+    pub logins: Vec<OriginLogin>,
+    pub warnings: Vec<PhishingWarning>,
+}
+
+/// Response of `credentials.saveLogin`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SavedLogin {
+    pub origin: String,
+    /// Password item id
+    pub id: String,
+    /// False if an existing login got the new password
+    pub created: bool,
+}
+
+/// Response of `credentials.generatePassword`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct GeneratedPassword {
+    pub password: String,
+}
+
+/// Payload of `external-bridge:credential-prompt`. The main window asks
+/// the user and answers with
+/// `external_bridge_credential_respond(promptId, allow, remember)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CredentialPromptRequested {
+    pub prompt_id: String,
+    pub client_id: String,
+    pub client_name: String,
+    pub origin: String,
+    pub kind: CredentialPromptKind,
+    /// Usernames of the logins to fill, or the one to save
+    pub usernames: Vec<String>,
+    /// Saving replaces the password of an existing login
+    pub replaces_existing: bool,
+    pub warnings: Vec<PhishingWarning>,
+}
+
+/// A decision the user asked to remember.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct RememberedDecision {
+    pub client_id: String,
+    pub origin: String,
+    pub kind: CredentialPromptKind,
+    pub allow: bool,
+    /// RFC3339
+    pub decided_at: String,
+}
+
+/// Character classes and length of `credentials.generatePassword`; all
+/// classes are on by default.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PasswordRules {
+    pub length: usize,
+    pub lowercase: bool,
+    pub uppercase: bool,
+    pub digits: bool,
+    pub symbols: bool,
+}
+
+impl Default for PasswordRules {
+    fn default() -> Self {
+        Self {
+            length: DEFAULT_PASSWORD_LENGTH,
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols: true,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OriginRequest {
+    origin: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SaveLoginRequest {
+    origin: String,
+    #[serde(default)]
+    username: Option<String>,
+    password: String,
+    #[serde(default)]
+    title: Option<String>,
+}
+
+/// Page origin a request is bound to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundOrigin(Url);
+
+impl BoundOrigin {
+    /// Accepts `http(s)://host[:port]` with an optional trailing slash.
+    /// Anything else (paths, credentials, other schemes) is rejected, so an
+    /// extension can't pass a full page URL where an origin belongs.
+    pub fn parse(value: &str) -> Result<Self, CredentialError> {
+        let invalid = |reason: &str| CredentialError::InvalidOrigin {
+            reason: format!("{reason}: {value}"),
+        };
+        let url = Url::parse(value.trim()).map_err(|_| invalid("not a URL"))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(invalid("must be http or https"));
+        }
+        if url.host_str().is_none_or(str::is_empty) {
+            return Err(invalid("has no host"));
+        }
+        if !url.username().is_empty() || url.password().is_some() {
+            return Err(invalid("must not contain credentials"));
+        }
+        if url.path() != "/" || url.query().is_some() || url.fragment().is_some() {
+            return Err(invalid("must not contain a path, query or fragment"));
+        }
+        Ok(Self(url))
+    }
+
+    /// Normalized host, as used for matching stored URLs
+    pub fn host(&self) -> String {
+        normalize_host(self.0.host_str().unwrap_or_default())
+    }
+
+    pub fn is_https(&self) -> bool {
+        self.0.scheme() == "https"
+    }
+
+    pub fn target(&self) -> AutofillTarget {
+        AutofillTarget {
+            app_id: None,
+            web_domain: Some(self.host()),
+        }
+    }
+
+    /// Whether a login stored under `stored_url` may be used on this page:
+    /// no https → http downgrade, and an explicit port has to match.
+    fn binds(&self, stored_url: &Url) -> bool {
+        if stored_url.scheme() == "https" && !self.is_https() {
+            return false;
+        }
+        stored_url.port().is_none()
+            || stored_url.port_or_known_default() == self.0.port_or_known_default()
+    }
+}
+
+impl fmt::Display for BoundOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.origin().ascii_serialization())
+    }
+}
+
+/// Keeps the candidates `origin` binds to and reports the withheld https
+/// logins.
+pub fn bind_candidates(
+    origin: &BoundOrigin,
+    candidates: Vec<AutofillCandidate>,
+) -> (Vec<AutofillCandidate>, Vec<PhishingWarning>) {
+    let mut warnings = Vec::new();
+    let bound = candidates
+        .into_iter()
+        .filter(|candidate| {
+            let Some(url) = parse_item_url(&candidate.url) else {
+                return false;
+            };
+            if origin.binds(&url) {
+                return true;
+            }
+            if url.scheme() == "https" && !origin.is_https() {
+                warnings.push(PhishingWarning::InsecureOrigin {
+                    stored_url: candidate.url.clone(),
+                });
+            }
+            false
+        })
+        .collect();
+    (bound, warnings)
+}
+
+/// The last two labels of a host. Without a public suffix list this is the
+/// best guess of the registrable domain.
+fn site(host: &str) -> &str {
+    match host.rmatch_indices('.').nth(1) {
+        Some((i, _)) => &host[i + 1..],
+        None => host,
+    }
+}
+
+fn split_site(site: &str) -> (&str, &str) {
+    site.rsplit_once('.').unwrap_or((site, ""))
+}
+
+/// ASCII part of a punycode label: `xn--pypal-4ve` → `pypal`.
+fn punycode_basic(label: &str) -> Option<&str> {
+    let encoded = label.strip_prefix("xn--")?;
+    Some(encoded.rsplit_once('-').map_or("", |(basic, _)| basic))
+}
+
+/// Folds characters that look alike, so `paypa1` and `paypal` compare equal.
+fn skeleton(label: &str) -> String {
+    label
+        .replace("rn", "m")
+        .replace("vv", "w")
+        .replace("cl", "d")
+        .chars()
+        .map(|c| match c {
+            '0' => 'o',
+            '1' => 'l',
+            '3' => 'e',
+            '5' => 's',
+            c => c,
+        })
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// The stored host `host` imitates, if any. Hosts of the same site (the
+/// stored host itself, its subdomains and parents) never count.
+pub fn lookalike_of<'a>(host: &str, stored: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let host = normalize_host(host);
+    let host_site = site(&host);
+    let (label, tld) = split_site(host_site);
+    let label = punycode_basic(label).unwrap_or(label);
+
+    stored.into_iter().find(|stored| {
+        let stored_site = site(stored);
+        if stored_site == host_site {
+            return false;
+        }
+        // A stored host used as a subdomain: `paypal.com.example.net`
+        if host.starts_with(&format!("{stored}.")) || host.contains(&format!(".{stored}.")) {
+            return true;
+        }
+        let (stored_label, stored_tld) = split_site(stored_site);
+        if tld != stored_tld || stored_label.chars().count() < MIN_LOOKALIKE_LABEL {
+            return false;
+        }
+        skeleton(label) == skeleton(stored_label) || edit_distance(label, stored_label) <= 1
+    })
+}
+
+/// Uniform index below `n`; rejection sampling avoids the modulo bias.
+fn random_index(n: usize) -> usize {
+    let n = n as u32;
+    let zone = u32::MAX - u32::MAX % n;
+    loop {
+        let value: u32 = rand::random();
+        if value < zone {
+            return (value % n) as usize;
+        }
+    }
+}
+
+/// Random password following `rules`, with at least one character of each
+/// enabled class.
+pub fn generate_password(rules: &PasswordRules) -> Result<String, CredentialError> {
+    if !(MIN_PASSWORD_LENGTH..=MAX_PASSWORD_LENGTH).contains(&rules.length) {
+        return Err(CredentialError::InvalidRequest {
+            reason: format!(
+                "length must be between {MIN_PASSWORD_LENGTH} and {MAX_PASSWORD_LENGTH}"
+            ),
+        });
+    }
+    let classes: Vec<&[u8]> = [
+        (rules.lowercase, LOWERCASE),
+        (rules.uppercase, UPPERCASE),
+        (rules.digits, DIGITS),
+        (rules.symbols, SYMBOLS),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, class)| enabled.then_some(class))
+    .collect();
+    if classes.is_empty() {
+        return Err(CredentialError::InvalidRequest {
+            reason: "at least one character class is required".to_string(),
+        });
+    }
+
+    let mut password: Vec<u8> = classes.iter().map(|c| c[random_index(c.len())]).collect();
+    let all = classes.concat();
+    while password.len() < rules.length {
+        password.push(all[random_index(all.len())]);
+    }
+    // The guaranteed characters shouldn't always come first
+    for i in (1..password.len()).rev() {
+        password.swap(i, random_index(i + 1));
+    }
+    Ok(password.into_iter().map(char::from).collect())
+}
+
+fn parse_payload<T: DeserializeOwned>(payload: &JsonValue) -> Result<T, CredentialError> {
+    serde_json::from_value(payload.clone()).map_err(|e| CredentialError::InvalidRequest {
+        reason: e.to_string(),
+    })
+}
+
+fn validate_field(name: &str, value: &str) -> Result<(), CredentialError> {
+    if value.chars().count() > MAX_FIELD_CHARS {
+        return Err(CredentialError::InvalidRequest {
+            reason: format!("{name} is longer than {MAX_FIELD_CHARS} characters"),
+        });
+    }
+    Ok(())
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Look-alike warning for `origin` against all stored hosts.
+fn lookalike_warning(
+    state: &AppState,
+    origin: &BoundOrigin,
+) -> Result<Option<PhishingWarning>, CredentialError> {
+    let hosts = autofill::web_hosts(state)?;
+    Ok(
+        lookalike_of(&origin.host(), hosts.iter().map(String::as_str)).map(|host| {
+            PhishingWarning::Lookalike {
+                stored_host: host.to_string(),
+            }
+        }),
+    )
+}
+
+/// Candidates `origin` binds to, with all phishing warnings.
+fn bound_candidates(
+    state: &AppState,
+    origin: &BoundOrigin,
+) -> Result<(Vec<AutofillCandidate>, Vec<PhishingWarning>), CredentialError> {
+    let (candidates, mut warnings) =
+        bind_candidates(origin, autofill::candidates(state, &origin.target())?);
+    warnings.extend(lookalike_warning(state, origin)?);
+    Ok((candidates, warnings))
+}
+
+fn load_passwords(
+    state: &AppState,
+    ids: &[&str],
+) -> Result<HashMap<String, String>, CredentialError> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let placeholders: Vec<String> = (1..=ids.len()).map(|i| format!("?{i}")).collect();
+    let sql = format!(
+        "SELECT id, password FROM haex_passwords_item_details WHERE id IN ({})",
+        placeholders.join(", ")
+    );
+    let params = ids
+        .iter()
+        .map(|id| JsonValue::String(id.to_string()))
+        .collect();
+    let rows = select_with_crdt(sql, params, &state.db)?;
+    Ok(rows
+        .iter()
+        .map(|row| (get_string(row, 0), get_string(row, 1)))
+        .filter(|(_, password)| !password.is_empty())
+        .collect())
+}
+
+/// What the user is asked to allow.
+struct Consent<'a> {
+    client_id: &'a str,
+    client_name: &'a str,
+    origin: &'a BoundOrigin,
+    kind: CredentialPromptKind,
+    usernames: Vec<String>,
+    replaces_existing: bool,
+    warnings: Vec<PhishingWarning>,
+}
+
+/// Applies a remembered decision or asks the user in the main window.
+async fn consent(
+    app_handle: &AppHandle,
+    prompts: &CredentialPrompts,
+    request: Consent<'_>,
+) -> Result<(), CredentialError> {
+    let state = app_handle.state::<AppState>();
+    let origin = request.origin.to_string();
+    let remembered = with_connection(&state.db, |conn| {
+        store::find(conn, request.client_id, &origin, request.kind)
+    })?;
+    match remembered {
+        Some(false) => return Err(CredentialError::Denied),
+        Some(true) if request.warnings.is_empty() => return Ok(()),
+        _ => {}
+    }
+
+    let (prompt_id, receiver) = prompts.begin(request.client_id, &origin, request.kind)?;
+    let event = CredentialPromptRequested {
+        prompt_id: prompt_id.clone(),
+        client_id: request.client_id.to_string(),
+        client_name: request.client_name.to_string(),
+        origin: origin.clone(),
+        kind: request.kind,
+        usernames: request.usernames,
+        replaces_existing: request.replaces_existing,
+        warnings: request.warnings,
+    };
+    if let Err(e) = events::emit_to_main(app_handle, &event) {
+        prompts.cancel(&prompt_id);
+        return Err(CredentialError::Internal {
+            reason: format!("Failed to show the prompt: {e}"),
+        });
+    }
+
+    let Some(answer) = prompts.wait(&prompt_id, receiver).await? else {
+        return Err(CredentialError::Denied);
+    };
+    if answer.remember {
+        let decision = RememberedDecision {
+            client_id: request.client_id.to_string(),
+            origin,
+            kind: request.kind,
+            allow: answer.allow,
+            decided_at: OffsetDateTime::now_utc()
+                .format(&Rfc3339)
+                .unwrap_or_default(),
+        };
+        with_connection(&state.db, |conn| store::remember(conn, decision))?;
+    }
+    if answer.allow {
+        Ok(())
+    } else {
+        Err(CredentialError::Denied)
+    }
+}
+
+async fn get_logins_for_origin(
+    app_handle: &AppHandle,
+    prompts: &CredentialPrompts,
+    client_id: &str,
+    client_name: &str,
+    payload: &JsonValue,
+) -> Result<LoginsForOrigin, CredentialError> {
+    let request: OriginRequest = parse_payload(payload)?;
+    let origin = BoundOrigin::parse(&request.origin)?;
+    let state = app_handle.state::<AppState>();
+    let (candidates, warnings) = bound_candidates(&state, &origin)?;
+
+    // Nothing to release, nothing to ask
+    if candidates.is_empty() {
+        return Ok(LoginsForOrigin {
+            origin: origin.to_string(),
+            logins: Vec::new(),
+            warnings,
+        });
+    }
+
+    let usernames = candidates
+        .iter()
+        .filter_map(|c| c.username.clone())
+        .collect();
+    consent(
+        app_handle,
+        prompts,
+        Consent {
+            client_id,
+            client_name,
+            origin: &origin,
+            kind: CredentialPromptKind::Fill,
+            usernames,
+            replaces_existing: false,
+            warnings: warnings.clone(),
+        },
+    )
+    .await?;
+
+    let ids: Vec<&str> = candidates.iter().map(|c| c.id.as_str()).collect();
+    let mut passwords = load_passwords(&state, &ids)?;
+    let logins = candidates
+        .into_iter()
+        .map(|c| OriginLogin {
+            password: passwords.remove(&c.id),
+            id: c.id,
+            title: c.title,
+            username: c.username,
+            url: c.url,
+            match_kind: c.match_kind,
+        })
+        .collect();
+    Ok(LoginsForOrigin {
+        origin: origin.to_string(),
+        logins,
+        warnings,
+    })
+}
+
+async fn save_login(
+    app_handle: &AppHandle,
+    prompts: &CredentialPrompts,
+    client_id: &str,
+    client_name: &str,
+    payload: &JsonValue,
+) -> Result<SavedLogin, CredentialError> {
+    let request: SaveLoginRequest = parse_payload(payload)?;
+    let origin = BoundOrigin::parse(&request.origin)?;
+    if request.password.is_empty() {
+        return Err(CredentialError::InvalidRequest {
+            reason: "password must not be empty".to_string(),
+        });
+    }
+    validate_field("password", &request.password)?;
+    let username = non_empty(request.username);
+    let title = non_empty(request.title).unwrap_or_else(|| origin.host());
+    validate_field("username", username.as_deref().unwrap_or_default())?;
+    validate_field("title", &title)?;
+
+    let state = app_handle.state::<AppState>();
+    let (candidates, warnings) = bound_candidates(&state, &origin)?;
+    // Only a login of this very host is updated; one of a parent domain
+    // stays as it is and the page gets its own
+    let existing = candidates
+        .into_iter()
+        .find(|c| c.match_kind == AutofillMatch::Exact && c.username == username)
+        .map(|c| c.id);
+
+    consent(
+        app_handle,
+        prompts,
+        Consent {
+            client_id,
+            client_name,
+            origin: &origin,
+            kind: CredentialPromptKind::Save,
+            usernames: username.iter().cloned().collect(),
+            replaces_existing: existing.is_some(),
+            warnings,
+        },
+    )
+    .await?;
+
+    let hlc_guard = state.hlc.lock().map_err(|e| CredentialError::Internal {
+        reason: format!("Failed to lock HLC: {e}"),
+    })?;
+    let (id, created) = match existing {
+        Some(id) => {
+            let sql = "UPDATE haex_passwords_item_details \
+                       SET password = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?1"
+                .to_string();
+            let params = vec![
+                JsonValue::String(id.clone()),
+                JsonValue::String(request.password),
+            ];
+            execute_with_crdt(sql, params, &state.db, &hlc_guard)?;
+            (id, false)
+        }
+        None => {
+            let id = uuid::Uuid::new_v4().to_string();
+            let sql = "INSERT INTO haex_passwords_item_details \
+                       (id, title, username, password, url) VALUES (?1, ?2, ?3, ?4, ?5)"
+                .to_string();
+            let params = vec![
+                JsonValue::String(id.clone()),
+                JsonValue::String(title),
+                username.map_or(JsonValue::Null, JsonValue::String),
+                JsonValue::String(request.password),
+                JsonValue::String(origin.to_string()),
+            ];
+            execute_with_crdt(sql, params, &state.db, &hlc_guard)?;
+            (id, true)
+        }
+    };
+    drop(hlc_guard);
+
+    let _ = events::emit_to_main(app_handle, &DirtyTablesChanged {});
+    Ok(SavedLogin {
+        origin: origin.to_string(),
+        id,
+        created,
+    })
+}
+
+fn to_data<T: Serialize>(value: T) -> Result<JsonValue, CredentialError> {
+    serde_json::to_value(value).map_err(|e| CredentialError::Internal {
+        reason: e.to_string(),
+    })
+}
+
+/// Handles a `credentials.*` action of a client authorized for the core
+/// target and returns the response payload.
+pub async fn handle_request(
+    app_handle: &AppHandle,
+    prompts: &CredentialPrompts,
+    client_id: &str,
+    client_name: &str,
+    action: &str,
+    payload: &JsonValue,
+) -> JsonValue {
+    let request_id = match payload.get("requestId").and_then(|v| v.as_str()) {
+        Some(id) if !id.is_empty() => id,
+        _ => {
+            return json!({
+                "success": false,
+                "error": "Missing required field: requestId"
+            });
+        }
+    };
+
+    let result = match action {
+        ACTION_GET_LOGINS_FOR_ORIGIN => {
+            get_logins_for_origin(app_handle, prompts, client_id, client_name, payload)
+                .await
+                .and_then(to_data)
+        }
+        ACTION_SAVE_LOGIN => save_login(app_handle, prompts, client_id, client_name, payload)
+            .await
+            .and_then(to_data),
+        ACTION_GENERATE_PASSWORD => parse_payload::<PasswordRules>(payload)
+            .and_then(|rules| generate_password(&rules))
+            .and_then(|password| to_data(GeneratedPassword { password })),
+        _ => Err(CredentialError::UnknownAction {
+            action: action.to_string(),
+        }),
+    };
+
+    match result {
+        Ok(data) => json!({
+            "requestId": request_id,
+            "success": true,
+            "data": data
+        }),
+        Err(e) => json!({
+            "requestId": request_id,
+            "success": false,
+            "error": e.to_string(),
+            "errorCode": e.code()
+        }),
+    }
+}
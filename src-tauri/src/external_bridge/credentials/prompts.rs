@@ -0,0 +1,149 @@
+//! Credential prompts (in-memory)
+//!
+//! Tracks the consent prompts shown in the main window, like
+//! `media::capture::CaptureBroker` does for captures. A client has at most
+//! one open prompt per origin and kind; the bridge request parks on it until
+//! the user answers or the prompt times out.
+
+use super::error::CredentialError;
+use super::CredentialPromptKind;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// How long a bridge request waits for the user
+pub const PROMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The user's answer to a prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CredentialAnswer {
+    pub allow: bool,
+    /// Store the decision for this client, origin and kind
+    pub remember: bool,
+}
+
+#[derive(Debug)]
+struct PendingPrompt {
+    client_id: String,
+    origin: String,
+    kind: CredentialPromptKind,
+    answer: oneshot::Sender<CredentialAnswer>,
+}
+
+/// Open prompts by prompt ID
+#[derive(Debug)]
+pub struct CredentialPrompts {
+    pending: Mutex<HashMap<String, PendingPrompt>>,
+    timeout: Duration,
+}
+
+impl Default for CredentialPrompts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialPrompts {
+    pub fn new() -> Self {
+        Self::with_timeout(PROMPT_TIMEOUT)
+    }
+
+    pub(crate) fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            timeout,
+        }
+    }
+
+    /// Opens a prompt and returns its ID together with the receiver to pass
+    /// to [`wait`](Self::wait).
+    pub fn begin(
+        &self,
+        client_id: &str,
+        origin: &str,
+        kind: CredentialPromptKind,
+    ) -> Result<(String, oneshot::Receiver<CredentialAnswer>), CredentialError> {
+        let mut pending = self.lock()?;
+        // Prompts whose request gave up (dropped receiver) don't block
+        pending.retain(|_, prompt| !prompt.answer.is_closed());
+        if pending
+            .values()
+            .any(|p| p.client_id == client_id && p.origin == origin && p.kind == kind)
+        {
+            return Err(CredentialError::PromptInProgress);
+        }
+
+        let prompt_id = uuid::Uuid::new_v4().to_string();
+        let (answer, receiver) = oneshot::channel();
+        pending.insert(
+            prompt_id.clone(),
+            PendingPrompt {
+                client_id: client_id.to_string(),
+                origin: origin.to_string(),
+                kind,
+                answer,
+            },
+        );
+        Ok((prompt_id, receiver))
+    }
+
+    /// Waits for the answer to `prompt_id`. `Ok(None)` means the prompt was
+    /// dropped without an answer.
+    pub async fn wait(
+        &self,
+        prompt_id: &str,
+        receiver: oneshot::Receiver<CredentialAnswer>,
+    ) -> Result<Option<CredentialAnswer>, CredentialError> {
+        match tokio::time::timeout(self.timeout, receiver).await {
+            Ok(Ok(answer)) => Ok(Some(answer)),
+            Ok(Err(_)) => Ok(None),
+            Err(_) => {
+                self.cancel(prompt_id);
+                Err(CredentialError::Timeout {
+                    timeout_secs: self.timeout.as_secs(),
+                })
+            }
+        }
+    }
+
+    /// Delivers the user's answer to the waiting request.
+    pub fn resolve(
+        &self,
+        prompt_id: &str,
+        answer: CredentialAnswer,
+    ) -> Result<(), CredentialError> {
+        let prompt =
+            self.lock()?
+                .remove(prompt_id)
+                .ok_or_else(|| CredentialError::PromptNotFound {
+                    id: prompt_id.to_string(),
+                })?;
+        // The client may have disconnected in the meantime
+        let _ = prompt.answer.send(answer);
+        Ok(())
+    }
+
+    /// Drops `prompt_id` without an answer.
+    pub fn cancel(&self, prompt_id: &str) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(prompt_id);
+        }
+    }
+
+    /// Drops all prompts of `client_id`, e.g. after its authorization was
+    /// revoked. The waiting requests are denied.
+    pub fn cancel_client(&self, client_id: &str) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.retain(|_, prompt| prompt.client_id != client_id);
+        }
+    }
+
+    fn lock(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, HashMap<String, PendingPrompt>>, CredentialError> {
+        self.pending.lock().map_err(|e| CredentialError::Internal {
+            reason: e.to_string(),
+        })
+    }
+}
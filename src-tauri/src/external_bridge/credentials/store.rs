@@ -0,0 +1,94 @@
+//! Persistence of remembered credential decisions in `haex_crdt_configs`
+//! (local-only: which browser may fill where is a decision per device).
+
+use crate::database::constants::vault_settings_key;
+use crate::database::error::DatabaseError;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, TABLE_CRDT_CONFIGS,
+};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::{CredentialPromptKind, RememberedDecision};
+
+/// `type` column value of the decisions row
+const CONFIG_TYPE: &str = "external_bridge";
+
+pub fn load(conn: &Connection) -> Result<Vec<RememberedDecision>, DatabaseError> {
+    let value: Option<String> = conn
+        .query_row(
+            &format!(
+                "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"
+            ),
+            params![vault_settings_key::EXTERNAL_BRIDGE_CREDENTIAL_DECISIONS],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match value {
+        None => Ok(Vec::new()),
+        Some(json) => serde_json::from_str(&json).map_err(|e| DatabaseError::SerializationError {
+            reason: format!("Invalid credential decisions: {e}"),
+        }),
+    }
+}
+
+fn save(conn: &Connection, decisions: &[RememberedDecision]) -> Result<(), DatabaseError> {
+    let json = serde_json::to_string(decisions).map_err(|e| DatabaseError::SerializationError {
+        reason: e.to_string(),
+    })?;
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?, ?, ?)"
+        ),
+        params![
+            vault_settings_key::EXTERNAL_BRIDGE_CREDENTIAL_DECISIONS,
+            CONFIG_TYPE,
+            json
+        ],
+    )?;
+    Ok(())
+}
+
+/// Remembered decision of `client_id` for `origin` and `kind`:
+/// `Some(true)` allow, `Some(false)` deny.
+pub fn find(
+    conn: &Connection,
+    client_id: &str,
+    origin: &str,
+    kind: CredentialPromptKind,
+) -> Result<Option<bool>, DatabaseError> {
+    Ok(load(conn)?
+        .into_iter()
+        .find(|d| d.client_id == client_id && d.origin == origin && d.kind == kind)
+        .map(|d| d.allow))
+}
+
+/// Stores `decision`, replacing an earlier one for the same client, origin
+/// and kind.
+pub fn remember(conn: &Connection, decision: RememberedDecision) -> Result<(), DatabaseError> {
+    let mut decisions = load(conn)?;
+    decisions.retain(|d| {
+        !(d.client_id == decision.client_id
+            && d.origin == decision.origin
+            && d.kind == decision.kind)
+    });
+    decisions.push(decision);
+    save(conn, &decisions)
+}
+
+/// Removes the decisions of `client_id`, only those for `origin` if given.
+/// Returns how many were removed.
+pub fn forget(
+    conn: &Connection,
+    client_id: &str,
+    origin: Option<&str>,
+) -> Result<usize, DatabaseError> {
+    let mut decisions = load(conn)?;
+    let before = decisions.len();
+    decisions.retain(|d| !(d.client_id == client_id && origin.is_none_or(|o| d.origin == o)));
+    let removed = before - decisions.len();
+    if removed > 0 {
+        save(conn, &decisions)?;
+    }
+    Ok(removed)
+}
@@ -0,0 +1,360 @@
+use super::error::CredentialError;
+use super::prompts::{CredentialAnswer, CredentialPrompts};
+use super::{
+    bind_candidates, generate_password, lookalike_of, store, BoundOrigin, CredentialPromptKind,
+    PasswordRules, PhishingWarning, RememberedDecision, DIGITS, LOWERCASE, MAX_PASSWORD_LENGTH,
+    SYMBOLS, UPPERCASE,
+};
+use crate::crdt::hlc::HlcService;
+use crate::passwords::autofill::{AutofillCandidate, AutofillMatch};
+use crate::test_support::crdt::open_crdt_connection;
+use serde_json::json;
+use std::time::Duration;
+
+fn candidate(id: &str, url: &str) -> AutofillCandidate {
+    AutofillCandidate {
+        id: id.to_string(),
+        title: None,
+        username: Some("alice".to_string()),
+        url: url.to_string(),
+        match_kind: AutofillMatch::Exact,
+    }
+}
+
+fn decision(
+    client_id: &str,
+    origin: &str,
+    kind: CredentialPromptKind,
+    allow: bool,
+) -> RememberedDecision {
+    RememberedDecision {
+        client_id: client_id.to_string(),
+        origin: origin.to_string(),
+        kind,
+        allow,
+        decided_at: "2026-10-01T00:00:00Z".to_string(),
+    }
+}
+
+#[test]
+fn test_origin_parsing() {
+    let origin = BoundOrigin::parse("https://Login.Example.com/").unwrap();
+    assert_eq!(origin.to_string(), "https://login.example.com");
+    assert_eq!(origin.host(), "login.example.com");
+    assert!(origin.is_https());
+
+    let origin = BoundOrigin::parse("http://localhost:8080").unwrap();
+    assert_eq!(origin.to_string(), "http://localhost:8080");
+    assert!(!origin.is_https());
+
+    // www. is dropped for matching, not from the origin itself
+    let origin = BoundOrigin::parse("https://www.example.com").unwrap();
+    assert_eq!(origin.to_string(), "https://www.example.com");
+    assert_eq!(origin.host(), "example.com");
+
+    // IDN hosts are bound in their punycode form
+    let origin = BoundOrigin::parse("https://bücher.example").unwrap();
+    assert_eq!(origin.to_string(), "https://xn--bcher-kva.example");
+
+    for invalid in [
+        "",
+        "example.com",
+        "ftp://example.com",
+        "file:///etc/passwd",
+        "https://example.com/login",
+        "https://example.com/?next=/",
+        "https://example.com/#top",
+        "https://user:pw@example.com",
+        "javascript:alert(1)",
+    ] {
+        assert!(
+            matches!(
+                BoundOrigin::parse(invalid),
+                Err(CredentialError::InvalidOrigin { .. })
+            ),
+            "{invalid} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn test_bind_candidates_withholds_https_logins_from_http() {
+    let candidates = vec![
+        candidate("secure", "https://example.com/login"),
+        candidate("plain", "http://example.com"),
+        candidate("bare", "example.com"),
+    ];
+
+    let https = BoundOrigin::parse("https://example.com").unwrap();
+    let (bound, warnings) = bind_candidates(&https, candidates.clone());
+    assert_eq!(bound.len(), 3);
+    assert!(warnings.is_empty());
+
+    // Bare hosts count as https, so they are withheld too
+    let http = BoundOrigin::parse("http://example.com").unwrap();
+    let (bound, warnings) = bind_candidates(&http, candidates);
+    let ids: Vec<&str> = bound.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, vec!["plain"]);
+    assert_eq!(
+        warnings,
+        vec![
+            PhishingWarning::InsecureOrigin {
+                stored_url: "https://example.com/login".to_string()
+            },
+            PhishingWarning::InsecureOrigin {
+                stored_url: "example.com".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_bind_candidates_checks_explicit_ports() {
+    let candidates = vec![
+        candidate("admin", "https://example.com:8443"),
+        candidate("any", "https://example.com/app"),
+    ];
+
+    let origin = BoundOrigin::parse("https://example.com").unwrap();
+    let (bound, warnings) = bind_candidates(&origin, candidates.clone());
+    let ids: Vec<&str> = bound.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, vec!["any"]);
+    assert!(warnings.is_empty());
+
+    let origin = BoundOrigin::parse("https://example.com:8443").unwrap();
+    let (bound, _) = bind_candidates(&origin, candidates);
+    let ids: Vec<&str> = bound.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, vec!["admin", "any"]);
+}
+
+#[test]
+fn test_lookalike_detection() {
+    let stored = ["paypal.com", "github.com", "accounts.google.com", "x.com"];
+    let check = |host: &str| lookalike_of(host, stored.iter().copied());
+
+    // Confusable characters and typos
+    assert_eq!(check("paypa1.com"), Some("paypal.com"));
+    assert_eq!(check("www.paypall.com"), Some("paypal.com"));
+    assert_eq!(check("githuh.com"), Some("github.com"));
+    assert_eq!(check("glthub.com"), Some("github.com"));
+    // A stored host used as a subdomain of another site
+    assert_eq!(check("paypal.com.example.net"), Some("paypal.com"));
+    assert_eq!(
+        check("login.accounts.google.com.evil.io"),
+        Some("accounts.google.com")
+    );
+    // Punycode keeps the ASCII characters of the label
+    assert_eq!(check("xn--pypal-4ve.com"), Some("paypal.com"));
+
+    // The stored sites themselves, their subdomains and parents
+    assert_eq!(check("paypal.com"), None);
+    assert_eq!(check("www.paypal.com"), None);
+    assert_eq!(check("mail.google.com"), None);
+    assert_eq!(check("google.com"), None);
+    // Unrelated hosts, other TLDs and too short labels
+    assert_eq!(check("example.com"), None);
+    assert_eq!(check("paypal.de"), None);
+    assert_eq!(check("y.com"), None);
+}
+
+#[test]
+fn test_generate_password_follows_rules() {
+    let password = generate_password(&PasswordRules::default()).unwrap();
+    assert_eq!(password.len(), 20);
+    for class in [LOWERCASE, UPPERCASE, DIGITS, SYMBOLS] {
+        assert!(password.bytes().any(|b| class.contains(&b)), "{password}");
+    }
+
+    let rules = PasswordRules {
+        length: 12,
+        uppercase: false,
+        symbols: false,
+        ..PasswordRules::default()
+    };
+    for _ in 0..50 {
+        let password = generate_password(&rules).unwrap();
+        assert_eq!(password.len(), 12);
+        assert!(password
+            .bytes()
+            .all(|b| LOWERCASE.contains(&b) || DIGITS.contains(&b)));
+        assert!(password.bytes().any(|b| DIGITS.contains(&b)));
+    }
+
+    let pin = PasswordRules {
+        length: 8,
+        lowercase: false,
+        uppercase: false,
+        symbols: false,
+        ..PasswordRules::default()
+    };
+    assert!(generate_password(&pin)
+        .unwrap()
+        .bytes()
+        .all(|b| b.is_ascii_digit()));
+
+    assert_ne!(
+        generate_password(&PasswordRules::default()).unwrap(),
+        generate_password(&PasswordRules::default()).unwrap()
+    );
+}
+
+#[test]
+fn test_generate_password_rejects_invalid_rules() {
+    for length in [0, 7, MAX_PASSWORD_LENGTH + 1] {
+        let rules = PasswordRules {
+            length,
+            ..PasswordRules::default()
+        };
+        assert!(matches!(
+            generate_password(&rules),
+            Err(CredentialError::InvalidRequest { .. })
+        ));
+    }
+
+    let nothing = PasswordRules {
+        lowercase: false,
+        uppercase: false,
+        digits: false,
+        symbols: false,
+        ..PasswordRules::default()
+    };
+    assert!(matches!(
+        generate_password(&nothing),
+        Err(CredentialError::InvalidRequest { .. })
+    ));
+}
+
+#[test]
+fn test_password_rules_from_payload() {
+    let rules: PasswordRules =
+        serde_json::from_value(json!({ "requestId": "r1", "length": 32, "symbols": false }))
+            .unwrap();
+    assert_eq!(
+        rules,
+        PasswordRules {
+            length: 32,
+            symbols: false,
+            ..PasswordRules::default()
+        }
+    );
+}
+
+#[test]
+fn test_phishing_warning_serialization() {
+    let warning = PhishingWarning::Lookalike {
+        stored_host: "paypal.com".to_string(),
+    };
+    assert_eq!(
+        serde_json::to_value(&warning).unwrap(),
+        json!({ "kind": "lookalike", "storedHost": "paypal.com" })
+    );
+    let warning = PhishingWarning::InsecureOrigin {
+        stored_url: "https://example.com".to_string(),
+    };
+    assert_eq!(
+        serde_json::to_value(&warning).unwrap(),
+        json!({ "kind": "insecureOrigin", "storedUrl": "https://example.com" })
+    );
+}
+
+#[test]
+fn test_store_remembers_decisions() {
+    let conn =
+        open_crdt_connection(HlcService::new_for_testing("credentials-test-device")).unwrap();
+    let origin = "https://example.com";
+    let fill = CredentialPromptKind::Fill;
+    let save = CredentialPromptKind::Save;
+
+    assert_eq!(store::find(&conn, "client-1", origin, fill).unwrap(), None);
+
+    store::remember(&conn, decision("client-1", origin, fill, true)).unwrap();
+    store::remember(&conn, decision("client-1", origin, save, false)).unwrap();
+    store::remember(&conn, decision("client-1", "https://other.org", fill, true)).unwrap();
+    store::remember(&conn, decision("client-2", origin, fill, false)).unwrap();
+    assert_eq!(
+        store::find(&conn, "client-1", origin, fill).unwrap(),
+        Some(true)
+    );
+    assert_eq!(
+        store::find(&conn, "client-1", origin, save).unwrap(),
+        Some(false)
+    );
+    assert_eq!(
+        store::find(&conn, "client-2", origin, fill).unwrap(),
+        Some(false)
+    );
+
+    // A new decision replaces the old one
+    store::remember(&conn, decision("client-1", origin, fill, false)).unwrap();
+    assert_eq!(
+        store::find(&conn, "client-1", origin, fill).unwrap(),
+        Some(false)
+    );
+    assert_eq!(store::load(&conn).unwrap().len(), 4);
+
+    assert_eq!(store::forget(&conn, "client-1", Some(origin)).unwrap(), 2);
+    assert_eq!(store::find(&conn, "client-1", origin, save).unwrap(), None);
+    assert_eq!(
+        store::find(&conn, "client-1", "https://other.org", fill).unwrap(),
+        Some(true)
+    );
+
+    assert_eq!(store::forget(&conn, "client-1", None).unwrap(), 1);
+    assert_eq!(store::forget(&conn, "client-1", None).unwrap(), 0);
+    assert_eq!(store::load(&conn).unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_prompts_deliver_answers() {
+    let prompts = CredentialPrompts::new();
+    let origin = "https://example.com";
+    let (prompt_id, receiver) = prompts
+        .begin("client-1", origin, CredentialPromptKind::Fill)
+        .unwrap();
+
+    // One open prompt per client, origin and kind
+    assert!(matches!(
+        prompts.begin("client-1", origin, CredentialPromptKind::Fill),
+        Err(CredentialError::PromptInProgress)
+    ));
+    let (save_id, _save_receiver) = prompts
+        .begin("client-1", origin, CredentialPromptKind::Save)
+        .unwrap();
+    assert_ne!(prompt_id, save_id);
+
+    let answer = CredentialAnswer {
+        allow: true,
+        remember: false,
+    };
+    prompts.resolve(&prompt_id, answer).unwrap();
+    assert_eq!(
+        prompts.wait(&prompt_id, receiver).await.unwrap(),
+        Some(answer)
+    );
+    assert!(matches!(
+        prompts.resolve(&prompt_id, answer),
+        Err(CredentialError::PromptNotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_prompts_cancel_and_timeout() {
+    let prompts = CredentialPrompts::with_timeout(Duration::from_millis(20));
+    let origin = "https://example.com";
+
+    let (prompt_id, receiver) = prompts
+        .begin("client-1", origin, CredentialPromptKind::Fill)
+        .unwrap();
+    assert!(matches!(
+        prompts.wait(&prompt_id, receiver).await,
+        Err(CredentialError::Timeout { .. })
+    ));
+    // A timed-out prompt no longer blocks the client
+    let (prompt_id, receiver) = prompts
+        .begin("client-1", origin, CredentialPromptKind::Fill)
+        .unwrap();
+
+    // Revoking the client drops its prompts; the request is denied
+    prompts.cancel_client("client-1");
+    assert_eq!(prompts.wait(&prompt_id, receiver).await.unwrap(), None);
+}
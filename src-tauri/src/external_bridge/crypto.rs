@@ -2,18 +2,27 @@
 //!
 //! Uses X25519 for key exchange and AES-256-GCM for encryption.
 //! Compatible with WebCrypto API in browsers.
+//!
+//! Requests are signed with HMAC-SHA256 under a key both sides derive from
+//! their static keys: `SHA-256(REQUEST_SIGNING_CONTEXT || X25519(client, server))`.
+//! Only the holder of the client's private key can produce it, which the
+//! ephemeral encryption key alone doesn't prove.
 
 use aes_gcm::{
     aead::{Aead, KeyInit},
     Aes256Gcm, Nonce,
 };
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
 use x25519_dalek::{PublicKey, StaticSecret};
 
 use super::error::BridgeError;
 
 const IV_LENGTH: usize = 12;
 const X25519_PUBLIC_KEY_LENGTH: usize = 32;
+/// Domain separation of the request signing key
+pub const REQUEST_SIGNING_CONTEXT: &[u8] = b"haex-vault bridge request signature v1";
 
 /// Server keypair for X25519 key exchange
 pub struct ServerKeyPair {
@@ -43,6 +52,19 @@ impl ServerKeyPair {
     }
 }
 
+/// Key a client signs its requests with (see module docs)
+pub fn request_signing_key(
+    server_keypair: &ServerKeyPair,
+    client_public_key_base64: &str,
+) -> Result<[u8; 32], BridgeError> {
+    let client_public_key = import_public_key(client_public_key_base64)?;
+    let shared_secret = server_keypair.derive_shared_secret(&client_public_key);
+    let mut hasher = Sha256::new();
+    hasher.update(REQUEST_SIGNING_CONTEXT);
+    hasher.update(shared_secret);
+    Ok(hasher.finalize().into())
+}
+
 /// Import a public key from Base64 raw format (32 bytes)
 pub fn import_public_key(base64_key: &str) -> Result<PublicKey, BridgeError> {
     let key_bytes = BASE64
@@ -109,9 +131,66 @@ pub struct EncryptedEnvelope {
     /// Target extension's name (from manifest) - together with public_key uniquely identifies the extension
     #[serde(default)]
     pub extension_name: Option<String>,
+    /// Target extension's ID - alternative to public_key + name, e.g. for
+    /// clients that talk to several extensions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extension_id: Option<String>,
+    /// Random value, unique per request (replay protection)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// Client time of the request in Unix milliseconds (replay protection)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+    /// Base64 HMAC-SHA256 over [`signing_input`](Self::signing_input),
+    /// keyed with the client's request signing key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
 }
 
 impl EncryptedEnvelope {
+    /// Whether the client signed this request
+    pub fn is_signed(&self) -> bool {
+        self.nonce.is_some() || self.timestamp.is_some() || self.signature.is_some()
+    }
+
+    /// The signed fields, one per line: action, extensionId,
+    /// extensionPublicKey, extensionName, publicKey, iv, message, nonce,
+    /// timestamp (absent fields as empty lines)
+    pub fn signing_input(&self) -> String {
+        let timestamp = self.timestamp.map(|t| t.to_string()).unwrap_or_default();
+        [
+            self.action.as_str(),
+            self.extension_id.as_deref().unwrap_or(""),
+            self.extension_public_key.as_deref().unwrap_or(""),
+            self.extension_name.as_deref().unwrap_or(""),
+            self.public_key.as_str(),
+            self.iv.as_str(),
+            self.message.as_str(),
+            self.nonce.as_deref().unwrap_or(""),
+            timestamp.as_str(),
+        ]
+        .join("\n")
+    }
+
+    /// Check the signature against the client's static public key
+    pub fn verify_signature(
+        &self,
+        server_keypair: &ServerKeyPair,
+        client_public_key_base64: &str,
+    ) -> Result<bool, BridgeError> {
+        let Some(signature) = &self.signature else {
+            return Ok(false);
+        };
+        let signature = BASE64
+            .decode(signature)
+            .map_err(|e| BridgeError::Crypto(format!("Invalid signature base64: {}", e)))?;
+        let key = request_signing_key(server_keypair, client_public_key_base64)?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(&key)
+            .map_err(|e| BridgeError::Crypto(e.to_string()))?;
+        mac.update(self.signing_input().as_bytes());
+        Ok(mac.verify_slice(&signature).is_ok())
+    }
+
     /// Decrypt this envelope using the server's private key
     pub fn decrypt(&self, server_keypair: &ServerKeyPair) -> Result<serde_json::Value, BridgeError> {
         // Import client's ephemeral public key
@@ -179,6 +258,10 @@ pub fn create_encrypted_response(
         public_key: ephemeral.public_key_base64(),
         extension_public_key: None,
         extension_name: None,
+        extension_id: None,
+        nonce: None,
+        timestamp: None,
+        signature: None,
     })
 }
 
@@ -225,4 +308,47 @@ mod tests {
 
         assert_eq!(shared_a, shared_b);
     }
+
+    #[test]
+    fn test_request_signature() {
+        let server = ServerKeyPair::generate();
+        let client = ServerKeyPair::generate();
+        let client_pk = client.public_key_base64();
+
+        // The client derives the same key from its side of the exchange
+        let client_key = {
+            let mut hasher = Sha256::new();
+            hasher.update(REQUEST_SIGNING_CONTEXT);
+            hasher.update(client.derive_shared_secret(&server.public_key));
+            <[u8; 32]>::from(hasher.finalize())
+        };
+        assert_eq!(client_key, request_signing_key(&server, &client_pk).unwrap());
+
+        let mut envelope = EncryptedEnvelope {
+            action: "get-logins".to_string(),
+            message: "ciphertext".to_string(),
+            iv: "iv".to_string(),
+            client_id: "client".to_string(),
+            public_key: "ephemeral".to_string(),
+            extension_public_key: None,
+            extension_name: None,
+            extension_id: Some("ext".to_string()),
+            nonce: Some("nonce-0123456789abcdef".to_string()),
+            timestamp: Some(1_700_000_000_000),
+            signature: None,
+        };
+        assert!(!envelope.verify_signature(&server, &client_pk).unwrap());
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(&client_key).unwrap();
+        mac.update(envelope.signing_input().as_bytes());
+        envelope.signature = Some(BASE64.encode(mac.finalize().into_bytes()));
+        assert!(envelope.verify_signature(&server, &client_pk).unwrap());
+
+        // Any signed field changed breaks it, as does another client key
+        let mut tampered = envelope.clone();
+        tampered.timestamp = Some(1_700_000_000_001);
+        assert!(!tampered.verify_signature(&server, &client_pk).unwrap());
+        let other = ServerKeyPair::generate().public_key_base64();
+        assert!(!envelope.verify_signature(&server, &other).unwrap());
+    }
 }
@@ -1,5 +1,6 @@
 //! Error types for browser bridge
 
+use crate::error_code::{CodedError, ErrorCode};
 use thiserror::Error;
 use tokio::sync::mpsc::error::SendError;
 use tokio_tungstenite::tungstenite::Message;
@@ -44,4 +45,37 @@ pub enum BridgeError {
 
     #[error("Crypto error: {0}")]
     Crypto(String),
+
+    #[error("TLS error: {0}")]
+    Tls(String),
+}
+
+impl CodedError for BridgeError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            BridgeError::WebSocket(_) | BridgeError::ChannelSend(_) | BridgeError::Tls(_) => {
+                ErrorCode::BridgeConnection
+            }
+            BridgeError::Io(_) => ErrorCode::Filesystem,
+            BridgeError::Json(_) => ErrorCode::Serialization,
+            BridgeError::Database(_) => ErrorCode::Database,
+            BridgeError::Unauthorized(_) => ErrorCode::BridgeUnauthorized,
+            BridgeError::ExtensionNotFound(_) => ErrorCode::NotFound,
+            BridgeError::InvalidRequest(_) => ErrorCode::Validation,
+            BridgeError::NotRunning => ErrorCode::BridgeNotRunning,
+            BridgeError::AlreadyRunning => ErrorCode::BridgeAlreadyRunning,
+            BridgeError::AuthorizationDenied => ErrorCode::BridgeAuthorizationDenied,
+            BridgeError::Timeout => ErrorCode::Timeout,
+            BridgeError::Crypto(_) => ErrorCode::Crypto,
+        }
+    }
+}
+
+impl serde::Serialize for BridgeError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(&self.envelope(), serializer)
+    }
 }
@@ -4,15 +4,26 @@
 //! CLI tools, servers, etc.) to communicate with haex-vault extensions.
 
 mod authorization;
+pub mod credentials;
 mod crypto;
 mod error;
+pub mod network;
 mod protocol;
+mod routing;
 mod server;
+pub mod session_tokens;
 #[cfg(test)]
 mod tests;
 
 pub use authorization::{AuthorizedClient, BlockedClient, PendingAuthorization};
+pub use error::BridgeError;
+pub use network::{BridgeNetworkConfig, BridgeNetworkStatus};
+pub use routing::RouteStatus;
+pub(crate) use routing::RouteTarget;
 pub use server::{ExternalBridge, SessionAuthorization, SessionBlockedClient, DEFAULT_BRIDGE_PORT};
+pub(crate) use server::{
+    check_client_blocked, dispatch_to_target, get_client_extension, get_extension_by_id,
+};
 
 /// Sentinel `extension_public_key` (and `extension_id`) used by external clients
 /// to address the haex-vault core itself instead of a specific extension.
@@ -21,16 +32,22 @@ pub const CORE_EXTENSION_ID: &str = "__core__";
 /// Sentinel `extension_name` paired with `CORE_EXTENSION_ID` for core requests.
 pub const CORE_EXTENSION_NAME: &str = "core";
 
-use crate::database::core::{execute_with_crdt, select_with_crdt};
-use crate::event_names::EVENT_CRDT_DIRTY_TABLES_CHANGED;
-use crate::AppState;
-use authorization::{
-    parse_authorized_client, parse_blocked_client,
-    SQL_DELETE_CLIENT, SQL_GET_ALL_CLIENTS, SQL_INSERT_CLIENT,
-    SQL_GET_ALL_BLOCKED_CLIENTS, SQL_INSERT_BLOCKED_CLIENT, SQL_DELETE_BLOCKED_CLIENT,
+use crate::database::core::{execute_with_crdt, with_connection};
+use crate::database::error::DatabaseError;
+use crate::database::generated::{
+    HaexExternalAuthorizedClientsNoSync, HaexExternalBlockedClientsNoSync,
+};
+use crate::events::{self, payloads::DirtyTablesChanged};
+use crate::table_names::{
+    COL_EXTERNAL_AUTHORIZED_CLIENTS_AUTHORIZED_AT, COL_EXTERNAL_BLOCKED_CLIENTS_BLOCKED_AT,
 };
+use crate::AppState;
+use authorization::{SQL_DELETE_BLOCKED_CLIENT, SQL_DELETE_CLIENT};
+use credentials::prompts::CredentialAnswer;
+use credentials::RememberedDecision;
 use serde_json::Value as JsonValue;
-use tauri::{AppHandle, Emitter, State};
+use session_tokens::SessionTokenInfo;
+use tauri::{AppHandle, Manager, State};
 
 /// Start the external bridge server on a specific port
 #[tauri::command]
@@ -79,15 +96,16 @@ pub fn external_bridge_get_default_port() -> u16 {
 /// Get all authorized external clients from database
 #[tauri::command]
 pub fn external_bridge_get_authorized_clients(state: State<'_, AppState>) -> Result<Vec<AuthorizedClient>, String> {
-    let rows = select_with_crdt(SQL_GET_ALL_CLIENTS.to_string(), vec![], &state.db)
-        .map_err(|e| e.to_string())?;
-
-    let clients: Vec<AuthorizedClient> = rows
-        .iter()
-        .filter_map(|row| parse_authorized_client(row))
-        .collect();
-
-    Ok(clients)
+    let rows = with_connection(&state.db, |conn| {
+        Ok(HaexExternalAuthorizedClientsNoSync::find(
+            conn,
+            &format!("ORDER BY {COL_EXTERNAL_AUTHORIZED_CLIENTS_AUTHORIZED_AT} DESC"),
+            [],
+        )?)
+    })
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows.into_iter().map(AuthorizedClient::from).collect())
 }
 
 /// Get all session-based authorizations (for "allow once" - not stored in database)
@@ -98,23 +116,107 @@ pub async fn external_bridge_get_session_authorizations(
     let bridge = state.external_bridge.lock().await;
     let session_auths = bridge.get_session_authorizations();
     let auths = session_auths.read().await;
-    Ok(auths.values().cloned().collect())
+    Ok(auths.values().flat_map(|routes| routes.values().cloned()).collect())
 }
 
 /// Revoke a session authorization (for "allow once")
+/// If extension_id is given, only the authorization for that extension is revoked.
 #[tauri::command]
 pub async fn external_bridge_revoke_session_authorization(
     client_id: String,
+    extension_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     let bridge = state.external_bridge.lock().await;
     let session_auths = bridge.get_session_authorizations();
     let mut auths = session_auths.write().await;
-    auths.remove(&client_id);
+    match &extension_id {
+        Some(extension_id) => {
+            if let Some(routes) = auths.get_mut(&client_id) {
+                routes.remove(extension_id);
+                if routes.is_empty() {
+                    auths.remove(&client_id);
+                }
+            }
+        }
+        None => {
+            auths.remove(&client_id);
+        }
+    }
+    drop(auths);
+    drop(bridge);
+
+    // Its session tokens would admit it again on the next reconnect
+    with_connection(&state.db, |conn| match &extension_id {
+        Some(extension_id) => session_tokens::store::remove_scope(conn, &client_id, extension_id),
+        None => session_tokens::store::revoke_client(conn, &client_id),
+    })
+    .map_err(|e| e.to_string())?;
     println!("[ExternalAuth] Session authorization revoked for client: {}", client_id);
     Ok(())
 }
 
+/// Request queues of the connected clients, one per client and extension
+#[tauri::command]
+pub async fn external_bridge_get_routes(app: AppHandle) -> Vec<RouteStatus> {
+    let state = app.state::<AppState>();
+    let bridge = state.external_bridge.lock().await;
+    bridge.get_routes().await
+}
+
+/// Get the stored bind address, TLS and client allowlist settings
+#[tauri::command]
+pub fn external_bridge_get_network_config(
+    app: AppHandle,
+) -> Result<BridgeNetworkConfig, BridgeError> {
+    BridgeNetworkConfig::load(&app)
+}
+
+/// Store the network settings and restart a running server with them
+#[tauri::command]
+pub async fn external_bridge_set_network_config(
+    app: AppHandle,
+    config: BridgeNetworkConfig,
+    state: State<'_, AppState>,
+) -> Result<BridgeNetworkStatus, BridgeError> {
+    config.validate()?;
+    config.save(&app)?;
+
+    let mut bridge = state.external_bridge.lock().await;
+    if bridge.is_running() {
+        let port = bridge.get_port();
+        bridge.stop().await?;
+        bridge.start(app, Some(port)).await?;
+    }
+    Ok(bridge.get_network_status())
+}
+
+/// Get the address, TLS fingerprint and allowlist the server runs with
+#[tauri::command]
+pub async fn external_bridge_get_network_status(app: AppHandle) -> BridgeNetworkStatus {
+    let state = app.state::<AppState>();
+    let bridge = state.external_bridge.lock().await;
+    bridge.get_network_status()
+}
+
+/// Replace the TLS identity, e.g. after the key leaked. Paired clients have
+/// to pin the new fingerprint.
+#[tauri::command]
+pub async fn external_bridge_regenerate_tls_identity(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<BridgeNetworkStatus, BridgeError> {
+    network::tls::TlsIdentity::regenerate(&app)?;
+
+    let mut bridge = state.external_bridge.lock().await;
+    if bridge.is_running() && bridge.get_network_status().tls {
+        let port = bridge.get_port();
+        bridge.stop().await?;
+        bridge.start(app, Some(port)).await?;
+    }
+    Ok(bridge.get_network_status())
+}
+
 /// Get all session-blocked clients (for "deny once" - not stored in database)
 #[tauri::command]
 pub async fn external_bridge_get_session_blocked_clients(
@@ -148,13 +250,22 @@ pub fn external_bridge_revoke_client(
         .lock()
         .map_err(|e| format!("Failed to lock HLC: {}", e))?;
 
-    let params = vec![JsonValue::String(client_id)];
+    let params = vec![JsonValue::String(client_id.clone())];
 
     execute_with_crdt(SQL_DELETE_CLIENT.to_string(), params, &state.db, &hlc_guard)
         .map_err(|e| e.to_string())?;
+    drop(hlc_guard);
+
+    // A revoked client keeps no remembered credential decisions or session tokens
+    with_connection(&state.db, |conn| {
+        credentials::store::forget(conn, &client_id, None)?;
+        session_tokens::store::revoke_client(conn, &client_id)
+    })
+    .map_err(|e| e.to_string())?;
+    state.bridge_credential_prompts.cancel_client(&client_id);
 
     // Emit event to notify frontend
-    let _ = app_handle.emit_to("main", EVENT_CRDT_DIRTY_TABLES_CHANGED, ());
+    let _ = events::emit_to_main(&app_handle, &DirtyTablesChanged {});
 
     Ok(())
 }
@@ -235,6 +346,20 @@ pub async fn external_bridge_client_allow(
     remember: bool,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
+    // Issued either way, so a reconnect skips the prompt even after a restart
+    let session_token = session_tokens::with_vault(&state.db, |conn| {
+        session_tokens::issue(
+            conn,
+            &client_id,
+            &client_name,
+            &public_key,
+            std::slice::from_ref(&extension_id),
+            session_tokens::now(),
+        )
+    })
+    .map_err(|e| eprintln!("[ExternalBridge] Failed to issue session token: {}", e))
+    .ok();
+
     if remember {
         // Insert into database via CRDT for permanent authorization
         {
@@ -243,21 +368,27 @@ pub async fn external_bridge_client_allow(
                 .lock()
                 .map_err(|e| format!("Failed to lock HLC: {}", e))?;
 
-            let row_id = uuid::Uuid::new_v4().to_string();
-            let params = vec![
-                JsonValue::String(row_id),
-                JsonValue::String(client_id.clone()),
-                JsonValue::String(client_name),
-                JsonValue::String(public_key),
-                JsonValue::String(extension_id.clone()),
-            ];
-
-            execute_with_crdt(SQL_INSERT_CLIENT.to_string(), params, &state.db, &hlc_guard)
-                .map_err(|e| e.to_string())?;
+            let row = HaexExternalAuthorizedClientsNoSync {
+                id: uuid::Uuid::new_v4().to_string(),
+                client_id: client_id.clone(),
+                client_name,
+                public_key,
+                extension_id: extension_id.clone(),
+                authorized_at: None,
+                last_seen: None,
+            };
+
+            with_connection(&state.db, |conn| {
+                let tx = conn.transaction()?;
+                row.insert(&tx, &hlc_guard)?;
+                tx.commit()?;
+                Ok(())
+            })
+            .map_err(|e| e.to_string())?;
         }
 
         // Emit event to notify frontend
-        let _ = app_handle.emit_to("main", EVENT_CRDT_DIRTY_TABLES_CHANGED, ());
+        let _ = events::emit_to_main(&app_handle, &DirtyTablesChanged {});
     } else {
         // Store session-based authorization (for "allow once")
         // This persists for the lifetime of the haex-vault session
@@ -270,7 +401,7 @@ pub async fn external_bridge_client_allow(
     // Notify connected client that authorization was granted
     let bridge = state.external_bridge.lock().await;
     bridge
-        .notify_authorization_granted(&client_id, &extension_id)
+        .notify_authorization_granted(&client_id, &extension_id, session_token.as_ref())
         .await
         .map_err(|e| e.to_string())
 }
@@ -295,20 +426,25 @@ pub async fn external_bridge_client_block(
                 .lock()
                 .map_err(|e| format!("Failed to lock HLC: {}", e))?;
 
-            let row_id = uuid::Uuid::new_v4().to_string();
-            let params = vec![
-                JsonValue::String(row_id),
-                JsonValue::String(client_id.clone()),
-                JsonValue::String(client_name),
-                JsonValue::String(public_key),
-            ];
-
-            execute_with_crdt(SQL_INSERT_BLOCKED_CLIENT.to_string(), params, &state.db, &hlc_guard)
-                .map_err(|e| e.to_string())?;
+            let row = HaexExternalBlockedClientsNoSync {
+                id: uuid::Uuid::new_v4().to_string(),
+                client_id: client_id.clone(),
+                client_name,
+                public_key,
+                blocked_at: None,
+            };
+
+            with_connection(&state.db, |conn| {
+                let tx = conn.transaction()?;
+                row.insert(&tx, &hlc_guard)?;
+                tx.commit()?;
+                Ok(())
+            })
+            .map_err(|e| e.to_string())?;
         }
 
         // Emit event to notify frontend
-        let _ = app_handle.emit_to("main", EVENT_CRDT_DIRTY_TABLES_CHANGED, ());
+        let _ = events::emit_to_main(&app_handle, &DirtyTablesChanged {});
     }
     // Without `remember`, we only reject this specific request. A session-wide
     // block would silently swallow every subsequent reconnect — bad UX when
@@ -325,15 +461,16 @@ pub async fn external_bridge_client_block(
 /// Get all blocked external clients from database
 #[tauri::command]
 pub fn external_bridge_get_blocked_clients(state: State<'_, AppState>) -> Result<Vec<BlockedClient>, String> {
-    let rows = select_with_crdt(SQL_GET_ALL_BLOCKED_CLIENTS.to_string(), vec![], &state.db)
-        .map_err(|e| e.to_string())?;
-
-    let clients: Vec<BlockedClient> = rows
-        .iter()
-        .filter_map(|row| parse_blocked_client(row))
-        .collect();
-
-    Ok(clients)
+    let rows = with_connection(&state.db, |conn| {
+        Ok(HaexExternalBlockedClientsNoSync::find(
+            conn,
+            &format!("ORDER BY {COL_EXTERNAL_BLOCKED_CLIENTS_BLOCKED_AT} DESC"),
+            [],
+        )?)
+    })
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows.into_iter().map(BlockedClient::from).collect())
 }
 
 /// Unblock an external client (remove from blocked list)
@@ -354,7 +491,7 @@ pub fn external_bridge_unblock_client(
         .map_err(|e| e.to_string())?;
 
     // Emit event to notify frontend
-    let _ = app_handle.emit_to("main", EVENT_CRDT_DIRTY_TABLES_CHANGED, ());
+    let _ = events::emit_to_main(&app_handle, &DirtyTablesChanged {});
 
     Ok(())
 }
@@ -372,3 +509,64 @@ pub async fn extension_signal_ready(
     Ok(())
 }
 
+
+/// Answer a consent prompt of the credential protocol
+/// (`external-bridge:credential-prompt`). With `remember`, the decision
+/// applies to later requests of the client for the same origin and kind.
+#[tauri::command]
+pub fn external_bridge_credential_respond(
+    prompt_id: String,
+    allow: bool,
+    remember: bool,
+    state: State<'_, AppState>,
+) -> Result<(), BridgeError> {
+    state
+        .bridge_credential_prompts
+        .resolve(&prompt_id, CredentialAnswer { allow, remember })
+        .map_err(|e| BridgeError::InvalidRequest(e.to_string()))
+}
+
+/// Remembered credential decisions of all clients
+#[tauri::command]
+pub fn external_bridge_get_credential_decisions(
+    state: State<'_, AppState>,
+) -> Result<Vec<RememberedDecision>, DatabaseError> {
+    with_connection(&state.db, |conn| credentials::store::load(conn))
+}
+
+/// Forget the remembered credential decisions of a client, only those for
+/// `origin` if given. Returns how many were removed.
+#[tauri::command]
+pub fn external_bridge_forget_credential_decisions(
+    client_id: String,
+    origin: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<usize, DatabaseError> {
+    with_connection(&state.db, |conn| {
+        credentials::store::forget(conn, &client_id, origin.as_deref())
+    })
+}
+
+/// Session tokens that are still valid, with the client they were issued to
+#[tauri::command]
+pub fn external_bridge_get_session_tokens(
+    state: State<'_, AppState>,
+) -> Result<Vec<SessionTokenInfo>, DatabaseError> {
+    let now = session_tokens::now();
+    with_connection(&state.db, |conn| {
+        session_tokens::store::prune(conn, now)?;
+        session_tokens::store::load(conn)
+    })
+}
+
+/// Revoke a single session token. Connected clients using it lose access
+/// with their next request. Returns whether the token existed.
+#[tauri::command]
+pub fn external_bridge_revoke_session_token(
+    token_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool, DatabaseError> {
+    with_connection(&state.db, |conn| {
+        session_tokens::store::remove(conn, &token_id)
+    })
+}
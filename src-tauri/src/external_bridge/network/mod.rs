@@ -0,0 +1,209 @@
+//! Bind address, TLS and client allowlist of the bridge
+//!
+//! By default the bridge listens on `127.0.0.1` with plain WebSockets. To let
+//! trusted devices on the home network connect directly, it can bind a LAN
+//! address instead; that requires TLS (`wss://`). Like LocalSend, the bridge
+//! then presents a self-signed certificate ([`tls`]) that clients pin by its
+//! SHA-256 fingerprint, shown in the settings, instead of trusting a CA.
+//!
+//! Peers outside loopback are only accepted from the configured client IP
+//! ranges (CIDR like `192.168.1.0/24`, or single addresses); the check runs
+//! before the TLS handshake. An empty allowlist keeps a LAN bridge closed to
+//! everyone but this device.
+//!
+//! The configuration lives in `external_bridge_network.json` in the app's
+//! local data dir rather than in the vault: the bridge starts before a vault
+//! is opened, and the setting belongs to this device.
+
+#[cfg(test)]
+mod tests;
+pub mod tls;
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tauri::AppHandle;
+use ts_rs::TS;
+
+use super::error::BridgeError;
+
+const NETWORK_CONFIG_FILE: &str = "external_bridge_network.json";
+
+/// Network settings of the bridge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeNetworkConfig {
+    /// IP address to listen on; `127.0.0.1` keeps the bridge on this device
+    #[serde(default = "default_bind_address")]
+    pub bind_address: String,
+    /// Serve `wss://` with the self-signed identity; required off loopback
+    #[serde(default)]
+    pub tls: bool,
+    /// Client IP ranges (CIDR or single address) accepted besides loopback
+    #[serde(default)]
+    pub allowed_client_ranges: Vec<String>,
+}
+
+fn default_bind_address() -> String {
+    Ipv4Addr::LOCALHOST.to_string()
+}
+
+impl Default for BridgeNetworkConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: default_bind_address(),
+            tls: false,
+            allowed_client_ranges: Vec::new(),
+        }
+    }
+}
+
+impl BridgeNetworkConfig {
+    pub fn bind_ip(&self) -> Result<IpAddr, BridgeError> {
+        IpAddr::from_str(self.bind_address.trim()).map_err(|_| {
+            BridgeError::InvalidRequest(format!("Invalid bind address: {}", self.bind_address))
+        })
+    }
+
+    /// Whether the bridge is reachable from other devices
+    pub fn is_lan(&self) -> bool {
+        self.bind_ip().is_ok_and(|ip| !ip.is_loopback())
+    }
+
+    /// Rejects unparsable addresses and ranges, and a LAN bind without TLS
+    pub fn validate(&self) -> Result<(), BridgeError> {
+        let ip = self.bind_ip()?;
+        if !ip.is_loopback() && !self.tls {
+            return Err(BridgeError::InvalidRequest(format!(
+                "Binding {} requires TLS",
+                ip
+            )));
+        }
+        self.allowlist().map(|_| ())
+    }
+
+    pub fn allowlist(&self) -> Result<ClientAllowlist, BridgeError> {
+        let ranges = self
+            .allowed_client_ranges
+            .iter()
+            .map(|range| IpRange::from_str(range))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ClientAllowlist { ranges })
+    }
+
+    fn path(app_handle: &AppHandle) -> Result<PathBuf, BridgeError> {
+        let dir = crate::app_profile::data_dir(app_handle)
+            .map_err(|e| std::io::Error::other(format!("Failed to resolve app data dir: {e}")))?;
+        Ok(dir.join(NETWORK_CONFIG_FILE))
+    }
+
+    /// Loads the configuration; without a file the bridge stays on loopback.
+    pub fn load(app_handle: &AppHandle) -> Result<Self, BridgeError> {
+        Self::load_from(&Self::path(app_handle)?)
+    }
+
+    pub fn save(&self, app_handle: &AppHandle) -> Result<(), BridgeError> {
+        self.save_to(&Self::path(app_handle)?)
+    }
+
+    pub(crate) fn load_from(path: &Path) -> Result<Self, BridgeError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub(crate) fn save_to(&self, path: &Path) -> Result<(), BridgeError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// What the running bridge listens on, for the settings UI and pairing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeNetworkStatus {
+    pub running: bool,
+    pub bind_address: String,
+    pub port: u16,
+    pub tls: bool,
+    /// SHA-256 of the TLS certificate (hex) clients pin; `None` without TLS
+    pub tls_fingerprint: Option<String>,
+    pub allowed_client_ranges: Vec<String>,
+}
+
+/// A CIDR range of client addresses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRange {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpRange {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = BridgeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || BridgeError::InvalidRequest(format!("Invalid client IP range: {s}"));
+        let (address, prefix_len) = match s.trim().split_once('/') {
+            Some((address, prefix_len)) => (address, Some(prefix_len)),
+            None => (s.trim(), None),
+        };
+        let network = IpAddr::from_str(address)
+            .map_err(|_| invalid())?
+            .to_canonical();
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(len) => len.parse::<u8>().map_err(|_| invalid())?,
+            None => max_len,
+        };
+        if prefix_len > max_len {
+            return Err(invalid());
+        }
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+}
+
+/// Which peers may open a connection
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientAllowlist {
+    ranges: Vec<IpRange>,
+}
+
+impl ClientAllowlist {
+    /// Loopback peers are always accepted; others must be in a range
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        ip.is_loopback() || self.ranges.iter().any(|range| range.contains(ip))
+    }
+}
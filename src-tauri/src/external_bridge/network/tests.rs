@@ -0,0 +1,137 @@
+use super::tls::TlsIdentity;
+use super::{BridgeNetworkConfig, ClientAllowlist, IpRange};
+use std::net::IpAddr;
+use std::str::FromStr;
+
+fn ip(s: &str) -> IpAddr {
+    IpAddr::from_str(s).unwrap()
+}
+
+fn lan_config(ranges: &[&str]) -> BridgeNetworkConfig {
+    BridgeNetworkConfig {
+        bind_address: "192.168.1.10".to_string(),
+        tls: true,
+        allowed_client_ranges: ranges.iter().map(|s| s.to_string()).collect(),
+    }
+}
+
+#[test]
+fn test_ip_range_parsing() {
+    assert!(IpRange::from_str("192.168.1.0/24").is_ok());
+    assert!(IpRange::from_str(" 10.0.0.7 ").is_ok());
+    assert!(IpRange::from_str("fd00::/8").is_ok());
+    assert!(IpRange::from_str("0.0.0.0/0").is_ok());
+
+    assert!(IpRange::from_str("192.168.1.0/33").is_err());
+    assert!(IpRange::from_str("fd00::/129").is_err());
+    assert!(IpRange::from_str("192.168.1.0/").is_err());
+    assert!(IpRange::from_str("home-network").is_err());
+}
+
+#[test]
+fn test_ip_range_contains() {
+    let range = IpRange::from_str("192.168.1.0/24").unwrap();
+    assert!(range.contains(ip("192.168.1.1")));
+    assert!(range.contains(ip("192.168.1.255")));
+    assert!(!range.contains(ip("192.168.2.1")));
+    // IPv4-mapped IPv6 peers (dual-stack sockets) match their IPv4 range
+    assert!(range.contains(ip("::ffff:192.168.1.20")));
+    assert!(!range.contains(ip("fd00::1")));
+
+    let single = IpRange::from_str("10.0.0.7").unwrap();
+    assert!(single.contains(ip("10.0.0.7")));
+    assert!(!single.contains(ip("10.0.0.8")));
+
+    let v6 = IpRange::from_str("fd00:1234::/32").unwrap();
+    assert!(v6.contains(ip("fd00:1234:5678::1")));
+    assert!(!v6.contains(ip("fd00:4321::1")));
+
+    assert!(IpRange::from_str("0.0.0.0/0")
+        .unwrap()
+        .contains(ip("203.0.113.9")));
+}
+
+#[test]
+fn test_allowlist_always_admits_loopback() {
+    let empty = ClientAllowlist::default();
+    assert!(empty.allows(ip("127.0.0.1")));
+    assert!(empty.allows(ip("::1")));
+    assert!(!empty.allows(ip("192.168.1.20")));
+
+    let allowlist = lan_config(&["192.168.1.0/24", "10.0.0.7"])
+        .allowlist()
+        .unwrap();
+    assert!(allowlist.allows(ip("192.168.1.20")));
+    assert!(allowlist.allows(ip("10.0.0.7")));
+    assert!(!allowlist.allows(ip("10.0.0.8")));
+}
+
+#[test]
+fn test_lan_bind_requires_tls() {
+    let default = BridgeNetworkConfig::default();
+    assert_eq!(default.bind_address, "127.0.0.1");
+    assert!(!default.is_lan());
+    assert!(default.validate().is_ok());
+
+    let mut config = lan_config(&["192.168.1.0/24"]);
+    assert!(config.is_lan());
+    assert!(config.validate().is_ok());
+    config.tls = false;
+    assert!(config.validate().is_err());
+
+    config.bind_address = "0.0.0.0".to_string();
+    assert!(config.is_lan());
+    assert!(config.validate().is_err());
+
+    let invalid_range = lan_config(&["192.168.1.0/99"]);
+    assert!(invalid_range.validate().is_err());
+    let invalid_address = BridgeNetworkConfig {
+        bind_address: "localhost".to_string(),
+        ..BridgeNetworkConfig::default()
+    };
+    assert!(invalid_address.validate().is_err());
+}
+
+#[test]
+fn test_network_config_persistence() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("external_bridge_network.json");
+    assert_eq!(
+        BridgeNetworkConfig::load_from(&path).unwrap(),
+        BridgeNetworkConfig::default()
+    );
+
+    let config = lan_config(&["192.168.1.0/24"]);
+    config.save_to(&path).unwrap();
+    assert_eq!(BridgeNetworkConfig::load_from(&path).unwrap(), config);
+
+    // Missing fields fall back to loopback without TLS
+    let parsed: BridgeNetworkConfig = serde_json::from_str("{}").unwrap();
+    assert_eq!(parsed, BridgeNetworkConfig::default());
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["bindAddress"], "192.168.1.10");
+    assert_eq!(json["allowedClientRanges"][0], "192.168.1.0/24");
+}
+
+#[test]
+fn test_tls_identity_is_kept_across_restarts() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("external_bridge_tls.json");
+
+    let identity = TlsIdentity::load_or_create_at(&path).unwrap();
+    assert!(identity
+        .certificate_pem
+        .starts_with("-----BEGIN CERTIFICATE-----"));
+    let fingerprint = identity.fingerprint().unwrap();
+    assert_eq!(fingerprint.len(), 64);
+
+    let reloaded = TlsIdentity::load_or_create_at(&path).unwrap();
+    assert_eq!(reloaded.fingerprint().unwrap(), fingerprint);
+
+    // A new identity has a new fingerprint
+    let other = TlsIdentity::generate().unwrap();
+    assert_ne!(other.fingerprint().unwrap(), fingerprint);
+
+    // The key must not end up in logs
+    assert!(!format!("{:?}", identity).contains("PRIVATE KEY"));
+}
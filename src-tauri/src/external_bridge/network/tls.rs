@@ -0,0 +1,111 @@
+//! Self-signed TLS identity of the bridge
+//!
+//! Created on first use and kept in `external_bridge_tls.json` in the app's
+//! local data dir, so the fingerprint clients pinned stays the same across
+//! restarts. It only changes when the user regenerates the identity.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use super::super::error::BridgeError;
+
+const TLS_IDENTITY_FILE: &str = "external_bridge_tls.json";
+/// Subject name of the certificate; clients pin the fingerprint, not the name
+const CERTIFICATE_NAME: &str = "haex-vault";
+
+/// Certificate and PKCS#8 key, both PEM
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsIdentity {
+    pub certificate_pem: String,
+    pub private_key_pem: String,
+}
+
+impl std::fmt::Debug for TlsIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsIdentity")
+            .field("certificate_pem", &self.certificate_pem)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TlsIdentity {
+    pub fn generate() -> Result<Self, BridgeError> {
+        let rcgen::CertifiedKey { cert, key_pair } =
+            rcgen::generate_simple_self_signed(vec![CERTIFICATE_NAME.to_string()])
+                .map_err(|e| BridgeError::Tls(e.to_string()))?;
+        Ok(Self {
+            certificate_pem: cert.pem(),
+            private_key_pem: key_pair.serialize_pem(),
+        })
+    }
+
+    /// SHA-256 of the DER certificate (lowercase hex), as pinned by clients
+    pub fn fingerprint(&self) -> Result<String, BridgeError> {
+        let der: String = self
+            .certificate_pem
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .map(str::trim)
+            .collect();
+        let der = BASE64
+            .decode(der)
+            .map_err(|e| BridgeError::Tls(format!("Invalid certificate PEM: {e}")))?;
+        Ok(hex::encode(Sha256::digest(der)))
+    }
+
+    pub fn acceptor(&self) -> Result<tokio_native_tls::TlsAcceptor, BridgeError> {
+        let identity = native_tls::Identity::from_pkcs8(
+            self.certificate_pem.as_bytes(),
+            self.private_key_pem.as_bytes(),
+        )
+        .map_err(|e| BridgeError::Tls(e.to_string()))?;
+        let acceptor =
+            native_tls::TlsAcceptor::new(identity).map_err(|e| BridgeError::Tls(e.to_string()))?;
+        Ok(acceptor.into())
+    }
+
+    fn path(app_handle: &AppHandle) -> Result<PathBuf, BridgeError> {
+        let dir = crate::app_profile::data_dir(app_handle)
+            .map_err(|e| std::io::Error::other(format!("Failed to resolve app data dir: {e}")))?;
+        Ok(dir.join(TLS_IDENTITY_FILE))
+    }
+
+    pub fn load_or_create(app_handle: &AppHandle) -> Result<Self, BridgeError> {
+        Self::load_or_create_at(&Self::path(app_handle)?)
+    }
+
+    /// Replaces the identity; clients have to pin the new fingerprint
+    pub fn regenerate(app_handle: &AppHandle) -> Result<Self, BridgeError> {
+        let identity = Self::generate()?;
+        identity.save_to(&Self::path(app_handle)?)?;
+        Ok(identity)
+    }
+
+    pub(crate) fn load_or_create_at(path: &Path) -> Result<Self, BridgeError> {
+        if path.exists() {
+            let content = fs::read_to_string(path)?;
+            return Ok(serde_json::from_str(&content)?);
+        }
+        let identity = Self::generate()?;
+        identity.save_to(path)?;
+        Ok(identity)
+    }
+
+    fn save_to(&self, path: &Path) -> Result<(), BridgeError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+}
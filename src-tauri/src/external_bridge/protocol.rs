@@ -1,8 +1,30 @@
 //! Protocol definitions for browser bridge communication
+//!
+//! Replay protection: every request is signed and carries a nonce and a
+//! timestamp ([`EncryptedEnvelope`]). The [`ReplayGuard`] accepts a request
+//! only if its timestamp lies within [`REPLAY_WINDOW_MS`] of the server
+//! clock and its nonce wasn't seen in that window, and rejects request IDs a
+//! client already used within the window. Unsigned requests are rejected:
+//! without a timestamp, a frame could be replayed once the window forgot its
+//! request ID.
+//!
+//! Version 2 of the protocol made signing mandatory. Handshakes announcing an
+//! older version are refused with [`UnsupportedVersion`], so an outdated
+//! client learns it has to upgrade instead of failing on every request.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
 use ts_rs::TS;
 
+/// Protocol version spoken by the server; version 2 requires signed requests
+pub const PROTOCOL_VERSION: u32 = 2;
+/// How far a request's timestamp may be off the server clock, either way
+pub const REPLAY_WINDOW_MS: i64 = 60_000;
+/// Accepted nonce length (characters)
+const MIN_NONCE_CHARS: usize = 16;
+const MAX_NONCE_CHARS: usize = 128;
+
 /// Extension requested by an external client
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, rename_all = "camelCase")]
@@ -59,6 +81,10 @@ pub struct HandshakeRequest {
     pub version: u32,
     /// Client information
     pub client: ClientInfo,
+    /// Session token from an earlier approval; a valid one skips the
+    /// authorization prompt (see `session_tokens`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_token: Option<String>,
 }
 
 /// Handshake response from server
@@ -117,3 +143,166 @@ impl BridgeResponse {
         }
     }
 }
+
+/// Current time in Unix milliseconds, the unit of request timestamps
+pub fn unix_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| i64::try_from(d.as_millis()).unwrap_or(i64::MAX))
+        .unwrap_or(0)
+}
+
+/// Why a request was rejected as a possible replay
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ReplayError {
+    #[error("Signed requests need nonce, timestamp and signature")]
+    MissingFields,
+
+    #[error("Requests must be signed")]
+    SignatureRequired,
+
+    #[error("Invalid request signature")]
+    InvalidSignature,
+
+    #[error("Invalid nonce")]
+    InvalidNonce,
+
+    #[error("Request timestamp is {skew_ms} ms off the server clock")]
+    StaleTimestamp { skew_ms: i64 },
+
+    #[error("Nonce was already used")]
+    ReplayedNonce,
+
+    #[error("Request ID was already used: {request_id}")]
+    DuplicateRequestId { request_id: String },
+}
+
+/// Handshake from a client speaking an older protocol version
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error(
+    "Protocol version {client_version} is unsupported, please upgrade the client to version {PROTOCOL_VERSION}"
+)]
+pub struct UnsupportedVersion {
+    pub client_version: u32,
+}
+
+impl UnsupportedVersion {
+    /// Code of the `error` protocol message
+    pub fn code(&self) -> &'static str {
+        "PROTOCOL_VERSION_UNSUPPORTED"
+    }
+}
+
+/// Accept handshakes from clients that speak at least [`PROTOCOL_VERSION`]
+pub fn check_protocol_version(client_version: u32) -> Result<(), UnsupportedVersion> {
+    if client_version < PROTOCOL_VERSION {
+        return Err(UnsupportedVersion { client_version });
+    }
+    Ok(())
+}
+
+impl ReplayError {
+    /// Code of the `error` protocol message
+    pub fn code(&self) -> &'static str {
+        match self {
+            ReplayError::MissingFields => "MISSING_SIGNATURE",
+            ReplayError::SignatureRequired => "SIGNATURE_REQUIRED",
+            ReplayError::InvalidSignature => "INVALID_SIGNATURE",
+            ReplayError::InvalidNonce => "INVALID_NONCE",
+            ReplayError::StaleTimestamp { .. } => "STALE_REQUEST",
+            ReplayError::ReplayedNonce => "REPLAYED_NONCE",
+            ReplayError::DuplicateRequestId { .. } => "DUPLICATE_REQUEST_ID",
+        }
+    }
+}
+
+/// Nonces and request IDs seen within the replay window, per client
+#[derive(Debug)]
+pub struct ReplayGuard {
+    window_ms: i64,
+    /// (client_id, nonce) → request timestamp
+    nonces: HashMap<(String, String), i64>,
+    /// (client_id, request_id) → time it was seen
+    request_ids: HashMap<(String, String), i64>,
+}
+
+impl Default for ReplayGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReplayGuard {
+    pub fn new() -> Self {
+        Self::with_window(REPLAY_WINDOW_MS)
+    }
+
+    pub fn with_window(window_ms: i64) -> Self {
+        Self {
+            window_ms,
+            nonces: HashMap::new(),
+            request_ids: HashMap::new(),
+        }
+    }
+
+    /// Checks the nonce and timestamp of a signed request and records the
+    /// nonce. Call only after the signature was verified.
+    pub fn check_nonce(
+        &mut self,
+        client_id: &str,
+        nonce: &str,
+        timestamp_ms: i64,
+        now_ms: i64,
+    ) -> Result<(), ReplayError> {
+        let nonce_chars = nonce.chars().count();
+        if !(MIN_NONCE_CHARS..=MAX_NONCE_CHARS).contains(&nonce_chars) {
+            return Err(ReplayError::InvalidNonce);
+        }
+        let skew_ms = now_ms.saturating_sub(timestamp_ms);
+        if skew_ms.saturating_abs() > self.window_ms {
+            return Err(ReplayError::StaleTimestamp { skew_ms });
+        }
+
+        self.prune(now_ms);
+        let key = (client_id.to_string(), nonce.to_string());
+        if self.nonces.contains_key(&key) {
+            return Err(ReplayError::ReplayedNonce);
+        }
+        self.nonces.insert(key, timestamp_ms);
+        Ok(())
+    }
+
+    /// Rejects a request ID `client_id` already used within the window
+    pub fn check_request_id(
+        &mut self,
+        client_id: &str,
+        request_id: &str,
+        now_ms: i64,
+    ) -> Result<(), ReplayError> {
+        self.prune(now_ms);
+        let key = (client_id.to_string(), request_id.to_string());
+        if self.request_ids.contains_key(&key) {
+            return Err(ReplayError::DuplicateRequestId {
+                request_id: request_id.to_string(),
+            });
+        }
+        self.request_ids.insert(key, now_ms);
+        Ok(())
+    }
+
+    /// Forgets what a timestamp check would reject anyway
+    fn prune(&mut self, now_ms: i64) {
+        let oldest = now_ms.saturating_sub(self.window_ms);
+        self.nonces.retain(|_, timestamp| *timestamp >= oldest);
+        self.request_ids.retain(|_, seen| *seen >= oldest);
+    }
+
+    /// Tracked nonces and request IDs
+    pub fn len(&self) -> usize {
+        self.nonces.len() + self.request_ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
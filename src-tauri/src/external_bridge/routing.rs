@@ -0,0 +1,175 @@
+//! Request routing for clients that talk to several targets
+//!
+//! A client can address any extension it is authorized for (or the core)
+//! over one connection: a request names its target by `extensionId`, or by
+//! `extensionPublicKey` + `extensionName`, and both resolve to the same
+//! [`RouteTarget`]. Each route (client and target) gets its own bounded
+//! queue with one worker, so requests to one extension keep their order
+//! while a slow extension only delays its own route. When a queue is full,
+//! further requests to that target are rejected right away with
+//! `ROUTE_BUSY` (backpressure) instead of piling up.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use ts_rs::TS;
+
+use super::{CORE_EXTENSION_ID, CORE_EXTENSION_NAME};
+
+/// Requests a route holds at most, including the one being processed
+pub const ROUTE_QUEUE_CAPACITY: usize = 16;
+
+/// Error code of requests rejected by a full route
+pub const ROUTE_BUSY: &str = "ROUTE_BUSY";
+
+/// A queued request: processes it and sends the response
+pub type RouteJob = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Resolved target of a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteTarget {
+    pub extension_id: String,
+    /// Extension's public key (from manifest)
+    pub public_key: String,
+    /// Extension's name (from manifest)
+    pub name: String,
+}
+
+impl RouteTarget {
+    pub fn core() -> Self {
+        Self {
+            extension_id: CORE_EXTENSION_ID.to_string(),
+            public_key: CORE_EXTENSION_ID.to_string(),
+            name: CORE_EXTENSION_NAME.to_string(),
+        }
+    }
+
+    pub fn is_core(&self) -> bool {
+        self.extension_id == CORE_EXTENSION_ID
+    }
+}
+
+/// Load of one route, for the settings UI
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub struct RouteStatus {
+    pub client_id: String,
+    pub extension_id: String,
+    /// Queued requests, including the one being processed
+    pub pending: u32,
+    #[ts(type = "number")]
+    pub completed: u64,
+    /// Requests rejected because the queue was full
+    #[ts(type = "number")]
+    pub rejected: u64,
+}
+
+#[derive(Debug, Default)]
+struct RouteCounters {
+    pending: AtomicUsize,
+    completed: AtomicU64,
+    rejected: AtomicU64,
+}
+
+struct Route {
+    queue: mpsc::Sender<RouteJob>,
+    counters: Arc<RouteCounters>,
+}
+
+/// The routes of one connection. Dropping it ends the workers once their
+/// queues are drained.
+pub struct RouteQueues {
+    capacity: usize,
+    routes: Mutex<HashMap<String, Route>>,
+}
+
+impl Default for RouteQueues {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RouteQueues {
+    pub fn new() -> Self {
+        Self::with_capacity(ROUTE_QUEUE_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            routes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Queues `job` on the route to `extension_id`, starting the route's
+    /// worker on first use. Returns `false` if the route is full.
+    pub fn enqueue(&self, extension_id: &str, job: RouteJob) -> bool {
+        let Ok(mut routes) = self.routes.lock() else {
+            return false;
+        };
+        // A worker that is gone (e.g. after a panic) gets replaced
+        if routes
+            .get(extension_id)
+            .is_some_and(|route| route.queue.is_closed())
+        {
+            routes.remove(extension_id);
+        }
+        let route = routes
+            .entry(extension_id.to_string())
+            .or_insert_with(|| self.spawn_route());
+
+        // `pending` includes the job being processed, which has already
+        // left the channel; counted before sending since the worker may
+        // finish the job right away
+        if route.counters.pending.fetch_add(1, Ordering::SeqCst) >= self.capacity {
+            route.counters.pending.fetch_sub(1, Ordering::SeqCst);
+            route.counters.rejected.fetch_add(1, Ordering::SeqCst);
+            return false;
+        }
+        if route.queue.try_send(job).is_err() {
+            route.counters.pending.fetch_sub(1, Ordering::SeqCst);
+            route.counters.rejected.fetch_add(1, Ordering::SeqCst);
+            return false;
+        }
+        true
+    }
+
+    fn spawn_route(&self) -> Route {
+        let (queue, mut jobs) = mpsc::channel::<RouteJob>(self.capacity);
+        let counters = Arc::new(RouteCounters::default());
+        let worker_counters = counters.clone();
+        tokio::spawn(async move {
+            while let Some(job) = jobs.recv().await {
+                job.await;
+                worker_counters.pending.fetch_sub(1, Ordering::SeqCst);
+                worker_counters.completed.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        Route { queue, counters }
+    }
+
+    /// Load of the routes of `client_id`, ordered by extension ID
+    pub fn status(&self, client_id: &str) -> Vec<RouteStatus> {
+        let Ok(routes) = self.routes.lock() else {
+            return Vec::new();
+        };
+        let mut status: Vec<RouteStatus> = routes
+            .iter()
+            .map(|(extension_id, route)| RouteStatus {
+                client_id: client_id.to_string(),
+                extension_id: extension_id.clone(),
+                pending: u32::try_from(route.counters.pending.load(Ordering::SeqCst))
+                    .unwrap_or(u32::MAX),
+                completed: route.counters.completed.load(Ordering::SeqCst),
+                rejected: route.counters.rejected.load(Ordering::SeqCst),
+            })
+            .collect();
+        status.sort_by(|a, b| a.extension_id.cmp(&b.extension_id));
+        status
+    }
+}
@@ -2,37 +2,62 @@
 //!
 //! Handles incoming connections from external clients (browser extensions,
 //! CLI tools, servers, etc.) and routes requests to haex-vault extensions.
+//! Peers of the sync relay (`crate::relay`) skip the vault authorization and
+//! can only use `relay.*` actions. `credentials.*` actions of clients
+//! authorized for the core target are answered by [`super::credentials`].
+//! Approved clients get a session token ([`super::session_tokens`]) that
+//! admits their later handshakes without a prompt. Extension requests run on
+//! per-target queues ([`super::routing`]). Every request passes the replay
+//! checks of [`ReplayGuard`] first. Bind address, TLS and the client
+//! allowlist come from [`super::network`].
 
 use crate::AppState;
 use crate::database::core::{execute_with_crdt, select_with_crdt};
-use crate::event_names::EVENT_EXTENSION_AUTO_START_REQUEST;
+use crate::relay;
 use futures_util::{SinkExt, StreamExt};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
-use tokio::net::{TcpListener, TcpStream};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use std::net::SocketAddr;
 use std::time::Duration;
 use tokio::sync::{mpsc, oneshot, Notify, RwLock};
 use tokio_tungstenite::{accept_async, tungstenite::Message};
 
 use super::authorization::{
-    PendingAuthorization, SQL_GET_CLIENT_EXTENSION, SQL_GET_EXTENSION_ID_BY_PUBLIC_KEY_AND_NAME,
-    SQL_IS_BLOCKED, SQL_IS_CLIENT_AUTHORIZED_FOR_EXTENSION, SQL_IS_CLIENT_KNOWN, SQL_UPDATE_LAST_SEEN,
+    PendingAuthorization, SQL_GET_CLIENT_EXTENSION, SQL_GET_EXTENSION_BY_ID,
+    SQL_GET_EXTENSION_ID_BY_PUBLIC_KEY_AND_NAME, SQL_IS_BLOCKED, SQL_IS_CLIENT_AUTHORIZED_FOR_EXTENSION,
+    SQL_IS_CLIENT_KNOWN, SQL_UPDATE_LAST_SEEN,
 };
+use super::credentials;
 use super::crypto::{ServerKeyPair, create_encrypted_response};
 use super::error::BridgeError;
-use super::protocol::{HandshakeResponse, ProtocolMessage};
+use super::network::{tls::TlsIdentity, BridgeNetworkConfig, BridgeNetworkStatus};
+use super::protocol::{
+    check_protocol_version, unix_millis, EncryptedEnvelope, HandshakeResponse, ProtocolMessage,
+    ReplayError, ReplayGuard, PROTOCOL_VERSION,
+};
+use super::routing::{self, RouteQueues, RouteStatus, RouteTarget};
+use super::session_tokens::{self, IssuedSessionToken, SessionGrant, SessionTokenInfo};
 
 /// Default port for the external bridge WebSocket server
 pub const DEFAULT_BRIDGE_PORT: u16 = 19455;
-const PROTOCOL_VERSION: u32 = 1;
 /// Default timeout for extension responses (can be overridden per extension)
 const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
 
 /// Type alias for pending response senders
 type ResponseSender = oneshot::Sender<serde_json::Value>;
 
+/// Session authorizations per route: client_id → extension_id → authorization
+pub type SessionAuthorizationMap = HashMap<String, HashMap<String, SessionAuthorization>>;
+
+/// A client connection, plain or TLS
+trait BridgeStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> BridgeStream for S {}
+
 /// Connected client state
 #[allow(dead_code)]
 struct ConnectedClient {
@@ -41,6 +66,12 @@ struct ConnectedClient {
     public_key: String,
     authorized: bool,
     extension_id: Option<String>,
+    /// Admitted by the sync relay's peer ACL; may only use `relay.*` actions
+    relay_peer: bool,
+    /// Session token the client was admitted with or last received
+    session_grant: Option<SessionGrant>,
+    /// Request queues of this connection, one per target
+    routes: Arc<RouteQueues>,
     tx: mpsc::UnboundedSender<Message>,
 }
 
@@ -76,6 +107,10 @@ pub struct SessionBlockedClient {
 pub struct ExternalBridge {
     running: bool,
     current_port: u16,
+    /// Network settings the server was started with
+    network: BridgeNetworkConfig,
+    /// Fingerprint of the TLS certificate while serving `wss://`
+    tls_fingerprint: Option<String>,
     shutdown_tx: Option<mpsc::Sender<()>>,
     /// Handle to the accept-loop task spawned by `start`. Kept so `stop`
     /// can `.await` (and as a fallback `.abort()`) the task — without this,
@@ -89,14 +124,17 @@ pub struct ExternalBridge {
     /// Pending responses waiting for extension callbacks (requestId → sender)
     pending_responses: Arc<RwLock<HashMap<String, ResponseSender>>>,
     /// Session-based authorizations (for "allow once" - cleared when server stops)
-    /// Key: client_id, Value: SessionAuthorization
-    session_authorizations: Arc<RwLock<HashMap<String, SessionAuthorization>>>,
+    /// One per client and extension
+    session_authorizations: Arc<RwLock<SessionAuthorizationMap>>,
     /// Session-based blocked clients (for "deny once" - cleared when server stops)
     /// Key: client_id, Value: SessionBlockedClient
     session_blocked: Arc<RwLock<HashMap<String, SessionBlockedClient>>>,
     /// Extension ready signals - notifies when an extension has completed initialization
     /// Key: extension_id, Value: Notify that fires when extension is ready
     extension_ready_signals: Arc<RwLock<HashMap<String, Arc<Notify>>>>,
+    /// Nonces and request IDs of the replay window, shared by all connections
+    /// so a frame can't be replayed over a second one
+    replay_guard: Arc<std::sync::Mutex<ReplayGuard>>,
 }
 
 impl Default for ExternalBridge {
@@ -110,6 +148,8 @@ impl ExternalBridge {
         Self {
             running: false,
             current_port: DEFAULT_BRIDGE_PORT,
+            network: BridgeNetworkConfig::default(),
+            tls_fingerprint: None,
             shutdown_tx: None,
             server_task: None,
             clients: Arc::new(RwLock::new(HashMap::new())),
@@ -119,6 +159,7 @@ impl ExternalBridge {
             session_authorizations: Arc::new(RwLock::new(HashMap::new())),
             session_blocked: Arc::new(RwLock::new(HashMap::new())),
             extension_ready_signals: Arc::new(RwLock::new(HashMap::new())),
+            replay_guard: Arc::new(std::sync::Mutex::new(ReplayGuard::new())),
         }
     }
 
@@ -128,7 +169,7 @@ impl ExternalBridge {
     }
 
     /// Get a clone of the session_authorizations map for use in Tauri commands
-    pub fn get_session_authorizations(&self) -> Arc<RwLock<HashMap<String, SessionAuthorization>>> {
+    pub fn get_session_authorizations(&self) -> Arc<RwLock<SessionAuthorizationMap>> {
         self.session_authorizations.clone()
     }
 
@@ -141,8 +182,8 @@ impl ExternalBridge {
         extension_id: &str,
     ) {
         let mut authorizations = self.session_authorizations.write().await;
-        authorizations.insert(
-            client_id.to_string(),
+        authorizations.entry(client_id.to_string()).or_default().insert(
+            extension_id.to_string(),
             SessionAuthorization {
                 client_id: client_id.to_string(),
                 client_name: client_name.to_string(),
@@ -156,10 +197,17 @@ impl ExternalBridge {
         );
     }
 
-    /// Check if a client has a session authorization
-    pub async fn get_session_authorization(&self, client_id: &str) -> Option<SessionAuthorization> {
+    /// Check if a client has a session authorization for an extension
+    pub async fn get_session_authorization(
+        &self,
+        client_id: &str,
+        extension_id: &str,
+    ) -> Option<SessionAuthorization> {
         let authorizations = self.session_authorizations.read().await;
-        authorizations.get(client_id).cloned()
+        authorizations
+            .get(client_id)
+            .and_then(|routes| routes.get(extension_id))
+            .cloned()
     }
 
     /// Get a clone of the session_blocked map for use in connection handlers
@@ -253,12 +301,41 @@ impl ExternalBridge {
         self.running
     }
 
+    /// Number of currently connected clients
+    pub async fn connected_client_count(&self) -> usize {
+        self.clients.read().await.len()
+    }
+
+    /// Request queues of all connected clients
+    pub async fn get_routes(&self) -> Vec<RouteStatus> {
+        let clients = self.clients.read().await;
+        let mut routes: Vec<RouteStatus> = clients
+            .values()
+            .flat_map(|client| client.routes.status(&client.client_id))
+            .collect();
+        routes.sort_by(|a, b| a.client_id.cmp(&b.client_id));
+        routes
+    }
+
     /// Get the current port the server is running on (or will run on)
     pub fn get_port(&self) -> u16 {
         self.current_port
     }
 
-    /// Start the WebSocket server on the specified port
+    /// Address, TLS fingerprint and allowlist of the server
+    pub fn get_network_status(&self) -> BridgeNetworkStatus {
+        BridgeNetworkStatus {
+            running: self.running,
+            bind_address: self.network.bind_address.clone(),
+            port: self.current_port,
+            tls: self.network.tls,
+            tls_fingerprint: self.tls_fingerprint.clone(),
+            allowed_client_ranges: self.network.allowed_client_ranges.clone(),
+        }
+    }
+
+    /// Start the WebSocket server on the specified port, with the network
+    /// settings stored on this device
     pub async fn start(&mut self, app_handle: AppHandle, port: Option<u16>) -> Result<(), BridgeError> {
         if self.running {
             return Err(BridgeError::AlreadyRunning);
@@ -267,6 +344,29 @@ impl ExternalBridge {
         let port = port.unwrap_or(DEFAULT_BRIDGE_PORT);
         self.current_port = port;
 
+        // A broken configuration must not open the bridge wider than intended
+        let network = match BridgeNetworkConfig::load(&app_handle).and_then(|config| {
+            config.validate()?;
+            Ok(config)
+        }) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("[ExternalBridge] Invalid network settings, using loopback: {}", e);
+                BridgeNetworkConfig::default()
+            }
+        };
+        let bind_ip = network.bind_ip()?;
+        let allowlist = network.allowlist()?;
+        let tls_acceptor = if network.tls {
+            let identity = TlsIdentity::load_or_create(&app_handle)?;
+            self.tls_fingerprint = Some(identity.fingerprint()?);
+            Some(identity.acceptor()?)
+        } else {
+            self.tls_fingerprint = None;
+            None
+        };
+        self.network = network;
+
         // Generate server keypair
         {
             let mut keypair = self.server_keypair.write().await;
@@ -276,10 +376,14 @@ impl ExternalBridge {
         let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
         self.shutdown_tx = Some(shutdown_tx);
 
-        let addr = format!("127.0.0.1:{}", port);
-        let listener = TcpListener::bind(&addr).await?;
+        let addr = SocketAddr::new(bind_ip, port);
+        let listener = TcpListener::bind(addr).await?;
 
-        println!("[ExternalBridge] WebSocket server listening on {}", addr);
+        println!(
+            "[ExternalBridge] WebSocket server listening on {} ({})",
+            addr,
+            if tls_acceptor.is_some() { "wss" } else { "ws" }
+        );
 
         let clients = self.clients.clone();
         let pending = self.pending_authorizations.clone();
@@ -287,6 +391,7 @@ impl ExternalBridge {
         let pending_responses = self.pending_responses.clone();
         let session_authorizations = self.session_authorizations.clone();
         let session_blocked = self.session_blocked.clone();
+        let replay_guard = self.replay_guard.clone();
 
         // Spawn the server task. The JoinHandle is stored on `self` so
         // `stop` can await it; without that the listener-bound port may
@@ -298,6 +403,12 @@ impl ExternalBridge {
                     result = listener.accept() => {
                         match result {
                             Ok((stream, addr)) => {
+                                // Checked before the TLS handshake, so peers
+                                // outside the allowlist cost nothing
+                                if !allowlist.allows(addr.ip()) {
+                                    println!("[ExternalBridge] Rejected connection from {} (not in client allowlist)", addr);
+                                    continue;
+                                }
                                 println!("[ExternalBridge] New connection from {}", addr);
                                 let app = app_handle.clone();
                                 let clients = clients.clone();
@@ -306,9 +417,21 @@ impl ExternalBridge {
                                 let pending_resp = pending_responses.clone();
                                 let session_auths = session_authorizations.clone();
                                 let session_blk = session_blocked.clone();
+                                let replay = replay_guard.clone();
+                                let tls = tls_acceptor.clone();
 
                                 tokio::spawn(async move {
-                                    if let Err(e) = handle_connection(stream, app, clients, pending, keypair, pending_resp, session_auths, session_blk).await {
+                                    let stream: Box<dyn BridgeStream> = match tls {
+                                        Some(acceptor) => match acceptor.accept(stream).await {
+                                            Ok(tls_stream) => Box::new(tls_stream),
+                                            Err(e) => {
+                                                eprintln!("[ExternalBridge] TLS handshake with {} failed: {}", addr, e);
+                                                return;
+                                            }
+                                        },
+                                        None => Box::new(stream),
+                                    };
+                                    if let Err(e) = handle_connection(stream, app, clients, pending, keypair, pending_resp, session_auths, session_blk, replay).await {
                                         eprintln!("[ExternalBridge] Connection error: {}", e);
                                     }
                                 });
@@ -393,11 +516,13 @@ impl ExternalBridge {
         Ok(())
     }
 
-    /// Notify a client that authorization was granted
+    /// Notify a client that authorization was granted, and hand it the
+    /// session token issued for the approval
     pub async fn notify_authorization_granted(
         &self,
         client_id: &str,
         extension_id: &str,
+        session_token: Option<&IssuedSessionToken>,
     ) -> Result<(), BridgeError> {
         println!(
             "[ExternalBridge] notify_authorization_granted called for client_id={}, extension_id={}",
@@ -421,6 +546,20 @@ impl ExternalBridge {
                 "[ExternalBridge] Sent authorization update to client {}: {:?}",
                 client_id, send_result
             );
+
+            if let Some(issued) = session_token {
+                client.session_grant = Some(SessionGrant::from(issued));
+                let payload = serde_json::json!({ "success": true, "data": issued });
+                match create_encrypted_response(session_tokens::ACTION_SESSION_TOKEN, &payload, &client.public_key) {
+                    Ok(envelope) => {
+                        let json = serde_json::to_string(&ProtocolMessage::Response(envelope))?;
+                        let _ = client.tx.send(Message::Text(json.into()));
+                    }
+                    Err(e) => {
+                        eprintln!("[ExternalBridge] Failed to encrypt session token: {}", e);
+                    }
+                }
+            }
         } else {
             println!(
                 "[ExternalBridge] WARNING: Client {} not found in connected clients!",
@@ -444,14 +583,15 @@ impl ExternalBridge {
 
 /// Handle a single WebSocket connection
 async fn handle_connection(
-    stream: TcpStream,
+    stream: Box<dyn BridgeStream>,
     app_handle: AppHandle,
     clients: Arc<RwLock<HashMap<String, ConnectedClient>>>,
     pending: Arc<RwLock<HashMap<String, PendingAuthorization>>>,
     server_keypair: Arc<RwLock<Option<ServerKeyPair>>>,
     pending_responses: Arc<RwLock<HashMap<String, ResponseSender>>>,
-    session_authorizations: Arc<RwLock<HashMap<String, SessionAuthorization>>>,
+    session_authorizations: Arc<RwLock<SessionAuthorizationMap>>,
     session_blocked: Arc<RwLock<HashMap<String, SessionBlockedClient>>>,
+    replay_guard: Arc<std::sync::Mutex<ReplayGuard>>,
 ) -> Result<(), BridgeError> {
     let ws_stream = accept_async(stream).await?;
     let (mut write, mut read) = ws_stream.split();
@@ -470,6 +610,7 @@ async fn handle_connection(
 
     let mut client_id: Option<String> = None;
     let mut client_public_key_spki: Option<String> = None;
+    let routes = Arc::new(RouteQueues::new());
 
     // Get server public key for handshake responses
     let server_public_key_base64 = {
@@ -516,6 +657,22 @@ async fn handle_connection(
 
                 match protocol_msg {
                     ProtocolMessage::Handshake(handshake) => {
+                        // Clients predating mandatory signing would pass the
+                        // handshake and then fail every request
+                        if let Err(e) = check_protocol_version(handshake.version) {
+                            eprintln!(
+                                "[ExternalBridge] Client {} rejected: {}",
+                                handshake.client.client_id, e
+                            );
+                            let error_msg = ProtocolMessage::Error {
+                                code: e.code().to_string(),
+                                message: e.to_string(),
+                            };
+                            let json = serde_json::to_string(&error_msg)?;
+                            tx.send(Message::Text(json.into()))?;
+                            break;
+                        }
+
                         let cid = handshake.client.client_id.clone();
                         client_id = Some(cid.clone());
 
@@ -543,20 +700,68 @@ async fn handle_connection(
                             break;
                         }
 
+                        // Relay peers are admitted by the relay's own ACL, which
+                        // works without an open vault
+                        let relay_peer = app_handle.state::<AppState>().relay.active_peer(&cid);
+                        if let Some(peer) = relay_peer {
+                            println!(
+                                "[ExternalBridge] Client {} connected as relay peer '{}'",
+                                cid, peer.label
+                            );
+                            let mut clients_guard = clients.write().await;
+                            clients_guard.insert(
+                                cid.clone(),
+                                ConnectedClient {
+                                    client_id: cid.clone(),
+                                    client_name: handshake.client.client_name.clone(),
+                                    public_key: handshake.client.public_key.clone(),
+                                    authorized: true,
+                                    extension_id: None,
+                                    relay_peer: true,
+                                    session_grant: None,
+                                    routes: routes.clone(),
+                                    tx: tx.clone(),
+                                },
+                            );
+                            drop(clients_guard);
+
+                            client_public_key_spki = Some(handshake.client.public_key.clone());
+
+                            let response = ProtocolMessage::HandshakeResponse(HandshakeResponse {
+                                version: PROTOCOL_VERSION,
+                                server_public_key: server_public_key_base64.clone(),
+                                authorized: true,
+                                pending_approval: false,
+                            });
+                            let json = serde_json::to_string(&response)?;
+                            tx.send(Message::Text(json.into()))?;
+                            continue;
+                        }
+
                         // Check if client is already authorized in database
                         let db_authorized = check_client_authorized(&app_handle, &cid).await;
 
                         // Check if client has session-based authorization (from "allow once")
                         let session_auth = {
                             let auths = session_authorizations.read().await;
-                            auths.get(&cid).cloned()
+                            auths.get(&cid).and_then(|routes| routes.values().next().cloned())
+                        };
+
+                        // Otherwise a session token from an earlier approval may admit it
+                        let token_info = match &handshake.session_token {
+                            Some(token) if !db_authorized && session_auth.is_none() => {
+                                verify_session_token(&app_handle, token, &cid, &handshake.client.public_key)
+                            }
+                            _ => None,
                         };
 
-                        let is_authorized = db_authorized || session_auth.is_some();
+                        let is_authorized = db_authorized || session_auth.is_some() || token_info.is_some();
                         let ext_id = if db_authorized {
                             get_client_extension(&app_handle, &cid).await
+                        } else if let Some(sa) = &session_auth {
+                            Some(sa.extension_id.clone())
                         } else {
-                            session_auth.as_ref().map(|sa| sa.extension_id.clone())
+                            token_info.as_ref().and_then(|t| t.scopes.first().cloned())
                         };
 
                         if is_authorized {
@@ -566,6 +771,11 @@ async fn handle_connection(
                                     "[ExternalBridge] Client {} authorized via session (allow once)",
                                     cid
                                 );
+                            } else if token_info.is_some() {
+                                println!(
+                                    "[ExternalBridge] Client {} authorized via session token",
+                                    cid
+                                );
                             }
 
                             // Add to connected clients
@@ -578,6 +788,9 @@ async fn handle_connection(
                                     public_key: handshake.client.public_key.clone(),
                                     authorized: true,
                                     extension_id: ext_id.clone(),
+                                    relay_peer: false,
+                                    session_grant: token_info.as_ref().map(SessionGrant::from),
+                                    routes: routes.clone(),
                                     tx: tx.clone(),
                                 },
                             );
@@ -611,6 +824,9 @@ async fn handle_connection(
                                     public_key: handshake.client.public_key.clone(),
                                     authorized: false,
                                     extension_id: None,
+                                    relay_peer: false,
+                                    session_grant: None,
+                                    routes: routes.clone(),
                                     tx: tx.clone(),
                                 },
                             );
@@ -700,10 +916,31 @@ async fn handle_connection(
                             continue;
                         }
 
-                        // Decrypt the envelope using server's keypair
+                        // Decrypt the envelope using server's keypair, after the
+                        // replay checks of its signature, nonce and timestamp
                         let keypair_guard = server_keypair.read().await;
                         let decrypted = match keypair_guard.as_ref() {
-                            Some(kp) => envelope.decrypt(kp),
+                            Some(kp) => {
+                                let cid = client_id.as_deref().unwrap_or("");
+                                let checked = check_request_signature(
+                                    &envelope,
+                                    kp,
+                                    client_public_key_spki.as_deref(),
+                                    cid,
+                                    &replay_guard,
+                                );
+                                if let Err(e) = checked {
+                                    eprintln!("[ExternalBridge] Request of client {} rejected: {}", cid, e);
+                                    let error_msg = ProtocolMessage::Error {
+                                        code: e.code().to_string(),
+                                        message: e.to_string(),
+                                    };
+                                    let json = serde_json::to_string(&error_msg)?;
+                                    tx.send(Message::Text(json.into()))?;
+                                    continue;
+                                }
+                                envelope.decrypt(kp)
+                            }
                             None => {
                                 let error_msg = ProtocolMessage::Error {
                                     code: "SERVER_ERROR".to_string(),
@@ -716,29 +953,56 @@ async fn handle_connection(
                         };
                         drop(keypair_guard);
 
+                        // A request ID is good for one request within the window
+                        if let (Ok(payload), Some(cid)) = (&decrypted, &client_id) {
+                            if let Some(request_id) = payload.get("requestId").and_then(|v| v.as_str()) {
+                                let checked = replay_guard
+                                    .lock()
+                                    .map(|mut guard| guard.check_request_id(cid, request_id, unix_millis()));
+                                if let Ok(Err(e)) = checked {
+                                    eprintln!("[ExternalBridge] Request of client {} rejected: {}", cid, e);
+                                    let error_msg = ProtocolMessage::Error {
+                                        code: e.code().to_string(),
+                                        message: e.to_string(),
+                                    };
+                                    let json = serde_json::to_string(&error_msg)?;
+                                    tx.send(Message::Text(json.into()))?;
+                                    continue;
+                                }
+                            }
+                        }
+
+                        let is_relay_peer = match &client_id {
+                            Some(cid) => {
+                                clients.read().await.get(cid).is_some_and(|c| c.relay_peer)
+                            }
+                            None => false,
+                        };
+                        let session_grant = match &client_id {
+                            Some(cid) => clients
+                                .read()
+                                .await
+                                .get(cid)
+                                .and_then(|c| c.session_grant.clone()),
+                            None => None,
+                        };
+
                         match decrypted {
-                            Ok(payload) => {
-                                // Process the decrypted request
-                                // Use client's public key as identifier (consistent with rest of haex-vault)
-                                let public_key = client_public_key_spki.as_deref().unwrap_or("");
+                            Ok(payload)
+                                if is_relay_peer
+                                    || envelope.action.starts_with(relay::RELAY_ACTION_PREFIX) =>
+                            {
                                 let cid = client_id.as_deref().unwrap_or("");
-                                let response_payload = process_request(
+                                let outcome = app_handle.state::<AppState>().relay.handle_request(
+                                    cid,
                                     &envelope.action,
                                     &payload,
-                                    public_key,
-                                    envelope.extension_public_key.as_deref(),
-                                    envelope.extension_name.as_deref(),
-                                    cid,
-                                    &app_handle,
-                                    pending_responses.clone(),
-                                    session_authorizations.clone(),
-                                ).await;
+                                );
 
-                                // Send encrypted response back
                                 if let Some(client_pk) = &client_public_key_spki {
                                     match create_encrypted_response(
                                         &envelope.action,
-                                        &response_payload,
+                                        &outcome.response,
                                         client_pk,
                                     ) {
                                         Ok(response_envelope) => {
@@ -747,17 +1011,158 @@ async fn handle_connection(
                                             tx.send(Message::Text(json.into()))?;
                                         }
                                         Err(e) => {
-                                            eprintln!("[ExternalBridge] Failed to encrypt response: {}", e);
-                                            let error_msg = ProtocolMessage::Error {
-                                                code: "ENCRYPTION_ERROR".to_string(),
-                                                message: "Failed to encrypt response".to_string(),
-                                            };
-                                            let json = serde_json::to_string(&error_msg)?;
+                                            eprintln!("[ExternalBridge] Failed to encrypt relay response: {}", e);
+                                        }
+                                    }
+                                }
+
+                                if let Some((channel, seq)) = outcome.pushed {
+                                    notify_relay_peers(&app_handle, &clients, &channel, cid, seq)
+                                        .await;
+                                }
+                            }
+                            Ok(payload) if envelope.action == session_tokens::ACTION_SESSION_REFRESH => {
+                                let cid = client_id.as_deref().unwrap_or("");
+                                let public_key = client_public_key_spki.as_deref().unwrap_or("");
+                                let response_payload =
+                                    refresh_session_token(&app_handle, &clients, cid, public_key, &payload).await;
+
+                                if let Some(client_pk) = &client_public_key_spki {
+                                    match create_encrypted_response(
+                                        &envelope.action,
+                                        &response_payload,
+                                        client_pk,
+                                    ) {
+                                        Ok(response_envelope) => {
+                                            let response = ProtocolMessage::Response(response_envelope);
+                                            let json = serde_json::to_string(&response)?;
                                             tx.send(Message::Text(json.into()))?;
                                         }
+                                        Err(e) => {
+                                            eprintln!("[ExternalBridge] Failed to encrypt session token: {}", e);
+                                        }
                                     }
                                 }
                             }
+                            Ok(payload)
+                                if envelope.action.starts_with(credentials::CREDENTIALS_ACTION_PREFIX) =>
+                            {
+                                // Credential requests may wait minutes for the user, so
+                                // they run next to the read loop instead of blocking it
+                                let cid = client_id.clone().unwrap_or_default();
+                                let client_name = clients
+                                    .read()
+                                    .await
+                                    .get(&cid)
+                                    .map(|c| c.client_name.clone())
+                                    .unwrap_or_default();
+                                let authorized = check_client_authorized_for_core(&app_handle, &cid).await
+                                    || session_authorizations
+                                        .read()
+                                        .await
+                                        .get(&cid)
+                                        .is_some_and(|routes| routes.contains_key(super::CORE_EXTENSION_ID))
+                                    || session_grant.as_ref().is_some_and(|grant| {
+                                        grant.allows(super::CORE_EXTENSION_ID)
+                                            && session_grant_active(&app_handle, grant, super::CORE_EXTENSION_ID)
+                                    });
+                                let app = app_handle.clone();
+                                let client_pk = client_public_key_spki.clone();
+                                let tx = tx.clone();
+                                let action = envelope.action.clone();
+
+                                tokio::spawn(async move {
+                                    let response_payload = if authorized {
+                                        let state = app.state::<AppState>();
+                                        let prompts = &state.bridge_credential_prompts;
+                                        credentials::handle_request(&app, prompts, &cid, &client_name, &action, &payload)
+                                            .await
+                                    } else {
+                                        serde_json::json!({
+                                            "requestId": payload.get("requestId"),
+                                            "success": false,
+                                            "error": "Client not authorized for core access"
+                                        })
+                                    };
+
+                                    let Some(client_pk) = client_pk else { return };
+                                    match create_encrypted_response(&action, &response_payload, &client_pk) {
+                                        Ok(response_envelope) => {
+                                            let response = ProtocolMessage::Response(response_envelope);
+                                            if let Ok(json) = serde_json::to_string(&response) {
+                                                let _ = tx.send(Message::Text(json.into()));
+                                            }
+                                        }
+                                        Err(e) => {
+                                            eprintln!("[ExternalBridge] Failed to encrypt credential response: {}", e);
+                                        }
+                                    }
+                                });
+                            }
+                            Ok(payload) => {
+                                // Process the decrypted request on the queue of its target,
+                                // so one slow extension doesn't hold up the others
+                                let cid = client_id.clone().unwrap_or_default();
+                                let Some(client_pk) = client_public_key_spki.clone() else {
+                                    continue;
+                                };
+                                let action = envelope.action.clone();
+
+                                let target = match resolve_route_target(&app_handle, &envelope).await {
+                                    Ok(target) => target,
+                                    Err(error) => {
+                                        let response_payload = serde_json::json!({
+                                            "requestId": payload.get("requestId"),
+                                            "success": false,
+                                            "error": error
+                                        });
+                                        send_encrypted_response(&tx, &action, &response_payload, &client_pk)?;
+                                        continue;
+                                    }
+                                };
+
+                                let request_id = payload.get("requestId").cloned();
+                                let extension_id = target.extension_id.clone();
+                                let job_tx = tx.clone();
+                                let job_action = action.clone();
+                                let job_client_pk = client_pk.clone();
+                                let app = app_handle.clone();
+                                let pending_resp = pending_responses.clone();
+                                let session_auths = session_authorizations.clone();
+                                let job = Box::pin(async move {
+                                    // Use client's public key as identifier (consistent with rest of haex-vault)
+                                    let response_payload = process_request(
+                                        &job_action,
+                                        &payload,
+                                        &job_client_pk,
+                                        &target,
+                                        &cid,
+                                        &app,
+                                        pending_resp,
+                                        session_auths,
+                                        session_grant.as_ref(),
+                                    ).await;
+
+                                    // Send encrypted response back
+                                    if let Err(e) = send_encrypted_response(&job_tx, &job_action, &response_payload, &job_client_pk) {
+                                        eprintln!("[ExternalBridge] Failed to send response: {}", e);
+                                    }
+                                });
+
+                                if !routes.enqueue(&extension_id, job) {
+                                    eprintln!(
+                                        "[ExternalBridge] Route to {} is full, rejecting request",
+                                        extension_id
+                                    );
+                                    let response_payload = serde_json::json!({
+                                        "requestId": request_id,
+                                        "success": false,
+                                        "error": "Too many pending requests for this extension",
+                                        "errorCode": routing::ROUTE_BUSY
+                                    });
+                                    send_encrypted_response(&tx, &action, &response_payload, &client_pk)?;
+                                }
+                            }
                             Err(e) => {
                                 eprintln!("[ExternalBridge] Failed to decrypt request: {}", e);
                                 let error_msg = ProtocolMessage::Error {
@@ -818,6 +1223,44 @@ async fn handle_connection(
 }
 
 /// Check if a client is authorized (via CRDT database query)
+/// Tells the connected peers of `channel` (except the sender) that a new
+/// relay message is waiting, so they pull right away.
+async fn notify_relay_peers(
+    app_handle: &AppHandle,
+    clients: &Arc<RwLock<HashMap<String, ConnectedClient>>>,
+    channel: &str,
+    sender: &str,
+    seq: i64,
+) {
+    let peers = app_handle
+        .state::<AppState>()
+        .relay
+        .channel_peers(channel, sender);
+    let payload = serde_json::json!({ "channel": channel, "seq": seq });
+    let clients_guard = clients.read().await;
+    for peer in peers {
+        let Some(client) = clients_guard.get(&peer).filter(|c| c.relay_peer) else {
+            continue;
+        };
+        let json =
+            create_encrypted_response(relay::RELAY_NOTIFY_ACTION, &payload, &client.public_key)
+                .map_err(|e| e.to_string())
+                .and_then(|envelope| {
+                    serde_json::to_string(&ProtocolMessage::Response(envelope))
+                        .map_err(|e| e.to_string())
+                });
+        match json {
+            Ok(json) => {
+                let _ = client.tx.send(Message::Text(json.into()));
+            }
+            Err(e) => eprintln!(
+                "[ExternalBridge] Failed to notify relay peer {}: {}",
+                peer, e
+            ),
+        }
+    }
+}
+
 async fn check_client_authorized(app_handle: &AppHandle, client_id: &str) -> bool {
     let state = app_handle.state::<AppState>();
     let params = vec![JsonValue::String(client_id.to_string())];
@@ -844,7 +1287,7 @@ async fn check_client_authorized(app_handle: &AppHandle, client_id: &str) -> boo
 /// the blocked check has the opposite polarity: returning `false` on error
 /// here would let a known-blocked client through during any transient DB
 /// outage.
-async fn check_client_blocked(app_handle: &AppHandle, client_id: &str) -> bool {
+pub(crate) async fn check_client_blocked(app_handle: &AppHandle, client_id: &str) -> bool {
     let state = app_handle.state::<AppState>();
     let params = vec![JsonValue::String(client_id.to_string())];
 
@@ -869,7 +1312,7 @@ async fn check_client_blocked(app_handle: &AppHandle, client_id: &str) -> bool {
 }
 
 /// Get the extension_id for an authorized client
-async fn get_client_extension(app_handle: &AppHandle, client_id: &str) -> Option<String> {
+pub(crate) async fn get_client_extension(app_handle: &AppHandle, client_id: &str) -> Option<String> {
     let state = app_handle.state::<AppState>();
     let params = vec![JsonValue::String(client_id.to_string())];
 
@@ -947,6 +1390,205 @@ async fn check_client_authorized_for_extension(
     }
 }
 
+/// Verify the session token of a handshake. An invalid token only costs the
+/// client the shortcut: it is asked for approval as without one.
+fn verify_session_token(
+    app_handle: &AppHandle,
+    token: &str,
+    client_id: &str,
+    public_key: &str,
+) -> Option<SessionTokenInfo> {
+    let state = app_handle.state::<AppState>();
+    let now = session_tokens::now();
+    match session_tokens::with_vault(&state.db, |conn| {
+        session_tokens::verify(conn, token, client_id, public_key, now)
+    }) {
+        Ok(info) => Some(info),
+        Err(e) => {
+            println!(
+                "[ExternalBridge] Session token of client {} not accepted: {}",
+                client_id, e
+            );
+            None
+        }
+    }
+}
+
+/// Check that a session token still admits the client to `extension_id`
+fn session_grant_active(app_handle: &AppHandle, grant: &SessionGrant, extension_id: &str) -> bool {
+    let state = app_handle.state::<AppState>();
+    let now = session_tokens::now();
+    session_tokens::with_vault(&state.db, |conn| {
+        session_tokens::is_active(conn, grant, extension_id, now)
+    })
+    .unwrap_or(false)
+}
+
+/// Handle `session.refresh`: swap the presented token for a new one and
+/// admit the connection with it
+async fn refresh_session_token(
+    app_handle: &AppHandle,
+    clients: &Arc<RwLock<HashMap<String, ConnectedClient>>>,
+    client_id: &str,
+    public_key: &str,
+    payload: &serde_json::Value,
+) -> serde_json::Value {
+    let request_id = payload.get("requestId").cloned().unwrap_or(JsonValue::Null);
+    let Some(token) = payload.get("token").and_then(|v| v.as_str()) else {
+        return serde_json::json!({
+            "requestId": request_id,
+            "success": false,
+            "error": "Missing required field: token",
+            "errorCode": "INVALID_REQUEST"
+        });
+    };
+
+    let state = app_handle.state::<AppState>();
+    let now = session_tokens::now();
+    match session_tokens::with_vault(&state.db, |conn| {
+        session_tokens::refresh(conn, token, client_id, public_key, now)
+    }) {
+        Ok(issued) => {
+            if let Some(client) = clients.write().await.get_mut(client_id) {
+                client.session_grant = Some(SessionGrant::from(&issued));
+            }
+            serde_json::json!({
+                "requestId": request_id,
+                "success": true,
+                "data": issued
+            })
+        }
+        Err(e) => serde_json::json!({
+            "requestId": request_id,
+            "success": false,
+            "error": e.to_string(),
+            "errorCode": e.code()
+        }),
+    }
+}
+
+/// Replay checks before a request is decrypted: the request needs a valid
+/// signature by the client's key and a fresh nonce and timestamp.
+pub(super) fn check_request_signature(
+    envelope: &EncryptedEnvelope,
+    server_keypair: &ServerKeyPair,
+    client_public_key: Option<&str>,
+    client_id: &str,
+    replay_guard: &std::sync::Mutex<ReplayGuard>,
+) -> Result<(), ReplayError> {
+    let Ok(mut guard) = replay_guard.lock() else {
+        return Err(ReplayError::InvalidSignature);
+    };
+
+    if !envelope.is_signed() {
+        return Err(ReplayError::SignatureRequired);
+    }
+
+    let (Some(nonce), Some(timestamp), Some(client_public_key)) =
+        (envelope.nonce.as_deref(), envelope.timestamp, client_public_key)
+    else {
+        return Err(ReplayError::MissingFields);
+    };
+    if envelope.signature.is_none() {
+        return Err(ReplayError::MissingFields);
+    }
+    if !envelope
+        .verify_signature(server_keypair, client_public_key)
+        .unwrap_or(false)
+    {
+        return Err(ReplayError::InvalidSignature);
+    }
+
+    guard.check_nonce(client_id, nonce, timestamp, unix_millis())
+}
+
+/// Encrypt `payload` for the client and queue it on its connection
+fn send_encrypted_response(
+    tx: &mpsc::UnboundedSender<Message>,
+    action: &str,
+    payload: &serde_json::Value,
+    client_public_key: &str,
+) -> Result<(), BridgeError> {
+    let message = match create_encrypted_response(action, payload, client_public_key) {
+        Ok(envelope) => ProtocolMessage::Response(envelope),
+        Err(e) => {
+            eprintln!("[ExternalBridge] Failed to encrypt response: {}", e);
+            ProtocolMessage::Error {
+                code: "ENCRYPTION_ERROR".to_string(),
+                message: "Failed to encrypt response".to_string(),
+            }
+        }
+    };
+    let json = serde_json::to_string(&message)?;
+    tx.send(Message::Text(json.into()))?;
+    Ok(())
+}
+
+/// Resolve the target of a request: by `extensionId` if given, otherwise by
+/// `extensionPublicKey` + `extensionName`. Both name the core by its sentinel.
+async fn resolve_route_target(
+    app_handle: &AppHandle,
+    envelope: &EncryptedEnvelope,
+) -> Result<RouteTarget, String> {
+    if let Some(extension_id) = envelope.extension_id.as_deref().filter(|id| !id.is_empty()) {
+        if extension_id == super::CORE_EXTENSION_ID {
+            return Ok(RouteTarget::core());
+        }
+        return get_extension_by_id(app_handle, extension_id)
+            .await
+            .ok_or_else(|| "Extension not found".to_string());
+    }
+
+    let (public_key, name) = match (
+        envelope.extension_public_key.as_deref(),
+        envelope.extension_name.as_deref(),
+    ) {
+        (Some(pk), Some(name)) if !pk.is_empty() && !name.is_empty() => (pk, name),
+        _ => {
+            return Err(
+                "Missing required fields: extensionId or extensionPublicKey and extensionName"
+                    .to_string(),
+            )
+        }
+    };
+
+    // Core-target detection: requests addressed to the haex-vault core itself
+    // (not a specific extension) carry the CORE sentinel as extensionPublicKey/name.
+    if public_key == super::CORE_EXTENSION_ID && name == super::CORE_EXTENSION_NAME {
+        return Ok(RouteTarget::core());
+    }
+
+    match get_extension_id_by_public_key_and_name(app_handle, public_key, name).await {
+        Some(extension_id) => Ok(RouteTarget {
+            extension_id,
+            public_key: public_key.to_string(),
+            name: name.to_string(),
+        }),
+        None => Err("Extension not found".to_string()),
+    }
+}
+
+/// Get an extension's public_key and name by ID
+pub(crate) async fn get_extension_by_id(app_handle: &AppHandle, extension_id: &str) -> Option<RouteTarget> {
+    let state = app_handle.state::<AppState>();
+    let params = vec![JsonValue::String(extension_id.to_string())];
+
+    match select_with_crdt(SQL_GET_EXTENSION_BY_ID.to_string(), params, &state.db) {
+        Ok(rows) => {
+            let row = rows.first()?;
+            Some(RouteTarget {
+                extension_id: extension_id.to_string(),
+                public_key: row.first()?.as_str()?.to_string(),
+                name: row.get(1)?.as_str()?.to_string(),
+            })
+        }
+        Err(e) => {
+            eprintln!("[ExternalBridge] Failed to get extension by ID: {}", e);
+            None
+        }
+    }
+}
+
 /// Check if a client is authorized for the core target.
 /// Uses the simpler (client_id, extension_id) lookup since core has no
 /// public_key/name pair to JOIN against.
@@ -1070,13 +1712,13 @@ async fn ensure_extension_loaded(
 
     // Emit event to frontend to start the extension
     // The frontend will handle this based on the extension's display_mode
-    let payload = serde_json::json!({
-        "extensionId": extension_id,
-    });
+    let payload = crate::events::payloads::ExtensionAutoStartRequest {
+        extension_id: extension_id.to_string(),
+    };
 
     // Nur Main-Window — die Extension läuft noch nicht. Das Frontend startet
     // sie basierend auf dem display_mode (WebviewWindow oder Iframe).
-    if let Err(e) = app_handle.emit_to("main", EVENT_EXTENSION_AUTO_START_REQUEST, &payload) {
+    if let Err(e) = crate::events::emit_to_main(app_handle, &payload) {
         return Err(format!("Failed to emit auto-start request: {}", e));
     }
 
@@ -1143,21 +1785,22 @@ async fn ensure_extension_loaded(
 /// * `action` - The action/method name to perform
 /// * `payload` - The decrypted request payload (must contain requestId)
 /// * `client_public_key` - Client's public key (Base64 SPKI format, used as identifier)
-/// * `extension_public_key` - Target extension's public key (from manifest)
-/// * `extension_name` - Target extension's name (from manifest)
+/// * `target` - Resolved target extension (or core)
 /// * `client_id` - Client's unique identifier
 /// * `app_handle` - Tauri app handle for emitting events
 /// * `pending_responses` - Map to store response channel for correlation
+/// * `session_authorizations` - "Allow once" authorizations
+/// * `session_grant` - Session token the connection was admitted with
 async fn process_request(
     action: &str,
     payload: &serde_json::Value,
     client_public_key: &str,
-    extension_public_key: Option<&str>,
-    extension_name: Option<&str>,
+    target: &RouteTarget,
     client_id: &str,
     app_handle: &AppHandle,
     pending_responses: Arc<RwLock<HashMap<String, ResponseSender>>>,
-    session_authorizations: Arc<RwLock<HashMap<String, SessionAuthorization>>>,
+    session_authorizations: Arc<RwLock<SessionAuthorizationMap>>,
+    session_grant: Option<&SessionGrant>,
 ) -> serde_json::Value {
     // Extract requestId - required for response correlation
     let request_id = match payload.get("requestId").and_then(|v| v.as_str()) {
@@ -1170,52 +1813,27 @@ async fn process_request(
         }
     };
 
-    // Validate that extension target is specified
-    let (ext_public_key, ext_name) = match (extension_public_key, extension_name) {
-        (Some(pk), Some(name)) if !pk.is_empty() && !name.is_empty() => (pk, name),
-        _ => {
-            return serde_json::json!({
-                "requestId": request_id,
-                "success": false,
-                "error": "Missing required fields: extensionPublicKey and extensionName"
-            });
-        }
-    };
-
-    // Core-target detection: requests addressed to the haex-vault core itself
-    // (not a specific extension) carry the CORE sentinel as extensionPublicKey/name.
-    let is_core = ext_public_key == super::CORE_EXTENSION_ID
-        && ext_name == super::CORE_EXTENSION_NAME;
-
-    // Lookup the extension's internal ID first (needed for session auth check)
-    let extension_id = if is_core {
-        super::CORE_EXTENSION_ID.to_string()
-    } else {
-        match get_extension_id_by_public_key_and_name(app_handle, ext_public_key, ext_name).await {
-            Some(id) => id,
-            None => {
-                return serde_json::json!({
-                    "requestId": request_id,
-                    "success": false,
-                    "error": "Extension not found"
-                });
-            }
-        }
-    };
+    let is_core = target.is_core();
+    let extension_id = target.extension_id.as_str();
 
     // Verify client is authorized for this extension (or core)
     // Check both database authorization AND session authorization ("allow once")
     let db_authorized = if is_core {
         check_client_authorized_for_core(app_handle, client_id).await
     } else {
-        check_client_authorized_for_extension(app_handle, client_id, ext_public_key, ext_name).await
+        check_client_authorized_for_extension(app_handle, client_id, &target.public_key, &target.name).await
     };
     let session_authorized = {
         let auths = session_authorizations.read().await;
-        auths.get(client_id).map(|sa| sa.extension_id == extension_id).unwrap_or(false)
+        auths
+            .get(client_id)
+            .is_some_and(|routes| routes.contains_key(extension_id))
     };
+    let token_authorized = session_grant.is_some_and(|grant| {
+        grant.allows(extension_id) && session_grant_active(app_handle, grant, extension_id)
+    });
 
-    if !db_authorized && !session_authorized {
+    if !db_authorized && !session_authorized && !token_authorized {
         return serde_json::json!({
             "requestId": request_id,
             "success": false,
@@ -1227,10 +1845,38 @@ async fn process_request(
         });
     }
 
+    // Build the external request payload to send to the extension
+    let external_request = serde_json::json!({
+        "requestId": request_id,
+        "publicKey": client_public_key,
+        "action": action,
+        "payload": payload,
+        "extensionId": extension_id,
+        "extensionPublicKey": target.public_key,
+        "extensionName": target.name
+    });
+
+    dispatch_to_target(app_handle, target, &request_id, external_request, pending_responses).await
+}
+
+/// Hand `external_request` to the target and wait for its answer, which
+/// arrives through `external_bridge_respond`. Also used by deep-link actions
+/// (`crate::deep_link`), which have no bridge connection of their own.
+pub(crate) async fn dispatch_to_target(
+    app_handle: &AppHandle,
+    target: &RouteTarget,
+    request_id: &str,
+    external_request: serde_json::Value,
+    pending_responses: Arc<RwLock<HashMap<String, ResponseSender>>>,
+) -> serde_json::Value {
+    let is_core = target.is_core();
+    let extension_id = target.extension_id.as_str();
+    let request_id = request_id.to_string();
+
     // Ensure the extension is loaded (auto-start if needed).
     // Core requests are handled by the main window — no extension to load.
     if !is_core {
-        if let Err(e) = ensure_extension_loaded(app_handle, &extension_id).await {
+        if let Err(e) = ensure_extension_loaded(app_handle, extension_id).await {
             eprintln!("[ExternalBridge] Failed to ensure extension is loaded: {}", e);
             return serde_json::json!({
                 "requestId": request_id,
@@ -1249,16 +1895,6 @@ async fn process_request(
         pending.insert(request_id.clone(), tx);
     }
 
-    // Build the external request payload to send to the extension
-    let external_request = serde_json::json!({
-        "requestId": request_id,
-        "publicKey": client_public_key,
-        "action": action,
-        "payload": payload,
-        "extensionPublicKey": ext_public_key,
-        "extensionName": ext_name
-    });
-
     // Emit the request to the extension via Tauri event.
     // - Core target: emit "haextension:external:core-request" to main window.
     // - WebView extension: emit_to_all_extension_windows() targets ONLY that
@@ -1284,7 +1920,7 @@ async fn process_request(
             // Try to emit to all webviews of this extension first
             match manager.emit_to_all_extension_windows(
                 app_handle,
-                &extension_id,
+                extension_id,
                 "haextension:external:request",
                 external_request.clone(),
             ) {
@@ -0,0 +1,67 @@
+//! Error types of bridge session tokens
+
+use crate::database::error::DatabaseError;
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize)]
+#[serde(tag = "type", content = "details")]
+pub enum SessionTokenError {
+    #[error("Malformed session token")]
+    Malformed,
+
+    #[error("Invalid session token signature")]
+    InvalidSignature,
+
+    /// The token was issued to another client or key
+    #[error("Session token belongs to another client")]
+    ClientMismatch,
+
+    #[error("Session token expired")]
+    Expired,
+
+    /// The token's metadata is gone, e.g. after a revocation
+    #[error("Session token was revoked")]
+    Revoked,
+
+    /// The approval is older than the maximum session age
+    #[error("Session expired, the client has to be approved again")]
+    SessionExpired,
+
+    #[error("The vault is locked")]
+    VaultLocked,
+
+    #[error("Database error: {reason}")]
+    Database { reason: String },
+
+    #[error("Internal error: {reason}")]
+    Internal { reason: String },
+}
+
+impl SessionTokenError {
+    /// Stable code sent to the client next to the message
+    pub fn code(&self) -> &'static str {
+        match self {
+            SessionTokenError::Malformed => "TOKEN_MALFORMED",
+            SessionTokenError::InvalidSignature => "TOKEN_INVALID_SIGNATURE",
+            SessionTokenError::ClientMismatch => "TOKEN_CLIENT_MISMATCH",
+            SessionTokenError::Expired => "TOKEN_EXPIRED",
+            SessionTokenError::Revoked => "TOKEN_REVOKED",
+            SessionTokenError::SessionExpired => "SESSION_EXPIRED",
+            SessionTokenError::VaultLocked => "VAULT_LOCKED",
+            SessionTokenError::Database { .. } => "DATABASE_ERROR",
+            SessionTokenError::Internal { .. } => "INTERNAL_ERROR",
+        }
+    }
+}
+
+impl From<DatabaseError> for SessionTokenError {
+    fn from(e: DatabaseError) -> Self {
+        match e {
+            DatabaseError::ConnectionError { .. } => SessionTokenError::VaultLocked,
+            e => SessionTokenError::Database {
+                reason: e.to_string(),
+            },
+        }
+    }
+}
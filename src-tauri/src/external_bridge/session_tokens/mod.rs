@@ -0,0 +1,333 @@
+//! Session tokens for approved clients
+//!
+//! Without a remembered authorization, a client had to be approved again on
+//! every start of haex-vault. After an approval the bridge now issues a
+//! short-lived token, pushed as an encrypted `session.token` response
+//! (`{ success, data: { token, tokenId, scopes, expiresAt, sessionExpiresAt } }`).
+//! A client that presents it in its next handshake (`sessionToken`) is
+//! admitted without a prompt, for the extensions in `scopes`.
+//!
+//! A token is `hxs_<claims>.<signature>`, both parts base64url. The claims
+//! name the token, the client and a hash of its public key and are signed
+//! with HMAC-SHA256 under a key that never leaves the vault. A token only
+//! works with the key it was issued to, for [`TOKEN_TTL_SECS`], and only
+//! while its metadata is stored ([`store`]): revoking deletes the metadata
+//! (or withdraws a scope from it), which connected clients notice with
+//! their next request.
+//!
+//! Before it expires the client swaps the token for a new one with
+//! `session.refresh` (`{ requestId, token }`, answered like `session.token`).
+//! Refreshing keeps the approval time; [`MAX_SESSION_AGE_SECS`] after the
+//! approval the user has to approve the client again.
+
+pub mod error;
+pub(crate) mod store;
+#[cfg(test)]
+mod tests;
+
+use crate::database::core::with_connection;
+use crate::database::DbConnection;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL, Engine};
+use error::SessionTokenError;
+use hmac::{Hmac, Mac};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use time::OffsetDateTime;
+use ts_rs::TS;
+
+/// Prefix of session tokens
+pub const SESSION_TOKEN_PREFIX: &str = "hxs_";
+/// Action of the response that delivers a token
+pub const ACTION_SESSION_TOKEN: &str = "session.token";
+/// Action a client refreshes its token with
+pub const ACTION_SESSION_REFRESH: &str = "session.refresh";
+/// Lifetime of a token
+pub const TOKEN_TTL_SECS: i64 = 60 * 60;
+/// How long refreshing can extend an approval
+pub const MAX_SESSION_AGE_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Signed content of a token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct TokenClaims {
+    /// Token ID
+    pub jti: String,
+    /// Client ID
+    pub cid: String,
+    /// SHA-256 of the client's public key (hex)
+    pub pkh: String,
+    /// Extension IDs the token grants access to
+    pub scopes: Vec<String>,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+/// Stored metadata of an issued token (the token itself is not stored)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTokenInfo {
+    pub id: String,
+    pub client_id: String,
+    pub client_name: String,
+    /// SHA-256 of the client's public key (hex)
+    pub public_key_hash: String,
+    /// Extension IDs the token grants access to
+    pub scopes: Vec<String>,
+    /// Unix seconds
+    #[ts(type = "number")]
+    pub issued_at: i64,
+    /// Unix seconds
+    #[ts(type = "number")]
+    pub expires_at: i64,
+    /// When the user approved the client (unix seconds); refreshing keeps it
+    #[ts(type = "number")]
+    pub approved_at: i64,
+    /// Refreshes since the approval
+    pub refresh_count: u32,
+}
+
+impl SessionTokenInfo {
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at <= now
+    }
+
+    /// End of the session; no refresh reaches past it
+    pub fn session_expires_at(&self) -> i64 {
+        self.approved_at + MAX_SESSION_AGE_SECS
+    }
+}
+
+/// A token as delivered to the client
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedSessionToken {
+    /// Opaque token for the next handshake
+    pub token: String,
+    pub token_id: String,
+    pub scopes: Vec<String>,
+    /// Unix seconds
+    #[ts(type = "number")]
+    pub expires_at: i64,
+    /// Unix seconds; refresh before `expiresAt`, approve again after this
+    #[ts(type = "number")]
+    pub session_expires_at: i64,
+}
+
+/// What a verified token grants its connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionGrant {
+    pub token_id: String,
+    pub scopes: Vec<String>,
+    pub expires_at: i64,
+}
+
+impl SessionGrant {
+    pub fn allows(&self, extension_id: &str) -> bool {
+        self.scopes.iter().any(|s| s == extension_id)
+    }
+}
+
+impl From<&SessionTokenInfo> for SessionGrant {
+    fn from(info: &SessionTokenInfo) -> Self {
+        Self {
+            token_id: info.id.clone(),
+            scopes: info.scopes.clone(),
+            expires_at: info.expires_at,
+        }
+    }
+}
+
+impl From<&IssuedSessionToken> for SessionGrant {
+    fn from(issued: &IssuedSessionToken) -> Self {
+        Self {
+            token_id: issued.token_id.clone(),
+            scopes: issued.scopes.clone(),
+            expires_at: issued.expires_at,
+        }
+    }
+}
+
+/// Current time in unix seconds
+pub fn now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+/// Hash a token is bound to
+pub fn public_key_hash(public_key: &str) -> String {
+    hex::encode(Sha256::digest(public_key.as_bytes()))
+}
+
+/// Runs `f` on the open vault
+pub fn with_vault<T>(
+    db: &DbConnection,
+    f: impl FnOnce(&Connection) -> Result<T, SessionTokenError>,
+) -> Result<T, SessionTokenError> {
+    with_connection(db, |conn| Ok(f(conn)))?
+}
+
+fn mac(key: &[u8]) -> Result<Hmac<Sha256>, SessionTokenError> {
+    Hmac::<Sha256>::new_from_slice(key).map_err(|e| SessionTokenError::Internal {
+        reason: e.to_string(),
+    })
+}
+
+pub(crate) fn encode(key: &[u8], claims: &TokenClaims) -> Result<String, SessionTokenError> {
+    let json = serde_json::to_vec(claims).map_err(|e| SessionTokenError::Internal {
+        reason: e.to_string(),
+    })?;
+    let body = BASE64_URL.encode(json);
+    let mut mac = mac(key)?;
+    mac.update(body.as_bytes());
+    let signature = BASE64_URL.encode(mac.finalize().into_bytes());
+    Ok(format!("{SESSION_TOKEN_PREFIX}{body}.{signature}"))
+}
+
+/// Checks the signature of `token` and returns its claims
+pub(crate) fn decode(key: &[u8], token: &str) -> Result<TokenClaims, SessionTokenError> {
+    let (body, signature) = token
+        .strip_prefix(SESSION_TOKEN_PREFIX)
+        .and_then(|rest| rest.split_once('.'))
+        .ok_or(SessionTokenError::Malformed)?;
+    let signature = BASE64_URL
+        .decode(signature)
+        .map_err(|_| SessionTokenError::Malformed)?;
+
+    let mut mac = mac(key)?;
+    mac.update(body.as_bytes());
+    mac.verify_slice(&signature)
+        .map_err(|_| SessionTokenError::InvalidSignature)?;
+
+    let json = BASE64_URL
+        .decode(body)
+        .map_err(|_| SessionTokenError::Malformed)?;
+    serde_json::from_slice(&json).map_err(|_| SessionTokenError::Malformed)
+}
+
+/// Stores `info` and signs a token for it
+fn mint(
+    conn: &Connection,
+    info: SessionTokenInfo,
+    now: i64,
+) -> Result<IssuedSessionToken, SessionTokenError> {
+    let key = store::signing_key(conn)?;
+    let claims = TokenClaims {
+        jti: info.id.clone(),
+        cid: info.client_id.clone(),
+        pkh: info.public_key_hash.clone(),
+        scopes: info.scopes.clone(),
+        iat: info.issued_at,
+        exp: info.expires_at,
+    };
+    let issued = IssuedSessionToken {
+        token: encode(&key, &claims)?,
+        token_id: info.id.clone(),
+        scopes: info.scopes.clone(),
+        expires_at: info.expires_at,
+        session_expires_at: info.session_expires_at(),
+    };
+    store::insert(conn, info, now)?;
+    Ok(issued)
+}
+
+/// Issues a token after the user approved `client_id` for `scopes`. The
+/// client's live tokens are replaced; their scopes carry over.
+pub fn issue(
+    conn: &Connection,
+    client_id: &str,
+    client_name: &str,
+    public_key: &str,
+    scopes: &[String],
+    now: i64,
+) -> Result<IssuedSessionToken, SessionTokenError> {
+    let public_key_hash = public_key_hash(public_key);
+    let mut merged: BTreeSet<String> = scopes.iter().cloned().collect();
+    for token in store::load(conn)? {
+        if token.client_id == client_id
+            && token.public_key_hash == public_key_hash
+            && !token.is_expired(now)
+        {
+            merged.extend(token.scopes);
+        }
+    }
+    store::revoke_client(conn, client_id)?;
+
+    let info = SessionTokenInfo {
+        id: uuid::Uuid::new_v4().to_string(),
+        client_id: client_id.to_string(),
+        client_name: client_name.to_string(),
+        public_key_hash,
+        scopes: merged.into_iter().collect(),
+        issued_at: now,
+        expires_at: now + TOKEN_TTL_SECS,
+        approved_at: now,
+        refresh_count: 0,
+    };
+    mint(conn, info, now)
+}
+
+/// Checks a token presented by `client_id` with `public_key`
+pub fn verify(
+    conn: &Connection,
+    token: &str,
+    client_id: &str,
+    public_key: &str,
+    now: i64,
+) -> Result<SessionTokenInfo, SessionTokenError> {
+    let key = store::signing_key(conn)?;
+    let claims = decode(&key, token)?;
+    if claims.cid != client_id || claims.pkh != public_key_hash(public_key) {
+        return Err(SessionTokenError::ClientMismatch);
+    }
+    if claims.exp <= now {
+        return Err(SessionTokenError::Expired);
+    }
+
+    let info = store::find(conn, &claims.jti)?.ok_or(SessionTokenError::Revoked)?;
+    if info.client_id != claims.cid || info.public_key_hash != claims.pkh {
+        return Err(SessionTokenError::ClientMismatch);
+    }
+    Ok(info)
+}
+
+/// Replaces a valid token with a new one for the same approval
+pub fn refresh(
+    conn: &Connection,
+    token: &str,
+    client_id: &str,
+    public_key: &str,
+    now: i64,
+) -> Result<IssuedSessionToken, SessionTokenError> {
+    let current = verify(conn, token, client_id, public_key, now)?;
+    let session_expires_at = current.session_expires_at();
+    if session_expires_at <= now {
+        return Err(SessionTokenError::SessionExpired);
+    }
+    store::remove(conn, &current.id)?;
+
+    let info = SessionTokenInfo {
+        id: uuid::Uuid::new_v4().to_string(),
+        issued_at: now,
+        expires_at: (now + TOKEN_TTL_SECS).min(session_expires_at),
+        refresh_count: current.refresh_count.saturating_add(1),
+        ..current
+    };
+    mint(conn, info, now)
+}
+
+/// Whether `grant` still holds for `extension_id`: not expired, not revoked
+/// and the scope not withdrawn in the meantime
+pub fn is_active(
+    conn: &Connection,
+    grant: &SessionGrant,
+    extension_id: &str,
+    now: i64,
+) -> Result<bool, SessionTokenError> {
+    if grant.expires_at <= now || !grant.allows(extension_id) {
+        return Ok(false);
+    }
+    Ok(store::find(conn, &grant.token_id)?
+        .is_some_and(|token| token.scopes.iter().any(|s| s == extension_id)))
+}
@@ -0,0 +1,145 @@
+//! Persistence of the token signing key and the metadata of issued tokens
+//! in `haex_crdt_configs` (local-only: an approval belongs to this device).
+
+use crate::database::constants::vault_settings_key;
+use crate::database::error::DatabaseError;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, TABLE_CRDT_CONFIGS,
+};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::SessionTokenInfo;
+
+/// `type` column value of the token rows
+const CONFIG_TYPE: &str = "external_bridge";
+/// Length of the HMAC key in bytes
+const SIGNING_KEY_LENGTH: usize = 32;
+
+fn read(conn: &Connection, key: &str) -> Result<Option<String>, DatabaseError> {
+    Ok(conn
+        .query_row(
+            &format!(
+                "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"
+            ),
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+fn write(conn: &Connection, key: &str, value: &str) -> Result<(), DatabaseError> {
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?, ?, ?)"
+        ),
+        params![key, CONFIG_TYPE, value],
+    )?;
+    Ok(())
+}
+
+/// Key the tokens are signed with; created on first use
+pub fn signing_key(conn: &Connection) -> Result<Vec<u8>, DatabaseError> {
+    if let Some(stored) = read(conn, vault_settings_key::EXTERNAL_BRIDGE_SESSION_TOKEN_KEY)? {
+        match hex::decode(&stored) {
+            Ok(key) if key.len() == SIGNING_KEY_LENGTH => return Ok(key),
+            _ => {
+                return Err(DatabaseError::SerializationError {
+                    reason: "Invalid session token signing key".to_string(),
+                })
+            }
+        }
+    }
+
+    let mut key = [0u8; SIGNING_KEY_LENGTH];
+    rand::fill(&mut key);
+    write(
+        conn,
+        vault_settings_key::EXTERNAL_BRIDGE_SESSION_TOKEN_KEY,
+        &hex::encode(key),
+    )?;
+    Ok(key.to_vec())
+}
+
+pub fn load(conn: &Connection) -> Result<Vec<SessionTokenInfo>, DatabaseError> {
+    match read(conn, vault_settings_key::EXTERNAL_BRIDGE_SESSION_TOKENS)? {
+        None => Ok(Vec::new()),
+        Some(json) => serde_json::from_str(&json).map_err(|e| DatabaseError::SerializationError {
+            reason: format!("Invalid session tokens: {e}"),
+        }),
+    }
+}
+
+fn save(conn: &Connection, tokens: &[SessionTokenInfo]) -> Result<(), DatabaseError> {
+    let json = serde_json::to_string(tokens).map_err(|e| DatabaseError::SerializationError {
+        reason: e.to_string(),
+    })?;
+    write(
+        conn,
+        vault_settings_key::EXTERNAL_BRIDGE_SESSION_TOKENS,
+        &json,
+    )
+}
+
+pub fn find(conn: &Connection, token_id: &str) -> Result<Option<SessionTokenInfo>, DatabaseError> {
+    Ok(load(conn)?.into_iter().find(|t| t.id == token_id))
+}
+
+/// Stores `token` and drops the tokens that expired before `now`.
+pub fn insert(conn: &Connection, token: SessionTokenInfo, now: i64) -> Result<(), DatabaseError> {
+    let mut tokens = load(conn)?;
+    tokens.retain(|t| !t.is_expired(now) && t.id != token.id);
+    tokens.push(token);
+    save(conn, &tokens)
+}
+
+/// Drops the tokens that expired before `now`. Returns how many were removed.
+pub fn prune(conn: &Connection, now: i64) -> Result<usize, DatabaseError> {
+    remove_where(conn, |t| t.is_expired(now))
+}
+
+/// Revokes one token. Returns whether it existed.
+pub fn remove(conn: &Connection, token_id: &str) -> Result<bool, DatabaseError> {
+    Ok(remove_where(conn, |t| t.id == token_id)? > 0)
+}
+
+/// Revokes all tokens of `client_id`. Returns how many were removed.
+pub fn revoke_client(conn: &Connection, client_id: &str) -> Result<usize, DatabaseError> {
+    remove_where(conn, |t| t.client_id == client_id)
+}
+
+/// Withdraws `extension_id` from the tokens of `client_id`; tokens left
+/// without scopes are revoked. Returns how many tokens changed.
+pub fn remove_scope(
+    conn: &Connection,
+    client_id: &str,
+    extension_id: &str,
+) -> Result<usize, DatabaseError> {
+    let mut tokens = load(conn)?;
+    let mut changed = 0;
+    for token in tokens.iter_mut().filter(|t| t.client_id == client_id) {
+        let before = token.scopes.len();
+        token.scopes.retain(|s| s != extension_id);
+        if token.scopes.len() != before {
+            changed += 1;
+        }
+    }
+    if changed > 0 {
+        tokens.retain(|t| !t.scopes.is_empty());
+        save(conn, &tokens)?;
+    }
+    Ok(changed)
+}
+
+fn remove_where(
+    conn: &Connection,
+    matches: impl Fn(&SessionTokenInfo) -> bool,
+) -> Result<usize, DatabaseError> {
+    let mut tokens = load(conn)?;
+    let before = tokens.len();
+    tokens.retain(|t| !matches(t));
+    let removed = before - tokens.len();
+    if removed > 0 {
+        save(conn, &tokens)?;
+    }
+    Ok(removed)
+}
@@ -0,0 +1,313 @@
+use super::error::SessionTokenError;
+use super::{
+    decode, encode, is_active, issue, public_key_hash, refresh, store, verify, SessionGrant,
+    TokenClaims, MAX_SESSION_AGE_SECS, TOKEN_TTL_SECS,
+};
+use crate::crdt::hlc::HlcService;
+use crate::test_support::crdt::open_crdt_connection;
+use rusqlite::Connection;
+
+const NOW: i64 = 1_800_000_000;
+
+fn vault() -> Connection {
+    let conn =
+        open_crdt_connection(HlcService::new_for_testing("session-token-test-device")).unwrap();
+    conn
+}
+
+fn scopes(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_token_encoding_roundtrip() {
+    let key = [7u8; 32];
+    let claims = TokenClaims {
+        jti: "token-1".to_string(),
+        cid: "client-1".to_string(),
+        pkh: public_key_hash("pk"),
+        scopes: scopes(&["ext-1"]),
+        iat: NOW,
+        exp: NOW + TOKEN_TTL_SECS,
+    };
+
+    let token = encode(&key, &claims).unwrap();
+    assert!(token.starts_with("hxs_"));
+    assert_eq!(decode(&key, &token).unwrap(), claims);
+
+    // Another key, a changed body and garbage are rejected
+    assert_eq!(
+        decode(&[8u8; 32], &token),
+        Err(SessionTokenError::InvalidSignature)
+    );
+    let (body, signature) = token.split_once('.').unwrap();
+    let tampered = format!("{}x.{}", body, signature);
+    assert_eq!(
+        decode(&key, &tampered),
+        Err(SessionTokenError::InvalidSignature)
+    );
+    assert_eq!(decode(&key, "hxs_nodot"), Err(SessionTokenError::Malformed));
+    assert_eq!(
+        decode(&key, "other.token"),
+        Err(SessionTokenError::Malformed)
+    );
+}
+
+#[test]
+fn test_signing_key_is_created_once() {
+    let conn = vault();
+    let key = store::signing_key(&conn).unwrap();
+    assert_eq!(key.len(), 32);
+    assert_eq!(store::signing_key(&conn).unwrap(), key);
+}
+
+#[test]
+fn test_issued_token_verifies_for_its_client_only() {
+    let conn = vault();
+    let issued = issue(
+        &conn,
+        "client-1",
+        "Browser",
+        "pk-1",
+        &scopes(&["ext-1"]),
+        NOW,
+    )
+    .unwrap();
+    assert_eq!(issued.expires_at, NOW + TOKEN_TTL_SECS);
+    assert_eq!(issued.session_expires_at, NOW + MAX_SESSION_AGE_SECS);
+
+    let info = verify(&conn, &issued.token, "client-1", "pk-1", NOW + 10).unwrap();
+    assert_eq!(info.id, issued.token_id);
+    assert_eq!(info.scopes, scopes(&["ext-1"]));
+
+    // Bound to client ID and public key
+    assert_eq!(
+        verify(&conn, &issued.token, "client-2", "pk-1", NOW),
+        Err(SessionTokenError::ClientMismatch)
+    );
+    assert_eq!(
+        verify(&conn, &issued.token, "client-1", "pk-2", NOW),
+        Err(SessionTokenError::ClientMismatch)
+    );
+    // Short-lived
+    assert_eq!(
+        verify(
+            &conn,
+            &issued.token,
+            "client-1",
+            "pk-1",
+            NOW + TOKEN_TTL_SECS
+        ),
+        Err(SessionTokenError::Expired)
+    );
+}
+
+#[test]
+fn test_issue_merges_and_replaces_client_tokens() {
+    let conn = vault();
+    let first = issue(
+        &conn,
+        "client-1",
+        "Browser",
+        "pk-1",
+        &scopes(&["ext-1"]),
+        NOW,
+    )
+    .unwrap();
+    let second = issue(
+        &conn,
+        "client-1",
+        "Browser",
+        "pk-1",
+        &scopes(&["ext-2"]),
+        NOW + 5,
+    )
+    .unwrap();
+
+    assert_eq!(second.scopes, scopes(&["ext-1", "ext-2"]));
+    assert_eq!(
+        verify(&conn, &first.token, "client-1", "pk-1", NOW + 5),
+        Err(SessionTokenError::Revoked)
+    );
+    assert_eq!(store::load(&conn).unwrap().len(), 1);
+}
+
+#[test]
+fn test_refresh_rotates_and_keeps_approval() {
+    let conn = vault();
+    let issued = issue(
+        &conn,
+        "client-1",
+        "Browser",
+        "pk-1",
+        &scopes(&["ext-1"]),
+        NOW,
+    )
+    .unwrap();
+
+    let later = NOW + TOKEN_TTL_SECS - 60;
+    let refreshed = refresh(&conn, &issued.token, "client-1", "pk-1", later).unwrap();
+    assert_ne!(refreshed.token_id, issued.token_id);
+    assert_eq!(refreshed.expires_at, later + TOKEN_TTL_SECS);
+    assert_eq!(refreshed.session_expires_at, issued.session_expires_at);
+
+    // The old token is gone
+    assert_eq!(
+        verify(&conn, &issued.token, "client-1", "pk-1", later),
+        Err(SessionTokenError::Revoked)
+    );
+    let info = verify(&conn, &refreshed.token, "client-1", "pk-1", later).unwrap();
+    assert_eq!(info.refresh_count, 1);
+    assert_eq!(info.approved_at, NOW);
+}
+
+#[test]
+fn test_refresh_stops_at_max_session_age() {
+    let conn = vault();
+    let mut token = issue(
+        &conn,
+        "client-1",
+        "Browser",
+        "pk-1",
+        &scopes(&["ext-1"]),
+        NOW,
+    )
+    .unwrap();
+
+    // Refreshing just before expiry until the session ends
+    let mut now = NOW;
+    while token.expires_at < token.session_expires_at {
+        now = token.expires_at - 1;
+        token = refresh(&conn, &token.token, "client-1", "pk-1", now).unwrap();
+    }
+    assert_eq!(token.expires_at, NOW + MAX_SESSION_AGE_SECS);
+
+    now = token.expires_at - 1;
+    // Still valid, but no further refresh past the session
+    assert!(verify(&conn, &token.token, "client-1", "pk-1", now).is_ok());
+    let refreshed = refresh(&conn, &token.token, "client-1", "pk-1", now).unwrap();
+    assert_eq!(refreshed.expires_at, NOW + MAX_SESSION_AGE_SECS);
+    assert_eq!(
+        refresh(
+            &conn,
+            &refreshed.token,
+            "client-1",
+            "pk-1",
+            refreshed.expires_at
+        ),
+        Err(SessionTokenError::Expired)
+    );
+}
+
+#[test]
+fn test_revocation() {
+    let conn = vault();
+    let one = issue(
+        &conn,
+        "client-1",
+        "Browser",
+        "pk-1",
+        &scopes(&["ext-1"]),
+        NOW,
+    )
+    .unwrap();
+    let two = issue(&conn, "client-2", "CLI", "pk-2", &scopes(&["ext-1"]), NOW).unwrap();
+    let grant = SessionGrant::from(&one);
+    assert!(grant.allows("ext-1"));
+    assert!(!grant.allows("ext-2"));
+    assert!(is_active(&conn, &grant, "ext-1", NOW).unwrap());
+    assert!(!is_active(&conn, &grant, "ext-2", NOW).unwrap());
+    assert!(!is_active(&conn, &grant, "ext-1", one.expires_at).unwrap());
+
+    assert!(store::remove(&conn, &one.token_id).unwrap());
+    assert!(!store::remove(&conn, &one.token_id).unwrap());
+    assert!(!is_active(&conn, &grant, "ext-1", NOW).unwrap());
+    assert_eq!(
+        verify(&conn, &one.token, "client-1", "pk-1", NOW),
+        Err(SessionTokenError::Revoked)
+    );
+
+    assert_eq!(store::revoke_client(&conn, "client-2").unwrap(), 1);
+    assert_eq!(
+        verify(&conn, &two.token, "client-2", "pk-2", NOW),
+        Err(SessionTokenError::Revoked)
+    );
+}
+
+#[test]
+fn test_expired_tokens_are_pruned() {
+    let conn = vault();
+    issue(
+        &conn,
+        "client-1",
+        "Browser",
+        "pk-1",
+        &scopes(&["ext-1"]),
+        NOW,
+    )
+    .unwrap();
+    issue(
+        &conn,
+        "client-2",
+        "CLI",
+        "pk-2",
+        &scopes(&["ext-1"]),
+        NOW + TOKEN_TTL_SECS,
+    )
+    .unwrap();
+
+    // Issuing dropped the expired token of client-1
+    let tokens = store::load(&conn).unwrap();
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].client_id, "client-2");
+
+    assert_eq!(store::prune(&conn, NOW + 2 * TOKEN_TTL_SECS).unwrap(), 1);
+    assert!(store::load(&conn).unwrap().is_empty());
+}
+
+#[test]
+fn test_session_token_info_serialization() {
+    let conn = vault();
+    issue(
+        &conn,
+        "client-1",
+        "Browser",
+        "pk-1",
+        &scopes(&["ext-1"]),
+        NOW,
+    )
+    .unwrap();
+    let json = serde_json::to_value(&store::load(&conn).unwrap()[0]).unwrap();
+    assert_eq!(json["clientId"], "client-1");
+    assert_eq!(json["publicKeyHash"], public_key_hash("pk-1"));
+    assert_eq!(json["approvedAt"], NOW);
+    assert_eq!(json["refreshCount"], 0);
+}
+
+#[test]
+fn test_withdrawing_a_scope() {
+    let conn = vault();
+    issue(&conn, "client-1", "CLI", "pk-1", &scopes(&["notes"]), NOW).unwrap();
+    let issued = issue(
+        &conn,
+        "client-1",
+        "CLI",
+        "pk-1",
+        &scopes(&["passwords"]),
+        NOW,
+    )
+    .unwrap();
+    let grant = SessionGrant::from(&issued);
+    assert!(is_active(&conn, &grant, "notes", NOW).unwrap());
+
+    assert_eq!(store::remove_scope(&conn, "client-1", "notes").unwrap(), 1);
+    assert!(!is_active(&conn, &grant, "notes", NOW).unwrap());
+    assert!(is_active(&conn, &grant, "passwords", NOW).unwrap());
+
+    // The last scope takes the token with it
+    assert_eq!(
+        store::remove_scope(&conn, "client-1", "passwords").unwrap(),
+        1
+    );
+    assert!(store::load(&conn).unwrap().is_empty());
+}
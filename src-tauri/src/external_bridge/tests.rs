@@ -13,6 +13,9 @@ mod tests {
     use super::super::authorization::*;
     use super::super::crypto::EncryptedEnvelope;
     use super::super::protocol::*;
+    use crate::database::generated::{
+        HaexExternalAuthorizedClientsNoSync, HaexExternalBlockedClientsNoSync,
+    };
 
     // ============================================================================
     // ClientInfo and RequestedExtension Tests
@@ -48,6 +51,8 @@ mod tests {
                 public_key: "pk123".to_string(),
                 requested_extensions: vec![],
             },
+            session_token: None,
+            signed_requests: false,
         };
 
         let json = serde_json::to_string(&handshake).unwrap();
@@ -59,6 +64,23 @@ mod tests {
         assert_eq!(deserialized.client.client_id, "client-abc");
     }
 
+    #[test]
+    fn test_handshake_request_with_session_token() {
+        let json = r#"{"version":1,"client":{"clientId":"c1","clientName":"Test","publicKey":"pk"},"sessionToken":"hxs_a.b"}"#;
+        let handshake: HandshakeRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(handshake.session_token.as_deref(), Some("hxs_a.b"));
+
+        // Omitted when absent, optional for older clients
+        let without = HandshakeRequest {
+            session_token: None,
+            ..handshake
+        };
+        let json = serde_json::to_string(&without).unwrap();
+        assert!(!json.contains("sessionToken"));
+        let parsed: HandshakeRequest = serde_json::from_str(&json).unwrap();
+        assert!(parsed.session_token.is_none());
+    }
+
     #[test]
     fn test_handshake_response_serialization() {
         let response = HandshakeResponse {
@@ -84,6 +106,8 @@ mod tests {
                 public_key: "pk".to_string(),
                 requested_extensions: vec![],
             },
+            session_token: None,
+            signed_requests: false,
         });
 
         let json = serde_json::to_string(&msg).unwrap();
@@ -149,18 +173,18 @@ mod tests {
     }
 
     #[test]
-    fn test_authorized_client_parsing() {
-        let row = vec![
-            serde_json::json!("row-id-1"),
-            serde_json::json!("client-id-abc"),
-            serde_json::json!("Test Client"),
-            serde_json::json!("public-key-xyz"),
-            serde_json::json!("haex-pass"),
-            serde_json::json!("2024-01-01T00:00:00Z"),
-            serde_json::json!("2024-01-02T00:00:00Z"),
-        ];
+    fn test_authorized_client_from_row() {
+        let row = HaexExternalAuthorizedClientsNoSync {
+            id: "row-id-1".to_string(),
+            client_id: "client-id-abc".to_string(),
+            client_name: "Test Client".to_string(),
+            public_key: "public-key-xyz".to_string(),
+            extension_id: "haex-pass".to_string(),
+            authorized_at: Some("2024-01-01T00:00:00Z".to_string()),
+            last_seen: Some("2024-01-02T00:00:00Z".to_string()),
+        };
 
-        let client = parse_authorized_client(&row).unwrap();
+        let client = AuthorizedClient::from(row);
         assert_eq!(client.id, "row-id-1");
         assert_eq!(client.client_id, "client-id-abc");
         assert_eq!(client.client_name, "Test Client");
@@ -171,33 +195,23 @@ mod tests {
     }
 
     #[test]
-    fn test_authorized_client_parsing_with_null_optional_fields() {
-        let row = vec![
-            serde_json::json!("row-id-1"),
-            serde_json::json!("client-id-abc"),
-            serde_json::json!("Test Client"),
-            serde_json::json!("public-key-xyz"),
-            serde_json::json!("haex-pass"),
-            serde_json::Value::Null,
-            serde_json::Value::Null,
-        ];
+    fn test_authorized_client_from_row_with_null_optional_fields() {
+        let row = HaexExternalAuthorizedClientsNoSync {
+            id: "row-id-1".to_string(),
+            client_id: "client-id-abc".to_string(),
+            client_name: "Test Client".to_string(),
+            public_key: "public-key-xyz".to_string(),
+            extension_id: "haex-pass".to_string(),
+            authorized_at: None,
+            last_seen: None,
+        };
 
-        let client = parse_authorized_client(&row).unwrap();
+        let client = AuthorizedClient::from(row);
         assert_eq!(client.id, "row-id-1");
         assert!(client.authorized_at.is_none());
         assert!(client.last_seen.is_none());
     }
 
-    #[test]
-    fn test_authorized_client_parsing_insufficient_columns() {
-        let row = vec![
-            serde_json::json!("row-id-1"),
-            serde_json::json!("client-id-abc"),
-        ];
-
-        assert!(parse_authorized_client(&row).is_none());
-    }
-
     #[test]
     fn test_pending_authorization_serialization() {
         let pending = PendingAuthorization {
@@ -231,6 +245,10 @@ mod tests {
             public_key: "public-key".to_string(),
             extension_public_key: None,
             extension_name: None,
+            extension_id: None,
+            nonce: None,
+            timestamp: None,
+            signature: None,
         };
 
         let json = serde_json::to_string(&envelope).unwrap();
@@ -251,6 +269,10 @@ mod tests {
             public_key: "client-ephemeral-key".to_string(),
             extension_public_key: Some("b4401f13f65e576b8a30ff9fd83df82a8bb707e1994d40c99996fe88603cefca".to_string()),
             extension_name: Some("haex-pass".to_string()),
+            extension_id: None,
+            nonce: None,
+            timestamp: None,
+            signature: None,
         };
 
         let json = serde_json::to_string(&envelope).unwrap();
@@ -382,12 +404,6 @@ mod tests {
         assert!(SQL_IS_AUTHORIZED.contains("?2"));
         assert!(SQL_IS_CLIENT_KNOWN.contains("?1"));
         assert!(SQL_GET_CLIENT_EXTENSION.contains("?1"));
-        assert!(SQL_GET_CLIENT.contains("?1"));
-        assert!(SQL_INSERT_CLIENT.contains("?1"));
-        assert!(SQL_INSERT_CLIENT.contains("?2"));
-        assert!(SQL_INSERT_CLIENT.contains("?3"));
-        assert!(SQL_INSERT_CLIENT.contains("?4"));
-        assert!(SQL_INSERT_CLIENT.contains("?5"));
         assert!(SQL_UPDATE_LAST_SEEN.contains("?1"));
         assert!(SQL_DELETE_CLIENT.contains("?1"));
     }
@@ -398,9 +414,7 @@ mod tests {
         assert!(SQL_IS_AUTHORIZED.contains(table_name));
         assert!(SQL_IS_CLIENT_KNOWN.contains(table_name));
         assert!(SQL_GET_CLIENT_EXTENSION.contains(table_name));
-        assert!(SQL_GET_CLIENT.contains(table_name));
-        assert!(SQL_GET_ALL_CLIENTS.contains(table_name));
-        assert!(SQL_INSERT_CLIENT.contains(table_name));
+        assert_eq!(HaexExternalAuthorizedClientsNoSync::TABLE, table_name);
         assert!(SQL_UPDATE_LAST_SEEN.contains(table_name));
         assert!(SQL_DELETE_CLIENT.contains(table_name));
     }
@@ -451,9 +465,7 @@ mod tests {
     fn test_sql_blocked_clients_queries_reference_correct_table() {
         let table_name = crate::table_names::TABLE_EXTERNAL_BLOCKED_CLIENTS;
         assert!(SQL_IS_BLOCKED.contains(table_name));
-        assert!(SQL_GET_BLOCKED_CLIENT.contains(table_name));
-        assert!(SQL_GET_ALL_BLOCKED_CLIENTS.contains(table_name));
-        assert!(SQL_INSERT_BLOCKED_CLIENT.contains(table_name));
+        assert_eq!(HaexExternalBlockedClientsNoSync::TABLE, table_name);
         assert!(SQL_DELETE_BLOCKED_CLIENT.contains(table_name));
     }
 
@@ -584,6 +596,10 @@ mod tests {
             public_key: "ephemeral-pk".to_string(),
             extension_public_key: Some("target-ext-pk".to_string()),
             extension_name: Some("haex-pass".to_string()),
+            extension_id: None,
+            nonce: None,
+            timestamp: None,
+            signature: None,
         };
 
         let msg = ProtocolMessage::Request(envelope);
@@ -607,6 +623,10 @@ mod tests {
             public_key: "server-ephemeral-pk".to_string(),
             extension_public_key: None,
             extension_name: None,
+            extension_id: None,
+            nonce: None,
+            timestamp: None,
+            signature: None,
         };
 
         let msg = ProtocolMessage::Response(envelope);
@@ -653,16 +673,16 @@ mod tests {
     }
 
     #[test]
-    fn test_blocked_client_parsing() {
-        let row = vec![
-            serde_json::json!("blocked-id-1"),
-            serde_json::json!("blocked-client-abc"),
-            serde_json::json!("Blocked Client"),
-            serde_json::json!("blocked-public-key"),
-            serde_json::json!("2024-01-01T00:00:00Z"),
-        ];
+    fn test_blocked_client_from_row() {
+        let row = HaexExternalBlockedClientsNoSync {
+            id: "blocked-id-1".to_string(),
+            client_id: "blocked-client-abc".to_string(),
+            client_name: "Blocked Client".to_string(),
+            public_key: "blocked-public-key".to_string(),
+            blocked_at: Some("2024-01-01T00:00:00Z".to_string()),
+        };
 
-        let client = parse_blocked_client(&row).unwrap();
+        let client = BlockedClient::from(row);
         assert_eq!(client.id, "blocked-id-1");
         assert_eq!(client.client_id, "blocked-client-abc");
         assert_eq!(client.client_name, "Blocked Client");
@@ -671,29 +691,19 @@ mod tests {
     }
 
     #[test]
-    fn test_blocked_client_parsing_with_null_blocked_at() {
-        let row = vec![
-            serde_json::json!("blocked-id-1"),
-            serde_json::json!("blocked-client-abc"),
-            serde_json::json!("Blocked Client"),
-            serde_json::json!("blocked-public-key"),
-            serde_json::Value::Null,
-        ];
+    fn test_blocked_client_from_row_with_null_blocked_at() {
+        let row = HaexExternalBlockedClientsNoSync {
+            id: "blocked-id-1".to_string(),
+            client_id: "blocked-client-abc".to_string(),
+            client_name: "Blocked Client".to_string(),
+            public_key: "blocked-public-key".to_string(),
+            blocked_at: None,
+        };
 
-        let client = parse_blocked_client(&row).unwrap();
+        let client = BlockedClient::from(row);
         assert!(client.blocked_at.is_none());
     }
 
-    #[test]
-    fn test_blocked_client_parsing_insufficient_columns() {
-        let row = vec![
-            serde_json::json!("id"),
-            serde_json::json!("client_id"),
-        ];
-
-        assert!(parse_blocked_client(&row).is_none());
-    }
-
     #[test]
     fn test_handshake_with_requested_extensions() {
         let handshake = HandshakeRequest {
@@ -709,6 +719,8 @@ mod tests {
                     },
                 ],
             },
+            session_token: None,
+            signed_requests: false,
         };
 
         let json = serde_json::to_string(&handshake).unwrap();
@@ -915,4 +927,307 @@ mod tests {
         // Additional signals after wait completed should be safe (no-op)
         bridge.signal_extension_ready(extension_id).await;
     }
+
+    // ============================================================================
+    // Routing Tests (per-route queues and backpressure)
+    // ============================================================================
+
+    #[test]
+    fn test_encrypted_envelope_with_extension_id() {
+        let json = r#"{
+            "action": "notes.list",
+            "message": "encrypted",
+            "iv": "iv123",
+            "clientId": "cli",
+            "publicKey": "pk123",
+            "extensionId": "ext-notes"
+        }"#;
+
+        let envelope: EncryptedEnvelope = serde_json::from_str(json).unwrap();
+        assert_eq!(envelope.extension_id.as_deref(), Some("ext-notes"));
+        assert!(envelope.extension_public_key.is_none());
+    }
+
+    #[test]
+    fn test_sql_get_extension_by_id_query_format() {
+        let query = &*SQL_GET_EXTENSION_BY_ID;
+        assert!(query.contains("?1"));
+        assert!(query.contains("haex_extensions"));
+        assert!(query.contains("public_key"));
+    }
+
+    #[test]
+    fn test_core_route_target() {
+        use super::super::routing::RouteTarget;
+
+        let core = RouteTarget::core();
+        assert!(core.is_core());
+        assert_eq!(core.extension_id, super::super::CORE_EXTENSION_ID);
+        assert_eq!(core.name, super::super::CORE_EXTENSION_NAME);
+    }
+
+    #[tokio::test]
+    async fn test_routes_run_independently() {
+        use super::super::routing::RouteQueues;
+        use tokio::sync::{mpsc, oneshot};
+
+        let routes = RouteQueues::new();
+        let (block_tx, block_rx) = oneshot::channel::<()>();
+        let (done_tx, mut done_rx) = mpsc::unbounded_channel::<&'static str>();
+
+        // The first route hangs until released
+        let done = done_tx.clone();
+        assert!(routes.enqueue(
+            "slow",
+            Box::pin(async move {
+                let _ = block_rx.await;
+                let _ = done.send("slow");
+            })
+        ));
+        let done = done_tx.clone();
+        assert!(routes.enqueue(
+            "fast",
+            Box::pin(async move {
+                let _ = done.send("fast");
+            })
+        ));
+
+        // The second route isn't held up by the first
+        assert_eq!(done_rx.recv().await, Some("fast"));
+        block_tx.send(()).unwrap();
+        assert_eq!(done_rx.recv().await, Some("slow"));
+    }
+
+    #[tokio::test]
+    async fn test_route_keeps_request_order() {
+        use super::super::routing::RouteQueues;
+        use tokio::sync::mpsc;
+
+        let routes = RouteQueues::new();
+        let (done_tx, mut done_rx) = mpsc::unbounded_channel::<u32>();
+        for i in 0..5 {
+            let done = done_tx.clone();
+            assert!(routes.enqueue(
+                "ext",
+                Box::pin(async move {
+                    tokio::task::yield_now().await;
+                    let _ = done.send(i);
+                })
+            ));
+        }
+
+        for i in 0..5 {
+            assert_eq!(done_rx.recv().await, Some(i));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_full_route_rejects_requests() {
+        use super::super::routing::RouteQueues;
+        use tokio::sync::oneshot;
+
+        let routes = RouteQueues::with_capacity(2);
+        let (block_tx, block_rx) = oneshot::channel::<()>();
+        assert!(routes.enqueue(
+            "ext",
+            Box::pin(async move {
+                let _ = block_rx.await;
+            })
+        ));
+        assert!(routes.enqueue("ext", Box::pin(async {})));
+
+        // Full: rejected right away, other routes unaffected
+        assert!(!routes.enqueue("ext", Box::pin(async {})));
+        assert!(routes.enqueue("other", Box::pin(async {})));
+
+        let status = routes.status("client");
+        let ext = status.iter().find(|r| r.extension_id == "ext").unwrap();
+        assert_eq!(ext.client_id, "client");
+        assert_eq!(ext.pending, 2);
+        assert_eq!(ext.rejected, 1);
+
+        // Accepts again once drained
+        block_tx.send(()).unwrap();
+        for _ in 0..100 {
+            if routes.status("client").iter().all(|r| r.pending == 0) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        assert!(routes.enqueue("ext", Box::pin(async {})));
+    }
+
+    // ============================================================================
+    // Replay Protection Tests
+    // ============================================================================
+
+    const NOW_MS: i64 = 1_800_000_000_000;
+    const NONCE: &str = "nonce-0123456789abcdef";
+
+    #[test]
+    fn test_replayed_nonce_is_rejected() {
+        let mut guard = ReplayGuard::new();
+        assert!(guard.check_nonce("c1", NONCE, NOW_MS, NOW_MS).is_ok());
+        assert_eq!(
+            guard.check_nonce("c1", NONCE, NOW_MS, NOW_MS + 10),
+            Err(ReplayError::ReplayedNonce)
+        );
+        // Nonces are per client
+        assert!(guard.check_nonce("c2", NONCE, NOW_MS, NOW_MS).is_ok());
+    }
+
+    #[test]
+    fn test_stale_timestamp_is_rejected() {
+        let mut guard = ReplayGuard::new();
+        let too_old = NOW_MS - REPLAY_WINDOW_MS - 1;
+        assert_eq!(
+            guard.check_nonce("c1", NONCE, too_old, NOW_MS),
+            Err(ReplayError::StaleTimestamp {
+                skew_ms: REPLAY_WINDOW_MS + 1
+            })
+        );
+        // Clock skew into the future counts the same
+        let too_new = NOW_MS + REPLAY_WINDOW_MS + 1;
+        assert!(matches!(
+            guard.check_nonce("c1", NONCE, too_new, NOW_MS),
+            Err(ReplayError::StaleTimestamp { .. })
+        ));
+        assert!(guard
+            .check_nonce("c1", NONCE, NOW_MS - REPLAY_WINDOW_MS, NOW_MS)
+            .is_ok());
+        assert_eq!(
+            ReplayError::StaleTimestamp { skew_ms: 1 }.code(),
+            "STALE_REQUEST"
+        );
+    }
+
+    #[test]
+    fn test_nonce_length_is_checked() {
+        let mut guard = ReplayGuard::new();
+        assert_eq!(
+            guard.check_nonce("c1", "short", NOW_MS, NOW_MS),
+            Err(ReplayError::InvalidNonce)
+        );
+        assert_eq!(
+            guard.check_nonce("c1", &"n".repeat(129), NOW_MS, NOW_MS),
+            Err(ReplayError::InvalidNonce)
+        );
+        assert!(guard.is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_request_id_is_rejected() {
+        let mut guard = ReplayGuard::new();
+        assert!(guard.check_request_id("c1", "req-1", NOW_MS).is_ok());
+        let err = guard.check_request_id("c1", "req-1", NOW_MS + 5).unwrap_err();
+        assert_eq!(
+            err,
+            ReplayError::DuplicateRequestId {
+                request_id: "req-1".to_string()
+            }
+        );
+        assert_eq!(err.code(), "DUPLICATE_REQUEST_ID");
+        assert!(guard.check_request_id("c2", "req-1", NOW_MS).is_ok());
+    }
+
+    #[test]
+    fn test_replay_window_is_pruned() {
+        let mut guard = ReplayGuard::with_window(1_000);
+        guard.check_nonce("c1", NONCE, NOW_MS, NOW_MS).unwrap();
+        guard.check_request_id("c1", "req-1", NOW_MS).unwrap();
+        assert_eq!(guard.len(), 2);
+
+        // Once out of the window the entries are dropped; a replay of the old
+        // frame then fails the timestamp check instead
+        let later = NOW_MS + 1_001;
+        guard.check_request_id("c1", "req-2", later).unwrap();
+        assert_eq!(guard.len(), 1);
+        assert!(matches!(
+            guard.check_nonce("c1", NONCE, NOW_MS, later),
+            Err(ReplayError::StaleTimestamp { .. })
+        ));
+    }
+
+    /// Envelope of `client`, signed with its request signing key if `nonce`
+    /// is given
+    fn envelope_from(
+        server: &super::super::crypto::ServerKeyPair,
+        client_public_key: &str,
+        nonce: Option<&str>,
+        timestamp: i64,
+    ) -> EncryptedEnvelope {
+        use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+        use hmac::{Hmac, Mac};
+        use sha2::Sha256;
+
+        let mut envelope = EncryptedEnvelope {
+            action: "get-logins".to_string(),
+            message: "ciphertext".to_string(),
+            iv: "iv".to_string(),
+            client_id: "c1".to_string(),
+            public_key: "ephemeral".to_string(),
+            extension_public_key: None,
+            extension_name: None,
+            extension_id: Some("ext".to_string()),
+            nonce: None,
+            timestamp: None,
+            signature: None,
+        };
+        if let Some(nonce) = nonce {
+            envelope.nonce = Some(nonce.to_string());
+            envelope.timestamp = Some(timestamp);
+            let key = super::super::crypto::request_signing_key(server, client_public_key).unwrap();
+            let mut mac = Hmac::<Sha256>::new_from_slice(&key).unwrap();
+            mac.update(envelope.signing_input().as_bytes());
+            envelope.signature = Some(BASE64.encode(mac.finalize().into_bytes()));
+        }
+        envelope
+    }
+
+    #[test]
+    fn test_unsigned_requests_are_rejected() {
+        use super::super::crypto::ServerKeyPair;
+        use super::super::server::check_request_signature;
+
+        let server = ServerKeyPair::generate();
+        let client_pk = ServerKeyPair::generate().public_key_base64();
+        let guard = std::sync::Mutex::new(ReplayGuard::new());
+        let now = unix_millis();
+
+        // No client can fall back to unsigned frames, which the request-ID
+        // window alone can't keep from being replayed later
+        let unsigned = envelope_from(&server, &client_pk, None, now);
+        let err = check_request_signature(&unsigned, &server, Some(&client_pk), "c1", &guard)
+            .unwrap_err();
+        assert_eq!(err, ReplayError::SignatureRequired);
+        assert_eq!(err.code(), "SIGNATURE_REQUIRED");
+
+        let signed = envelope_from(&server, &client_pk, Some(NONCE), now);
+        assert!(check_request_signature(&signed, &server, Some(&client_pk), "c1", &guard).is_ok());
+        assert_eq!(
+            check_request_signature(&signed, &server, Some(&client_pk), "c1", &guard),
+            Err(ReplayError::ReplayedNonce)
+        );
+    }
+
+    #[test]
+    fn test_handshakes_from_old_protocol_versions_are_rejected() {
+        // Version 1 clients don't sign their requests
+        let handshake: HandshakeRequest = serde_json::from_value(serde_json::json!({
+            "version": 1,
+            "client": {
+                "clientId": "old-client",
+                "clientName": "Old Browser Extension",
+                "publicKey": "base64-public-key"
+            }
+        }))
+        .unwrap();
+
+        let err = check_protocol_version(handshake.version).unwrap_err();
+        assert_eq!(err, UnsupportedVersion { client_version: 1 });
+        assert_eq!(err.code(), "PROTOCOL_VERSION_UNSUPPORTED");
+        assert!(err.to_string().contains("please upgrade"));
+
+        assert!(check_protocol_version(PROTOCOL_VERSION).is_ok());
+    }
 }
@@ -9,6 +9,7 @@ use std::time::Duration;
 use tauri::State;
 use tokio_util::sync::CancellationToken;
 
+use crate::error_code::{CodedError, ErrorCode};
 use crate::AppState;
 
 use std::sync::Arc;
@@ -98,12 +99,24 @@ pub enum FileSyncCommandError {
     Internal(String),
 }
 
+impl CodedError for FileSyncCommandError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            FileSyncCommandError::InvalidConfig(_) => ErrorCode::SyncInvalidConfig,
+            FileSyncCommandError::ProviderError(_) => ErrorCode::SyncProvider,
+            FileSyncCommandError::EngineError(_) => ErrorCode::SyncEngine,
+            FileSyncCommandError::NotRunning(_) => ErrorCode::SyncNotRunning,
+            FileSyncCommandError::Internal(_) => ErrorCode::Internal,
+        }
+    }
+}
+
 impl serde::Serialize for FileSyncCommandError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        serde::Serialize::serialize(&self.envelope(), serializer)
     }
 }
 
@@ -316,6 +316,29 @@ pub async fn execute_sync(
     db: &DbConnection,
     app_handle: Option<tauri::AppHandle>,
     cancel: Option<CancellationToken>,
+) -> Result<SyncResult, SyncEngineError> {
+    let run = run_sync(
+        source,
+        target,
+        direction,
+        delete_mode,
+        rule_id,
+        db,
+        app_handle,
+        cancel,
+    );
+    crate::metrics::time_sync(crate::metrics::SYNC_FILE, run).await
+}
+
+async fn run_sync(
+    source: Arc<dyn SyncProvider>,
+    target: Arc<dyn SyncProvider>,
+    direction: SyncDirection,
+    delete_mode: DeleteMode,
+    rule_id: &str,
+    db: &DbConnection,
+    app_handle: Option<tauri::AppHandle>,
+    cancel: Option<CancellationToken>,
 ) -> Result<SyncResult, SyncEngineError> {
     macro_rules! check_cancel {
         () => {
@@ -1213,8 +1236,7 @@ fn update_last_synced_at(app: &tauri::AppHandle, rule_id: &str) {
     }
 
     // Notify frontend that CRDT dirty tables changed (triggers sync push)
-    use tauri::Emitter;
-    let _ = app.emit_to("main", crate::event_names::EVENT_CRDT_DIRTY_TABLES_CHANGED, ());
+    let _ = crate::events::emit_to_main(app, &crate::events::payloads::DirtyTablesChanged {});
 }
 
 // ---------------------------------------------------------------------------
@@ -1458,11 +1480,7 @@ async fn auto_disable_rule(
             "lastError": last_error,
         }),
     );
-    let _ = app.emit_to(
-        "main",
-        crate::event_names::EVENT_CRDT_DIRTY_TABLES_CHANGED,
-        (),
-    );
+    let _ = crate::events::emit_to_main(app, &crate::events::payloads::DirtyTablesChanged {});
 }
 
 /// Run periodic sync for a rule. Cancellable via `CancellationToken`.
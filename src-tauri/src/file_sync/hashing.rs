@@ -135,8 +135,10 @@ where
         .get(&key)
         .cloned()
     {
+        crate::metrics::record_cache(crate::metrics::CACHE_FILE_HASH, true);
         return Ok(hash);
     }
+    crate::metrics::record_cache(crate::metrics::CACHE_FILE_HASH, false);
 
     let mut reader = open_reader()?;
     let mut hasher = Sha256::new();
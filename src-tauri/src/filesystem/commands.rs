@@ -4,22 +4,29 @@
 //!
 //! These commands provide low-level filesystem access that can be used by
 //! extensions and other parts of the application for local file operations.
+//!
+//! Every path argument goes through [`VaultPath`], so the same command works
+//! with desktop paths and with the Content URIs Android hands out for files
+//! picked through the Storage Access Framework.
 
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::UNIX_EPOCH;
-use tauri::State;
+use tauri::{AppHandle, State};
 use thiserror::Error;
 use ts_rs::TS;
 
+use crate::error_code::{serialize_tagged, CodedError, ErrorCode};
 use crate::AppState;
 
 // ============================================================================
 // Error Types
 // ============================================================================
 
-#[derive(Debug, Error)]
+#[derive(Debug, Error, Serialize)]
+#[serde(tag = "type", content = "details")]
+#[serde(remote = "Self")]
 pub enum FsError {
     #[error("File not found: {path}")]
     NotFound { path: String },
@@ -39,11 +46,49 @@ pub enum FsError {
     #[error("Not a file: {path}")]
     NotAFile { path: String },
 
+    #[error("Not supported for Content URIs: {operation}")]
+    UnsupportedForContentUri { operation: String },
+
     #[allow(dead_code)]
     #[error("Dialog cancelled by user")]
     DialogCancelled,
 }
 
+impl CodedError for FsError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            FsError::NotFound { .. } => ErrorCode::ResourceNotFound,
+            FsError::PermissionDenied { .. } => ErrorCode::PermissionDenied,
+            FsError::IoError { .. } => ErrorCode::Filesystem,
+            FsError::InvalidPath { .. }
+            | FsError::NotADirectory { .. }
+            | FsError::NotAFile { .. } => ErrorCode::Validation,
+            FsError::UnsupportedForContentUri { .. } => ErrorCode::Unsupported,
+            FsError::DialogCancelled => ErrorCode::Cancelled,
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        self.tagged().get("details").cloned()
+    }
+}
+
+impl FsError {
+    /// The derived `{ type, details }` representation
+    fn tagged(&self) -> serde_json::Value {
+        FsError::serialize(self, serde_json::value::Serializer).unwrap_or_default()
+    }
+}
+
+impl Serialize for FsError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_tagged(self, &self.tagged(), serializer)
+    }
+}
+
 impl From<std::io::Error> for FsError {
     fn from(e: std::io::Error) -> Self {
         match e.kind() {
@@ -60,15 +105,6 @@ impl From<std::io::Error> for FsError {
     }
 }
 
-impl Serialize for FsError {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_str(&self.to_string())
-    }
-}
-
 // ============================================================================
 // Types
 // ============================================================================
@@ -123,62 +159,418 @@ pub struct DirEntry {
 }
 
 // ============================================================================
-// Commands
+// Paths
 // ============================================================================
 
-/// Read file contents as base64
-#[tauri::command]
-pub async fn filesystem_read_file(
-    _state: State<'_, AppState>,
-    path: String,
-    #[allow(unused_variables)] app_handle: tauri::AppHandle,
-) -> Result<String, FsError> {
-    // Android: handle Content URIs (JSON format from folder picker)
+const CONTENT_URI_SCHEME: &str = "content://";
+
+/// A path argument of a filesystem command.
+///
+/// On Android, files and folders picked through the Storage Access Framework
+/// have no filesystem path, only a Content URI. The frontend passes those
+/// either as the JSON envelope `android_fs` serializes (`{"uri":"content://…"}`)
+/// or as a bare `content://` URI; everything else is a regular path.
+/// Content URIs name an existing document: writes need the document to exist,
+/// mkdir only accepts an existing directory, and rename and copying a
+/// directory are local-only.
+#[derive(Debug, Clone)]
+pub enum VaultPath {
+    Local(PathBuf),
     #[cfg(target_os = "android")]
-    if path.starts_with('{') {
-        let handle = app_handle.clone();
-        return tokio::task::spawn_blocking(move || read_file_android(&handle, &path))
-            .await
-            .unwrap_or_else(|e| Err(FsError::IoError { reason: e.to_string() }));
+    ContentUri(tauri_plugin_android_fs::FileUri),
+}
+
+impl VaultPath {
+    /// True for both forms of Content URI, on every platform.
+    pub fn is_content_uri(path: &str) -> bool {
+        path.starts_with('{') || path.starts_with(CONTENT_URI_SCHEME)
     }
 
-    let path_ref = Path::new(&path);
+    /// Parses a path argument. Content URIs are rejected outside Android.
+    pub fn parse(path: &str) -> Result<Self, FsError> {
+        if path.is_empty() {
+            return Err(FsError::InvalidPath {
+                reason: "Path is empty".to_string(),
+            });
+        }
+        if !Self::is_content_uri(path) {
+            return Ok(Self::Local(PathBuf::from(path)));
+        }
+        parse_content_uri(path)
+    }
 
-    if !path_ref.exists() {
-        return Err(FsError::NotFound { path });
+    /// The filesystem path, or an error naming `operation` for Content URIs.
+    pub fn local(&self, #[allow(unused_variables)] operation: &str) -> Result<&Path, FsError> {
+        match self {
+            Self::Local(path) => Ok(path),
+            #[cfg(target_os = "android")]
+            Self::ContentUri(_) => Err(FsError::UnsupportedForContentUri {
+                operation: operation.to_string(),
+            }),
+        }
     }
 
-    if !path_ref.is_file() {
-        return Err(FsError::NotAFile { path });
+    fn display(&self) -> String {
+        match self {
+            Self::Local(path) => path.display().to_string(),
+            #[cfg(target_os = "android")]
+            Self::ContentUri(uri) => uri.to_json_string().unwrap_or_else(|_| format!("{uri:?}")),
+        }
     }
 
-    let data = fs::read(path_ref).map_err(|e| FsError::IoError {
-        reason: format!("Failed to read '{}': {}", path, e),
-    })?;
+    pub fn exists(&self, #[allow(unused_variables)] app_handle: &AppHandle) -> bool {
+        match self {
+            Self::Local(path) => path.exists(),
+            #[cfg(target_os = "android")]
+            Self::ContentUri(uri) => {
+                use tauri_plugin_android_fs::AndroidFsExt;
+                app_handle.android_fs().get_info(uri).is_ok()
+            }
+        }
+    }
 
-    // Return as base64
-    use base64::{engine::general_purpose::STANDARD, Engine};
-    Ok(STANDARD.encode(&data))
+    pub fn read(
+        &self,
+        #[allow(unused_variables)] app_handle: &AppHandle,
+    ) -> Result<Vec<u8>, FsError> {
+        match self {
+            Self::Local(path) => {
+                if !path.exists() {
+                    return Err(FsError::NotFound {
+                        path: self.display(),
+                    });
+                }
+                if !path.is_file() {
+                    return Err(FsError::NotAFile {
+                        path: self.display(),
+                    });
+                }
+                fs::read(path).map_err(|e| FsError::IoError {
+                    reason: format!("Failed to read '{}': {}", path.display(), e),
+                })
+            }
+            #[cfg(target_os = "android")]
+            Self::ContentUri(uri) => {
+                use tauri_plugin_android_fs::AndroidFsExt;
+                app_handle
+                    .android_fs()
+                    .read(uri)
+                    .map_err(|e| FsError::IoError {
+                        reason: format!("Failed to read Android file: {:?}", e),
+                    })
+            }
+        }
+    }
+
+    /// Writes `bytes`, replacing the previous contents. Local parent
+    /// directories are created as needed; a Content URI must already exist.
+    pub fn write(
+        &self,
+        #[allow(unused_variables)] app_handle: &AppHandle,
+        bytes: &[u8],
+    ) -> Result<(), FsError> {
+        match self {
+            Self::Local(path) => {
+                if let Some(parent) = path.parent() {
+                    if !parent.exists() {
+                        fs::create_dir_all(parent).map_err(|e| FsError::IoError {
+                            reason: format!("Failed to create parent directories: {}", e),
+                        })?;
+                    }
+                }
+                fs::write(path, bytes).map_err(|e| FsError::IoError {
+                    reason: format!("Failed to write '{}': {}", path.display(), e),
+                })
+            }
+            #[cfg(target_os = "android")]
+            Self::ContentUri(uri) => {
+                use std::io::Write;
+                use tauri_plugin_android_fs::AndroidFsExt;
+
+                let mut file = app_handle
+                    .android_fs()
+                    .open_file_writable(uri)
+                    .map_err(|e| FsError::IoError {
+                        reason: format!("Failed to open Android file for writing: {:?}", e),
+                    })?;
+                file.write_all(bytes)
+                    .and_then(|_| file.flush())
+                    .map_err(|e| FsError::IoError {
+                        reason: format!("Failed to write Android file: {}", e),
+                    })
+            }
+        }
+    }
+
+    /// Unsorted directory entries. Entries of a Content URI directory carry
+    /// their own Content URI as `path`.
+    pub fn read_dir(
+        &self,
+        #[allow(unused_variables)] app_handle: &AppHandle,
+    ) -> Result<Vec<DirEntry>, FsError> {
+        match self {
+            Self::Local(path) => {
+                if !path.exists() {
+                    return Err(FsError::NotFound {
+                        path: self.display(),
+                    });
+                }
+                if !path.is_dir() {
+                    return Err(FsError::NotADirectory {
+                        path: self.display(),
+                    });
+                }
+
+                let mut entries = Vec::new();
+                for entry in fs::read_dir(path).map_err(|e| FsError::IoError {
+                    reason: format!("Failed to read directory '{}': {}", path.display(), e),
+                })? {
+                    let entry = entry.map_err(|e| FsError::IoError {
+                        reason: format!("Failed to read entry: {}", e),
+                    })?;
+
+                    let metadata = entry.metadata().map_err(|e| FsError::IoError {
+                        reason: format!("Failed to read metadata: {}", e),
+                    })?;
+
+                    entries.push(DirEntry {
+                        name: entry.file_name().to_string_lossy().to_string(),
+                        path: entry.path().to_string_lossy().to_string(),
+                        is_file: metadata.is_file(),
+                        is_directory: metadata.is_dir(),
+                        size: if metadata.is_file() {
+                            metadata.len()
+                        } else {
+                            0
+                        },
+                        modified: to_millis(metadata.modified().ok()),
+                    });
+                }
+                Ok(entries)
+            }
+            #[cfg(target_os = "android")]
+            Self::ContentUri(uri) => {
+                use tauri_plugin_android_fs::AndroidFsExt;
+
+                let dir_entries =
+                    app_handle
+                        .android_fs()
+                        .read_dir(uri)
+                        .map_err(|e| FsError::IoError {
+                            reason: format!("Failed to read Android directory: {:?}", e),
+                        })?;
+
+                Ok(dir_entries
+                    .into_iter()
+                    .filter_map(|entry: tauri_plugin_android_fs::Entry| {
+                        let is_dir = entry.is_dir();
+                        Some(DirEntry {
+                            name: entry.name().to_string(),
+                            path: entry.uri().to_json_string().ok()?,
+                            is_file: !is_dir,
+                            is_directory: is_dir,
+                            size: entry.file_len().unwrap_or(0),
+                            modified: to_millis(Some(entry.last_modified())),
+                        })
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    pub fn stat(
+        &self,
+        #[allow(unused_variables)] app_handle: &AppHandle,
+    ) -> Result<FileStat, FsError> {
+        match self {
+            Self::Local(path) => {
+                if !path.exists() {
+                    return Err(FsError::NotFound {
+                        path: self.display(),
+                    });
+                }
+
+                let metadata = fs::metadata(path).map_err(|e| FsError::IoError {
+                    reason: format!("Failed to read metadata for '{}': {}", path.display(), e),
+                })?;
+
+                Ok(FileStat {
+                    size: metadata.len(),
+                    is_file: metadata.is_file(),
+                    is_directory: metadata.is_dir(),
+                    is_symlink: metadata.file_type().is_symlink(),
+                    modified: to_millis(metadata.modified().ok()),
+                    created: to_millis(metadata.created().ok()),
+                    readonly: metadata.permissions().readonly(),
+                })
+            }
+            #[cfg(target_os = "android")]
+            Self::ContentUri(uri) => {
+                use tauri_plugin_android_fs::AndroidFsExt;
+
+                let info =
+                    app_handle
+                        .android_fs()
+                        .get_info(uri)
+                        .map_err(|_| FsError::NotFound {
+                            path: self.display(),
+                        })?;
+                let is_dir = info.is_dir();
+
+                // The provider doesn't expose creation time or write access
+                Ok(FileStat {
+                    size: info.file_len().unwrap_or(0),
+                    is_file: !is_dir,
+                    is_directory: is_dir,
+                    is_symlink: false,
+                    modified: to_millis(Some(info.last_modified())),
+                    created: None,
+                    readonly: false,
+                })
+            }
+        }
+    }
+
+    pub fn remove(
+        &self,
+        #[allow(unused_variables)] app_handle: &AppHandle,
+        recursive: bool,
+    ) -> Result<(), FsError> {
+        match self {
+            Self::Local(path) => {
+                if !path.exists() {
+                    return Err(FsError::NotFound {
+                        path: self.display(),
+                    });
+                }
+
+                if path.is_dir() {
+                    let result = if recursive {
+                        fs::remove_dir_all(path)
+                    } else {
+                        fs::remove_dir(path)
+                    };
+                    result.map_err(|e| FsError::IoError {
+                        reason: format!("Failed to remove directory '{}': {}", path.display(), e),
+                    })
+                } else {
+                    fs::remove_file(path).map_err(|e| FsError::IoError {
+                        reason: format!("Failed to remove file '{}': {}", path.display(), e),
+                    })
+                }
+            }
+            #[cfg(target_os = "android")]
+            Self::ContentUri(uri) => {
+                use tauri_plugin_android_fs::AndroidFsExt;
+
+                let api = app_handle.android_fs();
+                let info = api.get_info(uri).map_err(|_| FsError::NotFound {
+                    path: self.display(),
+                })?;
+
+                let result = if info.is_dir() {
+                    if !recursive && !self.read_dir(app_handle)?.is_empty() {
+                        return Err(FsError::IoError {
+                            reason: format!("Directory not empty: {}", self.display()),
+                        });
+                    }
+                    api.remove_dir_all(uri)
+                } else {
+                    api.remove_file(uri)
+                };
+                result.map_err(|e| FsError::IoError {
+                    reason: format!("Failed to remove Android file: {:?}", e),
+                })
+            }
+        }
+    }
+
+    /// Display name: the basename of a local path, the provider's document
+    /// name for a Content URI.
+    pub fn file_name(
+        &self,
+        #[allow(unused_variables)] app_handle: &AppHandle,
+    ) -> Result<String, FsError> {
+        match self {
+            Self::Local(path) => path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map(|s| s.to_string())
+                .ok_or_else(|| FsError::InvalidPath {
+                    reason: format!("Could not extract file name from '{}'", path.display()),
+                }),
+            #[cfg(target_os = "android")]
+            Self::ContentUri(uri) => {
+                use tauri_plugin_android_fs::AndroidFsExt;
+                app_handle
+                    .android_fs()
+                    .get_name(uri)
+                    .map_err(|e| FsError::IoError {
+                        reason: format!("Failed to read file name from Content URI: {:?}", e),
+                    })
+            }
+        }
+    }
 }
 
-/// Android: Read file via Content URI using android_fs plugin
+/// Accepts the JSON envelope of `FileUri` and bare `content://` URIs.
 #[cfg(target_os = "android")]
-fn read_file_android(app_handle: &tauri::AppHandle, path_json: &str) -> Result<String, FsError> {
-    use tauri_plugin_android_fs::AndroidFsExt;
+fn parse_content_uri(path: &str) -> Result<VaultPath, FsError> {
+    let json = if path.starts_with('{') {
+        path.to_string()
+    } else {
+        serde_json::json!({ "uri": path, "documentTopTreeUri": null }).to_string()
+    };
+    tauri_plugin_android_fs::FileUri::from_json_str(&json)
+        .map(VaultPath::ContentUri)
+        .map_err(|e| FsError::InvalidPath {
+            reason: format!("Invalid Content URI: {:?}", e),
+        })
+}
 
-    let api = app_handle.android_fs();
+#[cfg(not(target_os = "android"))]
+fn parse_content_uri(path: &str) -> Result<VaultPath, FsError> {
+    Err(FsError::InvalidPath {
+        reason: format!("Content URIs are only supported on Android: {path}"),
+    })
+}
 
-    let uri = tauri_plugin_android_fs::FileUri::from_json_str(path_json)
-        .map_err(|e| FsError::IoError {
-            reason: format!("Invalid Content URI: {:?}", e),
-        })?;
+fn to_millis(time: Option<std::time::SystemTime>) -> Option<u64> {
+    time.and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+}
 
-    let bytes = api.read(&uri).map_err(|e| FsError::IoError {
-        reason: format!("Failed to read Android file: {:?}", e),
-    })?;
+/// Runs a filesystem operation off the async executor. Android Content URI
+/// calls go through JNI and block for as long as the provider takes.
+async fn run_blocking<T, F>(op: F) -> Result<T, FsError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, FsError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(op).await.unwrap_or_else(|e| {
+        Err(FsError::IoError {
+            reason: e.to_string(),
+        })
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
 
+/// Read file contents as base64
+#[tauri::command]
+pub async fn filesystem_read_file(
+    _state: State<'_, AppState>,
+    path: String,
+    app_handle: AppHandle,
+) -> Result<String, FsError> {
+    let path = VaultPath::parse(&path)?;
+    let data = run_blocking(move || path.read(&app_handle)).await?;
+
+    // Return as base64
     use base64::{engine::general_purpose::STANDARD, Engine};
-    Ok(STANDARD.encode(&bytes))
+    Ok(STANDARD.encode(&data))
 }
 
 /// Write file contents from base64
@@ -187,17 +579,9 @@ pub async fn filesystem_write_file(
     _state: State<'_, AppState>,
     path: String,
     data: String,
+    app_handle: AppHandle,
 ) -> Result<(), FsError> {
-    let path_ref = Path::new(&path);
-
-    // Create parent directories if needed
-    if let Some(parent) = path_ref.parent() {
-        if !parent.exists() {
-            fs::create_dir_all(parent).map_err(|e| FsError::IoError {
-                reason: format!("Failed to create parent directories: {}", e),
-            })?;
-        }
-    }
+    let path = VaultPath::parse(&path)?;
 
     // Decode base64
     use base64::{engine::general_purpose::STANDARD, Engine};
@@ -205,11 +589,7 @@ pub async fn filesystem_write_file(
         reason: format!("Invalid base64 data: {}", e),
     })?;
 
-    fs::write(path_ref, &bytes).map_err(|e| FsError::IoError {
-        reason: format!("Failed to write '{}': {}", path, e),
-    })?;
-
-    Ok(())
+    run_blocking(move || path.write(&app_handle, &bytes)).await
 }
 
 /// Read directory contents with optional pagination.
@@ -221,65 +601,17 @@ pub async fn filesystem_read_dir(
     path: String,
     offset: Option<usize>,
     limit: Option<usize>,
-    #[allow(unused_variables)] app_handle: tauri::AppHandle,
+    app_handle: AppHandle,
 ) -> Result<DirListing, FsError> {
-    // Android: handle Content URIs (JSON format from folder picker).
-    // Use spawn_blocking because android_fs JNI calls are synchronous and
-    // would otherwise block the Tokio executor (especially for large folders).
-    #[cfg(target_os = "android")]
-    if path.starts_with('{') {
-        let handle = app_handle.clone();
-        return tokio::task::spawn_blocking(move || read_dir_android(&handle, &path, offset, limit))
-            .await
-            .unwrap_or_else(|e| Err(FsError::IoError { reason: e.to_string() }));
-    }
-
-    let path_ref = Path::new(&path);
-
-    if !path_ref.exists() {
-        return Err(FsError::NotFound { path });
-    }
-
-    if !path_ref.is_dir() {
-        return Err(FsError::NotADirectory { path });
-    }
-
-    let mut entries = Vec::new();
-
-    for entry in fs::read_dir(path_ref).map_err(|e| FsError::IoError {
-        reason: format!("Failed to read directory '{}': {}", path, e),
-    })? {
-        let entry = entry.map_err(|e| FsError::IoError {
-            reason: format!("Failed to read entry: {}", e),
-        })?;
-
-        let metadata = entry.metadata().map_err(|e| FsError::IoError {
-            reason: format!("Failed to read metadata: {}", e),
-        })?;
-
-        let modified = metadata
-            .modified()
-            .ok()
-            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-            .map(|d| d.as_millis() as u64);
-
-        entries.push(DirEntry {
-            name: entry.file_name().to_string_lossy().to_string(),
-            path: entry.path().to_string_lossy().to_string(),
-            is_file: metadata.is_file(),
-            is_directory: metadata.is_dir(),
-            size: if metadata.is_file() { metadata.len() } else { 0 },
-            modified,
-        });
-    }
+    let path = VaultPath::parse(&path)?;
+    // Large Android folders take a while, so this must not run on the executor
+    let mut entries = run_blocking(move || path.read_dir(&app_handle)).await?;
 
     // Sort: directories first, then files, both alphabetically
-    entries.sort_by(|a, b| {
-        match (a.is_directory, b.is_directory) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-        }
+    entries.sort_by(|a, b| match (a.is_directory, b.is_directory) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
     });
 
     let total = entries.len();
@@ -295,78 +627,25 @@ pub async fn filesystem_read_dir(
     Ok(DirListing { entries, total })
 }
 
-/// Android: Read directory via Content URI using android_fs plugin
-#[cfg(target_os = "android")]
-fn read_dir_android(
-    app_handle: &tauri::AppHandle,
-    path_json: &str,
-    offset: Option<usize>,
-    limit: Option<usize>,
-) -> Result<DirListing, FsError> {
-    use tauri_plugin_android_fs::AndroidFsExt;
-
-    let api = app_handle.android_fs();
-
-    // Parse the JSON Content URI
-    let uri = tauri_plugin_android_fs::FileUri::from_json_str(path_json)
-        .map_err(|e| FsError::IoError {
-            reason: format!("Invalid Content URI: {:?}", e),
-        })?;
-
-    let dir_entries = api.read_dir(&uri).map_err(|e| FsError::IoError {
-        reason: format!("Failed to read Android directory: {:?}", e),
-    })?;
-
-    let mut entries: Vec<DirEntry> = dir_entries
-        .into_iter()
-        .filter_map(|entry: tauri_plugin_android_fs::Entry| {
-            let name = entry.name().to_string();
-            let is_dir = entry.is_dir();
-            let modified = entry.last_modified()
-                .duration_since(UNIX_EPOCH)
-                .ok()
-                .map(|d: std::time::Duration| d.as_millis() as u64);
-            let size = entry.file_len().unwrap_or(0);
-            let uri_json = entry.uri().to_json_string().ok()?;
-
-            Some(DirEntry {
-                name,
-                path: uri_json,
-                is_file: !is_dir,
-                is_directory: is_dir,
-                size,
-                modified,
-            })
-        })
-        .collect();
-
-    entries.sort_by(|a, b| {
-        match (a.is_directory, b.is_directory) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-        }
-    });
-
-    let total = entries.len();
-
-    let entries = match (offset, limit) {
-        (Some(off), Some(lim)) => entries.into_iter().skip(off).take(lim).collect(),
-        (Some(off), None) => entries.into_iter().skip(off).collect(),
-        (None, Some(lim)) => entries.into_iter().take(lim).collect(),
-        (None, None) => entries,
-    };
-
-    Ok(DirListing { entries, total })
-}
-
 /// Create a directory (and parent directories if needed)
 #[tauri::command]
 pub async fn filesystem_mkdir(
     _state: State<'_, AppState>,
     path: String,
+    app_handle: AppHandle,
 ) -> Result<(), FsError> {
-    fs::create_dir_all(&path).map_err(|e| FsError::IoError {
+    let vault_path = VaultPath::parse(&path)?;
+
+    // A Content URI names an existing document, there is nothing to create
+    if VaultPath::is_content_uri(&path) {
+        return run_blocking(move || match vault_path.stat(&app_handle)? {
+            stat if stat.is_directory => Ok(()),
+            _ => Err(FsError::NotADirectory { path }),
+        })
+        .await;
+    }
+
+    fs::create_dir_all(vault_path.local("mkdir")?).map_err(|e| FsError::IoError {
         reason: format!("Failed to create directory '{}': {}", path, e),
     })?;
 
@@ -379,30 +658,11 @@ pub async fn filesystem_remove(
     _state: State<'_, AppState>,
     path: String,
     recursive: Option<bool>,
+    app_handle: AppHandle,
 ) -> Result<(), FsError> {
-    let path_ref = Path::new(&path);
-
-    if !path_ref.exists() {
-        return Err(FsError::NotFound { path });
-    }
-
-    if path_ref.is_dir() {
-        if recursive.unwrap_or(false) {
-            fs::remove_dir_all(path_ref).map_err(|e| FsError::IoError {
-                reason: format!("Failed to remove directory '{}': {}", path, e),
-            })?;
-        } else {
-            fs::remove_dir(path_ref).map_err(|e| FsError::IoError {
-                reason: format!("Failed to remove directory '{}': {}", path, e),
-            })?;
-        }
-    } else {
-        fs::remove_file(path_ref).map_err(|e| FsError::IoError {
-            reason: format!("Failed to remove file '{}': {}", path, e),
-        })?;
-    }
-
-    Ok(())
+    let path = VaultPath::parse(&path)?;
+    let recursive = recursive.unwrap_or(false);
+    run_blocking(move || path.remove(&app_handle, recursive)).await
 }
 
 /// Check if a path exists
@@ -410,8 +670,10 @@ pub async fn filesystem_remove(
 pub async fn filesystem_exists(
     _state: State<'_, AppState>,
     path: String,
+    app_handle: AppHandle,
 ) -> Result<bool, FsError> {
-    Ok(Path::new(&path).exists())
+    let path = VaultPath::parse(&path)?;
+    run_blocking(move || Ok(path.exists(&app_handle))).await
 }
 
 /// Get file/directory metadata
@@ -419,38 +681,10 @@ pub async fn filesystem_exists(
 pub async fn filesystem_stat(
     _state: State<'_, AppState>,
     path: String,
+    app_handle: AppHandle,
 ) -> Result<FileStat, FsError> {
-    let path_ref = Path::new(&path);
-
-    if !path_ref.exists() {
-        return Err(FsError::NotFound { path });
-    }
-
-    let metadata = fs::metadata(path_ref).map_err(|e| FsError::IoError {
-        reason: format!("Failed to read metadata for '{}': {}", path, e),
-    })?;
-
-    let modified = metadata
-        .modified()
-        .ok()
-        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-        .map(|d| d.as_millis() as u64);
-
-    let created = metadata
-        .created()
-        .ok()
-        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-        .map(|d| d.as_millis() as u64);
-
-    Ok(FileStat {
-        size: metadata.len(),
-        is_file: metadata.is_file(),
-        is_directory: metadata.is_dir(),
-        is_symlink: metadata.file_type().is_symlink(),
-        modified,
-        created,
-        readonly: metadata.permissions().readonly(),
-    })
+    let path = VaultPath::parse(&path)?;
+    run_blocking(move || path.stat(&app_handle)).await
 }
 
 /// Open a folder selection dialog
@@ -459,7 +693,7 @@ pub async fn filesystem_select_folder(
     #[allow(unused_variables)] window: tauri::WebviewWindow,
     #[allow(unused_variables)] title: Option<String>,
     #[allow(unused_variables)] default_path: Option<String>,
-    #[allow(unused_variables)] app_handle: tauri::AppHandle,
+    #[allow(unused_variables)] app_handle: AppHandle,
 ) -> Result<Option<String>, FsError> {
     #[cfg(not(target_os = "android"))]
     {
@@ -517,7 +751,7 @@ pub async fn filesystem_select_file(
     #[allow(unused_variables)] default_path: Option<String>,
     #[allow(unused_variables)] filters: Option<Vec<(String, Vec<String>)>>,
     #[allow(unused_variables)] multiple: Option<bool>,
-    #[allow(unused_variables)] app_handle: tauri::AppHandle,
+    #[allow(unused_variables)] app_handle: AppHandle,
 ) -> Result<Option<Vec<String>>, FsError> {
     #[cfg(not(target_os = "android"))]
     {
@@ -652,29 +886,11 @@ pub async fn filesystem_select_file(
 /// Android: resolves Content URIs (JSON envelope from android_fs) via ContentResolver.
 #[tauri::command]
 pub async fn filesystem_get_file_name(
-    #[allow(unused_variables)] app_handle: tauri::AppHandle,
+    app_handle: AppHandle,
     path: String,
 ) -> Result<String, FsError> {
-    #[cfg(target_os = "android")]
-    if path.starts_with('{') {
-        use tauri_plugin_android_fs::{AndroidFsExt, FileUri};
-
-        let api = app_handle.android_fs();
-        let uri = FileUri::from_json_str(&path).map_err(|e| FsError::IoError {
-            reason: format!("Invalid Content URI: {:?}", e),
-        })?;
-        return api.get_name(&uri).map_err(|e| FsError::IoError {
-            reason: format!("Failed to read file name from Content URI: {:?}", e),
-        });
-    }
-
-    Path::new(&path)
-        .file_name()
-        .and_then(|n| n.to_str())
-        .map(|s| s.to_string())
-        .ok_or_else(|| FsError::InvalidPath {
-            reason: format!("Could not extract file name from '{}'", path),
-        })
+    let path = VaultPath::parse(&path)?;
+    run_blocking(move || path.file_name(&app_handle)).await
 }
 
 /// Rename/move a file or directory
@@ -684,14 +900,16 @@ pub async fn filesystem_rename(
     from: String,
     to: String,
 ) -> Result<(), FsError> {
-    let from_path = Path::new(&from);
+    let from_vault_path = VaultPath::parse(&from)?;
+    let to_vault_path = VaultPath::parse(&to)?;
+    let from_path = from_vault_path.local("rename")?;
+    let to_path = to_vault_path.local("rename")?;
 
     if !from_path.exists() {
         return Err(FsError::NotFound { path: from });
     }
 
     // Create parent directories for destination if needed
-    let to_path = Path::new(&to);
     if let Some(parent) = to_path.parent() {
         if !parent.exists() {
             fs::create_dir_all(parent).map_err(|e| FsError::IoError {
@@ -700,7 +918,7 @@ pub async fn filesystem_rename(
         }
     }
 
-    fs::rename(&from, &to).map_err(|e| FsError::IoError {
+    fs::rename(from_path, to_path).map_err(|e| FsError::IoError {
         reason: format!("Failed to rename '{}' to '{}': {}", from, to, e),
     })?;
 
@@ -714,8 +932,10 @@ pub async fn filesystem_copy_dir(
     from: String,
     to: String,
 ) -> Result<(), FsError> {
-    let from_path = Path::new(&from);
-    let to_path = Path::new(&to);
+    let from_vault_path = VaultPath::parse(&from)?;
+    let to_vault_path = VaultPath::parse(&to)?;
+    let from_path = from_vault_path.local("copy directory")?;
+    let to_path = to_vault_path.local("copy directory")?;
 
     if !from_path.exists() {
         return Err(FsError::NotFound { path: from });
@@ -745,25 +965,48 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
     Ok(())
 }
 
-/// Copy a file
+/// Copy a file. Either side may be a Content URI; those are copied through
+/// memory since the provider has no native copy.
 #[tauri::command]
 pub async fn filesystem_copy(
     _state: State<'_, AppState>,
     from: String,
     to: String,
+    app_handle: AppHandle,
 ) -> Result<(), FsError> {
-    let from_path = Path::new(&from);
+    let from_path = VaultPath::parse(&from)?;
+    let to_path = VaultPath::parse(&to)?;
+
+    run_blocking(move || copy_file(&app_handle, &from_path, &to_path)).await
+}
+
+fn copy_file(
+    #[allow(unused_variables)] app_handle: &AppHandle,
+    from: &VaultPath,
+    to: &VaultPath,
+) -> Result<(), FsError> {
+    let (from_path, to_path) = match (from, to) {
+        (VaultPath::Local(from_path), VaultPath::Local(to_path)) => (from_path, to_path),
+        #[cfg(target_os = "android")]
+        _ => {
+            let bytes = from.read(app_handle)?;
+            return to.write(app_handle, &bytes);
+        }
+    };
 
     if !from_path.exists() {
-        return Err(FsError::NotFound { path: from });
+        return Err(FsError::NotFound {
+            path: from.display(),
+        });
     }
 
     if !from_path.is_file() {
-        return Err(FsError::NotAFile { path: from });
+        return Err(FsError::NotAFile {
+            path: from.display(),
+        });
     }
 
     // Create parent directories for destination if needed
-    let to_path = Path::new(&to);
     if let Some(parent) = to_path.parent() {
         if !parent.exists() {
             fs::create_dir_all(parent).map_err(|e| FsError::IoError {
@@ -772,9 +1015,47 @@ pub async fn filesystem_copy(
         }
     }
 
-    fs::copy(&from, &to).map_err(|e| FsError::IoError {
-        reason: format!("Failed to copy '{}' to '{}': {}", from, to, e),
+    fs::copy(from_path, to_path).map_err(|e| FsError::IoError {
+        reason: format!(
+            "Failed to copy '{}' to '{}': {}",
+            from.display(),
+            to.display(),
+            e
+        ),
     })?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_uri_detection() {
+        assert!(VaultPath::is_content_uri(r#"{"uri":"content://x"}"#));
+        assert!(VaultPath::is_content_uri(
+            "content://com.android.providers/doc/1"
+        ));
+        assert!(!VaultPath::is_content_uri("/home/user/content://x"));
+        assert!(!VaultPath::is_content_uri("C:\\Users\\me\\file.txt"));
+    }
+
+    #[test]
+    fn test_parse_local_and_content_paths() {
+        let path = VaultPath::parse("/tmp/notes.txt").unwrap();
+        assert_eq!(path.local("read").unwrap(), Path::new("/tmp/notes.txt"));
+
+        assert!(matches!(
+            VaultPath::parse(""),
+            Err(FsError::InvalidPath { .. })
+        ));
+
+        // Desktop builds have no ContentResolver to hand the URI to
+        #[cfg(not(target_os = "android"))]
+        assert!(matches!(
+            VaultPath::parse("content://com.android.providers/doc/1"),
+            Err(FsError::InvalidPath { .. })
+        ));
+    }
+}
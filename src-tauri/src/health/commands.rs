@@ -0,0 +1,166 @@
+// src-tauri/src/health/commands.rs
+//!
+//! Health check command
+
+use super::{
+    database_health, extension_manager_health, hlc_health, scheduler_health,
+    storage_backends_health, sync_orchestrator_health, vault_check, HealthReport, HealthStatus,
+    Subsystem, SubsystemHealth,
+};
+use crate::automation::store as automation_store;
+use crate::database::core::{select_with_crdt, with_connection};
+use crate::database::error::DatabaseError;
+use crate::database::row::get_bool;
+use crate::extension::core::manager::MissingExtension;
+use crate::remote_storage::queries::SQL_LIST_BACKENDS;
+use crate::remote_storage::usage::usage_report;
+use crate::AppState;
+use tauri::{AppHandle, Manager};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+/// Loaded, missing and revoked extensions of the last load
+fn extension_manager_state(
+    state: &AppState,
+) -> Result<(usize, Vec<MissingExtension>, usize), String> {
+    let manager = &state.extension_manager;
+    let loaded = manager
+        .available_extensions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .len();
+    let missing = manager
+        .missing_extensions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone();
+    let revoked = manager
+        .revoked_extensions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .len();
+    Ok((loaded, missing, revoked))
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+async fn check_external_bridge(state: &AppState) -> SubsystemHealth {
+    let bridge = state.external_bridge.lock().await;
+    let detail = if bridge.is_running() {
+        format!(
+            "Listening on port {}, {} clients connected",
+            bridge.get_port(),
+            bridge.connected_client_count().await
+        )
+    } else {
+        "Stopped".to_string()
+    };
+    SubsystemHealth::new(Subsystem::ExternalBridge, HealthStatus::Ok, detail)
+}
+
+#[cfg(any(target_os = "android", target_os = "ios"))]
+async fn check_external_bridge(_state: &AppState) -> SubsystemHealth {
+    SubsystemHealth::new(
+        Subsystem::ExternalBridge,
+        HealthStatus::Ok,
+        "Not available on this platform",
+    )
+}
+
+fn check_file_watchers(state: &AppState) -> SubsystemHealth {
+    match state.file_watcher.watch_counts() {
+        Ok((rules, extensions)) => SubsystemHealth::new(
+            Subsystem::FileWatchers,
+            HealthStatus::Ok,
+            format!("{rules} sync rule watches, {extensions} extension watches"),
+        ),
+        Err(e) => SubsystemHealth::new(Subsystem::FileWatchers, HealthStatus::Error, e),
+    }
+}
+
+async fn check_sync_orchestrator(state: &AppState) -> SubsystemHealth {
+    let orchestrators = state.sync_orchestrators.lock().await;
+    let finished = orchestrators
+        .values()
+        .filter(|handle| handle.is_finished())
+        .count();
+    sync_orchestrator_health(orchestrators.len() - finished, finished)
+}
+
+fn check_storage_backends(state: &AppState) -> Result<SubsystemHealth, DatabaseError> {
+    let rows = select_with_crdt(SQL_LIST_BACKENDS.clone(), vec![], &state.db)?;
+    let enabled = rows.iter().filter(|row| get_bool(row, 3)).count();
+
+    let report = with_connection(&state.db, |conn| usage_report(conn))?;
+    let over_quota: Vec<String> = report
+        .backends
+        .iter()
+        .filter(|usage| usage.quota_bytes.is_some_and(|quota| usage.bytes > quota))
+        .map(|usage| {
+            usage
+                .name
+                .clone()
+                .unwrap_or_else(|| usage.backend_id.clone())
+        })
+        .collect();
+
+    Ok(storage_backends_health(
+        enabled,
+        rows.len() - enabled,
+        &over_quota,
+    ))
+}
+
+fn check_scheduler(state: &AppState) -> Result<SubsystemHealth, DatabaseError> {
+    let rules = with_connection(&state.db, |conn| automation_store::list(conn))?;
+    let enabled: Vec<_> = rules.iter().filter(|rule| rule.enabled).collect();
+    let failing: Vec<String> = enabled
+        .iter()
+        .filter(|rule| rule.last_error.is_some())
+        .map(|rule| rule.name.clone())
+        .collect();
+    Ok(scheduler_health(enabled.len(), &failing))
+}
+
+/// Status of all subsystems for the diagnostics page
+#[tauri::command]
+pub async fn app_health_check(app_handle: AppHandle) -> HealthReport {
+    let state = app_handle.state::<AppState>();
+    let database = database_health(with_connection(&state.db, |conn| {
+        conn.query_row("SELECT 1", [], |_| Ok(()))
+            .map_err(DatabaseError::from)
+    }));
+    let vault_open = database.status == HealthStatus::Ok;
+
+    let hlc_initialized = state
+        .hlc
+        .lock()
+        .map_err(|e| e.to_string())
+        .and_then(|hlc| hlc.is_initialized().map_err(|e| e.to_string()));
+    let hlc = hlc_health(hlc_initialized, vault_open);
+
+    let (extension_manager, missing_extensions) = match extension_manager_state(&state) {
+        Ok((loaded, missing, revoked)) => {
+            (extension_manager_health(loaded, &missing, revoked), missing)
+        }
+        Err(e) => (
+            SubsystemHealth::new(Subsystem::ExtensionManager, HealthStatus::Error, e),
+            Vec::new(),
+        ),
+    };
+
+    let subsystems = vec![
+        database,
+        hlc,
+        extension_manager,
+        check_external_bridge(&state).await,
+        check_file_watchers(&state),
+        check_sync_orchestrator(&state).await,
+        vault_check(Subsystem::StorageBackends, check_storage_backends(&state)),
+        vault_check(Subsystem::Scheduler, check_scheduler(&state)),
+    ];
+
+    let checked_at = OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .unwrap_or_default();
+    HealthReport::new(subsystems, missing_extensions, checked_at)
+}
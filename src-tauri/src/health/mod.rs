@@ -0,0 +1,234 @@
+// src-tauri/src/health/mod.rs
+//!
+//! Health check
+//!
+//! `app_health_check` collects the state of every long-lived subsystem into
+//! one report for the diagnostics page. Each check is cheap and read-only: it
+//! looks at in-memory state or runs a single query, it never probes remote
+//! endpoints. A subsystem is `degraded` when it works but needs attention
+//! (missing extensions, a failing automation rule, no vault open) and
+//! `error` when it is unusable.
+
+pub mod commands;
+
+#[cfg(test)]
+mod tests;
+
+use crate::database::error::DatabaseError;
+use crate::extension::core::manager::MissingExtension;
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Detail of checks that need the vault database
+const NO_VAULT: &str = "Not checked, no vault open";
+
+/// Status of one subsystem, ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Ok,
+    Degraded,
+    Error,
+}
+
+/// Subsystems covered by the health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum Subsystem {
+    Database,
+    Hlc,
+    ExtensionManager,
+    ExternalBridge,
+    FileWatchers,
+    SyncOrchestrator,
+    StorageBackends,
+    Scheduler,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsystemHealth {
+    pub subsystem: Subsystem,
+    pub status: HealthStatus,
+    /// Human-readable explanation, shown next to the status
+    pub detail: String,
+}
+
+impl SubsystemHealth {
+    fn new(subsystem: Subsystem, status: HealthStatus, detail: impl Into<String>) -> Self {
+        Self {
+            subsystem,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Result of `app_health_check`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    /// Worst status of all subsystems
+    pub status: HealthStatus,
+    pub subsystems: Vec<SubsystemHealth>,
+    /// Installed extensions whose files are missing on this device
+    pub missing_extensions: Vec<MissingExtension>,
+    /// RFC 3339 time of the check
+    pub checked_at: String,
+}
+
+impl HealthReport {
+    pub fn new(
+        subsystems: Vec<SubsystemHealth>,
+        missing_extensions: Vec<MissingExtension>,
+        checked_at: String,
+    ) -> Self {
+        let status = subsystems
+            .iter()
+            .map(|s| s.status)
+            .max()
+            .unwrap_or(HealthStatus::Ok);
+        Self {
+            status,
+            subsystems,
+            missing_extensions,
+            checked_at,
+        }
+    }
+}
+
+/// Whether `error` only means that no vault is open
+pub fn is_vault_closed(error: &DatabaseError) -> bool {
+    matches!(error, DatabaseError::ConnectionError { .. })
+}
+
+/// Health of the vault connection from the result of a trivial query
+pub fn database_health(probe: Result<(), DatabaseError>) -> SubsystemHealth {
+    match probe {
+        Ok(()) => SubsystemHealth::new(Subsystem::Database, HealthStatus::Ok, "Vault open"),
+        Err(e) if is_vault_closed(&e) => {
+            SubsystemHealth::new(Subsystem::Database, HealthStatus::Degraded, "No vault open")
+        }
+        Err(e) => SubsystemHealth::new(Subsystem::Database, HealthStatus::Error, e.to_string()),
+    }
+}
+
+/// Health of the HLC. It is initialized while a vault is open.
+pub fn hlc_health(initialized: Result<bool, String>, vault_open: bool) -> SubsystemHealth {
+    let (status, detail) = match (initialized, vault_open) {
+        (Err(e), _) => (HealthStatus::Error, e),
+        (Ok(true), _) => (HealthStatus::Ok, "Initialized".to_string()),
+        (Ok(false), true) => (
+            HealthStatus::Error,
+            "Not initialized although a vault is open".to_string(),
+        ),
+        (Ok(false), false) => (HealthStatus::Degraded, NO_VAULT.to_string()),
+    };
+    SubsystemHealth::new(Subsystem::Hlc, status, detail)
+}
+
+/// Health of the extension manager from the outcome of the last load
+pub fn extension_manager_health(
+    loaded: usize,
+    missing: &[MissingExtension],
+    revoked: usize,
+) -> SubsystemHealth {
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        let names: Vec<&str> = missing.iter().map(|m| m.name.as_str()).collect();
+        problems.push(format!("{} missing ({})", missing.len(), names.join(", ")));
+    }
+    if revoked > 0 {
+        problems.push(format!("{revoked} revoked"));
+    }
+
+    if problems.is_empty() {
+        SubsystemHealth::new(
+            Subsystem::ExtensionManager,
+            HealthStatus::Ok,
+            format!("{loaded} extensions loaded"),
+        )
+    } else {
+        SubsystemHealth::new(
+            Subsystem::ExtensionManager,
+            HealthStatus::Degraded,
+            format!("{loaded} extensions loaded, {}", problems.join(", ")),
+        )
+    }
+}
+
+/// Health of the background sync orchestrators. Handles whose task already
+/// finished without being stopped point to a crashed loop.
+pub fn sync_orchestrator_health(running: usize, finished: usize) -> SubsystemHealth {
+    if finished > 0 {
+        SubsystemHealth::new(
+            Subsystem::SyncOrchestrator,
+            HealthStatus::Degraded,
+            format!("{running} running, {finished} stopped unexpectedly"),
+        )
+    } else {
+        SubsystemHealth::new(
+            Subsystem::SyncOrchestrator,
+            HealthStatus::Ok,
+            format!("{running} running"),
+        )
+    }
+}
+
+/// Health of the storage backends: `over_quota` lists the names of
+/// backends that exceed their quota.
+pub fn storage_backends_health(
+    enabled: usize,
+    disabled: usize,
+    over_quota: &[String],
+) -> SubsystemHealth {
+    let summary = format!("{enabled} enabled, {disabled} disabled");
+    if over_quota.is_empty() {
+        SubsystemHealth::new(Subsystem::StorageBackends, HealthStatus::Ok, summary)
+    } else {
+        SubsystemHealth::new(
+            Subsystem::StorageBackends,
+            HealthStatus::Degraded,
+            format!("{summary}, over quota: {}", over_quota.join(", ")),
+        )
+    }
+}
+
+/// Health of the automation scheduler: `failing` lists the names of enabled
+/// rules whose last run failed.
+pub fn scheduler_health(enabled_rules: usize, failing: &[String]) -> SubsystemHealth {
+    if failing.is_empty() {
+        SubsystemHealth::new(
+            Subsystem::Scheduler,
+            HealthStatus::Ok,
+            format!("{enabled_rules} enabled rules"),
+        )
+    } else {
+        SubsystemHealth::new(
+            Subsystem::Scheduler,
+            HealthStatus::Degraded,
+            format!(
+                "{enabled_rules} enabled rules, last run failed: {}",
+                failing.join(", ")
+            ),
+        )
+    }
+}
+
+/// Result of a check that needs the vault database
+pub fn vault_check(
+    subsystem: Subsystem,
+    result: Result<SubsystemHealth, DatabaseError>,
+) -> SubsystemHealth {
+    match result {
+        Ok(health) => health,
+        Err(e) if is_vault_closed(&e) => {
+            SubsystemHealth::new(subsystem, HealthStatus::Degraded, NO_VAULT)
+        }
+        Err(e) => SubsystemHealth::new(subsystem, HealthStatus::Error, e.to_string()),
+    }
+}
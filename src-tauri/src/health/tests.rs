@@ -0,0 +1,112 @@
+//! Tests for the health check evaluation
+
+use super::*;
+
+fn missing(name: &str) -> MissingExtension {
+    MissingExtension {
+        id: format!("id-{name}"),
+        public_key: "pk".to_string(),
+        name: name.to_string(),
+        version: "1.0.0".to_string(),
+    }
+}
+
+fn closed() -> DatabaseError {
+    DatabaseError::ConnectionError {
+        reason: "Connection to vault failed".to_string(),
+    }
+}
+
+#[test]
+fn test_report_status_is_worst_subsystem() {
+    let report = HealthReport::new(
+        vec![
+            sync_orchestrator_health(1, 0),
+            scheduler_health(2, &["Backup".to_string()]),
+        ],
+        Vec::new(),
+        String::new(),
+    );
+    assert_eq!(report.status, HealthStatus::Degraded);
+
+    let report = HealthReport::new(
+        vec![
+            scheduler_health(0, &[]),
+            hlc_health(Err("poisoned".to_string()), true),
+        ],
+        Vec::new(),
+        String::new(),
+    );
+    assert_eq!(report.status, HealthStatus::Error);
+    assert_eq!(
+        HealthReport::new(Vec::new(), Vec::new(), String::new()).status,
+        HealthStatus::Ok
+    );
+}
+
+#[test]
+fn test_database_and_vault_checks_without_vault() {
+    assert_eq!(database_health(Ok(())).status, HealthStatus::Ok);
+    assert_eq!(
+        database_health(Err(closed())).status,
+        HealthStatus::Degraded
+    );
+    let broken = database_health(Err(DatabaseError::StatementError {
+        reason: "disk I/O error".to_string(),
+    }));
+    assert_eq!(broken.status, HealthStatus::Error);
+    assert!(broken.detail.contains("disk I/O error"));
+
+    let skipped = vault_check(Subsystem::Scheduler, Err(closed()));
+    assert_eq!(skipped.subsystem, Subsystem::Scheduler);
+    assert_eq!(skipped.status, HealthStatus::Degraded);
+}
+
+#[test]
+fn test_hlc_health() {
+    assert_eq!(hlc_health(Ok(true), true).status, HealthStatus::Ok);
+    assert_eq!(hlc_health(Ok(false), true).status, HealthStatus::Error);
+    assert_eq!(hlc_health(Ok(false), false).status, HealthStatus::Degraded);
+}
+
+#[test]
+fn test_extension_manager_lists_missing() {
+    let ok = extension_manager_health(3, &[], 0);
+    assert_eq!(ok.status, HealthStatus::Ok);
+    assert_eq!(ok.detail, "3 extensions loaded");
+
+    let degraded = extension_manager_health(2, &[missing("Notes"), missing("Tasks")], 1);
+    assert_eq!(degraded.status, HealthStatus::Degraded);
+    assert_eq!(
+        degraded.detail,
+        "2 extensions loaded, 2 missing (Notes, Tasks), 1 revoked"
+    );
+}
+
+#[test]
+fn test_orchestrator_and_storage_health() {
+    assert_eq!(sync_orchestrator_health(2, 0).status, HealthStatus::Ok);
+    assert_eq!(
+        sync_orchestrator_health(1, 1).status,
+        HealthStatus::Degraded
+    );
+
+    assert_eq!(storage_backends_health(2, 1, &[]).status, HealthStatus::Ok);
+    let over = storage_backends_health(1, 0, &["S3".to_string()]);
+    assert_eq!(over.status, HealthStatus::Degraded);
+    assert_eq!(over.detail, "1 enabled, 0 disabled, over quota: S3");
+}
+
+#[test]
+fn test_serialized_shape() {
+    let report = HealthReport::new(
+        vec![hlc_health(Ok(true), true)],
+        vec![missing("Notes")],
+        "2026-01-01T00:00:00Z".to_string(),
+    );
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["status"], "ok");
+    assert_eq!(json["subsystems"][0]["subsystem"], "hlc");
+    assert_eq!(json["missingExtensions"][0]["publicKey"], "pk");
+    assert_eq!(json["checkedAt"], "2026-01-01T00:00:00Z");
+}
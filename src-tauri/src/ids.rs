@@ -0,0 +1,19 @@
+// src-tauri/src/ids.rs
+//!
+//! UUID generation for row, transport and job IDs.
+//!
+//! Goes through [`new_uuid`] instead of `Uuid::new_v4()` so tests can make
+//! the IDs of sync, cleanup and scheduler code deterministic (see
+//! `test_support::ids`).
+
+use uuid::Uuid;
+
+/// A new random (v4) UUID, or the next one of the thread's deterministic
+/// sequence when a test installed one
+pub fn new_uuid() -> Uuid {
+    #[cfg(any(test, feature = "test-support"))]
+    if let Some(id) = crate::test_support::ids::next_uuid() {
+        return id;
+    }
+    Uuid::new_v4()
+}
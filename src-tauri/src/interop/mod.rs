@@ -0,0 +1,8 @@
+// src-tauri/src/interop/mod.rs
+//!
+//! Document interop
+//!
+//! Host-side reading and editing of common document formats, so extensions
+//! don't have to bundle their own (WASM) parsers.
+
+pub mod pdf;
@@ -0,0 +1,350 @@
+// src-tauri/src/interop/pdf/commands.rs
+//!
+//! PDF Commands
+//!
+//! `pdf_*` are internal commands for the host UI.
+//! `extension_pdf_*` are the permission-checked variants: source files
+//! require `fs` read permission, destinations `fs` read/write permission.
+
+use super::document::{self, MAX_SPLIT_PARTS};
+use super::error::PdfError;
+use super::forms;
+use super::types::{PdfFormField, PdfMetadata, PdfSplitPart, PdfText, PdfWriteResult};
+use crate::extension::error::ExtensionError;
+use crate::extension::permissions::manager::PermissionManager;
+use crate::extension::permissions::types::{Action, FsAction};
+use crate::extension::utils::{emit_permission_prompt_if_needed, resolve_extension_id};
+use crate::AppState;
+use lopdf::Document;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use tauri::{AppHandle, State, WebviewWindow};
+
+fn to_extension_error(e: PdfError) -> ExtensionError {
+    ExtensionError::ValidationError {
+        reason: e.to_string(),
+    }
+}
+
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, PdfError> + Send + 'static,
+) -> Result<T, PdfError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| PdfError::Internal {
+            reason: format!("PDF task failed: {e}"),
+        })?
+}
+
+/// Reads a file, enforcing `MAX_INPUT_BYTES` before loading it.
+fn read_file(path: &str) -> Result<Vec<u8>, PdfError> {
+    document::check_size(std::fs::metadata(path)?.len())?;
+    Ok(std::fs::read(path)?)
+}
+
+/// Saves `doc` via a sibling temp file that is renamed into place, so a
+/// failed write never leaves a truncated PDF and a destination equal to
+/// the source is safe.
+fn write_document(doc: &mut Document, destination: &str) -> Result<PdfWriteResult, PdfError> {
+    let bytes = document::save(doc)?;
+    let path = Path::new(destination);
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp = path.with_file_name(format!(".{name}.{}.tmp", uuid::Uuid::new_v4()));
+    let result = File::create(&temp).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        writer.write_all(&bytes)?;
+        writer
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+        std::fs::rename(&temp, path)
+    });
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e.into());
+    }
+    Ok(PdfWriteResult {
+        path: destination.to_string(),
+        page_count: document::page_count(doc),
+        size: bytes.len() as u64,
+    })
+}
+
+async fn get_metadata(path: String) -> Result<PdfMetadata, PdfError> {
+    blocking(move || {
+        let bytes = read_file(&path)?;
+        let doc = document::load(&bytes)?;
+        Ok(document::metadata(&doc, bytes.len() as u64))
+    })
+    .await
+}
+
+async fn extract_text(path: String) -> Result<PdfText, PdfError> {
+    blocking(move || document::extract_text(&read_file(&path)?)).await
+}
+
+async fn list_form_fields(path: String) -> Result<Vec<PdfFormField>, PdfError> {
+    blocking(move || Ok(forms::fields(&document::load(&read_file(&path)?)?))).await
+}
+
+async fn fill_form(
+    path: String,
+    values: BTreeMap<String, String>,
+    destination: String,
+) -> Result<PdfWriteResult, PdfError> {
+    blocking(move || {
+        let mut doc = document::load(&read_file(&path)?)?;
+        forms::fill(&mut doc, &values)?;
+        write_document(&mut doc, &destination)
+    })
+    .await
+}
+
+async fn merge(sources: Vec<String>, destination: String) -> Result<PdfWriteResult, PdfError> {
+    if sources.len() > document::MAX_MERGE_SOURCES {
+        return Err(PdfError::InvalidRequest {
+            reason: format!(
+                "at most {} documents can be merged",
+                document::MAX_MERGE_SOURCES
+            ),
+        });
+    }
+    blocking(move || {
+        let documents = sources
+            .iter()
+            .map(|path| document::load(&read_file(path)?))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut merged = document::merge(documents)?;
+        write_document(&mut merged, &destination)
+    })
+    .await
+}
+
+async fn split(source: String, parts: Vec<PdfSplitPart>) -> Result<Vec<PdfWriteResult>, PdfError> {
+    if parts.is_empty() || parts.len() > MAX_SPLIT_PARTS {
+        return Err(PdfError::InvalidRequest {
+            reason: format!("1 to {MAX_SPLIT_PARTS} parts are required"),
+        });
+    }
+    blocking(move || {
+        let doc = document::load(&read_file(&source)?)?;
+        document::ensure_writable(&doc)?;
+        // All ranges are checked before the first file is written
+        let mut documents = parts
+            .iter()
+            .map(|part| document::extract_pages(&doc, part.start, part.end))
+            .collect::<Result<Vec<_>, _>>()?;
+        documents
+            .iter_mut()
+            .zip(&parts)
+            .map(|(part_doc, part)| write_document(part_doc, &part.destination))
+            .collect()
+    })
+    .await
+}
+
+/// Page count, version and document information of a PDF.
+#[tauri::command]
+pub async fn pdf_get_metadata(path: String) -> Result<PdfMetadata, PdfError> {
+    get_metadata(path).await
+}
+
+/// Text of every page of a PDF.
+#[tauri::command]
+pub async fn pdf_extract_text(path: String) -> Result<PdfText, PdfError> {
+    extract_text(path).await
+}
+
+/// The AcroForm fields of a PDF with their current values.
+#[tauri::command]
+pub async fn pdf_list_form_fields(path: String) -> Result<Vec<PdfFormField>, PdfError> {
+    list_form_fields(path).await
+}
+
+/// Fills AcroForm fields (by fully qualified name) and writes the result to
+/// `destination`, which may equal `path`.
+#[tauri::command]
+pub async fn pdf_fill_form(
+    path: String,
+    values: BTreeMap<String, String>,
+    destination: String,
+) -> Result<PdfWriteResult, PdfError> {
+    fill_form(path, values, destination).await
+}
+
+/// Concatenates the pages of `sources`, in order, into `destination`.
+#[tauri::command]
+pub async fn pdf_merge(
+    sources: Vec<String>,
+    destination: String,
+) -> Result<PdfWriteResult, PdfError> {
+    merge(sources, destination).await
+}
+
+/// Writes page ranges of `source` to separate files.
+#[tauri::command]
+pub async fn pdf_split(
+    source: String,
+    parts: Vec<PdfSplitPart>,
+) -> Result<Vec<PdfWriteResult>, PdfError> {
+    split(source, parts).await
+}
+
+async fn check_permissions(
+    app_handle: &AppHandle,
+    state: &State<'_, AppState>,
+    extension_id: &str,
+    sources: &[&str],
+    destinations: &[&str],
+) -> Result<(), ExtensionError> {
+    let checks = sources
+        .iter()
+        .map(|p| (*p, FsAction::Read))
+        .chain(destinations.iter().map(|p| (*p, FsAction::ReadWrite)));
+    for (path, action) in checks {
+        let result = PermissionManager::check_filesystem_permission(
+            state,
+            extension_id,
+            Action::Filesystem(action),
+            Path::new(path),
+        )
+        .await;
+        if let Err(ref e) = result {
+            emit_permission_prompt_if_needed(app_handle, e);
+        }
+        result?;
+    }
+    Ok(())
+}
+
+/// PDF metadata on behalf of an extension (permission-checked).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_pdf_get_metadata(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    path: String,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<PdfMetadata, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    check_permissions(&app_handle, &state, &extension_id, &[path.as_str()], &[]).await?;
+    get_metadata(path).await.map_err(to_extension_error)
+}
+
+/// PDF text extraction on behalf of an extension (permission-checked).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_pdf_extract_text(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    path: String,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<PdfText, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    check_permissions(&app_handle, &state, &extension_id, &[path.as_str()], &[]).await?;
+    extract_text(path).await.map_err(to_extension_error)
+}
+
+/// PDF form fields on behalf of an extension (permission-checked).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_pdf_list_form_fields(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    path: String,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<Vec<PdfFormField>, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    check_permissions(&app_handle, &state, &extension_id, &[path.as_str()], &[]).await?;
+    list_form_fields(path).await.map_err(to_extension_error)
+}
+
+/// PDF form filling on behalf of an extension (permission-checked).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_pdf_fill_form(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    path: String,
+    values: BTreeMap<String, String>,
+    destination: String,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<PdfWriteResult, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    check_permissions(
+        &app_handle,
+        &state,
+        &extension_id,
+        &[path.as_str()],
+        &[destination.as_str()],
+    )
+    .await?;
+    fill_form(path, values, destination)
+        .await
+        .map_err(to_extension_error)
+}
+
+/// PDF merging on behalf of an extension (permission-checked).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_pdf_merge(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    sources: Vec<String>,
+    destination: String,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<PdfWriteResult, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let source_paths: Vec<&str> = sources.iter().map(String::as_str).collect();
+    check_permissions(
+        &app_handle,
+        &state,
+        &extension_id,
+        &source_paths,
+        &[destination.as_str()],
+    )
+    .await?;
+    merge(sources, destination)
+        .await
+        .map_err(to_extension_error)
+}
+
+/// PDF splitting on behalf of an extension (permission-checked).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_pdf_split(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    source: String,
+    parts: Vec<PdfSplitPart>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<Vec<PdfWriteResult>, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let destinations: Vec<&str> = parts.iter().map(|p| p.destination.as_str()).collect();
+    check_permissions(
+        &app_handle,
+        &state,
+        &extension_id,
+        &[source.as_str()],
+        &destinations,
+    )
+    .await?;
+    split(source, parts).await.map_err(to_extension_error)
+}
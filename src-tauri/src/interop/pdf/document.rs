@@ -0,0 +1,249 @@
+// src-tauri/src/interop/pdf/document.rs
+//!
+//! Loading, inspecting, merging and splitting PDF documents
+//!
+
+use super::error::PdfError;
+use super::types::{PdfMetadata, PdfText};
+use lopdf::{dictionary, Document, Object, ObjectId, StringFormat};
+
+/// Upper bound for a single input document.
+pub const MAX_INPUT_BYTES: u64 = 100 * 1024 * 1024;
+/// Upper bound for the documents of one merge.
+pub const MAX_MERGE_SOURCES: usize = 50;
+/// Upper bound for the outputs of one split.
+pub const MAX_SPLIT_PARTS: usize = 100;
+
+/// Page attributes a page may inherit from its `Pages` ancestors. They are
+/// copied onto the pages before the page tree is rebuilt.
+const INHERITABLE: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+pub fn check_size(size: u64) -> Result<(), PdfError> {
+    if size > MAX_INPUT_BYTES {
+        return Err(PdfError::TooLarge {
+            size,
+            max: MAX_INPUT_BYTES,
+        });
+    }
+    Ok(())
+}
+
+pub fn load(bytes: &[u8]) -> Result<Document, PdfError> {
+    check_size(bytes.len() as u64)?;
+    Ok(Document::load_mem(bytes)?)
+}
+
+/// Encrypted documents are read-only: saving them would write decrypted
+/// objects next to a stale `Encrypt` dictionary.
+pub fn ensure_writable(doc: &Document) -> Result<(), PdfError> {
+    if doc.is_encrypted() {
+        return Err(PdfError::Encrypted);
+    }
+    Ok(())
+}
+
+pub fn save(doc: &mut Document) -> Result<Vec<u8>, PdfError> {
+    let mut bytes = Vec::new();
+    doc.save_to(&mut bytes)?;
+    Ok(bytes)
+}
+
+pub fn page_count(doc: &Document) -> u32 {
+    doc.get_pages().len() as u32
+}
+
+/// Text of every page, in page order.
+pub fn extract_text(bytes: &[u8]) -> Result<PdfText, PdfError> {
+    check_size(bytes.len() as u64)?;
+    let pages = pdf_extract::extract_text_from_mem_by_pages(bytes).map_err(|e| {
+        PdfError::ExtractionFailed {
+            reason: e.to_string(),
+        }
+    })?;
+    Ok(PdfText { pages })
+}
+
+/// Decodes a PDF text string: UTF-16BE or UTF-8 with byte order mark,
+/// PDFDocEncoding (read as Latin-1) otherwise.
+pub(super) fn decode_text(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = utf16
+            .chunks_exact(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    if let Some(utf8) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return String::from_utf8_lossy(utf8).into_owned();
+    }
+    bytes.iter().map(|&b| char::from(b)).collect()
+}
+
+/// Encodes a PDF text string; non-ASCII text is written as UTF-16BE.
+pub(super) fn encode_text(text: &str) -> Object {
+    if text.is_ascii() {
+        return Object::string_literal(text);
+    }
+    let mut bytes = vec![0xFE, 0xFF];
+    bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+    Object::String(bytes, StringFormat::Hexadecimal)
+}
+
+/// Follows a reference; `None` for dangling references.
+pub(super) fn resolve<'a>(doc: &'a Document, object: &'a Object) -> Option<&'a Object> {
+    doc.dereference(object).ok().map(|(_, object)| object)
+}
+
+fn info_string(doc: &Document, info: &lopdf::Dictionary, key: &[u8]) -> Option<String> {
+    let value = resolve(doc, info.get(key).ok()?)?.as_str().ok()?;
+    let text = decode_text(value);
+    (!text.trim().is_empty()).then_some(text)
+}
+
+pub fn metadata(doc: &Document, size: u64) -> PdfMetadata {
+    let info = doc
+        .trailer
+        .get(b"Info")
+        .ok()
+        .and_then(|o| resolve(doc, o))
+        .and_then(|o| o.as_dict().ok());
+    let field = |key: &[u8]| info.and_then(|info| info_string(doc, info, key));
+
+    PdfMetadata {
+        page_count: page_count(doc),
+        version: doc.version.clone(),
+        size,
+        title: field(b"Title"),
+        author: field(b"Author"),
+        subject: field(b"Subject"),
+        keywords: field(b"Keywords"),
+        creator: field(b"Creator"),
+        producer: field(b"Producer"),
+        creation_date: field(b"CreationDate"),
+        modification_date: field(b"ModDate"),
+        encrypted: doc.is_encrypted(),
+        has_form: !super::forms::fields(doc).is_empty(),
+    }
+}
+
+/// Copies inherited attributes onto every page, so the pages survive
+/// losing their original page tree.
+fn flatten_inherited(doc: &mut Document) {
+    let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    for page_id in pages {
+        let Ok(page) = doc.get_dictionary(page_id) else {
+            continue;
+        };
+        let mut inherited = Vec::new();
+        for key in INHERITABLE {
+            if page.has(key) {
+                continue;
+            }
+            let mut parent = page.get(b"Parent").and_then(Object::as_reference).ok();
+            // Bounded walk, page trees of broken files may contain cycles
+            for _ in 0..64 {
+                let Some(node) = parent.and_then(|id| doc.get_dictionary(id).ok()) else {
+                    break;
+                };
+                if let Ok(value) = node.get(key) {
+                    inherited.push((key, value.clone()));
+                    break;
+                }
+                parent = node.get(b"Parent").and_then(Object::as_reference).ok();
+            }
+        }
+        if let Ok(page) = doc.get_dictionary_mut(page_id) {
+            for (key, value) in inherited {
+                page.set(key, value);
+            }
+        }
+    }
+}
+
+/// Concatenates the pages of `documents` into a new document.
+///
+/// Page content, resources and annotations are kept. Document-level
+/// structures (outlines, named destinations, the AcroForm) are not merged.
+pub fn merge(documents: Vec<Document>) -> Result<Document, PdfError> {
+    if documents.is_empty() {
+        return Err(PdfError::InvalidRequest {
+            reason: "nothing to merge".to_string(),
+        });
+    }
+    if documents.len() > MAX_MERGE_SOURCES {
+        return Err(PdfError::InvalidRequest {
+            reason: format!("at most {MAX_MERGE_SOURCES} documents can be merged"),
+        });
+    }
+
+    let version = documents
+        .iter()
+        .map(|d| d.version.clone())
+        .max()
+        .unwrap_or_else(|| "1.7".to_string());
+    let mut merged = Document::with_version(version);
+    let mut kids = Vec::new();
+    let mut next_id = 1;
+
+    for mut doc in documents {
+        ensure_writable(&doc)?;
+        doc.renumber_objects_with(next_id);
+        next_id = doc.max_id + 1;
+        flatten_inherited(&mut doc);
+        kids.extend(doc.get_pages().into_values());
+
+        // The page tree nodes and the catalog are rebuilt below
+        doc.objects.retain(|_, object| {
+            let kind = object
+                .as_dict()
+                .and_then(|d| d.get(b"Type"))
+                .and_then(Object::as_name)
+                .unwrap_or_default();
+            kind != b"Pages" && kind != b"Catalog"
+        });
+        merged.objects.extend(doc.objects);
+    }
+
+    let pages_id = (next_id, 0);
+    let catalog_id = (next_id + 1, 0);
+    for kid in &kids {
+        if let Ok(page) = merged.get_dictionary_mut(*kid) {
+            page.set("Parent", pages_id);
+        }
+    }
+    merged.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Count" => kids.len() as i64,
+            "Kids" => kids.into_iter().map(Object::Reference).collect::<Vec<_>>(),
+        }),
+    );
+    merged.objects.insert(
+        catalog_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        }),
+    );
+    merged.trailer.set("Root", catalog_id);
+    merged.max_id = next_id + 1;
+    merged.prune_objects();
+    merged.renumber_objects();
+    Ok(merged)
+}
+
+/// Copy of `doc` with only the pages `start..=end` (1-based).
+pub fn extract_pages(doc: &Document, start: u32, end: u32) -> Result<Document, PdfError> {
+    let count = page_count(doc);
+    if start == 0 || start > end || end > count {
+        return Err(PdfError::InvalidPageRange {
+            reason: format!("{start}-{end} is outside 1-{count}"),
+        });
+    }
+    let mut part = doc.clone();
+    let removed: Vec<u32> = (1..=count).filter(|n| *n < start || *n > end).collect();
+    part.delete_pages(&removed);
+    part.prune_objects();
+    Ok(part)
+}
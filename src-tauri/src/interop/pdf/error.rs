@@ -0,0 +1,103 @@
+// src-tauri/src/interop/pdf/error.rs
+//!
+//! PDF Error Types
+//!
+
+use crate::error_code::{serialize_tagged, CodedError, ErrorCode};
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Error, Serialize)]
+#[serde(tag = "type", content = "details")]
+#[serde(remote = "Self")]
+pub enum PdfError {
+    #[error("Input too large: {size} bytes (max {max})")]
+    TooLarge { size: u64, max: u64 },
+
+    #[error("Invalid PDF: {reason}")]
+    InvalidDocument { reason: String },
+
+    #[error("PDF is encrypted and can't be modified")]
+    Encrypted,
+
+    #[error("Text extraction failed: {reason}")]
+    ExtractionFailed { reason: String },
+
+    #[error("PDF has no form")]
+    NoForm,
+
+    #[error("Unknown form field '{name}'")]
+    UnknownField { name: String },
+
+    #[error("Invalid value for form field '{name}': {reason}")]
+    InvalidFieldValue { name: String, reason: String },
+
+    #[error("Invalid page range: {reason}")]
+    InvalidPageRange { reason: String },
+
+    #[error("Invalid request: {reason}")]
+    InvalidRequest { reason: String },
+
+    #[error("I/O error: {reason}")]
+    Io { reason: String },
+
+    #[error("Internal error: {reason}")]
+    Internal { reason: String },
+}
+
+impl CodedError for PdfError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            PdfError::TooLarge { .. } => ErrorCode::LimitExceeded,
+            PdfError::InvalidDocument { .. } => ErrorCode::ProcessingFailed,
+            PdfError::Encrypted => ErrorCode::Unsupported,
+            PdfError::ExtractionFailed { .. } => ErrorCode::ProcessingFailed,
+            PdfError::NoForm
+            | PdfError::UnknownField { .. }
+            | PdfError::InvalidFieldValue { .. }
+            | PdfError::InvalidPageRange { .. }
+            | PdfError::InvalidRequest { .. } => ErrorCode::Validation,
+            PdfError::Io { .. } => ErrorCode::Filesystem,
+            PdfError::Internal { .. } => ErrorCode::Internal,
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        self.tagged().get("details").cloned()
+    }
+}
+
+impl PdfError {
+    /// The derived `{ type, details }` representation
+    fn tagged(&self) -> serde_json::Value {
+        PdfError::serialize(self, serde_json::value::Serializer).unwrap_or_default()
+    }
+}
+
+impl Serialize for PdfError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_tagged(self, &self.tagged(), serializer)
+    }
+}
+
+impl From<std::io::Error> for PdfError {
+    fn from(e: std::io::Error) -> Self {
+        PdfError::Io {
+            reason: e.to_string(),
+        }
+    }
+}
+
+impl From<lopdf::Error> for PdfError {
+    fn from(e: lopdf::Error) -> Self {
+        match e {
+            lopdf::Error::IO(e) => e.into(),
+            e => PdfError::InvalidDocument {
+                reason: e.to_string(),
+            },
+        }
+    }
+}
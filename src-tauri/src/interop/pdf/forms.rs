@@ -0,0 +1,343 @@
+// src-tauri/src/interop/pdf/forms.rs
+//!
+//! AcroForm fields
+//!
+//! Fields are addressed by their fully qualified name. Filling validates
+//! every value before the document is touched, so a rejected value never
+//! leaves a half-filled form behind.
+
+use super::document::{decode_text, encode_text, ensure_writable, resolve};
+use super::error::PdfError;
+use super::types::{PdfFieldKind, PdfFormField};
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::BTreeMap;
+
+/// Field flags (PDF 32000-1, 12.7.3.1 and 12.7.4)
+const FLAG_READ_ONLY: i64 = 1;
+const FLAG_RADIO: i64 = 1 << 15;
+const FLAG_PUSHBUTTON: i64 = 1 << 16;
+const FLAG_EDIT: i64 = 1 << 18;
+
+/// Field hierarchies deeper than this are ignored (cycle protection).
+const MAX_FIELD_DEPTH: usize = 32;
+
+const OFF: &[u8] = b"Off";
+
+/// A terminal field and the widget annotations showing it.
+struct FieldNode {
+    id: ObjectId,
+    name: String,
+    kind: PdfFieldKind,
+    flags: i64,
+    widgets: Vec<ObjectId>,
+}
+
+fn acroform(doc: &Document) -> Option<&Dictionary> {
+    let form = doc.catalog().ok()?.get(b"AcroForm").ok()?;
+    resolve(doc, form)?.as_dict().ok()
+}
+
+fn field_kind(field_type: &[u8], flags: i64) -> Option<PdfFieldKind> {
+    Some(match field_type {
+        b"Tx" => PdfFieldKind::Text,
+        b"Ch" => PdfFieldKind::Choice,
+        b"Sig" => PdfFieldKind::Signature,
+        b"Btn" if flags & FLAG_PUSHBUTTON != 0 => PdfFieldKind::Button,
+        b"Btn" if flags & FLAG_RADIO != 0 => PdfFieldKind::Radio,
+        b"Btn" => PdfFieldKind::Checkbox,
+        _ => return None,
+    })
+}
+
+fn collect(
+    doc: &Document,
+    refs: &[Object],
+    parent_name: &str,
+    inherited: (Option<&[u8]>, i64),
+    depth: usize,
+    out: &mut Vec<FieldNode>,
+) {
+    if depth > MAX_FIELD_DEPTH {
+        return;
+    }
+    for reference in refs {
+        let Ok(id) = reference.as_reference() else {
+            continue;
+        };
+        let Ok(dict) = doc.get_dictionary(id) else {
+            continue;
+        };
+        let name = match dict.get(b"T").and_then(Object::as_str) {
+            Ok(partial) if parent_name.is_empty() => decode_text(partial),
+            Ok(partial) => format!("{parent_name}.{}", decode_text(partial)),
+            Err(_) => parent_name.to_string(),
+        };
+        let field_type = dict
+            .get(b"FT")
+            .and_then(Object::as_name)
+            .ok()
+            .or(inherited.0);
+        let flags = dict
+            .get(b"Ff")
+            .and_then(Object::as_i64)
+            .unwrap_or(inherited.1);
+
+        let kids = dict
+            .get(b"Kids")
+            .and_then(Object::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        // Kids with a partial name are fields, without one they're widgets
+        let has_child_fields = kids.iter().any(|kid| {
+            kid.as_reference()
+                .and_then(|id| doc.get_dictionary(id))
+                .is_ok_and(|kid| kid.has(b"T"))
+        });
+        if has_child_fields {
+            collect(doc, kids, &name, (field_type, flags), depth + 1, out);
+            continue;
+        }
+
+        let Some(kind) = field_type.and_then(|t| field_kind(t, flags)) else {
+            continue;
+        };
+        let widgets = if kids.is_empty() {
+            vec![id]
+        } else {
+            kids.iter().filter_map(|k| k.as_reference().ok()).collect()
+        };
+        out.push(FieldNode {
+            id,
+            name,
+            kind,
+            flags,
+            widgets,
+        });
+    }
+}
+
+fn field_nodes(doc: &Document) -> Vec<FieldNode> {
+    let Some(fields) = acroform(doc)
+        .and_then(|form| form.get(b"Fields").ok())
+        .and_then(|fields| resolve(doc, fields))
+        .and_then(|fields| fields.as_array().ok())
+    else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    collect(doc, fields, "", (None, 0), 0, &mut out);
+    out
+}
+
+/// Appearance states of a checkbox or radio widget, "Off" excluded.
+fn widget_states(doc: &Document, widget: ObjectId) -> Vec<Vec<u8>> {
+    doc.get_dictionary(widget)
+        .ok()
+        .and_then(|w| w.get(b"AP").ok())
+        .and_then(|ap| resolve(doc, ap)?.as_dict().ok())
+        .and_then(|ap| ap.get(b"N").ok())
+        .and_then(|n| resolve(doc, n)?.as_dict().ok())
+        .map(|n| {
+            n.iter()
+                .map(|(state, _)| state.clone())
+                .filter(|state| state != OFF)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn options(doc: &Document, node: &FieldNode) -> Vec<String> {
+    match node.kind {
+        PdfFieldKind::Checkbox | PdfFieldKind::Radio => {
+            let mut states: Vec<String> = Vec::new();
+            for widget in &node.widgets {
+                for state in widget_states(doc, *widget) {
+                    let state = String::from_utf8_lossy(&state).into_owned();
+                    if !states.contains(&state) {
+                        states.push(state);
+                    }
+                }
+            }
+            states
+        }
+        PdfFieldKind::Choice => doc
+            .get_dictionary(node.id)
+            .ok()
+            .and_then(|d| d.get(b"Opt").ok())
+            .and_then(|opt| resolve(doc, opt)?.as_array().ok())
+            .map(|opt| {
+                opt.iter()
+                    .filter_map(|o| {
+                        // Either a text or an [export value, display text] pair
+                        let o = resolve(doc, o)?;
+                        let o = match o.as_array() {
+                            Ok(pair) => resolve(doc, pair.first()?)?,
+                            Err(_) => o,
+                        };
+                        o.as_str().ok().map(decode_text)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+fn value(doc: &Document, node: &FieldNode) -> Option<String> {
+    let value = doc.get_dictionary(node.id).ok()?.get(b"V").ok()?;
+    match resolve(doc, value)? {
+        Object::String(bytes, _) => Some(decode_text(bytes)),
+        Object::Name(name) if name == OFF => None,
+        Object::Name(name) => Some(String::from_utf8_lossy(name).into_owned()),
+        // Multi-select list boxes
+        Object::Array(values) => Some(
+            values
+                .iter()
+                .filter_map(|v| v.as_str().ok().map(decode_text))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        _ => None,
+    }
+}
+
+/// All terminal fields of the document's AcroForm.
+pub fn fields(doc: &Document) -> Vec<PdfFormField> {
+    field_nodes(doc)
+        .iter()
+        .map(|node| PdfFormField {
+            name: node.name.clone(),
+            kind: node.kind,
+            value: value(doc, node),
+            options: options(doc, node),
+            read_only: node.flags & FLAG_READ_ONLY != 0,
+        })
+        .collect()
+}
+
+enum Update {
+    Text(String),
+    /// On-state to select, `None` for off
+    State(Option<Vec<u8>>),
+}
+
+fn invalid(name: &str, reason: &str) -> PdfError {
+    PdfError::InvalidFieldValue {
+        name: name.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn plan_update(doc: &Document, node: &FieldNode, value: &str) -> Result<Update, PdfError> {
+    if node.flags & FLAG_READ_ONLY != 0 {
+        return Err(invalid(&node.name, "field is read-only"));
+    }
+    match node.kind {
+        PdfFieldKind::Text => Ok(Update::Text(value.to_string())),
+        PdfFieldKind::Choice => {
+            let options = options(doc, node);
+            if options.is_empty()
+                || node.flags & FLAG_EDIT != 0
+                || options.iter().any(|o| o == value)
+            {
+                Ok(Update::Text(value.to_string()))
+            } else {
+                Err(invalid(&node.name, "not one of the field's options"))
+            }
+        }
+        PdfFieldKind::Checkbox | PdfFieldKind::Radio => {
+            let states = options(doc, node);
+            if value.is_empty() || value == "Off" {
+                return Ok(Update::State(None));
+            }
+            if states.iter().any(|s| s == value) {
+                return Ok(Update::State(Some(value.as_bytes().to_vec())));
+            }
+            let on = states.first().map(|s| s.as_bytes().to_vec());
+            match (node.kind, value.to_ascii_lowercase().as_str()) {
+                (PdfFieldKind::Checkbox, "true" | "yes" | "on" | "1") if on.is_some() => {
+                    Ok(Update::State(on))
+                }
+                (PdfFieldKind::Checkbox, "false" | "no" | "off" | "0") => Ok(Update::State(None)),
+                _ => Err(invalid(
+                    &node.name,
+                    &format!("expected one of: Off, {}", states.join(", ")),
+                )),
+            }
+        }
+        PdfFieldKind::Button | PdfFieldKind::Signature => {
+            Err(invalid(&node.name, "field can't be filled"))
+        }
+    }
+}
+
+fn set_need_appearances(doc: &mut Document) -> Result<(), PdfError> {
+    let form = doc.catalog()?.get(b"AcroForm")?;
+    let form = match form.as_reference() {
+        Ok(id) => doc.get_dictionary_mut(id)?,
+        Err(_) => doc.catalog_mut()?.get_mut(b"AcroForm")?.as_dict_mut()?,
+    };
+    form.set("NeedAppearances", true);
+    Ok(())
+}
+
+/// Sets the fields named in `values` and returns their names.
+///
+/// Text and choice fields take the text; checkboxes take an on-state,
+/// "true"/"false" or "Off"; radio groups take the on-state of the button
+/// to select or "Off".
+pub fn fill(
+    doc: &mut Document,
+    values: &BTreeMap<String, String>,
+) -> Result<Vec<String>, PdfError> {
+    ensure_writable(doc)?;
+    let nodes = field_nodes(doc);
+    if nodes.is_empty() {
+        return Err(PdfError::NoForm);
+    }
+
+    let mut updates = Vec::with_capacity(values.len());
+    for (name, value) in values {
+        let node = nodes
+            .iter()
+            .find(|n| &n.name == name)
+            .ok_or_else(|| PdfError::UnknownField { name: name.clone() })?;
+        updates.push((node, plan_update(doc, node, value)?));
+    }
+
+    for (node, update) in &updates {
+        match update {
+            Update::Text(text) => {
+                doc.get_dictionary_mut(node.id)?.set("V", encode_text(text));
+                // The old appearance would still show the previous value;
+                // NeedAppearances makes viewers regenerate it
+                for widget in &node.widgets {
+                    if let Ok(widget) = doc.get_dictionary_mut(*widget) {
+                        widget.remove(b"AP");
+                    }
+                }
+            }
+            Update::State(state) => {
+                let selected = state.clone().unwrap_or_else(|| OFF.to_vec());
+                doc.get_dictionary_mut(node.id)?
+                    .set("V", Object::Name(selected.clone()));
+                for widget in &node.widgets {
+                    let appearance = if widget_states(doc, *widget).contains(&selected) {
+                        selected.clone()
+                    } else {
+                        OFF.to_vec()
+                    };
+                    if let Ok(widget) = doc.get_dictionary_mut(*widget) {
+                        widget.set("AS", Object::Name(appearance));
+                    }
+                }
+            }
+        }
+    }
+    set_need_appearances(doc)?;
+
+    Ok(updates
+        .into_iter()
+        .map(|(node, _)| node.name.clone())
+        .collect())
+}
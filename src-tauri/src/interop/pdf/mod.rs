@@ -0,0 +1,28 @@
+// src-tauri/src/interop/pdf/mod.rs
+//!
+//! PDF Documents
+//!
+//! Text and metadata extraction, AcroForm filling and merging/splitting of
+//! PDF files for document-management extensions.
+//!
+//! - Text comes from the bundled `pdf-extract` crate, which also backs the
+//!   PDF branch of `content_extract`.
+//! - Structural edits use `lopdf`. Filled forms set `NeedAppearances`, so
+//!   viewers render the new values; stale field appearances are dropped.
+//! - Encrypted documents can be inspected but not modified.
+//! - Inputs are capped at `MAX_INPUT_BYTES`, merges at `MAX_MERGE_SOURCES`
+//!   documents and splits at `MAX_SPLIT_PARTS` outputs.
+//!
+//! `pdf_*` are internal commands for the host UI, `extension_pdf_*` the
+//! permission-checked variants.
+
+pub mod commands;
+pub mod document;
+pub mod error;
+pub mod forms;
+pub mod types;
+
+#[cfg(test)]
+mod tests;
+
+pub use error::PdfError;
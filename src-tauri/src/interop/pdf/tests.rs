@@ -0,0 +1,316 @@
+//! Tests for PDF inspection, form filling, merging and splitting
+
+use super::document::{self, decode_text, encode_text};
+use super::error::PdfError;
+use super::forms;
+use super::types::PdfFieldKind;
+use lopdf::{dictionary, Document, Object, ObjectId, Stream};
+use std::collections::BTreeMap;
+
+/// A document with one text page per entry of `texts`. The font is only
+/// set on the page tree node, so pages inherit their resources.
+fn text_pdf(texts: &[&str], title: &str) -> Document {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+    });
+    let mut kids = Vec::new();
+    for text in texts {
+        let content = format!("BT /F1 24 Tf 72 720 Td ({text}) Tj ET");
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content.into_bytes()));
+        kids.push(Object::Reference(doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+        })));
+    }
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Count" => kids.len() as i64,
+            "Kids" => kids,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            "Resources" => dictionary! { "Font" => dictionary! { "F1" => font_id } },
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    let info_id = doc.add_object(dictionary! {
+        "Title" => Object::string_literal(title),
+        "Author" => encode_text("Jürgen"),
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc.trailer.set("Info", info_id);
+    doc
+}
+
+fn round_trip(doc: &mut Document) -> Document {
+    document::load(&document::save(doc).unwrap()).unwrap()
+}
+
+fn page_texts(doc: &mut Document) -> Vec<String> {
+    let bytes = document::save(doc).unwrap();
+    document::extract_text(&bytes)
+        .unwrap()
+        .pages
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .collect()
+}
+
+fn widget(doc: &mut Document, extra: lopdf::Dictionary, states: &[&str]) -> ObjectId {
+    let mut normal = lopdf::Dictionary::new();
+    for state in states.iter().chain(["Off"].iter()) {
+        let appearance = doc.add_object(Stream::new(dictionary! {}, Vec::new()));
+        normal.set(*state, appearance);
+    }
+    let mut dict = dictionary! {
+        "Type" => "Annot",
+        "Subtype" => "Widget",
+        "AP" => dictionary! { "N" => normal },
+        "AS" => "Off",
+    };
+    dict.extend(&extra);
+    doc.add_object(dict)
+}
+
+/// person.name (text), person.id (read-only text), agree (checkbox),
+/// color (radio group with two buttons), size (combo box)
+fn form_pdf() -> Document {
+    let mut doc = text_pdf(&["Form"], "Form");
+    let name_id = doc.add_object(dictionary! {
+        "FT" => "Tx",
+        "T" => Object::string_literal("name"),
+        "V" => Object::string_literal("old"),
+        "AP" => dictionary! {},
+    });
+    let id_id = doc.add_object(dictionary! {
+        "FT" => "Tx",
+        "T" => Object::string_literal("id"),
+        "Ff" => 1,
+    });
+    let person_id = doc.add_object(dictionary! {
+        "T" => Object::string_literal("person"),
+        "Kids" => vec![name_id.into(), id_id.into()],
+    });
+    let agree_id = widget(
+        &mut doc,
+        dictionary! { "FT" => "Btn", "T" => Object::string_literal("agree") },
+        &["Yes"],
+    );
+    let color_id = doc.new_object_id();
+    let red = widget(&mut doc, dictionary! { "Parent" => color_id }, &["Red"]);
+    let blue = widget(&mut doc, dictionary! { "Parent" => color_id }, &["Blue"]);
+    doc.objects.insert(
+        color_id,
+        Object::Dictionary(dictionary! {
+            "FT" => "Btn",
+            "Ff" => 1 << 15,
+            "T" => Object::string_literal("color"),
+            "Kids" => vec![red.into(), blue.into()],
+            "V" => "Off",
+        }),
+    );
+    let size_id = doc.add_object(dictionary! {
+        "FT" => "Ch",
+        "T" => Object::string_literal("size"),
+        "Opt" => vec![
+            Object::string_literal("S"),
+            vec![Object::string_literal("M"), Object::string_literal("Medium")].into(),
+        ],
+    });
+
+    let catalog_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+    doc.get_dictionary_mut(catalog_id).unwrap().set(
+        "AcroForm",
+        dictionary! {
+            "Fields" => vec![
+                person_id.into(),
+                agree_id.into(),
+                color_id.into(),
+                size_id.into(),
+            ],
+        },
+    );
+    doc
+}
+
+#[test]
+fn test_text_string_encoding() {
+    assert_eq!(decode_text(b"Report"), "Report");
+    assert_eq!(decode_text(&[0x4A, 0xFC, 0x72]), "Jür");
+    let Object::String(bytes, _) = encode_text("Grüße") else {
+        panic!("expected a string");
+    };
+    assert_eq!(&bytes[..2], &[0xFE, 0xFF]);
+    assert_eq!(decode_text(&bytes), "Grüße");
+    assert_eq!(decode_text(&[0xEF, 0xBB, 0xBF, b'o', b'k']), "ok");
+}
+
+#[test]
+fn test_metadata_and_text() {
+    let mut doc = round_trip(&mut text_pdf(&["Hello", "World"], "Report"));
+    let metadata = document::metadata(&doc, 1234);
+    assert_eq!(metadata.page_count, 2);
+    assert_eq!(metadata.version, "1.5");
+    assert_eq!(metadata.size, 1234);
+    assert_eq!(metadata.title.as_deref(), Some("Report"));
+    assert_eq!(metadata.author.as_deref(), Some("Jürgen"));
+    assert_eq!(metadata.producer, None);
+    assert!(!metadata.encrypted);
+    assert!(!metadata.has_form);
+
+    let pages = page_texts(&mut doc);
+    assert_eq!(pages.len(), 2);
+    assert!(pages[0].contains("Hello"));
+    assert!(pages[1].contains("World"));
+
+    assert!(matches!(
+        document::load(b"not a pdf"),
+        Err(PdfError::InvalidDocument { .. })
+    ));
+}
+
+#[test]
+fn test_merge_keeps_page_order_and_inherited_resources() {
+    let first = text_pdf(&["One", "Two"], "First");
+    let second = text_pdf(&["Three"], "Second");
+    let mut merged = round_trip(&mut document::merge(vec![first, second]).unwrap());
+
+    assert_eq!(document::page_count(&merged), 3);
+    for page_id in merged.get_pages().values() {
+        let page = merged.get_dictionary(*page_id).unwrap();
+        assert!(page.has(b"Resources"));
+        assert!(page.has(b"MediaBox"));
+    }
+    let pages = page_texts(&mut merged);
+    assert!(pages[0].contains("One"));
+    assert!(pages[2].contains("Three"));
+
+    assert!(matches!(
+        document::merge(Vec::new()),
+        Err(PdfError::InvalidRequest { .. })
+    ));
+}
+
+#[test]
+fn test_extract_pages() {
+    let doc = text_pdf(&["A", "B", "C", "D"], "Split");
+    let mut part = round_trip(&mut document::extract_pages(&doc, 2, 3).unwrap());
+    assert_eq!(document::page_count(&part), 2);
+    let pages = page_texts(&mut part);
+    assert!(pages[0].contains('B'));
+    assert!(pages[1].contains('C'));
+
+    for (start, end) in [(0, 1), (3, 2), (4, 5)] {
+        assert!(matches!(
+            document::extract_pages(&doc, start, end),
+            Err(PdfError::InvalidPageRange { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_list_form_fields() {
+    let doc = form_pdf();
+    let fields = forms::fields(&doc);
+    let names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["person.name", "person.id", "agree", "color", "size"]
+    );
+
+    assert_eq!(fields[0].kind, PdfFieldKind::Text);
+    assert_eq!(fields[0].value.as_deref(), Some("old"));
+    assert!(fields[1].read_only);
+    assert_eq!(fields[2].kind, PdfFieldKind::Checkbox);
+    assert_eq!(fields[2].options, vec!["Yes"]);
+    assert_eq!(fields[3].kind, PdfFieldKind::Radio);
+    assert_eq!(fields[3].options, vec!["Red", "Blue"]);
+    assert_eq!(fields[3].value, None);
+    assert_eq!(fields[4].kind, PdfFieldKind::Choice);
+    assert_eq!(fields[4].options, vec!["S", "M"]);
+    assert!(document::metadata(&doc, 0).has_form);
+}
+
+#[test]
+fn test_fill_form() {
+    let mut doc = form_pdf();
+    let values = BTreeMap::from([
+        ("person.name".to_string(), "Zoë".to_string()),
+        ("agree".to_string(), "true".to_string()),
+        ("color".to_string(), "Blue".to_string()),
+        ("size".to_string(), "M".to_string()),
+    ]);
+    let filled = forms::fill(&mut doc, &values).unwrap();
+    assert_eq!(filled.len(), 4);
+
+    let mut doc = round_trip(&mut doc);
+    let fields = forms::fields(&doc);
+    let value = |name: &str| {
+        fields
+            .iter()
+            .find(|f| f.name == name)
+            .and_then(|f| f.value.clone())
+    };
+    assert_eq!(value("person.name").as_deref(), Some("Zoë"));
+    assert_eq!(value("agree").as_deref(), Some("Yes"));
+    assert_eq!(value("color").as_deref(), Some("Blue"));
+    assert_eq!(value("size").as_deref(), Some("M"));
+
+    // Only the selected radio button shows its on-state
+    let states: Vec<Vec<u8>> = doc
+        .objects
+        .values()
+        .filter_map(|o| o.as_dict().ok())
+        .filter(|d| d.has(b"Parent") && d.has(b"AS"))
+        .map(|d| d.get(b"AS").unwrap().as_name().unwrap().to_vec())
+        .collect();
+    assert!(states.contains(&b"Blue".to_vec()));
+    assert!(!states.contains(&b"Red".to_vec()));
+
+    let catalog = doc.catalog().unwrap();
+    let form = catalog.get(b"AcroForm").unwrap().as_dict().unwrap();
+    assert!(form.get(b"NeedAppearances").unwrap().as_bool().unwrap());
+
+    // Unchecking
+    let values = BTreeMap::from([("agree".to_string(), "false".to_string())]);
+    forms::fill(&mut doc, &values).unwrap();
+    assert_eq!(forms::fields(&doc)[2].value, None);
+}
+
+#[test]
+fn test_fill_form_rejects_invalid_values_without_changes() {
+    let mut doc = form_pdf();
+    let cases = [
+        ("nope", "x"),
+        ("person.id", "42"),
+        ("color", "Green"),
+        ("size", "XL"),
+    ];
+    for (name, value) in cases {
+        let values = BTreeMap::from([
+            ("person.name".to_string(), "changed".to_string()),
+            (name.to_string(), value.to_string()),
+        ]);
+        let err = forms::fill(&mut doc, &values).unwrap_err();
+        assert!(matches!(
+            err,
+            PdfError::UnknownField { .. } | PdfError::InvalidFieldValue { .. }
+        ));
+    }
+    assert_eq!(forms::fields(&doc)[0].value.as_deref(), Some("old"));
+
+    let mut plain = text_pdf(&["No form"], "Plain");
+    let values = BTreeMap::from([("a".to_string(), "b".to_string())]);
+    assert!(matches!(
+        forms::fill(&mut plain, &values),
+        Err(PdfError::NoForm)
+    ));
+}
@@ -0,0 +1,107 @@
+// src-tauri/src/interop/pdf/types.rs
+//!
+//! PDF Types
+//!
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Document information of a PDF.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PdfMetadata {
+    pub page_count: u32,
+    /// Header version, e.g. "1.7"
+    pub version: String,
+    #[ts(type = "number")]
+    pub size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub subject: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub keywords: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub creator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub producer: Option<String>,
+    /// Raw PDF date string, e.g. "D:20261017120000+02'00'"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub creation_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub modification_date: Option<String>,
+    pub encrypted: bool,
+    /// The document has an AcroForm with at least one field
+    pub has_form: bool,
+}
+
+/// Extracted text, one entry per page.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PdfText {
+    pub pages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum PdfFieldKind {
+    Text,
+    Checkbox,
+    Radio,
+    /// Combo or list box
+    Choice,
+    /// Push button, can't hold a value
+    Button,
+    /// Signature, can't be filled
+    Signature,
+}
+
+/// A terminal AcroForm field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PdfFormField {
+    /// Fully qualified name (partial names joined by ".")
+    pub name: String,
+    pub kind: PdfFieldKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub value: Option<String>,
+    /// Export values of choices, on-states of checkboxes and radio buttons
+    pub options: Vec<String>,
+    pub read_only: bool,
+}
+
+/// One output of `pdf_split`: pages `start..=end` (1-based).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PdfSplitPart {
+    pub start: u32,
+    pub end: u32,
+    pub destination: String,
+}
+
+/// A written PDF file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PdfWriteResult {
+    pub path: String,
+    pub page_count: u32,
+    #[ts(type = "number")]
+    pub size: u64,
+}
@@ -9,26 +9,53 @@
 
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
 mod external_bridge;
+mod accessibility;
+mod app_profile;
+mod auth;
+mod automation;
+mod codes;
+mod command_middleware;
+mod content_extract;
 mod crypto;
 mod crdt;
 pub mod critical;
 pub mod database;
+pub mod dav;
+mod deep_link;
 mod device;
+pub mod error_code;
+mod events;
 mod extension;
 pub mod file_sync;
 mod filesystem;
+mod health;
+mod ids;
+mod interop;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod local_api;
 mod logging;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod mcp;
 pub mod mail;
+mod media;
 mod media_server;
+mod metrics;
 pub mod mls;
 #[cfg(desktop)]
 mod shortcuts;
 mod passwords;
 pub mod peer_storage;
 pub mod quic_did_auth;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod relay;
 mod remote_storage;
 pub mod space_delivery;
+mod sync;
+mod system_pim;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 pub mod ucan;
+mod webhooks;
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
 mod window;
 
@@ -46,6 +73,7 @@ use crate::extension::webview::ExtensionWebviewManager;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tauri::Manager;
+use tracing_subscriber::prelude::*;
 
 /// Initialize ndk-context from MainActivity.onCreate.
 ///
@@ -129,12 +157,36 @@ pub struct AppState {
     /// External bridge for WebSocket connections (desktop only)
     #[cfg(not(any(target_os = "android", target_os = "ios")))]
     pub external_bridge: tokio::sync::Mutex<ExternalBridge>,
+    /// Consent prompts of the bridge credential protocol (desktop only)
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    pub bridge_credential_prompts: external_bridge::credentials::prompts::CredentialPrompts,
+    /// Local REST API for integrations without WebSocket support (desktop only)
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    pub local_api: tokio::sync::Mutex<local_api::LocalApi>,
+    /// Background text extraction jobs (PDF/OCR/plain text)
+    pub content_extract: content_extract::ContentExtractQueue,
+    /// QR scans extensions requested from the main window
+    pub codes: codes::ScanBroker,
+    /// Photos and recordings extensions requested from the main window
+    pub media_capture: media::capture::CaptureBroker,
+    /// Pending drag-and-drop file drops routed to extensions
+    pub file_drops: extension::filedrop::FileDropRegistry,
+    /// Shares received from the OS share sheet, waiting for the chooser
+    pub share_intake: extension::share::ShareIntakeRegistry,
     /// File watcher for sync rules (no-op on Android)
     pub file_watcher: extension::filesystem::watcher::FileWatcherManager,
+    /// Open chunked file streams of extensions
+    pub file_streams: extension::filesystem::streams::FileStreamRegistry,
     /// Session-based permission store (in-memory, cleared on restart)
     pub session_permissions: extension::permissions::session::SessionPermissionStore,
+    /// Pending permission prompts; identical concurrent prompts are coalesced
+    pub permission_prompts: extension::permissions::broker::PermissionPromptBroker,
     /// Extension resource limits service (database, filesystem, web)
     pub limits: extension::limits::LimitsService,
+    /// Slow extension queries recorded for the index advisor (in-memory)
+    pub slow_queries: database::optimize::SlowQueryLog,
+    /// Undo/redo stacks of local CRDT writes (in-memory, per session)
+    pub undo: crdt::undo::UndoService,
     /// Peer storage endpoint for P2P file sharing via iroh/QUIC
     pub peer_storage: Arc<tokio::sync::RwLock<peer_storage::endpoint::PeerEndpoint>>,
     /// Active P2P transfer control (transfer_id → (cancel_token, pause_flag))
@@ -147,6 +199,8 @@ pub struct AppState {
     pub pty_manager: extension::shell::pty::PtyManager,
     /// Active local sync loops (space_id -> handle)
     pub local_sync_loops: tokio::sync::Mutex<HashMap<String, space_delivery::local::sync_loop::SyncLoopHandle>>,
+    /// Background sync orchestrators (backend_id -> handle)
+    pub sync_orchestrators: tokio::sync::Mutex<HashMap<String, sync::orchestrator::SyncOrchestratorHandle>>,
     /// Leader states for local space delivery, keyed by space_id.
     /// RwLock because reads (QUIC stream routing) are frequent and concurrent,
     /// writes (start/stop leader) are rare.
@@ -157,9 +211,67 @@ pub struct AppState {
     /// URI schemes don't work for `<audio>`/`<video>` on WebKitGTK — this
     /// is the cross-platform workaround.
     pub media_server: media_server::MediaServer,
+    /// Call counters and dispatch timings of all commands (in-memory)
+    pub command_metrics: command_middleware::CommandMetrics,
+    /// Headless sync relay for devices that are never online together (desktop only)
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    pub relay: relay::RelayService,
 }
 
 impl AppState {
+    /// State of a freshly started app: no vault open, empty registries and
+    /// brokers. `media_server` is passed in because binding it is async.
+    pub fn new(media_server: media_server::MediaServer) -> Self {
+        AppState {
+            db: DbConnection(Arc::new(Mutex::new(None))),
+            hlc: Mutex::new(HlcService::new()),
+            critical_sink: Mutex::new(None),
+            vault_lock: Mutex::new(None),
+            connection_context: Mutex::new(ConnectionContext::new()),
+            extension_manager: ExtensionManager::new(),
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            extension_webview_manager: ExtensionWebviewManager::new(),
+            context: Arc::new(Mutex::new(extension::core::context::ApplicationContext {
+                theme: "dark".to_string(),
+                locale: "en".to_string(),
+                platform: std::env::consts::OS.to_string(),
+                // Device ID is set after vault opens (loaded from instance.json store)
+                device_id: String::new(),
+                css_variables: std::collections::BTreeMap::new(),
+            })),
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            external_bridge: tokio::sync::Mutex::new(ExternalBridge::new()),
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            bridge_credential_prompts: external_bridge::credentials::prompts::CredentialPrompts::new(),
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            local_api: tokio::sync::Mutex::new(local_api::LocalApi::new()),
+            content_extract: content_extract::ContentExtractQueue::new(),
+            codes: codes::ScanBroker::new(),
+            media_capture: media::capture::CaptureBroker::new(),
+            file_drops: extension::filedrop::FileDropRegistry::new(),
+            share_intake: extension::share::ShareIntakeRegistry::new(),
+            file_watcher: extension::filesystem::watcher::FileWatcherManager::new(),
+            file_streams: extension::filesystem::streams::FileStreamRegistry::new(),
+            session_permissions: extension::permissions::session::SessionPermissionStore::new(),
+            permission_prompts: extension::permissions::broker::PermissionPromptBroker::new(),
+            limits: extension::limits::LimitsService::new(),
+            slow_queries: database::optimize::SlowQueryLog::new(),
+            undo: crdt::undo::UndoService::new(),
+            peer_storage: Arc::new(tokio::sync::RwLock::new(peer_storage::endpoint::PeerEndpoint::new_ephemeral())),
+            transfer_tokens: tokio::sync::Mutex::new(HashMap::new()),
+            sync_manager: tokio::sync::Mutex::new(SyncManager::new()),
+            auth_token: Arc::new(Mutex::new(None)),
+            pty_manager: extension::shell::pty::PtyManager::new(),
+            local_sync_loops: tokio::sync::Mutex::new(HashMap::new()),
+            sync_orchestrators: tokio::sync::Mutex::new(HashMap::new()),
+            leader_state: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            media_server,
+            command_metrics: command_middleware::CommandMetrics::new(),
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            relay: relay::RelayService::new(),
+        }
+    }
+
     /// `Mutex::lock` replacement that records mutex poisoning as a
     /// banner-visible critical-failure event. Use this instead of
     /// `.lock().unwrap()` / `.lock().unwrap_or_else(|e| e.into_inner())` /
@@ -240,12 +352,21 @@ pub fn run() {
     // The default keeps the volume sane: `info` for iroh, `warn` for
     // everything else, so a user without env vars still gets relay /
     // close-reason events but not full debug noise.
+    // Panics get a correlation ID in the log that command errors refer to
+    command_middleware::install_panic_hook();
+
     let filter = tracing_subscriber::EnvFilter::try_from_env("HAEX_LOG")
         .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("warn,iroh=info"));
-    let _ = tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_target(true)
-        .with_writer(std::io::stderr)
+    // The filter applies to the log output only; the panic layer sees the
+    // spans of async commands whatever `HAEX_LOG` says
+    let _ = tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_target(true)
+                .with_writer(std::io::stderr)
+                .with_filter(filter),
+        )
+        .with(command_middleware::task_panics::TaskPanicLayer::filtered())
         .try_init();
 
     // Reassigned under #[cfg(mobile)] / #[cfg(target_os = "android")] below;
@@ -266,6 +387,36 @@ pub fn run() {
         builder = builder.plugin(tauri_plugin_android_fs::init());
     }
 
+    // Background sync scheduler (Android only) - WorkManager wake-ups
+    #[cfg(target_os = "android")]
+    {
+        builder = builder.plugin(sync::background::init());
+    }
+
+    // Share intake (Android only) - ACTION_SEND into extensions
+    #[cfg(target_os = "android")]
+    {
+        builder = builder.plugin(extension::share::init());
+    }
+
+    // System contacts and calendars (Android only) - ContactsContract/CalendarContract
+    #[cfg(target_os = "android")]
+    {
+        builder = builder.plugin(system_pim::init());
+    }
+
+    // Password autofill (Android only) - AutofillService bridge
+    #[cfg(target_os = "android")]
+    {
+        builder = builder.plugin(passwords::autofill::android::init());
+    }
+
+    // Speech output (Android only) - TextToSpeech
+    #[cfg(target_os = "android")]
+    {
+        builder = builder.plugin(accessibility::speech::init());
+    }
+
     // Note: previously `tauri_plugin_single_instance` was registered here to
     // lock the app to one running instance per user, with a secondary purpose
     // of forwarding `haexvault://` deep-link CLI args from a 2nd launch to
@@ -286,7 +437,7 @@ pub fn run() {
                 Ok(response) => response,
                 Err(e) => {
                     eprintln!(
-                        "Fehler im Custom Protocol Handler für URI '{}': {}",
+                        "Error in custom protocol handler for URI '{}': {}",
                         request.uri(),
                         e
                     );
@@ -294,14 +445,14 @@ pub fn run() {
                         .status(500)
                         .header("Content-Type", "text/plain")
                         .body(Vec::from(format!(
-                            "Interner Serverfehler im Protokollhandler: {e}"
+                            "Internal server error in protocol handler: {e}"
                         )))
                         .unwrap_or_else(|build_err| {
-                            eprintln!("Konnte Fehler-Response nicht erstellen: {build_err}");
+                            eprintln!("Failed to build error response: {build_err}");
                             tauri::http::Response::builder()
                                 .status(500)
                                 .body(Vec::new())
-                                .expect("Konnte minimale Fallback-Response nicht erstellen")
+                                .expect("Failed to build minimal fallback response")
                         })
                 }
             }
@@ -313,41 +464,14 @@ pub fn run() {
                 remote_storage::streaming::stream_protocol_handler(app_handle, request, responder);
             },
         )
-        .manage(AppState {
-            db: DbConnection(Arc::new(Mutex::new(None))),
-            hlc: Mutex::new(HlcService::new()),
-            critical_sink: Mutex::new(None),
-            vault_lock: Mutex::new(None),
-            connection_context: Mutex::new(ConnectionContext::new()),
-            extension_manager: ExtensionManager::new(),
-            #[cfg(not(any(target_os = "android", target_os = "ios")))]
-            extension_webview_manager: ExtensionWebviewManager::new(),
-            context: Arc::new(Mutex::new(extension::core::context::ApplicationContext {
-                theme: "dark".to_string(),
-                locale: "en".to_string(),
-                platform: std::env::consts::OS.to_string(),
-                // Device ID is set after vault opens (loaded from instance.json store)
-                device_id: String::new(),
-            })),
-            #[cfg(not(any(target_os = "android", target_os = "ios")))]
-            external_bridge: tokio::sync::Mutex::new(ExternalBridge::new()),
-            file_watcher: extension::filesystem::watcher::FileWatcherManager::new(),
-            session_permissions: extension::permissions::session::SessionPermissionStore::new(),
-            limits: extension::limits::LimitsService::new(),
-            peer_storage: Arc::new(tokio::sync::RwLock::new(peer_storage::endpoint::PeerEndpoint::new_ephemeral())),
-            transfer_tokens: tokio::sync::Mutex::new(HashMap::new()),
-            sync_manager: tokio::sync::Mutex::new(SyncManager::new()),
-            auth_token: Arc::new(Mutex::new(None)),
-            pty_manager: extension::shell::pty::PtyManager::new(),
-            local_sync_loops: tokio::sync::Mutex::new(HashMap::new()),
-            leader_state: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
-            // Bind the loopback media server up-front. Failure to bind a
-            // random port is so unusual that crashing here is the right
-            // call — without the server the file browser's audio/video
-            // preview can't work on WebKitGTK at all.
-            media_server: tauri::async_runtime::block_on(media_server::MediaServer::start())
+        // Bind the loopback media server up-front. Failure to bind a
+        // random port is so unusual that crashing here is the right
+        // call — without the server the file browser's audio/video
+        // preview can't work on WebKitGTK at all.
+        .manage(AppState::new(
+            tauri::async_runtime::block_on(media_server::MediaServer::start())
                 .expect("failed to start local media server"),
-        })
+        ))
         //.manage(ExtensionState::default())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
@@ -361,6 +485,25 @@ pub fn run() {
         // Auto-start browser bridge on desktop and register main window close handler
         .setup(|app| {
             let _ = &app;
+
+            // Fix the app profile before anything resolves profile paths
+            app_profile::init(app.handle());
+
+            // Releases free pages of vaults in incremental auto-vacuum mode
+            tauri::async_runtime::spawn(database::storage::run_idle_vacuum_loop(
+                app.handle().clone(),
+            ));
+            // Applies data retention rules
+            tauri::async_runtime::spawn(crdt::retention::run_retention_loop(
+                app.handle().clone(),
+            ));
+            // Delivers outbound webhooks after CRDT commits
+            webhooks::start_webhook_service(app.handle().clone());
+            // Evaluates local automation rules
+            automation::start_automation_service(app.handle().clone());
+            // Samples CPU/memory of extension windows
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            extension::webview::monitor::start_resource_monitor(app.handle().clone());
             // Enable camera/media stream access in WebKitGTK on Linux
             #[cfg(target_os = "linux")]
             {
@@ -387,6 +530,33 @@ pub fn run() {
             {
                 let app_handle = app.handle().clone();
 
+                // Open the relay store before the bridge accepts peers
+                let state = app.state::<AppState>();
+                match app.path().app_data_dir() {
+                    Ok(dir) => {
+                        if let Err(e) = std::fs::create_dir_all(&dir)
+                            .map_err(|e| e.to_string())
+                            .and_then(|_| {
+                                state
+                                    .relay
+                                    .open(&dir.join(relay::RELAY_STORE_FILE))
+                                    .map_err(|e| e.to_string())
+                            })
+                        {
+                            eprintln!("[Relay] {}", e);
+                        }
+                    }
+                    Err(e) => eprintln!("[Relay] No app data directory: {}", e),
+                }
+                // `--headless`: run as a sync relay without showing the UI
+                if relay::headless_requested() {
+                    state.relay.set_headless(true);
+                    if let Some(main_window) = app.get_webview_window("main") {
+                        let _ = main_window.hide();
+                    }
+                    println!("[Relay] Running headless");
+                }
+
                 // Auto-start external bridge with default port
                 // Port can be changed later via settings when vault is opened
                 let app_handle_for_bridge = app_handle.clone();
@@ -403,20 +573,25 @@ pub fn run() {
                 // Register main window close handler to close all extension windows
                 if let Some(main_window) = app.get_webview_window("main") {
                     let app_handle_for_close = app_handle.clone();
-                    main_window.on_window_event(move |event| {
-                        if let tauri::WindowEvent::CloseRequested { .. } = event {
+                    main_window.on_window_event(move |event| match event {
+                        tauri::WindowEvent::CloseRequested { .. } => {
                             eprintln!("[Main Window] Close requested, closing all extension windows...");
                             let state = app_handle_for_close.state::<AppState>();
                             if let Err(e) = state.extension_webview_manager.close_all_extension_windows(&app_handle_for_close) {
                                 eprintln!("[Main Window] Failed to close extension windows: {:?}", e);
                             }
                         }
+                        tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
+                            extension::filedrop::handle_drop(&app_handle_for_close, "main", paths.clone());
+                        }
+                        _ => {}
                     });
                 }
             }
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        // Logging, metrics, panic capture and vault preconditions for every command
+        .invoke_handler(command_middleware::wrap(tauri::generate_handler![
             crypto::encrypt_for_identity,
             crypto::decrypt_for_identity,
             database::close_database,
@@ -426,18 +601,51 @@ pub fn run() {
             database::list_vaults,
             database::open_encrypted_database,
             database::sql_execute_with_crdt,
+            database::sql_execute_json_patch,
             database::sql_execute,
             database::sql_query_with_crdt,
             database::sql_select_with_crdt,
             database::sql_select,
+            database::sql_select_strict,
             database::sql_with_crdt,
             database::vault_exists,
             database::import_vault,
             database::crdt_cleanup_deleted_rows,
+            database::crdt_list_tombstoned,
+            database::crdt_restore_row,
+            database::undo_last,
+            database::redo_last,
+            database::undo_get_stack,
+            crdt::retention::crdt_list_retention_rules,
+            crdt::retention::crdt_save_retention_rule,
+            crdt::retention::crdt_remove_retention_rule,
+            crdt::retention::crdt_preview_retention,
+            crdt::retention::crdt_run_retention,
             database::crdt_get_stats,
             database::database_vacuum,
+            database::wal::database_checkpoint,
             database::change_vault_password,
             database::stats::get_database_info,
+            database::optimize::database_optimize,
+            database::storage::database_get_storage_info,
+            database::storage::database_configure_storage,
+            database::password_policy::vault_get_password_policy,
+            database::password_policy::vault_set_password_policy,
+            database::password_policy::vault_check_password,
+            database::password_policy::vault_get_password_rotation_status,
+            database::profile::profile_export,
+            database::profile::profile_import,
+            app_profile::commands::profile_list,
+            app_profile::commands::profile_create,
+            app_profile::commands::profile_switch,
+            app_profile::commands::profile_delete,
+            database::cipher::vault_get_cipher_settings,
+            database::metadata::vault_peek_metadata,
+            database::validate::vault_validate,
+            database::vault_set_cipher_settings,
+            database::vault_force_takeover,
+            database::unlock_throttle::vault_get_lockout_status,
+            database::unlock_throttle::vault_set_quick_unlock_wipe_threshold,
             database::migrations::apply_core_migrations,
             database::migrations::get_applied_core_migrations,
             database::migrations::get_unapplied_core_migrations,
@@ -456,22 +664,58 @@ pub fn run() {
             critical::commands::critical_app_restart,
             crdt::commands::get_table_schema,
             crdt::commands::get_dirty_tables,
+            crdt::commands::sync_get_status,
+            crdt::commands::sync_set_cursor,
+            sync::commands::sync_orchestrator_start,
+            sync::commands::sync_orchestrator_stop,
+            sync::commands::sync_orchestrator_trigger,
+            sync::commands::sync_orchestrator_list,
+            sync::commands::sync_messaging_list,
+            sync::commands::sync_messaging_save,
+            sync::commands::sync_messaging_delete,
+            sync::commands::sync_messaging_export_key,
+            sync::commands::share_create,
+            sync::commands::share_accept,
+            sync::commands::share_revoke,
+            sync::commands::share_list,
+            sync::commands::share_start_all,
+            sync::commands::share_set_role,
+            sync::commands::share_list_roles,
+            sync::commands::share_get_device_node,
+            sync::background::background_sync_get_settings,
+            sync::background::background_sync_set_settings,
+            sync::background::background_sync_run,
             crdt::commands::clear_dirty_table,
             crdt::commands::clear_all_dirty_tables,
             crdt::commands::get_all_crdt_tables,
             crdt::commands::ensure_extension_triggers,
             crdt::commands::apply_remote_changes_in_transaction,
+            crdt::commands::apply_remote_changes_chunked,
+            crdt::commands::crdt_cancel_remote_apply,
+            crdt::commands::crdt_reset_remote_apply,
+            crdt::commands::crdt_get_unique_conflict_settings,
+            crdt::commands::crdt_set_unique_conflict_strategy,
+            crdt::commands::crdt_get_hard_delete_tables,
+            crdt::commands::crdt_set_hard_delete_table,
             extension::database::commands::extension_database_execute,
             extension::database::commands::extension_database_transaction,
             extension::database::commands::extension_database_query,
+            extension::database::commands::extension_database_get_schema,
+            extension::database::commands::extension_database_set_row_filter,
+            extension::database::commands::extension_database_remove_row_filter,
+            extension::database::commands::extension_database_get_row_filters,
             extension::database::commands::extension_database_register_migrations,
             extension::database::commands::apply_synced_extension_migrations,
+            extension::database::data_archive::extension_export_data,
+            extension::database::data_archive::extension_import_data,
             extension::spaces::commands::extension_space_assign,
             passwords::commands::extension_password_list,
             passwords::commands::extension_password_read,
             passwords::commands::extension_password_create,
             passwords::commands::extension_password_update,
             passwords::commands::extension_password_delete,
+            passwords::autofill::commands::passwords_autofill_candidates,
+            passwords::autofill::commands::passwords_autofill_credential,
             extension::spaces::commands::extension_space_unassign,
             extension::spaces::commands::extension_space_get_assignments,
             extension::spaces::commands::extension_space_list,
@@ -486,6 +730,8 @@ pub fn run() {
             extension::mail::commands::extension_mail_append_message,
             extension::mail::commands::extension_mail_send_message,
             extension::mail::commands::extension_mail_build_rfc822,
+            extension::dav::commands::extension_dav_sync,
+            extension::dav::commands::extension_dav_reset_sync,
             extension::permissions::commands::extension_permissions_check_web,
             extension::permissions::commands::extension_permissions_check_database,
             extension::permissions::commands::extension_permissions_check_filesystem,
@@ -499,6 +745,13 @@ pub fn run() {
             extension::limits::commands::get_extension_limits,
             extension::limits::commands::update_extension_limits,
             extension::limits::commands::reset_extension_limits,
+            extension::revocation::commands::extension_check_revocations,
+            extension::revocation::commands::extension_set_revocation_override,
+            extension::recovery::commands::get_missing_extensions,
+            extension::recovery::commands::recover_missing_extension,
+            extension::dev_logs::commands::dev_extension_log,
+            extension::dev_logs::commands::dev_extension_get_logs,
+            extension::dev_logs::commands::dev_extension_clear_logs,
             extension::get_all_dev_extensions,
             extension::get_all_extensions,
             extension::get_extension_info,
@@ -513,6 +766,11 @@ pub fn run() {
             extension::get_extension_permissions,
             extension::update_extension_permissions,
             extension::update_extension_display_mode,
+            extension::get_extension_versions,
+            extension::set_extension_active_version,
+            extension::remove_extension_version,
+            extension::extension_list_migration_batches,
+            extension::extension_rollback_last_migration_batch,
             #[cfg(not(any(target_os = "android", target_os = "ios")))]
             extension::open_extension_webview_window,
             #[cfg(not(any(target_os = "android", target_os = "ios")))]
@@ -525,18 +783,36 @@ pub fn run() {
             extension::update_extension_webview_window_size,
             #[cfg(not(any(target_os = "android", target_os = "ios")))]
             extension::close_all_extension_webview_windows,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            extension::extension_webview_get_monitors,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            extension::extension_get_resource_usage,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            extension::extension_terminate_window,
             // WebView-specific API commands (for native window extensions, desktop only)
             #[cfg(not(any(target_os = "android", target_os = "ios")))]
             extension::webview::web::extension_get_info,
-            // Context commands (from core::context module)
-            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            // Mobile variants of the webview-only commands (extensions run in iframes)
+            #[cfg(any(target_os = "android", target_os = "ios"))]
+            extension::mobile::extension_get_info,
+            #[cfg(any(target_os = "android", target_os = "ios"))]
+            extension::mobile::open_extension_webview_window,
+            #[cfg(any(target_os = "android", target_os = "ios"))]
+            extension::mobile::close_all_extension_webview_windows,
+            extension::extension_get_host_url,
+            // Context commands (from core::context module, mobile variants reach the main window only)
             extension::core::context::extension_context_get,
-            #[cfg(not(any(target_os = "android", target_os = "ios")))]
             extension::core::context::extension_context_set,
-            #[cfg(not(any(target_os = "android", target_os = "ios")))]
             extension::core::context::extension_webview_broadcast,
-            #[cfg(not(any(target_os = "android", target_os = "ios")))]
             extension::core::context::extension_webview_emit,
+            extension::core::compatibility::host_get_api_version,
+            extension::capabilities::commands::extension_get_capabilities,
+            // Locale-aware formatting (intl module)
+            extension::intl::commands::extension_intl_format_number,
+            extension::intl::commands::extension_intl_format_currency,
+            extension::intl::commands::extension_intl_format_date,
+            extension::intl::commands::extension_intl_parse_number,
+            extension::intl::commands::extension_intl_parse_date,
             // Sync table filtering - needed for all platforms (mobile uses iframe forwarding)
             extension::extension_filter_sync_tables,
             // Sync table emission to webviews - desktop only
@@ -546,6 +822,10 @@ pub fn run() {
             extension::webview::filesystem::extension_filesystem_save_file,
             #[cfg(not(any(target_os = "android", target_os = "ios")))]
             extension::webview::filesystem::extension_filesystem_open_file,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            extension::webview::print::extension_webview_print,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            extension::webview::print::extension_export_pdf,
             // Window management (desktop only)
             #[cfg(not(any(target_os = "android", target_os = "ios")))]
             window::focus_main_window,
@@ -556,6 +836,13 @@ pub fn run() {
             shortcuts::create_desktop_shortcut,
             #[cfg(not(any(target_os = "android", target_os = "ios")))]
             shortcuts::remove_desktop_shortcut,
+            // Deep-link actions with result callbacks (desktop only)
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            deep_link::deep_link_parse_action,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            deep_link::deep_link_execute_action,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            deep_link::deep_link_get_public_key,
             // External bridge (desktop only)
             #[cfg(not(any(target_os = "android", target_os = "ios")))]
             external_bridge::external_bridge_start,
@@ -595,6 +882,70 @@ pub fn run() {
             external_bridge::external_bridge_unblock_session_client,
             #[cfg(not(any(target_os = "android", target_os = "ios")))]
             external_bridge::extension_signal_ready,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            external_bridge::external_bridge_credential_respond,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            external_bridge::external_bridge_get_credential_decisions,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            external_bridge::external_bridge_forget_credential_decisions,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            external_bridge::external_bridge_get_session_tokens,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            external_bridge::external_bridge_revoke_session_token,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            external_bridge::external_bridge_get_routes,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            external_bridge::external_bridge_get_network_config,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            external_bridge::external_bridge_set_network_config,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            external_bridge::external_bridge_get_network_status,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            external_bridge::external_bridge_regenerate_tls_identity,
+            // Local REST API (desktop only)
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            local_api::local_api_start,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            local_api::local_api_stop,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            local_api::local_api_get_status,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            local_api::local_api_create_token,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            local_api::local_api_list_tokens,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            local_api::local_api_revoke_token,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            mcp::mcp_get_status,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            mcp::mcp_set_enabled,
+            metrics::commands::metrics_snapshot,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            metrics::commands::metrics_get_endpoint_status,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            metrics::commands::metrics_set_endpoint_enabled,
+            health::commands::app_health_check,
+            error_code::commands::error_locale_set,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            relay::commands::relay_get_status,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            relay::commands::relay_set_config,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            relay::commands::relay_list_peers,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            relay::commands::relay_upsert_peer,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            relay::commands::relay_remove_peer,
+            webhooks::webhooks_list,
+            webhooks::webhooks_create,
+            webhooks::webhooks_update,
+            webhooks::webhooks_delete,
+            automation::automation_list_rules,
+            automation::automation_create_rule,
+            automation::automation_update_rule,
+            automation::automation_delete_rule,
+            auth::auth_biometric_status,
+            auth::auth_confirm_user,
             // Remote Storage API commands (internal - use extension_remote_storage_* for extensions)
             remote_storage::remote_storage_list_backends,
             remote_storage::remote_storage_add_backend,
@@ -609,6 +960,7 @@ pub fn run() {
             remote_storage::remote_storage_download_to_path,
             remote_storage::remote_storage_upload_from_path,
             remote_storage::remote_storage_cancel_transfer,
+            remote_storage::migration::remote_storage_migrate_backend,
             media_server::media_server_register,
             media_server::media_server_register_s3_stream,
             media_server::media_server_register_peer_stream,
@@ -650,11 +1002,86 @@ pub fn run() {
             extension::filesystem::commands::extension_filesystem_select_file,
             extension::filesystem::commands::extension_filesystem_rename,
             extension::filesystem::commands::extension_filesystem_copy,
+            extension::filesystem::commands::extension_filesystem_open_read_stream,
+            extension::filesystem::commands::extension_filesystem_read_chunk,
+            extension::filesystem::commands::extension_filesystem_open_write_stream,
+            extension::filesystem::commands::extension_filesystem_write_chunk,
+            extension::filesystem::commands::extension_filesystem_close_stream,
+            extension::filesystem::commands::extension_filesystem_zip,
+            extension::filesystem::commands::extension_filesystem_unzip,
             extension::filesystem::commands::extension_filesystem_known_paths,
             // File watcher commands
             extension::filesystem::commands::extension_filesystem_watch,
             extension::filesystem::commands::extension_filesystem_unwatch,
             extension::filesystem::commands::extension_filesystem_is_watching,
+            // Content extraction (PDF/OCR/plain text)
+            content_extract::commands::content_extract_text,
+            content_extract::commands::content_extract_get_job,
+            content_extract::commands::content_extract_ocr_available,
+            content_extract::commands::extension_content_extract_text,
+            content_extract::commands::extension_content_extract_get_job,
+            // QR codes
+            codes::commands::codes_generate_qr,
+            codes::commands::codes_scan_qr,
+            codes::commands::codes_resolve_scan,
+            codes::commands::extension_codes_generate_qr,
+            codes::commands::extension_codes_scan_qr,
+            // Image processing (resize/crop/convert, metadata removal)
+            media::image::commands::media_image_info,
+            media::image::commands::media_image_process,
+            media::image::commands::media_image_strip_metadata,
+            media::image::commands::extension_media_image_info,
+            media::image::commands::extension_media_image_process,
+            media::image::commands::extension_media_image_strip_metadata,
+            media::capture::commands::media_resolve_capture,
+            media::capture::commands::extension_media_capture_photo,
+            media::capture::commands::extension_media_record_audio,
+            interop::pdf::commands::pdf_get_metadata,
+            interop::pdf::commands::pdf_extract_text,
+            interop::pdf::commands::pdf_list_form_fields,
+            interop::pdf::commands::pdf_fill_form,
+            interop::pdf::commands::pdf_merge,
+            interop::pdf::commands::pdf_split,
+            interop::pdf::commands::extension_pdf_get_metadata,
+            interop::pdf::commands::extension_pdf_extract_text,
+            interop::pdf::commands::extension_pdf_list_form_fields,
+            interop::pdf::commands::extension_pdf_fill_form,
+            interop::pdf::commands::extension_pdf_merge,
+            interop::pdf::commands::extension_pdf_split,
+            system_pim::commands::system_pim_status,
+            system_pim::commands::extension_system_pim_status,
+            system_pim::commands::extension_system_pim_list_contacts,
+            system_pim::commands::extension_system_pim_list_calendars,
+            system_pim::commands::extension_system_pim_list_events,
+            accessibility::speech::commands::speech_status,
+            accessibility::speech::commands::speech_list_voices,
+            accessibility::speech::commands::speech_speak,
+            accessibility::speech::commands::speech_stop,
+            accessibility::speech::commands::speech_transcribe,
+            accessibility::speech::commands::extension_speech_status,
+            accessibility::speech::commands::extension_speech_list_voices,
+            accessibility::speech::commands::extension_speech_speak,
+            accessibility::speech::commands::extension_speech_stop,
+            accessibility::speech::commands::extension_speech_transcribe,
+            // File drop commands
+            extension::filedrop::commands::extension_filedrop_set_target,
+            extension::filedrop::commands::extension_filedrop_unload,
+            extension::filedrop::commands::extension_filedrop_read,
+            extension::filedrop::commands::extension_filedrop_release,
+            extension::share::commands::share_intake_take,
+            extension::share::commands::share_intake_forward,
+            extension::share::commands::share_intake_cancel,
+            // Extension event bus
+            extension::event_bus::commands::extension_event_publish,
+            extension::event_bus::commands::extension_event_get_policy,
+            extension::event_bus::commands::extension_event_set_policy,
+            // FileSync thumbnails
+            extension::filesync::commands::filesync_get_thumbnail,
+            extension::filesync::commands::filesync_clear_thumbnails,
+            extension::filesync::commands::filesync_get_usage,
+            extension::filesync::commands::filesync_set_backend_quota,
+            extension::filesync::commands::filesync_rebuild_usage,
+            extension::filesync::commands::extension_filesync_get_thumbnail,
             // Shell/PTY commands
             extension::shell::commands::extension_shell_list_available,
             extension::shell::commands::extension_shell_create,
@@ -720,7 +1147,7 @@ pub fn run() {
             file_sync::commands::file_sync_stop_all,
             file_sync::commands::file_sync_get_log,
             file_sync::commands::file_sync_clear_log,
-        ])
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
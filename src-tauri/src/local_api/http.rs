@@ -0,0 +1,282 @@
+//! Minimal HTTP/1.1 request parsing and JSON responses for the local API.
+//!
+//! One request per connection (`Connection: close`), bodies only via
+//! `Content-Length`. That is all the REST facade needs and keeps us from
+//! pulling an HTTP framework into the app.
+
+use serde::Serialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Upper bound for the request head
+pub const MAX_HEADER_BYTES: usize = 16 * 1024;
+/// Upper bound for request bodies (uploads are base64 in JSON)
+pub const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Error that maps directly to an HTTP status
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiError {
+    pub status: u16,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(400, message)
+    }
+
+    pub fn unauthorized() -> Self {
+        Self::new(401, "Missing or invalid API token")
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(403, message)
+    }
+
+    pub fn not_found() -> Self {
+        Self::new(404, "Not found")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// Case-insensitive header lookup
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn bearer_token(&self) -> Option<&str> {
+        let value = self.header("authorization")?;
+        let (scheme, token) = value.split_once(' ')?;
+        scheme
+            .eq_ignore_ascii_case("bearer")
+            .then(|| token.trim())
+            .filter(|t| !t.is_empty())
+    }
+
+    /// Rejects requests whose `Host` is not a loopback name, so a web page
+    /// cannot reach the API through DNS rebinding.
+    pub fn has_loopback_host(&self) -> bool {
+        let Some(host) = self.header("host") else {
+            return false;
+        };
+        let name = match host.rsplit_once(':') {
+            Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+            _ => host,
+        };
+        matches!(name, "127.0.0.1" | "localhost" | "[::1]")
+    }
+
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, ApiError> {
+        serde_json::from_slice(&self.body)
+            .map_err(|e| ApiError::bad_request(format!("Invalid JSON body: {e}")))
+    }
+}
+
+/// Parses the request line and headers (everything before CRLF CRLF).
+pub fn parse_head(head: &str) -> Result<(String, String, Vec<(String, String)>), ApiError> {
+    let mut lines = head.split("\r\n");
+    let request_line = lines
+        .next()
+        .ok_or_else(|| ApiError::bad_request("Empty request"))?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(ApiError::bad_request("Malformed request line"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(ApiError::new(505, "HTTP version not supported"));
+    }
+
+    let mut headers = Vec::new();
+    for line in lines.filter(|l| !l.is_empty()) {
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| ApiError::bad_request("Malformed header"))?;
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    // Query strings are not used by any route
+    let path = target.split('?').next().unwrap_or(target).to_string();
+    Ok((method.to_ascii_uppercase(), path, headers))
+}
+
+/// Reads one request from the stream.
+pub async fn read_request(stream: &mut TcpStream) -> Result<HttpRequest, ApiError> {
+    let mut buf = [0u8; 8192];
+    let mut data: Vec<u8> = Vec::with_capacity(1024);
+    let head_end = loop {
+        let n = stream
+            .read(&mut buf)
+            .await
+            .map_err(|e| ApiError::bad_request(e.to_string()))?;
+        if n == 0 {
+            return Err(ApiError::bad_request("Connection closed"));
+        }
+        data.extend_from_slice(&buf[..n]);
+        if let Some(pos) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if data.len() > MAX_HEADER_BYTES {
+            return Err(ApiError::new(431, "Request header fields too large"));
+        }
+    };
+
+    let head = std::str::from_utf8(&data[..head_end])
+        .map_err(|_| ApiError::bad_request("Request head is not UTF-8"))?;
+    let (method, path, headers) = parse_head(head)?;
+
+    let mut request = HttpRequest {
+        method,
+        path,
+        headers,
+        body: Vec::new(),
+    };
+    if request.header("transfer-encoding").is_some() {
+        return Err(ApiError::new(411, "Chunked bodies are not supported"));
+    }
+    let content_length = match request.header("content-length") {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| ApiError::bad_request("Invalid Content-Length"))?,
+        None => 0,
+    };
+    if content_length > MAX_BODY_BYTES {
+        return Err(ApiError::new(413, "Request body too large"));
+    }
+
+    let mut body = data.split_off(head_end + 4);
+    while body.len() < content_length {
+        let n = stream
+            .read(&mut buf)
+            .await
+            .map_err(|e| ApiError::bad_request(e.to_string()))?;
+        if n == 0 {
+            return Err(ApiError::bad_request("Incomplete request body"));
+        }
+        body.extend_from_slice(&buf[..n]);
+    }
+    body.truncate(content_length);
+    request.body = body;
+    Ok(request)
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        429 => "Too Many Requests",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        505 => "HTTP Version Not Supported",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Response envelope: `{ "success": true, "data": ... }` or
+/// `{ "success": false, "error": "..." }`
+#[derive(Serialize)]
+struct Envelope<'a, T: Serialize> {
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+}
+
+pub fn response_bytes<T: Serialize>(result: &Result<T, ApiError>) -> Vec<u8> {
+    let (status, body) = match result {
+        Ok(data) => (
+            200,
+            serde_json::to_vec(&Envelope {
+                success: true,
+                data: Some(data),
+                error: None,
+            }),
+        ),
+        Err(e) => (
+            e.status,
+            serde_json::to_vec(&Envelope::<()> {
+                success: false,
+                data: None,
+                error: Some(&e.message),
+            }),
+        ),
+    };
+    let body = body.unwrap_or_else(|_| br#"{"success":false}"#.to_vec());
+    raw_response_bytes(status, &body)
+}
+
+/// Response with a caller-provided JSON body (no envelope)
+pub fn raw_response_bytes(status: u16, body: &[u8]) -> Vec<u8> {
+    typed_response_bytes(status, "application/json", body)
+}
+
+/// Response with a caller-provided body of any content type
+pub fn typed_response_bytes(status: u16, content_type: &str, body: &[u8]) -> Vec<u8> {
+    let mut response = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n",
+        status_text(status),
+        body.len()
+    );
+    if status == 401 {
+        response.push_str("WWW-Authenticate: Bearer\r\n");
+    }
+    response.push_str("\r\n");
+    let mut bytes = response.into_bytes();
+    bytes.extend_from_slice(body);
+    bytes
+}
+
+pub async fn write_response<T: Serialize>(
+    stream: &mut TcpStream,
+    result: &Result<T, ApiError>,
+) -> std::io::Result<()> {
+    stream.write_all(&response_bytes(result)).await?;
+    stream.shutdown().await
+}
+
+pub async fn write_raw_response(
+    stream: &mut TcpStream,
+    status: u16,
+    body: &[u8],
+) -> std::io::Result<()> {
+    stream.write_all(&raw_response_bytes(status, body)).await?;
+    stream.shutdown().await
+}
+
+pub async fn write_text_response(
+    stream: &mut TcpStream,
+    content_type: &str,
+    body: &[u8],
+) -> std::io::Result<()> {
+    stream
+        .write_all(&typed_response_bytes(200, content_type, body))
+        .await?;
+    stream.shutdown().await
+}
@@ -0,0 +1,164 @@
+//! Local REST API
+//!
+//! Optional HTTP facade for integrations that cannot speak the external
+//! bridge's WebSocket protocol (shell scripts, automation tools). Desktop
+//! only, bound to 127.0.0.1 and off until started from the settings.
+//!
+//! Routes (JSON in, `{ success, data | error }` out):
+//! - `GET  /v1/status` — vault lock state and the extension the token acts as
+//! - `POST /v1/sql/select` — `{ sql, params }`, SELECT with the extension's
+//!   database permissions
+//! - `POST /v1/filesync/{list,download,upload,delete}` — remote storage
+//!   requests with the extension's `filesync` permissions
+//! - `POST /v1/mcp` — JSON-RPC endpoint of the MCP server (see `crate::mcp`),
+//!   only while MCP is enabled; answers with raw JSON-RPC, not the envelope
+//! - `GET  /metrics` — Prometheus metrics (see `crate::metrics`), only while
+//!   enabled; the one route that needs no token
+//!
+//! Requests carry `Authorization: Bearer <token>`. Tokens are issued for a
+//! client the user already authorized in the external bridge and every
+//! request re-checks the bridge authorization store, so revoking or blocking
+//! the client there locks it out here as well.
+
+mod http;
+mod server;
+#[cfg(test)]
+mod tests;
+mod tokens;
+
+pub use server::{LocalApi, MCP_PATH};
+pub use tokens::{IssuedLocalApiToken, LocalApiTokenInfo};
+
+use crate::error_code::{CodedError, ErrorCode};
+use crate::external_bridge::get_client_extension;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use thiserror::Error;
+use ts_rs::TS;
+
+/// Running state of the local API server
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalApiStatus {
+    pub running: bool,
+    pub port: u16,
+}
+
+/// Errors of the local API commands
+#[derive(Debug, Error)]
+pub enum LocalApiError {
+    #[error("Local API already running")]
+    AlreadyRunning,
+    #[error("Local API not running")]
+    NotRunning,
+    #[error("Failed to bind {addr}: {reason}")]
+    Bind { addr: String, reason: String },
+    #[error("Client {0} is not authorized")]
+    ClientNotAuthorized(String),
+    #[error("Token {0} not found")]
+    TokenNotFound(String),
+    #[error("{0}")]
+    TokenStore(String),
+}
+
+impl CodedError for LocalApiError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            LocalApiError::AlreadyRunning => ErrorCode::LocalApiAlreadyRunning,
+            LocalApiError::NotRunning => ErrorCode::LocalApiNotRunning,
+            LocalApiError::Bind { .. } => ErrorCode::PortUnavailable,
+            LocalApiError::ClientNotAuthorized(_) => ErrorCode::BridgeUnauthorized,
+            LocalApiError::TokenNotFound(_) => ErrorCode::ResourceNotFound,
+            LocalApiError::TokenStore(_) => ErrorCode::Filesystem,
+        }
+    }
+}
+
+impl Serialize for LocalApiError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Serialize::serialize(&self.envelope(), serializer)
+    }
+}
+
+/// Start the local API server (default port 19456)
+#[tauri::command]
+pub async fn local_api_start(
+    app_handle: AppHandle,
+    port: Option<u16>,
+    state: State<'_, AppState>,
+) -> Result<(), LocalApiError> {
+    let mut api = state.local_api.lock().await;
+    if api.is_running() {
+        return Ok(());
+    }
+    api.start(app_handle, port).await
+}
+
+/// Stop the local API server
+#[tauri::command]
+pub async fn local_api_stop(state: State<'_, AppState>) -> Result<(), LocalApiError> {
+    state.local_api.lock().await.stop().await
+}
+
+#[tauri::command]
+pub async fn local_api_get_status(
+    state: State<'_, AppState>,
+) -> Result<LocalApiStatus, LocalApiError> {
+    let api = state.local_api.lock().await;
+    Ok(LocalApiStatus {
+        running: api.is_running(),
+        port: api.get_port(),
+    })
+}
+
+/// Issue a token for an external bridge client. The client must hold a
+/// permanent authorization; the plaintext token is only returned here.
+#[tauri::command]
+pub async fn local_api_create_token(
+    app_handle: AppHandle,
+    client_id: String,
+    label: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<IssuedLocalApiToken, LocalApiError> {
+    if get_client_extension(&app_handle, &client_id)
+        .await
+        .is_none()
+    {
+        return Err(LocalApiError::ClientNotAuthorized(client_id));
+    }
+
+    let tokens = state.local_api.lock().await.tokens(&app_handle).await?;
+    let mut store = tokens.write().await;
+    let issued = store.issue(&client_id, label);
+    store.save(&app_handle)?;
+    Ok(issued)
+}
+
+#[tauri::command]
+pub async fn local_api_list_tokens(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<LocalApiTokenInfo>, LocalApiError> {
+    let tokens = state.local_api.lock().await.tokens(&app_handle).await?;
+    let store = tokens.read().await;
+    Ok(store.list())
+}
+
+#[tauri::command]
+pub async fn local_api_revoke_token(
+    app_handle: AppHandle,
+    token_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), LocalApiError> {
+    let tokens = state.local_api.lock().await.tokens(&app_handle).await?;
+    let mut store = tokens.write().await;
+    if !store.revoke(&token_id) {
+        return Err(LocalApiError::TokenNotFound(token_id));
+    }
+    store.save(&app_handle)
+}
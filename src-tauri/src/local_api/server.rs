@@ -0,0 +1,471 @@
+//! HTTP server and routes of the local REST API
+
+use crate::error_code::ErrorCode;
+use crate::extension::database::commands::query_as_extension;
+use crate::extension::error::ExtensionError;
+use crate::extension::remote_storage::commands::{
+    extension_remote_storage_delete, extension_remote_storage_download,
+    extension_remote_storage_list, extension_remote_storage_upload,
+};
+use crate::external_bridge::{check_client_blocked, get_client_extension};
+use crate::metrics::commands::METRICS_PATH;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, RwLock};
+use ts_rs::TS;
+
+use super::http::{
+    read_request, write_raw_response, write_response, write_text_response, ApiError, HttpRequest,
+};
+use super::tokens::TokenStore;
+use super::LocalApiError;
+
+/// Default port of the local REST API (next to the external bridge)
+pub const DEFAULT_LOCAL_API_PORT: u16 = 19456;
+/// Upper bound for a single request, including slow clients
+const REQUEST_TIMEOUT_SECS: u64 = 60;
+/// Path of the MCP endpoint (only served while MCP is enabled)
+pub const MCP_PATH: &str = "/v1/mcp";
+/// Content type of the metrics endpoint (Prometheus text format)
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Response of `GET /v1/status`
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalApiVaultStatus {
+    pub vault_open: bool,
+    /// Extension the token acts as (`None` while the vault is locked)
+    pub extension_id: Option<String>,
+    pub extension_name: Option<String>,
+}
+
+/// Body of `POST /v1/sql/select`
+#[derive(Debug, Deserialize)]
+struct SqlSelectBody {
+    sql: String,
+    #[serde(default)]
+    params: Vec<JsonValue>,
+}
+
+pub struct LocalApi {
+    running: bool,
+    current_port: u16,
+    shutdown_tx: Option<mpsc::Sender<()>>,
+    server_task: Option<tokio::task::JoinHandle<()>>,
+    tokens: Arc<RwLock<TokenStore>>,
+    tokens_loaded: bool,
+    mcp_enabled: Arc<AtomicBool>,
+    metrics_enabled: Arc<AtomicBool>,
+}
+
+impl Default for LocalApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalApi {
+    pub fn new() -> Self {
+        Self {
+            running: false,
+            current_port: DEFAULT_LOCAL_API_PORT,
+            shutdown_tx: None,
+            server_task: None,
+            tokens: Arc::new(RwLock::new(TokenStore::default())),
+            tokens_loaded: false,
+            mcp_enabled: Arc::new(AtomicBool::new(false)),
+            metrics_enabled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn get_port(&self) -> u16 {
+        self.current_port
+    }
+
+    /// Whether the MCP endpoint answers (opt-in, off by default)
+    pub fn is_mcp_enabled(&self) -> bool {
+        self.mcp_enabled.load(Ordering::SeqCst)
+    }
+
+    /// Takes effect immediately, also while the server is running
+    pub fn set_mcp_enabled(&self, enabled: bool) {
+        self.mcp_enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Whether `GET /metrics` answers (opt-in, off by default)
+    pub fn is_metrics_enabled(&self) -> bool {
+        self.metrics_enabled.load(Ordering::SeqCst)
+    }
+
+    /// Takes effect immediately, also while the server is running
+    pub fn set_metrics_enabled(&self, enabled: bool) {
+        self.metrics_enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Token store, loaded from disk on first use
+    pub async fn tokens(
+        &mut self,
+        app_handle: &AppHandle,
+    ) -> Result<Arc<RwLock<TokenStore>>, LocalApiError> {
+        if !self.tokens_loaded {
+            *self.tokens.write().await = TokenStore::load(app_handle)?;
+            self.tokens_loaded = true;
+        }
+        Ok(self.tokens.clone())
+    }
+
+    /// Starts the server on 127.0.0.1 (never on other interfaces)
+    pub async fn start(
+        &mut self,
+        app_handle: AppHandle,
+        port: Option<u16>,
+    ) -> Result<(), LocalApiError> {
+        if self.running {
+            return Err(LocalApiError::AlreadyRunning);
+        }
+        let tokens = self.tokens(&app_handle).await?;
+        let mcp_enabled = self.mcp_enabled.clone();
+        let metrics_enabled = self.metrics_enabled.clone();
+
+        let port = port.unwrap_or(DEFAULT_LOCAL_API_PORT);
+        let addr = format!("127.0.0.1:{port}");
+        let listener = TcpListener::bind(&addr)
+            .await
+            .map_err(|e| LocalApiError::Bind {
+                addr: addr.clone(),
+                reason: e.to_string(),
+            })?;
+        println!("[LocalApi] Listening on http://{addr}");
+
+        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    result = listener.accept() => match result {
+                        Ok((stream, _)) => {
+                            let app = app_handle.clone();
+                            let tokens = tokens.clone();
+                            let mcp_enabled = mcp_enabled.clone();
+                            let metrics_enabled = metrics_enabled.clone();
+                            tokio::spawn(async move {
+                                if let Err(e) = handle_connection(
+                                    stream,
+                                    app,
+                                    tokens,
+                                    mcp_enabled,
+                                    metrics_enabled,
+                                )
+                                .await
+                                {
+                                    eprintln!("[LocalApi] Connection error: {e}");
+                                }
+                            });
+                        }
+                        Err(e) => eprintln!("[LocalApi] Accept error: {e}"),
+                    },
+                    _ = shutdown_rx.recv() => break,
+                }
+            }
+        });
+
+        self.current_port = port;
+        self.shutdown_tx = Some(shutdown_tx);
+        self.server_task = Some(task);
+        self.running = true;
+        Ok(())
+    }
+
+    pub async fn stop(&mut self) -> Result<(), LocalApiError> {
+        if !self.running {
+            return Err(LocalApiError::NotRunning);
+        }
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(()).await;
+        }
+        // Wait for the accept loop so the port is free for a quick restart
+        if let Some(mut task) = self.server_task.take() {
+            if tokio::time::timeout(Duration::from_secs(2), &mut task)
+                .await
+                .is_err()
+            {
+                eprintln!("[LocalApi] Server task did not exit within 2s; aborting");
+                task.abort();
+            }
+        }
+        self.running = false;
+        Ok(())
+    }
+}
+
+/// What a route produced: an enveloped REST result, a raw MCP reply or the
+/// metrics text
+enum RouteOutput {
+    Rest(JsonValue),
+    /// JSON-RPC response body, `None` for notifications (202 Accepted)
+    Mcp(Option<JsonValue>),
+    /// Prometheus text exposition
+    Metrics(String),
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    app_handle: AppHandle,
+    tokens: Arc<RwLock<TokenStore>>,
+    mcp_enabled: Arc<AtomicBool>,
+    metrics_enabled: Arc<AtomicBool>,
+) -> std::io::Result<()> {
+    let result = tokio::time::timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS), async {
+        let request = read_request(&mut stream).await?;
+        if request.path == METRICS_PATH {
+            handle_metrics_request(&app_handle, &metrics_enabled, &request)
+                .await
+                .map(RouteOutput::Metrics)
+        } else if request.path == MCP_PATH {
+            handle_mcp_request(&app_handle, &tokens, &mcp_enabled, &request)
+                .await
+                .map(RouteOutput::Mcp)
+        } else {
+            handle_request(&app_handle, &tokens, &request)
+                .await
+                .map(RouteOutput::Rest)
+        }
+    })
+    .await
+    .unwrap_or_else(|_| Err(ApiError::new(408, "Request timeout")));
+
+    match result {
+        Ok(RouteOutput::Rest(data)) => write_response(&mut stream, &Ok(data)).await,
+        Ok(RouteOutput::Mcp(Some(body))) => {
+            let body = serde_json::to_vec(&body).unwrap_or_default();
+            write_raw_response(&mut stream, 200, &body).await
+        }
+        Ok(RouteOutput::Mcp(None)) => write_raw_response(&mut stream, 202, &[]).await,
+        Ok(RouteOutput::Metrics(text)) => {
+            write_text_response(&mut stream, PROMETHEUS_CONTENT_TYPE, text.as_bytes()).await
+        }
+        Err(e) => {
+            eprintln!("[LocalApi] Request failed ({}): {}", e.status, e.message);
+            write_response::<()>(&mut stream, &Err(e)).await
+        }
+    }
+}
+
+fn map_extension_error(e: ExtensionError) -> ApiError {
+    let status = match e.code() {
+        ErrorCode::PermissionDenied
+        | ErrorCode::PermissionPromptRequired
+        | ErrorCode::SecurityViolation => 403,
+        ErrorCode::PermissionPromptTimeout => 408,
+        ErrorCode::NotFound => 404,
+        ErrorCode::Validation | ErrorCode::Database => 400,
+        ErrorCode::LimitExceeded => 429,
+        _ => 500,
+    };
+    ApiError::new(status, e.to_string())
+}
+
+fn is_vault_open(state: &AppState) -> bool {
+    state.db.0.lock().map(|db| db.is_some()).unwrap_or(false)
+}
+
+/// Checks the Host header and maps the bearer token to its bridge client.
+async fn authenticate(
+    tokens: &RwLock<TokenStore>,
+    request: &HttpRequest,
+) -> Result<String, ApiError> {
+    if !request.has_loopback_host() {
+        return Err(ApiError::forbidden("Host not allowed"));
+    }
+
+    let store = tokens.read().await;
+    request
+        .bearer_token()
+        .and_then(|token| store.client_for(token))
+        .map(str::to_string)
+        .ok_or_else(ApiError::unauthorized)
+}
+
+/// The extension an authenticated client acts as
+struct ActingExtension {
+    extension_id: String,
+    public_key: String,
+    name: String,
+}
+
+/// Applies the bridge authorization store: blocked clients are rejected and
+/// the client's authorized extension must be loaded.
+async fn resolve_acting_extension(
+    app_handle: &AppHandle,
+    client_id: &str,
+) -> Result<ActingExtension, ApiError> {
+    if check_client_blocked(app_handle, client_id).await {
+        return Err(ApiError::forbidden("Client is blocked"));
+    }
+    let extension_id = get_client_extension(app_handle, client_id)
+        .await
+        .ok_or_else(|| ApiError::forbidden("Client is not authorized for any extension"))?;
+    let extension = app_handle
+        .state::<AppState>()
+        .extension_manager
+        .get_extension(&extension_id)
+        .ok_or_else(|| ApiError::new(503, "Extension is not loaded"))?;
+    Ok(ActingExtension {
+        extension_id,
+        public_key: extension.manifest.public_key.clone(),
+        name: extension.manifest.name.clone(),
+    })
+}
+
+/// JSON-RPC endpoint of the MCP server.
+async fn handle_mcp_request(
+    app_handle: &AppHandle,
+    tokens: &RwLock<TokenStore>,
+    mcp_enabled: &AtomicBool,
+    request: &HttpRequest,
+) -> Result<Option<JsonValue>, ApiError> {
+    let client_id = authenticate(tokens, request).await?;
+    if !mcp_enabled.load(Ordering::SeqCst) {
+        return Err(ApiError::not_found());
+    }
+    if request.method != "POST" {
+        return Err(ApiError::new(405, "Method not allowed"));
+    }
+    if !is_vault_open(&app_handle.state::<AppState>()) {
+        return Err(ApiError::new(503, "Vault is locked"));
+    }
+    let acting = resolve_acting_extension(app_handle, &client_id).await?;
+
+    let message: JsonValue = request.json()?;
+    Ok(crate::mcp::handle_message(app_handle, &acting.extension_id, &client_id, message).await)
+}
+
+/// Prometheus scrape endpoint. Needs no token: it is opt-in, loopback only
+/// and exposes counters, not vault content.
+async fn handle_metrics_request(
+    app_handle: &AppHandle,
+    metrics_enabled: &AtomicBool,
+    request: &HttpRequest,
+) -> Result<String, ApiError> {
+    if !request.has_loopback_host() {
+        return Err(ApiError::forbidden("Host not allowed"));
+    }
+    if !metrics_enabled.load(Ordering::SeqCst) {
+        return Err(ApiError::not_found());
+    }
+    if request.method != "GET" {
+        return Err(ApiError::new(405, "Method not allowed"));
+    }
+    let snapshot = crate::metrics::commands::collect(&app_handle.state::<AppState>()).await;
+    Ok(crate::metrics::render_prometheus(&snapshot))
+}
+
+/// Authenticates the request and dispatches it to a route.
+async fn handle_request(
+    app_handle: &AppHandle,
+    tokens: &RwLock<TokenStore>,
+    request: &HttpRequest,
+) -> Result<JsonValue, ApiError> {
+    let client_id = authenticate(tokens, request).await?;
+
+    let state = app_handle.state::<AppState>();
+    let route = (request.method.as_str(), request.path.as_str());
+
+    // Authorization lives in the vault; while it is locked only the status
+    // route answers.
+    if !is_vault_open(&state) {
+        return match route {
+            ("GET", "/v1/status") => to_json(LocalApiVaultStatus {
+                vault_open: false,
+                extension_id: None,
+                extension_name: None,
+            }),
+            _ => Err(ApiError::new(503, "Vault is locked")),
+        };
+    }
+
+    let ActingExtension {
+        extension_id,
+        public_key,
+        name,
+    } = resolve_acting_extension(app_handle, &client_id).await?;
+
+    match route {
+        ("GET", "/v1/status") => to_json(LocalApiVaultStatus {
+            vault_open: true,
+            extension_id: Some(extension_id),
+            extension_name: Some(name),
+        }),
+        ("POST", "/v1/sql/select") => {
+            let body: SqlSelectBody = request.json()?;
+            let result = query_as_extension(&state, &extension_id, body.sql, body.params)
+                .await
+                .map_err(map_extension_error)?;
+            to_json(result.rows)
+        }
+        ("POST", "/v1/filesync/list") => to_json(
+            extension_remote_storage_list(
+                app_handle.clone(),
+                public_key,
+                name,
+                request.json()?,
+                state,
+            )
+            .await
+            .map_err(map_extension_error)?,
+        ),
+        ("POST", "/v1/filesync/download") => to_json(
+            extension_remote_storage_download(
+                app_handle.clone(),
+                public_key,
+                name,
+                request.json()?,
+                state,
+            )
+            .await
+            .map_err(map_extension_error)?,
+        ),
+        ("POST", "/v1/filesync/upload") => to_json(
+            extension_remote_storage_upload(
+                app_handle.clone(),
+                public_key,
+                name,
+                request.json()?,
+                state,
+            )
+            .await
+            .map_err(map_extension_error)?,
+        ),
+        ("POST", "/v1/filesync/delete") => to_json(
+            extension_remote_storage_delete(
+                app_handle.clone(),
+                public_key,
+                name,
+                request.json()?,
+                state,
+            )
+            .await
+            .map_err(map_extension_error)?,
+        ),
+        (_, "/v1/status" | "/v1/sql/select")
+        | (_, "/v1/filesync/list" | "/v1/filesync/download")
+        | (_, "/v1/filesync/upload" | "/v1/filesync/delete") => {
+            Err(ApiError::new(405, "Method not allowed"))
+        }
+        _ => Err(ApiError::not_found()),
+    }
+}
+
+fn to_json<T: Serialize>(value: T) -> Result<JsonValue, ApiError> {
+    serde_json::to_value(value).map_err(|e| ApiError::new(500, e.to_string()))
+}
@@ -0,0 +1,98 @@
+use super::http::{parse_head, response_bytes, typed_response_bytes, ApiError, HttpRequest};
+use super::tokens::TokenStore;
+
+fn request_with_headers(headers: &[(&str, &str)]) -> HttpRequest {
+    HttpRequest {
+        method: "GET".to_string(),
+        path: "/v1/status".to_string(),
+        headers: headers
+            .iter()
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+            .collect(),
+        body: Vec::new(),
+    }
+}
+
+#[test]
+fn test_parse_head() {
+    let (method, path, headers) = parse_head(
+        "post /v1/sql/select?x=1 HTTP/1.1\r\nHost: 127.0.0.1:19456\r\nContent-Length: 2",
+    )
+    .unwrap();
+    assert_eq!(method, "POST");
+    assert_eq!(path, "/v1/sql/select");
+    assert_eq!(headers.len(), 2);
+    assert_eq!(headers[1], ("Content-Length".to_string(), "2".to_string()));
+}
+
+#[test]
+fn test_parse_head_rejects_malformed() {
+    assert_eq!(parse_head("GET /").unwrap_err().status, 400);
+    assert_eq!(parse_head("GET / HTTP/2").unwrap_err().status, 505);
+    assert_eq!(
+        parse_head("GET / HTTP/1.1\r\nno-colon").unwrap_err().status,
+        400
+    );
+}
+
+#[test]
+fn test_bearer_token() {
+    let req = request_with_headers(&[("authorization", "Bearer hxl_abc")]);
+    assert_eq!(req.bearer_token(), Some("hxl_abc"));
+
+    let req = request_with_headers(&[("Authorization", "Basic abc")]);
+    assert_eq!(req.bearer_token(), None);
+
+    let req = request_with_headers(&[("Authorization", "Bearer ")]);
+    assert_eq!(req.bearer_token(), None);
+}
+
+#[test]
+fn test_loopback_host() {
+    assert!(request_with_headers(&[("Host", "127.0.0.1:19456")]).has_loopback_host());
+    assert!(request_with_headers(&[("Host", "localhost")]).has_loopback_host());
+    assert!(request_with_headers(&[("Host", "[::1]:19456")]).has_loopback_host());
+    assert!(!request_with_headers(&[("Host", "evil.example:19456")]).has_loopback_host());
+    assert!(!request_with_headers(&[]).has_loopback_host());
+}
+
+#[test]
+fn test_token_store_issue_lookup_revoke() {
+    let mut store = TokenStore::default();
+    let issued = store.issue("client-1", Some("cli".to_string()));
+
+    assert!(issued.token.starts_with("hxl_"));
+    assert_eq!(store.client_for(&issued.token), Some("client-1"));
+    assert_eq!(store.client_for("hxl_wrong"), None);
+    assert_eq!(store.list().len(), 1);
+
+    // Only the hash is persisted
+    let serialized = serde_json::to_string(&store).unwrap();
+    assert!(!serialized.contains(&issued.token));
+
+    assert!(store.revoke(&issued.info.id));
+    assert!(!store.revoke(&issued.info.id));
+    assert_eq!(store.client_for(&issued.token), None);
+}
+
+#[test]
+fn test_response_envelope() {
+    let ok: Result<u32, ApiError> = Ok(7);
+    let text = String::from_utf8(response_bytes(&ok)).unwrap();
+    assert!(text.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(text.ends_with(r#"{"success":true,"data":7}"#));
+
+    let err: Result<u32, ApiError> = Err(ApiError::unauthorized());
+    let text = String::from_utf8(response_bytes(&err)).unwrap();
+    assert!(text.starts_with("HTTP/1.1 401 Unauthorized\r\n"));
+    assert!(text.contains("WWW-Authenticate: Bearer\r\n"));
+    assert!(text.contains(r#""success":false"#));
+}
+
+#[test]
+fn test_typed_response_bytes() {
+    let text = String::from_utf8(typed_response_bytes(200, "text/plain", b"up 1\n")).unwrap();
+    assert!(text.contains("Content-Type: text/plain\r\n"));
+    assert!(text.contains("Content-Length: 5\r\n"));
+    assert!(text.ends_with("\r\n\r\nup 1\n"));
+}
@@ -0,0 +1,157 @@
+//! API tokens of the local REST API
+//!
+//! Tokens are stored device-locally (never synced) as SHA-256 hashes; the
+//! plaintext is only returned once when the token is issued. Each token is
+//! bound to an external bridge client, whose authorization decides which
+//! extension the token acts as.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+use ts_rs::TS;
+
+use super::LocalApiError;
+
+const TOKEN_STORE_FILE: &str = "local_api_tokens.json";
+/// Prefix that makes leaked tokens recognizable in logs and secret scanners
+const TOKEN_PREFIX: &str = "hxl_";
+
+/// A stored token (hash only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalApiToken {
+    pub id: String,
+    /// Bridge client this token authenticates as
+    pub client_id: String,
+    pub label: Option<String>,
+    /// Hex-encoded SHA-256 of the plaintext token
+    pub token_hash: String,
+    /// Unix timestamp (ms)
+    pub created_at: i64,
+}
+
+/// Token metadata shown in the settings UI
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalApiTokenInfo {
+    pub id: String,
+    pub client_id: String,
+    pub label: Option<String>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+/// Result of issuing a token; `token` is not retrievable later
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedLocalApiToken {
+    pub info: LocalApiTokenInfo,
+    pub token: String,
+}
+
+impl From<&LocalApiToken> for LocalApiTokenInfo {
+    fn from(token: &LocalApiToken) -> Self {
+        Self {
+            id: token.id.clone(),
+            client_id: token.client_id.clone(),
+            label: token.label.clone(),
+            created_at: token.created_at,
+        }
+    }
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenStore {
+    #[serde(default)]
+    tokens: Vec<LocalApiToken>,
+}
+
+impl TokenStore {
+    fn path(app_handle: &AppHandle) -> Result<PathBuf, LocalApiError> {
+        let dir = crate::app_profile::data_dir(app_handle).map_err(|e| {
+            LocalApiError::TokenStore(format!("Failed to resolve app data dir: {e}"))
+        })?;
+        Ok(dir.join(TOKEN_STORE_FILE))
+    }
+
+    /// Loads the store; a missing file yields an empty store.
+    pub fn load(app_handle: &AppHandle) -> Result<Self, LocalApiError> {
+        let path = Self::path(app_handle)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path).map_err(|e| {
+            LocalApiError::TokenStore(format!("Failed to read {}: {e}", path.display()))
+        })?;
+        serde_json::from_str(&content)
+            .map_err(|e| LocalApiError::TokenStore(format!("Invalid local API token store: {e}")))
+    }
+
+    pub fn save(&self, app_handle: &AppHandle) -> Result<(), LocalApiError> {
+        let path = Self::path(app_handle)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                LocalApiError::TokenStore(format!("Failed to create {}: {e}", parent.display()))
+            })?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| LocalApiError::TokenStore(e.to_string()))?;
+        fs::write(&path, content).map_err(|e| {
+            LocalApiError::TokenStore(format!("Failed to write {}: {e}", path.display()))
+        })
+    }
+
+    /// Issues a new token for `client_id` and returns its plaintext.
+    pub fn issue(&mut self, client_id: &str, label: Option<String>) -> IssuedLocalApiToken {
+        let mut secret = [0u8; 32];
+        rand::fill(&mut secret);
+        let token = format!("{TOKEN_PREFIX}{}", hex::encode(secret));
+
+        let entry = LocalApiToken {
+            id: uuid::Uuid::new_v4().to_string(),
+            client_id: client_id.to_string(),
+            label,
+            token_hash: hash_token(&token),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as i64)
+                .unwrap_or(0),
+        };
+        let info = LocalApiTokenInfo::from(&entry);
+        self.tokens.push(entry);
+        IssuedLocalApiToken { info, token }
+    }
+
+    /// Client ID the token belongs to, if it is known.
+    pub fn client_for(&self, token: &str) -> Option<&str> {
+        if !token.starts_with(TOKEN_PREFIX) {
+            return None;
+        }
+        let hash = hash_token(token);
+        self.tokens
+            .iter()
+            .find(|t| t.token_hash == hash)
+            .map(|t| t.client_id.as_str())
+    }
+
+    pub fn list(&self) -> Vec<LocalApiTokenInfo> {
+        self.tokens.iter().map(LocalApiTokenInfo::from).collect()
+    }
+
+    /// Removes a token; returns false if it did not exist.
+    pub fn revoke(&mut self, id: &str) -> bool {
+        let before = self.tokens.len();
+        self.tokens.retain(|t| t.id != id);
+        self.tokens.len() != before
+    }
+}
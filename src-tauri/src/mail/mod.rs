@@ -6,7 +6,9 @@
 //! permission checks + extension-id resolution).
 //!
 //! Credentials are passed in per call by the caller (typically loaded
-//! from the core passwords vault). This module never persists secrets.
+//! from the core passwords vault). Both password/app-password login and
+//! OAuth2 (SASL XOAUTH2) are supported, see `MailAuthMethod`. This module
+//! never persists secrets.
 //!
 //! # Connection model
 //!
@@ -23,6 +25,6 @@ pub mod types;
 
 pub use error::MailError;
 pub use types::{
-    Account, Address, Attachment, ConnectionSecurity, FetchRange, ImapConfig, MailboxInfo,
-    Message, MessageEnvelope, OutgoingAttachment, OutgoingMessage, SmtpConfig,
+    Account, Address, Attachment, ConnectionSecurity, FetchRange, ImapConfig, MailAuthMethod,
+    MailboxInfo, Message, MessageEnvelope, OutgoingAttachment, OutgoingMessage, SmtpConfig,
 };
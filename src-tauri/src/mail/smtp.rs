@@ -8,11 +8,13 @@ use base64::engine::general_purpose::STANDARD;
 use base64::Engine as _;
 use lettre::message::header::ContentType;
 use lettre::message::{Attachment as LettreAttachment, MultiPart, SinglePart};
-use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::authentication::{Credentials, Mechanism};
 use lettre::{AsyncSmtpTransport, AsyncTransport, Message as LettreMessage, Tokio1Executor};
 
 use crate::mail::error::MailError;
-use crate::mail::types::{Address, ConnectionSecurity, OutgoingMessage, SmtpConfig};
+use crate::mail::types::{
+    Address, ConnectionSecurity, MailAuthMethod, OutgoingMessage, SmtpConfig,
+};
 
 /// Send an outgoing message. Returns the Message-ID assigned by lettre,
 /// useful for storing a reference locally and for threading follow-ups.
@@ -63,6 +65,10 @@ fn build_transport(
     config: &SmtpConfig,
 ) -> Result<AsyncSmtpTransport<Tokio1Executor>, MailError> {
     let creds = Credentials::new(config.username.clone(), config.password.clone());
+    let mechanisms = match config.auth {
+        MailAuthMethod::Password => vec![Mechanism::Plain, Mechanism::Login],
+        MailAuthMethod::OAuth2 => vec![Mechanism::Xoauth2],
+    };
 
     let transport = match config.security {
         ConnectionSecurity::Tls => {
@@ -72,6 +78,7 @@ fn build_transport(
                 })?
                 .port(config.port)
                 .credentials(creds)
+                .authentication(mechanisms)
                 .build()
         }
         ConnectionSecurity::StartTls => {
@@ -81,6 +88,7 @@ fn build_transport(
                 })?
                 .port(config.port)
                 .credentials(creds)
+                .authentication(mechanisms)
                 .build()
         }
         ConnectionSecurity::None => {
@@ -21,6 +21,19 @@ pub enum ConnectionSecurity {
     None,
 }
 
+/// How the client authenticates after connecting.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum MailAuthMethod {
+    /// LOGIN / AUTH PLAIN with the account or app password.
+    #[default]
+    Password,
+    /// SASL XOAUTH2; `password` holds the OAuth2 access token.
+    #[serde(rename = "oauth2")]
+    OAuth2,
+}
+
 /// IMAP server configuration + credentials.
 #[derive(Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
@@ -29,8 +42,20 @@ pub struct ImapConfig {
     pub host: String,
     pub port: u16,
     pub security: ConnectionSecurity,
+    #[serde(default)]
+    pub auth: MailAuthMethod,
+    #[serde(default)]
     pub username: String,
+    /// Password, app password or OAuth2 access token (see `auth`). May be
+    /// left empty when `credential_item_id` is set.
+    #[serde(default)]
     pub password: String,
+    /// Password-vault item to take username and secret from. Resolved by
+    /// the extension wrapper within the extension's tag scope, so the
+    /// secret never passes through extension code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub credential_item_id: Option<String>,
 }
 
 impl fmt::Debug for ImapConfig {
@@ -40,8 +65,10 @@ impl fmt::Debug for ImapConfig {
             .field("host", &self.host)
             .field("port", &self.port)
             .field("security", &self.security)
+            .field("auth", &self.auth)
             .field("username", &self.username)
             .field("password", &"<redacted>")
+            .field("credential_item_id", &self.credential_item_id)
             .finish()
     }
 }
@@ -54,8 +81,17 @@ pub struct SmtpConfig {
     pub host: String,
     pub port: u16,
     pub security: ConnectionSecurity,
+    #[serde(default)]
+    pub auth: MailAuthMethod,
+    #[serde(default)]
     pub username: String,
+    /// Password, app password or OAuth2 access token (see `auth`).
+    #[serde(default)]
     pub password: String,
+    /// Password-vault item to take username and secret from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub credential_item_id: Option<String>,
 }
 
 impl fmt::Debug for SmtpConfig {
@@ -64,8 +100,10 @@ impl fmt::Debug for SmtpConfig {
             .field("host", &self.host)
             .field("port", &self.port)
             .field("security", &self.security)
+            .field("auth", &self.auth)
             .field("username", &self.username)
             .field("password", &"<redacted>")
+            .field("credential_item_id", &self.credential_item_id)
             .finish()
     }
 }
@@ -0,0 +1,221 @@
+//! MCP (Model Context Protocol) server
+//!
+//! Lets local AI assistants call a small allow-list of vault operations as
+//! MCP tools (see `tools`). Opt-in and off by default: the JSON-RPC endpoint
+//! is served by the local REST API at `POST /v1/mcp` and only answers while
+//! enabled. Desktop only.
+//!
+//! Clients authenticate with a local API token, i.e. as an external bridge
+//! client the user already authorized, and every tool runs as that client's
+//! extension with its regular permission checks and prompts. Each tool call
+//! is written to the audit log (`haex_logs`, source `mcp`).
+
+mod protocol;
+#[cfg(test)]
+mod tests;
+mod tools;
+
+use crate::local_api::MCP_PATH;
+use crate::AppState;
+use protocol::{
+    parse_request, JsonRpcRequest, JsonRpcResponse, INTERNAL_ERROR, INVALID_PARAMS,
+    METHOD_NOT_FOUND,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use tauri::{AppHandle, Manager};
+use tools::{call_tool, tool_definitions, ToolError};
+use ts_rs::TS;
+
+/// Newest protocol revision we implement
+pub const LATEST_PROTOCOL_VERSION: &str = "2025-06-18";
+/// Revisions whose tool surface is identical to ours
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Audit log source of all MCP entries
+const AUDIT_SOURCE: &str = "mcp";
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct McpStatus {
+    pub enabled: bool,
+    /// The endpoint is only reachable while the local API runs
+    pub local_api_running: bool,
+    pub endpoint: String,
+}
+
+/// Echoes the client's revision if we support it, otherwise offers ours.
+pub fn negotiate_protocol_version(requested: Option<&str>) -> &'static str {
+    requested
+        .and_then(|v| SUPPORTED_PROTOCOL_VERSIONS.iter().find(|s| **s == v))
+        .copied()
+        .unwrap_or(LATEST_PROTOCOL_VERSION)
+}
+
+/// MCP `tools/call` result wrapping a JSON value as text content
+pub fn tool_result(value: &JsonValue, is_error: bool) -> JsonValue {
+    let text = match value {
+        JsonValue::String(s) => s.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    };
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    })
+}
+
+fn audit_tool_call(
+    state: &AppState,
+    extension_id: &str,
+    client_id: &str,
+    tool: &str,
+    outcome: &str,
+) {
+    let level = if outcome == "ok" { "info" } else { "warn" };
+    let message = format!("MCP tool '{tool}' called by client {client_id}: {outcome}");
+    let metadata = json!({
+        "subsystem": "Mcp",
+        "clientId": client_id,
+        "tool": tool,
+        "outcome": outcome,
+    });
+    if let Err(e) = crate::logging::insert_log(
+        state,
+        level,
+        AUDIT_SOURCE,
+        Some(extension_id),
+        &message,
+        Some(metadata),
+        "rust",
+    ) {
+        eprintln!("[MCP] Failed to write audit entry: {e}");
+    }
+}
+
+async fn handle_tools_call(
+    app_handle: &AppHandle,
+    extension_id: &str,
+    client_id: &str,
+    request: &JsonRpcRequest,
+) -> Result<JsonValue, JsonRpcResponse> {
+    let id = request.id.clone().unwrap_or(JsonValue::Null);
+    let params = request.params.clone().unwrap_or(JsonValue::Null);
+    let name = params
+        .get("name")
+        .and_then(JsonValue::as_str)
+        .ok_or_else(|| JsonRpcResponse::error(id.clone(), INVALID_PARAMS, "Missing tool name"))?
+        .to_string();
+    let arguments = params
+        .get("arguments")
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+
+    let state = app_handle.state::<AppState>();
+    let result = call_tool(app_handle, extension_id, &name, arguments).await;
+
+    let outcome = match &result {
+        Ok(_) => "ok".to_string(),
+        Err(ToolError::UnknownTool(_)) => "unknown_tool".to_string(),
+        Err(ToolError::InvalidArguments(_)) => "invalid_arguments".to_string(),
+        Err(ToolError::Failed(e)) => format!("{:?}", e.code()),
+    };
+    audit_tool_call(&state, extension_id, client_id, &name, &outcome);
+
+    match result {
+        Ok(value) => Ok(tool_result(&value, false)),
+        Err(ToolError::UnknownTool(tool)) => Err(JsonRpcResponse::error(
+            id,
+            INVALID_PARAMS,
+            format!("Unknown tool: {tool}"),
+        )),
+        Err(ToolError::InvalidArguments(reason)) => Err(JsonRpcResponse::error(
+            id,
+            INVALID_PARAMS,
+            format!("Invalid arguments: {reason}"),
+        )),
+        // Tool failures are results, not protocol errors, so the assistant
+        // sees them (e.g. "approve the prompt and retry")
+        Err(ToolError::Failed(e)) => Ok(tool_result(&JsonValue::String(e.to_string()), true)),
+    }
+}
+
+/// Handles one JSON-RPC message from an authenticated client acting as
+/// `extension_id`. Returns `None` for notifications.
+pub async fn handle_message(
+    app_handle: &AppHandle,
+    extension_id: &str,
+    client_id: &str,
+    message: JsonValue,
+) -> Option<JsonValue> {
+    let response = match parse_request(message) {
+        Err(response) => response,
+        Ok(request) if request.is_notification() => return None,
+        Ok(request) => {
+            let id = request.id.clone().unwrap_or(JsonValue::Null);
+            let result = match request.method.as_str() {
+                "initialize" => {
+                    let requested = request
+                        .params
+                        .as_ref()
+                        .and_then(|p| p.get("protocolVersion"))
+                        .and_then(JsonValue::as_str);
+                    Ok(json!({
+                        "protocolVersion": negotiate_protocol_version(requested),
+                        "capabilities": { "tools": { "listChanged": false } },
+                        "serverInfo": {
+                            "name": "haex-vault",
+                            "version": env!("CARGO_PKG_VERSION"),
+                        },
+                    }))
+                }
+                "ping" => Ok(json!({})),
+                "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+                "tools/call" => {
+                    handle_tools_call(app_handle, extension_id, client_id, &request).await
+                }
+                other => Err(JsonRpcResponse::error(
+                    id.clone(),
+                    METHOD_NOT_FOUND,
+                    format!("Method not found: {other}"),
+                )),
+            };
+            match result {
+                Ok(value) => JsonRpcResponse::success(id, value),
+                Err(response) => response,
+            }
+        }
+    };
+
+    Some(serde_json::to_value(&response).unwrap_or_else(|e| {
+        json!({
+            "jsonrpc": protocol::JSONRPC_VERSION,
+            "id": JsonValue::Null,
+            "error": { "code": INTERNAL_ERROR, "message": e.to_string() },
+        })
+    }))
+}
+
+#[tauri::command]
+pub async fn mcp_get_status(app_handle: AppHandle) -> McpStatus {
+    let state = app_handle.state::<AppState>();
+    let api = state.local_api.lock().await;
+    McpStatus {
+        enabled: api.is_mcp_enabled(),
+        local_api_running: api.is_running(),
+        endpoint: format!("http://127.0.0.1:{}{MCP_PATH}", api.get_port()),
+    }
+}
+
+/// Enable or disable the MCP endpoint. Not persisted: MCP starts disabled
+/// with every app launch.
+#[tauri::command]
+pub async fn mcp_set_enabled(app_handle: AppHandle, enabled: bool) {
+    let state = app_handle.state::<AppState>();
+    let api = state.local_api.lock().await;
+    api.set_mcp_enabled(enabled);
+    println!(
+        "[MCP] Endpoint {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+}
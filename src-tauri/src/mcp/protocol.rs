@@ -0,0 +1,91 @@
+//! JSON-RPC 2.0 message types used by the MCP endpoint
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+pub const JSONRPC_VERSION: &str = "2.0";
+
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// Incoming request or notification (notifications have no `id`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct JsonRpcRequest {
+    pub jsonrpc: String,
+    #[serde(default)]
+    pub id: Option<JsonValue>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Option<JsonValue>,
+}
+
+impl JsonRpcRequest {
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: String,
+    /// `null` when the request id could not be determined
+    pub id: JsonValue,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+impl JsonRpcResponse {
+    pub fn success(id: JsonValue, result: JsonValue) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn error(id: JsonValue, code: i64, message: impl Into<String>) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: None,
+            error: Some(JsonRpcError {
+                code,
+                message: message.into(),
+            }),
+        }
+    }
+}
+
+/// Parses a raw message. Batches are not supported (dropped from MCP in
+/// 2025-06-18), so arrays are rejected like any other malformed request.
+pub fn parse_request(message: JsonValue) -> Result<JsonRpcRequest, JsonRpcResponse> {
+    let id = message.get("id").cloned().unwrap_or(JsonValue::Null);
+    if !message.is_object() {
+        return Err(JsonRpcResponse::error(
+            JsonValue::Null,
+            INVALID_REQUEST,
+            "Expected a single JSON-RPC request object",
+        ));
+    }
+    let request: JsonRpcRequest = serde_json::from_value(message)
+        .map_err(|e| JsonRpcResponse::error(id.clone(), INVALID_REQUEST, e.to_string()))?;
+    if request.jsonrpc != JSONRPC_VERSION {
+        return Err(JsonRpcResponse::error(
+            id,
+            INVALID_REQUEST,
+            "Unsupported JSON-RPC version",
+        ));
+    }
+    Ok(request)
+}
@@ -0,0 +1,134 @@
+use super::protocol::{parse_request, JsonRpcResponse, INVALID_REQUEST};
+use super::tools::{
+    credential_prompt_target, filter_entries, tool_definitions, TOOL_GET_CREDENTIAL,
+    TOOL_LIST_FILES, TOOL_SEARCH_ENTRIES,
+};
+use super::{negotiate_protocol_version, tool_result, LATEST_PROTOCOL_VERSION};
+use crate::passwords::commands::PasswordItemSummary;
+use serde_json::json;
+
+fn entry(id: &str, title: &str, username: Option<&str>, tags: &[&str]) -> PasswordItemSummary {
+    PasswordItemSummary {
+        id: id.to_string(),
+        title: Some(title.to_string()),
+        username: username.map(str::to_string),
+        url: None,
+        icon: None,
+        color: None,
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        created_at: None,
+        updated_at: None,
+    }
+}
+
+#[test]
+fn test_parse_request() {
+    let request = parse_request(json!({
+        "jsonrpc": "2.0",
+        "id": 7,
+        "method": "tools/list"
+    }))
+    .unwrap();
+    assert_eq!(request.method, "tools/list");
+    assert_eq!(request.id, Some(json!(7)));
+    assert!(!request.is_notification());
+
+    let notification = parse_request(json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    }))
+    .unwrap();
+    assert!(notification.is_notification());
+}
+
+#[test]
+fn test_parse_request_rejects_invalid() {
+    let batch =
+        parse_request(json!([{ "jsonrpc": "2.0", "id": 1, "method": "ping" }])).unwrap_err();
+    assert_eq!(batch.error.unwrap().code, INVALID_REQUEST);
+    assert_eq!(batch.id, json!(null));
+
+    let version =
+        parse_request(json!({ "jsonrpc": "1.0", "id": "a", "method": "ping" })).unwrap_err();
+    assert_eq!(version.error.unwrap().code, INVALID_REQUEST);
+    assert_eq!(version.id, json!("a"));
+
+    let no_method = parse_request(json!({ "jsonrpc": "2.0", "id": 2 })).unwrap_err();
+    assert_eq!(no_method.error.unwrap().code, INVALID_REQUEST);
+}
+
+#[test]
+fn test_response_serialization() {
+    let ok = serde_json::to_value(JsonRpcResponse::success(json!(1), json!({}))).unwrap();
+    assert_eq!(ok, json!({ "jsonrpc": "2.0", "id": 1, "result": {} }));
+
+    let err = serde_json::to_value(JsonRpcResponse::error(json!(1), -32601, "nope")).unwrap();
+    assert!(err.get("result").is_none());
+    assert_eq!(err["error"]["code"], json!(-32601));
+}
+
+#[test]
+fn test_negotiate_protocol_version() {
+    assert_eq!(negotiate_protocol_version(Some("2025-03-26")), "2025-03-26");
+    assert_eq!(
+        negotiate_protocol_version(Some("1999-01-01")),
+        LATEST_PROTOCOL_VERSION
+    );
+    assert_eq!(negotiate_protocol_version(None), LATEST_PROTOCOL_VERSION);
+}
+
+#[test]
+fn test_tool_definitions() {
+    let names: Vec<_> = tool_definitions().iter().map(|t| t.name).collect();
+    assert_eq!(
+        names,
+        vec![TOOL_SEARCH_ENTRIES, TOOL_GET_CREDENTIAL, TOOL_LIST_FILES]
+    );
+
+    let value = serde_json::to_value(tool_definitions()).unwrap();
+    assert_eq!(value[0]["inputSchema"]["type"], json!("object"));
+}
+
+#[test]
+fn test_filter_entries() {
+    let items = vec![
+        entry("1", "GitHub", Some("octo"), &[]),
+        entry("2", "Bank", None, &["finance"]),
+        entry("3", "Mail", Some("me@github.example"), &[]),
+    ];
+
+    let hits = filter_entries(items.clone(), "GITHUB", None);
+    assert_eq!(
+        hits.iter().map(|i| i.id.as_str()).collect::<Vec<_>>(),
+        vec!["1", "3"]
+    );
+
+    let by_tag = filter_entries(items.clone(), "fin", None);
+    assert_eq!(by_tag.len(), 1);
+    assert_eq!(by_tag[0].id, "2");
+
+    assert_eq!(filter_entries(items.clone(), "", Some(2)).len(), 2);
+    // limit 0 is clamped to at least one result
+    assert_eq!(filter_entries(items, "", Some(0)).len(), 1);
+}
+
+#[test]
+fn test_credential_prompt_target_is_per_item() {
+    assert_eq!(credential_prompt_target("abc"), "mcp:get_credential:abc");
+    assert_ne!(credential_prompt_target("a"), credential_prompt_target("b"));
+}
+
+#[test]
+fn test_tool_result() {
+    let ok = tool_result(&json!({ "a": 1 }), false);
+    assert_eq!(ok["isError"], json!(false));
+    assert_eq!(ok["content"][0]["type"], json!("text"));
+    assert!(ok["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .contains("\"a\": 1"));
+
+    let err = tool_result(&json!("Permission required"), true);
+    assert_eq!(err["content"][0]["text"], json!("Permission required"));
+    assert_eq!(err["isError"], json!(true));
+}
@@ -0,0 +1,247 @@
+//! Allow-listed vault operations exposed as MCP tools
+//!
+//! Each tool runs as the extension the calling client is authorized for and
+//! goes through that extension's regular permission checks, so a missing
+//! grant raises the same permission prompt as a request from the extension
+//! itself. Secrets are never part of a listing: `get_credential` is the only
+//! tool that returns them and it asks the user on every single call.
+
+use crate::extension::error::ExtensionError;
+use crate::extension::permissions::types::ResourceType;
+use crate::extension::remote_storage::commands::extension_remote_storage_list;
+use crate::extension::utils::emit_permission_prompt_if_needed;
+use crate::passwords::commands::{
+    list_items_as_extension, read_item_as_extension, PasswordItemSummary,
+};
+use crate::remote_storage::types::StorageListRequest;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use tauri::{AppHandle, Manager};
+
+pub const TOOL_SEARCH_ENTRIES: &str = "search_entries";
+pub const TOOL_GET_CREDENTIAL: &str = "get_credential";
+pub const TOOL_LIST_FILES: &str = "list_files";
+
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+const MAX_SEARCH_LIMIT: usize = 100;
+
+/// Entry of a `tools/list` result
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub input_schema: JsonValue,
+}
+
+pub fn tool_definitions() -> Vec<McpToolDefinition> {
+    vec![
+        McpToolDefinition {
+            name: TOOL_SEARCH_ENTRIES,
+            description: "Search vault entries by title, username, URL or tag. \
+                          Returns summaries without secrets.",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Case-insensitive search text" },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": MAX_SEARCH_LIMIT }
+                },
+                "required": ["query"]
+            }),
+        },
+        McpToolDefinition {
+            name: TOOL_GET_CREDENTIAL,
+            description: "Fetch one entry including its secrets. The user has to \
+                          approve every call in haex-vault; retry after approval.",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Entry id from search_entries" }
+                },
+                "required": ["id"]
+            }),
+        },
+        McpToolDefinition {
+            name: TOOL_LIST_FILES,
+            description: "List files of a remote storage backend.",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "backendId": { "type": "string" },
+                    "prefix": { "type": "string" }
+                },
+                "required": ["backendId"]
+            }),
+        },
+    ]
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchEntriesArgs {
+    query: String,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetCredentialArgs {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListFilesArgs {
+    backend_id: String,
+    #[serde(default)]
+    prefix: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ToolError {
+    UnknownTool(String),
+    InvalidArguments(String),
+    Failed(ExtensionError),
+}
+
+impl From<ExtensionError> for ToolError {
+    fn from(e: ExtensionError) -> Self {
+        Self::Failed(e)
+    }
+}
+
+fn parse_args<T: for<'de> Deserialize<'de>>(arguments: JsonValue) -> Result<T, ToolError> {
+    serde_json::from_value(arguments).map_err(|e| ToolError::InvalidArguments(e.to_string()))
+}
+
+/// Case-insensitive match on the non-secret summary fields
+pub fn filter_entries(
+    items: Vec<PasswordItemSummary>,
+    query: &str,
+    limit: Option<usize>,
+) -> Vec<PasswordItemSummary> {
+    let needle = query.trim().to_lowercase();
+    let limit = limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+    let contains = |field: &Option<String>| {
+        field
+            .as_deref()
+            .is_some_and(|v| v.to_lowercase().contains(&needle))
+    };
+
+    items
+        .into_iter()
+        .filter(|item| {
+            needle.is_empty()
+                || contains(&item.title)
+                || contains(&item.username)
+                || contains(&item.url)
+                || item.tags.iter().any(|t| t.to_lowercase().contains(&needle))
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Session-permission target of the per-call credential prompt
+pub fn credential_prompt_target(item_id: &str) -> String {
+    format!("mcp:{TOOL_GET_CREDENTIAL}:{item_id}")
+}
+
+/// Requires a one-time session decision for this exact item. The decision
+/// is consumed, so the next call prompts again.
+fn consume_credential_approval(
+    app_handle: &AppHandle,
+    state: &AppState,
+    extension_id: &str,
+    extension_name: &str,
+    item_id: &str,
+) -> Result<(), ExtensionError> {
+    let target = credential_prompt_target(item_id);
+    let sessions = &state.session_permissions;
+
+    if sessions.is_granted(extension_id, ResourceType::Passwords, &target) {
+        sessions.remove_permission(extension_id, ResourceType::Passwords, &target);
+        return Ok(());
+    }
+    if sessions.is_denied(extension_id, ResourceType::Passwords, &target) {
+        sessions.remove_permission(extension_id, ResourceType::Passwords, &target);
+        return Err(ExtensionError::permission_denied(
+            extension_id,
+            "read",
+            &format!("credential '{item_id}' via MCP"),
+        ));
+    }
+
+    let error = ExtensionError::permission_prompt_required(
+        extension_id,
+        extension_name,
+        "passwords",
+        "read",
+        &target,
+    );
+    emit_permission_prompt_if_needed(app_handle, &error);
+    Err(error)
+}
+
+/// Runs a tool as `extension_id` and returns its JSON result.
+pub async fn call_tool(
+    app_handle: &AppHandle,
+    extension_id: &str,
+    name: &str,
+    arguments: JsonValue,
+) -> Result<JsonValue, ToolError> {
+    let state = app_handle.state::<AppState>();
+    let extension = state
+        .extension_manager
+        .get_extension(extension_id)
+        .ok_or_else(|| ExtensionError::NotFound {
+            public_key: String::new(),
+            name: extension_id.to_string(),
+        })?;
+
+    let value = match name {
+        TOOL_SEARCH_ENTRIES => {
+            let args: SearchEntriesArgs = parse_args(arguments)?;
+            let items = list_items_as_extension(app_handle, &state, extension_id).await?;
+            serde_json::to_value(filter_entries(items, &args.query, args.limit))
+        }
+        TOOL_GET_CREDENTIAL => {
+            let args: GetCredentialArgs = parse_args(arguments)?;
+            consume_credential_approval(
+                app_handle,
+                &state,
+                extension_id,
+                &extension.manifest.name,
+                &args.id,
+            )?;
+            serde_json::to_value(
+                read_item_as_extension(app_handle, &state, extension_id, &args.id).await?,
+            )
+        }
+        TOOL_LIST_FILES => {
+            let args: ListFilesArgs = parse_args(arguments)?;
+            let request = StorageListRequest {
+                backend_id: args.backend_id,
+                prefix: args.prefix,
+            };
+            serde_json::to_value(
+                extension_remote_storage_list(
+                    app_handle.clone(),
+                    extension.manifest.public_key.clone(),
+                    extension.manifest.name.clone(),
+                    request,
+                    state,
+                )
+                .await?,
+            )
+        }
+        other => return Err(ToolError::UnknownTool(other.to_string())),
+    };
+
+    value.map_err(|e| {
+        ToolError::Failed(ExtensionError::ValidationError {
+            reason: format!("Failed to serialize tool result: {e}"),
+        })
+    })
+}
@@ -0,0 +1,154 @@
+// src-tauri/src/media/capture/broker.rs
+//!
+//! Capture broker (in-memory)
+//!
+//! Tracks the captures extensions requested from the main window, like
+//! `codes::ScanBroker` does for scans. Each extension has at most one open
+//! request; the extension's command parks on it until the main window
+//! delivers the media, the user cancels or the request times out.
+
+use super::error::MediaCaptureError;
+use super::types::CaptureKind;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// How long an extension waits for the user to take a photo or to start
+/// and stop a recording, on top of the recording limit
+pub const CAPTURE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Encoded photo or recording as delivered by the main window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedMedia {
+    pub mime_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// A capture the main window has not answered yet
+#[derive(Debug)]
+struct PendingCapture {
+    extension_id: String,
+    kind: CaptureKind,
+    /// `None` when the user cancels
+    result: oneshot::Sender<Option<CapturedMedia>>,
+}
+
+/// Pending capture requests by request ID
+#[derive(Debug)]
+pub struct CaptureBroker {
+    pending: Mutex<HashMap<String, PendingCapture>>,
+    timeout: Duration,
+}
+
+impl Default for CaptureBroker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CaptureBroker {
+    pub fn new() -> Self {
+        Self::with_timeout(CAPTURE_TIMEOUT)
+    }
+
+    pub(crate) fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            timeout,
+        }
+    }
+
+    /// Opens a capture request for `extension_id` and returns its ID
+    /// together with the receiver to pass to [`wait`](Self::wait).
+    pub fn begin(
+        &self,
+        extension_id: &str,
+        kind: CaptureKind,
+    ) -> Result<(String, oneshot::Receiver<Option<CapturedMedia>>), MediaCaptureError> {
+        let mut pending = self.lock()?;
+        // Requests whose caller gave up (dropped receiver) don't block
+        pending.retain(|_, capture| !capture.result.is_closed());
+        if pending.values().any(|c| c.extension_id == extension_id) {
+            return Err(MediaCaptureError::CaptureInProgress);
+        }
+
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let (result, receiver) = oneshot::channel();
+        pending.insert(
+            request_id.clone(),
+            PendingCapture {
+                extension_id: extension_id.to_string(),
+                kind,
+                result,
+            },
+        );
+        Ok((request_id, receiver))
+    }
+
+    /// Waits for the answer to `request_id`, at most the broker timeout plus
+    /// `recording`. `Ok(None)` means the user cancelled.
+    pub async fn wait(
+        &self,
+        request_id: &str,
+        receiver: oneshot::Receiver<Option<CapturedMedia>>,
+        recording: Duration,
+    ) -> Result<Option<CapturedMedia>, MediaCaptureError> {
+        let timeout = self.timeout + recording;
+        match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(media)) => Ok(media),
+            // Sender dropped without an answer: treat like a cancel
+            Ok(Err(_)) => Ok(None),
+            Err(_) => {
+                self.cancel(request_id);
+                Err(MediaCaptureError::Timeout {
+                    timeout_secs: timeout.as_secs(),
+                })
+            }
+        }
+    }
+
+    /// Kind of the open request `request_id`.
+    pub fn kind(&self, request_id: &str) -> Result<CaptureKind, MediaCaptureError> {
+        self.lock()?.get(request_id).map(|c| c.kind).ok_or_else(|| {
+            MediaCaptureError::RequestNotFound {
+                id: request_id.to_string(),
+            }
+        })
+    }
+
+    /// Delivers the captured media (`None` = cancelled) to the waiting
+    /// extension.
+    pub fn resolve(
+        &self,
+        request_id: &str,
+        media: Option<CapturedMedia>,
+    ) -> Result<(), MediaCaptureError> {
+        let capture =
+            self.lock()?
+                .remove(request_id)
+                .ok_or_else(|| MediaCaptureError::RequestNotFound {
+                    id: request_id.to_string(),
+                })?;
+        // The extension may have stopped waiting in the meantime
+        let _ = capture.result.send(media);
+        Ok(())
+    }
+
+    /// Drops `request_id` without an answer.
+    pub fn cancel(&self, request_id: &str) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(request_id);
+        }
+    }
+
+    fn lock(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, HashMap<String, PendingCapture>>, MediaCaptureError> {
+        self.pending
+            .lock()
+            .map_err(|e| MediaCaptureError::Internal {
+                reason: e.to_string(),
+            })
+    }
+}
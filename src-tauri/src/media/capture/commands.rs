@@ -0,0 +1,204 @@
+// src-tauri/src/media/capture/commands.rs
+//!
+//! Capture Commands
+//!
+//! `media_resolve_capture` is the main window's answer to
+//! `media:capture-requested`. `extension_media_*` are the variants for
+//! extensions:
+//! - `extension_media_capture_photo` requires `camera` permission with the
+//!   `capture` action
+//! - `extension_media_record_audio` requires `microphone` permission with
+//!   the `record` action
+
+use super::broker::CapturedMedia;
+use super::error::MediaCaptureError;
+use super::store;
+use super::types::{CaptureKind, MediaCaptureRequested, MediaCaptureResult};
+use crate::events;
+use crate::extension::error::ExtensionError;
+use crate::extension::filesystem::commands::check_private_quota;
+use crate::extension::filesystem::private_dir::{self, PRIVATE_ROOT};
+use crate::extension::permissions::manager::PermissionManager;
+use crate::extension::permissions::types::{CameraAction, MicrophoneAction};
+use crate::extension::utils::{emit_permission_prompt_if_needed, resolve_extension_id};
+use crate::AppState;
+use std::time::Duration;
+use tauri::{AppHandle, State, WebviewWindow};
+
+/// Recording limit if the extension sets none
+const DEFAULT_AUDIO_SECS: u32 = 300;
+const MAX_AUDIO_SECS: u32 = 3600;
+
+fn to_extension_error(e: MediaCaptureError) -> ExtensionError {
+    ExtensionError::ValidationError {
+        reason: e.to_string(),
+    }
+}
+
+/// Answer a `media:capture-requested` event with the base64-encoded photo
+/// or recording. `data` is `None` if the user cancelled.
+#[tauri::command(rename_all = "camelCase")]
+pub fn media_resolve_capture(
+    state: State<'_, AppState>,
+    request_id: String,
+    data: Option<String>,
+    mime_type: Option<String>,
+) -> Result<(), MediaCaptureError> {
+    let media = match data {
+        None => None,
+        Some(data) => {
+            let kind = state.media_capture.kind(&request_id)?;
+            let mime_type = mime_type.ok_or_else(|| MediaCaptureError::InvalidRequest {
+                reason: "mimeType is required with data".to_string(),
+            })?;
+            store::file_extension(kind, &mime_type)?;
+            Some(CapturedMedia {
+                mime_type: store::essence(&mime_type),
+                bytes: store::decode(&data)?,
+            })
+        }
+    };
+    state.media_capture.resolve(&request_id, media)
+}
+
+/// Let the user take a photo for an extension (permission-checked).
+///
+/// The main window shows the camera; the photo lands in the extension's
+/// private directory. `None` if the user cancelled.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_media_capture_photo(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<Option<MediaCaptureResult>, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+
+    let permission_result =
+        PermissionManager::check_camera_permission(&state, &extension_id, CameraAction::Capture)
+            .await;
+    if let Err(ref e) = permission_result {
+        emit_permission_prompt_if_needed(&app_handle, e);
+    }
+    permission_result?;
+
+    capture(&app_handle, &state, &extension_id, CaptureKind::Photo, None).await
+}
+
+/// Let the user record audio for an extension (permission-checked).
+///
+/// Recordings stop after `max_duration_secs` (default 5 minutes, at most an
+/// hour) and land in the extension's private directory. `None` if the user
+/// cancelled.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_media_record_audio(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    max_duration_secs: Option<u32>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<Option<MediaCaptureResult>, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let max_duration_secs = max_duration_secs.unwrap_or(DEFAULT_AUDIO_SECS);
+    if max_duration_secs == 0 || max_duration_secs > MAX_AUDIO_SECS {
+        return Err(ExtensionError::ValidationError {
+            reason: format!("maxDurationSecs must be 1 to {MAX_AUDIO_SECS}"),
+        });
+    }
+
+    let permission_result = PermissionManager::check_microphone_permission(
+        &state,
+        &extension_id,
+        MicrophoneAction::Record,
+    )
+    .await;
+    if let Err(ref e) = permission_result {
+        emit_permission_prompt_if_needed(&app_handle, e);
+    }
+    permission_result?;
+
+    capture(
+        &app_handle,
+        &state,
+        &extension_id,
+        CaptureKind::Audio,
+        Some(max_duration_secs),
+    )
+    .await
+}
+
+/// Asks the main window for a capture, waits for it and stores the result
+/// in the extension's private directory.
+async fn capture(
+    app_handle: &AppHandle,
+    state: &State<'_, AppState>,
+    extension_id: &str,
+    kind: CaptureKind,
+    max_duration_secs: Option<u32>,
+) -> Result<Option<MediaCaptureResult>, ExtensionError> {
+    let extension = state
+        .extension_manager
+        .get_extension(extension_id)
+        .ok_or_else(|| ExtensionError::ValidationError {
+            reason: format!("Extension not found: {}", extension_id),
+        })?;
+
+    let (request_id, receiver) = state
+        .media_capture
+        .begin(extension_id, kind)
+        .map_err(to_extension_error)?;
+    let request = MediaCaptureRequested {
+        request_id: request_id.clone(),
+        extension_id: extension_id.to_string(),
+        extension_name: extension.manifest.name.clone(),
+        kind,
+        max_duration_secs,
+    };
+    if let Err(e) = events::emit_to_main(app_handle, &request) {
+        state.media_capture.cancel(&request_id);
+        return Err(ExtensionError::ValidationError {
+            reason: format!("Failed to request capture: {e}"),
+        });
+    }
+
+    let recording = Duration::from_secs(max_duration_secs.unwrap_or(0).into());
+    let Some(media) = state
+        .media_capture
+        .wait(&request_id, receiver, recording)
+        .await
+        .map_err(to_extension_error)?
+    else {
+        return Ok(None);
+    };
+
+    let file_extension =
+        store::file_extension(kind, &media.mime_type).map_err(to_extension_error)?;
+    let relative = store::relative_path(kind, file_extension);
+    let root = private_dir::private_dir(
+        app_handle,
+        &extension.manifest.public_key,
+        &extension.manifest.name,
+    )?;
+    let path = private_dir::resolve_in(&root, &relative)?;
+    let size = media.bytes.len() as u64;
+    check_private_quota(state, &root, size, 0)?;
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| ExtensionError::filesystem_with_path(parent.display().to_string(), e))?;
+    }
+    tokio::fs::write(&path, &media.bytes)
+        .await
+        .map_err(|e| ExtensionError::filesystem_with_path(path.display().to_string(), e))?;
+
+    Ok(Some(MediaCaptureResult {
+        path: format!("{PRIVATE_ROOT}{relative}"),
+        mime_type: media.mime_type,
+        size,
+    }))
+}
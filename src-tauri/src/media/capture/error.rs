@@ -0,0 +1,73 @@
+// src-tauri/src/media/capture/error.rs
+//!
+//! Capture Error Types
+//!
+
+use crate::error_code::{serialize_tagged, CodedError, ErrorCode};
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Error, Serialize)]
+#[serde(tag = "type", content = "details")]
+#[serde(remote = "Self")]
+pub enum MediaCaptureError {
+    #[error("A capture is already in progress for this extension")]
+    CaptureInProgress,
+
+    #[error("Capture request not found: {id}")]
+    RequestNotFound { id: String },
+
+    #[error("Nothing was captured within {timeout_secs} seconds")]
+    Timeout { timeout_secs: u64 },
+
+    #[error("Unsupported {kind} format: {mime_type}")]
+    UnsupportedFormat { kind: String, mime_type: String },
+
+    #[error("Capture too large: {size} bytes (max {max})")]
+    TooLarge { size: u64, max: u64 },
+
+    #[error("Invalid capture data: {reason}")]
+    InvalidData { reason: String },
+
+    #[error("Invalid request: {reason}")]
+    InvalidRequest { reason: String },
+
+    #[error("Internal error: {reason}")]
+    Internal { reason: String },
+}
+
+impl CodedError for MediaCaptureError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            MediaCaptureError::CaptureInProgress => ErrorCode::AlreadyInProgress,
+            MediaCaptureError::RequestNotFound { .. } => ErrorCode::ResourceNotFound,
+            MediaCaptureError::Timeout { .. } => ErrorCode::Timeout,
+            MediaCaptureError::UnsupportedFormat { .. } => ErrorCode::Unsupported,
+            MediaCaptureError::TooLarge { .. } => ErrorCode::LimitExceeded,
+            MediaCaptureError::InvalidData { .. } | MediaCaptureError::InvalidRequest { .. } => {
+                ErrorCode::Validation
+            }
+            MediaCaptureError::Internal { .. } => ErrorCode::Internal,
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        self.tagged().get("details").cloned()
+    }
+}
+
+impl MediaCaptureError {
+    /// The derived `{ type, details }` representation
+    fn tagged(&self) -> serde_json::Value {
+        MediaCaptureError::serialize(self, serde_json::value::Serializer).unwrap_or_default()
+    }
+}
+
+impl Serialize for MediaCaptureError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_tagged(self, &self.tagged(), serializer)
+    }
+}
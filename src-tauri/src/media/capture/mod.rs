@@ -0,0 +1,30 @@
+// src-tauri/src/media/capture/mod.rs
+//!
+//! Camera and Microphone Capture
+//!
+//! Extension webviews can't reliably use `getUserMedia` under the custom
+//! protocol, so capturing works like QR scanning (`crate::codes`): the
+//! extension's command asks the main window (`media:capture-requested`),
+//! which owns camera and microphone, and parks until it answers through
+//! `media_resolve_capture` with the encoded photo or recording.
+//!
+//! Results are written to `private://captures/` in the extension's private
+//! directory and count against its storage quota; the extension gets the
+//! `private://` path back, never the raw device stream.
+//!
+//! - `extension_media_capture_photo` needs `camera` permission with the
+//!   `capture` action
+//! - `extension_media_record_audio` needs `microphone` permission with the
+//!   `record` action
+
+pub mod broker;
+pub mod commands;
+pub mod error;
+pub mod store;
+pub mod types;
+
+#[cfg(test)]
+mod tests;
+
+pub use broker::CaptureBroker;
+pub use error::MediaCaptureError;
@@ -0,0 +1,91 @@
+// src-tauri/src/media/capture/store.rs
+//!
+//! Validation and naming of captured files
+//!
+
+use super::error::MediaCaptureError;
+use super::types::CaptureKind;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+
+/// Largest photo or recording accepted from the main window
+pub const MAX_CAPTURE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Folder inside the extension's private directory
+pub const CAPTURES_DIR: &str = "captures";
+
+/// Formats the main window may deliver, by kind
+const PHOTO_TYPES: &[(&str, &str)] = &[
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+    ("image/webp", "webp"),
+];
+const AUDIO_TYPES: &[(&str, &str)] = &[
+    ("audio/webm", "webm"),
+    ("audio/ogg", "ogg"),
+    ("audio/mp4", "m4a"),
+    ("audio/mpeg", "mp3"),
+    ("audio/wav", "wav"),
+];
+
+/// `audio/webm;codecs=opus` to `audio/webm`.
+pub fn essence(mime_type: &str) -> String {
+    mime_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// File extension for `mime_type`, rejecting formats that don't fit `kind`.
+pub fn file_extension(
+    kind: CaptureKind,
+    mime_type: &str,
+) -> Result<&'static str, MediaCaptureError> {
+    let types = match kind {
+        CaptureKind::Photo => PHOTO_TYPES,
+        CaptureKind::Audio => AUDIO_TYPES,
+    };
+    let essence = essence(mime_type);
+    types
+        .iter()
+        .find(|(mime, _)| *mime == essence)
+        .map(|(_, extension)| *extension)
+        .ok_or_else(|| MediaCaptureError::UnsupportedFormat {
+            kind: kind.as_str().to_string(),
+            mime_type: mime_type.to_string(),
+        })
+}
+
+/// Decodes the base64 payload, enforcing `MAX_CAPTURE_BYTES`.
+pub fn decode(data: &str) -> Result<Vec<u8>, MediaCaptureError> {
+    // Checked before decoding so an oversized payload is never allocated twice
+    let estimated = data.len() as u64 / 4 * 3;
+    if estimated > MAX_CAPTURE_BYTES {
+        return Err(MediaCaptureError::TooLarge {
+            size: estimated,
+            max: MAX_CAPTURE_BYTES,
+        });
+    }
+    let bytes = BASE64
+        .decode(data)
+        .map_err(|e| MediaCaptureError::InvalidData {
+            reason: e.to_string(),
+        })?;
+    if bytes.is_empty() {
+        return Err(MediaCaptureError::InvalidData {
+            reason: "empty capture".to_string(),
+        });
+    }
+    Ok(bytes)
+}
+
+/// Path of a new capture relative to the private directory, e.g.
+/// `captures/photo-<uuid>.jpg`.
+pub fn relative_path(kind: CaptureKind, extension: &str) -> String {
+    format!(
+        "{CAPTURES_DIR}/{}-{}.{extension}",
+        kind.as_str(),
+        uuid::Uuid::new_v4()
+    )
+}
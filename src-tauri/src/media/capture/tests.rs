@@ -0,0 +1,138 @@
+// src-tauri/src/media/capture/tests.rs
+//!
+//! Tests for capture validation and the capture broker
+
+use super::broker::{CaptureBroker, CapturedMedia};
+use super::error::MediaCaptureError;
+use super::store::{decode, essence, file_extension, relative_path, MAX_CAPTURE_BYTES};
+use super::types::CaptureKind;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::time::Duration;
+
+fn photo() -> CapturedMedia {
+    CapturedMedia {
+        mime_type: "image/jpeg".to_string(),
+        bytes: vec![0xFF, 0xD8, 0xFF],
+    }
+}
+
+#[test]
+fn test_file_extension_matches_kind() {
+    assert_eq!(
+        file_extension(CaptureKind::Photo, "image/jpeg").unwrap(),
+        "jpg"
+    );
+    assert_eq!(
+        file_extension(CaptureKind::Audio, "audio/webm;codecs=opus").unwrap(),
+        "webm"
+    );
+    assert_eq!(
+        file_extension(CaptureKind::Audio, "Audio/MP4").unwrap(),
+        "m4a"
+    );
+
+    assert!(matches!(
+        file_extension(CaptureKind::Photo, "audio/webm"),
+        Err(MediaCaptureError::UnsupportedFormat { .. })
+    ));
+    assert!(matches!(
+        file_extension(CaptureKind::Audio, "text/html"),
+        Err(MediaCaptureError::UnsupportedFormat { .. })
+    ));
+    assert_eq!(essence(" audio/ogg ; codecs=opus"), "audio/ogg");
+}
+
+#[test]
+fn test_decode() {
+    assert_eq!(decode(&BASE64.encode(b"RIFF")).unwrap(), b"RIFF");
+    assert!(matches!(
+        decode("%%%"),
+        Err(MediaCaptureError::InvalidData { .. })
+    ));
+    assert!(matches!(
+        decode(""),
+        Err(MediaCaptureError::InvalidData { .. })
+    ));
+
+    let oversized = "A".repeat((MAX_CAPTURE_BYTES / 3 * 4 + 8) as usize);
+    assert!(matches!(
+        decode(&oversized),
+        Err(MediaCaptureError::TooLarge { .. })
+    ));
+}
+
+#[test]
+fn test_relative_path_stays_in_captures() {
+    let path = relative_path(CaptureKind::Audio, "webm");
+    assert!(path.starts_with("captures/audio-"));
+    assert!(path.ends_with(".webm"));
+    assert_ne!(path, relative_path(CaptureKind::Audio, "webm"));
+}
+
+#[tokio::test]
+async fn test_broker_delivers_media() {
+    let broker = CaptureBroker::new();
+    let (request_id, receiver) = broker.begin("ext-1", CaptureKind::Photo).unwrap();
+
+    // One open capture per extension; others are independent
+    assert!(matches!(
+        broker.begin("ext-1", CaptureKind::Audio),
+        Err(MediaCaptureError::CaptureInProgress)
+    ));
+    let (other_id, _other) = broker.begin("ext-2", CaptureKind::Audio).unwrap();
+    assert_eq!(broker.kind(&other_id).unwrap(), CaptureKind::Audio);
+
+    broker.resolve(&request_id, Some(photo())).unwrap();
+    assert_eq!(
+        broker
+            .wait(&request_id, receiver, Duration::ZERO)
+            .await
+            .unwrap(),
+        Some(photo())
+    );
+
+    // Resolved requests are gone
+    assert!(matches!(
+        broker.kind(&request_id),
+        Err(MediaCaptureError::RequestNotFound { .. })
+    ));
+    assert!(matches!(
+        broker.resolve(&request_id, None),
+        Err(MediaCaptureError::RequestNotFound { .. })
+    ));
+    broker.resolve(&other_id, None).unwrap();
+    assert!(broker.begin("ext-1", CaptureKind::Photo).is_ok());
+}
+
+#[tokio::test]
+async fn test_broker_cancel_and_timeout() {
+    let broker = CaptureBroker::with_timeout(Duration::from_millis(20));
+
+    let (request_id, receiver) = broker.begin("ext-1", CaptureKind::Audio).unwrap();
+    broker.resolve(&request_id, None).unwrap();
+    assert_eq!(
+        broker
+            .wait(&request_id, receiver, Duration::ZERO)
+            .await
+            .unwrap(),
+        None
+    );
+
+    // The recording time extends the wait
+    let (request_id, receiver) = broker.begin("ext-1", CaptureKind::Audio).unwrap();
+    let waiting = broker.wait(&request_id, receiver, Duration::from_millis(200));
+    let resolve = async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        broker.resolve(&request_id, Some(photo())).unwrap();
+    };
+    let (result, ()) = tokio::join!(waiting, resolve);
+    assert!(result.unwrap().is_some());
+
+    let (request_id, receiver) = broker.begin("ext-1", CaptureKind::Photo).unwrap();
+    assert!(matches!(
+        broker.wait(&request_id, receiver, Duration::ZERO).await,
+        Err(MediaCaptureError::Timeout { .. })
+    ));
+    // A timed-out request no longer blocks the extension
+    assert!(broker.begin("ext-1", CaptureKind::Photo).is_ok());
+}
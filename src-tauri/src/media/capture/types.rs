@@ -0,0 +1,53 @@
+// src-tauri/src/media/capture/types.rs
+//!
+//! Capture Types
+//!
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum CaptureKind {
+    Photo,
+    Audio,
+}
+
+impl CaptureKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaptureKind::Photo => "photo",
+            CaptureKind::Audio => "audio",
+        }
+    }
+}
+
+/// Payload of `media:capture-requested`. The main window opens the camera
+/// or microphone and answers with
+/// `media_resolve_capture(requestId, data, mimeType)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct MediaCaptureRequested {
+    pub request_id: String,
+    pub extension_id: String,
+    pub extension_name: String,
+    pub kind: CaptureKind,
+    /// Recording limit, audio only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub max_duration_secs: Option<u32>,
+}
+
+/// A capture stored in the extension's private directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct MediaCaptureResult {
+    /// `private://captures/...`, usable with the filesystem API
+    pub path: String,
+    pub mime_type: String,
+    #[ts(type = "number")]
+    pub size: u64,
+}
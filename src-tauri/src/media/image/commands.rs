@@ -0,0 +1,376 @@
+// src-tauri/src/media/image/commands.rs
+//!
+//! Image Processing Commands
+//!
+//! `media_image_*` are internal commands for the host UI.
+//! `extension_media_image_*` are the permission-checked variants:
+//! - `path` sources require `fs` read permission for the path
+//! - `blob` sources require `filesync` read permission for backends
+//! - a `destination` requires `fs` read/write permission for that path
+
+use super::error::MediaImageError;
+use super::metadata::{self, Container};
+use super::ops::{self, Encoded, MAX_INLINE_BYTES, MAX_INPUT_BYTES};
+use super::types::{ImageInfo, ImageMetadata, ImageOperation, ImageOutputFormat, ProcessedImage};
+use crate::content_extract::types::ContentSource;
+use crate::database::DbConnection;
+use crate::extension::error::ExtensionError;
+use crate::extension::permissions::manager::PermissionManager;
+use crate::extension::permissions::types::{Action, FileSyncAction, FileSyncTarget, FsAction};
+use crate::extension::utils::{emit_permission_prompt_if_needed, resolve_extension_id};
+use crate::AppState;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State, WebviewWindow};
+
+fn to_extension_error(e: MediaImageError) -> ExtensionError {
+    ExtensionError::ValidationError {
+        reason: e.to_string(),
+    }
+}
+
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, MediaImageError> + Send + 'static,
+) -> Result<T, MediaImageError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| MediaImageError::Internal {
+            reason: format!("image task failed: {e}"),
+        })?
+}
+
+/// Loads the source bytes, enforcing `MAX_INPUT_BYTES`.
+async fn load_source(
+    db: &DbConnection,
+    source: &ContentSource,
+) -> Result<Vec<u8>, MediaImageError> {
+    let bytes = match source {
+        ContentSource::Path { path } => {
+            let size = tokio::fs::metadata(path).await?.len();
+            if size > MAX_INPUT_BYTES {
+                return Err(MediaImageError::TooLarge {
+                    size,
+                    max: MAX_INPUT_BYTES,
+                });
+            }
+            tokio::fs::read(path).await?
+        }
+        ContentSource::Blob { backend_id, key } => {
+            let backend =
+                crate::remote_storage::commands::get_backend_instance_from_db_with_overrides(
+                    db, backend_id, None,
+                )
+                .await?;
+            backend.download(key).await?
+        }
+    };
+    if bytes.len() as u64 > MAX_INPUT_BYTES {
+        return Err(MediaImageError::TooLarge {
+            size: bytes.len() as u64,
+            max: MAX_INPUT_BYTES,
+        });
+    }
+    Ok(bytes)
+}
+
+/// Sibling temp file; results are renamed into place so a failed write
+/// never leaves a truncated image, and a destination equal to the source
+/// is safe.
+fn temp_path(destination: &Path) -> PathBuf {
+    let name = destination
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    destination.with_file_name(format!(".{name}.{}.tmp", uuid::Uuid::new_v4()))
+}
+
+fn write_atomic(
+    destination: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+) -> Result<u64, MediaImageError> {
+    let temp = temp_path(destination);
+    let result = File::create(&temp).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        write(&mut writer)?;
+        writer
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+        std::fs::rename(&temp, destination)
+    });
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e.into());
+    }
+    Ok(std::fs::metadata(destination)?.len())
+}
+
+/// Writes `encoded` to `destination`, or returns it inline.
+fn finish(
+    encoded: Encoded,
+    removed: ImageMetadata,
+    destination: Option<String>,
+) -> Result<ProcessedImage, MediaImageError> {
+    let mut result = ProcessedImage {
+        mime_type: encoded.format.mime_type().to_string(),
+        width: encoded.width,
+        height: encoded.height,
+        size: encoded.bytes.len() as u64,
+        removed,
+        path: None,
+        data: None,
+    };
+    match destination {
+        Some(destination) => {
+            write_atomic(Path::new(&destination), |w| {
+                io::Write::write_all(w, &encoded.bytes)
+            })?;
+            result.path = Some(destination);
+        }
+        None => {
+            if encoded.bytes.len() > MAX_INLINE_BYTES {
+                return Err(MediaImageError::TooLarge {
+                    size: encoded.bytes.len() as u64,
+                    max: MAX_INLINE_BYTES as u64,
+                });
+            }
+            result.data = Some(BASE64.encode(&encoded.bytes));
+        }
+    }
+    Ok(result)
+}
+
+/// Streams an upright JPEG/PNG file to `destination` without its metadata.
+/// `None` if the file needs re-encoding instead.
+pub(super) fn strip_file(
+    source: &Path,
+    destination: &Path,
+) -> Result<Option<ProcessedImage>, MediaImageError> {
+    let mut reader = BufReader::new(File::open(source)?);
+    let Some(container) = Container::detect(reader.fill_buf()?) else {
+        return Ok(None);
+    };
+    // First pass only inspects, so nothing is written for rotated images
+    let report = match metadata::strip(container, &mut reader, &mut io::sink()) {
+        Ok(report) if report.orientation <= 1 => report,
+        _ => return Ok(None),
+    };
+    let (width, height) = ::image::ImageReader::open(source)?
+        .with_guessed_format()?
+        .into_dimensions()?;
+
+    let size = write_atomic(destination, |writer| {
+        let mut reader = BufReader::new(File::open(source)?);
+        metadata::strip(container, &mut reader, writer).map(|_| ())
+    })?;
+    let format = match container {
+        Container::Jpeg => ImageOutputFormat::Jpeg,
+        Container::Png => ImageOutputFormat::Png,
+    };
+    Ok(Some(ProcessedImage {
+        mime_type: format.mime_type().to_string(),
+        width,
+        height,
+        size,
+        removed: report.removed,
+        path: Some(destination.to_string_lossy().into_owned()),
+        data: None,
+    }))
+}
+
+async fn info(db: &DbConnection, source: &ContentSource) -> Result<ImageInfo, MediaImageError> {
+    let bytes = load_source(db, source).await?;
+    blocking(move || ops::info(&bytes)).await
+}
+
+async fn process(
+    db: &DbConnection,
+    source: &ContentSource,
+    operations: Vec<ImageOperation>,
+    format: Option<ImageOutputFormat>,
+    quality: Option<u8>,
+    destination: Option<String>,
+) -> Result<ProcessedImage, MediaImageError> {
+    let bytes = load_source(db, source).await?;
+    blocking(move || {
+        let (encoded, removed) = ops::process(&bytes, &operations, format, quality)?;
+        finish(encoded, removed, destination)
+    })
+    .await
+}
+
+async fn strip_metadata(
+    db: &DbConnection,
+    source: &ContentSource,
+    destination: Option<String>,
+) -> Result<ProcessedImage, MediaImageError> {
+    if let (ContentSource::Path { path }, Some(destination)) = (source, &destination) {
+        let size = tokio::fs::metadata(path).await?.len();
+        if size > MAX_INPUT_BYTES {
+            return Err(MediaImageError::TooLarge {
+                size,
+                max: MAX_INPUT_BYTES,
+            });
+        }
+        let (path, dest) = (PathBuf::from(path), PathBuf::from(destination));
+        if let Some(result) = blocking(move || strip_file(&path, &dest)).await? {
+            return Ok(result);
+        }
+    }
+
+    let bytes = load_source(db, source).await?;
+    blocking(move || {
+        let (encoded, removed) = ops::strip_bytes(&bytes)?;
+        finish(encoded, removed, destination)
+    })
+    .await
+}
+
+/// Dimensions, format and metadata of an image.
+#[tauri::command]
+pub async fn media_image_info(
+    state: State<'_, AppState>,
+    source: ContentSource,
+) -> Result<ImageInfo, MediaImageError> {
+    info(&state.db, &source).await
+}
+
+/// Apply `operations` and re-encode (as `format`, default: the source
+/// format if it is JPEG or WebP, PNG otherwise). The output carries no
+/// metadata.
+#[tauri::command]
+pub async fn media_image_process(
+    state: State<'_, AppState>,
+    source: ContentSource,
+    operations: Vec<ImageOperation>,
+    format: Option<ImageOutputFormat>,
+    quality: Option<u8>,
+    destination: Option<String>,
+) -> Result<ProcessedImage, MediaImageError> {
+    process(&state.db, &source, operations, format, quality, destination).await
+}
+
+/// Remove EXIF (incl. GPS), XMP, IPTC and comments from an image.
+#[tauri::command]
+pub async fn media_image_strip_metadata(
+    state: State<'_, AppState>,
+    source: ContentSource,
+    destination: Option<String>,
+) -> Result<ProcessedImage, MediaImageError> {
+    strip_metadata(&state.db, &source, destination).await
+}
+
+async fn check_permissions(
+    app_handle: &AppHandle,
+    state: &State<'_, AppState>,
+    extension_id: &str,
+    source: &ContentSource,
+    destination: Option<&str>,
+) -> Result<(), ExtensionError> {
+    let mut result = match source {
+        ContentSource::Path { path } => {
+            PermissionManager::check_filesystem_permission(
+                state,
+                extension_id,
+                Action::Filesystem(FsAction::Read),
+                Path::new(path),
+            )
+            .await
+        }
+        ContentSource::Blob { .. } => {
+            PermissionManager::check_filesync_permission(
+                state,
+                extension_id,
+                FileSyncAction::Read,
+                FileSyncTarget::Backends,
+            )
+            .await
+        }
+    };
+    if let (Ok(()), Some(destination)) = (&result, destination) {
+        result = PermissionManager::check_filesystem_permission(
+            state,
+            extension_id,
+            Action::Filesystem(FsAction::ReadWrite),
+            Path::new(destination),
+        )
+        .await;
+    }
+    if let Err(ref e) = result {
+        emit_permission_prompt_if_needed(app_handle, e);
+    }
+    result
+}
+
+/// Image info on behalf of an extension (permission-checked).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_media_image_info(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    source: ContentSource,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<ImageInfo, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    check_permissions(&app_handle, &state, &extension_id, &source, None).await?;
+    info(&state.db, &source).await.map_err(to_extension_error)
+}
+
+/// Image processing on behalf of an extension (permission-checked).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_media_image_process(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    source: ContentSource,
+    operations: Vec<ImageOperation>,
+    format: Option<ImageOutputFormat>,
+    quality: Option<u8>,
+    destination: Option<String>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<ProcessedImage, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    check_permissions(
+        &app_handle,
+        &state,
+        &extension_id,
+        &source,
+        destination.as_deref(),
+    )
+    .await?;
+    process(&state.db, &source, operations, format, quality, destination)
+        .await
+        .map_err(to_extension_error)
+}
+
+/// Metadata removal on behalf of an extension (permission-checked).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_media_image_strip_metadata(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    source: ContentSource,
+    destination: Option<String>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<ProcessedImage, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    check_permissions(
+        &app_handle,
+        &state,
+        &extension_id,
+        &source,
+        destination.as_deref(),
+    )
+    .await?;
+    strip_metadata(&state.db, &source, destination)
+        .await
+        .map_err(to_extension_error)
+}
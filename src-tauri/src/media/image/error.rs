@@ -0,0 +1,109 @@
+// src-tauri/src/media/image/error.rs
+//!
+//! Image Processing Error Types
+//!
+
+use crate::error_code::{serialize_tagged, CodedError, ErrorCode};
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Error, Serialize)]
+#[serde(tag = "type", content = "details")]
+#[serde(remote = "Self")]
+pub enum MediaImageError {
+    #[error("Input too large: {size} bytes (max {max})")]
+    TooLarge { size: u64, max: u64 },
+
+    #[error("Unsupported image format: {reason}")]
+    UnsupportedFormat { reason: String },
+
+    #[error("Failed to decode image: {reason}")]
+    Decode { reason: String },
+
+    #[error("Failed to encode image: {reason}")]
+    Encode { reason: String },
+
+    #[error("Invalid operation: {reason}")]
+    InvalidOperation { reason: String },
+
+    #[error("I/O error: {reason}")]
+    Io { reason: String },
+
+    #[error("Storage error: {reason}")]
+    Storage { reason: String },
+
+    #[error("Internal error: {reason}")]
+    Internal { reason: String },
+}
+
+impl CodedError for MediaImageError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            MediaImageError::TooLarge { .. } => ErrorCode::LimitExceeded,
+            MediaImageError::UnsupportedFormat { .. } => ErrorCode::Unsupported,
+            MediaImageError::Decode { .. } | MediaImageError::Encode { .. } => {
+                ErrorCode::ProcessingFailed
+            }
+            MediaImageError::InvalidOperation { .. } => ErrorCode::Validation,
+            MediaImageError::Io { .. } => ErrorCode::Filesystem,
+            MediaImageError::Storage { .. } => ErrorCode::Storage,
+            MediaImageError::Internal { .. } => ErrorCode::Internal,
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        self.tagged().get("details").cloned()
+    }
+}
+
+impl MediaImageError {
+    /// The derived `{ type, details }` representation
+    fn tagged(&self) -> serde_json::Value {
+        MediaImageError::serialize(self, serde_json::value::Serializer).unwrap_or_default()
+    }
+}
+
+impl Serialize for MediaImageError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_tagged(self, &self.tagged(), serializer)
+    }
+}
+
+impl From<std::io::Error> for MediaImageError {
+    fn from(e: std::io::Error) -> Self {
+        MediaImageError::Io {
+            reason: e.to_string(),
+        }
+    }
+}
+
+impl From<crate::remote_storage::StorageError> for MediaImageError {
+    fn from(e: crate::remote_storage::StorageError) -> Self {
+        MediaImageError::Storage {
+            reason: e.to_string(),
+        }
+    }
+}
+
+impl From<::image::ImageError> for MediaImageError {
+    fn from(e: ::image::ImageError) -> Self {
+        match e {
+            ::image::ImageError::Unsupported(e) => MediaImageError::UnsupportedFormat {
+                reason: e.to_string(),
+            },
+            ::image::ImageError::Limits(e) => MediaImageError::Decode {
+                reason: format!("image exceeds the decoding limits ({e})"),
+            },
+            ::image::ImageError::IoError(e) => e.into(),
+            ::image::ImageError::Encoding(e) => MediaImageError::Encode {
+                reason: e.to_string(),
+            },
+            e => MediaImageError::Decode {
+                reason: e.to_string(),
+            },
+        }
+    }
+}
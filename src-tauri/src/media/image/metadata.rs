@@ -0,0 +1,269 @@
+// src-tauri/src/media/image/metadata.rs
+//!
+//! Lossless metadata removal for JPEG and PNG
+//!
+//! Both containers are walked segment by segment (JPEG markers, PNG chunks)
+//! and copied to the writer minus the metadata; pixel data is never
+//! decoded. Works on any `BufRead`, so files are streamed.
+
+use super::types::ImageMetadata;
+use std::io::{self, BufRead, Read, Write};
+
+const JPEG_SOI: [u8; 2] = [0xFF, 0xD8];
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+const XMP_HEADER: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+const XMP_PNG_KEYWORD: &[u8] = b"XML:com.adobe.xmp\0";
+/// EXIF tag pointing to the GPS IFD
+const GPS_IFD_TAG: u16 = 0x8825;
+/// EXIF orientation tag (1 = upright)
+const ORIENTATION_TAG: u16 = 0x0112;
+/// PNG metadata chunks are buffered to be inspected; anything larger is
+/// not a plausible text or EXIF chunk
+const MAX_PNG_METADATA_CHUNK: u32 = 16 * 1024 * 1024;
+
+/// Containers whose metadata can be stripped losslessly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Container {
+    Jpeg,
+    Png,
+}
+
+impl Container {
+    pub fn detect(header: &[u8]) -> Option<Self> {
+        if header.starts_with(&JPEG_SOI) {
+            Some(Container::Jpeg)
+        } else if header.starts_with(&PNG_SIGNATURE) {
+            Some(Container::Png)
+        } else {
+            None
+        }
+    }
+}
+
+/// What [`strip`] removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StripReport {
+    pub removed: ImageMetadata,
+    /// EXIF orientation of the original (1 = upright). Viewers rotate by
+    /// this tag, so an image whose tag is not 1 looks different once the
+    /// tag is gone and has to be re-encoded upright instead.
+    pub orientation: u16,
+}
+
+impl Default for StripReport {
+    fn default() -> Self {
+        Self {
+            removed: ImageMetadata::default(),
+            orientation: 1,
+        }
+    }
+}
+
+/// Copies the image from `reader` to `writer` without its metadata and
+/// reports what was removed. Data after the end of the image (e.g. the
+/// extra images of an MPO, which carry their own EXIF) is dropped too.
+pub fn strip<R: BufRead, W: Write>(
+    container: Container,
+    reader: &mut R,
+    writer: &mut W,
+) -> io::Result<StripReport> {
+    let mut report = StripReport::default();
+    match container {
+        Container::Jpeg => strip_jpeg(reader, writer, &mut report)?,
+        Container::Png => strip_png(reader, writer, &mut report)?,
+    }
+    writer.flush()?;
+    Ok(report)
+}
+
+fn invalid(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason.to_string())
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut buf = [0u8; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn strip_jpeg<R: BufRead, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    report: &mut StripReport,
+) -> io::Result<()> {
+    let mut soi = [0u8; 2];
+    reader.read_exact(&mut soi)?;
+    if soi != JPEG_SOI {
+        return Err(invalid("not a JPEG file"));
+    }
+    writer.write_all(&JPEG_SOI)?;
+
+    // Inside entropy-coded data (after SOS) bytes are copied until the
+    // next real marker
+    let mut in_scan = false;
+    loop {
+        let byte = read_u8(reader)?;
+        if byte != 0xFF {
+            if !in_scan {
+                return Err(invalid("expected a JPEG marker"));
+            }
+            writer.write_all(&[byte])?;
+            continue;
+        }
+
+        // Fill bytes before a marker are dropped
+        let mut marker = read_u8(reader)?;
+        while marker == 0xFF {
+            marker = read_u8(reader)?;
+        }
+        match marker {
+            // Stuffed 0xFF and restart markers belong to the scan
+            0x00 | 0xD0..=0xD7 if in_scan => writer.write_all(&[0xFF, marker])?,
+            0x00 => return Err(invalid("unexpected stuffed byte")),
+            0xD9 => {
+                writer.write_all(&[0xFF, 0xD9])?;
+                return Ok(());
+            }
+            0x01 | 0xD0..=0xD7 => writer.write_all(&[0xFF, marker])?,
+            _ => {
+                let mut len = [0u8; 2];
+                reader.read_exact(&mut len)?;
+                let payload_len = usize::from(u16::from_be_bytes(len))
+                    .checked_sub(2)
+                    .ok_or_else(|| invalid("invalid JPEG segment length"))?;
+                let mut payload = vec![0u8; payload_len];
+                reader.read_exact(&mut payload)?;
+
+                if keep_jpeg_segment(marker, &payload, report) {
+                    writer.write_all(&[0xFF, marker])?;
+                    writer.write_all(&len)?;
+                    writer.write_all(&payload)?;
+                }
+                in_scan = marker == 0xDA;
+            }
+        }
+    }
+}
+
+fn keep_jpeg_segment(marker: u8, payload: &[u8], report: &mut StripReport) -> bool {
+    let removed = &mut report.removed;
+    match marker {
+        // APP0: the JFIF header stays, JFXX (thumbnail) goes
+        0xE0 if payload.starts_with(b"JFIF\0") => true,
+        0xE1 if payload.starts_with(EXIF_HEADER) => {
+            let tiff = &payload[EXIF_HEADER.len()..];
+            removed.exif = true;
+            removed.gps |= exif_has_gps(tiff);
+            report.orientation = exif_orientation(tiff).unwrap_or(report.orientation);
+            false
+        }
+        0xE1 if payload.starts_with(XMP_HEADER) => {
+            removed.xmp = true;
+            false
+        }
+        // APP2 ICC profile and APP14 Adobe (color transform) affect how
+        // the pixels are rendered
+        0xE2 if payload.starts_with(b"ICC_PROFILE\0") => true,
+        0xEE if payload.starts_with(b"Adobe") => true,
+        // Any other APPn (IPTC, MPF, vendor data) and comments
+        0xE0..=0xEF | 0xFE => {
+            removed.other = true;
+            false
+        }
+        _ => true,
+    }
+}
+
+fn strip_png<R: BufRead, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    report: &mut StripReport,
+) -> io::Result<()> {
+    let mut signature = [0u8; 8];
+    reader.read_exact(&mut signature)?;
+    if signature != PNG_SIGNATURE {
+        return Err(invalid("not a PNG file"));
+    }
+    writer.write_all(&PNG_SIGNATURE)?;
+
+    loop {
+        // Length and type
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+        let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        let chunk_type = [header[4], header[5], header[6], header[7]];
+
+        let drop = matches!(&chunk_type, b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME");
+        if !drop {
+            writer.write_all(&header)?;
+            // Data and CRC, streamed
+            let copied = io::copy(&mut reader.by_ref().take(u64::from(len) + 4), writer)?;
+            if copied != u64::from(len) + 4 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            if &chunk_type == b"IEND" {
+                return Ok(());
+            }
+            continue;
+        }
+
+        if len > MAX_PNG_METADATA_CHUNK {
+            return Err(invalid("PNG metadata chunk too large"));
+        }
+        let mut data = vec![0u8; len as usize + 4];
+        reader.read_exact(&mut data)?;
+        let data = &data[..len as usize];
+        let removed = &mut report.removed;
+        match &chunk_type {
+            b"eXIf" => {
+                removed.exif = true;
+                removed.gps |= exif_has_gps(data);
+                report.orientation = exif_orientation(data).unwrap_or(report.orientation);
+            }
+            b"iTXt" if data.starts_with(XMP_PNG_KEYWORD) => removed.xmp = true,
+            _ => removed.other = true,
+        }
+    }
+}
+
+/// Whether the first IFD of a TIFF-structured EXIF block links a GPS IFD.
+pub fn exif_has_gps(tiff: &[u8]) -> bool {
+    ifd0_entries(tiff).any(|(tag, _)| tag == GPS_IFD_TAG)
+}
+
+/// The orientation tag of the first IFD, if present.
+pub fn exif_orientation(tiff: &[u8]) -> Option<u16> {
+    ifd0_entries(tiff)
+        .find(|(tag, _)| *tag == ORIENTATION_TAG)
+        .map(|(_, value)| value)
+}
+
+/// `(tag, first SHORT of the value)` of every entry in the first IFD.
+/// Malformed blocks yield fewer (or no) entries instead of an error.
+fn ifd0_entries(tiff: &[u8]) -> impl Iterator<Item = (u16, u16)> + '_ {
+    let little_endian = match tiff.get(..2) {
+        Some(b"II") => Some(true),
+        Some(b"MM") => Some(false),
+        _ => None,
+    };
+    let read = move |offset: usize, len: usize| -> Option<u32> {
+        let bytes = tiff.get(offset..offset.checked_add(len)?)?;
+        let value = bytes.iter().fold(0u32, |acc, b| (acc << 8) | u32::from(*b));
+        Some(if little_endian? {
+            value.swap_bytes() >> (32 - 8 * len)
+        } else {
+            value
+        })
+    };
+
+    let ifd = read(4, 4).map(|o| o as usize);
+    let count = ifd.and_then(|o| read(o, 2)).unwrap_or(0) as usize;
+    (0..count).map_while(move |i| {
+        let entry = ifd? + 2 + i * 12;
+        let tag = read(entry, 2)? as u16;
+        // Inline SHORT values sit at the start of the 4-byte value field
+        let value = read(entry + 8, 2)? as u16;
+        Some((tag, value))
+    })
+}
@@ -0,0 +1,29 @@
+// src-tauri/src/media/image/mod.rs
+//!
+//! Image Processing
+//!
+//! Resize, crop, rotate and convert images from local files or storage
+//! blobs, and strip their metadata (EXIF incl. GPS position, XMP, IPTC,
+//! comments) before they end up in synced files.
+//!
+//! - JPEG and PNG metadata is removed losslessly, segment by segment, and
+//!   streamed file to file without loading the whole image.
+//! - Everything else is decoded and re-encoded; the encoders write no
+//!   metadata. The EXIF orientation is applied while decoding, so images
+//!   keep their visual orientation once the tag is gone.
+//! - Inputs are capped at `MAX_INPUT_BYTES`, decoding at `MAX_DIMENSION`
+//!   per edge and `MAX_DECODE_BYTES` of pixel memory.
+//!
+//! `media_image_*` are internal commands for the host UI,
+//! `extension_media_image_*` the permission-checked variants.
+
+pub mod commands;
+pub mod error;
+pub mod metadata;
+pub mod ops;
+pub mod types;
+
+#[cfg(test)]
+mod tests;
+
+pub use error::MediaImageError;
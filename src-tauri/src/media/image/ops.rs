@@ -0,0 +1,237 @@
+// src-tauri/src/media/image/ops.rs
+//!
+//! Decoding, transformations and encoding (no I/O, no permissions)
+
+use super::error::MediaImageError;
+use super::metadata::{self, Container, StripReport};
+use super::types::{ImageInfo, ImageMetadata, ImageOperation, ImageOutputFormat};
+use ::image::codecs::jpeg::JpegEncoder;
+use ::image::codecs::webp::WebPEncoder;
+use ::image::imageops::FilterType;
+use ::image::metadata::Orientation;
+use ::image::{DynamicImage, ExtendedColorType, ImageDecoder, ImageFormat, ImageReader, Limits};
+use std::io::{self, Cursor};
+
+/// Largest accepted input (file or blob)
+pub const MAX_INPUT_BYTES: u64 = 100 * 1024 * 1024;
+/// Largest edge length that is decoded or produced
+pub const MAX_DIMENSION: u32 = 16_384;
+/// Pixel memory a single decode may allocate
+pub const MAX_DECODE_BYTES: u64 = 512 * 1024 * 1024;
+/// Largest result returned inline (base64) instead of written to a file
+pub const MAX_INLINE_BYTES: usize = 20 * 1024 * 1024;
+pub const DEFAULT_JPEG_QUALITY: u8 = 85;
+
+/// An encoded result
+#[derive(Debug, Clone, PartialEq)]
+pub struct Encoded {
+    pub format: ImageOutputFormat,
+    pub width: u32,
+    pub height: u32,
+    pub bytes: Vec<u8>,
+}
+
+fn reader(bytes: &[u8]) -> Result<ImageReader<Cursor<&[u8]>>, MediaImageError> {
+    let mut reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format()?;
+    if reader.format().is_none() {
+        return Err(MediaImageError::UnsupportedFormat {
+            reason: "unknown image format".to_string(),
+        });
+    }
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_BYTES);
+    reader.limits(limits);
+    Ok(reader)
+}
+
+/// Decodes `bytes` and applies the EXIF orientation.
+pub fn decode(bytes: &[u8]) -> Result<(DynamicImage, ImageFormat), MediaImageError> {
+    let reader = reader(bytes)?;
+    let format = reader.format().unwrap_or(ImageFormat::Png);
+    let mut decoder = reader.into_decoder()?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+    Ok((image, format))
+}
+
+/// Dimensions (as displayed), format and metadata without decoding pixels.
+pub fn info(bytes: &[u8]) -> Result<ImageInfo, MediaImageError> {
+    let reader = reader(bytes)?;
+    let format = reader.format().unwrap_or(ImageFormat::Png);
+    let mut decoder = reader.into_decoder()?;
+    let (width, height) = decoder.dimensions();
+    let rotated = matches!(
+        decoder.orientation(),
+        Ok(Orientation::Rotate90
+            | Orientation::Rotate270
+            | Orientation::Rotate90FlipH
+            | Orientation::Rotate270FlipH)
+    );
+    let (width, height) = if rotated {
+        (height, width)
+    } else {
+        (width, height)
+    };
+
+    let metadata = inspect_metadata(bytes).map(|r| r.removed);
+
+    Ok(ImageInfo {
+        mime_type: format.to_mime_type().to_string(),
+        width,
+        height,
+        size: bytes.len() as u64,
+        metadata,
+    })
+}
+
+/// Metadata of a JPEG or PNG; `None` for other formats or if the
+/// container structure can't be walked.
+pub fn inspect_metadata(bytes: &[u8]) -> Option<StripReport> {
+    let container = Container::detect(bytes)?;
+    metadata::strip(container, &mut Cursor::new(bytes), &mut io::sink()).ok()
+}
+
+/// Applies one operation.
+pub fn apply(
+    image: DynamicImage,
+    operation: &ImageOperation,
+) -> Result<DynamicImage, MediaImageError> {
+    match *operation {
+        ImageOperation::Resize {
+            max_width,
+            max_height,
+        } => {
+            if max_width.is_none() && max_height.is_none() {
+                return Err(MediaImageError::InvalidOperation {
+                    reason: "resize needs maxWidth or maxHeight".to_string(),
+                });
+            }
+            let max_width = max_width.unwrap_or(MAX_DIMENSION).clamp(1, MAX_DIMENSION);
+            let max_height = max_height.unwrap_or(MAX_DIMENSION).clamp(1, MAX_DIMENSION);
+            if image.width() <= max_width && image.height() <= max_height {
+                return Ok(image);
+            }
+            Ok(image.resize(max_width, max_height, FilterType::Lanczos3))
+        }
+        ImageOperation::Crop {
+            x,
+            y,
+            width,
+            height,
+        } => {
+            let fits = width > 0
+                && height > 0
+                && x.checked_add(width).is_some_and(|r| r <= image.width())
+                && y.checked_add(height).is_some_and(|b| b <= image.height());
+            if !fits {
+                return Err(MediaImageError::InvalidOperation {
+                    reason: format!(
+                        "crop {width}x{height}+{x}+{y} is outside the {}x{} image",
+                        image.width(),
+                        image.height()
+                    ),
+                });
+            }
+            Ok(image.crop_imm(x, y, width, height))
+        }
+        ImageOperation::Rotate { degrees } => match degrees {
+            90 => Ok(image.rotate90()),
+            180 => Ok(image.rotate180()),
+            270 => Ok(image.rotate270()),
+            _ => Err(MediaImageError::InvalidOperation {
+                reason: format!("rotation must be 90, 180 or 270 degrees, got {degrees}"),
+            }),
+        },
+    }
+}
+
+/// Output format used when the caller doesn't pick one.
+pub fn default_output_format(source: ImageFormat) -> ImageOutputFormat {
+    match source {
+        ImageFormat::Jpeg => ImageOutputFormat::Jpeg,
+        ImageFormat::WebP => ImageOutputFormat::Webp,
+        _ => ImageOutputFormat::Png,
+    }
+}
+
+/// Encodes `image`. `quality` (1-100) only applies to JPEG.
+pub fn encode(
+    image: &DynamicImage,
+    format: ImageOutputFormat,
+    quality: Option<u8>,
+) -> Result<Encoded, MediaImageError> {
+    let mut bytes = Vec::new();
+    match format {
+        ImageOutputFormat::Jpeg => {
+            // JPEG has no alpha channel
+            let rgb = image.to_rgb8();
+            let quality = quality.unwrap_or(DEFAULT_JPEG_QUALITY).clamp(1, 100);
+            JpegEncoder::new_with_quality(&mut bytes, quality).encode_image(&rgb)?;
+        }
+        ImageOutputFormat::Png => image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?,
+        ImageOutputFormat::Webp => {
+            let rgba = image.to_rgba8();
+            WebPEncoder::new_lossless(&mut bytes).encode(
+                rgba.as_raw(),
+                rgba.width(),
+                rgba.height(),
+                ExtendedColorType::Rgba8,
+            )?;
+        }
+    }
+    Ok(Encoded {
+        format,
+        width: image.width(),
+        height: image.height(),
+        bytes,
+    })
+}
+
+/// Decodes, applies `operations` in order and re-encodes. The result never
+/// carries metadata; the returned [`ImageMetadata`] says what was dropped.
+pub fn process(
+    bytes: &[u8],
+    operations: &[ImageOperation],
+    format: Option<ImageOutputFormat>,
+    quality: Option<u8>,
+) -> Result<(Encoded, ImageMetadata), MediaImageError> {
+    let removed = inspect_metadata(bytes).unwrap_or_default().removed;
+    let (mut image, source_format) = decode(bytes)?;
+    for operation in operations {
+        image = apply(image, operation)?;
+    }
+    let format = format.unwrap_or_else(|| default_output_format(source_format));
+    Ok((encode(&image, format, quality)?, removed))
+}
+
+/// Removes the metadata of an in-memory image.
+///
+/// Upright JPEGs and PNGs are stripped losslessly; everything else
+/// (including JPEGs rotated by their EXIF orientation) is re-encoded.
+pub fn strip_bytes(bytes: &[u8]) -> Result<(Encoded, ImageMetadata), MediaImageError> {
+    if let Some(container) = Container::detect(bytes) {
+        let mut stripped = Vec::with_capacity(bytes.len());
+        if let Ok(report) = metadata::strip(container, &mut Cursor::new(bytes), &mut stripped) {
+            if report.orientation <= 1 {
+                let (width, height) = reader(bytes)?.into_dimensions()?;
+                let format = match container {
+                    Container::Jpeg => ImageOutputFormat::Jpeg,
+                    Container::Png => ImageOutputFormat::Png,
+                };
+                let encoded = Encoded {
+                    format,
+                    width,
+                    height,
+                    bytes: stripped,
+                };
+                return Ok((encoded, report.removed));
+            }
+        }
+    }
+    // Re-encoding at high quality; metadata isn't carried over by the
+    // encoders
+    process(bytes, &[], None, Some(95))
+}
@@ -0,0 +1,251 @@
+// src-tauri/src/media/image/tests.rs
+//!
+//! Tests for metadata stripping and image operations (in memory, no
+//! storage backends)
+
+use super::commands::strip_file;
+use super::error::MediaImageError;
+use super::metadata::{exif_has_gps, exif_orientation};
+use super::ops::{self, MAX_DIMENSION};
+use super::types::{ImageOperation, ImageOutputFormat};
+use ::image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+use std::io::Cursor;
+
+fn encode(image: &DynamicImage, format: ImageFormat) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), format)
+        .unwrap();
+    bytes
+}
+
+/// 64x32 gradient, wider than tall so rotations are visible
+fn sample() -> DynamicImage {
+    DynamicImage::ImageRgb8(RgbImage::from_fn(64, 32, |x, y| {
+        Rgb([(x * 4) as u8, (y * 8) as u8, 128])
+    }))
+}
+
+/// Little-endian TIFF block with an orientation tag and optionally a GPS
+/// IFD pointer
+fn tiff(orientation: u16, gps: bool) -> Vec<u8> {
+    let count: u16 = if gps { 2 } else { 1 };
+    let mut tiff = b"II\x2a\x00\x08\x00\x00\x00".to_vec();
+    tiff.extend_from_slice(&count.to_le_bytes());
+    tiff.extend_from_slice(&[0x12, 0x01, 3, 0, 1, 0, 0, 0]);
+    tiff.extend_from_slice(&orientation.to_le_bytes());
+    tiff.extend_from_slice(&[0, 0]);
+    if gps {
+        tiff.extend_from_slice(&[0x25, 0x88, 4, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+    }
+    tiff.extend_from_slice(&[0, 0, 0, 0]);
+    tiff
+}
+
+fn jpeg_segment(marker: u8, payload: &[u8]) -> Vec<u8> {
+    let mut segment = vec![0xFF, marker];
+    segment.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+    segment.extend_from_slice(payload);
+    segment
+}
+
+/// `jpeg` with EXIF and a comment after SOI and trailing data after EOI
+fn with_metadata(jpeg: &[u8], orientation: u16) -> Vec<u8> {
+    let mut exif = b"Exif\0\0".to_vec();
+    exif.extend(tiff(orientation, true));
+    let mut bytes = jpeg[..2].to_vec();
+    bytes.extend(jpeg_segment(0xE1, &exif));
+    bytes.extend(jpeg_segment(0xFE, b"taken at home"));
+    bytes.extend_from_slice(&jpeg[2..]);
+    bytes.extend_from_slice(b"MPO trailer with more EXIF");
+    bytes
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+fn png_chunk(chunk_type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+    chunk.extend_from_slice(chunk_type);
+    chunk.extend_from_slice(data);
+    chunk.extend_from_slice(&crc32(&chunk[4..]).to_be_bytes());
+    chunk
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+#[test]
+fn test_strip_jpeg_is_lossless() {
+    let jpeg = encode(&sample(), ImageFormat::Jpeg);
+    let tagged = with_metadata(&jpeg, 1);
+
+    let (stripped, removed) = ops::strip_bytes(&tagged).unwrap();
+    assert!(removed.exif && removed.gps && removed.other);
+    assert!(!removed.xmp);
+    assert_eq!(stripped.format, ImageOutputFormat::Jpeg);
+    assert_eq!((stripped.width, stripped.height), (64, 32));
+    // Exactly the original stream: JFIF header and scan untouched
+    assert_eq!(stripped.bytes, jpeg);
+}
+
+#[test]
+fn test_strip_rotated_jpeg_reencodes_upright() {
+    let tagged = with_metadata(&encode(&sample(), ImageFormat::Jpeg), 6);
+
+    let (stripped, removed) = ops::strip_bytes(&tagged).unwrap();
+    assert!(removed.gps);
+    assert_eq!((stripped.width, stripped.height), (32, 64));
+    assert!(!contains(&stripped.bytes, b"Exif"));
+    assert!(!contains(&stripped.bytes, b"taken at home"));
+    assert!(!contains(&stripped.bytes, b"MPO trailer"));
+}
+
+#[test]
+fn test_strip_png_text_and_exif_chunks() {
+    let png = encode(&sample(), ImageFormat::Png);
+    // IHDR is 8 + 25 bytes in
+    let mut tagged = png[..33].to_vec();
+    tagged.extend(png_chunk(b"eXIf", &tiff(1, true)));
+    tagged.extend(png_chunk(b"tEXt", b"Comment\0secret"));
+    tagged.extend(png_chunk(
+        b"iTXt",
+        b"XML:com.adobe.xmp\0\0\0\0\0<x:xmpmeta/>",
+    ));
+    tagged.extend_from_slice(&png[33..]);
+
+    let info = ops::info(&tagged).unwrap();
+    let metadata = info.metadata.unwrap();
+    assert!(metadata.exif && metadata.gps && metadata.xmp && metadata.other);
+
+    let (stripped, removed) = ops::strip_bytes(&tagged).unwrap();
+    assert_eq!(removed, metadata);
+    assert_eq!(stripped.bytes, png);
+}
+
+#[test]
+fn test_strip_file_streams_in_place() {
+    let dir = std::env::temp_dir().join(format!("media-image-test-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("photo.jpg");
+    let jpeg = encode(&sample(), ImageFormat::Jpeg);
+    std::fs::write(&path, with_metadata(&jpeg, 1)).unwrap();
+
+    let result = strip_file(&path, &path).unwrap().unwrap();
+    assert!(result.removed.gps);
+    assert_eq!(result.size, jpeg.len() as u64);
+    assert_eq!(std::fs::read(&path).unwrap(), jpeg);
+    // No temp files left behind
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+    // Rotated images are left to the re-encoding path
+    std::fs::write(&path, with_metadata(&jpeg, 6)).unwrap();
+    assert!(strip_file(&path, &path).unwrap().is_none());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_exif_parsing_both_byte_orders() {
+    let le = tiff(8, true);
+    assert!(exif_has_gps(&le));
+    assert_eq!(exif_orientation(&le), Some(8));
+    assert!(!exif_has_gps(&tiff(1, false)));
+
+    let be = b"MM\x00\x2a\x00\x00\x00\x08\x00\x01\x01\x12\x00\x03\x00\x00\x00\x01\x00\x03\x00\x00\x00\x00\x00\x00"
+        .to_vec();
+    assert_eq!(exif_orientation(&be), Some(3));
+    assert!(!exif_has_gps(&be));
+
+    // Truncated blocks don't panic
+    assert_eq!(exif_orientation(&le[..12]), None);
+    assert!(!exif_has_gps(b"II"));
+}
+
+#[test]
+fn test_info_applies_orientation() {
+    let tagged = with_metadata(&encode(&sample(), ImageFormat::Jpeg), 6);
+    let info = ops::info(&tagged).unwrap();
+    assert_eq!(info.mime_type, "image/jpeg");
+    assert_eq!((info.width, info.height), (32, 64));
+    assert!(info.metadata.unwrap().gps);
+}
+
+#[test]
+fn test_process_operations() {
+    let png = encode(&sample(), ImageFormat::Png);
+    let operations = [
+        ImageOperation::Crop {
+            x: 16,
+            y: 0,
+            width: 48,
+            height: 32,
+        },
+        ImageOperation::Resize {
+            max_width: Some(24),
+            max_height: None,
+        },
+        ImageOperation::Rotate { degrees: 90 },
+        // Already fits: no upscaling
+        ImageOperation::Resize {
+            max_width: Some(1000),
+            max_height: Some(1000),
+        },
+    ];
+    let (encoded, removed) =
+        ops::process(&png, &operations, Some(ImageOutputFormat::Webp), None).unwrap();
+    assert!(!removed.exif);
+    assert_eq!((encoded.width, encoded.height), (16, 24));
+    let decoded = ::image::load_from_memory(&encoded.bytes).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (16, 24));
+    assert_eq!(
+        ::image::guess_format(&encoded.bytes).unwrap(),
+        ImageFormat::WebP
+    );
+
+    // Default output format follows the source
+    let (encoded, _) = ops::process(&png, &[], None, None).unwrap();
+    assert_eq!(encoded.format, ImageOutputFormat::Png);
+}
+
+#[test]
+fn test_invalid_operations_and_limits() {
+    let png = encode(&sample(), ImageFormat::Png);
+    for operation in [
+        ImageOperation::Crop {
+            x: 60,
+            y: 0,
+            width: 10,
+            height: 10,
+        },
+        ImageOperation::Rotate { degrees: 45 },
+        ImageOperation::Resize {
+            max_width: None,
+            max_height: None,
+        },
+    ] {
+        assert!(matches!(
+            ops::process(&png, &[operation], None, None),
+            Err(MediaImageError::InvalidOperation { .. })
+        ));
+    }
+
+    let too_wide = encode(
+        &DynamicImage::new_luma8(MAX_DIMENSION + 1, 1),
+        ImageFormat::Png,
+    );
+    assert!(ops::decode(&too_wide).is_err());
+    assert!(matches!(
+        ops::info(b"definitely not an image"),
+        Err(MediaImageError::UnsupportedFormat { .. })
+    ));
+}
@@ -0,0 +1,113 @@
+// src-tauri/src/media/image/types.rs
+//!
+//! Image Processing Types
+//!
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// One step of `media_image_process`, applied in order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(tag = "op", rename_all = "camelCase")]
+#[ts(export)]
+pub enum ImageOperation {
+    /// Scales down to fit into the box, keeping the aspect ratio. Images
+    /// that already fit are left alone (never upscaled).
+    #[serde(rename_all = "camelCase")]
+    Resize {
+        #[ts(optional)]
+        max_width: Option<u32>,
+        #[ts(optional)]
+        max_height: Option<u32>,
+    },
+    /// Cuts out a rectangle; it must lie within the image.
+    #[serde(rename_all = "camelCase")]
+    Crop {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    /// Clockwise rotation by 90, 180 or 270 degrees
+    #[serde(rename_all = "camelCase")]
+    Rotate { degrees: u32 },
+}
+
+/// Encoding of processed images. WebP is written lossless.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum ImageOutputFormat {
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl ImageOutputFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ImageOutputFormat::Jpeg => "image/jpeg",
+            ImageOutputFormat::Png => "image/png",
+            ImageOutputFormat::Webp => "image/webp",
+        }
+    }
+}
+
+/// Metadata found in (or removed from) an image.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ImageMetadata {
+    pub exif: bool,
+    /// The EXIF block contains a GPS position
+    pub gps: bool,
+    pub xmp: bool,
+    /// IPTC, comments, text chunks and other application data
+    pub other: bool,
+}
+
+impl ImageMetadata {
+    pub fn any(&self) -> bool {
+        self.exif || self.xmp || self.other
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ImageInfo {
+    pub mime_type: String,
+    /// As displayed, i.e. after applying the EXIF orientation
+    pub width: u32,
+    pub height: u32,
+    #[ts(type = "number")]
+    pub size: u64,
+    /// `None` for formats whose metadata isn't inspected (only JPEG and
+    /// PNG are)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub metadata: Option<ImageMetadata>,
+}
+
+/// Result of `media_image_process` / `media_image_strip_metadata`.
+///
+/// The image was written to `path` if a destination was given, otherwise
+/// it is returned base64-encoded in `data`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ProcessedImage {
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+    #[ts(type = "number")]
+    pub size: u64,
+    /// Metadata the output no longer carries
+    pub removed: ImageMetadata,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub data: Option<String>,
+}
@@ -0,0 +1,9 @@
+// src-tauri/src/media/mod.rs
+//!
+//! Media processing
+//!
+//! Host-side processing of media files, so extensions don't have to bundle
+//! their own (WASM) codecs, and camera/microphone capture on their behalf.
+
+pub mod capture;
+pub mod image;
@@ -0,0 +1,74 @@
+// src-tauri/src/metrics/commands.rs
+//!
+//! Metrics commands
+
+use super::{registry, MetricsSnapshot};
+use crate::AppState;
+use tauri::{AppHandle, Manager};
+
+/// Path of the Prometheus endpoint in the local REST API
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub const METRICS_PATH: &str = "/metrics";
+
+/// State of the Prometheus endpoint
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+#[derive(Debug, Clone, serde::Serialize, ts_rs::TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsEndpointStatus {
+    pub enabled: bool,
+    pub local_api_running: bool,
+    /// URL to scrape (served only while enabled and the local API runs)
+    pub endpoint: String,
+}
+
+/// Current metrics of this process.
+pub async fn collect(state: &AppState) -> MetricsSnapshot {
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    let bridge_connections = state
+        .external_bridge
+        .lock()
+        .await
+        .connected_client_count()
+        .await;
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    let bridge_connections = 0;
+
+    MetricsSnapshot {
+        commands: state.command_metrics.snapshot(),
+        syncs: registry().syncs(),
+        caches: registry().caches(),
+        bridge_connections: u32::try_from(bridge_connections).unwrap_or(u32::MAX),
+    }
+}
+
+#[tauri::command]
+pub async fn metrics_snapshot(app_handle: AppHandle) -> MetricsSnapshot {
+    collect(&app_handle.state::<AppState>()).await
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+#[tauri::command]
+pub async fn metrics_get_endpoint_status(app_handle: AppHandle) -> MetricsEndpointStatus {
+    let state = app_handle.state::<AppState>();
+    let api = state.local_api.lock().await;
+    MetricsEndpointStatus {
+        enabled: api.is_metrics_enabled(),
+        local_api_running: api.is_running(),
+        endpoint: format!("http://127.0.0.1:{}{METRICS_PATH}", api.get_port()),
+    }
+}
+
+/// Enable or disable the Prometheus endpoint. Not persisted: it starts
+/// disabled with every app launch.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+#[tauri::command]
+pub async fn metrics_set_endpoint_enabled(app_handle: AppHandle, enabled: bool) {
+    let state = app_handle.state::<AppState>();
+    let api = state.local_api.lock().await;
+    api.set_metrics_enabled(enabled);
+    println!(
+        "[Metrics] Endpoint {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+}
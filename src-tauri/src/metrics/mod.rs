@@ -0,0 +1,325 @@
+// src-tauri/src/metrics/mod.rs
+//!
+//! Internal metrics
+//!
+//! Collects what a self-hoster needs to watch an always-on vault node:
+//! - command dispatch timings (recorded by `crate::command_middleware`)
+//! - durations and failures of sync runs (remote sync, local delivery, file
+//!   sync), see [`time_sync`]
+//! - hit rates of the file hash and thumbnail caches, see [`record_cache`]
+//! - open external bridge connections (read when a snapshot is taken)
+//!
+//! `metrics_snapshot` returns everything for in-app display. On desktop the
+//! same snapshot is served in the Prometheus text format at
+//! `GET /metrics` of the local REST API once the user enabled it (off by
+//! default, loopback only, no token needed since it holds no vault content).
+
+pub mod commands;
+
+#[cfg(test)]
+mod tests;
+
+use crate::command_middleware::metrics::CommandStats;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use ts_rs::TS;
+
+/// Sync runs of the background sync orchestrator
+pub const SYNC_REMOTE: &str = "remote";
+/// Sync cycles of local space delivery
+pub const SYNC_LOCAL_DELIVERY: &str = "local_delivery";
+/// Runs of file sync rules
+pub const SYNC_FILE: &str = "file_sync";
+
+/// Content hashes of scanned files (file sync)
+pub const CACHE_FILE_HASH: &str = "file_hash";
+/// Rendered thumbnails of remote storage files
+pub const CACHE_THUMBNAIL: &str = "thumbnail";
+
+/// Durations of one kind of sync run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStats {
+    /// Finished runs (including failed ones)
+    #[ts(type = "number")]
+    pub runs: u64,
+    #[ts(type = "number")]
+    pub failures: u64,
+    /// Sum of all run durations in milliseconds
+    #[ts(type = "number")]
+    pub total_millis: u64,
+    #[ts(type = "number")]
+    pub max_millis: u64,
+    #[ts(type = "number")]
+    pub last_millis: u64,
+}
+
+/// Lookups of one cache.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    #[ts(type = "number")]
+    pub hits: u64,
+    #[ts(type = "number")]
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// Share of lookups answered from the cache (0 without lookups)
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// All metrics at one point in time.
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsSnapshot {
+    /// Keyed by command name
+    pub commands: BTreeMap<String, CommandStats>,
+    /// Keyed by sync kind (`remote`, `local_delivery`, `file_sync`)
+    pub syncs: BTreeMap<String, SyncStats>,
+    /// Keyed by cache name (`file_hash`, `thumbnail`)
+    pub caches: BTreeMap<String, CacheStats>,
+    /// Connected external bridge clients (always 0 on mobile)
+    pub bridge_connections: u32,
+}
+
+/// Sync and cache counters. Commands are counted in `AppState::command_metrics`.
+pub struct MetricsRegistry {
+    syncs: Mutex<HashMap<&'static str, SyncStats>>,
+    caches: Mutex<HashMap<&'static str, CacheStats>>,
+}
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self {
+            syncs: Mutex::new(HashMap::new()),
+            caches: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn record_sync(&self, kind: &'static str, elapsed: Duration, success: bool) {
+        // A poisoned map only loses diagnostics; never fail the sync for it.
+        let Ok(mut syncs) = self.syncs.lock() else {
+            return;
+        };
+        let millis = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+        let stats = syncs.entry(kind).or_default();
+        stats.runs += 1;
+        if !success {
+            stats.failures += 1;
+        }
+        stats.total_millis = stats.total_millis.saturating_add(millis);
+        stats.max_millis = stats.max_millis.max(millis);
+        stats.last_millis = millis;
+    }
+
+    pub fn record_cache(&self, cache: &'static str, hit: bool) {
+        let Ok(mut caches) = self.caches.lock() else {
+            return;
+        };
+        let stats = caches.entry(cache).or_default();
+        if hit {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+        }
+    }
+
+    pub fn syncs(&self) -> BTreeMap<String, SyncStats> {
+        self.syncs
+            .lock()
+            .map(|syncs| {
+                syncs
+                    .iter()
+                    .map(|(kind, stats)| (kind.to_string(), stats.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn caches(&self) -> BTreeMap<String, CacheStats> {
+        self.caches
+            .lock()
+            .map(|caches| {
+                caches
+                    .iter()
+                    .map(|(cache, stats)| (cache.to_string(), stats.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Process-wide registry. Sync loops and caches run outside of any command,
+/// so they record here instead of in `AppState`.
+static REGISTRY: LazyLock<MetricsRegistry> = LazyLock::new(MetricsRegistry::new);
+
+pub fn registry() -> &'static MetricsRegistry {
+    &REGISTRY
+}
+
+pub fn record_cache(cache: &'static str, hit: bool) {
+    REGISTRY.record_cache(cache, hit);
+}
+
+/// Runs one sync and records its duration and outcome under `kind`.
+pub async fn time_sync<T, E>(
+    kind: &'static str,
+    run: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let started = Instant::now();
+    let result = run.await;
+    REGISTRY.record_sync(kind, started.elapsed(), result.is_ok());
+    result
+}
+
+/// Renders a snapshot in the Prometheus text exposition format (0.0.4).
+pub fn render_prometheus(snapshot: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+    let mut family = |name: &str, kind: &str, help: &str, samples: Vec<(String, f64)>| {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} {kind}");
+        for (labels, value) in samples {
+            let _ = writeln!(out, "{name}{labels} {value}");
+        }
+    };
+
+    let commands = |f: fn(&CommandStats) -> f64| {
+        snapshot
+            .commands
+            .iter()
+            .map(|(name, stats)| (label("command", name), f(stats)))
+            .collect::<Vec<_>>()
+    };
+    family(
+        "haex_command_calls_total",
+        "counter",
+        "Dispatched command invocations",
+        commands(|s| s.calls as f64),
+    );
+    family(
+        "haex_command_rejected_total",
+        "counter",
+        "Command invocations rejected by a precondition",
+        commands(|s| s.rejected as f64),
+    );
+    family(
+        "haex_command_panics_total",
+        "counter",
+        "Command invocations that panicked during dispatch",
+        commands(|s| s.panicked as f64),
+    );
+    family(
+        "haex_command_dispatch_seconds_sum",
+        "counter",
+        "Total command dispatch time",
+        commands(|s| s.total_micros as f64 / 1_000_000.0),
+    );
+    family(
+        "haex_command_dispatch_seconds_max",
+        "gauge",
+        "Longest command dispatch",
+        commands(|s| s.max_micros as f64 / 1_000_000.0),
+    );
+
+    let syncs = |f: fn(&SyncStats) -> f64| {
+        snapshot
+            .syncs
+            .iter()
+            .map(|(kind, stats)| (label("kind", kind), f(stats)))
+            .collect::<Vec<_>>()
+    };
+    family(
+        "haex_sync_runs_total",
+        "counter",
+        "Finished sync runs",
+        syncs(|s| s.runs as f64),
+    );
+    family(
+        "haex_sync_failures_total",
+        "counter",
+        "Failed sync runs",
+        syncs(|s| s.failures as f64),
+    );
+    family(
+        "haex_sync_duration_seconds_sum",
+        "counter",
+        "Total sync run time",
+        syncs(|s| s.total_millis as f64 / 1000.0),
+    );
+    family(
+        "haex_sync_duration_seconds_max",
+        "gauge",
+        "Longest sync run",
+        syncs(|s| s.max_millis as f64 / 1000.0),
+    );
+    family(
+        "haex_sync_duration_seconds_last",
+        "gauge",
+        "Duration of the latest sync run",
+        syncs(|s| s.last_millis as f64 / 1000.0),
+    );
+
+    let caches = |f: fn(&CacheStats) -> f64| {
+        snapshot
+            .caches
+            .iter()
+            .map(|(cache, stats)| (label("cache", cache), f(stats)))
+            .collect::<Vec<_>>()
+    };
+    family(
+        "haex_cache_hits_total",
+        "counter",
+        "Cache lookups answered from the cache",
+        caches(|s| s.hits as f64),
+    );
+    family(
+        "haex_cache_misses_total",
+        "counter",
+        "Cache lookups that had to compute the value",
+        caches(|s| s.misses as f64),
+    );
+    family(
+        "haex_cache_hit_ratio",
+        "gauge",
+        "Share of cache lookups answered from the cache",
+        caches(CacheStats::hit_rate),
+    );
+
+    family(
+        "haex_bridge_connections",
+        "gauge",
+        "Connected external bridge clients",
+        vec![(String::new(), f64::from(snapshot.bridge_connections))],
+    );
+    out
+}
+
+/// `{name="value"}` with the value escaped as Prometheus requires
+fn label(name: &str, value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("{{{name}=\"{escaped}\"}}")
+}
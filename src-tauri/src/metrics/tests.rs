@@ -0,0 +1,74 @@
+// src-tauri/src/metrics/tests.rs
+//!
+//! Tests for the metrics registry and the Prometheus rendering
+
+use super::{render_prometheus, CacheStats, MetricsRegistry, MetricsSnapshot, SYNC_REMOTE};
+use crate::command_middleware::metrics::CommandStats;
+use std::time::Duration;
+
+#[test]
+fn test_sync_runs_accumulate() {
+    let registry = MetricsRegistry::new();
+    registry.record_sync(SYNC_REMOTE, Duration::from_millis(300), true);
+    registry.record_sync(SYNC_REMOTE, Duration::from_millis(100), false);
+
+    let syncs = registry.syncs();
+    let remote = &syncs["remote"];
+    assert_eq!(remote.runs, 2);
+    assert_eq!(remote.failures, 1);
+    assert_eq!(remote.total_millis, 400);
+    assert_eq!(remote.max_millis, 300);
+    assert_eq!(remote.last_millis, 100);
+}
+
+#[test]
+fn test_cache_hit_rate() {
+    let registry = MetricsRegistry::new();
+    for hit in [true, true, true, false] {
+        registry.record_cache("file_hash", hit);
+    }
+    let caches = registry.caches();
+    assert_eq!(caches["file_hash"].hits, 3);
+    assert_eq!(caches["file_hash"].hit_rate(), 0.75);
+    assert_eq!(CacheStats::default().hit_rate(), 0.0);
+}
+
+#[test]
+fn test_render_prometheus() {
+    let mut snapshot = MetricsSnapshot {
+        bridge_connections: 2,
+        ..Default::default()
+    };
+    snapshot.commands.insert(
+        "sql_select".to_string(),
+        CommandStats {
+            calls: 4,
+            total_micros: 1_500_000,
+            max_micros: 500_000,
+            ..Default::default()
+        },
+    );
+    snapshot
+        .caches
+        .insert("thumbnail".to_string(), CacheStats { hits: 1, misses: 1 });
+
+    let text = render_prometheus(&snapshot);
+    assert!(text.contains("# TYPE haex_command_calls_total counter\n"));
+    assert!(text.contains("haex_command_calls_total{command=\"sql_select\"} 4\n"));
+    assert!(text.contains("haex_command_dispatch_seconds_sum{command=\"sql_select\"} 1.5\n"));
+    assert!(text.contains("haex_cache_hit_ratio{cache=\"thumbnail\"} 0.5\n"));
+    assert!(text.contains("haex_bridge_connections 2\n"));
+    // Families without samples still declare their type
+    assert!(text.contains("# TYPE haex_sync_runs_total counter\n"));
+    assert!(!text.contains("haex_sync_runs_total{"));
+}
+
+#[test]
+fn test_label_values_are_escaped() {
+    let mut snapshot = MetricsSnapshot::default();
+    snapshot
+        .syncs
+        .insert("a\"b\\c".to_string(), Default::default());
+    let text = render_prometheus(&snapshot);
+    assert!(text.contains("haex_sync_runs_total{kind=\"a\\\"b\\\\c\"} 0\n"));
+}
@@ -0,0 +1,102 @@
+//! Bridge for the native Android autofill components.
+//!
+//! `HaexAutofillService` and `AutofillAuthActivity` (copied from `.github/`
+//! into the generated Android project at build time) run outside the
+//! webview, so they call [`Java_space_haex_vault_AutofillBridge_nativeQuery`]
+//! with a JSON request instead of going through a Tauri plugin. The activity
+//! shows the system biometric prompt before it asks for a credential; the
+//! association check happens here, in [`super::credential`].
+
+use super::error::AutofillError;
+use super::AutofillTarget;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Keeps the app handle for calls from the native side.
+pub fn init() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+    tauri::plugin::Builder::new("autofill")
+        .setup(|app, _api| {
+            let _ = APP.set(app.clone());
+            Ok(())
+        })
+        .build()
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum BridgeRequest {
+    Candidates {
+        target: AutofillTarget,
+    },
+    Credential {
+        target: AutofillTarget,
+        item_id: String,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BridgeResponse {
+    Ok(serde_json::Value),
+    Error(AutofillError),
+}
+
+fn run(request: BridgeRequest) -> Result<serde_json::Value, AutofillError> {
+    let app = APP.get().ok_or(AutofillError::VaultLocked)?;
+    let state = app.state::<AppState>();
+    let value = match request {
+        BridgeRequest::Candidates { target } => {
+            serde_json::to_value(super::candidates(&state, &target)?)
+        }
+        BridgeRequest::Credential { target, item_id } => {
+            serde_json::to_value(super::credential(&state, &target, &item_id)?)
+        }
+    };
+    value.map_err(|e| AutofillError::InvalidTarget {
+        reason: e.to_string(),
+    })
+}
+
+/// Answers one JSON request; always returns a JSON response.
+pub fn handle(request: &str) -> String {
+    let response = match serde_json::from_str::<BridgeRequest>(request) {
+        Ok(request) => match run(request) {
+            Ok(value) => BridgeResponse::Ok(value),
+            Err(e) => BridgeResponse::Error(e),
+        },
+        Err(e) => BridgeResponse::Error(AutofillError::InvalidTarget {
+            reason: e.to_string(),
+        }),
+    };
+    serde_json::to_string(&response)
+        .unwrap_or_else(|_| r#"{"error":{"type":"VaultLocked"}}"#.to_string())
+}
+
+/// `AutofillBridge.nativeQuery(request: String): String` on the Kotlin side.
+#[no_mangle]
+pub extern "system" fn Java_space_haex_vault_AutofillBridge_nativeQuery<'local>(
+    mut unowned_env: jni::EnvUnowned<'local>,
+    _class: jni::objects::JClass<'local>,
+    request: jni::objects::JString<'local>,
+) -> jni::sys::jstring {
+    let outcome = unowned_env.with_env(|env| -> jni::errors::Result<jni::sys::jstring> {
+        let request = request.try_to_string(env)?;
+        let response = handle(&request);
+        Ok(env.new_string(response)?.into_raw())
+    });
+    match outcome.into_outcome() {
+        jni::Outcome::Ok(response) => response,
+        jni::Outcome::Err(e) => {
+            eprintln!("[Autofill] Bridge call failed: {e}");
+            std::ptr::null_mut()
+        }
+        jni::Outcome::Panic(_) => {
+            eprintln!("[Autofill] Bridge call panicked");
+            std::ptr::null_mut()
+        }
+    }
+}
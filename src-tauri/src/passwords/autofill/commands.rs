@@ -0,0 +1,51 @@
+//! Autofill commands for the host UI.
+//!
+//! The vault's own autofill picker (and the desktop browser integration)
+//! lists the candidates for a target and fetches the chosen credential.
+//! Fetching confirms the user first: biometrics where available, otherwise
+//! the vault password passed as `password` after an
+//! `AuthError::PasswordRequired`.
+
+use super::error::AutofillError;
+use super::{AutofillCandidate, AutofillCredential, AutofillTarget};
+use crate::auth::confirm_user;
+use crate::AppState;
+use tauri::State;
+
+/// Prompt text of the biometric confirmation.
+pub fn confirmation_reason(target: &AutofillTarget) -> String {
+    match (&target.web_domain, &target.app_id) {
+        (Some(domain), _) => format!("Fill in your login for {domain}"),
+        (None, Some(app_id)) => format!("Fill in your login for {app_id}"),
+        (None, None) => "Fill in your login".to_string(),
+    }
+}
+
+/// Password items associated with the requesting app or website, without
+/// secrets.
+#[tauri::command]
+pub async fn passwords_autofill_candidates(
+    state: State<'_, AppState>,
+    target: AutofillTarget,
+) -> Result<Vec<AutofillCandidate>, AutofillError> {
+    super::candidates(&state, &target)
+}
+
+/// Username and password of an associated item, after confirming the user.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn passwords_autofill_credential(
+    state: State<'_, AppState>,
+    target: AutofillTarget,
+    item_id: String,
+    password: Option<String>,
+) -> Result<AutofillCredential, AutofillError> {
+    // Unknown or unrelated items fail before the user is bothered
+    if !super::candidates(&state, &target)?
+        .iter()
+        .any(|c| c.id == item_id)
+    {
+        return Err(AutofillError::NotFound);
+    }
+    confirm_user(&state, &confirmation_reason(&target), password.as_deref()).await?;
+    super::credential(&state, &target, &item_id)
+}
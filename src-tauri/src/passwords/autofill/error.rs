@@ -0,0 +1,72 @@
+//! Autofill error types
+
+use crate::auth::AuthError;
+use crate::database::error::DatabaseError;
+use crate::error_code::{serialize_tagged, CodedError, ErrorCode};
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize)]
+#[serde(tag = "type", content = "details")]
+#[serde(remote = "Self")]
+pub enum AutofillError {
+    /// No vault is open; the app has to be unlocked first
+    #[error("The vault is locked")]
+    VaultLocked,
+
+    #[error("Invalid autofill target: {reason}")]
+    InvalidTarget { reason: String },
+
+    /// The item doesn't exist or isn't associated with the target
+    #[error("Password item not found for this app or website")]
+    NotFound,
+
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+
+    #[error("Database error: {reason}")]
+    Database { reason: String },
+}
+
+impl CodedError for AutofillError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            AutofillError::VaultLocked => ErrorCode::VaultNotOpen,
+            AutofillError::InvalidTarget { .. } => ErrorCode::Validation,
+            AutofillError::NotFound => ErrorCode::ResourceNotFound,
+            AutofillError::Auth(e) => e.error_code(),
+            AutofillError::Database { .. } => ErrorCode::Database,
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        self.tagged().get("details").cloned()
+    }
+}
+
+impl AutofillError {
+    /// The derived `{ type, details }` representation
+    fn tagged(&self) -> serde_json::Value {
+        AutofillError::serialize(self, serde_json::value::Serializer).unwrap_or_default()
+    }
+}
+
+impl Serialize for AutofillError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_tagged(self, &self.tagged(), serializer)
+    }
+}
+
+impl From<DatabaseError> for AutofillError {
+    fn from(e: DatabaseError) -> Self {
+        match e {
+            DatabaseError::ConnectionError { .. } => AutofillError::VaultLocked,
+            e => AutofillError::Database {
+                reason: e.to_string(),
+            },
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! Password autofill for other apps and websites.
+//!
+//! The OS asks the vault for credentials of a target: the requesting app
+//! (Android package name, iOS bundle id) and, for browsers, the web domain
+//! of the page. An item is offered only if one of its URLs is associated
+//! with that target:
+//! - its `url` or a key-value that holds a URL, with the same host as the
+//!   page or a parent domain of it (`example.com` fills `login.example.com`,
+//!   never the other way around)
+//! - `androidapp://<package>` / `iosapp://<bundle id>` for native apps
+//!
+//! Browsers report their own package name next to the page's domain, so a
+//! web domain always wins over the app id.
+//!
+//! Listing candidates reveals titles and usernames only. The secrets of an
+//! item are released after the user confirmed with biometrics (or the vault
+//! password, see `auth::confirm_user`), and only for an associated target.
+//!
+//! On Android the `HaexAutofillService` answers fill requests with an
+//! authentication step: a native `AutofillAuthActivity` shows the system
+//! biometric prompt and queries the vault through [`android`]. The vault has
+//! to be unlocked in the running app; otherwise the activity opens it.
+//!
+//! iOS needs a Credential Provider extension target in the Xcode project,
+//! which is not part of this repository yet; it would use the same
+//! [`candidates`] / [`credential`] queries.
+
+#[cfg(target_os = "android")]
+pub mod android;
+pub mod commands;
+pub mod error;
+
+#[cfg(test)]
+mod tests;
+
+use crate::database::core::select_with_crdt;
+use crate::database::row::get_string;
+use crate::AppState;
+use error::AutofillError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeSet;
+use ts_rs::TS;
+use url::Url;
+
+/// URL scheme associating an item with an Android app
+pub const ANDROID_APP_SCHEME: &str = "androidapp";
+/// URL scheme associating an item with an iOS app
+pub const IOS_APP_SCHEME: &str = "iosapp";
+
+/// Who asks for credentials.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct AutofillTarget {
+    /// Android package name or iOS bundle id of the requesting app
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub app_id: Option<String>,
+    /// Host of the page when the request comes from a browser
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub web_domain: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum AutofillMatch {
+    /// Same host, or the item's app id
+    Exact,
+    /// The item belongs to a parent domain of the page
+    ParentDomain,
+}
+
+/// An item offered for a target, without secrets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct AutofillCandidate {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// The associated URL that matched
+    pub url: String,
+    #[serde(rename = "match")]
+    pub match_kind: AutofillMatch,
+}
+
+/// What gets filled into the requesting app.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct AutofillCredential {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+/// Lowercase host without a trailing dot or `www.` prefix.
+pub fn normalize_host(host: &str) -> String {
+    let host = host.trim().trim_end_matches('.').to_ascii_lowercase();
+    match host.strip_prefix("www.") {
+        Some(rest) => rest.to_string(),
+        None => host,
+    }
+}
+
+/// Parses an item URL; bare hosts (`example.com/login`) count as https.
+pub fn parse_item_url(value: &str) -> Option<Url> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    match Url::parse(value) {
+        Ok(url) if url.has_host() => Some(url),
+        _ => Url::parse(&format!("https://{value}"))
+            .ok()
+            .filter(|url| url.has_host()),
+    }
+}
+
+/// How the item URL `value` matches `target`, if at all.
+pub fn match_url(value: &str, target: &AutofillTarget) -> Option<AutofillMatch> {
+    let url = parse_item_url(value)?;
+    let host = normalize_host(url.host_str()?);
+
+    if let Some(domain) = target
+        .web_domain
+        .as_deref()
+        .filter(|d| !d.trim().is_empty())
+    {
+        if !matches!(url.scheme(), "http" | "https") {
+            return None;
+        }
+        let domain = normalize_host(domain);
+        return if domain == host {
+            Some(AutofillMatch::Exact)
+        } else if domain.ends_with(&format!(".{host}")) {
+            Some(AutofillMatch::ParentDomain)
+        } else {
+            None
+        };
+    }
+
+    let app_id = target.app_id.as_deref()?.trim().to_ascii_lowercase();
+    let app_scheme = matches!(url.scheme(), ANDROID_APP_SCHEME | IOS_APP_SCHEME);
+    (app_scheme && !app_id.is_empty() && host == app_id).then_some(AutofillMatch::Exact)
+}
+
+/// Best match among the URLs of one item.
+pub fn best_match<'a>(
+    urls: impl IntoIterator<Item = &'a str>,
+    target: &AutofillTarget,
+) -> Option<(AutofillMatch, &'a str)> {
+    urls.into_iter()
+        .filter_map(|url| match_url(url, target).map(|m| (m, url)))
+        .min_by_key(|(m, _)| *m)
+}
+
+fn validate_target(target: &AutofillTarget) -> Result<(), AutofillError> {
+    let empty = |v: &Option<String>| v.as_deref().is_none_or(|v| v.trim().is_empty());
+    if empty(&target.app_id) && empty(&target.web_domain) {
+        return Err(AutofillError::InvalidTarget {
+            reason: "needs an app id or a web domain".to_string(),
+        });
+    }
+    Ok(())
+}
+
+struct ItemUrls {
+    id: String,
+    title: String,
+    username: String,
+    urls: Vec<String>,
+}
+
+/// All items with their URLs: the `url` column plus key-values holding one.
+fn load_items(state: &AppState) -> Result<Vec<ItemUrls>, AutofillError> {
+    let sql = "SELECT i.id, i.title, i.username, i.url, \
+                      GROUP_CONCAT(kv.value, char(10)) \
+               FROM haex_passwords_item_details i \
+               LEFT JOIN haex_passwords_item_key_values kv \
+                   ON kv.item_id = i.id AND kv.value LIKE '%://%' \
+               GROUP BY i.id"
+        .to_string();
+    let rows = select_with_crdt(sql, vec![], &state.db)?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let mut urls = vec![get_string(row, 3)];
+            urls.extend(get_string(row, 4).lines().map(str::to_string));
+            urls.retain(|url| !url.trim().is_empty());
+            ItemUrls {
+                id: get_string(row, 0),
+                title: get_string(row, 1),
+                username: get_string(row, 2),
+                urls,
+            }
+        })
+        .collect())
+}
+
+fn non_empty(s: String) -> Option<String> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+/// Hosts of all stored web URLs, normalized like the page hosts they are
+/// matched against.
+pub fn web_hosts(state: &AppState) -> Result<BTreeSet<String>, AutofillError> {
+    Ok(load_items(state)?
+        .iter()
+        .flat_map(|item| item.urls.iter())
+        .filter_map(|url| parse_item_url(url))
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .filter_map(|url| url.host_str().map(normalize_host))
+        .collect())
+}
+
+/// Items associated with `target`, exact matches first.
+pub fn candidates(
+    state: &AppState,
+    target: &AutofillTarget,
+) -> Result<Vec<AutofillCandidate>, AutofillError> {
+    validate_target(target)?;
+    let mut candidates: Vec<AutofillCandidate> = load_items(state)?
+        .into_iter()
+        .filter_map(|item| {
+            let (match_kind, url) = best_match(item.urls.iter().map(String::as_str), target)?;
+            let url = url.to_string();
+            Some(AutofillCandidate {
+                id: item.id,
+                title: non_empty(item.title),
+                username: non_empty(item.username),
+                url,
+                match_kind,
+            })
+        })
+        .collect();
+    candidates.sort_by(|a, b| {
+        a.match_kind
+            .cmp(&b.match_kind)
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    Ok(candidates)
+}
+
+/// Username and password of `item_id`, if the item is associated with
+/// `target`. Callers confirm the user first.
+pub fn credential(
+    state: &AppState,
+    target: &AutofillTarget,
+    item_id: &str,
+) -> Result<AutofillCredential, AutofillError> {
+    if !candidates(state, target)?.iter().any(|c| c.id == item_id) {
+        return Err(AutofillError::NotFound);
+    }
+    let sql =
+        "SELECT username, password FROM haex_passwords_item_details WHERE id = ?1".to_string();
+    let rows = select_with_crdt(sql, vec![JsonValue::String(item_id.to_string())], &state.db)?;
+    let row = rows.first().ok_or(AutofillError::NotFound)?;
+    Ok(AutofillCredential {
+        id: item_id.to_string(),
+        username: non_empty(get_string(row, 0)),
+        password: non_empty(get_string(row, 1)),
+    })
+}
@@ -0,0 +1,108 @@
+//! Tests for the autofill association rules
+
+use super::commands::confirmation_reason;
+use super::{best_match, match_url, AutofillMatch, AutofillTarget};
+
+fn web(domain: &str) -> AutofillTarget {
+    AutofillTarget {
+        // Browsers report their own package next to the page
+        app_id: Some("org.mozilla.firefox".to_string()),
+        web_domain: Some(domain.to_string()),
+    }
+}
+
+fn app(app_id: &str) -> AutofillTarget {
+    AutofillTarget {
+        app_id: Some(app_id.to_string()),
+        web_domain: None,
+    }
+}
+
+#[test]
+fn test_match_url_web_domains() {
+    let target = web("login.example.com");
+    assert_eq!(
+        match_url("https://login.example.com/signin", &target),
+        Some(AutofillMatch::Exact)
+    );
+    assert_eq!(
+        match_url("https://www.example.com", &target),
+        Some(AutofillMatch::ParentDomain)
+    );
+    // Bare hosts count as https
+    assert_eq!(
+        match_url("example.com/login", &target),
+        Some(AutofillMatch::ParentDomain)
+    );
+    assert_eq!(
+        match_url("https://WWW.Login.Example.com.", &target),
+        Some(AutofillMatch::Exact)
+    );
+
+    // A subdomain item never fills its parent, lookalikes never match
+    assert_eq!(
+        match_url("https://accounts.example.com", &web("example.com")),
+        None
+    );
+    assert_eq!(
+        match_url("https://badexample.com", &web("example.com")),
+        None
+    );
+    assert_eq!(
+        match_url("https://example.com.evil.net", &web("example.com")),
+        None
+    );
+    // App associations don't count for pages
+    assert_eq!(
+        match_url("androidapp://example.com", &web("example.com")),
+        None
+    );
+    assert_eq!(match_url("", &target), None);
+}
+
+#[test]
+fn test_match_url_apps() {
+    let target = app("com.example.app");
+    assert_eq!(
+        match_url("androidapp://com.example.app", &target),
+        Some(AutofillMatch::Exact)
+    );
+    assert_eq!(
+        match_url("iosapp://com.example.app", &target),
+        Some(AutofillMatch::Exact)
+    );
+    assert_eq!(match_url("androidapp://com.example.other", &target), None);
+    // A website URL doesn't vouch for an app with a similar name
+    assert_eq!(match_url("https://app.example.com", &target), None);
+    assert_eq!(
+        match_url("https://example.com", &AutofillTarget::default()),
+        None
+    );
+}
+
+#[test]
+fn test_best_match_prefers_exact() {
+    let target = web("mail.example.com");
+    let urls = [
+        "https://other.net",
+        "https://example.com",
+        "https://mail.example.com",
+    ];
+    assert_eq!(
+        best_match(urls, &target),
+        Some((AutofillMatch::Exact, "https://mail.example.com"))
+    );
+    assert_eq!(best_match(["https://other.net"], &target), None);
+}
+
+#[test]
+fn test_confirmation_reason_names_the_target() {
+    assert_eq!(
+        confirmation_reason(&web("example.com")),
+        "Fill in your login for example.com"
+    );
+    assert_eq!(
+        confirmation_reason(&app("com.example.app")),
+        "Fill in your login for com.example.app"
+    );
+}
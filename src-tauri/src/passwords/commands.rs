@@ -71,12 +71,22 @@ pub async fn extension_password_list(
     name: Option<String>,
 ) -> Result<Vec<PasswordItemSummary>, ExtensionError> {
     let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    list_items_as_extension(&app_handle, &state, &extension_id).await
+}
 
+/// Permission-checked item listing for an already resolved extension.
+///
+/// Shared by the bridge command and callers without a webview (MCP tools).
+pub(crate) async fn list_items_as_extension(
+    app_handle: &AppHandle,
+    state: &State<'_, AppState>,
+    extension_id: &str,
+) -> Result<Vec<PasswordItemSummary>, ExtensionError> {
     let perm_result =
-        PermissionManager::check_passwords_permission(&state, &extension_id, PasswordsAction::Read)
+        PermissionManager::check_passwords_permission(state, extension_id, PasswordsAction::Read)
             .await;
     if let Err(ref e) = perm_result {
-        emit_permission_prompt_if_needed(&app_handle, e);
+        emit_permission_prompt_if_needed(app_handle, e);
     }
     let scope = perm_result?;
 
@@ -211,17 +221,26 @@ pub async fn extension_password_read(
     name: Option<String>,
 ) -> Result<PasswordItemFull, ExtensionError> {
     let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    read_item_as_extension(&app_handle, &state, &extension_id, &item_id).await
+}
 
+/// Permission-checked item read for an already resolved extension.
+pub(crate) async fn read_item_as_extension(
+    app_handle: &AppHandle,
+    state: &State<'_, AppState>,
+    extension_id: &str,
+    item_id: &str,
+) -> Result<PasswordItemFull, ExtensionError> {
     let perm_result =
-        PermissionManager::check_passwords_permission(&state, &extension_id, PasswordsAction::Read)
+        PermissionManager::check_passwords_permission(state, extension_id, PasswordsAction::Read)
             .await;
     if let Err(ref e) = perm_result {
-        emit_permission_prompt_if_needed(&app_handle, e);
+        emit_permission_prompt_if_needed(app_handle, e);
     }
     let scope = perm_result?;
 
     let item_rows = {
-        let (sql, params) = build_read_item_query(&scope, &item_id);
+        let (sql, params) = build_read_item_query(&scope, item_id);
         select_with_crdt(sql, params, &state.db).map_err(|e| ExtensionError::Database {
             source: DatabaseError::DatabaseError {
                 reason: e.to_string(),
@@ -233,8 +252,8 @@ pub async fn extension_password_read(
         reason: format!("Password item {} not found", item_id),
     })?;
 
-    let tags = read_item_tags(&state, &item_id)?;
-    let key_values = read_item_key_values(&state, &item_id)?;
+    let tags = read_item_tags(state, item_id)?;
+    let key_values = read_item_key_values(state, item_id)?;
 
     Ok(PasswordItemFull {
         id: get_string(row, 0),
@@ -258,6 +277,31 @@ pub async fn extension_password_read(
     })
 }
 
+/// Fills `username` / `password` from a password item for host-side
+/// protocol clients (mail, CalDAV/CardDAV), so the secret never reaches the
+/// extension. Goes through the read permission, so only items within the
+/// extension's tag scope can be used. The item's secret always wins; its
+/// username only fills in a username left empty.
+pub(crate) async fn fill_credentials_as_extension(
+    app_handle: &AppHandle,
+    state: &State<'_, AppState>,
+    extension_id: &str,
+    item_id: &str,
+    username: &mut String,
+    password: &mut String,
+) -> Result<(), ExtensionError> {
+    let item = read_item_as_extension(app_handle, state, extension_id, item_id).await?;
+    *password = item
+        .password
+        .ok_or_else(|| ExtensionError::ValidationError {
+            reason: format!("Password item {} has no password", item_id),
+        })?;
+    if username.is_empty() {
+        *username = item.username.unwrap_or_default();
+    }
+    Ok(())
+}
+
 fn build_read_item_query(scope: &PasswordsScope, item_id: &str) -> (String, Vec<JsonValue>) {
     const COLS: &str = "id, title, username, password, note, icon, color, url, \
                         otp_secret, otp_digits, otp_period, otp_algorithm, \
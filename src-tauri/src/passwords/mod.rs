@@ -9,5 +9,10 @@
 //! Access scoping is performed via tags: the permission's `target` field
 //! restricts an extension to items carrying a specific tag ("calendar",
 //! "mail", ...), or grants `*` for full access.
+//!
+//! `autofill` serves the same items to other apps and websites through the
+//! platform autofill frameworks; it is host-only and not reachable by
+//! extensions.
 
+pub mod autofill;
 pub mod commands;
@@ -21,10 +21,11 @@ use iroh::{
 
 const DEFAULT_RELAY_URL: &str = "https://relay.sync.haex.space";
 
-use tauri::Emitter;
-
 use ed25519_dalek::SigningKey;
 
+use crate::events::payloads::{
+    PeerConnectionChanged, PeerStorageStateChanged, PeerStorageStateReason,
+};
 use crate::peer_storage::error::PeerStorageError;
 use crate::peer_storage::protocol::{self, Request, Response, ALPN};
 
@@ -81,7 +82,8 @@ pub fn is_content_uri(path: &str) -> bool {
 }
 
 /// What kind of network path a QUIC connection is currently using.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, ts_rs::TS)]
+#[ts(export)]
 #[serde(rename_all = "snake_case")]
 pub enum PathType {
     /// Hole-punched/LAN — packets travel directly between the two endpoints.
@@ -94,7 +96,8 @@ pub enum PathType {
     Closed,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, ts_rs::TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct ConnectionDiagnostics {
     pub path_type: PathType,
@@ -408,14 +411,13 @@ impl PeerEndpoint {
                         &state, "error", "Endpoint", None, &msg, None, "rust",
                     );
                 }
-                let _ = app.emit_to(
-                    "main",
-                    crate::event_names::EVENT_PEER_STORAGE_STATE_CHANGED,
-                    serde_json::json!({
-                        "running": false,
-                        "reason": "endpoint-closed",
-                        "uptimeSecs": uptime.as_secs(),
-                    }),
+                let _ = crate::events::emit_to_main(
+                    &app,
+                    &PeerStorageStateChanged {
+                        running: false,
+                        reason: PeerStorageStateReason::EndpointClosed,
+                        uptime_secs: uptime.as_secs(),
+                    },
                 );
             }
         });
@@ -993,13 +995,12 @@ async fn emit_diagnostics(
         // pre-init) — silently skip the emit.
         return;
     };
-    let _ = app.emit_to(
-        "main",
-        crate::event_names::EVENT_PEER_CONNECTION_CHANGED,
-        serde_json::json!({
-            "nodeId": node_id_str,
-            "diagnostics": diagnostics,
-        }),
+    let _ = crate::events::emit_to_main(
+        &app,
+        &PeerConnectionChanged {
+            node_id: node_id_str.to_string(),
+            diagnostics,
+        },
     );
 }
 
@@ -0,0 +1,113 @@
+//! Relay commands (main window)
+
+use super::{RelayConfig, RelayError, RelayPeer, RelayStats};
+use crate::AppState;
+use serde::Serialize;
+use tauri::State;
+use ts_rs::TS;
+
+/// Longest accepted channel name
+const MAX_CHANNEL_LEN: usize = 128;
+
+/// State of the relay as shown in the settings
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayStatus {
+    /// Accepting peers (enabled in the config or running headless)
+    pub active: bool,
+    pub headless: bool,
+    pub config: RelayConfig,
+    pub stats: RelayStats,
+}
+
+#[tauri::command]
+pub fn relay_get_status(state: State<'_, AppState>) -> Result<RelayStatus, RelayError> {
+    let (config, stats) = state
+        .relay
+        .with_store_ref(|store| Ok((store.config()?, store.stats()?)))?;
+    Ok(RelayStatus {
+        active: state.relay.is_headless() || config.enabled,
+        headless: state.relay.is_headless(),
+        config,
+        stats,
+    })
+}
+
+#[tauri::command]
+pub fn relay_set_config(
+    state: State<'_, AppState>,
+    config: RelayConfig,
+) -> Result<RelayConfig, RelayError> {
+    if config.retention_days == 0 {
+        return Err(RelayError::Invalid(
+            "Retention must be at least one day".to_string(),
+        ));
+    }
+    state
+        .relay
+        .with_store_ref(|store| store.save_config(&config))?;
+    Ok(config)
+}
+
+#[tauri::command]
+pub fn relay_list_peers(state: State<'_, AppState>) -> Result<Vec<RelayPeer>, RelayError> {
+    state.relay.with_store_ref(|store| store.peers())
+}
+
+/// Adds a peer or replaces the label and channels of an existing one.
+#[tauri::command]
+pub fn relay_upsert_peer(
+    state: State<'_, AppState>,
+    client_id: String,
+    label: String,
+    channels: Vec<String>,
+) -> Result<RelayPeer, RelayError> {
+    if client_id.trim().is_empty() {
+        return Err(RelayError::Invalid(
+            "Client id must not be empty".to_string(),
+        ));
+    }
+    for channel in &channels {
+        validate_channel(channel)?;
+    }
+    let peer = RelayPeer {
+        client_id,
+        label,
+        channels,
+        created_at: time::OffsetDateTime::now_utc().unix_timestamp(),
+    };
+    state
+        .relay
+        .with_store_ref(|store| store.upsert_peer(&peer))?;
+    Ok(peer)
+}
+
+/// Removes a peer. Its messages stay until they expire.
+#[tauri::command]
+pub fn relay_remove_peer(state: State<'_, AppState>, client_id: String) -> Result<(), RelayError> {
+    let removed = state
+        .relay
+        .with_store_ref(|store| store.remove_peer(&client_id))?;
+    if !removed {
+        return Err(RelayError::PeerNotFound(client_id));
+    }
+    Ok(())
+}
+
+/// Channel names are chosen by the devices (e.g. a space id); keep them
+/// printable and bounded.
+pub fn validate_channel(channel: &str) -> Result<(), RelayError> {
+    let valid = !channel.is_empty()
+        && channel.len() <= MAX_CHANNEL_LEN
+        && channel
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'));
+    if valid {
+        Ok(())
+    } else {
+        Err(RelayError::Invalid(format!(
+            "Invalid relay channel '{channel}'"
+        )))
+    }
+}
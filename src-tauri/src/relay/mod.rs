@@ -0,0 +1,376 @@
+//! Headless sync relay
+//!
+//! Lets a desktop instance act as an always-on sync node for devices that
+//! are never online at the same time. Devices connect through the external
+//! bridge and push end-to-end encrypted payloads to named channels; the
+//! relay stores them (see [`store`]) until every other device of the channel
+//! pulled them or they expire, and pings connected peers of the channel when
+//! something new arrives.
+//!
+//! Access is governed by the relay's own peer ACL, not by the vault: a peer
+//! is a bridge client id with the channels it may use. That way the relay
+//! works while no vault is open, e.g. on a server started with `--headless`
+//! (main window hidden, relay enabled for the session). Bridge actions
+//! (payloads of encrypted `request` envelopes, answered like extension
+//! requests):
+//! - `relay.push` — `{ requestId, channel, data, ttlSecs? }`, `data` is
+//!   base64; returns `{ seq }`
+//! - `relay.pull` — `{ requestId, channel, afterSeq?, limit? }`; returns
+//!   `{ messages, hasMore }` without the caller's own messages
+//! - `relay.channels` — `{ requestId }`; returns the caller's channels
+//!
+//! Peers are notified with an encrypted `relay.notify` response
+//! (`{ channel, seq }`) and then pull.
+
+pub mod commands;
+pub(crate) mod store;
+#[cfg(test)]
+mod tests;
+
+use crate::error_code::{CodedError, ErrorCode};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use store::RelayStore;
+use thiserror::Error;
+use ts_rs::TS;
+
+/// File name of the relay store in the app data directory
+pub const RELAY_STORE_FILE: &str = "relay.db";
+/// Prefix of the bridge actions handled by the relay
+pub const RELAY_ACTION_PREFIX: &str = "relay.";
+/// Action of the notification sent to connected peers after a push
+pub const RELAY_NOTIFY_ACTION: &str = "relay.notify";
+/// Largest accepted payload (decoded)
+pub const MAX_MESSAGE_BYTES: usize = 4 * 1024 * 1024;
+/// Default and upper bound of messages returned by one pull
+const DEFAULT_PULL_LIMIT: u32 = 100;
+const MAX_PULL_LIMIT: u32 = 500;
+
+/// Relay settings, stored in the relay store.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RelayConfig {
+    /// Accept relay peers on the external bridge
+    pub enabled: bool,
+    /// Messages are dropped after this many days, or earlier if the pusher
+    /// asked for a shorter TTL
+    pub retention_days: u32,
+    /// Upper bound for the stored payloads of one channel
+    #[ts(type = "number")]
+    pub max_channel_bytes: u64,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: 30,
+            max_channel_bytes: 512 * 1024 * 1024,
+        }
+    }
+}
+
+/// A bridge client allowed to use the relay.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayPeer {
+    /// Bridge client id (public key fingerprint)
+    pub client_id: String,
+    pub label: String,
+    /// Channels the peer may push to and pull from
+    pub channels: Vec<String>,
+    /// Unix seconds
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+/// A stored message as returned by `relay.pull`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayMessage {
+    #[ts(type = "number")]
+    pub seq: i64,
+    /// Client id of the pushing peer
+    pub sender: String,
+    /// Base64 ciphertext
+    pub data: String,
+    /// Unix seconds
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+/// Size of the relay store.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayStats {
+    pub peers: u32,
+    #[ts(type = "number")]
+    pub messages: u64,
+    #[ts(type = "number")]
+    pub bytes: u64,
+}
+
+/// Result of a relay request: the response for the caller and, after a
+/// successful push, the message to announce to the channel's other peers.
+pub struct RelayOutcome {
+    pub response: JsonValue,
+    pub pushed: Option<(String, i64)>,
+}
+
+/// Errors of the relay commands and bridge actions
+#[derive(Debug, Error)]
+pub enum RelayError {
+    #[error("{0}")]
+    Invalid(String),
+    #[error("{0}")]
+    Denied(String),
+    #[error("Relay peer {0} not found")]
+    PeerNotFound(String),
+    #[error("Relay store is not open")]
+    NotOpen,
+    #[error("Relay store lock is poisoned")]
+    LockPoisoned,
+    #[error("Relay store error: {0}")]
+    Store(#[from] rusqlite::Error),
+}
+
+impl CodedError for RelayError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            RelayError::Invalid(_) => ErrorCode::Validation,
+            RelayError::Denied(_) => ErrorCode::PermissionDenied,
+            RelayError::PeerNotFound(_) => ErrorCode::ResourceNotFound,
+            RelayError::NotOpen | RelayError::Store(_) => ErrorCode::Database,
+            RelayError::LockPoisoned => ErrorCode::MutexPoisoned,
+        }
+    }
+}
+
+impl Serialize for RelayError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Serialize::serialize(&self.envelope(), serializer)
+    }
+}
+
+/// The relay of this process (`AppState::relay`).
+pub struct RelayService {
+    store: Mutex<Option<RelayStore>>,
+    /// Started with `--headless`: relay enabled regardless of the config
+    headless: AtomicBool,
+}
+
+impl Default for RelayService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RelayService {
+    pub fn new() -> Self {
+        Self {
+            store: Mutex::new(None),
+            headless: AtomicBool::new(false),
+        }
+    }
+
+    /// Opens the store at `path` and drops expired messages.
+    pub fn open(&self, path: &Path) -> Result<(), RelayError> {
+        let store = RelayStore::open(path)?;
+        if let Err(e) = store.purge_expired(unix_now()) {
+            eprintln!("[Relay] Failed to purge expired messages: {e}");
+        }
+        *self.store.lock().map_err(|_| RelayError::LockPoisoned)? = Some(store);
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub fn with_store(store: RelayStore) -> Self {
+        Self {
+            store: Mutex::new(Some(store)),
+            headless: AtomicBool::new(false),
+        }
+    }
+
+    pub fn is_headless(&self) -> bool {
+        self.headless.load(Ordering::SeqCst)
+    }
+
+    pub fn set_headless(&self, headless: bool) {
+        self.headless.store(headless, Ordering::SeqCst);
+    }
+
+    pub fn with_store_ref<T>(
+        &self,
+        f: impl FnOnce(&RelayStore) -> rusqlite::Result<T>,
+    ) -> Result<T, RelayError> {
+        let guard = self.store.lock().map_err(|_| RelayError::LockPoisoned)?;
+        let store = guard.as_ref().ok_or(RelayError::NotOpen)?;
+        Ok(f(store)?)
+    }
+
+    /// Whether the relay accepts peers right now
+    pub fn is_active(&self) -> bool {
+        self.is_headless()
+            || self
+                .with_store_ref(|store| store.config())
+                .is_ok_and(|config| config.enabled)
+    }
+
+    /// The ACL entry of `client_id`, if the relay is active and knows it
+    pub fn active_peer(&self, client_id: &str) -> Option<RelayPeer> {
+        if !self.is_active() {
+            return None;
+        }
+        self.with_store_ref(|store| store.peer(client_id))
+            .ok()
+            .flatten()
+    }
+
+    /// Handles a `relay.*` bridge action of `client_id`.
+    pub fn handle_request(
+        &self,
+        client_id: &str,
+        action: &str,
+        payload: &JsonValue,
+    ) -> RelayOutcome {
+        let request_id = payload
+            .get("requestId")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let result = match self.active_peer(client_id) {
+            None => Err(RelayError::Denied("Client is not a relay peer".to_string())),
+            Some(peer) => self.dispatch(&peer, action, payload, unix_now()),
+        };
+
+        match result {
+            Ok((data, pushed)) => RelayOutcome {
+                response: json!({ "requestId": request_id, "success": true, "data": data }),
+                pushed,
+            },
+            Err(error) => RelayOutcome {
+                response: json!({ "requestId": request_id, "success": false, "error": error.to_string() }),
+                pushed: None,
+            },
+        }
+    }
+
+    fn dispatch(
+        &self,
+        peer: &RelayPeer,
+        action: &str,
+        payload: &JsonValue,
+        now: i64,
+    ) -> Result<(JsonValue, Option<(String, i64)>), RelayError> {
+        if action == "relay.channels" {
+            return Ok((json!({ "channels": peer.channels }), None));
+        }
+
+        let channel = payload
+            .get("channel")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| RelayError::Invalid("Missing required field: channel".to_string()))?;
+        if !peer.channels.iter().any(|c| c == channel) {
+            return Err(RelayError::Denied(format!(
+                "Peer may not use channel '{channel}'"
+            )));
+        }
+
+        match action {
+            "relay.push" => {
+                let data = payload
+                    .get("data")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        RelayError::Invalid("Missing required field: data".to_string())
+                    })?;
+                let bytes = BASE64
+                    .decode(data)
+                    .map_err(|e| RelayError::Invalid(format!("Invalid base64 data: {e}")))?;
+                if bytes.len() > MAX_MESSAGE_BYTES {
+                    return Err(RelayError::Invalid(format!(
+                        "Message is {} bytes, the limit is {MAX_MESSAGE_BYTES}",
+                        bytes.len()
+                    )));
+                }
+                let ttl_secs = payload.get("ttlSecs").and_then(|v| v.as_i64());
+                let seq = self.with_store_ref(|store| {
+                    let config = store.config()?;
+                    // Expired messages don't count against the quota
+                    store.purge_expired(now)?;
+                    let used = store.channel_bytes(channel)?;
+                    if used.saturating_add(bytes.len() as u64) > config.max_channel_bytes {
+                        return Ok(None);
+                    }
+                    let expires_at = now + message_ttl(&config, ttl_secs);
+                    store
+                        .push(channel, &peer.client_id, &bytes, now, expires_at)
+                        .map(Some)
+                })?;
+                let seq =
+                    seq.ok_or_else(|| RelayError::Invalid(format!("Channel '{channel}' is full")))?;
+                Ok((json!({ "seq": seq }), Some((channel.to_string(), seq))))
+            }
+            "relay.pull" => {
+                let after_seq = payload
+                    .get("afterSeq")
+                    .and_then(|v| v.as_i64())
+                    .unwrap_or(0);
+                let limit = payload
+                    .get("limit")
+                    .and_then(|v| v.as_u64())
+                    .map(|l| l.clamp(1, u64::from(MAX_PULL_LIMIT)) as u32)
+                    .unwrap_or(DEFAULT_PULL_LIMIT);
+                // One extra row tells whether there is more
+                let mut messages = self.with_store_ref(|store| {
+                    store.pull(channel, &peer.client_id, after_seq, limit + 1, now)
+                })?;
+                let has_more = messages.len() > limit as usize;
+                messages.truncate(limit as usize);
+                Ok((json!({ "messages": messages, "hasMore": has_more }), None))
+            }
+            _ => Err(RelayError::Invalid(format!(
+                "Unknown relay action '{action}'"
+            ))),
+        }
+    }
+
+    /// Client ids of the peers (other than `sender`) that use `channel`
+    pub fn channel_peers(&self, channel: &str, sender: &str) -> Vec<String> {
+        self.with_store_ref(|store| store.peers())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|peer| peer.client_id != sender && peer.channels.iter().any(|c| c == channel))
+            .map(|peer| peer.client_id)
+            .collect()
+    }
+}
+
+/// Lifetime of a new message in seconds: the requested TTL, capped by the
+/// configured retention.
+pub fn message_ttl(config: &RelayConfig, requested_secs: Option<i64>) -> i64 {
+    let retention = i64::from(config.retention_days.max(1)) * 24 * 60 * 60;
+    requested_secs
+        .filter(|ttl| *ttl > 0)
+        .map_or(retention, |ttl| ttl.min(retention))
+}
+
+/// Whether `--headless` was passed on the command line
+pub fn headless_requested() -> bool {
+    std::env::args().any(|arg| arg == "--headless")
+}
+
+fn unix_now() -> i64 {
+    time::OffsetDateTime::now_utc().unix_timestamp()
+}
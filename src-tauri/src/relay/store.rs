@@ -0,0 +1,203 @@
+//! Relay store
+//!
+//! A SQLite file next to the vaults (`<app_data>/relay.db`), independent of
+//! any vault so the relay keeps working while all vaults are locked. It only
+//! ever holds ciphertext: payloads are encrypted end-to-end by the devices.
+
+use super::{RelayConfig, RelayMessage, RelayPeer, RelayStats};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS relay_config (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS relay_peers (
+    client_id TEXT PRIMARY KEY,
+    label TEXT NOT NULL,
+    channels TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS relay_messages (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    channel TEXT NOT NULL,
+    sender TEXT NOT NULL,
+    payload BLOB NOT NULL,
+    created_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS relay_messages_channel_seq ON relay_messages (channel, seq);
+CREATE INDEX IF NOT EXISTS relay_messages_expires_at ON relay_messages (expires_at);
+";
+
+pub struct RelayStore {
+    conn: Connection,
+}
+
+impl RelayStore {
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> rusqlite::Result<Self> {
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    pub fn config(&self) -> rusqlite::Result<RelayConfig> {
+        let stored: Option<String> = self
+            .conn
+            .query_row("SELECT value FROM relay_config WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        // An unreadable config falls back to the defaults (relay disabled)
+        Ok(stored
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
+
+    pub fn save_config(&self, config: &RelayConfig) -> rusqlite::Result<()> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO relay_config (id, value) VALUES (1, ?1)",
+            params![json],
+        )?;
+        Ok(())
+    }
+
+    pub fn peer(&self, client_id: &str) -> rusqlite::Result<Option<RelayPeer>> {
+        self.conn
+            .query_row(
+                "SELECT client_id, label, channels, created_at FROM relay_peers WHERE client_id = ?1",
+                params![client_id],
+                read_peer,
+            )
+            .optional()
+    }
+
+    pub fn peers(&self) -> rusqlite::Result<Vec<RelayPeer>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT client_id, label, channels, created_at FROM relay_peers ORDER BY label, client_id",
+        )?;
+        let peers = stmt.query_map([], read_peer)?.collect();
+        peers
+    }
+
+    /// Inserts the peer or replaces its label and channels.
+    pub fn upsert_peer(&self, peer: &RelayPeer) -> rusqlite::Result<()> {
+        let channels = serde_json::to_string(&peer.channels)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        self.conn.execute(
+            "INSERT INTO relay_peers (client_id, label, channels, created_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (client_id) DO UPDATE SET label = excluded.label, channels = excluded.channels",
+            params![peer.client_id, peer.label, channels, peer.created_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn remove_peer(&self, client_id: &str) -> rusqlite::Result<bool> {
+        let removed = self.conn.execute(
+            "DELETE FROM relay_peers WHERE client_id = ?1",
+            params![client_id],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Bytes of all stored payloads of `channel`
+    pub fn channel_bytes(&self, channel: &str) -> rusqlite::Result<u64> {
+        self.conn.query_row(
+            "SELECT COALESCE(SUM(LENGTH(payload)), 0) FROM relay_messages WHERE channel = ?1",
+            params![channel],
+            |row| row.get::<_, i64>(0).map(|n| n.max(0) as u64),
+        )
+    }
+
+    /// Stores a message and returns its sequence number.
+    pub fn push(
+        &self,
+        channel: &str,
+        sender: &str,
+        payload: &[u8],
+        now: i64,
+        expires_at: i64,
+    ) -> rusqlite::Result<i64> {
+        self.conn.execute(
+            "INSERT INTO relay_messages (channel, sender, payload, created_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![channel, sender, payload, now, expires_at],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Up to `limit` unexpired messages of `channel` after `after_seq` that
+    /// were not sent by `reader`, oldest first.
+    pub fn pull(
+        &self,
+        channel: &str,
+        reader: &str,
+        after_seq: i64,
+        limit: u32,
+        now: i64,
+    ) -> rusqlite::Result<Vec<RelayMessage>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT seq, sender, payload, created_at FROM relay_messages
+             WHERE channel = ?1 AND seq > ?2 AND sender != ?3 AND expires_at > ?4
+             ORDER BY seq LIMIT ?5",
+        )?;
+        let messages = stmt
+            .query_map(params![channel, after_seq, reader, now, limit], |row| {
+                Ok(RelayMessage {
+                    seq: row.get(0)?,
+                    sender: row.get(1)?,
+                    data: BASE64.encode(row.get::<_, Vec<u8>>(2)?),
+                    created_at: row.get(3)?,
+                })
+            })?
+            .collect();
+        messages
+    }
+
+    /// Deletes expired messages and returns how many were removed.
+    pub fn purge_expired(&self, now: i64) -> rusqlite::Result<usize> {
+        self.conn.execute(
+            "DELETE FROM relay_messages WHERE expires_at <= ?1",
+            params![now],
+        )
+    }
+
+    pub fn stats(&self) -> rusqlite::Result<RelayStats> {
+        let peers: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM relay_peers", [], |row| row.get(0))?;
+        let (messages, bytes): (i64, i64) = self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(LENGTH(payload)), 0) FROM relay_messages",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(RelayStats {
+            peers: peers.max(0) as u32,
+            messages: messages.max(0) as u64,
+            bytes: bytes.max(0) as u64,
+        })
+    }
+}
+
+fn read_peer(row: &rusqlite::Row<'_>) -> rusqlite::Result<RelayPeer> {
+    let channels: String = row.get(2)?;
+    Ok(RelayPeer {
+        client_id: row.get(0)?,
+        label: row.get(1)?,
+        channels: serde_json::from_str(&channels).unwrap_or_default(),
+        created_at: row.get(3)?,
+    })
+}
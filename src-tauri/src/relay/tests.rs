@@ -0,0 +1,183 @@
+//! Tests for the relay store, ACL and bridge actions
+
+use super::commands::validate_channel;
+use super::store::RelayStore;
+use super::{message_ttl, RelayConfig, RelayPeer, RelayService};
+use serde_json::json;
+
+const NOW: i64 = 1_700_000_000;
+
+fn peer(client_id: &str, channels: &[&str]) -> RelayPeer {
+    RelayPeer {
+        client_id: client_id.to_string(),
+        label: client_id.to_string(),
+        channels: channels.iter().map(|c| c.to_string()).collect(),
+        created_at: NOW,
+    }
+}
+
+/// Relay with peers `laptop` and `phone` on `space-1` and `tablet` on `space-2`
+fn relay(config: RelayConfig) -> RelayService {
+    let store = RelayStore::open_in_memory().unwrap();
+    store.save_config(&config).unwrap();
+    store.upsert_peer(&peer("laptop", &["space-1"])).unwrap();
+    store.upsert_peer(&peer("phone", &["space-1"])).unwrap();
+    store.upsert_peer(&peer("tablet", &["space-2"])).unwrap();
+    RelayService::with_store(store)
+}
+
+fn enabled() -> RelayConfig {
+    RelayConfig {
+        enabled: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_push_then_pull_by_other_peer() {
+    let relay = relay(enabled());
+    let laptop = relay.active_peer("laptop").unwrap();
+    let phone = relay.active_peer("phone").unwrap();
+
+    let (data, pushed) = relay
+        .dispatch(
+            &laptop,
+            "relay.push",
+            &json!({ "channel": "space-1", "data": "aGVsbG8=" }),
+            NOW,
+        )
+        .unwrap();
+    assert_eq!(pushed, Some(("space-1".to_string(), 1)));
+    assert_eq!(data, json!({ "seq": 1 }));
+
+    let (data, _) = relay
+        .dispatch(&phone, "relay.pull", &json!({ "channel": "space-1" }), NOW)
+        .unwrap();
+    assert_eq!(data["hasMore"], json!(false));
+    assert_eq!(data["messages"][0]["sender"], json!("laptop"));
+    assert_eq!(data["messages"][0]["data"], json!("aGVsbG8="));
+
+    // The pusher doesn't get its own message back
+    let (data, _) = relay
+        .dispatch(&laptop, "relay.pull", &json!({ "channel": "space-1" }), NOW)
+        .unwrap();
+    assert_eq!(data["messages"], json!([]));
+
+    // Cursor and limit
+    for _ in 0..3 {
+        relay
+            .dispatch(
+                &laptop,
+                "relay.push",
+                &json!({ "channel": "space-1", "data": "eA==" }),
+                NOW,
+            )
+            .unwrap();
+    }
+    let (data, _) = relay
+        .dispatch(
+            &phone,
+            "relay.pull",
+            &json!({ "channel": "space-1", "afterSeq": 1, "limit": 2 }),
+            NOW,
+        )
+        .unwrap();
+    assert_eq!(data["messages"].as_array().unwrap().len(), 2);
+    assert_eq!(data["messages"][0]["seq"], json!(2));
+    assert_eq!(data["hasMore"], json!(true));
+}
+
+#[test]
+fn test_acl_limits_peers_to_their_channels() {
+    let relay = relay(enabled());
+    let tablet = relay.active_peer("tablet").unwrap();
+
+    assert!(relay
+        .dispatch(
+            &tablet,
+            "relay.push",
+            &json!({ "channel": "space-1", "data": "eA==" }),
+            NOW
+        )
+        .is_err());
+    assert!(relay
+        .dispatch(&tablet, "relay.pull", &json!({ "channel": "space-1" }), NOW)
+        .is_err());
+
+    let outcome = relay.handle_request("stranger", "relay.channels", &json!({ "requestId": "r1" }));
+    assert_eq!(outcome.response["success"], json!(false));
+    assert_eq!(outcome.response["requestId"], json!("r1"));
+
+    assert_eq!(relay.channel_peers("space-1", "laptop"), vec!["phone"]);
+}
+
+#[test]
+fn test_disabled_relay_has_no_active_peers() {
+    let relay = relay(RelayConfig::default());
+    assert!(!relay.is_active());
+    assert!(relay.active_peer("laptop").is_none());
+
+    relay.set_headless(true);
+    assert!(relay.is_active());
+    assert!(relay.active_peer("laptop").is_some());
+}
+
+#[test]
+fn test_expiry_and_channel_quota() {
+    let relay = relay(RelayConfig {
+        enabled: true,
+        retention_days: 1,
+        max_channel_bytes: 4,
+    });
+    let laptop = relay.active_peer("laptop").unwrap();
+    let phone = relay.active_peer("phone").unwrap();
+
+    let push = |data: &str, ttl: i64| {
+        relay.dispatch(
+            &laptop,
+            "relay.push",
+            &json!({ "channel": "space-1", "data": data, "ttlSecs": ttl }),
+            NOW,
+        )
+    };
+    assert!(push("eHl6", 60).is_ok()); // 3 bytes
+    assert!(push("eHl6", 60).is_err()); // would exceed 4 bytes
+
+    // Expired messages are neither returned nor kept
+    let (data, _) = relay
+        .dispatch(
+            &phone,
+            "relay.pull",
+            &json!({ "channel": "space-1" }),
+            NOW + 61,
+        )
+        .unwrap();
+    assert_eq!(data["messages"], json!([]));
+    assert_eq!(
+        relay
+            .with_store_ref(|store| store.purge_expired(NOW + 61))
+            .unwrap(),
+        1
+    );
+}
+
+#[test]
+fn test_message_ttl_is_capped_by_retention() {
+    let config = RelayConfig {
+        retention_days: 2,
+        ..Default::default()
+    };
+    assert_eq!(message_ttl(&config, None), 2 * 86_400);
+    assert_eq!(message_ttl(&config, Some(60)), 60);
+    assert_eq!(message_ttl(&config, Some(10 * 86_400)), 2 * 86_400);
+    assert_eq!(message_ttl(&config, Some(-5)), 2 * 86_400);
+}
+
+#[test]
+fn test_validate_channel() {
+    assert!(validate_channel("space-1").is_ok());
+    assert!(validate_channel("space:0b7f.crdt_changes").is_ok());
+    assert!(validate_channel("").is_err());
+    assert!(validate_channel("with space").is_err());
+    assert!(validate_channel(&"x".repeat(129)).is_err());
+}
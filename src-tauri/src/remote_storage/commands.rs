@@ -13,6 +13,7 @@ use super::types::{
     StorageListDirResponse, StorageListRequest, StorageObjectInfo, StorageUploadRequest,
     UpdateStorageBackendRequest,
 };
+use super::usage::AccountedBackend;
 use crate::database::core;
 use crate::database::row::{get_bool, get_string};
 use crate::critical::CriticalFailureCode;
@@ -179,7 +180,7 @@ pub async fn remote_storage_remove_backend(
 
     core::execute_with_crdt(
         SQL_DELETE_BACKEND.clone(),
-        vec![JsonValue::String(backend_id)],
+        vec![JsonValue::String(backend_id.clone())],
         &state.db,
         &hlc_service,
     )
@@ -187,6 +188,13 @@ pub async fn remote_storage_remove_backend(
         reason: e.to_string(),
     })?;
 
+    core::with_connection(&state.db, |conn| {
+        Ok(super::usage::forget_backend(conn, &backend_id)?)
+    })
+    .map_err(|e| StorageError::DatabaseError {
+        reason: e.to_string(),
+    })?;
+
     Ok(())
 }
 
@@ -693,15 +701,15 @@ pub async fn get_backend_instance_from_db_with_overrides(
             reason: format!("Failed to parse config: {}", e),
         })?;
 
+    let bucket_override = bucket_override.filter(|bucket| !bucket.is_empty());
     if let Some(bucket) = bucket_override {
-        if !bucket.is_empty() {
-            if let Some(obj) = config.as_object_mut() {
-                obj.insert("bucket".to_string(), JsonValue::String(bucket.to_string()));
-            }
+        if let Some(obj) = config.as_object_mut() {
+            obj.insert("bucket".to_string(), JsonValue::String(bucket.to_string()));
         }
     }
 
-    create_backend(&backend_type, &config).await
+    let backend = create_backend(&backend_type, &config).await?;
+    Ok(AccountedBackend::wrap(backend, db, backend_id, bucket_override))
 }
 
 /// Get a backend instance by ID (from Tauri State)
@@ -744,5 +752,6 @@ async fn get_backend_instance(
             reason: format!("Failed to parse config: {}", e),
         })?;
 
-    create_backend(&backend_type, &config).await
+    let backend = create_backend(&backend_type, &config).await?;
+    Ok(AccountedBackend::wrap(backend, &state.db, backend_id, None))
 }
@@ -3,11 +3,13 @@
 //! Storage Error Types
 //!
 
+use crate::error_code::{serialize_tagged, CodedError, ErrorCode};
 use serde::Serialize;
 use thiserror::Error;
 
 #[derive(Debug, Error, Serialize)]
 #[serde(tag = "type", content = "details")]
+#[serde(remote = "Self")]
 pub enum StorageError {
     #[error("Backend not found: {id}")]
     BackendNotFound { id: String },
@@ -35,6 +37,51 @@ pub enum StorageError {
 
     #[error("Internal error: {reason}")]
     Internal { reason: String },
+
+    #[error("Storage quota of backend {backend_id} exceeded: {used} of {quota} bytes used, {needed} more needed")]
+    QuotaExceeded {
+        backend_id: String,
+        quota: u64,
+        used: u64,
+        needed: u64,
+    },
+}
+
+impl CodedError for StorageError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            StorageError::BackendNotFound { .. } => ErrorCode::ResourceNotFound,
+            StorageError::ConnectionFailed { .. } => ErrorCode::StorageConnection,
+            StorageError::UploadFailed { .. }
+            | StorageError::DownloadFailed { .. }
+            | StorageError::DeleteFailed { .. } => ErrorCode::Storage,
+            StorageError::ObjectNotFound { .. } => ErrorCode::ResourceNotFound,
+            StorageError::InvalidConfig { .. } => ErrorCode::Validation,
+            StorageError::DatabaseError { .. } => ErrorCode::Database,
+            StorageError::Internal { .. } => ErrorCode::Internal,
+            StorageError::QuotaExceeded { .. } => ErrorCode::StorageQuotaExceeded,
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        self.tagged().get("details").cloned()
+    }
+}
+
+impl StorageError {
+    /// The derived `{ type, details }` representation
+    fn tagged(&self) -> serde_json::Value {
+        StorageError::serialize(self, serde_json::value::Serializer).unwrap_or_default()
+    }
+}
+
+impl Serialize for StorageError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_tagged(self, &self.tagged(), serializer)
+    }
 }
 
 impl From<rusqlite::Error> for StorageError {
@@ -0,0 +1,482 @@
+// src-tauri/src/remote_storage/migration.rs
+//!
+//! Moving the vault's blobs from one storage backend to another
+//!
+//! `remote_storage_migrate_backend` copies every object the vault references
+//! on the source backend — the key prefixes of the cloud sync rules pointing
+//! at it — to the target backend. Each copy is verified by reading it back
+//! from the target and comparing its SHA-256 with the source. Only when all
+//! objects arrived are the references switched, in one transaction: the
+//! rules' `backendId` and the thumbnail cache of the source backend.
+//!
+//! - Objects already on the target with the same hash are skipped, so an
+//!   interrupted migration can simply be run again.
+//! - Objects land in the target's own bucket under the same key; a rule's
+//!   `bucket` override belongs to the source and is dropped on the switch.
+//! - The source is left untouched. Deleting it is up to the user once the
+//!   target has been checked.
+//! - Objects are copied one at a time; `maxBytesPerSecond` throttles the
+//!   copy so a migration doesn't saturate the uplink.
+//!
+//! Shares keep their backend: recipients read a share from the backend
+//! named in their invite. Extensions that store backend ids in their own
+//! tables are not covered either.
+//!
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+
+use rusqlite::{params, Connection, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, State};
+use ts_rs::TS;
+
+use super::backend::StorageBackend;
+use super::commands::get_backend_instance_from_db_with_overrides;
+use super::error::StorageError;
+use crate::crdt::hlc::HlcService;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::events::{self, payloads::DirtyTablesChanged};
+use crate::extension::database::executor::SqlExecutor;
+use crate::table_names::{
+    COL_SYNC_RULES_ID, COL_SYNC_RULES_SOURCE_CONFIG, COL_SYNC_RULES_SOURCE_TYPE,
+    COL_SYNC_RULES_TARGET_CONFIG, COL_SYNC_RULES_TARGET_TYPE, COL_THUMBNAILS_NO_SYNC_BACKEND_ID,
+    TABLE_SYNC_RULES, TABLE_THUMBNAILS_NO_SYNC,
+};
+use crate::AppState;
+
+/// Provider type of sync rule endpoints stored in a storage backend
+const CLOUD_PROVIDER: &str = "cloud";
+
+/// Progress of a running backend migration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendMigrationProgress {
+    pub from_backend_id: String,
+    pub to_backend_id: String,
+    /// Object being copied, `None` once all objects are done
+    pub current_key: Option<String>,
+    pub objects_done: u32,
+    pub objects_total: u32,
+    #[ts(type = "number")]
+    pub bytes_done: u64,
+    #[ts(type = "number")]
+    pub bytes_total: u64,
+}
+
+/// Result of `remote_storage_migrate_backend`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendMigrationResult {
+    pub from_backend_id: String,
+    pub to_backend_id: String,
+    pub objects_copied: u32,
+    /// Objects that were already on the target with the same content
+    pub objects_skipped: u32,
+    #[ts(type = "number")]
+    pub bytes_copied: u64,
+    /// Sync rules now pointing at the target. Running sync loops of these
+    /// rules were stopped and have to be restarted with the new config.
+    pub updated_rule_ids: Vec<String>,
+}
+
+/// Which endpoint of a sync rule references the backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleSide {
+    Source,
+    Target,
+}
+
+impl RuleSide {
+    fn config_column(self) -> &'static str {
+        match self {
+            RuleSide::Source => COL_SYNC_RULES_SOURCE_CONFIG,
+            RuleSide::Target => COL_SYNC_RULES_TARGET_CONFIG,
+        }
+    }
+}
+
+/// A cloud endpoint of a sync rule that stores its files in the backend
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleReference {
+    pub rule_id: String,
+    pub side: RuleSide,
+    pub config: JsonValue,
+}
+
+impl RuleReference {
+    fn bucket(&self) -> Option<String> {
+        self.config
+            .get("bucket")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    }
+
+    fn prefix(&self) -> String {
+        let prefix = self
+            .config
+            .get("prefix")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        // Same normalization as `CloudProvider::new`
+        if prefix.is_empty() || prefix.ends_with('/') {
+            prefix.to_string()
+        } else {
+            format!("{prefix}/")
+        }
+    }
+}
+
+/// An object to copy: `key` in `bucket` (`None` = the backend's own bucket)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedCopy {
+    pub bucket: Option<String>,
+    pub key: String,
+    pub size: u64,
+}
+
+/// Outcome of [`copy_objects`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CopyStats {
+    pub copied: u32,
+    pub skipped: u32,
+    pub bytes_copied: u64,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Cloud endpoints of all sync rules that point at `backend_id`
+pub fn find_rule_references(
+    conn: &Connection,
+    backend_id: &str,
+) -> Result<Vec<RuleReference>, DatabaseError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COL_SYNC_RULES_ID}, {COL_SYNC_RULES_SOURCE_TYPE}, {COL_SYNC_RULES_SOURCE_CONFIG}, \
+         {COL_SYNC_RULES_TARGET_TYPE}, {COL_SYNC_RULES_TARGET_CONFIG} \
+         FROM {TABLE_SYNC_RULES} ORDER BY {COL_SYNC_RULES_ID}"
+    ))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut references = Vec::new();
+    for (rule_id, source_type, source_config, target_type, target_config) in rows {
+        for (side, provider_type, config) in [
+            (RuleSide::Source, source_type, source_config),
+            (RuleSide::Target, target_type, target_config),
+        ] {
+            if provider_type != CLOUD_PROVIDER {
+                continue;
+            }
+            // A broken config can't reference anything we could copy
+            let Ok(config) = serde_json::from_str::<JsonValue>(&config) else {
+                continue;
+            };
+            if config.get("backendId").and_then(|v| v.as_str()) == Some(backend_id) {
+                references.push(RuleReference {
+                    rule_id: rule_id.clone(),
+                    side,
+                    config,
+                });
+            }
+        }
+    }
+    Ok(references)
+}
+
+/// Key prefixes to copy per bucket. Prefixes nested in another prefix of the
+/// same bucket are dropped, since the outer listing already covers them.
+pub fn migration_scopes(references: &[RuleReference]) -> BTreeMap<Option<String>, Vec<String>> {
+    let mut by_bucket: BTreeMap<Option<String>, BTreeSet<String>> = BTreeMap::new();
+    for reference in references {
+        by_bucket
+            .entry(reference.bucket())
+            .or_default()
+            .insert(reference.prefix());
+    }
+
+    by_bucket
+        .into_iter()
+        .map(|(bucket, prefixes)| {
+            let mut kept: Vec<String> = Vec::new();
+            // Sorted, so an enclosing prefix always comes first
+            for prefix in prefixes {
+                if !kept.iter().any(|outer| prefix.starts_with(outer.as_str())) {
+                    kept.push(prefix);
+                }
+            }
+            (bucket, kept)
+        })
+        .collect()
+}
+
+/// Flattens the listings into the objects to copy. Directory markers are
+/// skipped; two buckets holding the same key would overwrite each other on
+/// the target and are rejected.
+pub fn plan_copies(
+    listings: Vec<(Option<String>, Vec<super::types::StorageObjectInfo>)>,
+) -> Result<Vec<PlannedCopy>, StorageError> {
+    let mut planned: BTreeMap<String, PlannedCopy> = BTreeMap::new();
+    for (bucket, objects) in listings {
+        for object in objects {
+            if object.key.ends_with('/') {
+                continue;
+            }
+            if let Some(existing) = planned.get(&object.key) {
+                if existing.bucket != bucket {
+                    return Err(StorageError::InvalidConfig {
+                        reason: format!(
+                            "Key '{}' exists in bucket '{}' and '{}' and can't be merged into one target",
+                            object.key,
+                            existing.bucket.as_deref().unwrap_or("(default)"),
+                            bucket.as_deref().unwrap_or("(default)")
+                        ),
+                    });
+                }
+                continue;
+            }
+            planned.insert(
+                object.key.clone(),
+                PlannedCopy {
+                    bucket: bucket.clone(),
+                    key: object.key,
+                    size: object.size,
+                },
+            );
+        }
+    }
+    Ok(planned.into_values().collect())
+}
+
+/// Copies `plan` from `sources` (one backend per bucket) to `target`,
+/// verifying every copy by hash. Stops at the first object that can't be
+/// copied or verified.
+pub async fn copy_objects(
+    sources: &HashMap<Option<String>, Box<dyn StorageBackend>>,
+    target: &dyn StorageBackend,
+    plan: &[PlannedCopy],
+    max_bytes_per_second: Option<u64>,
+    mut on_progress: impl FnMut(Option<&str>, u32, u64),
+) -> Result<CopyStats, StorageError> {
+    let started = Instant::now();
+    let mut stats = CopyStats::default();
+    let mut bytes_done = 0u64;
+
+    for (index, copy) in plan.iter().enumerate() {
+        on_progress(Some(&copy.key), index as u32, bytes_done);
+        let source = sources
+            .get(&copy.bucket)
+            .ok_or_else(|| StorageError::Internal {
+                reason: format!("No source backend for bucket {:?}", copy.bucket),
+            })?;
+
+        let data = source.download(&copy.key).await?;
+        let hash = sha256_hex(&data);
+
+        let already_there = target.exists(&copy.key).await?
+            && sha256_hex(&target.download(&copy.key).await?) == hash;
+        if already_there {
+            stats.skipped += 1;
+        } else {
+            target.upload(&copy.key, &data).await?;
+            let written = target.download(&copy.key).await?;
+            if sha256_hex(&written) != hash {
+                return Err(StorageError::UploadFailed {
+                    reason: format!("Verification of '{}' failed: hash mismatch", copy.key),
+                });
+            }
+            stats.copied += 1;
+            stats.bytes_copied += data.len() as u64;
+        }
+        bytes_done += data.len() as u64;
+
+        // Throttle to the average rate since the start
+        if let Some(limit) = max_bytes_per_second.filter(|l| *l > 0) {
+            let due = Duration::from_secs_f64(stats.bytes_copied as f64 / limit as f64);
+            if let Some(wait) = due.checked_sub(started.elapsed()) {
+                tokio::time::sleep(wait).await;
+            }
+        }
+    }
+
+    on_progress(None, plan.len() as u32, bytes_done);
+    Ok(stats)
+}
+
+/// Points all `references` at `to_backend_id` and drops the thumbnail cache
+/// of `from_backend_id` (its entries are keyed by the old backend and would
+/// be re-rendered anyway). Returns the ids of the updated rules.
+pub fn switch_references(
+    tx: &Transaction,
+    hlc_service: &HlcService,
+    from_backend_id: &str,
+    to_backend_id: &str,
+    references: &[RuleReference],
+) -> Result<Vec<String>, DatabaseError> {
+    let mut updated = BTreeSet::new();
+    for reference in references {
+        let mut config = reference.config.clone();
+        if let Some(object) = config.as_object_mut() {
+            object.insert(
+                "backendId".to_string(),
+                JsonValue::String(to_backend_id.to_string()),
+            );
+            object.remove("bucket");
+        }
+        let config =
+            serde_json::to_string(&config).map_err(|e| DatabaseError::SerializationError {
+                reason: e.to_string(),
+            })?;
+        SqlExecutor::execute_internal_typed(
+            tx,
+            hlc_service,
+            &format!(
+                "UPDATE {TABLE_SYNC_RULES} SET {} = ? WHERE {COL_SYNC_RULES_ID} = ?",
+                reference.side.config_column()
+            ),
+            params![config, reference.rule_id],
+        )?;
+        updated.insert(reference.rule_id.clone());
+    }
+
+    tx.execute(
+        &format!(
+            "DELETE FROM {TABLE_THUMBNAILS_NO_SYNC} WHERE {COL_THUMBNAILS_NO_SYNC_BACKEND_ID} = ?1"
+        ),
+        params![from_backend_id],
+    )?;
+    Ok(updated.into_iter().collect())
+}
+
+fn database_error(e: DatabaseError) -> StorageError {
+    StorageError::DatabaseError {
+        reason: e.to_string(),
+    }
+}
+
+/// Copy all blobs the vault references from one backend to another, then
+/// switch the references to the target. See the module docs.
+#[tauri::command]
+pub async fn remote_storage_migrate_backend(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    from_id: String,
+    to_id: String,
+    max_bytes_per_second: Option<u64>,
+) -> Result<BackendMigrationResult, StorageError> {
+    if from_id == to_id {
+        return Err(StorageError::InvalidConfig {
+            reason: "Source and target backend are the same".to_string(),
+        });
+    }
+
+    let references = with_connection(&state.db, |conn| find_rule_references(conn, &from_id))
+        .map_err(database_error)?;
+    let scopes = migration_scopes(&references);
+
+    let target = get_backend_instance_from_db_with_overrides(&state.db, &to_id, None).await?;
+    target.ensure_container().await?;
+
+    let mut sources = HashMap::new();
+    let mut listings = Vec::new();
+    for (bucket, prefixes) in scopes {
+        let source =
+            get_backend_instance_from_db_with_overrides(&state.db, &from_id, bucket.as_deref())
+                .await?;
+        for prefix in prefixes {
+            let objects = source
+                .list((!prefix.is_empty()).then_some(prefix.as_str()))
+                .await?;
+            listings.push((bucket.clone(), objects));
+        }
+        sources.insert(bucket, source);
+    }
+    let plan = plan_copies(listings)?;
+    let objects_total = plan.len() as u32;
+    let bytes_total = plan.iter().map(|c| c.size).sum();
+    println!(
+        "[RemoteStorage] Migrating {} objects ({} bytes) from {} to {}",
+        objects_total, bytes_total, from_id, to_id
+    );
+
+    let stats = copy_objects(
+        &sources,
+        target.as_ref(),
+        &plan,
+        max_bytes_per_second,
+        |current_key, objects_done, bytes_done| {
+            let _ = events::emit_to_main(
+                &app_handle,
+                &BackendMigrationProgress {
+                    from_backend_id: from_id.clone(),
+                    to_backend_id: to_id.clone(),
+                    current_key: current_key.map(str::to_string),
+                    objects_done,
+                    objects_total,
+                    bytes_done,
+                    bytes_total,
+                },
+            );
+        },
+    )
+    .await?;
+
+    // Stop loops that still hold a provider for the source backend
+    {
+        let mut manager = state.sync_manager.lock().await;
+        for reference in &references {
+            manager.stop(&reference.rule_id);
+        }
+    }
+
+    let updated_rule_ids = with_connection(&state.db, |conn| {
+        let tx = conn.transaction()?;
+        let hlc_service = state.lock_or_fail(
+            &state.hlc,
+            crate::critical::CriticalFailureCode::HlcMutexPoisoned,
+            "remote_storage::migration::remote_storage_migrate_backend",
+            serde_json::json!({}),
+        )?;
+        let updated = switch_references(&tx, &hlc_service, &from_id, &to_id, &references)?;
+        tx.commit()?;
+        Ok(updated)
+    })
+    .map_err(database_error)?;
+
+    if !updated_rule_ids.is_empty() {
+        let _ = events::emit_to_main(&app_handle, &DirtyTablesChanged {});
+    }
+    println!(
+        "[RemoteStorage] Migration from {} to {} done: {} copied, {} already present, {} rules switched",
+        from_id,
+        to_id,
+        stats.copied,
+        stats.skipped,
+        updated_rule_ids.len()
+    );
+
+    Ok(BackendMigrationResult {
+        from_backend_id: from_id,
+        to_backend_id: to_id,
+        objects_copied: stats.copied,
+        objects_skipped: stats.skipped,
+        bytes_copied: stats.bytes_copied,
+        updated_rule_ids,
+    })
+}
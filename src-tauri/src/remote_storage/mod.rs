@@ -9,10 +9,15 @@
 pub mod backend;
 pub mod commands;
 pub mod error;
+pub mod migration;
 pub mod progress;
 pub mod queries;
 pub mod streaming;
 pub mod types;
+pub mod usage;
+
+#[cfg(test)]
+mod tests;
 
 pub use commands::*;
 pub use error::StorageError;
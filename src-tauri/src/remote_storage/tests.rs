@@ -0,0 +1,599 @@
+// src-tauri/src/remote_storage/tests.rs
+//!
+//! Tests for the backend migration (scope planning, verified copies and the
+//! reference switch) and for the usage ledger with quotas
+//!
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use rusqlite::Connection;
+use serde_json::{json, Value as JsonValue};
+
+use super::backend::StorageBackend;
+use super::error::StorageError;
+use super::migration::{
+    copy_objects, find_rule_references, migration_scopes, plan_copies, switch_references,
+    PlannedCopy, RuleSide,
+};
+use super::types::StorageObjectInfo;
+use super::usage::{
+    check_quota, record_delete, record_upload, replace_bucket_usage, set_quota, usage_report,
+    AccountedBackend, BackendUsage, SpaceUsage,
+};
+use crate::crdt::hlc::HlcService;
+use crate::database::core::with_connection;
+use crate::table_names::{TABLE_STORAGE_BACKENDS, TABLE_SYNC_RULES, TABLE_THUMBNAILS_NO_SYNC};
+use crate::test_support::crdt::{create_crdt_table, open_crdt_connection};
+use crate::test_support::db::db_connection;
+
+/// In-memory backend. With `corrupt_uploads` every upload stores different
+/// bytes, so verification has to catch it.
+#[derive(Default)]
+struct MemoryBackend {
+    objects: Mutex<BTreeMap<String, Vec<u8>>>,
+    uploads: Mutex<u32>,
+    corrupt_uploads: bool,
+}
+
+impl MemoryBackend {
+    fn with(objects: &[(&str, &[u8])]) -> Self {
+        let backend = Self::default();
+        for (key, data) in objects {
+            backend
+                .objects
+                .lock()
+                .unwrap()
+                .insert(key.to_string(), data.to_vec());
+        }
+        backend
+    }
+
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.objects.lock().unwrap().get(key).cloned()
+    }
+
+    fn uploads(&self) -> u32 {
+        *self.uploads.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl StorageBackend for MemoryBackend {
+    fn backend_type(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn test_connection(&self) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    async fn upload(&self, key: &str, data: &[u8]) -> Result<(), StorageError> {
+        *self.uploads.lock().unwrap() += 1;
+        let mut data = data.to_vec();
+        if self.corrupt_uploads {
+            data.push(0);
+        }
+        self.objects.lock().unwrap().insert(key.to_string(), data);
+        Ok(())
+    }
+
+    async fn download(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        self.get(key).ok_or_else(|| StorageError::ObjectNotFound {
+            key: key.to_string(),
+        })
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        self.objects.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StorageError> {
+        Ok(self.objects.lock().unwrap().contains_key(key))
+    }
+
+    async fn list(&self, prefix: Option<&str>) -> Result<Vec<StorageObjectInfo>, StorageError> {
+        Ok(self
+            .objects
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix.unwrap_or("")))
+            .map(|(key, data)| StorageObjectInfo {
+                key: key.clone(),
+                size: data.len() as u64,
+                last_modified: None,
+            })
+            .collect())
+    }
+}
+
+fn object(key: &str, size: u64) -> StorageObjectInfo {
+    StorageObjectInfo {
+        key: key.to_string(),
+        size,
+        last_modified: None,
+    }
+}
+
+fn copy(bucket: Option<&str>, key: &str, size: u64) -> PlannedCopy {
+    PlannedCopy {
+        bucket: bucket.map(str::to_string),
+        key: key.to_string(),
+        size,
+    }
+}
+
+/// In-memory vault with the backend, sync rule, thumbnail and usage tables
+fn vault() -> (Connection, HlcService) {
+    let hlc = HlcService::new_for_testing("migration-test-device");
+    let conn = open_crdt_connection(hlc.clone()).unwrap();
+    create_crdt_table(
+        &conn,
+        TABLE_SYNC_RULES,
+        &format!(
+            "CREATE TABLE {TABLE_SYNC_RULES} (
+                 id TEXT PRIMARY KEY NOT NULL,
+                 space_id TEXT,
+                 source_type TEXT NOT NULL,
+                 source_config TEXT NOT NULL,
+                 target_type TEXT NOT NULL,
+                 target_config TEXT NOT NULL
+             )"
+        ),
+    )
+    .unwrap();
+    conn.execute_batch(&format!(
+        "CREATE TABLE {TABLE_STORAGE_BACKENDS} (id TEXT PRIMARY KEY NOT NULL, name TEXT NOT NULL);
+         CREATE TABLE {TABLE_THUMBNAILS_NO_SYNC} (
+             backend_id TEXT NOT NULL,
+             file_id TEXT NOT NULL,
+             data BLOB,
+             PRIMARY KEY (backend_id, file_id)
+         );"
+    ))
+    .unwrap();
+    conn.execute_batch(include_str!(
+        "../../database/migrations/0019_add_storage_usage.sql"
+    ))
+    .unwrap();
+    (conn, hlc)
+}
+
+fn insert_rule(conn: &Connection, id: &str, source: (&str, JsonValue), target: (&str, JsonValue)) {
+    conn.execute(
+        &format!(
+            "INSERT INTO {TABLE_SYNC_RULES} (id, source_type, source_config, target_type, target_config)
+             VALUES (?1, ?2, ?3, ?4, ?5)"
+        ),
+        rusqlite::params![
+            id,
+            source.0,
+            source.1.to_string(),
+            target.0,
+            target.1.to_string()
+        ],
+    )
+    .unwrap();
+}
+
+fn rule_config(conn: &Connection, id: &str, column: &str) -> JsonValue {
+    let config: String = conn
+        .query_row(
+            &format!("SELECT {column} FROM {TABLE_SYNC_RULES} WHERE id = ?1"),
+            [id],
+            |row| row.get(0),
+        )
+        .unwrap();
+    serde_json::from_str(&config).unwrap()
+}
+
+#[test]
+fn test_find_rule_references_matches_cloud_endpoints_only() {
+    let (conn, _hlc) = vault();
+    let local = json!({"path": "/home/me/photos"});
+    insert_rule(
+        &conn,
+        "up",
+        ("local", local.clone()),
+        ("cloud", json!({"backendId": "old", "prefix": "photos"})),
+    );
+    insert_rule(
+        &conn,
+        "down",
+        ("cloud", json!({"backendId": "old", "bucket": "archive"})),
+        ("local", local.clone()),
+    );
+    insert_rule(
+        &conn,
+        "other",
+        ("local", local.clone()),
+        ("cloud", json!({"backendId": "elsewhere"})),
+    );
+    // A local path that happens to carry the id is not a reference
+    insert_rule(
+        &conn,
+        "local",
+        ("local", json!({"backendId": "old"})),
+        ("local", local),
+    );
+
+    let references = find_rule_references(&conn, "old").unwrap();
+    let found: Vec<_> = references
+        .iter()
+        .map(|r| (r.rule_id.as_str(), r.side))
+        .collect();
+    assert_eq!(
+        found,
+        vec![("down", RuleSide::Source), ("up", RuleSide::Target)]
+    );
+}
+
+#[test]
+fn test_migration_scopes_merge_nested_prefixes() {
+    let (conn, _hlc) = vault();
+    let local = json!({"path": "/"});
+    for (id, config) in [
+        ("a", json!({"backendId": "old", "prefix": "photos"})),
+        ("b", json!({"backendId": "old", "prefix": "photos/2024/"})),
+        ("c", json!({"backendId": "old", "prefix": "docs/"})),
+        ("d", json!({"backendId": "old", "bucket": "archive"})),
+        (
+            "e",
+            json!({"backendId": "old", "bucket": "archive", "prefix": "x"}),
+        ),
+        ("f", json!({"backendId": "old", "bucket": ""})),
+    ] {
+        insert_rule(&conn, id, ("local", local.clone()), ("cloud", config));
+    }
+
+    let scopes = migration_scopes(&find_rule_references(&conn, "old").unwrap());
+    assert_eq!(scopes.len(), 2);
+    // An empty bucket override means the backend's own bucket, and an empty
+    // prefix covers the whole bucket
+    assert_eq!(scopes[&None], vec!["".to_string()]);
+    assert_eq!(scopes[&Some("archive".to_string())], vec!["".to_string()]);
+}
+
+#[test]
+fn test_migration_scopes_keep_sibling_prefixes() {
+    let (conn, _hlc) = vault();
+    let local = json!({"path": "/"});
+    for (id, prefix) in [("a", "photos"), ("b", "photos/2024"), ("c", "docs")] {
+        insert_rule(
+            &conn,
+            id,
+            ("local", local.clone()),
+            ("cloud", json!({"backendId": "old", "prefix": prefix})),
+        );
+    }
+
+    let scopes = migration_scopes(&find_rule_references(&conn, "old").unwrap());
+    assert_eq!(
+        scopes[&None],
+        vec!["docs/".to_string(), "photos/".to_string()]
+    );
+}
+
+#[test]
+fn test_plan_copies_skips_markers_and_rejects_colliding_buckets() {
+    let plan = plan_copies(vec![
+        (
+            None,
+            vec![object("a/", 0), object("a/1", 3), object("a/2", 5)],
+        ),
+        // Overlapping listings of the same bucket are merged
+        (None, vec![object("a/1", 3)]),
+    ])
+    .unwrap();
+    assert_eq!(plan, vec![copy(None, "a/1", 3), copy(None, "a/2", 5)]);
+
+    let collision = plan_copies(vec![
+        (None, vec![object("a/1", 3)]),
+        (Some("archive".to_string()), vec![object("a/1", 4)]),
+    ]);
+    assert!(matches!(collision, Err(StorageError::InvalidConfig { .. })));
+}
+
+#[tokio::test]
+async fn test_copy_objects_copies_verifies_and_resumes() {
+    let mut sources: HashMap<Option<String>, Box<dyn StorageBackend>> = HashMap::new();
+    sources.insert(
+        None,
+        Box::new(MemoryBackend::with(&[("a", b"one"), ("b", b"two")])),
+    );
+    sources.insert(
+        Some("archive".to_string()),
+        Box::new(MemoryBackend::with(&[("c", b"three")])),
+    );
+    // `a` is already there from an earlier run, `b` is stale
+    let target = MemoryBackend::with(&[("a", b"one"), ("b", b"old")]);
+    let plan = vec![
+        copy(None, "a", 3),
+        copy(None, "b", 3),
+        copy(Some("archive"), "c", 5),
+    ];
+
+    let mut progress = Vec::new();
+    let stats = copy_objects(&sources, &target, &plan, None, |key, done, bytes| {
+        progress.push((key.map(str::to_string), done, bytes))
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(stats.copied, 2);
+    assert_eq!(stats.skipped, 1);
+    assert_eq!(stats.bytes_copied, 8);
+    assert_eq!(target.uploads(), 2);
+    assert_eq!(target.get("b").unwrap(), b"two");
+    assert_eq!(target.get("c").unwrap(), b"three");
+    assert_eq!(
+        progress.last().unwrap(),
+        &(None, 3, 11),
+        "final progress reports everything done"
+    );
+    assert_eq!(progress[1], (Some("b".to_string()), 1, 3));
+}
+
+#[tokio::test]
+async fn test_copy_objects_fails_on_verification_mismatch() {
+    let mut sources: HashMap<Option<String>, Box<dyn StorageBackend>> = HashMap::new();
+    sources.insert(None, Box::new(MemoryBackend::with(&[("a", b"one")])));
+    let target = MemoryBackend {
+        corrupt_uploads: true,
+        ..Default::default()
+    };
+
+    let result = copy_objects(&sources, &target, &[copy(None, "a", 3)], None, |_, _, _| {}).await;
+    assert!(matches!(result, Err(StorageError::UploadFailed { .. })));
+}
+
+#[tokio::test]
+async fn test_copy_objects_throttles() {
+    let mut sources: HashMap<Option<String>, Box<dyn StorageBackend>> = HashMap::new();
+    sources.insert(None, Box::new(MemoryBackend::with(&[("a", &[7u8; 100])])));
+    let target = MemoryBackend::default();
+
+    let started = std::time::Instant::now();
+    copy_objects(
+        &sources,
+        &target,
+        &[copy(None, "a", 100)],
+        Some(1000),
+        |_, _, _| {},
+    )
+    .await
+    .unwrap();
+    assert!(started.elapsed() >= std::time::Duration::from_millis(100));
+}
+
+#[test]
+fn test_switch_references_rewrites_rules_and_drops_thumbnails() {
+    let (mut conn, hlc) = vault();
+    insert_rule(
+        &conn,
+        "up",
+        ("local", json!({"path": "/"})),
+        (
+            "cloud",
+            json!({"backendId": "old", "bucket": "archive", "prefix": "photos/"}),
+        ),
+    );
+    insert_rule(
+        &conn,
+        "both",
+        ("cloud", json!({"backendId": "old"})),
+        ("cloud", json!({"backendId": "old", "prefix": "copy/"})),
+    );
+    insert_rule(
+        &conn,
+        "other",
+        ("local", json!({"path": "/"})),
+        ("cloud", json!({"backendId": "elsewhere"})),
+    );
+    conn.execute_batch(&format!(
+        "INSERT INTO {TABLE_THUMBNAILS_NO_SYNC} (backend_id, file_id) VALUES
+             ('old', 'f1'), ('old', 'f2'), ('elsewhere', 'f1');"
+    ))
+    .unwrap();
+
+    let references = find_rule_references(&conn, "old").unwrap();
+    let tx = conn.transaction().unwrap();
+    let updated = switch_references(&tx, &hlc, "old", "new", &references).unwrap();
+    tx.commit().unwrap();
+
+    assert_eq!(updated, vec!["both".to_string(), "up".to_string()]);
+    assert_eq!(
+        rule_config(&conn, "up", "target_config"),
+        json!({"backendId": "new", "prefix": "photos/"})
+    );
+    assert_eq!(
+        rule_config(&conn, "both", "source_config"),
+        json!({"backendId": "new"})
+    );
+    assert_eq!(
+        rule_config(&conn, "both", "target_config"),
+        json!({"backendId": "new", "prefix": "copy/"})
+    );
+    assert_eq!(
+        rule_config(&conn, "other", "target_config"),
+        json!({"backendId": "elsewhere"})
+    );
+    assert!(find_rule_references(&conn, "old").unwrap().is_empty());
+
+    let thumbnails: Vec<String> = conn
+        .prepare(&format!(
+            "SELECT backend_id FROM {TABLE_THUMBNAILS_NO_SYNC}"
+        ))
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(thumbnails, vec!["elsewhere".to_string()]);
+
+    // Rule updates go through the CRDT layer
+    let hlc_set: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM {TABLE_SYNC_RULES} WHERE haex_hlc IS NOT NULL"),
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(hlc_set, 2);
+}
+
+fn insert_space_rule(conn: &Connection, id: &str, space_id: &str, target: JsonValue) {
+    insert_rule(conn, id, ("local", json!({"path": "/"})), ("cloud", target));
+    conn.execute(
+        &format!("UPDATE {TABLE_SYNC_RULES} SET space_id = ?1 WHERE id = ?2"),
+        [space_id, id],
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_usage_report_groups_by_backend_and_space() {
+    let (mut conn, _hlc) = vault();
+    conn.execute_batch(&format!(
+        "INSERT INTO {TABLE_STORAGE_BACKENDS} (id, name) VALUES ('b1', 'Main'), ('b2', 'Empty');"
+    ))
+    .unwrap();
+    insert_space_rule(
+        &conn,
+        "photos",
+        "space-photos",
+        json!({"backendId": "b1", "prefix": "photos"}),
+    );
+    insert_space_rule(
+        &conn,
+        "raw",
+        "space-raw",
+        json!({"backendId": "b1", "prefix": "photos/raw/"}),
+    );
+    insert_space_rule(
+        &conn,
+        "archive",
+        "space-archive",
+        json!({"backendId": "b1", "bucket": "archive"}),
+    );
+    set_quota(&conn, "b1", Some(1000)).unwrap();
+
+    record_upload(&conn, "b1", "", "photos/a.jpg", 10).unwrap();
+    record_upload(&conn, "b1", "", "photos/raw/a.cr2", 100).unwrap();
+    record_upload(&conn, "b1", "", "loose.txt", 1).unwrap();
+    record_upload(&conn, "b1", "archive", "photos/old.jpg", 7).unwrap();
+    // Replacing an object keeps one entry with the new size
+    record_upload(&conn, "b1", "", "photos/b.jpg", 5).unwrap();
+    record_upload(&conn, "b1", "", "photos/b.jpg", 20).unwrap();
+    record_upload(&conn, "gone", "", "x", 3).unwrap();
+    record_delete(&conn, "b1", "", "missing").unwrap();
+
+    let report = usage_report(&conn).unwrap();
+    assert_eq!(
+        report.backends,
+        vec![
+            BackendUsage {
+                backend_id: "b1".to_string(),
+                name: Some("Main".to_string()),
+                files: 5,
+                bytes: 138,
+                quota_bytes: Some(1000),
+            },
+            BackendUsage {
+                backend_id: "b2".to_string(),
+                name: Some("Empty".to_string()),
+                files: 0,
+                bytes: 0,
+                quota_bytes: None,
+            },
+            BackendUsage {
+                backend_id: "gone".to_string(),
+                name: None,
+                files: 1,
+                bytes: 3,
+                quota_bytes: None,
+            },
+        ]
+    );
+
+    let space = |backend_id: &str, space_id: Option<&str>, files, bytes| SpaceUsage {
+        space_id: space_id.map(str::to_string),
+        backend_id: backend_id.to_string(),
+        files,
+        bytes,
+    };
+    assert_eq!(
+        report.spaces,
+        vec![
+            space("b1", None, 1, 1),
+            space("b1", Some("space-archive"), 1, 7),
+            space("b1", Some("space-photos"), 2, 30),
+            space("b1", Some("space-raw"), 1, 100),
+            space("gone", None, 1, 3),
+        ]
+    );
+
+    // A rebuild replaces one bucket and skips directory markers
+    replace_bucket_usage(
+        &mut conn,
+        "b1",
+        "",
+        &[object("photos/", 0), object("photos/c.jpg", 50)],
+    )
+    .unwrap();
+    let report = usage_report(&conn).unwrap();
+    assert_eq!(
+        (report.backends[0].files, report.backends[0].bytes),
+        (2, 57)
+    );
+}
+
+#[test]
+fn test_quota_counts_only_the_size_difference_of_replacements() {
+    let (conn, _hlc) = vault();
+    // No quota, no limit
+    check_quota(&conn, "b1", "", "a", u64::MAX / 2).unwrap();
+
+    set_quota(&conn, "b1", Some(100)).unwrap();
+    record_upload(&conn, "b1", "", "a", 60).unwrap();
+    check_quota(&conn, "b1", "", "b", 40).unwrap();
+    assert!(matches!(
+        check_quota(&conn, "b1", "", "b", 41),
+        Err(StorageError::QuotaExceeded {
+            quota: 100,
+            used: 60,
+            needed: 41,
+            ..
+        })
+    ));
+    // Replacing `a` frees its 60 bytes first
+    check_quota(&conn, "b1", "", "a", 100).unwrap();
+
+    set_quota(&conn, "b1", None).unwrap();
+    check_quota(&conn, "b1", "", "b", 1000).unwrap();
+}
+
+#[tokio::test]
+async fn test_accounted_backend_records_uploads_and_deletes() {
+    let (conn, _hlc) = vault();
+    let db = db_connection(conn);
+    with_connection(&db, |conn| set_quota(conn, "b1", Some(10))).unwrap();
+
+    let backend = AccountedBackend::wrap(Box::new(MemoryBackend::default()), &db, "b1", None);
+    backend.upload("a", b"12345").await.unwrap();
+    backend.upload("b", b"123").await.unwrap();
+    let rejected = backend.upload("c", b"123").await;
+    assert!(matches!(rejected, Err(StorageError::QuotaExceeded { .. })));
+    // The rejected object never reached the backend
+    assert!(!backend.exists("c").await.unwrap());
+
+    backend.delete("a").await.unwrap();
+    backend.upload("c", b"123").await.unwrap();
+
+    let report = with_connection(&db, |conn| usage_report(conn)).unwrap();
+    assert_eq!(report.backends.len(), 1);
+    assert_eq!((report.backends[0].files, report.backends[0].bytes), (2, 6));
+}
@@ -0,0 +1,582 @@
+// src-tauri/src/remote_storage/usage.rs
+//!
+//! Storage usage accounting per backend
+//!
+//! Every backend handed out by `remote_storage::commands` is wrapped in an
+//! [`AccountedBackend`], which records the size of each object it uploads and
+//! forgets it again on delete. The ledger lives in the local-only
+//! [`USAGE_TABLE`]; reports are sums over it, so they never need a listing
+//! of the remote side. Objects written before accounting existed, or by
+//! other devices, are picked up by [`rebuild_backend_usage`].
+//!
+//! A backend can have a quota (bytes). Uploads that would push the recorded
+//! usage past it fail with [`StorageError::QuotaExceeded`]; replacing an
+//! object only counts the size difference.
+//!
+//! Usage per space is derived from the cloud sync rules: an object belongs
+//! to the space of the rule with the longest prefix covering its key in the
+//! same backend and bucket.
+//!
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::backend::StorageBackend;
+use super::error::StorageError;
+use super::progress::ProgressCallback;
+use super::types::{StorageListDirResponse, StorageObjectInfo};
+use crate::database::constants::vault_settings_key;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::database::DbConnection;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, COL_STORAGE_BACKENDS_ID,
+    COL_STORAGE_BACKENDS_NAME, COL_SYNC_RULES_SOURCE_CONFIG, COL_SYNC_RULES_SOURCE_TYPE,
+    COL_SYNC_RULES_SPACE_ID, COL_SYNC_RULES_TARGET_CONFIG, COL_SYNC_RULES_TARGET_TYPE,
+    TABLE_CRDT_CONFIGS, TABLE_STORAGE_BACKENDS, TABLE_SYNC_RULES,
+};
+
+/// Local-only ledger of the objects stored per backend, created by migration
+/// `0019_add_storage_usage`. `bucket` is empty for the backend's own bucket.
+/// Never synced (`_no_sync`).
+pub const USAGE_TABLE: &str = "haex_storage_usage_no_sync";
+
+const CONFIG_TYPE: &str = "system";
+
+/// Usage of one storage backend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendUsage {
+    pub backend_id: String,
+    /// `None` for ledger entries of a backend that no longer exists
+    pub name: Option<String>,
+    #[ts(type = "number")]
+    pub files: u64,
+    #[ts(type = "number")]
+    pub bytes: u64,
+    #[ts(type = "number | null")]
+    pub quota_bytes: Option<u64>,
+}
+
+/// Usage of one space within one backend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SpaceUsage {
+    /// `None` for objects no space's sync rule covers
+    pub space_id: Option<String>,
+    pub backend_id: String,
+    #[ts(type = "number")]
+    pub files: u64,
+    #[ts(type = "number")]
+    pub bytes: u64,
+}
+
+/// Result of `filesync_get_usage`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsageReport {
+    pub backends: Vec<BackendUsage>,
+    pub spaces: Vec<SpaceUsage>,
+}
+
+/// Records an object of `size` bytes, replacing an earlier entry for the key
+pub fn record_upload(
+    conn: &Connection,
+    backend_id: &str,
+    bucket: &str,
+    key: &str,
+    size: u64,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO \"{USAGE_TABLE}\" (backend_id, bucket, key, size) VALUES (?1, ?2, ?3, ?4)"
+        ),
+        params![backend_id, bucket, key, size as i64],
+    )?;
+    Ok(())
+}
+
+pub fn record_delete(
+    conn: &Connection,
+    backend_id: &str,
+    bucket: &str,
+    key: &str,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        &format!(
+            "DELETE FROM \"{USAGE_TABLE}\" WHERE backend_id = ?1 AND bucket = ?2 AND key = ?3"
+        ),
+        params![backend_id, bucket, key],
+    )?;
+    Ok(())
+}
+
+/// Replaces the ledger entries of one bucket with `objects`. Used by the
+/// rebuild; directory markers are skipped.
+pub fn replace_bucket_usage(
+    conn: &mut Connection,
+    backend_id: &str,
+    bucket: &str,
+    objects: &[StorageObjectInfo],
+) -> Result<(), rusqlite::Error> {
+    let tx = conn.transaction()?;
+    tx.execute(
+        &format!("DELETE FROM \"{USAGE_TABLE}\" WHERE backend_id = ?1 AND bucket = ?2"),
+        params![backend_id, bucket],
+    )?;
+    {
+        let mut insert = tx.prepare(&format!(
+            "INSERT OR REPLACE INTO \"{USAGE_TABLE}\" (backend_id, bucket, key, size) VALUES (?1, ?2, ?3, ?4)"
+        ))?;
+        for object in objects.iter().filter(|o| !o.key.ends_with('/')) {
+            insert.execute(params![backend_id, bucket, object.key, object.size as i64])?;
+        }
+    }
+    tx.commit()
+}
+
+/// Drops the whole ledger of a backend, e.g. when the backend is removed
+pub fn forget_backend(conn: &Connection, backend_id: &str) -> Result<usize, rusqlite::Error> {
+    conn.execute(
+        &format!("DELETE FROM \"{USAGE_TABLE}\" WHERE backend_id = ?1"),
+        params![backend_id],
+    )
+}
+
+pub fn load_quotas(conn: &Connection) -> Result<BTreeMap<String, u64>, DatabaseError> {
+    let value: Option<String> = conn
+        .query_row(
+            &format!(
+                "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"
+            ),
+            params![vault_settings_key::STORAGE_QUOTAS],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match value {
+        None => Ok(BTreeMap::new()),
+        Some(json) => serde_json::from_str(&json).map_err(|e| DatabaseError::SerializationError {
+            reason: format!("Invalid storage quotas: {e}"),
+        }),
+    }
+}
+
+/// Sets the quota of a backend, or removes it with `None`
+pub fn set_quota(
+    conn: &Connection,
+    backend_id: &str,
+    quota_bytes: Option<u64>,
+) -> Result<(), DatabaseError> {
+    let mut quotas = load_quotas(conn)?;
+    match quota_bytes {
+        Some(bytes) => quotas.insert(backend_id.to_string(), bytes),
+        None => quotas.remove(backend_id),
+    };
+    let json = serde_json::to_string(&quotas).map_err(|e| DatabaseError::SerializationError {
+        reason: e.to_string(),
+    })?;
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?, ?, ?)"
+        ),
+        params![vault_settings_key::STORAGE_QUOTAS, CONFIG_TYPE, json],
+    )?;
+    Ok(())
+}
+
+fn backend_bytes(conn: &Connection, backend_id: &str) -> Result<u64, rusqlite::Error> {
+    conn.query_row(
+        &format!("SELECT COALESCE(SUM(size), 0) FROM \"{USAGE_TABLE}\" WHERE backend_id = ?1"),
+        params![backend_id],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|bytes| bytes as u64)
+}
+
+/// Fails if storing `size` bytes under `key` would exceed the backend's quota
+pub fn check_quota(
+    conn: &Connection,
+    backend_id: &str,
+    bucket: &str,
+    key: &str,
+    size: u64,
+) -> Result<(), StorageError> {
+    let quotas = load_quotas(conn).map_err(|e| StorageError::DatabaseError {
+        reason: e.to_string(),
+    })?;
+    let Some(&quota) = quotas.get(backend_id) else {
+        return Ok(());
+    };
+
+    let used = backend_bytes(conn, backend_id)?;
+    let replaced: i64 = conn
+        .query_row(
+            &format!(
+                "SELECT size FROM \"{USAGE_TABLE}\" WHERE backend_id = ?1 AND bucket = ?2 AND key = ?3"
+            ),
+            params![backend_id, bucket, key],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(0);
+    if used.saturating_sub(replaced as u64) + size > quota {
+        return Err(StorageError::QuotaExceeded {
+            backend_id: backend_id.to_string(),
+            quota,
+            used,
+            needed: size,
+        });
+    }
+    Ok(())
+}
+
+/// A cloud sync rule endpoint, as far as usage attribution is concerned
+struct SpaceScope {
+    space_id: Option<String>,
+    backend_id: String,
+    bucket: String,
+    prefix: String,
+}
+
+fn space_scopes(conn: &Connection) -> Result<Vec<SpaceScope>, DatabaseError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COL_SYNC_RULES_SPACE_ID}, {COL_SYNC_RULES_SOURCE_TYPE}, {COL_SYNC_RULES_SOURCE_CONFIG}, \
+         {COL_SYNC_RULES_TARGET_TYPE}, {COL_SYNC_RULES_TARGET_CONFIG} FROM {TABLE_SYNC_RULES}"
+    ))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut scopes = Vec::new();
+    for (space_id, source_type, source_config, target_type, target_config) in rows {
+        for (provider_type, config) in [(source_type, source_config), (target_type, target_config)]
+        {
+            if provider_type != "cloud" {
+                continue;
+            }
+            let Ok(config) = serde_json::from_str::<serde_json::Value>(&config) else {
+                continue;
+            };
+            let field = |name: &str| {
+                config
+                    .get(name)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string()
+            };
+            let backend_id = field("backendId");
+            if backend_id.is_empty() {
+                continue;
+            }
+            let mut prefix = field("prefix");
+            // Same normalization as `CloudProvider::new`
+            if !prefix.is_empty() && !prefix.ends_with('/') {
+                prefix.push('/');
+            }
+            scopes.push(SpaceScope {
+                space_id: space_id.clone(),
+                backend_id,
+                bucket: field("bucket"),
+                prefix,
+            });
+        }
+    }
+    // Longest prefix first, so the most specific rule wins
+    scopes.sort_by_key(|scope| std::cmp::Reverse(scope.prefix.len()));
+    Ok(scopes)
+}
+
+/// Sums the ledger per backend and per space
+pub fn usage_report(conn: &Connection) -> Result<StorageUsageReport, DatabaseError> {
+    let quotas = load_quotas(conn)?;
+
+    let mut names: HashMap<String, String> = HashMap::new();
+    {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {COL_STORAGE_BACKENDS_ID}, {COL_STORAGE_BACKENDS_NAME} FROM {TABLE_STORAGE_BACKENDS}"
+        ))?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (id, name) = row?;
+            names.insert(id, name);
+        }
+    }
+
+    let scopes = space_scopes(conn)?;
+    let mut backends: BTreeMap<String, (u64, u64)> =
+        names.keys().map(|id| (id.clone(), (0, 0))).collect();
+    let mut spaces: BTreeMap<(String, Option<String>), (u64, u64)> = BTreeMap::new();
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT backend_id, bucket, key, size FROM \"{USAGE_TABLE}\""
+    ))?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let backend_id: String = row.get(0)?;
+        let bucket: String = row.get(1)?;
+        let key: String = row.get(2)?;
+        let size = row.get::<_, i64>(3)? as u64;
+
+        let totals = backends.entry(backend_id.clone()).or_default();
+        totals.0 += 1;
+        totals.1 += size;
+
+        let space_id = scopes
+            .iter()
+            .find(|s| {
+                s.backend_id == backend_id && s.bucket == bucket && key.starts_with(&s.prefix)
+            })
+            .and_then(|s| s.space_id.clone());
+        let totals = spaces.entry((backend_id, space_id)).or_default();
+        totals.0 += 1;
+        totals.1 += size;
+    }
+
+    Ok(StorageUsageReport {
+        backends: backends
+            .into_iter()
+            .map(|(backend_id, (files, bytes))| BackendUsage {
+                name: names.get(&backend_id).cloned(),
+                quota_bytes: quotas.get(&backend_id).copied(),
+                backend_id,
+                files,
+                bytes,
+            })
+            .collect(),
+        spaces: spaces
+            .into_iter()
+            .map(|((backend_id, space_id), (files, bytes))| SpaceUsage {
+                space_id,
+                backend_id,
+                files,
+                bytes,
+            })
+            .collect(),
+    })
+}
+
+/// Lists the backend's own bucket and every bucket override of a sync rule
+/// pointing at it, and replaces the backend's ledger with the result
+pub async fn rebuild_backend_usage(
+    db: &DbConnection,
+    backend_id: &str,
+) -> Result<(), StorageError> {
+    let references = with_connection(db, |conn| {
+        super::migration::find_rule_references(conn, backend_id)
+    })
+    .map_err(|e| StorageError::DatabaseError {
+        reason: e.to_string(),
+    })?;
+    let mut buckets: Vec<Option<String>> = super::migration::migration_scopes(&references)
+        .into_keys()
+        .collect();
+    if !buckets.contains(&None) {
+        buckets.insert(0, None);
+    }
+
+    with_connection(db, |conn| Ok(forget_backend(conn, backend_id)?)).map_err(|e| {
+        StorageError::DatabaseError {
+            reason: e.to_string(),
+        }
+    })?;
+    for bucket in buckets {
+        let backend = super::commands::get_backend_instance_from_db_with_overrides(
+            db,
+            backend_id,
+            bucket.as_deref(),
+        )
+        .await?;
+        let objects = backend.list(None).await?;
+        let bucket = bucket.unwrap_or_default();
+        with_connection(db, |conn| {
+            Ok(replace_bucket_usage(conn, backend_id, &bucket, &objects)?)
+        })
+        .map_err(|e| StorageError::DatabaseError {
+            reason: e.to_string(),
+        })?;
+    }
+    Ok(())
+}
+
+/// Backend decorator that keeps the usage ledger up to date
+pub struct AccountedBackend {
+    inner: Box<dyn StorageBackend>,
+    db: DbConnection,
+    backend_id: String,
+    bucket: String,
+}
+
+impl AccountedBackend {
+    pub fn wrap(
+        inner: Box<dyn StorageBackend>,
+        db: &DbConnection,
+        backend_id: &str,
+        bucket_override: Option<&str>,
+    ) -> Box<dyn StorageBackend> {
+        Box::new(Self {
+            inner,
+            db: DbConnection(db.0.clone()),
+            backend_id: backend_id.to_string(),
+            bucket: bucket_override.unwrap_or("").to_string(),
+        })
+    }
+
+    fn check_quota(&self, key: &str, size: u64) -> Result<(), StorageError> {
+        match with_connection(&self.db, |conn| {
+            Ok(check_quota(conn, &self.backend_id, &self.bucket, key, size))
+        }) {
+            Ok(result) => result,
+            // Without a readable ledger there is nothing to enforce
+            Err(e) => {
+                eprintln!(
+                    "[RemoteStorage] Quota check for {} skipped: {}",
+                    self.backend_id, e
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// The object is already stored, so a failing ledger write is only
+    /// logged; a rebuild corrects it.
+    fn record(&self, key: &str, size: Option<u64>) {
+        let result = with_connection(&self.db, |conn| {
+            match size {
+                Some(size) => record_upload(conn, &self.backend_id, &self.bucket, key, size)?,
+                None => record_delete(conn, &self.backend_id, &self.bucket, key)?,
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            eprintln!(
+                "[RemoteStorage] Usage accounting for {}/{} failed: {}",
+                self.backend_id, key, e
+            );
+        }
+    }
+
+    async fn file_size(source_path: &Path) -> Result<u64, StorageError> {
+        tokio::fs::metadata(source_path)
+            .await
+            .map(|m| m.len())
+            .map_err(|e| StorageError::UploadFailed {
+                reason: format!("read source: {}", e),
+            })
+    }
+}
+
+#[async_trait]
+impl StorageBackend for AccountedBackend {
+    fn backend_type(&self) -> &'static str {
+        self.inner.backend_type()
+    }
+
+    async fn test_connection(&self) -> Result<(), StorageError> {
+        self.inner.test_connection().await
+    }
+
+    async fn ensure_container(&self) -> Result<(), StorageError> {
+        self.inner.ensure_container().await
+    }
+
+    async fn upload(&self, key: &str, data: &[u8]) -> Result<(), StorageError> {
+        self.check_quota(key, data.len() as u64)?;
+        self.inner.upload(key, data).await?;
+        self.record(key, Some(data.len() as u64));
+        Ok(())
+    }
+
+    async fn download(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        self.inner.download(key).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        self.inner.delete(key).await?;
+        self.record(key, None);
+        Ok(())
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StorageError> {
+        self.inner.exists(key).await
+    }
+
+    async fn list(&self, prefix: Option<&str>) -> Result<Vec<StorageObjectInfo>, StorageError> {
+        self.inner.list(prefix).await
+    }
+
+    async fn list_dir(&self, prefix: Option<&str>) -> Result<StorageListDirResponse, StorageError> {
+        self.inner.list_dir(prefix).await
+    }
+
+    async fn upload_from_path(
+        &self,
+        key: &str,
+        source_path: &Path,
+        on_progress: Option<ProgressCallback>,
+    ) -> Result<u64, StorageError> {
+        self.check_quota(key, Self::file_size(source_path).await?)?;
+        let size = self
+            .inner
+            .upload_from_path(key, source_path, on_progress)
+            .await?;
+        self.record(key, Some(size));
+        Ok(size)
+    }
+
+    async fn upload_from_path_cancellable(
+        &self,
+        key: &str,
+        source_path: &Path,
+        on_progress: Option<ProgressCallback>,
+        cancel_token: Option<tokio_util::sync::CancellationToken>,
+    ) -> Result<u64, StorageError> {
+        self.check_quota(key, Self::file_size(source_path).await?)?;
+        let size = self
+            .inner
+            .upload_from_path_cancellable(key, source_path, on_progress, cancel_token)
+            .await?;
+        self.record(key, Some(size));
+        Ok(size)
+    }
+
+    async fn download_to_path(
+        &self,
+        key: &str,
+        output_path: &Path,
+        on_progress: Option<ProgressCallback>,
+    ) -> Result<u64, StorageError> {
+        self.inner
+            .download_to_path(key, output_path, on_progress)
+            .await
+    }
+
+    async fn download_to_path_resumable(
+        &self,
+        key: &str,
+        output_path: &Path,
+        on_progress: Option<ProgressCallback>,
+    ) -> Result<u64, StorageError> {
+        self.inner
+            .download_to_path_resumable(key, output_path, on_progress)
+            .await
+    }
+}
@@ -7,7 +7,6 @@ use base64::Engine;
 
 use crate::database::{core, core::select_with_crdt};
 use crate::database::DbConnection;
-use uuid::Uuid;
 
 use super::error::DeliveryError;
 
@@ -105,7 +104,7 @@ pub fn store_key_package(
     target_did: &str,
     package_blob: &[u8],
 ) -> Result<String, DeliveryError> {
-    let id = Uuid::new_v4().to_string();
+    let id = crate::ids::new_uuid().to_string();
     let blob_b64 = base64::engine::general_purpose::STANDARD.encode(package_blob);
     core::execute(
         "INSERT INTO haex_local_delivery_key_packages_no_sync (id, space_id, target_did, package_blob) VALUES (?1, ?2, ?3, ?4)".to_string(),
@@ -215,7 +214,7 @@ pub fn store_welcome(
     recipient_did: &str,
     welcome_blob: &[u8],
 ) -> Result<String, DeliveryError> {
-    let id = Uuid::new_v4().to_string();
+    let id = crate::ids::new_uuid().to_string();
     let blob_b64 = base64::engine::general_purpose::STANDARD.encode(welcome_blob);
     core::execute(
         "INSERT INTO haex_local_delivery_welcomes_no_sync (id, space_id, recipient_did, welcome_blob) VALUES (?1, ?2, ?3, ?4)".to_string(),
@@ -281,7 +280,7 @@ pub fn store_pending_commit(
     message_id: i64,
     expected_dids: &[String],
 ) -> Result<String, DeliveryError> {
-    let id = Uuid::new_v4().to_string();
+    let id = crate::ids::new_uuid().to_string();
     let expected_json = serde_json::to_string(expected_dids).unwrap_or_else(|_| "[]".to_string());
     core::execute(
         "INSERT INTO haex_local_delivery_pending_commits_no_sync \
@@ -8,7 +8,7 @@ use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use time::OffsetDateTime;
 use tokio::sync::RwLock;
 
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Manager};
 
 use crate::crdt::commands::{apply_remote_changes_to_db, RemoteColumnChange};
 use crate::crdt::hlc::HlcService;
@@ -16,6 +16,7 @@ use crate::crdt::scanner::{scan_space_scoped_tables_for_local_changes, LocalColu
 use crate::critical::CriticalFailureCode;
 use crate::ucan::{require_audience, require_capability, validate_token, CapabilityLevel, ValidatedUcan};
 use crate::database::DbConnection;
+use crate::events::payloads::LocalSyncCompleted;
 use super::buffer;
 use super::error::DeliveryError;
 use super::invite_tokens::{self, LocalInviteToken};
@@ -455,7 +456,7 @@ pub async fn handle_claim_invite(
             (id, did, name, source) VALUES (?1, ?2, ?3, 'contact')"
             .to_string();
         let ensure_identity_params = vec![
-            JsonValue::String(uuid::Uuid::new_v4().to_string()),
+            JsonValue::String(crate::ids::new_uuid().to_string()),
             JsonValue::String(did.clone()),
             JsonValue::String(resolved_label),
         ];
@@ -475,7 +476,7 @@ pub async fn handle_claim_invite(
             SELECT ?1, ?2, id, ?3, ?4 FROM haex_identities WHERE did = ?5"
             .to_string();
         let member_params = vec![
-            JsonValue::String(uuid::Uuid::new_v4().to_string()),
+            JsonValue::String(crate::ids::new_uuid().to_string()),
             JsonValue::String(space_id.clone()),
             JsonValue::String(capability.clone()),
             JsonValue::String(now),
@@ -592,7 +593,7 @@ fn persist_admin_ucan(
         Err(_) => return,
     };
 
-    let ucan_id = uuid::Uuid::new_v4().to_string();
+    let ucan_id = crate::ids::new_uuid().to_string();
     let now_secs = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
@@ -1072,14 +1073,13 @@ pub(super) async fn handle_delivery_request(
 
             // Notify the leader's own frontend so UI stores (file browser peer
             // list, space devices) reload without waiting for the next cloud pull.
-            // emit_to(label, …) keeps the event out of extension webviews.
-            let _ = state.app_handle.emit_to(
-                "main",
-                "local-sync-completed",
-                serde_json::json!({
-                    "spaceId": &space_id,
-                    "tables": &affected_tables,
-                }),
+            // emit_to_main keeps the event out of extension webviews.
+            let _ = crate::events::emit_to_main(
+                &state.app_handle,
+                &LocalSyncCompleted {
+                    space_id: space_id.clone(),
+                    tables: affected_tables,
+                },
             );
 
             Response::Ok
@@ -5,6 +5,8 @@
 //! `(key, device_id)` unique index). The cursor is the max HLC string of
 //! the last successfully pushed chunk; the next sync-loop session resumes
 //! from there instead of re-scanning every space-scoped row from t=0.
+//! Saving it also advances the space leader's row in
+//! `haex_crdt_sync_status_no_sync` (see `crdt::sync_status`).
 //!
 //! Without this cursor, every reconnect re-scans the whole local DB and
 //! tries to push every row again — including rows that were just pulled
@@ -14,6 +16,7 @@
 use crate::database::constants::vault_settings_key::{
     LOCAL_SYNC_MLS_CURSOR_PREFIX, LOCAL_SYNC_PUSH_HLC_PREFIX,
 };
+use crate::crdt::sync_status::{advance_push_cursor, local_space_peer_id};
 use crate::database::core::with_connection;
 use crate::database::error::DatabaseError;
 use crate::database::DbConnection;
@@ -62,7 +65,7 @@ pub fn save_last_push_hlc(
     hlc: &str,
 ) {
     let key = cursor_key(space_id);
-    let row_id = uuid::Uuid::new_v4().to_string();
+    let row_id = crate::ids::new_uuid().to_string();
 
     let result: Result<(), DatabaseError> = with_connection(db, |conn| {
         conn.execute(
@@ -71,8 +74,8 @@ pub fn save_last_push_hlc(
              ON CONFLICT(key, device_id) DO UPDATE SET value = excluded.value",
             rusqlite::params![row_id, key, hlc, device_id],
         )
-        .map(|_| ())
-        .map_err(DatabaseError::from)
+        .map_err(DatabaseError::from)?;
+        advance_push_cursor(conn, &local_space_peer_id(space_id), hlc)
     });
 
     if let Err(e) = result {
@@ -126,7 +129,7 @@ pub fn save_last_mls_cursor(
     message_id: i64,
 ) {
     let key = mls_cursor_key(space_id);
-    let row_id = uuid::Uuid::new_v4().to_string();
+    let row_id = crate::ids::new_uuid().to_string();
     let value = message_id.to_string();
 
     let result: Result<(), DatabaseError> = with_connection(db, |conn| {
@@ -17,7 +17,11 @@ use crate::crdt::scanner::{
     scan_membership_tables_for_local_changes, scan_space_scoped_tables_for_local_changes,
     LocalColumnChange,
 };
+use crate::crdt::sync_status::{advance_pull_cursor, local_space_peer_id, record_error};
+use crate::database::core::with_connection;
 use crate::database::DbConnection;
+use crate::events::payloads::{LocalSyncCompleted, LocalSyncError};
+use crate::metrics;
 use super::error::DeliveryError;
 use super::peer::PeerSession;
 use super::push_cursor::{
@@ -45,7 +49,7 @@ const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(60);
 /// `PUSH_CHUNK_SOFT_LIMIT` — see `src/stores/sync/orchestrator/push.ts`.
 /// A single transaction-HLC group larger than this is still sent in one
 /// request rather than split.
-const PUSH_CHUNK_SOFT_LIMIT: usize = 2000;
+pub(crate) const PUSH_CHUNK_SOFT_LIMIT: usize = 2000;
 
 /// Splits an HLC-sorted slice of local changes into HLC-aligned chunks.
 ///
@@ -53,7 +57,7 @@ const PUSH_CHUNK_SOFT_LIMIT: usize = 2000;
 /// - Input must be sorted by hlc_timestamp ascending.
 /// - An HLC group is never split between chunks.
 /// - A group larger than `soft_limit` becomes its own oversized chunk.
-fn chunk_changes_by_hlc(
+pub(crate) fn chunk_changes_by_hlc(
     changes: &[LocalColumnChange],
     soft_limit: usize,
 ) -> Vec<&[LocalColumnChange]> {
@@ -334,7 +338,7 @@ async fn run_sync_loop(
             break;
         }
 
-        match run_sync_cycle(
+        let cycle = run_sync_cycle(
             &db,
             &session,
             &space_id,
@@ -348,9 +352,8 @@ async fn run_sync_loop(
             &mut last_pull_timestamp,
             &mut last_mls_message_id,
             &mut key_packages_refilled,
-        )
-        .await
-        {
+        );
+        match metrics::time_sync(metrics::SYNC_LOCAL_DELIVERY, cycle).await {
             Ok(()) => {
                 // Cycle completed successfully, wait for next cycle, an
                 // external wake-up (force_sync), or a stop signal.
@@ -392,16 +395,15 @@ async fn run_sync_loop(
                     // Emit error event for frontend (main window only).
                     // Tauri v2 emit() broadcasts to every webview — extensions
                     // must not learn about p2p sync state for other spaces.
-                    let _ = app_handle.emit_to(
-                        "main",
-                        "local-sync-error",
-                        serde_json::json!({
-                            "spaceId": space_id,
-                            "error": e.to_string(),
-                            "reconnecting": true,
-                            "endpointClosed": endpoint_closed_now,
-                            "attempt": reconnect_attempt,
-                        }),
+                    let _ = crate::events::emit_to_main(
+                        &app_handle,
+                        &LocalSyncError {
+                            space_id: space_id.clone(),
+                            error: e.to_string(),
+                            reconnecting: true,
+                            endpoint_closed: endpoint_closed_now,
+                            attempt: reconnect_attempt,
+                        },
                     );
 
                     // Wait for backoff duration or stop signal
@@ -619,6 +621,9 @@ async fn run_sync_cycle(
     // 1. PUSH (best-effort) — never blocks the pull below.
     if let Err(e) = run_push_phase(db, session, space_id, device_id, our_node, can_push_user_content, our_identity_id, our_endpoint_id, last_push_hlc).await {
         eprintln!("[SyncLoop] Push phase failed (pull continues): {}", e);
+        let _ = with_connection(db, |conn| {
+            record_error(conn, &local_space_peer_id(space_id), &e.to_string())
+        });
     }
 
     // 2. PULL: Get changes from leader
@@ -704,17 +709,22 @@ async fn run_sync_cycle(
 
                 // Update last_pull_timestamp
                 if !max_pulled_hlc.is_empty() {
+                    let recorded = with_connection(db, |conn| {
+                        advance_pull_cursor(conn, &local_space_peer_id(space_id), &max_pulled_hlc)
+                    });
+                    if let Err(e) = recorded {
+                        eprintln!("[SyncLoop] Warning: failed to record pull cursor: {}", e);
+                    }
                     *last_pull_timestamp = Some(max_pulled_hlc);
                 }
 
                 // Emit Tauri event for frontend UI refresh (main window only).
-                let _ = app_handle.emit_to(
-                    "main",
-                    "local-sync-completed",
-                    serde_json::json!({
-                        "spaceId": space_id,
-                        "tables": affected_tables,
-                    }),
+                let _ = crate::events::emit_to_main(
+                    app_handle,
+                    &LocalSyncCompleted {
+                        space_id: space_id.to_string(),
+                        tables: affected_tables,
+                    },
                 );
             }
         }
@@ -1059,7 +1069,7 @@ fn extract_pk_id(row_pks: &str) -> Option<String> {
 ///
 /// This matches the format used by CRDT dirty table triggers so that the
 /// `last_modified <= ?` comparison works correctly.
-fn sqlite_datetime_now() -> String {
+pub(crate) fn sqlite_datetime_now() -> String {
     let now = time::OffsetDateTime::now_utc();
     format!(
         "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
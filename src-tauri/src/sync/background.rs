@@ -0,0 +1,304 @@
+//! Background sync on mobile.
+//!
+//! Mobile systems suspend the app shortly after it leaves the foreground,
+//! and with it the sync orchestrators and FileSync loops. On Android a
+//! periodic WorkManager job (`BackgroundSyncWorker`, copied from `.github/`
+//! into the generated Android project at build time) wakes the app within
+//! the constraints configured here; the native `BackgroundSyncPlugin`
+//! forwards each wake-up to the frontend as its `wake` event, and the
+//! frontend calls [`background_sync_run`] to run every orchestrator and
+//! FileSync rule right away. The worker keeps the process alive for a short
+//! budget while they do.
+//!
+//! A wake-up never starts anything that wasn't running: the vault is
+//! encrypted, so a process the OS had killed comes back without an open
+//! vault and the pass does nothing.
+//!
+//! iOS needs a Swift BGTask plugin in the generated Xcode project, which is
+//! not part of this repository yet; there, like on desktop, the settings are
+//! stored but nothing is scheduled (`scheduled: false`).
+//!
+//! Settings are per device and live in haex_crdt_configs.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use ts_rs::TS;
+
+use super::error::SyncError;
+use crate::database::constants::vault_settings_key;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, TABLE_CRDT_CONFIGS,
+};
+use crate::AppState;
+
+/// WorkManager doesn't run periodic work more often than every 15 minutes.
+pub const MIN_INTERVAL_MINUTES: u32 = 15;
+
+/// Longest accepted interval (one day).
+pub const MAX_INTERVAL_MINUTES: u32 = 24 * 60;
+
+/// When and under which conditions the app is woken up to sync.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundSyncSettings {
+    pub enabled: bool,
+    /// Minutes between wake-ups; the OS may stretch it
+    pub interval_minutes: u32,
+    /// Only sync on unmetered networks (Wi-Fi)
+    pub unmetered_only: bool,
+    pub requires_charging: bool,
+    /// Skip wake-ups while the battery is low
+    pub requires_battery_not_low: bool,
+}
+
+impl Default for BackgroundSyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 60,
+            unmetered_only: true,
+            requires_charging: false,
+            requires_battery_not_low: true,
+        }
+    }
+}
+
+impl BackgroundSyncSettings {
+    /// Clamps the interval into what the schedulers accept.
+    pub fn normalized(mut self) -> Self {
+        self.interval_minutes = self
+            .interval_minutes
+            .clamp(MIN_INTERVAL_MINUTES, MAX_INTERVAL_MINUTES);
+        self
+    }
+}
+
+/// Result of [`background_sync_set_settings`]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundSyncStatus {
+    pub settings: BackgroundSyncSettings,
+    /// A periodic wake-up is scheduled with the OS
+    pub scheduled: bool,
+}
+
+/// What a background pass woke up
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundSyncReport {
+    /// Backend ids of the orchestrators that run a cycle now
+    pub orchestrators: Vec<String>,
+    /// FileSync rules that sync now
+    pub file_sync_rules: Vec<String>,
+}
+
+pub(super) fn read_config(conn: &Connection, key: &str) -> Result<Option<String>, DatabaseError> {
+    Ok(conn
+        .query_row(
+            &format!(
+                "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"
+            ),
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+pub(super) fn write_config(conn: &Connection, key: &str, value: &str) -> Result<(), DatabaseError> {
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?, ?, ?)"
+        ),
+        params![key, "system", value],
+    )?;
+    Ok(())
+}
+
+/// The stored settings, or the defaults if none were saved.
+pub fn load_settings(conn: &Connection) -> Result<BackgroundSyncSettings, DatabaseError> {
+    match read_config(conn, vault_settings_key::BACKGROUND_SYNC)? {
+        None => Ok(BackgroundSyncSettings::default()),
+        Some(json) => serde_json::from_str(&json).map_err(|e| DatabaseError::SerializationError {
+            reason: format!("Invalid background sync settings: {e}"),
+        }),
+    }
+}
+
+pub fn save_settings(
+    conn: &Connection,
+    settings: &BackgroundSyncSettings,
+) -> Result<(), DatabaseError> {
+    let json = serde_json::to_string(settings).map_err(|e| DatabaseError::SerializationError {
+        reason: e.to_string(),
+    })?;
+    write_config(conn, vault_settings_key::BACKGROUND_SYNC, &json)
+}
+
+/// Starts the next cycle of every running orchestrator and FileSync rule.
+pub async fn wake_all(state: &AppState) -> BackgroundSyncReport {
+    let mut orchestrators: Vec<String> = state
+        .sync_orchestrators
+        .lock()
+        .await
+        .iter()
+        .filter(|(_, handle)| !handle.is_finished())
+        .map(|(id, handle)| {
+            handle.wakeup();
+            id.clone()
+        })
+        .collect();
+    orchestrators.sort();
+
+    let manager = state.sync_manager.lock().await;
+    let mut file_sync_rules = manager.running_rule_ids();
+    file_sync_rules.sort();
+    for rule_id in &file_sync_rules {
+        manager.trigger(rule_id).await;
+    }
+
+    BackgroundSyncReport {
+        orchestrators,
+        file_sync_rules,
+    }
+}
+
+// ============================================================================
+// Android scheduler plugin
+// ============================================================================
+
+/// Name of the plugin the frontend listens to for `wake` events.
+#[cfg(target_os = "android")]
+pub const PLUGIN_NAME: &str = "background-sync";
+
+/// Handle to the native `BackgroundSyncPlugin`.
+#[cfg(target_os = "android")]
+pub struct BackgroundScheduler<R: tauri::Runtime>(tauri::plugin::PluginHandle<R>);
+
+#[cfg(target_os = "android")]
+impl<R: tauri::Runtime> BackgroundScheduler<R> {
+    /// Replaces the periodic job with one for `settings`, or cancels it when
+    /// background sync is disabled. Returns whether a job is scheduled.
+    pub fn apply(&self, settings: &BackgroundSyncSettings) -> Result<bool, SyncError> {
+        let command = if settings.enabled {
+            "schedule"
+        } else {
+            "cancel"
+        };
+        self.0
+            .run_mobile_plugin::<serde_json::Value>(command, settings.clone())
+            .map_err(|e| SyncError::InvalidConfig {
+                reason: format!("Background scheduler failed: {e}"),
+            })?;
+        Ok(settings.enabled)
+    }
+}
+
+/// Registers the native scheduler (`space.haex.vault.BackgroundSyncPlugin`).
+#[cfg(target_os = "android")]
+pub fn init<R: tauri::Runtime>() -> tauri::plugin::TauriPlugin<R> {
+    use tauri::Manager;
+
+    tauri::plugin::Builder::new(PLUGIN_NAME)
+        .setup(|app, api| {
+            let handle = api.register_android_plugin("space.haex.vault", "BackgroundSyncPlugin")?;
+            app.manage(BackgroundScheduler(handle));
+            Ok(())
+        })
+        .build()
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn background_sync_get_settings(
+    state: State<'_, AppState>,
+) -> Result<BackgroundSyncSettings, SyncError> {
+    Ok(with_connection(&state.db, |conn| load_settings(conn))?)
+}
+
+/// Saves the settings and (re)schedules or cancels the OS job to match.
+#[tauri::command]
+pub fn background_sync_set_settings(
+    #[allow(unused_variables)] app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    settings: BackgroundSyncSettings,
+) -> Result<BackgroundSyncStatus, SyncError> {
+    let settings = settings.normalized();
+    with_connection(&state.db, |conn| save_settings(conn, &settings))?;
+
+    #[cfg(target_os = "android")]
+    let scheduled = {
+        use tauri::Manager;
+        match app_handle.try_state::<BackgroundScheduler<tauri::Wry>>() {
+            Some(scheduler) => scheduler.apply(&settings)?,
+            None => false,
+        }
+    };
+    #[cfg(not(target_os = "android"))]
+    let scheduled = false;
+
+    Ok(BackgroundSyncStatus {
+        settings,
+        scheduled,
+    })
+}
+
+/// Runs every running orchestrator and FileSync rule now. Called by the
+/// frontend when the background scheduler woke the app.
+#[tauri::command]
+pub async fn background_sync_run(
+    state: State<'_, AppState>,
+) -> Result<BackgroundSyncReport, SyncError> {
+    Ok(wake_all(&state).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crdt::hlc::HlcService;
+    use crate::test_support::crdt::open_crdt_connection;
+
+    fn setup() -> Connection {
+        let conn = open_crdt_connection(HlcService::new_for_testing("background-sync-test-device"))
+            .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let conn = setup();
+        assert_eq!(
+            load_settings(&conn).unwrap(),
+            BackgroundSyncSettings::default()
+        );
+
+        let settings = BackgroundSyncSettings {
+            enabled: true,
+            interval_minutes: 30,
+            unmetered_only: false,
+            requires_charging: true,
+            requires_battery_not_low: false,
+        };
+        save_settings(&conn, &settings).unwrap();
+        assert_eq!(load_settings(&conn).unwrap(), settings);
+    }
+
+    #[test]
+    fn test_interval_is_clamped() {
+        let settings = |interval_minutes| BackgroundSyncSettings {
+            interval_minutes,
+            ..Default::default()
+        };
+        assert_eq!(settings(1).normalized().interval_minutes, 15);
+        assert_eq!(settings(90).normalized().interval_minutes, 90);
+        assert_eq!(settings(100_000).normalized().interval_minutes, 24 * 60);
+    }
+}
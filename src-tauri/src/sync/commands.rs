@@ -0,0 +1,632 @@
+//! Tauri commands to run the sync orchestrator against a storage backend,
+//! and to manage read-only shares with other vaults.
+
+use std::time::Duration;
+
+use tauri::State;
+
+use super::envelope::SyncKey;
+use super::error::SyncError;
+use super::messaging::{self, MessagingTransport, MessagingTransportInfo};
+use super::orchestrator::{self, SyncMode, SyncScope, SyncSession, DEFAULT_INTERVAL};
+use super::roles::{self, ShareRole, ShareRoleAssignment};
+use super::share::{
+    self, CreateShareRequest, Share, ShareCreated, ShareDirection, ShareInvite, ShareMember,
+    ShareStatus,
+};
+use super::transport::{StorageTransport, SyncTransport, BATCH_PREFIX};
+use crate::crdt::hlc::{device_uuid_to_hlc_node, parse_hlc_node_hex, HlcService};
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::database::DbConnection;
+use crate::remote_storage::commands::get_backend_instance_from_db_with_overrides;
+use crate::space_delivery::local::quic_retry::load_signing_identity_for_did;
+use crate::AppState;
+
+/// Builds a session for this device against `transport`.
+fn new_session(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    key: SyncKey,
+    transport: Box<dyn SyncTransport>,
+    mode: SyncMode,
+    scope: SyncScope,
+) -> Result<SyncSession, SyncError> {
+    let device_id =
+        HlcService::get_or_create_device_id(app_handle).map_err(|e| SyncError::InvalidConfig {
+            reason: format!("Failed to read device id: {e}"),
+        })?;
+    let hlc = lock_hlc(state, "sync::commands::new_session")?;
+
+    Ok(SyncSession {
+        db: DbConnection(state.db.0.clone()),
+        hlc,
+        origin_node: device_uuid_to_hlc_node(&device_id),
+        device_id,
+        key,
+        transport,
+        mode,
+        scope,
+    })
+}
+
+/// Starts `session` under `id`, stopping the orchestrator it replaces.
+async fn replace_orchestrator(
+    app_handle: tauri::AppHandle,
+    state: &AppState,
+    id: String,
+    session: SyncSession,
+    interval: Duration,
+) {
+    let mut orchestrators = state.sync_orchestrators.lock().await;
+    if let Some(previous) = orchestrators.remove(&id) {
+        previous.stop();
+    }
+    orchestrators.insert(
+        id,
+        orchestrator::start_orchestrator(app_handle, session, interval),
+    );
+}
+
+/// The vault's own sync transport for `id`: a messaging transport if one is
+/// configured with that id, the storage backend `id` otherwise.
+async fn open_transport(state: &AppState, id: &str) -> Result<Box<dyn SyncTransport>, SyncError> {
+    let configured = with_connection(&state.db, |conn| messaging::find_transport(conn, id))?;
+    if let Some(transport) = configured {
+        return transport.open();
+    }
+    let backend = get_backend_instance_from_db_with_overrides(&state.db, id, None).await?;
+    Ok(Box::new(StorageTransport::new(
+        id.to_string(),
+        BATCH_PREFIX.to_string(),
+        backend,
+    )))
+}
+
+/// Starts background sync with the storage backend or messaging transport
+/// `backend_id`, replacing a running orchestrator for it.
+///
+/// `sync_key` is the base64 sync secret (at least 32 bytes) shared by all
+/// devices of the vault; batches are sealed with a key derived from it.
+/// `interval_secs` defaults to 60 and is raised to at least 5.
+#[tauri::command]
+pub async fn sync_orchestrator_start(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    backend_id: String,
+    sync_key: String,
+    interval_secs: Option<u64>,
+) -> Result<(), SyncError> {
+    let key = SyncKey::from_base64(&sync_key)?;
+    let transport = open_transport(&state, &backend_id).await?;
+    let session = new_session(
+        &app_handle,
+        &state,
+        key,
+        transport,
+        SyncMode::Full,
+        SyncScope::default(),
+    )?;
+    let interval = interval_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_INTERVAL);
+
+    replace_orchestrator(app_handle, &state, backend_id, session, interval).await;
+    Ok(())
+}
+
+/// Stops background sync with `backend_id`. No-op if it isn't running.
+#[tauri::command]
+pub async fn sync_orchestrator_stop(
+    state: State<'_, AppState>,
+    backend_id: String,
+) -> Result<(), SyncError> {
+    if let Some(handle) = state.sync_orchestrators.lock().await.remove(&backend_id) {
+        handle.stop();
+    }
+    Ok(())
+}
+
+/// Runs the next cycle for `backend_id` now.
+#[tauri::command]
+pub async fn sync_orchestrator_trigger(
+    state: State<'_, AppState>,
+    backend_id: String,
+) -> Result<(), SyncError> {
+    match state.sync_orchestrators.lock().await.get(&backend_id) {
+        Some(handle) if !handle.is_finished() => {
+            handle.wakeup();
+            Ok(())
+        }
+        _ => Err(SyncError::InvalidConfig {
+            reason: format!("No sync running for backend {backend_id}"),
+        }),
+    }
+}
+
+/// Backend ids with a running orchestrator.
+#[tauri::command]
+pub async fn sync_orchestrator_list(state: State<'_, AppState>) -> Result<Vec<String>, SyncError> {
+    let orchestrators = state.sync_orchestrators.lock().await;
+    let mut ids: Vec<String> = orchestrators
+        .iter()
+        .filter(|(_, handle)| !handle.is_finished())
+        .map(|(id, _)| id.clone())
+        .collect();
+    ids.sort();
+    Ok(ids)
+}
+
+/// Configured Matrix/Nostr transports, without credentials.
+#[tauri::command]
+pub fn sync_messaging_list(
+    state: State<'_, AppState>,
+) -> Result<Vec<MessagingTransportInfo>, SyncError> {
+    let transports = with_connection(&state.db, |conn| messaging::load_transports(conn))?;
+    Ok(transports.iter().map(MessagingTransport::info).collect())
+}
+
+/// Adds a messaging transport (empty `id`) or replaces the one with `id`.
+/// A Nostr transport saved without a secret key gets a new one; copy it to
+/// the other devices with `sync_messaging_export_key`.
+#[tauri::command]
+pub fn sync_messaging_save(
+    state: State<'_, AppState>,
+    mut transport: MessagingTransport,
+) -> Result<MessagingTransportInfo, SyncError> {
+    if transport.id.is_empty() {
+        transport.id = crate::ids::new_uuid().to_string();
+    }
+    let transport = transport.normalized()?;
+    with_connection(&state.db, |conn| {
+        let mut transports = messaging::load_transports(conn)?;
+        transports.retain(|t| t.id != transport.id);
+        transports.push(transport.clone());
+        messaging::save_transports(conn, &transports)
+    })?;
+    Ok(transport.info())
+}
+
+/// Removes a messaging transport and stops its sync.
+#[tauri::command]
+pub async fn sync_messaging_delete(
+    state: State<'_, AppState>,
+    transport_id: String,
+) -> Result<(), SyncError> {
+    let removed = with_connection(&state.db, |conn| {
+        let mut transports = messaging::load_transports(conn)?;
+        let before = transports.len();
+        transports.retain(|t| t.id != transport_id);
+        messaging::save_transports(conn, &transports)?;
+        Ok(transports.len() < before)
+    })?;
+    if !removed {
+        return Err(SyncError::InvalidConfig {
+            reason: format!("Unknown messaging transport {transport_id}"),
+        });
+    }
+    if let Some(handle) = state.sync_orchestrators.lock().await.remove(&transport_id) {
+        handle.stop();
+    }
+    Ok(())
+}
+
+/// The hex secret key of a Nostr transport, to set up the vault's other
+/// devices with the same key.
+#[tauri::command]
+pub fn sync_messaging_export_key(
+    state: State<'_, AppState>,
+    transport_id: String,
+) -> Result<String, SyncError> {
+    let transport = with_connection(&state.db, |conn| {
+        messaging::find_transport(conn, &transport_id)
+    })?;
+    match transport.map(|t| t.config) {
+        Some(messaging::MessagingConfig::Nostr {
+            secret_key: Some(key),
+            ..
+        }) => Ok(key),
+        _ => Err(SyncError::InvalidConfig {
+            reason: format!("{transport_id} is not a Nostr transport"),
+        }),
+    }
+}
+
+fn share_identity_error(e: impl std::fmt::Display) -> SyncError {
+    SyncError::Share {
+        reason: e.to_string(),
+    }
+}
+
+/// Gives this device of the share owner an editor role, so recipients accept
+/// what it publishes.
+fn ensure_owner_role(
+    state: &AppState,
+    share: &Share,
+    session: &SyncSession,
+) -> Result<(), SyncError> {
+    let Some(node) = session.origin_node else {
+        return Err(SyncError::Share {
+            reason: "This device has no HLC node to publish under".to_string(),
+        });
+    };
+    let existing = with_connection(&state.db, |conn| Ok(roles::list_roles(conn, share)))??;
+    let device_node = roles::format_device_node(node);
+    if existing.iter().any(|r| {
+        r.did == share.owner_did && r.device_node == device_node && r.role == ShareRole::Editor
+    }) {
+        return Ok(());
+    }
+
+    let owner =
+        load_signing_identity_for_did(&state.db, &share.owner_did).map_err(share_identity_error)?;
+    let assignment = roles::sign_role(
+        &share.id,
+        &share.owner_did,
+        node,
+        ShareRole::Editor,
+        &owner.signing_key,
+    );
+    with_connection(&state.db, |conn| {
+        let tx = conn.transaction()?;
+        if let Err(e) = roles::upsert_role(&tx, &session.hlc, &assignment) {
+            return Ok(Err(e));
+        }
+        tx.commit()?;
+        Ok(Ok(()))
+    })?
+}
+
+/// Starts the orchestrator of an active share. Readers only pull, the
+/// owner only publishes until someone else may edit, editors sync both
+/// ways (see [`roles::share_sync_mode`]).
+async fn start_share_sync(
+    app_handle: tauri::AppHandle,
+    state: &AppState,
+    share: &Share,
+    share_key: &[u8; 32],
+) -> Result<(), SyncError> {
+    let backend =
+        get_backend_instance_from_db_with_overrides(&state.db, &share.backend_id, None).await?;
+    let transport = Box::new(StorageTransport::new(
+        share::share_peer_id(&share.id, share.key_generation),
+        share::share_prefix(&share.id, share.key_generation),
+        backend,
+    ));
+    let mut session = new_session(
+        &app_handle,
+        state,
+        SyncKey::derive(share_key)?,
+        transport,
+        SyncMode::ConsumeOnly,
+        share.scope(),
+    )?;
+    if share.direction == ShareDirection::Outgoing {
+        ensure_owner_role(state, share, &session)?;
+    }
+    session.mode = with_connection(&state.db, |conn| {
+        Ok(roles::share_sync_mode(conn, share, session.origin_node))
+    })??;
+
+    replace_orchestrator(
+        app_handle,
+        state,
+        share::share_orchestrator_id(&share.id),
+        session,
+        DEFAULT_INTERVAL,
+    )
+    .await;
+    Ok(())
+}
+
+async fn stop_share_sync(state: &AppState, share_id: &str) {
+    let id = share::share_orchestrator_id(share_id);
+    if let Some(handle) = state.sync_orchestrators.lock().await.remove(&id) {
+        handle.stop();
+    }
+}
+
+/// Deletes the share's objects under `prefix`. Best effort: a failure only
+/// leaves unreadable batches behind.
+async fn delete_share_objects(state: &AppState, backend_id: &str, prefix: String) {
+    let backend =
+        match get_backend_instance_from_db_with_overrides(&state.db, backend_id, None).await {
+            Ok(backend) => backend,
+            Err(e) => {
+                eprintln!("[SHARE] Failed to open backend {backend_id} for cleanup: {e}");
+                return;
+            }
+        };
+    let transport = StorageTransport::new(String::new(), prefix.clone(), backend);
+    if let Err(e) = transport.delete_all().await {
+        eprintln!("[SHARE] Failed to delete {prefix}: {e}");
+    }
+}
+
+fn load_share_or_fail(db: &DbConnection, share_id: &str) -> Result<(Share, [u8; 32]), SyncError> {
+    with_connection(db, |conn| Ok(share::load_share(conn, share_id)))??.ok_or_else(|| {
+        SyncError::Share {
+            reason: format!("Unknown share {share_id}"),
+        }
+    })
+}
+
+fn lock_hlc(state: &AppState, path: &str) -> Result<HlcService, SyncError> {
+    Ok(state
+        .lock_or_fail(
+            &state.hlc,
+            crate::critical::CriticalFailureCode::HlcMutexPoisoned,
+            path,
+            serde_json::json!({}),
+        )
+        .map_err(DatabaseError::from)?
+        .clone())
+}
+
+/// Shares `tables` (optionally only the rows of one space) read-only with
+/// `recipient_dids` and starts publishing them. Returns one invite per
+/// recipient, to be handed over out of band.
+#[tauri::command]
+pub async fn share_create(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    request: CreateShareRequest,
+) -> Result<ShareCreated, SyncError> {
+    let owner = load_signing_identity_for_did(&state.db, &request.owner_did)
+        .map_err(share_identity_error)?;
+    let hlc = lock_hlc(&state, "sync::commands::share_create")?;
+
+    let share = Share {
+        id: crate::ids::new_uuid().to_string(),
+        direction: ShareDirection::Outgoing,
+        name: request.name,
+        tables: request.tables,
+        space_id: request.space_id,
+        owner_did: request.owner_did,
+        backend_id: request.backend_id,
+        key_generation: 1,
+        status: ShareStatus::Active,
+        created_at: None,
+        revoked_at: None,
+        members: request
+            .recipient_dids
+            .into_iter()
+            .map(|did| ShareMember {
+                did,
+                created_at: None,
+                revoked_at: None,
+            })
+            .collect(),
+    };
+    let share_key = share::generate_share_key();
+    let invites = share
+        .members
+        .iter()
+        .map(|member| share::create_invite(&share, &share_key, &member.did, &owner.signing_key))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    with_connection(&state.db, |conn| {
+        let tx = conn.transaction()?;
+        if let Err(e) = share::validate_share_tables(&tx, &share.tables, share.space_id.as_deref())
+            .and_then(|()| share::insert_share(&tx, &hlc, &share, &share_key))
+        {
+            return Ok(Err(e));
+        }
+        tx.commit()?;
+        Ok(Ok(()))
+    })??;
+
+    let (share, share_key) = load_share_or_fail(&state.db, &share.id)?;
+    start_share_sync(app_handle, &state, &share, &share_key).await?;
+    Ok(ShareCreated { share, invites })
+}
+
+/// Accepts an invite addressed to one of this vault's identities and starts
+/// pulling the share from `backend_id`, which must point at the owner's
+/// storage.
+#[tauri::command]
+pub async fn share_accept(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    invite: ShareInvite,
+    backend_id: String,
+) -> Result<Share, SyncError> {
+    let recipient = load_signing_identity_for_did(&state.db, &invite.recipient_did)
+        .map_err(share_identity_error)?;
+    let share_key = share::open_invite(&invite, &recipient.signing_key.to_bytes())?;
+    let hlc = lock_hlc(&state, "sync::commands::share_accept")?;
+
+    let share = with_connection(&state.db, |conn| {
+        let tx = conn.transaction()?;
+        let share = share::accept_invite(&tx, &hlc, &invite, &share_key, &backend_id);
+        if share.is_ok() {
+            tx.commit()?;
+        }
+        Ok(share)
+    })??;
+
+    start_share_sync(app_handle, &state, &share, &share_key).await?;
+    Ok(share)
+}
+
+/// Revokes access to a share.
+///
+/// For an own share and a `did`, only that member loses access: the key is
+/// rotated and the returned invites must reach the remaining members.
+/// Without a `did` the share ends for everyone and its objects are deleted.
+/// For an incoming share, this vault stops consuming it; the rows received
+/// so far stay.
+#[tauri::command]
+pub async fn share_revoke(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    share_id: String,
+    did: Option<String>,
+) -> Result<Vec<ShareInvite>, SyncError> {
+    let (share, _) = load_share_or_fail(&state.db, &share_id)?;
+    let hlc = lock_hlc(&state, "sync::commands::share_revoke")?;
+
+    let Some(did) = did.filter(|_| share.direction == ShareDirection::Outgoing) else {
+        with_connection(&state.db, |conn| {
+            let tx = conn.transaction()?;
+            if let Err(e) = share::mark_share_revoked(&tx, &hlc, &share_id) {
+                return Ok(Err(e));
+            }
+            tx.commit()?;
+            Ok(Ok(()))
+        })??;
+        stop_share_sync(&state, &share_id).await;
+        if share.direction == ShareDirection::Outgoing {
+            delete_share_objects(
+                &state,
+                &share.backend_id,
+                share::share_root_prefix(&share_id),
+            )
+            .await;
+        }
+        return Ok(Vec::new());
+    };
+
+    let owner =
+        load_signing_identity_for_did(&state.db, &share.owner_did).map_err(share_identity_error)?;
+    let new_key = share::generate_share_key();
+    let new_generation = share.key_generation + 1;
+    with_connection(&state.db, |conn| {
+        let tx = conn.transaction()?;
+        let result = share::revoke_member(&tx, &hlc, &share_id, &did).and_then(|revoked| {
+            if !revoked {
+                return Err(SyncError::Share {
+                    reason: format!("{did} is not a member of share {share_id}"),
+                });
+            }
+            share::set_share_key(&tx, &hlc, &share_id, &new_key, new_generation)
+        });
+        if result.is_ok() {
+            tx.commit()?;
+        }
+        Ok(result)
+    })??;
+
+    let (rotated, share_key) = load_share_or_fail(&state.db, &share_id)?;
+    let invites = rotated
+        .members
+        .iter()
+        .filter(|member| member.revoked_at.is_none())
+        .map(|member| share::create_invite(&rotated, &share_key, &member.did, &owner.signing_key))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    start_share_sync(app_handle, &state, &rotated, &share_key).await?;
+    delete_share_objects(
+        &state,
+        &share.backend_id,
+        share::share_prefix(&share_id, share.key_generation),
+    )
+    .await;
+    Ok(invites)
+}
+
+/// All shares of this vault, outgoing and incoming.
+#[tauri::command]
+pub fn share_list(state: State<'_, AppState>) -> Result<Vec<Share>, SyncError> {
+    with_connection(&state.db, |conn| Ok(share::list_shares(conn)))?
+}
+
+/// Starts syncing every active share, e.g. after the vault was opened.
+/// Returns the ids of the shares that failed to start.
+#[tauri::command]
+pub async fn share_start_all(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, SyncError> {
+    let shares = with_connection(&state.db, |conn| Ok(share::list_shares(conn)))??;
+    let mut failed = Vec::new();
+    for share in shares
+        .iter()
+        .filter(|share| share.status == ShareStatus::Active)
+    {
+        let started = match load_share_or_fail(&state.db, &share.id) {
+            Ok((share, key)) => start_share_sync(app_handle.clone(), &state, &share, &key).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = started {
+            eprintln!("[SHARE] Failed to start share {}: {e}", share.id);
+            failed.push(share.id.clone());
+        }
+    }
+    Ok(failed)
+}
+
+/// Assigns `role` to one device of a member of an own share, signed with
+/// the owner's key. `device_node` is the HLC node the member's device
+/// reports via `share_get_device_node`. Restarts the share's sync so the
+/// owner starts pulling once there is an editor.
+#[tauri::command]
+pub async fn share_set_role(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    share_id: String,
+    did: String,
+    device_node: String,
+    role: ShareRole,
+) -> Result<ShareRoleAssignment, SyncError> {
+    let (share, share_key) = load_share_or_fail(&state.db, &share_id)?;
+    if share.direction != ShareDirection::Outgoing || share.status != ShareStatus::Active {
+        return Err(SyncError::Share {
+            reason: "Roles can only be set on an active own share".to_string(),
+        });
+    }
+    let is_member = did == share.owner_did
+        || share
+            .members
+            .iter()
+            .any(|m| m.did == did && m.revoked_at.is_none());
+    if !is_member {
+        return Err(SyncError::Share {
+            reason: format!("{did} is not a member of share {share_id}"),
+        });
+    }
+    let node = parse_hlc_node_hex(&device_node).ok_or_else(|| SyncError::Share {
+        reason: format!("Invalid device node '{device_node}'"),
+    })?;
+
+    let owner =
+        load_signing_identity_for_did(&state.db, &share.owner_did).map_err(share_identity_error)?;
+    let assignment = roles::sign_role(&share_id, &did, node, role, &owner.signing_key);
+    let hlc = lock_hlc(&state, "sync::commands::share_set_role")?;
+    with_connection(&state.db, |conn| {
+        let tx = conn.transaction()?;
+        if let Err(e) = roles::upsert_role(&tx, &hlc, &assignment) {
+            return Ok(Err(e));
+        }
+        tx.commit()?;
+        Ok(Ok(()))
+    })??;
+
+    start_share_sync(app_handle, &state, &share, &share_key).await?;
+    Ok(assignment)
+}
+
+/// Role assignments of a share with a valid owner signature.
+#[tauri::command]
+pub fn share_list_roles(
+    state: State<'_, AppState>,
+    share_id: String,
+) -> Result<Vec<ShareRoleAssignment>, SyncError> {
+    let (share, _) = load_share_or_fail(&state.db, &share_id)?;
+    with_connection(&state.db, |conn| Ok(roles::list_roles(conn, &share)))?
+}
+
+/// This device's HLC node (hex), to be passed to the share owner for
+/// `share_set_role`.
+#[tauri::command]
+pub fn share_get_device_node(app_handle: tauri::AppHandle) -> Result<String, SyncError> {
+    let device_id =
+        HlcService::get_or_create_device_id(&app_handle).map_err(|e| SyncError::InvalidConfig {
+            reason: format!("Failed to read device id: {e}"),
+        })?;
+    device_uuid_to_hlc_node(&device_id)
+        .map(roles::format_device_node)
+        .ok_or_else(|| SyncError::InvalidConfig {
+            reason: format!("Device id {device_id} is not a UUID"),
+        })
+}
@@ -0,0 +1,178 @@
+//! End-to-end encrypted envelope for pushed change batches.
+//!
+//! A batch of local column changes is serialized to JSON and sealed with
+//! AES-256-GCM under a key derived (HKDF-SHA256) from the sync secret the
+//! frontend hands over when it starts the orchestrator. The remote only ever
+//! sees the envelope: the sender's device id and the batch's max HLC in the
+//! clear (needed for listing and cursors), everything else encrypted. Both
+//! clear fields are bound as associated data, so a remote that swaps them
+//! between batches makes decryption fail instead of corrupting cursors.
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use super::error::SyncError;
+use crate::crdt::scanner::LocalColumnChange;
+
+/// Envelope format version, bumped on incompatible changes.
+pub const ENVELOPE_VERSION: u32 = 1;
+
+const NONCE_LENGTH: usize = 12;
+const HKDF_INFO: &[u8] = b"haex-vault-sync-envelope-v1";
+
+/// AES-256 key for sealing sync envelopes.
+#[derive(Clone)]
+pub struct SyncKey([u8; 32]);
+
+impl SyncKey {
+    /// Derives the envelope key from the base64-encoded sync secret.
+    pub fn from_base64(secret: &str) -> Result<Self, SyncError> {
+        let secret = BASE64
+            .decode(secret.trim())
+            .map_err(|e| SyncError::InvalidConfig {
+                reason: format!("Sync key is not valid base64: {e}"),
+            })?;
+        Self::derive(&secret)
+    }
+
+    /// Derives the envelope key from raw secret bytes (at least 32).
+    pub fn derive(secret: &[u8]) -> Result<Self, SyncError> {
+        if secret.len() < 32 {
+            return Err(SyncError::InvalidConfig {
+                reason: format!("Sync key must be at least 32 bytes, got {}", secret.len()),
+            });
+        }
+        let hk = Hkdf::<Sha256>::new(None, secret);
+        let mut key = [0u8; 32];
+        hk.expand(HKDF_INFO, &mut key)
+            .map_err(|e| SyncError::InvalidConfig {
+                reason: format!("HKDF expand failed: {e}"),
+            })?;
+        Ok(Self(key))
+    }
+
+    fn cipher(&self) -> Result<Aes256Gcm, SyncError> {
+        Aes256Gcm::new_from_slice(&self.0).map_err(|e| SyncError::Envelope {
+            reason: format!("AES init failed: {e}"),
+        })
+    }
+}
+
+/// One sealed batch as stored on the remote.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncEnvelope {
+    pub version: u32,
+    /// Device that pushed the batch.
+    pub device_id: String,
+    /// Newest HLC in the batch; the receiver's pull cursor for the device.
+    pub max_hlc: String,
+    pub change_count: usize,
+    /// Base64 AES-GCM nonce.
+    pub nonce: String,
+    /// Base64 ciphertext of the JSON-encoded changes.
+    pub ciphertext: String,
+}
+
+fn associated_data(version: u32, device_id: &str, max_hlc: &str) -> Vec<u8> {
+    format!("{version}|{device_id}|{max_hlc}").into_bytes()
+}
+
+/// Seals `changes` pushed by `device_id`. `max_hlc` is the newest HLC
+/// among them.
+pub fn seal(
+    key: &SyncKey,
+    device_id: &str,
+    max_hlc: &str,
+    changes: &[LocalColumnChange],
+) -> Result<SyncEnvelope, SyncError> {
+    let plaintext = serde_json::to_vec(changes).map_err(|e| SyncError::Envelope {
+        reason: format!("Failed to serialize changes: {e}"),
+    })?;
+
+    let mut nonce = [0u8; NONCE_LENGTH];
+    rand::fill(&mut nonce);
+    let aad = associated_data(ENVELOPE_VERSION, device_id, max_hlc);
+    let ciphertext = key
+        .cipher()?
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &plaintext,
+                aad: &aad,
+            },
+        )
+        .map_err(|e| SyncError::Envelope {
+            reason: format!("Encryption failed: {e}"),
+        })?;
+
+    Ok(SyncEnvelope {
+        version: ENVELOPE_VERSION,
+        device_id: device_id.to_string(),
+        max_hlc: max_hlc.to_string(),
+        change_count: changes.len(),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+/// Opens an envelope, failing if it was sealed under another key or any
+/// part of it was modified.
+pub fn open(key: &SyncKey, envelope: &SyncEnvelope) -> Result<Vec<LocalColumnChange>, SyncError> {
+    if envelope.version != ENVELOPE_VERSION {
+        return Err(SyncError::Envelope {
+            reason: format!("Unsupported envelope version {}", envelope.version),
+        });
+    }
+
+    let nonce = BASE64
+        .decode(&envelope.nonce)
+        .map_err(|e| SyncError::Envelope {
+            reason: format!("Invalid nonce: {e}"),
+        })?;
+    if nonce.len() != NONCE_LENGTH {
+        return Err(SyncError::Envelope {
+            reason: format!("Invalid nonce length {}", nonce.len()),
+        });
+    }
+    let ciphertext = BASE64
+        .decode(&envelope.ciphertext)
+        .map_err(|e| SyncError::Envelope {
+            reason: format!("Invalid ciphertext: {e}"),
+        })?;
+
+    let aad = associated_data(envelope.version, &envelope.device_id, &envelope.max_hlc);
+    let plaintext = key
+        .cipher()?
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| SyncError::Envelope {
+            reason: "Decryption failed: wrong key or tampered envelope".to_string(),
+        })?;
+
+    let changes: Vec<LocalColumnChange> =
+        serde_json::from_slice(&plaintext).map_err(|e| SyncError::Envelope {
+            reason: format!("Failed to parse changes: {e}"),
+        })?;
+    if changes.len() != envelope.change_count {
+        return Err(SyncError::Envelope {
+            reason: format!(
+                "Envelope announces {} changes but contains {}",
+                envelope.change_count,
+                changes.len()
+            ),
+        });
+    }
+    Ok(changes)
+}
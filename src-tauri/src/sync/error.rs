@@ -0,0 +1,71 @@
+//! Error types for the background sync orchestrator.
+
+use serde::Serialize;
+
+use crate::database::error::DatabaseError;
+use crate::error_code::{serialize_tagged, CodedError, ErrorCode};
+use crate::remote_storage::error::StorageError;
+
+#[derive(Debug, thiserror::Error, Serialize)]
+#[serde(tag = "type", content = "details")]
+#[serde(remote = "Self")]
+pub enum SyncError {
+    #[error("Invalid sync configuration: {reason}")]
+    InvalidConfig { reason: String },
+    #[error("Transport error: {reason}")]
+    Transport { reason: String },
+    #[error("Envelope error: {reason}")]
+    Envelope { reason: String },
+    #[error("Database error: {reason}")]
+    Database { reason: String },
+    #[error("Share error: {reason}")]
+    Share { reason: String },
+}
+
+impl CodedError for SyncError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            SyncError::InvalidConfig { .. } => ErrorCode::Validation,
+            SyncError::Transport { .. } => ErrorCode::VaultSyncTransport,
+            SyncError::Envelope { .. } => ErrorCode::VaultSyncEnvelope,
+            SyncError::Database { .. } => ErrorCode::Database,
+            SyncError::Share { .. } => ErrorCode::VaultSyncShare,
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        self.tagged().get("details").cloned()
+    }
+}
+
+impl SyncError {
+    /// The derived `{ type, details }` representation
+    fn tagged(&self) -> serde_json::Value {
+        SyncError::serialize(self, serde_json::value::Serializer).unwrap_or_default()
+    }
+}
+
+impl Serialize for SyncError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_tagged(self, &self.tagged(), serializer)
+    }
+}
+
+impl From<DatabaseError> for SyncError {
+    fn from(e: DatabaseError) -> Self {
+        SyncError::Database {
+            reason: e.to_string(),
+        }
+    }
+}
+
+impl From<StorageError> for SyncError {
+    fn from(e: StorageError) -> Self {
+        SyncError::Transport {
+            reason: e.to_string(),
+        }
+    }
+}
@@ -0,0 +1,343 @@
+//! Matrix room transport.
+//!
+//! Every batch is one timeline event of type [`BATCH_EVENT_TYPE`] in a room
+//! all devices joined. Small envelopes travel inline in the event content;
+//! larger ones go to the homeserver's media repository and the event carries
+//! the `mxc://` URI. The room doesn't need Matrix encryption, the envelope is
+//! sealed already.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use url::Url;
+
+use crate::sync::envelope::SyncEnvelope;
+use crate::sync::error::SyncError;
+use crate::sync::transport::{sort_batches, BatchInfo, SyncTransport};
+
+/// Event type of sync batches.
+pub const BATCH_EVENT_TYPE: &str = "space.haex.sync.batch";
+
+/// Largest envelope sent inline; Matrix events are capped at 64 KiB.
+const INLINE_LIMIT: usize = 32 * 1024;
+
+/// Events fetched per `/messages` page.
+const PAGE_LIMIT: u32 = 100;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Content of a batch event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchContent {
+    pub device_id: String,
+    pub max_hlc: String,
+    /// The envelope, when small enough to be inline
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub envelope: Option<SyncEnvelope>,
+    /// `mxc://` URI of the uploaded envelope otherwise
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+pub struct MatrixTransport {
+    peer_id: String,
+    homeserver: Url,
+    room_id: String,
+    access_token: String,
+    client: reqwest::Client,
+    /// Batch events of the last listing by event id, so downloads of inline
+    /// batches need no request
+    listed: Mutex<HashMap<String, BatchContent>>,
+}
+
+impl MatrixTransport {
+    pub fn new(
+        peer_id: String,
+        homeserver_url: &str,
+        room_id: String,
+        access_token: String,
+    ) -> Result<Self, SyncError> {
+        let homeserver = Url::parse(homeserver_url).map_err(|e| SyncError::InvalidConfig {
+            reason: format!("Invalid homeserver URL: {e}"),
+        })?;
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(transport_error)?;
+        Ok(Self {
+            peer_id,
+            homeserver,
+            room_id,
+            access_token,
+            client,
+            listed: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// `<homeserver>/<segments>` with every segment percent-encoded.
+    fn endpoint(&self, segments: &[&str]) -> Result<Url, SyncError> {
+        endpoint(&self.homeserver, segments)
+    }
+
+    async fn upload_media(&self, data: Vec<u8>) -> Result<String, SyncError> {
+        let mut url = self.endpoint(&["_matrix", "media", "v3", "upload"])?;
+        url.query_pairs_mut().append_pair("filename", "batch.json");
+        let response = self
+            .client
+            .post(url)
+            .bearer_auth(&self.access_token)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(data)
+            .send()
+            .await
+            .map_err(transport_error)?;
+        let body = check(response).await?;
+        body.get("content_uri")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| SyncError::Transport {
+                reason: "Media upload returned no content_uri".to_string(),
+            })
+    }
+
+    async fn download_media(&self, mxc: &str) -> Result<Vec<u8>, SyncError> {
+        let (server, media_id) = parse_mxc(mxc).ok_or_else(|| SyncError::Transport {
+            reason: format!("Invalid media URI {mxc}"),
+        })?;
+        let url = self.endpoint(&[
+            "_matrix", "client", "v1", "media", "download", server, media_id,
+        ])?;
+        let response = self
+            .client
+            .get(url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .map_err(transport_error)?;
+        if !response.status().is_success() {
+            return Err(SyncError::Transport {
+                reason: format!("Media download failed with {}", response.status()),
+            });
+        }
+        Ok(response.bytes().await.map_err(transport_error)?.to_vec())
+    }
+
+    async fn fetch_event(&self, event_id: &str) -> Result<BatchContent, SyncError> {
+        let url = self.endpoint(&[
+            "_matrix",
+            "client",
+            "v3",
+            "rooms",
+            &self.room_id,
+            "event",
+            event_id,
+        ])?;
+        let response = self
+            .client
+            .get(url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .map_err(transport_error)?;
+        let event = check(response).await?;
+        parse_batch_event(&event)
+            .map(|(_, content)| content)
+            .ok_or_else(|| SyncError::Transport {
+                reason: format!("Event {event_id} is not a sync batch"),
+            })
+    }
+}
+
+#[async_trait]
+impl SyncTransport for MatrixTransport {
+    fn peer_id(&self) -> &str {
+        &self.peer_id
+    }
+
+    async fn upload_batch(&self, envelope: &SyncEnvelope) -> Result<(), SyncError> {
+        let data = serde_json::to_vec(envelope).map_err(|e| SyncError::Envelope {
+            reason: format!("Failed to serialize envelope: {e}"),
+        })?;
+        let mut content = BatchContent {
+            device_id: envelope.device_id.clone(),
+            max_hlc: envelope.max_hlc.clone(),
+            envelope: None,
+            url: None,
+        };
+        if data.len() <= INLINE_LIMIT {
+            content.envelope = Some(envelope.clone());
+        } else {
+            content.url = Some(self.upload_media(data).await?);
+        }
+
+        let txn_id = uuid::Uuid::new_v4().to_string();
+        let url = self.endpoint(&[
+            "_matrix",
+            "client",
+            "v3",
+            "rooms",
+            &self.room_id,
+            "send",
+            BATCH_EVENT_TYPE,
+            &txn_id,
+        ])?;
+        let response = self
+            .client
+            .put(url)
+            .bearer_auth(&self.access_token)
+            .json(&content)
+            .send()
+            .await
+            .map_err(transport_error)?;
+        check(response).await?;
+        Ok(())
+    }
+
+    async fn list_batches(&self) -> Result<Vec<BatchInfo>, SyncError> {
+        let filter = serde_json::json!({ "types": [BATCH_EVENT_TYPE] }).to_string();
+        let mut listed = HashMap::new();
+        let mut seen = std::collections::HashSet::new();
+        let mut batches = Vec::new();
+        let mut from: Option<String> = None;
+
+        // Newest first, back to the room's creation
+        loop {
+            let mut url = self.endpoint(&[
+                "_matrix",
+                "client",
+                "v3",
+                "rooms",
+                &self.room_id,
+                "messages",
+            ])?;
+            {
+                let mut query = url.query_pairs_mut();
+                query
+                    .append_pair("dir", "b")
+                    .append_pair("limit", &PAGE_LIMIT.to_string())
+                    .append_pair("filter", &filter);
+                if let Some(from) = &from {
+                    query.append_pair("from", from);
+                }
+            }
+            let response = self
+                .client
+                .get(url)
+                .bearer_auth(&self.access_token)
+                .send()
+                .await
+                .map_err(transport_error)?;
+            let page = check(response).await?;
+
+            let chunk = page
+                .get("chunk")
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default();
+            for event in &chunk {
+                let Some((event_id, content)) = parse_batch_event(event) else {
+                    continue;
+                };
+                // A retried upload may have posted the same batch twice
+                if !seen.insert((content.device_id.clone(), content.max_hlc.clone())) {
+                    continue;
+                }
+                batches.push(BatchInfo {
+                    device_id: content.device_id.clone(),
+                    max_hlc: content.max_hlc.clone(),
+                    key: event_id.clone(),
+                });
+                listed.insert(event_id, content);
+            }
+
+            match page.get("end").and_then(|v| v.as_str()) {
+                Some(end) if !chunk.is_empty() => from = Some(end.to_string()),
+                _ => break,
+            }
+        }
+
+        if let Ok(mut cache) = self.listed.lock() {
+            *cache = listed;
+        }
+        sort_batches(&mut batches);
+        Ok(batches)
+    }
+
+    async fn download_batch(&self, batch: &BatchInfo) -> Result<SyncEnvelope, SyncError> {
+        let cached = self
+            .listed
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&batch.key).cloned());
+        let content = match cached {
+            Some(content) => content,
+            None => self.fetch_event(&batch.key).await?,
+        };
+
+        if let Some(envelope) = content.envelope {
+            return Ok(envelope);
+        }
+        let mxc = content.url.ok_or_else(|| SyncError::Transport {
+            reason: format!("Batch event {} has neither envelope nor url", batch.key),
+        })?;
+        let data = self.download_media(&mxc).await?;
+        serde_json::from_slice(&data).map_err(|e| SyncError::Envelope {
+            reason: format!("Invalid envelope at {mxc}: {e}"),
+        })
+    }
+}
+
+fn transport_error(e: impl std::fmt::Display) -> SyncError {
+    SyncError::Transport {
+        reason: e.to_string(),
+    }
+}
+
+/// Returns the JSON body of a successful response, or the Matrix error.
+async fn check(response: reqwest::Response) -> Result<JsonValue, SyncError> {
+    let status = response.status();
+    let body: JsonValue = response.json().await.unwrap_or(JsonValue::Null);
+    if status.is_success() {
+        return Ok(body);
+    }
+    let errcode = body.get("errcode").and_then(|v| v.as_str()).unwrap_or("");
+    let message = body.get("error").and_then(|v| v.as_str()).unwrap_or("");
+    Err(SyncError::Transport {
+        reason: format!("Matrix request failed with {status}: {errcode} {message}")
+            .trim_end()
+            .to_string(),
+    })
+}
+
+pub fn endpoint(base: &Url, segments: &[&str]) -> Result<Url, SyncError> {
+    let mut url = base.clone();
+    url.path_segments_mut()
+        .map_err(|()| SyncError::InvalidConfig {
+            reason: format!("{base} cannot be a homeserver URL"),
+        })?
+        .pop_if_empty()
+        .extend(segments);
+    Ok(url)
+}
+
+/// Event id and content of a batch event; `None` for other events and
+/// redacted batches.
+pub fn parse_batch_event(event: &JsonValue) -> Option<(String, BatchContent)> {
+    if event.get("type")?.as_str()? != BATCH_EVENT_TYPE {
+        return None;
+    }
+    let event_id = event.get("event_id")?.as_str()?.to_string();
+    let content: BatchContent = serde_json::from_value(event.get("content")?.clone()).ok()?;
+    (content.envelope.is_some() || content.url.is_some()).then_some((event_id, content))
+}
+
+/// `mxc://<server>/<media_id>` into its parts.
+pub fn parse_mxc(uri: &str) -> Option<(&str, &str)> {
+    let (server, media_id) = uri.strip_prefix("mxc://")?.split_once('/')?;
+    (!server.is_empty() && !media_id.is_empty() && !media_id.contains('/'))
+        .then_some((server, media_id))
+}
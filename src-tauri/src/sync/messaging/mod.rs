@@ -0,0 +1,206 @@
+//! Messaging networks as sync transports.
+//!
+//! For users without S3/WebDAV storage, sealed batches can travel through a
+//! Matrix room ([`matrix`]) or a set of Nostr relays ([`nostr`]) instead.
+//! Both only ever see the [`SyncEnvelope`](super::envelope::SyncEnvelope),
+//! so the network learns device ids and HLCs but no content.
+//!
+//! Transports are configured per device and stored with their credentials
+//! (Matrix access token, Nostr secret key) in haex_crdt_configs under
+//! `sync_messaging_transports`. Each gets an id like a storage backend;
+//! starting the orchestrator with that id selects the transport, so one
+//! device can sync the vault through S3 and another remote through Matrix.
+
+pub mod matrix;
+pub mod nostr;
+
+#[cfg(test)]
+mod tests;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::background::{read_config, write_config};
+use super::error::SyncError;
+use super::transport::SyncTransport;
+use crate::database::constants::vault_settings_key;
+use crate::database::error::DatabaseError;
+
+/// Where a messaging transport publishes batches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum MessagingConfig {
+    /// A room every device of the vault has joined
+    Matrix {
+        /// e.g. `https://matrix.example.org`
+        homeserver_url: String,
+        /// Room id (`!abc:example.org`), not an alias
+        room_id: String,
+        access_token: String,
+    },
+    Nostr {
+        /// `wss://` relay URLs; a batch counts as uploaded once one accepts it
+        relays: Vec<String>,
+        /// Hex secret key shared by all devices of the vault. Generated
+        /// when saved without one.
+        #[serde(default)]
+        secret_key: Option<String>,
+    },
+}
+
+/// A configured messaging transport.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct MessagingTransport {
+    pub id: String,
+    pub name: String,
+    pub config: MessagingConfig,
+}
+
+/// A messaging transport as listed, without its credentials.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct MessagingTransportInfo {
+    pub id: String,
+    pub name: String,
+    /// `matrix` or `nostr`
+    pub kind: String,
+    /// Room id or relay URLs
+    pub targets: Vec<String>,
+    /// Hex public key, to check that all devices use the same Nostr key
+    pub nostr_public_key: Option<String>,
+}
+
+impl MessagingTransport {
+    pub fn info(&self) -> MessagingTransportInfo {
+        let (kind, targets, nostr_public_key) = match &self.config {
+            MessagingConfig::Matrix { room_id, .. } => ("matrix", vec![room_id.clone()], None),
+            MessagingConfig::Nostr {
+                relays, secret_key, ..
+            } => (
+                "nostr",
+                relays.clone(),
+                secret_key
+                    .as_deref()
+                    .and_then(|key| nostr::public_key_hex(key).ok()),
+            ),
+        };
+        MessagingTransportInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            kind: kind.to_string(),
+            targets,
+            nostr_public_key,
+        }
+    }
+
+    /// Checks the config and fills in a generated Nostr key if none is set.
+    pub fn normalized(mut self) -> Result<Self, SyncError> {
+        let invalid = |reason: String| SyncError::InvalidConfig { reason };
+        if self.name.trim().is_empty() {
+            return Err(invalid("Transport name must not be empty".to_string()));
+        }
+        match &mut self.config {
+            MessagingConfig::Matrix {
+                homeserver_url,
+                room_id,
+                access_token,
+            } => {
+                *homeserver_url = homeserver_url.trim().trim_end_matches('/').to_string();
+                url::Url::parse(homeserver_url)
+                    .map_err(|e| invalid(format!("Invalid homeserver URL: {e}")))?;
+                if !room_id.starts_with('!') || !room_id.contains(':') {
+                    return Err(invalid(format!(
+                        "'{room_id}' is not a room id (expected !id:server)"
+                    )));
+                }
+                if access_token.trim().is_empty() {
+                    return Err(invalid("Matrix access token must not be empty".to_string()));
+                }
+            }
+            MessagingConfig::Nostr { relays, secret_key } => {
+                relays.retain(|relay| !relay.trim().is_empty());
+                if relays.is_empty() {
+                    return Err(invalid("At least one Nostr relay is required".to_string()));
+                }
+                for relay in relays.iter() {
+                    if !relay.starts_with("wss://") && !relay.starts_with("ws://") {
+                        return Err(invalid(format!("'{relay}' is not a websocket URL")));
+                    }
+                }
+                match secret_key.as_deref().map(str::trim) {
+                    Some(key) if !key.is_empty() => {
+                        nostr::public_key_hex(key)?;
+                    }
+                    _ => *secret_key = Some(nostr::generate_secret_key_hex()),
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// Opens the transport; cursors are tracked under the transport id.
+    pub fn open(&self) -> Result<Box<dyn SyncTransport>, SyncError> {
+        Ok(match &self.config {
+            MessagingConfig::Matrix {
+                homeserver_url,
+                room_id,
+                access_token,
+            } => Box::new(matrix::MatrixTransport::new(
+                self.id.clone(),
+                homeserver_url,
+                room_id.clone(),
+                access_token.clone(),
+            )?),
+            MessagingConfig::Nostr { relays, secret_key } => {
+                let secret_key = secret_key
+                    .as_deref()
+                    .ok_or_else(|| SyncError::InvalidConfig {
+                        reason: "Nostr transport has no secret key".to_string(),
+                    })?;
+                Box::new(nostr::NostrTransport::new(
+                    self.id.clone(),
+                    relays.clone(),
+                    secret_key,
+                )?)
+            }
+        })
+    }
+}
+
+/// All configured messaging transports.
+pub fn load_transports(conn: &Connection) -> Result<Vec<MessagingTransport>, DatabaseError> {
+    match read_config(conn, vault_settings_key::SYNC_MESSAGING_TRANSPORTS)? {
+        None => Ok(Vec::new()),
+        Some(json) => serde_json::from_str(&json).map_err(|e| DatabaseError::SerializationError {
+            reason: format!("Invalid messaging transports: {e}"),
+        }),
+    }
+}
+
+pub fn save_transports(
+    conn: &Connection,
+    transports: &[MessagingTransport],
+) -> Result<(), DatabaseError> {
+    let json =
+        serde_json::to_string(transports).map_err(|e| DatabaseError::SerializationError {
+            reason: e.to_string(),
+        })?;
+    write_config(conn, vault_settings_key::SYNC_MESSAGING_TRANSPORTS, &json)
+}
+
+/// The transport with `id`, if one is configured.
+pub fn find_transport(
+    conn: &Connection,
+    id: &str,
+) -> Result<Option<MessagingTransport>, DatabaseError> {
+    Ok(load_transports(conn)?.into_iter().find(|t| t.id == id))
+}
@@ -0,0 +1,475 @@
+//! Nostr relay transport.
+//!
+//! Batches are published as NIP-78 application data events (kind
+//! [`BATCH_EVENT_KIND`]) signed with a key all devices of the vault share;
+//! listing asks the relays for events of that author tagged
+//! `t=`[`APP_TAG`]. Relays cap event sizes, so the serialized envelope is
+//! split into parts of [`PART_SIZE`] bytes, one event each, tagged with the
+//! batch key (`b`), an id of the upload (`u`) and `part <index> <count>`; a
+//! batch is listed once all parts of one upload arrived. The upload id keeps
+//! parts of a retried upload (sealed with another nonce) apart. Events with a wrong id or signature are ignored, so a
+//! relay can't inject parts.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use k256::schnorr::{Signature, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use sha2::{Digest, Sha256};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use crate::sync::envelope::SyncEnvelope;
+use crate::sync::error::SyncError;
+use crate::sync::transport::{batch_key, parse_batch_key, sort_batches, BatchInfo, SyncTransport};
+
+/// NIP-78 "application-specific data" (parameterized replaceable).
+pub const BATCH_EVENT_KIND: u16 = 30078;
+
+/// `t` tag of all sync events.
+pub const APP_TAG: &str = "haex-sync";
+
+/// Content bytes per event; most relays accept events up to 64 KiB or more.
+pub const PART_SIZE: usize = 32 * 1024;
+
+/// Events asked for per query; older ones are paged with `until`.
+const QUERY_LIMIT: usize = 500;
+
+const RELAY_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// A signed Nostr event (NIP-01).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    pub id: String,
+    pub pubkey: String,
+    pub created_at: i64,
+    pub kind: u16,
+    pub tags: Vec<Vec<String>>,
+    pub content: String,
+    pub sig: String,
+}
+
+impl Event {
+    /// Value of the first `name` tag
+    fn tag(&self, name: &str) -> Option<&[String]> {
+        self.tags
+            .iter()
+            .find(|tag| tag.first().is_some_and(|n| n == name))
+            .map(|tag| &tag[1..])
+    }
+}
+
+fn invalid_key(e: impl std::fmt::Display) -> SyncError {
+    SyncError::InvalidConfig {
+        reason: format!("Invalid Nostr secret key: {e}"),
+    }
+}
+
+fn transport_error(e: impl std::fmt::Display) -> SyncError {
+    SyncError::Transport {
+        reason: e.to_string(),
+    }
+}
+
+pub fn parse_secret_key(secret_key_hex: &str) -> Result<SigningKey, SyncError> {
+    let bytes = hex::decode(secret_key_hex.trim()).map_err(invalid_key)?;
+    SigningKey::from_bytes(&bytes).map_err(invalid_key)
+}
+
+pub fn public_key_hex(secret_key_hex: &str) -> Result<String, SyncError> {
+    let key = parse_secret_key(secret_key_hex)?;
+    Ok(hex::encode(key.verifying_key().to_bytes()))
+}
+
+pub fn generate_secret_key_hex() -> String {
+    loop {
+        let mut bytes = [0u8; 32];
+        rand::fill(&mut bytes);
+        // Almost every 32-byte string is a valid scalar
+        if SigningKey::from_bytes(&bytes).is_ok() {
+            return hex::encode(bytes);
+        }
+    }
+}
+
+/// Event id: SHA-256 of the NIP-01 serialization.
+pub fn event_id(
+    pubkey: &str,
+    created_at: i64,
+    kind: u16,
+    tags: &[Vec<String>],
+    content: &str,
+) -> [u8; 32] {
+    let serialized = json!([0, pubkey, created_at, kind, tags, content]).to_string();
+    Sha256::digest(serialized.as_bytes()).into()
+}
+
+pub fn sign_event(
+    key: &SigningKey,
+    created_at: i64,
+    kind: u16,
+    tags: Vec<Vec<String>>,
+    content: String,
+) -> Result<Event, SyncError> {
+    let pubkey = hex::encode(key.verifying_key().to_bytes());
+    let id = event_id(&pubkey, created_at, kind, &tags, &content);
+    let mut aux = [0u8; 32];
+    rand::fill(&mut aux);
+    let sig = key.sign_raw(&id, &aux).map_err(|e| SyncError::Envelope {
+        reason: format!("Failed to sign Nostr event: {e}"),
+    })?;
+    Ok(Event {
+        id: hex::encode(id),
+        pubkey,
+        created_at,
+        kind,
+        tags,
+        content,
+        sig: hex::encode(sig.to_bytes()),
+    })
+}
+
+/// Whether the id matches the content and the signature the id.
+pub fn verify_event(event: &Event) -> bool {
+    let id = event_id(
+        &event.pubkey,
+        event.created_at,
+        event.kind,
+        &event.tags,
+        &event.content,
+    );
+    if hex::encode(id) != event.id {
+        return false;
+    }
+    let (Ok(pubkey), Ok(sig)) = (hex::decode(&event.pubkey), hex::decode(&event.sig)) else {
+        return false;
+    };
+    let (Ok(pubkey), Ok(sig)) = (
+        VerifyingKey::from_bytes(&pubkey),
+        Signature::try_from(sig.as_slice()),
+    ) else {
+        return false;
+    };
+    pubkey.verify_raw(&id, &sig).is_ok()
+}
+
+/// Splits `content` into parts of at most [`PART_SIZE`] bytes on char
+/// boundaries.
+pub fn split_parts(content: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = content;
+    while rest.len() > PART_SIZE {
+        let mut end = PART_SIZE;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (part, tail) = rest.split_at(end);
+        parts.push(part);
+        rest = tail;
+    }
+    parts.push(rest);
+    parts
+}
+
+/// Events of one batch, ready to publish.
+pub fn batch_events(
+    key: &SigningKey,
+    envelope: &SyncEnvelope,
+    created_at: i64,
+) -> Result<Vec<Event>, SyncError> {
+    let content = serde_json::to_string(envelope).map_err(|e| SyncError::Envelope {
+        reason: format!("Failed to serialize envelope: {e}"),
+    })?;
+    let batch = batch_key("", &envelope.device_id, &envelope.max_hlc);
+    let mut upload = [0u8; 8];
+    rand::fill(&mut upload);
+    let upload = hex::encode(upload);
+    let parts = split_parts(&content);
+    let count = parts.len().to_string();
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(index, part)| {
+            let tags = vec![
+                vec!["d".to_string(), format!("{batch}#{upload}#{index}")],
+                vec!["t".to_string(), APP_TAG.to_string()],
+                vec!["b".to_string(), batch.clone()],
+                vec!["u".to_string(), upload.clone()],
+                vec!["part".to_string(), index.to_string(), count.clone()],
+            ];
+            sign_event(key, created_at, BATCH_EVENT_KIND, tags, part.to_string())
+        })
+        .collect()
+}
+
+/// Complete batches among `events` (of `pubkey`) with their serialized
+/// envelopes, keyed by batch key. Of several complete uploads of a batch
+/// any one is taken.
+pub fn assemble_batches(pubkey: &str, events: &[Event]) -> HashMap<String, String> {
+    let mut parts: HashMap<(&str, &str), (usize, BTreeMap<usize, &str>)> = HashMap::new();
+    for event in events {
+        if event.pubkey != pubkey || event.kind != BATCH_EVENT_KIND || !verify_event(event) {
+            continue;
+        }
+        let (Some([batch]), Some([upload]), Some([index, count])) =
+            (event.tag("b"), event.tag("u"), event.tag("part"))
+        else {
+            continue;
+        };
+        let (Ok(index), Ok(count)) = (index.parse::<usize>(), count.parse::<usize>()) else {
+            continue;
+        };
+        if index >= count {
+            continue;
+        }
+        let entry = parts
+            .entry((batch.as_str(), upload.as_str()))
+            .or_insert((count, BTreeMap::new()));
+        if entry.0 == count {
+            entry.1.insert(index, event.content.as_str());
+        }
+    }
+
+    parts
+        .into_iter()
+        .filter(|(_, (count, found))| found.len() == *count)
+        .map(|((batch, _), (_, found))| (batch.to_string(), found.into_values().collect()))
+        .collect()
+}
+
+pub struct NostrTransport {
+    peer_id: String,
+    relays: Vec<String>,
+    key: SigningKey,
+    pubkey: String,
+    /// Serialized envelopes of the last listing by batch key
+    listed: Mutex<HashMap<String, String>>,
+}
+
+impl NostrTransport {
+    pub fn new(
+        peer_id: String,
+        relays: Vec<String>,
+        secret_key_hex: &str,
+    ) -> Result<Self, SyncError> {
+        let key = parse_secret_key(secret_key_hex)?;
+        let pubkey = hex::encode(key.verifying_key().to_bytes());
+        Ok(Self {
+            peer_id,
+            relays,
+            key,
+            pubkey,
+            listed: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn batch_filter(&self) -> JsonValue {
+        json!({
+            "authors": [self.pubkey],
+            "kinds": [BATCH_EVENT_KIND],
+            "#t": [APP_TAG],
+        })
+    }
+
+    /// Events matching `filter` from every reachable relay. Fails only if no
+    /// relay answered.
+    async fn query(&self, filter: JsonValue) -> Result<Vec<Event>, SyncError> {
+        let mut events = Vec::new();
+        let mut seen = HashSet::new();
+        let mut errors = Vec::new();
+        for relay in &self.relays {
+            match tokio::time::timeout(RELAY_TIMEOUT * 3, query_relay(relay, filter.clone())).await
+            {
+                Ok(Ok(found)) => {
+                    events.extend(found.into_iter().filter(|e| seen.insert(e.id.clone())));
+                }
+                Ok(Err(e)) => errors.push(format!("{relay}: {e}")),
+                Err(_) => errors.push(format!("{relay}: timed out")),
+            }
+        }
+        if errors.len() == self.relays.len() {
+            return Err(SyncError::Transport {
+                reason: format!("No Nostr relay reachable ({})", errors.join("; ")),
+            });
+        }
+        Ok(events)
+    }
+}
+
+#[async_trait]
+impl SyncTransport for NostrTransport {
+    fn peer_id(&self) -> &str {
+        &self.peer_id
+    }
+
+    async fn upload_batch(&self, envelope: &SyncEnvelope) -> Result<(), SyncError> {
+        let created_at = time::OffsetDateTime::now_utc().unix_timestamp();
+        let events = batch_events(&self.key, envelope, created_at)?;
+
+        let mut errors = Vec::new();
+        for relay in &self.relays {
+            match tokio::time::timeout(RELAY_TIMEOUT * 3, publish_to_relay(relay, &events)).await {
+                Ok(Ok(())) => return Ok(()),
+                Ok(Err(e)) => errors.push(format!("{relay}: {e}")),
+                Err(_) => errors.push(format!("{relay}: timed out")),
+            }
+        }
+        Err(SyncError::Transport {
+            reason: format!("No Nostr relay accepted the batch ({})", errors.join("; ")),
+        })
+    }
+
+    async fn list_batches(&self) -> Result<Vec<BatchInfo>, SyncError> {
+        let events = self.query(self.batch_filter()).await?;
+        let assembled = assemble_batches(&self.pubkey, &events);
+
+        let mut batches: Vec<BatchInfo> = assembled
+            .keys()
+            .filter_map(|key| parse_batch_key("", key))
+            .collect();
+        sort_batches(&mut batches);
+        if let Ok(mut cache) = self.listed.lock() {
+            *cache = assembled;
+        }
+        Ok(batches)
+    }
+
+    async fn download_batch(&self, batch: &BatchInfo) -> Result<SyncEnvelope, SyncError> {
+        let cached = self
+            .listed
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&batch.key).cloned());
+        let content = match cached {
+            Some(content) => content,
+            None => {
+                let mut filter = self.batch_filter();
+                filter["#b"] = json!([batch.key]);
+                let events = self.query(filter).await?;
+                assemble_batches(&self.pubkey, &events)
+                    .remove(&batch.key)
+                    .ok_or_else(|| SyncError::Transport {
+                        reason: format!("Batch {} is incomplete on all relays", batch.key),
+                    })?
+            }
+        };
+        serde_json::from_str(&content).map_err(|e| SyncError::Envelope {
+            reason: format!("Invalid envelope in batch {}: {e}", batch.key),
+        })
+    }
+}
+
+/// Next text frame as JSON, or `None` once the relay closed.
+async fn next_message<S>(ws: &mut S) -> Result<Option<JsonValue>, SyncError>
+where
+    S: StreamExt<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    loop {
+        let message = match tokio::time::timeout(RELAY_TIMEOUT, ws.next()).await {
+            Err(_) => return Err(transport_error("relay timed out")),
+            Ok(None) => return Ok(None),
+            Ok(Some(message)) => message.map_err(transport_error)?,
+        };
+        match message {
+            Message::Text(text) => {
+                if let Ok(value) = serde_json::from_str(&text) {
+                    return Ok(Some(value));
+                }
+            }
+            Message::Close(_) => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+/// Sends every event and waits until the relay acknowledged all of them.
+async fn publish_to_relay(relay: &str, events: &[Event]) -> Result<(), SyncError> {
+    let (mut ws, _) = connect_async(relay).await.map_err(transport_error)?;
+    let mut pending: HashSet<&str> = events.iter().map(|e| e.id.as_str()).collect();
+    for event in events {
+        let frame = json!(["EVENT", event]).to_string();
+        ws.send(Message::Text(frame.into()))
+            .await
+            .map_err(transport_error)?;
+    }
+
+    while !pending.is_empty() {
+        let Some(message) = next_message(&mut ws).await? else {
+            return Err(transport_error("relay closed the connection"));
+        };
+        if message[0] != "OK" {
+            continue;
+        }
+        let id = message[1].as_str().unwrap_or_default();
+        if message[2].as_bool() != Some(true) {
+            let reason = message[3].as_str().unwrap_or("rejected");
+            return Err(transport_error(format!("event {id} rejected: {reason}")));
+        }
+        pending.remove(id);
+    }
+    let _ = ws.close(None).await;
+    Ok(())
+}
+
+/// All stored events matching `filter`, paging back with `until` while the
+/// relay returns full pages.
+async fn query_relay(relay: &str, mut filter: JsonValue) -> Result<Vec<Event>, SyncError> {
+    let (mut ws, _) = connect_async(relay).await.map_err(transport_error)?;
+    let mut events: Vec<Event> = Vec::new();
+    let mut until: Option<i64> = None;
+    filter["limit"] = json!(QUERY_LIMIT);
+
+    for page in 0.. {
+        let subscription = format!("haex-sync-{page}");
+        let frame = json!(["REQ", subscription, filter]).to_string();
+        ws.send(Message::Text(frame.into()))
+            .await
+            .map_err(transport_error)?;
+
+        let mut received = 0;
+        let mut oldest = i64::MAX;
+        loop {
+            let Some(message) = next_message(&mut ws).await? else {
+                return Err(transport_error("relay closed the connection"));
+            };
+            if message[1] != subscription.as_str() {
+                continue;
+            }
+            match message[0].as_str() {
+                Some("EVENT") => {
+                    if let Ok(event) = serde_json::from_value::<Event>(message[2].clone()) {
+                        received += 1;
+                        oldest = oldest.min(event.created_at);
+                        events.push(event);
+                    }
+                }
+                Some("EOSE") => break,
+                Some("CLOSED") => {
+                    let reason = message[2].as_str().unwrap_or("closed");
+                    return Err(transport_error(format!("subscription closed: {reason}")));
+                }
+                _ => {}
+            }
+        }
+        let close = json!(["CLOSE", subscription]).to_string();
+        let _ = ws.send(Message::Text(close.into())).await;
+
+        if received < QUERY_LIMIT {
+            break;
+        }
+        // Events of the same second may straddle pages (duplicates are
+        // dropped by the caller); step past a second that fills a page
+        let next = if until == Some(oldest) {
+            oldest - 1
+        } else {
+            oldest
+        };
+        until = Some(next);
+        filter["until"] = json!(next);
+    }
+    let _ = ws.close(None).await;
+    Ok(events)
+}
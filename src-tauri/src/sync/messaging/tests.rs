@@ -0,0 +1,168 @@
+//! Tests for the Matrix/Nostr transports (without network)
+
+use serde_json::json;
+
+use super::matrix::{self, BatchContent, BATCH_EVENT_TYPE};
+use super::nostr::{self, PART_SIZE};
+use super::{load_transports, save_transports, MessagingConfig, MessagingTransport};
+use crate::crdt::hlc::HlcService;
+use crate::sync::envelope::{SyncEnvelope, ENVELOPE_VERSION};
+use crate::test_support::crdt::open_crdt_connection;
+
+fn envelope(ciphertext_len: usize) -> SyncEnvelope {
+    SyncEnvelope {
+        version: ENVELOPE_VERSION,
+        device_id: "device-a".to_string(),
+        max_hlc: "7345012345678901234/0a1b2c".to_string(),
+        change_count: 1,
+        nonce: "bm9uY2U=".to_string(),
+        ciphertext: "x".repeat(ciphertext_len),
+    }
+}
+
+fn nostr_transport(secret_key: Option<&str>) -> MessagingTransport {
+    MessagingTransport {
+        id: "t1".to_string(),
+        name: "Relays".to_string(),
+        config: MessagingConfig::Nostr {
+            relays: vec!["wss://relay.example".to_string(), " ".to_string()],
+            secret_key: secret_key.map(str::to_string),
+        },
+    }
+}
+
+#[test]
+fn test_normalized_generates_nostr_key_and_drops_blank_relays() {
+    let transport = nostr_transport(None).normalized().unwrap();
+    let MessagingConfig::Nostr { relays, secret_key } = &transport.config else {
+        panic!("expected a Nostr config");
+    };
+    assert_eq!(relays, &vec!["wss://relay.example".to_string()]);
+    let key = secret_key.as_deref().unwrap();
+    assert_eq!(
+        transport.info().nostr_public_key,
+        Some(nostr::public_key_hex(key).unwrap())
+    );
+
+    assert!(nostr_transport(Some("not-hex")).normalized().is_err());
+}
+
+#[test]
+fn test_normalized_checks_matrix_config() {
+    let matrix = |room_id: &str| MessagingTransport {
+        id: "t2".to_string(),
+        name: "Room".to_string(),
+        config: MessagingConfig::Matrix {
+            homeserver_url: "https://matrix.example/".to_string(),
+            room_id: room_id.to_string(),
+            access_token: "token".to_string(),
+        },
+    };
+    let transport = matrix("!abc:matrix.example").normalized().unwrap();
+    assert!(matches!(
+        &transport.config,
+        MessagingConfig::Matrix { homeserver_url, .. } if homeserver_url == "https://matrix.example"
+    ));
+    assert_eq!(transport.info().targets, vec!["!abc:matrix.example"]);
+    assert!(matrix("#alias:matrix.example").normalized().is_err());
+}
+
+#[test]
+fn test_transports_round_trip() {
+    let conn = open_crdt_connection(HlcService::new_for_testing("messaging-test-device")).unwrap();
+    assert!(load_transports(&conn).unwrap().is_empty());
+
+    let transports = vec![nostr_transport(None).normalized().unwrap()];
+    save_transports(&conn, &transports).unwrap();
+    assert_eq!(load_transports(&conn).unwrap(), transports);
+}
+
+#[test]
+fn test_nostr_events_are_signed_and_verified() {
+    let key = nostr::parse_secret_key(&nostr::generate_secret_key_hex()).unwrap();
+    let mut event = nostr::sign_event(&key, 1_700_000_000, 1, vec![], "hello".to_string()).unwrap();
+    assert!(nostr::verify_event(&event));
+
+    event.content = "tampered".to_string();
+    assert!(!nostr::verify_event(&event));
+}
+
+#[test]
+fn test_split_parts() {
+    assert_eq!(nostr::split_parts("abc"), vec!["abc"]);
+
+    let long = "a".repeat(PART_SIZE - 1) + "ü" + &"b".repeat(10);
+    let parts = nostr::split_parts(&long);
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts[0].len(), PART_SIZE - 1);
+    assert_eq!(parts.concat(), long);
+}
+
+#[test]
+fn test_assemble_batches_needs_all_parts_of_one_upload() {
+    let secret = nostr::generate_secret_key_hex();
+    let key = nostr::parse_secret_key(&secret).unwrap();
+    let pubkey = nostr::public_key_hex(&secret).unwrap();
+    let envelope = envelope(PART_SIZE * 2);
+
+    let first = nostr::batch_events(&key, &envelope, 1_700_000_000).unwrap();
+    assert_eq!(first.len(), 3);
+    let batches = nostr::assemble_batches(&pubkey, &first);
+    let batch = crate::sync::transport::batch_key("", &envelope.device_id, &envelope.max_hlc);
+    let assembled: SyncEnvelope = serde_json::from_str(&batches[&batch]).unwrap();
+    assert_eq!(assembled, envelope);
+
+    // Parts of two interrupted uploads don't make a batch
+    let second = nostr::batch_events(&key, &envelope, 1_700_000_001).unwrap();
+    let mixed = vec![first[0].clone(), first[1].clone(), second[2].clone()];
+    assert!(nostr::assemble_batches(&pubkey, &mixed).is_empty());
+
+    // Events of other authors are ignored
+    assert!(nostr::assemble_batches("00".repeat(32).as_str(), &first).is_empty());
+}
+
+#[test]
+fn test_parse_matrix_batch_event() {
+    let content = BatchContent {
+        device_id: "device-a".to_string(),
+        max_hlc: "1/ab".to_string(),
+        envelope: None,
+        url: Some("mxc://matrix.example/media123".to_string()),
+    };
+    let event = json!({
+        "type": BATCH_EVENT_TYPE,
+        "event_id": "$event1",
+        "content": content,
+    });
+    assert_eq!(
+        matrix::parse_batch_event(&event),
+        Some(("$event1".to_string(), content))
+    );
+
+    let redacted = json!({ "type": BATCH_EVENT_TYPE, "event_id": "$event2", "content": {} });
+    assert_eq!(matrix::parse_batch_event(&redacted), None);
+    let message = json!({ "type": "m.room.message", "event_id": "$event3", "content": {} });
+    assert_eq!(matrix::parse_batch_event(&message), None);
+
+    assert_eq!(
+        matrix::parse_mxc("mxc://matrix.example/media123"),
+        Some(("matrix.example", "media123"))
+    );
+    assert_eq!(matrix::parse_mxc("https://matrix.example/media123"), None);
+}
+
+#[test]
+fn test_matrix_endpoint_encodes_room_id() {
+    let base = url::Url::parse("https://matrix.example").unwrap();
+    let url = matrix::endpoint(
+        &base,
+        &["_matrix", "client", "v3", "rooms", "!abc:matrix.example"],
+    )
+    .unwrap();
+    assert_eq!(
+        url.as_str(),
+        "https://matrix.example/_matrix/client/v3/rooms/!abc:matrix.example"
+    );
+    let url = matrix::endpoint(&base, &["rooms", "a/b#c"]).unwrap();
+    assert_eq!(url.as_str(), "https://matrix.example/rooms/a%2Fb%23c");
+}
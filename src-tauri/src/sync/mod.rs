@@ -0,0 +1,25 @@
+//! Background sync orchestrator.
+//!
+//! Runs push/pull cycles against a remote without the frontend driving
+//! them: local changes are found via the dirty-table tracker and the HLC
+//! scanner, sealed into end-to-end encrypted envelopes, uploaded through a
+//! [`transport::SyncTransport`], and remote batches are pulled and applied.
+//! Progress is tracked per peer in the sync status table and reported via
+//! the `sync:*` events.
+//!
+//! Besides storage backends, batches can travel through Matrix rooms or
+//! Nostr relays ([`messaging`]). [`share`] builds sharing with other vaults
+//! on top of it; [`roles`] decides who may write to shared tables.
+
+pub mod background;
+pub mod commands;
+pub mod envelope;
+pub mod error;
+pub mod messaging;
+pub mod orchestrator;
+pub mod roles;
+pub mod share;
+pub mod transport;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,555 @@
+//! Push/pull cycles and the background loop that drives them.
+//!
+//! A cycle first pushes: every CRDT table is scanned for changes newer than
+//! the remote's push cursor in `haex_crdt_sync_status_no_sync`, the changes
+//! are cut into HLC-aligned chunks, and every chunk is sealed and uploaded
+//! as one batch. The push cursor moves after each uploaded batch, so an
+//! interrupted push resumes with the next one. Dirty-table markers only
+//! decide whether a push is needed at all — the scan itself goes by HLC —
+//! and are cleared once the push went through.
+//!
+//! It then pulls: batches of other devices newer than that device's pull
+//! cursor (peer id `<remote>:<device_id>`) are downloaded, opened and
+//! applied in HLC order, advancing the cursor per batch.
+//!
+//! A session can be limited to one direction ([`SyncMode`]) and to some
+//! tables or one space ([`SyncScope`]); shares use this to publish a subset
+//! of the vault. Out-of-scope changes are dropped on both ends.
+//!
+//! The loop runs a cycle on start, every interval, and shortly after local
+//! writes (the dirty-tables event). A failed cycle is retried with
+//! exponential backoff; the error is recorded on the remote's status row.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::Listener;
+use tokio::sync::{watch, Notify};
+use ts_rs::TS;
+
+use super::envelope::{self, SyncKey};
+use super::error::SyncError;
+use super::roles;
+use super::transport::SyncTransport;
+use crate::crdt::commands::{apply_remote_changes_to_db, clear_dirty_table_inner};
+use crate::crdt::hlc::{compare_hlc_strings, hlc_is_newer, hlc_max, HlcService};
+use crate::crdt::scanner::{scan_table_for_local_changes_scoped, LocalColumnChange};
+use crate::crdt::sync_status::{
+    advance_pull_cursor, advance_push_cursor, get_status, list_statuses, record_error,
+};
+use crate::crdt::trigger::DELETED_ROWS_TABLE;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::database::init::discover_crdt_tables;
+use crate::database::DbConnection;
+use crate::event_names::EVENT_CRDT_DIRTY_TABLES_CHANGED;
+use crate::events::{self, payloads::SyncStarted};
+use crate::metrics;
+use crate::space_delivery::local::sync_loop::{
+    chunk_changes_by_hlc, local_to_remote_change, sqlite_datetime_now, PUSH_CHUNK_SOFT_LIMIT,
+};
+use crate::table_names::{TABLE_CRDT_DIRTY_TABLES, TABLE_SHARE_ROLES};
+
+/// Interval between cycles when the caller doesn't pass one.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Shortest accepted interval.
+pub const MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// First retry delay after a failed cycle; doubles per failure.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Upper bound for the retry delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(15 * 60);
+
+/// Delay between a local write and the cycle it triggers, so a burst of
+/// writes is pushed in one go.
+const CHANGE_DEBOUNCE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncPhase {
+    Push,
+    Pull,
+}
+
+/// Payload of `sync:progress`, emitted after every batch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgress {
+    pub peer_id: String,
+    pub phase: SyncPhase,
+    pub completed_batches: u32,
+    pub total_batches: u32,
+    /// Column changes pushed or pulled so far in this phase.
+    pub changes: u64,
+}
+
+/// Payload of `sync:completed`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncCycleReport {
+    pub peer_id: String,
+    pub pushed_changes: u64,
+    pub pushed_batches: u32,
+    pub pulled_changes: u64,
+    pub pulled_batches: u32,
+}
+
+/// Payload of `sync:failed`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncFailure {
+    pub peer_id: String,
+    pub error: String,
+    /// Consecutive failed cycles, including this one.
+    pub attempt: u32,
+    /// Seconds until the next attempt.
+    pub retry_in_secs: u64,
+}
+
+/// Which directions a session syncs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+    /// Push and pull, e.g. between the devices of one vault.
+    Full,
+    /// Only push; used by the owner of a share.
+    PublishOnly,
+    /// Only pull; used by the recipient of a share.
+    ConsumeOnly,
+}
+
+/// Which data a session syncs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncScope {
+    /// Tables to sync; `None` syncs every CRDT table.
+    pub tables: Option<Vec<String>>,
+    /// Restricts rows and deletions to this space; the delete log records
+    /// the space of each deleted row.
+    pub space_id: Option<String>,
+    /// Share whose role assignments are synced along (see
+    /// [`super::roles`]).
+    pub share_id: Option<String>,
+}
+
+impl SyncScope {
+    fn is_full(&self) -> bool {
+        self.tables.is_none() && self.space_id.is_none() && self.share_id.is_none()
+    }
+
+    fn includes_table(&self, table: &str) -> bool {
+        self.tables
+            .as_ref()
+            .is_none_or(|tables| tables.iter().any(|t| t == table))
+    }
+
+    /// Tables a push scans: every CRDT table in scope, the delete log and
+    /// the role table for a share.
+    fn scan_tables(&self, conn: &rusqlite::Connection) -> Result<Vec<String>, DatabaseError> {
+        Ok(discover_crdt_tables(conn)?
+            .into_iter()
+            .filter(|table| {
+                if table == TABLE_SHARE_ROLES {
+                    self.share_id.is_some()
+                } else {
+                    table == DELETED_ROWS_TABLE || self.includes_table(table)
+                }
+            })
+            .collect())
+    }
+
+    /// Drops changes outside the scope: rows of other tables, delete-log
+    /// entries for them or for rows of other spaces, and role assignments of
+    /// other shares. Space membership of rows can't be checked here — the
+    /// sender's scan already restricted them.
+    pub fn retain(&self, changes: Vec<LocalColumnChange>) -> Vec<LocalColumnChange> {
+        if self.is_full() {
+            return changes;
+        }
+        let deletions_of_tables: HashSet<&str> = changes
+            .iter()
+            .filter(|c| c.table_name == DELETED_ROWS_TABLE && c.column_name == "table_name")
+            .filter(|c| c.value.as_str().is_some_and(|t| self.includes_table(t)))
+            .map(|c| c.row_pks.as_str())
+            .collect();
+        let deletions_in_space: HashSet<&str> = changes
+            .iter()
+            .filter(|c| c.table_name == DELETED_ROWS_TABLE && c.column_name == "space_id")
+            .filter(|c| c.value.as_str() == self.space_id.as_deref())
+            .map(|c| c.row_pks.as_str())
+            .collect();
+        let deletions_in_scope: HashSet<String> = deletions_of_tables
+            .into_iter()
+            .filter(|row_pks| self.space_id.is_none() || deletions_in_space.contains(row_pks))
+            .map(str::to_string)
+            .collect();
+        changes
+            .into_iter()
+            .filter(|c| {
+                if c.table_name == DELETED_ROWS_TABLE {
+                    deletions_in_scope.contains(&c.row_pks)
+                } else if c.table_name == TABLE_SHARE_ROLES {
+                    self.share_id
+                        .as_deref()
+                        .is_some_and(|share_id| roles::role_row_in_share(&c.row_pks, share_id))
+                } else {
+                    self.includes_table(&c.table_name)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Everything a cycle needs; owned by the loop.
+pub struct SyncSession {
+    pub db: DbConnection,
+    pub hlc: HlcService,
+    pub device_id: String,
+    /// HLC node of this device. Changes stamped by other nodes were pulled
+    /// and are not pushed back.
+    pub origin_node: Option<u128>,
+    pub key: SyncKey,
+    pub transport: Box<dyn SyncTransport>,
+    pub mode: SyncMode,
+    pub scope: SyncScope,
+}
+
+/// Peer id of the pull cursor for `device_id`'s batches on `peer_id`.
+pub fn remote_device_peer_id(peer_id: &str, device_id: &str) -> String {
+    format!("{peer_id}:{device_id}")
+}
+
+fn has_dirty_tables(db: &DbConnection) -> Result<bool, SyncError> {
+    Ok(with_connection(db, |conn| {
+        Ok(conn.query_row(
+            &format!("SELECT EXISTS(SELECT 1 FROM {TABLE_CRDT_DIRTY_TABLES})"),
+            [],
+            |row| row.get(0),
+        )?)
+    })?)
+}
+
+fn dirty_table_names(db: &DbConnection) -> Result<Vec<String>, SyncError> {
+    Ok(with_connection(db, |conn| {
+        let mut stmt =
+            conn.prepare(&format!("SELECT table_name FROM {TABLE_CRDT_DIRTY_TABLES}"))?;
+        let names = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(names)
+    })?)
+}
+
+/// Local changes after `after_hlc` in the session's scope, sorted by HLC.
+fn scan_local_changes(
+    session: &SyncSession,
+    after_hlc: Option<&str>,
+) -> Result<Vec<LocalColumnChange>, SyncError> {
+    let changes = with_connection(&session.db, |conn| {
+        let mut changes = Vec::new();
+        for table in session.scope.scan_tables(conn)? {
+            let space_id = if table == TABLE_SHARE_ROLES {
+                None
+            } else {
+                session.scope.space_id.as_deref()
+            };
+            changes.extend(scan_table_for_local_changes_scoped(
+                conn,
+                &table,
+                after_hlc,
+                &session.device_id,
+                space_id,
+                session.origin_node,
+            )?);
+        }
+        Ok(changes)
+    })?;
+    let mut changes = session.scope.retain(changes);
+    changes.sort_by(|a, b| compare_hlc_strings(&a.hlc_timestamp, &b.hlc_timestamp));
+    Ok(changes)
+}
+
+async fn push(
+    session: &SyncSession,
+    report: &mut SyncCycleReport,
+    on_progress: &mut impl FnMut(SyncProgress),
+) -> Result<(), SyncError> {
+    let peer_id = session.transport.peer_id();
+    let cursor = with_connection(&session.db, |conn| get_status(conn, peer_id))?
+        .and_then(|status| status.last_push_hlc_timestamp);
+    // Dirty markers belong to the full sync: a scoped session can't rely on
+    // them (the full sync may have cleared them already) and must not clear
+    // them.
+    let full_scope = session.scope.is_full();
+    if full_scope && cursor.is_some() && !has_dirty_tables(&session.db)? {
+        return Ok(());
+    }
+
+    let changes = scan_local_changes(session, cursor.as_deref())?;
+    let chunks = chunk_changes_by_hlc(&changes, PUSH_CHUNK_SOFT_LIMIT);
+    let total_batches = chunks.len() as u32;
+
+    for chunk in chunks {
+        let max_hlc = hlc_max(chunk.iter().map(|c| c.hlc_timestamp.as_str()))
+            .unwrap_or("")
+            .to_string();
+        let sealed = envelope::seal(&session.key, &session.device_id, &max_hlc, chunk)?;
+        session.transport.upload_batch(&sealed).await?;
+        with_connection(&session.db, |conn| {
+            advance_push_cursor(conn, peer_id, &max_hlc)
+        })?;
+
+        report.pushed_batches += 1;
+        report.pushed_changes += chunk.len() as u64;
+        on_progress(SyncProgress {
+            peer_id: peer_id.to_string(),
+            phase: SyncPhase::Push,
+            completed_batches: report.pushed_batches,
+            total_batches,
+            changes: report.pushed_changes,
+        });
+    }
+
+    if !full_scope {
+        return Ok(());
+    }
+    // Every table was scanned, so every marker set before now is covered.
+    // Captured after the upload for the same reason as in the local sync loop.
+    let push_timestamp = sqlite_datetime_now();
+    for table_name in dirty_table_names(&session.db)? {
+        clear_dirty_table_inner(&session.db, &table_name, Some(&push_timestamp))?;
+    }
+    Ok(())
+}
+
+async fn pull(
+    session: &SyncSession,
+    report: &mut SyncCycleReport,
+    on_progress: &mut impl FnMut(SyncProgress),
+) -> Result<(), SyncError> {
+    let peer_id = session.transport.peer_id();
+    let cursors: HashMap<String, String> =
+        with_connection(&session.db, |conn| list_statuses(conn))?
+            .into_iter()
+            .filter_map(|status| Some((status.peer_id, status.last_pull_hlc_timestamp?)))
+            .collect();
+
+    let batches: Vec<_> = session
+        .transport
+        .list_batches()
+        .await?
+        .into_iter()
+        .filter(|batch| batch.device_id != session.device_id)
+        .filter(|batch| {
+            cursors
+                .get(&remote_device_peer_id(peer_id, &batch.device_id))
+                .is_none_or(|cursor| hlc_is_newer(&batch.max_hlc, cursor))
+        })
+        .collect();
+    let total_batches = batches.len() as u32;
+
+    for batch in batches {
+        let sealed = session.transport.download_batch(&batch).await?;
+        if sealed.device_id != batch.device_id || sealed.max_hlc != batch.max_hlc {
+            return Err(SyncError::Envelope {
+                reason: format!("Batch {} does not match its envelope", batch.key),
+            });
+        }
+        let changes = session.scope.retain(envelope::open(&session.key, &sealed)?);
+        let change_count = changes.len() as u64;
+        apply_remote_changes_to_db(
+            &session.db,
+            changes.iter().map(local_to_remote_change).collect(),
+            None,
+            Some(&session.hlc),
+        )?;
+        with_connection(&session.db, |conn| {
+            advance_pull_cursor(
+                conn,
+                &remote_device_peer_id(peer_id, &batch.device_id),
+                &batch.max_hlc,
+            )?;
+            advance_pull_cursor(conn, peer_id, &batch.max_hlc)
+        })?;
+
+        report.pulled_batches += 1;
+        report.pulled_changes += change_count;
+        on_progress(SyncProgress {
+            peer_id: peer_id.to_string(),
+            phase: SyncPhase::Pull,
+            completed_batches: report.pulled_batches,
+            total_batches,
+            changes: report.pulled_changes,
+        });
+    }
+    Ok(())
+}
+
+/// Runs one push followed by one pull, as far as the session's mode allows.
+pub async fn run_cycle(
+    session: &SyncSession,
+    mut on_progress: impl FnMut(SyncProgress),
+) -> Result<SyncCycleReport, SyncError> {
+    let mut report = SyncCycleReport {
+        peer_id: session.transport.peer_id().to_string(),
+        ..Default::default()
+    };
+    if session.mode != SyncMode::ConsumeOnly {
+        push(session, &mut report, &mut on_progress).await?;
+    }
+    if session.mode != SyncMode::PublishOnly {
+        pull(session, &mut report, &mut on_progress).await?;
+    }
+    Ok(report)
+}
+
+/// Retry delay after `attempt` consecutive failures.
+pub fn backoff_for_attempt(attempt: u32) -> Duration {
+    let factor = 1u32 << attempt.saturating_sub(1).min(16);
+    INITIAL_BACKOFF.saturating_mul(factor).min(MAX_BACKOFF)
+}
+
+/// Handle to a running orchestrator. Call `stop()` to terminate.
+pub struct SyncOrchestratorHandle {
+    stop_sender: watch::Sender<bool>,
+    wakeup: Arc<Notify>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl SyncOrchestratorHandle {
+    /// Signal the orchestrator to stop after the current cycle.
+    pub fn stop(&self) {
+        let _ = self.stop_sender.send(true);
+    }
+
+    /// Start the next cycle now instead of after the interval or backoff.
+    pub fn wakeup(&self) {
+        self.wakeup.notify_one();
+    }
+
+    /// Check if the orchestrator task has finished.
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+/// Starts the background loop for `session`.
+pub fn start_orchestrator(
+    app_handle: tauri::AppHandle,
+    session: SyncSession,
+    interval: Duration,
+) -> SyncOrchestratorHandle {
+    let (stop_tx, stop_rx) = watch::channel(false);
+    let wakeup = Arc::new(Notify::new());
+    let task = tokio::spawn(run_loop(
+        app_handle,
+        session,
+        interval.max(MIN_INTERVAL),
+        stop_rx,
+        wakeup.clone(),
+    ));
+
+    SyncOrchestratorHandle {
+        stop_sender: stop_tx,
+        wakeup,
+        task,
+    }
+}
+
+async fn run_loop(
+    app_handle: tauri::AppHandle,
+    session: SyncSession,
+    interval: Duration,
+    mut stop_rx: watch::Receiver<bool>,
+    wakeup: Arc<Notify>,
+) {
+    let peer_id = session.transport.peer_id().to_string();
+
+    // Local writes wake the loop; the debounce below batches them up.
+    let local_change = Arc::new(Notify::new());
+    let listener_notify = local_change.clone();
+    let listener = app_handle.listen_any(EVENT_CRDT_DIRTY_TABLES_CHANGED, move |_| {
+        listener_notify.notify_one();
+    });
+
+    let mut failed_attempts: u32 = 0;
+    'cycles: loop {
+        if *stop_rx.borrow() {
+            break;
+        }
+
+        let _ = events::emit_to_main(
+            &app_handle,
+            &SyncStarted {
+                peer_id: peer_id.clone(),
+            },
+        );
+        let result = metrics::time_sync(
+            metrics::SYNC_REMOTE,
+            run_cycle(&session, |progress| {
+                let _ = events::emit_to_main(&app_handle, &progress);
+            }),
+        )
+        .await;
+
+        let delay = match result {
+            Ok(report) => {
+                failed_attempts = 0;
+                let _ = events::emit_to_main(&app_handle, &report);
+                interval
+            }
+            Err(e) => {
+                failed_attempts += 1;
+                let delay = backoff_for_attempt(failed_attempts);
+                eprintln!(
+                    "[SyncOrchestrator] Cycle failed for {} (attempt {}): {}",
+                    peer_id, failed_attempts, e
+                );
+                if let Err(db_err) = with_connection(&session.db, |conn| {
+                    record_error(conn, &peer_id, &e.to_string())
+                }) {
+                    eprintln!("[SyncOrchestrator] Failed to record error: {db_err}");
+                }
+                let _ = events::emit_to_main(
+                    &app_handle,
+                    &SyncFailure {
+                        peer_id: peer_id.clone(),
+                        error: e.to_string(),
+                        attempt: failed_attempts,
+                        retry_in_secs: delay.as_secs(),
+                    },
+                );
+                delay
+            }
+        };
+
+        let mut wake_at = tokio::time::Instant::now() + delay;
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(wake_at) => break,
+                _ = wakeup.notified() => break,
+                _ = local_change.notified() => {
+                    // Don't hammer a failing remote on every local write.
+                    if failed_attempts == 0 {
+                        wake_at = wake_at.min(tokio::time::Instant::now() + CHANGE_DEBOUNCE);
+                    }
+                },
+                changed = stop_rx.changed() => {
+                    // A dropped handle counts as a stop request.
+                    if changed.is_err() {
+                        break 'cycles;
+                    }
+                    break;
+                },
+            }
+        }
+    }
+
+    app_handle.unlisten(listener);
+}
@@ -0,0 +1,468 @@
+//! Collaboration roles on shares.
+//!
+//! Every device taking part in a share holds a role assignment signed by the
+//! share owner: `editor` devices may write to the share's tables, `reader`
+//! devices only consume. A device is identified by the HLC node its writes
+//! are stamped with, so each remote change can be attributed without
+//! trusting anything the sender claims.
+//!
+//! Assignments live in `haex_share_roles` and are published in the share's
+//! own batches. Whenever remote changes are applied, writes to a table of an
+//! active share are dropped unless they were stamped by a device of this
+//! vault or by an editor of a share covering that table. The check is per
+//! table, so a space-scoped share guards its tables as a whole.
+
+use std::collections::{HashMap, HashSet};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier};
+use rusqlite::{Connection, OptionalExtension, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use ts_rs::TS;
+
+use super::error::SyncError;
+use super::orchestrator::SyncMode;
+use super::share::{Share, ShareDirection};
+use crate::crdt::commands::RemoteColumnChange;
+use crate::crdt::hlc::{
+    device_uuid_to_hlc_node, hlc_node_id_suffix, parse_hlc_node_hex, HlcService,
+};
+use crate::crdt::trigger::DELETED_ROWS_TABLE;
+use crate::database::error::DatabaseError;
+use crate::extension::database::executor::SqlExecutor;
+use crate::table_names::{
+    COL_DEVICES_DEVICE_ID, COL_SHARES_ID, COL_SHARES_OWNER_DID, COL_SHARES_STATUS,
+    COL_SHARES_TABLES, COL_SHARE_ROLES_DEVICE_NODE, COL_SHARE_ROLES_DID, COL_SHARE_ROLES_ID,
+    COL_SHARE_ROLES_ROLE, COL_SHARE_ROLES_SHARE_ID, COL_SHARE_ROLES_SIGNATURE, TABLE_DEVICES,
+    TABLE_SHARES, TABLE_SHARE_ROLES,
+};
+use crate::ucan::public_key_from_did;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum ShareRole {
+    Reader,
+    Editor,
+}
+
+impl ShareRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShareRole::Reader => "reader",
+            ShareRole::Editor => "editor",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "reader" => Some(ShareRole::Reader),
+            "editor" => Some(ShareRole::Editor),
+            _ => None,
+        }
+    }
+}
+
+/// Role of one member device in a share, signed by the share owner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareRoleAssignment {
+    pub share_id: String,
+    pub did: String,
+    /// HLC node id (hex) the device stamps its writes with.
+    pub device_node: String,
+    pub role: ShareRole,
+    /// Base64 Ed25519 signature of the share owner.
+    pub signature: String,
+}
+
+/// Canonical hex form of an HLC node, as used in role rows.
+pub fn format_device_node(node: u128) -> String {
+    format!("{node:x}")
+}
+
+/// Primary key of a role row.
+pub fn role_row_id(share_id: &str, did: &str, device_node: &str) -> String {
+    format!("{share_id}/{did}/{device_node}")
+}
+
+/// Whether a `haex_share_roles` change (by its `row_pks`) belongs to
+/// `share_id`.
+pub fn role_row_in_share(row_pks: &str, share_id: &str) -> bool {
+    serde_json::from_str::<serde_json::Map<String, JsonValue>>(row_pks)
+        .ok()
+        .and_then(|pks| pks.get("id")?.as_str().map(str::to_string))
+        .is_some_and(|id| {
+            id.strip_prefix(share_id)
+                .is_some_and(|rest| rest.starts_with('/'))
+        })
+}
+
+fn signing_payload(share_id: &str, did: &str, device_node: &str, role: ShareRole) -> Vec<u8> {
+    format!(
+        "haex-share-role-v1|{share_id}|{did}|{device_node}|{}",
+        role.as_str()
+    )
+    .into_bytes()
+}
+
+/// Signs a role assignment with the share owner's key.
+pub fn sign_role(
+    share_id: &str,
+    did: &str,
+    device_node: u128,
+    role: ShareRole,
+    owner_key: &SigningKey,
+) -> ShareRoleAssignment {
+    let device_node = format_device_node(device_node);
+    let signature = owner_key.sign(&signing_payload(share_id, did, &device_node, role));
+    ShareRoleAssignment {
+        share_id: share_id.to_string(),
+        did: did.to_string(),
+        device_node,
+        role,
+        signature: BASE64.encode(signature.to_bytes()),
+    }
+}
+
+/// Whether `assignment` carries a valid signature of `owner_did`.
+pub fn verify_role(assignment: &ShareRoleAssignment, owner_did: &str) -> bool {
+    let Ok(owner_key) = public_key_from_did(owner_did) else {
+        return false;
+    };
+    let Some(signature) = BASE64
+        .decode(&assignment.signature)
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+    else {
+        return false;
+    };
+    owner_key
+        .verify(
+            &signing_payload(
+                &assignment.share_id,
+                &assignment.did,
+                &assignment.device_node,
+                assignment.role,
+            ),
+            &signature,
+        )
+        .is_ok()
+}
+
+/// Stores `assignment`, replacing the previous role of that device.
+pub fn upsert_role(
+    tx: &Transaction,
+    hlc: &HlcService,
+    assignment: &ShareRoleAssignment,
+) -> Result<(), SyncError> {
+    let id = role_row_id(
+        &assignment.share_id,
+        &assignment.did,
+        &assignment.device_node,
+    );
+    let exists = tx
+        .query_row(
+            &format!("SELECT 1 FROM {TABLE_SHARE_ROLES} WHERE {COL_SHARE_ROLES_ID} = ?1"),
+            [&id],
+            |_| Ok(()),
+        )
+        .optional()
+        .map_err(DatabaseError::from)?
+        .is_some();
+
+    if exists {
+        SqlExecutor::execute_internal(
+            tx,
+            hlc,
+            &format!(
+                "UPDATE {TABLE_SHARE_ROLES} SET {COL_SHARE_ROLES_ROLE} = ?, \
+                 {COL_SHARE_ROLES_SIGNATURE} = ? WHERE {COL_SHARE_ROLES_ID} = ?"
+            ),
+            &[
+                JsonValue::from(assignment.role.as_str()),
+                JsonValue::from(assignment.signature.as_str()),
+                JsonValue::from(id),
+            ],
+        )?;
+    } else {
+        SqlExecutor::execute_internal(
+            tx,
+            hlc,
+            &format!(
+                "INSERT INTO {TABLE_SHARE_ROLES} ({COL_SHARE_ROLES_ID}, {COL_SHARE_ROLES_SHARE_ID}, \
+                 {COL_SHARE_ROLES_DID}, {COL_SHARE_ROLES_DEVICE_NODE}, {COL_SHARE_ROLES_ROLE}, \
+                 {COL_SHARE_ROLES_SIGNATURE}) VALUES (?, ?, ?, ?, ?, ?)"
+            ),
+            &[
+                JsonValue::from(id),
+                JsonValue::from(assignment.share_id.as_str()),
+                JsonValue::from(assignment.did.as_str()),
+                JsonValue::from(assignment.device_node.as_str()),
+                JsonValue::from(assignment.role.as_str()),
+                JsonValue::from(assignment.signature.as_str()),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Role assignments of a share with a valid owner signature. Forged or
+/// stale rows are left out.
+pub fn list_roles(conn: &Connection, share: &Share) -> Result<Vec<ShareRoleAssignment>, SyncError> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {COL_SHARE_ROLES_DID}, {COL_SHARE_ROLES_DEVICE_NODE}, {COL_SHARE_ROLES_ROLE}, \
+             {COL_SHARE_ROLES_SIGNATURE} FROM {TABLE_SHARE_ROLES} \
+             WHERE {COL_SHARE_ROLES_SHARE_ID} = ?1 ORDER BY {COL_SHARE_ROLES_ID}"
+        ))
+        .map_err(DatabaseError::from)?;
+    let rows = stmt
+        .query_map([&share.id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .map_err(DatabaseError::from)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(DatabaseError::from)?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(did, device_node, role, signature)| {
+            Some(ShareRoleAssignment {
+                share_id: share.id.clone(),
+                did,
+                device_node,
+                role: ShareRole::parse(&role)?,
+                signature,
+            })
+        })
+        .filter(|assignment| verify_role(assignment, &share.owner_did))
+        .collect())
+}
+
+/// How this device syncs `share`: owners pull too once someone else may
+/// edit, recipients push only with an editor role for `own_node`. Picked
+/// when the share's sync starts.
+pub fn share_sync_mode(
+    conn: &Connection,
+    share: &Share,
+    own_node: Option<u128>,
+) -> Result<SyncMode, SyncError> {
+    let roles = list_roles(conn, share)?;
+    let editors = roles.iter().filter(|r| r.role == ShareRole::Editor);
+    Ok(match share.direction {
+        ShareDirection::Outgoing => {
+            if editors.clone().any(|r| r.did != share.owner_did) {
+                SyncMode::Full
+            } else {
+                SyncMode::PublishOnly
+            }
+        }
+        ShareDirection::Incoming => {
+            let own = own_node.map(format_device_node);
+            if editors
+                .clone()
+                .any(|r| Some(&r.device_node) == own.as_ref())
+            {
+                SyncMode::Full
+            } else {
+                SyncMode::ConsumeOnly
+            }
+        }
+    })
+}
+
+fn table_exists(conn: &Connection, table: &str) -> Result<bool, DatabaseError> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+        [table],
+        |row| row.get(0),
+    )?)
+}
+
+/// HLC nodes of this vault's own devices.
+fn own_device_nodes(conn: &Connection) -> Result<HashSet<u128>, DatabaseError> {
+    if !table_exists(conn, TABLE_DEVICES)? {
+        return Ok(HashSet::new());
+    }
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COL_DEVICES_DEVICE_ID} FROM {TABLE_DEVICES} WHERE {COL_DEVICES_DEVICE_ID} IS NOT NULL"
+    ))?;
+    let ids = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids
+        .iter()
+        .filter_map(|id| device_uuid_to_hlc_node(id))
+        .collect())
+}
+
+/// Role rows as they will look after `changes` are applied: stored values
+/// overlaid with the incoming column changes.
+fn role_rows_after(
+    conn: &Connection,
+    changes: &[RemoteColumnChange],
+) -> Result<HashMap<String, HashMap<String, JsonValue>>, DatabaseError> {
+    let mut rows: HashMap<String, HashMap<String, JsonValue>> = HashMap::new();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COL_SHARE_ROLES_ID}, {COL_SHARE_ROLES_SHARE_ID}, {COL_SHARE_ROLES_DID}, \
+         {COL_SHARE_ROLES_DEVICE_NODE}, {COL_SHARE_ROLES_ROLE}, {COL_SHARE_ROLES_SIGNATURE} \
+         FROM {TABLE_SHARE_ROLES}"
+    ))?;
+    let stored = stmt
+        .query_map([], |row| {
+            let mut values = HashMap::new();
+            for (i, column) in [
+                COL_SHARE_ROLES_SHARE_ID,
+                COL_SHARE_ROLES_DID,
+                COL_SHARE_ROLES_DEVICE_NODE,
+                COL_SHARE_ROLES_ROLE,
+                COL_SHARE_ROLES_SIGNATURE,
+            ]
+            .iter()
+            .enumerate()
+            {
+                values.insert(
+                    column.to_string(),
+                    JsonValue::from(row.get::<_, String>(i + 1)?),
+                );
+            }
+            Ok((row.get::<_, String>(0)?, values))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    // Keyed by row_pks, in the shape the scanner emits.
+    for (id, values) in stored {
+        rows.insert(serde_json::json!({ "id": id }).to_string(), values);
+    }
+    for change in changes.iter().filter(|c| c.table_name == TABLE_SHARE_ROLES) {
+        rows.entry(change.row_pks.clone())
+            .or_default()
+            .insert(change.column_name.clone(), change.decrypted_value.clone());
+    }
+    Ok(rows)
+}
+
+fn assignment_from_values(values: &HashMap<String, JsonValue>) -> Option<ShareRoleAssignment> {
+    let get = |column: &str| values.get(column)?.as_str().map(str::to_string);
+    Some(ShareRoleAssignment {
+        share_id: get(COL_SHARE_ROLES_SHARE_ID)?,
+        did: get(COL_SHARE_ROLES_DID)?,
+        device_node: get(COL_SHARE_ROLES_DEVICE_NODE)?,
+        role: ShareRole::parse(&get(COL_SHARE_ROLES_ROLE)?)?,
+        signature: get(COL_SHARE_ROLES_SIGNATURE)?,
+    })
+}
+
+fn change_node(change: &RemoteColumnChange) -> Option<u128> {
+    hlc_node_id_suffix(&change.hlc_timestamp).and_then(parse_hlc_node_hex)
+}
+
+/// Drops remote writes that the role assignments don't allow (see the
+/// module docs). Role rows themselves are only accepted when the resulting
+/// row carries a valid owner signature. Without share tables in the
+/// database, all changes pass.
+pub(crate) fn drop_unauthorized_writes(
+    conn: &Connection,
+    changes: Vec<RemoteColumnChange>,
+) -> Result<Vec<RemoteColumnChange>, DatabaseError> {
+    if !table_exists(conn, TABLE_SHARES)? || !table_exists(conn, TABLE_SHARE_ROLES)? {
+        return Ok(changes);
+    }
+
+    // Active shares: id → (owner, tables).
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COL_SHARES_ID}, {COL_SHARES_OWNER_DID}, {COL_SHARES_TABLES} FROM {TABLE_SHARES} \
+         WHERE {COL_SHARES_STATUS} = 'active'"
+    ))?;
+    let shares: HashMap<String, (String, Vec<String>)> = stmt
+        .query_map([], |row| {
+            let tables: String = row.get(2)?;
+            Ok((
+                row.get::<_, String>(0)?,
+                (
+                    row.get::<_, String>(1)?,
+                    serde_json::from_str(&tables).unwrap_or_default(),
+                ),
+            ))
+        })?
+        .collect::<Result<_, _>>()?;
+    if shares.is_empty() {
+        return Ok(changes);
+    }
+
+    let mut valid_role_rows = HashSet::new();
+    let mut editors: HashMap<&str, HashSet<u128>> = HashMap::new();
+    for (row_pks, values) in role_rows_after(conn, &changes)? {
+        let Some(assignment) = assignment_from_values(&values) else {
+            continue;
+        };
+        let Some((owner_did, tables)) = shares.get(&assignment.share_id) else {
+            continue;
+        };
+        if !verify_role(&assignment, owner_did) {
+            continue;
+        }
+        valid_role_rows.insert(row_pks);
+        if assignment.role == ShareRole::Editor {
+            if let Some(node) = parse_hlc_node_hex(&assignment.device_node) {
+                for table in tables {
+                    editors.entry(table.as_str()).or_default().insert(node);
+                }
+            }
+        }
+    }
+    let guarded: HashSet<&str> = shares
+        .values()
+        .flat_map(|(_, tables)| tables.iter().map(String::as_str))
+        .collect();
+    let own_nodes = own_device_nodes(conn)?;
+
+    // Target tables of delete-log entries in this batch.
+    let deletion_targets: HashMap<String, String> = changes
+        .iter()
+        .filter(|c| c.table_name == DELETED_ROWS_TABLE && c.column_name == "table_name")
+        .filter_map(|c| Some((c.row_pks.clone(), c.decrypted_value.as_str()?.to_string())))
+        .collect();
+
+    let before = changes.len();
+    let allowed: Vec<RemoteColumnChange> = changes
+        .into_iter()
+        .filter(|change| {
+            if change.table_name == TABLE_SHARE_ROLES {
+                return valid_role_rows.contains(&change.row_pks);
+            }
+            let table = if change.table_name == DELETED_ROWS_TABLE {
+                match deletion_targets.get(&change.row_pks) {
+                    Some(target) => target.as_str(),
+                    None => return true,
+                }
+            } else {
+                change.table_name.as_str()
+            };
+            if !guarded.contains(table) {
+                return true;
+            }
+            change_node(change).is_some_and(|node| {
+                own_nodes.contains(&node)
+                    || editors
+                        .get(table)
+                        .is_some_and(|nodes| nodes.contains(&node))
+            })
+        })
+        .collect();
+
+    if allowed.len() < before {
+        eprintln!(
+            "[SYNC RUST] Dropped {} remote change(s) without editor role",
+            before - allowed.len()
+        );
+    }
+    Ok(allowed)
+}
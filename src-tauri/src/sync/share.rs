@@ -0,0 +1,595 @@
+//! Sharing of vault data with other vaults.
+//!
+//! A share publishes a set of tables — optionally restricted to one space,
+//! e.g. a FileSync space — to other users. It runs the sync orchestrator
+//! with its own key and object prefix. Recipients are readers by default:
+//! the owner only pushes, recipients only pull, so changes a recipient makes
+//! locally never reach the owner. Devices with an editor role (see
+//! [`super::roles`]) sync both ways.
+//!
+//! The share key is random and reaches recipients inside a signed
+//! [`ShareInvite`], wrapped for the recipient's `did:key`. Revoking a member
+//! rotates the key; the remaining members get fresh invites and the old
+//! generation's objects are deleted, so the revoked member can't read new
+//! changes.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier};
+use rusqlite::{Connection, OptionalExtension, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use ts_rs::TS;
+
+use super::error::SyncError;
+use super::orchestrator::SyncScope;
+use crate::crdt::hlc::HlcService;
+use crate::crypto::{
+    open_sealed_with_ed25519_seed, seal_for_ed25519_public_key, IdentitySealedData,
+};
+use crate::database::error::DatabaseError;
+use crate::database::init::discover_crdt_tables;
+use crate::extension::database::executor::SqlExecutor;
+use crate::table_names::{
+    COL_SHARES_BACKEND_ID, COL_SHARES_CREATED_AT, COL_SHARES_DIRECTION, COL_SHARES_ID,
+    COL_SHARES_KEY_GENERATION, COL_SHARES_NAME, COL_SHARES_OWNER_DID, COL_SHARES_REVOKED_AT,
+    COL_SHARES_SHARE_KEY, COL_SHARES_SPACE_ID, COL_SHARES_STATUS, COL_SHARES_TABLES,
+    COL_SHARE_MEMBERS_CREATED_AT, COL_SHARE_MEMBERS_DID, COL_SHARE_MEMBERS_ID,
+    COL_SHARE_MEMBERS_REVOKED_AT, COL_SHARE_MEMBERS_SHARE_ID, TABLE_PEER_SHARES, TABLE_SHARES,
+    TABLE_SHARE_MEMBERS,
+};
+use crate::ucan::public_key_from_did;
+
+/// Key prefix of all share batches in a storage backend.
+pub const SHARE_PREFIX: &str = "haex-share/";
+
+/// System tables that may be shared. They carry space-bound metadata (the
+/// files of a FileSync space), so a share of them needs a space.
+pub const SHAREABLE_SYSTEM_TABLES: &[&str] = &[TABLE_PEER_SHARES];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum ShareDirection {
+    /// Shared by this vault.
+    Outgoing,
+    /// Shared with this vault.
+    Incoming,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub enum ShareStatus {
+    Active,
+    Revoked,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareMember {
+    pub did: String,
+    pub created_at: Option<String>,
+    pub revoked_at: Option<String>,
+}
+
+/// A share as shown to the user; the share key is never exposed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Share {
+    pub id: String,
+    pub direction: ShareDirection,
+    pub name: String,
+    pub tables: Vec<String>,
+    pub space_id: Option<String>,
+    pub owner_did: String,
+    pub backend_id: String,
+    /// Bumped on every key rotation; part of the object prefix.
+    pub key_generation: u32,
+    pub status: ShareStatus,
+    pub created_at: Option<String>,
+    pub revoked_at: Option<String>,
+    /// Recipients of an outgoing share, including revoked ones. Empty for
+    /// incoming shares.
+    pub members: Vec<ShareMember>,
+}
+
+impl Share {
+    pub fn scope(&self) -> SyncScope {
+        SyncScope {
+            tables: Some(self.tables.clone()),
+            space_id: self.space_id.clone(),
+            share_id: Some(self.id.clone()),
+        }
+    }
+}
+
+/// Everything a recipient needs to consume a share, signed by the owner.
+/// The share key is sealed for `recipient_did` only.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareInvite {
+    pub share_id: String,
+    pub name: String,
+    pub tables: Vec<String>,
+    pub space_id: Option<String>,
+    pub owner_did: String,
+    pub recipient_did: String,
+    pub key_generation: u32,
+    pub wrapped_key: String,
+    pub wrap_nonce: String,
+    pub wrap_salt: String,
+    pub wrap_ephemeral_public_key: String,
+    /// Base64 Ed25519 signature of the owner over the invite with an empty
+    /// signature.
+    pub signature: String,
+}
+
+/// Parameters of `share_create`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateShareRequest {
+    /// Identity the share is published as; must have a private key.
+    pub owner_did: String,
+    pub name: String,
+    pub tables: Vec<String>,
+    pub space_id: Option<String>,
+    /// Storage backend the batches are published to. Recipients need
+    /// read access to it.
+    pub backend_id: String,
+    pub recipient_dids: Vec<String>,
+}
+
+/// Result of `share_create`: the share and one invite per recipient, to be
+/// delivered out of band.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareCreated {
+    pub share: Share,
+    pub invites: Vec<ShareInvite>,
+}
+
+/// Object prefix of one key generation of a share.
+pub fn share_prefix(share_id: &str, generation: u32) -> String {
+    format!("{SHARE_PREFIX}{share_id}/{generation}/")
+}
+
+/// Object prefix of all generations of a share.
+pub fn share_root_prefix(share_id: &str) -> String {
+    format!("{SHARE_PREFIX}{share_id}/")
+}
+
+/// Peer id of a share generation in the sync status table, so a rotated
+/// key starts with fresh cursors.
+pub fn share_peer_id(share_id: &str, generation: u32) -> String {
+    format!("share:{share_id}:{generation}")
+}
+
+/// Key of a share's orchestrator in `AppState::sync_orchestrators`.
+pub fn share_orchestrator_id(share_id: &str) -> String {
+    format!("share:{share_id}")
+}
+
+pub fn generate_share_key() -> [u8; 32] {
+    let mut key = [0u8; 32];
+    rand::fill(&mut key);
+    key
+}
+
+fn share_error(reason: impl Into<String>) -> SyncError {
+    SyncError::Share {
+        reason: reason.into(),
+    }
+}
+
+/// Checks that `tables` can be shared: existing CRDT tables, no internal
+/// `haex_` tables except [`SHAREABLE_SYSTEM_TABLES`] (which need a space),
+/// and with a space every table must have a `space_id` column.
+pub fn validate_share_tables(
+    conn: &Connection,
+    tables: &[String],
+    space_id: Option<&str>,
+) -> Result<(), SyncError> {
+    if tables.is_empty() {
+        return Err(share_error("A share needs at least one table"));
+    }
+    let crdt_tables = discover_crdt_tables(conn)?;
+    for table in tables {
+        if !crdt_tables.contains(table) {
+            return Err(share_error(format!("Table '{table}' is not synced")));
+        }
+        if table.starts_with("haex_") {
+            if !SHAREABLE_SYSTEM_TABLES.contains(&table.as_str()) {
+                return Err(share_error(format!("Table '{table}' can't be shared")));
+            }
+            if space_id.is_none() {
+                return Err(share_error(format!(
+                    "Table '{table}' can only be shared for a space"
+                )));
+            }
+        }
+        if space_id.is_some() {
+            let has_space_column: bool = conn
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM pragma_table_info(?1) WHERE name = 'space_id')",
+                    [table],
+                    |row| row.get(0),
+                )
+                .map_err(DatabaseError::from)?;
+            if !has_space_column {
+                return Err(share_error(format!(
+                    "Table '{table}' has no space_id column"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn signing_payload(invite: &ShareInvite) -> Result<Vec<u8>, SyncError> {
+    let unsigned = ShareInvite {
+        signature: String::new(),
+        ..invite.clone()
+    };
+    serde_json::to_vec(&unsigned).map_err(|e| share_error(format!("Failed to encode invite: {e}")))
+}
+
+/// Builds an invite for `recipient_did`, wrapping `share_key` for it and
+/// signing with the owner's key.
+pub fn create_invite(
+    share: &Share,
+    share_key: &[u8; 32],
+    recipient_did: &str,
+    owner_key: &SigningKey,
+) -> Result<ShareInvite, SyncError> {
+    let recipient_key = public_key_from_did(recipient_did)
+        .map_err(|e| share_error(format!("Invalid recipient {recipient_did}: {e}")))?;
+    let sealed = seal_for_ed25519_public_key(share_key, recipient_key.as_bytes())
+        .map_err(|e| share_error(format!("Failed to wrap share key: {e}")))?;
+
+    let mut invite = ShareInvite {
+        share_id: share.id.clone(),
+        name: share.name.clone(),
+        tables: share.tables.clone(),
+        space_id: share.space_id.clone(),
+        owner_did: share.owner_did.clone(),
+        recipient_did: recipient_did.to_string(),
+        key_generation: share.key_generation,
+        wrapped_key: sealed.encrypted_data,
+        wrap_nonce: sealed.nonce,
+        wrap_salt: sealed.salt,
+        wrap_ephemeral_public_key: sealed.ephemeral_public_key,
+        signature: String::new(),
+    };
+    let signature = owner_key.sign(&signing_payload(&invite)?);
+    invite.signature = BASE64.encode(signature.to_bytes());
+    Ok(invite)
+}
+
+/// Verifies the owner's signature and unwraps the share key with the
+/// recipient's Ed25519 seed.
+pub fn open_invite(invite: &ShareInvite, recipient_seed: &[u8; 32]) -> Result<[u8; 32], SyncError> {
+    let owner_key = public_key_from_did(&invite.owner_did)
+        .map_err(|e| share_error(format!("Invalid owner {}: {e}", invite.owner_did)))?;
+    let signature_bytes = BASE64
+        .decode(&invite.signature)
+        .map_err(|e| share_error(format!("Invalid signature base64: {e}")))?;
+    let signature = Signature::from_slice(&signature_bytes)
+        .map_err(|e| share_error(format!("Invalid signature: {e}")))?;
+    owner_key
+        .verify(&signing_payload(invite)?, &signature)
+        .map_err(|_| share_error("Invite signature does not match its owner"))?;
+
+    let sealed = IdentitySealedData {
+        encrypted_data: invite.wrapped_key.clone(),
+        nonce: invite.wrap_nonce.clone(),
+        salt: invite.wrap_salt.clone(),
+        ephemeral_public_key: invite.wrap_ephemeral_public_key.clone(),
+    };
+    let key = open_sealed_with_ed25519_seed(&sealed, recipient_seed)
+        .map_err(|e| share_error(format!("Failed to unwrap share key: {e}")))?;
+    key.try_into()
+        .map_err(|_| share_error("Share key must be 32 bytes"))
+}
+
+fn direction_str(direction: ShareDirection) -> &'static str {
+    match direction {
+        ShareDirection::Outgoing => "outgoing",
+        ShareDirection::Incoming => "incoming",
+    }
+}
+
+/// Inserts a new share with its key and recipients.
+pub fn insert_share(
+    tx: &Transaction,
+    hlc: &HlcService,
+    share: &Share,
+    share_key: &[u8; 32],
+) -> Result<(), SyncError> {
+    let tables = serde_json::to_string(&share.tables)
+        .map_err(|e| share_error(format!("Failed to encode tables: {e}")))?;
+    SqlExecutor::execute_internal(
+        tx,
+        hlc,
+        &format!(
+            "INSERT INTO {TABLE_SHARES} ({COL_SHARES_ID}, {COL_SHARES_DIRECTION}, {COL_SHARES_NAME}, \
+             {COL_SHARES_TABLES}, {COL_SHARES_SPACE_ID}, {COL_SHARES_OWNER_DID}, \
+             {COL_SHARES_BACKEND_ID}, {COL_SHARES_SHARE_KEY}, {COL_SHARES_KEY_GENERATION}) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        ),
+        &[
+            JsonValue::from(share.id.as_str()),
+            JsonValue::from(direction_str(share.direction)),
+            JsonValue::from(share.name.as_str()),
+            JsonValue::from(tables),
+            share
+                .space_id
+                .as_deref()
+                .map_or(JsonValue::Null, JsonValue::from),
+            JsonValue::from(share.owner_did.as_str()),
+            JsonValue::from(share.backend_id.as_str()),
+            JsonValue::from(BASE64.encode(share_key)),
+            JsonValue::from(share.key_generation),
+        ],
+    )?;
+    for member in &share.members {
+        SqlExecutor::execute_internal(
+            tx,
+            hlc,
+            &format!(
+                "INSERT INTO {TABLE_SHARE_MEMBERS} ({COL_SHARE_MEMBERS_ID}, \
+                 {COL_SHARE_MEMBERS_SHARE_ID}, {COL_SHARE_MEMBERS_DID}) VALUES (?, ?, ?)"
+            ),
+            &[
+                JsonValue::from(crate::ids::new_uuid().to_string()),
+                JsonValue::from(share.id.as_str()),
+                JsonValue::from(member.did.as_str()),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn load_members(conn: &Connection, share_id: &str) -> Result<Vec<ShareMember>, DatabaseError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COL_SHARE_MEMBERS_DID}, {COL_SHARE_MEMBERS_CREATED_AT}, \
+         {COL_SHARE_MEMBERS_REVOKED_AT} FROM {TABLE_SHARE_MEMBERS} \
+         WHERE {COL_SHARE_MEMBERS_SHARE_ID} = ?1 ORDER BY {COL_SHARE_MEMBERS_DID}"
+    ))?;
+    let members = stmt
+        .query_map([share_id], |row| {
+            Ok(ShareMember {
+                did: row.get(0)?,
+                created_at: row.get(1)?,
+                revoked_at: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(members)
+}
+
+const SHARE_COLUMNS: &str = "id, direction, name, tables, space_id, owner_did, backend_id, \
+                             key_generation, status, created_at, revoked_at";
+
+fn share_from_row(row: &rusqlite::Row) -> rusqlite::Result<(Share, String)> {
+    let direction: String = row.get(1)?;
+    let tables: String = row.get(3)?;
+    let status: String = row.get(8)?;
+    let share = Share {
+        id: row.get(0)?,
+        direction: if direction == "incoming" {
+            ShareDirection::Incoming
+        } else {
+            ShareDirection::Outgoing
+        },
+        name: row.get(2)?,
+        tables: serde_json::from_str(&tables).unwrap_or_default(),
+        space_id: row.get(4)?,
+        owner_did: row.get(5)?,
+        backend_id: row.get(6)?,
+        key_generation: row.get(7)?,
+        status: if status == "revoked" {
+            ShareStatus::Revoked
+        } else {
+            ShareStatus::Active
+        },
+        created_at: row.get(9)?,
+        revoked_at: row.get(10)?,
+        members: Vec::new(),
+    };
+    Ok((share, row.get(11)?))
+}
+
+fn decode_share_key(encoded: &str) -> Result<[u8; 32], SyncError> {
+    BASE64
+        .decode(encoded)
+        .ok()
+        .and_then(|key| key.try_into().ok())
+        .ok_or_else(|| share_error("Stored share key is invalid"))
+}
+
+/// A share with its members and key.
+pub fn load_share(
+    conn: &Connection,
+    share_id: &str,
+) -> Result<Option<(Share, [u8; 32])>, SyncError> {
+    let row = conn
+        .query_row(
+            &format!(
+                "SELECT {SHARE_COLUMNS}, {COL_SHARES_SHARE_KEY} FROM {TABLE_SHARES} \
+                 WHERE {COL_SHARES_ID} = ?1"
+            ),
+            [share_id],
+            share_from_row,
+        )
+        .optional()
+        .map_err(DatabaseError::from)?;
+    let Some((mut share, key)) = row else {
+        return Ok(None);
+    };
+    share.members = load_members(conn, share_id)?;
+    Ok(Some((share, decode_share_key(&key)?)))
+}
+
+/// All shares, newest first.
+pub fn list_shares(conn: &Connection) -> Result<Vec<Share>, SyncError> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {SHARE_COLUMNS}, {COL_SHARES_SHARE_KEY} FROM {TABLE_SHARES} \
+             ORDER BY {COL_SHARES_CREATED_AT} DESC, {COL_SHARES_ID}"
+        ))
+        .map_err(DatabaseError::from)?;
+    let rows = stmt
+        .query_map([], share_from_row)
+        .map_err(DatabaseError::from)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(DatabaseError::from)?;
+    rows.into_iter()
+        .map(|(mut share, _)| {
+            share.members = load_members(conn, &share.id)?;
+            Ok(share)
+        })
+        .collect()
+}
+
+/// Marks a member as revoked. Returns `false` if `did` isn't an active
+/// member.
+pub fn revoke_member(
+    tx: &Transaction,
+    hlc: &HlcService,
+    share_id: &str,
+    did: &str,
+) -> Result<bool, SyncError> {
+    let active: Option<String> = tx
+        .query_row(
+            &format!(
+                "SELECT {COL_SHARE_MEMBERS_ID} FROM {TABLE_SHARE_MEMBERS} \
+                 WHERE {COL_SHARE_MEMBERS_SHARE_ID} = ?1 AND {COL_SHARE_MEMBERS_DID} = ?2 \
+                 AND {COL_SHARE_MEMBERS_REVOKED_AT} IS NULL"
+            ),
+            [share_id, did],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(DatabaseError::from)?;
+    let Some(member_id) = active else {
+        return Ok(false);
+    };
+    SqlExecutor::execute_internal(
+        tx,
+        hlc,
+        &format!(
+            "UPDATE {TABLE_SHARE_MEMBERS} SET {COL_SHARE_MEMBERS_REVOKED_AT} = CURRENT_TIMESTAMP \
+             WHERE {COL_SHARE_MEMBERS_ID} = ?"
+        ),
+        &[JsonValue::from(member_id)],
+    )?;
+    Ok(true)
+}
+
+/// Stores a new key generation of a share.
+pub fn set_share_key(
+    tx: &Transaction,
+    hlc: &HlcService,
+    share_id: &str,
+    share_key: &[u8; 32],
+    generation: u32,
+) -> Result<(), SyncError> {
+    SqlExecutor::execute_internal(
+        tx,
+        hlc,
+        &format!(
+            "UPDATE {TABLE_SHARES} SET {COL_SHARES_SHARE_KEY} = ?, {COL_SHARES_KEY_GENERATION} = ?, \
+             {COL_SHARES_STATUS} = 'active', {COL_SHARES_REVOKED_AT} = NULL \
+             WHERE {COL_SHARES_ID} = ?"
+        ),
+        &[
+            JsonValue::from(BASE64.encode(share_key)),
+            JsonValue::from(generation),
+            JsonValue::from(share_id),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Ends a share. The synced rows stay in both vaults.
+pub fn mark_share_revoked(
+    tx: &Transaction,
+    hlc: &HlcService,
+    share_id: &str,
+) -> Result<(), SyncError> {
+    SqlExecutor::execute_internal(
+        tx,
+        hlc,
+        &format!(
+            "UPDATE {TABLE_SHARES} SET {COL_SHARES_STATUS} = 'revoked', \
+             {COL_SHARES_REVOKED_AT} = CURRENT_TIMESTAMP WHERE {COL_SHARES_ID} = ?"
+        ),
+        &[JsonValue::from(share_id)],
+    )?;
+    Ok(())
+}
+
+/// Records an accepted invite as an incoming share. An invite for a share
+/// that is already known must be of a newer key generation; it replaces the
+/// key and reactivates the share.
+pub fn accept_invite(
+    tx: &Transaction,
+    hlc: &HlcService,
+    invite: &ShareInvite,
+    share_key: &[u8; 32],
+    backend_id: &str,
+) -> Result<Share, SyncError> {
+    match load_share(tx, &invite.share_id)? {
+        Some((share, _)) if share.direction == ShareDirection::Outgoing => {
+            return Err(share_error("Can't accept an invite to an own share"));
+        }
+        Some((share, _)) if share.owner_did != invite.owner_did => {
+            return Err(share_error("Invite owner does not match the known share"));
+        }
+        Some((share, _)) if share.key_generation >= invite.key_generation => {
+            return Err(share_error(format!(
+                "Invite is not newer than key generation {}",
+                share.key_generation
+            )));
+        }
+        Some(_) => {
+            set_share_key(tx, hlc, &invite.share_id, share_key, invite.key_generation)?;
+            SqlExecutor::execute_internal(
+                tx,
+                hlc,
+                &format!(
+                    "UPDATE {TABLE_SHARES} SET {COL_SHARES_BACKEND_ID} = ? WHERE {COL_SHARES_ID} = ?"
+                ),
+                &[
+                    JsonValue::from(backend_id),
+                    JsonValue::from(invite.share_id.as_str()),
+                ],
+            )?;
+        }
+        None => {
+            let share = Share {
+                id: invite.share_id.clone(),
+                direction: ShareDirection::Incoming,
+                name: invite.name.clone(),
+                tables: invite.tables.clone(),
+                space_id: invite.space_id.clone(),
+                owner_did: invite.owner_did.clone(),
+                backend_id: backend_id.to_string(),
+                key_generation: invite.key_generation,
+                status: ShareStatus::Active,
+                created_at: None,
+                revoked_at: None,
+                members: Vec::new(),
+            };
+            insert_share(tx, hlc, &share, share_key)?;
+        }
+    }
+    load_share(tx, &invite.share_id)?
+        .map(|(share, _)| share)
+        .ok_or_else(|| share_error("Accepted share vanished"))
+}
@@ -0,0 +1,716 @@
+//! Tests for the sync orchestrator: envelope sealing, batch keys, backoff
+//! and full push/pull cycles between two vaults over an in-memory transport,
+//! plus scoped shares between vaults.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ed25519_dalek::SigningKey;
+use serde_json::Value as JsonValue;
+
+use super::envelope::{open, seal, SyncEnvelope, SyncKey};
+use super::error::SyncError;
+use super::orchestrator::{
+    backoff_for_attempt, remote_device_peer_id, run_cycle, SyncMode, SyncScope, SyncSession,
+};
+use super::share::{
+    accept_invite, create_invite, insert_share, list_shares, load_share, open_invite,
+    revoke_member, validate_share_tables, Share, ShareDirection, ShareMember, ShareStatus,
+};
+use super::transport::{
+    batch_key, parse_batch_key, sort_batches, BatchInfo, SyncTransport, BATCH_PREFIX,
+};
+use crate::crdt::commands::{apply_remote_changes_to_db, RemoteColumnChange};
+use crate::crdt::hlc::{hlc_node_id_suffix, parse_hlc_node_hex, HlcService};
+use crate::crdt::scanner::LocalColumnChange;
+use crate::crdt::sync_status::get_status;
+use crate::crdt::trigger::{ensure_crdt_columns, setup_triggers_for_table, DELETED_ROWS_TABLE};
+use crate::database::core::with_connection;
+use crate::extension::database::executor::SqlExecutor;
+use crate::table_names::{TABLE_SHARES, TABLE_SHARE_MEMBERS, TABLE_SHARE_ROLES};
+use crate::test_support::crdt::{create_crdt_table, open_crdt_connection};
+use crate::test_support::db::db_connection;
+use crate::ucan::did_key_from_public_key;
+
+const REMOTE: &str = "backend-1";
+
+fn key() -> SyncKey {
+    SyncKey::derive(&[7u8; 32]).unwrap()
+}
+
+fn change(hlc: &str) -> LocalColumnChange {
+    LocalColumnChange {
+        table_name: "notes".to_string(),
+        row_pks: r#"{"id":"n1"}"#.to_string(),
+        column_name: "title".to_string(),
+        hlc_timestamp: hlc.to_string(),
+        value: JsonValue::from("hello"),
+        device_id: "device-a".to_string(),
+    }
+}
+
+/// Batches shared by every session that holds a clone of the store.
+struct MemoryTransport {
+    store: Arc<Mutex<BTreeMap<String, SyncEnvelope>>>,
+}
+
+#[async_trait]
+impl SyncTransport for MemoryTransport {
+    fn peer_id(&self) -> &str {
+        REMOTE
+    }
+
+    async fn upload_batch(&self, envelope: &SyncEnvelope) -> Result<(), SyncError> {
+        self.store.lock().unwrap().insert(
+            batch_key(BATCH_PREFIX, &envelope.device_id, &envelope.max_hlc),
+            envelope.clone(),
+        );
+        Ok(())
+    }
+
+    async fn list_batches(&self) -> Result<Vec<BatchInfo>, SyncError> {
+        let mut batches: Vec<BatchInfo> = self
+            .store
+            .lock()
+            .unwrap()
+            .keys()
+            .filter_map(|key| parse_batch_key(BATCH_PREFIX, key))
+            .collect();
+        sort_batches(&mut batches);
+        Ok(batches)
+    }
+
+    async fn download_batch(&self, batch: &BatchInfo) -> Result<SyncEnvelope, SyncError> {
+        self.store
+            .lock()
+            .unwrap()
+            .get(&batch.key)
+            .cloned()
+            .ok_or_else(|| SyncError::Transport {
+                reason: format!("missing {}", batch.key),
+            })
+    }
+}
+
+fn setup_session(
+    device_id: &str,
+    store: &Arc<Mutex<BTreeMap<String, SyncEnvelope>>>,
+) -> SyncSession {
+    let hlc = HlcService::new_for_testing(device_id);
+    let conn = open_crdt_connection(hlc.clone()).unwrap();
+    conn.execute_batch(include_str!(
+        "../../database/migrations/0009_add_crdt_sync_status.sql"
+    ))
+    .unwrap();
+    create_crdt_table(
+        &conn,
+        "notes",
+        "CREATE TABLE notes (id TEXT PRIMARY KEY NOT NULL, title TEXT, body TEXT)",
+    )
+    .unwrap();
+
+    // The node the test clock stamps its changes with.
+    let stamp = hlc.new_timestamp().unwrap().to_string();
+    let origin_node = hlc_node_id_suffix(&stamp).and_then(parse_hlc_node_hex);
+
+    SyncSession {
+        db: db_connection(conn),
+        hlc,
+        device_id: device_id.to_string(),
+        origin_node,
+        key: key(),
+        transport: Box::new(MemoryTransport {
+            store: store.clone(),
+        }),
+        mode: SyncMode::Full,
+        scope: SyncScope::default(),
+    }
+}
+
+fn insert_note(session: &SyncSession, id: &str, title: &str) {
+    with_connection(&session.db, |conn| {
+        let tx = conn.transaction()?;
+        SqlExecutor::execute_internal(
+            &tx,
+            &session.hlc,
+            "INSERT INTO notes (id, title, body) VALUES (?, ?, ?)",
+            &[
+                JsonValue::from(id),
+                JsonValue::from(title),
+                JsonValue::from("body"),
+            ],
+        )?;
+        tx.commit()?;
+        Ok(())
+    })
+    .unwrap();
+}
+
+fn titles(session: &SyncSession) -> Vec<String> {
+    with_connection(&session.db, |conn| {
+        let mut stmt = conn.prepare("SELECT title FROM notes ORDER BY id")?;
+        let rows = stmt
+            .query_map([], |r| r.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(rows)
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_envelope_round_trip() {
+    let changes = vec![change("100/aa"), change("200/aa")];
+    let sealed = seal(&key(), "device-a", "200/aa", &changes).unwrap();
+    assert_eq!(sealed.change_count, 2);
+    assert!(!sealed.ciphertext.contains("hello"));
+
+    let opened = open(&key(), &sealed).unwrap();
+    assert_eq!(opened.len(), 2);
+    assert_eq!(opened[1].hlc_timestamp, "200/aa");
+}
+
+#[test]
+fn test_envelope_rejects_wrong_key_and_tampering() {
+    let sealed = seal(&key(), "device-a", "100/aa", &[change("100/aa")]).unwrap();
+
+    let other = SyncKey::derive(&[8u8; 32]).unwrap();
+    assert!(open(&other, &sealed).is_err());
+
+    let mut moved = sealed.clone();
+    moved.max_hlc = "900/aa".to_string();
+    assert!(open(&key(), &moved).is_err());
+
+    let mut relabeled = sealed;
+    relabeled.device_id = "device-b".to_string();
+    assert!(open(&key(), &relabeled).is_err());
+}
+
+#[test]
+fn test_short_sync_key_is_rejected() {
+    assert!(SyncKey::derive(&[1u8; 16]).is_err());
+    assert!(SyncKey::from_base64("not base64!").is_err());
+}
+
+#[test]
+fn test_batch_keys_round_trip() {
+    let key = batch_key(BATCH_PREFIX, "device-a", "7310000000000000000/1a2b");
+    assert_eq!(key, "haex-sync/device-a/7310000000000000000-1a2b.json");
+
+    let info = parse_batch_key(BATCH_PREFIX, &key).unwrap();
+    assert_eq!(info.device_id, "device-a");
+    assert_eq!(info.max_hlc, "7310000000000000000/1a2b");
+
+    assert!(parse_batch_key(BATCH_PREFIX, "haex-sync/device-a/readme.txt").is_none());
+    assert!(parse_batch_key(BATCH_PREFIX, "other/device-a/1-2.json").is_none());
+}
+
+#[test]
+fn test_batches_sort_numerically_by_hlc() {
+    let mut batches: Vec<BatchInfo> = ["100/a", "99/b", "1000/a"]
+        .iter()
+        .map(|hlc| parse_batch_key(BATCH_PREFIX, &batch_key(BATCH_PREFIX, "d", hlc)).unwrap())
+        .collect();
+    sort_batches(&mut batches);
+    let order: Vec<&str> = batches.iter().map(|b| b.max_hlc.as_str()).collect();
+    assert_eq!(order, vec!["99/b", "100/a", "1000/a"]);
+}
+
+#[test]
+fn test_backoff_doubles_up_to_cap() {
+    assert_eq!(backoff_for_attempt(1), Duration::from_secs(5));
+    assert_eq!(backoff_for_attempt(2), Duration::from_secs(10));
+    assert_eq!(backoff_for_attempt(4), Duration::from_secs(40));
+    assert_eq!(backoff_for_attempt(50), Duration::from_secs(15 * 60));
+}
+
+#[tokio::test]
+async fn test_cycle_pushes_and_pulls_between_devices() {
+    let store = Arc::new(Mutex::new(BTreeMap::new()));
+    let a = setup_session("device-a", &store);
+    let b = setup_session("device-b", &store);
+
+    insert_note(&a, "n1", "first");
+    insert_note(&a, "n2", "second");
+
+    let mut progress = Vec::new();
+    let report = run_cycle(&a, |p| progress.push(p)).await.unwrap();
+    assert_eq!(report.pushed_batches, 1);
+    assert_eq!(report.pushed_changes, 4);
+    assert_eq!(report.pulled_batches, 0);
+    assert_eq!(progress.len(), 1);
+    assert_eq!(store.lock().unwrap().len(), 1);
+
+    let report = run_cycle(&b, |_| {}).await.unwrap();
+    assert_eq!(report.pulled_batches, 1);
+    assert_eq!(report.pulled_changes, 4);
+    // Pulled rows carry A's HLC node and are not pushed back.
+    assert_eq!(report.pushed_batches, 0);
+    assert_eq!(titles(&b), vec!["first", "second"]);
+
+    let cursor = with_connection(&b.db, |conn| {
+        get_status(conn, &remote_device_peer_id(REMOTE, "device-a"))
+    })
+    .unwrap()
+    .unwrap();
+    assert!(cursor.last_pull_hlc_timestamp.is_some());
+
+    // Nothing new on either side.
+    let report = run_cycle(&b, |_| {}).await.unwrap();
+    assert_eq!((report.pushed_batches, report.pulled_batches), (0, 0));
+    let report = run_cycle(&a, |_| {}).await.unwrap();
+    assert_eq!((report.pushed_batches, report.pulled_batches), (0, 0));
+}
+
+#[tokio::test]
+async fn test_later_cycles_only_push_new_changes() {
+    let store = Arc::new(Mutex::new(BTreeMap::new()));
+    let a = setup_session("device-a", &store);
+    let b = setup_session("device-b", &store);
+
+    insert_note(&a, "n1", "first");
+    run_cycle(&a, |_| {}).await.unwrap();
+    insert_note(&a, "n2", "second");
+    let report = run_cycle(&a, |_| {}).await.unwrap();
+    assert_eq!(report.pushed_changes, 2);
+    assert_eq!(store.lock().unwrap().len(), 2);
+
+    insert_note(&b, "n3", "third");
+    let report = run_cycle(&b, |_| {}).await.unwrap();
+    assert_eq!(report.pulled_batches, 2);
+    assert_eq!(report.pushed_batches, 1);
+
+    run_cycle(&a, |_| {}).await.unwrap();
+    assert_eq!(titles(&a), vec!["first", "second", "third"]);
+}
+
+fn create_synced_table(session: &SyncSession, sql: &str, table: &str) {
+    with_connection(&session.db, |conn| create_crdt_table(conn, table, sql)).unwrap();
+}
+
+fn add_share_tables(session: &SyncSession) {
+    with_connection(&session.db, |conn| {
+        let tx = conn.transaction()?;
+        tx.execute_batch(include_str!(
+            "../../database/migrations/0010_add_shares.sql"
+        ))?;
+        tx.execute_batch(include_str!(
+            "../../database/migrations/0011_add_share_roles.sql"
+        ))?;
+        for table in [TABLE_SHARES, TABLE_SHARE_MEMBERS, TABLE_SHARE_ROLES] {
+            ensure_crdt_columns(&tx, table)?;
+            setup_triggers_for_table(&tx, table, false)?;
+        }
+        tx.commit()?;
+        Ok(())
+    })
+    .unwrap();
+}
+
+fn identity(seed: u8) -> (SigningKey, String) {
+    let key = SigningKey::from_bytes(&[seed; 32]);
+    let did = did_key_from_public_key(&key.verifying_key());
+    (key, did)
+}
+
+fn outgoing_share(owner_did: &str, members: &[&str]) -> Share {
+    Share {
+        id: "share-1".to_string(),
+        direction: ShareDirection::Outgoing,
+        name: "Notes".to_string(),
+        tables: vec!["notes".to_string()],
+        space_id: None,
+        owner_did: owner_did.to_string(),
+        backend_id: REMOTE.to_string(),
+        key_generation: 1,
+        status: ShareStatus::Active,
+        created_at: None,
+        revoked_at: None,
+        members: members
+            .iter()
+            .map(|did| ShareMember {
+                did: did.to_string(),
+                created_at: None,
+                revoked_at: None,
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn test_scope_retains_only_shared_tables_and_their_deletions() {
+    let scope = SyncScope {
+        tables: Some(vec!["notes".to_string()]),
+        space_id: None,
+        share_id: None,
+    };
+    let mut secret = change("100/aa");
+    secret.table_name = "secrets".to_string();
+    let deletion = |pks: &str, table: &str| LocalColumnChange {
+        table_name: DELETED_ROWS_TABLE.to_string(),
+        row_pks: pks.to_string(),
+        column_name: "table_name".to_string(),
+        hlc_timestamp: "200/aa".to_string(),
+        value: JsonValue::from(table),
+        device_id: "device-a".to_string(),
+    };
+
+    let kept = scope.retain(vec![
+        change("100/aa"),
+        secret,
+        deletion(r#"{"id":"d1"}"#, "notes"),
+        deletion(r#"{"id":"d2"}"#, "secrets"),
+    ]);
+    let kept: Vec<(&str, &str)> = kept
+        .iter()
+        .map(|c| (c.table_name.as_str(), c.row_pks.as_str()))
+        .collect();
+    assert_eq!(
+        kept,
+        vec![
+            ("notes", r#"{"id":"n1"}"#),
+            (DELETED_ROWS_TABLE, r#"{"id":"d1"}"#)
+        ]
+    );
+
+    let spaced = SyncScope {
+        space_id: Some("space-1".to_string()),
+        ..scope
+    };
+    let deleted_in = |pks: &str, space_id: &str| LocalColumnChange {
+        column_name: "space_id".to_string(),
+        value: JsonValue::from(space_id),
+        ..deletion(pks, "notes")
+    };
+    let kept = spaced.retain(vec![
+        change("100/aa"),
+        deletion(r#"{"id":"d1"}"#, "notes"),
+        deleted_in(r#"{"id":"d1"}"#, "space-1"),
+        deletion(r#"{"id":"d2"}"#, "notes"),
+        deleted_in(r#"{"id":"d2"}"#, "space-2"),
+        deletion(r#"{"id":"d3"}"#, "notes"),
+    ]);
+    let kept: Vec<(&str, &str, &str)> = kept
+        .iter()
+        .map(|c| {
+            (
+                c.table_name.as_str(),
+                c.row_pks.as_str(),
+                c.column_name.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        kept,
+        vec![
+            ("notes", r#"{"id":"n1"}"#, "title"),
+            (DELETED_ROWS_TABLE, r#"{"id":"d1"}"#, "table_name"),
+            (DELETED_ROWS_TABLE, r#"{"id":"d1"}"#, "space_id"),
+        ]
+    );
+}
+
+#[test]
+fn test_share_tables_are_validated() {
+    let store = Arc::new(Mutex::new(BTreeMap::new()));
+    let session = setup_session("device-a", &store);
+    with_connection(&session.db, |conn| {
+        let notes = vec!["notes".to_string()];
+        assert!(validate_share_tables(conn, &notes, None).is_ok());
+        assert!(validate_share_tables(conn, &[], None).is_err());
+        assert!(validate_share_tables(conn, &["missing".to_string()], None).is_err());
+        assert!(validate_share_tables(conn, &[DELETED_ROWS_TABLE.to_string()], None).is_err());
+        // notes has no space_id column.
+        assert!(validate_share_tables(conn, &notes, Some("space-1")).is_err());
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_invite_wraps_key_for_recipient_only() {
+    let (owner_key, owner_did) = identity(1);
+    let (_, recipient_did) = identity(2);
+    let share = outgoing_share(&owner_did, &[&recipient_did]);
+    let share_key = [9u8; 32];
+
+    let invite = create_invite(&share, &share_key, &recipient_did, &owner_key).unwrap();
+    assert_eq!(open_invite(&invite, &[2u8; 32]).unwrap(), share_key);
+    assert!(open_invite(&invite, &[3u8; 32]).is_err());
+
+    let mut widened = invite.clone();
+    widened.tables.push("secrets".to_string());
+    assert!(open_invite(&widened, &[2u8; 32]).is_err());
+
+    // Signed by someone other than the claimed owner.
+    let (forger_key, _) = identity(4);
+    let forged = create_invite(&share, &share_key, &recipient_did, &forger_key).unwrap();
+    assert!(open_invite(&forged, &[2u8; 32]).is_err());
+}
+
+#[test]
+fn test_share_membership_and_accept_are_persisted() {
+    let store = Arc::new(Mutex::new(BTreeMap::new()));
+    let owner = setup_session("device-a", &store);
+    let recipient = setup_session("device-b", &store);
+    add_share_tables(&owner);
+    add_share_tables(&recipient);
+
+    let (owner_key, owner_did) = identity(1);
+    let (_, bob) = identity(2);
+    let (_, carol) = identity(3);
+    let share = outgoing_share(&owner_did, &[&bob, &carol]);
+    with_connection(&owner.db, |conn| {
+        let tx = conn.transaction()?;
+        insert_share(&tx, &owner.hlc, &share, &[9u8; 32]).unwrap();
+        assert!(revoke_member(&tx, &owner.hlc, &share.id, &carol).unwrap());
+        assert!(!revoke_member(&tx, &owner.hlc, &share.id, &carol).unwrap());
+        tx.commit()?;
+
+        let (stored, key) = load_share(conn, &share.id).unwrap().unwrap();
+        assert_eq!(key, [9u8; 32]);
+        assert_eq!(stored.tables, vec!["notes"]);
+        let revoked: Vec<bool> = stored
+            .members
+            .iter()
+            .map(|m| m.revoked_at.is_some())
+            .collect();
+        assert_eq!(stored.members.len(), 2);
+        assert_eq!(revoked.iter().filter(|r| **r).count(), 1);
+        Ok(())
+    })
+    .unwrap();
+
+    let invite = create_invite(&share, &[9u8; 32], &bob, &owner_key).unwrap();
+    let key = open_invite(&invite, &[2u8; 32]).unwrap();
+    with_connection(&recipient.db, |conn| {
+        let tx = conn.transaction()?;
+        let accepted = accept_invite(&tx, &recipient.hlc, &invite, &key, "backend-b").unwrap();
+        assert_eq!(accepted.direction, ShareDirection::Incoming);
+        assert_eq!(accepted.backend_id, "backend-b");
+        // The same generation can't be accepted twice.
+        assert!(accept_invite(&tx, &recipient.hlc, &invite, &key, "backend-b").is_err());
+
+        let mut rotated = invite.clone();
+        rotated.key_generation = 2;
+        let accepted =
+            accept_invite(&tx, &recipient.hlc, &rotated, &[5u8; 32], "backend-b").unwrap();
+        assert_eq!(accepted.key_generation, 2);
+        tx.commit()?;
+
+        assert_eq!(
+            load_share(conn, &invite.share_id).unwrap().unwrap().1,
+            [5u8; 32]
+        );
+        assert_eq!(list_shares(conn).unwrap().len(), 1);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_share_publishes_scope_one_way() {
+    let store = Arc::new(Mutex::new(BTreeMap::new()));
+    let scope = SyncScope {
+        tables: Some(vec!["notes".to_string()]),
+        space_id: None,
+        share_id: None,
+    };
+    let mut owner = setup_session("device-a", &store);
+    owner.mode = SyncMode::PublishOnly;
+    owner.scope = scope.clone();
+    let mut recipient = setup_session("device-b", &store);
+    recipient.mode = SyncMode::ConsumeOnly;
+    recipient.scope = scope;
+
+    create_synced_table(
+        &owner,
+        "CREATE TABLE secrets (id TEXT PRIMARY KEY NOT NULL, value TEXT);",
+        "secrets",
+    );
+    with_connection(&owner.db, |conn| {
+        let tx = conn.transaction()?;
+        SqlExecutor::execute_internal(
+            &tx,
+            &owner.hlc,
+            "INSERT INTO secrets (id, value) VALUES (?, ?)",
+            &[JsonValue::from("s1"), JsonValue::from("hidden")],
+        )?;
+        tx.commit()?;
+        Ok(())
+    })
+    .unwrap();
+    insert_note(&owner, "n1", "shared");
+
+    let report = run_cycle(&owner, |_| {}).await.unwrap();
+    assert_eq!(report.pushed_changes, 2);
+    let sealed = store.lock().unwrap().values().next().cloned().unwrap();
+    let published = open(&key(), &sealed).unwrap();
+    assert!(published.iter().all(|c| c.table_name == "notes"));
+
+    // The recipient's own edits never reach the owner.
+    insert_note(&recipient, "n2", "local");
+    let report = run_cycle(&recipient, |_| {}).await.unwrap();
+    assert_eq!((report.pushed_batches, report.pulled_batches), (0, 1));
+    assert_eq!(titles(&recipient), vec!["shared", "local"]);
+
+    let report = run_cycle(&owner, |_| {}).await.unwrap();
+    assert_eq!(report.pulled_batches, 0);
+    assert_eq!(titles(&owner), vec!["shared"]);
+}
+
+fn remote(
+    table: &str,
+    row_pks: &str,
+    column: &str,
+    hlc: &str,
+    value: JsonValue,
+) -> RemoteColumnChange {
+    RemoteColumnChange {
+        table_name: table.to_string(),
+        row_pks: row_pks.to_string(),
+        column_name: column.to_string(),
+        hlc_timestamp: hlc.to_string(),
+        decrypted_value: value,
+    }
+}
+
+/// Column changes of a role row as the owner's device `hlc` publishes it.
+fn role_changes(assignment: &ShareRoleAssignment, hlc: &str) -> Vec<RemoteColumnChange> {
+    let row_pks = serde_json::json!({
+        "id": role_row_id(&assignment.share_id, &assignment.did, &assignment.device_node)
+    })
+    .to_string();
+    [
+        ("share_id", assignment.share_id.as_str()),
+        ("did", assignment.did.as_str()),
+        ("device_node", assignment.device_node.as_str()),
+        ("role", assignment.role.as_str()),
+        ("signature", assignment.signature.as_str()),
+    ]
+    .into_iter()
+    .map(|(column, value)| {
+        remote(
+            TABLE_SHARE_ROLES,
+            &row_pks,
+            column,
+            hlc,
+            JsonValue::from(value),
+        )
+    })
+    .collect()
+}
+
+fn note_change(id: &str, hlc: &str) -> RemoteColumnChange {
+    remote(
+        "notes",
+        &serde_json::json!({ "id": id }).to_string(),
+        "title",
+        hlc,
+        JsonValue::from(id),
+    )
+}
+
+#[test]
+fn test_role_signatures_bind_all_fields() {
+    let (owner_key, owner_did) = identity(1);
+    let (_, bob) = identity(2);
+    let assignment = sign_role("share-1", &bob, 0xab, ShareRole::Editor, &owner_key);
+    assert_eq!(assignment.device_node, "ab");
+    assert!(verify_role(&assignment, &owner_did));
+    assert!(!verify_role(&assignment, &bob));
+
+    let mut promoted = sign_role("share-1", &bob, 0xab, ShareRole::Reader, &owner_key);
+    promoted.role = ShareRole::Editor;
+    assert!(!verify_role(&promoted, &owner_did));
+
+    let mut moved = assignment.clone();
+    moved.device_node = "cd".to_string();
+    assert!(!verify_role(&moved, &owner_did));
+
+    let row_pks = serde_json::json!({ "id": role_row_id("share-1", &bob, "ab") }).to_string();
+    assert!(role_row_in_share(&row_pks, "share-1"));
+    assert!(!role_row_in_share(&row_pks, "share-10"));
+    assert!(!role_row_in_share(&row_pks, "share"));
+}
+
+#[test]
+fn test_scope_syncs_only_own_share_roles() {
+    let scope = SyncScope {
+        tables: Some(vec!["notes".to_string()]),
+        space_id: Some("space-1".to_string()),
+        share_id: Some("share-1".to_string()),
+    };
+    let role = |share_id: &str| LocalColumnChange {
+        table_name: TABLE_SHARE_ROLES.to_string(),
+        row_pks: serde_json::json!({ "id": role_row_id(share_id, "did:key:z", "ab") }).to_string(),
+        column_name: "role".to_string(),
+        hlc_timestamp: "100/aa".to_string(),
+        value: JsonValue::from("editor"),
+        device_id: "device-a".to_string(),
+    };
+    let kept = scope.retain(vec![role("share-1"), role("share-2"), change("100/aa")]);
+    assert_eq!(kept.len(), 2);
+    assert!(kept
+        .iter()
+        .all(|c| c.table_name == "notes" || role_row_in_share(&c.row_pks, "share-1")));
+}
+
+#[test]
+fn test_writes_to_shared_tables_need_editor_role() {
+    let store = Arc::new(Mutex::new(BTreeMap::new()));
+    let recipient = setup_session("device-b", &store);
+    add_share_tables(&recipient);
+
+    let (owner_key, owner_did) = identity(1);
+    let (_, bob) = identity(2);
+    let (forger_key, _) = identity(4);
+    let mut share = outgoing_share(&owner_did, &[]);
+    share.direction = ShareDirection::Incoming;
+    with_connection(&recipient.db, |conn| {
+        let tx = conn.transaction()?;
+        insert_share(&tx, &recipient.hlc, &share, &[9u8; 32]).unwrap();
+        tx.commit()?;
+        Ok(())
+    })
+    .unwrap();
+
+    // The owner's device (node ab) is an editor; bob's device (node cd)
+    // only claims to be one.
+    let owner_role = sign_role(&share.id, &owner_did, 0xab, ShareRole::Editor, &owner_key);
+    let forged_role = sign_role(&share.id, &bob, 0xcd, ShareRole::Editor, &forger_key);
+    let mut changes = role_changes(&owner_role, "100/ab");
+    changes.extend(role_changes(&forged_role, "110/cd"));
+    changes.push(note_change("from-owner", "120/ab"));
+    changes.push(note_change("from-bob", "130/cd"));
+    changes.push(remote(
+        "other",
+        r#"{"id":"o1"}"#,
+        "value",
+        "140/cd",
+        JsonValue::from("unshared"),
+    ));
+
+    let allowed = with_connection(&recipient.db, |conn| {
+        drop_unauthorized_writes(conn, changes.clone())
+    })
+    .unwrap();
+    let from_cd: Vec<&str> = allowed
+        .iter()
+        .filter(|c| c.hlc_timestamp.ends_with("/cd"))
+        .map(|c| c.table_name.as_str())
+        .collect();
+    assert_eq!(from_cd, vec!["other"]);
+    assert_eq!(allowed.len(), changes.len() - 5 - 1);
+
+    apply_remote_changes_to_db(&recipient.db, changes, None, Some(&recipient.hlc)).unwrap();
+    assert_eq!(titles(&recipient), vec!["from-owner"]);
+
+    // Once the owner makes bob's device an editor, its writes are applied.
+    let bob_role = sign_role(&share.id, &bob, 0xcd, ShareRole::Editor, &owner_key);
+    let mut changes = role_changes(&bob_role, "150/ab");
+    changes.push(note_change("from-bob", "160/cd"));
+    apply_remote_changes_to_db(&recipient.db, changes, None, Some(&recipient.hlc)).unwrap();
+    assert_eq!(titles(&recipient), vec!["from-bob", "from-owner"]);
+}
@@ -0,0 +1,131 @@
+//! Where sealed batches go.
+//!
+//! The orchestrator only needs to upload a batch, list what is there and
+//! download single batches, so any remote that can store blobs works. The
+//! storage transport keeps every batch as its own object under
+//! `<prefix><device_id>/<hlc>.json` — `haex-sync/` for the vault's own sync,
+//! a per-share prefix for shares; the HLC's `/` separator is stored as `-`
+//! so the key stays one path segment.
+
+use async_trait::async_trait;
+
+use super::envelope::SyncEnvelope;
+use super::error::SyncError;
+use crate::crdt::hlc::compare_hlc_strings;
+use crate::remote_storage::backend::StorageBackend;
+
+/// Key prefix of the vault's own sync batches in a storage backend.
+pub const BATCH_PREFIX: &str = "haex-sync/";
+
+/// A batch on the remote, as listed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchInfo {
+    /// Device that pushed the batch.
+    pub device_id: String,
+    /// Newest HLC in the batch.
+    pub max_hlc: String,
+    /// Transport-specific location of the batch.
+    pub key: String,
+}
+
+#[async_trait]
+pub trait SyncTransport: Send + Sync {
+    /// Peer id under which this remote's cursors are tracked in the sync
+    /// status table.
+    fn peer_id(&self) -> &str;
+
+    async fn upload_batch(&self, envelope: &SyncEnvelope) -> Result<(), SyncError>;
+
+    /// All batches on the remote, ordered by `max_hlc`.
+    async fn list_batches(&self) -> Result<Vec<BatchInfo>, SyncError>;
+
+    async fn download_batch(&self, batch: &BatchInfo) -> Result<SyncEnvelope, SyncError>;
+}
+
+/// Object key of a batch under `prefix`.
+pub fn batch_key(prefix: &str, device_id: &str, max_hlc: &str) -> String {
+    format!("{prefix}{device_id}/{}.json", max_hlc.replace('/', "-"))
+}
+
+/// Parses an object key written by [`batch_key`] with the same `prefix`;
+/// `None` for foreign objects.
+pub fn parse_batch_key(prefix: &str, key: &str) -> Option<BatchInfo> {
+    let rest = key.strip_prefix(prefix)?;
+    let (device_id, file) = rest.split_once('/')?;
+    let (time, node) = file.strip_suffix(".json")?.split_once('-')?;
+    if device_id.is_empty() || time.is_empty() || node.is_empty() || node.contains('/') {
+        return None;
+    }
+    Some(BatchInfo {
+        device_id: device_id.to_string(),
+        max_hlc: format!("{time}/{node}"),
+        key: key.to_string(),
+    })
+}
+
+/// Orders batches by HLC so older changes are applied first.
+pub fn sort_batches(batches: &mut [BatchInfo]) {
+    batches.sort_by(|a, b| compare_hlc_strings(&a.max_hlc, &b.max_hlc));
+}
+
+/// Batches stored in a remote storage backend.
+pub struct StorageTransport {
+    peer_id: String,
+    prefix: String,
+    backend: Box<dyn StorageBackend>,
+}
+
+impl StorageTransport {
+    pub fn new(peer_id: String, prefix: String, backend: Box<dyn StorageBackend>) -> Self {
+        Self {
+            peer_id,
+            prefix,
+            backend,
+        }
+    }
+
+    /// Deletes every batch under the prefix.
+    pub async fn delete_all(&self) -> Result<(), SyncError> {
+        for object in self.backend.list(Some(&self.prefix)).await? {
+            self.backend.delete(&object.key).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SyncTransport for StorageTransport {
+    fn peer_id(&self) -> &str {
+        &self.peer_id
+    }
+
+    async fn upload_batch(&self, envelope: &SyncEnvelope) -> Result<(), SyncError> {
+        let data = serde_json::to_vec(envelope).map_err(|e| SyncError::Envelope {
+            reason: format!("Failed to serialize envelope: {e}"),
+        })?;
+        self.backend
+            .upload(
+                &batch_key(&self.prefix, &envelope.device_id, &envelope.max_hlc),
+                &data,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn list_batches(&self) -> Result<Vec<BatchInfo>, SyncError> {
+        let objects = self.backend.list(Some(&self.prefix)).await?;
+        let mut batches: Vec<BatchInfo> = objects
+            .iter()
+            .filter_map(|object| parse_batch_key(&self.prefix, &object.key))
+            .collect();
+        sort_batches(&mut batches);
+        Ok(batches)
+    }
+
+    async fn download_batch(&self, batch: &BatchInfo) -> Result<SyncEnvelope, SyncError> {
+        let data = self.backend.download(&batch.key).await?;
+        serde_json::from_slice(&data).map_err(|e| SyncError::Envelope {
+            reason: format!("Invalid envelope at {}: {e}", batch.key),
+        })
+    }
+}
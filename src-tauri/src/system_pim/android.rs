@@ -0,0 +1,149 @@
+//! Contacts and calendars through the native `SystemPimPlugin`
+//! (`ContactsContract` / `CalendarContract`).
+//!
+//! The plugin resolves the runtime permission itself, so calls may block on
+//! the system dialog; they run on the blocking pool.
+
+use super::normalize::from_epoch_millis;
+use super::types::{SystemCalendar, SystemContact, SystemEvent, SystemPimStatus};
+use super::{EventRange, SystemPimError};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tauri::plugin::mobile::PluginInvokeError;
+use tauri::plugin::PluginHandle;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Handle to the native `SystemPimPlugin`.
+pub struct SystemPimPlugin<R: Runtime>(PluginHandle<R>);
+
+/// Registers the native plugin (`space.haex.vault.SystemPimPlugin`).
+pub fn init<R: Runtime>() -> tauri::plugin::TauriPlugin<R> {
+    tauri::plugin::Builder::new("system-pim")
+        .setup(|app, api| {
+            let handle = api.register_android_plugin("space.haex.vault", "SystemPimPlugin")?;
+            app.manage(SystemPimPlugin(handle));
+            Ok(())
+        })
+        .build()
+}
+
+/// Lists come wrapped, the plugin can only resolve with an object.
+#[derive(Deserialize)]
+struct Items<T> {
+    items: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NativeEvent {
+    id: String,
+    calendar_id: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    location: Option<String>,
+    begin: i64,
+    #[serde(default)]
+    end: Option<i64>,
+    #[serde(default)]
+    all_day: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EventsArgs {
+    from: i64,
+    to: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    calendar_ids: Option<Vec<String>>,
+}
+
+fn map_error(resource: &str, e: PluginInvokeError) -> SystemPimError {
+    match e {
+        PluginInvokeError::InvokeRejected(response)
+            if response.code.as_deref() == Some("PERMISSION_DENIED") =>
+        {
+            SystemPimError::AccessDenied {
+                resource: resource.to_string(),
+            }
+        }
+        e => SystemPimError::Unavailable {
+            reason: e.to_string(),
+        },
+    }
+}
+
+async fn run<T: DeserializeOwned + Send + 'static>(
+    app: &AppHandle,
+    resource: &'static str,
+    command: &'static str,
+    args: impl Serialize + Send + 'static,
+) -> Result<T, SystemPimError> {
+    let plugin = app
+        .try_state::<SystemPimPlugin<tauri::Wry>>()
+        .ok_or_else(|| SystemPimError::Unavailable {
+            reason: "SystemPimPlugin is not registered".to_string(),
+        })?
+        .0
+        .clone();
+    tokio::task::spawn_blocking(move || plugin.run_mobile_plugin::<T>(command, args))
+        .await
+        .map_err(|e| SystemPimError::Internal {
+            reason: format!("System PIM task failed: {e}"),
+        })?
+        .map_err(|e| map_error(resource, e))
+}
+
+pub async fn status(_app: &AppHandle) -> SystemPimStatus {
+    SystemPimStatus {
+        contacts: true,
+        calendar: true,
+        reason: None,
+    }
+}
+
+pub async fn contacts(app: &AppHandle) -> Result<Vec<SystemContact>, SystemPimError> {
+    let list: Items<SystemContact> = run(app, "contacts", "listContacts", ()).await?;
+    Ok(list.items)
+}
+
+pub async fn calendars(app: &AppHandle) -> Result<Vec<SystemCalendar>, SystemPimError> {
+    let list: Items<SystemCalendar> = run(app, "calendar", "listCalendars", ()).await?;
+    Ok(list.items)
+}
+
+pub async fn events(
+    app: &AppHandle,
+    range: EventRange,
+    calendar_ids: Option<Vec<String>>,
+) -> Result<Vec<SystemEvent>, SystemPimError> {
+    let args = EventsArgs {
+        from: (range.from.unix_timestamp_nanos() / 1_000_000) as i64,
+        to: (range.to.unix_timestamp_nanos() / 1_000_000) as i64,
+        calendar_ids,
+    };
+    let list: Items<NativeEvent> = run(app, "calendar", "listEvents", args).await?;
+    Ok(list
+        .items
+        .into_iter()
+        .filter_map(|event| {
+            Some(SystemEvent {
+                // Instances of a recurring event share the event id
+                id: format!("{}:{}", event.id, event.begin),
+                calendar_id: event.calendar_id,
+                title: event.title.unwrap_or_default(),
+                description: event.description.filter(|d| !d.is_empty()),
+                location: event.location.filter(|l| !l.is_empty()),
+                start: from_epoch_millis(event.begin, event.all_day)?,
+                end: event
+                    .end
+                    .and_then(|end| from_epoch_millis(end, event.all_day)),
+                all_day: event.all_day,
+                time_zone: None,
+                recurrence: None,
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,165 @@
+// src-tauri/src/system_pim/commands.rs
+//!
+//! System Contacts and Calendar Commands
+//!
+//! `system_pim_status` is for the host UI (settings page).
+//! `extension_system_pim_*` are the permission-checked variants: contacts
+//! need the `contacts` permission, calendars and events the `calendar`
+//! permission.
+
+use super::error::SystemPimError;
+use super::normalize::paginate;
+use super::types::{SystemCalendar, SystemContactPage, SystemEvent, SystemPimStatus};
+use super::EventRange;
+use crate::extension::error::ExtensionError;
+use crate::extension::permissions::manager::PermissionManager;
+use crate::extension::permissions::types::{CalendarAction, ContactsAction};
+use crate::extension::utils::{emit_permission_prompt_if_needed, resolve_extension_id};
+use crate::AppState;
+use tauri::{AppHandle, State, WebviewWindow};
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+
+const DEFAULT_PAGE_SIZE: u32 = 200;
+const MAX_PAGE_SIZE: u32 = 1000;
+/// Longest span of one event query
+const MAX_EVENT_RANGE: Duration = Duration::days(366);
+
+fn to_extension_error(e: SystemPimError) -> ExtensionError {
+    ExtensionError::ValidationError {
+        reason: e.to_string(),
+    }
+}
+
+/// Parses and bounds an RFC 3339 `from`/`to` pair.
+pub fn parse_range(from: &str, to: &str) -> Result<EventRange, SystemPimError> {
+    let parse = |value: &str| {
+        OffsetDateTime::parse(value, &Rfc3339).map_err(|e| SystemPimError::InvalidRequest {
+            reason: format!("'{value}' is not an RFC 3339 timestamp: {e}"),
+        })
+    };
+    let range = EventRange {
+        from: parse(from)?,
+        to: parse(to)?,
+    };
+    if range.to <= range.from {
+        return Err(SystemPimError::InvalidRequest {
+            reason: "'to' must be after 'from'".to_string(),
+        });
+    }
+    if range.to - range.from > MAX_EVENT_RANGE {
+        return Err(SystemPimError::InvalidRequest {
+            reason: format!("range exceeds {} days", MAX_EVENT_RANGE.whole_days()),
+        });
+    }
+    Ok(range)
+}
+
+/// Whether system contacts and calendars can be read on this device.
+#[tauri::command]
+pub async fn system_pim_status(app_handle: AppHandle) -> SystemPimStatus {
+    super::status(&app_handle).await
+}
+
+/// Platform support on behalf of an extension. Reveals no data, so it
+/// needs no permission.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_system_pim_status(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<SystemPimStatus, ExtensionError> {
+    resolve_extension_id(&window, &state, public_key, name)?;
+    Ok(super::status(&app_handle).await)
+}
+
+/// System contacts, filtered by `query` (names, organization, email,
+/// phone digits) and sorted by display name.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_system_pim_list_contacts(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    query: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<SystemContactPage, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let perm_result =
+        PermissionManager::check_contacts_permission(&state, &extension_id, ContactsAction::Read)
+            .await;
+    if let Err(ref e) = perm_result {
+        emit_permission_prompt_if_needed(&app_handle, e);
+    }
+    perm_result?;
+
+    let contacts = super::contacts(&app_handle)
+        .await
+        .map_err(to_extension_error)?;
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    Ok(paginate(
+        contacts,
+        query.as_deref(),
+        offset.unwrap_or(0) as usize,
+        limit as usize,
+    ))
+}
+
+/// System calendars on behalf of an extension (permission-checked).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_system_pim_list_calendars(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<Vec<SystemCalendar>, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let perm_result =
+        PermissionManager::check_calendar_permission(&state, &extension_id, CalendarAction::Read)
+            .await;
+    if let Err(ref e) = perm_result {
+        emit_permission_prompt_if_needed(&app_handle, e);
+    }
+    perm_result?;
+
+    super::calendars(&app_handle)
+        .await
+        .map_err(to_extension_error)
+}
+
+/// Events between `from` and `to` (RFC 3339, at most a year apart) from
+/// the given calendars or all of them, sorted by start.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn extension_system_pim_list_events(
+    app_handle: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    from: String,
+    to: String,
+    calendar_ids: Option<Vec<String>>,
+    // Optional parameters for iframe mode (verified by frontend via origin)
+    public_key: Option<String>,
+    name: Option<String>,
+) -> Result<Vec<SystemEvent>, ExtensionError> {
+    let extension_id = resolve_extension_id(&window, &state, public_key, name)?;
+    let range = parse_range(&from, &to).map_err(to_extension_error)?;
+    let perm_result =
+        PermissionManager::check_calendar_permission(&state, &extension_id, CalendarAction::Read)
+            .await;
+    if let Err(ref e) = perm_result {
+        emit_permission_prompt_if_needed(&app_handle, e);
+    }
+    perm_result?;
+
+    super::events(&app_handle, range, calendar_ids)
+        .await
+        .map_err(to_extension_error)
+}
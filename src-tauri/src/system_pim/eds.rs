@@ -0,0 +1,266 @@
+//! Contacts and calendars of Evolution Data Server over the session bus.
+//!
+//! The source registry lists the configured address books and calendars
+//! (each with a key-file description); every source is opened through its
+//! factory, which hands out a per-client object on the backend's bus name.
+
+use super::normalize::{contact_from_vcard, event_from_ical};
+use super::types::{SystemCalendar, SystemContact, SystemEvent, SystemPimStatus};
+use super::{EventRange, SystemPimError};
+use std::collections::HashMap;
+use tauri::AppHandle;
+use time::{OffsetDateTime, UtcOffset};
+use zbus::fdo::ObjectManagerProxy;
+use zbus::zvariant::{OwnedValue, Value};
+use zbus::{Connection, Proxy};
+
+const SOURCES_SERVICE: &str = "org.gnome.evolution.dataserver.Sources5";
+const SOURCES_PATH: &str = "/org/gnome/evolution/dataserver/SourceManager";
+const SOURCE_INTERFACE: &str = "org.gnome.evolution.dataserver.Source";
+
+const ADDRESS_BOOK_SERVICE: &str = "org.gnome.evolution.dataserver.AddressBook10";
+const ADDRESS_BOOK_FACTORY_PATH: &str = "/org/gnome/evolution/dataserver/AddressBookFactory";
+const ADDRESS_BOOK_FACTORY_INTERFACE: &str = "org.gnome.evolution.dataserver.AddressBookFactory";
+const ADDRESS_BOOK_INTERFACE: &str = "org.gnome.evolution.dataserver.AddressBook";
+
+const CALENDAR_SERVICE: &str = "org.gnome.evolution.dataserver.Calendar8";
+const CALENDAR_FACTORY_PATH: &str = "/org/gnome/evolution/dataserver/CalendarFactory";
+const CALENDAR_FACTORY_INTERFACE: &str = "org.gnome.evolution.dataserver.CalendarFactory";
+const CALENDAR_INTERFACE: &str = "org.gnome.evolution.dataserver.Calendar";
+
+/// Book query matching every contact
+const ALL_CONTACTS: &str = "(contains \"x-evolution-any-field\" \"\")";
+
+/// An address book or calendar from the source registry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct EdsSource {
+    pub uid: String,
+    pub name: String,
+    pub enabled: bool,
+    pub address_book: bool,
+    pub calendar: bool,
+    pub color: Option<String>,
+}
+
+/// Parses the key file a source describes itself with.
+pub(super) fn parse_source(uid: &str, data: &str) -> EdsSource {
+    let mut source = EdsSource {
+        uid: uid.to_string(),
+        name: uid.to_string(),
+        enabled: true,
+        ..Default::default()
+    };
+    let mut section = "";
+    for line in data.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name;
+            source.address_book |= section == "Address Book";
+            source.calendar |= section == "Calendar";
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match (section, key.trim(), value.trim()) {
+            ("Data Source", "DisplayName", name) if !name.is_empty() => {
+                source.name = name.to_string()
+            }
+            ("Data Source", "Enabled", enabled) => source.enabled = enabled != "false",
+            ("Calendar", "Color", color) if color.starts_with('#') => {
+                source.color = Some(color.to_string())
+            }
+            _ => {}
+        }
+    }
+    source
+}
+
+fn string_property(properties: &HashMap<String, OwnedValue>, name: &str) -> Option<String> {
+    match &**properties.get(name)? {
+        Value::Str(value) => Some(value.as_str().to_string()),
+        _ => None,
+    }
+}
+
+async fn sources(conn: &Connection) -> Result<Vec<EdsSource>, SystemPimError> {
+    let manager = ObjectManagerProxy::builder(conn)
+        .destination(SOURCES_SERVICE)?
+        .path(SOURCES_PATH)?
+        .build()
+        .await?;
+    let mut sources = Vec::new();
+    for interfaces in manager.get_managed_objects().await?.values() {
+        let Some(properties) = interfaces
+            .iter()
+            .find(|(name, _)| name.as_str() == SOURCE_INTERFACE)
+            .map(|(_, properties)| properties)
+        else {
+            continue;
+        };
+        if let (Some(uid), Some(data)) = (
+            string_property(properties, "UID"),
+            string_property(properties, "Data"),
+        ) {
+            let source = parse_source(&uid, &data);
+            if source.enabled {
+                sources.push(source);
+            }
+        }
+    }
+    sources.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(sources)
+}
+
+/// Opens a source through its factory.
+async fn open(
+    conn: &Connection,
+    service: &'static str,
+    factory_path: &'static str,
+    factory_interface: &'static str,
+    method: &'static str,
+    interface: &'static str,
+    uid: &str,
+) -> Result<Proxy<'static>, SystemPimError> {
+    let factory = Proxy::new(conn, service, factory_path, factory_interface).await?;
+    let (path, bus_name): (String, String) = factory.call(method, &(uid,)).await?;
+    let proxy = Proxy::new(conn, bus_name, path, interface).await?;
+    proxy.call_method("Open", &()).await?;
+    Ok(proxy)
+}
+
+async fn close(proxy: &Proxy<'static>) {
+    let _ = proxy.call_method("Close", &()).await;
+}
+
+fn eds_time(time: OffsetDateTime) -> String {
+    let time = time.to_offset(UtcOffset::UTC);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        time.year(),
+        u8::from(time.month()),
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second()
+    )
+}
+
+pub async fn status(_app: &AppHandle) -> SystemPimStatus {
+    let unavailable = |reason: String| SystemPimStatus {
+        reason: Some(reason),
+        ..Default::default()
+    };
+    let conn = match Connection::session().await {
+        Ok(conn) => conn,
+        Err(e) => return unavailable(format!("Session bus unavailable: {e}")),
+    };
+    match sources(&conn).await {
+        Ok(sources) => {
+            let contacts = sources.iter().any(|s| s.address_book);
+            let calendar = sources.iter().any(|s| s.calendar);
+            SystemPimStatus {
+                contacts,
+                calendar,
+                reason: (!contacts && !calendar)
+                    .then(|| "No address books or calendars configured".to_string()),
+            }
+        }
+        Err(e) => unavailable(format!("Evolution Data Server is not available: {e}")),
+    }
+}
+
+pub async fn contacts(_app: &AppHandle) -> Result<Vec<SystemContact>, SystemPimError> {
+    let conn = Connection::session().await?;
+    let mut contacts = Vec::new();
+    for source in sources(&conn).await?.iter().filter(|s| s.address_book) {
+        // An unreachable remote book must not hide the local ones
+        let book = match open(
+            &conn,
+            ADDRESS_BOOK_SERVICE,
+            ADDRESS_BOOK_FACTORY_PATH,
+            ADDRESS_BOOK_FACTORY_INTERFACE,
+            "OpenAddressBook",
+            ADDRESS_BOOK_INTERFACE,
+            &source.uid,
+        )
+        .await
+        {
+            Ok(book) => book,
+            Err(e) => {
+                eprintln!("[SystemPim] Skipping address book '{}': {e}", source.name);
+                continue;
+            }
+        };
+        let vcards: Result<Vec<String>, _> = book.call("GetContactList", &(ALL_CONTACTS,)).await;
+        close(&book).await;
+        contacts.extend(
+            vcards?
+                .iter()
+                .filter_map(|vcard| contact_from_vcard(vcard, Some(&source.name))),
+        );
+    }
+    Ok(contacts)
+}
+
+pub async fn calendars(_app: &AppHandle) -> Result<Vec<SystemCalendar>, SystemPimError> {
+    let conn = Connection::session().await?;
+    Ok(sources(&conn)
+        .await?
+        .into_iter()
+        .filter(|s| s.calendar)
+        .map(|s| SystemCalendar {
+            id: s.uid,
+            name: s.name,
+            color: s.color,
+        })
+        .collect())
+}
+
+pub async fn events(
+    _app: &AppHandle,
+    range: EventRange,
+    calendar_ids: Option<Vec<String>>,
+) -> Result<Vec<SystemEvent>, SystemPimError> {
+    let conn = Connection::session().await?;
+    let query = format!(
+        "(occur-in-time-range? (make-time \"{}\") (make-time \"{}\"))",
+        eds_time(range.from),
+        eds_time(range.to)
+    );
+    let selected = |s: &&EdsSource| {
+        s.calendar
+            && calendar_ids
+                .as_ref()
+                .is_none_or(|ids| ids.iter().any(|id| id == &s.uid))
+    };
+
+    let mut events = Vec::new();
+    for source in sources(&conn).await?.iter().filter(selected) {
+        let calendar = match open(
+            &conn,
+            CALENDAR_SERVICE,
+            CALENDAR_FACTORY_PATH,
+            CALENDAR_FACTORY_INTERFACE,
+            "OpenCalendar",
+            CALENDAR_INTERFACE,
+            &source.uid,
+        )
+        .await
+        {
+            Ok(calendar) => calendar,
+            Err(e) => {
+                eprintln!("[SystemPim] Skipping calendar '{}': {e}", source.name);
+                continue;
+            }
+        };
+        let objects: Result<Vec<String>, _> =
+            calendar.call("GetObjectList", &(query.as_str(),)).await;
+        close(&calendar).await;
+        events.extend(
+            objects?
+                .iter()
+                .filter_map(|object| event_from_ical(object, &source.uid)),
+        );
+    }
+    Ok(events)
+}
@@ -0,0 +1,48 @@
+// src-tauri/src/system_pim/error.rs
+//!
+//! System Contacts and Calendar Error Types
+//!
+
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Error, Serialize)]
+#[serde(tag = "type", content = "details")]
+pub enum SystemPimError {
+    /// No supported contacts/calendar API on this platform
+    #[error("Not supported on this platform: {reason}")]
+    Unsupported { reason: String },
+
+    /// The platform API exists but can't be reached (service not running,
+    /// no address book configured, ...)
+    #[error("System data unavailable: {reason}")]
+    Unavailable { reason: String },
+
+    /// The user denied the OS-level permission
+    #[error("Access to {resource} was denied by the system")]
+    AccessDenied { resource: String },
+
+    #[error("Invalid request: {reason}")]
+    InvalidRequest { reason: String },
+
+    #[error("Internal error: {reason}")]
+    Internal { reason: String },
+}
+
+#[cfg(target_os = "linux")]
+impl From<zbus::Error> for SystemPimError {
+    fn from(e: zbus::Error) -> Self {
+        SystemPimError::Unavailable {
+            reason: e.to_string(),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl From<zbus::fdo::Error> for SystemPimError {
+    fn from(e: zbus::fdo::Error) -> Self {
+        SystemPimError::Unavailable {
+            reason: e.to_string(),
+        }
+    }
+}
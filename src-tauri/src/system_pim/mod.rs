@@ -0,0 +1,81 @@
+// src-tauri/src/system_pim/mod.rs
+//!
+//! System Contacts and Calendars (read-only)
+//!
+//! Reads the contacts and calendars the operating system already manages,
+//! so extensions (personal CRM, scheduling) can link vault records to them
+//! without raw OS API access. Everything is returned in the normalized
+//! shapes of [`types`]; nothing is ever written back.
+//!
+//! - Linux: Evolution Data Server over the session bus (GNOME, incl.
+//!   accounts added through GNOME Online Accounts)
+//! - Android: the native `SystemPimPlugin` (copied from `.github/` into the
+//!   generated Android project at build time), which queries
+//!   `ContactsContract` / `CalendarContract` and asks for the
+//!   READ_CONTACTS / READ_CALENDAR runtime permission on first use
+//! - Other platforms report both as unavailable; macOS and Windows need
+//!   entitlements/package identity the desktop build doesn't have yet
+//!
+//! Extensions need the `contacts` / `calendar` permission (`read`).
+
+#[cfg(target_os = "android")]
+mod android;
+#[cfg(target_os = "linux")]
+mod eds;
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+mod unsupported;
+
+#[cfg(target_os = "android")]
+use android as platform;
+#[cfg(target_os = "linux")]
+use eds as platform;
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+use unsupported as platform;
+
+pub mod commands;
+pub mod error;
+pub mod normalize;
+pub mod types;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(target_os = "android")]
+pub use android::init;
+pub use error::SystemPimError;
+
+use tauri::AppHandle;
+use time::OffsetDateTime;
+use types::{SystemCalendar, SystemContact, SystemEvent, SystemPimStatus};
+
+/// Time span of an event query, `from` inclusive, `to` exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventRange {
+    pub from: OffsetDateTime,
+    pub to: OffsetDateTime,
+}
+
+/// What this platform can provide right now.
+pub async fn status(app: &AppHandle) -> SystemPimStatus {
+    platform::status(app).await
+}
+
+/// All contacts of all enabled address books.
+pub async fn contacts(app: &AppHandle) -> Result<Vec<SystemContact>, SystemPimError> {
+    platform::contacts(app).await
+}
+
+pub async fn calendars(app: &AppHandle) -> Result<Vec<SystemCalendar>, SystemPimError> {
+    platform::calendars(app).await
+}
+
+/// Events overlapping `range`, from the given calendars or all of them.
+pub async fn events(
+    app: &AppHandle,
+    range: EventRange,
+    calendar_ids: Option<Vec<String>>,
+) -> Result<Vec<SystemEvent>, SystemPimError> {
+    let mut events = platform::events(app, range, calendar_ids).await?;
+    events.sort_by(|a, b| a.start.cmp(&b.start).then_with(|| a.id.cmp(&b.id)));
+    Ok(events)
+}
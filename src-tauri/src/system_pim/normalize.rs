@@ -0,0 +1,208 @@
+// src-tauri/src/system_pim/normalize.rs
+//!
+//! Conversion of platform records into the normalized types
+//!
+//! vCard/iCalendar text (Evolution Data Server) goes through the DAV
+//! content-line parser; epoch timestamps (Android) are formatted here.
+
+use super::types::{SystemContact, SystemContactPage, SystemEvent};
+use crate::dav::parsing::{parse_object_lines, ContentLine};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+fn non_empty(value: String) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// `19850412` / `1985-04-12` / `--0412` (with optional time) to
+/// `1985-04-12` / `--04-12`.
+pub fn normalize_date(value: &str) -> Option<String> {
+    let date = value.split('T').next()?.replace('-', "");
+    let digits = date.chars().all(|c| c.is_ascii_digit());
+    match (value.starts_with("--"), date.len()) {
+        (true, 4) if digits => Some(format!("--{}-{}", &date[..2], &date[2..])),
+        (false, 8) if digits => Some(format!("{}-{}-{}", &date[..4], &date[4..6], &date[6..])),
+        _ => None,
+    }
+}
+
+/// A vCard as [`SystemContact`]; `None` without `UID` or any name.
+pub fn contact_from_vcard(vcard: &str, address_book: Option<&str>) -> Option<SystemContact> {
+    let (component, lines) = parse_object_lines(vcard)?;
+    if component != "VCARD" {
+        return None;
+    }
+
+    let mut contact = SystemContact {
+        address_book: address_book.map(str::to_string),
+        ..Default::default()
+    };
+    for line in &lines {
+        match line.name.as_str() {
+            "UID" => contact.id = line.text().trim().to_string(),
+            "FN" => contact.display_name = line.text().trim().to_string(),
+            "N" => {
+                let mut parts = line.components().into_iter();
+                contact.family_name = parts.next().and_then(non_empty);
+                contact.given_name = parts.next().and_then(non_empty);
+            }
+            "ORG" => {
+                contact.organization = line.components().into_iter().next().and_then(non_empty)
+            }
+            "EMAIL" => contact.emails.extend(non_empty(line.text())),
+            "TEL" => {
+                let text = line.text();
+                let number = text.strip_prefix("tel:").unwrap_or(&text).to_string();
+                contact.phones.extend(non_empty(number));
+            }
+            "BDAY" => contact.birthday = normalize_date(line.value.trim()),
+            "NOTE" => contact.note = non_empty(line.text()),
+            _ => {}
+        }
+    }
+
+    if contact.display_name.is_empty() {
+        let name = [
+            contact.given_name.as_deref(),
+            contact.family_name.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+        contact.display_name = non_empty(name)
+            .or_else(|| contact.organization.clone())
+            .or_else(|| contact.emails.first().cloned())?;
+    }
+    (!contact.id.is_empty()).then_some(contact)
+}
+
+/// `DTSTART`/`DTEND` as (value, all-day, time zone).
+fn ical_time(line: &ContentLine) -> Option<(String, bool, Option<String>)> {
+    let value = line.value.trim();
+    if line.param("VALUE") == Some("DATE") || (value.len() == 8 && !value.contains('T')) {
+        return Some((normalize_date(value)?, true, None));
+    }
+
+    let (date, time) = value.split_once('T')?;
+    let utc = time.ends_with('Z');
+    let time = time.trim_end_matches('Z');
+    if !time.is_ascii() || time.len() < 6 || !time[..6].chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let formatted = format!(
+        "{}T{}:{}:{}{}",
+        normalize_date(date)?,
+        &time[..2],
+        &time[2..4],
+        &time[4..6],
+        if utc { "Z" } else { "" }
+    );
+    let zone = if utc {
+        None
+    } else {
+        line.param("TZID").map(str::to_string)
+    };
+    Some((formatted, false, zone))
+}
+
+/// A `VEVENT` as [`SystemEvent`]; `None` without `UID` or `DTSTART`.
+///
+/// Occurrences of recurring events are not expanded, the rule is passed on
+/// in `recurrence`. Detached instances (`RECURRENCE-ID`) get their own id.
+pub fn event_from_ical(ical: &str, calendar_id: &str) -> Option<SystemEvent> {
+    let (component, lines) = parse_object_lines(ical)?;
+    if component != "VEVENT" {
+        return None;
+    }
+
+    let mut event = SystemEvent {
+        calendar_id: calendar_id.to_string(),
+        ..Default::default()
+    };
+    let mut start = None;
+    let mut recurrence_id = None;
+    for line in &lines {
+        match line.name.as_str() {
+            "UID" => event.id = line.text().trim().to_string(),
+            "SUMMARY" => event.title = line.text().trim().to_string(),
+            "DESCRIPTION" => event.description = non_empty(line.text()),
+            "LOCATION" => event.location = non_empty(line.text()),
+            "DTSTART" => start = ical_time(line),
+            "DTEND" => event.end = ical_time(line).map(|(value, _, _)| value),
+            "RRULE" => event.recurrence = non_empty(line.value.clone()),
+            "RECURRENCE-ID" => recurrence_id = non_empty(line.value.clone()),
+            _ => {}
+        }
+    }
+
+    let (start, all_day, time_zone) = start?;
+    event.start = start;
+    event.all_day = all_day;
+    event.time_zone = time_zone;
+    if let Some(recurrence_id) = recurrence_id {
+        event.id = format!("{}:{recurrence_id}", event.id);
+    }
+    (!event.id.is_empty()).then_some(event)
+}
+
+/// Milliseconds since the epoch as RFC 3339 (UTC), or as `YYYY-MM-DD` for
+/// all-day events, which platforms store as UTC midnight.
+pub fn from_epoch_millis(millis: i64, all_day: bool) -> Option<String> {
+    let time = OffsetDateTime::from_unix_timestamp_nanos(i128::from(millis) * 1_000_000).ok()?;
+    if all_day {
+        let date = time.date();
+        return Some(format!(
+            "{:04}-{:02}-{:02}",
+            date.year(),
+            u8::from(date.month()),
+            date.day()
+        ));
+    }
+    time.format(&Rfc3339).ok()
+}
+
+fn matches_query(contact: &SystemContact, query: &str) -> bool {
+    let needle = query.to_lowercase();
+    let text_match = [
+        Some(&contact.display_name),
+        contact.given_name.as_ref(),
+        contact.family_name.as_ref(),
+        contact.organization.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .chain(&contact.emails)
+    .any(|value| value.to_lowercase().contains(&needle));
+
+    let digits: String = query.chars().filter(char::is_ascii_digit).collect();
+    text_match
+        || (!digits.is_empty()
+            && contact.phones.iter().any(|phone| {
+                phone
+                    .chars()
+                    .filter(char::is_ascii_digit)
+                    .collect::<String>()
+                    .contains(&digits)
+            }))
+}
+
+/// Filters by `query` (names, organization, emails, phone digits), sorts by
+/// display name and cuts out one page.
+pub fn paginate(
+    mut contacts: Vec<SystemContact>,
+    query: Option<&str>,
+    offset: usize,
+    limit: usize,
+) -> SystemContactPage {
+    if let Some(query) = query.map(str::trim).filter(|q| !q.is_empty()) {
+        contacts.retain(|c| matches_query(c, query));
+    }
+    contacts.sort_by_cached_key(|c| (c.display_name.to_lowercase(), c.id.clone()));
+    let total = contacts.len() as u32;
+    SystemContactPage {
+        contacts: contacts.into_iter().skip(offset).take(limit).collect(),
+        total,
+    }
+}
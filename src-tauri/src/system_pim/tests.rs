@@ -0,0 +1,193 @@
+//! Tests for the normalization of system contacts and events
+
+use super::commands::parse_range;
+use super::normalize::{
+    contact_from_vcard, event_from_ical, from_epoch_millis, normalize_date, paginate,
+};
+use super::types::SystemContact;
+
+const VCARD: &str = "BEGIN:VCARD\r\n\
+                     VERSION:3.0\r\n\
+                     UID:pas-id-1\r\n\
+                     N:Example;Alice;;;\r\n\
+                     FN:Alice Example\r\n\
+                     ORG:Example Corp\\; Ltd;Sales\r\n\
+                     EMAIL;TYPE=WORK:alice@work.example\r\n\
+                     item1.EMAIL:alice@home.example\r\n\
+                     TEL;TYPE=\"cell,voice\":tel:+49 170 1234567\r\n\
+                     BDAY:19850412\r\n\
+                     NOTE:Met at the\\nconference\r\n\
+                     END:VCARD\r\n";
+
+fn contact(id: &str, name: &str, phone: Option<&str>) -> SystemContact {
+    SystemContact {
+        id: id.to_string(),
+        display_name: name.to_string(),
+        phones: phone.map(str::to_string).into_iter().collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_contact_from_vcard() {
+    let contact = contact_from_vcard(VCARD, Some("Personal")).unwrap();
+    assert_eq!(contact.id, "pas-id-1");
+    assert_eq!(contact.display_name, "Alice Example");
+    assert_eq!(contact.given_name.as_deref(), Some("Alice"));
+    assert_eq!(contact.family_name.as_deref(), Some("Example"));
+    assert_eq!(contact.organization.as_deref(), Some("Example Corp; Ltd"));
+    assert_eq!(
+        contact.emails,
+        vec!["alice@work.example", "alice@home.example"]
+    );
+    assert_eq!(contact.phones, vec!["+49 170 1234567"]);
+    assert_eq!(contact.birthday.as_deref(), Some("1985-04-12"));
+    assert_eq!(contact.note.as_deref(), Some("Met at the\nconference"));
+    assert_eq!(contact.address_book.as_deref(), Some("Personal"));
+}
+
+#[test]
+fn test_contact_from_vcard_falls_back_for_missing_name() {
+    let vcard = "BEGIN:VCARD\nUID:2\nN:;Bob;;;\nEND:VCARD\n";
+    assert_eq!(contact_from_vcard(vcard, None).unwrap().display_name, "Bob");
+
+    let vcard = "BEGIN:VCARD\nUID:3\nEMAIL:only@mail.example\nEND:VCARD\n";
+    assert_eq!(
+        contact_from_vcard(vcard, None).unwrap().display_name,
+        "only@mail.example"
+    );
+
+    // Neither a name nor an id
+    assert!(contact_from_vcard("BEGIN:VCARD\nUID:4\nEND:VCARD\n", None).is_none());
+    assert!(contact_from_vcard("BEGIN:VCARD\nFN:Nobody\nEND:VCARD\n", None).is_none());
+    assert!(contact_from_vcard("BEGIN:VCALENDAR\nEND:VCALENDAR\n", None).is_none());
+}
+
+#[test]
+fn test_event_from_ical() {
+    let ical = "BEGIN:VEVENT\r\n\
+                UID:event-1\r\n\
+                SUMMARY:Planning\\, Q3\r\n\
+                LOCATION:Room 1\r\n\
+                DTSTART;TZID=Europe/Berlin:20261020T090000\r\n\
+                DTEND;TZID=Europe/Berlin:20261020T100000\r\n\
+                RRULE:FREQ=WEEKLY;BYDAY=TU\r\n\
+                END:VEVENT\r\n";
+    let event = event_from_ical(ical, "work").unwrap();
+    assert_eq!(event.id, "event-1");
+    assert_eq!(event.calendar_id, "work");
+    assert_eq!(event.title, "Planning, Q3");
+    assert_eq!(event.location.as_deref(), Some("Room 1"));
+    assert_eq!(event.start, "2026-10-20T09:00:00");
+    assert_eq!(event.end.as_deref(), Some("2026-10-20T10:00:00"));
+    assert!(!event.all_day);
+    assert_eq!(event.time_zone.as_deref(), Some("Europe/Berlin"));
+    assert_eq!(event.recurrence.as_deref(), Some("FREQ=WEEKLY;BYDAY=TU"));
+}
+
+#[test]
+fn test_event_from_ical_all_day_utc_and_detached_instance() {
+    let ical = "BEGIN:VEVENT\nUID:holiday\nDTSTART;VALUE=DATE:20261225\nEND:VEVENT\n";
+    let event = event_from_ical(ical, "cal").unwrap();
+    assert_eq!(event.start, "2026-12-25");
+    assert!(event.all_day);
+    assert_eq!(event.time_zone, None);
+
+    let ical = "BEGIN:VEVENT\nUID:standup\nRECURRENCE-ID:20261021T080000Z\n\
+                DTSTART:20261021T083000Z\nEND:VEVENT\n";
+    let event = event_from_ical(ical, "cal").unwrap();
+    assert_eq!(event.id, "standup:20261021T080000Z");
+    assert_eq!(event.start, "2026-10-21T08:30:00Z");
+    assert_eq!(event.time_zone, None);
+
+    assert!(event_from_ical("BEGIN:VEVENT\nUID:x\nEND:VEVENT\n", "cal").is_none());
+}
+
+#[test]
+fn test_normalize_date() {
+    assert_eq!(normalize_date("19850412").as_deref(), Some("1985-04-12"));
+    assert_eq!(normalize_date("1985-04-12").as_deref(), Some("1985-04-12"));
+    assert_eq!(
+        normalize_date("1985-04-12T00:00:00Z").as_deref(),
+        Some("1985-04-12")
+    );
+    assert_eq!(normalize_date("--0412").as_deref(), Some("--04-12"));
+    assert_eq!(normalize_date("April 12"), None);
+    assert_eq!(normalize_date("1985"), None);
+}
+
+#[test]
+fn test_from_epoch_millis() {
+    assert_eq!(
+        from_epoch_millis(1_792_486_800_000, false).as_deref(),
+        Some("2026-10-20T09:00:00Z")
+    );
+    assert_eq!(
+        from_epoch_millis(1_792_454_400_000, true).as_deref(),
+        Some("2026-10-20")
+    );
+}
+
+#[test]
+fn test_paginate_filters_sorts_and_pages() {
+    let contacts = vec![
+        contact("1", "charlie", None),
+        contact("2", "Alice", Some("+49 (170) 123")),
+        contact("3", "bob", None),
+    ];
+
+    let page = paginate(contacts.clone(), None, 1, 10);
+    assert_eq!(page.total, 3);
+    let names: Vec<_> = page
+        .contacts
+        .iter()
+        .map(|c| c.display_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["bob", "charlie"]);
+
+    let page = paginate(contacts.clone(), Some("  ALI "), 0, 10);
+    assert_eq!(page.total, 1);
+    assert_eq!(page.contacts[0].id, "2");
+
+    // Phone numbers match on digits only
+    let page = paginate(contacts.clone(), Some("170123"), 0, 10);
+    assert_eq!(page.contacts[0].id, "2");
+
+    let page = paginate(contacts, Some("nobody"), 0, 10);
+    assert_eq!(page.total, 0);
+    assert!(page.contacts.is_empty());
+}
+
+#[test]
+fn test_parse_range() {
+    let range = parse_range("2026-10-01T00:00:00Z", "2026-11-01T00:00:00+01:00").unwrap();
+    assert!(range.to > range.from);
+
+    assert!(parse_range("2026-10-01", "2026-11-01T00:00:00Z").is_err());
+    assert!(parse_range("2026-10-01T00:00:00Z", "2026-10-01T00:00:00Z").is_err());
+    assert!(parse_range("2026-01-01T00:00:00Z", "2027-06-01T00:00:00Z").is_err());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_parse_eds_source() {
+    let data = "[Data Source]\n\
+                DisplayName=Work\n\
+                Enabled=true\n\
+                Parent=local-stub\n\
+                \n\
+                [Calendar]\n\
+                BackendName=local\n\
+                Color=#3465a4\n";
+    let source = super::eds::parse_source("work-uid", data);
+    assert_eq!(source.name, "Work");
+    assert!(source.enabled);
+    assert!(source.calendar);
+    assert!(!source.address_book);
+    assert_eq!(source.color.as_deref(), Some("#3465a4"));
+
+    let source = super::eds::parse_source("old", "[Data Source]\nEnabled=false\n[Address Book]\n");
+    assert_eq!(source.name, "old");
+    assert!(!source.enabled);
+    assert!(source.address_book);
+}
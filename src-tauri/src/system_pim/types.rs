@@ -0,0 +1,111 @@
+// src-tauri/src/system_pim/types.rs
+//!
+//! System Contacts and Calendar Types
+//!
+//! Normalized shapes, identical on every platform. Platform-specific
+//! fields are dropped rather than passed through.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Which parts of the system PIM data this platform can provide.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SystemPimStatus {
+    pub contacts: bool,
+    pub calendar: bool,
+    /// Why contacts or calendars are unavailable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub reason: Option<String>,
+}
+
+/// A contact of the system address book.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SystemContact {
+    /// Platform id, stable on this device only
+    pub id: String,
+    pub display_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub given_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub family_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub organization: Option<String>,
+    #[serde(default)]
+    pub emails: Vec<String>,
+    #[serde(default)]
+    pub phones: Vec<String>,
+    /// `YYYY-MM-DD`, or `--MM-DD` without a year
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub birthday: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub note: Option<String>,
+    /// Name of the address book (account) the contact belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub address_book: Option<String>,
+}
+
+/// One page of `extension_system_pim_list_contacts`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SystemContactPage {
+    pub contacts: Vec<SystemContact>,
+    /// Matching contacts over all pages
+    pub total: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SystemCalendar {
+    pub id: String,
+    pub name: String,
+    /// `#rrggbb`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub color: Option<String>,
+}
+
+/// An event (or, where the platform expands them, one occurrence of a
+/// recurring event).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SystemEvent {
+    pub id: String,
+    pub calendar_id: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub location: Option<String>,
+    /// RFC 3339; `YYYY-MM-DD` for all-day events; without offset for local
+    /// times in `time_zone`
+    pub start: String,
+    /// Same format as `start`; all-day ends are exclusive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub end: Option<String>,
+    pub all_day: bool,
+    /// IANA zone of `start`/`end` if they carry no offset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub time_zone: Option<String>,
+    /// `RRULE` of a recurring event whose occurrences were not expanded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub recurrence: Option<String>,
+}
@@ -0,0 +1,36 @@
+//! Platforms without a supported contacts/calendar API.
+
+use super::types::{SystemCalendar, SystemContact, SystemEvent, SystemPimStatus};
+use super::{EventRange, SystemPimError};
+use tauri::AppHandle;
+
+const REASON: &str = "No system contacts or calendar API on this platform";
+
+fn unsupported() -> SystemPimError {
+    SystemPimError::Unsupported {
+        reason: REASON.to_string(),
+    }
+}
+
+pub async fn status(_app: &AppHandle) -> SystemPimStatus {
+    SystemPimStatus {
+        reason: Some(REASON.to_string()),
+        ..Default::default()
+    }
+}
+
+pub async fn contacts(_app: &AppHandle) -> Result<Vec<SystemContact>, SystemPimError> {
+    Err(unsupported())
+}
+
+pub async fn calendars(_app: &AppHandle) -> Result<Vec<SystemCalendar>, SystemPimError> {
+    Err(unsupported())
+}
+
+pub async fn events(
+    _app: &AppHandle,
+    _range: EventRange,
+    _calendar_ids: Option<Vec<String>>,
+) -> Result<Vec<SystemEvent>, SystemPimError> {
+    Err(unsupported())
+}
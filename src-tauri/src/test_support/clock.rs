@@ -0,0 +1,67 @@
+// src-tauri/src/test_support/clock.rs
+//!
+//! Controllable clock for `HlcService`
+
+use std::cell::Cell;
+use std::time::Duration;
+use uhlc::NTP64;
+
+/// Start time of a fresh [`TestClock`]: 2024-01-01T00:00:00Z
+pub const TEST_EPOCH: Duration = Duration::from_secs(1_704_067_200);
+
+thread_local! {
+    static NOW: Cell<Option<Duration>> = const { Cell::new(None) };
+}
+
+/// Clock function for `HLCBuilder::with_clock`. Returns the time of the
+/// thread's [`TestClock`], or the system time if none is installed.
+pub fn test_clock() -> NTP64 {
+    match NOW.with(Cell::get) {
+        Some(now) => NTP64::from(now),
+        None => uhlc::system_time_clock(),
+    }
+}
+
+/// Handle to the thread's simulated time. The clock stands still until the
+/// test moves it; dropping the handle returns to the system time.
+#[derive(Debug)]
+pub struct TestClock {
+    _not_send: std::marker::PhantomData<*const ()>,
+}
+
+impl TestClock {
+    /// Installs the clock for the current thread, starting at [`TEST_EPOCH`]
+    pub fn install() -> Self {
+        Self::install_at(TEST_EPOCH)
+    }
+
+    /// Installs the clock for the current thread at `since_unix_epoch`
+    pub fn install_at(since_unix_epoch: Duration) -> Self {
+        NOW.with(|now| now.set(Some(since_unix_epoch)));
+        TestClock {
+            _not_send: std::marker::PhantomData,
+        }
+    }
+
+    /// Current simulated time since the Unix epoch
+    pub fn now(&self) -> Duration {
+        NOW.with(Cell::get).unwrap_or(TEST_EPOCH)
+    }
+
+    /// Moves the clock to `since_unix_epoch`, also backwards (the HLC
+    /// itself never goes backwards, which tests can check this way)
+    pub fn set(&self, since_unix_epoch: Duration) {
+        NOW.with(|now| now.set(Some(since_unix_epoch)));
+    }
+
+    /// Moves the clock forward by `by`
+    pub fn advance(&self, by: Duration) {
+        self.set(self.now() + by);
+    }
+}
+
+impl Drop for TestClock {
+    fn drop(&mut self) {
+        NOW.with(|now| now.set(None));
+    }
+}
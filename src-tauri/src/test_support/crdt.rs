@@ -0,0 +1,142 @@
+// src-tauri/src/test_support/crdt.rs
+//!
+//! In-memory vaults with the CRDT system tables, for tests and benchmarks
+//! of the write, read and sync-apply paths
+
+use super::clock::test_clock;
+use super::db::{db_connection, open_in_memory_vault};
+use crate::crdt::commands::apply_remote_changes_to_db;
+use crate::crdt::hlc::HlcService;
+use crate::crdt::trigger::{ensure_crdt_columns, setup_triggers_for_table, DELETED_ROWS_TABLE};
+use crate::database::connection_context::ConnectionContext;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::database::DbConnection;
+use crate::table_names::{TABLE_CRDT_CONFIGS, TABLE_CRDT_CONFLICTS, TABLE_CRDT_DIRTY_TABLES};
+use rusqlite::Connection;
+use std::sync::Mutex;
+
+pub use crate::crdt::commands::RemoteColumnChange;
+
+/// A vault with the CRDT bookkeeping tables, holding connection and HLC the
+/// way `AppState` does
+pub struct CrdtVault {
+    pub db: DbConnection,
+    pub hlc: Mutex<HlcService>,
+}
+
+impl CrdtVault {
+    /// Opens an in-memory vault for `device_id` with the CRDT system tables
+    /// ([`open_crdt_connection`]). The HLC runs on the thread's test clock
+    /// (system time unless a `TestClock` is installed).
+    pub fn open(device_id: &str) -> Result<Self, DatabaseError> {
+        let hlc =
+            HlcService::with_clock(device_id, test_clock).map_err(|e| DatabaseError::HlcError {
+                reason: e.to_string(),
+            })?;
+        let conn = open_crdt_connection(hlc.clone())?;
+
+        Ok(Self {
+            db: db_connection(conn),
+            hlc: Mutex::new(hlc),
+        })
+    }
+
+    /// Creates a CRDT table in this vault, see [`create_crdt_table`]
+    pub fn create_crdt_table(
+        &self,
+        table_name: &str,
+        create_sql: &str,
+    ) -> Result<(), DatabaseError> {
+        with_connection(&self.db, |conn| {
+            create_crdt_table(conn, table_name, create_sql)
+        })
+    }
+
+    /// A fresh timestamp of this vault's HLC, formatted like the
+    /// `hlc_timestamp` of synced changes
+    pub fn new_hlc_timestamp(&self) -> Result<String, DatabaseError> {
+        let hlc = self.hlc.lock().map_err(|_| DatabaseError::MutexPoisoned {
+            reason: "hlc mutex poisoned".to_string(),
+        })?;
+        hlc.new_timestamp()
+            .map(|timestamp| timestamp.to_string())
+            .map_err(|e| DatabaseError::HlcError {
+                reason: e.to_string(),
+            })
+    }
+
+    /// Applies synced column changes like the sync loop does for changes
+    /// from local delivery
+    pub fn apply_remote_changes(
+        &self,
+        changes: Vec<RemoteColumnChange>,
+    ) -> Result<(), DatabaseError> {
+        let hlc = self.hlc.lock().map_err(|_| DatabaseError::MutexPoisoned {
+            reason: "hlc mutex poisoned".to_string(),
+        })?;
+        apply_remote_changes_to_db(&self.db, changes, None, Some(&*hlc))
+    }
+}
+
+/// Opens an in-memory vault ([`open_in_memory_vault`]) and creates the CRDT
+/// config, dirty-table, delete-log, conflict and trash tables, with triggers
+/// enabled
+pub fn open_crdt_connection(hlc: HlcService) -> Result<Connection, DatabaseError> {
+    let conn = open_in_memory_vault(hlc, ConnectionContext::new())?;
+    create_crdt_system_tables(&conn)?;
+    Ok(conn)
+}
+
+/// Creates the CRDT bookkeeping tables a vault's migrations create
+pub fn create_crdt_system_tables(conn: &Connection) -> Result<(), DatabaseError> {
+    conn.execute_batch(&format!(
+        "CREATE TABLE {TABLE_CRDT_CONFIGS} (key TEXT PRIMARY KEY, type TEXT NOT NULL, value TEXT NOT NULL);
+         INSERT INTO {TABLE_CRDT_CONFIGS} (key, type, value) VALUES ('triggers_enabled', 'system', '1');
+         CREATE TABLE {TABLE_CRDT_DIRTY_TABLES} (table_name TEXT PRIMARY KEY, last_modified TEXT);
+         CREATE TABLE {DELETED_ROWS_TABLE} (
+             id TEXT PRIMARY KEY NOT NULL,
+             table_name TEXT NOT NULL,
+             row_pks TEXT NOT NULL,
+             space_id TEXT,
+             haex_hlc TEXT,
+             haex_column_hlcs TEXT NOT NULL DEFAULT '{{}}'
+         );
+         CREATE TABLE {TABLE_CRDT_CONFLICTS} (
+             id TEXT PRIMARY KEY NOT NULL,
+             table_name TEXT NOT NULL,
+             conflict_type TEXT NOT NULL,
+             local_row_id TEXT NOT NULL,
+             remote_row_id TEXT NOT NULL,
+             local_row_data TEXT NOT NULL,
+             remote_row_data TEXT NOT NULL,
+             local_timestamp TEXT NOT NULL,
+             remote_timestamp TEXT NOT NULL,
+             conflict_key TEXT NOT NULL,
+             detected_at TEXT NOT NULL,
+             resolved INTEGER DEFAULT false NOT NULL,
+             resolution TEXT,
+             resolved_at TEXT
+         );"
+    ))?;
+    conn.execute_batch(include_str!("../../database/migrations/0017_add_trash.sql"))?;
+    conn.execute_batch(include_str!(
+        "../../database/migrations/0020_add_cascade_rules.sql"
+    ))?;
+    Ok(())
+}
+
+/// Runs `create_sql` and turns the table into a CRDT table: HLC columns plus
+/// dirty-tracking and delete-log triggers
+pub fn create_crdt_table(
+    conn: &Connection,
+    table_name: &str,
+    create_sql: &str,
+) -> Result<(), DatabaseError> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute_batch(create_sql)?;
+    ensure_crdt_columns(&tx, table_name)?;
+    setup_triggers_for_table(&tx, table_name, false)?;
+    tx.commit()?;
+    Ok(())
+}
@@ -0,0 +1,51 @@
+// src-tauri/src/test_support/db.rs
+//!
+//! In-memory SQLCipher vaults
+
+use crate::crdt::hlc::HlcService;
+use crate::database::connection_context::ConnectionContext;
+use crate::database::core::{install_tx_hlc_hooks, register_current_hlc_udf, register_uuid_udf};
+use crate::database::error::DatabaseError;
+use crate::database::DbConnection;
+use rusqlite::Connection;
+use std::sync::{Arc, Mutex};
+
+/// Key of the in-memory test vaults
+pub const TEST_VAULT_KEY: &str = "test-vault-key";
+
+/// Opens an encrypted in-memory database set up like `open_and_init_db`
+/// does for a vault file: keyed, foreign keys on, `gen_uuid()` and
+/// `current_hlc()` registered and the transaction HLC hooks installed.
+///
+/// The schema is empty; tests create the tables they need and call
+/// `crdt::trigger::setup_triggers_for_table` for CRDT tables.
+pub fn open_in_memory_vault(
+    hlc_service: HlcService,
+    context: ConnectionContext,
+) -> Result<Connection, DatabaseError> {
+    let conn = Connection::open_in_memory().map_err(|e| DatabaseError::ConnectionFailed {
+        path: ":memory:".to_string(),
+        reason: e.to_string(),
+    })?;
+
+    conn.pragma_update(None, "key", TEST_VAULT_KEY)
+        .map_err(|e| DatabaseError::PragmaError {
+            pragma: "key".to_string(),
+            reason: e.to_string(),
+        })?;
+    conn.pragma_update(None, "foreign_keys", "ON")
+        .map_err(|e| DatabaseError::PragmaError {
+            pragma: "foreign_keys".to_string(),
+            reason: e.to_string(),
+        })?;
+
+    register_uuid_udf(&conn)?;
+    register_current_hlc_udf(&conn, hlc_service, context.clone())?;
+    install_tx_hlc_hooks(&conn, context)?;
+    Ok(conn)
+}
+
+/// Wraps a connection the way `AppState::db` holds an open vault
+pub fn db_connection(conn: Connection) -> DbConnection {
+    DbConnection(Arc::new(Mutex::new(Some(conn))))
+}
@@ -0,0 +1,55 @@
+// src-tauri/src/test_support/ids.rs
+//!
+//! Deterministic UUIDs for `crate::ids::new_uuid`
+
+use std::cell::Cell;
+use uuid::Uuid;
+
+thread_local! {
+    static SEQUENCE: Cell<Option<(u64, u64)>> = const { Cell::new(None) };
+}
+
+/// Next UUID of the thread's sequence, `None` without [`DeterministicIds`]
+pub(crate) fn next_uuid() -> Option<Uuid> {
+    SEQUENCE.with(|sequence| {
+        let (seed, counter) = sequence.get()?;
+        sequence.set(Some((seed, counter + 1)));
+        Some(sequential_uuid(seed, counter + 1))
+    })
+}
+
+/// The `n`-th UUID of the sequence for `seed`. Formatted as a v4 UUID so
+/// code validating IDs accepts it; `seed` is the first 8 bytes, `n` the last.
+pub fn sequential_uuid(seed: u64, n: u64) -> Uuid {
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&seed.to_be_bytes());
+    bytes[8..].copy_from_slice(&n.to_be_bytes());
+    uuid::Builder::from_random_bytes(bytes).into_uuid()
+}
+
+/// While alive, `crate::ids::new_uuid` on this thread returns
+/// `sequential_uuid(seed, 1)`, `sequential_uuid(seed, 2)`, …
+#[derive(Debug)]
+pub struct DeterministicIds {
+    _not_send: std::marker::PhantomData<*const ()>,
+}
+
+impl DeterministicIds {
+    pub fn install(seed: u64) -> Self {
+        SEQUENCE.with(|sequence| sequence.set(Some((seed, 0))));
+        DeterministicIds {
+            _not_send: std::marker::PhantomData,
+        }
+    }
+
+    /// Number of UUIDs handed out so far
+    pub fn issued(&self) -> u64 {
+        SEQUENCE.with(|sequence| sequence.get().map_or(0, |(_, counter)| counter))
+    }
+}
+
+impl Drop for DeterministicIds {
+    fn drop(&mut self) {
+        SEQUENCE.with(|sequence| sequence.set(None));
+    }
+}
@@ -0,0 +1,28 @@
+// src-tauri/src/test_support/mod.rs
+//!
+//! Deterministic building blocks for tests (`test-support` feature)
+//!
+//! - [`clock`]: a controllable clock for `HlcService`
+//! - [`ids`]: deterministic UUIDs for everything created through
+//!   `crate::ids::new_uuid`
+//! - [`db`]: in-memory SQLCipher vaults with the same UDFs and hooks as
+//!   `open_and_init_db`
+//! - [`crdt`]: vaults with the CRDT system tables, for the CRDT write, read
+//!   and sync-apply paths
+//! - [`state`]: an `AppState` without a Tauri app
+//!
+//! Clock and UUID sequence are per thread, so parallel tests don't see each
+//! other's time or IDs. Async tests should use the default current-thread
+//! runtime of `#[tokio::test]`.
+//!
+//! Compiled for the crate's own tests and, with `--features test-support`,
+//! for integration tests in `tests/`.
+
+pub mod clock;
+pub mod crdt;
+pub mod db;
+pub mod ids;
+pub mod state;
+
+#[cfg(test)]
+mod tests;
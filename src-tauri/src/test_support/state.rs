@@ -0,0 +1,56 @@
+// src-tauri/src/test_support/state.rs
+//!
+//! `AppState` fixtures without a Tauri app
+
+use super::clock::test_clock;
+use super::db::open_in_memory_vault;
+use crate::crdt::hlc::HlcService;
+use crate::database::connection_context::ConnectionContext;
+use crate::database::error::DatabaseError;
+use crate::media_server::MediaServer;
+use crate::AppState;
+
+fn poisoned(what: &str) -> DatabaseError {
+    DatabaseError::MutexPoisoned {
+        reason: format!("{what} mutex poisoned"),
+    }
+}
+
+/// State of a freshly started app without an open vault
+pub async fn app_state() -> Result<AppState, DatabaseError> {
+    let media_server = MediaServer::start()
+        .await
+        .map_err(|e| DatabaseError::IoError {
+            path: "127.0.0.1".to_string(),
+            reason: format!("Failed to start media server: {e}"),
+        })?;
+    Ok(AppState::new(media_server))
+}
+
+/// State with an open in-memory vault (see [`open_in_memory_vault`]). The
+/// HLC runs on the thread's test clock, so timestamps only move when the
+/// test installs a [`super::clock::TestClock`] and advances it.
+pub async fn app_state_with_vault(device_id: &str) -> Result<AppState, DatabaseError> {
+    let state = app_state().await?;
+
+    let hlc =
+        HlcService::with_clock(device_id, test_clock).map_err(|e| DatabaseError::HlcError {
+            reason: e.to_string(),
+        })?;
+    let context = ConnectionContext::new();
+    let conn = open_in_memory_vault(hlc.clone(), context.clone())?;
+
+    *state.db.0.lock().map_err(|_| poisoned("db"))? = Some(conn);
+    *state.hlc.lock().map_err(|_| poisoned("hlc"))? = hlc;
+    *state
+        .connection_context
+        .lock()
+        .map_err(|_| poisoned("connection context"))? = context;
+    state
+        .context
+        .lock()
+        .map_err(|_| poisoned("application context"))?
+        .device_id = device_id.to_string();
+
+    Ok(state)
+}
@@ -0,0 +1,141 @@
+// src-tauri/src/test_support/tests.rs
+//!
+//! Tests for the test clock, deterministic IDs and vault fixtures
+
+use super::clock::{test_clock, TestClock, TEST_EPOCH};
+use super::crdt::{CrdtVault, RemoteColumnChange};
+use super::db::open_in_memory_vault;
+use super::ids::{sequential_uuid, DeterministicIds};
+use super::state::app_state_with_vault;
+use crate::crdt::hlc::HlcService;
+use crate::database::connection_context::ConnectionContext;
+use crate::database::core::{execute_with_crdt, select_with_crdt};
+use serde_json::json;
+use std::time::Duration;
+
+#[test]
+fn test_hlc_follows_test_clock() {
+    let clock = TestClock::install();
+    let hlc = HlcService::with_clock("device-a", test_clock).unwrap();
+
+    let first = hlc.new_timestamp().unwrap();
+    assert_eq!(first.get_time().to_duration(), TEST_EPOCH);
+
+    // A stopped clock still yields strictly increasing timestamps
+    let second = hlc.new_timestamp().unwrap();
+    assert!(second > first);
+
+    clock.advance(Duration::from_secs(60));
+    let later = hlc.new_timestamp().unwrap();
+    assert_eq!(
+        later.get_time().to_duration(),
+        TEST_EPOCH + Duration::from_secs(60)
+    );
+}
+
+#[test]
+fn test_hlc_timestamps_repeat_across_runs() {
+    let run = || {
+        let _clock = TestClock::install();
+        let hlc = HlcService::with_clock("device-a", test_clock).unwrap();
+        (0..3)
+            .map(|_| hlc.new_timestamp().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn test_deterministic_ids_in_vault() {
+    let ids = DeterministicIds::install(42);
+    let conn = open_in_memory_vault(
+        HlcService::with_clock("device-a", test_clock).unwrap(),
+        ConnectionContext::new(),
+    )
+    .unwrap();
+
+    let (first, second): (String, String) = conn
+        .query_row("SELECT gen_uuid(), gen_uuid()", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .unwrap();
+    let mut generated = [first, second];
+    generated.sort();
+    let mut expected = [
+        sequential_uuid(42, 1).to_string(),
+        sequential_uuid(42, 2).to_string(),
+    ];
+    expected.sort();
+    assert_eq!(generated, expected);
+    assert_eq!(ids.issued(), 2);
+    assert_eq!(crate::ids::new_uuid(), sequential_uuid(42, 3));
+
+    drop(ids);
+    assert_ne!(crate::ids::new_uuid(), sequential_uuid(42, 4));
+}
+
+#[test]
+fn test_sequential_uuid_is_valid_v4() {
+    let id = sequential_uuid(7, 1);
+    assert_eq!(id.get_version_num(), 4);
+    assert_ne!(id, sequential_uuid(7, 2));
+    assert_ne!(id, sequential_uuid(8, 1));
+}
+
+#[tokio::test]
+async fn test_app_state_with_vault() {
+    let _clock = TestClock::install();
+    let state = app_state_with_vault("device-a").await.unwrap();
+
+    let guard = state.db.0.lock().unwrap();
+    let conn = guard.as_ref().expect("vault is open");
+    let hlc: String = conn
+        .query_row("SELECT current_hlc()", [], |row| row.get(0))
+        .unwrap();
+    assert!(!hlc.is_empty());
+    assert_eq!(state.context.lock().unwrap().device_id, "device-a");
+}
+
+#[test]
+fn test_crdt_vault_writes_and_applies_remote_changes() {
+    let vault = CrdtVault::open("device-a").unwrap();
+    vault
+        .create_crdt_table(
+            "notes",
+            "CREATE TABLE notes (id TEXT PRIMARY KEY NOT NULL, title TEXT)",
+        )
+        .unwrap();
+
+    execute_with_crdt(
+        "INSERT INTO notes (id, title) VALUES (?, ?)".to_string(),
+        vec![json!("local"), json!("Local")],
+        &vault.db,
+        &vault.hlc.lock().unwrap(),
+    )
+    .unwrap();
+
+    let remote = CrdtVault::open("device-b").unwrap();
+    vault
+        .apply_remote_changes(vec![RemoteColumnChange {
+            table_name: "notes".to_string(),
+            row_pks: r#"{"id":"remote"}"#.to_string(),
+            column_name: "title".to_string(),
+            hlc_timestamp: remote.new_hlc_timestamp().unwrap(),
+            decrypted_value: json!("Remote"),
+        }])
+        .unwrap();
+
+    let rows = select_with_crdt(
+        "SELECT id, title FROM notes ORDER BY id".to_string(),
+        vec![],
+        &vault.db,
+    )
+    .unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            vec![json!("local"), json!("Local")],
+            vec![json!("remote"), json!("Remote")],
+        ]
+    );
+}
@@ -0,0 +1,186 @@
+//! Payload building, signing and HTTP delivery with retries
+
+use crate::crdt::hlc::hlc_max;
+use crate::crdt::scanner::LocalColumnChange;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri_plugin_http::reqwest;
+
+/// Rows listed per table in one payload; further rows only set `truncated`
+pub const MAX_ROWS_PER_TABLE: usize = 500;
+/// Attempts per delivery before it is left for the next trigger
+pub const MAX_ATTEMPTS: u32 = 5;
+/// Delay before the first retry; doubles per attempt
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+pub const SIGNATURE_HEADER: &str = "X-Haex-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Haex-Timestamp";
+pub const WEBHOOK_ID_HEADER: &str = "X-Haex-Webhook-Id";
+
+/// `true` if `table` is selected by one of the filters. A filter is an exact
+/// table name, `*` for every table, or a prefix ending in `*`.
+pub fn matches_table(filters: &[String], table: &str) -> bool {
+    filters.iter().any(|filter| match filter.strip_suffix('*') {
+        Some(prefix) => table.starts_with(prefix),
+        None => filter == table,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangedRow {
+    /// Primary key values, e.g. `{ "id": "..." }`
+    pub pk: JsonValue,
+    pub columns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableChanges {
+    pub table: String,
+    pub rows: Vec<ChangedRow>,
+    pub truncated: bool,
+}
+
+/// Body of a delivery. Lists which rows and columns changed, never values.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    pub id: String,
+    pub event: &'static str,
+    pub webhook_id: String,
+    pub created_at: String,
+    pub changes: Vec<TableChanges>,
+}
+
+/// Groups scanned column changes by table and row. Returns `None` if there
+/// is nothing to deliver, otherwise the payload and the newest HLC in it.
+pub fn build_payload(
+    webhook_id: &str,
+    changes: &[LocalColumnChange],
+    created_at: String,
+) -> Option<(WebhookPayload, String)> {
+    let max_hlc = hlc_max(changes.iter().map(|c| c.hlc_timestamp.as_str()))?.to_string();
+
+    let mut by_table: BTreeMap<&str, BTreeMap<&str, Vec<String>>> = BTreeMap::new();
+    for change in changes {
+        let columns = by_table
+            .entry(change.table_name.as_str())
+            .or_default()
+            .entry(change.row_pks.as_str())
+            .or_default();
+        if !columns.contains(&change.column_name) {
+            columns.push(change.column_name.clone());
+        }
+    }
+
+    let changes = by_table
+        .into_iter()
+        .map(|(table, rows)| {
+            let truncated = rows.len() > MAX_ROWS_PER_TABLE;
+            let rows = rows
+                .into_iter()
+                .take(MAX_ROWS_PER_TABLE)
+                .map(|(pks, columns)| ChangedRow {
+                    pk: serde_json::from_str(pks)
+                        .unwrap_or_else(|_| JsonValue::String(pks.to_string())),
+                    columns,
+                })
+                .collect();
+            TableChanges {
+                table: table.to_string(),
+                rows,
+                truncated,
+            }
+        })
+        .collect();
+
+    Some((
+        WebhookPayload {
+            id: crate::ids::new_uuid().to_string(),
+            event: "data.changed",
+            webhook_id: webhook_id.to_string(),
+            created_at,
+            changes,
+        },
+        max_hlc,
+    ))
+}
+
+/// Hex HMAC-SHA256 of `<timestamp>.<body>`. Receivers recompute it and
+/// reject stale timestamps to prevent replays.
+pub fn sign(secret: &str, timestamp: u64, body: &[u8]) -> Result<String, String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|e| format!("Invalid webhook secret: {e}"))?;
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Network errors, 408, 429 and 5xx are retried; other statuses are final.
+pub fn is_retryable_status(status: u16) -> bool {
+    status == 408 || status == 429 || status >= 500
+}
+
+pub fn retry_delay(attempt: u32) -> Duration {
+    INITIAL_RETRY_DELAY * 2u32.saturating_pow(attempt.saturating_sub(1))
+}
+
+pub fn build_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))
+}
+
+/// POSTs `body` with up to [`MAX_ATTEMPTS`] attempts and exponential backoff.
+pub async fn deliver(
+    client: &reqwest::Client,
+    webhook_id: &str,
+    url: &str,
+    secret: &str,
+    body: &[u8],
+) -> Result<(), String> {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let signature = sign(secret, timestamp, body)?;
+
+        let result = client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header(WEBHOOK_ID_HEADER, webhook_id)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, format!("sha256={signature}"))
+            .body(body.to_vec())
+            .send()
+            .await;
+
+        let error = match result {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => {
+                let status = response.status().as_u16();
+                if !is_retryable_status(status) {
+                    return Err(format!("Endpoint answered with HTTP {status}"));
+                }
+                format!("Endpoint answered with HTTP {status}")
+            }
+            Err(e) => format!("Request failed: {e}"),
+        };
+
+        if attempt >= MAX_ATTEMPTS {
+            return Err(format!("{error} (gave up after {attempt} attempts)"));
+        }
+        tokio::time::sleep(retry_delay(attempt)).await;
+    }
+}
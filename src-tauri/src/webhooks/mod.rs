@@ -0,0 +1,290 @@
+//! Outbound webhooks
+//!
+//! Users register URLs with a signing secret and table filters. After CRDT
+//! commits touching a matching table (local writes and applied sync
+//! changes), the service POSTs a signed JSON payload naming the changed
+//! rows and columns — never their values — e.g. to let a home server react
+//! when the shopping list changes.
+//!
+//! Each request carries `X-Haex-Timestamp` and `X-Haex-Signature:
+//! sha256=<hex>`, the HMAC-SHA256 of `<timestamp>.<body>` keyed with the
+//! webhook secret. Failed deliveries are retried with exponential backoff
+//! and, after that, with the next change or the periodic tick.
+//!
+//! The configuration lives in `haex_crdt_configs` and therefore only on
+//! this device.
+
+pub(crate) mod delivery;
+mod service;
+pub(crate) mod store;
+#[cfg(test)]
+mod tests;
+
+pub use service::start_webhook_service;
+
+use crate::critical::CriticalFailureCode;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::error_code::{CodedError, ErrorCode};
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use thiserror::Error;
+use ts_rs::TS;
+
+/// Length of generated secrets in bytes (hex encoded when returned)
+const GENERATED_SECRET_BYTES: usize = 32;
+
+/// Stored configuration of one webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub url: String,
+    pub secret: String,
+    /// Exact table names, `*`, or prefixes ending in `*`
+    pub tables: Vec<String>,
+    pub enabled: bool,
+    pub created_at: String,
+    /// HLC of the newest change already delivered
+    #[serde(default)]
+    pub cursor_hlc: Option<String>,
+    #[serde(default)]
+    pub last_delivery_at: Option<String>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+/// Webhook as shown in the settings (without secret and cursor)
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookInfo {
+    pub id: String,
+    pub name: Option<String>,
+    pub url: String,
+    pub tables: Vec<String>,
+    pub enabled: bool,
+    pub created_at: String,
+    pub last_delivery_at: Option<String>,
+    /// Error of the last failed delivery, cleared by the next success
+    pub last_error: Option<String>,
+}
+
+impl From<&Webhook> for WebhookInfo {
+    fn from(webhook: &Webhook) -> Self {
+        Self {
+            id: webhook.id.clone(),
+            name: webhook.name.clone(),
+            url: webhook.url.clone(),
+            tables: webhook.tables.clone(),
+            enabled: webhook.enabled,
+            created_at: webhook.created_at.clone(),
+            last_delivery_at: webhook.last_delivery_at.clone(),
+            last_error: webhook.last_error.clone(),
+        }
+    }
+}
+
+/// Result of `webhooks_create`; the secret is only returned here
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedWebhook {
+    pub webhook: WebhookInfo,
+    pub secret: String,
+}
+
+/// Errors of the webhook commands
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("{0}")]
+    Invalid(String),
+    #[error("Webhook {0} not found")]
+    NotFound(String),
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+impl CodedError for WebhookError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            WebhookError::Invalid(_) => ErrorCode::Validation,
+            WebhookError::NotFound(_) => ErrorCode::ResourceNotFound,
+            WebhookError::Database(e) => e.error_code(),
+        }
+    }
+
+    fn error_details(&self) -> Option<serde_json::Value> {
+        match self {
+            WebhookError::Database(e) => e.error_details(),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for WebhookError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Serialize::serialize(&self.envelope(), serializer)
+    }
+}
+
+fn now_rfc3339() -> String {
+    time::OffsetDateTime::now_utc()
+        .format(&time::format_description::well_known::Rfc3339)
+        .unwrap_or_default()
+}
+
+pub fn validate_url(url: &str) -> Result<(), WebhookError> {
+    let parsed = tauri::Url::parse(url)
+        .map_err(|e| WebhookError::Invalid(format!("Invalid webhook URL: {e}")))?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(()),
+        scheme => Err(WebhookError::Invalid(format!(
+            "Unsupported webhook URL scheme: {scheme}"
+        ))),
+    }
+}
+
+pub fn validate_tables(tables: &[String]) -> Result<(), WebhookError> {
+    if tables.is_empty() {
+        return Err(WebhookError::Invalid(
+            "At least one table filter is required".to_string(),
+        ));
+    }
+    if tables.iter().any(|t| t.trim().is_empty()) {
+        return Err(WebhookError::Invalid(
+            "Table filters must not be empty".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; GENERATED_SECRET_BYTES];
+    rand::fill(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Current HLC, so a new webhook only reports changes from now on
+fn current_hlc(state: &AppState) -> Result<String, DatabaseError> {
+    let hlc = state.lock_or_fail(
+        &state.hlc,
+        CriticalFailureCode::HlcMutexPoisoned,
+        "webhooks::current_hlc",
+        serde_json::json!({}),
+    )?;
+    hlc.new_timestamp()
+        .map(|ts| ts.to_string())
+        .map_err(|e| DatabaseError::HlcError {
+            reason: e.to_string(),
+        })
+}
+
+#[tauri::command]
+pub fn webhooks_list(state: State<'_, AppState>) -> Result<Vec<WebhookInfo>, WebhookError> {
+    let webhooks = with_connection(&state.db, |conn| store::load(conn))?;
+    Ok(webhooks.iter().map(WebhookInfo::from).collect())
+}
+
+/// Register a webhook. Without `secret` a random one is generated.
+#[tauri::command]
+pub fn webhooks_create(
+    state: State<'_, AppState>,
+    url: String,
+    tables: Vec<String>,
+    name: Option<String>,
+    secret: Option<String>,
+) -> Result<CreatedWebhook, WebhookError> {
+    validate_url(&url)?;
+    validate_tables(&tables)?;
+    let secret = secret
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(generate_secret);
+
+    let webhook = Webhook {
+        id: crate::ids::new_uuid().to_string(),
+        name,
+        url,
+        secret: secret.clone(),
+        tables,
+        enabled: true,
+        created_at: now_rfc3339(),
+        cursor_hlc: Some(current_hlc(&state)?),
+        last_delivery_at: None,
+        last_error: None,
+    };
+    let info = WebhookInfo::from(&webhook);
+
+    with_connection(&state.db, |conn| {
+        store::update(conn, |webhooks| {
+            webhooks.push(webhook);
+            Ok(())
+        })
+    })?;
+
+    Ok(CreatedWebhook {
+        webhook: info,
+        secret,
+    })
+}
+
+/// Change URL, filters, name or enabled state; omitted fields stay as they are.
+#[tauri::command]
+pub fn webhooks_update(
+    state: State<'_, AppState>,
+    id: String,
+    url: Option<String>,
+    tables: Option<Vec<String>>,
+    name: Option<String>,
+    enabled: Option<bool>,
+) -> Result<WebhookInfo, WebhookError> {
+    if let Some(url) = &url {
+        validate_url(url)?;
+    }
+    if let Some(tables) = &tables {
+        validate_tables(tables)?;
+    }
+
+    with_connection(&state.db, |conn| {
+        store::update(conn, |webhooks| {
+            let Some(webhook) = webhooks.iter_mut().find(|w| w.id == id) else {
+                return Ok(None);
+            };
+            if let Some(url) = url {
+                webhook.url = url;
+            }
+            if let Some(tables) = tables {
+                webhook.tables = tables;
+            }
+            if let Some(name) = name {
+                webhook.name = Some(name).filter(|n| !n.is_empty());
+            }
+            if let Some(enabled) = enabled {
+                webhook.enabled = enabled;
+            }
+            Ok(Some(WebhookInfo::from(&*webhook)))
+        })
+    })?
+    .ok_or(WebhookError::NotFound(id))
+}
+
+#[tauri::command]
+pub fn webhooks_delete(state: State<'_, AppState>, id: String) -> Result<(), WebhookError> {
+    let deleted = with_connection(&state.db, |conn| {
+        store::update(conn, |webhooks| {
+            let before = webhooks.len();
+            webhooks.retain(|w| w.id != id);
+            Ok(webhooks.len() != before)
+        })
+    })?;
+    if !deleted {
+        return Err(WebhookError::NotFound(id));
+    }
+    Ok(())
+}
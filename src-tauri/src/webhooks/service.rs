@@ -0,0 +1,169 @@
+//! Background task that turns CRDT commits into webhook deliveries.
+//!
+//! Local writes wake the task through the dirty-tables event; a periodic
+//! tick additionally picks up changes applied by sync and retries failed
+//! deliveries. Every webhook keeps an HLC cursor, so each change is
+//! delivered once and a failed delivery is retried with everything that
+//! accumulated in the meantime.
+
+use crate::crdt::scanner::scan_table_for_local_changes;
+use crate::database::core::with_connection;
+use crate::database::error::DatabaseError;
+use crate::database::init::discover_crdt_tables;
+use crate::event_names::EVENT_CRDT_DIRTY_TABLES_CHANGED;
+use crate::table_names::TABLE_LOGS;
+use crate::AppState;
+use rusqlite::Connection;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager};
+use tauri_plugin_http::reqwest;
+use tokio::sync::Notify;
+
+use super::delivery::{build_client, build_payload, deliver, matches_table};
+use super::{now_rfc3339, store};
+
+/// Delay between a local write and the deliveries it triggers, so a burst
+/// of writes ends up in one payload.
+const CHANGE_DEBOUNCE: Duration = Duration::from_secs(2);
+/// Interval of the catch-up tick (synced changes, failed deliveries)
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// One payload ready to be sent
+struct PendingDelivery {
+    webhook_id: String,
+    url: String,
+    secret: String,
+    body: Vec<u8>,
+    max_hlc: String,
+}
+
+/// Starts the delivery task. Called once at app start; it idles while no
+/// vault is open or no webhook is configured.
+pub fn start_webhook_service(app_handle: AppHandle) {
+    let local_change = Arc::new(Notify::new());
+    let listener_notify = local_change.clone();
+    app_handle.listen_any(EVENT_CRDT_DIRTY_TABLES_CHANGED, move |_| {
+        listener_notify.notify_one();
+    });
+
+    tauri::async_runtime::spawn(async move {
+        let client = match build_client() {
+            Ok(client) => client,
+            Err(e) => {
+                eprintln!("[Webhooks] Service not started: {e}");
+                return;
+            }
+        };
+        let mut tick = tokio::time::interval(TICK_INTERVAL);
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = tick.tick() => {}
+                _ = local_change.notified() => tokio::time::sleep(CHANGE_DEBOUNCE).await,
+            }
+            dispatch_pending(&app_handle, &client).await;
+        }
+    });
+}
+
+fn is_vault_open(state: &AppState) -> bool {
+    state.db.0.lock().map(|db| db.is_some()).unwrap_or(false)
+}
+
+/// Scans the matching tables of every enabled webhook past its cursor.
+fn collect_deliveries(conn: &Connection) -> Result<Vec<PendingDelivery>, DatabaseError> {
+    let webhooks = store::load(conn)?;
+    if !webhooks.iter().any(|w| w.enabled) {
+        return Ok(Vec::new());
+    }
+
+    // Failed deliveries are logged to haex_logs; never report those rows or
+    // a `*` webhook would feed itself.
+    let tables: Vec<String> = discover_crdt_tables(conn)?
+        .into_iter()
+        .filter(|t| t != TABLE_LOGS)
+        .collect();
+
+    let mut deliveries = Vec::new();
+    for webhook in webhooks.into_iter().filter(|w| w.enabled) {
+        let mut changes = Vec::new();
+        for table in tables.iter().filter(|t| matches_table(&webhook.tables, t)) {
+            changes.extend(scan_table_for_local_changes(
+                conn,
+                table,
+                webhook.cursor_hlc.as_deref(),
+                "",
+            )?);
+        }
+
+        let Some((payload, max_hlc)) = build_payload(&webhook.id, &changes, now_rfc3339()) else {
+            continue;
+        };
+        let body = serde_json::to_vec(&payload).map_err(|e| DatabaseError::SerializationError {
+            reason: e.to_string(),
+        })?;
+        deliveries.push(PendingDelivery {
+            webhook_id: webhook.id,
+            url: webhook.url,
+            secret: webhook.secret,
+            body,
+            max_hlc,
+        });
+    }
+    Ok(deliveries)
+}
+
+async fn dispatch_pending(app_handle: &AppHandle, client: &reqwest::Client) {
+    let state = app_handle.state::<AppState>();
+    if !is_vault_open(&state) {
+        return;
+    }
+
+    let deliveries = match with_connection(&state.db, |conn| collect_deliveries(conn)) {
+        Ok(deliveries) => deliveries,
+        Err(e) => {
+            eprintln!("[Webhooks] Failed to collect changes: {e}");
+            return;
+        }
+    };
+
+    for delivery in deliveries {
+        let result = deliver(
+            client,
+            &delivery.webhook_id,
+            &delivery.url,
+            &delivery.secret,
+            &delivery.body,
+        )
+        .await;
+
+        if let Err(e) = &result {
+            let message = format!("Delivery of webhook {} failed: {e}", delivery.webhook_id);
+            let _ = crate::logging::insert_log(
+                &state, "warn", "Webhooks", None, &message, None, "rust",
+            );
+        }
+
+        // The webhook may have been edited or deleted while we were sending.
+        let recorded = with_connection(&state.db, |conn| {
+            store::update(conn, |webhooks| {
+                if let Some(webhook) = webhooks.iter_mut().find(|w| w.id == delivery.webhook_id) {
+                    match &result {
+                        Ok(()) => {
+                            webhook.cursor_hlc = Some(delivery.max_hlc.clone());
+                            webhook.last_delivery_at = Some(now_rfc3339());
+                            webhook.last_error = None;
+                        }
+                        Err(e) => webhook.last_error = Some(e.clone()),
+                    }
+                }
+                Ok(())
+            })
+        });
+        if let Err(e) = recorded {
+            eprintln!("[Webhooks] Failed to record delivery result: {e}");
+        }
+    }
+}
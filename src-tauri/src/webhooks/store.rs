@@ -0,0 +1,58 @@
+//! Persistence of webhook configurations in `haex_crdt_configs` (local-only,
+//! so secrets and cursors never leave this device).
+
+use crate::database::constants::vault_settings_key;
+use crate::database::error::DatabaseError;
+use crate::table_names::{
+    COL_CRDT_CONFIGS_KEY, COL_CRDT_CONFIGS_TYPE, COL_CRDT_CONFIGS_VALUE, TABLE_CRDT_CONFIGS,
+};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::Webhook;
+
+/// `type` column value of the webhook config row
+const CONFIG_TYPE: &str = "webhooks";
+
+pub fn load(conn: &Connection) -> Result<Vec<Webhook>, DatabaseError> {
+    let value: Option<String> = conn
+        .query_row(
+            &format!(
+                "SELECT {COL_CRDT_CONFIGS_VALUE} FROM {TABLE_CRDT_CONFIGS} WHERE {COL_CRDT_CONFIGS_KEY} = ?"
+            ),
+            params![vault_settings_key::WEBHOOKS],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match value {
+        None => Ok(Vec::new()),
+        Some(json) => serde_json::from_str(&json).map_err(|e| DatabaseError::SerializationError {
+            reason: format!("Invalid webhook configuration: {e}"),
+        }),
+    }
+}
+
+pub fn save(conn: &Connection, webhooks: &[Webhook]) -> Result<(), DatabaseError> {
+    let json = serde_json::to_string(webhooks).map_err(|e| DatabaseError::SerializationError {
+        reason: e.to_string(),
+    })?;
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {TABLE_CRDT_CONFIGS} ({COL_CRDT_CONFIGS_KEY}, {COL_CRDT_CONFIGS_TYPE}, {COL_CRDT_CONFIGS_VALUE}) VALUES (?, ?, ?)"
+        ),
+        params![vault_settings_key::WEBHOOKS, CONFIG_TYPE, json],
+    )?;
+    Ok(())
+}
+
+/// Loads, lets `f` modify and saves the list in one go. Run inside
+/// `with_connection` so concurrent updates are serialized by the DB lock.
+pub fn update<T>(
+    conn: &Connection,
+    f: impl FnOnce(&mut Vec<Webhook>) -> Result<T, DatabaseError>,
+) -> Result<T, DatabaseError> {
+    let mut webhooks = load(conn)?;
+    let result = f(&mut webhooks)?;
+    save(conn, &webhooks)?;
+    Ok(result)
+}
@@ -0,0 +1,150 @@
+use super::delivery::{
+    build_payload, is_retryable_status, matches_table, retry_delay, sign, MAX_ROWS_PER_TABLE,
+};
+use super::{store, validate_tables, validate_url, Webhook};
+use crate::crdt::hlc::HlcService;
+use crate::crdt::scanner::LocalColumnChange;
+use crate::test_support::crdt::open_crdt_connection;
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+use std::time::Duration;
+
+fn change(table: &str, id: &str, column: &str, hlc: &str) -> LocalColumnChange {
+    LocalColumnChange {
+        table_name: table.to_string(),
+        row_pks: json!({ "id": id }).to_string(),
+        column_name: column.to_string(),
+        hlc_timestamp: hlc.to_string(),
+        value: json!("secret value"),
+        device_id: String::new(),
+    }
+}
+
+fn webhook(id: &str) -> Webhook {
+    Webhook {
+        id: id.to_string(),
+        name: None,
+        url: "https://example.com/hook".to_string(),
+        secret: "s3cret".to_string(),
+        tables: vec!["*".to_string()],
+        enabled: true,
+        created_at: "2026-01-01T00:00:00Z".to_string(),
+        cursor_hlc: None,
+        last_delivery_at: None,
+        last_error: None,
+    }
+}
+
+#[test]
+fn test_matches_table() {
+    let filters = vec!["shopping_list".to_string(), "ext_abc__*".to_string()];
+    assert!(matches_table(&filters, "shopping_list"));
+    assert!(matches_table(&filters, "ext_abc__notes"));
+    assert!(!matches_table(&filters, "shopping_list_items"));
+    assert!(!matches_table(&filters, "ext_xyz__notes"));
+    assert!(matches_table(&["*".to_string()], "anything"));
+    assert!(!matches_table(&[], "anything"));
+}
+
+#[test]
+fn test_build_payload_groups_rows_without_values() {
+    let changes = vec![
+        change("notes", "1", "title", "100/a"),
+        change("notes", "1", "body", "102/a"),
+        change("notes", "1", "title", "101/a"),
+        change("tasks", "7", "done", "99/a"),
+    ];
+    let (payload, max_hlc) = build_payload("hook", &changes, "now".to_string()).unwrap();
+
+    assert_eq!(max_hlc, "102/a");
+    assert_eq!(payload.event, "data.changed");
+    assert_eq!(payload.webhook_id, "hook");
+    assert_eq!(payload.changes.len(), 2);
+    assert_eq!(payload.changes[0].table, "notes");
+    assert_eq!(payload.changes[0].rows.len(), 1);
+    assert_eq!(payload.changes[0].rows[0].pk, json!({ "id": "1" }));
+    assert_eq!(payload.changes[0].rows[0].columns, vec!["title", "body"]);
+    assert!(!payload.changes[0].truncated);
+
+    let body = serde_json::to_string(&payload).unwrap();
+    assert!(!body.contains("secret value"));
+}
+
+#[test]
+fn test_build_payload_empty_and_truncated() {
+    assert!(build_payload("hook", &[], "now".to_string()).is_none());
+
+    let changes: Vec<_> = (0..MAX_ROWS_PER_TABLE + 1)
+        .map(|i| change("notes", &i.to_string(), "title", "1/a"))
+        .collect();
+    let (payload, _) = build_payload("hook", &changes, "now".to_string()).unwrap();
+    assert_eq!(payload.changes[0].rows.len(), MAX_ROWS_PER_TABLE);
+    assert!(payload.changes[0].truncated);
+}
+
+#[test]
+fn test_sign_matches_hmac_sha256() {
+    let mut mac = Hmac::<Sha256>::new_from_slice(b"key").unwrap();
+    mac.update(b"1700000000.{\"a\":1}");
+    let expected = hex::encode(mac.finalize().into_bytes());
+
+    assert_eq!(sign("key", 1_700_000_000, b"{\"a\":1}").unwrap(), expected);
+    assert_ne!(
+        sign("other", 1_700_000_000, b"{\"a\":1}").unwrap(),
+        expected
+    );
+    assert_ne!(sign("key", 1_700_000_001, b"{\"a\":1}").unwrap(), expected);
+}
+
+#[test]
+fn test_retry_policy() {
+    assert!(is_retryable_status(500));
+    assert!(is_retryable_status(503));
+    assert!(is_retryable_status(429));
+    assert!(is_retryable_status(408));
+    assert!(!is_retryable_status(400));
+    assert!(!is_retryable_status(404));
+
+    assert_eq!(retry_delay(1), Duration::from_secs(1));
+    assert_eq!(retry_delay(2), Duration::from_secs(2));
+    assert_eq!(retry_delay(4), Duration::from_secs(8));
+}
+
+#[test]
+fn test_validation() {
+    assert!(validate_url("https://example.com/hook").is_ok());
+    assert!(validate_url("http://127.0.0.1:8080").is_ok());
+    assert!(validate_url("ftp://example.com").is_err());
+    assert!(validate_url("not a url").is_err());
+
+    assert!(validate_tables(&["notes".to_string()]).is_ok());
+    assert!(validate_tables(&[]).is_err());
+    assert!(validate_tables(&[" ".to_string()]).is_err());
+}
+
+#[test]
+fn test_store_roundtrip() {
+    let conn = open_crdt_connection(HlcService::new_for_testing("webhook-test-device")).unwrap();
+
+    assert!(store::load(&conn).unwrap().is_empty());
+
+    store::update(&conn, |webhooks| {
+        webhooks.push(webhook("a"));
+        webhooks.push(webhook("b"));
+        Ok(())
+    })
+    .unwrap();
+    store::update(&conn, |webhooks| {
+        webhooks.retain(|w| w.id != "a");
+        webhooks[0].cursor_hlc = Some("5/x".to_string());
+        Ok(())
+    })
+    .unwrap();
+
+    let loaded = store::load(&conn).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].id, "b");
+    assert_eq!(loaded[0].cursor_hlc.as_deref(), Some("5/x"));
+    assert_eq!(loaded[0].secret, "s3cret");
+}
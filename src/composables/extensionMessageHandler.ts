@@ -28,6 +28,7 @@ import { handleLoggingMethodAsync } from './handlers/logging'
 import { handleShellMethodAsync } from './handlers/shell'
 import { handlePasswordsMethodAsync } from './handlers/passwords'
 import { handleMailMethodAsync } from './handlers/mail'
+import { handleEventsMethodAsync } from './handlers/events'
 import type { ExtensionRequest, ExtensionInstance } from './handlers/types'
 import { useExtensionBroadcastStore } from '~/stores/extensions/broadcast'
 
@@ -91,6 +92,10 @@ export const handleExtensionRequestAsync = async (
       || method === TAURI_COMMANDS.database.execute
       || method === TAURI_COMMANDS.database.transaction
       || method === TAURI_COMMANDS.database.registerMigrations
+      || method === 'extension_database_get_schema'
+      || method === 'extension_database_set_row_filter'
+      || method === 'extension_database_remove_row_filter'
+      || method === 'extension_database_get_row_filters'
     ) {
       result = await handleDatabaseMethodAsync(request, instance.extension)
     }
@@ -129,6 +134,9 @@ export const handleExtensionRequestAsync = async (
     else if (method.startsWith('extension_mail_')) {
       result = await handleMailMethodAsync(request, instance.extension)
     }
+    else if (method.startsWith('extension_event_')) {
+      result = await handleEventsMethodAsync(request, instance.extension)
+    }
     else {
       throw new Error(`Unknown method: ${method}`)
     }
@@ -74,6 +74,44 @@ export async function handleDatabaseMethodAsync(
       })
     }
 
+    case 'extension_database_get_schema': {
+      return invoke('extension_database_get_schema', {
+        publicKey: extension.publicKey,
+        name: extension.name,
+      })
+    }
+
+    case 'extension_database_set_row_filter': {
+      const filterParams = request.params as {
+        tableName: string
+        predicate: string
+        params?: unknown[]
+      }
+      return invoke('extension_database_set_row_filter', {
+        tableName: filterParams.tableName,
+        predicate: filterParams.predicate,
+        params: filterParams.params || [],
+        publicKey: extension.publicKey,
+        name: extension.name,
+      })
+    }
+
+    case 'extension_database_remove_row_filter': {
+      const filterParams = request.params as { tableName: string }
+      return invoke('extension_database_remove_row_filter', {
+        tableName: filterParams.tableName,
+        publicKey: extension.publicKey,
+        name: extension.name,
+      })
+    }
+
+    case 'extension_database_get_row_filters': {
+      return invoke('extension_database_get_row_filters', {
+        publicKey: extension.publicKey,
+        name: extension.name,
+      })
+    }
+
     case TAURI_COMMANDS.database.registerMigrations: {
       const migrationParams = request.params as {
         extensionVersion: string
@@ -0,0 +1,35 @@
+import { invoke } from '@tauri-apps/api/core'
+import type { IHaexSpaceExtension } from '~/types/haexspace'
+import type { ExtensionRequest } from './types'
+
+/**
+ * Iframe handler for the extension event bus.
+ *
+ * Forwards `publicKey` + `name` so the Rust side can resolve the extension
+ * and check the channel against its manifest's `events.publish`. Delivery to
+ * subscribers happens in Rust (webviews) and `broadcast.ts` (iframes).
+ */
+export async function handleEventsMethodAsync(
+  request: ExtensionRequest,
+  extension: IHaexSpaceExtension,
+) {
+  if (!extension || !request) {
+    throw new Error('Extension not found')
+  }
+
+  const { method, params } = request
+
+  switch (method) {
+    case 'extension_event_publish': {
+      return invoke<number>('extension_event_publish', {
+        channel: params.channel as string,
+        payload: params.payload ?? null,
+        publicKey: extension.publicKey,
+        name: extension.name,
+      })
+    }
+
+    default:
+      throw new Error(`Unknown event method: ${method}`)
+  }
+}
@@ -1,6 +1,7 @@
 import type { IHaexSpaceExtension } from '~/types/haexspace'
 import type { ExtensionRequest } from './types'
 import { invoke } from '@tauri-apps/api/core'
+import { ErrorCode } from '@bindings/ErrorCode'
 import {
   isPermissionPromptRequired,
   extractPromptData,
@@ -66,7 +67,7 @@ async function checkWebPermissionAsync(
 
     // Permission denied errors return a specific error code
     const err = error as { code?: number; message?: string }
-    if (err?.code === 1002 || err?.message?.includes('Permission denied')) {
+    if (err?.code === ErrorCode.PermissionDenied || err?.message?.includes('Permission denied')) {
       return { status: 'denied' }
     }
     // Other errors should be thrown
@@ -106,7 +107,7 @@ async function checkDatabasePermissionAsync(
     }
 
     const err = error as { code?: number; message?: string }
-    if (err?.code === 1002 || err?.message?.includes('Permission denied')) {
+    if (err?.code === ErrorCode.PermissionDenied || err?.message?.includes('Permission denied')) {
       return { status: 'denied' }
     }
     throw error
@@ -145,7 +146,7 @@ async function checkFilesystemPermissionAsync(
     }
 
     const err = error as { code?: number; message?: string }
-    if (err?.code === 1002 || err?.message?.includes('Permission denied')) {
+    if (err?.code === ErrorCode.PermissionDenied || err?.message?.includes('Permission denied')) {
       return { status: 'denied' }
     }
     throw error
@@ -4,16 +4,24 @@ type I18nMap = { [key in string]: ManifestI18nEntry } | null | undefined
 
 /**
  * Resolves a localized field from an extension's i18n map.
- * Fallback chain: i18n[locale] → i18n["en"] → defaultValue
+ * Fallback chain: i18n[locale] → i18n[base language] → i18n[defaultLocale]
+ * → i18n["en"] → defaultValue (same order the protocol handler uses for
+ * `locales/<locale>.json`)
  */
 function resolveI18nField(
   i18n: I18nMap,
   field: keyof ManifestI18nEntry,
   locale: string,
   defaultValue: string,
+  defaultLocale?: string | null,
 ): string {
   if (!i18n) return defaultValue
-  return i18n[locale]?.[field] ?? i18n['en']?.[field] ?? defaultValue
+  const chain = [locale, locale.split(/[-_]/)[0], defaultLocale, 'en']
+  for (const candidate of chain) {
+    const value = candidate ? i18n[candidate]?.[field] : undefined
+    if (value) return value
+  }
+  return defaultValue
 }
 
 /**
@@ -27,12 +35,21 @@ export function useExtensionI18n() {
   const localizedName = (
     name: string,
     i18n: I18nMap,
-  ) => resolveI18nField(i18n, 'name', locale.value, name)
+    defaultLocale?: string | null,
+  ) => resolveI18nField(i18n, 'name', locale.value, name, defaultLocale)
 
   const localizedDescription = (
     description: string | null | undefined,
     i18n: I18nMap,
-  ) => resolveI18nField(i18n, 'description', locale.value, description ?? '')
+    defaultLocale?: string | null,
+  ) =>
+    resolveI18nField(
+      i18n,
+      'description',
+      locale.value,
+      description ?? '',
+      defaultLocale,
+    )
 
   return {
     localizedName,
@@ -0,0 +1,38 @@
+import { integer, sqliteTable, text } from 'drizzle-orm/sqlite-core'
+import tableNames from '@/database/tableNames.json'
+
+/**
+ * Local automation rules ("if this, then that"). NOT CRDT-synced — a rule
+ * acts on this device's extensions, webhooks and file sync rules, so
+ * syncing it would fire the action once per device.
+ *
+ * Evaluated by `crate::automation` on the Rust side; managed through the
+ * `automation_*_rule(s)` commands, which validate the JSON configs.
+ */
+export const haexAutomationRulesNoSync = sqliteTable(
+  tableNames.haex.automation_rules_no_sync.name,
+  {
+    id: text(tableNames.haex.automation_rules_no_sync.columns.id).primaryKey(),
+    name: text(tableNames.haex.automation_rules_no_sync.columns.name).notNull(),
+    enabled: integer(tableNames.haex.automation_rules_no_sync.columns.enabled, { mode: 'boolean' }).notNull().default(true),
+    /** `tableChange`, `schedule` or `fileEvent`. */
+    triggerType: text(tableNames.haex.automation_rules_no_sync.columns.triggerType).notNull(),
+    /** JSON settings of the trigger (tables, interval, file sync events). */
+    triggerConfig: text(tableNames.haex.automation_rules_no_sync.columns.triggerConfig).notNull(),
+    /** `runExtensionCommand`, `sendNotification` or `callWebhook`. */
+    actionType: text(tableNames.haex.automation_rules_no_sync.columns.actionType).notNull(),
+    /** JSON settings of the action. */
+    actionConfig: text(tableNames.haex.automation_rules_no_sync.columns.actionConfig).notNull(),
+    /** HLC of the newest change a table-change rule already reacted to. */
+    cursorHlc: text(tableNames.haex.automation_rules_no_sync.columns.cursorHlc),
+    /** RFC3339 timestamp of the last execution; drives the schedule trigger. */
+    lastRunAt: text(tableNames.haex.automation_rules_no_sync.columns.lastRunAt),
+    /** Error of the last failed execution, cleared by the next success. */
+    lastError: text(tableNames.haex.automation_rules_no_sync.columns.lastError),
+    createdAt: text(tableNames.haex.automation_rules_no_sync.columns.createdAt).notNull(),
+    updatedAt: text(tableNames.haex.automation_rules_no_sync.columns.updatedAt).notNull(),
+  },
+)
+
+export type InsertHaexAutomationRule = typeof haexAutomationRulesNoSync.$inferInsert
+export type SelectHaexAutomationRule = typeof haexAutomationRulesNoSync.$inferSelect
@@ -2,6 +2,7 @@ import { sql } from 'drizzle-orm'
 import {
   check,
   integer,
+  primaryKey,
   sqliteTable,
   text,
   uniqueIndex,
@@ -105,6 +106,70 @@ export type InserthaexExtensionPermissions =
 export type SelecthaexExtensionPermissions =
   typeof haexExtensionPermissions.$inferSelect
 
+/**
+ * Signing key currently trusted for an extension after a key rotation. The
+ * id is the extension id; `public_key` in haex_extensions stays the original
+ * key. Written by the Rust installer only.
+ */
+export const haexExtensionSigningKeys = sqliteTable(
+  tableNames.haex.extension_signing_keys.name,
+  {
+    id: text(tableNames.haex.extension_signing_keys.columns.id)
+      .primaryKey()
+      .references((): AnySQLiteColumn => haexExtensions.id, {
+        onDelete: 'cascade',
+      }),
+    publicKey: text(
+      tableNames.haex.extension_signing_keys.columns.publicKey,
+    ).notNull(),
+    // accepted KeyRotationProof[] (JSON)
+    rotationChain: text(
+      tableNames.haex.extension_signing_keys.columns.rotationChain,
+      { mode: 'json' },
+    ).notNull(),
+    rotatedAt: text(
+      tableNames.haex.extension_signing_keys.columns.rotatedAt,
+    ).default(sql`(CURRENT_TIMESTAMP)`),
+  },
+)
+export type InsertHaexExtensionSigningKeys =
+  typeof haexExtensionSigningKeys.$inferInsert
+export type SelectHaexExtensionSigningKeys =
+  typeof haexExtensionSigningKeys.$inferSelect
+
+/**
+ * Row filter an extension registered on one of its tables. Other extensions
+ * reading the table only see rows matching `predicate`. The id is the
+ * filtered table's name. Written by the Rust extension database commands.
+ */
+export const haexExtensionRowFilters = sqliteTable(
+  tableNames.haex.extension_row_filters.name,
+  {
+    id: text(tableNames.haex.extension_row_filters.columns.id).primaryKey(),
+    extensionId: text(tableNames.haex.extension_row_filters.columns.extensionId)
+      .notNull()
+      .references((): AnySQLiteColumn => haexExtensions.id, {
+        onDelete: 'cascade',
+      }),
+    predicate: text(
+      tableNames.haex.extension_row_filters.columns.predicate,
+    ).notNull(),
+    // values bound to the predicate's ? placeholders (JSON array)
+    params: text(tableNames.haex.extension_row_filters.columns.params, {
+      mode: 'json',
+    })
+      .notNull()
+      .default(sql`'[]'`),
+    updatedAt: text(
+      tableNames.haex.extension_row_filters.columns.updatedAt,
+    ).default(sql`(CURRENT_TIMESTAMP)`),
+  },
+)
+export type InsertHaexExtensionRowFilters =
+  typeof haexExtensionRowFilters.$inferInsert
+export type SelectHaexExtensionRowFilters =
+  typeof haexExtensionRowFilters.$inferSelect
+
 // ---------------------------------------------------------------------------
 // Logs — structured logging for system processes and extensions
 // ---------------------------------------------------------------------------
@@ -242,6 +307,57 @@ export const haexExtensionMigrations = sqliteTable(
 export type InsertHaexExtensionMigrations = typeof haexExtensionMigrations.$inferInsert
 export type SelectHaexExtensionMigrations = typeof haexExtensionMigrations.$inferSelect
 
+export const extensionMigrationBatchesTableName =
+  tableNames.haex.extension_migration_batches_no_sync
+export const extensionMigrationSnapshotsTableName =
+  tableNames.haex.extension_migration_snapshots_no_sync
+
+/**
+ * Migration batches of extensions (WITHOUT CRDT - local-only). Every run of
+ * an extension's bundle migrations is one batch; Rust records its outcome in
+ * `extension::core::migration_batches`.
+ */
+export const haexExtensionMigrationBatchesNoSync = sqliteTable(
+  extensionMigrationBatchesTableName.name,
+  {
+    id: text(extensionMigrationBatchesTableName.columns.id).primaryKey(),
+    extensionId: text(extensionMigrationBatchesTableName.columns.extensionId).notNull(),
+    extensionVersion: text(extensionMigrationBatchesTableName.columns.extensionVersion).notNull(),
+    // running | applied | failed | rolled_back
+    status: text(extensionMigrationBatchesTableName.columns.status).notNull(),
+    // JSON array of the applied migration tags
+    migrations: text(extensionMigrationBatchesTableName.columns.migrations).notNull().default('[]'),
+    error: text(extensionMigrationBatchesTableName.columns.error),
+    startedAt: text(extensionMigrationBatchesTableName.columns.startedAt).notNull(),
+    finishedAt: text(extensionMigrationBatchesTableName.columns.finishedAt),
+    hasSnapshot: integer(extensionMigrationBatchesTableName.columns.hasSnapshot, { mode: 'boolean' })
+      .notNull()
+      .default(false),
+  },
+)
+export type SelectHaexExtensionMigrationBatches =
+  typeof haexExtensionMigrationBatchesNoSync.$inferSelect
+
+/**
+ * Objects saved before the latest batch of an extension (WITHOUT CRDT -
+ * local-only): the SQL of its tables, indexes and views, and for tables the
+ * backup table holding their rows.
+ */
+export const haexExtensionMigrationSnapshotsNoSync = sqliteTable(
+  extensionMigrationSnapshotsTableName.name,
+  {
+    batchId: text(extensionMigrationSnapshotsTableName.columns.batchId).notNull(),
+    position: integer(extensionMigrationSnapshotsTableName.columns.position).notNull(),
+    objectType: text(extensionMigrationSnapshotsTableName.columns.objectType).notNull(),
+    name: text(extensionMigrationSnapshotsTableName.columns.name).notNull(),
+    sql: text(extensionMigrationSnapshotsTableName.columns.sql).notNull(),
+    backupTable: text(extensionMigrationSnapshotsTableName.columns.backupTable),
+  },
+  (table) => [primaryKey({ columns: [table.batchId, table.position] })],
+)
+export type SelectHaexExtensionMigrationSnapshots =
+  typeof haexExtensionMigrationSnapshotsNoSync.$inferSelect
+
 // ---------------------------------------------------------------------------
 // External Authorized & Blocked Clients
 // ---------------------------------------------------------------------------
@@ -320,6 +436,9 @@ export const haexExtensionLimits = sqliteTable(
     maxQuerySizeBytes: integer(tableNames.haex.extension_limits.columns.maxQuerySizeBytes)
       .notNull()
       .default(1048576),
+    // Process limits for the resource monitor (desktop); null = default
+    maxMemoryBytes: integer(tableNames.haex.extension_limits.columns.maxMemoryBytes),
+    maxCpuPercent: integer(tableNames.haex.extension_limits.columns.maxCpuPercent),
     createdAt: text(tableNames.haex.extension_limits.columns.createdAt).default(sql`(CURRENT_TIMESTAMP)`),
     updatedAt: integer(tableNames.haex.extension_limits.columns.updatedAt, { mode: 'timestamp' })
       .$onUpdate(() => new Date()),
@@ -34,6 +34,9 @@ export const haexDeletedRows = sqliteTable(
     rowPks: text(deletedRowsTableName.columns.rowPks, {
       mode: 'json',
     }).notNull(),
+    // Space of the deleted row, for tables with a space_id column; lets
+    // space-scoped syncs send the deletes of their space
+    spaceId: text(deletedRowsTableName.columns.spaceId),
   },
   (table) => [
     // Non-unique lookup index: the BEFORE-DELETE trigger does a plain INSERT
@@ -48,6 +51,53 @@ export const haexDeletedRows = sqliteTable(
 export type InsertHaexDeletedRows = typeof haexDeletedRows.$inferInsert
 export type SelectHaexDeletedRows = typeof haexDeletedRows.$inferSelect
 
+export const trashTableName = tableNames.haex.trash_no_sync
+
+/**
+ * Record trash (WITHOUT CRDT - local-only). The Rust BEFORE-DELETE trigger
+ * stores a JSON snapshot of every locally deleted row here, keyed by the id
+ * of its `haex_deleted_rows` entry. Snapshots are purged together with their
+ * delete-log entry by the CRDT cleanup.
+ */
+export const haexTrashNoSync = sqliteTable(
+  trashTableName.name,
+  {
+    deletedRowId: text(trashTableName.columns.deletedRowId).primaryKey(),
+    tableName: text(trashTableName.columns.tableName).notNull(),
+    rowPks: text(trashTableName.columns.rowPks).notNull(),
+    // JSON object of the row; BLOBs as {"$blob": "<hex>"}
+    rowData: text(trashTableName.columns.rowData).notNull(),
+    deletedHlc: text(trashTableName.columns.deletedHlc).notNull(),
+    deletedAt: text(trashTableName.columns.deletedAt).notNull(),
+  },
+  (table) => [
+    index('haex_trash_no_sync_table_idx').on(table.tableName, table.deletedHlc),
+  ],
+)
+export type SelectHaexTrash = typeof haexTrashNoSync.$inferSelect
+
+export const cascadeRulesTableName = tableNames.haex.crdt_cascade_rules_no_sync
+
+/**
+ * Cascade delete rules (WITHOUT CRDT - local-only). Extensions declare them
+ * in their migration metadata; Rust turns each rule into an AFTER-DELETE
+ * trigger on the parent table. `owner` is the extension's table prefix.
+ */
+export const haexCrdtCascadeRulesNoSync = sqliteTable(
+  cascadeRulesTableName.name,
+  {
+    owner: text(cascadeRulesTableName.columns.owner).notNull(),
+    childTable: text(cascadeRulesTableName.columns.childTable).notNull(),
+    // JSON array of the foreign key columns in the child table
+    childColumns: text(cascadeRulesTableName.columns.childColumns).notNull(),
+    parentTable: text(cascadeRulesTableName.columns.parentTable).notNull(),
+    // JSON array of the referenced columns, pairwise with childColumns
+    parentColumns: text(cascadeRulesTableName.columns.parentColumns).notNull(),
+  },
+  (table) => [primaryKey({ columns: [table.childTable, table.childColumns] })],
+)
+export type SelectHaexCrdtCascadeRules = typeof haexCrdtCascadeRulesNoSync.$inferSelect
+
 /**
  * CRDT Configuration (WITHOUT CRDT - local-only metadata)
  * Stores HLC node ID and last timestamp for this device
@@ -79,6 +129,26 @@ export const haexCrdtDirtyTables = sqliteTable(
 export type InsertHaexCrdtDirtyTables = typeof haexCrdtDirtyTables.$inferInsert
 export type SelectHaexCrdtDirtyTables = typeof haexCrdtDirtyTables.$inferSelect
 
+/**
+ * CRDT Sync Status (WITHOUT CRDT - local-only metadata)
+ * One row per sync peer (server backend or local space leader) with the HLC
+ * cursors of the last push and pull, so every peer is synced incrementally
+ * Maintained by the Rust sync paths and the `sync_set_cursor` command
+ */
+export const haexCrdtSyncStatus = sqliteTable(crdtTableNames.sync_status.name, {
+  peerId: text(crdtTableNames.sync_status.columns.peerId).primaryKey(),
+  lastPushHlcTimestamp: text(
+    crdtTableNames.sync_status.columns.lastPushHlcTimestamp,
+  ),
+  lastPullHlcTimestamp: text(
+    crdtTableNames.sync_status.columns.lastPullHlcTimestamp,
+  ),
+  lastSyncAt: text(crdtTableNames.sync_status.columns.lastSyncAt),
+  error: text(crdtTableNames.sync_status.columns.error),
+})
+export type InsertHaexCrdtSyncStatus = typeof haexCrdtSyncStatus.$inferInsert
+export type SelectHaexCrdtSyncStatus = typeof haexCrdtSyncStatus.$inferSelect
+
 /**
  * CRDT Conflicts (WITHOUT CRDT - local-only conflict tracking)
  * Tracks synchronization conflicts that require user resolution
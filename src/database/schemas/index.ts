@@ -1,3 +1,4 @@
+export * from './automation'
 export * from './core'
 export * from './crdt'
 export * from './critical'
@@ -8,5 +9,7 @@ export * from './localDelivery'
 export * from './marketplaces'
 export * from './mls'
 export * from './passwords'
+export * from './shares'
 export * from './spaces'
 export * from './storage'
+export * from './thumbnails'
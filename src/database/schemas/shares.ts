@@ -0,0 +1,83 @@
+import { sql } from 'drizzle-orm'
+import { integer, sqliteTable, text } from 'drizzle-orm/sqlite-core'
+import tableNames from '@/database/tableNames.json'
+
+/**
+ * Read-only shares with other vaults, in both directions. Managed by the
+ * Rust `share_*` commands; `shareKey` is the current-generation key.
+ */
+export const haexShares = sqliteTable(tableNames.haex.shares.name, {
+  id: text(tableNames.haex.shares.columns.id)
+    .$defaultFn(() => crypto.randomUUID())
+    .primaryKey(),
+  direction: text(tableNames.haex.shares.columns.direction, {
+    enum: ['outgoing', 'incoming'],
+  }).notNull(),
+  name: text(tableNames.haex.shares.columns.name).notNull(),
+  // JSON array of table names
+  tables: text(tableNames.haex.shares.columns.tables).notNull(),
+  spaceId: text(tableNames.haex.shares.columns.spaceId),
+  ownerDid: text(tableNames.haex.shares.columns.ownerDid).notNull(),
+  backendId: text(tableNames.haex.shares.columns.backendId).notNull(),
+  shareKey: text(tableNames.haex.shares.columns.shareKey).notNull(),
+  keyGeneration: integer(tableNames.haex.shares.columns.keyGeneration)
+    .notNull()
+    .default(1),
+  status: text(tableNames.haex.shares.columns.status, {
+    enum: ['active', 'revoked'],
+  })
+    .notNull()
+    .default('active'),
+  createdAt: text(tableNames.haex.shares.columns.createdAt).default(
+    sql`(CURRENT_TIMESTAMP)`,
+  ),
+  revokedAt: text(tableNames.haex.shares.columns.revokedAt),
+})
+
+export type InsertHaexShares = typeof haexShares.$inferInsert
+export type SelectHaexShares = typeof haexShares.$inferSelect
+
+export const haexShareMembers = sqliteTable(
+  tableNames.haex.share_members.name,
+  {
+    id: text(tableNames.haex.share_members.columns.id)
+      .$defaultFn(() => crypto.randomUUID())
+      .primaryKey(),
+    shareId: text(tableNames.haex.share_members.columns.shareId)
+      .notNull()
+      .references(() => haexShares.id, { onDelete: 'cascade' }),
+    did: text(tableNames.haex.share_members.columns.did).notNull(),
+    createdAt: text(tableNames.haex.share_members.columns.createdAt).default(
+      sql`(CURRENT_TIMESTAMP)`,
+    ),
+    revokedAt: text(tableNames.haex.share_members.columns.revokedAt),
+  },
+)
+
+export type InsertHaexShareMembers = typeof haexShareMembers.$inferInsert
+export type SelectHaexShareMembers = typeof haexShareMembers.$inferSelect
+
+/**
+ * Owner-signed collaboration roles per member device of a share. The id is
+ * `<shareId>/<did>/<deviceNode>`; rows are written by the Rust
+ * `share_set_role` command only.
+ */
+export const haexShareRoles = sqliteTable(tableNames.haex.share_roles.name, {
+  id: text(tableNames.haex.share_roles.columns.id).primaryKey(),
+  shareId: text(tableNames.haex.share_roles.columns.shareId)
+    .notNull()
+    .references(() => haexShares.id, { onDelete: 'cascade' }),
+  did: text(tableNames.haex.share_roles.columns.did).notNull(),
+  // HLC node id (hex) the device stamps its writes with
+  deviceNode: text(tableNames.haex.share_roles.columns.deviceNode).notNull(),
+  role: text(tableNames.haex.share_roles.columns.role, {
+    enum: ['reader', 'editor'],
+  }).notNull(),
+  signature: text(tableNames.haex.share_roles.columns.signature).notNull(),
+  createdAt: text(tableNames.haex.share_roles.columns.createdAt).default(
+    sql`(CURRENT_TIMESTAMP)`,
+  ),
+})
+
+export type InsertHaexShareRoles = typeof haexShareRoles.$inferInsert
+export type SelectHaexShareRoles = typeof haexShareRoles.$inferSelect
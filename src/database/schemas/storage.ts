@@ -1,6 +1,7 @@
 import { sql } from 'drizzle-orm'
 import {
   integer,
+  primaryKey,
   sqliteTable,
   text,
   uniqueIndex,
@@ -51,3 +52,23 @@ export const haexStorageBackends = sqliteTable(
 )
 export type InsertHaexStorageBackends = typeof haexStorageBackends.$inferInsert
 export type SelectHaexStorageBackends = typeof haexStorageBackends.$inferSelect
+
+export const storageUsageTableName = tableNames.haex.storage_usage_no_sync
+
+/**
+ * Storage usage ledger (WITHOUT CRDT - local-only). Rust records the size of
+ * every object uploaded through a backend and forgets it on delete; usage
+ * reports and quotas are sums over it. `bucket` is empty for the backend's
+ * own bucket.
+ */
+export const haexStorageUsageNoSync = sqliteTable(
+  storageUsageTableName.name,
+  {
+    backendId: text(storageUsageTableName.columns.backendId).notNull(),
+    bucket: text(storageUsageTableName.columns.bucket).notNull(),
+    key: text(storageUsageTableName.columns.key).notNull(),
+    size: integer(storageUsageTableName.columns.size).notNull(),
+  },
+  (table) => [primaryKey({ columns: [table.backendId, table.bucket, table.key] })],
+)
+export type SelectHaexStorageUsage = typeof haexStorageUsageNoSync.$inferSelect
@@ -0,0 +1,31 @@
+import { blob, integer, primaryKey, sqliteTable, text } from 'drizzle-orm/sqlite-core'
+import tableNames from '@/database/tableNames.json'
+
+/**
+ * Local cache of rendered thumbnails for FileSync blobs. NOT CRDT-synced —
+ * every device can regenerate them from the blob, so `_no_sync` keeps them
+ * local. Lives in the vault DB so thumbnails are encrypted at rest.
+ *
+ * Written by `crate::extension::filesync::thumbnails` on the Rust side.
+ * Rows are re-rendered when `sourceModified` no longer matches the blob.
+ */
+export const haexThumbnailsNoSync = sqliteTable(
+  tableNames.haex.thumbnails_no_sync.name,
+  {
+    backendId: text(tableNames.haex.thumbnails_no_sync.columns.backendId).notNull(),
+    /** Object key of the source blob in the backend. */
+    fileId: text(tableNames.haex.thumbnails_no_sync.columns.fileId).notNull(),
+    /** Bounding box edge length in pixels (one of the fixed size buckets). */
+    size: integer(tableNames.haex.thumbnails_no_sync.columns.size).notNull(),
+    /** Backend last-modified timestamp of the blob this thumbnail was rendered from. */
+    sourceModified: text(tableNames.haex.thumbnails_no_sync.columns.sourceModified),
+    mimeType: text(tableNames.haex.thumbnails_no_sync.columns.mimeType).notNull(),
+    width: integer(tableNames.haex.thumbnails_no_sync.columns.width).notNull(),
+    height: integer(tableNames.haex.thumbnails_no_sync.columns.height).notNull(),
+    data: blob(tableNames.haex.thumbnails_no_sync.columns.data, { mode: 'buffer' }).notNull(),
+    createdAt: text(tableNames.haex.thumbnails_no_sync.columns.createdAt).notNull(),
+  },
+  (table) => [primaryKey({ columns: [table.backendId, table.fileId, table.size] })],
+)
+
+export type SelectHaexThumbnail = typeof haexThumbnailsNoSync.$inferSelect
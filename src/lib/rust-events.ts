@@ -9,6 +9,7 @@
 
 import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import eventNames from '@/constants/eventNames.json'
+import type { EventEnvelope } from '@bindings/EventEnvelope'
 
 // ---------------------------------------------------------------------------
 // Event name registry — derived from the shared JSON, not hardcoded strings
@@ -22,43 +23,39 @@ export const RUST_EVENTS = {
 } as const
 
 // ---------------------------------------------------------------------------
-// Payload types — mirror the serde_json payloads emitted on the Rust side
+// Payload types — generated by ts-rs from src-tauri/src/events
 // ---------------------------------------------------------------------------
 
-export interface PeerStorageStateEvent {
-  running: boolean
-  /** Why the state changed. */
-  reason: 'endpoint-closed' | 'user-stopped'
-  /** How long the endpoint was alive before it closed, in seconds. */
-  uptimeSecs: number
-}
-
-/** What kind of network path a QUIC connection is currently using. */
-export type PathType = 'direct' | 'relay' | 'unknown' | 'closed'
-
-export interface PeerConnectionDiagnostics {
-  pathType: PathType
-  remoteAddr: string | null
-  rttMs: number | null
-}
-
-export interface PeerConnectionChangedEvent {
-  /** Endpoint ID of the remote peer whose path changed. */
-  nodeId: string
-  diagnostics: PeerConnectionDiagnostics
-}
+export type { PathType } from '@bindings/PathType'
+export type { ConnectionDiagnostics as PeerConnectionDiagnostics } from '@bindings/ConnectionDiagnostics'
+export type { PeerStorageStateChanged as PeerStorageStateEvent } from '@bindings/PeerStorageStateChanged'
+export type { PeerConnectionChanged as PeerConnectionChangedEvent } from '@bindings/PeerConnectionChanged'
+export type { LocalSyncCompleted as LocalSyncCompletedEvent } from '@bindings/LocalSyncCompleted'
+export type { LocalSyncError as LocalSyncErrorEvent } from '@bindings/LocalSyncError'
 
-export interface LocalSyncCompletedEvent {
-  spaceId: string
-  tables: string[]
-}
+// ---------------------------------------------------------------------------
+// Envelope — every registered event arrives as `{ v, payload }`
+// ---------------------------------------------------------------------------
 
-export interface LocalSyncErrorEvent {
-  spaceId: string
-  error: string
-  reconnecting: boolean
-  endpointClosed: boolean
-  attempt: number
+/**
+ * Listens to a registered Rust event on the main window and hands the
+ * unwrapped payload to `handler`. `version` is the payload schema version
+ * from the envelope; handlers only need it once a payload shape changes.
+ *
+ * Pinned to the main window: these events are emitted with
+ * `emit_to("main", …)` on the Rust side, and in production builds Tauri v2
+ * silently drops them when the listener is registered with the default
+ * `target: { kind: 'Any' }`.
+ */
+export function listenRustEvent<T>(
+  name: string,
+  handler: (payload: T, version: number) => void | Promise<void>,
+): Promise<UnlistenFn> {
+  return listen<EventEnvelope<T>>(
+    name,
+    event => handler(event.payload.payload, event.payload.v),
+    { target: 'main' },
+  )
 }
 
 // ---------------------------------------------------------------------------
@@ -81,16 +78,7 @@ export class RustEventGroup {
     name: string,
     handler: (payload: T) => void | Promise<void>,
   ): Promise<void> {
-    // Pin to the main window. The events delivered through this group are
-    // emitted with `emit_to("main", …)` on the Rust side; in production
-    // builds Tauri v2 silently drops them when the listener is registered
-    // with the default `target: { kind: 'Any' }`, which is what was
-    // breaking the P2P state-change auto-restart and the local-sync UI.
-    const unlisten = await listen<T>(
-      name,
-      event => handler(event.payload),
-      { target: 'main' },
-    )
+    const unlisten = await listenRustEvent<T>(name, handler)
     this.unlisteners.push(unlisten)
   }
 
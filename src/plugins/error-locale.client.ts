@@ -0,0 +1,27 @@
+import { invoke } from '@tauri-apps/api/core'
+import { createLogger } from '@/stores/logging'
+
+const log = createLogger('ERROR-LOCALE')
+
+/**
+ * Keeps the locale of backend error messages (`userMessage`) in sync with
+ * the i18n locale, on every platform. Until the first call the backend
+ * answers in German, the app's default language.
+ */
+export default defineNuxtPlugin({
+  name: 'error-locale',
+  parallel: true,
+  setup(nuxtApp) {
+    const i18n = nuxtApp.$i18n as { locale: Ref<string> }
+
+    watch(
+      i18n.locale,
+      (locale) => {
+        invoke('error_locale_set', { locale }).catch((error) => {
+          log.error('Failed to set the error locale:', error)
+        })
+      },
+      { immediate: true },
+    )
+  },
+})
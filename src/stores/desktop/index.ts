@@ -600,7 +600,7 @@ export const useDesktopStore = defineStore('desktopStore', () => {
             (ext) => ext.id === item.referenceId,
           )
           const { localizedName } = useExtensionI18n()
-          label = extension ? localizedName(extension.name, extension.i18n) : 'Unknown'
+          label = extension ? localizedName(extension.name, extension.i18n, extension.locales?.defaultLocale) : 'Unknown'
           icon = extension?.iconUrl || ''
         }
 
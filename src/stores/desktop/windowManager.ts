@@ -2,9 +2,11 @@ import { defineAsyncComponent, type Component } from 'vue'
 import { getFullscreenDimensions } from '~/utils/viewport'
 import { isDesktop } from '~/utils/platform'
 import { invoke } from '@tauri-apps/api/core'
-import { listen } from '@tauri-apps/api/event'
+import { listenRustEvent } from '~/lib/rust-events'
 import { EXTENSION_AUTO_START_REQUEST, EXTENSION_WINDOW_CLOSED } from '~/constants/events'
 import { createLogger } from '~/stores/logging'
+import type { ExtensionAutoStartRequest } from '@bindings/ExtensionAutoStartRequest'
+import type { ExtensionWindowClosed } from '@bindings/ExtensionWindowClosed'
 import windowManagerDe from './windowManager.de.json'
 import windowManagerEn from './windowManager.en.json'
 
@@ -666,13 +668,10 @@ export const useWindowManagerStore = defineStore('windowManager', () => {
     log.debug('EXTENSION_AUTO_START_REQUEST event:', EXTENSION_AUTO_START_REQUEST)
 
     // Listen for native WebviewWindow close events from backend.
-    // Pin to the main window via target='main' — backend emits via
-    // emit_to("main", …) and Tauri v2's default-Any listener loses
-    // these events in production builds.
-    await listen<string>(
+    // listenRustEvent pins to the main window and unwraps the event envelope.
+    await listenRustEvent<ExtensionWindowClosed>(
       EXTENSION_WINDOW_CLOSED,
-      (event) => {
-        const windowId = event.payload
+      ({ windowId }) => {
         log.info(`Native extension window closed: ${windowId}`)
 
         // Remove from frontend tracking (read-only mirror of backend state)
@@ -681,19 +680,18 @@ export const useWindowManagerStore = defineStore('windowManager', () => {
           windows.value.splice(index, 1)
         }
       },
-      { target: 'main' },
     )
 
     // Listen for extension auto-start requests from ExternalBridge.
     // Triggered when an external client sends a request for an extension
-    // that is not currently loaded. Same target='main' pinning as above.
-    await listen<{ extensionId: string }>(
+    // that is not currently loaded.
+    await listenRustEvent<ExtensionAutoStartRequest>(
       EXTENSION_AUTO_START_REQUEST,
-      async (event) => {
-        const { extensionId } = event.payload
+      async (payload) => {
+        const { extensionId } = payload
         log.info('========== AUTO-START REQUEST RECEIVED ==========')
         log.info(`Extension ID: ${extensionId}`)
-        log.debug('Event payload:', JSON.stringify(event.payload))
+        log.debug('Event payload:', JSON.stringify(payload))
         log.debug(`Current windows count: ${windows.value.length}`)
         log.debug('Current windows:', windows.value.map(w => ({ id: w.id, type: w.type, sourceId: w.sourceId })))
 
@@ -725,7 +723,6 @@ export const useWindowManagerStore = defineStore('windowManager', () => {
         }
         log.info('========== AUTO-START REQUEST COMPLETE ==========')
       },
-      { target: 'main' },
     )
 
     log.info('Desktop event listeners setup complete')
@@ -14,6 +14,12 @@
  *   - File Changed: filtered by Rust-computed `readerExtensionIds`.
  *   - Shell output / exit: scoped to the session's owning extension.
  *   - External request: routed to the target extension only.
+ *   - Event bus: filtered by Rust-computed `subscriberExtensionIds`.
+ *   - File drop: scoped to the extension Rust registered the drop for.
+ *
+ * File drops onto the main window go to the iframe extension of the active
+ * window; the store mirrors that target to Rust and releases an extension's
+ * pending drops once its last iframe is unregistered.
  *
  * Startup buffering: events that arrive before the SDK finishes its handshake
  * are buffered per-iframe and flushed on PORT_READY — no events are dropped
@@ -36,8 +42,14 @@ import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import type { IHaexSpaceExtension } from '~/types/haexspace'
 import { createLogger } from '~/stores/logging'
 import {
+  EXTENSION_BUS_EVENT,
+  FILEDROP_EVENT,
+  dispatchExtensionEventBroadcast,
   dispatchFileChangedBroadcast,
+  dispatchFileDropBroadcast,
   dispatchShellEventBroadcast,
+  type ExtensionEventBroadcastInput,
+  type FileDropBroadcastInput,
 } from './broadcastRouting'
 import {
   handleExtensionRequestAsync,
@@ -72,6 +84,7 @@ interface ExtensionInstance {
 export const useExtensionBroadcastStore = defineStore('extensionBroadcastStore', () => {
   const deviceStore = useDeviceStore()
   const { isDesktop } = storeToRefs(deviceStore)
+  const { activeWindowId } = storeToRefs(useWindowManagerStore())
 
   // Map iframe element to entry. Use markRaw to prevent Vue reactivity from
   // trying to proxy DOM elements / MessagePorts.
@@ -101,6 +114,7 @@ export const useExtensionBroadcastStore = defineStore('extensionBroadcastStore',
       destroyed: false,
     }
     iframeRegistry.set(iframe, entry)
+    syncFileDropTargetAsync()
 
     // The SDK's PORT_INIT listener is registered inside sdk.init(), which runs
     // as an async Nuxt plugin — after the iframe's load event fires. Sending
@@ -166,8 +180,66 @@ export const useExtensionBroadcastStore = defineStore('extensionBroadcastStore',
     entry.buffer.length = 0
     iframeRegistry.delete(iframe)
     log.info(`Unregistered iframe for ${entry.extension.name}`)
+
+    const extensionId = entry.extension.id
+    const stillLoaded = [...iframeRegistry.values()].some(
+      (other) => other.extension.id === extensionId,
+    )
+    if (stillLoaded) {
+      syncFileDropTargetAsync()
+    }
+    else {
+      unloadFileDropsAsync(extensionId)
+    }
   }
 
+  // ============================================================================
+  // File drop target
+  // ============================================================================
+
+  /** Extension Rust currently routes main-window drops to (as last sent). */
+  let fileDropTarget: string | null = null
+
+  /**
+   * Point main-window file drops at the iframe extension of the active
+   * window, or at nobody if the active window is not an iframe extension.
+   */
+  const syncFileDropTargetAsync = async () => {
+    let target: string | null = null
+    for (const entry of iframeRegistry.values()) {
+      if (entry.windowId === activeWindowId.value) {
+        target = entry.extension.id
+        break
+      }
+    }
+    if (target === fileDropTarget) return
+
+    fileDropTarget = target
+    try {
+      await invoke('extension_filedrop_set_target', { extensionId: target })
+    }
+    catch (error) {
+      log.error('Failed to set file drop target:', error)
+    }
+  }
+
+  /** Release the pending drops of an extension whose last iframe is gone. */
+  const unloadFileDropsAsync = async (extensionId: string) => {
+    // Rust clears the target together with the drops
+    if (fileDropTarget === extensionId) fileDropTarget = null
+    try {
+      await invoke('extension_filedrop_unload', { extensionId })
+    }
+    catch (error) {
+      log.error(`Failed to release file drops of ${extensionId}:`, error)
+    }
+    await syncFileDropTargetAsync()
+  }
+
+  watch(activeWindowId, () => {
+    syncFileDropTargetAsync()
+  })
+
   /**
    * Handle a message arriving on port1 from the extension's port2.
    * Two categories:
@@ -268,19 +340,8 @@ export const useExtensionBroadcastStore = defineStore('extensionBroadcastStore',
       else entry.buffer.push(message)
     }
 
-    // Webview-mode extensions still use Tauri emit — unaffected by the port
-    // handshake since they don't run in an iframe.
-    if (isDesktop.value) {
-      try {
-        await invoke(TAURI_COMMANDS.extension.webviewBroadcast, {
-          event: HAEXTENSION_EVENTS.CONTEXT_CHANGED,
-          payload: { context },
-        })
-      }
-      catch (error) {
-        log.error('Failed to broadcast to webview extensions:', error)
-      }
-    }
+    // Webview-mode extensions are notified by Rust: `extension_context_set`
+    // pushes CONTEXT_CHANGED into every open extension webview itself.
   }
 
   /**
@@ -334,6 +395,14 @@ export const useExtensionBroadcastStore = defineStore('extensionBroadcastStore',
     dispatchShellEventBroadcast(type, payload, entriesForDispatch())
   }
 
+  const broadcastExtensionEvent = (payload: ExtensionEventBroadcastInput) => {
+    dispatchExtensionEventBroadcast(payload, entriesForDispatch())
+  }
+
+  const broadcastFileDrop = (payload: FileDropBroadcastInput) => {
+    dispatchFileDropBroadcast(payload, entriesForDispatch())
+  }
+
   /**
    * Forward an external request to the extension it targets (first matching
    * iframe). External requests expect a single response, so we fan out to
@@ -422,6 +491,26 @@ export const useExtensionBroadcastStore = defineStore('extensionBroadcastStore',
           { target: 'main' },
         ),
       )
+
+      unlistenFns.push(
+        await listen<ExtensionEventBroadcastInput>(
+          EXTENSION_BUS_EVENT,
+          (event) => {
+            broadcastExtensionEvent(event.payload)
+          },
+          { target: 'main' },
+        ),
+      )
+
+      unlistenFns.push(
+        await listen<FileDropBroadcastInput>(
+          FILEDROP_EVENT,
+          (event) => {
+            broadcastFileDrop(event.payload)
+          },
+          { target: 'main' },
+        ),
+      )
     }
     catch (error) {
       log.error('Failed to setup event listeners:', error)
@@ -433,8 +522,10 @@ export const useExtensionBroadcastStore = defineStore('extensionBroadcastStore',
     unlistenFns.length = 0
     eventListenersRegistered = false
 
+    const extensionIds = new Set<string>()
     for (const entry of iframeRegistry.values()) {
       entry.destroyed = true
+      extensionIds.add(entry.extension.id)
       try {
         entry.port.close()
       }
@@ -443,6 +534,7 @@ export const useExtensionBroadcastStore = defineStore('extensionBroadcastStore',
       }
     }
     iframeRegistry.clear()
+    for (const extensionId of extensionIds) unloadFileDropsAsync(extensionId)
   }
 
   return {
@@ -455,6 +547,7 @@ export const useExtensionBroadcastStore = defineStore('extensionBroadcastStore',
     broadcastSyncTablesUpdated,
     broadcastFileChanged,
     broadcastShellEvent,
+    broadcastFileDrop,
     forwardExternalRequest,
 
     setupEventListeners,
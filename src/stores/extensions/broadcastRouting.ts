@@ -17,8 +17,14 @@
  *     signal — missing/empty ⇒ zero fan-out (fail-closed).
  *   - `extensionId` on shell events scopes delivery to the session owner
  *     only; no other extension sees stdout, not even those sharing an origin.
- *   - `readerExtensionIds` / routing `extensionId` are stripped from the
- *     forwarded payload so iframes can never learn who else has access.
+ *   - `extensionId` on file drops scopes delivery to the drop's owner; only
+ *     that extension can read the files with the drop ID.
+ *   - `subscriberExtensionIds` on event bus messages is computed by Rust
+ *     from the manifests' `events.subscribe` and the vault's event policy;
+ *     missing/empty ⇒ zero fan-out (fail-closed).
+ *   - `readerExtensionIds` / `subscriberExtensionIds` / routing `extensionId`
+ *     are stripped from the forwarded payload so iframes can never learn who
+ *     else has access.
  */
 
 import { HAEXTENSION_EVENTS, type FileChangePayload } from '@haex-space/vault-sdk'
@@ -127,6 +133,71 @@ export const dispatchShellEventBroadcast = <TEntry extends RoutableEntry>(
   return { postedTo, buffered, message }
 }
 
+/** Event bus message type. Matches `EXTENSION_EVENT` in `extension::event_bus`. */
+export const EXTENSION_BUS_EVENT = 'haextension:event'
+
+export interface ExtensionEventBroadcastInput {
+  channel: string
+  sourcePublicKey: string
+  sourceName: string
+  payload: unknown
+  subscriberExtensionIds?: string[]
+}
+
+/**
+ * Dispatch an event bus message to the iframes of the subscribed extensions.
+ * Subscribers with a native webview already got the event from Rust and are
+ * not part of `subscriberExtensionIds`.
+ */
+export const dispatchExtensionEventBroadcast = <TEntry extends RoutableEntry>(
+  payload: ExtensionEventBroadcastInput,
+  entries: Iterable<TEntry>,
+  now: () => number = Date.now,
+): BroadcastResult<TEntry> => {
+  const { subscriberExtensionIds, ...event } = payload
+  const subscribers = subscriberExtensionIds ?? []
+  if (subscribers.length === 0) {
+    return { postedTo: [], buffered: [], message: null }
+  }
+
+  const message: Record<string, unknown> = {
+    type: EXTENSION_BUS_EVENT,
+    ...event,
+    timestamp: now(),
+  }
+
+  const subscriberSet = new Set(subscribers)
+  const postedTo: TEntry[] = []
+  const buffered: TEntry[] = []
+  for (const entry of entries) {
+    if (!subscriberSet.has(entry.instance.extension.id)) continue
+    deliver(entry, message, postedTo, buffered)
+  }
+
+  return { postedTo, buffered, message }
+}
+
+/** File drop message type. Matches `FILEDROP_EVENT` in `extension::filedrop`. */
+export const FILEDROP_EVENT = 'haextension:filedrop'
+
+export interface FileDropBroadcastInput extends ShellEventBroadcastInput {
+  dropId: string
+  files: unknown[]
+  rejected: unknown[]
+}
+
+/**
+ * Dispatch a file drop to the iframes of the extension Rust registered the
+ * drop for (the focused extension set via `extension_filedrop_set_target`).
+ * `extensionId` is stripped like on shell events.
+ */
+export const dispatchFileDropBroadcast = <TEntry extends RoutableEntry>(
+  payload: FileDropBroadcastInput,
+  entries: Iterable<TEntry>,
+  now: () => number = Date.now,
+): BroadcastResult<TEntry> =>
+  dispatchShellEventBroadcast(FILEDROP_EVENT, payload, entries, now)
+
 const deliver = <TEntry extends RoutableEntry>(
   entry: TEntry,
   message: Record<string, unknown>,
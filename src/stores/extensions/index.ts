@@ -7,6 +7,10 @@ import type {
   IHaexSpaceExtensionManifest,
 } from '~/types/haexspace'
 import type { ExtensionPreview } from '@bindings/ExtensionPreview'
+import type { ExtensionFilesInstallResult } from '@bindings/ExtensionFilesInstallResult'
+import type { PermissionDiff } from '@bindings/PermissionDiff'
+import type { RevocationReport } from '@bindings/RevocationReport'
+import type { DevLogEntry } from '@bindings/DevLogEntry'
 import type { ExtensionPermissions } from '~~/src-tauri/bindings/ExtensionPermissions'
 import type { ExtensionInfoResponse } from '~~/src-tauri/bindings/ExtensionInfoResponse'
 import type { DisplayMode } from '~~/src-tauri/bindings/DisplayMode'
@@ -244,34 +248,84 @@ export const useExtensionsStore = defineStore('extensionsStore', () => {
     }
   }
 
+  /**
+   * Permission diff of the last file install, used to prompt after updates.
+   */
+  const lastPermissionDiff = ref<PermissionDiff | null>(null)
+
   /**
    * Install extension files only (no DB registration).
    * Use when extension already exists in DB (e.g., from sync).
+   * With `blockAutoGrant`, permissions added by an update stay on "ask".
    */
-  const installFilesAsync = async (extensionId: string) => {
+  const installFilesAsync = async (
+    extensionId: string,
+    options?: { blockAutoGrant?: boolean },
+  ) => {
     if (!pendingInstallBytes.value) {
       throw new Error('Keine Extension zum Installieren vorhanden')
     }
 
     try {
-      const resultId = await invoke<string>(
+      const result = await invoke<ExtensionFilesInstallResult>(
         'install_extension_files',
         {
           fileBytes: Array.from(pendingInstallBytes.value),
           extensionId,
+          blockAutoGrant: options?.blockAutoGrant ?? false,
         },
       )
 
       // Clear cache after successful install
       pendingInstallBytes.value = null
+      lastPermissionDiff.value = result.permissionDiff
 
-      return resultId
+      return result.extensionId
     } catch (error) {
       console.error('Fehler bei Datei-Installation:', error)
       throw error
     }
   }
 
+  /**
+   * Updates the local extension revocation list (fetched from `url` or
+   * imported from `listJson` for air-gapped setups) and unloads revoked
+   * extensions. Without arguments the stored list is re-applied.
+   */
+  const checkRevocationsAsync = async (options?: {
+    url?: string
+    listJson?: string
+    registryPublicKey?: string
+  }) => {
+    const report = await invoke<RevocationReport>(
+      'extension_check_revocations',
+      {
+        url: options?.url ?? null,
+        listJson: options?.listJson ?? null,
+        registryPublicKey: options?.registryPublicKey ?? null,
+      },
+    )
+    if (report.revoked.length) {
+      await loadExtensionsAsync()
+    }
+    return report
+  }
+
+  /**
+   * Captured console output of a dev extension, oldest first.
+   * Live entries arrive via the `dev:extension-log` event.
+   */
+  const getDevLogsAsync = async (
+    extensionId: string,
+    options?: { since?: number; limit?: number },
+  ) => {
+    return invoke<DevLogEntry[]>('dev_extension_get_logs', {
+      extensionId,
+      since: options?.since ?? null,
+      limit: options?.limit ?? null,
+    })
+  }
+
   /**
    * Full installation: Register in DB, then install files.
    * Explicitly performs both steps separately.
@@ -520,6 +574,7 @@ export const useExtensionsStore = defineStore('extensionsStore', () => {
 
   return {
     availableExtensions,
+    checkRevocationsAsync,
     checkManifest,
     clearPendingInstall,
     compareVersions,
@@ -527,12 +582,14 @@ export const useExtensionsStore = defineStore('extensionsStore', () => {
     currentExtensionId,
     downloadAndPreviewAsync,
     extensionEntry,
+    getDevLogsAsync,
     installAsync,
     installFilesAsync,
     installPendingAsync,
     registerAndInstallFilesAsync,
     //isActive,
     isExtensionInstalledAsync,
+    lastPermissionDiff,
     loadExtensionsAsync,
     preview,
     previewManifestAsync,
@@ -1,6 +1,5 @@
 import { invoke, Channel } from '@tauri-apps/api/core'
-import { listen } from '@tauri-apps/api/event'
-import { RUST_EVENTS, type PeerStorageStateEvent } from '@/lib/rust-events'
+import { RUST_EVENTS, listenRustEvent, type PeerStorageStateEvent } from '@/lib/rust-events'
 import { createOnceListener, type OnceListener } from '@/lib/once-listener'
 import { and, eq, or } from 'drizzle-orm'
 import { createLogger } from '@/stores/logging'
@@ -349,17 +348,16 @@ export const usePeerStorageStore = defineStore('peerStorageStore', () => {
     // then be unreachable, leaving the Tauri-side listener leaked).
     if (!stateEvents) {
       stateEvents = createOnceListener(() =>
-        listen<PeerStorageStateEvent>(
+        listenRustEvent<PeerStorageStateEvent>(
           RUST_EVENTS.peerStorageStateChanged,
-          (event) => {
-            const { running: isRunning, reason, uptimeSecs } = event.payload
+          (payload) => {
+            const { running: isRunning, reason, uptimeSecs } = payload
             if (!isRunning && running.value) {
               log.warn(`[P2P] Endpoint closed (reason=${reason}, uptime=${uptimeSecs}s), restarting`)
               running.value = false
               startAsync().catch(err => log.error('[P2P] Post-close restart failed:', err))
             }
           },
-          { target: 'main' },
         ),
       )
     }
@@ -0,0 +1,92 @@
+/**
+ * Path-level HLCs of JSON columns patched via `sql_execute_json_patch`.
+ * Mirrors `src-tauri/src/crdt/json_patch.rs`: `haex_column_hlcs` entries
+ * `"<column>#<path>"` hold one HLC per patched path, `"<column>#"` the HLC
+ * of the last whole-column write.
+ */
+
+import { compareHlc, hlcIsNewer } from '@/utils/hlc'
+
+const PATH_KEY_SEPARATOR = '#'
+
+export interface JsonPathChange {
+  columnName: string
+  hlc: string
+  /** JSON text of the value at the path */
+  value: string
+}
+
+function pathEntries(
+  columnHlcs: Record<string, string>,
+  column: string,
+): Array<[string, string]> {
+  const prefix = `${column}${PATH_KEY_SEPARATOR}`
+  return Object.entries(columnHlcs)
+    .filter(([key]) => key.startsWith(prefix) && key.length > prefix.length)
+    .map(([key, hlc]) => [key.slice(prefix.length), hlc])
+}
+
+/**
+ * HLC a whole-column write has to beat. Equals the column HLC unless the
+ * last write to the column was a JSON patch.
+ */
+export function columnBaseHlc(
+  columnHlcs: Record<string, string>,
+  column: string,
+): string | undefined {
+  const columnHlc = columnHlcs[column]
+  if (!columnHlc) return undefined
+  if (pathEntries(columnHlcs, column).some(([, hlc]) => hlc === columnHlc)) {
+    return columnHlcs[`${column}${PATH_KEY_SEPARATOR}`]
+  }
+  return columnHlc
+}
+
+function getAtPath(document: unknown, path: string): unknown {
+  const segments = path.slice(1).match(/\.[^.[]+|\[\d+\]/g) ?? []
+  let current = document
+  for (const segment of segments) {
+    if (current === null || typeof current !== 'object') return null
+    if (segment.startsWith('[')) {
+      current = Array.isArray(current)
+        ? current[Number(segment.slice(1, -1))]
+        : undefined
+    } else {
+      current = Array.isArray(current)
+        ? undefined
+        : (current as Record<string, unknown>)[segment.slice(1)]
+    }
+  }
+  return current ?? null
+}
+
+function decodeJsonColumn(value: unknown): unknown {
+  if (value === null || value === undefined) return {}
+  if (typeof value !== 'string') return null
+  try {
+    return JSON.parse(value)
+  } catch {
+    return null
+  }
+}
+
+/**
+ * Path changes of one column that are newer than its base, oldest first.
+ */
+export function livePathChanges(
+  columnHlcs: Record<string, string>,
+  column: string,
+  columnValue: unknown,
+): JsonPathChange[] {
+  const base = columnBaseHlc(columnHlcs, column) ?? ''
+  const document = decodeJsonColumn(columnValue)
+
+  return pathEntries(columnHlcs, column)
+    .filter(([, hlc]) => hlcIsNewer(hlc, base))
+    .sort(([, a], [, b]) => compareHlc(a, b))
+    .map(([path, hlc]) => ({
+      columnName: `${column}${PATH_KEY_SEPARATOR}${path}`,
+      hlc,
+      value: JSON.stringify(getAtPath(document, path)),
+    }))
+}
@@ -313,6 +313,7 @@ export const pushToBackendAsync = async (
       // after all chunks succeed — any mid-loop throw leaves the remaining
       // groups in the scanner's next sweep.
       await syncBackendsStore.updateBackendAsync(backendId, { lastPushHlcTimestamp: chunkMaxHlc })
+      await invoke('sync_set_cursor', { peerId: backendId, hlc: chunkMaxHlc })
     }
 
     // Final bookkeeping: lastPullServerTimestamp init + dirty-table cleanup
@@ -584,6 +585,7 @@ export const pushAllDataToBackendAsync = async (
 
     log.debug('Updating backend timestamps:', updateData)
     await syncBackendsStore.updateBackendAsync(backendId, updateData)
+    await invoke('sync_set_cursor', { peerId: backendId, hlc: maxHlc })
 
     // Clear all dirty tables after successful push
     log.debug('Clearing all dirty tables...')
@@ -9,6 +9,7 @@ import type { DirtyTable } from '@bindings/DirtyTable'
 import { encryptCrdtData } from '@haex-space/vault-sdk'
 import tableNames from '@/database/tableNames.json'
 import { hlcIsNewer } from '@/utils/hlc'
+import { columnBaseHlc, livePathChanges } from './jsonPatch'
 import { createLogger } from '@/stores/logging'
 
 const CRDT_COLUMNS = tableNames.crdt.columns
@@ -39,10 +40,13 @@ export async function getTableSchemaAsync(
 }
 
 /**
- * Gets all dirty tables that need to be synced
+ * Gets all dirty tables that need to be synced, with pending change counts,
+ * HLC range and estimated payload size relative to `lastPushHlcTimestamp`
  */
-export async function getDirtyTablesAsync(): Promise<DirtyTable[]> {
-  return await invoke('get_dirty_tables')
+export async function getDirtyTablesAsync(
+  lastPushHlcTimestamp?: string | null,
+): Promise<DirtyTable[]> {
+  return await invoke('get_dirty_tables', { lastPushHlcTimestamp })
 }
 
 /**
@@ -157,7 +161,9 @@ async function processRowsToChangesAsync(
 
     // For each data column, create a change entry if it has a newer HLC
     for (const col of dataColumns) {
-      const columnHlc = columnHlcs[col.name]
+      // Columns patched via json_set report the HLC of their last whole write;
+      // the patched paths are pushed as separate changes below.
+      const columnHlc = columnBaseHlc(columnHlcs, col.name)
 
       // Use row-level HLC as fallback if column doesn't have individual HLC yet
       // This ensures ALL columns (including NULL values) are pushed on first sync
@@ -193,6 +199,27 @@ async function processRowsToChangesAsync(
           ...(epoch !== undefined && { epoch }),
         })
       }
+
+      for (const pathChange of livePathChanges(columnHlcs, col.name, row[col.name])) {
+        if (lastPushHlcTimestamp && !hlcIsNewer(pathChange.hlc, lastPushHlcTimestamp)) {
+          continue
+        }
+        const { encryptedData, nonce } = await encryptCrdtData(
+          { value: pathChange.value },
+          vaultKey,
+        )
+
+        changes.push({
+          tableName,
+          rowPks: pkJson,
+          columnName: pathChange.columnName,
+          hlcTimestamp: pathChange.hlc,
+          deviceId,
+          encryptedValue: encryptedData,
+          nonce,
+          ...(epoch !== undefined && { epoch }),
+        })
+      }
     }
   }
 
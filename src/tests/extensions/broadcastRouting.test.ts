@@ -7,7 +7,8 @@
  * unauthorised extensions observe nothing.
  *
  * Covered properties:
- *   - authorisation scoping (file readers, shell owner)
+ *   - authorisation scoping (file readers, shell owner, event bus subscribers,
+ *     file drop owner)
  *   - fail-closed defaults (empty / missing / unknown readers)
  *   - multi-instance fan-out (same extension, multiple iframes)
  *   - ready-ACK buffering (events before PORT_READY land in `buffer`)
@@ -16,7 +17,11 @@
 
 import { afterEach, beforeEach, describe, expect, it } from 'vitest'
 import {
+  EXTENSION_BUS_EVENT,
+  FILEDROP_EVENT,
+  dispatchExtensionEventBroadcast,
   dispatchFileChangedBroadcast,
+  dispatchFileDropBroadcast,
   dispatchShellEventBroadcast,
   type RoutableEntry,
   type RoutablePort,
@@ -400,3 +405,102 @@ describe('dispatchShellEventBroadcast — payload integrity', () => {
     expect(calls).toHaveLength(1)
   })
 })
+
+// ---------------------------------------------------------------------------
+// Event bus broadcast — subscriber scoping
+// ---------------------------------------------------------------------------
+
+describe('dispatchExtensionEventBroadcast — subscriber scoping', () => {
+  const busEvent = (subscriberExtensionIds?: string[]) => ({
+    channel: 'notes.updated',
+    sourcePublicKey: 'pk',
+    sourceName: 'notes',
+    payload: { noteId: 'n1' },
+    subscriberExtensionIds,
+  })
+
+  it('delivers only to subscribed extensions', async () => {
+    const subscriber = track(makeEntry('ext-sub'))
+    const other = track(makeEntry('ext-other'))
+
+    dispatchExtensionEventBroadcast(busEvent(['ext-sub']), entriesOf([subscriber, other]))
+    await flush()
+
+    expect(subscriber.remoteReceived.length).toBe(1)
+    // Security invariant: non-subscribers observe nothing.
+    expect(other.remoteReceived.length).toBe(0)
+  })
+
+  it('does not broadcast without subscribers (fail-closed)', async () => {
+    const a = track(makeEntry('ext-a'))
+
+    const missing = dispatchExtensionEventBroadcast(busEvent(), entriesOf([a]))
+    const empty = dispatchExtensionEventBroadcast(busEvent([]), entriesOf([a]))
+    await flush()
+
+    expect(missing.message).toBeNull()
+    expect(empty.message).toBeNull()
+    expect(a.remoteReceived.length).toBe(0)
+  })
+
+  it('strips subscriberExtensionIds from the forwarded message', async () => {
+    const a = track(makeEntry('ext-a'))
+
+    dispatchExtensionEventBroadcast(busEvent(['ext-a', 'ext-b']), entriesOf([a]))
+    await flush()
+
+    const message = a.remoteReceived[0] as Record<string, unknown>
+    expect(message).not.toHaveProperty('subscriberExtensionIds')
+    expect(message.type).toBe(EXTENSION_BUS_EVENT)
+    expect(message.channel).toBe('notes.updated')
+    expect(message.payload).toEqual({ noteId: 'n1' })
+  })
+})
+
+// ---------------------------------------------------------------------------
+// File drop broadcast — owner scoping
+// ---------------------------------------------------------------------------
+
+describe('dispatchFileDropBroadcast — owner scoping', () => {
+  const drop = (extensionId: string) => ({
+    dropId: 'drop-1',
+    extensionId,
+    files: [{ index: 0, name: 'receipt.pdf', size: 10, mimeType: 'application/pdf', modified: null }],
+    rejected: [],
+  })
+
+  it('delivers only to the extension the drop was registered for', async () => {
+    const owner = track(makeEntry('ext-owner'))
+    const other = track(makeEntry('ext-other'))
+
+    dispatchFileDropBroadcast(drop('ext-owner'), entriesOf([owner, other]))
+    await flush()
+
+    expect(owner.remoteReceived.length).toBe(1)
+    // Security invariant: the drop ID must not reach any other extension.
+    expect(other.remoteReceived.length).toBe(0)
+  })
+
+  it('does not broadcast without an owner (fail-closed)', async () => {
+    const a = track(makeEntry('ext-a'))
+
+    const result = dispatchFileDropBroadcast(drop(''), entriesOf([a]))
+    await flush()
+
+    expect(result.message).toBeNull()
+    expect(a.remoteReceived.length).toBe(0)
+  })
+
+  it('strips extensionId and keeps the drop metadata', async () => {
+    const owner = track(makeEntry('ext-owner'))
+
+    dispatchFileDropBroadcast(drop('ext-owner'), entriesOf([owner]))
+    await flush()
+
+    const message = owner.remoteReceived[0] as Record<string, unknown>
+    expect(message).not.toHaveProperty('extensionId')
+    expect(message.type).toBe(FILEDROP_EVENT)
+    expect(message.dropId).toBe('drop-1')
+    expect(message.files).toHaveLength(1)
+  })
+})
@@ -1,5 +1,30 @@
+import type { ErrorCode } from '~~/src-tauri/bindings/ErrorCode'
+import type { ErrorEnvelope } from '~~/src-tauri/bindings/ErrorEnvelope'
+
+/**
+ * Whether `error` is a command error serialized by the backend
+ * (`{ code, message, details }`, see src-tauri/src/error_code)
+ */
+export function isErrorEnvelope(error: unknown): error is ErrorEnvelope {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as { code?: unknown }).code === 'number' &&
+    typeof (error as { message?: unknown }).message === 'string'
+  )
+}
+
+/**
+ * Stable numeric error code of a command error, if it has one. Compare it
+ * against the `ErrorCode` enum, e.g. `getErrorCode(e) === ErrorCode.VaultBusy`.
+ */
+export function getErrorCode(error: unknown): ErrorCode | undefined {
+  return isErrorEnvelope(error) ? (error.code as ErrorCode) : undefined
+}
+
 export function getErrorMessage(error: unknown): string {
   if (error instanceof Error) return error.message
   if (typeof error === 'string') return error
+  if (isErrorEnvelope(error)) return error.userMessage || error.message
   return String(error)
 }